@@ -0,0 +1,150 @@
+use std::path::{Path, PathBuf};
+
+use proc_macro::TokenStream;
+
+use devise::{DeriveGenerator, FromMeta, MapperBuild, Support, ValidatorBuild};
+use devise::proc_macro2_diagnostics::{Diagnostic, SpanDiagnosticExt};
+use devise::syn::{self, spanned::Spanned};
+
+use crate::scan;
+
+const ONE_TEMPLATE_ATTR: &str = "missing `#[template(\"name\")]` attribute";
+const NAMED_FIELDS: &str = "only structs with named fields are supported";
+const DEFAULT_TEMPLATE_DIR: &str = "templates";
+
+/// The engine extensions, in the order in which templates are resolved.
+const EXTENSIONS: &[&str] = &["tera", "hbs", "j2"];
+
+#[derive(Debug, FromMeta)]
+struct TemplateAttribute {
+    #[meta(naked)]
+    name: String,
+    dir: Option<String>,
+}
+
+#[derive(Debug, Default, FromMeta)]
+struct FieldAttribute {
+    name: Option<String>,
+}
+
+/// A template resolved at compile-time.
+struct Resolved {
+    name: String,
+    path: PathBuf,
+}
+
+fn resolve(s: &syn::ItemStruct) -> Result<Resolved, Diagnostic> {
+    let attr = TemplateAttribute::one_from_attrs("template", &s.attrs)?
+        .ok_or_else(|| s.span().error(ONE_TEMPLATE_ATTR))?;
+
+    let manifest_dir = std::env::var_os("CARGO_MANIFEST_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_default();
+
+    let root = manifest_dir.join(attr.dir.as_deref().unwrap_or(DEFAULT_TEMPLATE_DIR));
+    match find_template(&root, &attr.name) {
+        Some(path) => Ok(Resolved { name: attr.name, path }),
+        None => Err(s.span()
+            .error(format!("template `{}` not found", attr.name))
+            .note(format!("searched for templates in `{}`", root.display()))
+            .help("set the template directory with `#[template(\"name\", dir = \"path\")]`")),
+    }
+}
+
+/// Finds the template named `name` in `root` like `rocket_dyn_templates`
+/// does: by its path relative to `root` without the engine and data
+/// extensions.
+fn find_template(root: &Path, name: &str) -> Option<PathBuf> {
+    fn visit(dir: &Path, files: &mut Vec<PathBuf>) {
+        let Ok(entries) = std::fs::read_dir(dir) else { return };
+        for path in entries.flatten().map(|entry| entry.path()) {
+            if path.is_dir() {
+                visit(&path, files);
+            } else {
+                files.push(path);
+            }
+        }
+    }
+
+    let mut files = vec![];
+    visit(root, &mut files);
+    EXTENSIONS.iter().find_map(|&ext| {
+        files.iter().find(|path| {
+            let Ok(relative) = path.strip_prefix(root) else { return false };
+            let has_ext = relative.extension().map_or(false, |e| e == ext);
+            let stem = relative.with_extension("");
+            let stem = stem.with_extension("").to_string_lossy().replace('\\', "/");
+            has_ext && stem == name
+        }).cloned()
+    })
+}
+
+/// Returns the names of the struct's fields as seen by templates.
+fn field_names(s: &syn::ItemStruct) -> Result<Vec<String>, Diagnostic> {
+    let syn::Fields::Named(fields) = &s.fields else {
+        return Err(s.span().error(NAMED_FIELDS));
+    };
+
+    let mut names = vec![];
+    for field in &fields.named {
+        let attr = FieldAttribute::one_from_attrs("template", &field.attrs)?.unwrap_or_default();
+        match (attr.name, field.ident.as_ref()) {
+            (Some(name), _) => names.push(name),
+            (None, Some(ident)) => names.push(ident.to_string()),
+            (None, None) => continue,
+        }
+    }
+
+    Ok(names)
+}
+
+fn validate(s: &syn::ItemStruct) -> Result<(), Diagnostic> {
+    let template = resolve(s)?;
+    let fields = field_names(s)?;
+    let source = std::fs::read_to_string(&template.path).map_err(|e| {
+        s.span().error(format!("failed to read template `{}`: {e}", template.name))
+    })?;
+
+    let required = match template.path.extension().and_then(|e| e.to_str()) {
+        Some("hbs") => scan::handlebars(&source),
+        _ => scan::jinja(&source),
+    };
+
+    let missing: Vec<_> = required.iter()
+        .filter(|var| !fields.contains(var))
+        .map(|var| format!("`{var}`"))
+        .collect();
+
+    if missing.is_empty() {
+        return Ok(());
+    }
+
+    Err(s.ident.span()
+        .error(format!("context is missing variables used by template `{}`: {}",
+            template.name, missing.join(", ")))
+        .note(format!("template found at `{}`", template.path.display()))
+        .help("add a field for each variable or rename a field with \
+            `#[template(name = \"variable\")]`"))
+}
+
+pub fn derive_template_context(input: TokenStream) -> TokenStream {
+    DeriveGenerator::build_for(input, quote!(impl rocket_dyn_templates::TemplateContext))
+        .support(Support::Struct | Support::Lifetime)
+        .validator(ValidatorBuild::new()
+            .struct_validate(|_, s| validate(&s))
+        )
+        .outer_mapper(MapperBuild::new()
+            .try_struct_map(|_, s| {
+                // Recompile whenever the template changes.
+                let path = resolve(&s)?.path.to_string_lossy().into_owned();
+                Ok(quote!(const _: &[u8] = include_bytes!(#path);))
+            })
+        )
+        .inner_mapper(MapperBuild::new()
+            .try_struct_map(|_, s| {
+                let name = resolve(&s)?.name;
+                Ok(quote!(const NAME: &'static str = #name;))
+            })
+        )
+        .to_tokens()
+}
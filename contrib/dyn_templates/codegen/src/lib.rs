@@ -0,0 +1,65 @@
+#![recursion_limit="256"]
+#![warn(rust_2018_idioms)]
+
+//! # `rocket_dyn_templates` - Code Generation
+//!
+//! Implements the code generation portion of the `rocket_dyn_templates` crate.
+//! This is an implementation detail. This create should never be depended on
+//! directly.
+
+#[macro_use] extern crate quote;
+
+mod context;
+mod scan;
+
+/// Automatic derive for the [`TemplateContext`] trait.
+///
+/// ```rust,ignore
+/// use rocket_dyn_templates::TemplateContext;
+/// use rocket::serde::Serialize;
+///
+/// #[derive(Serialize, TemplateContext)]
+/// #[serde(crate = "rocket::serde")]
+/// #[template("index")]
+/// struct Index<'a> {
+///     title: &'a str,
+///     #[serde(rename = "user")]
+///     #[template(name = "user")]
+///     username: &'a str,
+/// }
+/// ```
+///
+/// The derive resolves the template named in the `#[template("name")]`
+/// attribute at compile-time, exactly as [`Template::render()`] does at
+/// runtime, in the `templates` directory relative to the crate's
+/// `Cargo.toml`. Set `dir` to use another directory:
+/// `#[template("name", dir = "path")]`. It then scans the template for the
+/// variables it looks up in its context and fails to compile if the struct
+/// doesn't have a field for each. The name of a field is its identifier
+/// unless set with `#[template(name = "variable")]`, which should match the
+/// name with which the field is serialized.
+///
+/// The scan is lenient. Variables aren't required when they're:
+///
+///   * only tested by a conditional, like `{% if x %}` or `{{#if x}}`, or
+///     used inside one,
+///   * given a default, like `{{ x | default(value=1) }}`, or tested with
+///     `is defined`,
+///   * bound by the template, like loop variables and `set`s, or
+///   * looked up inside Handlebars blocks that change the context, like
+///     `each` and `with`, unless via `@root`.
+///
+/// Templates that a template extends or includes aren't scanned, and a
+/// Handlebars expression with a single name, like `{{x}}`, is taken to be a
+/// variable, not a helper without parameters.
+///
+/// The derive generates an implementation of [`TemplateContext`] whose
+/// [`TemplateContext::NAME`] is the template's name.
+///
+/// [`TemplateContext`]: ../rocket_dyn_templates/trait.TemplateContext.html
+/// [`TemplateContext::NAME`]: ../rocket_dyn_templates/trait.TemplateContext.html#associatedconstant.NAME
+/// [`Template::render()`]: ../rocket_dyn_templates/struct.Template.html#method.render
+#[proc_macro_derive(TemplateContext, attributes(template))]
+pub fn derive_template_context(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    crate::context::derive_template_context(input)
+}
@@ -0,0 +1,440 @@
+//! Discovery of the variables a template requires from its context.
+//!
+//! The scanners here don't fully parse templates. They find the roots of the
+//! paths a template looks up in its context, like `user` in `user.name`, and
+//! err on the side of leniency: variables that are only tested by conditionals,
+//! that have defaults, or that are looked up where the context is replaced,
+//! like in a Handlebars `each`, aren't considered required.
+
+/// A (lexical) scope in a template.
+#[derive(Default)]
+struct Frame {
+    /// Names bound by the template itself: loop variables, `set`s, imports.
+    locals: Vec<String>,
+    /// Names tested by an enclosing conditional, which may thus be missing.
+    guards: Vec<String>,
+    /// Whether lookups in this scope don't resolve to the context.
+    opaque: bool,
+}
+
+struct Scope {
+    frames: Vec<Frame>,
+    required: Vec<String>,
+}
+
+impl Scope {
+    fn new() -> Self {
+        Scope { frames: vec![Frame::default()], required: vec![] }
+    }
+
+    fn current(&mut self) -> &mut Frame {
+        self.frames.last_mut().expect("root frame")
+    }
+
+    fn push(&mut self, frame: Frame) {
+        self.frames.push(frame);
+    }
+
+    fn pop(&mut self) {
+        if self.frames.len() > 1 {
+            self.frames.pop();
+        }
+    }
+
+    /// Marks `name` as required unless it's bound, guarded, or looked up in
+    /// an opaque scope. `absolute` lookups are never opaque.
+    fn require(&mut self, name: &str, absolute: bool) {
+        let opaque = !absolute && self.frames.iter().any(|f| f.opaque);
+        let known = self.frames.iter()
+            .any(|f| f.locals.iter().chain(&f.guards).any(|n| n == name));
+
+        if !opaque && !known && !self.required.iter().any(|n| n == name) {
+            self.required.push(name.into());
+        }
+    }
+}
+
+/// Returns the index of `close` in `s`, skipping over string literals.
+fn find_close(s: &str, close: &str) -> Option<usize> {
+    let mut quote = None;
+    for (i, c) in s.char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => continue,
+            None if c == '"' || c == '\'' || c == '`' => quote = Some(c),
+            None if s[i..].starts_with(close) => return Some(i),
+            None => continue,
+        }
+    }
+
+    None
+}
+
+/// Splits `s` into its first word and the remainder.
+fn split_word(s: &str) -> (&str, &str) {
+    let s = s.trim();
+    match s.find(|c: char| c.is_whitespace()) {
+        Some(i) => (&s[..i], s[i..].trim_start()),
+        None => (s, ""),
+    }
+}
+
+fn is_ident_start(b: u8) -> bool {
+    b.is_ascii_alphabetic() || b == b'_'
+}
+
+fn is_ident(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_'
+}
+
+/// Returns the variables required by a Tera or MiniJinja template.
+pub fn jinja(source: &str) -> Vec<String> {
+    let mut scope = Scope::new();
+    let mut rest = source;
+    while let Some(start) = rest.find('{') {
+        rest = &rest[start..];
+        let (kind, close) = match rest.as_bytes().get(1) {
+            Some(b'{') => (b'{', "}}"),
+            Some(b'%') => (b'%', "%}"),
+            Some(b'#') => (b'#', "#}"),
+            _ => {
+                rest = &rest[1..];
+                continue;
+            }
+        };
+
+        let end = match kind {
+            b'#' => rest[2..].find(close),
+            _ => find_close(&rest[2..], close),
+        };
+
+        let Some(end) = end else { break };
+        let body = rest[2..2 + end].trim_matches(|c: char| c == '-' || c.is_whitespace());
+        rest = &rest[2 + end + close.len()..];
+        match kind {
+            b'{' => jinja_require(&mut scope, body),
+            b'%' if split_word(body).0 == "raw" => {
+                let Some(end) = rest.find("endraw") else { break };
+                let Some(close) = rest[end..].find("%}") else { break };
+                rest = &rest[end + close + 2..];
+            }
+            b'%' => jinja_statement(&mut scope, body),
+            _ => continue,
+        }
+    }
+
+    scope.required
+}
+
+fn jinja_statement(scope: &mut Scope, statement: &str) {
+    let (keyword, args) = split_word(statement);
+    match keyword {
+        "for" => {
+            let Some((targets, iter)) = args.split_once(" in ") else { return };
+            let iter = iter.split(" if ").next().unwrap_or(iter);
+            let iter = iter.trim().trim_end_matches("recursive");
+            jinja_require(scope, iter);
+
+            let locals = targets.split(',').map(|t| t.trim().into()).collect();
+            scope.push(Frame { locals, ..Frame::default() });
+        }
+        "if" => {
+            let guards = jinja_roots(args).0.into_iter().map(String::from).collect();
+            scope.push(Frame { guards, ..Frame::default() });
+        }
+        "elif" | "elseif" => {
+            let guards = jinja_roots(args).0.into_iter().map(String::from);
+            scope.current().guards.extend(guards);
+        }
+        "set" | "set_global" => match args.split_once('=') {
+            Some((target, value)) => {
+                jinja_require(scope, value);
+                let frame = match keyword {
+                    "set_global" => &mut scope.frames[0],
+                    _ => scope.current(),
+                };
+
+                frame.locals.extend(target.split(',').map(|t| t.trim().into()));
+            }
+            None => {
+                scope.current().locals.push(split_word(args).0.into());
+                scope.push(Frame::default());
+            }
+        },
+        "with" => {
+            let mut frame = Frame::default();
+            for binding in args.split(',') {
+                if let Some((target, value)) = binding.split_once('=') {
+                    jinja_require(scope, value);
+                    frame.locals.push(target.trim().into());
+                }
+            }
+
+            scope.push(frame);
+        }
+        "import" => {
+            if let Some((_, alias)) = args.rsplit_once(" as ") {
+                scope.current().locals.push(alias.trim().into());
+            }
+        }
+        "from" => {
+            let Some((_, names)) = args.split_once(" import ") else { return };
+            for name in names.split(',') {
+                let name = name.rsplit_once(" as ").map_or(name, |(_, alias)| alias);
+                scope.current().locals.push(name.trim().into());
+            }
+        }
+        "macro" => scope.push(Frame { opaque: true, ..Frame::default() }),
+        "block" | "filter" | "call" | "autoescape" => scope.push(Frame::default()),
+        _ if keyword.starts_with("end") => scope.pop(),
+        _ => { /* `include`, `extends`, `break`, `else`, ... */ }
+    }
+}
+
+fn jinja_require(scope: &mut Scope, expr: &str) {
+    let (roots, optional) = jinja_roots(expr);
+    if !optional {
+        roots.iter().for_each(|root| scope.require(root, false));
+    }
+}
+
+/// Returns the root variables looked up by the Jinja expression `expr` and
+/// whether the expression is optional: whether it has a `default` or tests
+/// whether a value is `defined`.
+fn jinja_roots(expr: &str) -> (Vec<&str>, bool) {
+    const KEYWORDS: &[&str] = &[
+        "and", "or", "not", "in", "is", "if", "else", "true", "false", "True", "False",
+        "none", "None", "loop", "self", "super", "caller", "__tera_context",
+    ];
+
+    let bytes = expr.as_bytes();
+    let (mut roots, mut optional) = (vec![], false);
+    let (mut prev, mut after_is) = (None, false);
+    let mut i = 0;
+    while i < bytes.len() {
+        let c = bytes[i];
+        if c == b'"' || c == b'\'' || c == b'`' {
+            i += 1 + expr[i + 1..].find(c as char).unwrap_or(expr.len() - i - 1) + 1;
+            prev = Some(b'"');
+        } else if is_ident_start(c) {
+            let start = i;
+            while i < bytes.len() && is_ident(bytes[i]) {
+                i += 1;
+            }
+
+            let word = &expr[start..i];
+            let next = expr[i..].trim_start();
+            let test = after_is && word != "not";
+            match prev {
+                Some(b'|') if word == "default" => optional = true,
+                _ if test && (word == "defined" || word == "undefined") => optional = true,
+                _ => {}
+            }
+
+            let skip = matches!(prev, Some(b'.' | b'|' | b':'))
+                || test
+                || next.starts_with('(')
+                || next.starts_with("::")
+                || (next.starts_with('=') && !next.starts_with("=="))
+                || KEYWORDS.contains(&word);
+
+            if !skip {
+                roots.push(word);
+            }
+
+            after_is = word == "is" || (after_is && word == "not");
+            prev = Some(b'a');
+        } else if c.is_ascii_digit() {
+            while i < bytes.len() && (is_ident(bytes[i]) || bytes[i] == b'.') {
+                i += 1;
+            }
+
+            prev = Some(b'0');
+        } else {
+            if !c.is_ascii_whitespace() {
+                prev = Some(c);
+            }
+
+            i += 1;
+        }
+    }
+
+    (roots, optional)
+}
+
+/// Returns the variables required by a Handlebars template.
+pub fn handlebars(source: &str) -> Vec<String> {
+    let mut scope = Scope::new();
+    let mut rest = source;
+    while let Some(start) = rest.find("{{") {
+        if rest[..start].ends_with('\\') {
+            rest = &rest[start + 2..];
+            continue;
+        }
+
+        rest = &rest[start..];
+        if rest.starts_with("{{{{") {
+            // A raw block: skip to the end of the matching closing tag.
+            let Some(end) = rest.find("{{{{/") else { break };
+            let Some(close) = rest[end..].find("}}}}") else { break };
+            rest = &rest[end + close + 4..];
+            continue;
+        }
+
+        let (open, close) = match () {
+            _ if rest.starts_with("{{{") => (3, "}}}"),
+            _ if rest.starts_with("{{!--") || rest.starts_with("{{~!--") => (2, "--}}"),
+            _ => (2, "}}"),
+        };
+
+        let end = match rest[open..].trim_start_matches('~').starts_with('!') {
+            true => rest[open..].find(close),
+            false => find_close(&rest[open..], close),
+        };
+
+        let Some(end) = end else { break };
+        let body = rest[open..open + end].trim_matches(|c: char| c == '~' || c.is_whitespace());
+        rest = &rest[open + end + close.len()..];
+        handlebars_tag(&mut scope, body);
+    }
+
+    scope.required
+}
+
+fn handlebars_tag(scope: &mut Scope, tag: &str) {
+    if tag.starts_with('!') || tag.starts_with('>') {
+        return;
+    }
+
+    if let Some(block) = tag.strip_prefix('#') {
+        if block.starts_with('>') {
+            return scope.push(Frame::default());
+        } else if block.starts_with('*') {
+            return scope.push(Frame { opaque: true, ..Frame::default() });
+        }
+
+        let tokens = handlebars_tokens(block);
+        let (helper, params) = tokens.split_first().map_or(("", &[][..]), |(h, p)| (*h, p));
+        match helper {
+            "if" | "unless" => {
+                let guards = handlebars_paths(params).into_iter()
+                    .filter_map(|path| Some(handlebars_root(path)?.0.into()))
+                    .collect();
+
+                scope.push(Frame { guards, ..Frame::default() });
+            }
+            "each" | "with" => {
+                handlebars_require(scope, params);
+                scope.push(Frame { opaque: true, ..Frame::default() });
+            }
+            _ => {
+                handlebars_require(scope, params);
+                scope.push(Frame::default());
+            }
+        }
+    } else if tag.starts_with('/') {
+        scope.pop();
+    } else if let Some(inverse) = tag.strip_prefix('^') {
+        if !inverse.trim().is_empty() {
+            let tokens = handlebars_tokens(inverse);
+            let guards = handlebars_paths(&tokens).into_iter()
+                .filter_map(|path| Some(handlebars_root(path)?.0.into()))
+                .collect();
+
+            scope.push(Frame { guards, ..Frame::default() });
+        }
+    } else if tag == "else" || tag.starts_with("else ") {
+        let tokens = handlebars_tokens(&tag[4..]);
+        let guards = handlebars_paths(tokens.get(1..).unwrap_or_default()).into_iter()
+            .filter_map(|path| Some(handlebars_root(path)?.0.into()));
+
+        scope.current().guards.extend(guards);
+    } else {
+        let tokens = handlebars_tokens(tag.trim_start_matches('&'));
+        match tokens.as_slice() {
+            [path] => handlebars_require(scope, &[path]),
+            [_helper, params @ ..] => handlebars_require(scope, params),
+            [] => {}
+        }
+    }
+}
+
+fn handlebars_require(scope: &mut Scope, params: &[&str]) {
+    for path in handlebars_paths(params) {
+        if let Some((root, absolute)) = handlebars_root(path) {
+            scope.require(root, absolute);
+        }
+    }
+}
+
+/// Splits `expr` at whitespace, keeping literals and subexpressions whole.
+fn handlebars_tokens(expr: &str) -> Vec<&str> {
+    let (mut tokens, mut start) = (vec![], None);
+    let (mut depth, mut quote) = (0usize, None);
+    for (i, c) in expr.char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == '(' => depth += 1,
+            None if c == ')' => depth = depth.saturating_sub(1),
+            None if c.is_whitespace() && depth == 0 => {
+                tokens.extend(start.take().map(|s| &expr[s..i]));
+                continue;
+            }
+            None => {}
+        }
+
+        start.get_or_insert(i);
+    }
+
+    tokens.extend(start.map(|s| &expr[s..]));
+    tokens
+}
+
+/// Returns the paths among the helper parameters `params`, including those
+/// in hash values and subexpressions, up to any block parameters.
+fn handlebars_paths<'a>(params: &[&'a str]) -> Vec<&'a str> {
+    let mut paths = vec![];
+    for &param in params.iter().take_while(|p| **p != "as") {
+        let param = match param.split_once('=') {
+            Some((key, value)) if !key.starts_with(['(', '"', '\'']) => value,
+            _ => param,
+        };
+
+        if let Some(inner) = param.strip_prefix('(').and_then(|p| p.strip_suffix(')')) {
+            let tokens = handlebars_tokens(inner);
+            paths.extend(handlebars_paths(tokens.get(1..).unwrap_or_default()));
+        } else {
+            paths.push(param);
+        }
+    }
+
+    paths
+}
+
+/// Returns the root variable of `path` and whether it's an absolute `@root`
+/// lookup, or `None` if `path` is a literal or not relative to the context.
+fn handlebars_root(path: &str) -> Option<(&str, bool)> {
+    const LITERALS: &[&str] = &["true", "false", "null", "undefined", "this", "."];
+
+    if !path.bytes().next().is_some_and(|b| is_ident_start(b) || b == b'@' || b == b'.') {
+        return None;
+    }
+
+    let (path, absolute) = match path.strip_prefix("@root") {
+        Some(rest) => (rest.strip_prefix(['.', '/'])?, true),
+        None if path.starts_with('@') || path.starts_with("..") => return None,
+        None => (path, false),
+    };
+
+    let path = ["this.", "this/", "./"].iter()
+        .find_map(|prefix| path.strip_prefix(prefix))
+        .unwrap_or(path);
+
+    let root = path.split(['.', '/', '[']).next()?;
+    match root.is_empty() || LITERALS.contains(&root) {
+        true => None,
+        false => Some((root, absolute)),
+    }
+}
@@ -5,6 +5,7 @@ use std::error::Error;
 use crate::engine::Engines;
 use crate::template::TemplateInfo;
 
+use rocket::{Rocket, Phase};
 use rocket::http::ContentType;
 use normpath::PathExt;
 
@@ -25,8 +26,14 @@ pub(crate) use self::manager::ContextManager;
 impl Context {
     /// Load all of the templates at `root`, initialize them using the relevant
     /// template engine, and store all of the initialized state in a `Context`
-    /// structure, which is returned if all goes well.
-    pub fn initialize(root: &Path, callback: &Callback) -> Option<Context> {
+    /// structure, which is returned if all goes well. If an `Integrity`
+    /// fairing is attached to `rocket`, its `integrity` helper is registered.
+    #[cfg_attr(not(feature = "integrity"), allow(unused_variables))]
+    pub fn initialize<P: Phase>(
+        root: &Path,
+        callback: &Callback,
+        rocket: &Rocket<P>,
+    ) -> Option<Context> {
         fn is_file_with_ext(entry: &walkdir::DirEntry, ext: &str) -> bool {
             let is_file = entry.file_type().is_file();
             let has_ext = entry.path().extension().map_or(false, |e| e == ext);
@@ -76,6 +83,11 @@ impl Context {
         }
 
         let mut engines = Engines::init(&templates)?;
+        #[cfg(feature = "integrity")]
+        if let Some(integrity) = rocket.fairing::<rocket::integrity::Integrity>() {
+            engines.register_integrity(integrity);
+        }
+
         if let Err(reason) = callback(&mut engines) {
             error!(%reason, "template customization callback failed");
             return None;
@@ -129,6 +141,8 @@ mod manager {
 
     use notify::{recommended_watcher, Error, Event, RecommendedWatcher, RecursiveMode, Watcher};
 
+    use rocket::{Rocket, Orbit};
+
     use super::{Callback, Context};
 
     /// Wraps a Context. With `cfg(debug_assertions)` active, this structure
@@ -176,14 +190,14 @@ mod manager {
         /// have been changes since the last reload, all templates are
         /// reinitialized from disk and the user's customization callback is run
         /// again.
-        pub fn reload_if_needed(&self, callback: &Callback) {
+        pub fn reload_if_needed(&self, callback: &Callback, rocket: &Rocket<Orbit>) {
             let templates_changes = self.watcher.as_ref()
                 .map(|(_, rx)| rx.lock().expect("fsevents lock").try_iter().count() > 0);
 
             if let Some(true) = templates_changes {
                 debug!("template change detected: reloading templates");
                 let root = self.context().root.clone();
-                if let Some(new_ctxt) = Context::initialize(&root, callback) {
+                if let Some(new_ctxt) = Context::initialize(&root, callback, rocket) {
                     *self.context_mut() = new_ctxt;
                 } else {
                     warn!("error while reloading template\n\
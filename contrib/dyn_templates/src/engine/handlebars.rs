@@ -1,15 +1,19 @@
 use std::path::Path;
 
-use handlebars::Handlebars;
+use handlebars::{Handlebars, HelperDef, Helper, Context, RenderContext, ScopedJson};
+use handlebars::{JsonValue, RenderError, RenderErrorReason};
 use rocket::serde::Serialize;
 
 use crate::engine::Engine;
+use crate::locale::{Locale, DateStyle, Plural};
 
 impl Engine for Handlebars<'static> {
     const EXT: &'static str = "hbs";
 
     fn init<'a>(templates: impl Iterator<Item = (&'a str, &'a Path)>) -> Option<Self> {
         let mut hb = Handlebars::new();
+        register_locale_helpers(&mut hb);
+
         let mut ok = true;
         for (template, path) in templates {
             if let Err(e) = hb.register_template_file(template, path) {
@@ -34,3 +38,107 @@ impl Engine for Handlebars<'static> {
             .ok()
     }
 }
+
+/// A helper formatting its first parameter for the locale in its `locale` hash.
+struct LocaleHelper(fn(&JsonValue, &Helper<'_>, Locale) -> Result<String, String>);
+
+impl HelperDef for LocaleHelper {
+    fn call_inner<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'rc>,
+        _: &'reg Handlebars<'reg>,
+        _: &'rc Context,
+        _: &mut RenderContext<'reg, 'rc>,
+    ) -> Result<ScopedJson<'rc>, RenderError> {
+        let value = h.param(0).ok_or_else(|| RenderErrorReason::Other(format!(
+            "helper `{}` expects a parameter", h.name()
+        )))?;
+
+        let locale = h.hash_get("locale")
+            .and_then(|locale| locale.value().as_str())
+            .and_then(Locale::new)
+            .unwrap_or_default();
+
+        match (self.0)(value.value(), h, locale) {
+            Ok(string) => Ok(ScopedJson::Derived(JsonValue::String(string))),
+            Err(e) => Err(RenderErrorReason::Other(format!("helper `{}`: {e}", h.name())).into()),
+        }
+    }
+}
+
+fn register_locale_helpers(hb: &mut Handlebars<'static>) {
+    fn hash<'a>(h: &'a Helper<'_>, name: &str) -> Option<&'a JsonValue> {
+        h.hash_get(name).map(|value| value.value())
+    }
+
+    fn number(value: &JsonValue) -> Result<f64, String> {
+        value.as_f64().ok_or_else(|| "expected a number".into())
+    }
+
+    hb.register_helper("format_number", Box::new(LocaleHelper(|value, h, locale| {
+        let decimals = hash(h, "decimals").and_then(JsonValue::as_u64).map(|d| d as usize);
+        Ok(locale.format_number(number(value)?, decimals))
+    })));
+
+    hb.register_helper("format_currency", Box::new(LocaleHelper(|value, h, locale| {
+        let currency = hash(h, "currency").and_then(JsonValue::as_str)
+            .ok_or("expected a `currency`")?;
+
+        Ok(locale.format_currency(number(value)?, currency))
+    })));
+
+    hb.register_helper("format_date", Box::new(LocaleHelper(|value, h, locale| {
+        let style = match hash(h, "style").and_then(JsonValue::as_str) {
+            Some(style) => DateStyle::parse(style).ok_or(format!("unknown style `{style}`"))?,
+            None => DateStyle::default(),
+        };
+
+        let date = match value {
+            JsonValue::String(date) => locale.format_date(date, style),
+            JsonValue::Number(secs) => secs.as_i64().map(|s| locale.format_timestamp(s, style)),
+            _ => None,
+        };
+
+        date.ok_or_else(|| "expected an ISO 8601 date or a timestamp".into())
+    })));
+
+    hb.register_helper("plural", Box::new(LocaleHelper(|value, h, locale| {
+        let form = |plural: Plural| hash(h, plural.as_str()).and_then(JsonValue::as_str);
+        locale.select_plural(number(value)?, form)
+            .ok_or_else(|| "expected at least an `other` form".into())
+    })));
+}
+
+/// A helper returning the integrity of the asset at its first parameter.
+#[cfg(feature = "integrity")]
+struct IntegrityHelper(rocket::integrity::Integrity);
+
+#[cfg(feature = "integrity")]
+impl HelperDef for IntegrityHelper {
+    fn call_inner<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'rc>,
+        _: &'reg Handlebars<'reg>,
+        _: &'rc Context,
+        _: &mut RenderContext<'reg, 'rc>,
+    ) -> Result<ScopedJson<'rc>, RenderError> {
+        let uri = h.param(0).and_then(|uri| uri.value().as_str()).ok_or_else(|| {
+            RenderErrorReason::Other("helper `integrity` expects a URI".into())
+        })?;
+
+        match self.0.manifest().and_then(|manifest| manifest.asset(uri)) {
+            Some(integrity) => Ok(ScopedJson::Derived(JsonValue::String(integrity.into()))),
+            None => Err(RenderErrorReason::Other(format!(
+                "helper `integrity`: no asset `{uri}` in integrity manifest"
+            )).into()),
+        }
+    }
+}
+
+#[cfg(feature = "integrity")]
+pub(crate) fn register_integrity_helper(
+    hb: &mut Handlebars<'static>,
+    integrity: rocket::integrity::Integrity,
+) {
+    hb.register_helper("integrity", Box::new(IntegrityHelper(integrity)));
+}
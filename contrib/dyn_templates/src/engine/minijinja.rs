@@ -64,3 +64,17 @@ impl Engine for Environment<'static> {
         }
     }
 }
+
+#[cfg(feature = "integrity")]
+pub(crate) fn register_integrity_function(
+    env: &mut Environment<'static>,
+    integrity: rocket::integrity::Integrity,
+) {
+    env.add_function("integrity", move |uri: &str| {
+        integrity.manifest()
+            .and_then(|manifest| manifest.asset(uri))
+            .map(|integrity| integrity.to_string())
+            .ok_or_else(|| Error::new(ErrorKind::InvalidOperation,
+                format!("no asset `{uri}` in integrity manifest")))
+    });
+}
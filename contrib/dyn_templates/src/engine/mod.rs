@@ -154,6 +154,22 @@ impl Engines {
         None
     }
 
+    /// Registers the `integrity` function or helper, resolving asset URIs in
+    /// the manifest of `integrity`, with every enabled engine.
+    #[cfg(feature = "integrity")]
+    pub(crate) fn register_integrity(&mut self, integrity: &rocket::integrity::Integrity) {
+        #[cfg(feature = "tera")]
+        tera::register_integrity_function(&mut self.tera, integrity.clone());
+
+        #[cfg(feature = "handlebars")]
+        handlebars::register_integrity_helper(&mut self.handlebars, integrity.clone());
+
+        #[cfg(feature = "minijinja")]
+        minijinja::register_integrity_function(&mut self.minijinja, integrity.clone());
+
+        let _ = integrity;
+    }
+
     /// Returns iterator over template (name, engine_extension).
     pub(crate) fn templates(&self) -> impl Iterator<Item = (&str, &'static str)> {
         #[cfg(feature = "tera")]
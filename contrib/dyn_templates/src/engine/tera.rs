@@ -1,10 +1,12 @@
 use std::path::Path;
 use std::error::Error;
+use std::collections::HashMap;
 
-use tera::{Context, Tera};
+use tera::{Context, Tera, Value};
 use rocket::serde::Serialize;
 
 use crate::engine::Engine;
+use crate::locale::{Locale, DateStyle, Plural};
 
 impl Engine for Tera {
     const EXT: &'static str = "tera";
@@ -14,6 +16,7 @@ impl Engine for Tera {
         let mut tera = Tera::default();
         let ext = [".html.tera", ".htm.tera", ".xml.tera", ".html", ".htm", ".xml"];
         tera.autoescape_on(ext.to_vec());
+        register_locale_filters(&mut tera);
 
         // Collect into a tuple of (name, path) for Tera. If we register one at
         // a time, it will complain about unregistered base templates.
@@ -61,3 +64,74 @@ impl Engine for Tera {
         }
     }
 }
+
+type Args = HashMap<String, Value>;
+
+fn register_locale_filters(tera: &mut Tera) {
+    fn locale(args: &Args) -> Locale {
+        args.get("locale").and_then(Value::as_str).and_then(Locale::new).unwrap_or_default()
+    }
+
+    fn number(filter: &str, value: &Value) -> tera::Result<f64> {
+        value.as_f64()
+            .ok_or_else(|| tera::Error::msg(format!("filter `{filter}` expects a number")))
+    }
+
+    tera.register_filter("format_number", |value: &Value, args: &Args| {
+        let decimals = args.get("decimals").and_then(Value::as_u64).map(|d| d as usize);
+        let number = number("format_number", value)?;
+        Ok(Value::from(locale(args).format_number(number, decimals)))
+    });
+
+    tera.register_filter("format_currency", |value: &Value, args: &Args| {
+        let currency = args.get("currency").and_then(Value::as_str)
+            .ok_or_else(|| tera::Error::msg("filter `format_currency` expects a `currency`"))?;
+
+        let number = number("format_currency", value)?;
+        Ok(Value::from(locale(args).format_currency(number, currency)))
+    });
+
+    tera.register_filter("format_date", |value: &Value, args: &Args| {
+        let style = match args.get("style").and_then(Value::as_str) {
+            Some(style) => DateStyle::parse(style).ok_or_else(|| {
+                tera::Error::msg(format!("filter `format_date`: unknown style `{style}`"))
+            })?,
+            None => DateStyle::default(),
+        };
+
+        let locale = locale(args);
+        let date = match value {
+            Value::String(date) => locale.format_date(date, style),
+            Value::Number(secs) => secs.as_i64().map(|s| locale.format_timestamp(s, style)),
+            _ => None,
+        };
+
+        date.map(Value::from).ok_or_else(|| {
+            tera::Error::msg("filter `format_date` expects an ISO 8601 date or a timestamp")
+        })
+    });
+
+    tera.register_filter("plural", |value: &Value, args: &Args| {
+        let number = number("plural", value)?;
+        let form = |plural: Plural| args.get(plural.as_str()).and_then(Value::as_str);
+        locale(args).select_plural(number, form)
+            .map(Value::from)
+            .ok_or_else(|| tera::Error::msg("filter `plural` expects at least an `other` form"))
+    });
+}
+
+#[cfg(feature = "integrity")]
+pub(crate) fn register_integrity_function(
+    tera: &mut Tera,
+    integrity: rocket::integrity::Integrity,
+) {
+    tera.register_function("integrity", move |args: &Args| {
+        let uri = args.get("uri").and_then(Value::as_str)
+            .ok_or_else(|| tera::Error::msg("function `integrity` expects a `uri`"))?;
+
+        integrity.manifest()
+            .and_then(|manifest| manifest.asset(uri))
+            .map(Value::from)
+            .ok_or_else(|| tera::Error::msg(format!("no asset `{uri}` in integrity manifest")))
+    });
+}
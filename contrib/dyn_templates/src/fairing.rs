@@ -46,7 +46,7 @@ impl Fairing for TemplateFairing {
             }
         };
 
-        if let Some(ctxt) = Context::initialize(&path, &self.callback) {
+        if let Some(ctxt) = Context::initialize(&path, &self.callback, &rocket) {
             Ok(rocket.manage(ContextManager::new(ctxt)))
         } else {
             error!("Template initialization failed. Aborting launch.");
@@ -69,6 +69,6 @@ impl Fairing for TemplateFairing {
         let cm = req.rocket().state::<ContextManager>()
             .expect("Template ContextManager registered in on_ignite");
 
-        cm.reload_if_needed(&self.callback);
+        cm.reload_if_needed(&self.callback, req.rocket());
     }
 }
@@ -175,6 +175,40 @@
 //! metadata, such as whether a template is known to exist
 //! ([`Metadata::contains_template()`]), and to render templates to `String`
 //! ([`Metadata::render()`]).
+//!
+//! ### Typed Contexts
+//!
+//! Deriving [`TemplateContext`](derive@TemplateContext) for a struct ties it
+//! to a template: the derive checks at compile-time that the template exists
+//! and that the struct provides every variable the template uses, turning a
+//! missing variable into a compile error instead of a rendering failure.
+//! [`TemplateContext::render()`] renders the template with the struct.
+//!
+//! ### Locale-Aware Formatting
+//!
+//! The Tera and Handlebars engines are initialized with `format_number`,
+//! `format_currency`, `format_date`, and `plural` filters and helpers that
+//! format values for a locale. The [`Locale`] request guard negotiates the
+//! locale of a request; see the [`locale`] module for details.
+//!
+//! ### Asset Integrity
+//!
+//! With the `integrity` feature enabled and Rocket's [`Integrity`] fairing
+//! attached, each engine is initialized with an `integrity` function (Tera,
+//! Minijinja) or helper (Handlebars) that returns the [Subresource Integrity]
+//! value of the static asset at a URI in the fairing's manifest. Rendering
+//! fails if the asset isn't in the manifest:
+//!
+//! ```text
+//! <script src="/static/app.js" integrity="{{ integrity(uri='/static/app.js') }}"></script>
+//! <script src="/static/app.js" integrity="{{ integrity("/static/app.js") }}"></script>
+//! <script src="/static/app.js" integrity="{{integrity "/static/app.js"}}"></script>
+//! ```
+//!
+//! The lines above are for Tera, Minijinja, and Handlebars, respectively.
+//!
+//! [`Integrity`]: rocket::integrity::Integrity
+//! [Subresource Integrity]: https://developer.mozilla.org/en-US/docs/Web/Security/Subresource_Integrity
 
 #![doc(html_root_url = "https://api.rocket.rs/master/rocket_dyn_templates")]
 #![doc(html_favicon_url = "https://rocket.rs/images/favicon.ico")]
@@ -206,6 +240,12 @@ mod context;
 mod metadata;
 mod template;
 
+pub mod locale;
+
 pub use engine::Engines;
 pub use metadata::Metadata;
-pub use template::Template;
+pub use template::{Template, TemplateContext};
+pub use locale::Locale;
+
+#[doc(inline)]
+pub use rocket_dyn_templates_codegen::TemplateContext;
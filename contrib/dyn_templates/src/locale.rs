@@ -0,0 +1,480 @@
+//! Locale-aware formatting of numbers, currencies, dates, and plurals.
+//!
+//! A [`Locale`] is negotiated from a request's `Accept-Language` header by its
+//! [`FromRequest`] implementation and formats values per a compact subset of
+//! [CLDR] data. The same formatting is available in templates as filters
+//! (Tera) and helpers (Handlebars), registered automatically, which take the
+//! locale's tag as their `locale` argument. Pass the negotiated locale to the
+//! template in its context:
+//!
+//! ```rust
+//! # #[macro_use] extern crate rocket;
+//! use rocket_dyn_templates::{Template, Locale, context};
+//!
+//! #[get("/invoice")]
+//! fn invoice(locale: Locale) -> Template {
+//!     Template::render("invoice", context! { locale, total: 1234.5, items: 3 })
+//! }
+//! ```
+//!
+//! The template then formats the values with the locale, here in Tera:
+//!
+//! ```text
+//! {{ total | format_number(locale=locale, decimals=2) }}
+//! {{ total | format_currency(locale=locale, currency="EUR") }}
+//! {{ due | format_date(locale=locale, style="long") }}
+//! {{ items | plural(locale=locale, one="# item", other="# items") }}
+//! ```
+//!
+//! and in Handlebars:
+//!
+//! ```text
+//! {{format_number total locale=locale decimals=2}}
+//! {{format_currency total locale=locale currency="EUR"}}
+//! {{format_date due locale=locale style="long"}}
+//! {{plural items locale=locale one="# item" other="# items"}}
+//! ```
+//!
+//! Dates are either ISO 8601 strings, of which only the date is used, or Unix
+//! timestamps in seconds. The style of a date is either `short`, the default,
+//! or `long`. A plural form's `#` is replaced by the formatted number; missing
+//! forms fall back to `other`. An unknown or missing `locale` formats as
+//! [`Locale::default()`], `en`.
+//!
+//! The supported locales are `en`, `en-GB`, `de`, `fr`, `es`, `it`, `pt`,
+//! `nl`, `pl`, and `ja`. Locales with a region, like `de-AT`, use the data of
+//! their language.
+//!
+//! [CLDR]: https://cldr.unicode.org/
+//! [`FromRequest`]: rocket::request::FromRequest
+
+use std::fmt;
+
+use rocket::Request;
+use rocket::request::{self, FromRequest};
+use rocket::serde::{Serialize, Serializer};
+
+/// A locale to format values for. See the [module docs](self).
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Locale(&'static Data);
+
+/// The style of a formatted date.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DateStyle {
+    /// A numeric date, as in `3/5/2024` or `05.03.2024`.
+    #[default]
+    Short,
+    /// A date with the month spelled out, as in `March 5, 2024`.
+    Long,
+}
+
+/// A CLDR plural category.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Plural {
+    /// The `one` category.
+    One,
+    /// The `few` category.
+    Few,
+    /// The `many` category.
+    Many,
+    /// The `other` category.
+    Other,
+}
+
+struct Data {
+    tag: &'static str,
+    decimal: &'static str,
+    group: &'static str,
+    /// Where the currency symbol, `¤`, goes relative to the number, `#`.
+    currency: &'static str,
+    short_date: &'static str,
+    long_date: &'static str,
+    months: [&'static str; 12],
+    /// The plural category of a number with integer part `i` and `v` visible
+    /// fraction digits.
+    plural: fn(i: u64, v: usize) -> Plural,
+}
+
+const EN_MONTHS: [&str; 12] = ["January", "February", "March", "April", "May", "June", "July",
+    "August", "September", "October", "November", "December"];
+
+static LOCALES: &[Data] = &[
+    Data {
+        tag: "en", decimal: ".", group: ",", currency: "¤#",
+        short_date: "M/d/y", long_date: "MMMM d, y",
+        months: EN_MONTHS,
+        plural: one_if_one,
+    },
+    Data {
+        tag: "en-GB", decimal: ".", group: ",", currency: "¤#",
+        short_date: "dd/MM/y", long_date: "d MMMM y",
+        months: EN_MONTHS,
+        plural: one_if_one,
+    },
+    Data {
+        tag: "de", decimal: ",", group: ".", currency: "#\u{a0}¤",
+        short_date: "dd.MM.y", long_date: "d. MMMM y",
+        months: ["Januar", "Februar", "März", "April", "Mai", "Juni", "Juli", "August",
+            "September", "Oktober", "November", "Dezember"],
+        plural: one_if_one,
+    },
+    Data {
+        tag: "fr", decimal: ",", group: "\u{202f}", currency: "#\u{a0}¤",
+        short_date: "dd/MM/y", long_date: "d MMMM y",
+        months: ["janvier", "février", "mars", "avril", "mai", "juin", "juillet", "août",
+            "septembre", "octobre", "novembre", "décembre"],
+        plural: |i, _| if i <= 1 { Plural::One } else { Plural::Other },
+    },
+    Data {
+        tag: "es", decimal: ",", group: ".", currency: "#\u{a0}¤",
+        short_date: "d/M/y", long_date: "d 'de' MMMM 'de' y",
+        months: ["enero", "febrero", "marzo", "abril", "mayo", "junio", "julio", "agosto",
+            "septiembre", "octubre", "noviembre", "diciembre"],
+        plural: one_if_one,
+    },
+    Data {
+        tag: "it", decimal: ",", group: ".", currency: "#\u{a0}¤",
+        short_date: "dd/MM/y", long_date: "d MMMM y",
+        months: ["gennaio", "febbraio", "marzo", "aprile", "maggio", "giugno", "luglio",
+            "agosto", "settembre", "ottobre", "novembre", "dicembre"],
+        plural: one_if_one,
+    },
+    Data {
+        tag: "pt", decimal: ",", group: ".", currency: "¤\u{a0}#",
+        short_date: "dd/MM/y", long_date: "d 'de' MMMM 'de' y",
+        months: ["janeiro", "fevereiro", "março", "abril", "maio", "junho", "julho", "agosto",
+            "setembro", "outubro", "novembro", "dezembro"],
+        plural: |i, _| if i <= 1 { Plural::One } else { Plural::Other },
+    },
+    Data {
+        tag: "nl", decimal: ",", group: ".", currency: "¤\u{a0}#",
+        short_date: "dd-MM-y", long_date: "d MMMM y",
+        months: ["januari", "februari", "maart", "april", "mei", "juni", "juli", "augustus",
+            "september", "oktober", "november", "december"],
+        plural: one_if_one,
+    },
+    Data {
+        tag: "pl", decimal: ",", group: "\u{a0}", currency: "#\u{a0}¤",
+        short_date: "d.MM.y", long_date: "d MMMM y",
+        months: ["stycznia", "lutego", "marca", "kwietnia", "maja", "czerwca", "lipca",
+            "sierpnia", "września", "października", "listopada", "grudnia"],
+        plural: |i, v| match (v, i % 10, i % 100) {
+            (0, _, _) if i == 1 => Plural::One,
+            (0, 2..=4, n) if !(12..=14).contains(&n) => Plural::Few,
+            (0, _, _) => Plural::Many,
+            _ => Plural::Other,
+        },
+    },
+    Data {
+        tag: "ja", decimal: ".", group: ",", currency: "¤#",
+        short_date: "y/MM/dd", long_date: "y年M月d日",
+        months: ["1月", "2月", "3月", "4月", "5月", "6月", "7月", "8月", "9月", "10月", "11月",
+            "12月"],
+        plural: |_, _| Plural::Other,
+    },
+];
+
+/// Symbols of common currencies and their number of fraction digits.
+static CURRENCIES: &[(&str, &str, usize)] = &[
+    ("USD", "$", 2), ("EUR", "€", 2), ("GBP", "£", 2), ("JPY", "¥", 0), ("CHF", "CHF", 2),
+    ("PLN", "zł", 2), ("BRL", "R$", 2), ("CAD", "CA$", 2), ("AUD", "A$", 2), ("CNY", "CN¥", 2),
+];
+
+fn one_if_one(i: u64, v: usize) -> Plural {
+    if i == 1 && v == 0 { Plural::One } else { Plural::Other }
+}
+
+impl Locale {
+    /// Returns the supported locale for `tag`, if any. A tag with a region
+    /// that isn't supported falls back to its language.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_dyn_templates::Locale;
+    ///
+    /// assert_eq!(Locale::new("en-GB").unwrap().tag(), "en-GB");
+    /// assert_eq!(Locale::new("de-AT").unwrap().tag(), "de");
+    /// assert!(Locale::new("xx").is_none());
+    /// ```
+    pub fn new(tag: &str) -> Option<Locale> {
+        let mut tag = tag.trim();
+        loop {
+            if let Some(data) = LOCALES.iter().find(|l| l.tag.eq_ignore_ascii_case(tag)) {
+                return Some(Locale(data));
+            }
+
+            tag = &tag[..tag.rfind('-')?];
+        }
+    }
+
+    /// Returns the tags of all supported locales.
+    pub fn available() -> impl Iterator<Item = &'static str> {
+        LOCALES.iter().map(|l| l.tag)
+    }
+
+    /// Returns this locale's tag.
+    pub fn tag(&self) -> &'static str {
+        self.0.tag
+    }
+
+    /// Formats `value` with `decimals` fraction digits or, if `None`, with up
+    /// to three, omitting trailing zeros.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_dyn_templates::Locale;
+    ///
+    /// let de = Locale::new("de").unwrap();
+    /// assert_eq!(de.format_number(1234567.891, None), "1.234.567,891");
+    /// assert_eq!(de.format_number(-1234.5, Some(2)), "-1.234,50");
+    /// assert_eq!(Locale::default().format_number(1000.0, None), "1,000");
+    /// ```
+    pub fn format_number(&self, value: f64, decimals: Option<usize>) -> String {
+        let formatted = format!("{:.*}", decimals.unwrap_or(3), value.abs());
+        let (int, mut frac) = formatted.split_once('.').unwrap_or((&formatted, ""));
+        if decimals.is_none() {
+            frac = frac.trim_end_matches('0');
+        }
+
+        let mut number = String::new();
+        if value < 0.0 && formatted.bytes().any(|b| b.is_ascii_digit() && b != b'0') {
+            number.push('-');
+        }
+
+        for (i, digit) in int.chars().enumerate() {
+            if i > 0 && (int.len() - i) % 3 == 0 {
+                number.push_str(self.0.group);
+            }
+
+            number.push(digit);
+        }
+
+        if !frac.is_empty() {
+            number.push_str(self.0.decimal);
+            number.push_str(frac);
+        }
+
+        number
+    }
+
+    /// Formats `value` as an amount of the currency with ISO 4217 code
+    /// `currency`. Unknown currencies are shown by their code.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_dyn_templates::Locale;
+    ///
+    /// let en = Locale::default();
+    /// assert_eq!(en.format_currency(1234.5, "USD"), "$1,234.50");
+    ///
+    /// let fr = Locale::new("fr").unwrap();
+    /// let eur = fr.format_currency(1234.5, "EUR");
+    /// assert_eq!(eur, "1\u{202f}234,50\u{a0}€");
+    /// assert_eq!(fr.format_currency(1234.5, "JPY"), "1\u{202f}235\u{a0}¥");
+    /// ```
+    pub fn format_currency(&self, value: f64, currency: &str) -> String {
+        let (symbol, decimals) = CURRENCIES.iter()
+            .find(|(code, ..)| code.eq_ignore_ascii_case(currency))
+            .map_or((currency, 2), |(_, symbol, decimals)| (*symbol, *decimals));
+
+        let number = self.format_number(value, Some(decimals));
+        let (sign, number) = match number.strip_prefix('-') {
+            Some(number) => ("-", number),
+            None => ("", &*number),
+        };
+
+        format!("{sign}{}", self.0.currency.replace('¤', symbol).replace('#', number))
+    }
+
+    /// Formats the ISO 8601 date at the start of `date`, as in `2024-03-05` or
+    /// `2024-03-05T10:00:00Z`. Returns `None` if `date` isn't a valid date.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_dyn_templates::{Locale, locale::DateStyle};
+    ///
+    /// let en = Locale::default();
+    /// assert_eq!(en.format_date("2024-03-05", DateStyle::Short).unwrap(), "3/5/2024");
+    /// assert_eq!(en.format_date("2024-03-05", DateStyle::Long).unwrap(), "March 5, 2024");
+    ///
+    /// let es = Locale::new("es").unwrap();
+    /// let date = es.format_date("2024-03-05T10:00:00Z", DateStyle::Long).unwrap();
+    /// assert_eq!(date, "5 de marzo de 2024");
+    /// ```
+    pub fn format_date(&self, date: &str, style: DateStyle) -> Option<String> {
+        let (year, month, day) = parse_date(date)?;
+        Some(self.format_ymd(year, month, day, style))
+    }
+
+    /// Formats the date of the Unix timestamp `secs`, in UTC.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_dyn_templates::{Locale, locale::DateStyle};
+    ///
+    /// let de = Locale::new("de").unwrap();
+    /// assert_eq!(de.format_timestamp(1709632800, DateStyle::Short), "05.03.2024");
+    /// ```
+    pub fn format_timestamp(&self, secs: i64, style: DateStyle) -> String {
+        let (year, month, day) = civil_from_days(secs.div_euclid(86_400));
+        self.format_ymd(year, month, day, style)
+    }
+
+    /// Returns the plural category of `n`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_dyn_templates::{Locale, locale::Plural};
+    ///
+    /// let pl = Locale::new("pl").unwrap();
+    /// assert_eq!(pl.plural(1.0), Plural::One);
+    /// assert_eq!(pl.plural(3.0), Plural::Few);
+    /// assert_eq!(pl.plural(13.0), Plural::Many);
+    /// assert_eq!(pl.plural(1.5), Plural::Other);
+    /// ```
+    pub fn plural(&self, n: f64) -> Plural {
+        let formatted = self.format_number(n, None);
+        let v = formatted.split_once(self.0.decimal).map_or(0, |(_, frac)| frac.len());
+        (self.0.plural)(n.abs().trunc() as u64, v)
+    }
+
+    /// Selects the form of `n` for its plural category via `form`, falling
+    /// back to the `other` form, and replaces `#` in it with `n`, formatted.
+    pub fn select_plural<'a, F>(&self, n: f64, form: F) -> Option<String>
+        where F: Fn(Plural) -> Option<&'a str>
+    {
+        let text = form(self.plural(n)).or_else(|| form(Plural::Other))?;
+        Some(text.replace('#', &self.format_number(n, None)))
+    }
+
+    fn format_ymd(&self, year: i64, month: u32, day: u32, style: DateStyle) -> String {
+        let pattern = match style {
+            DateStyle::Short => self.0.short_date,
+            DateStyle::Long => self.0.long_date,
+        };
+
+        let mut output = String::new();
+        let mut chars = pattern.chars().peekable();
+        while let Some(c) = chars.next() {
+            let mut count = 1;
+            while chars.peek() == Some(&c) && "yMd".contains(c) {
+                chars.next();
+                count += 1;
+            }
+
+            match (c, count) {
+                ('y', _) => output.push_str(&year.to_string()),
+                ('M', 1) => output.push_str(&month.to_string()),
+                ('M', 2) => output.push_str(&format!("{month:02}")),
+                ('M', _) => output.push_str(self.0.months[month as usize - 1]),
+                ('d', 1) => output.push_str(&day.to_string()),
+                ('d', _) => output.push_str(&format!("{day:02}")),
+                ('\'', _) => output.extend(chars.by_ref().take_while(|&c| c != '\'')),
+                (c, _) => output.push(c),
+            }
+        }
+
+        output
+    }
+}
+
+impl Plural {
+    /// Returns the CLDR name of this category: `one`, `few`, `many`, or
+    /// `other`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Plural::One => "one",
+            Plural::Few => "few",
+            Plural::Many => "many",
+            Plural::Other => "other",
+        }
+    }
+}
+
+impl DateStyle {
+    /// Parses `short` or `long`.
+    pub fn parse(style: &str) -> Option<DateStyle> {
+        match style {
+            "short" => Some(DateStyle::Short),
+            "long" => Some(DateStyle::Long),
+            _ => None,
+        }
+    }
+}
+
+/// Parses the `YYYY-MM-DD` at the start of `date`.
+fn parse_date(date: &str) -> Option<(i64, u32, u32)> {
+    let date = date.trim();
+    let ymd = date.get(..10)?;
+    if !matches!(date.as_bytes().get(10), None | Some(b'T' | b't' | b' ')) {
+        return None;
+    }
+
+    let mut parts = ymd.splitn(3, '-');
+    let year: i64 = parts.next()?.parse().ok()?;
+    let month: u32 = parts.next()?.parse().ok()?;
+    let day: u32 = parts.next()?.parse().ok()?;
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days = [31, if leap { 29 } else { 28 }, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
+    let valid = (1..=12).contains(&month) && day >= 1 && day <= days[month as usize - 1];
+    valid.then_some((year, month, day))
+}
+
+/// Returns the civil date `days` days after the Unix epoch.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+impl Default for Locale {
+    /// Returns the `en` locale.
+    fn default() -> Self {
+        Locale(&LOCALES[0])
+    }
+}
+
+impl fmt::Debug for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Locale").field(&self.tag()).finish()
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.tag().fmt(f)
+    }
+}
+
+impl Serialize for Locale {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.tag())
+    }
+}
+
+/// Negotiates the locale from the `Accept-Language` header among the
+/// [available](Locale::available()) locales, falling back to
+/// [`Locale::default()`]. Never fails.
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for Locale {
+    type Error = std::convert::Infallible;
+
+    async fn from_request(req: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        let tags: Vec<_> = Locale::available().collect();
+        let locale = req.accept_language().negotiate(&tags).and_then(|tag| Locale::new(tag));
+        request::Outcome::Success(locale.unwrap_or_default())
+    }
+}
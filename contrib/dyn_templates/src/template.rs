@@ -296,6 +296,46 @@ impl Sentinel for Template {
     }
 }
 
+/// A context for a single template, checked at compile-time to provide every
+/// variable the template requires.
+///
+/// This trait should be implemented via `#[derive(TemplateContext)]`, which
+/// fails to compile if the named template doesn't exist or uses a variable
+/// the struct doesn't provide. See the [derive's
+/// documentation](derive@crate::TemplateContext) for details.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # #[macro_use] extern crate rocket;
+/// use rocket::serde::Serialize;
+/// use rocket_dyn_templates::{Template, TemplateContext};
+///
+/// /// The template uses the variables `title` and `content`.
+/// #[derive(Serialize, TemplateContext)]
+/// #[serde(crate = "rocket::serde")]
+/// #[template("tera/html_test", dir = "tests/templates")]
+/// struct Page<'a> {
+///     title: &'a str,
+///     content: &'a str,
+/// }
+///
+/// #[get("/")]
+/// fn index() -> Template {
+///     Page { title: "Home", content: "Welcome!" }.render()
+/// }
+/// ```
+pub trait TemplateContext: Serialize {
+    /// The name of the template this context is for.
+    const NAME: &'static str;
+
+    /// Returns a [`Template`] that renders [`Self::NAME`] with `self` as the
+    /// context.
+    fn render(self) -> Template where Self: Sized {
+        Template::render(Self::NAME, self)
+    }
+}
+
 /// A macro to easily create a template rendering context.
 ///
 /// Invocations of this macro expand to a value of an anonymous type which
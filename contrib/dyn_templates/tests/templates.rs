@@ -6,7 +6,7 @@ use rocket::{Rocket, Build};
 use rocket::config::Config;
 use rocket::figment::value::Value;
 use rocket::serde::{Serialize, Deserialize};
-use rocket_dyn_templates::{Template, Metadata, context};
+use rocket_dyn_templates::{Template, Metadata, Locale, context};
 
 #[get("/<engine>/<name>")]
 fn template_check(md: Metadata<'_>, engine: &str, name: &str) -> Option<()> {
@@ -200,6 +200,29 @@ fn test_context_macro() {
     }
 }
 
+#[test]
+fn test_locale_negotiation() {
+    use rocket::local::blocking::Client;
+    use rocket::http::Header;
+
+    #[get("/")]
+    fn locale(locale: Locale) -> String {
+        locale.to_string()
+    }
+
+    let client = Client::debug_with(routes![locale]).unwrap();
+    let negotiate = |header: &'static str| {
+        let header = Header::new("Accept-Language", header);
+        client.get("/").header(header).dispatch().into_string().unwrap()
+    };
+
+    assert_eq!(negotiate("de-DE, en;q=0.5"), "de");
+    assert_eq!(negotiate("en-GB"), "en-GB");
+    assert_eq!(negotiate("xx, pl;q=0.1"), "pl");
+    assert_eq!(negotiate("xx"), "en");
+    assert_eq!(client.get("/").dispatch().into_string().unwrap(), "en");
+}
+
 #[cfg(feature = "tera")]
 mod tera_tests {
     use super::*;
@@ -281,6 +304,42 @@ mod tera_tests {
         let response = client.get("/hbs/txt_test").dispatch();
         assert_eq!(response.status(), Status::NotFound);
     }
+
+    #[test]
+    fn test_tera_template_context() {
+        use rocket::local::blocking::Client;
+        use rocket_dyn_templates::TemplateContext;
+
+        #[derive(Serialize, TemplateContext)]
+        #[serde(crate = "rocket::serde")]
+        #[template("tera/txt_test", dir = "tests/templates")]
+        struct TxtTest<'a> {
+            title: &'a str,
+            content: &'a str,
+        }
+
+        assert_eq!(TxtTest::NAME, "tera/txt_test");
+
+        let client = Client::debug(rocket()).unwrap();
+        let context = TxtTest { title: "_test_", content: "<script />" };
+        let template = Template::show(client.rocket(), TxtTest::NAME, context);
+        assert_eq!(template, Some(UNESCAPED_EXPECTED.into()));
+    }
+
+    #[test]
+    fn test_tera_locale_filters() {
+        use rocket::local::blocking::Client;
+
+        let client = Client::debug(rocket()).unwrap();
+        let render = |locale: &str, items: usize| {
+            let context = context! { locale, total: 1234.5, due: "2024-03-05", items };
+            Template::show(client.rocket(), "tera/locale", context).unwrap()
+        };
+
+        assert_eq!(render("en", 3), "1,234.50\n€1,234.50\nMarch 5, 2024\n3 items");
+        assert_eq!(render("de", 1), "1.234,50\n1.234,50\u{a0}€\n5. März 2024\n1 item");
+        assert_eq!(render("xx", 1), "1,234.50\n€1,234.50\nMarch 5, 2024\n1 item");
+    }
 }
 
 #[cfg(feature = "handlebars")]
@@ -344,6 +403,47 @@ mod handlebars_tests {
         assert_eq!(response.status(), Status::NotFound);
     }
 
+    #[test]
+    fn test_handlebars_template_context() {
+        use rocket::local::blocking::Client;
+        use rocket_dyn_templates::TemplateContext;
+
+        #[derive(Serialize, TemplateContext)]
+        #[serde(crate = "rocket::serde")]
+        #[template("hbs/locale", dir = "tests/templates")]
+        struct Invoice {
+            #[serde(rename = "locale")]
+            #[template(name = "locale")]
+            language: Locale,
+            total: f64,
+            due: &'static str,
+            items: usize,
+        }
+
+        let client = Client::debug(rocket()).unwrap();
+        let language = Locale::new("en").unwrap();
+        let context = Invoice { language, total: 1234.5, due: "2024-03-05", items: 3 };
+        let template = Template::show(client.rocket(), Invoice::NAME, context).unwrap();
+        assert_eq!(template, "1,234.50\n€1,234.50\nMarch 5, 2024\n3 items");
+    }
+
+    #[test]
+    fn test_handlebars_locale_helpers() {
+        use rocket::local::blocking::Client;
+
+        let client = Client::debug(rocket()).unwrap();
+        let render = |locale: &str, items: usize| {
+            let context = context! { locale, total: 1234.5, due: "2024-03-05", items };
+            Template::show(client.rocket(), "hbs/locale", context).unwrap()
+        };
+
+        assert_eq!(render("en", 3), "1,234.50\n€1,234.50\nMarch 5, 2024\n3 items");
+
+        const FR: &str = "1\u{202f}234,50\n1\u{202f}234,50\u{a0}€\n5 mars 2024\n";
+        assert_eq!(render("fr", 1), format!("{FR}1 item"));
+        assert_eq!(render("fr", 2), format!("{FR}2 items"));
+    }
+
     #[test]
     #[cfg(debug_assertions)]
     fn test_template_reload() {
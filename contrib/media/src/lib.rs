@@ -0,0 +1,465 @@
+//! On-the-fly image transformations for Rocket.
+//!
+//! This crate provides [`Media`], a mountable handler that serves stored
+//! images, like user uploads, and resizes and converts them on request: the
+//! common "thumbnailer" service.
+//!
+//! # Usage
+//!
+//! Depend on the crate:
+//!
+//! ```toml
+//! [dependencies]
+//! rocket_media = "0.1.0"
+//! ```
+//!
+//! Then, mount a [`Media`] handler with the directory that stores the images
+//! and a key used to sign transformations:
+//!
+//! ```rust
+//! # use rocket::launch;
+//! use rocket_media::Media;
+//!
+//! #[launch]
+//! fn rocket() -> _ {
+//!     let media = Media::new("uploads", b"a secret signing key")
+//!         .cache("cache/media");
+//!
+//!     rocket::build().mount("/media", media)
+//! }
+//! ```
+//!
+//! A request to `/media/cat.jpg` now responds with `uploads/cat.jpg` as it is,
+//! while a request with [`Transform`] query parameters, like
+//! `/media/cat.jpg?w=200&sig=...`, responds with the image transformed.
+//!
+//! # Signing
+//!
+//! Transforming an image is expensive, so only transformations signed with
+//! the key are served. This prevents clients from requesting arbitrary sizes,
+//! and thus from filling the cache or exhausting the server. Requests with
+//! transformation parameters but without a valid `sig` parameter fail with
+//! `403 Forbidden`.
+//!
+//! Sign transformations when generating URLs with [`Media::sign()`], which
+//! returns the query string for a transformation of an image:
+//!
+//! ```rust
+//! use rocket_media::{Media, Transform, Fit};
+//!
+//! let media = Media::new("uploads", b"a secret signing key");
+//! let thumbnail = Transform::new().width(128).height(128).fit(Fit::Cover);
+//! let query = media.sign("pets/cat.jpg", &thumbnail);
+//! assert!(query.starts_with("w=128&h=128&fit=cover&sig="));
+//!
+//! let url = format!("/media/pets/cat.jpg?{query}");
+//! ```
+//!
+//! The key should be secret and at least 32 bytes long. Changing it
+//! invalidates all previously generated URLs.
+//!
+//! # Format Negotiation
+//!
+//! Unless the transformation sets a [`Format`], the transformed image's format
+//! is negotiated with the client via the `Accept` header: AVIF, when the
+//! `avif` feature is enabled, and then WebP are preferred if the client
+//! explicitly accepts them. Otherwise, JPEGs remain JPEGs while all other
+//! images are encoded as PNG. Transformed responses set `Vary: Accept`.
+//!
+//! # Caching
+//!
+//! When a cache directory is set with [`Media::cache()`], transformed images
+//! are written to it and served from it on subsequent requests. Cached images
+//! are keyed by the image's path, size, and modification time as well as the
+//! transformation and format, so replacing an image never serves a stale
+//! transformation. The cache is never pruned; it's safe to delete any or all
+//! of its files at any time.
+//!
+//! # Features
+//!
+//! JPEG, PNG, GIF, and WebP images are served and can be transformed. The
+//! `avif` feature enables encoding transformed images as AVIF.
+
+#[macro_use] extern crate rocket;
+
+mod transform;
+
+pub use transform::{Transform, Fit, Format};
+
+use std::fmt;
+use std::io::{self, Cursor};
+use std::path::{Path, PathBuf};
+
+use ring::{digest, hmac};
+use rocket::{Request, Response, Data};
+use rocket::fs::NamedFile;
+use rocket::http::{Header, Method, Status, MediaType, uri::Segments};
+use rocket::http::uri::fmt::Path as UriPath;
+use rocket::response::{self, Responder};
+use rocket::route::{Route, Handler, Outcome};
+use rocket::tokio::fs;
+
+/// The default maximum width and height of transformed images.
+const DEFAULT_MAX_SIZE: u32 = 4096;
+
+/// The `Cache-Control` header of transformed images.
+const CACHE_CONTROL: &str = "public, max-age=86400";
+
+/// A handler that serves stored images, transforming them on request.
+///
+/// See the [crate docs](crate) for details.
+///
+/// A `Media` handler is mounted directly: it generates a single `GET` route
+/// matching all paths below the mount point. By default, the route has a rank
+/// of `10`, which can be changed with [`Media::rank()`]. Requests for paths
+/// that don't name a JPEG, PNG, GIF, or WebP image in the root directory are
+/// forwarded with a `404` status.
+#[derive(Clone)]
+pub struct Media {
+    root: PathBuf,
+    key: hmac::Key,
+    cache: Option<PathBuf>,
+    max_size: (u32, u32),
+    rank: isize,
+}
+
+impl Media {
+    /// The default rank used by `Media` routes.
+    pub const DEFAULT_RANK: isize = 10;
+
+    /// Returns a handler serving images in `root` that verifies
+    /// transformations with the signing key `key`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_media::Media;
+    ///
+    /// let media = Media::new("uploads", b"a secret signing key");
+    /// ```
+    pub fn new<P: AsRef<Path>>(root: P, key: &[u8]) -> Self {
+        Media {
+            root: root.as_ref().to_path_buf(),
+            key: hmac::Key::new(hmac::HMAC_SHA256, key),
+            cache: None,
+            max_size: (DEFAULT_MAX_SIZE, DEFAULT_MAX_SIZE),
+            rank: Self::DEFAULT_RANK,
+        }
+    }
+
+    /// Caches transformed images in the directory `dir`, which is created if
+    /// it doesn't exist.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_media::Media;
+    ///
+    /// let media = Media::new("uploads", b"a secret signing key")
+    ///     .cache("/var/cache/media");
+    /// ```
+    pub fn cache<P: AsRef<Path>>(mut self, dir: P) -> Self {
+        self.cache = Some(dir.as_ref().to_path_buf());
+        self
+    }
+
+    /// Sets the maximum width and height of transformed images. Requests for
+    /// larger images fail with `400 Bad Request`, even if signed. Both default
+    /// to `4096`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_media::Media;
+    ///
+    /// let media = Media::new("uploads", b"a secret signing key")
+    ///     .max_size(1920, 1080);
+    /// ```
+    pub fn max_size(mut self, width: u32, height: u32) -> Self {
+        self.max_size = (width, height);
+        self
+    }
+
+    /// Sets the rank of the route emitted by the handler to `rank`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_media::Media;
+    ///
+    /// let media = Media::new("uploads", b"a secret signing key").rank(5);
+    /// ```
+    pub fn rank(mut self, rank: isize) -> Self {
+        self.rank = rank;
+        self
+    }
+
+    /// Returns the signed query string for `transform` of the image at
+    /// `path`, relative to the handler's root, as in `pets/cat.jpg`.
+    ///
+    /// The query string includes the transformation's parameters, as returned
+    /// by [`Transform::to_query()`], and its signature.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_media::{Media, Transform};
+    ///
+    /// let media = Media::new("uploads", b"a secret signing key");
+    /// let query = media.sign("cat.jpg", &Transform::new().width(200));
+    /// let url = format!("/media/cat.jpg?{query}");
+    /// ```
+    pub fn sign(&self, path: &str, transform: &Transform) -> String {
+        let query = transform.to_query();
+        let tag = hmac::sign(&self.key, message(Path::new(path), &query).as_bytes());
+        match query.is_empty() {
+            true => format!("sig={}", hex(tag.as_ref())),
+            false => format!("{query}&sig={}", hex(tag.as_ref())),
+        }
+    }
+
+    /// Returns the verified transformation requested by `req` for the image at
+    /// `path`, if any.
+    fn transform(&self, req: &Request<'_>, path: &Path) -> Result<Option<Transform>, Status> {
+        fn param<T>(req: &Request<'_>, name: &str, parse: fn(&str) -> Option<T>)
+            -> Result<Option<T>, Status>
+        {
+            match req.query_value::<&str>(name) {
+                Some(Ok(value)) => parse(value).map(Some).ok_or(Status::BadRequest),
+                Some(Err(_)) => Err(Status::BadRequest),
+                None => Ok(None),
+            }
+        }
+
+        let width = param(req, "w", |v| v.parse().ok())?;
+        let height = param(req, "h", |v| v.parse().ok())?;
+        let fit = param(req, "fit", Fit::parse)?;
+        let quality = param(req, "q", |v| v.parse().ok())?;
+        let format = param(req, "fmt", Format::parse)?;
+        if (width, height, fit, quality, format) == (None, None, None, None, None) {
+            return Ok(None);
+        }
+
+        let fit = fit.unwrap_or_default();
+        let transform = Transform { width, height, fit, quality, format };
+        let signature = param(req, "sig", unhex)?.ok_or(Status::Forbidden)?;
+        let message = message(path, &transform.to_query());
+        hmac::verify(&self.key, message.as_bytes(), &signature)
+            .map_err(|_| Status::Forbidden)?;
+
+        let (max_width, max_height) = self.max_size;
+        if width.map_or(false, |w| w == 0 || w > max_width)
+            || height.map_or(false, |h| h == 0 || h > max_height)
+            || quality.map_or(false, |q| q == 0 || q > 100)
+        {
+            return Err(Status::BadRequest);
+        }
+
+        Ok(Some(transform))
+    }
+
+    /// Returns the image at `path` transformed by `transform` and encoded as
+    /// `format`, from the cache if possible.
+    async fn render(
+        &self,
+        path: &Path,
+        transform: Transform,
+        format: Format
+    ) -> io::Result<Vec<u8>> {
+        let file = self.root.join(path);
+        let metadata = fs::metadata(&file).await?;
+        if !metadata.is_file() {
+            return Err(io::ErrorKind::NotFound.into());
+        }
+
+        let cached = self.cache.as_ref().map(|dir| {
+            let modified = metadata.modified().ok()
+                .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+                .map_or(0, |time| time.as_nanos());
+
+            let key = format!("{}\0{}\0{format}\0{}\0{modified}",
+                canonical(path), transform.to_query(), metadata.len());
+
+            let digest = digest::digest(&digest::SHA256, key.as_bytes());
+            dir.join(format!("{}.{format}", hex(digest.as_ref())))
+        });
+
+        if let Some(cached) = &cached {
+            if let Ok(bytes) = fs::read(cached).await {
+                return Ok(bytes);
+            }
+        }
+
+        let bytes = rocket::tokio::task::spawn_blocking(move || transform.apply(&file, format))
+            .await
+            .map_err(io::Error::other)??;
+
+        if let Some(cached) = &cached {
+            if let Err(e) = write_cache(cached, &bytes).await {
+                warn!(name: "media", path = %cached.display(), error = %e,
+                    "failed to cache transformed image");
+            }
+        }
+
+        Ok(bytes)
+    }
+}
+
+/// Writes `bytes` to `path` atomically: readers never see a partial file.
+async fn write_cache(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).await?;
+    }
+
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(format!(".{}.tmp", std::process::id()));
+    fs::write(&tmp, bytes).await?;
+    if let Err(e) = fs::rename(&tmp, path).await {
+        let _ = fs::remove_file(&tmp).await;
+        return Err(e);
+    }
+
+    Ok(())
+}
+
+/// Returns `path` with `/` separators.
+fn canonical(path: &Path) -> String {
+    path.components()
+        .filter_map(|c| match c {
+            std::path::Component::Normal(part) => part.to_str(),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Returns the signed message for the transformation `query` of `path`.
+fn message(path: &Path, query: &str) -> String {
+    format!("{}?{query}", canonical(path))
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn unhex(string: &str) -> Option<Vec<u8>> {
+    if string.len() % 2 != 0 || !string.is_ascii() {
+        return None;
+    }
+
+    (0..string.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&string[i..i + 2], 16).ok())
+        .collect()
+}
+
+/// Returns `true` if `path` names an image that can be served.
+fn is_image(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map_or(false, |ext| {
+            ["jpg", "jpeg", "png", "gif", "webp"].iter().any(|e| ext.eq_ignore_ascii_case(e))
+        })
+}
+
+/// Returns the format to encode the image at `path` as for `req`.
+fn negotiate(req: &Request<'_>, path: &Path) -> Format {
+    let accepts = |media_type: MediaType| req.accept().map_or(false, |accept| {
+        accept.iter().any(|q| *q.media_type() == media_type && q.weight_or(1.0) > 0.0)
+    });
+
+    #[cfg(feature = "avif")]
+    if accepts(MediaType::AVIF) {
+        return Format::Avif;
+    }
+
+    if accepts(MediaType::WEBP) {
+        return Format::Webp;
+    }
+
+    let ext = path.extension().and_then(|ext| ext.to_str()).unwrap_or_default();
+    match ext.eq_ignore_ascii_case("jpg") || ext.eq_ignore_ascii_case("jpeg") {
+        true => Format::Jpeg,
+        false => Format::Png,
+    }
+}
+
+/// A transformed image.
+struct Image {
+    format: Format,
+    negotiated: bool,
+    bytes: Vec<u8>,
+}
+
+impl<'r> Responder<'r, 'static> for Image {
+    fn respond_to(self, _: &'r Request<'_>) -> response::Result<'static> {
+        let mut response = Response::build();
+        response.header(self.format.content_type())
+            .header(Header::new("Cache-Control", CACHE_CONTROL))
+            .sized_body(self.bytes.len(), Cursor::new(self.bytes));
+
+        if self.negotiated {
+            response.header(Header::new("Vary", "Accept"));
+        }
+
+        response.ok()
+    }
+}
+
+impl From<Media> for Vec<Route> {
+    fn from(media: Media) -> Self {
+        let mut route = Route::ranked(media.rank, Method::Get, "/<path..>", media);
+        route.name = Some("Media".into());
+        vec![route]
+    }
+}
+
+#[rocket::async_trait]
+impl Handler for Media {
+    async fn handle<'r>(&self, req: &'r Request<'_>, data: Data<'r>) -> Outcome<'r> {
+        let path = req.segments::<Segments<'_, UriPath>>(0..).ok()
+            .and_then(|segments| segments.to_path_buf(false).ok())
+            .filter(|path| is_image(path));
+
+        let Some(path) = path else {
+            return Outcome::forward(data, Status::NotFound);
+        };
+
+        let transform = match self.transform(req, &path) {
+            Ok(Some(transform)) => transform,
+            Ok(None) => return match NamedFile::open(self.root.join(&path)).await {
+                Ok(file) => Outcome::from(req, file),
+                Err(_) => Outcome::forward(data, Status::NotFound),
+            },
+            Err(status) => return Outcome::error(status),
+        };
+
+        let format = transform.format.unwrap_or_else(|| negotiate(req, &path));
+        match self.render(&path, transform, format).await {
+            Ok(bytes) => {
+                let negotiated = transform.format.is_none();
+                Outcome::from(req, Image { format, negotiated, bytes })
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                Outcome::forward(data, Status::NotFound)
+            }
+            Err(e) => {
+                error!(name: "media", path = %path.display(), error = %e,
+                    "failed to transform image");
+
+                Outcome::error(Status::InternalServerError)
+            }
+        }
+    }
+}
+
+impl fmt::Debug for Media {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Media")
+            .field("root", &self.root)
+            .field("cache", &self.cache)
+            .field("max_size", &self.max_size)
+            .field("rank", &self.rank)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,267 @@
+use std::fmt;
+use std::io::{self, Cursor};
+use std::path::Path;
+
+use image::{DynamicImage, ImageFormat, ImageReader};
+use image::imageops::FilterType;
+use rocket::http::ContentType;
+
+/// How an image is fit to the requested dimensions.
+///
+/// The fit only matters when both a width and a height are requested. When
+/// only one is, the image is scaled to it, preserving the aspect ratio.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Fit {
+    /// Scale the image, preserving its aspect ratio, to the largest size that
+    /// fits within the dimensions. This is the default.
+    #[default]
+    Contain,
+    /// Scale the image, preserving its aspect ratio, to the smallest size that
+    /// covers the dimensions, then crop it, centered, to the dimensions.
+    Cover,
+    /// Scale the image to exactly the dimensions, ignoring its aspect ratio.
+    Fill,
+}
+
+/// An output image format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Format {
+    /// JPEG. Supports [`Transform::quality()`].
+    Jpeg,
+    /// PNG.
+    Png,
+    /// Lossless WebP.
+    Webp,
+    /// AVIF. Supports [`Transform::quality()`]. Only available when the `avif`
+    /// feature is enabled.
+    #[cfg(feature = "avif")]
+    #[cfg_attr(nightly, doc(cfg(feature = "avif")))]
+    Avif,
+}
+
+/// A transformation of an image: a resize and an output format.
+///
+/// A `Transform` is built with its builder methods, starting from
+/// [`Transform::new()`], which leaves the image as it is. Its URL form, as
+/// query parameters, is:
+///
+/// | Method                     | Parameter | Values                        |
+/// |----------------------------|-----------|-------------------------------|
+/// | [`Transform::width()`]     | `w`       | width in pixels               |
+/// | [`Transform::height()`]    | `h`       | height in pixels              |
+/// | [`Transform::fit()`]       | `fit`     | `contain`, `cover`, or `fill` |
+/// | [`Transform::quality()`]   | `q`       | `1` to `100`                  |
+/// | [`Transform::format()`]    | `fmt`     | `jpeg`, `png`, `webp`, `avif` |
+///
+/// # Example
+///
+/// ```rust
+/// use rocket_media::{Transform, Fit, Format};
+///
+/// let transform = Transform::new()
+///     .width(320)
+///     .height(240)
+///     .fit(Fit::Cover)
+///     .format(Format::Jpeg)
+///     .quality(75);
+///
+/// assert_eq!(transform.to_query(), "w=320&h=240&fit=cover&q=75&fmt=jpeg");
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Transform {
+    pub(crate) width: Option<u32>,
+    pub(crate) height: Option<u32>,
+    pub(crate) fit: Fit,
+    pub(crate) quality: Option<u8>,
+    pub(crate) format: Option<Format>,
+}
+
+/// The JPEG and AVIF quality used when none is requested.
+const DEFAULT_QUALITY: u8 = 80;
+
+impl Fit {
+    /// Parses a fit from its URL form: `contain`, `cover`, or `fill`.
+    pub fn parse(string: &str) -> Option<Fit> {
+        match string {
+            "contain" => Some(Fit::Contain),
+            "cover" => Some(Fit::Cover),
+            "fill" => Some(Fit::Fill),
+            _ => None,
+        }
+    }
+
+    /// Returns the URL form of `self`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Fit::Contain => "contain",
+            Fit::Cover => "cover",
+            Fit::Fill => "fill",
+        }
+    }
+}
+
+impl Format {
+    /// Parses a format from its URL form: `jpeg`, `png`, `webp`, or, when the
+    /// `avif` feature is enabled, `avif`.
+    pub fn parse(string: &str) -> Option<Format> {
+        match string {
+            "jpeg" | "jpg" => Some(Format::Jpeg),
+            "png" => Some(Format::Png),
+            "webp" => Some(Format::Webp),
+            #[cfg(feature = "avif")]
+            "avif" => Some(Format::Avif),
+            _ => None,
+        }
+    }
+
+    /// Returns the URL form of `self`, which is also its file extension.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Format::Jpeg => "jpeg",
+            Format::Png => "png",
+            Format::Webp => "webp",
+            #[cfg(feature = "avif")]
+            Format::Avif => "avif",
+        }
+    }
+
+    /// Returns the Content-Type of images in this format.
+    pub fn content_type(&self) -> ContentType {
+        match self {
+            Format::Jpeg => ContentType::JPEG,
+            Format::Png => ContentType::PNG,
+            Format::Webp => ContentType::WEBP,
+            #[cfg(feature = "avif")]
+            Format::Avif => ContentType::AVIF,
+        }
+    }
+}
+
+impl Transform {
+    /// Returns a transform that leaves an image as it is.
+    pub fn new() -> Transform {
+        Transform::default()
+    }
+
+    /// Resizes the image to `width` pixels wide.
+    pub fn width(mut self, width: u32) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    /// Resizes the image to `height` pixels high.
+    pub fn height(mut self, height: u32) -> Self {
+        self.height = Some(height);
+        self
+    }
+
+    /// Fits the image to the width and height with `fit`.
+    pub fn fit(mut self, fit: Fit) -> Self {
+        self.fit = fit;
+        self
+    }
+
+    /// Encodes the image with `quality`, from `1` to `100`, in lossy formats.
+    pub fn quality(mut self, quality: u8) -> Self {
+        self.quality = Some(quality);
+        self
+    }
+
+    /// Encodes the image as `format` instead of negotiating a format with the
+    /// client.
+    pub fn format(mut self, format: Format) -> Self {
+        self.format = Some(format);
+        self
+    }
+
+    /// Returns the canonical URL form of `self` as query parameters.
+    ///
+    /// Parameters with default values are omitted, so the URL form of
+    /// [`Transform::new()`] is empty.
+    pub fn to_query(&self) -> String {
+        let mut params = vec![];
+        if let Some(width) = self.width {
+            params.push(format!("w={width}"));
+        }
+
+        if let Some(height) = self.height {
+            params.push(format!("h={height}"));
+        }
+
+        if self.fit != Fit::Contain {
+            params.push(format!("fit={}", self.fit.as_str()));
+        }
+
+        if let Some(quality) = self.quality {
+            params.push(format!("q={quality}"));
+        }
+
+        if let Some(format) = self.format {
+            params.push(format!("fmt={}", format.as_str()));
+        }
+
+        params.join("&")
+    }
+
+    /// Decodes the image at `path`, transforms it, and encodes it as `format`.
+    pub(crate) fn apply(&self, path: &Path, format: Format) -> io::Result<Vec<u8>> {
+        let image = ImageReader::open(path)?
+            .with_guessed_format()?
+            .decode()
+            .map_err(io::Error::other)?;
+
+        let image = self.resize(image);
+        let quality = self.quality.unwrap_or(DEFAULT_QUALITY);
+        let mut bytes = Cursor::new(vec![]);
+        let result = match format {
+            Format::Jpeg => {
+                let encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(
+                    &mut bytes, quality
+                );
+
+                image.to_rgb8().write_with_encoder(encoder)
+            }
+            Format::Png => image.write_to(&mut bytes, ImageFormat::Png),
+            Format::Webp => {
+                let encoder = image::codecs::webp::WebPEncoder::new_lossless(&mut bytes);
+                image.to_rgba8().write_with_encoder(encoder)
+            }
+            #[cfg(feature = "avif")]
+            Format::Avif => {
+                let encoder = image::codecs::avif::AvifEncoder::new_with_speed_quality(
+                    &mut bytes, 8, quality
+                );
+
+                image.to_rgba8().write_with_encoder(encoder)
+            }
+        };
+
+        result.map_err(io::Error::other)?;
+        Ok(bytes.into_inner())
+    }
+
+    fn resize(&self, image: DynamicImage) -> DynamicImage {
+        let filter = FilterType::Lanczos3;
+        match (self.width, self.height, self.fit) {
+            (None, None, _) => image,
+            (Some(w), Some(h), Fit::Cover) => image.resize_to_fill(w, h, filter),
+            (Some(w), Some(h), Fit::Fill) => image.resize_exact(w, h, filter),
+            (w, h, _) => {
+                let (w, h) = (w.unwrap_or(u32::MAX), h.unwrap_or(u32::MAX));
+                image.resize(w, h, filter)
+            }
+        }
+    }
+}
+
+impl fmt::Display for Fit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
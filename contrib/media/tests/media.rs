@@ -0,0 +1,180 @@
+use std::path::Path;
+
+use rocket::http::{Accept, ContentType, MediaType, Status};
+use rocket::local::blocking::Client;
+use rocket_media::{Media, Transform, Fit, Format};
+use tempfile::TempDir;
+
+const KEY: &[u8] = b"an example key that is definitely 32 bytes";
+
+/// Writes a 40x20 PNG to `uploads/pics/image.png` in a new directory.
+fn fixture() -> TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    let pics = dir.path().join("uploads/pics");
+    std::fs::create_dir_all(&pics).unwrap();
+    image::RgbImage::from_fn(40, 20, |x, y| image::Rgb([x as u8 * 6, y as u8 * 12, 128]))
+        .save(pics.join("image.png"))
+        .unwrap();
+
+    std::fs::write(pics.join("notes.txt"), "not an image").unwrap();
+    dir
+}
+
+fn media(dir: &Path) -> Media {
+    Media::new(dir.join("uploads"), KEY).cache(dir.join("cache"))
+}
+
+fn client(media: Media) -> Client {
+    Client::debug(rocket::build().mount("/media", media)).unwrap()
+}
+
+fn dimensions(bytes: &[u8]) -> (u32, u32) {
+    let image = image::load_from_memory(bytes).unwrap();
+    (image.width(), image.height())
+}
+
+#[test]
+fn serves_originals() {
+    let dir = fixture();
+    let client = client(media(dir.path()));
+
+    let response = client.get("/media/pics/image.png").dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.content_type(), Some(ContentType::PNG));
+    let original = std::fs::read(dir.path().join("uploads/pics/image.png")).unwrap();
+    assert_eq!(response.into_bytes().unwrap(), original);
+
+    let response = client.get("/media/pics/missing.png").dispatch();
+    assert_eq!(response.status(), Status::NotFound);
+
+    let response = client.get("/media/pics/notes.txt").dispatch();
+    assert_eq!(response.status(), Status::NotFound);
+}
+
+#[test]
+fn requires_signatures() {
+    let dir = fixture();
+    let media = media(dir.path());
+    let query = media.sign("pics/image.png", &Transform::new().width(10));
+    let client = client(media);
+
+    let response = client.get("/media/pics/image.png?w=10").dispatch();
+    assert_eq!(response.status(), Status::Forbidden);
+
+    let response = client.get(format!("/media/pics/image.png?w=11&{}",
+        query.trim_start_matches("w=10&"))).dispatch();
+    assert_eq!(response.status(), Status::Forbidden);
+
+    let response = client.get(format!("/media/pics/other.png?{query}")).dispatch();
+    assert_eq!(response.status(), Status::Forbidden);
+
+    let response = client.get("/media/pics/image.png?w=ten&sig=00").dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
+
+    let other = Media::new(dir.path(), b"another key that is also 32 bytes long");
+    let forged = other.sign("pics/image.png", &Transform::new().width(10));
+    let response = client.get(format!("/media/pics/image.png?{forged}")).dispatch();
+    assert_eq!(response.status(), Status::Forbidden);
+
+    let response = client.get(format!("/media/pics/image.png?{query}")).dispatch();
+    assert_eq!(response.status(), Status::Ok);
+}
+
+#[test]
+fn resizes_images() {
+    let dir = fixture();
+    let media = media(dir.path());
+    let transforms = [
+        (Transform::new().width(10), (10, 5)),
+        (Transform::new().height(10), (20, 10)),
+        (Transform::new().width(10).height(10), (10, 5)),
+        (Transform::new().width(10).height(10).fit(Fit::Cover), (10, 10)),
+        (Transform::new().width(10).height(30).fit(Fit::Fill), (10, 30)),
+    ];
+
+    let queries: Vec<_> = transforms.iter()
+        .map(|(transform, size)| (media.sign("/pics/image.png", transform), *size))
+        .collect();
+
+    let client = client(media);
+    for (query, size) in queries {
+        let response = client.get(format!("/media/pics/image.png?{query}")).dispatch();
+        assert_eq!(response.status(), Status::Ok, "{query}");
+        assert_eq!(response.content_type(), Some(ContentType::PNG));
+        assert_eq!(dimensions(&response.into_bytes().unwrap()), size, "{query}");
+    }
+}
+
+#[test]
+fn negotiates_formats() {
+    let dir = fixture();
+    let media = media(dir.path());
+    let query = media.sign("pics/image.png", &Transform::new().width(10));
+    let jpeg = media.sign("pics/image.png", &Transform::new().width(10).format(Format::Jpeg));
+    let client = client(media);
+
+    let response = client.get(format!("/media/pics/image.png?{query}"))
+        .header(Accept::new([MediaType::WEBP, MediaType::Any]))
+        .dispatch();
+
+    assert_eq!(response.content_type(), Some(ContentType::WEBP));
+    assert_eq!(response.headers().get_one("Vary"), Some("Accept"));
+    assert_eq!(dimensions(&response.into_bytes().unwrap()), (10, 5));
+
+    let response = client.get(format!("/media/pics/image.png?{query}"))
+        .header(Accept::new([MediaType::Any]))
+        .dispatch();
+
+    assert_eq!(response.content_type(), Some(ContentType::PNG));
+
+    let response = client.get(format!("/media/pics/image.png?{jpeg}"))
+        .header(Accept::new([MediaType::WEBP]))
+        .dispatch();
+
+    assert_eq!(response.content_type(), Some(ContentType::JPEG));
+    assert!(response.headers().get_one("Vary").is_none());
+    assert_eq!(dimensions(&response.into_bytes().unwrap()), (10, 5));
+}
+
+#[test]
+fn limits_sizes() {
+    let dir = fixture();
+    let media = media(dir.path()).max_size(100, 100);
+    let large = media.sign("pics/image.png", &Transform::new().width(101));
+    let zero = media.sign("pics/image.png", &Transform::new().height(0));
+    let quality = media.sign("pics/image.png", &Transform::new().quality(101));
+    let client = client(media);
+
+    for query in [large, zero, quality] {
+        let response = client.get(format!("/media/pics/image.png?{query}")).dispatch();
+        assert_eq!(response.status(), Status::BadRequest, "{query}");
+    }
+}
+
+#[test]
+fn caches_transformations() {
+    let dir = fixture();
+    let media = media(dir.path());
+    let query = media.sign("pics/image.png", &Transform::new().width(10));
+    let client = client(media);
+
+    let first = client.get(format!("/media/pics/image.png?{query}")).dispatch();
+    let first = first.into_bytes().unwrap();
+    let cached: Vec<_> = std::fs::read_dir(dir.path().join("cache")).unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+
+    assert_eq!(cached.len(), 1);
+    assert_eq!(cached[0].extension().unwrap(), "png");
+    assert_eq!(std::fs::read(&cached[0]).unwrap(), first);
+
+    // Responses are served from the cache.
+    std::fs::write(&cached[0], b"cached").unwrap();
+    let second = client.get(format!("/media/pics/image.png?{query}")).dispatch();
+    assert_eq!(second.into_string().unwrap(), "cached");
+
+    // Replacing the image invalidates the cache.
+    image::RgbImage::new(20, 20).save(dir.path().join("uploads/pics/image.png")).unwrap();
+    let third = client.get(format!("/media/pics/image.png?{query}")).dispatch();
+    assert_eq!(dimensions(&third.into_bytes().unwrap()), (10, 10));
+}
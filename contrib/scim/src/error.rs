@@ -0,0 +1,161 @@
+use std::fmt;
+
+use rocket::Request;
+use rocket::http::Status;
+use rocket::response::{self, Responder};
+use rocket::serde::json::{json, Value};
+
+use crate::ScimJson;
+
+/// A SCIM error: a status and, for `400 Bad Request` errors, a [`ScimType`].
+///
+/// Errors respond with a SCIM error message. [`ScimBackend`](crate::ScimBackend)
+/// methods return them to fail a request.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::http::Status;
+/// use rocket_scim::{Error, ScimType};
+///
+/// let error = Error::conflict("userName `alice` is taken");
+/// assert_eq!(error.status(), Status::Conflict);
+/// assert_eq!(error.scim_type(), Some(ScimType::Uniqueness));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+    status: Status,
+    scim_type: Option<ScimType>,
+    detail: String,
+}
+
+/// The `scimType` of an [`Error`], detailing a `400 Bad Request` or `409
+/// Conflict`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ScimType {
+    /// The filter is malformed or unsupported.
+    InvalidFilter,
+    /// The filter yields too many results.
+    TooMany,
+    /// A value conflicts with an existing resource.
+    Uniqueness,
+    /// An attribute can't be modified.
+    Mutability,
+    /// The request body is malformed.
+    InvalidSyntax,
+    /// A `path` is malformed or names an unsupported attribute.
+    InvalidPath,
+    /// A `path` matches no value.
+    NoTarget,
+    /// A value is invalid or missing.
+    InvalidValue,
+    /// The requested SCIM protocol version isn't supported.
+    InvalidVers,
+    /// The request contains sensitive data that can't be accepted.
+    Sensitive,
+}
+
+impl Error {
+    /// Returns an error with status `status`, no `scimType`, and the
+    /// human-readable `detail`.
+    pub fn new<D: fmt::Display>(status: Status, detail: D) -> Self {
+        Error { status, scim_type: None, detail: detail.to_string() }
+    }
+
+    /// Returns a `400 Bad Request` error of type `scim_type`.
+    pub fn bad_request<D: fmt::Display>(scim_type: ScimType, detail: D) -> Self {
+        Error::new(Status::BadRequest, detail).with_type(scim_type)
+    }
+
+    /// Returns a `404 Not Found` error for the resource with ID `id`.
+    pub fn not_found(id: &str) -> Self {
+        Error::new(Status::NotFound, format_args!("resource {id:?} not found"))
+    }
+
+    /// Returns a `409 Conflict` error of type [`ScimType::Uniqueness`].
+    pub fn conflict<D: fmt::Display>(detail: D) -> Self {
+        Error::new(Status::Conflict, detail).with_type(ScimType::Uniqueness)
+    }
+
+    /// Returns a `500 Internal Server Error` error.
+    pub fn internal<D: fmt::Display>(detail: D) -> Self {
+        Error::new(Status::InternalServerError, detail)
+    }
+
+    /// Sets the `scimType` of the error to `scim_type`.
+    pub fn with_type(mut self, scim_type: ScimType) -> Self {
+        self.scim_type = Some(scim_type);
+        self
+    }
+
+    /// Returns the status of the error.
+    pub fn status(&self) -> Status {
+        self.status
+    }
+
+    /// Returns the `scimType` of the error, if any.
+    pub fn scim_type(&self) -> Option<ScimType> {
+        self.scim_type
+    }
+
+    /// Returns the human-readable detail of the error.
+    pub fn detail(&self) -> &str {
+        &self.detail
+    }
+
+    /// Returns the SCIM error message for the error.
+    pub fn to_json(&self) -> Value {
+        let mut value = json!({
+            "schemas": [crate::schema::ERROR],
+            "status": self.status.code.to_string(),
+            "detail": self.detail,
+        });
+
+        if let Some(scim_type) = self.scim_type {
+            value["scimType"] = scim_type.as_str().into();
+        }
+
+        value
+    }
+}
+
+impl ScimType {
+    /// Returns the `scimType` keyword, like `invalidFilter`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ScimType::InvalidFilter => "invalidFilter",
+            ScimType::TooMany => "tooMany",
+            ScimType::Uniqueness => "uniqueness",
+            ScimType::Mutability => "mutability",
+            ScimType::InvalidSyntax => "invalidSyntax",
+            ScimType::InvalidPath => "invalidPath",
+            ScimType::NoTarget => "noTarget",
+            ScimType::InvalidValue => "invalidValue",
+            ScimType::InvalidVers => "invalidVers",
+            ScimType::Sensitive => "sensitive",
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.scim_type {
+            Some(scim_type) => write!(f, "{} ({}): {}", self.status, scim_type, self.detail),
+            None => write!(f, "{}: {}", self.status, self.detail),
+        }
+    }
+}
+
+impl fmt::Display for ScimType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::error::Error for Error { }
+
+impl<'r> Responder<'r, 'static> for Error {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
+        ScimJson(self.status, Some(self.to_json())).respond_to(req)
+    }
+}
@@ -0,0 +1,477 @@
+use std::fmt;
+use std::str::FromStr;
+use std::cmp::Ordering;
+
+use rocket::serde::json::{Value, serde_json};
+
+use crate::{Error, ScimType};
+
+/// A path to an attribute, like `name.givenName`, optionally qualified by a
+/// schema URN, as in
+/// `urn:ietf:params:scim:schemas:extension:enterprise:2.0:User:manager`.
+///
+/// Attribute names are case-insensitive.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket_scim::AttrPath;
+///
+/// let path: AttrPath = "name.givenName".parse().unwrap();
+/// assert_eq!(path.name, "name");
+/// assert_eq!(path.sub_attribute.as_deref(), Some("givenName"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AttrPath {
+    /// The schema URN qualifying the attribute, if any.
+    pub schema: Option<String>,
+    /// The name of the attribute.
+    pub name: String,
+    /// The name of the sub-attribute, if any.
+    pub sub_attribute: Option<String>,
+}
+
+/// A comparison operator in a [`Filter`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CompareOp {
+    /// `eq`: equal.
+    Eq,
+    /// `ne`: not equal.
+    Ne,
+    /// `co`: contains.
+    Co,
+    /// `sw`: starts with.
+    Sw,
+    /// `ew`: ends with.
+    Ew,
+    /// `gt`: greater than.
+    Gt,
+    /// `lt`: less than.
+    Lt,
+    /// `ge`: greater than or equal.
+    Ge,
+    /// `le`: less than or equal.
+    Le,
+}
+
+/// A parsed SCIM filter, as in `userName eq "alice" and active eq true`.
+///
+/// Backends can translate a filter into a query or evaluate it against a
+/// resource with [`Filter::matches()`]. Filters support all operators and
+/// value paths, like `emails[type eq "work" and value co "@example.com"]`.
+/// Strings are compared case-insensitively.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::serde::json::json;
+/// use rocket_scim::Filter;
+///
+/// let filter: Filter = r#"emails[type eq "work"] and not (active eq false)"#.parse().unwrap();
+/// let user = json!({
+///     "userName": "alice",
+///     "active": true,
+///     "emails": [{ "type": "work", "value": "alice@example.com" }],
+/// });
+///
+/// assert!(filter.matches(&user));
+///
+/// let filter: Filter = r#"userName sw "A" and emails co "example.com""#.parse().unwrap();
+/// assert!(filter.matches(&user));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum Filter {
+    /// `attr pr`: the attribute has a non-empty value.
+    Present(AttrPath),
+    /// `attr op value`: the attribute compares to the value.
+    Compare(AttrPath, CompareOp, Value),
+    /// `attr[filter]`: a value of the multi-valued attribute matches the
+    /// filter, whose attribute paths are relative to the value.
+    Value(AttrPath, Box<Filter>),
+    /// `not (filter)`.
+    Not(Box<Filter>),
+    /// `filter and filter`.
+    And(Box<Filter>, Box<Filter>),
+    /// `filter or filter`.
+    Or(Box<Filter>, Box<Filter>),
+}
+
+/// A recursive descent parser for filters and attribute paths.
+pub(crate) struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl AttrPath {
+    /// Returns the values at the path in `resource`. Values of multi-valued
+    /// attributes are returned individually.
+    pub fn resolve<'v>(&self, resource: &'v Value) -> Vec<&'v Value> {
+        let root = match &self.schema {
+            Some(schema) => match get(resource, schema) {
+                Some(extension) => extension,
+                None => return vec![],
+            },
+            None => resource,
+        };
+
+        let values = flatten(get(root, &self.name));
+        match &self.sub_attribute {
+            Some(sub) => values.into_iter().flat_map(|v| flatten(get(v, sub))).collect(),
+            None => values,
+        }
+    }
+
+    fn parse_name(name: &str) -> Option<AttrPath> {
+        let valid = |name: &str| {
+            name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '$')
+                && name.chars().all(|c| c.is_ascii_alphanumeric() || "-_$".contains(c))
+        };
+
+        let qualified = name.get(..4).is_some_and(|p| p.eq_ignore_ascii_case("urn:"));
+        let (schema, rest) = match qualified {
+            true => name.rsplit_once(':').map(|(s, r)| (Some(s.to_string()), r))?,
+            false => (None, name),
+        };
+
+        let (name, sub_attribute) = match rest.split_once('.') {
+            Some((name, sub)) => (name, Some(sub)),
+            None => (rest, None),
+        };
+
+        if !valid(name) || sub_attribute.is_some_and(|sub| !valid(sub)) {
+            return None;
+        }
+
+        Some(AttrPath {
+            schema,
+            name: name.to_string(),
+            sub_attribute: sub_attribute.map(|s| s.to_string()),
+        })
+    }
+}
+
+impl CompareOp {
+    /// Returns the operator's keyword, like `eq`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CompareOp::Eq => "eq",
+            CompareOp::Ne => "ne",
+            CompareOp::Co => "co",
+            CompareOp::Sw => "sw",
+            CompareOp::Ew => "ew",
+            CompareOp::Gt => "gt",
+            CompareOp::Lt => "lt",
+            CompareOp::Ge => "ge",
+            CompareOp::Le => "le",
+        }
+    }
+
+    fn parse(keyword: &str) -> Option<CompareOp> {
+        [
+            CompareOp::Eq, CompareOp::Ne, CompareOp::Co, CompareOp::Sw, CompareOp::Ew,
+            CompareOp::Gt, CompareOp::Lt, CompareOp::Ge, CompareOp::Le,
+        ].into_iter().find(|op| op.as_str().eq_ignore_ascii_case(keyword))
+    }
+
+    /// Returns `true` if `actual` compares to `expected` with this operator.
+    fn compare(&self, actual: &Value, expected: &Value) -> bool {
+        let ordering = match (actual, expected) {
+            (Value::String(a), Value::String(b)) => {
+                let (a, b) = (a.to_lowercase(), b.to_lowercase());
+                match self {
+                    CompareOp::Co => return a.contains(&b),
+                    CompareOp::Sw => return a.starts_with(&b),
+                    CompareOp::Ew => return a.ends_with(&b),
+                    _ => a.cmp(&b),
+                }
+            }
+            (Value::Number(a), Value::Number(b)) => {
+                match a.as_f64().zip(b.as_f64()).and_then(|(a, b)| a.partial_cmp(&b)) {
+                    Some(ordering) => ordering,
+                    None => return false,
+                }
+            }
+            (Value::Bool(a), Value::Bool(b)) => match self {
+                CompareOp::Eq | CompareOp::Ne => a.cmp(b),
+                _ => return false,
+            },
+            _ => return false,
+        };
+
+        match self {
+            CompareOp::Eq => ordering == Ordering::Equal,
+            CompareOp::Ne => ordering != Ordering::Equal,
+            CompareOp::Gt => ordering == Ordering::Greater,
+            CompareOp::Lt => ordering == Ordering::Less,
+            CompareOp::Ge => ordering != Ordering::Less,
+            CompareOp::Le => ordering != Ordering::Greater,
+            CompareOp::Co | CompareOp::Sw | CompareOp::Ew => false,
+        }
+    }
+}
+
+impl Filter {
+    /// Parses `string` as a filter. Fails with an [`Error`] of type
+    /// [`ScimType::InvalidFilter`] if `string` isn't a valid filter.
+    pub fn parse(string: &str) -> Result<Filter, Error> {
+        let mut parser = Parser::new(string);
+        let filter = parser.filter()
+            .filter(|_| parser.is_done())
+            .ok_or_else(|| invalid_filter(string))?;
+
+        Ok(filter)
+    }
+
+    /// Returns `true` if `resource` matches the filter.
+    ///
+    /// Comparisons without a sub-attribute against complex values, like
+    /// `emails co "example.com"`, compare against the `value` sub-attribute.
+    /// `ne` matches if no value is equal, including when there are no values.
+    pub fn matches(&self, resource: &Value) -> bool {
+        match self {
+            Filter::Present(path) => path.resolve(resource).into_iter().any(|v| match v {
+                Value::Null => false,
+                Value::String(s) => !s.is_empty(),
+                Value::Object(o) => !o.is_empty(),
+                _ => true,
+            }),
+            Filter::Compare(path, op, expected) => {
+                let mut values = path.resolve(resource).into_iter()
+                    .map(|v| match (v, &path.sub_attribute) {
+                        (Value::Object(_), None) => get(v, "value").unwrap_or(&Value::Null),
+                        _ => v,
+                    });
+
+                match (op, expected) {
+                    (CompareOp::Eq, Value::Null) => values.all(Value::is_null),
+                    (CompareOp::Ne, Value::Null) => !values.all(Value::is_null),
+                    (CompareOp::Ne, _) => !values.any(|v| CompareOp::Eq.compare(v, expected)),
+                    _ => values.any(|v| op.compare(v, expected)),
+                }
+            }
+            Filter::Value(path, filter) => {
+                path.resolve(resource).into_iter().any(|v| filter.matches(v))
+            }
+            Filter::Not(filter) => !filter.matches(resource),
+            Filter::And(a, b) => a.matches(resource) && b.matches(resource),
+            Filter::Or(a, b) => a.matches(resource) || b.matches(resource),
+        }
+    }
+}
+
+impl<'a> Parser<'a> {
+    pub(crate) fn new(input: &'a str) -> Self {
+        Parser { input, pos: 0 }
+    }
+
+    pub(crate) fn is_done(&mut self) -> bool {
+        self.skip_whitespace();
+        self.pos == self.input.len()
+    }
+
+    /// Parses `filter = term ("or" term)*`.
+    pub(crate) fn filter(&mut self) -> Option<Filter> {
+        let mut filter = self.term()?;
+        while self.keyword("or") {
+            filter = Filter::Or(Box::new(filter), Box::new(self.term()?));
+        }
+
+        Some(filter)
+    }
+
+    /// Parses `term = factor ("and" factor)*`.
+    fn term(&mut self) -> Option<Filter> {
+        let mut filter = self.factor()?;
+        while self.keyword("and") {
+            filter = Filter::And(Box::new(filter), Box::new(self.factor()?));
+        }
+
+        Some(filter)
+    }
+
+    /// Parses `factor = "not"? "(" filter ")" / attrPath "[" filter "]" /
+    /// attrPath "pr" / attrPath compareOp compValue`.
+    fn factor(&mut self) -> Option<Filter> {
+        if self.keyword("not") {
+            return self.group().map(|f| Filter::Not(Box::new(f)));
+        } else if self.peek() == Some('(') {
+            return self.group();
+        }
+
+        let path = self.attr_path()?;
+        if self.eat('[') {
+            let filter = self.filter()?;
+            return self.eat(']').then(|| Filter::Value(path, Box::new(filter)));
+        }
+
+        if self.keyword("pr") {
+            return Some(Filter::Present(path));
+        }
+
+        let op = CompareOp::parse(self.word())?;
+        Some(Filter::Compare(path, op, self.value()?))
+    }
+
+    fn group(&mut self) -> Option<Filter> {
+        if !self.eat('(') {
+            return None;
+        }
+
+        let filter = self.filter()?;
+        self.eat(')').then_some(filter)
+    }
+
+    pub(crate) fn attr_path(&mut self) -> Option<AttrPath> {
+        self.skip_whitespace();
+        let rest = &self.input[self.pos..];
+        let len = rest.find(|c: char| !(c.is_ascii_alphanumeric() || "-_$:.".contains(c)))
+            .unwrap_or(rest.len());
+
+        self.pos += len;
+        AttrPath::parse_name(&rest[..len])
+    }
+
+    /// Parses a sub-attribute name following a value path, as in the
+    /// `.value` of `emails[type eq "work"].value`.
+    pub(crate) fn sub_attribute(&mut self) -> Option<String> {
+        let rest = &self.input[self.pos..];
+        let len = rest.find(|c: char| !(c.is_ascii_alphanumeric() || "-_$".contains(c)))
+            .unwrap_or(rest.len());
+
+        self.pos += len;
+        (len > 0).then(|| rest[..len].to_string())
+    }
+
+    /// Parses a JSON string, number, or `true`, `false`, or `null`.
+    fn value(&mut self) -> Option<Value> {
+        self.skip_whitespace();
+        let rest = &self.input[self.pos..];
+        let len = match rest.starts_with('"') {
+            true => {
+                let mut escaped = false;
+                let end = rest[1..].find(|c| match (escaped, c) {
+                    (true, _) => { escaped = false; false }
+                    (false, '\\') => { escaped = true; false }
+                    (false, c) => c == '"',
+                })?;
+
+                end + 2
+            }
+            false => rest.find(|c: char| c.is_whitespace() || c == ')' || c == ']')
+                .unwrap_or(rest.len()),
+        };
+
+        self.pos += len;
+        let literal = &rest[..len];
+        match literal {
+            _ if literal.eq_ignore_ascii_case("true") => Some(Value::Bool(true)),
+            _ if literal.eq_ignore_ascii_case("false") => Some(Value::Bool(false)),
+            _ if literal.eq_ignore_ascii_case("null") => Some(Value::Null),
+            _ => serde_json::from_str::<Value>(literal).ok()
+                .filter(|v| v.is_string() || v.is_number()),
+        }
+    }
+
+    /// Consumes and returns the next word.
+    fn word(&mut self) -> &'a str {
+        self.skip_whitespace();
+        let rest = &self.input[self.pos..];
+        let len = rest.find(|c: char| !c.is_ascii_alphabetic()).unwrap_or(rest.len());
+        self.pos += len;
+        &rest[..len]
+    }
+
+    /// Consumes the case-insensitive `keyword` if it's the next word.
+    fn keyword(&mut self, keyword: &str) -> bool {
+        let pos = self.pos;
+        if self.word().eq_ignore_ascii_case(keyword) {
+            return true;
+        }
+
+        self.pos = pos;
+        false
+    }
+
+    pub(crate) fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += c.len_utf8();
+            return true;
+        }
+
+        false
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.skip_whitespace();
+        self.input[self.pos..].chars().next()
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = &self.input[self.pos..];
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+}
+
+impl FromStr for AttrPath {
+    type Err = Error;
+
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        AttrPath::parse_name(string.trim()).ok_or_else(|| {
+            Error::bad_request(ScimType::InvalidPath, format_args!("invalid path {string:?}"))
+        })
+    }
+}
+
+impl FromStr for Filter {
+    type Err = Error;
+
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        Filter::parse(string)
+    }
+}
+
+impl fmt::Display for AttrPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(schema) = &self.schema {
+            write!(f, "{schema}:")?;
+        }
+
+        f.write_str(&self.name)?;
+        if let Some(sub) = &self.sub_attribute {
+            write!(f, ".{sub}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Filter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Filter::Present(path) => write!(f, "{path} pr"),
+            Filter::Compare(path, op, value) => write!(f, "{path} {} {value}", op.as_str()),
+            Filter::Value(path, filter) => write!(f, "{path}[{filter}]"),
+            Filter::Not(filter) => write!(f, "not ({filter})"),
+            Filter::And(a, b) => write!(f, "({a}) and ({b})"),
+            Filter::Or(a, b) => write!(f, "({a}) or ({b})"),
+        }
+    }
+}
+
+fn invalid_filter(filter: &str) -> Error {
+    Error::bad_request(ScimType::InvalidFilter, format_args!("invalid filter {filter:?}"))
+}
+
+/// Returns the value of the attribute `name` of `value`, ignoring case.
+pub(crate) fn get<'v>(value: &'v Value, name: &str) -> Option<&'v Value> {
+    value.as_object()?.iter().find(|(k, _)| k.eq_ignore_ascii_case(name)).map(|(_, v)| v)
+}
+
+/// Returns the elements of `value` if it's an array, or `value` otherwise.
+fn flatten(value: Option<&Value>) -> Vec<&Value> {
+    match value {
+        Some(Value::Array(values)) => values.iter().collect(),
+        Some(value) => vec![value],
+        None => vec![],
+    }
+}
@@ -0,0 +1,661 @@
+//! SCIM 2.0 user and group provisioning for Rocket.
+//!
+//! This crate provides [`Scim`], a mountable handler implementing the
+//! [SCIM 2.0] protocol's user and group endpoints over a [`ScimBackend`]
+//! implemented by the application. Identity providers like Okta and Microsoft
+//! Entra ID use these endpoints to create, update, and deactivate the accounts
+//! of an organization's members: "enterprise provisioning".
+//!
+//! # Usage
+//!
+//! Depend on the crate:
+//!
+//! ```toml
+//! [dependencies]
+//! rocket_scim = "0.1.0"
+//! ```
+//!
+//! Then, implement [`ScimBackend`] and mount a [`Scim`] handler:
+//!
+//! ```rust
+//! # use rocket::launch;
+//! use rocket::serde::json::Value;
+//! use rocket_scim::{Scim, ScimBackend, ResourceType, ListQuery, Page, Error};
+//!
+//! struct Directory {
+//!     /* a database connection pool */
+//! }
+//!
+//! #[rocket::async_trait]
+//! impl ScimBackend for Directory {
+//!     async fn authorize(&self, token: &str) -> bool {
+//!         /* check the identity provider's bearer token */
+//!         # true
+//!     }
+//!
+//!     async fn list(&self, kind: ResourceType, query: &ListQuery) -> Result<Page, Error> {
+//!         /* find resources matching `query.filter`, then paginate */
+//!         # Ok(query.paginate(vec![]))
+//!     }
+//!
+//!     async fn get(&self, kind: ResourceType, id: &str) -> Result<Value, Error> {
+//!         # Err(Error::not_found(id))
+//!     }
+//!
+//!     async fn create(&self, kind: ResourceType, resource: Value) -> Result<Value, Error> {
+//!         /* assign an `id` and store the resource */
+//!         # Ok(resource)
+//!     }
+//!
+//!     async fn replace(&self, kind: ResourceType, id: &str, resource: Value)
+//!         -> Result<Value, Error>
+//!     {
+//!         # Ok(resource)
+//!     }
+//!
+//!     async fn delete(&self, kind: ResourceType, id: &str) -> Result<(), Error> {
+//!         # Ok(())
+//!     }
+//! }
+//!
+//! #[launch]
+//! fn rocket() -> _ {
+//!     rocket::build().mount("/scim/v2", Scim::new(Directory { }))
+//! }
+//! ```
+//!
+//! # Endpoints
+//!
+//! When mounted at `/scim/v2`, [`Scim`] handles:
+//!
+//!   * `GET /scim/v2/Users`: lists users, with `filter`, `startIndex`, and
+//!     `count` query parameters. Filters are parsed into a [`Filter`].
+//!   * `POST /scim/v2/Users`: creates a user.
+//!   * `GET`, `PUT`, `PATCH`, and `DELETE` `/scim/v2/Users/<id>`: returns,
+//!     replaces, modifies with [`PatchOperation`]s, and deletes a user.
+//!   * The same for groups, at `/scim/v2/Groups`.
+//!   * `GET /scim/v2/ServiceProviderConfig` and `GET /scim/v2/ResourceTypes`:
+//!     describe the supported features and resource types.
+//!
+//! Resources are JSON objects, which backends may store as-is or map to and
+//! from their own types. Responses set each resource's `meta.resourceType`
+//! and `meta.location`, and its `schemas` if missing. Bulk operations,
+//! sorting, ETags, and the `attributes` parameter are not supported.
+//!
+//! # Authorization
+//!
+//! Requests to the user and group endpoints must carry a bearer token, as in
+//! `Authorization: Bearer <token>`, accepted by [`ScimBackend::authorize()`].
+//! Other requests fail with `401 Unauthorized`. The discovery endpoints don't
+//! require authorization.
+//!
+//! # Errors
+//!
+//! Failures respond with SCIM error messages: [`Error`]s returned by the
+//! backend, or raised by the handler for malformed requests, as with a
+//! `scimType` of `invalidFilter` for an invalid filter.
+//!
+//! [SCIM 2.0]: https://datatracker.ietf.org/doc/html/rfc7644
+
+#[macro_use] extern crate rocket;
+
+mod error;
+mod filter;
+mod patch;
+
+pub use error::{Error, ScimType};
+pub use filter::{AttrPath, CompareOp, Filter};
+pub use patch::{PatchOp, PatchOperation, PatchPath};
+
+use std::fmt;
+use std::sync::Arc;
+
+use rocket::{Request, Response, Data};
+use rocket::data::Limits;
+use rocket::http::{ContentType, Header, Method, Status};
+use rocket::response::{self, Responder};
+use rocket::route::{Route, Handler, Outcome};
+use rocket::serde::json::{json, Json, Value, serde_json};
+
+/// The URNs of the SCIM schemas and messages used by this crate.
+pub mod schema {
+    /// The core user schema.
+    pub const USER: &str = "urn:ietf:params:scim:schemas:core:2.0:User";
+    /// The core group schema.
+    pub const GROUP: &str = "urn:ietf:params:scim:schemas:core:2.0:Group";
+    /// The enterprise user extension schema.
+    pub const ENTERPRISE_USER: &str =
+        "urn:ietf:params:scim:schemas:extension:enterprise:2.0:User";
+    /// The service provider configuration schema.
+    pub const SERVICE_PROVIDER_CONFIG: &str =
+        "urn:ietf:params:scim:schemas:core:2.0:ServiceProviderConfig";
+    /// The resource type schema.
+    pub const RESOURCE_TYPE: &str = "urn:ietf:params:scim:schemas:core:2.0:ResourceType";
+    /// The list response message.
+    pub const LIST_RESPONSE: &str = "urn:ietf:params:scim:api:messages:2.0:ListResponse";
+    /// The PATCH request message.
+    pub const PATCH_OP: &str = "urn:ietf:params:scim:api:messages:2.0:PatchOp";
+    /// The error message.
+    pub const ERROR: &str = "urn:ietf:params:scim:api:messages:2.0:Error";
+}
+
+/// A type of SCIM resource.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ResourceType {
+    /// A user, at `/Users`.
+    User,
+    /// A group, at `/Groups`.
+    Group,
+}
+
+/// The parameters of a request to list resources.
+///
+/// `start_index` is 1-based, as in SCIM, and `count` is already limited to the
+/// handler's maximum: see [`Scim::max_results()`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListQuery {
+    /// The filter resources must match, if any.
+    pub filter: Option<Filter>,
+    /// The 1-based index of the first resource to return.
+    pub start_index: usize,
+    /// The maximum number of resources to return.
+    pub count: usize,
+}
+
+/// A page of resources in response to a [`ListQuery`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Page {
+    /// The resources in the page.
+    pub resources: Vec<Value>,
+    /// The total number of resources matching the query across all pages.
+    pub total_results: usize,
+}
+
+/// A backend storing SCIM resources, implemented by the application.
+///
+/// Methods fail with an [`Error`], like [`Error::not_found()`] or
+/// [`Error::conflict()`], which is returned to the client. See the [crate
+/// docs](crate) for an example.
+#[rocket::async_trait]
+pub trait ScimBackend: Send + Sync + 'static {
+    /// Returns `true` if the bearer `token` authorizes requests.
+    async fn authorize(&self, token: &str) -> bool;
+
+    /// Returns the page of resources of type `kind` requested by `query`.
+    ///
+    /// Backends that evaluate filters in memory can use
+    /// [`Filter::matches()`] and [`ListQuery::paginate()`].
+    async fn list(&self, kind: ResourceType, query: &ListQuery) -> Result<Page, Error>;
+
+    /// Returns the resource of type `kind` with ID `id`.
+    async fn get(&self, kind: ResourceType, id: &str) -> Result<Value, Error>;
+
+    /// Creates a resource of type `kind` from `resource`, assigning its `id`.
+    /// Returns the created resource.
+    async fn create(&self, kind: ResourceType, resource: Value) -> Result<Value, Error>;
+
+    /// Replaces the resource of type `kind` with ID `id` with `resource`.
+    /// Returns the replaced resource.
+    async fn replace(&self, kind: ResourceType, id: &str, resource: Value)
+        -> Result<Value, Error>;
+
+    /// Applies `operations` to the resource of type `kind` with ID `id`.
+    /// Returns the modified resource.
+    ///
+    /// The default implementation gets the resource, applies the operations
+    /// with [`PatchOperation::apply()`], and replaces the resource. Backends
+    /// can override it to apply operations more efficiently, like adding a
+    /// member to a large group.
+    async fn patch(&self, kind: ResourceType, id: &str, operations: &[PatchOperation])
+        -> Result<Value, Error>
+    {
+        let mut resource = self.get(kind, id).await?;
+        for operation in operations {
+            operation.apply(&mut resource)?;
+        }
+
+        self.replace(kind, id, resource).await
+    }
+
+    /// Deletes the resource of type `kind` with ID `id`.
+    async fn delete(&self, kind: ResourceType, id: &str) -> Result<(), Error>;
+}
+
+/// A handler for the SCIM user and group endpoints.
+///
+/// See the [crate docs](crate) for details.
+///
+/// # Example
+///
+/// ```rust
+/// # use rocket::serde::json::Value;
+/// # use rocket_scim::*;
+/// # struct Directory;
+/// # #[rocket::async_trait]
+/// # impl ScimBackend for Directory {
+/// #     async fn authorize(&self, _: &str) -> bool { false }
+/// #     async fn list(&self, _: ResourceType, q: &ListQuery) -> Result<Page, Error> {
+/// #         Ok(q.paginate(vec![]))
+/// #     }
+/// #     async fn get(&self, _: ResourceType, id: &str) -> Result<Value, Error> {
+/// #         Err(Error::not_found(id))
+/// #     }
+/// #     async fn create(&self, _: ResourceType, r: Value) -> Result<Value, Error> { Ok(r) }
+/// #     async fn replace(&self, _: ResourceType, _: &str, r: Value) -> Result<Value, Error> {
+/// #         Ok(r)
+/// #     }
+/// #     async fn delete(&self, _: ResourceType, _: &str) -> Result<(), Error> { Ok(()) }
+/// # }
+/// use rocket_scim::Scim;
+///
+/// let scim = Scim::new(Directory).max_results(50);
+/// let rocket = rocket::build().mount("/scim/v2", scim);
+/// ```
+pub struct Scim<B> {
+    backend: Arc<B>,
+    max_results: usize,
+}
+
+/// The resource or resources targeted by a route emitted by [`Scim`].
+#[derive(Debug, Clone, Copy)]
+enum Target {
+    Collection(ResourceType),
+    Resource(ResourceType),
+    ServiceProviderConfig,
+    ResourceTypes,
+}
+
+/// One of the routes emitted by [`Scim`].
+struct Endpoint<B> {
+    scim: Scim<B>,
+    target: Target,
+}
+
+/// A SCIM response: a JSON body, if any, with a `Content-Type` of
+/// `application/scim+json`.
+pub(crate) struct ScimJson(pub Status, pub Option<Value>);
+
+impl ResourceType {
+    /// Returns the name of the resource type, like `User`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ResourceType::User => "User",
+            ResourceType::Group => "Group",
+        }
+    }
+
+    /// Returns the path of the resource type's endpoint, like `/Users`.
+    pub fn endpoint(&self) -> &'static str {
+        match self {
+            ResourceType::User => "/Users",
+            ResourceType::Group => "/Groups",
+        }
+    }
+
+    /// Returns the URN of the resource type's core schema.
+    pub fn schema(&self) -> &'static str {
+        match self {
+            ResourceType::User => schema::USER,
+            ResourceType::Group => schema::GROUP,
+        }
+    }
+}
+
+impl ListQuery {
+    /// Filters `resources` by [`ListQuery::filter`] and returns the requested
+    /// page of those that match.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::serde::json::json;
+    /// use rocket_scim::ListQuery;
+    ///
+    /// let users = (1..=5).map(|i| json!({ "id": i.to_string(), "active": i % 2 == 1 }));
+    /// let query = ListQuery {
+    ///     filter: Some("active eq true".parse().unwrap()),
+    ///     start_index: 2,
+    ///     count: 1,
+    /// };
+    ///
+    /// let page = query.paginate(users);
+    /// assert_eq!(page.total_results, 3);
+    /// assert_eq!(page.resources, vec![json!({ "id": "3", "active": true })]);
+    /// ```
+    pub fn paginate<I: IntoIterator<Item = Value>>(&self, resources: I) -> Page {
+        let matching: Vec<_> = resources.into_iter()
+            .filter(|r| self.filter.as_ref().map_or(true, |f| f.matches(r)))
+            .collect();
+
+        let total_results = matching.len();
+        let resources = matching.into_iter()
+            .skip(self.start_index.saturating_sub(1))
+            .take(self.count)
+            .collect();
+
+        Page { resources, total_results }
+    }
+}
+
+impl<B: ScimBackend> Scim<B> {
+    /// The default maximum number of resources returned in one page.
+    pub const DEFAULT_MAX_RESULTS: usize = 100;
+
+    /// Returns a handler serving the resources in `backend`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use rocket::serde::json::Value;
+    /// # use rocket_scim::*;
+    /// # struct Directory;
+    /// # #[rocket::async_trait]
+    /// # impl ScimBackend for Directory {
+    /// #     async fn authorize(&self, _: &str) -> bool { false }
+    /// #     async fn list(&self, _: ResourceType, q: &ListQuery) -> Result<Page, Error> {
+    /// #         Ok(q.paginate(vec![]))
+    /// #     }
+    /// #     async fn get(&self, _: ResourceType, id: &str) -> Result<Value, Error> {
+    /// #         Err(Error::not_found(id))
+    /// #     }
+    /// #     async fn create(&self, _: ResourceType, r: Value) -> Result<Value, Error> {
+    /// #         Ok(r)
+    /// #     }
+    /// #     async fn replace(&self, _: ResourceType, _: &str, r: Value)
+    /// #         -> Result<Value, Error> { Ok(r) }
+    /// #     async fn delete(&self, _: ResourceType, _: &str) -> Result<(), Error> { Ok(()) }
+    /// # }
+    /// use rocket_scim::Scim;
+    ///
+    /// let scim = Scim::new(Directory);
+    /// ```
+    pub fn new(backend: B) -> Self {
+        Scim { backend: Arc::new(backend), max_results: Self::DEFAULT_MAX_RESULTS }
+    }
+
+    /// Sets the maximum number of resources returned in one page to `max`.
+    /// Requests for more resources return at most `max`. Defaults to
+    /// [`Scim::DEFAULT_MAX_RESULTS`].
+    pub fn max_results(mut self, max: usize) -> Self {
+        self.max_results = max;
+        self
+    }
+
+    /// Returns the backend.
+    pub fn backend(&self) -> &B {
+        &self.backend
+    }
+
+    async fn dispatch(&self, target: Target, req: &Request<'_>, data: Data<'_>)
+        -> Result<ScimJson, Error>
+    {
+        let (kind, id) = match target {
+            Target::ServiceProviderConfig => return Ok(self.service_provider_config()),
+            Target::ResourceTypes => return Ok(self.resource_types(req)),
+            Target::Collection(kind) => (kind, None),
+            Target::Resource(kind) => match req.param::<&str>(0) {
+                Some(Ok(id)) => (kind, Some(id)),
+                _ => return Err(Error::new(Status::NotFound, "missing resource ID")),
+            },
+        };
+
+        let token = req.headers().get_one("Authorization")
+            .and_then(|value| value.split_once(' '))
+            .filter(|(scheme, _)| scheme.eq_ignore_ascii_case("Bearer"))
+            .map(|(_, token)| token.trim());
+
+        match token {
+            Some(token) if self.backend.authorize(token).await => {},
+            _ => return Err(Error::new(Status::Unauthorized, "invalid or missing bearer token")),
+        }
+
+        let backend = &self.backend;
+        let resource = match (req.method(), id) {
+            (Method::Get, None) => return self.list(kind, req).await,
+            (Method::Post, None) => {
+                let resource = backend.create(kind, body(req, data).await?).await?;
+                return Ok(ScimJson(Status::Created, Some(self.finish(kind, req, resource))));
+            }
+            (Method::Get, Some(id)) => backend.get(kind, id).await?,
+            (Method::Put, Some(id)) => backend.replace(kind, id, body(req, data).await?).await?,
+            (Method::Patch, Some(id)) => {
+                let body = body(req, data).await?;
+                let operations = match body.get("Operations") {
+                    Some(Value::Array(operations)) => operations.iter()
+                        .map(PatchOperation::from_json)
+                        .collect::<Result<Vec<_>, _>>()?,
+                    _ => return Err(Error::bad_request(ScimType::InvalidSyntax,
+                        "missing `Operations`")),
+                };
+
+                backend.patch(kind, id, &operations).await?
+            }
+            (Method::Delete, Some(id)) => {
+                backend.delete(kind, id).await?;
+                return Ok(ScimJson(Status::NoContent, None));
+            }
+            _ => return Err(Error::new(Status::MethodNotAllowed, "method not allowed")),
+        };
+
+        Ok(ScimJson(Status::Ok, Some(self.finish(kind, req, resource))))
+    }
+
+    async fn list(&self, kind: ResourceType, req: &Request<'_>) -> Result<ScimJson, Error> {
+        fn param(req: &Request<'_>, name: &str) -> Result<Option<String>, Error> {
+            match req.query_value::<String>(name) {
+                Some(Ok(value)) => Ok(Some(value)),
+                Some(Err(_)) => Err(Error::bad_request(ScimType::InvalidValue,
+                    format_args!("invalid `{name}`"))),
+                None => Ok(None),
+            }
+        }
+
+        fn number(value: Option<String>, name: &str) -> Result<Option<i64>, Error> {
+            value.map(|v| v.trim().parse::<i64>()).transpose().map_err(|_| {
+                Error::bad_request(ScimType::InvalidValue, format_args!("invalid `{name}`"))
+            })
+        }
+
+        let filter = param(req, "filter")?.map(|f| Filter::parse(&f)).transpose()?;
+        let start_index = number(param(req, "startIndex")?, "startIndex")?.unwrap_or(1);
+        let count = number(param(req, "count")?, "count")?;
+        let query = ListQuery {
+            filter,
+            start_index: start_index.max(1) as usize,
+            count: count.map_or(self.max_results, |c| (c.max(0) as usize).min(self.max_results)),
+        };
+
+        let page = self.backend.list(kind, &query).await?;
+        let resources: Vec<_> = page.resources.into_iter()
+            .take(query.count)
+            .map(|resource| self.finish(kind, req, resource))
+            .collect();
+
+        Ok(ScimJson(Status::Ok, Some(json!({
+            "schemas": [schema::LIST_RESPONSE],
+            "totalResults": page.total_results,
+            "startIndex": query.start_index,
+            "itemsPerPage": resources.len(),
+            "Resources": resources,
+        }))))
+    }
+
+    /// Sets `resource`'s `schemas`, if missing, and `meta` attributes.
+    fn finish(&self, kind: ResourceType, req: &Request<'_>, mut resource: Value) -> Value {
+        let Some(object) = resource.as_object_mut() else {
+            return resource;
+        };
+
+        object.entry("schemas").or_insert_with(|| json!([kind.schema()]));
+        let id = object.get("id").and_then(Value::as_str).map(|id| id.to_string());
+        let meta = object.entry("meta").or_insert_with(|| json!({}));
+        if let Some(meta) = meta.as_object_mut() {
+            meta.insert("resourceType".into(), kind.name().into());
+            if let Some(id) = id {
+                let location = format!("{}{}/{id}", base(req), kind.endpoint());
+                meta.insert("location".into(), location.into());
+            }
+        }
+
+        resource
+    }
+
+    fn service_provider_config(&self) -> ScimJson {
+        ScimJson(Status::Ok, Some(json!({
+            "schemas": [schema::SERVICE_PROVIDER_CONFIG],
+            "patch": { "supported": true },
+            "bulk": { "supported": false, "maxOperations": 0, "maxPayloadSize": 0 },
+            "filter": { "supported": true, "maxResults": self.max_results },
+            "changePassword": { "supported": false },
+            "sort": { "supported": false },
+            "etag": { "supported": false },
+            "authenticationSchemes": [{
+                "type": "oauthbearertoken",
+                "name": "OAuth Bearer Token",
+                "description": "Authentication with a bearer token.",
+                "primary": true,
+            }],
+        })))
+    }
+
+    fn resource_types(&self, req: &Request<'_>) -> ScimJson {
+        let base = base(req);
+        let resource_types: Vec<_> = [ResourceType::User, ResourceType::Group].iter()
+            .map(|kind| {
+                let mut resource_type = json!({
+                    "schemas": [schema::RESOURCE_TYPE],
+                    "id": kind.name(),
+                    "name": kind.name(),
+                    "endpoint": kind.endpoint(),
+                    "schema": kind.schema(),
+                    "meta": {
+                        "resourceType": "ResourceType",
+                        "location": format!("{base}/ResourceTypes/{}", kind.name()),
+                    },
+                });
+
+                if *kind == ResourceType::User {
+                    resource_type["schemaExtensions"] = json!([{
+                        "schema": schema::ENTERPRISE_USER,
+                        "required": false,
+                    }]);
+                }
+
+                resource_type
+            })
+            .collect();
+
+        ScimJson(Status::Ok, Some(json!({
+            "schemas": [schema::LIST_RESPONSE],
+            "totalResults": resource_types.len(),
+            "startIndex": 1,
+            "itemsPerPage": resource_types.len(),
+            "Resources": resource_types,
+        })))
+    }
+}
+
+/// Returns the path `req`'s route is mounted at, without a trailing `/`.
+fn base(req: &Request<'_>) -> String {
+    let base = req.route().map(|route| route.uri.base().to_string()).unwrap_or_default();
+    base.trim_end_matches('/').to_string()
+}
+
+/// Reads and parses the JSON object in the body of `req`.
+async fn body(req: &Request<'_>, data: Data<'_>) -> Result<Value, Error> {
+    let limit = req.limits().get("json").unwrap_or(Limits::JSON);
+    let bytes = match data.open(limit).into_bytes().await {
+        Ok(bytes) if bytes.is_complete() => bytes.into_inner(),
+        Ok(_) => return Err(Error::new(Status::PayloadTooLarge, "request body too large")),
+        Err(e) => return Err(Error::new(Status::BadRequest, e)),
+    };
+
+    match serde_json::from_slice::<Value>(&bytes) {
+        Ok(value) if value.is_object() => Ok(value),
+        Ok(_) => Err(Error::bad_request(ScimType::InvalidSyntax, "body must be an object")),
+        Err(e) => Err(Error::bad_request(ScimType::InvalidSyntax, e)),
+    }
+}
+
+impl<'r> Responder<'r, 'static> for ScimJson {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
+        let ScimJson(status, body) = self;
+        let Some(body) = body else {
+            return Response::build().status(status).ok();
+        };
+
+        let location = body.pointer("/meta/location")
+            .and_then(Value::as_str)
+            .filter(|_| status == Status::Created)
+            .map(|location| Header::new("Location", location.to_string()));
+
+        let mut response = Response::build_from(Json(body).respond_to(req)?);
+        response.status(status).header(ContentType::new("application", "scim+json"));
+        if let Some(location) = location {
+            response.header(location);
+        }
+
+        response.ok()
+    }
+}
+
+impl<B> Clone for Scim<B> {
+    fn clone(&self) -> Self {
+        Scim { backend: self.backend.clone(), max_results: self.max_results }
+    }
+}
+
+impl<B> Clone for Endpoint<B> {
+    fn clone(&self) -> Self {
+        Endpoint { scim: self.scim.clone(), target: self.target }
+    }
+}
+
+impl<B: ScimBackend> From<Scim<B>> for Vec<Route> {
+    fn from(scim: Scim<B>) -> Self {
+        let mut routes = vec![];
+        let mut add = |method, path: String, target| {
+            let endpoint = Endpoint { scim: scim.clone(), target };
+            let mut route = Route::new(method, &path, endpoint);
+            route.name = Some("Scim".into());
+            routes.push(route);
+        };
+
+        for kind in [ResourceType::User, ResourceType::Group] {
+            let (collection, resource) = (Target::Collection(kind), Target::Resource(kind));
+            add(Method::Get, kind.endpoint().into(), collection);
+            add(Method::Post, kind.endpoint().into(), collection);
+            for method in [Method::Get, Method::Put, Method::Patch, Method::Delete] {
+                add(method, format!("{}/<id>", kind.endpoint()), resource);
+            }
+        }
+
+        add(Method::Get, "/ServiceProviderConfig".into(), Target::ServiceProviderConfig);
+        add(Method::Get, "/ResourceTypes".into(), Target::ResourceTypes);
+        routes
+    }
+}
+
+#[rocket::async_trait]
+impl<B: ScimBackend> Handler for Endpoint<B> {
+    async fn handle<'r>(&self, req: &'r Request<'_>, data: Data<'r>) -> Outcome<'r> {
+        match self.scim.dispatch(self.target, req, data).await {
+            Ok(response) => Outcome::from(req, response),
+            Err(e) => {
+                if e.status().class().is_server_error() {
+                    error!(name: "scim", error = %e, "SCIM request failed");
+                }
+
+                Outcome::from(req, e)
+            }
+        }
+    }
+}
+
+impl<B> fmt::Debug for Scim<B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Scim")
+            .field("max_results", &self.max_results)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,384 @@
+use std::fmt;
+use std::str::FromStr;
+
+use rocket::serde::json::{Value, serde_json::Map};
+
+use crate::{AttrPath, Error, Filter, ScimType};
+use crate::filter::{Parser, get};
+
+/// The operation of a [`PatchOperation`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PatchOp {
+    /// `add`: adds values to the target.
+    Add,
+    /// `remove`: removes the target's values.
+    Remove,
+    /// `replace`: replaces the target's values.
+    Replace,
+}
+
+/// The `path` of a [`PatchOperation`]: an attribute, optionally filtered to
+/// the values of a multi-valued attribute that match a filter.
+///
+/// When there's a filter, the attribute's sub-attribute, if any, is that of
+/// the matching values, as in `emails[type eq "work"].value`.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket_scim::PatchPath;
+///
+/// let path: PatchPath = r#"emails[type eq "work"].value"#.parse().unwrap();
+/// assert_eq!(path.attribute.name, "emails");
+/// assert_eq!(path.attribute.sub_attribute.as_deref(), Some("value"));
+/// assert!(path.filter.is_some());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct PatchPath {
+    /// The target attribute.
+    pub attribute: AttrPath,
+    /// The filter selecting values of the target attribute, if any.
+    pub filter: Option<Filter>,
+}
+
+/// An operation in a SCIM PATCH request.
+///
+/// Operations are applied to a resource with [`PatchOperation::apply()`],
+/// which implements the semantics of [RFC 7644 §3.5.2]. The default
+/// implementation of [`ScimBackend::patch()`](crate::ScimBackend::patch())
+/// applies a request's operations to the stored resource and replaces it.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::serde::json::json;
+/// use rocket_scim::{PatchOperation, PatchOp};
+///
+/// let mut user = json!({
+///     "userName": "alice",
+///     "emails": [{ "type": "work", "value": "alice@example.com" }],
+/// });
+///
+/// let op = PatchOperation::new(PatchOp::Replace)
+///     .path(r#"emails[type eq "work"].value"#.parse().unwrap())
+///     .value(json!("alice@example.org"));
+///
+/// op.apply(&mut user).unwrap();
+/// assert_eq!(user["emails"][0]["value"], "alice@example.org");
+///
+/// let op = PatchOperation::new(PatchOp::Add).value(json!({ "active": false }));
+/// op.apply(&mut user).unwrap();
+/// assert_eq!(user["active"], false);
+/// ```
+///
+/// [RFC 7644 §3.5.2]: https://datatracker.ietf.org/doc/html/rfc7644#section-3.5.2
+#[derive(Debug, Clone, PartialEq)]
+pub struct PatchOperation {
+    /// The operation.
+    pub op: PatchOp,
+    /// The target of the operation. Without one, the target is the resource.
+    pub path: Option<PatchPath>,
+    /// The value of the operation, required for `add` and `replace`.
+    pub value: Option<Value>,
+}
+
+impl PatchOp {
+    /// Returns the operation's name, like `add`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PatchOp::Add => "add",
+            PatchOp::Remove => "remove",
+            PatchOp::Replace => "replace",
+        }
+    }
+
+    fn parse(name: &str) -> Option<PatchOp> {
+        [PatchOp::Add, PatchOp::Remove, PatchOp::Replace].into_iter()
+            .find(|op| op.as_str().eq_ignore_ascii_case(name))
+    }
+}
+
+impl PatchOperation {
+    /// Returns an operation `op` targeting the resource, without a value.
+    pub fn new(op: PatchOp) -> Self {
+        PatchOperation { op, path: None, value: None }
+    }
+
+    /// Sets the target of the operation to `path`.
+    pub fn path(mut self, path: PatchPath) -> Self {
+        self.path = Some(path);
+        self
+    }
+
+    /// Sets the value of the operation to `value`.
+    pub fn value(mut self, value: Value) -> Self {
+        self.value = Some(value);
+        self
+    }
+
+    /// Parses an operation from its JSON representation, as in
+    /// `{ "op": "add", "path": "nickName", "value": "Al" }`. The operation's
+    /// name is case-insensitive.
+    pub fn from_json(json: &Value) -> Result<Self, Error> {
+        let op = get(json, "op")
+            .and_then(Value::as_str)
+            .and_then(PatchOp::parse)
+            .ok_or_else(|| Error::bad_request(ScimType::InvalidSyntax, "invalid `op`"))?;
+
+        let path = match get(json, "path") {
+            Some(Value::String(path)) => Some(path.parse()?),
+            Some(Value::Null) | None => None,
+            Some(_) => return Err(Error::bad_request(ScimType::InvalidPath, "invalid `path`")),
+        };
+
+        Ok(PatchOperation { op, path, value: get(json, "value").cloned() })
+    }
+
+    /// Applies the operation to `resource`.
+    ///
+    /// Fails with [`ScimType::InvalidValue`] if a value is required but
+    /// missing or of the wrong type, [`ScimType::NoTarget`] if an `add` or
+    /// `replace` filter matches no values or a `remove` has no path, and
+    /// [`ScimType::InvalidPath`] if the path traverses a value that isn't
+    /// complex.
+    pub fn apply(&self, resource: &mut Value) -> Result<(), Error> {
+        let root = resource.as_object_mut().ok_or_else(|| invalid_value("resource"))?;
+        let Some(path) = &self.path else {
+            let Some(Value::Object(attributes)) = &self.value else {
+                return match self.op {
+                    PatchOp::Remove => Err(no_target("a `remove` requires a `path`")),
+                    _ => Err(invalid_value("`value` must be an object without a `path`")),
+                };
+            };
+
+            for (name, value) in attributes {
+                apply(root, name, self.op, Some(value))?;
+            }
+
+            return Ok(());
+        };
+
+        let attribute = &path.attribute;
+        let root = match &attribute.schema {
+            Some(schema) => match child(root, schema, self.op)? {
+                Some(extension) => extension,
+                None => return Ok(()),
+            },
+            None => root,
+        };
+
+        let value = self.value.as_ref();
+        let sub = attribute.sub_attribute.as_deref();
+        let Some(filter) = &path.filter else {
+            let Some(sub) = sub else {
+                return apply(root, &attribute.name, self.op, value);
+            };
+
+            return match root.get_mut(&key(root, &attribute.name)) {
+                Some(Value::Array(elements)) => elements.iter_mut()
+                    .filter_map(Value::as_object_mut)
+                    .try_for_each(|element| apply(element, sub, self.op, value)),
+                _ => match child(root, &attribute.name, self.op)? {
+                    Some(object) => apply(object, sub, self.op, value),
+                    None => Ok(()),
+                },
+            };
+        };
+
+        let field = key(root, &attribute.name);
+        let Some(Value::Array(elements)) = root.get_mut(&field) else {
+            return match self.op {
+                PatchOp::Remove => Ok(()),
+                _ => Err(no_target(path)),
+            };
+        };
+
+        if !elements.iter().any(|element| filter.matches(element)) {
+            return match self.op {
+                PatchOp::Remove => Ok(()),
+                _ => Err(no_target(path)),
+            };
+        }
+
+        if let (PatchOp::Remove, None) = (self.op, sub) {
+            elements.retain(|element| !filter.matches(element));
+            if elements.is_empty() {
+                root.remove(&field);
+            }
+
+            return Ok(());
+        }
+
+        let matching = elements.iter_mut().filter(|element| filter.matches(element));
+        for element in matching {
+            match (self.op, sub) {
+                (PatchOp::Replace, None) => {
+                    *element = value.cloned().ok_or_else(|| invalid_value("`value` is missing"))?;
+                }
+                (op, None) => {
+                    let Some(Value::Object(attributes)) = value else {
+                        return Err(invalid_value("`value` must be an object"));
+                    };
+
+                    let element = element.as_object_mut().ok_or_else(|| invalid_path(path))?;
+                    for (name, value) in attributes {
+                        apply(element, name, op, Some(value))?;
+                    }
+                }
+                (op, Some(sub)) => {
+                    let element = element.as_object_mut().ok_or_else(|| invalid_path(path))?;
+                    apply(element, sub, op, value)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Applies `op` with `value` to the attribute `name` of `object`.
+fn apply(
+    object: &mut Map<String, Value>,
+    name: &str,
+    op: PatchOp,
+    value: Option<&Value>,
+) -> Result<(), Error> {
+    let key = key(object, name);
+    if op == PatchOp::Remove {
+        let existing = object.get_mut(&key);
+        if let (Some(Value::Array(existing)), Some(Value::Array(values))) = (existing, value) {
+            existing.retain(|e| !values.iter().any(|v| same_value(e, v)));
+            if !existing.is_empty() {
+                return Ok(());
+            }
+        }
+
+        object.remove(&key);
+        return Ok(());
+    }
+
+    let value = value.ok_or_else(|| invalid_value("`value` is missing"))?;
+    match (object.get_mut(&key), value) {
+        (Some(Value::Array(existing)), Value::Array(values)) if op == PatchOp::Add => {
+            for value in values {
+                if !existing.iter().any(|e| same_value(e, value)) {
+                    existing.push(value.clone());
+                }
+            }
+        }
+        (Some(Value::Array(existing)), value) if op == PatchOp::Add => {
+            if !existing.iter().any(|e| same_value(e, value)) {
+                existing.push(value.clone());
+            }
+        }
+        (Some(Value::Object(existing)), Value::Object(attributes)) => {
+            for (name, value) in attributes {
+                apply(existing, name, op, Some(value))?;
+            }
+        }
+        _ => {
+            object.insert(key, value.clone());
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the complex attribute `name` of `object`, creating it for an `add`
+/// or `replace`. Returns `None` for a `remove` if it doesn't exist.
+fn child<'m>(
+    object: &'m mut Map<String, Value>,
+    name: &str,
+    op: PatchOp,
+) -> Result<Option<&'m mut Map<String, Value>>, Error> {
+    let key = key(object, name);
+    if op == PatchOp::Remove && !object.contains_key(&key) {
+        return Ok(None);
+    }
+
+    object.entry(key)
+        .or_insert_with(|| Value::Object(Map::new()))
+        .as_object_mut()
+        .map(Some)
+        .ok_or_else(|| invalid_path(name))
+}
+
+/// Returns the key of the attribute `name` in `object`, which may differ in
+/// case, or `name` if there's no such attribute.
+fn key(object: &Map<String, Value>, name: &str) -> String {
+    object.keys()
+        .find(|k| k.eq_ignore_ascii_case(name))
+        .cloned()
+        .unwrap_or_else(|| name.to_string())
+}
+
+/// Returns `true` if the multi-valued attribute values `a` and `b` are the
+/// same: equal, or complex with equal `value` sub-attributes.
+fn same_value(a: &Value, b: &Value) -> bool {
+    a == b || matches!((get(a, "value"), get(b, "value")), (Some(a), Some(b)) if a == b)
+}
+
+fn invalid_value(detail: &str) -> Error {
+    Error::bad_request(ScimType::InvalidValue, detail)
+}
+
+fn invalid_path<P: fmt::Display>(path: P) -> Error {
+    Error::bad_request(ScimType::InvalidPath, format_args!("invalid path `{path}`"))
+}
+
+fn no_target<D: fmt::Display>(detail: D) -> Error {
+    Error::bad_request(ScimType::NoTarget, format_args!("no target: {detail}"))
+}
+
+impl FromStr for PatchPath {
+    type Err = Error;
+
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        PatchPath::parse(&mut Parser::new(string)).ok_or_else(|| invalid_path(string))
+    }
+}
+
+impl PatchPath {
+    /// Parses `attrPath ["[" filter "]" ["." subAttr]]`.
+    fn parse(parser: &mut Parser<'_>) -> Option<PatchPath> {
+        let mut attribute = parser.attr_path()?;
+        let filter = match parser.eat('[') {
+            true => {
+                let filter = parser.filter()?;
+                if !parser.eat(']') || attribute.sub_attribute.is_some() {
+                    return None;
+                }
+
+                if parser.eat('.') {
+                    attribute.sub_attribute = Some(parser.sub_attribute()?);
+                }
+
+                Some(filter)
+            }
+            false => None,
+        };
+
+        parser.is_done().then_some(PatchPath { attribute, filter })
+    }
+}
+
+impl fmt::Display for PatchPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let attribute = &self.attribute;
+        match &self.filter {
+            Some(filter) => {
+                if let Some(schema) = &attribute.schema {
+                    write!(f, "{schema}:")?;
+                }
+
+                write!(f, "{}[{filter}]", attribute.name)?;
+                if let Some(sub) = &attribute.sub_attribute {
+                    write!(f, ".{sub}")?;
+                }
+
+                Ok(())
+            }
+            None => attribute.fmt(f),
+        }
+    }
+}
@@ -0,0 +1,325 @@
+use std::sync::Mutex;
+
+use rocket::http::{ContentType, Header, Status};
+use rocket::local::blocking::{Client, LocalResponse};
+use rocket::serde::json::{json, Value};
+use rocket_scim::*;
+
+const TOKEN: &str = "secret-token";
+
+#[derive(Default)]
+struct Directory {
+    resources: Mutex<Vec<(ResourceType, Value)>>,
+    next_id: Mutex<usize>,
+}
+
+#[rocket::async_trait]
+impl ScimBackend for Directory {
+    async fn authorize(&self, token: &str) -> bool {
+        token == TOKEN
+    }
+
+    async fn list(&self, kind: ResourceType, query: &ListQuery) -> Result<Page, Error> {
+        let resources = self.resources.lock().unwrap();
+        let of_kind = resources.iter()
+            .filter(|(k, _)| *k == kind)
+            .map(|(_, resource)| resource.clone());
+
+        Ok(query.paginate(of_kind))
+    }
+
+    async fn get(&self, kind: ResourceType, id: &str) -> Result<Value, Error> {
+        let resources = self.resources.lock().unwrap();
+        resources.iter()
+            .find(|(k, r)| *k == kind && r["id"] == id)
+            .map(|(_, resource)| resource.clone())
+            .ok_or_else(|| Error::not_found(id))
+    }
+
+    async fn create(&self, kind: ResourceType, mut resource: Value) -> Result<Value, Error> {
+        let mut resources = self.resources.lock().unwrap();
+        let name = &resource["userName"];
+        if kind == ResourceType::User && resources.iter().any(|(_, r)| &r["userName"] == name) {
+            return Err(Error::conflict(format_args!("userName {name} is taken")));
+        }
+
+        let mut next_id = self.next_id.lock().unwrap();
+        *next_id += 1;
+        resource["id"] = next_id.to_string().into();
+        resources.push((kind, resource.clone()));
+        Ok(resource)
+    }
+
+    async fn replace(&self, kind: ResourceType, id: &str, mut resource: Value)
+        -> Result<Value, Error>
+    {
+        let mut resources = self.resources.lock().unwrap();
+        let (_, existing) = resources.iter_mut()
+            .find(|(k, r)| *k == kind && r["id"] == id)
+            .ok_or_else(|| Error::not_found(id))?;
+
+        resource["id"] = id.into();
+        *existing = resource.clone();
+        Ok(resource)
+    }
+
+    async fn delete(&self, kind: ResourceType, id: &str) -> Result<(), Error> {
+        let mut resources = self.resources.lock().unwrap();
+        let index = resources.iter()
+            .position(|(k, r)| *k == kind && r["id"] == id)
+            .ok_or_else(|| Error::not_found(id))?;
+
+        resources.remove(index);
+        Ok(())
+    }
+}
+
+fn client() -> Client {
+    let scim = Scim::new(Directory::default()).max_results(10);
+    Client::debug(rocket::build().mount("/scim/v2", scim)).unwrap()
+}
+
+fn auth() -> Header<'static> {
+    Header::new("Authorization", format!("Bearer {TOKEN}"))
+}
+
+fn json(response: LocalResponse<'_>) -> Value {
+    assert_eq!(response.content_type(), Some(ContentType::new("application", "scim+json")));
+    response.into_json().unwrap()
+}
+
+fn create_user(client: &Client, name: &str, active: bool) -> Value {
+    let response = client.post("/scim/v2/Users")
+        .header(auth())
+        .json(&json!({ "schemas": [schema::USER], "userName": name, "active": active }))
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Created);
+    json(response)
+}
+
+#[test]
+fn requests_require_authorization() {
+    let client = client();
+    let response = client.get("/scim/v2/Users").dispatch();
+    assert_eq!(response.status(), Status::Unauthorized);
+    let error = json(response);
+    assert_eq!(error["schemas"], json!([schema::ERROR]));
+    assert_eq!(error["status"], "401");
+
+    let response = client.get("/scim/v2/Users")
+        .header(Header::new("Authorization", "Bearer wrong"))
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Unauthorized);
+
+    let response = client.get("/scim/v2/Users")
+        .header(Header::new("Authorization", format!("Basic {TOKEN}")))
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Unauthorized);
+
+    let response = client.get("/scim/v2/Users")
+        .header(Header::new("Authorization", format!("bearer {TOKEN}")))
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Ok);
+
+    // Discovery endpoints are public.
+    let response = client.get("/scim/v2/ServiceProviderConfig").dispatch();
+    let config = json(response);
+    assert_eq!(config["patch"]["supported"], true);
+    assert_eq!(config["filter"]["maxResults"], 10);
+
+    let types = json(client.get("/scim/v2/ResourceTypes").dispatch());
+    assert_eq!(types["totalResults"], 2);
+    assert_eq!(types["Resources"][0]["endpoint"], "/Users");
+    assert_eq!(types["Resources"][1]["meta"]["location"], "/scim/v2/ResourceTypes/Group");
+}
+
+#[test]
+fn create_get_replace_delete() {
+    let client = client();
+    let response = client.post("/scim/v2/Users")
+        .header(auth())
+        .json(&json!({ "userName": "alice", "name": { "givenName": "Alice" } }))
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Created);
+    assert_eq!(response.headers().get_one("Location"), Some("/scim/v2/Users/1"));
+    let user = json(response);
+    assert_eq!(user["id"], "1");
+    assert_eq!(user["schemas"], json!([schema::USER]));
+    assert_eq!(user["meta"]["resourceType"], "User");
+    assert_eq!(user["meta"]["location"], "/scim/v2/Users/1");
+
+    let response = client.get("/scim/v2/Users/1").header(auth()).dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert!(response.headers().get_one("Location").is_none());
+    assert_eq!(json(response)["name"]["givenName"], "Alice");
+
+    let response = client.put("/scim/v2/Users/1")
+        .header(auth())
+        .json(&json!({ "userName": "alice", "active": false }))
+        .dispatch();
+
+    let user = json(response);
+    assert_eq!(user["active"], false);
+    assert!(user.get("name").is_none());
+
+    // Usernames are unique.
+    let response = client.post("/scim/v2/Users")
+        .header(auth())
+        .json(&json!({ "userName": "alice" }))
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Conflict);
+    assert_eq!(json(response)["scimType"], "uniqueness");
+
+    let response = client.delete("/scim/v2/Users/1").header(auth()).dispatch();
+    assert_eq!(response.status(), Status::NoContent);
+    assert!(response.into_string().is_none());
+
+    let response = client.get("/scim/v2/Users/1").header(auth()).dispatch();
+    assert_eq!(response.status(), Status::NotFound);
+    assert_eq!(json(response)["status"], "404");
+
+    // Groups are separate from users.
+    let response = client.post("/scim/v2/Groups")
+        .header(auth())
+        .json(&json!({ "displayName": "Admins" }))
+        .dispatch();
+
+    assert_eq!(response.headers().get_one("Location"), Some("/scim/v2/Groups/2"));
+    assert_eq!(json(response)["schemas"], json!([schema::GROUP]));
+    let response = client.get("/scim/v2/Users/2").header(auth()).dispatch();
+    assert_eq!(response.status(), Status::NotFound);
+}
+
+#[test]
+fn list_with_filter_and_pagination() {
+    let client = client();
+    for i in 1..=15 {
+        create_user(&client, &format!("user{i:02}"), i % 3 != 0);
+    }
+
+    let list = json(client.get("/scim/v2/Users").header(auth()).dispatch());
+    assert_eq!(list["schemas"], json!([schema::LIST_RESPONSE]));
+    assert_eq!(list["totalResults"], 15);
+    assert_eq!(list["startIndex"], 1);
+    assert_eq!(list["itemsPerPage"], 10);
+
+    let list = json(client.get("/scim/v2/Users?startIndex=11&count=3").header(auth()).dispatch());
+    assert_eq!(list["totalResults"], 15);
+    assert_eq!(list["itemsPerPage"], 3);
+    assert_eq!(list["Resources"][0]["userName"], "user11");
+
+    let list = json(client.get("/scim/v2/Users?count=0").header(auth()).dispatch());
+    assert_eq!(list["totalResults"], 15);
+    assert_eq!(list["Resources"], json!([]));
+
+    let uri = "/scim/v2/Users?filter=userName%20eq%20%22USER07%22";
+    let list = json(client.get(uri).header(auth()).dispatch());
+    assert_eq!(list["totalResults"], 1);
+    assert_eq!(list["Resources"][0]["userName"], "user07");
+    assert_eq!(list["Resources"][0]["meta"]["location"], "/scim/v2/Users/7");
+
+    let uri = "/scim/v2/Users?filter=active%20eq%20false%20and%20userName%20sw%20%22user1%22";
+    let list = json(client.get(uri).header(auth()).dispatch());
+    assert_eq!(list["totalResults"], 2);
+    assert_eq!(list["Resources"][0]["userName"], "user12");
+    assert_eq!(list["Resources"][1]["userName"], "user15");
+
+    let response = client.get("/scim/v2/Users?filter=userName%20eq").header(auth()).dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
+    assert_eq!(json(response)["scimType"], "invalidFilter");
+
+    let response = client.get("/scim/v2/Users?count=many").header(auth()).dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
+    assert_eq!(json(response)["scimType"], "invalidValue");
+}
+
+#[test]
+fn patch_operations() {
+    let client = client();
+    create_user(&client, "alice", true);
+    create_user(&client, "bob", true);
+
+    let response = client.post("/scim/v2/Groups")
+        .header(auth())
+        .json(&json!({ "displayName": "Admins", "members": [{ "value": "1" }] }))
+        .dispatch();
+
+    let group = json(response);
+    let uri = format!("/scim/v2/Groups/{}", group["id"].as_str().unwrap());
+    let patch = |operations: Value| {
+        client.patch(&uri)
+            .header(auth())
+            .json(&json!({ "schemas": [schema::PATCH_OP], "Operations": operations }))
+            .dispatch()
+    };
+
+    let group = json(patch(json!([
+        { "op": "Add", "path": "members", "value": [{ "value": "2" }, { "value": "1" }] },
+        { "op": "Replace", "value": { "displayName": "Administrators" } },
+    ])));
+
+    assert_eq!(group["displayName"], "Administrators");
+    assert_eq!(group["members"], json!([{ "value": "1" }, { "value": "2" }]));
+
+    // Entra ID removes members with a `value` and no filter.
+    let group = json(patch(json!([
+        { "op": "Remove", "path": "members", "value": [{ "value": "1" }] },
+    ])));
+
+    assert_eq!(group["members"], json!([{ "value": "2" }]));
+
+    let group = json(patch(json!([{ "op": "remove", "path": "members[value eq \"2\"]" }])));
+    assert!(group.get("members").is_none());
+
+    let response = patch(json!([{ "op": "replace", "path": "members[value eq \"9\"]" }]));
+    assert_eq!(response.status(), Status::BadRequest);
+    assert_eq!(json(response)["scimType"], "noTarget");
+
+    let response = patch(json!([{ "op": "move", "path": "displayName" }]));
+    assert_eq!(response.status(), Status::BadRequest);
+    assert_eq!(json(response)["scimType"], "invalidSyntax");
+
+    let response = patch(json!([{ "op": "add", "path": "members[value eq", "value": [] }]));
+    assert_eq!(response.status(), Status::BadRequest);
+
+    let response = client.patch(&uri).header(auth()).json(&json!({})).dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
+    assert_eq!(json(response)["scimType"], "invalidSyntax");
+
+    // Deactivating a user, as identity providers do on deprovisioning.
+    let response = client.patch("/scim/v2/Users/2")
+        .header(auth())
+        .json(&json!({ "Operations": [{ "op": "replace", "path": "active", "value": false }] }))
+        .dispatch();
+
+    assert_eq!(json(response)["active"], false);
+
+    let response = client.patch("/scim/v2/Users/9")
+        .header(auth())
+        .json(&json!({ "Operations": [] }))
+        .dispatch();
+
+    assert_eq!(response.status(), Status::NotFound);
+}
+
+#[test]
+fn malformed_bodies_are_rejected() {
+    let client = client();
+    let response = client.post("/scim/v2/Users")
+        .header(auth())
+        .header(ContentType::JSON)
+        .body("{ not json")
+        .dispatch();
+
+    assert_eq!(response.status(), Status::BadRequest);
+    assert_eq!(json(response)["scimType"], "invalidSyntax");
+
+    let response = client.post("/scim/v2/Users").header(auth()).json(&json!([1, 2])).dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
+}
@@ -0,0 +1,83 @@
+use devise::{Result, Diagnostic, Spanned};
+use devise::ext::SpanDiagnosticExt;
+use proc_macro2::TokenStream;
+use syn::punctuated::Punctuated;
+
+/// The names of the route attributes `#[data]` can be used with.
+const ROUTE_ATTRS: &[&str] = &["route", "get", "put", "post", "delete", "head", "patch", "options"];
+
+/// The parsed `#[data(..)]` attribute.
+#[derive(Debug, Default)]
+pub struct DataAttr {
+    pub verify_content_type: bool,
+}
+
+impl DataAttr {
+    fn parse(args: TokenStream) -> Result<Self> {
+        let parser = Punctuated::<syn::Ident, syn::Token![,]>::parse_terminated;
+        let options = syn::parse::Parser::parse2(parser, args)?;
+        if options.is_empty() {
+            return Err(proc_macro2::Span::call_site()
+                .error("`#[data]` expects at least one option")
+                .help("try `#[data(verify_content_type)]`"));
+        }
+
+        let mut attr = DataAttr::default();
+        for option in options {
+            match option.to_string().as_str() {
+                "verify_content_type" => attr.verify_content_type = true,
+                _ => return Err(option.span().error("unknown `#[data]` option")
+                    .help("the only supported option is `verify_content_type`")),
+            }
+        }
+
+        Ok(attr)
+    }
+
+    /// Removes the `#[data]` attribute, if any, from `attrs` and returns it,
+    /// parsed.
+    pub fn take_from_attrs(attrs: &mut Vec<syn::Attribute>) -> Result<Option<Self>> {
+        let is_data = |attr: &syn::Attribute| attr.path().segments.last()
+            .map_or(false, |s| s.ident == "data");
+
+        let mut data = None;
+        for attr in attrs.iter().filter(|attr| is_data(attr)) {
+            if data.is_some() {
+                return Err(attr.span().error("duplicate `#[data]` attribute"));
+            }
+
+            let list = attr.meta.require_list().map_err(Diagnostic::from)?;
+            data = Some(DataAttr::parse(list.tokens.clone())?);
+        }
+
+        attrs.retain(|attr| !is_data(attr));
+        Ok(data)
+    }
+}
+
+fn data(args: TokenStream, input: TokenStream) -> Result<TokenStream> {
+    let mut function: syn::ItemFn = syn::parse2(input)
+        .map_err(Diagnostic::from)
+        .map_err(|d| d.help("`#[data]` can only be used on route handlers"))?;
+
+    DataAttr::parse(args.clone())?;
+
+    // Move the attribute below the route attribute, which will consume it.
+    let is_route = |attr: &syn::Attribute| attr.path().segments.last()
+        .map_or(false, |s| ROUTE_ATTRS.iter().any(|name| s.ident == name));
+
+    let Some(i) = function.attrs.iter().rposition(is_route) else {
+        return Err(function.sig.ident.span().error("`#[data]` requires a route attribute")
+            .help("add a route attribute, such as `#[post]`, below `#[data]`"));
+    };
+
+    function.attrs.insert(i + 1, syn::parse_quote!(#[::rocket::data(#args)]));
+    Ok(quote!(#function))
+}
+
+pub fn data_attribute(
+    args: proc_macro::TokenStream,
+    input: proc_macro::TokenStream
+) -> TokenStream {
+    data(args.into(), input.into()).unwrap_or_else(|diag| diag.emit_as_item_tokens())
+}
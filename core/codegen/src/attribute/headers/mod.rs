@@ -0,0 +1,114 @@
+use devise::{Result, Diagnostic, Spanned};
+use devise::ext::SpanDiagnosticExt;
+use proc_macro2::TokenStream;
+use syn::punctuated::Punctuated;
+
+/// The names of the route attributes `#[headers]` can be used with.
+const ROUTE_ATTRS: &[&str] = &["route", "get", "put", "post", "delete", "head", "patch", "options"];
+
+/// The parsed `#[headers(..)]` attribute: header names and values, in order.
+#[derive(Debug, Default)]
+pub struct HeadersAttr {
+    pub headers: Vec<(String, String)>,
+}
+
+/// Converts a header key such as `x_robots_tag` to its name, `X-Robots-Tag`.
+fn header_name(key: &syn::Ident) -> String {
+    key.to_string()
+        .split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(c) => c.to_ascii_uppercase().to_string() + &chars.as_str().to_lowercase(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+impl HeadersAttr {
+    fn parse(args: TokenStream) -> Result<Self> {
+        let parser = Punctuated::<syn::MetaNameValue, syn::Token![,]>::parse_terminated;
+        let pairs = syn::parse::Parser::parse2(parser, args)?;
+        if pairs.is_empty() {
+            return Err(proc_macro2::Span::call_site()
+                .error("`#[headers]` expects at least one header")
+                .help("try `#[headers(x_robots_tag = \"noindex\")]`"));
+        }
+
+        let mut attr = HeadersAttr::default();
+        for pair in pairs {
+            let Some(key) = pair.path.get_ident() else {
+                return Err(pair.path.span().error("expected a header name")
+                    .help("header names are written in snake case, as in `cache_control`"));
+            };
+
+            let value = match &pair.value {
+                syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(s), .. }) => s.value(),
+                value => return Err(value.span().error("expected a string literal")),
+            };
+
+            if value.contains(['\r', '\n']) {
+                return Err(pair.value.span().error("header values cannot contain line breaks"));
+            }
+
+            let name = header_name(key);
+            if attr.headers.iter().any(|(n, _)| n == &name) {
+                return Err(key.span().error(format!("duplicate header `{name}`")));
+            }
+
+            attr.headers.push((name, value));
+        }
+
+        Ok(attr)
+    }
+
+    /// Removes the `#[headers]` attribute, if any, from `attrs` and returns
+    /// it, parsed.
+    pub fn take_from_attrs(attrs: &mut Vec<syn::Attribute>) -> Result<Option<Self>> {
+        let is_headers = |attr: &syn::Attribute| attr.path().segments.last()
+            .map_or(false, |s| s.ident == "headers");
+
+        let mut headers = None;
+        for attr in attrs.iter().filter(|attr| is_headers(attr)) {
+            if headers.is_some() {
+                return Err(attr.span().error("duplicate `#[headers]` attribute")
+                    .help("declare all headers in a single `#[headers]` attribute"));
+            }
+
+            let list = attr.meta.require_list().map_err(Diagnostic::from)?;
+            headers = Some(HeadersAttr::parse(list.tokens.clone())?);
+        }
+
+        attrs.retain(|attr| !is_headers(attr));
+        Ok(headers)
+    }
+}
+
+fn headers(args: TokenStream, input: TokenStream) -> Result<TokenStream> {
+    let mut function: syn::ItemFn = syn::parse2(input)
+        .map_err(Diagnostic::from)
+        .map_err(|d| d.help("`#[headers]` can only be used on route handlers"))?;
+
+    HeadersAttr::parse(args.clone())?;
+
+    // Move the attribute below the route attribute, which will consume it.
+    let is_route = |attr: &syn::Attribute| attr.path().segments.last()
+        .map_or(false, |s| ROUTE_ATTRS.iter().any(|name| s.ident == name));
+
+    let Some(i) = function.attrs.iter().rposition(is_route) else {
+        return Err(function.sig.ident.span().error("`#[headers]` requires a route attribute")
+            .help("add a route attribute, such as `#[get]`, below `#[headers]`"));
+    };
+
+    function.attrs.insert(i + 1, syn::parse_quote!(#[::rocket::headers(#args)]));
+    Ok(quote!(#function))
+}
+
+pub fn headers_attribute(
+    args: proc_macro::TokenStream,
+    input: proc_macro::TokenStream
+) -> TokenStream {
+    headers(args.into(), input.into()).unwrap_or_else(|diag| diag.emit_as_item_tokens())
+}
@@ -4,3 +4,7 @@ pub mod route;
 pub mod param;
 pub mod async_bound;
 pub mod suppress;
+pub mod route_if;
+pub mod data;
+pub mod headers;
+pub mod openapi;
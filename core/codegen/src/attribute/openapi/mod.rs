@@ -0,0 +1,118 @@
+use devise::{Result, Diagnostic, Spanned};
+use devise::ext::SpanDiagnosticExt;
+use proc_macro2::TokenStream;
+use syn::punctuated::Punctuated;
+
+/// The names of the route attributes `#[openapi]` can be used with.
+const ROUTE_ATTRS: &[&str] = &["route", "get", "put", "post", "delete", "head", "patch", "options"];
+
+/// The parsed `#[openapi(..)]` attribute.
+#[derive(Debug, Default)]
+pub struct OpenApiAttr {
+    pub skip: bool,
+    pub tags: Vec<String>,
+    pub operation_id: Option<String>,
+}
+
+impl OpenApiAttr {
+    fn parse(args: TokenStream) -> Result<Self> {
+        let parser = Punctuated::<syn::Meta, syn::Token![,]>::parse_terminated;
+        let options = syn::parse::Parser::parse2(parser, args)?;
+        if options.is_empty() {
+            return Err(proc_macro2::Span::call_site()
+                .error("`#[openapi]` expects at least one option")
+                .help("try `#[openapi(skip)]` or `#[openapi(tag = \"name\")]`"));
+        }
+
+        let mut attr = OpenApiAttr::default();
+        for option in options {
+            let value = match &option {
+                syn::Meta::Path(path) if path.is_ident("skip") => {
+                    attr.skip = true;
+                    continue;
+                }
+                syn::Meta::NameValue(pair) => match &pair.value {
+                    syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(s), .. }) => s.value(),
+                    value => return Err(value.span().error("expected a string literal")),
+                },
+                _ => return Err(option.span().error("unknown `#[openapi]` option")
+                    .help("options are `skip`, `tag = \"..\"`, and `operation_id = \"..\"`")),
+            };
+
+            let path = option.path();
+            if path.is_ident("tag") {
+                attr.tags.push(value);
+            } else if path.is_ident("operation_id") {
+                if attr.operation_id.replace(value).is_some() {
+                    return Err(option.span().error("duplicate `operation_id` option"));
+                }
+            } else {
+                return Err(path.span().error("unknown `#[openapi]` option")
+                    .help("options are `skip`, `tag = \"..\"`, and `operation_id = \"..\"`"));
+            }
+        }
+
+        Ok(attr)
+    }
+
+    /// Removes the `#[openapi]` attribute, if any, from `attrs` and returns
+    /// it, parsed.
+    pub fn take_from_attrs(attrs: &mut Vec<syn::Attribute>) -> Result<Option<Self>> {
+        let is_openapi = |attr: &syn::Attribute| attr.path().segments.last()
+            .map_or(false, |s| s.ident == "openapi");
+
+        let mut openapi = None;
+        for attr in attrs.iter().filter(|attr| is_openapi(attr)) {
+            if openapi.is_some() {
+                return Err(attr.span().error("duplicate `#[openapi]` attribute")
+                    .help("declare all options in a single `#[openapi]` attribute"));
+            }
+
+            let list = attr.meta.require_list().map_err(Diagnostic::from)?;
+            openapi = Some(OpenApiAttr::parse(list.tokens.clone())?);
+        }
+
+        attrs.retain(|attr| !is_openapi(attr));
+        Ok(openapi)
+    }
+}
+
+/// Returns the doc comment in `attrs`, without the leading space of each line.
+pub fn doc_comment(attrs: &[syn::Attribute]) -> String {
+    attrs.iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .filter_map(|attr| match &attr.meta.require_name_value().ok()?.value {
+            syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(s), .. }) => Some(s.value()),
+            _ => None,
+        })
+        .map(|line| line.strip_prefix(' ').map(|l| l.to_owned()).unwrap_or(line))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn openapi(args: TokenStream, input: TokenStream) -> Result<TokenStream> {
+    let mut function: syn::ItemFn = syn::parse2(input)
+        .map_err(Diagnostic::from)
+        .map_err(|d| d.help("`#[openapi]` can only be used on route handlers"))?;
+
+    OpenApiAttr::parse(args.clone())?;
+
+    // Move the attribute below the route attribute, which will consume it.
+    let is_route = |attr: &syn::Attribute| attr.path().segments.last()
+        .map_or(false, |s| ROUTE_ATTRS.iter().any(|name| s.ident == name));
+
+    let Some(i) = function.attrs.iter().rposition(is_route) else {
+        return Err(function.sig.ident.span().error("`#[openapi]` requires a route attribute")
+            .help("add a route attribute, such as `#[get]`, below `#[openapi]`"));
+    };
+
+    function.attrs.insert(i + 1, syn::parse_quote!(#[::rocket::openapi(#args)]));
+    Ok(quote!(#function))
+}
+
+pub fn openapi_attribute(
+    args: proc_macro::TokenStream,
+    input: proc_macro::TokenStream
+) -> TokenStream {
+    openapi(args.into(), input.into()).unwrap_or_else(|diag| diag.emit_as_item_tokens())
+}
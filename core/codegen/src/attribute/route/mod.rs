@@ -3,7 +3,7 @@ mod parse;
 use std::hash::Hash;
 
 use devise::{Spanned, SpanWrapped, Result, FromMeta, Diagnostic};
-use devise::ext::TypeExt as _;
+use devise::ext::{SpanDiagnosticExt, TypeExt as _};
 use proc_macro2::{TokenStream, Span};
 
 use crate::proc_macro_ext::StringLit;
@@ -15,6 +15,10 @@ use crate::exports::mixed;
 use self::parse::{Route, Attribute, MethodAttribute};
 
 use super::suppress::Lint;
+use super::route_if::RouteIf;
+use super::data::DataAttr;
+use super::headers::HeadersAttr;
+use super::openapi::{OpenApiAttr, doc_comment};
 
 impl Route {
     pub fn guards(&self) -> impl Iterator<Item = &Guard> {
@@ -41,7 +45,7 @@ fn query_decls(route: &Route) -> Option<TokenStream> {
     }
 
     define_spanned_export!(Span::call_site() =>
-        __req, __data, _form, Outcome, _Ok, _Err, _Some, _None, Status
+        __req, __data, _form, _request, Outcome, _Ok, _Err, _Some, _None, Status
     );
 
     // Record all of the static parameters for later filtering.
@@ -77,6 +81,10 @@ fn query_decls(route: &Route) -> Option<TokenStream> {
         })
         .split6();
 
+    let (fn_ident, ty) = route.query_guards()
+        .map(|guard| (&guard.fn_ident, &guard.ty))
+        .split2();
+
     #[allow(non_snake_case)]
     Some(quote! {
         let (#(#ident),*) = {
@@ -98,6 +106,11 @@ fn query_decls(route: &Route) -> Option<TokenStream> {
                 let #ident = match #finalize_expr {
                     #_Ok(_v) => #_Some(_v),
                     #_Err(_err) => {
+                        #_request::Diagnostic::record(
+                            #__req, "query", stringify!(#fn_ident), stringify!(#ty),
+                            true, #Status::UnprocessableEntity, #_Some(&_err)
+                        );
+
                         __e.extend(_err.with_name(#_form::NameView::new(#name)));
                         #_None
                     },
@@ -123,15 +136,26 @@ fn query_decls(route: &Route) -> Option<TokenStream> {
 }
 
 fn request_guard_decl(guard: &Guard) -> TokenStream {
-    let (ident, ty) = (guard.fn_ident.rocketized(), &guard.ty);
+    let (fn_ident, ty) = (&guard.fn_ident, &guard.ty);
+    let ident = fn_ident.rocketized();
     define_spanned_export!(ty.span() =>
-        __req, __data, _request, display_hack, FromRequest, Outcome
+        __req, __data, _request, _None, _Some, display_hack, FromRequest, Outcome
     );
 
     quote_spanned! { ty.span() =>
-        let #ident: #ty = match <#ty as #FromRequest>::from_request(#__req).await {
+        let __timer = #_request::Stage::start(#__req);
+        let __outcome = <#ty as #FromRequest>::from_request(#__req).await;
+        #_request::Stage::record(#__req, __timer, "guard",
+            concat!(stringify!(#fn_ident), ": ", stringify!(#ty)));
+
+        let #ident: #ty = match __outcome {
             #Outcome::Success(__v) => __v,
             #Outcome::Forward(__e) => {
+                #_request::Diagnostic::record(
+                    #__req, "request", stringify!(#fn_ident), stringify!(#ty),
+                    true, __e, #_None
+                );
+
                 ::rocket::trace::info!(
                     name: "forward",
                     target: concat!("rocket::codegen::route::", module_path!()),
@@ -145,6 +169,11 @@ fn request_guard_decl(guard: &Guard) -> TokenStream {
             },
             #[allow(unreachable_code)]
             #Outcome::Error((__c, __e)) => {
+                #_request::Diagnostic::record(
+                    #__req, "request", stringify!(#fn_ident), stringify!(#ty),
+                    false, __c, #_Some(&#display_hack!(&__e))
+                );
+
                 ::rocket::trace::info!(
                     name: "failure",
                     target: concat!("rocket::codegen::route::", module_path!()),
@@ -162,13 +191,19 @@ fn request_guard_decl(guard: &Guard) -> TokenStream {
 
 fn param_guard_decl(guard: &Guard) -> TokenStream {
     let (i, name, ty) = (guard.index, &guard.name, &guard.ty);
+    let fn_ident = &guard.fn_ident;
     define_spanned_export!(ty.span() =>
-        __req, __data, _None, _Some, _Ok, _Err,
+        __req, __data, _request, _None, _Some, _Ok, _Err,
         Outcome, FromSegments, FromParam, Status, display_hack
     );
 
     // Returned when a dynamic parameter fails to parse.
     let parse_error = quote!({
+        #_request::Diagnostic::record(
+            #__req, "path", stringify!(#fn_ident), stringify!(#ty),
+            true, #Status::UnprocessableEntity, #_Some(&#display_hack!(&__error))
+        );
+
         ::rocket::trace::info!(
             name: "forward",
             target: concat!("rocket::codegen::route::", module_path!()),
@@ -218,13 +253,26 @@ fn param_guard_decl(guard: &Guard) -> TokenStream {
 }
 
 fn data_guard_decl(guard: &Guard) -> TokenStream {
-    let (ident, ty) = (guard.fn_ident.rocketized(), &guard.ty);
-    define_spanned_export!(ty.span() => __req, __data, display_hack, FromData, Outcome);
+    let (fn_ident, ty) = (&guard.fn_ident, &guard.ty);
+    let ident = fn_ident.rocketized();
+    define_spanned_export!(ty.span() =>
+        __req, __data, _request, _None, _Some, display_hack, FromData, Outcome
+    );
 
     quote_spanned! { ty.span() =>
-        let #ident: #ty = match <#ty as #FromData>::from_data(#__req, #__data).await {
+        let __timer = #_request::Stage::start(#__req);
+        let __outcome = <#ty as #FromData>::from_data(#__req, #__data).await;
+        #_request::Stage::record(#__req, __timer, "guard",
+            concat!(stringify!(#fn_ident), ": ", stringify!(#ty)));
+
+        let #ident: #ty = match __outcome {
             #Outcome::Success(__d) => __d,
             #Outcome::Forward((__d, __e)) => {
+                #_request::Diagnostic::record(
+                    #__req, "data", stringify!(#fn_ident), stringify!(#ty),
+                    true, __e, #_None
+                );
+
                 ::rocket::trace::info!(
                     name: "forward",
                     target: concat!("rocket::codegen::route::", module_path!()),
@@ -238,6 +286,11 @@ fn data_guard_decl(guard: &Guard) -> TokenStream {
             }
             #[allow(unreachable_code)]
             #Outcome::Error((__c, __e)) => {
+                #_request::Diagnostic::record(
+                    #__req, "data", stringify!(#fn_ident), stringify!(#ty),
+                    false, __c, #_Some(&#display_hack!(&__e))
+                );
+
                 ::rocket::trace::info!(
                     name: "failure",
                     target: concat!("rocket::codegen::route::", module_path!()),
@@ -307,10 +360,22 @@ fn responder_outcome_expr(route: &Route) -> TokenStream {
     let _await = route.handler.sig.asyncness
         .map(|a| quote_spanned!(a.span() => .await));
 
-    define_spanned_export!(ret_span => __req, _route);
+    let responder = match route.handler.sig.output {
+        syn::ReturnType::Default => quote!("()"),
+        syn::ReturnType::Type(_, ref ty) => quote!(stringify!(#ty)),
+    };
+
+    define_spanned_export!(ret_span => __req, _route, _request);
     quote_spanned! { mixed(ret_span) =>
+        let ___timer = #_request::Stage::start(#__req);
         let ___responder = #user_handler_fn_name(#(#parameter_names),*) #_await;
-        #_route::Outcome::from(#__req, ___responder)
+        #_request::Stage::record(#__req, ___timer, "handler",
+            stringify!(#user_handler_fn_name));
+
+        let ___timer = #_request::Stage::start(#__req);
+        let ___outcome = #_route::Outcome::from(#__req, ___responder);
+        #_request::Stage::record(#__req, ___timer, "responder", #responder);
+        ___outcome
     }
 }
 
@@ -376,9 +441,91 @@ fn sentinels_expr(route: &Route) -> TokenStream {
     quote!(::std::vec![#(#sentinel),*])
 }
 
-fn codegen_route(route: Route) -> Result<TokenStream> {
+fn operation_expr(route: &Route, attr: OpenApiAttr) -> TokenStream {
     use crate::exports::*;
 
+    if attr.skip {
+        return quote!(#_None);
+    }
+
+    let docs = doc_comment(&route.handler.attrs);
+    let deprecated = route.handler.attrs.iter().any(|a| a.path().is_ident("deprecated"));
+    let operation_id = attr.operation_id.iter();
+    let tags = attr.tags.iter();
+
+    let generic_idents: Vec<_> = route.handler.sig.generics
+        .type_params()
+        .map(|p| &p.ident)
+        .collect();
+
+    // Types that aren't concrete can't be named outside of the handler, so
+    // they're documented without a schema, as are types without `ToSchema`.
+    let resolved = |ty: &syn::Type| {
+        let ty = ty.with_stripped_lifetimes();
+        match ty.is_concrete(&generic_idents) {
+            true => quote_spanned!(ty.span() => #_openapi::resolve_schema!(#ty)),
+            false => quote!(#_openapi::resolution::Resolved::unknown()),
+        }
+    };
+
+    let path_params = route.param_guards().map(|guard| (guard, quote!(Path)));
+    let query_params = route.query_guards().map(|guard| (guard, quote!(Query)));
+    let params = path_params.chain(query_params).map(|(guard, location)| {
+        let (name, resolved) = (&guard.source.name, resolved(&guard.ty));
+        quote! {
+            __op.parameters.push(#resolved.into_parameter(#name, #_openapi::Location::#location));
+        }
+    });
+
+    let body = route.data_guard.as_ref().map(|guard| {
+        let resolved = resolved(&guard.ty);
+        quote!(__op.request_body = #_Some(#resolved.into_body());)
+    });
+
+    let ret = match route.handler.sig.output {
+        syn::ReturnType::Default => quote!(#_openapi::resolve_schema!(())),
+        syn::ReturnType::Type(_, ref ty) => resolved(ty),
+    };
+
+    quote!({
+        let mut __op = #_openapi::Operation::new(#docs);
+        __op.deprecated = #deprecated;
+        #(__op.operation_id = #_Some(#operation_id.into());)*
+        #(__op.tags.push(#tags.into());)*
+        #(#params)*
+        #body
+        __op.responses.extend(#ret.into_responses());
+        #_Some(__op)
+    })
+}
+
+fn codegen_route(mut route: Route) -> Result<TokenStream> {
+    use crate::exports::*;
+
+    // Remove the condition, if any, from the handler's attributes.
+    let route_if = RouteIf::take_from_attrs(&mut route.handler.attrs)?;
+    let cfg = route_if.as_ref().and_then(|r| r.cfg());
+    let not_cfg = route_if.as_ref().and_then(|r| r.not_cfg());
+    let condition = RouteIf::condition_expr(route_if.as_ref());
+
+    // Remove the data options, if any, from the handler's attributes.
+    let data_attr = DataAttr::take_from_attrs(&mut route.handler.attrs)?.unwrap_or_default();
+    if data_attr.verify_content_type && route.data_guard.is_none() {
+        return Err(route.handler.sig.ident.span()
+            .error("`#[data(verify_content_type)]` requires a data guard")
+            .help("declare one with `data = \"<param>\"` in the route attribute"));
+    }
+
+    let verify_content_type = data_attr.verify_content_type;
+
+    // Remove the response headers, if any, from the handler's attributes.
+    let headers_attr = HeadersAttr::take_from_attrs(&mut route.handler.attrs)?;
+    let headers = headers_attr.unwrap_or_default().headers.into_iter()
+        .map(|(name, value)| quote!((#name, #value)));
+
+    // Remove the OpenAPI options, if any, from the handler's attributes.
+    let openapi_attr = OpenApiAttr::take_from_attrs(&mut route.handler.attrs)?;
+
     // Generate the declarations for all of the guards.
     let request_guards = route.request_guards.iter().map(request_guard_decl);
     let param_guards = route.param_guards().map(param_guard_decl);
@@ -388,6 +535,9 @@ fn codegen_route(route: Route) -> Result<TokenStream> {
     // Extract the sentinels from the route.
     let sentinels = sentinels_expr(&route);
 
+    // Document the route from its doc comment and signature.
+    let operation = operation_expr(&route, openapi_attr.unwrap_or_default());
+
     // Gather info about the function.
     let (vis, handler_fn) = (&route.handler.vis, &route.handler);
     let deprecated = handler_fn.attrs.iter().find(|a| a.path().is_ident("deprecated"));
@@ -401,7 +551,7 @@ fn codegen_route(route: Route) -> Result<TokenStream> {
     let format = Optional(route.attr.format.as_ref());
 
     Ok(quote! {
-        #handler_fn
+        #cfg #handler_fn
 
         #[doc(hidden)]
         #[allow(nonstandard_style)]
@@ -412,7 +562,7 @@ fn codegen_route(route: Route) -> Result<TokenStream> {
         #[allow(nonstandard_style, deprecated, clippy::style)]
         impl #handler_fn_name {
             fn into_info(self) -> #_route::StaticInfo {
-                fn monomorphized_function<'__r>(
+                #cfg fn monomorphized_function<'__r>(
                     #__req: &'__r #Request<'_>,
                     #__data: #Data<'__r>
                 ) -> #_route::BoxFuture<'__r> {
@@ -426,6 +576,8 @@ fn codegen_route(route: Route) -> Result<TokenStream> {
                     })
                 }
 
+                #not_cfg use #_route::dummy_handler as monomorphized_function;
+
                 #_route::StaticInfo {
                     name: stringify!(#handler_fn_name),
                     method: #method,
@@ -434,6 +586,10 @@ fn codegen_route(route: Route) -> Result<TokenStream> {
                     format: #format,
                     rank: #rank,
                     sentinels: #sentinels,
+                    condition: #condition,
+                    verify_content_type: #verify_content_type,
+                    headers: &[#(#headers),*],
+                    operation: #operation,
                     location: (::core::file!(), ::core::line!(), ::core::column!()),
                 }
             }
@@ -0,0 +1,90 @@
+use devise::{Result, FromMeta, Diagnostic};
+use devise::ext::SpanDiagnosticExt;
+use proc_macro2::TokenStream;
+
+use crate::exports::*;
+
+/// The names of the route attributes `#[route_if]` can be used with.
+const ROUTE_ATTRS: &[&str] = &["route", "get", "put", "post", "delete", "head", "patch", "options"];
+
+/// The parsed `#[route_if(..)]` attribute.
+#[derive(Debug, FromMeta)]
+pub struct RouteIf {
+    pub feature: Option<String>,
+    pub config: Option<String>,
+}
+
+impl RouteIf {
+    /// Removes the `#[route_if]` attribute, if any, from `attrs` and returns
+    /// it, parsed.
+    pub fn take_from_attrs(attrs: &mut Vec<syn::Attribute>) -> Result<Option<Self>> {
+        let route_if = RouteIf::one_from_attrs("route_if", attrs)?;
+        if let Some(route_if) = &route_if {
+            route_if.validate()?;
+        }
+
+        attrs.retain(|attr| !attr.path().segments.last().map_or(false, |s| s.ident == "route_if"));
+        Ok(route_if)
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.feature.is_none() && self.config.is_none() {
+            return Err(proc_macro2::Span::call_site()
+                .error("`#[route_if]` expects a `feature` or `config` condition")
+                .help("try `#[route_if(feature = \"beta\")]` or `#[route_if(config = \"beta\")]`"));
+        }
+
+        Ok(())
+    }
+
+    /// The `#[cfg]` attribute under which the handler is compiled, if any.
+    pub fn cfg(&self) -> Option<TokenStream> {
+        self.feature.as_ref().map(|feature| quote!(#[cfg(feature = #feature)]))
+    }
+
+    /// The `#[cfg]` attribute under which the handler is _not_ compiled, if
+    /// any.
+    pub fn not_cfg(&self) -> Option<TokenStream> {
+        self.feature.as_ref().map(|feature| quote!(#[cfg(not(feature = #feature))]))
+    }
+
+    /// An expression of type `Option<Condition>`.
+    pub fn condition_expr(this: Option<&Self>) -> TokenStream {
+        let Some(route_if) = this else {
+            return quote!(#_Option::None);
+        };
+
+        let feature = route_if.feature.as_ref()
+            .map(|f| quote!(.feature(#f, ::core::cfg!(feature = #f))));
+
+        let config = route_if.config.as_ref().map(|c| quote!(.config(#c)));
+        quote!(#_Option::Some(#_route::Condition::new() #feature #config))
+    }
+}
+
+fn route_if(args: TokenStream, input: TokenStream) -> Result<TokenStream> {
+    let mut function: syn::ItemFn = syn::parse2(input)
+        .map_err(Diagnostic::from)
+        .map_err(|d| d.help("`#[route_if]` can only be used on route handlers"))?;
+
+    RouteIf::from_meta(&syn::parse2(quote!(route_if(#args)))?)?.validate()?;
+
+    // Move the attribute below the route attribute, which will consume it.
+    let is_route = |attr: &syn::Attribute| attr.path().segments.last()
+        .map_or(false, |s| ROUTE_ATTRS.iter().any(|name| s.ident == name));
+
+    let Some(i) = function.attrs.iter().rposition(is_route) else {
+        return Err(function.sig.ident.span().error("`#[route_if]` requires a route attribute")
+            .help("add a route attribute, such as `#[get]`, below `#[route_if]`"));
+    };
+
+    function.attrs.insert(i + 1, syn::parse_quote!(#[::rocket::route_if(#args)]));
+    Ok(quote!(#function))
+}
+
+pub fn route_if_attribute(
+    args: proc_macro::TokenStream,
+    input: proc_macro::TokenStream
+) -> TokenStream {
+    route_if(args.into(), input.into()).unwrap_or_else(|diag| diag.emit_as_item_tokens())
+}
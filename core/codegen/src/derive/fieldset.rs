@@ -0,0 +1,45 @@
+use devise::*;
+use devise::ext::SpanDiagnosticExt;
+
+use quote::quote;
+use proc_macro2::TokenStream;
+
+use crate::name::Name;
+
+#[derive(Default, FromMeta)]
+struct FieldAttr {
+    name: Option<Name>,
+    always: bool,
+}
+
+pub fn derive_fieldset(input: proc_macro::TokenStream) -> TokenStream {
+    DeriveGenerator::build_for(input, quote!(impl ::rocket::serde::json::Fieldset))
+        .support(Support::Struct | Support::Lifetime)
+        .validator(ValidatorBuild::new().fields_validate(|_, fields| {
+            if fields.are_unnamed() {
+                return Err(fields.span().error("only structs with named fields are supported"));
+            }
+
+            Ok(())
+        }))
+        .inner_mapper(MapperBuild::new().try_fields_map(|_, fields| {
+            let mut names = vec![];
+            for field in fields.iter() {
+                let attr = FieldAttr::one_from_attrs("fieldset", &field.attrs)?
+                    .unwrap_or_default();
+
+                if attr.always {
+                    continue;
+                }
+
+                match (attr.name, field.ident.as_ref()) {
+                    (Some(name), _) => names.push(name),
+                    (None, Some(ident)) => names.push(Name::from(ident)),
+                    (None, None) => continue,
+                }
+            }
+
+            Ok(quote!(const FIELDS: &'static [&'static str] = &[#(#names),*];))
+        }))
+        .to_tokens()
+}
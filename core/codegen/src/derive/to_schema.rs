@@ -0,0 +1,105 @@
+use devise::*;
+use devise::ext::SpanDiagnosticExt;
+
+use quote::quote;
+use proc_macro2::TokenStream;
+use syn::ext::IdentExt;
+
+use crate::exports::*;
+use crate::name::Name;
+use crate::attribute::openapi::doc_comment;
+use crate::syn_ext::{GenericsExt as _, TypeExt as _};
+
+#[derive(Default, FromMeta)]
+struct FieldAttr {
+    name: Option<Name>,
+}
+
+pub fn derive_to_schema(input: proc_macro::TokenStream) -> TokenStream {
+    DeriveGenerator::build_for(input, quote!(impl #_openapi::ToSchema))
+        .support(Support::Struct | Support::Enum | Support::Type | Support::Lifetime)
+        .validator(ValidatorBuild::new()
+            .enum_validate(|_, data| {
+                if data.variants().count() == 0 {
+                    return Err(data.brace_token.span.join().error("empty enums are not supported"));
+                }
+
+                if let Some(variant) = data.variants().find(|v| !v.fields().is_empty()) {
+                    return Err(variant.span().error("variants with data fields are not supported"));
+                }
+
+                Ok(())
+            })
+            .struct_validate(|_, data| {
+                if data.fields().are_unnamed() {
+                    return Err(data.span().error("only structs with named fields are supported"));
+                }
+
+                Ok(())
+            })
+        )
+        .type_bound_mapper(MapperBuild::new()
+            .try_enum_map(|m, e| mapper::enum_null(m, e))
+            .try_fields_map(|_, fields| {
+                let generic_idents = fields.parent.input().generics().type_idents();
+                let bounds = fields.iter()
+                    .filter(|f| !f.ty.is_concrete(&generic_idents))
+                    .map(|f| &f.field.inner.ty)
+                    .map(|ty| quote_spanned!(ty.span() => #ty: #_openapi::ToSchema));
+
+                Ok(quote!(#(#bounds,)*))
+            })
+        )
+        .inner_mapper(MapperBuild::new()
+            .with_output(|_, output| quote! {
+                fn schema() -> #_openapi::Schema {
+                    #output
+                }
+            })
+            .enum_map(|_, data| {
+                let names = data.variants().map(|variant| {
+                    let name = variant.ident.unraw();
+                    quote!(stringify!(#name).into())
+                });
+
+                quote!(#_openapi::Schema::Enum(::std::vec![#(#names),*]))
+            })
+            .try_fields_map(|_, fields| {
+                let mut properties = vec![];
+                for field in fields.iter() {
+                    let attr = FieldAttr::one_from_attrs("schema", &field.attrs)?
+                        .unwrap_or_default();
+
+                    let name = match (attr.name, field.ident.as_ref()) {
+                        (Some(name), _) => name,
+                        (None, Some(ident)) => Name::from(ident),
+                        (None, None) => continue,
+                    };
+
+                    let (ty, docs) = (&field.ty, doc_comment(&field.attrs));
+                    properties.push(quote_spanned! { ty.span() =>
+                        #_openapi::resolve_schema!(#ty).into_property(#name, #docs)
+                    });
+                }
+
+                Ok(quote!(#_openapi::Schema::Object(::std::vec![#(#properties),*])))
+            })
+        )
+        .inner_mapper(MapperBuild::new()
+            .try_input_map(|_, input| {
+                // Generic types are described inline, since their schemas vary
+                // with their type parameters.
+                if input.generics().type_params().next().is_some() {
+                    return Ok(quote!());
+                }
+
+                let name = input.ident().unraw();
+                Ok(quote! {
+                    fn name() -> #_Option<#_Cow<'static, str>> {
+                        #_Some(#_Cow::Borrowed(stringify!(#name)))
+                    }
+                })
+            })
+        )
+        .to_tokens()
+}
@@ -72,6 +72,7 @@ define_exported_paths! {
     _error => ::rocket::error,
     _catcher => ::rocket::catcher,
     _sentinel => ::rocket::sentinel,
+    _openapi => ::rocket::openapi,
     _form => ::rocket::form::prelude,
     _http => ::rocket::http,
     _uri => ::rocket::http::uri,
@@ -383,6 +383,181 @@ pub fn suppress(args: TokenStream, input: TokenStream) -> TokenStream {
     emit!(attribute::suppress::suppress_attribute(args, input))
 }
 
+/// Conditionally compile and mount a route.
+///
+/// The attribute is applied to a route handler alongside a route attribute,
+/// above or below it, and accepts the following conditions, at least one of
+/// which must be present:
+///
+///   * `feature = "name"`
+///
+///     The handler is only compiled when the Cargo feature `name` of the
+///     crate is enabled. When it isn't, the route still exists, so
+///     [`routes!`] and [`uri!`] continue to work, but it is never mounted.
+///
+///   * `config = "key"`
+///
+///     The route is only mounted at ignition when the configuration
+///     parameter `key`, a dotted path such as `features.beta`, is `true`. A
+///     missing parameter is `false`; a parameter that isn't a boolean fails
+///     ignition.
+///
+/// When both are present, both must hold. The conditions are recorded in the
+/// route's [`Condition`]. Routes that aren't mounted are reported as
+/// `disabled` in the routes logged at ignition.
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// #[route_if(feature = "beta", config = "features.beta")]
+/// #[get("/beta")]
+/// fn beta() -> &'static str { "beta" }
+///
+/// #[get("/preview")]
+/// #[route_if(config = "features.preview")]
+/// fn preview() -> &'static str { "preview" }
+///
+/// #[launch]
+/// fn rocket() -> _ {
+///     // `beta` is mounted when built with `--features beta` and
+///     // `features.beta = true` is configured.
+///     rocket::build().mount("/", routes![beta, preview])
+/// }
+/// ```
+///
+/// [`routes!`]: macro.routes.html
+/// [`uri!`]: macro.uri.html
+/// [`Condition`]: ../rocket/route/struct.Condition.html
+#[proc_macro_attribute]
+pub fn route_if(args: TokenStream, input: TokenStream) -> TokenStream {
+    emit!(attribute::route_if::route_if_attribute(args, input))
+}
+
+/// Configure how a route handles its request body.
+///
+/// The attribute is applied to a route handler with a data guard alongside a
+/// route attribute, above or below it, and accepts the following options:
+///
+///   * `verify_content_type`
+///
+///     The leading bytes of the request body are sniffed for the magic bytes
+///     of well-known formats such as PNG, PDF, ZIP, or HTML. The request is
+///     rejected with a `415 Unsupported Media Type` error when the sniffed
+///     format doesn't match the declared `Content-Type`, when content declared
+///     as a format with magic bytes doesn't have them, or when recognized
+///     content is declared as something else entirely, such as an HTML
+///     document uploaded as `image/png` or `application/octet-stream`. Data
+///     fields of `multipart/form-data` forms are checked the same way against
+///     their own content types and fail to parse with a `415` form error.
+///
+///     Text, JSON, and other content without magic bytes is never rejected.
+///
+/// The option is recorded in the route's `verify_content_type` field.
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::fs::TempFile;
+///
+/// #[post("/avatar", data = "<file>")]
+/// #[data(verify_content_type)]
+/// async fn avatar(mut file: TempFile<'_>) -> std::io::Result<()> {
+///     file.persist_to("/tmp/avatar").await
+/// }
+///
+/// #[derive(FromForm)]
+/// struct Upload<'r> {
+///     name: &'r str,
+///     file: TempFile<'r>,
+/// }
+///
+/// #[data(verify_content_type)]
+/// #[post("/upload", data = "<upload>")]
+/// fn upload(upload: rocket::form::Form<Upload<'_>>) { /* .. */ }
+/// ```
+#[proc_macro_attribute]
+pub fn data(args: TokenStream, input: TokenStream) -> TokenStream {
+    emit!(attribute::data::data_attribute(args, input))
+}
+
+/// Set headers on responses to requests handled by a route.
+///
+/// The attribute is applied to a route handler alongside a route attribute,
+/// above or below it, and accepts a list of `name = "value"` pairs. Names are
+/// written in snake case and converted to the header name by capitalizing
+/// each word and separating words with dashes: `x_robots_tag` becomes
+/// `X-Robots-Tag`. Values must be string literals without line breaks, and
+/// each header may be declared only once.
+///
+/// The headers are recorded in the route's `headers` field and set by the
+/// [`Shield`] fairing, attached by default, on every response to a request
+/// handled by the route, including error responses, unless the response
+/// already contains the header. A declared header replaces any `Shield`
+/// policy for the same header on those responses.
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// #[get("/account")]
+/// #[headers(x_robots_tag = "noindex", cache_control = "no-store")]
+/// fn account() -> &'static str { "private" }
+///
+/// #[headers(cache_control = "public, max-age=3600")]
+/// #[get("/logo")]
+/// fn logo() -> &'static str { "logo" }
+/// ```
+///
+/// [`Shield`]: ../rocket/shield/struct.Shield.html
+#[proc_macro_attribute]
+pub fn headers(args: TokenStream, input: TokenStream) -> TokenStream {
+    emit!(attribute::headers::headers_attribute(args, input))
+}
+
+/// Adjust how a route is documented in the OpenAPI document.
+///
+/// The attribute is applied to a route handler alongside a route attribute,
+/// above or below it, and accepts the following options:
+///
+///   * `skip`
+///
+///     The route is left out of the document: its `operation` is `None`.
+///
+///   * `tag = "name"`
+///
+///     The tag `name` is added to the route's operation. The option may be
+///     repeated to add several tags.
+///
+///   * `operation_id = "id"`
+///
+///     The route's operation is identified by `id` instead of the route's
+///     name.
+///
+/// Without the attribute, routes are documented by their doc comment and
+/// signature. See the [`openapi`] module for details.
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// /// Lists all users.
+/// #[get("/users")]
+/// #[openapi(tag = "users", operation_id = "listUsers")]
+/// fn users() -> &'static str { "[]" }
+///
+/// #[openapi(skip)]
+/// #[get("/health")]
+/// fn health() { }
+/// ```
+///
+/// [`openapi`]: ../rocket/openapi/index.html
+#[proc_macro_attribute]
+pub fn openapi(args: TokenStream, input: TokenStream) -> TokenStream {
+    emit!(attribute::openapi::openapi_attribute(args, input))
+}
+
 /// Retrofits supports for `async fn` in unit tests.
 ///
 /// Simply decorate a test `async fn` with `#[async_test]` instead of `#[test]`:
@@ -1129,6 +1304,109 @@ pub fn derive_uri_display_path(input: TokenStream) -> TokenStream {
     emit!(derive::uri_display::derive_uri_display_path(input))
 }
 
+/// Derive for the [`Fieldset`] trait.
+///
+/// The [`Fieldset`] derive can only be applied to structs with named fields. It
+/// generates an implementation whose allowlist of selectable fields contains
+/// the name of every field:
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::serde::Serialize;
+/// use rocket::serde::json::Fieldset;
+///
+/// #[derive(Serialize, Fieldset)]
+/// #[serde(crate = "rocket::serde")]
+/// struct User {
+///     #[fieldset(always)]
+///     id: usize,
+///     name: String,
+///     #[serde(rename = "mail")]
+///     #[fieldset(name = "mail")]
+///     email: String,
+/// }
+///
+/// assert_eq!(User::FIELDS, ["name", "mail"]);
+/// ```
+///
+/// The derive accepts one field attribute: `fieldset`, with the following
+/// syntax:
+///
+/// ```text
+/// fieldset := 'always' | 'name' '=' '"' NAME '"'
+/// ```
+///
+/// A field with `always` is left out of the allowlist: it can't be selected
+/// and is always serialized. The value of `name` is used as the name of the
+/// field in place of the field's identifier. It should match the name with
+/// which the field is serialized, such as the value of a `serde(rename)`.
+///
+/// [`Fieldset`]: ../rocket/serde/json/trait.Fieldset.html
+#[proc_macro_derive(Fieldset, attributes(fieldset))]
+pub fn derive_fieldset(input: TokenStream) -> TokenStream {
+    emit!(derive::fieldset::derive_fieldset(input))
+}
+
+/// Derive for the [`ToSchema`] trait.
+///
+/// The [`ToSchema`] derive can be applied to structs with named fields and to
+/// enums whose variants have no fields. A struct is described by an object
+/// with a property for every field, described by the field's doc comment and
+/// required unless the field's type isn't, as with an `Option`. An enum is
+/// described by a string that is the name of one of its variants:
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::openapi::{ToSchema, Schema};
+///
+/// #[derive(FromForm, ToSchema)]
+/// struct Signup<'r> {
+///     /// The name to sign up with.
+///     name: &'r str,
+///     #[field(name = "e-mail")]
+///     #[schema(name = "e-mail")]
+///     email: Option<&'r str>,
+///     plan: Plan,
+/// }
+///
+/// #[derive(FromFormField, ToSchema)]
+/// enum Plan {
+///     Free,
+///     Pro,
+/// }
+///
+/// let Schema::Object(props) = Signup::schema() else { panic!() };
+/// assert_eq!(props[0].description.as_deref(), Some("The name to sign up with."));
+/// assert_eq!(props[1].name, "e-mail");
+/// assert!(props[0].required && !props[1].required);
+/// assert!(matches!(props[2].schema, Schema::Ref { ref name, .. } if name == "Plan"));
+/// ```
+///
+/// The derive accepts one field attribute: `schema`, with the following
+/// syntax:
+///
+/// ```text
+/// schema := 'name' '=' '"' NAME '"'
+/// ```
+///
+/// The value of `name` is used as the name of the property in place of the
+/// field's identifier. It should match the name with which the field is
+/// parsed or serialized, such as the value of a `field(name)` or
+/// `serde(rename)`.
+///
+/// Types without generic type parameters are named after their identifier via
+/// [`ToSchema::name()`] and are described in the document's `components`. The
+/// schema of a field whose type doesn't implement [`ToSchema`] is `Any`. For
+/// generic types, a `ToSchema` bound is added for every field type that isn't
+/// concrete.
+///
+/// [`ToSchema`]: ../rocket/openapi/trait.ToSchema.html
+/// [`ToSchema::name()`]: ../rocket/openapi/trait.ToSchema.html#method.name
+#[proc_macro_derive(ToSchema, attributes(schema))]
+pub fn derive_to_schema(input: TokenStream) -> TokenStream {
+    emit!(derive::to_schema::derive_to_schema(input))
+}
+
 /// Generates a `Vec` of [`Route`]s from a set of route paths.
 ///
 /// The `routes!` macro expands a list of route paths into a `Vec` of their
@@ -0,0 +1,303 @@
+use std::fmt;
+
+/// The parsed value of an [`Accept-Language`] header: the natural languages a
+/// client prefers, ordered by preference.
+///
+/// Each entry is a [`LanguageRange`], such as `en-US`, `en`, or `*`, with a
+/// quality weight between `0` and `1`, defaulting to `1`. Entries are ordered
+/// by descending weight; entries with equal weights keep the order in which the
+/// client listed them. Malformed entries are ignored.
+///
+/// A request's `AcceptLanguage` can be retrieved via
+/// [`Request::accept_language()`] or directly as a [request guard].
+///
+/// # Negotiation
+///
+/// [`AcceptLanguage::negotiate()`] selects the best of a set of available
+/// locales. Use [`AcceptLanguage::iter()`] and [`LanguageRange::matches()`] to
+/// implement other strategies.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::http::AcceptLanguage;
+///
+/// let accept = AcceptLanguage::parse("fr-CH, fr;q=0.9, en;q=0.8, *;q=0.5");
+/// assert_eq!(accept.preferred().map(|r| r.tag()), Some("fr-CH"));
+/// assert_eq!(accept.iter().count(), 4);
+///
+/// let available = ["en", "fr"];
+/// assert_eq!(accept.negotiate(&available), Some(&"fr"));
+/// ```
+///
+/// [`Accept-Language`]: https://httpwg.org/specs/rfc9110.html#field.accept-language
+/// [`Request::accept_language()`]: ../../rocket/request/struct.Request.html#method.accept_language
+/// [request guard]: ../../rocket/request/trait.FromRequest.html#provided-implementations
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AcceptLanguage<'a> {
+    ranges: Vec<LanguageRange<'a>>,
+}
+
+/// A language range with a quality weight in an [`AcceptLanguage`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct LanguageRange<'a> {
+    tag: &'a str,
+    weight: f32,
+}
+
+impl<'a> LanguageRange<'a> {
+    /// Returns the language range as written by the client, such as `en-US`
+    /// or `*`.
+    pub fn tag(&self) -> &'a str {
+        self.tag
+    }
+
+    /// Returns the quality weight, between `0` and `1`, of this range.
+    pub fn weight(&self) -> f32 {
+        self.weight
+    }
+
+    /// Returns `true` if this is the wildcard range, `*`.
+    pub fn is_wildcard(&self) -> bool {
+        self.tag == "*"
+    }
+
+    /// Returns `true` if `locale` is matched by this range per the "basic
+    /// filtering" scheme of [RFC 4647]: the range is `*`, is equal to
+    /// `locale`, or is a prefix of `locale` followed by a `-`. Comparisons are
+    /// case-insensitive.
+    ///
+    /// [RFC 4647]: https://www.rfc-editor.org/rfc/rfc4647#section-3.3.1
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::AcceptLanguage;
+    ///
+    /// let accept = AcceptLanguage::parse("de");
+    /// let range = accept.preferred().unwrap();
+    /// assert!(range.matches("de"));
+    /// assert!(range.matches("de-CH"));
+    /// assert!(range.matches("DE-ch"));
+    /// assert!(!range.matches("den"));
+    /// assert!(!range.matches("en"));
+    /// ```
+    pub fn matches(&self, locale: &str) -> bool {
+        if self.is_wildcard() {
+            return true;
+        }
+
+        match locale.get(..self.tag.len()) {
+            Some(prefix) if prefix.eq_ignore_ascii_case(self.tag) => {
+                matches!(locale.as_bytes().get(self.tag.len()), None | Some(b'-'))
+            }
+            _ => false,
+        }
+    }
+
+    fn parse(entry: &'a str) -> Option<Self> {
+        let mut parts = entry.split(';').map(|p| p.trim());
+        let tag = parts.next().filter(|tag| is_language_range(tag))?;
+        let mut weight = 1.0;
+        for param in parts {
+            let (name, value) = param.split_once('=')?;
+            if name.trim().eq_ignore_ascii_case("q") {
+                weight = value.trim().parse::<f32>().ok().filter(|q| (0.0..=1.0).contains(q))?;
+            }
+        }
+
+        Some(LanguageRange { tag, weight })
+    }
+}
+
+/// Whether `tag` is a valid `language-range`: `*` or one or more `-`
+/// separated subtags of 1 to 8 alphanumerics, the first alphabetic.
+fn is_language_range(tag: &str) -> bool {
+    if tag == "*" {
+        return true;
+    }
+
+    tag.split('-').enumerate().all(|(i, subtag)| {
+        (1..=8).contains(&subtag.len()) && subtag.bytes().all(|b| match i {
+            0 => b.is_ascii_alphabetic(),
+            _ => b.is_ascii_alphanumeric() || b == b'*',
+        })
+    })
+}
+
+impl<'a> AcceptLanguage<'a> {
+    /// Parses the value of an `Accept-Language` header. Malformed entries are
+    /// ignored.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::AcceptLanguage;
+    ///
+    /// let accept = AcceptLanguage::parse("en;q=0.5, de, !!, fr;q=2");
+    /// let tags: Vec<_> = accept.iter().map(|r| r.tag()).collect();
+    /// assert_eq!(tags, ["de", "en"]);
+    /// ```
+    pub fn parse(value: &'a str) -> Self {
+        let mut ranges: Vec<_> = value.split(',')
+            .map(|entry| entry.trim())
+            .filter(|entry| !entry.is_empty())
+            .filter_map(LanguageRange::parse)
+            .collect();
+
+        ranges.sort_by(|a, b| b.weight.total_cmp(&a.weight));
+        AcceptLanguage { ranges }
+    }
+
+    /// Returns the client's most preferred language range, if any. A range
+    /// with a weight of `0`, meaning "not acceptable", is never preferred.
+    pub fn preferred(&self) -> Option<&LanguageRange<'a>> {
+        self.ranges.first().filter(|range| range.weight > 0.0)
+    }
+
+    /// Returns an iterator over all language ranges, including those with a
+    /// weight of `0`, in order of preference.
+    pub fn iter(&self) -> impl Iterator<Item = &LanguageRange<'a>> + '_ {
+        self.ranges.iter()
+    }
+
+    /// Returns `true` if the client expressed no language preferences.
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Returns the entry of `available` that best matches the client's
+    /// preferences, or `None` if none is acceptable.
+    ///
+    /// Ranges are considered in order of preference. For each range, the first
+    /// locale in `available` it [matches](LanguageRange::matches()) is
+    /// selected. If there is none, the range is progressively truncated, as in
+    /// the "lookup" scheme of [RFC 4647], so that `en-US` selects `en` if only
+    /// the latter is available. The wildcard range selects the first locale
+    /// not otherwise matched by a range. Locales matched by a range with a
+    /// weight of `0` are never selected.
+    ///
+    /// If the client expressed no preferences, `None` is returned so that the
+    /// application can apply its own default.
+    ///
+    /// [RFC 4647]: https://www.rfc-editor.org/rfc/rfc4647#section-3.4
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::AcceptLanguage;
+    ///
+    /// let available = ["en", "de-CH", "fr"];
+    ///
+    /// let accept = AcceptLanguage::parse("de, en;q=0.5");
+    /// assert_eq!(accept.negotiate(&available), Some(&"de-CH"));
+    ///
+    /// let accept = AcceptLanguage::parse("en-GB, fr;q=0.9");
+    /// assert_eq!(accept.negotiate(&available), Some(&"en"));
+    ///
+    /// let accept = AcceptLanguage::parse("*, en;q=0");
+    /// assert_eq!(accept.negotiate(&available), Some(&"de-CH"));
+    ///
+    /// let accept = AcceptLanguage::parse("ja");
+    /// assert_eq!(accept.negotiate(&available), None);
+    /// ```
+    pub fn negotiate<'l, S: AsRef<str>>(&self, available: &'l [S]) -> Option<&'l S> {
+        let excluded = &|locale: &str| self.ranges.iter()
+            .any(|r| r.weight == 0.0 && !r.is_wildcard() && r.matches(locale));
+
+        let explicit = |locale: &str| self.ranges.iter()
+            .any(|r| !r.is_wildcard() && r.matches(locale));
+
+        let candidates = || available.iter().filter(move |l| !excluded(l.as_ref()));
+        for range in self.ranges.iter().filter(|r| r.weight > 0.0) {
+            if range.is_wildcard() {
+                if let Some(locale) = candidates().find(|l| !explicit(l.as_ref())) {
+                    return Some(locale);
+                }
+
+                continue;
+            }
+
+            if let Some(locale) = candidates().find(|l| range.matches(l.as_ref())) {
+                return Some(locale);
+            }
+
+            let mut tag = range.tag;
+            while let Some(i) = tag.rfind('-') {
+                tag = &tag[..i];
+                // Per RFC 4647, a trailing single-character subtag is dropped too.
+                if tag.len() >= 2 && tag.as_bytes()[tag.len() - 2] == b'-' {
+                    continue;
+                }
+
+                if let Some(locale) = candidates().find(|l| l.as_ref().eq_ignore_ascii_case(tag)) {
+                    return Some(locale);
+                }
+            }
+        }
+
+        None
+    }
+}
+
+impl<'a> From<&'a str> for AcceptLanguage<'a> {
+    fn from(value: &'a str) -> Self {
+        AcceptLanguage::parse(value)
+    }
+}
+
+impl fmt::Display for LanguageRange<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.tag)?;
+        if self.weight != 1.0 {
+            write!(f, ";q={}", self.weight)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for AcceptLanguage<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, range) in self.ranges.iter().enumerate() {
+            if i != 0 { f.write_str(", ")?; }
+            range.fmt(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::AcceptLanguage;
+
+    #[test]
+    fn test_parse_order() {
+        let accept = AcceptLanguage::parse("en;q=0.8, fr-CH, fr;q=0.9 , de;q=0.8, x;q=1.5");
+        let tags: Vec<_> = accept.iter().map(|r| r.tag()).collect();
+        assert_eq!(tags, ["fr-CH", "fr", "en", "de"]);
+        assert_eq!(accept.to_string(), "fr-CH, fr;q=0.9, en;q=0.8, de;q=0.8");
+
+        assert!(AcceptLanguage::parse("").is_empty());
+        assert!(AcceptLanguage::parse("en-, -en, 123, toolonglang").is_empty());
+        assert!(AcceptLanguage::parse("*;q=0").preferred().is_none());
+    }
+
+    #[test]
+    fn test_negotiate() {
+        let available = ["en-US", "en-GB", "zh-Hant"];
+        let negotiate = |header| AcceptLanguage::parse(header).negotiate(&available).copied();
+
+        assert_eq!(negotiate("en"), Some("en-US"));
+        assert_eq!(negotiate("en-gb"), Some("en-GB"));
+        assert_eq!(negotiate("en-AU, en-GB;q=0.5"), Some("en-GB"));
+        assert_eq!(negotiate("zh-Hant-TW"), Some("zh-Hant"));
+        assert_eq!(negotiate("en-US-x-twain"), Some("en-US"));
+        assert_eq!(negotiate("en, en-US;q=0"), Some("en-GB"));
+        assert_eq!(negotiate("*, en-US;q=0.1"), Some("en-GB"));
+        assert_eq!(negotiate("*, en;q=0"), Some("zh-Hant"));
+        assert_eq!(negotiate("de"), None);
+        assert_eq!(negotiate(""), None);
+    }
+}
@@ -0,0 +1,239 @@
+use std::borrow::Cow;
+use std::fmt::{self, Write};
+
+use crate::header::Header;
+
+/// A typed `Content-Disposition` response header.
+///
+/// A `ContentDisposition` tells a client whether to display a response
+/// [`inline`](ContentDisposition::inline()) or to save it as an
+/// [`attachment`](ContentDisposition::attachment()) and, optionally, which
+/// [`filename`](ContentDisposition::with_filename()) to suggest.
+///
+/// File names are rendered so that they survive any client: a quoted ASCII
+/// `filename` in which non-ASCII, control, `"`, and `\` characters are replaced
+/// with `_` is always emitted, and if it differs from the actual name, the
+/// exact name is additionally emitted as an [RFC 5987] encoded UTF-8
+/// `filename*`, which clients that support it prefer.
+///
+/// [RFC 5987]: https://datatracker.ietf.org/doc/html/rfc5987
+///
+/// # Header
+///
+/// `ContentDisposition` implements `Into<Header>`. As such, it can be used in
+/// any context where an `Into<Header>` is expected:
+///
+/// ```rust
+/// # extern crate rocket;
+/// use rocket::http::ContentDisposition;
+/// use rocket::response::Response;
+///
+/// let disposition = ContentDisposition::attachment().with_filename("résumé.pdf");
+/// let response = Response::build().header(disposition).finalize();
+/// assert_eq!(response.headers().get_one("Content-Disposition"),
+///     Some("attachment; filename=\"r_sum_.pdf\"; filename*=UTF-8''r%C3%A9sum%C3%A9.pdf"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ContentDisposition {
+    inline: bool,
+    filename: Option<Cow<'static, str>>,
+}
+
+impl ContentDisposition {
+    /// Returns an `inline` disposition: the client should display the response.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # extern crate rocket;
+    /// use rocket::http::ContentDisposition;
+    ///
+    /// let disposition = ContentDisposition::inline();
+    /// assert_eq!(disposition.to_string(), "inline");
+    /// ```
+    pub fn inline() -> Self {
+        ContentDisposition { inline: true, filename: None }
+    }
+
+    /// Returns an `attachment` disposition: the client should save the
+    /// response.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # extern crate rocket;
+    /// use rocket::http::ContentDisposition;
+    ///
+    /// let disposition = ContentDisposition::attachment();
+    /// assert_eq!(disposition.to_string(), "attachment");
+    /// ```
+    pub fn attachment() -> Self {
+        ContentDisposition { inline: false, filename: None }
+    }
+
+    /// Sets the file name suggested to the client to `name`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # extern crate rocket;
+    /// use rocket::http::ContentDisposition;
+    ///
+    /// let disposition = ContentDisposition::attachment().with_filename("report.csv");
+    /// assert_eq!(disposition.to_string(), "attachment; filename=\"report.csv\"");
+    ///
+    /// let disposition = ContentDisposition::inline().with_filename("€ rates.csv");
+    /// assert_eq!(disposition.to_string(),
+    ///     "inline; filename=\"_ rates.csv\"; filename*=UTF-8''%E2%82%AC%20rates.csv");
+    /// ```
+    pub fn with_filename<N: Into<Cow<'static, str>>>(mut self, name: N) -> Self {
+        self.filename = Some(name.into());
+        self
+    }
+
+    /// Returns `true` if `self` is an `inline` disposition.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # extern crate rocket;
+    /// use rocket::http::ContentDisposition;
+    ///
+    /// assert!(ContentDisposition::inline().is_inline());
+    /// assert!(!ContentDisposition::attachment().is_inline());
+    /// ```
+    pub fn is_inline(&self) -> bool {
+        self.inline
+    }
+
+    /// Returns `true` if `self` is an `attachment` disposition.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # extern crate rocket;
+    /// use rocket::http::ContentDisposition;
+    ///
+    /// assert!(ContentDisposition::attachment().is_attachment());
+    /// assert!(!ContentDisposition::inline().is_attachment());
+    /// ```
+    pub fn is_attachment(&self) -> bool {
+        !self.inline
+    }
+
+    /// Returns the suggested file name, if one is set.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # extern crate rocket;
+    /// use rocket::http::ContentDisposition;
+    ///
+    /// let disposition = ContentDisposition::attachment();
+    /// assert_eq!(disposition.filename(), None);
+    ///
+    /// let disposition = disposition.with_filename("ü.txt");
+    /// assert_eq!(disposition.filename(), Some("ü.txt"));
+    /// ```
+    pub fn filename(&self) -> Option<&str> {
+        self.filename.as_deref()
+    }
+}
+
+/// Returns `name` with every character that can't appear verbatim in a quoted
+/// ASCII `filename` replaced with `_`.
+fn ascii_fallback(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            '"' | '\\' => '_',
+            c if c.is_ascii() && !c.is_ascii_control() => c,
+            _ => '_',
+        })
+        .collect()
+}
+
+/// Writes `name` as an RFC 5987 `ext-value` in UTF-8.
+fn write_ext_value(f: &mut fmt::Formatter<'_>, name: &str) -> fmt::Result {
+    f.write_str("UTF-8''")?;
+    for byte in name.bytes() {
+        match byte {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' => f.write_char(byte as char)?,
+            b'!' | b'#' | b'$' | b'&' | b'+' | b'-' | b'.' => f.write_char(byte as char)?,
+            b'^' | b'_' | b'`' | b'|' | b'~' => f.write_char(byte as char)?,
+            _ => write!(f, "%{:02X}", byte)?,
+        }
+    }
+
+    Ok(())
+}
+
+impl fmt::Display for ContentDisposition {
+    /// Formats the header value.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # extern crate rocket;
+    /// use rocket::http::ContentDisposition;
+    ///
+    /// let disposition = ContentDisposition::attachment().with_filename("a \"b\".txt");
+    /// assert_eq!(disposition.to_string(),
+    ///     "attachment; filename=\"a _b_.txt\"; filename*=UTF-8''a%20%22b%22.txt");
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(if self.inline { "inline" } else { "attachment" })?;
+        if let Some(name) = &self.filename {
+            let ascii = ascii_fallback(name);
+            write!(f, "; filename=\"{}\"", ascii)?;
+            if ascii != *name {
+                f.write_str("; filename*=")?;
+                write_ext_value(f, name)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Creates a new `Header` with name `Content-Disposition` and the value set to
+/// the HTTP rendering of this disposition.
+impl From<ContentDisposition> for Header<'static> {
+    #[inline(always)]
+    fn from(disposition: ContentDisposition) -> Self {
+        Header::new("Content-Disposition", disposition.to_string())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ContentDisposition;
+
+    fn attachment(name: &'static str) -> String {
+        ContentDisposition::attachment().with_filename(name).to_string()
+    }
+
+    #[test]
+    fn ascii_filenames() {
+        assert_eq!(attachment("files.zip"), "attachment; filename=\"files.zip\"");
+        assert_eq!(attachment("a b%20.zip"), "attachment; filename=\"a b%20.zip\"");
+        assert_eq!(attachment(""), "attachment; filename=\"\"");
+        assert_eq!(attachment("~^_`|!#$&+-.'"),
+            "attachment; filename=\"~^_`|!#$&+-.'\"");
+    }
+
+    #[test]
+    fn escaped_filenames() {
+        assert_eq!(attachment("a\"b.zip"),
+            "attachment; filename=\"a_b.zip\"; filename*=UTF-8''a%22b.zip");
+        assert_eq!(attachment("a\\b\r\n.zip"),
+            "attachment; filename=\"a_b__.zip\"; filename*=UTF-8''a%5Cb%0D%0A.zip");
+    }
+
+    #[test]
+    fn utf8_filenames() {
+        assert_eq!(attachment("ü.zip"),
+            "attachment; filename=\"_.zip\"; filename*=UTF-8''%C3%BC.zip");
+        assert_eq!(attachment("日本.txt"),
+            "attachment; filename=\"__.txt\"; filename*=UTF-8''%E6%97%A5%E6%9C%AC.txt");
+    }
+}
@@ -2,12 +2,16 @@
 mod known_media_types;
 mod media_type;
 mod content_type;
+mod content_disposition;
 mod accept;
+mod accept_language;
 mod header;
 mod proxy_proto;
 
 pub use self::content_type::ContentType;
+pub use self::content_disposition::ContentDisposition;
 pub use self::accept::{Accept, QMediaType};
+pub use self::accept_language::{AcceptLanguage, LanguageRange};
 pub use self::media_type::MediaType;
 pub use self::header::{Header, HeaderMap};
 pub use self::proxy_proto::ProxyProto;
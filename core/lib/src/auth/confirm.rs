@@ -0,0 +1,351 @@
+//! Confirmation tokens for dangerous, administrative actions.
+//!
+//! Destructive admin routes, like deleting a user or purging a cache, are easy
+//! to trigger by accident: a mistyped `curl` invocation, a retried script, or a
+//! cross-site request riding on an admin's session. This module requires such
+//! requests to carry a short-lived confirmation token issued by a prior
+//! request, typically a `GET` that describes what is about to happen:
+//!
+//!   * [`Action`] names an action and the lifetime of its tokens.
+//!   * [`Confirm::token()`] issues a token for an action on one target path.
+//!   * [`Confirm`] guards the dangerous route, succeeding only when the request
+//!     carries a valid, unexpired token, in the [`HEADER`] header, for the
+//!     action and the request's path.
+//!
+//! Tokens are signed with the application's configured [`SecretKey`], so they
+//! can't be forged, and they're bound to the action and to the path they were
+//! issued for, so a token to delete one user can't delete another. Because
+//! browsers only send custom headers on same-origin requests, a cross-site
+//! form or link can't supply one.
+//!
+//! Every confirmed and refused request is logged with the name `confirm`,
+//! along with the action, path, and token nonce, providing an audit trail.
+//!
+//! This module is only available when the `confirm` feature is enabled, which
+//! also enables the `secrets` feature.
+//!
+//! # Example
+//!
+//! ```rust
+//! # #[macro_use] extern crate rocket;
+//! use rocket::auth::confirm::{Action, Confirm};
+//! use rocket::config::SecretKey;
+//!
+//! struct DeleteUser;
+//!
+//! impl Action for DeleteUser {
+//!     const NAME: &'static str = "delete-user";
+//! }
+//!
+//! #[get("/users/<id>/delete")]
+//! fn prepare(key: &SecretKey, id: u64) -> String {
+//!     Confirm::<DeleteUser>::token(key, &format!("/admin/users/{id}"))
+//! }
+//!
+//! #[delete("/users/<id>")]
+//! fn delete(confirm: Confirm<DeleteUser>, id: u64) -> String {
+//!     format!("deleted user {id} (confirmation {})", confirm.nonce())
+//! }
+//!
+//! #[launch]
+//! fn rocket() -> _ {
+//!     rocket::build().mount("/admin", routes![prepare, delete])
+//! }
+//! ```
+//!
+//! A client first requests `GET /admin/users/7/delete`, then sends the
+//! returned token with `DELETE /admin/users/7` in the `X-Confirmation-Token`
+//! header.
+//!
+//! # Replay
+//!
+//! A token remains valid until it expires. To ensure each token confirms at
+//! most one request, record [`Confirm::nonce()`] when the action completes
+//! and refuse tokens whose nonce was already used.
+
+use std::fmt;
+use std::marker::PhantomData;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use rand::RngCore;
+use ring::hmac;
+
+use crate::config::SecretKey;
+use crate::http::Status;
+use crate::request::{FromRequest, Outcome, Request};
+
+/// The name of the header carrying a confirmation token.
+pub const HEADER: &str = "X-Confirmation-Token";
+
+/// The number of seconds a token's issue time may be in the future, allowing
+/// for clock differences between instances.
+const CLOCK_SKEW: u64 = 30;
+
+/// A dangerous action that requires confirmation.
+///
+/// The [`NAME`](Action::NAME) is included in every token so that a token
+/// issued for one action can't confirm another. It should be unique among an
+/// application's actions and is included in audit logs.
+///
+/// # Example
+///
+/// ```rust
+/// use std::time::Duration;
+/// use rocket::auth::confirm::Action;
+///
+/// struct PurgeCache;
+///
+/// impl Action for PurgeCache {
+///     const NAME: &'static str = "purge-cache";
+///     const TTL: Duration = Duration::from_secs(60);
+/// }
+/// ```
+pub trait Action: Send + Sync + 'static {
+    /// The name of the action.
+    const NAME: &'static str;
+
+    /// How long a token remains valid after it is issued. Defaults to five
+    /// minutes.
+    const TTL: Duration = Duration::from_secs(5 * 60);
+}
+
+/// A request guard for requests confirmed with a token for the action `T`.
+///
+/// The guard succeeds when the request's [`HEADER`] header contains a token
+/// issued by [`Confirm::token()`] for `T` and the request's path that hasn't
+/// yet expired. Otherwise it fails with an [`Error`]: a status of `428
+/// Precondition Required` when no token was sent and `403 Forbidden` when
+/// the token is malformed, invalid, or expired.
+///
+/// See the [module-level docs](self) for an example.
+pub struct Confirm<T: Action> {
+    issued_at: u64,
+    nonce: String,
+    _action: PhantomData<fn() -> T>,
+}
+
+/// An error validating a confirmation token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// The request didn't include a token.
+    Missing,
+    /// The token couldn't be parsed.
+    Malformed,
+    /// The token wasn't issued for this action and path, or with this
+    /// application's secret key.
+    Invalid,
+    /// The token has expired.
+    Expired,
+}
+
+impl<T: Action> Confirm<T> {
+    /// Issues a token confirming the action `T` for requests to `path`.
+    ///
+    /// `path` must be the full, percent-encoded path of the confirmed request,
+    /// including any mount point, but without a query.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::auth::confirm::{Action, Confirm};
+    /// use rocket::config::SecretKey;
+    ///
+    /// struct DeleteUser;
+    ///
+    /// impl Action for DeleteUser {
+    ///     const NAME: &'static str = "delete-user";
+    /// }
+    ///
+    /// let key = SecretKey::generate().unwrap();
+    /// let token = Confirm::<DeleteUser>::token(&key, "/admin/users/7");
+    /// assert_eq!(token.split('.').count(), 3);
+    /// ```
+    pub fn token(key: &SecretKey, path: &str) -> String {
+        let mut nonce = [0u8; 12];
+        rand::rngs::OsRng.fill_bytes(&mut nonce);
+        let (issued_at, nonce) = (unix_now(), hex(&nonce));
+        let tag = sign::<T>(key, path, issued_at, &nonce);
+        format!("{issued_at}.{nonce}.{}", hex(tag.as_ref()))
+    }
+
+    /// Returns the time at which the token was issued.
+    pub fn issued_at(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(self.issued_at)
+    }
+
+    /// Returns the time at which the token expires.
+    pub fn expires_at(&self) -> SystemTime {
+        self.issued_at() + T::TTL
+    }
+
+    /// Returns the token's random nonce, which uniquely identifies it.
+    pub fn nonce(&self) -> &str {
+        &self.nonce
+    }
+
+    fn verify(key: &SecretKey, path: &str, token: &str) -> Result<Self, Error> {
+        let mut parts = token.trim().splitn(3, '.');
+        let (issued_at, nonce, tag) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(issued_at), Some(nonce), Some(tag)) => (issued_at, nonce, tag),
+            _ => return Err(Error::Malformed),
+        };
+
+        let issued_at: u64 = issued_at.parse().map_err(|_| Error::Malformed)?;
+        let is_hex = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_hexdigit());
+        if !is_hex(nonce) || !is_hex(tag) {
+            return Err(Error::Malformed);
+        }
+
+        let expected = hex(sign::<T>(key, path, issued_at, nonce).as_ref());
+        if !constant_time_eq(&expected, &tag.to_ascii_lowercase()) {
+            return Err(Error::Invalid);
+        }
+
+        let now = unix_now();
+        if issued_at > now.saturating_add(CLOCK_SKEW) {
+            return Err(Error::Invalid);
+        }
+
+        if now >= issued_at.saturating_add(T::TTL.as_secs()) {
+            return Err(Error::Expired);
+        }
+
+        Ok(Confirm { issued_at, nonce: nonce.into(), _action: PhantomData })
+    }
+}
+
+#[crate::async_trait]
+impl<'r, T: Action> FromRequest<'r> for Confirm<T> {
+    type Error = Error;
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let path = req.uri().path();
+        let Some(token) = req.headers().get_one(HEADER) else {
+            warn!(name: "confirm", action = T::NAME, %path, "unconfirmed request refused");
+            return Outcome::Error((Status::PreconditionRequired, Error::Missing));
+        };
+
+        let key = &req.rocket().config().secret_key;
+        match Confirm::<T>::verify(key, path.as_str(), token) {
+            Ok(confirm) => {
+                info!(name: "confirm", action = T::NAME, %path, nonce = confirm.nonce(),
+                    "request confirmed");
+
+                Outcome::Success(confirm)
+            }
+            Err(e) => {
+                warn!(name: "confirm", action = T::NAME, %path, "{e}: request refused");
+                Outcome::Error((Status::Forbidden, e))
+            }
+        }
+    }
+}
+
+impl<T: Action> fmt::Debug for Confirm<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Confirm")
+            .field("action", &T::NAME)
+            .field("issued_at", &self.issued_at)
+            .field("nonce", &self.nonce)
+            .finish()
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Missing => "missing confirmation token".fmt(f),
+            Error::Malformed => "malformed confirmation token".fmt(f),
+            Error::Invalid => "invalid confirmation token".fmt(f),
+            Error::Expired => "expired confirmation token".fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Computes the tag of a token for the action `T` on `path`.
+///
+/// The key is domain-separated from the secret key's other uses, and every
+/// field is length-prefixed so that no two distinct inputs share a message.
+fn sign<T: Action>(key: &SecretKey, path: &str, issued_at: u64, nonce: &str) -> hmac::Tag {
+    let derived = hmac::sign(&hmac::Key::new(hmac::HMAC_SHA256, key.key.master()), b"confirm");
+    let key = hmac::Key::new(hmac::HMAC_SHA256, derived.as_ref());
+    let mut context = hmac::Context::with_key(&key);
+    for field in [T::NAME, path, &issued_at.to_string(), nonce] {
+        context.update(&(field.len() as u64).to_be_bytes());
+        context.update(field.as_bytes());
+    }
+
+    context.sign()
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs())
+}
+
+fn hex(bytes: &[u8]) -> String {
+    const DIGITS: &[u8] = b"0123456789abcdef";
+    bytes.iter()
+        .flat_map(|b| [DIGITS[(b >> 4) as usize] as char, DIGITS[(b & 0xf) as usize] as char])
+        .collect()
+}
+
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len() && a.bytes().zip(b.bytes()).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct DeleteUser;
+
+    impl Action for DeleteUser {
+        const NAME: &'static str = "delete-user";
+    }
+
+    struct PurgeCache;
+
+    impl Action for PurgeCache {
+        const NAME: &'static str = "purge-cache";
+        const TTL: Duration = Duration::ZERO;
+    }
+
+    #[test]
+    fn tokens_verify_for_their_action_and_path() {
+        let key = SecretKey::generate().unwrap();
+        let token = Confirm::<DeleteUser>::token(&key, "/users/7");
+        let confirm = Confirm::<DeleteUser>::verify(&key, "/users/7", &token).unwrap();
+        assert_eq!(confirm.nonce(), token.split('.').nth(1).unwrap());
+        assert_eq!(confirm.expires_at(), confirm.issued_at() + DeleteUser::TTL);
+
+        let verify = Confirm::<DeleteUser>::verify;
+        assert_eq!(verify(&key, "/users/8", &token).unwrap_err(), Error::Invalid);
+
+        let other_key = SecretKey::generate().unwrap();
+        assert_eq!(verify(&other_key, "/users/7", &token).unwrap_err(), Error::Invalid);
+
+        let token = Confirm::<PurgeCache>::token(&key, "/users/7");
+        assert_eq!(verify(&key, "/users/7", &token).unwrap_err(), Error::Invalid);
+    }
+
+    #[test]
+    fn expired_and_malformed_tokens_are_refused() {
+        let key = SecretKey::generate().unwrap();
+        let token = Confirm::<PurgeCache>::token(&key, "/cache");
+        let verify = Confirm::<PurgeCache>::verify;
+        assert_eq!(verify(&key, "/cache", &token).unwrap_err(), Error::Expired);
+
+        for token in ["", "1.2", "x.ab.cd", "1..cd", "1.ab.", "1.ab.zz", "1.a-b.cd"] {
+            assert_eq!(verify(&key, "/cache", token).unwrap_err(), Error::Malformed);
+        }
+
+        // Tokens issued in the future are forged, even with a valid tag.
+        let nonce = "00";
+        let tag = sign::<DeleteUser>(&key, "/users/7", u64::MAX, nonce);
+        let token = format!("{}.{nonce}.{}", u64::MAX, hex(tag.as_ref()));
+        let verify = Confirm::<DeleteUser>::verify;
+        assert_eq!(verify(&key, "/users/7", &token).unwrap_err(), Error::Invalid);
+    }
+}
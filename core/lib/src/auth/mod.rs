@@ -0,0 +1,28 @@
+//! Authentication helpers.
+//!
+//! This module provides:
+//!
+//!   * [`totp`]: two-factor authentication with time-based one-time
+//!     passwords, when the `totp` feature is enabled.
+//!   * [`passkey`]: passwordless authentication with WebAuthn passkeys, when
+//!     the `passkey` feature is enabled.
+//!   * [`saml`]: single sign-on through a SAML 2.0 identity provider, when the
+//!     `saml` feature is enabled.
+//!   * [`confirm`]: confirmation tokens for dangerous administrative actions,
+//!     when the `confirm` feature is enabled.
+
+#[cfg(feature = "totp")]
+#[cfg_attr(nightly, doc(cfg(feature = "totp")))]
+pub mod totp;
+
+#[cfg(feature = "passkey")]
+#[cfg_attr(nightly, doc(cfg(feature = "passkey")))]
+pub mod passkey;
+
+#[cfg(feature = "saml")]
+#[cfg_attr(nightly, doc(cfg(feature = "saml")))]
+pub mod saml;
+
+#[cfg(feature = "confirm")]
+#[cfg_attr(nightly, doc(cfg(feature = "confirm")))]
+pub mod confirm;
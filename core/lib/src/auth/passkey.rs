@@ -0,0 +1,1055 @@
+//! Passwordless authentication with WebAuthn passkeys.
+//!
+//! This module implements the server side of the [WebAuthn] registration and
+//! authentication ceremonies, with which users sign in using a passkey stored
+//! by their device, password manager, or security key:
+//!
+//!   * [`Passkeys`] is mounted to add routes that issue challenges and verify
+//!     the responses of authenticators.
+//!   * [`CredentialStore`], implemented by the application, stores users'
+//!     [`Credential`]s.
+//!   * [`PasskeyUser`] is a request guard for users signed in with a passkey.
+//!
+//! The state of a ceremony in progress and the signed-in user are kept in
+//! private cookies. This module is only available when the `passkey` feature
+//! is enabled, which also enables the `secrets` and `json` features.
+//!
+//! # Routes
+//!
+//! When mounted at `/passkey`, [`Passkeys`] handles the following routes, all
+//! of which accept and return JSON:
+//!
+//! | Route                              | Request                     | Response             |
+//! |------------------------------------|-----------------------------|----------------------|
+//! | `POST /passkey/register/challenge` | `{ "username": "alice" }`   | creation options     |
+//! | `POST /passkey/register/verify`    | the new credential          | `{ "username": .. }` |
+//! | `POST /passkey/login/challenge`    | `{ "username": "alice" }`   | request options      |
+//! | `POST /passkey/login/verify`       | the assertion               | `{ "username": .. }` |
+//!
+//! The options returned by the challenge routes are in the format accepted by
+//! `PublicKeyCredential.parseCreationOptionsFromJSON()` and
+//! `PublicKeyCredential.parseRequestOptionsFromJSON()`, and the verify routes
+//! accept the result of `PublicKeyCredential.toJSON()`:
+//!
+//! ```js
+//! let res = await fetch("/passkey/register/challenge", {
+//!   method: "POST",
+//!   body: JSON.stringify({ username: "alice" }),
+//! });
+//!
+//! let options = PublicKeyCredential.parseCreationOptionsFromJSON(await res.json());
+//! let credential = await navigator.credentials.create({ publicKey: options });
+//! await fetch("/passkey/register/verify", {
+//!   method: "POST",
+//!   body: JSON.stringify(credential.toJSON()),
+//! });
+//! ```
+//!
+//! Registration adds a passkey to the signed-in user if there is one.
+//! Otherwise it creates a new user with the requested username, which must not
+//! already be in use, and responds with `409 Conflict` if it is. The username
+//! in a login challenge is optional: without it, or if there's no such user,
+//! the authenticator offers any discoverable passkey for the site. Both verify
+//! routes sign the user in on success.
+//!
+//! # Example
+//!
+//! ```rust
+//! # #[macro_use] extern crate rocket;
+//! use std::io;
+//! use std::sync::Mutex;
+//!
+//! use rocket::auth::passkey::{Passkeys, PasskeyUser, CredentialStore, Credential};
+//!
+//! #[derive(Default)]
+//! struct Store(Mutex<Vec<Credential>>);
+//!
+//! #[rocket::async_trait]
+//! impl CredentialStore for Store {
+//!     async fn user_handle(&self, username: &str) -> Option<Vec<u8>> {
+//!         let credentials = self.0.lock().unwrap();
+//!         credentials.iter()
+//!             .find(|c| c.username == username)
+//!             .map(|c| c.user_handle.clone())
+//!     }
+//!
+//!     async fn credentials(&self, user_handle: &[u8]) -> Vec<Credential> {
+//!         let credentials = self.0.lock().unwrap();
+//!         credentials.iter().filter(|c| c.user_handle == user_handle).cloned().collect()
+//!     }
+//!
+//!     async fn credential(&self, id: &[u8]) -> Option<Credential> {
+//!         self.0.lock().unwrap().iter().find(|c| c.id == id).cloned()
+//!     }
+//!
+//!     async fn register(&self, credential: Credential) -> io::Result<()> {
+//!         self.0.lock().unwrap().push(credential);
+//!         Ok(())
+//!     }
+//!
+//!     async fn update(&self, credential: &Credential) -> io::Result<()> {
+//!         let mut credentials = self.0.lock().unwrap();
+//!         if let Some(c) = credentials.iter_mut().find(|c| c.id == credential.id) {
+//!             c.sign_count = credential.sign_count;
+//!         }
+//!
+//!         Ok(())
+//!     }
+//! }
+//!
+//! #[get("/")]
+//! fn index(user: PasskeyUser) -> String {
+//!     format!("Hello, {}!", user.username())
+//! }
+//!
+//! #[launch]
+//! fn rocket() -> _ {
+//!     let passkeys = Passkeys::new("example.com", "https://example.com", Store::default());
+//!     rocket::build()
+//!         .mount("/passkey", passkeys)
+//!         .mount("/", routes![index])
+//! }
+//! ```
+//!
+//! # Attestation
+//!
+//! Creation options request no attestation, and attestation statements, which
+//! prove the make or model of an authenticator, are not verified. Credentials
+//! signed with ES256, Ed25519, and RS256 are supported.
+//!
+//! [WebAuthn]: https://www.w3.org/TR/webauthn-3/
+
+use std::fmt;
+use std::io;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use rand::RngCore;
+use ring::{digest, signature};
+use serde::{Deserialize, Serialize};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+
+use crate::{Data, Request, Route};
+use crate::data::Limits;
+use crate::http::{Cookie, CookieJar, Method, SameSite, Status};
+use crate::request::{self, FromRequest};
+use crate::route::{Handler, Outcome};
+
+/// Storage for users' passkey credentials, implemented by the application.
+///
+/// A user is identified by a _user handle_, random bytes generated by
+/// [`Passkeys`] when the user is created, and has a unique username. Both are
+/// stored with each of the user's [`Credential`]s. See the [module
+/// documentation](self) for an example implementation.
+#[crate::async_trait]
+pub trait CredentialStore: Send + Sync + 'static {
+    /// Returns the user handle of the user named `username`, if there is one.
+    async fn user_handle(&self, username: &str) -> Option<Vec<u8>>;
+
+    /// Returns the credentials of the user with the handle `user_handle`.
+    async fn credentials(&self, user_handle: &[u8]) -> Vec<Credential>;
+
+    /// Returns the credential with the ID `id`, if there is one.
+    async fn credential(&self, id: &[u8]) -> Option<Credential>;
+
+    /// Stores the newly registered `credential`.
+    ///
+    /// Should fail if `credential.username` belongs to a user with a different
+    /// handle: two users may have requested the same new username at once.
+    async fn register(&self, credential: Credential) -> io::Result<()>;
+
+    /// Updates the stored signature counter of `credential`, the only field
+    /// that changes after registration, to `credential.sign_count`.
+    async fn update(&self, credential: &Credential) -> io::Result<()>;
+}
+
+/// A registered passkey credential.
+///
+/// Credentials are serializable so they can be stored as-is.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Credential {
+    /// The credential ID, chosen by the authenticator.
+    pub id: Vec<u8>,
+    /// The handle of the user the credential belongs to.
+    pub user_handle: Vec<u8>,
+    /// The name of the user the credential belongs to.
+    pub username: String,
+    /// The public key that verifies the credential's signatures.
+    pub public_key: PublicKey,
+    /// The authenticator's signature counter, or `0` if it doesn't keep one.
+    pub sign_count: u32,
+}
+
+/// The public key of a [`Credential`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PublicKey {
+    /// An ECDSA P-256 key for SHA-256 signatures (COSE algorithm `-7`): the
+    /// uncompressed SEC1 encoding of the point.
+    Es256(Vec<u8>),
+    /// An Ed25519 key (COSE algorithm `-8`).
+    Ed25519(Vec<u8>),
+    /// An RSA key for PKCS#1 v1.5 SHA-256 signatures (COSE algorithm `-257`):
+    /// the big-endian modulus `n` and exponent `e`.
+    Rs256 {
+        /// The modulus.
+        n: Vec<u8>,
+        /// The public exponent.
+        e: Vec<u8>,
+    },
+}
+
+/// Mountable routes for passkey registration and authentication.
+///
+/// `Passkeys` is configured with the _relying party ID_, the domain passkeys
+/// are scoped to, the origins of the pages performing ceremonies, and a
+/// [`CredentialStore`]. Mounting it adds the routes described in the [module
+/// documentation](self).
+///
+/// # Example
+///
+/// ```rust
+/// # use rocket::auth::passkey::{CredentialStore, Credential};
+/// # struct Store;
+/// # #[rocket::async_trait]
+/// # impl CredentialStore for Store {
+/// #     async fn user_handle(&self, _: &str) -> Option<Vec<u8>> { None }
+/// #     async fn credentials(&self, _: &[u8]) -> Vec<Credential> { vec![] }
+/// #     async fn credential(&self, _: &[u8]) -> Option<Credential> { None }
+/// #     async fn register(&self, _: Credential) -> std::io::Result<()> { Ok(()) }
+/// #     async fn update(&self, _: &Credential) -> std::io::Result<()> { Ok(()) }
+/// # }
+/// use std::time::Duration;
+/// use rocket::auth::passkey::Passkeys;
+///
+/// let passkeys = Passkeys::new("example.com", "https://example.com", Store)
+///     .rp_name("Example")
+///     .origin("https://login.example.com")
+///     .timeout(Duration::from_secs(120))
+///     .require_user_verification(true);
+///
+/// let rocket = rocket::build().mount("/passkey", passkeys);
+/// ```
+pub struct Passkeys<S> {
+    inner: Arc<Inner<S>>,
+}
+
+struct Inner<S> {
+    rp_id: String,
+    rp_name: String,
+    origins: Vec<String>,
+    timeout: Duration,
+    user_verification: bool,
+    store: S,
+}
+
+/// One of the routes mounted by [`Passkeys`].
+struct Endpoint<S> {
+    passkeys: Passkeys<S>,
+    step: Step,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Step {
+    RegisterChallenge,
+    RegisterVerify,
+    LoginChallenge,
+    LoginVerify,
+}
+
+/// The state of a ceremony in progress, kept in a private cookie.
+#[derive(Serialize, Deserialize)]
+struct Ceremony {
+    register: bool,
+    challenge: String,
+    user_handle: Option<String>,
+    username: Option<String>,
+    expires_at: u64,
+}
+
+/// A request guard for users signed in with a passkey.
+///
+/// The guard succeeds if the user signed in through one of the routes mounted
+/// by [`Passkeys`], which record the user in a private cookie. Otherwise, it
+/// forwards with a status of `401 Unauthorized`, so that a lower-ranked route
+/// or the `401` catcher can ask the user to sign in.
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::http::CookieJar;
+/// use rocket::auth::passkey::PasskeyUser;
+///
+/// #[get("/account")]
+/// fn account(user: PasskeyUser) -> String {
+///     format!("signed in as {}", user.username())
+/// }
+///
+/// #[post("/logout")]
+/// fn logout(jar: &CookieJar<'_>) {
+///     PasskeyUser::logout(jar);
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PasskeyUser {
+    user_handle: Vec<u8>,
+    username: String,
+    credential_id: Vec<u8>,
+    authenticated_at: u64,
+}
+
+/// The signed-in user as stored in a private cookie.
+#[derive(Serialize, Deserialize)]
+struct Session {
+    user_handle: String,
+    username: String,
+    credential_id: String,
+    authenticated_at: u64,
+}
+
+#[derive(Default, Deserialize)]
+struct Start {
+    username: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RegistrationResponse {
+    raw_id: String,
+    response: AttestationResponse,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AttestationResponse {
+    #[serde(rename = "clientDataJSON")]
+    client_data_json: String,
+    attestation_object: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AuthenticationResponse {
+    raw_id: String,
+    response: AssertionResponse,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AssertionResponse {
+    #[serde(rename = "clientDataJSON")]
+    client_data_json: String,
+    authenticator_data: String,
+    signature: String,
+    user_handle: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ClientData {
+    #[serde(rename = "type")]
+    kind: String,
+    challenge: String,
+    origin: String,
+    #[serde(default)]
+    cross_origin: bool,
+}
+
+/// The parts of authenticator data used to verify a ceremony.
+struct AuthenticatorData<'a> {
+    rp_id_hash: &'a [u8],
+    flags: u8,
+    sign_count: u32,
+    credential: Option<(&'a [u8], Cbor)>,
+}
+
+/// A decoded CBOR data item, limited to the types WebAuthn uses.
+#[derive(Debug, PartialEq)]
+enum Cbor {
+    Int(i64),
+    Bytes(Vec<u8>),
+    Text(String),
+    Array(Vec<Cbor>),
+    Map(Vec<(Cbor, Cbor)>),
+    Bool(bool),
+    Null,
+}
+
+impl PublicKey {
+    /// Returns the COSE algorithm identifier of the key.
+    pub fn algorithm(&self) -> i64 {
+        match self {
+            PublicKey::Es256(_) => -7,
+            PublicKey::Ed25519(_) => -8,
+            PublicKey::Rs256 { .. } => -257,
+        }
+    }
+
+    /// Returns `true` if `sig` is a valid signature of `message`.
+    pub fn verify(&self, message: &[u8], sig: &[u8]) -> bool {
+        use signature::{UnparsedPublicKey, RsaPublicKeyComponents};
+
+        match self {
+            PublicKey::Es256(key) => {
+                UnparsedPublicKey::new(&signature::ECDSA_P256_SHA256_ASN1, key)
+                    .verify(message, sig)
+                    .is_ok()
+            }
+            PublicKey::Ed25519(key) => {
+                UnparsedPublicKey::new(&signature::ED25519, key)
+                    .verify(message, sig)
+                    .is_ok()
+            }
+            PublicKey::Rs256 { n, e } => {
+                RsaPublicKeyComponents { n, e }
+                    .verify(&signature::RSA_PKCS1_2048_8192_SHA256, message, sig)
+                    .is_ok()
+            }
+        }
+    }
+
+    /// Decodes a COSE key of a supported algorithm.
+    fn from_cose(key: &Cbor) -> Option<PublicKey> {
+        let field = move |label| key.get(&Cbor::Int(label));
+        let bytes = move |label| field(label).and_then(Cbor::as_bytes);
+        match (field(1)?, field(3)?) {
+            (Cbor::Int(2), Cbor::Int(-7)) if field(-1) == Some(&Cbor::Int(1)) => {
+                let (x, y) = (bytes(-2)?, bytes(-3)?);
+                if x.len() != 32 || y.len() != 32 {
+                    return None;
+                }
+
+                Some(PublicKey::Es256([&[0x04][..], x, y].concat()))
+            }
+            (Cbor::Int(1), Cbor::Int(-8)) if field(-1) == Some(&Cbor::Int(6)) => {
+                let x = bytes(-2)?;
+                (x.len() == 32).then(|| PublicKey::Ed25519(x.to_vec()))
+            }
+            (Cbor::Int(3), Cbor::Int(-257)) => {
+                Some(PublicKey::Rs256 { n: bytes(-1)?.to_vec(), e: bytes(-2)?.to_vec() })
+            }
+            _ => None,
+        }
+    }
+}
+
+impl<S: CredentialStore> Passkeys<S> {
+    /// Returns `Passkeys` for the relying party ID `rp_id`, accepting
+    /// ceremonies performed by pages at `origin`, and storing credentials in
+    /// `store`.
+    ///
+    /// The relying party ID is the domain of `origin` or one of its parents,
+    /// like `example.com` for `https://login.example.com`. It's also used as
+    /// the relying party name unless one is set with [`Passkeys::rp_name()`].
+    pub fn new<I, O>(rp_id: I, origin: O, store: S) -> Self
+        where I: Into<String>, O: Into<String>
+    {
+        let rp_id = rp_id.into();
+        Passkeys {
+            inner: Arc::new(Inner {
+                rp_name: rp_id.clone(),
+                rp_id,
+                origins: vec![origin.into()],
+                timeout: Duration::from_secs(5 * 60),
+                user_verification: false,
+                store,
+            })
+        }
+    }
+
+    /// Sets the relying party name shown by authenticators to `name`.
+    pub fn rp_name<N: Into<String>>(mut self, name: N) -> Self {
+        self.inner_mut().rp_name = name.into();
+        self
+    }
+
+    /// Additionally accepts ceremonies performed by pages at `origin`.
+    pub fn origin<O: Into<String>>(mut self, origin: O) -> Self {
+        self.inner_mut().origins.push(origin.into());
+        self
+    }
+
+    /// Sets the time a user has to complete a ceremony after its challenge is
+    /// issued to `timeout`. Defaults to five minutes.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.inner_mut().timeout = timeout;
+        self
+    }
+
+    /// Sets whether authenticators must verify the user, with a PIN or
+    /// biometric, rather than only test for their presence. Defaults to
+    /// `false`, in which case verification is preferred but not required.
+    pub fn require_user_verification(mut self, require: bool) -> Self {
+        self.inner_mut().user_verification = require;
+        self
+    }
+
+    /// Returns the credential store.
+    pub fn store(&self) -> &S {
+        &self.inner.store
+    }
+
+    fn inner_mut(&mut self) -> &mut Inner<S> {
+        Arc::get_mut(&mut self.inner).expect("`Passkeys` is configured before it's mounted")
+    }
+
+    fn user_verification(&self) -> &'static str {
+        match self.inner.user_verification {
+            true => "required",
+            false => "preferred",
+        }
+    }
+
+    async fn register_challenge(&self, req: &Request<'_>, body: &[u8]) -> Result<Value, Status> {
+        let store = &self.inner.store;
+        let (user_handle, username) = match PasskeyUser::from_jar(req.cookies()) {
+            Some(user) => (user.user_handle, user.username),
+            None => {
+                let username = Start::parse(body)?.username
+                    .map(|name| name.trim().to_string())
+                    .filter(|name| !name.is_empty() && name.len() <= 64)
+                    .ok_or(Status::BadRequest)?;
+
+                if store.user_handle(&username).await.is_some() {
+                    return Err(Status::Conflict);
+                }
+
+                (random_bytes(16), username)
+            }
+        };
+
+        let exclude = store.credentials(&user_handle).await;
+        let params = [-7, -8, -257].map(|alg| json!({ "type": "public-key", "alg": alg }));
+        let challenge = self.begin(req.cookies(), Ceremony {
+            register: true,
+            challenge: String::new(),
+            user_handle: Some(encode(&user_handle)),
+            username: Some(username.clone()),
+            expires_at: 0,
+        });
+
+        Ok(json!({
+            "rp": { "id": self.inner.rp_id, "name": self.inner.rp_name },
+            "user": { "id": encode(&user_handle), "name": username, "displayName": username },
+            "challenge": challenge,
+            "pubKeyCredParams": params,
+            "timeout": self.inner.timeout.as_millis() as u64,
+            "excludeCredentials": descriptors(&exclude),
+            "authenticatorSelection": {
+                "residentKey": "preferred",
+                "userVerification": self.user_verification(),
+            },
+            "attestation": "none",
+        }))
+    }
+
+    async fn register_verify(&self, req: &Request<'_>, body: &[u8]) -> Result<Value, Status> {
+        let ceremony = Ceremony::take(req.cookies(), true).ok_or(Status::BadRequest)?;
+        let response = parse::<RegistrationResponse>(body)?;
+        let id = decode(&response.raw_id).ok_or(Status::BadRequest)?;
+        let client_data = decode(&response.response.client_data_json).ok_or(Status::BadRequest)?;
+        self.verify_client_data(&client_data, "webauthn.create", &ceremony.challenge)?;
+
+        let attestation = decode(&response.response.attestation_object)
+            .and_then(|bytes| Some(Cbor::decode(&bytes)?.0))
+            .ok_or(Status::BadRequest)?;
+
+        let auth_data = attestation.get(&Cbor::Text("authData".into()))
+            .and_then(Cbor::as_bytes)
+            .and_then(AuthenticatorData::parse)
+            .ok_or(Status::BadRequest)?;
+
+        self.verify_authenticator_data(&auth_data)?;
+        let Some((credential_id, key)) = &auth_data.credential else {
+            return Err(Status::BadRequest);
+        };
+
+        if *credential_id != id || id.len() > 1023 {
+            return Err(Status::BadRequest);
+        }
+
+        let Some(public_key) = PublicKey::from_cose(key) else {
+            warn!(name: "passkey", "passkey with unsupported key type refused");
+            return Err(Status::BadRequest);
+        };
+
+        let store = &self.inner.store;
+        if store.credential(&id).await.is_some() {
+            return Err(Status::Conflict);
+        }
+
+        let user_handle = ceremony.user_handle.as_deref().and_then(decode);
+        let credential = Credential {
+            id,
+            user_handle: user_handle.ok_or(Status::BadRequest)?,
+            username: ceremony.username.ok_or(Status::BadRequest)?,
+            public_key,
+            sign_count: auth_data.sign_count,
+        };
+
+        if let Err(e) = store.register(credential.clone()).await {
+            warn!(name: "passkey", username = %credential.username, error = %e,
+                "failed to store passkey");
+
+            return Err(Status::InternalServerError);
+        }
+
+        PasskeyUser::login(req.cookies(), &credential);
+        Ok(json!({ "username": credential.username }))
+    }
+
+    async fn login_challenge(&self, req: &Request<'_>, body: &[u8]) -> Result<Value, Status> {
+        let store = &self.inner.store;
+        let user_handle = match Start::parse(body)?.username {
+            Some(username) => store.user_handle(username.trim()).await,
+            None => None,
+        };
+
+        let allow = match &user_handle {
+            Some(user_handle) => store.credentials(user_handle).await,
+            None => vec![],
+        };
+
+        let challenge = self.begin(req.cookies(), Ceremony {
+            register: false,
+            challenge: String::new(),
+            user_handle: user_handle.as_deref().map(encode),
+            username: None,
+            expires_at: 0,
+        });
+
+        Ok(json!({
+            "challenge": challenge,
+            "rpId": self.inner.rp_id,
+            "timeout": self.inner.timeout.as_millis() as u64,
+            "allowCredentials": descriptors(&allow),
+            "userVerification": self.user_verification(),
+        }))
+    }
+
+    async fn login_verify(&self, req: &Request<'_>, body: &[u8]) -> Result<Value, Status> {
+        let ceremony = Ceremony::take(req.cookies(), false).ok_or(Status::BadRequest)?;
+        let AuthenticationResponse { raw_id, response } = parse(body)?;
+        let id = decode(&raw_id).ok_or(Status::BadRequest)?;
+        let mut credential = self.inner.store.credential(&id).await.ok_or(Status::Unauthorized)?;
+
+        let expected_user = ceremony.user_handle.as_deref().and_then(decode);
+        let claimed_user = response.user_handle.as_deref()
+            .filter(|handle| !handle.is_empty())
+            .and_then(decode);
+
+        let other_user = |handle: &Option<Vec<u8>>| {
+            handle.as_ref().is_some_and(|handle| *handle != credential.user_handle)
+        };
+
+        if other_user(&expected_user) || other_user(&claimed_user) {
+            return Err(Status::Unauthorized);
+        }
+
+        let client_data = decode(&response.client_data_json).ok_or(Status::BadRequest)?;
+        self.verify_client_data(&client_data, "webauthn.get", &ceremony.challenge)?;
+
+        let raw_auth_data = decode(&response.authenticator_data).ok_or(Status::BadRequest)?;
+        let auth_data = AuthenticatorData::parse(&raw_auth_data).ok_or(Status::BadRequest)?;
+        self.verify_authenticator_data(&auth_data)?;
+
+        let sig = decode(&response.signature).ok_or(Status::BadRequest)?;
+        let client_data_hash = digest::digest(&digest::SHA256, &client_data);
+        let message = [&raw_auth_data[..], client_data_hash.as_ref()].concat();
+        if !credential.public_key.verify(&message, &sig) {
+            warn!(name: "passkey", username = %credential.username, "invalid passkey signature");
+            return Err(Status::Unauthorized);
+        }
+
+        // A counter that doesn't increase suggests a cloned authenticator.
+        let sign_count = auth_data.sign_count;
+        if (sign_count != 0 || credential.sign_count != 0) && sign_count <= credential.sign_count {
+            warn!(name: "passkey", username = %credential.username, sign_count,
+                stored = credential.sign_count, "passkey signature counter did not increase");
+
+            return Err(Status::Unauthorized);
+        }
+
+        if sign_count != credential.sign_count {
+            credential.sign_count = sign_count;
+            if let Err(e) = self.inner.store.update(&credential).await {
+                warn!(name: "passkey", username = %credential.username, error = %e,
+                    "failed to update passkey signature counter");
+
+                return Err(Status::InternalServerError);
+            }
+        }
+
+        PasskeyUser::login(req.cookies(), &credential);
+        Ok(json!({ "username": credential.username }))
+    }
+
+    /// Starts a ceremony with a new challenge, recording it in a cookie.
+    /// Returns the encoded challenge.
+    fn begin(&self, jar: &CookieJar<'_>, mut ceremony: Ceremony) -> String {
+        let timeout = self.inner.timeout;
+        ceremony.challenge = encode(&random_bytes(32));
+        ceremony.expires_at = unix_now().saturating_add(timeout.as_secs());
+
+        let value = serde_json::to_string(&ceremony).expect("ceremony is serializable");
+        let cookie = Cookie::build((Ceremony::COOKIE, value))
+            .path("/")
+            .http_only(true)
+            .same_site(SameSite::Strict)
+            .max_age(time::Duration::seconds(timeout.as_secs() as i64));
+
+        jar.add_private(cookie);
+        ceremony.challenge
+    }
+
+    fn verify_client_data(&self, json: &[u8], kind: &str, challenge: &str) -> Result<(), Status> {
+        let client_data = serde_json::from_slice::<ClientData>(json)
+            .map_err(|_| Status::BadRequest)?;
+
+        if client_data.kind != kind
+            || !constant_time_eq(client_data.challenge.as_bytes(), challenge.as_bytes())
+            || !self.inner.origins.contains(&client_data.origin)
+            || client_data.cross_origin
+        {
+            warn!(name: "passkey", origin = %client_data.origin, "passkey client data mismatch");
+            return Err(Status::Unauthorized);
+        }
+
+        Ok(())
+    }
+
+    fn verify_authenticator_data(&self, data: &AuthenticatorData<'_>) -> Result<(), Status> {
+        let rp_id_hash = digest::digest(&digest::SHA256, self.inner.rp_id.as_bytes());
+        let verified = data.flags & AuthenticatorData::USER_VERIFIED != 0;
+        if data.rp_id_hash != rp_id_hash.as_ref()
+            || data.flags & AuthenticatorData::USER_PRESENT == 0
+            || (self.inner.user_verification && !verified)
+        {
+            return Err(Status::Unauthorized);
+        }
+
+        Ok(())
+    }
+}
+
+impl<S> Clone for Passkeys<S> {
+    fn clone(&self) -> Self {
+        Passkeys { inner: self.inner.clone() }
+    }
+}
+
+impl<S> Clone for Endpoint<S> {
+    fn clone(&self) -> Self {
+        Endpoint { passkeys: self.passkeys.clone(), step: self.step }
+    }
+}
+
+impl<S> fmt::Debug for Passkeys<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Passkeys")
+            .field("rp_id", &self.inner.rp_id)
+            .field("rp_name", &self.inner.rp_name)
+            .field("origins", &self.inner.origins)
+            .field("timeout", &self.inner.timeout)
+            .field("user_verification", &self.inner.user_verification)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S: CredentialStore> From<Passkeys<S>> for Vec<Route> {
+    fn from(passkeys: Passkeys<S>) -> Self {
+        [
+            (Step::RegisterChallenge, "/register/challenge"),
+            (Step::RegisterVerify, "/register/verify"),
+            (Step::LoginChallenge, "/login/challenge"),
+            (Step::LoginVerify, "/login/verify"),
+        ].into_iter().map(|(step, path)| {
+            let endpoint = Endpoint { passkeys: passkeys.clone(), step };
+            let mut route = Route::new(Method::Post, path, endpoint);
+            route.name = Some("Passkeys".into());
+            route
+        }).collect()
+    }
+}
+
+#[crate::async_trait]
+impl<S: CredentialStore> Handler for Endpoint<S> {
+    async fn handle<'r>(&self, req: &'r Request<'_>, data: Data<'r>) -> Outcome<'r> {
+        let limit = req.limits().get("json").unwrap_or(Limits::JSON);
+        let body = match data.open(limit).into_bytes().await {
+            Ok(bytes) if bytes.is_complete() => bytes.into_inner(),
+            Ok(_) => return Outcome::Error(Status::PayloadTooLarge),
+            Err(_) => return Outcome::Error(Status::BadRequest),
+        };
+
+        let passkeys = &self.passkeys;
+        let result = match self.step {
+            Step::RegisterChallenge => passkeys.register_challenge(req, &body).await,
+            Step::RegisterVerify => passkeys.register_verify(req, &body).await,
+            Step::LoginChallenge => passkeys.login_challenge(req, &body).await,
+            Step::LoginVerify => passkeys.login_verify(req, &body).await,
+        };
+
+        match result {
+            Ok(value) => Outcome::from(req, value),
+            Err(status) => Outcome::Error(status),
+        }
+    }
+}
+
+impl Ceremony {
+    /// The name of the private cookie holding the state of a ceremony.
+    const COOKIE: &'static str = "rocket_passkey_ceremony";
+
+    /// Removes the ceremony cookie, returning the ceremony it records if it's
+    /// a registration ceremony if `register` is `true`, an authentication
+    /// ceremony otherwise, and hasn't expired.
+    fn take(jar: &CookieJar<'_>, register: bool) -> Option<Ceremony> {
+        let cookie = jar.get_private(Self::COOKIE)?;
+        jar.remove_private(Cookie::build(Self::COOKIE).path("/"));
+        let ceremony: Ceremony = serde_json::from_str(cookie.value()).ok()?;
+        (ceremony.register == register && unix_now() < ceremony.expires_at).then_some(ceremony)
+    }
+}
+
+impl PasskeyUser {
+    /// The name of the private cookie recording the signed-in user.
+    pub const COOKIE: &'static str = "rocket_passkey_user";
+
+    /// Signs the user out, if one is signed in.
+    pub fn logout(jar: &CookieJar<'_>) {
+        jar.remove_private(Cookie::build(Self::COOKIE).path("/"));
+    }
+
+    /// Returns the user's handle.
+    pub fn user_handle(&self) -> &[u8] {
+        &self.user_handle
+    }
+
+    /// Returns the user's name.
+    pub fn username(&self) -> &str {
+        &self.username
+    }
+
+    /// Returns the ID of the credential the user signed in with.
+    pub fn credential_id(&self) -> &[u8] {
+        &self.credential_id
+    }
+
+    /// Returns the time at which the user signed in.
+    pub fn authenticated_at(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(self.authenticated_at)
+    }
+
+    fn login(jar: &CookieJar<'_>, credential: &Credential) {
+        let session = Session {
+            user_handle: encode(&credential.user_handle),
+            username: credential.username.clone(),
+            credential_id: encode(&credential.id),
+            authenticated_at: unix_now(),
+        };
+
+        let value = serde_json::to_string(&session).expect("session is serializable");
+        let cookie = Cookie::build((Self::COOKIE, value))
+            .path("/")
+            .http_only(true)
+            .same_site(SameSite::Lax);
+
+        jar.add_private(cookie);
+    }
+
+    fn from_jar(jar: &CookieJar<'_>) -> Option<PasskeyUser> {
+        let cookie = jar.get_private(Self::COOKIE)?;
+        let session: Session = serde_json::from_str(cookie.value()).ok()?;
+        Some(PasskeyUser {
+            user_handle: decode(&session.user_handle)?,
+            username: session.username,
+            credential_id: decode(&session.credential_id)?,
+            authenticated_at: session.authenticated_at,
+        })
+    }
+}
+
+#[crate::async_trait]
+impl<'r> FromRequest<'r> for PasskeyUser {
+    type Error = std::convert::Infallible;
+
+    async fn from_request(req: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        match PasskeyUser::from_jar(req.cookies()) {
+            Some(user) => request::Outcome::Success(user),
+            None => request::Outcome::Forward(Status::Unauthorized),
+        }
+    }
+}
+
+impl<'a> AuthenticatorData<'a> {
+    const USER_PRESENT: u8 = 0x01;
+    const USER_VERIFIED: u8 = 0x04;
+    const ATTESTED_CREDENTIAL: u8 = 0x40;
+
+    fn parse(bytes: &'a [u8]) -> Option<Self> {
+        let (rp_id_hash, rest) = split(bytes, 32)?;
+        let (&flags, rest) = rest.split_first()?;
+        let (sign_count, rest) = split(rest, 4)?;
+        let sign_count = u32::from_be_bytes(sign_count.try_into().ok()?);
+        let credential = match flags & Self::ATTESTED_CREDENTIAL != 0 {
+            true => {
+                let (_aaguid, rest) = split(rest, 16)?;
+                let (len, rest) = split(rest, 2)?;
+                let (id, rest) = split(rest, u16::from_be_bytes([len[0], len[1]]) as usize)?;
+                Some((id, Cbor::decode(rest)?.0))
+            }
+            false => None,
+        };
+
+        Some(AuthenticatorData { rp_id_hash, flags, sign_count, credential })
+    }
+}
+
+impl Cbor {
+    /// The maximum nesting depth of decoded items.
+    const MAX_DEPTH: usize = 16;
+
+    /// Decodes the data item at the start of `bytes`, returning it and the
+    /// bytes that follow it.
+    fn decode(bytes: &[u8]) -> Option<(Cbor, &[u8])> {
+        Self::decode_nested(bytes, 0)
+    }
+
+    fn decode_nested(bytes: &[u8], depth: usize) -> Option<(Cbor, &[u8])> {
+        if depth > Self::MAX_DEPTH {
+            return None;
+        }
+
+        let (&initial, rest) = bytes.split_first()?;
+        let (major, info) = (initial >> 5, initial & 0x1f);
+        let (arg, mut rest) = match info {
+            0..=23 => (info as u64, rest),
+            24..=27 => {
+                let (arg, rest) = split(rest, 1 << (info - 24))?;
+                (arg.iter().fold(0u64, |acc, b| (acc << 8) | *b as u64), rest)
+            }
+            _ => return None,
+        };
+
+        let item = match major {
+            0 => Cbor::Int(i64::try_from(arg).ok()?),
+            1 => Cbor::Int(-1 - i64::try_from(arg).ok()?),
+            2 | 3 => {
+                let (data, remaining) = split(rest, usize::try_from(arg).ok()?)?;
+                rest = remaining;
+                match major {
+                    2 => Cbor::Bytes(data.to_vec()),
+                    _ => Cbor::Text(std::str::from_utf8(data).ok()?.to_string()),
+                }
+            }
+            4 | 5 if arg > rest.len() as u64 => return None,
+            4 => {
+                let mut items = vec![];
+                for _ in 0..arg {
+                    let (item, remaining) = Self::decode_nested(rest, depth + 1)?;
+                    items.push(item);
+                    rest = remaining;
+                }
+
+                Cbor::Array(items)
+            }
+            5 => {
+                let mut entries = vec![];
+                for _ in 0..arg {
+                    let (key, remaining) = Self::decode_nested(rest, depth + 1)?;
+                    let (value, remaining) = Self::decode_nested(remaining, depth + 1)?;
+                    entries.push((key, value));
+                    rest = remaining;
+                }
+
+                Cbor::Map(entries)
+            }
+            7 => match arg {
+                20 => Cbor::Bool(false),
+                21 => Cbor::Bool(true),
+                22 => Cbor::Null,
+                _ => return None,
+            },
+            _ => return None,
+        };
+
+        Some((item, rest))
+    }
+
+    /// Returns the value of the entry with key `key` if `self` is a map.
+    fn get(&self, key: &Cbor) -> Option<&Cbor> {
+        match self {
+            Cbor::Map(entries) => entries.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Cbor::Bytes(bytes) => Some(bytes),
+            _ => None,
+        }
+    }
+}
+
+impl Start {
+    /// Parses the JSON request `body`. An empty body has no username.
+    fn parse(body: &[u8]) -> Result<Start, Status> {
+        match body.iter().all(|b| b.is_ascii_whitespace()) {
+            true => Ok(Start::default()),
+            false => parse(body),
+        }
+    }
+}
+
+fn parse<T: DeserializeOwned>(body: &[u8]) -> Result<T, Status> {
+    serde_json::from_slice(body).map_err(|_| Status::BadRequest)
+}
+
+fn descriptors(credentials: &[Credential]) -> Vec<Value> {
+    credentials.iter()
+        .map(|c| json!({ "type": "public-key", "id": encode(&c.id) }))
+        .collect()
+}
+
+fn random_bytes(len: usize) -> Vec<u8> {
+    let mut bytes = vec![0; len];
+    rand::rngs::OsRng.fill_bytes(&mut bytes);
+    bytes
+}
+
+fn split(bytes: &[u8], n: usize) -> Option<(&[u8], &[u8])> {
+    (bytes.len() >= n).then(|| bytes.split_at(n))
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs())
+}
+
+/// Returns the unpadded base64url encoding of `bytes`.
+fn encode(bytes: &[u8]) -> String {
+    let mut buf = vec![0; (bytes.len() + 2) / 3 * 4];
+    let encoded = binascii::b64encode(bytes, &mut buf).expect("sufficient buffer");
+    String::from_utf8_lossy(encoded)
+        .trim_end_matches('=')
+        .replace('+', "-")
+        .replace('/', "_")
+}
+
+/// Decodes base64url with or without padding.
+fn decode(string: &str) -> Option<Vec<u8>> {
+    let mut standard = string.trim_end_matches('=').replace('-', "+").replace('_', "/");
+    while standard.len() % 4 != 0 {
+        standard.push('=');
+    }
+
+    // `binascii` requires more space than the actual output for padding.
+    let mut buf = vec![0; standard.len()];
+    binascii::b64decode(standard.as_bytes(), &mut buf).ok().map(|bytes| bytes.to_vec())
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
@@ -0,0 +1,1064 @@
+//! SAML 2.0 single sign-on as a service provider.
+//!
+//! This module implements the service provider (SP) side of SP-initiated
+//! [SAML 2.0 Web Browser SSO], with which users sign in through an
+//! organization's identity provider (IdP), like Okta, Microsoft Entra ID, or
+//! AD FS:
+//!
+//!   * [`Saml`] is mounted to add routes that publish the SP's metadata,
+//!     redirect users to the IdP, and consume the IdP's responses.
+//!   * [`IdentityProvider`] configures the IdP and the certificates its
+//!     responses are signed with.
+//!   * [`SamlUser`] is a request guard for users signed in via SAML, each
+//!     identified by a [`Principal`].
+//!
+//! The state of a sign-in in progress and the signed-in user are kept in
+//! private cookies. This module is only available when the `saml` feature is
+//! enabled, which also enables the `secrets` feature.
+//!
+//! # Routes
+//!
+//! When mounted at `/saml`, [`Saml`] handles the following routes:
+//!
+//! | Route                  | Description                                            |
+//! |------------------------|--------------------------------------------------------|
+//! | `GET /saml/metadata`   | The SP's metadata, to be registered with the IdP.      |
+//! | `GET /saml/login`      | Redirects to the IdP with an `AuthnRequest`.           |
+//! | `POST /saml/acs`       | The assertion consumer service: consumes the response. |
+//!
+//! `/saml/login` accepts an optional `redirect` query parameter, a path on
+//! this server to which the user is redirected once signed in, as in
+//! `/saml/login?redirect=/account`. It defaults to `/`. The path is kept in
+//! the private request cookie rather than sent to the IdP; the `RelayState`
+//! sent instead is random and must be returned unchanged with the response.
+//!
+//! The assertion consumer service URL configured with [`Saml::new()`] must be
+//! the absolute URL of the `acs` route, like `https://example.com/saml/acs`.
+//! Responses are posted to it by the user's browser from the IdP's origin,
+//! so the request cookie is `SameSite=None` and `Secure`, and the server must
+//! be served over HTTPS for browsers other than on `localhost` to send it.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! # #[macro_use] extern crate rocket;
+//! use rocket::auth::saml::{Saml, IdentityProvider, SamlUser};
+//! use rocket::http::CookieJar;
+//!
+//! #[get("/")]
+//! fn index(user: SamlUser) -> String {
+//!     format!("Hello, {}!", user.principal().id())
+//! }
+//!
+//! #[post("/logout")]
+//! fn logout(jar: &CookieJar<'_>) {
+//!     SamlUser::logout(jar);
+//! }
+//!
+//! #[launch]
+//! fn rocket() -> _ {
+//!     let certificate = std::fs::read("idp.pem").expect("IdP certificate");
+//!     let idp = IdentityProvider::new("https://idp.example.com", "https://idp.example.com/sso")
+//!         .certificate(&certificate)
+//!         .expect("valid IdP certificate");
+//!
+//!     let saml = Saml::new("https://example.com", "https://example.com/saml/acs", idp)
+//!         .scope_attribute("groups")
+//!         .attribute("email");
+//!
+//!     rocket::build()
+//!         .mount("/saml", saml)
+//!         .mount("/", routes![index, logout])
+//! }
+//! ```
+//!
+//! # Validation
+//!
+//! A response is accepted only if it is in response to the request recorded
+//! in the request cookie, which is consumed by the attempt, and:
+//!
+//!   * it or its single assertion carries a valid enveloped XML signature by
+//!     one of the IdP's certificates, and any other signature is also valid,
+//!   * its status is `Success`, and its issuer, if any, and its assertion's
+//!     issuer are the IdP's entity ID,
+//!   * its destination, if any, and the recipient of a bearer subject
+//!     confirmation are the assertion consumer service URL,
+//!   * the assertion's conditions are met, within the allowed clock skew, and
+//!     restrict its audience to the SP's entity ID.
+//!
+//! Signatures must use exclusive canonicalization with SHA-256 or SHA-512
+//! digests, and RSA or ECDSA P-256 or P-384 keys. Encrypted assertions and
+//! IdP-initiated sign-ins are not supported. Failures respond with `400 Bad
+//! Request` for malformed requests and `401 Unauthorized` otherwise; the
+//! reason is logged.
+//!
+//! [SAML 2.0 Web Browser SSO]: https://docs.oasis-open.org/security/saml/v2.0/saml-profiles-2.0-os.pdf
+
+mod xml;
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use rand::RngCore;
+use ring::{digest, signature};
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
+
+use crate::{Data, Request, Route};
+use crate::data::Limits;
+use crate::handshake::Principal;
+use crate::http::{ContentType, Cookie, CookieJar, Method, RawStr, SameSite, Status};
+use crate::http::uri::Reference;
+use crate::request::{self, FromRequest};
+use crate::response::Redirect;
+use crate::route::{Handler, Outcome};
+
+use self::xml::Element;
+
+const PROTOCOL: &str = "urn:oasis:names:tc:SAML:2.0:protocol";
+const ASSERTION: &str = "urn:oasis:names:tc:SAML:2.0:assertion";
+const METADATA: &str = "urn:oasis:names:tc:SAML:2.0:metadata";
+const HTTP_POST: &str = "urn:oasis:names:tc:SAML:2.0:bindings:HTTP-POST";
+const BEARER: &str = "urn:oasis:names:tc:SAML:2.0:cm:bearer";
+const SUCCESS: &str = "urn:oasis:names:tc:SAML:2.0:status:Success";
+
+const DSIG: &str = "http://www.w3.org/2000/09/xmldsig#";
+const ENVELOPED: &str = "http://www.w3.org/2000/09/xmldsig#enveloped-signature";
+const EXC_C14N: &str = "http://www.w3.org/2001/10/xml-exc-c14n#";
+const SHA256: &str = "http://www.w3.org/2001/04/xmlenc#sha256";
+const SHA512: &str = "http://www.w3.org/2001/04/xmlenc#sha512";
+const RSA_SHA256: &str = "http://www.w3.org/2001/04/xmldsig-more#rsa-sha256";
+const RSA_SHA512: &str = "http://www.w3.org/2001/04/xmldsig-more#rsa-sha512";
+const ECDSA_SHA256: &str = "http://www.w3.org/2001/04/xmldsig-more#ecdsa-sha256";
+const ECDSA_SHA384: &str = "http://www.w3.org/2001/04/xmldsig-more#ecdsa-sha384";
+
+/// A SAML identity provider (IdP).
+///
+/// An IdP is identified by its entity ID, receives authentication requests at
+/// its single sign-on service URL, and signs its responses with a private key
+/// whose certificate must be added with [`IdentityProvider::certificate()`].
+/// All three are found in the IdP's metadata or administrative console.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use rocket::auth::saml::IdentityProvider;
+///
+/// let certificate = std::fs::read("idp.pem").expect("IdP certificate");
+/// let idp = IdentityProvider::new("https://idp.example.com", "https://idp.example.com/sso")
+///     .certificate(&certificate)
+///     .expect("valid IdP certificate");
+/// ```
+#[derive(Debug, Clone)]
+pub struct IdentityProvider {
+    entity_id: String,
+    sso_url: String,
+    keys: Vec<VerifyingKey>,
+}
+
+/// The error returned by [`IdentityProvider::certificate()`] when a
+/// certificate can't be parsed or has an unsupported key type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CertificateError(&'static str);
+
+/// Mountable routes for SAML single sign-on.
+///
+/// `Saml` is configured with the SP's entity ID, the absolute URL of its
+/// assertion consumer service, and the [`IdentityProvider`]. Mounting it adds
+/// the routes described in the [module documentation](self).
+///
+/// # Example
+///
+/// ```rust
+/// # use rocket::auth::saml::IdentityProvider;
+/// use std::time::Duration;
+/// use rocket::auth::saml::Saml;
+///
+/// # let idp = IdentityProvider::new("https://idp.example.com", "https://idp.example.com/sso");
+/// let saml = Saml::new("https://example.com", "https://example.com/saml/acs", idp)
+///     .name_id_format("urn:oasis:names:tc:SAML:1.1:nameid-format:emailAddress")
+///     .scope_attribute("groups")
+///     .attribute("email")
+///     .timeout(Duration::from_secs(120))
+///     .clock_skew(Duration::from_secs(30));
+///
+/// let rocket = rocket::build().mount("/saml", saml);
+/// ```
+#[derive(Clone)]
+pub struct Saml {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    entity_id: String,
+    acs_url: String,
+    idp: IdentityProvider,
+    name_id_format: Option<String>,
+    scope_attribute: Option<String>,
+    attributes: Vec<String>,
+    timeout: Duration,
+    clock_skew: Duration,
+}
+
+/// One of the routes mounted by [`Saml`].
+#[derive(Clone)]
+struct Endpoint {
+    saml: Saml,
+    step: Step,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Step {
+    Metadata,
+    Login,
+    Acs,
+}
+
+/// A public key that verifies an IdP's signatures.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum VerifyingKey {
+    /// A DER-encoded `RSAPublicKey`.
+    Rsa(Vec<u8>),
+    /// An uncompressed P-256 point.
+    EcdsaP256(Vec<u8>),
+    /// An uncompressed P-384 point.
+    EcdsaP384(Vec<u8>),
+}
+
+/// A sign-in in progress, kept in a private cookie.
+#[derive(Serialize, Deserialize)]
+struct Pending {
+    id: String,
+    relay_state: String,
+    redirect: String,
+    expires_at: u64,
+}
+
+/// A request guard for users signed in via SAML.
+///
+/// The guard succeeds if the user signed in through the routes mounted by
+/// [`Saml`], which record the user in a private cookie, and the IdP's session
+/// hasn't expired. Otherwise, it forwards with a status of `401
+/// Unauthorized`, so that a lower-ranked route or the `401` catcher can
+/// redirect the user to the `login` route.
+///
+/// The user is identified by a [`Principal`] whose ID is the assertion's
+/// `NameID` and whose scopes are the values of the [scope
+/// attribute](Saml::scope_attribute()).
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::auth::saml::SamlUser;
+///
+/// #[get("/admin")]
+/// fn admin(user: SamlUser) -> Option<String> {
+///     let principal = user.principal();
+///     principal.has_scope("admins").then(|| format!("welcome, {}", principal.id()))
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SamlUser {
+    principal: Principal,
+    name_id_format: Option<String>,
+    session_index: Option<String>,
+    attributes: BTreeMap<String, Vec<String>>,
+    authenticated_at: u64,
+    expires_at: Option<u64>,
+}
+
+/// The signed-in user as stored in a private cookie.
+#[derive(Serialize, Deserialize)]
+struct Session {
+    name_id: String,
+    name_id_format: Option<String>,
+    session_index: Option<String>,
+    scopes: Vec<String>,
+    attributes: BTreeMap<String, Vec<String>>,
+    authenticated_at: u64,
+    expires_at: Option<u64>,
+}
+
+impl IdentityProvider {
+    /// Returns an IdP with the entity ID `entity_id` and the single sign-on
+    /// service URL `sso_url`, supporting the HTTP-Redirect binding, without
+    /// any certificates.
+    pub fn new<E, U>(entity_id: E, sso_url: U) -> Self
+        where E: Into<String>, U: Into<String>
+    {
+        IdentityProvider { entity_id: entity_id.into(), sso_url: sso_url.into(), keys: vec![] }
+    }
+
+    /// Trusts signatures by the key of the X.509 `certificate`, encoded as
+    /// PEM, as DER, or as the bare base64 shown in IdP metadata.
+    ///
+    /// Adding more than one certificate allows rolling over the IdP's key.
+    /// The certificate's validity period and issuer are not checked: it's
+    /// trusted because it's configured.
+    pub fn certificate(mut self, certificate: &[u8]) -> Result<Self, CertificateError> {
+        use x509_parser::prelude::{FromDer, X509Certificate};
+
+        const BEGIN: &str = "-----BEGIN CERTIFICATE-----";
+        const END: &str = "-----END CERTIFICATE-----";
+
+        let der = match std::str::from_utf8(certificate) {
+            Ok(text) => {
+                let base64 = match (text.find(BEGIN), text.find(END)) {
+                    (Some(start), Some(end)) if start < end => &text[start + BEGIN.len()..end],
+                    _ => text,
+                };
+
+                decode(base64).ok_or(CertificateError("invalid base64"))?
+            }
+            Err(_) => certificate.to_vec(),
+        };
+
+        let (_, cert) = X509Certificate::from_der(&der)
+            .map_err(|_| CertificateError("invalid certificate"))?;
+
+        let spki = cert.public_key();
+        let key = spki.subject_public_key.data.to_vec();
+        let curve = spki.algorithm.parameters.as_ref()
+            .and_then(|params| params.as_oid().ok())
+            .map(|oid| oid.to_id_string());
+
+        let key = match spki.algorithm.algorithm.to_id_string().as_str() {
+            "1.2.840.113549.1.1.1" => VerifyingKey::Rsa(key),
+            "1.2.840.10045.2.1" => match curve.as_deref() {
+                Some("1.2.840.10045.3.1.7") => VerifyingKey::EcdsaP256(key),
+                Some("1.3.132.0.34") => VerifyingKey::EcdsaP384(key),
+                _ => return Err(CertificateError("unsupported elliptic curve")),
+            },
+            _ => return Err(CertificateError("unsupported key type")),
+        };
+
+        self.keys.push(key);
+        Ok(self)
+    }
+
+    /// Returns the IdP's entity ID.
+    pub fn entity_id(&self) -> &str {
+        &self.entity_id
+    }
+
+    /// Returns the IdP's single sign-on service URL.
+    pub fn sso_url(&self) -> &str {
+        &self.sso_url
+    }
+}
+
+impl VerifyingKey {
+    /// Returns `true` if `sig` is a valid signature of `message` with the
+    /// XML signature algorithm `method`.
+    fn verify(&self, method: &str, message: &[u8], sig: &[u8]) -> bool {
+        use signature::UnparsedPublicKey as Key;
+
+        let result = match (self, method) {
+            (VerifyingKey::Rsa(key), RSA_SHA256) => {
+                Key::new(&signature::RSA_PKCS1_2048_8192_SHA256, key).verify(message, sig)
+            }
+            (VerifyingKey::Rsa(key), RSA_SHA512) => {
+                Key::new(&signature::RSA_PKCS1_2048_8192_SHA512, key).verify(message, sig)
+            }
+            (VerifyingKey::EcdsaP256(key), ECDSA_SHA256) => {
+                Key::new(&signature::ECDSA_P256_SHA256_FIXED, key).verify(message, sig)
+            }
+            (VerifyingKey::EcdsaP384(key), ECDSA_SHA384) => {
+                Key::new(&signature::ECDSA_P384_SHA384_FIXED, key).verify(message, sig)
+            }
+            _ => return false,
+        };
+
+        result.is_ok()
+    }
+}
+
+impl Saml {
+    /// Returns `Saml` for the SP with the entity ID `entity_id`, consuming
+    /// responses from `idp` at the absolute URL `acs_url`.
+    ///
+    /// The entity ID is a URI, conventionally the URL of the site, like
+    /// `https://example.com`. `acs_url` is the URL of the `acs` route, like
+    /// `https://example.com/saml/acs` when mounted at `/saml`.
+    pub fn new<E, A>(entity_id: E, acs_url: A, idp: IdentityProvider) -> Self
+        where E: Into<String>, A: Into<String>
+    {
+        Saml {
+            inner: Arc::new(Inner {
+                entity_id: entity_id.into(),
+                acs_url: acs_url.into(),
+                idp,
+                name_id_format: None,
+                scope_attribute: None,
+                attributes: vec![],
+                timeout: Duration::from_secs(5 * 60),
+                clock_skew: Duration::from_secs(60),
+            })
+        }
+    }
+
+    /// Requests `NameID`s in `format`, like
+    /// `urn:oasis:names:tc:SAML:1.1:nameid-format:emailAddress`. By default,
+    /// the IdP chooses the format.
+    pub fn name_id_format<F: Into<String>>(mut self, format: F) -> Self {
+        self.inner_mut().name_id_format = Some(format.into());
+        self
+    }
+
+    /// Grants each signed-in [`Principal`] a scope for each value of the
+    /// assertion attribute named `name`, like `groups`. By default, principals
+    /// have no scopes.
+    pub fn scope_attribute<N: Into<String>>(mut self, name: N) -> Self {
+        self.inner_mut().scope_attribute = Some(name.into());
+        self
+    }
+
+    /// Keeps the values of the assertion attribute named `name`, like `email`,
+    /// available via [`SamlUser::attribute()`].
+    ///
+    /// Attributes are stored in the session cookie, so only the attributes
+    /// needed by the application should be kept.
+    pub fn attribute<N: Into<String>>(mut self, name: N) -> Self {
+        self.inner_mut().attributes.push(name.into());
+        self
+    }
+
+    /// Sets the time a user has to complete a sign-in after being redirected
+    /// to the IdP to `timeout`. Defaults to five minutes.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.inner_mut().timeout = timeout;
+        self
+    }
+
+    /// Sets the allowed difference between this server's and the IdP's clocks
+    /// when checking an assertion's validity period. Defaults to one minute.
+    pub fn clock_skew(mut self, skew: Duration) -> Self {
+        self.inner_mut().clock_skew = skew;
+        self
+    }
+
+    /// Returns the IdP.
+    pub fn idp(&self) -> &IdentityProvider {
+        &self.inner.idp
+    }
+
+    /// Returns the SP's metadata, an `EntityDescriptor` to be registered with
+    /// the IdP, as served by the `metadata` route.
+    pub fn metadata(&self) -> String {
+        let inner = &self.inner;
+        let name_id_format = inner.name_id_format.as_deref()
+            .map(|format| format!("<md:NameIDFormat>{}</md:NameIDFormat>", escape(format)))
+            .unwrap_or_default();
+
+        format!("<md:EntityDescriptor xmlns:md=\"{METADATA}\" entityID=\"{}\">\
+            <md:SPSSODescriptor AuthnRequestsSigned=\"false\" \
+            protocolSupportEnumeration=\"{PROTOCOL}\">{name_id_format}\
+            <md:AssertionConsumerService Binding=\"{HTTP_POST}\" Location=\"{}\" \
+            index=\"0\" isDefault=\"true\"/></md:SPSSODescriptor></md:EntityDescriptor>",
+            escape(&inner.entity_id), escape(&inner.acs_url))
+    }
+
+    fn inner_mut(&mut self) -> &mut Inner {
+        Arc::get_mut(&mut self.inner).expect("`Saml` is configured before it's mounted")
+    }
+
+    /// Records a new sign-in in a cookie and returns the redirect to the IdP
+    /// carrying its `AuthnRequest`.
+    fn login(&self, req: &Request<'_>) -> Redirect {
+        let inner = &self.inner;
+        let redirect = req.query_value::<&str>("redirect")
+            .and_then(Result::ok)
+            .filter(|path| is_local(path))
+            .unwrap_or("/");
+
+        let pending = Pending {
+            id: format!("_{}", hex(&random_bytes(20))),
+            relay_state: hex(&random_bytes(16)),
+            redirect: redirect.to_string(),
+            expires_at: unix_now().saturating_add(inner.timeout.as_secs()),
+        };
+
+        let name_id_policy = match &inner.name_id_format {
+            Some(format) => format!("<samlp:NameIDPolicy Format=\"{}\" AllowCreate=\"true\"/>",
+                escape(format)),
+            None => "<samlp:NameIDPolicy AllowCreate=\"true\"/>".into(),
+        };
+
+        let request = format!("<samlp:AuthnRequest xmlns:samlp=\"{PROTOCOL}\" \
+            xmlns:saml=\"{ASSERTION}\" ID=\"{}\" Version=\"2.0\" IssueInstant=\"{}\" \
+            Destination=\"{}\" AssertionConsumerServiceURL=\"{}\" ProtocolBinding=\"{HTTP_POST}\">\
+            <saml:Issuer>{}</saml:Issuer>{name_id_policy}</samlp:AuthnRequest>",
+            pending.id, instant(OffsetDateTime::now_utc()), escape(&inner.idp.sso_url),
+            escape(&inner.acs_url), escape(&inner.entity_id));
+
+        let encoded = encode(&deflate(request.as_bytes()));
+        let separator = if inner.idp.sso_url.contains('?') { '&' } else { '?' };
+        let url = format!("{}{separator}SAMLRequest={}&RelayState={}", inner.idp.sso_url,
+            RawStr::new(&encoded).percent_encode(), pending.relay_state);
+
+        let value = serde_json::to_string(&pending).expect("pending sign-in is serializable");
+        let cookie = Cookie::build((Pending::COOKIE, value))
+            .path("/")
+            .http_only(true)
+            .secure(true)
+            .same_site(SameSite::None)
+            .max_age(time::Duration::seconds(inner.timeout.as_secs() as i64));
+
+        req.cookies().add_private(cookie);
+        Redirect::to(url)
+    }
+
+    /// Consumes the IdP's response posted in `data`, signing the user in.
+    /// Returns the redirect to the path requested at sign-in.
+    async fn acs(&self, req: &Request<'_>, data: Data<'_>) -> Result<Redirect, Status> {
+        let pending = Pending::take(req.cookies()).ok_or_else(|| {
+            warn!(name: "saml", "SAML response without a pending sign-in refused");
+            Status::BadRequest
+        })?;
+
+        let limit = req.limits().get("form").unwrap_or(Limits::FORM);
+        let body = match data.open(limit).into_bytes().await {
+            Ok(bytes) if bytes.is_complete() => bytes.into_inner(),
+            Ok(_) => return Err(Status::PayloadTooLarge),
+            Err(_) => return Err(Status::BadRequest),
+        };
+
+        let (mut response, mut relay_state) = (None, None);
+        let body = std::str::from_utf8(&body).map_err(|_| Status::BadRequest)?;
+        for field in body.split('&') {
+            let (name, value) = field.split_once('=').unwrap_or((field, ""));
+            let value = RawStr::new(value).url_decode().map_err(|_| Status::BadRequest)?;
+            match name {
+                "SAMLResponse" => response = Some(value.into_owned()),
+                "RelayState" => relay_state = Some(value.into_owned()),
+                _ => {}
+            }
+        }
+
+        if relay_state.as_deref() != Some(pending.relay_state.as_str()) {
+            warn!(name: "saml", "SAML response with mismatched RelayState refused");
+            return Err(Status::BadRequest);
+        }
+
+        let xml = response.as_deref()
+            .and_then(decode)
+            .and_then(|bytes| String::from_utf8(bytes).ok())
+            .and_then(|xml| Element::parse(&xml))
+            .ok_or(Status::BadRequest)?;
+
+        let user = self.validate(&xml, &pending, OffsetDateTime::now_utc().unix_timestamp())
+            .map_err(|reason| {
+                warn!(name: "saml", reason, idp = %self.inner.idp.entity_id,
+                    "SAML response refused");
+
+                Status::Unauthorized
+            })?;
+
+        user.login(req.cookies());
+        Ok(Redirect::to(pending.redirect))
+    }
+
+    /// Validates `response` to the sign-in `pending` at the Unix time `now`,
+    /// returning the signed-in user or the reason the response is invalid.
+    fn validate(
+        &self,
+        response: &Element,
+        pending: &Pending,
+        now: i64,
+    ) -> Result<SamlUser, &'static str> {
+        let inner = &self.inner;
+        let skew = inner.clock_skew.as_secs() as i64;
+        if !response.is(PROTOCOL, "Response") || response.attr("Version") != Some("2.0") {
+            return Err("not a SAML 2.0 response");
+        }
+
+        // Duplicate IDs enable signature wrapping attacks.
+        let mut ids: Vec<_> = response.descendants().into_iter()
+            .filter_map(|e| e.attr("ID"))
+            .collect();
+
+        let count = ids.len();
+        ids.sort_unstable();
+        ids.dedup();
+        if ids.len() != count {
+            return Err("duplicate IDs");
+        }
+
+        if response.attr("InResponseTo") != Some(pending.id.as_str()) {
+            return Err("not in response to the pending request");
+        }
+
+        if response.attr("Destination").is_some_and(|dest| dest != inner.acs_url) {
+            return Err("wrong destination");
+        }
+
+        if !self.is_issuer(response.child(ASSERTION, "Issuer"), true) {
+            return Err("wrong response issuer");
+        }
+
+        let status = response.child(PROTOCOL, "Status")
+            .and_then(|status| status.child(PROTOCOL, "StatusCode"))
+            .and_then(|code| code.attr("Value"));
+
+        if status != Some(SUCCESS) {
+            return Err("unsuccessful status");
+        }
+
+        if response.children(ASSERTION, "EncryptedAssertion").next().is_some() {
+            return Err("encrypted assertions are not supported");
+        }
+
+        let assertion = response.child(ASSERTION, "Assertion").ok_or("not one assertion")?;
+        let response_signed = self.verify_signature(response)?;
+        let assertion_signed = self.verify_signature(assertion)?;
+        if !response_signed && !assertion_signed {
+            return Err("unsigned response and assertion");
+        }
+
+        if assertion.attr("Version") != Some("2.0") {
+            return Err("not a SAML 2.0 assertion");
+        }
+
+        if !self.is_issuer(assertion.child(ASSERTION, "Issuer"), false) {
+            return Err("wrong assertion issuer");
+        }
+
+        let conditions = assertion.child(ASSERTION, "Conditions").ok_or("missing conditions")?;
+        if let Some(not_before) = conditions.attr("NotBefore") {
+            if timestamp(not_before).ok_or("invalid NotBefore")? > now + skew {
+                return Err("assertion not yet valid");
+            }
+        }
+
+        if let Some(not_on_or_after) = conditions.attr("NotOnOrAfter") {
+            if timestamp(not_on_or_after).ok_or("invalid NotOnOrAfter")? <= now - skew {
+                return Err("assertion expired");
+            }
+        }
+
+        let mut restrictions = conditions.children(ASSERTION, "AudienceRestriction").peekable();
+        if restrictions.peek().is_none() {
+            return Err("missing audience restriction");
+        }
+
+        for restriction in restrictions {
+            let mut audiences = restriction.children(ASSERTION, "Audience");
+            if !audiences.any(|audience| audience.text().trim() == inner.entity_id) {
+                return Err("wrong audience");
+            }
+        }
+
+        let subject = assertion.child(ASSERTION, "Subject").ok_or("missing subject")?;
+        let name_id = subject.child(ASSERTION, "NameID").ok_or("missing NameID")?;
+        let confirmed = subject.children(ASSERTION, "SubjectConfirmation")
+            .filter(|confirmation| confirmation.attr("Method") == Some(BEARER))
+            .filter_map(|confirmation| confirmation.child(ASSERTION, "SubjectConfirmationData"))
+            .any(|data| {
+                data.attr("Recipient") == Some(inner.acs_url.as_str())
+                    && data.attr("NotBefore").is_none()
+                    && data.attr("InResponseTo").unwrap_or(&pending.id) == pending.id
+                    && data.attr("NotOnOrAfter")
+                        .and_then(timestamp)
+                        .is_some_and(|time| time > now - skew)
+            });
+
+        if !confirmed {
+            return Err("no valid bearer subject confirmation");
+        }
+
+        let id = name_id.text().trim().to_string();
+        if id.is_empty() {
+            return Err("empty NameID");
+        }
+
+        let statement = assertion.children(ASSERTION, "AuthnStatement")
+            .next()
+            .ok_or("missing authentication statement")?;
+
+        let expires_at = match statement.attr("SessionNotOnOrAfter") {
+            Some(time) => Some(timestamp(time).ok_or("invalid SessionNotOnOrAfter")?),
+            None => None,
+        };
+
+        if expires_at.is_some_and(|time| time <= now) {
+            return Err("session expired");
+        }
+
+        let mut principal = Principal::new(id);
+        let mut attributes: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        let statements = assertion.children(ASSERTION, "AttributeStatement");
+        for attribute in statements.flat_map(|s| s.children(ASSERTION, "Attribute")) {
+            let Some(name) = attribute.attr("Name") else { continue };
+            let values: Vec<_> = attribute.children(ASSERTION, "AttributeValue")
+                .map(|value| value.text().trim().to_string())
+                .collect();
+
+            if inner.scope_attribute.as_deref() == Some(name) {
+                for scope in values.iter().filter(|value| !value.is_empty()) {
+                    principal = principal.scope(scope.as_str());
+                }
+            }
+
+            if inner.attributes.iter().any(|kept| kept == name) {
+                attributes.entry(name.to_string()).or_default().extend(values);
+            }
+        }
+
+        Ok(SamlUser {
+            principal,
+            name_id_format: name_id.attr("Format").map(String::from),
+            session_index: statement.attr("SessionIndex").map(String::from),
+            attributes,
+            authenticated_at: now.max(0) as u64,
+            expires_at: expires_at.map(|time| time.max(0) as u64),
+        })
+    }
+
+    /// Returns `true` if `issuer` is the IdP's entity ID or, if `optional`,
+    /// is missing.
+    fn is_issuer(&self, issuer: Option<&Element>, optional: bool) -> bool {
+        match issuer {
+            Some(issuer) => issuer.text().trim() == self.inner.idp.entity_id,
+            None => optional,
+        }
+    }
+
+    /// Verifies the enveloped signature of `element`, if it has one. Returns
+    /// `true` if the element is signed by the IdP and `false` if it's
+    /// unsigned.
+    fn verify_signature(&self, element: &Element) -> Result<bool, &'static str> {
+        let mut signatures = element.children(DSIG, "Signature");
+        let Some(signature) = signatures.next() else {
+            return Ok(false);
+        };
+
+        if signatures.next().is_some() {
+            return Err("multiple signatures");
+        }
+
+        let signed_info = signature.child(DSIG, "SignedInfo").ok_or("missing SignedInfo")?;
+        let c14n = signed_info.child(DSIG, "CanonicalizationMethod")
+            .filter(|method| method.attr("Algorithm") == Some(EXC_C14N))
+            .ok_or("unsupported canonicalization")?;
+
+        let method = signed_info.child(DSIG, "SignatureMethod")
+            .and_then(|method| method.attr("Algorithm"))
+            .ok_or("missing signature method")?;
+
+        let reference = signed_info.child(DSIG, "Reference").ok_or("not one reference")?;
+        let id = element.attr("ID").ok_or("signed element without ID")?;
+        if reference.attr("URI").and_then(|uri| uri.strip_prefix('#')) != Some(id) {
+            return Err("signature references another element");
+        }
+
+        let (mut enveloped, mut canonical) = (false, None);
+        let transforms = reference.child(DSIG, "Transforms").ok_or("missing transforms")?;
+        for transform in transforms.elements() {
+            match transform.attr("Algorithm") {
+                _ if !transform.is(DSIG, "Transform") => return Err("invalid transform"),
+                Some(ENVELOPED) if canonical.is_none() => enveloped = true,
+                Some(EXC_C14N) if canonical.is_none() => canonical = Some(transform),
+                _ => return Err("unsupported transform"),
+            }
+        }
+
+        let (true, Some(canonical)) = (enveloped, canonical) else {
+            return Err("unsupported transforms");
+        };
+
+        let algorithm = match reference.child(DSIG, "DigestMethod")
+            .and_then(|method| method.attr("Algorithm"))
+        {
+            Some(SHA256) => &digest::SHA256,
+            Some(SHA512) => &digest::SHA512,
+            _ => return Err("unsupported digest method"),
+        };
+
+        let expected = reference.child(DSIG, "DigestValue")
+            .and_then(|value| decode(&value.text()))
+            .ok_or("invalid digest value")?;
+
+        let content = element.canonicalize(Some(signature), &inclusive_prefixes(canonical));
+        if digest::digest(algorithm, content.as_bytes()).as_ref() != expected {
+            return Err("digest mismatch");
+        }
+
+        let sig = signature.child(DSIG, "SignatureValue")
+            .and_then(|value| decode(&value.text()))
+            .ok_or("invalid signature value")?;
+
+        let signed = signed_info.canonicalize(None, &inclusive_prefixes(c14n));
+        match self.inner.idp.keys.iter().any(|key| key.verify(method, signed.as_bytes(), &sig)) {
+            true => Ok(true),
+            false => Err("invalid signature"),
+        }
+    }
+}
+
+impl fmt::Debug for Saml {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Saml")
+            .field("entity_id", &self.inner.entity_id)
+            .field("acs_url", &self.inner.acs_url)
+            .field("idp", &self.inner.idp)
+            .field("name_id_format", &self.inner.name_id_format)
+            .field("scope_attribute", &self.inner.scope_attribute)
+            .field("attributes", &self.inner.attributes)
+            .field("timeout", &self.inner.timeout)
+            .field("clock_skew", &self.inner.clock_skew)
+            .finish()
+    }
+}
+
+impl From<Saml> for Vec<Route> {
+    fn from(saml: Saml) -> Self {
+        [
+            (Method::Get, Step::Metadata, "/metadata"),
+            (Method::Get, Step::Login, "/login"),
+            (Method::Post, Step::Acs, "/acs"),
+        ].into_iter().map(|(method, step, path)| {
+            let endpoint = Endpoint { saml: saml.clone(), step };
+            let mut route = Route::new(method, path, endpoint);
+            route.name = Some("Saml".into());
+            route
+        }).collect()
+    }
+}
+
+#[crate::async_trait]
+impl Handler for Endpoint {
+    async fn handle<'r>(&self, req: &'r Request<'_>, data: Data<'r>) -> Outcome<'r> {
+        match self.step {
+            Step::Metadata => {
+                let content_type = ContentType::new("application", "samlmetadata+xml");
+                Outcome::from(req, (content_type, self.saml.metadata()))
+            }
+            Step::Login => Outcome::from(req, self.saml.login(req)),
+            Step::Acs => match self.saml.acs(req, data).await {
+                Ok(redirect) => Outcome::from(req, redirect),
+                Err(status) => Outcome::Error(status),
+            },
+        }
+    }
+}
+
+impl Pending {
+    /// The name of the private cookie holding the sign-in in progress.
+    const COOKIE: &'static str = "rocket_saml_request";
+
+    /// Removes the request cookie, returning the sign-in it records if it
+    /// hasn't expired.
+    fn take(jar: &CookieJar<'_>) -> Option<Pending> {
+        let cookie = jar.get_private(Self::COOKIE)?;
+        jar.remove_private(Cookie::build(Self::COOKIE).path("/"));
+        let pending: Pending = serde_json::from_str(cookie.value()).ok()?;
+        (unix_now() < pending.expires_at).then_some(pending)
+    }
+}
+
+impl SamlUser {
+    /// The name of the private cookie recording the signed-in user.
+    pub const COOKIE: &'static str = "rocket_saml_user";
+
+    /// Signs the user out, if one is signed in.
+    ///
+    /// This ends the session with this server only, not with the IdP.
+    pub fn logout(jar: &CookieJar<'_>) {
+        jar.remove_private(Cookie::build(Self::COOKIE).path("/"));
+    }
+
+    /// Returns the principal identifying the user.
+    pub fn principal(&self) -> &Principal {
+        &self.principal
+    }
+
+    /// Returns the principal identifying the user, consuming `self`.
+    pub fn into_principal(self) -> Principal {
+        self.principal
+    }
+
+    /// Returns the format of the user's `NameID`, if the IdP specified one.
+    pub fn name_id_format(&self) -> Option<&str> {
+        self.name_id_format.as_deref()
+    }
+
+    /// Returns the index of the user's session with the IdP, if it issued one.
+    pub fn session_index(&self) -> Option<&str> {
+        self.session_index.as_deref()
+    }
+
+    /// Returns the first value of the kept attribute `name`, if it has one.
+    /// See [`Saml::attribute()`].
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attribute_values(name).first().map(|value| value.as_str())
+    }
+
+    /// Returns all values of the kept attribute `name`. See
+    /// [`Saml::attribute()`].
+    pub fn attribute_values(&self, name: &str) -> &[String] {
+        self.attributes.get(name).map_or(&[], |values| values.as_slice())
+    }
+
+    /// Returns the time at which the user signed in.
+    pub fn authenticated_at(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(self.authenticated_at)
+    }
+
+    /// Returns the time at which the IdP's session ends, if it set one.
+    pub fn expires_at(&self) -> Option<SystemTime> {
+        self.expires_at.map(|secs| UNIX_EPOCH + Duration::from_secs(secs))
+    }
+
+    fn login(&self, jar: &CookieJar<'_>) {
+        let session = Session {
+            name_id: self.principal.id().to_string(),
+            name_id_format: self.name_id_format.clone(),
+            session_index: self.session_index.clone(),
+            scopes: self.principal.scopes().map(String::from).collect(),
+            attributes: self.attributes.clone(),
+            authenticated_at: self.authenticated_at,
+            expires_at: self.expires_at,
+        };
+
+        let value = serde_json::to_string(&session).expect("session is serializable");
+        let cookie = Cookie::build((Self::COOKIE, value))
+            .path("/")
+            .http_only(true)
+            .same_site(SameSite::Lax);
+
+        jar.add_private(cookie);
+    }
+
+    fn from_jar(jar: &CookieJar<'_>) -> Option<SamlUser> {
+        let cookie = jar.get_private(Self::COOKIE)?;
+        let session: Session = serde_json::from_str(cookie.value()).ok()?;
+        if session.expires_at.is_some_and(|time| time <= unix_now()) {
+            return None;
+        }
+
+        let mut principal = Principal::new(session.name_id);
+        for scope in session.scopes {
+            principal = principal.scope(scope);
+        }
+
+        Some(SamlUser {
+            principal,
+            name_id_format: session.name_id_format,
+            session_index: session.session_index,
+            attributes: session.attributes,
+            authenticated_at: session.authenticated_at,
+            expires_at: session.expires_at,
+        })
+    }
+}
+
+#[crate::async_trait]
+impl<'r> FromRequest<'r> for SamlUser {
+    type Error = std::convert::Infallible;
+
+    async fn from_request(req: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        match SamlUser::from_jar(req.cookies()) {
+            Some(user) => request::Outcome::Success(user),
+            None => request::Outcome::Forward(Status::Unauthorized),
+        }
+    }
+}
+
+impl fmt::Display for CertificateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid IdP certificate: {}", self.0)
+    }
+}
+
+impl std::error::Error for CertificateError { }
+
+/// Returns the prefixes listed by the `InclusiveNamespaces` of the
+/// canonicalization `method`.
+fn inclusive_prefixes(method: &Element) -> Vec<&str> {
+    method.child(EXC_C14N, "InclusiveNamespaces")
+        .and_then(|namespaces| namespaces.attr("PrefixList"))
+        .map(|list| list.split_whitespace().collect())
+        .unwrap_or_default()
+}
+
+/// Returns `true` if `path` is a valid absolute path on this server, rather
+/// than a URL or scheme-relative reference to another.
+fn is_local(path: &str) -> bool {
+    path.starts_with('/')
+        && !path.starts_with("//")
+        && !path.starts_with("/\\")
+        && Reference::parse(path).is_ok()
+}
+
+/// Returns `data` as raw DEFLATE stored blocks, as the HTTP-Redirect binding
+/// requires. The messages are small, so they aren't compressed.
+fn deflate(data: &[u8]) -> Vec<u8> {
+    let mut output = Vec::with_capacity(data.len() + 5);
+    let mut chunks = data.chunks(u16::MAX as usize).peekable();
+    while let Some(chunk) = chunks.next() {
+        let len = chunk.len() as u16;
+        output.push(chunks.peek().is_none() as u8);
+        output.extend_from_slice(&len.to_le_bytes());
+        output.extend_from_slice(&(!len).to_le_bytes());
+        output.extend_from_slice(chunk);
+    }
+
+    output
+}
+
+/// Formats `time` as an `xs:dateTime` in UTC.
+fn instant(time: OffsetDateTime) -> String {
+    format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z", time.year(), time.month() as u8,
+        time.day(), time.hour(), time.minute(), time.second())
+}
+
+/// Parses the `xs:dateTime` `string` into a Unix timestamp.
+fn timestamp(string: &str) -> Option<i64> {
+    OffsetDateTime::parse(string.trim(), &Rfc3339).ok().map(|time| time.unix_timestamp())
+}
+
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    xml::escape(&mut escaped, value, true);
+    escaped
+}
+
+fn random_bytes(len: usize) -> Vec<u8> {
+    let mut bytes = vec![0; len];
+    rand::rngs::OsRng.fill_bytes(&mut bytes);
+    bytes
+}
+
+fn hex(bytes: &[u8]) -> String {
+    const DIGITS: &[u8] = b"0123456789abcdef";
+    bytes.iter()
+        .flat_map(|b| [DIGITS[(b >> 4) as usize] as char, DIGITS[(b & 0xf) as usize] as char])
+        .collect()
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs())
+}
+
+/// Returns the padded base64 encoding of `bytes`.
+fn encode(bytes: &[u8]) -> String {
+    let mut buf = vec![0; (bytes.len() + 2) / 3 * 4];
+    let encoded = binascii::b64encode(bytes, &mut buf).expect("sufficient buffer");
+    String::from_utf8_lossy(encoded).into_owned()
+}
+
+/// Decodes base64, ignoring whitespace.
+fn decode(string: &str) -> Option<Vec<u8>> {
+    let standard: String = string.chars().filter(|c| !c.is_ascii_whitespace()).collect();
+    if standard.len() % 4 != 0 {
+        return None;
+    }
+
+    // `binascii` requires more space than the actual output for padding.
+    let mut buf = vec![0; standard.len()];
+    binascii::b64decode(standard.as_bytes(), &mut buf).ok().map(|bytes| bytes.to_vec())
+}
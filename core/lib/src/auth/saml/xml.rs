@@ -0,0 +1,527 @@
+//! A minimal XML parser and exclusive XML canonicalizer for SAML messages.
+//!
+//! The parser accepts the subset of XML that SAML messages use: elements,
+//! attributes, namespaces, text, CDATA sections, character and predefined
+//! entity references, comments, and processing instructions. Document type
+//! declarations are refused, which rules out entity expansion attacks.
+
+use std::fmt::Write;
+
+/// The namespace bound to the `xml` prefix.
+const XML_NAMESPACE: &str = "http://www.w3.org/XML/1998/namespace";
+
+/// A parsed XML element.
+#[derive(Debug)]
+pub(super) struct Element {
+    prefix: Option<String>,
+    name: String,
+    namespace: Option<String>,
+    attributes: Vec<Attribute>,
+    /// The namespaces in scope: `(prefix, namespace)`, `None` for the default.
+    scope: Vec<(Option<String>, String)>,
+    children: Vec<Node>,
+}
+
+#[derive(Debug)]
+struct Attribute {
+    prefix: Option<String>,
+    name: String,
+    namespace: Option<String>,
+    value: String,
+}
+
+#[derive(Debug)]
+enum Node {
+    Element(Element),
+    Text(String),
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl Element {
+    /// The maximum nesting depth of parsed elements.
+    const MAX_DEPTH: usize = 64;
+
+    /// Parses the XML document `input`, returning its root element.
+    pub fn parse(input: &str) -> Option<Element> {
+        let input = input.replace("\r\n", "\n").replace('\r', "\n");
+        let mut parser = Parser { input: &input, pos: 0 };
+        parser.misc()?;
+        let root = parser.element(&[(Some("xml".into()), XML_NAMESPACE.into())], 0)?;
+        parser.misc()?;
+        parser.is_done().then_some(root)
+    }
+
+    /// Returns `true` if the element is named `name` in `namespace`.
+    pub fn is(&self, namespace: &str, name: &str) -> bool {
+        self.name == name && self.namespace.as_deref() == Some(namespace)
+    }
+
+    /// Returns the value of the unqualified attribute `name`.
+    pub fn attr(&self, name: &str) -> Option<&str> {
+        self.attributes.iter()
+            .find(|a| a.namespace.is_none() && a.name == name)
+            .map(|a| a.value.as_str())
+    }
+
+    /// Returns the child elements.
+    pub fn elements(&self) -> impl Iterator<Item = &Element> {
+        self.children.iter().filter_map(|node| match node {
+            Node::Element(element) => Some(element),
+            Node::Text(_) => None,
+        })
+    }
+
+    /// Returns the child elements named `name` in `namespace`.
+    pub fn children<'a>(
+        &'a self,
+        namespace: &'a str,
+        name: &'a str,
+    ) -> impl Iterator<Item = &'a Element> + 'a {
+        self.elements().filter(move |e| e.is(namespace, name))
+    }
+
+    /// Returns the only child element named `name` in `namespace`, if there
+    /// is exactly one.
+    pub fn child(&self, namespace: &str, name: &str) -> Option<&Element> {
+        let mut children = self.children(namespace, name);
+        let child = children.next()?;
+        children.next().is_none().then_some(child)
+    }
+
+    /// Returns the concatenated text content of the element, excluding that
+    /// of child elements.
+    pub fn text(&self) -> String {
+        self.children.iter()
+            .filter_map(|node| match node {
+                Node::Text(text) => Some(text.as_str()),
+                Node::Element(_) => None,
+            })
+            .collect()
+    }
+
+    /// Returns the element and all of its descendant elements.
+    pub fn descendants(&self) -> Vec<&Element> {
+        let mut elements = vec![self];
+        let mut i = 0;
+        while i < elements.len() {
+            let element = elements[i];
+            elements.extend(element.elements());
+            i += 1;
+        }
+
+        elements
+    }
+
+    /// Returns the exclusive canonical form, without comments, of the element
+    /// with the descendant `omit` removed, as required by the XML signature
+    /// `enveloped-signature` and `xml-exc-c14n#` transforms.
+    ///
+    /// `inclusive` lists the prefixes, `#default` for the default namespace,
+    /// that are declared as in inclusive canonicalization.
+    pub fn canonicalize(&self, omit: Option<&Element>, inclusive: &[&str]) -> String {
+        let mut output = String::new();
+        self.write_canonical(&mut output, &mut vec![], omit, inclusive);
+        output
+    }
+
+    fn lookup(&self, prefix: Option<&str>) -> Option<&str> {
+        self.scope.iter()
+            .rev()
+            .find(|(p, _)| p.as_deref() == prefix)
+            .map(|(_, namespace)| namespace.as_str())
+    }
+
+    fn write_canonical(
+        &self,
+        out: &mut String,
+        rendered: &mut Vec<(Option<String>, String)>,
+        omit: Option<&Element>,
+        inclusive: &[&str],
+    ) {
+        let mut utilized = vec![self.prefix.as_deref()];
+        utilized.extend(self.attributes.iter().filter_map(|a| a.prefix.as_deref().map(Some)));
+        for prefix in inclusive {
+            match *prefix {
+                "#default" => utilized.push(None),
+                prefix => utilized.push(Some(prefix)),
+            }
+        }
+
+        let mut declarations: Vec<(Option<&str>, &str)> = vec![];
+        for prefix in utilized {
+            if prefix == Some("xml") || declarations.iter().any(|(p, _)| *p == prefix) {
+                continue;
+            }
+
+            let namespace = self.lookup(prefix).filter(|ns| !ns.is_empty());
+            let current = rendered.iter()
+                .rev()
+                .find(|(p, _)| p.as_deref() == prefix)
+                .map(|(_, ns)| ns.as_str());
+
+            match (prefix, namespace) {
+                (None, None) if current.is_some_and(|ns| !ns.is_empty()) => {
+                    declarations.push((None, ""));
+                }
+                (_, Some(namespace)) if current != Some(namespace) => {
+                    declarations.push((prefix, namespace));
+                }
+                _ => {}
+            }
+        }
+
+        declarations.sort();
+        let mut attributes: Vec<_> = self.attributes.iter().collect();
+        attributes.sort_by(|a, b| (&a.namespace, &a.name).cmp(&(&b.namespace, &b.name)));
+
+        out.push('<');
+        write_qname(out, self.prefix.as_deref(), &self.name);
+        for (prefix, namespace) in &declarations {
+            match prefix {
+                Some(prefix) => write!(out, " xmlns:{prefix}=\"").unwrap(),
+                None => out.push_str(" xmlns=\""),
+            }
+
+            escape(out, namespace, true);
+            out.push('"');
+        }
+
+        for attribute in attributes {
+            out.push(' ');
+            write_qname(out, attribute.prefix.as_deref(), &attribute.name);
+            out.push_str("=\"");
+            escape(out, &attribute.value, true);
+            out.push('"');
+        }
+
+        out.push('>');
+        let depth = rendered.len();
+        rendered.extend(declarations.iter().map(|(p, ns)| (p.map(String::from), ns.to_string())));
+        for node in &self.children {
+            match node {
+                Node::Text(text) => escape(out, text, false),
+                Node::Element(e) if omit.is_some_and(|omit| std::ptr::eq(e, omit)) => {},
+                Node::Element(e) => e.write_canonical(out, rendered, omit, inclusive),
+            }
+        }
+
+        rendered.truncate(depth);
+        out.push_str("</");
+        write_qname(out, self.prefix.as_deref(), &self.name);
+        out.push('>');
+    }
+}
+
+impl<'a> Parser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    fn is_done(&self) -> bool {
+        self.pos == self.input.len()
+    }
+
+    fn eat(&mut self, prefix: &str) -> bool {
+        let matched = self.rest().starts_with(prefix);
+        if matched {
+            self.pos += prefix.len();
+        }
+
+        matched
+    }
+
+    fn expect(&mut self, prefix: &str) -> Option<()> {
+        self.eat(prefix).then_some(())
+    }
+
+    /// Consumes input through the next `end`, returning what precedes it.
+    fn until(&mut self, end: &str) -> Option<&'a str> {
+        let rest = self.rest();
+        let len = rest.find(end)?;
+        self.pos += len + end.len();
+        Some(&rest[..len])
+    }
+
+    fn whitespace(&mut self) -> bool {
+        let rest = self.rest();
+        let trimmed = rest.trim_start_matches([' ', '\t', '\n']);
+        self.pos += rest.len() - trimmed.len();
+        trimmed.len() != rest.len()
+    }
+
+    /// Skips whitespace, comments, processing instructions, and the XML
+    /// declaration. Fails on a document type declaration.
+    fn misc(&mut self) -> Option<()> {
+        loop {
+            self.whitespace();
+            if self.eat("<!--") {
+                self.until("-->")?;
+            } else if self.rest().starts_with("<?") {
+                self.until("?>")?;
+            } else if self.rest().starts_with("<!") {
+                return None;
+            } else {
+                return Some(());
+            }
+        }
+    }
+
+    fn name(&mut self) -> Option<(Option<String>, String)> {
+        let rest = self.rest();
+        let len = rest.find(|c: char| c.is_whitespace() || "/>=<\"'".contains(c))?;
+        let name = &rest[..len];
+        if name.is_empty() {
+            return None;
+        }
+
+        self.pos += len;
+        match name.split_once(':') {
+            Some((prefix, local)) if !prefix.is_empty() && !local.is_empty() => {
+                Some((Some(prefix.to_string()), local.to_string()))
+            }
+            Some(_) => None,
+            None => Some((None, name.to_string())),
+        }
+    }
+
+    fn element(&mut self, scope: &[(Option<String>, String)], depth: usize) -> Option<Element> {
+        if depth > Element::MAX_DEPTH {
+            return None;
+        }
+
+        self.expect("<")?;
+        let (prefix, name) = self.name()?;
+        let mut scope = scope.to_vec();
+        let mut raw_attributes = vec![];
+        let empty = loop {
+            let spaced = self.whitespace();
+            if self.eat("/>") {
+                break true;
+            } else if self.eat(">") {
+                break false;
+            } else if !spaced {
+                return None;
+            }
+
+            let (attr_prefix, attr_name) = self.name()?;
+            self.whitespace();
+            self.expect("=")?;
+            self.whitespace();
+            let quote = if self.eat("\"") { "\"" } else { self.expect("'").map(|_| "'")? };
+            let value = unescape(self.until(quote)?, true)?;
+            match (attr_prefix.as_deref(), attr_name.as_str()) {
+                (None, "xmlns") => scope.push((None, value)),
+                (Some("xmlns"), prefix) => {
+                    if value.is_empty() || prefix == "xml" || prefix == "xmlns" {
+                        return None;
+                    }
+
+                    scope.push((Some(prefix.to_string()), value));
+                }
+                _ => raw_attributes.push((attr_prefix, attr_name, value)),
+            }
+        };
+
+        let mut element = Element {
+            prefix,
+            name,
+            namespace: None,
+            attributes: Vec::with_capacity(raw_attributes.len()),
+            scope,
+            children: vec![],
+        };
+
+        element.namespace = match element.lookup(element.prefix.as_deref()) {
+            Some("") => None,
+            Some(namespace) => Some(namespace.to_string()),
+            None if element.prefix.is_some() => return None,
+            None => None,
+        };
+
+        for (prefix, name, value) in raw_attributes {
+            let namespace = match &prefix {
+                Some(prefix) => Some(element.lookup(Some(prefix))?.to_string()),
+                None => None,
+            };
+
+            let duplicate = element.attributes.iter()
+                .any(|a| a.namespace == namespace && a.name == name);
+
+            if duplicate {
+                return None;
+            }
+
+            element.attributes.push(Attribute { prefix, name, namespace, value });
+        }
+
+        if empty {
+            return Some(element);
+        }
+
+        loop {
+            if self.eat("</") {
+                let (prefix, name) = self.name()?;
+                self.whitespace();
+                self.expect(">")?;
+                return (prefix == element.prefix && name == element.name).then_some(element);
+            } else if self.eat("<!--") {
+                self.until("-->")?;
+            } else if self.eat("<![CDATA[") {
+                let text = self.until("]]>")?.to_string();
+                element.push_text(text);
+            } else if self.rest().starts_with("<?") {
+                self.until("?>")?;
+            } else if self.rest().starts_with('<') {
+                let child = self.element(&element.scope, depth + 1)?;
+                element.children.push(Node::Element(child));
+            } else {
+                let rest = self.rest();
+                let len = rest.find('<')?;
+                self.pos += len;
+                element.push_text(unescape(&rest[..len], false)?);
+            }
+        }
+    }
+}
+
+impl Element {
+    fn push_text(&mut self, text: String) {
+        match self.children.last_mut() {
+            Some(Node::Text(existing)) => existing.push_str(&text),
+            _ => self.children.push(Node::Text(text)),
+        }
+    }
+}
+
+/// Resolves the references in `raw`. In attribute values, literal whitespace
+/// is normalized to spaces.
+fn unescape(raw: &str, attribute: bool) -> Option<String> {
+    let mut string = String::with_capacity(raw.len());
+    let mut rest = raw;
+    while let Some(i) = rest.find(['&', '<', '\t', '\n']) {
+        string.push_str(&rest[..i]);
+        let c = rest[i..].chars().next()?;
+        rest = &rest[i + 1..];
+        match c {
+            '<' => return None,
+            '\t' | '\n' if attribute => string.push(' '),
+            '\t' | '\n' => string.push(c),
+            _ => {
+                let (reference, remaining) = rest.split_once(';')?;
+                rest = remaining;
+                let c = match reference {
+                    "lt" => '<',
+                    "gt" => '>',
+                    "amp" => '&',
+                    "quot" => '"',
+                    "apos" => '\'',
+                    _ => {
+                        let code = match reference.strip_prefix("#x") {
+                            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                            None => reference.strip_prefix('#')?.parse().ok()?,
+                        };
+
+                        char::from_u32(code)?
+                    }
+                };
+
+                string.push(c);
+            }
+        }
+    }
+
+    string.push_str(rest);
+    Some(string)
+}
+
+/// Writes `value` escaped for canonical XML text or, if `attribute`, for a
+/// canonical XML attribute value.
+pub(super) fn escape(out: &mut String, value: &str, attribute: bool) {
+    for c in value.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' if !attribute => out.push_str("&gt;"),
+            '"' if attribute => out.push_str("&quot;"),
+            '\t' if attribute => out.push_str("&#x9;"),
+            '\n' if attribute => out.push_str("&#xA;"),
+            '\r' => out.push_str("&#xD;"),
+            c => out.push(c),
+        }
+    }
+}
+
+fn write_qname(out: &mut String, prefix: Option<&str>, name: &str) {
+    if let Some(prefix) = prefix {
+        out.push_str(prefix);
+        out.push(':');
+    }
+
+    out.push_str(name);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Element;
+
+    #[test]
+    fn parses_namespaces_and_text() {
+        let xml = r#"<?xml version="1.0"?>
+            <!-- a comment -->
+            <a:root xmlns:a="urn:a" xmlns="urn:default" b='1 &amp; 2'>
+                <child>x &lt; y<![CDATA[ & <z>]]></child>
+                <a:child a:c="3"/>
+            </a:root>"#;
+
+        let root = Element::parse(xml).unwrap();
+        assert!(root.is("urn:a", "root"));
+        assert_eq!(root.attr("b"), Some("1 & 2"));
+
+        let children: Vec<_> = root.elements().collect();
+        assert!(children[0].is("urn:default", "child"));
+        assert_eq!(children[0].text(), "x < y & <z>");
+        assert_eq!(root.child("urn:a", "child").unwrap().attr("c"), None);
+        assert_eq!(root.descendants().len(), 3);
+    }
+
+    #[test]
+    fn refuses_malformed_documents() {
+        for xml in [
+            r#"<!DOCTYPE a [<!ENTITY b "c">]><a>&b;</a>"#,
+            "<a><b></a></b>",
+            "<a>",
+            "<a></a><b></b>",
+            "<p:a></p:a>",
+            r#"<a b="1" b="2"></a>"#,
+            "<a>&unknown;</a>",
+            "<a>text",
+        ] {
+            assert!(Element::parse(xml).is_none(), "{xml}");
+        }
+
+        let deep = "<a>".repeat(100) + &"</a>".repeat(100);
+        assert!(Element::parse(&deep).is_none());
+    }
+
+    #[test]
+    fn canonicalizes_exclusively() {
+        let xml = "<p:root xmlns:p=\"urn:p\" xmlns:q=\"urn:q\" xmlns:r=\"urn:r\">\
+            <p:child z=\"1\" q:b=\"2\" a=\"x&#9;y\"><r:leaf/>a &gt; b</p:child>\
+            <omitted/></p:root>";
+
+        let root = Element::parse(xml).unwrap();
+        let child = root.elements().next().unwrap();
+        assert_eq!(child.canonicalize(None, &[]), "<p:child xmlns:p=\"urn:p\" \
+            xmlns:q=\"urn:q\" a=\"x&#x9;y\" z=\"1\" q:b=\"2\"><r:leaf xmlns:r=\"urn:r\">\
+            </r:leaf>a &gt; b</p:child>");
+
+        let omitted = root.elements().nth(1).unwrap();
+        assert_eq!(root.canonicalize(Some(omitted), &["r"]), "<p:root xmlns:p=\"urn:p\" \
+            xmlns:r=\"urn:r\"><p:child xmlns:q=\"urn:q\" a=\"x&#x9;y\" z=\"1\" q:b=\"2\">\
+            <r:leaf></r:leaf>a &gt; b</p:child></p:root>");
+    }
+}
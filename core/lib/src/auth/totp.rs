@@ -0,0 +1,514 @@
+//! Two-factor authentication with time-based one-time passwords (TOTP).
+//!
+//! This module implements [RFC 6238] TOTP codes as used by authenticator apps:
+//!
+//!   * [`Secret`] generates and encodes the secret shared with the app.
+//!   * [`Totp::provisioning_uri()`] returns the `otpauth://` URI that apps
+//!     scan to enroll. With the `barcode` feature, render it as a QR code
+//!     with `QrCode::new(uri)`.
+//!   * [`Totp::verify()`] verifies codes, accepting codes from adjacent time
+//!     steps to allow for clock drift.
+//!   * [`recovery_codes()`] generates single-use recovery codes for users who
+//!     lose their device, and [`redeem_recovery_code()`] verifies them.
+//!   * [`StepUp`] records, in a private cookie, that a user recently verified
+//!     a second factor, and guards routes that require it.
+//!
+//! This module is only available when the `totp` feature is enabled, which
+//! also enables the `secrets` feature.
+//!
+//! # Example
+//!
+//! ```rust
+//! # #[macro_use] extern crate rocket;
+//! use std::time::Duration;
+//!
+//! use rocket::http::CookieJar;
+//! use rocket::auth::totp::{Totp, Secret, StepUp};
+//!
+//! fn user_totp() -> Totp {
+//!     /* load the user's secret, generated with `Secret::generate()` */
+//!     # let secret = Secret::generate();
+//!     Totp::new(secret)
+//! }
+//!
+//! #[get("/2fa/enroll")]
+//! fn enroll() -> String {
+//!     user_totp().provisioning_uri("Rocket", "alice@example.com")
+//! }
+//!
+//! #[post("/2fa/verify?<code>")]
+//! fn verify(code: &str, jar: &CookieJar<'_>) -> &'static str {
+//!     if !user_totp().verify(code) {
+//!         return "invalid code";
+//!     }
+//!
+//!     StepUp::grant(jar, Duration::from_secs(5 * 60));
+//!     "verified"
+//! }
+//!
+//! #[delete("/account")]
+//! fn delete_account(_step_up: StepUp) -> &'static str {
+//!     "deleted"
+//! }
+//! ```
+//!
+//! # Replay
+//!
+//! A code remains valid for its entire time step and, with drift, adjacent
+//! steps. To ensure each code is used at most once, store the step returned
+//! by [`Totp::verify_at()`] for each user and reject codes for the same or an
+//! earlier step.
+//!
+//! [RFC 6238]: https://datatracker.ietf.org/doc/html/rfc6238
+
+use std::convert::Infallible;
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use rand::RngCore;
+use ring::{digest, hmac};
+
+use crate::http::{Cookie, CookieJar, SameSite, Status};
+use crate::request::{FromRequest, Outcome, Request};
+
+/// The RFC 4648 base32 alphabet.
+const BASE32: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// A secret shared with an authenticator app.
+///
+/// Secrets are displayed and stored as base32 strings, the format apps
+/// expect when a secret is entered by hand. The `Debug` implementation
+/// doesn't reveal the secret.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::auth::totp::Secret;
+///
+/// let secret = Secret::generate();
+/// let encoded = secret.to_base32();
+/// assert_eq!(encoded.len(), 32);
+/// assert_eq!(Secret::from_base32(&encoded), Some(secret));
+///
+/// let secret = Secret::from_base32("jbsw y3dp ehpk 3pxp").unwrap();
+/// assert_eq!(secret.as_bytes(), b"Hello!\xde\xad\xbe\xef");
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct Secret(Vec<u8>);
+
+/// The HMAC algorithm used to compute codes.
+///
+/// Most authenticator apps only support [`Algorithm::Sha1`], the default.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Algorithm {
+    /// HMAC-SHA1. The default.
+    #[default]
+    Sha1,
+    /// HMAC-SHA256.
+    Sha256,
+    /// HMAC-SHA512.
+    Sha512,
+}
+
+/// A TOTP code generator and verifier for one secret.
+///
+/// By default, codes have 6 digits, change every 30 seconds, are computed
+/// with [`Algorithm::Sha1`], and are accepted for one time step before and
+/// after the current one.
+///
+/// # Example
+///
+/// ```rust
+/// use std::time::{Duration, UNIX_EPOCH};
+/// use rocket::auth::totp::{Totp, Secret};
+///
+/// let totp = Totp::new(Secret::from_bytes(b"12345678901234567890")).digits(8);
+/// let time = UNIX_EPOCH + Duration::from_secs(59);
+/// assert_eq!(totp.code_at(time), "94287082");
+/// assert_eq!(totp.verify_at("94287082", time), Some(1));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Totp {
+    secret: Secret,
+    algorithm: Algorithm,
+    digits: u32,
+    period: u64,
+    skew: u64,
+}
+
+impl Secret {
+    /// The length, in bytes, of generated secrets.
+    pub const LEN: usize = 20;
+
+    /// Generates a new random secret of [`Secret::LEN`] bytes.
+    pub fn generate() -> Secret {
+        let mut bytes = vec![0; Self::LEN];
+        rand::rngs::OsRng.fill_bytes(&mut bytes);
+        Secret(bytes)
+    }
+
+    /// Returns the secret with the raw `bytes`.
+    pub fn from_bytes(bytes: &[u8]) -> Secret {
+        Secret(bytes.to_vec())
+    }
+
+    /// Decodes a base32 secret, ignoring case, whitespace, and padding.
+    /// Returns `None` if `string` isn't valid base32 or is empty.
+    pub fn from_base32(string: &str) -> Option<Secret> {
+        let mut bytes = vec![];
+        let (mut buffer, mut bits) = (0u64, 0);
+        for c in string.bytes().filter(|c| !c.is_ascii_whitespace() && *c != b'=') {
+            let value = BASE32.iter().position(|a| *a == c.to_ascii_uppercase())?;
+            buffer = (buffer << 5) | value as u64;
+            bits += 5;
+            if bits >= 8 {
+                bits -= 8;
+                bytes.push((buffer >> bits) as u8);
+            }
+        }
+
+        (!bytes.is_empty()).then_some(Secret(bytes))
+    }
+
+    /// Returns the unpadded base32 encoding of the secret.
+    pub fn to_base32(&self) -> String {
+        base32(&self.0)
+    }
+
+    /// Returns the raw bytes of the secret.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl Algorithm {
+    /// Returns the name of the algorithm as used in provisioning URIs.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Algorithm::Sha1 => "SHA1",
+            Algorithm::Sha256 => "SHA256",
+            Algorithm::Sha512 => "SHA512",
+        }
+    }
+
+    fn hmac(&self) -> hmac::Algorithm {
+        match self {
+            Algorithm::Sha1 => hmac::HMAC_SHA1_FOR_LEGACY_USE_ONLY,
+            Algorithm::Sha256 => hmac::HMAC_SHA256,
+            Algorithm::Sha512 => hmac::HMAC_SHA512,
+        }
+    }
+}
+
+impl Totp {
+    /// Returns a `Totp` for `secret` with the default parameters.
+    pub fn new(secret: Secret) -> Totp {
+        Totp { secret, algorithm: Algorithm::Sha1, digits: 6, period: 30, skew: 1 }
+    }
+
+    /// Sets the HMAC algorithm to `algorithm`.
+    pub fn algorithm(mut self, algorithm: Algorithm) -> Self {
+        self.algorithm = algorithm;
+        self
+    }
+
+    /// Sets the number of digits in a code to `digits`, clamped between `6`
+    /// and `9`.
+    pub fn digits(mut self, digits: u32) -> Self {
+        self.digits = digits.clamp(6, 9);
+        self
+    }
+
+    /// Sets the duration of a time step, after which codes change, to
+    /// `period`, in whole seconds. Periods shorter than a second are treated
+    /// as one second.
+    pub fn period(mut self, period: Duration) -> Self {
+        self.period = period.as_secs().max(1);
+        self
+    }
+
+    /// Sets the number of time steps before and after the current one whose
+    /// codes are also accepted to `skew`. A `skew` of `0` only accepts codes
+    /// for the current step.
+    pub fn skew(mut self, skew: u64) -> Self {
+        self.skew = skew;
+        self
+    }
+
+    /// Returns the shared secret.
+    pub fn secret(&self) -> &Secret {
+        &self.secret
+    }
+
+    /// Returns the code for the current time.
+    pub fn code(&self) -> String {
+        self.code_at(SystemTime::now())
+    }
+
+    /// Returns the code for `time`.
+    pub fn code_at(&self, time: SystemTime) -> String {
+        self.code_for_step(self.step(time))
+    }
+
+    /// Returns `true` if `code` is valid for the current time, allowing for
+    /// drift. See [`Totp::verify_at()`].
+    pub fn verify(&self, code: &str) -> bool {
+        self.verify_at(code, SystemTime::now()).is_some()
+    }
+
+    /// Returns the time step `code` is valid for if it's valid for `time`,
+    /// allowing for drift, and `None` otherwise.
+    ///
+    /// Whitespace in `code` is ignored. Codes are compared in constant time.
+    pub fn verify_at(&self, code: &str, time: SystemTime) -> Option<u64> {
+        let code: String = code.chars().filter(|c| !c.is_whitespace()).collect();
+        let current = self.step(time);
+        let steps = current.saturating_sub(self.skew)..=current.saturating_add(self.skew);
+        steps.filter(|&step| constant_time_eq(&self.code_for_step(step), &code))
+            .min_by_key(|&step| step.abs_diff(current))
+    }
+
+    /// Returns the `otpauth://` URI that enrolls the secret in an
+    /// authenticator app, labeled with `issuer` and `account`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::auth::totp::{Totp, Secret};
+    ///
+    /// let totp = Totp::new(Secret::from_base32("JBSWY3DPEHPK3PXP").unwrap());
+    /// assert_eq!(totp.provisioning_uri("Rocket Inc", "alice@example.com"),
+    ///     "otpauth://totp/Rocket%20Inc:alice%40example.com?secret=JBSWY3DPEHPK3PXP\
+    ///     &issuer=Rocket%20Inc&algorithm=SHA1&digits=6&period=30");
+    /// ```
+    pub fn provisioning_uri(&self, issuer: &str, account: &str) -> String {
+        let (issuer, account) = (percent_encode(issuer), percent_encode(account));
+        format!("otpauth://totp/{issuer}:{account}?secret={}&issuer={issuer}\
+            &algorithm={}&digits={}&period={}",
+            self.secret.to_base32(), self.algorithm.as_str(), self.digits, self.period)
+    }
+
+    fn step(&self, time: SystemTime) -> u64 {
+        let secs = time.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+        secs / self.period
+    }
+
+    fn code_for_step(&self, step: u64) -> String {
+        let key = hmac::Key::new(self.algorithm.hmac(), &self.secret.0);
+        let tag = hmac::sign(&key, &step.to_be_bytes());
+        let hash = tag.as_ref();
+
+        // RFC 4226 dynamic truncation.
+        let offset = (hash[hash.len() - 1] & 0xf) as usize;
+        let value = u32::from_be_bytes([
+            hash[offset] & 0x7f, hash[offset + 1], hash[offset + 2], hash[offset + 3]
+        ]);
+
+        let code = value % 10u32.pow(self.digits);
+        format!("{code:0width$}", width = self.digits as usize)
+    }
+}
+
+/// Generates `count` random single-use recovery codes.
+///
+/// Codes are 10 lowercase base32 characters, about 50 bits of entropy, split
+/// into groups of five, like `k3m7q-xw2ab`. Show them to the user once and
+/// store only their hashes, as returned by [`hash_recovery_code()`].
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::auth::totp::{recovery_codes, hash_recovery_code, redeem_recovery_code};
+///
+/// let codes = recovery_codes(10);
+/// let mut hashes: Vec<_> = codes.iter().map(|c| hash_recovery_code(c)).collect();
+///
+/// assert!(redeem_recovery_code(&codes[3].to_uppercase(), &mut hashes));
+/// assert!(!redeem_recovery_code(&codes[3], &mut hashes));
+/// assert_eq!(hashes.len(), 9);
+/// ```
+pub fn recovery_codes(count: usize) -> Vec<String> {
+    (0..count)
+        .map(|_| {
+            let mut bytes = [0u8; 10];
+            rand::rngs::OsRng.fill_bytes(&mut bytes);
+            let chars: String = bytes.iter()
+                .map(|b| BASE32[(b & 0x1f) as usize].to_ascii_lowercase() as char)
+                .collect();
+
+            format!("{}-{}", &chars[..5], &chars[5..])
+        })
+        .collect()
+}
+
+/// Returns the hex-encoded SHA-256 hash of the recovery `code`, ignoring case,
+/// whitespace, and dashes.
+pub fn hash_recovery_code(code: &str) -> String {
+    let normalized: String = code.chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+
+    let digest = digest::digest(&digest::SHA256, normalized.as_bytes());
+    digest.as_ref().iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Redeems the recovery `code`: if its hash is in `hashes`, removes it and
+/// returns `true`. Otherwise returns `false`.
+pub fn redeem_recovery_code(code: &str, hashes: &mut Vec<String>) -> bool {
+    let hash = hash_recovery_code(code);
+    match hashes.iter().position(|h| constant_time_eq(h, &hash)) {
+        Some(i) => {
+            hashes.remove(i);
+            true
+        }
+        None => false,
+    }
+}
+
+/// A request guard for requests from users who recently verified a second
+/// factor: step-up authentication.
+///
+/// After verifying a code, call [`StepUp::grant()`] to record the
+/// verification in a private cookie for some duration. Until it expires, the
+/// `StepUp` guard succeeds. Otherwise, it forwards with a status of
+/// `401 Unauthorized`, so that a lower-ranked route or the `401` catcher can
+/// ask for a code.
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use std::time::Duration;
+///
+/// use rocket::http::CookieJar;
+/// use rocket::response::Redirect;
+/// use rocket::auth::totp::StepUp;
+///
+/// #[get("/settings/security")]
+/// fn security(step_up: StepUp) -> String {
+///     format!("verified {:?} ago", step_up.elapsed())
+/// }
+///
+/// #[get("/settings/security", rank = 2)]
+/// fn security_verify() -> Redirect {
+///     Redirect::to(uri!("/2fa"))
+/// }
+///
+/// #[post("/logout")]
+/// fn logout(jar: &CookieJar<'_>) {
+///     StepUp::revoke(jar);
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StepUp {
+    verified_at: u64,
+    expires_at: u64,
+}
+
+impl StepUp {
+    /// The name of the private cookie recording step-up authentication.
+    pub const COOKIE: &'static str = "rocket_step_up";
+
+    /// Records that the user verified a second factor now, satisfying the
+    /// `StepUp` guard for `duration`.
+    pub fn grant(jar: &CookieJar<'_>, duration: Duration) {
+        let now = unix_now();
+        let expires_at = now.saturating_add(duration.as_secs());
+        let cookie = Cookie::build((Self::COOKIE, format!("{now}:{expires_at}")))
+            .path("/")
+            .http_only(true)
+            .same_site(SameSite::Strict)
+            .max_age(time::Duration::seconds(duration.as_secs() as i64));
+
+        jar.add_private(cookie);
+    }
+
+    /// Removes the record of step-up authentication, if any.
+    pub fn revoke(jar: &CookieJar<'_>) {
+        jar.remove_private(Cookie::build(Self::COOKIE).path("/"));
+    }
+
+    /// Returns the time at which the second factor was verified.
+    pub fn verified_at(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(self.verified_at)
+    }
+
+    /// Returns the time at which the step-up authentication expires.
+    pub fn expires_at(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(self.expires_at)
+    }
+
+    /// Returns the time elapsed since the second factor was verified.
+    pub fn elapsed(&self) -> Duration {
+        Duration::from_secs(unix_now().saturating_sub(self.verified_at))
+    }
+
+    fn from_jar(jar: &CookieJar<'_>) -> Option<StepUp> {
+        let cookie = jar.get_private(Self::COOKIE)?;
+        let (verified_at, expires_at) = cookie.value().split_once(':')?;
+        let step_up = StepUp {
+            verified_at: verified_at.parse().ok()?,
+            expires_at: expires_at.parse().ok()?,
+        };
+
+        (unix_now() < step_up.expires_at).then_some(step_up)
+    }
+}
+
+#[crate::async_trait]
+impl<'r> FromRequest<'r> for StepUp {
+    type Error = Infallible;
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        match StepUp::from_jar(req.cookies()) {
+            Some(step_up) => Outcome::Success(step_up),
+            None => Outcome::Forward(Status::Unauthorized),
+        }
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Secret(..)")
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs())
+}
+
+fn base32(bytes: &[u8]) -> String {
+    let mut string = String::new();
+    let (mut buffer, mut bits) = (0u64, 0);
+    for &byte in bytes {
+        buffer = (buffer << 8) | byte as u64;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            string.push(BASE32[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+    }
+
+    if bits > 0 {
+        string.push(BASE32[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+
+    string
+}
+
+/// Percent-encodes all but unreserved characters in `string`.
+fn percent_encode(string: &str) -> String {
+    string.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len() && a.bytes().zip(b.bytes()).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
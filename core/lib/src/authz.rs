@@ -0,0 +1,259 @@
+//! Cached authorization decisions.
+//!
+//! A [`PolicyEngine`] decides whether a principal may perform an action on a
+//! resource. Because engines often consult a remote service, they are
+//! frequently a request's latency hot spot. The [`Authorizer`] caches their
+//! decisions, keyed by `(principal, action, resource)`:
+//!
+//!   * per request, so that repeated checks in guards and handlers consult the
+//!     engine at most once, and
+//!   * optionally across requests, for a configurable time-to-live.
+//!
+//! Cached decisions can be invalidated explicitly, for instance when a
+//! resource is soft-deleted or a principal's role changes. An invalidation
+//! takes effect immediately, including in requests that are in flight.
+//!
+//! # Example
+//!
+//! ```rust
+//! # #[macro_use] extern crate rocket;
+//! use std::time::Duration;
+//!
+//! use rocket::State;
+//! use rocket::authz::{Authorizer, Authz, Decision, PolicyEngine};
+//!
+//! struct Owners;
+//!
+//! #[rocket::async_trait]
+//! impl PolicyEngine for Owners {
+//!     async fn decide(&self, principal: &str, action: &str, resource: &str) -> Decision {
+//!         /* consult the policy service */
+//!         # Decision::Allow
+//!     }
+//! }
+//!
+//! #[get("/docs/<id>")]
+//! async fn read(id: &str, authz: Authz<'_>) -> Option<&'static str> {
+//!     authz.allowed("alice", "read", id).await.then_some("contents")
+//! }
+//!
+//! #[delete("/docs/<id>")]
+//! async fn delete(id: &str, authz: Authz<'_>, authorizer: &State<Authorizer>) {
+//!     if authz.allowed("alice", "delete", id).await {
+//!         /* soft-delete the document */
+//!         authorizer.invalidate_resource(id);
+//!     }
+//! }
+//!
+//! #[launch]
+//! fn rocket() -> _ {
+//!     rocket::build()
+//!         .manage(Authorizer::new(Owners).ttl(Duration::from_secs(30)))
+//!         .mount("/", routes![read, delete])
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+
+use crate::Request;
+use crate::http::Status;
+use crate::request::{FromRequest, Outcome};
+
+/// Decides whether a principal may perform an action on a resource.
+///
+/// See the [module documentation](crate::authz) for an example.
+#[crate::async_trait]
+pub trait PolicyEngine: Send + Sync + 'static {
+    /// Returns whether `principal` may perform `action` on `resource`.
+    async fn decide(&self, principal: &str, action: &str, resource: &str) -> Decision;
+}
+
+/// An authorization decision.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Decision {
+    /// The action is allowed.
+    Allow,
+    /// The action is denied.
+    Deny,
+}
+
+impl Decision {
+    /// Returns `true` if `self` is [`Decision::Allow`].
+    pub fn is_allowed(self) -> bool {
+        self == Decision::Allow
+    }
+}
+
+type Key = (String, String, String);
+
+/// Caches the decisions of a [`PolicyEngine`].
+///
+/// An `Authorizer` is typically [managed](crate::Rocket::manage()) and used
+/// via the [`Authz`] request guard. Decisions are always cached for the
+/// duration of a request. When a time-to-live is set via
+/// [`Authorizer::ttl()`], they are also cached across requests until they
+/// expire or are invalidated.
+///
+/// See the [module documentation](crate::authz) for an example.
+pub struct Authorizer {
+    engine: Box<dyn PolicyEngine>,
+    ttl: Option<Duration>,
+    cache: Mutex<HashMap<Key, (Decision, Instant)>>,
+    generation: AtomicU64,
+}
+
+/// Decisions cached for the duration of a request, tagged with the
+/// `Authorizer` generation they were made in.
+#[derive(Default)]
+struct RequestCache(Mutex<HashMap<Key, (Decision, u64)>>);
+
+impl Authorizer {
+    /// Returns an `Authorizer` for `engine` that caches decisions for the
+    /// duration of a request only.
+    pub fn new<E: PolicyEngine>(engine: E) -> Self {
+        Authorizer {
+            engine: Box::new(engine),
+            ttl: None,
+            cache: Mutex::new(HashMap::new()),
+            generation: AtomicU64::new(0),
+        }
+    }
+
+    /// Caches decisions across requests for `ttl`.
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// Returns the decision for `principal`, `action`, and `resource` in the
+    /// context of `req`, consulting the engine only if no cached decision is
+    /// available.
+    pub async fn decide(
+        &self,
+        req: &Request<'_>,
+        principal: &str,
+        action: &str,
+        resource: &str,
+    ) -> Decision {
+        let key = (principal.to_string(), action.to_string(), resource.to_string());
+        let local = req.local_cache(RequestCache::default);
+        let generation = self.generation.load(Ordering::Acquire);
+        if let Some((decision, made)) = local.0.lock().get(&key) {
+            if *made == generation {
+                return *decision;
+            }
+        }
+
+        let cached = self.ttl.and_then(|ttl| self.cache.lock().get(&key)
+            .filter(|(_, at)| at.elapsed() < ttl)
+            .map(|(decision, _)| *decision));
+
+        let decision = match cached {
+            Some(decision) => decision,
+            None => {
+                let decision = self.engine.decide(principal, action, resource).await;
+                // Don't cache a decision made before an invalidation.
+                if self.ttl.is_some() && self.generation.load(Ordering::Acquire) == generation {
+                    let mut cache = self.cache.lock();
+                    if let Some(ttl) = self.ttl {
+                        cache.retain(|_, (_, at)| at.elapsed() < ttl);
+                    }
+
+                    cache.insert(key.clone(), (decision, Instant::now()));
+                }
+
+                decision
+            }
+        };
+
+        local.0.lock().insert(key, (decision, generation));
+        decision
+    }
+
+    /// Returns `true` if the decision for `principal`, `action`, and `resource`
+    /// is [`Decision::Allow`]. See [`Authorizer::decide()`].
+    pub async fn allowed(
+        &self,
+        req: &Request<'_>,
+        principal: &str,
+        action: &str,
+        resource: &str,
+    ) -> bool {
+        self.decide(req, principal, action, resource).await.is_allowed()
+    }
+
+    /// Invalidates the cached decision for `principal`, `action`, and
+    /// `resource`.
+    pub fn invalidate(&self, principal: &str, action: &str, resource: &str) {
+        self.invalidate_where(|(p, a, r)| p == principal && a == action && r == resource);
+    }
+
+    /// Invalidates all cached decisions for `principal`.
+    pub fn invalidate_principal(&self, principal: &str) {
+        self.invalidate_where(|(p, _, _)| p == principal);
+    }
+
+    /// Invalidates all cached decisions for `resource`, as when it is deleted,
+    /// soft-deleted, or restored.
+    pub fn invalidate_resource(&self, resource: &str) {
+        self.invalidate_where(|(_, _, r)| r == resource);
+    }
+
+    /// Invalidates all cached decisions.
+    pub fn clear(&self) {
+        self.invalidate_where(|_| true);
+    }
+
+    /// Removes cross-request decisions for which `f` returns `true` and marks
+    /// all per-request decisions stale.
+    fn invalidate_where<F: Fn(&Key) -> bool>(&self, f: F) {
+        let mut cache = self.cache.lock();
+        cache.retain(|key, _| !f(key));
+        self.generation.fetch_add(1, Ordering::AcqRel);
+    }
+}
+
+/// A request guard for checking authorization with the managed [`Authorizer`].
+///
+/// The guard forwards with a status of `500 Internal Server Error` if no
+/// `Authorizer` is managed.
+///
+/// See the [module documentation](crate::authz) for an example.
+pub struct Authz<'r> {
+    authorizer: &'r Authorizer,
+    request: &'r Request<'r>,
+}
+
+impl Authz<'_> {
+    /// Returns the decision for `principal`, `action`, and `resource`. See
+    /// [`Authorizer::decide()`].
+    pub async fn decide(&self, principal: &str, action: &str, resource: &str) -> Decision {
+        self.authorizer.decide(self.request, principal, action, resource).await
+    }
+
+    /// Returns `true` if `principal` may perform `action` on `resource`. See
+    /// [`Authorizer::decide()`].
+    pub async fn allowed(&self, principal: &str, action: &str, resource: &str) -> bool {
+        self.decide(principal, action, resource).await.is_allowed()
+    }
+}
+
+#[crate::async_trait]
+impl<'r> FromRequest<'r> for Authz<'r> {
+    type Error = Infallible;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Infallible> {
+        match request.rocket().state::<Authorizer>() {
+            Some(authorizer) => Outcome::Success(Authz { authorizer, request }),
+            None => {
+                error!("`Authz` guard used but no `Authorizer` is managed");
+                Outcome::Forward(Status::InternalServerError)
+            }
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// A global allocator that counts the allocations made on each thread.
+///
+/// `Counting` forwards to another allocator, [`System`] by default, counting
+/// the number and size of allocations on the calling thread so that they can
+/// be attributed to requests by the [`Budget`](crate::budget::Budget) fairing.
+/// A reallocation counts as an allocation of the new size. Counting is cheap:
+/// two thread-local additions per allocation.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::budget::Counting;
+///
+/// #[global_allocator]
+/// static ALLOCATOR: Counting = Counting::system();
+/// ```
+///
+/// To count allocations made via another allocator:
+///
+/// ```rust
+/// use std::alloc::System;
+/// use rocket::budget::Counting;
+///
+/// # type MyAllocator = System;
+/// #[global_allocator]
+/// static ALLOCATOR: Counting<MyAllocator> = Counting::new(System);
+/// ```
+#[derive(Debug, Default)]
+pub struct Counting<A = System>(A);
+
+/// The number and total size of allocations made on a thread.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub(crate) struct Tally {
+    pub count: u64,
+    pub bytes: u64,
+}
+
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    static TALLY: Cell<Tally> = const { Cell::new(Tally { count: 0, bytes: 0 }) };
+}
+
+impl Counting<System> {
+    /// Returns a `Counting` allocator that forwards to [`System`].
+    pub const fn system() -> Self {
+        Counting(System)
+    }
+}
+
+impl<A> Counting<A> {
+    /// Returns a `Counting` allocator that forwards to `allocator`.
+    pub const fn new(allocator: A) -> Self {
+        Counting(allocator)
+    }
+
+    #[inline(always)]
+    fn record(size: usize) {
+        if !INSTALLED.load(Ordering::Relaxed) {
+            INSTALLED.store(true, Ordering::Relaxed);
+        }
+
+        // Ignore allocations made while the thread is being torn down.
+        let _ = TALLY.try_with(|tally| {
+            let Tally { count, bytes } = tally.get();
+            tally.set(Tally { count: count + 1, bytes: bytes + size as u64 });
+        });
+    }
+}
+
+unsafe impl<A: GlobalAlloc> GlobalAlloc for Counting<A> {
+    #[inline]
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        Self::record(layout.size());
+        self.0.alloc(layout)
+    }
+
+    #[inline]
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        Self::record(layout.size());
+        self.0.alloc_zeroed(layout)
+    }
+
+    #[inline]
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        Self::record(new_size);
+        self.0.realloc(ptr, layout, new_size)
+    }
+
+    #[inline]
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        self.0.dealloc(ptr, layout)
+    }
+}
+
+impl Tally {
+    /// Returns the allocations made on the current thread so far.
+    pub fn current() -> Tally {
+        TALLY.try_with(|tally| tally.get()).unwrap_or_default()
+    }
+
+    /// Returns the allocations made between `earlier` and `self`.
+    pub fn since(self, earlier: Tally) -> Tally {
+        Tally {
+            count: self.count.wrapping_sub(earlier.count),
+            bytes: self.bytes.wrapping_sub(earlier.bytes),
+        }
+    }
+}
+
+/// Returns `true` if [`Counting`] is the global allocator.
+pub(crate) fn installed() -> bool {
+    INSTALLED.load(Ordering::Relaxed)
+}
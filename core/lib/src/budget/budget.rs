@@ -0,0 +1,231 @@
+use std::fmt;
+use std::pin::Pin;
+use std::future::Future;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+use pin_project_lite::pin_project;
+use state::InitCell;
+
+use crate::{Rocket, Request, Response, Data, Build};
+use crate::data::{ByteUnit, ToByteUnit};
+use crate::fairing::{self, Fairing, Info, Kind};
+use crate::trace::Trace;
+use crate::budget::BudgetConfig;
+use crate::budget::alloc::{self, Tally};
+
+/// A [`Fairing`] that measures the [`Cost`] of requests and reports
+/// [outliers](crate::budget).
+///
+/// Limits are read from the `budget` configuration parameter at ignition. If
+/// the parameter is invalid, ignition fails. Costs are measured even if no
+/// limit is configured.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::budget::Budget;
+/// use rocket::local::blocking::Client;
+///
+/// let client = Client::debug(rocket::build().attach(Budget::new())).unwrap();
+/// client.get("/").dispatch();
+///
+/// let budget = client.rocket().fairing::<Budget>().unwrap();
+/// assert_eq!(budget.outliers(), 0);
+/// ```
+pub struct Budget {
+    config: InitCell<BudgetConfig>,
+    outliers: AtomicU64,
+}
+
+/// The wall time and allocations of a request.
+///
+/// The cost of a request is retrieved via [`Cost::of()`]. Allocations are
+/// counted as the request's handlers run; the wall time is known once the
+/// [`Budget`] fairing's response callback has run. Both are `0` if `Budget`
+/// isn't attached, and allocations are `0` if the
+/// [`Counting`](crate::budget::Counting) allocator isn't installed.
+#[derive(Default)]
+pub struct Cost {
+    start: InitCell<Instant>,
+    elapsed: AtomicU64,
+    allocations: AtomicU64,
+    allocated: AtomicU64,
+}
+
+/// Whether handler allocations are being counted.
+static METERING: AtomicBool = AtomicBool::new(false);
+
+pin_project! {
+    /// A handler future whose allocations are added to a request's [`Cost`].
+    pub(crate) struct Metered<'r, F> {
+        #[pin]
+        future: F,
+        cost: Option<&'r Cost>,
+    }
+}
+
+impl Budget {
+    /// Returns a `Budget` fairing.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::budget::Budget;
+    ///
+    /// let budget = Budget::new();
+    /// ```
+    pub fn new() -> Self {
+        Budget { config: InitCell::new(), outliers: AtomicU64::new(0) }
+    }
+
+    /// Returns the number of requests that have exceeded their budget.
+    pub fn outliers(&self) -> u64 {
+        self.outliers.load(Ordering::Relaxed)
+    }
+
+    fn exceeded(&self, cost: &Cost) -> bool {
+        let Some(config) = self.config.try_get() else { return false };
+        config.time().map_or(false, |limit| cost.elapsed() > limit)
+            || config.allocations.map_or(false, |limit| cost.allocations() > limit)
+            || config.allocated.map_or(false, |limit| cost.allocated() > limit)
+    }
+}
+
+impl Default for Budget {
+    fn default() -> Self {
+        Budget::new()
+    }
+}
+
+impl Cost {
+    /// Returns the cost of `request`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::budget::Cost;
+    ///
+    /// # let c = rocket::local::blocking::Client::debug_with(vec![]).unwrap();
+    /// # let request = c.get("/");
+    /// let cost = Cost::of(&request);
+    /// assert_eq!(cost.allocations(), 0);
+    /// ```
+    pub fn of<'r>(request: &'r Request<'_>) -> &'r Cost {
+        request.local_cache(Cost::default)
+    }
+
+    /// Returns the wall time from when request fairings ran to when response
+    /// fairings ran.
+    pub fn elapsed(&self) -> Duration {
+        Duration::from_nanos(self.elapsed.load(Ordering::Acquire))
+    }
+
+    /// Returns the number of allocations made by the request's handlers.
+    pub fn allocations(&self) -> u64 {
+        self.allocations.load(Ordering::Acquire)
+    }
+
+    /// Returns the total size of allocations made by the request's handlers.
+    pub fn allocated(&self) -> ByteUnit {
+        self.allocated.load(Ordering::Acquire).bytes()
+    }
+
+    fn add(&self, tally: Tally) {
+        self.allocations.fetch_add(tally.count, Ordering::AcqRel);
+        self.allocated.fetch_add(tally.bytes, Ordering::AcqRel);
+    }
+}
+
+/// Adds the allocations made while polling `future`, a handler of `request`,
+/// to the request's [`Cost`] if allocations are being counted.
+pub(crate) fn meter<'r, F: Future>(request: &'r Request<'_>, future: F) -> Metered<'r, F> {
+    let cost = METERING.load(Ordering::Relaxed).then(|| Cost::of(request));
+    Metered { future, cost }
+}
+
+impl<F: Future> Future for Metered<'_, F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let Some(cost) = this.cost else {
+            return this.future.poll(cx);
+        };
+
+        let before = Tally::current();
+        let output = this.future.poll(cx);
+        cost.add(Tally::current().since(before));
+        output
+    }
+}
+
+impl fmt::Debug for Cost {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Cost")
+            .field("elapsed", &self.elapsed())
+            .field("allocations", &self.allocations())
+            .field("allocated", &self.allocated())
+            .finish()
+    }
+}
+
+#[crate::async_trait]
+impl Fairing for Budget {
+    fn info(&self) -> Info {
+        Info {
+            name: "Budget",
+            kind: Kind::Ignite | Kind::Request | Kind::Response | Kind::Singleton,
+        }
+    }
+
+    async fn on_ignite(&self, rocket: Rocket<Build>) -> fairing::Result {
+        let config = match rocket.figment().contains("budget") {
+            true => match rocket.figment().extract_inner::<BudgetConfig>("budget") {
+                Ok(config) => config,
+                Err(e) => {
+                    e.trace_error();
+                    return Err(rocket);
+                }
+            },
+            false => BudgetConfig::default(),
+        };
+
+        let limits_allocations = config.allocations.is_some() || config.allocated.is_some();
+        if limits_allocations && !alloc::installed() {
+            warn!(name: "budget",
+                "allocation limits are configured but `Counting` is not the global allocator\n\
+                allocation limits will not be enforced");
+        }
+
+        METERING.store(alloc::installed(), Ordering::Relaxed);
+        self.config.set(config);
+        Ok(rocket)
+    }
+
+    async fn on_request(&self, req: &mut Request<'_>, _: &mut Data<'_>) {
+        Cost::of(req).start.set(Instant::now());
+    }
+
+    async fn on_response<'r>(&self, req: &'r Request<'_>, _: &mut Response<'r>) {
+        let cost = Cost::of(req);
+        if let Some(start) = cost.start.try_get() {
+            let elapsed = start.elapsed().as_nanos().try_into().unwrap_or(u64::MAX);
+            cost.elapsed.store(elapsed, Ordering::Release);
+        }
+
+        if self.exceeded(cost) {
+            self.outliers.fetch_add(1, Ordering::Relaxed);
+            warn!(
+                name: "budget",
+                route = req.route_template(),
+                uri = %req.uri(),
+                elapsed = ?cost.elapsed(),
+                allocations = cost.allocations(),
+                allocated = %cost.allocated(),
+                "request exceeded its budget"
+            );
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::data::ByteUnit;
+
+/// The budget configuration: the `budget` configuration parameter.
+///
+/// A request exceeding any limit that is set is an outlier. Limits that are
+/// unset are not enforced. Allocation limits are only enforced when the
+/// [`Counting`](crate::budget::Counting) allocator is installed.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::budget::BudgetConfig;
+/// use rocket::data::ToByteUnit;
+/// use rocket::figment::{Figment, providers::{Format, Toml}};
+///
+/// let figment = Figment::from(Toml::string(r#"
+///     [budget]
+///     millis = 250
+///     allocated = "16 MiB"
+/// "#));
+///
+/// let config: BudgetConfig = figment.extract_inner("budget").unwrap();
+/// assert_eq!(config.millis, Some(250));
+/// assert_eq!(config.allocations, None);
+/// assert_eq!(config.allocated, Some(16.mebibytes()));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BudgetConfig {
+    /// The wall time limit, in milliseconds.
+    ///
+    /// **default: `None`**
+    pub millis: Option<u64>,
+    /// The limit on the number of allocations.
+    ///
+    /// **default: `None`**
+    pub allocations: Option<u64>,
+    /// The limit on the total size of allocations.
+    ///
+    /// **default: `None`**
+    pub allocated: Option<ByteUnit>,
+}
+
+impl BudgetConfig {
+    /// Returns the wall time limit, if any.
+    pub fn time(&self) -> Option<Duration> {
+        self.millis.map(Duration::from_millis)
+    }
+}
@@ -0,0 +1,65 @@
+//! Per-request wall time and allocation budgets.
+//!
+//! The [`Budget`] fairing measures the [`Cost`] of every request: the wall
+//! time from when request fairings run to when response fairings run and,
+//! when the [`Counting`] allocator is installed as the global allocator, the
+//! number and total size of the allocations made while polling the request's
+//! handlers. Requests that exceed any configured limit are _outliers_: a `WARN`
+//! level `budget` event is emitted in the request's span and
+//! [`Budget::outliers()`] is incremented. The cost of every request is
+//! available to later fairings via [`Cost::of()`], for export as metrics.
+//!
+//! Measuring allocations is opt-in as it requires replacing the global
+//! allocator:
+//!
+//! ```rust
+//! use rocket::budget::Counting;
+//!
+//! #[global_allocator]
+//! static ALLOCATOR: Counting = Counting::system();
+//! ```
+//!
+//! Allocations are counted per thread and attributed to a request only while
+//! one of its handlers is being polled. Allocations made by tasks a handler
+//! spawns, including blocking tasks, are not attributed to the request.
+//!
+//! # Configuration
+//!
+//! Limits are configured via the `budget` configuration parameter, which is
+//! deserialized as a [`BudgetConfig`]:
+//!
+//! ```toml
+//! [default.budget]
+//! millis = 500
+//! allocations = 100_000
+//! allocated = "64 MiB"
+//! ```
+//!
+//! # Example
+//!
+//! ```rust
+//! # #[macro_use] extern crate rocket;
+//! use rocket::budget::{Budget, Cost};
+//! use rocket::fairing::AdHoc;
+//!
+//! #[launch]
+//! fn rocket() -> _ {
+//!     rocket::build()
+//!         .attach(Budget::new())
+//!         .attach(AdHoc::on_response("Metrics", |req, _| Box::pin(async move {
+//!             let cost = Cost::of(req);
+//!             # let record = |_, _, _| ();
+//!             record(cost.elapsed(), cost.allocations(), cost.allocated());
+//!         })))
+//! }
+//! ```
+
+mod alloc;
+mod config;
+mod budget;
+
+pub use alloc::Counting;
+pub use config::BudgetConfig;
+pub use budget::{Budget, Cost};
+
+pub(crate) use budget::meter;
@@ -0,0 +1,595 @@
+//! Caching of route responses for use as an origin-shield cache.
+//!
+//! The [`Cache`] fairing memoizes responses to `GET` requests that carry a
+//! public `Cache-Control` with a `max-age` or `s-maxage` and serves them to
+//! subsequent `GET` and `HEAD` requests for the same URI without running any
+//! route. This lets an application, a [`FileServer`] for instance, serve as a
+//! small shared cache in front of which a CDN or reverse proxy sits.
+//!
+//! Beyond freshness, the `stale-while-revalidate` and `stale-if-error`
+//! directives of [RFC 5861] are supported:
+//!
+//!   * `stale-while-revalidate=N`: For `N` seconds after a response becomes
+//!     stale, it is served while the route is dispatched again in the
+//!     background to refresh it. At most one revalidation per URI is in flight.
+//!
+//!   * `stale-if-error=N`: For `N` seconds after a response becomes stale, it
+//!     is served in place of a `5xx` response from the route.
+//!
+//! # Request Coalescing
+//!
+//! When a `GET` request misses, it leads a fetch of the URI: concurrent `GET`
+//! and `HEAD` requests for the same URI that also miss wait for the leading
+//! request's response instead of dispatching to the route themselves, then
+//! are served the stored response. This prevents a stampede of requests to an
+//! expensive route when a popular response expires. A waiting request is
+//! dispatched to the route anyway if the response isn't storable, varies with
+//! a request header the waiting request sends differently, or doesn't arrive
+//! within the [coalescing timeout](Cache::coalesce_timeout()), each waiter
+//! timing out individually.
+//!
+//! # Caching Rules
+//!
+//! A response is stored when all of the following hold:
+//!
+//!   * The request method is `GET`.
+//!   * The status is one of `200`, `203`, `204`, `300`, `301`, `308`, `404`,
+//!     `405`, `410`, `414`, or `501`.
+//!   * `Cache-Control` contains `s-maxage` or `max-age`, the former taking
+//!     precedence, and none of `no-store`, `no-cache`, or `private`.
+//!   * There is no `Set-Cookie` header and `Vary` isn't `*`.
+//!   * The body is sized and no larger than the [maximum body
+//!     size](Cache::max_body()).
+//!
+//! Responses that vary are stored once for every combination of the values of
+//! the request headers named in `Vary`. Cached responses carry an `Age` header.
+//! Because the cache fronts the application, `Cache-Control` directives in
+//! requests are ignored.
+//!
+//! A route's response can only be stored with the `Cache-Control` it is
+//! written with by the time `Cache`'s response callback runs. To add
+//! `Cache-Control` via a fairing like [`HeaderRules`], attach it _before_
+//! `Cache`. Cached responses are served by `Cache`'s dispatch callback, so
+//! fairings that refuse requests before routing, like [`Shedder`], apply to
+//! cached responses only if they're attached _before_ `Cache`.
+//!
+//! Revalidation requests are dispatched like any other request, including
+//! through request fairings, but without a remote address. With a local
+//! [`Client`](crate::local), revalidation happens before the stale response
+//! is returned.
+//!
+//! [`FileServer`]: crate::fs::FileServer
+//! [`HeaderRules`]: crate::shield::HeaderRules
+//! [`Shedder`]: crate::shed::Shedder
+//! [RFC 5861]: https://datatracker.ietf.org/doc/html/rfc5861
+//!
+//! # Example
+//!
+//! ```rust
+//! # #[macro_use] extern crate rocket;
+//! use rocket::cache::Cache;
+//! use rocket::http::Header;
+//!
+//! #[derive(Responder)]
+//! struct Cached(String, Header<'static>);
+//!
+//! #[get("/feed")]
+//! fn feed() -> Cached {
+//!     let control = "public, max-age=10, stale-while-revalidate=60, stale-if-error=600";
+//!     Cached("an expensive feed".into(), Header::new("Cache-Control", control))
+//! }
+//!
+//! #[launch]
+//! fn rocket() -> _ {
+//!     rocket::build()
+//!         .mount("/", routes![feed])
+//!         .attach(Cache::new())
+//! }
+//! ```
+
+use std::io::Cursor;
+use std::sync::Arc;
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+use tokio::sync::watch;
+
+use crate::{Request, Response};
+use crate::response;
+use crate::data::{ByteUnit, ToByteUnit};
+use crate::fairing::{Fairing, Info, Kind};
+use crate::http::{Header, Method, Status};
+use crate::http::uncased::UncasedStr;
+
+/// A [`Fairing`] that caches route responses. See the [module
+/// documentation](crate::cache) for details.
+pub struct Cache {
+    capacity: usize,
+    max_body: ByteUnit,
+    coalesce_timeout: Duration,
+    entries: Mutex<HashMap<String, Vec<Arc<Entry>>>>,
+    revalidating: Mutex<HashSet<String>>,
+    flights: Flights,
+}
+
+/// The in-flight fetches by key, each signaled when it lands.
+type Flights = Arc<Mutex<HashMap<String, watch::Receiver<()>>>>;
+
+/// A fetch of a missed URI led by a request. Requests for the same URI wait
+/// for it to land, when the response is stored or the leading request is
+/// dropped.
+struct Flight {
+    key: String,
+    flights: Flights,
+    landed: watch::Sender<()>,
+}
+
+/// A stored response.
+struct Entry {
+    status: Status,
+    headers: Vec<Header<'static>>,
+    body: Arc<[u8]>,
+    vary: Vec<(String, Option<String>)>,
+    policy: Policy,
+    stored: Instant,
+}
+
+/// The lifetimes given by a response's `Cache-Control`.
+#[derive(Debug, Copy, Clone)]
+struct Policy {
+    fresh: Duration,
+    stale_while_revalidate: Duration,
+    stale_if_error: Duration,
+}
+
+/// What `lookup()` found for a request.
+#[derive(Default)]
+struct Lookup {
+    /// A response was served from the cache.
+    hit: bool,
+    /// The served response was stale and should be revalidated.
+    revalidate: bool,
+    /// A stale response to serve if the route fails.
+    fallback: Option<Arc<Entry>>,
+    /// The fetch the request leads, if it does.
+    flight: Option<Flight>,
+}
+
+/// Marks a request dispatched to revalidate a stale response.
+struct Revalidation(bool);
+
+impl Cache {
+    /// The default maximum number of stored responses: `1024`.
+    pub const DEFAULT_CAPACITY: usize = 1024;
+
+    /// The default time a request waits for a coalesced fetch: 10 seconds.
+    pub const DEFAULT_COALESCE_TIMEOUT: Duration = Duration::from_secs(10);
+
+    /// Returns a `Cache` that stores up to [`Cache::DEFAULT_CAPACITY`]
+    /// responses with bodies of up to 1MiB each and coalesces fetches with a
+    /// timeout of [`Cache::DEFAULT_COALESCE_TIMEOUT`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::cache::Cache;
+    ///
+    /// let cache = Cache::new();
+    /// ```
+    pub fn new() -> Self {
+        Cache {
+            capacity: Self::DEFAULT_CAPACITY,
+            max_body: 1.mebibytes(),
+            coalesce_timeout: Self::DEFAULT_COALESCE_TIMEOUT,
+            entries: Mutex::new(HashMap::new()),
+            revalidating: Mutex::new(HashSet::new()),
+            flights: Flights::default(),
+        }
+    }
+
+    /// Sets the maximum number of stored responses. When full, the response
+    /// stored the longest ago is evicted first.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::cache::Cache;
+    ///
+    /// let cache = Cache::new().capacity(64);
+    /// ```
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Sets the maximum size of the body of a stored response.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::cache::Cache;
+    /// use rocket::data::ToByteUnit;
+    ///
+    /// let cache = Cache::new().max_body(64.kibibytes());
+    /// ```
+    pub fn max_body(mut self, max_body: ByteUnit) -> Self {
+        self.max_body = max_body;
+        self
+    }
+
+    /// Sets the maximum time a request that misses waits for a concurrent
+    /// request's fetch of the same URI before it is dispatched to the route
+    /// itself. A timeout of zero disables [request
+    /// coalescing](crate::cache#request-coalescing).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use rocket::cache::Cache;
+    ///
+    /// let cache = Cache::new().coalesce_timeout(Duration::from_millis(500));
+    /// let uncoalesced = Cache::new().coalesce_timeout(Duration::ZERO);
+    /// ```
+    pub fn coalesce_timeout(mut self, timeout: Duration) -> Self {
+        self.coalesce_timeout = timeout;
+        self
+    }
+
+    /// Removes all stored responses.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #[macro_use] extern crate rocket;
+    /// use rocket::Rocket;
+    /// use rocket::cache::Cache;
+    ///
+    /// #[post("/purge")]
+    /// fn purge(rocket: &Rocket<rocket::Orbit>) {
+    ///     if let Some(cache) = rocket.fairing::<Cache>() {
+    ///         cache.purge();
+    ///     }
+    /// }
+    /// ```
+    pub fn purge(&self) {
+        self.entries.lock().clear();
+    }
+
+    /// Removes stored responses that are expired or were stored at least
+    /// `ttl` ago. Returns the number removed and the total size of their
+    /// bodies.
+    #[cfg(feature = "retention")]
+    pub(crate) fn evict(&self, ttl: Duration) -> (u64, u64) {
+        let (mut count, mut bytes) = (0, 0);
+        let mut entries = self.entries.lock();
+        for variants in entries.values_mut() {
+            variants.retain(|e| {
+                let keep = !e.is_expired() && e.stored.elapsed() < ttl;
+                if !keep {
+                    count += 1;
+                    bytes += e.body.len() as u64;
+                }
+
+                keep
+            });
+        }
+
+        entries.retain(|_, v| !v.is_empty());
+        (count, bytes)
+    }
+
+    /// Returns the cached response to `req`, if one can be served, waiting for a
+    /// coalesced fetch of the URI if one is in flight.
+    async fn lookup<'r>(&self, req: &'r Request<'_>) -> Option<Response<'r>> {
+        if !matches!(req.method(), Method::Get | Method::Head) || is_revalidation(req) {
+            return None;
+        }
+
+        let key = req.uri().to_string();
+        let mut fallback = None;
+        if let Some(entry) = self.get(&key, req) {
+            let (age, policy) = (entry.stored.elapsed(), entry.policy);
+            if age < policy.fresh {
+                req.local_cache(|| Lookup { hit: true, ..Default::default() });
+                return Some(entry.response());
+            }
+
+            if age < policy.fresh + policy.stale_while_revalidate {
+                let revalidate = self.revalidating.lock().insert(key);
+                req.local_cache(|| Lookup { hit: true, revalidate, ..Default::default() });
+                return Some(entry.response());
+            }
+
+            if age < policy.fresh + policy.stale_if_error {
+                fallback = Some(entry);
+            }
+        }
+
+        // Miss: lead a fetch of the URI or wait for the one in flight to land.
+        if self.coalesce_timeout > Duration::ZERO {
+            let lead = req.method() == Method::Get;
+            if let Some(flight) = self.coalesce(&key, lead).await {
+                req.local_cache(|| Lookup { fallback, flight: Some(flight), ..Default::default() });
+                return None;
+            }
+
+            let entry = self.get(&key, req);
+            if let Some(entry) = entry.filter(|e| e.stored.elapsed() < e.policy.fresh) {
+                req.local_cache(|| Lookup { hit: true, ..Default::default() });
+                return Some(entry.response());
+            }
+        }
+
+        req.local_cache(|| Lookup { fallback, ..Default::default() });
+        None
+    }
+
+    fn get(&self, key: &str, req: &Request<'_>) -> Option<Arc<Entry>> {
+        self.entries.lock().get(key)?.iter().find(|e| e.matches(req)).cloned()
+    }
+
+    fn insert(&self, key: String, entry: Entry) {
+        let mut entries = self.entries.lock();
+        entries.values_mut().for_each(|v| v.retain(|e| !e.is_expired()));
+        entries.retain(|_, v| !v.is_empty());
+
+        let count: usize = entries.values().map(|v| v.len()).sum();
+        if count >= self.capacity {
+            let oldest = entries.iter()
+                .flat_map(|(k, v)| v.iter().enumerate().map(move |(i, e)| (k, i, e.stored)))
+                .min_by_key(|(_, _, stored)| *stored)
+                .map(|(k, i, _)| (k.clone(), i));
+
+            if let Some((k, i)) = oldest {
+                if let Some(variants) = entries.get_mut(&k) {
+                    variants.remove(i);
+                }
+            }
+
+            entries.retain(|_, v| !v.is_empty());
+        }
+
+        if self.capacity > 0 {
+            let variants = entries.entry(key).or_default();
+            variants.retain(|e| e.vary != entry.vary);
+            variants.push(Arc::new(entry));
+        }
+    }
+
+    fn remove(&self, key: &str) {
+        self.entries.lock().remove(key);
+    }
+
+    /// Leads a new fetch of `key` if none is in flight. Otherwise, waits for
+    /// the one in flight to land or time out and returns `None`.
+    async fn coalesce(&self, key: &str, lead: bool) -> Option<Flight> {
+        let mut landed = {
+            let mut flights = self.flights.lock();
+            match flights.get(key) {
+                Some(landed) => landed.clone(),
+                None if lead => {
+                    let (landed, receiver) = watch::channel(());
+                    flights.insert(key.to_string(), receiver);
+                    let flights = self.flights.clone();
+                    return Some(Flight { key: key.to_string(), flights, landed });
+                }
+                None => return None,
+            }
+        };
+
+        if tokio::time::timeout(self.coalesce_timeout, landed.changed()).await.is_err() {
+            debug!(name: "cache", key, "coalesced fetch timed out");
+        }
+
+        None
+    }
+}
+
+impl Flight {
+    /// Wakes the requests waiting for this fetch and ends it.
+    fn land(&self) {
+        let mut flights = self.flights.lock();
+        if flights.get(&self.key).map_or(false, |r| r.same_channel(&self.landed.subscribe())) {
+            flights.remove(&self.key);
+        }
+
+        self.landed.send_replace(());
+    }
+}
+
+impl Drop for Flight {
+    fn drop(&mut self) {
+        self.land();
+    }
+}
+
+impl Default for Cache {
+    fn default() -> Self {
+        Cache::new()
+    }
+}
+
+impl Policy {
+    /// Parses the `Cache-Control` of `res`, returning `None` if it doesn't
+    /// allow storing the response in a shared cache.
+    fn of(res: &Response<'_>) -> Option<Policy> {
+        let (mut max_age, mut s_maxage) = (None, None);
+        let mut policy = Policy {
+            fresh: Duration::ZERO,
+            stale_while_revalidate: Duration::ZERO,
+            stale_if_error: Duration::ZERO,
+        };
+
+        let directives = res.headers().get("Cache-Control").flat_map(|v| v.split(','));
+        for directive in directives {
+            let (name, value) = match directive.split_once('=') {
+                Some((name, value)) => (name.trim(), Some(value.trim().trim_matches('"'))),
+                None => (directive.trim(), None),
+            };
+
+            let secs = || value.and_then(|v| v.parse().ok()).map(Duration::from_secs);
+            match UncasedStr::new(name) {
+                n if n == "no-store" || n == "no-cache" || n == "private" => return None,
+                n if n == "max-age" => max_age = secs(),
+                n if n == "s-maxage" => s_maxage = secs(),
+                n if n == "stale-while-revalidate" => {
+                    policy.stale_while_revalidate = secs().unwrap_or_default();
+                }
+                n if n == "stale-if-error" => policy.stale_if_error = secs().unwrap_or_default(),
+                _ => continue,
+            }
+        }
+
+        policy.fresh = s_maxage.or(max_age)?;
+        Some(policy)
+    }
+}
+
+impl Entry {
+    /// Stores `res`, replacing its body with an equivalent one, if it's
+    /// storable. Otherwise returns `None`, leaving `res` unchanged.
+    async fn new(req: &Request<'_>, res: &mut Response<'_>, max_body: ByteUnit) -> Option<Entry> {
+        const STORABLE: &[u16] = &[200, 203, 204, 300, 301, 308, 404, 405, 410, 414, 501];
+
+        if req.method() != Method::Get || !STORABLE.contains(&res.status().code) {
+            return None;
+        }
+
+        if res.headers().contains("Set-Cookie") {
+            return None;
+        }
+
+        let policy = Policy::of(res)?;
+        let mut vary = vec![];
+        for name in res.headers().get("Vary").flat_map(|v| v.split(',')).map(|v| v.trim()) {
+            if name == "*" {
+                return None;
+            }
+
+            let values: Vec<_> = req.headers().get(name).collect();
+            vary.push((name.to_string(), (!values.is_empty()).then(|| values.join(","))));
+        }
+
+        let size = res.body_mut().size().await?;
+        if size as u64 > max_body.as_u64() {
+            return None;
+        }
+
+        let body: Arc<[u8]> = match res.body_mut().to_bytes().await {
+            Ok(bytes) => bytes.into(),
+            Err(_) => return None,
+        };
+
+        res.set_sized_body(body.len(), Cursor::new(body.clone()));
+        const UNSTORED: &[&str] = &["Content-Length", "Transfer-Encoding", "Age"];
+        let headers = res.headers().iter()
+            .filter(|h| !UNSTORED.iter().any(|n| h.name() == *n))
+            .map(|h| Header::new(h.name.as_str().to_string(), h.value.to_string()))
+            .collect();
+
+        Some(Entry { status: res.status(), headers, body, vary, policy, stored: Instant::now() })
+    }
+
+    /// Whether `req` sends the same values of the `Vary` headers.
+    fn matches(&self, req: &Request<'_>) -> bool {
+        self.vary.iter().all(|(name, value)| {
+            let values: Vec<_> = req.headers().get(name).collect();
+            match value {
+                Some(value) => values.join(",") == *value,
+                None => values.is_empty(),
+            }
+        })
+    }
+
+    /// Whether the entry can no longer be served in any case.
+    fn is_expired(&self) -> bool {
+        let stale = self.policy.stale_while_revalidate.max(self.policy.stale_if_error);
+        self.stored.elapsed() >= self.policy.fresh + stale
+    }
+
+    fn response<'r>(&self) -> Response<'r> {
+        let mut response = Response::build()
+            .status(self.status)
+            .sized_body(self.body.len(), Cursor::new(self.body.clone()))
+            .finalize();
+
+        for header in &self.headers {
+            response.adjoin_header(header.clone());
+        }
+
+        let age = self.stored.elapsed().as_secs();
+        response.set_header(Header::new("Age", age.to_string()));
+        response
+    }
+}
+
+/// Whether the response to `req` was stale and `req` should be dispatched
+/// again, marked via [`mark_revalidation()`], to refresh it.
+pub(crate) fn wants_revalidation(req: &Request<'_>) -> bool {
+    req.local_cache(Lookup::default).revalidate
+}
+
+/// Marks `req` as dispatched to revalidate a stale response.
+pub(crate) fn mark_revalidation(req: &Request<'_>) {
+    req.local_cache(|| Revalidation(true));
+}
+
+fn is_revalidation(req: &Request<'_>) -> bool {
+    req.local_cache(|| Revalidation(false)).0
+}
+
+#[crate::async_trait]
+impl Fairing for Cache {
+    fn info(&self) -> Info {
+        Info {
+            name: "Cache",
+            kind: Kind::Dispatch | Kind::Response | Kind::Singleton,
+        }
+    }
+
+    async fn on_dispatch<'r>(&self, req: &'r Request<'_>) -> Option<response::Result<'r>> {
+        self.lookup(req).await.map(Ok)
+    }
+
+    async fn on_response<'r>(&self, req: &'r Request<'_>, res: &mut Response<'r>) {
+        let lookup = req.local_cache(Lookup::default);
+        if lookup.hit {
+            return;
+        }
+
+        let key = req.uri().to_string();
+        if is_revalidation(req) {
+            self.revalidating.lock().remove(&key);
+        }
+
+        if res.status().class().is_server_error() {
+            if let Some(flight) = &lookup.flight {
+                flight.land();
+            }
+
+            if let Some(entry) = &lookup.fallback {
+                warn!(name: "cache", %key, status = res.status().code,
+                    "serving stale response in place of error");
+
+                *res = entry.response();
+            }
+
+            return;
+        }
+
+        // A successful unsafe request invalidates the stored responses for its
+        // URI, as does a revalidation that is no longer storable.
+        match Entry::new(req, res, self.max_body).await {
+            Some(entry) => self.insert(key, entry),
+            None if is_revalidation(req) || !req.method().is_safe() => self.remove(&key),
+            None => {},
+        }
+
+        // Wake the requests waiting for this one's response, stored or not.
+        if let Some(flight) = &lookup.flight {
+            flight.land();
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use crate::request::Request;
+use crate::http::Status;
+
+/// Localized messages for error responses.
+///
+/// A `Catalog` holds, for each of its locales, a [`Message`] for any number of
+/// statuses. When a `Catalog` is [managed](crate::Rocket::manage()), the
+/// built-in default catcher negotiates a locale for every error between the
+/// request's `Accept-Language` header and the catalog's locales. If there's a
+/// message for the error's status in the negotiated locale, the HTML or JSON
+/// error response uses its reason and description, is marked with the locale
+/// as its `lang`, and is sent with a `Content-Language` header. Otherwise, the
+/// built-in English messages are used.
+///
+/// User catchers can do the same via [`Request::locale()`] and
+/// [`Catalog::localize()`].
+///
+/// Locales are negotiated as in [`AcceptLanguage::negotiate()`], with the
+/// catalog's locales in the order they were first added. The first locale is
+/// thus chosen for a wildcard range.
+///
+/// [`AcceptLanguage::negotiate()`]: crate::http::AcceptLanguage::negotiate()
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::Request;
+/// use rocket::catcher::Catalog;
+/// use rocket::http::Status;
+///
+/// #[catch(410)]
+/// fn gone(req: &Request) -> String {
+///     let catalog = req.rocket().state::<Catalog>().unwrap();
+///     match catalog.localize(req, Status::Gone) {
+///         Some(message) => message.description.to_string(),
+///         None => "It's gone.".into(),
+///     }
+/// }
+///
+/// #[launch]
+/// fn rocket() -> _ {
+///     let catalog = Catalog::new()
+///         .message("de", 404, "Nicht gefunden", "Die Ressource wurde nicht gefunden.")
+///         .message("de", 410, "Verschwunden", "Die Ressource ist nicht mehr verfügbar.")
+///         .message("fr", 404, "Introuvable", "La ressource est introuvable.");
+///
+///     rocket::build()
+///         .manage(catalog)
+///         .register("/", catchers![gone])
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Catalog {
+    locales: Vec<(Cow<'static, str>, HashMap<u16, Message>)>,
+}
+
+/// The localized reason and description for an error status.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Message {
+    /// A short phrase for the status, like `Not Found`.
+    pub reason: Cow<'static, str>,
+    /// A sentence describing the error.
+    pub description: Cow<'static, str>,
+}
+
+impl Catalog {
+    /// Returns a catalog without any locales.
+    pub fn new() -> Self {
+        Catalog::default()
+    }
+
+    /// Adds the message with `reason` and `description` for the status `code`
+    /// in `locale`, replacing any existing one.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::catcher::Catalog;
+    /// use rocket::http::Status;
+    ///
+    /// let catalog = Catalog::new()
+    ///     .message("de", 404, "Nicht gefunden", "Die Ressource wurde nicht gefunden.");
+    ///
+    /// let message = catalog.get("de", Status::NotFound).unwrap();
+    /// assert_eq!(message.reason, "Nicht gefunden");
+    /// assert!(catalog.get("de", Status::Gone).is_none());
+    /// ```
+    pub fn message<L, R, D>(mut self, locale: L, code: u16, reason: R, description: D) -> Self
+        where L: Into<Cow<'static, str>>,
+              R: Into<Cow<'static, str>>,
+              D: Into<Cow<'static, str>>,
+    {
+        let locale = locale.into();
+        let message = Message { reason: reason.into(), description: description.into() };
+        match self.locales.iter_mut().find(|(l, _)| l.eq_ignore_ascii_case(&locale)) {
+            Some((_, messages)) => { messages.insert(code, message); }
+            None => self.locales.push((locale, HashMap::from([(code, message)]))),
+        }
+
+        self
+    }
+
+    /// Returns the locales of the catalog in the order they were first added.
+    pub fn locales(&self) -> impl Iterator<Item = &str> {
+        self.locales.iter().map(|(locale, _)| &**locale)
+    }
+
+    /// Returns the message for `status` in `locale`, if there is one.
+    pub fn get(&self, locale: &str, status: Status) -> Option<&Message> {
+        self.locales.iter()
+            .find(|(l, _)| l.eq_ignore_ascii_case(locale))
+            .and_then(|(_, messages)| messages.get(&status.code))
+    }
+
+    /// Returns the locale negotiated between the `Accept-Language` header of
+    /// `req` and the locales of the catalog, if any.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::catcher::Catalog;
+    /// use rocket::http::Header;
+    ///
+    /// let catalog = Catalog::new()
+    ///     .message("de", 404, "Nicht gefunden", "Die Ressource wurde nicht gefunden.")
+    ///     .message("fr", 404, "Introuvable", "La ressource est introuvable.");
+    ///
+    /// # let client = rocket::local::blocking::Client::debug_with(vec![]).unwrap();
+    /// let req = client.get("/").header(Header::new("Accept-Language", "fr-CH, de;q=0.5"));
+    /// assert_eq!(catalog.negotiate(&req), Some("fr"));
+    ///
+    /// let req = client.get("/");
+    /// assert_eq!(catalog.negotiate(&req), None);
+    /// ```
+    pub fn negotiate(&self, req: &Request<'_>) -> Option<&str> {
+        let locales: Vec<&str> = self.locales().collect();
+        req.accept_language().negotiate(&locales).copied()
+    }
+
+    /// Returns the message for `status` in the locale negotiated for `req`,
+    /// if there is one.
+    pub fn localize(&self, req: &Request<'_>, status: Status) -> Option<&Message> {
+        self.negotiate(req).and_then(|locale| self.get(locale, status))
+    }
+}
@@ -4,9 +4,10 @@ use std::io::Cursor;
 use crate::http::uri::Path;
 use crate::http::ext::IntoOwned;
 use crate::response::Response;
-use crate::request::Request;
+use crate::request::{Request, Diagnostic};
 use crate::http::{Status, ContentType, uri};
-use crate::catcher::{Handler, BoxFuture};
+use crate::catcher::{Handler, BoxFuture, Catalog};
+use crate::util::{EscapeHtml, EscapeJson};
 
 /// An error catching route.
 ///
@@ -64,6 +65,13 @@ use crate::catcher::{Handler, BoxFuture};
 /// a custom fashion. The built-in default never conflicts with any
 /// user-registered catchers.
 ///
+/// In the `debug` profile, the built-in default additionally lists the
+/// [`Diagnostic`]s of guards that forwarded or failed and, for `500` errors
+/// requested as HTML, renders a detailed page with any handler panic, the
+/// handler's name and source location, the request's headers (with
+/// credentials redacted), and the request's recent trace events. None of this
+/// is shown in any other profile.
+///
 /// # Code Generation
 ///
 /// Catchers should rarely be constructed or used directly. Instead, they are
@@ -360,9 +368,12 @@ impl fmt::Debug for Catcher {
 
 macro_rules! html_error_template {
     ($code:expr, $reason:expr, $description:expr) => (
+        html_error_template!("en", $code, $reason, $description)
+    );
+    ($lang:expr, $code:expr, $reason:expr, $description:expr) => (
         concat!(
 r#"<!DOCTYPE html>
-<html lang="en">
+<html lang=""#, $lang, r#"">
 <head>
     <meta charset="utf-8">
     <meta name="color-scheme" content="light dark">
@@ -421,7 +432,39 @@ macro_rules! default_handler_fn {
             req: &'r Request<'_>
         ) -> Response<'r> {
             let preferred = req.accept().map(|a| a.preferred());
-            let (mime, text) = if preferred.map_or(false, |a| a.is_json()) {
+            let json = preferred.map_or(false, |a| a.is_json());
+            // In debug, replace the generic 500 page with a detailed one.
+            if let Some(page) = (!json).then(|| super::debug::page(status, req)).flatten() {
+                return Response::build()
+                    .status(status)
+                    .header(ContentType::HTML)
+                    .sized_body(page.len(), Cursor::new(page))
+                    .finalize();
+            }
+
+            // Use the managed catalog's message in the negotiated locale, if any.
+            let localized = req.rocket().state::<Catalog>().and_then(|catalog| {
+                let locale = catalog.negotiate(req)?;
+                Some((locale, catalog.get(locale, status)?))
+            });
+
+            let (mime, text) = if let Some((locale, message)) = localized {
+                let (reason, description) = (&message.reason, &message.description);
+                let text = match json {
+                    true => format!(json_error_fmt_template!("{code}", "{reason}", "{description}"),
+                        code = status.code,
+                        reason = EscapeJson(reason),
+                        description = EscapeJson(description)),
+                    false => format!(html_error_template!("{lang}", "{code}", "{reason}",
+                            "{description}"),
+                        lang = EscapeHtml(locale),
+                        code = status.code,
+                        reason = EscapeHtml(reason),
+                        description = EscapeHtml(description)),
+                };
+
+                (if json { ContentType::JSON } else { ContentType::HTML }, text.into())
+            } else if json {
                 let json: Cow<'_, str> = match status.code {
                     $($code => json_error_template!($code, $reason, $description).into(),)*
                     code => format!(json_error_fmt_template!("{}", "Unknown Error",
@@ -439,7 +482,14 @@ macro_rules! default_handler_fn {
                 (ContentType::HTML, html)
             };
 
+            // In debug, show why guards forwarded or failed, if any did.
+            let text = Diagnostic::annotate(text, json, &req.diagnostics());
+
             let mut r = Response::build().status(status).header(mime).finalize();
+            if let Some((locale, _)) = localized {
+                r.set_raw_header("Content-Language", locale.to_string());
+            }
+
             match text {
                 Cow::Owned(v) => r.set_sized_body(v.len(), Cursor::new(v)),
                 Cow::Borrowed(v) => r.set_sized_body(v.len(), Cursor::new(v)),
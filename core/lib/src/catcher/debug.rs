@@ -0,0 +1,143 @@
+use std::any::Any;
+use std::fmt::Write;
+
+use parking_lot::Mutex;
+
+use crate::{Request, Config};
+use crate::http::Status;
+use crate::util::{EscapeHtml, is_sensitive_header};
+
+/// Messages of handlers that panicked while handling a request.
+#[derive(Default)]
+struct Panics(Mutex<Vec<String>>);
+
+fn is_debug(req: &Request<'_>) -> bool {
+    req.rocket().config().profile == Config::DEBUG_PROFILE
+}
+
+/// Records the panic `payload` of a handler for `req` if `req` is being
+/// handled in the `debug` profile.
+pub(crate) fn record_panic(req: &Request<'_>, payload: &(dyn Any + Send)) {
+    if !is_debug(req) {
+        return;
+    }
+
+    let message = payload.downcast_ref::<&str>().map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "[non-string panic payload]".into());
+
+    req.local_cache(Panics::default).0.lock().push(message);
+}
+
+/// Renders the debug page for `status` and `req`: the captured errors, the
+/// handler that was running, the request, and the request's recent trace
+/// events. Returns `None` unless `status` is `500` and `req` is being handled
+/// in the `debug` profile.
+pub(crate) fn page(status: Status, req: &Request<'_>) -> Option<String> {
+    if status.code != 500 || !is_debug(req) {
+        return None;
+    }
+
+    let mut errors = String::new();
+    if let Some(panics) = req.state.cache.try_get::<Panics>() {
+        for message in panics.0.lock().iter() {
+            let _ = write!(errors, "<li>handler panicked: {}</li>", EscapeHtml(message));
+        }
+    }
+
+    for diagnostic in req.diagnostics() {
+        let _ = write!(errors, "<li>{}</li>", EscapeHtml(&diagnostic.to_string()));
+    }
+
+    if errors.is_empty() {
+        errors.push_str("<li>No error was captured: the handler or its responder \
+            returned a <code>500</code> status.</li>");
+    }
+
+    let handler = match req.route() {
+        Some(route) => {
+            let location = route.location
+                .map(|(file, line, col)| format!("{file}:{line}:{col}"))
+                .unwrap_or_else(|| "unknown".into());
+
+            format!("<tr><th>name</th><td>{}</td></tr>\
+                <tr><th>route</th><td>{} {}</td></tr>\
+                <tr><th>rank</th><td>{}</td></tr>\
+                <tr><th>location</th><td><code>{}</code></td></tr>",
+                EscapeHtml(route.name.as_deref().unwrap_or("[unnamed]")),
+                route.method, EscapeHtml(&route.uri.to_string()), route.rank,
+                EscapeHtml(&location))
+        }
+        None => "<tr><td>No route was matched.</td></tr>".into(),
+    };
+
+    let mut request = format!("<tr><th>method</th><td>{}</td></tr>\
+        <tr><th>uri</th><td>{}</td></tr>\
+        <tr><th>remote</th><td>{}</td></tr>",
+        req.method(), EscapeHtml(&req.uri().to_string()),
+        EscapeHtml(&req.remote().map_or("unknown".into(), |r| r.to_string())));
+
+    for header in req.headers().iter() {
+        let redact = is_sensitive_header(header.name().as_str());
+        let value = if redact { "[redacted]" } else { header.value() };
+        let _ = write!(request, "<tr><th>{}</th><td>{}</td></tr>",
+            EscapeHtml(header.name().as_str()), EscapeHtml(value));
+    }
+
+    let (request_id, events) = recent_events();
+    let events = match events.is_empty() {
+        true => "No trace events were recorded for this request.".to_string(),
+        false => events.iter().map(|e| EscapeHtml(e).to_string()).collect::<Vec<_>>().join("\n"),
+    };
+
+    Some(format!(r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="utf-8">
+    <meta name="color-scheme" content="light dark">
+    <title>500 Internal Server Error</title>
+    <style>
+        body {{ font-family: sans-serif; margin: 2em auto; max-width: 60em; }}
+        summary {{ cursor: pointer; font-size: 1.2em; font-weight: bold; }}
+        details {{ margin: 1em 0; }}
+        th {{ text-align: left; padding-right: 1em; vertical-align: top; }}
+        td, pre {{ font-family: monospace; word-break: break-all; }}
+        pre {{ white-space: pre-wrap; }}
+    </style>
+</head>
+<body>
+    <h1>500: Internal Server Error</h1>
+    <details open>
+        <summary>Errors</summary>
+        <ol>{errors}</ol>
+    </details>
+    <details open>
+        <summary>Handler</summary>
+        <table>{handler}</table>
+    </details>
+    <details open>
+        <summary>Request</summary>
+        <table>{request}</table>
+    </details>
+    <details>
+        <summary>Trace{request_id}</summary>
+        <pre>{events}</pre>
+    </details>
+    <hr />
+    <small>Rocket: this page is only shown in the <code>debug</code> profile.</small>
+</body>
+</html>"#))
+}
+
+#[cfg(feature = "trace")]
+fn recent_events() -> (String, Vec<String>) {
+    use crate::trace::subscriber::{RequestId, RecentEvents};
+
+    let id = RequestId::current().map_or_else(String::new, |id| format!(" ({id:x})"));
+    (id, RecentEvents::current())
+}
+
+#[cfg(not(feature = "trace"))]
+fn recent_events() -> (String, Vec<String>) {
+    (String::new(), vec![])
+}
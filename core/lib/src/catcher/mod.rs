@@ -2,6 +2,11 @@
 
 mod catcher;
 mod handler;
+mod debug;
+mod catalog;
 
 pub use catcher::*;
 pub use handler::*;
+pub use catalog::{Catalog, Message};
+
+pub(crate) use debug::record_panic;
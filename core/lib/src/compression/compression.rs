@@ -0,0 +1,211 @@
+use async_compression::Level;
+use async_compression::tokio::bufread::{BrotliEncoder, GzipEncoder, ZstdEncoder};
+use state::InitCell;
+use tokio::io::BufReader;
+
+use crate::{Rocket, Request, Response, Build};
+use crate::fairing::{self, Fairing, Info, Kind};
+use crate::http::{MediaType, Status};
+use crate::trace::Trace;
+use crate::compression::{CompressionConfig, Encoding};
+
+/// The Brotli quality bodies are compressed with. The default, `11`, is meant
+/// for ahead-of-time compression and is far too slow for responses.
+const BROTLI_QUALITY: i32 = 4;
+
+/// A [`Fairing`] that [compresses](crate::compression) response bodies.
+///
+/// The configuration is read from the `compression` configuration parameter at
+/// ignition, if it is set. If it is invalid, or any of its `types` isn't a
+/// valid media type, ignition fails.
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::compression::Compression;
+/// use rocket::http::{Header, ContentType};
+/// use rocket::local::blocking::Client;
+///
+/// #[get("/")]
+/// fn index() -> (ContentType, String) {
+///     (ContentType::HTML, "<p>Hello, world!</p>".repeat(100))
+/// }
+///
+/// let rocket = rocket::build()
+///     .mount("/", routes![index])
+///     .attach(Compression::new());
+///
+/// let client = Client::debug(rocket).unwrap();
+/// let response = client.get("/")
+///     .header(Header::new("Accept-Encoding", "gzip, br;q=0.5"))
+///     .dispatch();
+///
+/// assert_eq!(response.headers().get_one("Content-Encoding"), Some("gzip"));
+/// assert_eq!(response.headers().get_one("Vary"), Some("Accept-Encoding"));
+/// ```
+#[derive(Default)]
+pub struct Compression {
+    config: InitCell<CompressionConfig>,
+    types: InitCell<Vec<MediaType>>,
+}
+
+impl Compression {
+    /// Returns a `Compression` fairing.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::compression::Compression;
+    ///
+    /// let compression = Compression::new();
+    /// ```
+    pub fn new() -> Self {
+        Compression::default()
+    }
+
+    /// Returns `true` if bodies of type `media_type` are compressed.
+    fn compresses(&self, media_type: &MediaType) -> bool {
+        // Events must reach clients as they're sent, not when a block fills.
+        if media_type.top() == "text" && media_type.sub() == "event-stream" {
+            return false;
+        }
+
+        self.types.try_get().map_or(false, |types| types.iter().any(|t| {
+            t.top() == media_type.top() && (t.sub() == "*" || t.sub() == media_type.sub())
+        }))
+    }
+}
+
+/// Returns the encoding in `encodings` the `Accept-Encoding` header values in
+/// `accept` prefer, if any is acceptable. Ties are broken by the order of
+/// `encodings`.
+fn negotiate<'a, I>(accept: I, encodings: &[Encoding]) -> Option<Encoding>
+    where I: Iterator<Item = &'a str>
+{
+    let mut qualities: Vec<(&str, f32)> = vec![];
+    for coding in accept.flat_map(|value| value.split(',')) {
+        let mut params = coding.split(';');
+        let name = params.next().unwrap_or_default().trim();
+        let quality = params
+            .filter_map(|param| param.split_once('='))
+            .find(|(key, _)| key.trim().eq_ignore_ascii_case("q"))
+            .map_or(Some(1.0), |(_, q)| q.trim().parse::<f32>().ok());
+
+        if let (false, Some(quality)) = (name.is_empty(), quality) {
+            qualities.push((name, quality));
+        }
+    }
+
+    let quality = |encoding: Encoding| {
+        let find = |name: &str| qualities.iter().find(|(n, _)| n.eq_ignore_ascii_case(name));
+        find(encoding.as_str()).or_else(|| find("*")).map_or(0.0, |(_, q)| *q)
+    };
+
+    let mut best: Option<(Encoding, f32)> = None;
+    for &encoding in encodings {
+        let quality = quality(encoding);
+        if quality > 0.0 && best.map_or(true, |(_, q)| quality > q) {
+            best = Some((encoding, quality));
+        }
+    }
+
+    best.map(|(encoding, _)| encoding)
+}
+
+#[crate::async_trait]
+impl Fairing for Compression {
+    fn info(&self) -> Info {
+        Info { name: "Compression", kind: Kind::Ignite | Kind::Response | Kind::Singleton }
+    }
+
+    async fn on_ignite(&self, rocket: Rocket<Build>) -> fairing::Result {
+        let config = match rocket.figment().contains("compression") {
+            true => match rocket.figment().extract_inner::<CompressionConfig>("compression") {
+                Ok(config) => config,
+                Err(e) => {
+                    e.trace_error();
+                    return Err(rocket);
+                }
+            },
+            false => CompressionConfig::default(),
+        };
+
+        let mut types = Vec::with_capacity(config.types.len());
+        for name in &config.types {
+            match MediaType::parse_flexible(name) {
+                Some(media_type) => types.push(media_type),
+                None => {
+                    error!(name: "compression", "invalid compression media type {name:?}");
+                    return Err(rocket);
+                }
+            }
+        }
+
+        self.types.set(types);
+        self.config.set(config);
+        Ok(rocket)
+    }
+
+    async fn on_response<'r>(&self, req: &'r Request<'_>, res: &mut Response<'r>) {
+        let Some(config) = self.config.try_get() else { return };
+        let status = res.status();
+        if res.body().is_none()
+            || status.class().is_informational()
+            || status == Status::NoContent
+            || status == Status::NotModified
+            || status == Status::PartialContent
+        {
+            return;
+        }
+
+        // Leave encoded, ranged, and untransformable responses as they are.
+        let headers = res.headers();
+        let no_transform = headers.get("Cache-Control")
+            .flat_map(|value| value.split(','))
+            .any(|directive| directive.trim().eq_ignore_ascii_case("no-transform"));
+
+        let encoded = headers.contains("Content-Encoding") || headers.contains("Content-Range");
+        if no_transform || encoded {
+            return;
+        }
+
+        match res.content_type() {
+            Some(content_type) if self.compresses(content_type.media_type()) => {},
+            _ => return,
+        }
+
+        let min_size = config.min_size.as_u64();
+        if res.body_mut().size().await.map_or(false, |size| (size as u64) < min_size) {
+            return;
+        }
+
+        // Whether the response is compressed depends on `Accept-Encoding`.
+        res.adjoin_raw_header("Vary", "Accept-Encoding");
+        let accept = req.headers().get("Accept-Encoding");
+        let Some(encoding) = negotiate(accept, &config.encodings) else { return };
+
+        let max_chunk = res.body().max_chunk_size();
+        let body = BufReader::new(res.body_mut().take());
+        match encoding {
+            Encoding::Brotli => {
+                let quality = Level::Precise(BROTLI_QUALITY);
+                res.set_streamed_body(BrotliEncoder::with_quality(body, quality));
+            }
+            Encoding::Zstd => res.set_streamed_body(ZstdEncoder::new(body)),
+            Encoding::Gzip => res.set_streamed_body(GzipEncoder::new(body)),
+        }
+
+        res.set_max_chunk_size(max_chunk);
+        res.set_raw_header("Content-Encoding", encoding.as_str());
+
+        // The compressed body is a different representation of the resource.
+        let strong_etag = res.headers().get_one("ETag")
+            .filter(|etag| !etag.starts_with("W/"))
+            .map(|etag| format!("W/{etag}"));
+
+        if let Some(etag) = strong_etag {
+            res.set_raw_header("ETag", etag);
+        }
+    }
+}
@@ -0,0 +1,111 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::data::{ByteUnit, ToByteUnit};
+
+/// A content coding a response body can be compressed with.
+///
+/// Deserializes from and serializes to the coding's name in `Accept-Encoding`
+/// and `Content-Encoding` headers: `"br"`, `"zstd"`, or `"gzip"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Encoding {
+    /// Brotli: `br`.
+    #[serde(rename = "br")]
+    Brotli,
+    /// Zstandard: `zstd`.
+    #[serde(rename = "zstd")]
+    Zstd,
+    /// Gzip: `gzip`.
+    #[serde(rename = "gzip")]
+    Gzip,
+}
+
+impl Encoding {
+    /// Returns the name of the coding as it appears in headers.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::compression::Encoding;
+    ///
+    /// assert_eq!(Encoding::Brotli.as_str(), "br");
+    /// assert_eq!(Encoding::Gzip.as_str(), "gzip");
+    /// ```
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Encoding::Brotli => "br",
+            Encoding::Zstd => "zstd",
+            Encoding::Gzip => "gzip",
+        }
+    }
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
+
+/// The [`Compression`] configuration: the `compression` configuration
+/// parameter.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::compression::{CompressionConfig, Encoding};
+/// use rocket::data::ToByteUnit;
+/// use rocket::figment::{Figment, providers::{Format, Toml}};
+///
+/// let figment = Figment::from(Toml::string(r#"
+///     [compression]
+///     encodings = ["zstd", "gzip"]
+///     types = ["text/*", "application/json"]
+///     min_size = "2KiB"
+/// "#));
+///
+/// let config: CompressionConfig = figment.extract_inner("compression").unwrap();
+/// assert_eq!(config.encodings, [Encoding::Zstd, Encoding::Gzip]);
+/// assert_eq!(config.min_size, 2.kibibytes());
+///
+/// let config = CompressionConfig::default();
+/// assert_eq!(config.encodings, [Encoding::Brotli, Encoding::Zstd, Encoding::Gzip]);
+/// assert_eq!(config.min_size, 1.kibibytes());
+/// ```
+///
+/// [`Compression`]: crate::compression::Compression
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CompressionConfig {
+    /// The encodings to compress with, in order of preference. The client's
+    /// preferences, as indicated by `q` values in `Accept-Encoding`, take
+    /// precedence; ties are broken by this order.
+    ///
+    /// **default: `["br", "zstd", "gzip"]`**
+    pub encodings: Vec<Encoding>,
+    /// The media types of the bodies to compress. A `*` subtype, as in
+    /// `text/*`, matches any subtype.
+    ///
+    /// **default: `["text/*", "application/json", "application/javascript",
+    /// "application/xml", "application/wasm", "image/svg+xml"]`**
+    pub types: Vec<String>,
+    /// The size below which bodies of known size aren't compressed.
+    ///
+    /// **default: `1KiB`**
+    pub min_size: ByteUnit,
+}
+
+impl Default for CompressionConfig {
+    fn default() -> Self {
+        let types = [
+            "text/*", "application/json", "application/javascript",
+            "application/xml", "application/wasm", "image/svg+xml",
+        ];
+
+        CompressionConfig {
+            encodings: vec![Encoding::Brotli, Encoding::Zstd, Encoding::Gzip],
+            types: types.iter().map(|t| t.to_string()).collect(),
+            min_size: 1.kibibytes(),
+        }
+    }
+}
@@ -0,0 +1,69 @@
+//! Response compression.
+//!
+//! The [`Compression`] fairing compresses response bodies with Brotli,
+//! Zstandard, or gzip, as negotiated via the request's `Accept-Encoding`
+//! header. This module is only available when the `compression` feature is
+//! enabled.
+//!
+//! # Negotiation
+//!
+//! Of the configured encodings, the one with the highest `q` value in
+//! `Accept-Encoding` is used, ties broken by the configured order of
+//! preference. A `*` coding matches any encoding not named explicitly, and a
+//! `q` value of `0` refuses an encoding. Without an acceptable encoding, the
+//! body is sent as it is.
+//!
+//! A body is only compressed if its `Content-Type` is one of the configured
+//! `types` and its size, if known, is at least `min_size`. Responses that have a
+//! `Content-Encoding` or `Content-Range` header, a `206 Partial Content`
+//! status, or a `Cache-Control: no-transform` directive are left as they are,
+//! as are `text/event-stream` responses, whose events must reach clients as
+//! they are sent. `Vary: Accept-Encoding` is added to all other responses of a
+//! compressible type and size.
+//!
+//! # Bodies
+//!
+//! Bodies are compressed as they are written, never buffered in full, and are
+//! always streamed: the compressed size isn't known in advance, so compressed
+//! responses are chunk-encoded rather than sent with a `Content-Length`. A
+//! strong `ETag` is made weak, since the compressed body is a different
+//! representation of the resource. Because compressed bodies are encoded in
+//! blocks, attach `Compression` only where the latency this adds to streamed
+//! responses is acceptable, or exclude their types.
+//!
+//! # Configuration
+//!
+//! Compression is configured via the `compression` configuration parameter,
+//! which is deserialized as a [`CompressionConfig`]:
+//!
+//! ```toml
+//! [default.compression]
+//! encodings = ["br", "gzip"]
+//! types = ["text/*", "application/json", "image/svg+xml"]
+//! min_size = "4KiB"
+//! ```
+//!
+//! # Example
+//!
+//! ```rust
+//! # #[macro_use] extern crate rocket;
+//! use rocket::compression::Compression;
+//!
+//! #[get("/")]
+//! fn index() -> &'static str {
+//!     "Hello, world!"
+//! }
+//!
+//! #[launch]
+//! fn rocket() -> _ {
+//!     rocket::build()
+//!         .mount("/", routes![index])
+//!         .attach(Compression::new())
+//! }
+//! ```
+
+mod config;
+mod compression;
+
+pub use config::{CompressionConfig, Encoding};
+pub use compression::Compression;
@@ -0,0 +1,140 @@
+use std::borrow::Cow;
+
+use serde::{Deserialize, Serialize};
+
+use crate::http::{Header, RawStr};
+
+/// Redaction and sampling of the access log: the `request` span and events of
+/// every request the server handles.
+///
+/// Values of query parameters and headers named in `redact` are replaced with
+/// `[redacted]` in logged URIs and headers. Names are compared
+/// case-insensitively, query parameter names after percent-decoding.
+///
+/// Requests whose path is under the `path` of a [`SampleRule`] are logged with
+/// probability `rate`. Requests that aren't sampled still log warnings and
+/// errors, outside of a `request` span. When more than one rule matches, the
+/// rule with the longest `path` applies. Revalidation requests dispatched by
+/// the [`Cache`](crate::cache::Cache) are never sampled out.
+///
+/// ```toml
+/// [default.access_log]
+/// redact = ["token", "Authorization", "Cookie"]
+/// sample = [
+///     { path = "/health", rate = 0.01 },
+///     { path = "/static", rate = 0.1 },
+/// ]
+/// ```
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::config::{Config, AccessLogConfig};
+/// use rocket::figment::{Figment, providers::{Format, Toml}};
+///
+/// let figment = Figment::from(Config::default()).merge(Toml::string(r#"
+///     access_log.redact = ["token", "Authorization"]
+///     access_log.sample = [{ path = "/health", rate = 0.5 }]
+/// "#));
+///
+/// let config = Config::from(figment);
+/// assert!(config.access_log.redacts("authorization"));
+/// assert_eq!(config.access_log.redact_query("token=abc&page=2"), "token=[redacted]&page=2");
+/// assert_eq!(config.access_log.sample_rate("/health/db"), 0.5);
+/// assert_eq!(config.access_log.sample_rate("/healthy"), 1.0);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AccessLogConfig {
+    /// Names of query parameters and headers whose values are redacted.
+    ///
+    /// **default: `[]`**
+    pub redact: Vec<String>,
+    /// Rules sampling requests under certain paths.
+    ///
+    /// **default: `[]`**
+    pub sample: Vec<SampleRule>,
+}
+
+/// Logs requests under `path` with probability `rate`. See
+/// [`AccessLogConfig`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SampleRule {
+    /// The path prefix the rule applies to. A rule applies to a request if its
+    /// path is equal to `path` or, segment-wise, begins with `path`.
+    pub path: String,
+    /// The probability, between `0` and `1`, that a request is logged.
+    pub rate: f64,
+}
+
+impl AccessLogConfig {
+    /// The value that replaces redacted values.
+    pub const REDACTED: &'static str = "[redacted]";
+
+    /// Returns `true` if values named `name` are redacted.
+    pub fn redacts(&self, name: &str) -> bool {
+        self.redact.iter().any(|redacted| redacted.eq_ignore_ascii_case(name))
+    }
+
+    /// Returns `query` with the values of redacted parameters replaced.
+    pub fn redact_query<'a>(&self, query: &'a str) -> Cow<'a, str> {
+        let redacts = |name: &str| self.redacts(&RawStr::new(name).url_decode_lossy());
+        if self.redact.is_empty() || !query.split('&').any(|p| redacts(param_name(p))) {
+            return Cow::Borrowed(query);
+        }
+
+        let params: Vec<_> = query.split('&')
+            .map(|param| match redacts(param_name(param)) {
+                true => Cow::Owned(format!("{}={}", param_name(param), Self::REDACTED)),
+                false => Cow::Borrowed(param),
+            })
+            .collect();
+
+        Cow::Owned(params.join("&"))
+    }
+
+    /// Returns `header` with its value replaced if it is redacted.
+    pub fn redact_header<'h>(&self, header: Header<'h>) -> Header<'h> {
+        match self.redacts(header.name().as_str()) {
+            true => Header { name: header.name, value: Cow::Borrowed(Self::REDACTED) },
+            false => header,
+        }
+    }
+
+    /// Returns the probability that a request for `path` is logged.
+    pub fn sample_rate(&self, path: &str) -> f64 {
+        self.sample.iter()
+            .filter(|rule| rule.matches(path))
+            .max_by_key(|rule| rule.path.trim_end_matches('/').len())
+            .map_or(1.0, |rule| rule.rate)
+    }
+
+    /// Returns `uri`, as logged, with redacted query parameters replaced.
+    pub(crate) fn redact_uri(&self, uri: &http::Uri) -> String {
+        match uri.query() {
+            Some(query) if !self.redact.is_empty() => {
+                let uri = uri.to_string();
+                let head = &uri[..uri.find('?').unwrap_or(uri.len())];
+                format!("{}?{}", head, self.redact_query(query))
+            }
+            _ => uri.to_string(),
+        }
+    }
+
+    /// Randomly decides whether to log a request for `path`.
+    pub(crate) fn sampled(&self, path: &str) -> bool {
+        let rate = self.sample_rate(path);
+        rate >= 1.0 || rand::random::<f64>() < rate
+    }
+}
+
+impl SampleRule {
+    fn matches(&self, path: &str) -> bool {
+        let prefix = self.path.trim_end_matches('/');
+        path.strip_prefix(prefix).map_or(false, |rest| rest.is_empty() || rest.starts_with('/'))
+    }
+}
+
+fn param_name(param: &str) -> &str {
+    param.split_once('=').map_or(param, |(name, _)| name)
+}
@@ -5,7 +5,8 @@ use serde::{Deserialize, Serialize};
 
 #[cfg(feature = "secrets")]
 use crate::config::SecretKey;
-use crate::config::{ShutdownConfig, Level, TraceFormat, Ident, CliColors};
+use crate::config::{ShutdownConfig, Level, TraceFormat, Ident, CliColors, CookieConfig};
+use crate::config::{AccessLogConfig, ScrubConfig, HostConfig};
 use crate::request::{self, Request, FromRequest};
 use crate::http::uncased::Uncased;
 use crate::data::Limits;
@@ -102,6 +103,20 @@ pub struct Config {
     /// [`"X-Forwarded-Proto"`]: https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/X-Forwarded-Proto
     #[serde(deserialize_with = "crate::config::http_header::deserialize")]
     pub proxy_proto_header: Option<Uncased<'static>>,
+    /// The hosts the application may be reached at, as validated by the
+    /// [`Host`] and [`AbsoluteUri`] request guards. **(default:
+    /// [`HostConfig::default()`])**
+    ///
+    /// [`Host`]: crate::request::Host
+    /// [`AbsoluteUri`]: crate::request::AbsoluteUri
+    pub hosts: HostConfig,
+    /// Client certificates forwarded by trusted proxies that terminate mutual
+    /// TLS. **(default: [`ForwardedCertConfig::default()`])**
+    ///
+    /// [`ForwardedCertConfig::default()`]: crate::mtls::ForwardedCertConfig
+    #[cfg(feature = "mtls")]
+    #[cfg_attr(nightly, doc(cfg(feature = "mtls")))]
+    pub forwarded_certs: crate::mtls::ForwardedCertConfig,
     /// Streaming read size limits. **(default: [`Limits::default()`])**
     pub limits: Limits,
     /// Directory to store temporary files in. **(default:
@@ -110,6 +125,11 @@ pub struct Config {
     pub temp_dir: RelativePathBuf,
     /// Keep-alive timeout in seconds; disabled when `0`. **(default: `5`)**
     pub keep_alive: u32,
+    /// Whether to meter request and response sizes for [`Usage`]. When
+    /// disabled, all [`Usage`] counters remain `0`. **(default: `false`)**
+    ///
+    /// [`Usage`]: crate::request::Usage
+    pub metering: bool,
     /// The secret key for signing and encrypting. **(default: `0`)**
     ///
     /// _**Note:** This field _always_ serializes as a 256-bit array of `0`s to
@@ -120,11 +140,19 @@ pub struct Config {
     pub secret_key: SecretKey,
     /// Graceful shutdown configuration. **(default: [`ShutdownConfig::default()`])**
     pub shutdown: ShutdownConfig,
+    /// Default cookie attributes. **(default: [`CookieConfig::default()`])**
+    pub cookies: CookieConfig,
+    /// Access log redaction and sampling. **(default:
+    /// [`AccessLogConfig::default()`])**
+    pub access_log: AccessLogConfig,
     /// Max level to log. **(default: _debug_ `info` / _release_ `error`)**
     #[serde(with = "crate::trace::level")]
     pub log_level: Option<Level>,
     /// Format to use when logging. **(default: _debug_ `pretty` / _release_ `compact`)**
     pub log_format: TraceFormat,
+    /// Rules scrubbing sensitive values from trace output. **(default:
+    /// [`ScrubConfig::default()`])**
+    pub log_scrub: ScrubConfig,
     /// Whether to use colors and emoji when logging. **(default:
     /// [`CliColors::Auto`])**
     pub cli_colors: CliColors,
@@ -187,14 +215,21 @@ impl Config {
             ident: Ident::default(),
             ip_header: Some(Uncased::from_borrowed("X-Real-IP")),
             proxy_proto_header: None,
+            hosts: HostConfig::default(),
+            #[cfg(feature = "mtls")]
+            forwarded_certs: Default::default(),
             limits: Limits::default(),
             temp_dir: std::env::temp_dir().into(),
             keep_alive: 5,
+            metering: false,
             #[cfg(feature = "secrets")]
             secret_key: SecretKey::zero(),
             shutdown: ShutdownConfig::default(),
+            cookies: CookieConfig::default(),
+            access_log: AccessLogConfig::default(),
             log_level: Some(Level::INFO),
             log_format: TraceFormat::Pretty,
+            log_scrub: ScrubConfig::default(),
             cli_colors: CliColors::Auto,
             __non_exhaustive: (),
         }
@@ -341,6 +376,9 @@ impl Config {
     /// The stringy parameter name for setting/extracting [`Config::keep_alive`].
     pub const KEEP_ALIVE: &'static str = "keep_alive";
 
+    /// The stringy parameter name for setting/extracting [`Config::metering`].
+    pub const METERING: &'static str = "metering";
+
     /// The stringy parameter name for setting/extracting [`Config::ident`].
     pub const IDENT: &'static str = "ident";
 
@@ -350,6 +388,10 @@ impl Config {
     /// The stringy parameter name for setting/extracting [`Config::proxy_proto_header`].
     pub const PROXY_PROTO_HEADER: &'static str = "proxy_proto_header";
 
+    /// The stringy parameter name for setting/extracting
+    /// [`Config::forwarded_certs`].
+    pub const FORWARDED_CERTS: &'static str = "forwarded_certs";
+
     /// The stringy parameter name for setting/extracting [`Config::limits`].
     pub const LIMITS: &'static str = "limits";
 
@@ -365,18 +407,28 @@ impl Config {
     /// The stringy parameter name for setting/extracting [`Config::log_format`].
     pub const LOG_FORMAT: &'static str = "log_format";
 
+    /// The stringy parameter name for setting/extracting [`Config::log_scrub`].
+    pub const LOG_SCRUB: &'static str = "log_scrub";
+
     /// The stringy parameter name for setting/extracting [`Config::shutdown`].
     pub const SHUTDOWN: &'static str = "shutdown";
 
     /// The stringy parameter name for setting/extracting [`Config::cli_colors`].
     pub const CLI_COLORS: &'static str = "cli_colors";
 
+    /// The stringy parameter name for setting/extracting [`Config::cookies`].
+    pub const COOKIES: &'static str = "cookies";
+
+    /// The stringy parameter name for setting/extracting [`Config::access_log`].
+    pub const ACCESS_LOG: &'static str = "access_log";
+
     /// An array of all of the stringy parameter names.
     pub const PARAMETERS: &'static [&'static str] = &[
         Self::WORKERS, Self::MAX_BLOCKING, Self::KEEP_ALIVE, Self::IDENT,
-        Self::IP_HEADER, Self::PROXY_PROTO_HEADER, Self::LIMITS,
+        Self::IP_HEADER, Self::PROXY_PROTO_HEADER, Self::FORWARDED_CERTS, Self::LIMITS,
         Self::SECRET_KEY, Self::TEMP_DIR, Self::LOG_LEVEL, Self::LOG_FORMAT,
-        Self::SHUTDOWN, Self::CLI_COLORS,
+        Self::LOG_SCRUB, Self::SHUTDOWN, Self::CLI_COLORS, Self::COOKIES,
+        Self::ACCESS_LOG, Self::METERING,
     ];
 
     /// The stringy parameter name for setting/extracting [`Config::profile`].
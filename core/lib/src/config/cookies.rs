@@ -0,0 +1,104 @@
+use serde::{Deserialize, Serialize};
+
+use crate::http::SameSite;
+
+/// Default attributes for cookies added to a [`CookieJar`].
+///
+/// The attributes are applied by [`CookieJar::add()`] and
+/// [`CookieJar::add_private()`] to cookies that don't set them explicitly.
+/// Because configuration is per-profile, the defaults can differ between, say,
+/// a `debug` profile served over HTTP and a `release` profile served over HTTPS
+/// without branching at every call site:
+///
+/// ```toml
+/// [debug.cookies]
+/// default_same_site = "lax"
+/// secure = false
+///
+/// [release.cookies]
+/// default_same_site = "strict"
+/// secure = true
+/// http_only = true
+/// ```
+///
+/// Note that browsers reject cookies with `SameSite=None` that aren't also
+/// `Secure`.
+///
+/// [`CookieJar`]: crate::http::CookieJar
+/// [`CookieJar::add()`]: crate::http::CookieJar::add()
+/// [`CookieJar::add_private()`]: crate::http::CookieJar::add_private()
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::config::{Config, CookieConfig};
+/// use rocket::http::SameSite;
+/// use rocket::figment::{Figment, providers::{Format, Toml}};
+///
+/// let figment = Figment::from(Config::default())
+///     .merge(Toml::string("cookies = { default_same_site = \"lax\" }"));
+///
+/// let config = Config::from(figment);
+/// assert_eq!(config.cookies.default_same_site, SameSite::Lax);
+/// assert_eq!(config.cookies.secure, None);
+/// assert_eq!(config.cookies.http_only, None);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CookieConfig {
+    /// The `SameSite` attribute of cookies without one: one of `"strict"`,
+    /// `"lax"`, or `"none"`.
+    ///
+    /// **default: `"strict"`**
+    #[serde(with = "same_site")]
+    pub default_same_site: SameSite,
+    /// Whether cookies without a `Secure` attribute are marked `Secure`. When
+    /// `None`, they are marked `Secure` if
+    /// [`Request::context_is_likely_secure()`] is `true`.
+    ///
+    /// **default: `None`**
+    ///
+    /// [`Request::context_is_likely_secure()`]: crate::Request::context_is_likely_secure()
+    pub secure: Option<bool>,
+    /// Whether cookies without an `HttpOnly` attribute are marked `HttpOnly`.
+    /// When `None`, only private cookies are.
+    ///
+    /// **default: `None`**
+    pub http_only: Option<bool>,
+}
+
+impl Default for CookieConfig {
+    fn default() -> Self {
+        CookieConfig {
+            default_same_site: SameSite::Strict,
+            secure: None,
+            http_only: None,
+        }
+    }
+}
+
+mod same_site {
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    use crate::http::SameSite;
+
+    pub fn serialize<S: Serializer>(same_site: &SameSite, s: S) -> Result<S::Ok, S::Error> {
+        match same_site {
+            SameSite::Strict => s.serialize_str("strict"),
+            SameSite::Lax => s.serialize_str("lax"),
+            SameSite::None => s.serialize_str("none"),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(de: D) -> Result<SameSite, D::Error> {
+        const E: &str = r#"one of "strict", "lax", or "none""#;
+
+        let value = String::deserialize(de)?;
+        match value.to_ascii_lowercase().as_str() {
+            "strict" => Ok(SameSite::Strict),
+            "lax" => Ok(SameSite::Lax),
+            "none" => Ok(SameSite::None),
+            _ => Err(de::Error::invalid_value(de::Unexpected::Str(&value), &E)),
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+
+use crate::http::uncased::Uncased;
+use crate::http::uri::Host;
+
+/// The hosts the application may be reached at.
+///
+/// The validating [`Host`](crate::request::Host) and
+/// [`AbsoluteUri`](crate::request::AbsoluteUri) request guards only succeed
+/// for requests to an allowed host, so that URIs built from them, such as
+/// links in password reset emails, can't be pointed at a host chosen by an
+/// attacker via the `Host` header. By default, no host is allowed.
+///
+/// Behind a proxy that rewrites the `Host` header, the original host is read
+/// from `forwarded_header` instead, when it's configured and present:
+///
+/// ```toml
+/// [release.hosts]
+/// allow = ["example.com", "*.example.com", "localhost:8000"]
+/// forwarded_header = "X-Forwarded-Host"
+/// ```
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::config::Config;
+/// use rocket::http::uri::Host;
+/// use rocket::figment::{Figment, providers::{Format, Toml}};
+///
+/// let figment = Figment::from(Config::default())
+///     .merge(Toml::string("hosts.allow = [\"example.com\", \"*.example.com:443\"]"));
+///
+/// let config = Config::from(figment);
+/// assert!(config.hosts.allows(&Host::parse("example.com:8000").unwrap()));
+/// assert!(config.hosts.allows(&Host::parse("API.example.com:443").unwrap()));
+/// assert!(!config.hosts.allows(&Host::parse("api.example.com").unwrap()));
+/// assert!(!config.hosts.allows(&Host::parse("example.com.evil.com").unwrap()));
+/// assert_eq!(config.hosts.forwarded_header, None);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HostConfig {
+    /// The allowed hosts, as `host` or `host:port`. A host without a port
+    /// allows any port. The host may begin with `*.`, allowing any subdomain
+    /// of the rest. Hosts are compared case-insensitively.
+    ///
+    /// **default: `[]`**
+    pub allow: Vec<String>,
+    /// The name of a header, set by a trusted proxy, containing the host the
+    /// client requested, usually `"X-Forwarded-Host"`. Deserialization
+    /// semantics are identical to those of [`Config::ip_header`].
+    ///
+    /// **default: `None`**
+    ///
+    /// [`Config::ip_header`]: crate::Config::ip_header
+    #[serde(deserialize_with = "crate::config::http_header::deserialize")]
+    pub forwarded_header: Option<Uncased<'static>>,
+}
+
+impl HostConfig {
+    /// Returns `true` if `host` is allowed.
+    pub fn allows(&self, host: &Host<'_>) -> bool {
+        let domain = host.domain().as_str();
+        self.allow.iter().any(|allowed| {
+            // A trailing `:port`, except after an unbracketed IPv6 address.
+            let (allowed_host, allowed_port) = match allowed.rsplit_once(':') {
+                Some((host, port)) if !host.contains(':') || host.ends_with(']') => {
+                    (host, Some(port))
+                }
+                _ => (allowed.as_str(), None),
+            };
+
+            let port_matches = match allowed_port {
+                Some(port) => port.parse().ok() == host.port(),
+                None => true,
+            };
+
+            let host_matches = match allowed_host.strip_prefix("*.") {
+                Some(suffix) => domain.len() > suffix.len() + 1
+                    && domain.as_bytes()[domain.len() - suffix.len() - 1] == b'.'
+                    && domain[domain.len() - suffix.len()..].eq_ignore_ascii_case(suffix),
+                None => domain.eq_ignore_ascii_case(allowed_host),
+            };
+
+            port_matches && host_matches
+        })
+    }
+}
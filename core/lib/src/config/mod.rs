@@ -115,12 +115,20 @@ mod ident;
 mod config;
 mod cli_colors;
 mod http_header;
+mod cookies;
+mod access_log;
+mod hosts;
+mod scrub;
 #[cfg(test)]
 mod tests;
 
 pub use ident::Ident;
 pub use config::Config;
 pub use cli_colors::CliColors;
+pub use cookies::CookieConfig;
+pub use access_log::{AccessLogConfig, SampleRule};
+pub use scrub::ScrubConfig;
+pub use hosts::HostConfig;
 
 pub use crate::trace::{TraceFormat, Level};
 pub use crate::shutdown::ShutdownConfig;
@@ -0,0 +1,82 @@
+use serde::{Deserialize, Serialize};
+
+/// Rules that scrub sensitive values from trace output.
+///
+/// Scrubbing is applied by Rocket's [subscriber](crate::trace::subscriber) to
+/// the fields of every span and event it formats, including the message and
+/// events emitted by applications, libraries, and third-party fairings, before
+/// they are written to the log or recorded for the debug error page.
+///
+///   * The values of fields named in `fields` are replaced with `[redacted]`.
+///     Names are compared case-insensitively to the whole field name and to
+///     its last `.`-separated segment, so `token` also scrubs `query.token`.
+///   * Every match of a regular expression in `patterns` in any field value,
+///     including messages, is replaced with `[redacted]`.
+///
+/// ```toml
+/// [default.log_scrub]
+/// fields = ["password", "token", "authorization"]
+/// patterns = ['\b\d{4}[ -]?\d{4}[ -]?\d{4}[ -]?\d{4}\b', 'Bearer [A-Za-z0-9._~+/-]+=*']
+/// ```
+///
+/// Patterns use the syntax of the [`regex`] crate. If any is invalid, the
+/// configuration fails to extract.
+///
+/// [`regex`]: https://docs.rs/regex
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::config::{Config, ScrubConfig};
+/// use rocket::figment::{Figment, providers::{Format, Toml}};
+///
+/// let figment = Figment::from(Config::default()).merge(Toml::string(r#"
+///     log_scrub.fields = ["password"]
+///     log_scrub.patterns = ['sk_live_\w+']
+/// "#));
+///
+/// let config = Config::from(figment);
+/// assert_eq!(config.log_scrub.fields, ["password"]);
+/// assert_eq!(config.log_scrub.patterns, [r"sk_live_\w+"]);
+///
+/// let figment = Figment::from(Config::default())
+///     .merge(Toml::string("log_scrub.patterns = ['(unclosed']"));
+///
+/// assert!(Config::try_from(figment).is_err());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScrubConfig {
+    /// Names of fields whose values are replaced.
+    ///
+    /// **default: `[]`**
+    pub fields: Vec<String>,
+    /// Regular expressions whose matches in field values are replaced.
+    ///
+    /// **default: `[]`**
+    #[serde(deserialize_with = "patterns")]
+    pub patterns: Vec<String>,
+}
+
+impl ScrubConfig {
+    /// The value that replaces scrubbed values.
+    pub const REDACTED: &'static str = "[redacted]";
+
+    /// Returns `true` if there are no rules to apply.
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty() && self.patterns.is_empty()
+    }
+}
+
+fn patterns<'de, D: serde::Deserializer<'de>>(de: D) -> Result<Vec<String>, D::Error> {
+    let patterns = Vec::<String>::deserialize(de)?;
+
+    #[cfg(feature = "trace")]
+    for pattern in &patterns {
+        if let Err(e) = regex::Regex::new(pattern) {
+            return Err(serde::de::Error::custom(format!("invalid pattern {pattern:?}: {e}")));
+        }
+    }
+
+    Ok(patterns)
+}
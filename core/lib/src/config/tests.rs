@@ -4,7 +4,8 @@ use tracing::Level;
 
 // use crate::log::LogLevel;
 use crate::data::{Limits, ToByteUnit};
-use crate::config::{Config, CliColors};
+use crate::config::{Config, CliColors, CookieConfig, AccessLogConfig, SampleRule, ScrubConfig};
+use crate::http::{SameSite, Header};
 
 #[test]
 fn test_figment_is_default() {
@@ -207,6 +208,147 @@ fn test_cli_colors() {
     })
 }
 
+#[test]
+fn test_cookies() {
+    figment::Jail::expect_with(|jail| {
+        jail.create_file("Rocket.toml", r#"
+                [default.cookies]
+                default_same_site = "lax"
+
+                [release.cookies]
+                default_same_site = "None"
+                secure = true
+                http_only = true
+            "#)?;
+
+        let config = Config::from(Config::figment());
+        assert_eq!(config.cookies, CookieConfig {
+            default_same_site: SameSite::Lax,
+            secure: None,
+            http_only: None,
+        });
+
+        jail.set_env("ROCKET_PROFILE", "release");
+        let config = Config::from(Config::figment());
+        assert_eq!(config.cookies, CookieConfig {
+            default_same_site: SameSite::None,
+            secure: Some(true),
+            http_only: Some(true),
+        });
+
+        jail.set_env("ROCKET_COOKIES", r#"{default_same_site="strict",secure=false}"#);
+        let config = Config::from(Config::figment());
+        assert_eq!(config.cookies, CookieConfig {
+            default_same_site: SameSite::Strict,
+            secure: Some(false),
+            http_only: None,
+        });
+
+        jail.create_file("Rocket.toml", r#"
+                [default.cookies]
+                default_same_site = "sometimes"
+            "#)?;
+
+        jail.clear_env();
+        assert!(Config::try_from(Config::figment()).is_err());
+        Ok(())
+    });
+}
+
+#[test]
+fn test_access_log() {
+    figment::Jail::expect_with(|jail| {
+        jail.create_file("Rocket.toml", r#"
+                [default.access_log]
+                redact = ["token", "Authorization"]
+                sample = [
+                    { path = "/health", rate = 0.01 },
+                    { path = "/health/db/", rate = 0.5 },
+                ]
+            "#)?;
+
+        let config = Config::from(Config::figment());
+        assert_eq!(config.access_log, AccessLogConfig {
+            redact: vec!["token".into(), "Authorization".into()],
+            sample: vec![
+                SampleRule { path: "/health".into(), rate: 0.01 },
+                SampleRule { path: "/health/db/".into(), rate: 0.5 },
+            ],
+        });
+
+        let log = &config.access_log;
+        assert_eq!(log.redact_query("page=2"), "page=2");
+        assert_eq!(log.redact_query("TOKEN=a&page=2&to%6Ben=b"),
+            "TOKEN=[redacted]&page=2&to%6Ben=[redacted]");
+        assert_eq!(log.redact_query("token&page"), "token=[redacted]&page");
+
+        let uri: http::Uri = "/a/b?page=2&token=secret".parse().unwrap();
+        assert_eq!(log.redact_uri(&uri), "/a/b?page=2&token=[redacted]");
+
+        let header = log.redact_header(Header::new("authorization", "Bearer secret"));
+        assert_eq!(header.value(), AccessLogConfig::REDACTED);
+        let header = log.redact_header(Header::new("Accept", "*/*"));
+        assert_eq!(header.value(), "*/*");
+
+        assert_eq!(log.sample_rate("/"), 1.0);
+        assert_eq!(log.sample_rate("/healthy"), 1.0);
+        assert_eq!(log.sample_rate("/health"), 0.01);
+        assert_eq!(log.sample_rate("/health/dbs"), 0.01);
+        assert_eq!(log.sample_rate("/health/db"), 0.5);
+        assert_eq!(log.sample_rate("/health/db/x"), 0.5);
+
+        jail.clear_env();
+        jail.create_file("Rocket.toml", r#"
+                [default.access_log]
+                sample = [{ path = "/health" }]
+            "#)?;
+
+        assert!(Config::try_from(Config::figment()).is_err());
+        Ok(())
+    });
+}
+
+#[test]
+#[cfg(feature = "trace")]
+fn test_log_scrub() {
+    use crate::trace::subscriber::scrub;
+
+    figment::Jail::expect_with(|jail| {
+        jail.create_file("Rocket.toml", r#"
+                [default.log_scrub]
+                fields = ["password", "Token"]
+                patterns = ['sk_live_\w+', '\d{16}']
+            "#)?;
+
+        let config = Config::from(Config::figment());
+        assert_eq!(config.log_scrub, ScrubConfig {
+            fields: vec!["password".into(), "Token".into()],
+            patterns: vec![r"sk_live_\w+".into(), r"\d{16}".into()],
+        });
+
+        scrub::install(&config.log_scrub);
+        let password = scrub::scrub("password", "hunter2").into_owned();
+        let token = scrub::scrub("query.token", "abc").into_owned();
+        let tokens = scrub::scrub("tokens", "abc").into_owned();
+        let message = scrub::scrub("message", "key sk_live_abc123, card 4242424242424242")
+            .into_owned();
+
+        scrub::install(&ScrubConfig::default());
+        assert_eq!(password, ScrubConfig::REDACTED);
+        assert_eq!(token, ScrubConfig::REDACTED);
+        assert_eq!(tokens, "abc");
+        assert_eq!(message, "key [redacted], card [redacted]");
+
+        jail.create_file("Rocket.toml", r#"
+                [default.log_scrub]
+                patterns = ['(unclosed']
+            "#)?;
+
+        assert!(Config::try_from(Config::figment()).is_err());
+        Ok(())
+    });
+}
+
 #[test]
 fn test_profiles_merge() {
     figment::Jail::expect_with(|jail| {
@@ -0,0 +1,226 @@
+//! Background consumers of event streams.
+//!
+//! Event-driven services often ingest messages from a broker, such as Kafka
+//! or NATS, in the same process that serves HTTP requests. A [`Consumer`]
+//! does just that, with its lifecycle managed by Rocket: the [`Consume`]
+//! fairing starts it in the background at liftoff, restarts it with a backoff
+//! according to a [`Retry`] policy when it fails, and lets it drain when
+//! shutdown is triggered.
+//!
+//! A consumer receives the instance of [`Rocket<Orbit>`], for access to
+//! managed state and configuration, and a [`Shutdown`] future that resolves
+//! when shutdown is triggered. It's expected to consume messages until either
+//! its stream ends, when it returns `Ok(())`, shutdown is triggered, when it
+//! should finish processing in-flight messages and return, or it fails, when
+//! it returns an `Err`. A consumer that hasn't returned by the end of the
+//! [grace period](crate::config::ShutdownConfig::grace) after shutdown is
+//! triggered is cancelled.
+//!
+//! A failed consumer is restarted after each delay in the [`Retry`] policy.
+//! Once the policy is exhausted, the consumer is abandoned; the server keeps
+//! running. A run that lasts longer than a minute resets the policy, so that
+//! only consecutive failures exhaust it. Each start, failure, and restart is
+//! logged.
+//!
+//! Consumers are only started by [`Rocket::launch()`] and friends, not by
+//! local clients, so that testing an application's routes doesn't consume
+//! messages.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! # #[macro_use] extern crate rocket;
+//! use rocket::{Rocket, Orbit, Shutdown};
+//! use rocket::consumer::{self, Consumer};
+//! use rocket::fairing::Retry;
+//!
+//! # struct Ledger;
+//! # impl Ledger { fn record(&self, _: String) { } }
+//! # struct Stream;
+//! # impl Stream { async fn next(&mut self) -> Option<Result<String, std::io::Error>> { None } }
+//! # async fn subscribe(_: &str) -> Result<Stream, std::io::Error> { Ok(Stream) }
+//! struct Orders;
+//!
+//! #[rocket::async_trait]
+//! impl Consumer for Orders {
+//!     async fn consume(&self, rocket: &Rocket<Orbit>, shutdown: Shutdown) -> consumer::Result {
+//!         let ledger = rocket.state::<Ledger>().ok_or("missing ledger")?;
+//!         let mut stream = subscribe("orders").await?;
+//!         loop {
+//!             let message = rocket::tokio::select! {
+//!                 message = stream.next() => message,
+//!                 _ = shutdown.clone() => return Ok(()),
+//!             };
+//!
+//!             match message {
+//!                 Some(message) => ledger.record(message?),
+//!                 None => return Ok(()),
+//!             }
+//!         }
+//!     }
+//! }
+//!
+//! #[launch]
+//! fn rocket() -> _ {
+//!     rocket::build()
+//!         .manage(Ledger)
+//!         .consume("orders", Retry::new().retries(u32::MAX), Orders)
+//! }
+//! ```
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use tokio::time::Instant;
+
+use crate::{Rocket, Orbit, Shutdown};
+use crate::fairing::{Fairing, Info, Kind, Retry};
+use crate::util::FutureExt;
+
+/// An error returned by a consumer.
+pub type Error = Box<dyn std::error::Error + Send + Sync>;
+
+/// The result of running a consumer.
+pub type Result<T = (), E = Error> = std::result::Result<T, E>;
+
+/// How long a run must last to reset the retry policy.
+const HEALTHY: Duration = Duration::from_secs(60);
+
+/// A background consumer of an event stream.
+///
+/// See the [module level docs](self) for details and an example.
+#[crate::async_trait]
+pub trait Consumer: Send + Sync + 'static {
+    /// Consumes messages until the stream ends, `shutdown` resolves, or an
+    /// error occurs.
+    async fn consume(&self, rocket: &Rocket<Orbit>, shutdown: Shutdown) -> Result;
+}
+
+/// A [`Fairing`] that runs a [`Consumer`] in the background from liftoff
+/// until shutdown.
+///
+/// `Consume` fairings are typically attached via [`Rocket::consume()`]. See
+/// the [module level docs](self) for details.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::{Rocket, Orbit, Shutdown};
+/// use rocket::consumer::{self, Consume, Consumer};
+/// use rocket::fairing::Retry;
+///
+/// struct Noop;
+///
+/// #[rocket::async_trait]
+/// impl Consumer for Noop {
+///     async fn consume(&self, _: &Rocket<Orbit>, shutdown: Shutdown) -> consumer::Result {
+///         shutdown.await;
+///         Ok(())
+///     }
+/// }
+///
+/// let fairing = Consume::new("noop", Retry::new(), Noop);
+/// assert_eq!(fairing.name(), "noop");
+/// assert_eq!(fairing.starts(), 0);
+/// ```
+pub struct Consume {
+    name: &'static str,
+    policy: Retry,
+    consumer: Arc<dyn Consumer>,
+    starts: Arc<AtomicUsize>,
+}
+
+impl Consume {
+    /// Returns a `Consume` fairing named `name` that runs `consumer` and
+    /// restarts it according to `policy` when it fails.
+    pub fn new<C: Consumer>(name: &'static str, policy: Retry, consumer: C) -> Self {
+        let starts = Arc::new(AtomicUsize::new(0));
+        Consume { name, policy, consumer: Arc::new(consumer), starts }
+    }
+
+    /// Returns the name of the consumer.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Returns the number of times the consumer was started, including
+    /// restarts.
+    pub fn starts(&self) -> usize {
+        self.starts.load(Ordering::Acquire)
+    }
+
+    /// Spawns the consumer, which holds on to `rocket` until it exits.
+    pub(crate) fn spawn(&self, rocket: Arc<Rocket<Orbit>>) {
+        let (name, policy) = (self.name, self.policy.clone());
+        let (consumer, starts) = (self.consumer.clone(), self.starts.clone());
+        tokio::spawn(async move {
+            let shutdown = rocket.shutdown();
+            let grace = rocket.config().shutdown.grace();
+            let cancel = async { shutdown.await; tokio::time::sleep(grace).await };
+            let supervisor = supervise(name, policy, &*consumer, &rocket, &starts);
+            if supervisor.race(cancel).await.is_right() {
+                warn!(name: "consumer", consumer = name,
+                    "consumer didn't drain within the grace period: cancelled");
+            }
+        });
+    }
+}
+
+async fn supervise(
+    name: &'static str,
+    policy: Retry,
+    consumer: &dyn Consumer,
+    rocket: &Rocket<Orbit>,
+    starts: &AtomicUsize,
+) {
+    let shutdown = rocket.shutdown();
+    let mut delays = policy.delays();
+    loop {
+        let attempt = starts.fetch_add(1, Ordering::AcqRel) + 1;
+        info!(name: "consumer", consumer = name, attempt, "consumer started");
+
+        let started = Instant::now();
+        let result = consumer.consume(rocket, shutdown.clone()).await;
+        if shutdown.notified() {
+            info!(name: "consumer", consumer = name, "consumer drained");
+            return;
+        }
+
+        let error = match result {
+            Ok(()) => {
+                info!(name: "consumer", consumer = name, "consumer finished");
+                return;
+            }
+            Err(error) => error,
+        };
+
+        if started.elapsed() >= HEALTHY {
+            delays = policy.delays();
+        }
+
+        let Some(delay) = delays.next() else {
+            error!(name: "consumer", consumer = name, %error, "consumer failed: giving up");
+            return;
+        };
+
+        warn!(name: "consumer", consumer = name, %error,
+            "consumer failed: restarting in {}ms", delay.as_millis());
+
+        if tokio::time::sleep(delay).race(shutdown.clone()).await.is_right() {
+            return;
+        }
+    }
+}
+
+#[crate::async_trait]
+impl Fairing for Consume {
+    fn info(&self) -> Info {
+        Info { name: self.name, kind: Kind::Liftoff }
+    }
+
+    async fn on_liftoff(&self, _: &Rocket<Orbit>) {
+        // The consumer is spawned right after liftoff, by `Rocket::launch()`,
+        // when an owned handle to the instance is available.
+    }
+}
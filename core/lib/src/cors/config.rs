@@ -0,0 +1,105 @@
+use serde::{Deserialize, Serialize};
+
+use crate::http::Method;
+
+/// The CORS configuration: the `cors` configuration parameter.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::cors::CorsConfig;
+/// use rocket::http::Method;
+/// use rocket::figment::{Figment, providers::{Format, Toml}};
+///
+/// let figment = Figment::from(Toml::string(r#"
+///     [cors]
+///     origins = ["https://app.example.com"]
+///     methods = ["GET", "POST"]
+///     headers = ["Content-Type", "Authorization"]
+///     credentials = true
+///     max_age = 3600
+/// "#));
+///
+/// let config: CorsConfig = figment.extract_inner("cors").unwrap();
+/// assert!(config.allows("https://app.example.com"));
+/// assert!(!config.allows("https://evil.example.com"));
+/// assert!(config.allows_method(Method::Post));
+/// assert!(!config.allows_method(Method::Delete));
+/// assert_eq!(config.max_age, Some(3600));
+///
+/// let config = CorsConfig::default();
+/// assert!(config.allows("https://evil.example.com"));
+/// assert!(config.allows_method(Method::Delete));
+/// assert!(config.headers.is_none());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CorsConfig {
+    /// The origins allowed to make cross-origin requests, compared
+    /// case-insensitively. `"*"` allows any origin.
+    ///
+    /// **default: `["*"]`**
+    pub origins: Vec<String>,
+    /// The methods allowed in cross-origin requests. When `None`, the methods
+    /// of the matching routes are allowed. Otherwise, only those of the
+    /// matching routes' methods that are listed are.
+    ///
+    /// **default: `None`**
+    pub methods: Option<Vec<Method>>,
+    /// The request headers allowed in cross-origin requests. When `None`, the
+    /// headers requested by a preflight are allowed.
+    ///
+    /// **default: `None`**
+    pub headers: Option<Vec<String>>,
+    /// The response headers exposed to cross-origin requests.
+    ///
+    /// **default: `[]`**
+    pub expose_headers: Vec<String>,
+    /// Whether cross-origin requests may include credentials. When `true`, the
+    /// request's origin is echoed back in place of `*`.
+    ///
+    /// **default: `false`**
+    pub credentials: bool,
+    /// The number of seconds clients may cache a preflight response for. When
+    /// `None`, no `Access-Control-Max-Age` header is sent.
+    ///
+    /// **default: `None`**
+    pub max_age: Option<u64>,
+}
+
+impl Default for CorsConfig {
+    fn default() -> Self {
+        CorsConfig {
+            origins: vec!["*".into()],
+            methods: None,
+            headers: None,
+            expose_headers: vec![],
+            credentials: false,
+            max_age: None,
+        }
+    }
+}
+
+impl CorsConfig {
+    /// Returns `true` if cross-origin requests from `origin` are allowed.
+    pub fn allows(&self, origin: &str) -> bool {
+        self.origins.iter().any(|o| o == "*" || o.eq_ignore_ascii_case(origin))
+    }
+
+    /// Returns `true` if cross-origin requests with method `method` are
+    /// allowed.
+    pub fn allows_method(&self, method: Method) -> bool {
+        self.methods.as_ref().map_or(true, |methods| methods.contains(&method))
+    }
+
+    /// Returns the value of `Access-Control-Allow-Origin` for requests from
+    /// `origin`, or `None` if the origin is not allowed.
+    pub(crate) fn allow_origin<'a>(&self, origin: &'a str) -> Option<&'a str> {
+        let any = self.origins.iter().any(|o| o == "*");
+        match self.allows(origin) {
+            true if any && !self.credentials => Some("*"),
+            true => Some(origin),
+            false => None,
+        }
+    }
+}
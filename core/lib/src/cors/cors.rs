@@ -0,0 +1,249 @@
+use std::sync::{Arc, Weak};
+use std::collections::HashMap;
+
+use parking_lot::Mutex;
+use state::InitCell;
+
+use crate::{Rocket, Request, Response, Build};
+use crate::fairing::{self, Fairing, Info, Kind};
+use crate::http::{Method, Status};
+use crate::response::Body;
+use crate::router::Router;
+use crate::trace::Trace;
+use crate::cors::CorsConfig;
+
+/// The maximum number of cached preflight responses. The cache is cleared
+/// when it is full and whenever the live routes change.
+const CACHE_CAPACITY: usize = 1024;
+
+/// A [`Fairing`] that implements [CORS](crate::cors), answering preflight
+/// requests with the methods of the routes that match them.
+///
+/// The configuration is read from the `cors` configuration parameter at
+/// ignition, if it is set. If it is invalid, ignition fails.
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::cors::Cors;
+/// use rocket::http::{Header, Method, Status};
+/// use rocket::local::blocking::Client;
+///
+/// #[get("/items")]
+/// fn list() { }
+///
+/// #[post("/items")]
+/// fn create() { }
+///
+/// let rocket = rocket::build()
+///     .mount("/", routes![list, create])
+///     .attach(Cors::new());
+///
+/// let client = Client::debug(rocket).unwrap();
+/// let response = client.req(Method::Options, "/items")
+///     .header(Header::new("Origin", "https://app.example.com"))
+///     .header(Header::new("Access-Control-Request-Method", "POST"))
+///     .dispatch();
+///
+/// assert_eq!(response.status(), Status::NoContent);
+/// let methods = response.headers().get_one("Access-Control-Allow-Methods");
+/// assert_eq!(methods, Some("GET, HEAD, POST"));
+/// ```
+pub struct Cors {
+    config: InitCell<CorsConfig>,
+    cache: Mutex<Cache>,
+}
+
+/// A preflight cache key: the request URI and the value of
+/// `Access-Control-Allow-Origin`.
+type Key = (String, String);
+
+/// Preflight responses computed against the routes in `router`.
+#[derive(Default)]
+struct Cache {
+    router: Weak<Router>,
+    preflights: HashMap<Key, Arc<Preflight>>,
+}
+
+/// A computed preflight response.
+#[derive(Debug)]
+struct Preflight {
+    allow_origin: String,
+    allow_methods: String,
+}
+
+impl Cors {
+    /// Returns a `Cors` fairing.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::cors::Cors;
+    ///
+    /// let cors = Cors::new();
+    /// ```
+    pub fn new() -> Self {
+        Cors { config: InitCell::new(), cache: Mutex::default() }
+    }
+
+    /// Returns the number of cached preflight responses.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::cors::Cors;
+    ///
+    /// let cors = Cors::new();
+    /// assert_eq!(cors.cached(), 0);
+    /// ```
+    pub fn cached(&self) -> usize {
+        self.cache.lock().preflights.len()
+    }
+
+    /// Returns the preflight response to `req`, an `OPTIONS` request from the
+    /// allowed `origin`, or `None` if no route matches its URI or none of the
+    /// matching routes' methods are allowed by `config`.
+    fn preflight(
+        &self,
+        config: &CorsConfig,
+        req: &Request<'_>,
+        allow_origin: &str
+    ) -> Option<Arc<Preflight>> {
+        let key = (req.uri().to_string(), allow_origin.to_string());
+        if let Some(preflight) = self.cache.lock().sync(req.router()).get(&key) {
+            return Some(preflight.clone());
+        }
+
+        let mut methods: Vec<Method> = vec![];
+        for route in req.router().routes().filter(|r| r.matches_uri(req)) {
+            if !methods.contains(&route.method) {
+                methods.push(route.method);
+            }
+        }
+
+        if methods.is_empty() {
+            return None;
+        }
+
+        // `HEAD` requests are handled by `GET` routes when there's no `HEAD`.
+        if methods.contains(&Method::Get) && !methods.contains(&Method::Head) {
+            methods.push(Method::Head);
+        }
+
+        methods.retain(|m| config.allows_method(*m));
+        if methods.is_empty() {
+            return None;
+        }
+
+        methods.sort_by_key(|m| m.as_str());
+        let methods: Vec<_> = methods.iter().map(|m| m.as_str()).collect();
+        let preflight = Arc::new(Preflight {
+            allow_origin: allow_origin.to_string(),
+            allow_methods: methods.join(", "),
+        });
+
+        let mut cache = self.cache.lock();
+        let preflights = cache.sync(req.router());
+        if preflights.len() >= CACHE_CAPACITY {
+            preflights.clear();
+        }
+
+        preflights.insert(key, preflight.clone());
+        Some(preflight)
+    }
+}
+
+impl Cache {
+    /// Returns the preflights cached for `router`, first clearing the cache if
+    /// it was filled against a different router.
+    fn sync(&mut self, router: &Arc<Router>) -> &mut HashMap<Key, Arc<Preflight>> {
+        // `Weak` keeps the allocation, and thus its address, from being reused.
+        if !std::ptr::eq(self.router.as_ptr(), Arc::as_ptr(router)) {
+            self.router = Arc::downgrade(router);
+            self.preflights.clear();
+        }
+
+        &mut self.preflights
+    }
+}
+
+impl Default for Cors {
+    fn default() -> Self {
+        Cors::new()
+    }
+}
+
+#[crate::async_trait]
+impl Fairing for Cors {
+    fn info(&self) -> Info {
+        Info { name: "CORS", kind: Kind::Ignite | Kind::Response | Kind::Singleton }
+    }
+
+    async fn on_ignite(&self, rocket: Rocket<Build>) -> fairing::Result {
+        let config = match rocket.figment().contains("cors") {
+            true => match rocket.figment().extract_inner::<CorsConfig>("cors") {
+                Ok(config) => config,
+                Err(e) => {
+                    e.trace_error();
+                    return Err(rocket);
+                }
+            },
+            false => CorsConfig::default(),
+        };
+
+        self.config.set(config);
+        Ok(rocket)
+    }
+
+    async fn on_response<'r>(&self, req: &'r Request<'_>, res: &mut Response<'r>) {
+        let Some(config) = self.config.try_get() else { return };
+        let Some(origin) = req.headers().get_one("Origin") else { return };
+        let Some(allow_origin) = config.allow_origin(origin) else { return };
+
+        let requested = req.headers().get_one("Access-Control-Request-Method");
+        let unhandled = res.status() == Status::NotFound;
+        if req.method() == Method::Options && requested.is_some() && unhandled {
+            let Some(preflight) = self.preflight(config, req, allow_origin) else { return };
+
+            res.set_status(Status::NoContent);
+            res.remove_header("Content-Type");
+            *res.body_mut() = Body::default();
+
+            res.set_raw_header("Access-Control-Allow-Origin", preflight.allow_origin.clone());
+            res.set_raw_header("Access-Control-Allow-Methods", preflight.allow_methods.clone());
+            match (&config.headers, req.headers().get_one("Access-Control-Request-Headers")) {
+                (Some(headers), _) => {
+                    res.set_raw_header("Access-Control-Allow-Headers", headers.join(", "));
+                }
+                (None, Some(requested)) => {
+                    res.set_raw_header("Access-Control-Allow-Headers", requested.to_string());
+                    res.adjoin_raw_header("Vary", "Access-Control-Request-Headers");
+                }
+                (None, None) => {}
+            }
+
+            if let Some(max_age) = config.max_age {
+                res.set_raw_header("Access-Control-Max-Age", max_age.to_string());
+            }
+        } else {
+            if !config.allows_method(req.method()) {
+                return;
+            }
+
+            res.set_raw_header("Access-Control-Allow-Origin", allow_origin.to_string());
+            if !config.expose_headers.is_empty() {
+                let exposed = config.expose_headers.join(", ");
+                res.set_raw_header("Access-Control-Expose-Headers", exposed);
+            }
+        }
+
+        if allow_origin != "*" {
+            res.adjoin_raw_header("Vary", "Origin");
+        }
+
+        if config.credentials {
+            res.set_raw_header("Access-Control-Allow-Credentials", "true");
+        }
+    }
+}
@@ -0,0 +1,61 @@
+//! Cross-Origin Resource Sharing (CORS).
+//!
+//! The [`Cors`] fairing adds CORS headers to the responses to cross-origin
+//! requests, those with an `Origin` header, from allowed origins. Responses to
+//! requests from other origins are left as they are, so browsers reject them.
+//!
+//! # Preflight Requests
+//!
+//! A preflight request, an `OPTIONS` request with an
+//! `Access-Control-Request-Method` header, that no route handles is answered
+//! with `204 No Content`. The `Access-Control-Allow-Methods` header lists the
+//! methods of the routes whose URI matches the request's, derived from the
+//! route table, so it never drifts from the methods that are actually
+//! handled. `HEAD` is listed for `GET` routes, which handle `HEAD` requests.
+//! Preflights to URIs no route matches are left unhandled.
+//!
+//! If `methods` is configured, only the listed methods are allowed. Preflight
+//! responses list only the routes' methods that are also configured, and
+//! responses to requests with other methods are left without CORS headers.
+//!
+//! The methods are computed once per request URI and allowed origin, then
+//! cached until the [live routes](crate::Rocket::mount_live()) change. Routes
+//! that handle `OPTIONS` themselves take precedence.
+//!
+//! # Configuration
+//!
+//! CORS is configured via the `cors` configuration parameter, which is
+//! deserialized as a [`CorsConfig`]:
+//!
+//! ```toml
+//! [default.cors]
+//! origins = ["https://app.example.com"]
+//! methods = ["GET", "POST", "DELETE"]
+//! headers = ["Content-Type", "Authorization"]
+//! expose_headers = ["X-Total-Count"]
+//! credentials = true
+//! max_age = 3600
+//! ```
+//!
+//! # Example
+//!
+//! ```rust
+//! # #[macro_use] extern crate rocket;
+//! use rocket::cors::Cors;
+//!
+//! #[get("/items")]
+//! fn items() -> &'static str { "[]" }
+//!
+//! #[launch]
+//! fn rocket() -> _ {
+//!     rocket::build()
+//!         .mount("/", routes![items])
+//!         .attach(Cors::new())
+//! }
+//! ```
+
+mod config;
+mod cors;
+
+pub use config::CorsConfig;
+pub use cors::Cors;
@@ -162,6 +162,12 @@ impl<'r> Data<'r> {
         self.stream.peek(num).await
     }
 
+    /// Like [`Data::peek()`], but buffers up to `num` bytes even when `num`
+    /// exceeds the 512-byte limit of the `peek` buffer.
+    pub(crate) async fn buffer(&mut self, num: usize) -> &[u8] {
+        self.stream.fill(num).await
+    }
+
     /// Returns true if the `peek` buffer contains all of the data in the body
     /// of the request. Returns `false` if it does not or it is not known.
     ///
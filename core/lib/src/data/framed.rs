@@ -0,0 +1,114 @@
+use std::io;
+
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use crate::data::ByteUnit;
+
+/// Length-delimited binary frames over a raw stream.
+///
+/// Each frame is sent as its length, a big-endian `u32`, followed by its
+/// bytes. `Framed` is typically used over the [`IoStream`] of an upgraded
+/// connection to implement a binary protocol; see
+/// [`Upgrade`](crate::response::Upgrade).
+///
+/// Frames larger than the maximum frame size, [`Framed::DEFAULT_MAX_FRAME`]
+/// unless changed via [`Framed::max_frame()`], are neither sent nor received:
+/// both result in an error of kind [`InvalidData`](io::ErrorKind::InvalidData).
+///
+/// [`IoStream`]: crate::data::IoStream
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::data::Framed;
+///
+/// # rocket::async_test(async {
+/// let (client, server) = rocket::tokio::io::duplex(64);
+/// let (mut client, mut server) = (Framed::new(client), Framed::new(server));
+///
+/// client.send(b"ping").await.unwrap();
+/// assert_eq!(server.recv().await.unwrap().unwrap(), b"ping");
+///
+/// drop(client);
+/// assert!(server.recv().await.unwrap().is_none());
+/// # });
+/// ```
+#[derive(Debug)]
+pub struct Framed<S> {
+    io: S,
+    max_frame: ByteUnit,
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> Framed<S> {
+    /// The default maximum frame size: 1MiB.
+    pub const DEFAULT_MAX_FRAME: ByteUnit = ByteUnit::Mebibyte(1);
+
+    /// Frames `io` with a maximum frame size of [`Framed::DEFAULT_MAX_FRAME`].
+    pub fn new(io: S) -> Self {
+        Framed { io, max_frame: Self::DEFAULT_MAX_FRAME }
+    }
+
+    /// Sets the maximum frame size to `max_frame`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::data::{Framed, ToByteUnit};
+    ///
+    /// # rocket::async_test(async {
+    /// let (client, server) = rocket::tokio::io::duplex(64);
+    /// let mut client = Framed::new(client).max_frame(2.bytes());
+    /// assert!(client.send(b"too long").await.is_err());
+    /// # });
+    /// ```
+    pub fn max_frame(mut self, max_frame: ByteUnit) -> Self {
+        self.max_frame = max_frame;
+        self
+    }
+
+    /// Receives the next frame. Returns `None` if the stream ended before a
+    /// frame began. A stream ending mid-frame is an error.
+    pub async fn recv(&mut self) -> io::Result<Option<Vec<u8>>> {
+        let mut len = [0; 4];
+        match self.io.read(&mut len[..1]).await? {
+            0 => return Ok(None),
+            _ => self.io.read_exact(&mut len[1..]).await?,
+        };
+
+        let len = u32::from_be_bytes(len);
+        if u64::from(len) > self.max_frame.as_u64() {
+            let msg = format!("frame of {len} bytes exceeds maximum of {}", self.max_frame);
+            return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+        }
+
+        let mut frame = vec![0; len as usize];
+        self.io.read_exact(&mut frame).await?;
+        Ok(Some(frame))
+    }
+
+    /// Sends `frame` and flushes the stream.
+    pub async fn send(&mut self, frame: &[u8]) -> io::Result<()> {
+        let len = u32::try_from(frame.len()).ok()
+            .filter(|len| u64::from(*len) <= self.max_frame.as_u64())
+            .ok_or_else(|| {
+                let msg = format!("frame of {} bytes exceeds maximum of {}",
+                    frame.len(), self.max_frame);
+
+                io::Error::new(io::ErrorKind::InvalidData, msg)
+            })?;
+
+        self.io.write_all(&len.to_be_bytes()).await?;
+        self.io.write_all(frame).await?;
+        self.io.flush().await
+    }
+
+    /// Returns a reference to the underlying stream.
+    pub fn get_ref(&self) -> &S {
+        &self.io
+    }
+
+    /// Consumes `self` and returns the underlying stream.
+    pub fn into_inner(self) -> S {
+        self.io
+    }
+}
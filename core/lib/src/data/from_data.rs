@@ -88,6 +88,22 @@ pub type Outcome<'r, T, E = <T as FromData<'r>>::Error>
 ///
 ///     - **Forwards:** Never.
 ///
+///   * [`RangedUpload`](crate::data::RangedUpload)
+///
+///     _Limited by the `file` [data limit]._
+///
+///     Validates the `Content-Range` header of one chunk of a ranged upload.
+///     The body is appended to a target via [`RangedUpload::append_to()`].
+///
+///     - **Fails:** If the `Content-Range` header is missing, malformed, or
+///     unsatisfiable, if the range exceeds the limit, or if `Content-Length`
+///     doesn't match the range. The error type is
+///     [`RangeError`](crate::data::RangeError).
+///
+///     - **Succeeds:** Otherwise.
+///
+///     - **Forwards:** Never.
+///
 ///   * Deserializers: [`Json<T>`], [`MsgPack<T>`]
 ///
 ///     _Limited by the `json`, `msgpack` [data limit], respectively._
@@ -2,7 +2,7 @@ use std::io;
 use std::task::{Context, Poll};
 use std::pin::Pin;
 
-use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio::io::{AsyncRead, AsyncWrite, DuplexStream, ReadBuf};
 use hyper::upgrade::Upgraded;
 use hyper_util::rt::TokioIo;
 
@@ -26,9 +26,11 @@ pub struct IoStream {
     kind: IoStreamKind,
 }
 
-/// Just in case we want to add stream kinds in the future.
+/// The kinds of upgraded streams: from a connection or, for local clients,
+/// in memory.
 enum IoStreamKind {
-    Upgraded(TokioIo<Upgraded>)
+    Upgraded(TokioIo<Upgraded>),
+    Local(DuplexStream),
 }
 
 /// An upgraded connection I/O handler.
@@ -85,6 +87,13 @@ impl From<Upgraded> for IoStream {
     }
 }
 
+impl IoStream {
+    /// Returns the server's end of an in-memory upgraded connection.
+    pub(crate) fn local(io: DuplexStream) -> Self {
+        IoStream { kind: IoStreamKind::Local(io) }
+    }
+}
+
 /// A "trait alias" of sorts so we can use `AsyncRead + AsyncWrite + Unpin` in `dyn`.
 pub trait AsyncReadWrite: AsyncRead + AsyncWrite + Unpin { }
 
@@ -96,6 +105,7 @@ impl IoStream {
     fn inner_mut(&mut self) -> Pin<&mut dyn AsyncReadWrite> {
         match self.kind {
             IoStreamKind::Upgraded(ref mut io) => Pin::new(io),
+            IoStreamKind::Local(ref mut io) => Pin::new(io),
         }
     }
 
@@ -103,6 +113,7 @@ impl IoStream {
     fn inner_is_write_vectored(&self) -> bool {
         match self.kind {
             IoStreamKind::Upgraded(ref io) => io.is_write_vectored(),
+            IoStreamKind::Local(ref io) => io.is_write_vectored(),
         }
     }
 }
@@ -7,8 +7,12 @@ mod data_stream;
 mod from_data;
 mod limits;
 mod io_stream;
+mod framed;
 mod transform;
 mod peekable;
+mod ranged;
+
+pub(crate) mod sniff;
 
 pub use self::data::Data;
 pub use self::data_stream::DataStream;
@@ -16,7 +20,9 @@ pub use self::from_data::{FromData, Outcome};
 pub use self::limits::Limits;
 pub use self::capped::{N, Capped};
 pub use self::io_stream::{IoHandler, IoStream};
+pub use self::framed::Framed;
 pub use ubyte::{ByteUnit, ToByteUnit};
 pub use self::transform::{Transform, TransformBuf};
+pub use self::ranged::{ContentRange, RangedUpload, RangeError, AppendTarget};
 
 pub(crate) use self::data_stream::RawStream;
@@ -16,11 +16,16 @@ impl<const N: usize, R: AsyncRead + Unpin> Peekable<N, R> {
     }
 
     pub async fn peek(&mut self, num: usize) -> &[u8] {
+        self.fill(std::cmp::min(N, num)).await
+    }
+
+    /// Like `peek()`, but buffers up to `to_read` bytes even if that exceeds
+    /// `N`, the usual limit.
+    pub async fn fill(&mut self, to_read: usize) -> &[u8] {
         if self.complete {
             return self.buffer.as_slice();
         }
 
-        let to_read = std::cmp::min(N, num);
         if self.buffer.len() >= to_read {
             return self.buffer.as_slice();
         }
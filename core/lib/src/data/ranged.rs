@@ -0,0 +1,315 @@
+use std::{fmt, io};
+use std::str::FromStr;
+
+use tokio::fs::File;
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWrite, AsyncWriteExt};
+
+use crate::Request;
+use crate::http::Status;
+use crate::data::{Data, FromData, Outcome, ToByteUnit};
+
+/// The value of a `Content-Range` request header: `bytes $start-$end/$total`.
+///
+/// Both `start` and `end` are inclusive. The `total` length of the resource is
+/// `None` if it is unknown, that is, if the header's length is `*`.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::data::ContentRange;
+///
+/// let range: ContentRange = "bytes 0-499/1234".parse().unwrap();
+/// assert_eq!(range, ContentRange { start: 0, end: 499, total: Some(1234) });
+/// assert_eq!(range.size(), 500);
+///
+/// let range: ContentRange = "bytes 500-999/*".parse().unwrap();
+/// assert_eq!(range.total, None);
+///
+/// assert!("bytes 10-5/20".parse::<ContentRange>().is_err());
+/// assert!("bytes 0-20/20".parse::<ContentRange>().is_err());
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct ContentRange {
+    /// The offset of the first byte in the range.
+    pub start: u64,
+    /// The offset of the last byte in the range.
+    pub end: u64,
+    /// The total length of the resource, if known.
+    pub total: Option<u64>,
+}
+
+impl ContentRange {
+    /// The number of bytes in the range.
+    pub fn size(&self) -> u64 {
+        self.end - self.start + 1
+    }
+
+    /// Returns `true` if the range ends at the last byte of the resource.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::data::ContentRange;
+    ///
+    /// let range: ContentRange = "bytes 500-999/1000".parse().unwrap();
+    /// assert!(range.is_last());
+    ///
+    /// let range: ContentRange = "bytes 500-999/*".parse().unwrap();
+    /// assert!(!range.is_last());
+    /// ```
+    pub fn is_last(&self) -> bool {
+        self.total == Some(self.end + 1)
+    }
+}
+
+impl FromStr for ContentRange {
+    type Err = RangeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let range = s.trim().strip_prefix("bytes ").ok_or(RangeError::Malformed)?;
+        let (range, total) = range.split_once('/').ok_or(RangeError::Malformed)?;
+        let (start, end) = range.split_once('-').ok_or(RangeError::Malformed)?;
+        let number = |s: &str| s.trim().parse::<u64>().map_err(|_| RangeError::Malformed);
+        let range = ContentRange {
+            start: number(start)?,
+            end: number(end)?,
+            total: match total.trim() {
+                "*" => None,
+                total => Some(number(total)?),
+            },
+        };
+
+        if range.end < range.start || range.total.map_or(false, |total| range.end >= total) {
+            return Err(RangeError::Unsatisfiable);
+        }
+
+        Ok(range)
+    }
+}
+
+impl fmt::Display for ContentRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "bytes {}-{}/", self.start, self.end)?;
+        match self.total {
+            Some(total) => write!(f, "{}", total),
+            None => write!(f, "*"),
+        }
+    }
+}
+
+/// An error validating or appending a ranged upload.
+#[derive(Debug)]
+pub enum RangeError {
+    /// The request has no `Content-Range` header.
+    Missing,
+    /// The `Content-Range` header is not of the form `bytes $start-$end/$total`.
+    Malformed,
+    /// The range is empty or ends beyond the total length.
+    Unsatisfiable,
+    /// The range is larger than the `file` data limit.
+    TooLarge,
+    /// The body's length does not match the length of the range.
+    LengthMismatch,
+    /// The range starts past the end of the target, leaving a gap.
+    Gap {
+        /// The offset at which the next range must start.
+        expected: u64,
+    },
+    /// An I/O error occurred while reading the body or appending to the target.
+    Io(io::Error),
+}
+
+impl RangeError {
+    /// The status most appropriate for responding with this error.
+    ///
+    /// ```rust
+    /// use rocket::http::Status;
+    /// use rocket::data::RangeError;
+    ///
+    /// assert_eq!(RangeError::Gap { expected: 10 }.status(), Status::Conflict);
+    /// assert_eq!(RangeError::Unsatisfiable.status(), Status::RangeNotSatisfiable);
+    /// ```
+    pub fn status(&self) -> Status {
+        match self {
+            RangeError::Missing | RangeError::Malformed => Status::BadRequest,
+            RangeError::LengthMismatch => Status::BadRequest,
+            RangeError::Unsatisfiable => Status::RangeNotSatisfiable,
+            RangeError::TooLarge => Status::PayloadTooLarge,
+            RangeError::Gap { .. } => Status::Conflict,
+            RangeError::Io(_) => Status::InternalServerError,
+        }
+    }
+}
+
+impl fmt::Display for RangeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RangeError::Missing => write!(f, "missing `Content-Range` header"),
+            RangeError::Malformed => write!(f, "malformed `Content-Range` header"),
+            RangeError::Unsatisfiable => write!(f, "unsatisfiable content range"),
+            RangeError::TooLarge => write!(f, "content range exceeds data limit"),
+            RangeError::LengthMismatch => write!(f, "body length does not match range"),
+            RangeError::Gap { expected } => write!(f, "range must start at or before {}", expected),
+            RangeError::Io(e) => write!(f, "i/o error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for RangeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RangeError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for RangeError {
+    fn from(e: io::Error) -> Self {
+        RangeError::Io(e)
+    }
+}
+
+/// A target to which a [`RangedUpload`] can be appended.
+///
+/// Implemented for [`File`](tokio::fs::File) and `Vec<u8>`. Implement it for
+/// other storage, such as an object store's multipart upload, to append ranged
+/// uploads there.
+#[crate::async_trait]
+pub trait AppendTarget: AsyncWrite + Unpin + Send {
+    /// Returns the number of bytes currently in the target and ensures that
+    /// subsequent writes append to its end.
+    async fn end(&mut self) -> io::Result<u64>;
+}
+
+#[crate::async_trait]
+impl AppendTarget for File {
+    async fn end(&mut self) -> io::Result<u64> {
+        self.seek(io::SeekFrom::End(0)).await
+    }
+}
+
+#[crate::async_trait]
+impl AppendTarget for Vec<u8> {
+    async fn end(&mut self) -> io::Result<u64> {
+        Ok(self.len() as u64)
+    }
+}
+
+/// A data guard for one chunk of a ranged upload via `PUT` or `PATCH`.
+///
+/// Clients that upload large resources in chunks send each chunk as the body
+/// of a request with a `Content-Range` header indicating where in the resource
+/// the chunk belongs. A `RangedUpload` validates the header and, if present,
+/// that `Content-Length` matches the range. The body is then appended to an
+/// [`AppendTarget`] via [`RangedUpload::append_to()`].
+///
+/// Appends are idempotent to support retries: bytes in the range that the
+/// target already contains are skipped. A range that starts past the end of the
+/// target is rejected with [`RangeError::Gap`].
+///
+/// _Limited by the `file` [data limit](crate::data::Limits): ranges larger
+/// than the limit are rejected._
+///
+///   - **Fails:** If the `Content-Range` header is missing, malformed, or
+///     unsatisfiable, if the range exceeds the limit, or if `Content-Length`
+///     doesn't match the range. The error type is [`RangeError`] and the status
+///     is [`RangeError::status()`].
+///
+///   - **Succeeds:** Otherwise.
+///
+///   - **Forwards:** Never.
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::data::{RangedUpload, RangeError};
+/// use rocket::http::Status;
+/// use rocket::tokio::fs::OpenOptions;
+///
+/// #[patch("/upload/<id>", data = "<upload>")]
+/// async fn upload(
+///     id: u32,
+///     upload: Result<RangedUpload<'_>, RangeError>,
+/// ) -> Result<String, Status> {
+///     let upload = upload.map_err(|e| e.status())?;
+///     let mut file = OpenOptions::new()
+///         .create(true)
+///         .write(true)
+///         .open(format!("/tmp/upload-{}", id))
+///         .await
+///         .map_err(|_| Status::InternalServerError)?;
+///
+///     let length = upload.append_to(&mut file).await.map_err(|e| e.status())?;
+///     Ok(format!("{} bytes received", length))
+/// }
+/// ```
+pub struct RangedUpload<'r> {
+    range: ContentRange,
+    data: Data<'r>,
+}
+
+impl<'r> RangedUpload<'r> {
+    /// The range of the resource this upload contains.
+    pub fn range(&self) -> ContentRange {
+        self.range
+    }
+
+    /// Appends the body to `target`, skipping any bytes in the range that the
+    /// target already contains. Returns the length of the target afterwards.
+    ///
+    /// Returns [`RangeError::Gap`] if the range starts past the end of the
+    /// target and [`RangeError::LengthMismatch`] if the body is shorter or
+    /// longer than the range. In the latter case, some data may have already
+    /// been appended.
+    pub async fn append_to<T>(self, target: &mut T) -> Result<u64, RangeError>
+        where T: AppendTarget + ?Sized
+    {
+        let length = target.end().await?;
+        if self.range.start > length {
+            return Err(RangeError::Gap { expected: length });
+        }
+
+        let mut stream = self.data.open(self.range.size().bytes());
+        let skip = length.min(self.range.end + 1) - self.range.start;
+        let mut prefix = (&mut stream).take(skip);
+        let skipped = tokio::io::copy(&mut prefix, &mut tokio::io::sink()).await?;
+        let written = stream.stream_to(&mut *target).await?;
+        target.flush().await?;
+        if !written.complete || skipped + written.written != self.range.size() {
+            return Err(RangeError::LengthMismatch);
+        }
+
+        Ok(length + written.written)
+    }
+}
+
+#[crate::async_trait]
+impl<'r> FromData<'r> for RangedUpload<'r> {
+    type Error = RangeError;
+
+    async fn from_data(req: &'r Request<'_>, data: Data<'r>) -> Outcome<'r, Self> {
+        let range = match req.headers().get_one("Content-Range") {
+            Some(value) => value.parse::<ContentRange>(),
+            None => Err(RangeError::Missing),
+        };
+
+        let range = match range {
+            Ok(range) => range,
+            Err(e) => return Outcome::Error((e.status(), e)),
+        };
+
+        let limit = req.limits().get("file").unwrap_or(crate::data::Limits::FILE);
+        if range.size() > limit.as_u64() {
+            return Outcome::Error((Status::PayloadTooLarge, RangeError::TooLarge));
+        }
+
+        let length = req.headers().get_one("Content-Length");
+        if length.map_or(false, |n| n.trim().parse::<u64>().ok() != Some(range.size())) {
+            return Outcome::Error((Status::BadRequest, RangeError::LengthMismatch));
+        }
+
+        Outcome::Success(RangedUpload { range, data })
+    }
+}
@@ -0,0 +1,291 @@
+use std::fmt;
+
+use crate::http::{ContentType, Status};
+use crate::data::Data;
+use crate::request::Request;
+
+/// The number of bytes peeked at to sniff the content type of a body.
+const SNIFF_BYTES: usize = 512;
+
+/// The magic bytes of a family of formats and the media types, as declared by
+/// clients, the formats may have.
+struct Signature {
+    name: &'static str,
+    matches: fn(&[u8]) -> bool,
+    types: &'static [&'static str],
+    /// Whether content declared as one of `types` must have the magic bytes.
+    /// `false` for formats whose magic bytes are optional or heuristic.
+    required: bool,
+}
+
+const SIGNATURES: &[Signature] = &[
+    Signature {
+        name: "PNG",
+        matches: |b| b.starts_with(b"\x89PNG\r\n\x1a\n"),
+        types: &["image/png", "image/apng"],
+        required: true,
+    },
+    Signature {
+        name: "JPEG",
+        matches: |b| b.starts_with(b"\xff\xd8\xff"),
+        types: &["image/jpeg", "image/jpg", "image/pjpeg"],
+        required: true,
+    },
+    Signature {
+        name: "GIF",
+        matches: |b| b.starts_with(b"GIF87a") || b.starts_with(b"GIF89a"),
+        types: &["image/gif"],
+        required: true,
+    },
+    Signature {
+        name: "WEBP",
+        matches: |b| b.starts_with(b"RIFF") && b.get(8..12) == Some(b"WEBP"),
+        types: &["image/webp"],
+        required: true,
+    },
+    Signature {
+        name: "BMP",
+        matches: |b| b.starts_with(b"BM") && b.get(6..10) == Some(&[0; 4]),
+        types: &["image/bmp", "image/x-bmp", "image/x-ms-bmp"],
+        required: true,
+    },
+    Signature {
+        name: "TIFF",
+        matches: |b| b.starts_with(b"II*\0") || b.starts_with(b"MM\0*"),
+        types: &["image/tiff"],
+        required: true,
+    },
+    Signature {
+        name: "Icon",
+        matches: |b| b.starts_with(b"\0\0\x01\0"),
+        types: &["image/x-icon", "image/vnd.microsoft.icon"],
+        required: true,
+    },
+    Signature {
+        name: "ISO media",
+        matches: |b| b.get(4..8) == Some(b"ftyp"),
+        types: &[
+            "video/mp4", "audio/mp4", "video/quicktime", "image/avif", "image/heic",
+            "image/heif", "video/3gpp", "audio/x-m4a", "audio/aac",
+        ],
+        required: true,
+    },
+    Signature {
+        name: "WEBM",
+        matches: |b| b.starts_with(b"\x1a\x45\xdf\xa3"),
+        types: &["video/webm", "audio/webm", "video/x-matroska"],
+        required: true,
+    },
+    Signature {
+        name: "OGG",
+        matches: |b| b.starts_with(b"OggS"),
+        types: &["video/ogg", "audio/ogg", "application/ogg"],
+        required: true,
+    },
+    Signature {
+        name: "FLAC",
+        matches: |b| b.starts_with(b"fLaC"),
+        types: &["audio/flac", "audio/x-flac"],
+        required: true,
+    },
+    Signature {
+        name: "WAV",
+        matches: |b| b.starts_with(b"RIFF") && b.get(8..12) == Some(b"WAVE"),
+        types: &["audio/wav", "audio/x-wav", "audio/wave"],
+        required: true,
+    },
+    Signature {
+        name: "MP3",
+        matches: |b| b.starts_with(b"ID3"),
+        types: &["audio/mpeg", "audio/mp3"],
+        required: false,
+    },
+    Signature {
+        name: "PDF",
+        matches: |b| b.starts_with(b"%PDF-"),
+        types: &["application/pdf"],
+        required: true,
+    },
+    Signature {
+        name: "ZIP",
+        matches: |b| b.starts_with(b"PK\x03\x04") || b.starts_with(b"PK\x05\x06"),
+        types: &[
+            "application/zip", "application/x-zip-compressed", "application/epub+zip",
+            "application/vnd.comicbook+zip", "application/java-archive",
+            "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+            "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+            "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+            "application/vnd.oasis.opendocument.text",
+            "application/vnd.oasis.opendocument.spreadsheet",
+            "application/vnd.oasis.opendocument.presentation",
+        ],
+        required: true,
+    },
+    Signature {
+        name: "GZIP",
+        matches: |b| b.starts_with(b"\x1f\x8b"),
+        types: &["application/gzip", "application/x-gzip"],
+        required: true,
+    },
+    Signature {
+        name: "RAR",
+        matches: |b| b.starts_with(b"Rar!\x1a\x07"),
+        types: &["application/vnd.rar", "application/x-rar-compressed"],
+        required: true,
+    },
+    Signature {
+        name: "7z",
+        matches: |b| b.starts_with(b"7z\xbc\xaf\x27\x1c"),
+        types: &["application/x-7z-compressed"],
+        required: true,
+    },
+    Signature {
+        name: "WASM",
+        matches: |b| b.starts_with(b"\0asm"),
+        types: &["application/wasm"],
+        required: true,
+    },
+    Signature {
+        name: "executable",
+        matches: |b| b.starts_with(b"MZ") || b.starts_with(b"\x7fELF"),
+        types: &[
+            "application/vnd.microsoft.portable-executable", "application/x-msdownload",
+            "application/x-dosexec", "application/x-executable",
+        ],
+        required: true,
+    },
+    Signature {
+        name: "HTML",
+        matches: is_html,
+        types: &["text/html"],
+        required: false,
+    },
+];
+
+/// Returns `true` if `bytes`, after leading whitespace, begin with a tag that
+/// browsers sniff as HTML.
+fn is_html(bytes: &[u8]) -> bool {
+    const TAGS: &[&[u8]] = &[
+        b"<!doctype html", b"<html", b"<head", b"<body", b"<script", b"<iframe", b"<title",
+        b"<style", b"<table", b"<div", b"<a", b"<img", b"<!--",
+    ];
+
+    let start = bytes.iter().position(|b| !b.is_ascii_whitespace()).unwrap_or(bytes.len());
+    let bytes = &bytes[start..];
+    TAGS.iter().any(|tag| {
+        bytes.len() > tag.len()
+            && bytes[..tag.len()].eq_ignore_ascii_case(tag)
+            && matches!(bytes[tag.len()], b' ' | b'>' | b'\t' | b'\n' | b'\r')
+    })
+}
+
+/// A body whose sniffed format doesn't match its declared `Content-Type`.
+#[derive(Debug)]
+pub(crate) struct Mismatch {
+    declared: Option<String>,
+    sniffed: Option<&'static str>,
+}
+
+/// Checks that the format sniffed from the leading `bytes` of a body is
+/// consistent with its `declared` content type.
+///
+/// Content with recognized magic bytes must be declared as one of the
+/// format's media types. Content declared as a media type whose magic bytes
+/// are required must have them. Anything else, such as text, JSON, or content
+/// declared as `application/octet-stream` without recognized magic bytes, is
+/// consistent.
+pub(crate) fn verify(declared: Option<&ContentType>, bytes: &[u8]) -> Result<(), Mismatch> {
+    let essence = declared.map(|ty| format!("{}/{}", ty.top(), ty.sub()));
+    let declares = |sig: &Signature| match &essence {
+        Some(essence) => sig.types.iter().any(|ty| ty.eq_ignore_ascii_case(essence)),
+        None => false,
+    };
+
+    let consistent = match SIGNATURES.iter().find(|sig| (sig.matches)(bytes)) {
+        Some(sig) => declares(sig),
+        None => !SIGNATURES.iter().any(|sig| sig.required && declares(sig)),
+    };
+
+    if consistent {
+        return Ok(());
+    }
+
+    let sniffed = SIGNATURES.iter().find(|sig| (sig.matches)(bytes)).map(|sig| sig.name);
+    Err(Mismatch { declared: essence, sniffed })
+}
+
+/// Verifies the body of `req`, via [`verify()`], for routes that opted in via
+/// `#[data(verify_content_type)]`. Returns `Err` with the status of the
+/// response on a mismatch.
+pub(crate) async fn verify_data(req: &Request<'_>, data: &mut Data<'_>) -> Result<(), Status> {
+    let bytes = data.peek(SNIFF_BYTES).await;
+    verify(req.content_type(), bytes).map_err(|e| {
+        warn!(name: "sniff", declared = e.declared.as_deref(), sniffed = e.sniffed,
+            "request body doesn't match its declared content type");
+
+        Status::UnsupportedMediaType
+    })
+}
+
+/// Verifies the data of a form field in `req` declared as `content_type`.
+pub(crate) async fn verify_field(
+    req: &Request<'_>,
+    content_type: &ContentType,
+    data: &mut Data<'_>,
+) -> Result<(), Mismatch> {
+    if !req.route().map_or(false, |route| route.verify_content_type) {
+        return Ok(());
+    }
+
+    verify(Some(content_type), data.peek(SNIFF_BYTES).await)
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let declared = self.declared.as_deref().unwrap_or("no content type");
+        match self.sniffed {
+            Some(sniffed) => write!(f, "{sniffed} content declared as {declared}"),
+            None => write!(f, "content declared as {declared} doesn't match it"),
+        }
+    }
+}
+
+impl std::error::Error for Mismatch { }
+
+#[cfg(test)]
+mod tests {
+    use super::verify;
+    use crate::http::ContentType;
+
+    const PNG: &[u8] = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+
+    #[test]
+    fn test_sniffed_types_must_be_declared() {
+        assert!(verify(Some(&ContentType::PNG), PNG).is_ok());
+        assert!(verify(Some(&ContentType::JPEG), PNG).is_err());
+        assert!(verify(Some(&ContentType::Binary), PNG).is_err());
+        assert!(verify(None, PNG).is_err());
+
+        let html = b"  <!DOCTYPE html><script>alert(1)</script>";
+        assert!(verify(Some(&ContentType::HTML), html).is_ok());
+        assert!(verify(Some(&ContentType::PNG), html).is_err());
+        assert!(verify(Some(&ContentType::Plain), html).is_err());
+
+        let docx = ContentType::new("application",
+            "vnd.openxmlformats-officedocument.wordprocessingml.document");
+        assert!(verify(Some(&docx), b"PK\x03\x04\x14\0").is_ok());
+        assert!(verify(Some(&ContentType::ZIP), b"PK\x03\x04\x14\0").is_ok());
+    }
+
+    #[test]
+    fn test_required_magic_bytes() {
+        assert!(verify(Some(&ContentType::PNG), b"hello").is_err());
+        assert!(verify(Some(&ContentType::PDF), b"").is_err());
+        assert!(verify(Some(&ContentType::Plain), b"hello").is_ok());
+        assert!(verify(Some(&ContentType::JSON), b"{\"a\": 1}").is_ok());
+        assert!(verify(Some(&ContentType::Binary), b"\x01\x02").is_ok());
+        assert!(verify(Some(&ContentType::HTML), b"<!-- a --><p>hi</p>").is_ok());
+        assert!(verify(Some(&ContentType::MP3), b"\xff\xfb\x90\x00").is_ok());
+        assert!(verify(None, b"").is_ok());
+    }
+}
@@ -0,0 +1,275 @@
+//! Development-time utilities.
+//!
+//! The [`Reloader`] fairing watches files and directories for changes and
+//! reacts to them via user callbacks: invalidating a template cache, flipping
+//! an application's maintenance flag, or restarting the application
+//! gracefully. It provides a single place to describe what should happen when
+//! files change during development instead of several ad-hoc watchers.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, SystemTime};
+
+use crate::{Rocket, Orbit, Config};
+use crate::fairing::{Fairing, Info, Kind};
+use crate::util::FutureExt;
+
+/// What a [`Reloader`] should do after a watch callback runs.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Action {
+    /// Keep the application running.
+    Continue,
+    /// Gracefully shut the application down, as if by
+    /// [`Shutdown::notify()`](crate::Shutdown::notify()), so that it can be
+    /// relaunched.
+    Restart,
+}
+
+type Callback = dyn Fn(&[PathBuf]) -> Action + Send + Sync + 'static;
+
+/// A [`Fairing`] that watches paths for changes and invokes callbacks.
+///
+/// A `Reloader` is configured with any number of watches, each consisting of
+/// a path, a file or a directory watched recursively, and a callback. The
+/// callback is invoked with the list of changed paths whenever files under the
+/// watched path are created, modified, or removed. The [`Action`] it returns
+/// determines whether the application keeps running or gracefully shuts down
+/// to be restarted.
+///
+/// Paths are polled every [`interval`](Reloader::interval()), 500ms by default,
+/// starting at liftoff. Relative paths are resolved against the current
+/// working directory. Watching stops when the application shuts down.
+///
+/// The reloader is **only** active in the `debug` profile. In any other
+/// profile, attaching it has no effect.
+///
+/// # Example
+///
+/// Invalidate a cache when files in `static/` change and restart when
+/// `Rocket.toml` changes:
+///
+/// ```rust,no_run
+/// # #[macro_use] extern crate rocket;
+/// use std::sync::atomic::{AtomicBool, Ordering};
+/// use rocket::dev::{Reloader, Action};
+///
+/// static STALE: AtomicBool = AtomicBool::new(false);
+///
+/// #[rocket::main]
+/// async fn main() -> Result<(), rocket::Error> {
+///     loop {
+///         let reloader = Reloader::new()
+///             .watch("static", |_| {
+///                 STALE.store(true, Ordering::Release);
+///                 Action::Continue
+///             })
+///             .restart_on("Rocket.toml");
+///
+///         let rocket = rocket::build().attach(reloader).launch().await?;
+///         if !Reloader::restart_requested(&rocket) {
+///             return Ok(());
+///         }
+///     }
+/// }
+/// ```
+#[derive(Clone)]
+pub struct Reloader {
+    interval: Duration,
+    watches: Vec<(PathBuf, Arc<Callback>)>,
+}
+
+impl Reloader {
+    /// Returns a new `Reloader` with no watches and the default polling
+    /// interval of 500ms.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::dev::Reloader;
+    ///
+    /// let reloader = Reloader::new();
+    /// ```
+    pub fn new() -> Self {
+        Reloader { interval: Duration::from_millis(500), watches: vec![] }
+    }
+
+    /// Sets the interval at which watched paths are polled for changes.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use rocket::dev::Reloader;
+    ///
+    /// let reloader = Reloader::new().interval(Duration::from_secs(1));
+    /// ```
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Watches `path`, invoking `callback` with the changed paths whenever
+    /// files under `path` change.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::dev::{Reloader, Action};
+    ///
+    /// let reloader = Reloader::new().watch("templates", |changed| {
+    ///     println!("templates changed: {:?}", changed);
+    ///     Action::Continue
+    /// });
+    /// ```
+    pub fn watch<P, F>(mut self, path: P, callback: F) -> Self
+        where P: AsRef<Path>, F: Fn(&[PathBuf]) -> Action + Send + Sync + 'static
+    {
+        self.watches.push((path.as_ref().to_path_buf(), Arc::new(callback)));
+        self
+    }
+
+    /// Watches `path` and gracefully restarts the application whenever files
+    /// under `path` change. Equivalent to `watch(path, |_| Action::Restart)`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::dev::Reloader;
+    ///
+    /// let reloader = Reloader::new().restart_on("Rocket.toml");
+    /// ```
+    pub fn restart_on<P: AsRef<Path>>(self, path: P) -> Self {
+        self.watch(path, |_| Action::Restart)
+    }
+
+    /// Returns `true` if the instance of `Rocket` returned by
+    /// [`Rocket::launch()`] shut down because a watch callback returned
+    /// [`Action::Restart`].
+    ///
+    /// See the [type level docs](Reloader#example) for an example.
+    pub fn restart_requested(rocket: &Rocket<crate::Ignite>) -> bool {
+        rocket.state::<RestartSlot>().map_or(false, |slot| slot.requested())
+    }
+}
+
+impl Default for Reloader {
+    fn default() -> Self {
+        Reloader::new()
+    }
+}
+
+/// Modification times of all files in a watched path.
+type Snapshot = HashMap<PathBuf, Option<SystemTime>>;
+
+fn snapshot(path: &Path, snapshot: &mut Snapshot) {
+    let Ok(metadata) = std::fs::metadata(path) else { return };
+    if metadata.is_dir() {
+        let Ok(entries) = std::fs::read_dir(path) else { return };
+        for entry in entries.flatten() {
+            self::snapshot(&entry.path(), snapshot);
+        }
+    } else {
+        snapshot.insert(path.to_path_buf(), metadata.modified().ok());
+    }
+}
+
+fn changes(old: &Snapshot, new: &Snapshot) -> Vec<PathBuf> {
+    let mut changed: Vec<PathBuf> = new.iter()
+        .filter(|(path, time)| old.get(*path) != Some(time))
+        .map(|(path, _)| path.clone())
+        .chain(old.keys().filter(|path| !new.contains_key(*path)).cloned())
+        .collect();
+
+    changed.sort();
+    changed
+}
+
+#[crate::async_trait]
+impl Fairing for Reloader {
+    fn info(&self) -> Info {
+        Info {
+            name: "Reloader",
+            kind: Kind::Ignite | Kind::Liftoff | Kind::Singleton,
+        }
+    }
+
+    async fn on_ignite(&self, rocket: Rocket<crate::Build>) -> crate::fairing::Result {
+        // State can't be managed after ignition, so manage the flag now.
+        Ok(rocket.manage(RestartSlot::default()))
+    }
+
+    async fn on_liftoff(&self, rocket: &Rocket<Orbit>) {
+        if rocket.config().profile != Config::DEBUG_PROFILE {
+            info!(name: "reloader", "reloader is only enabled in debug profile");
+            return;
+        }
+
+        if self.watches.is_empty() {
+            return;
+        }
+
+        let shutdown = rocket.shutdown();
+        let slot = rocket.state::<RestartSlot>().cloned().unwrap_or_default();
+        let (interval, watches) = (self.interval, self.watches.clone());
+        span_info!("reloader", interval_ms = interval.as_millis() as u64 => {
+            watches.iter().for_each(|(path, _)| info!(path = %path.display(), "watching"));
+        });
+
+        tokio::spawn(async move {
+            let scan = |watches: Vec<(PathBuf, Arc<Callback>)>| async move {
+                tokio::task::spawn_blocking(move || {
+                    watches.iter().map(|(path, _)| {
+                        let mut snap = Snapshot::new();
+                        snapshot(path, &mut snap);
+                        snap
+                    }).collect::<Vec<_>>()
+                }).await.unwrap_or_default()
+            };
+
+            let mut snapshots = scan(watches.clone()).await;
+            loop {
+                let tick = tokio::time::sleep(interval);
+                if shutdown.clone().race(tick).await.is_left() {
+                    break;
+                }
+
+                let current = scan(watches.clone()).await;
+                for ((path, callback), (old, new)) in watches.iter().zip(snapshots.iter().zip(&current)) {
+                    let changed = changes(old, new);
+                    if changed.is_empty() {
+                        continue;
+                    }
+
+                    info!(name: "reloader", path = %path.display(), changes = changed.len(),
+                        "watched path changed");
+
+                    if callback(&changed) == Action::Restart {
+                        warn!(name: "reloader", path = %path.display(),
+                            "restart requested: shutting down gracefully");
+
+                        slot.request();
+                        shutdown.notify();
+                    }
+                }
+
+                snapshots = current;
+            }
+        });
+    }
+}
+
+/// Shared flag, in managed state, recording whether a restart was requested.
+#[derive(Default, Clone)]
+struct RestartSlot(Arc<AtomicBool>);
+
+impl RestartSlot {
+    fn request(&self) {
+        self.0.store(true, Ordering::Release);
+    }
+
+    fn requested(&self) -> bool {
+        self.0.load(Ordering::Acquire)
+    }
+}
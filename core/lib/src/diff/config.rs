@@ -0,0 +1,77 @@
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use crate::data::{ByteUnit, ToByteUnit};
+
+/// The response diffing configuration: the `diff` configuration parameter.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::diff::DiffConfig;
+/// use rocket::data::ToByteUnit;
+/// use rocket::figment::{Figment, providers::{Format, Toml}};
+///
+/// let figment = Figment::from(Toml::string(r#"
+///     [diff]
+///     rate = 0.5
+///     candidates = { "/users" = "/next/users" }
+/// "#));
+///
+/// let config: DiffConfig = figment.extract_inner("diff").unwrap();
+/// assert_eq!(config.rate, 0.5);
+/// assert_eq!(config.candidate("/users/10"), Some("/next/users/10".into()));
+/// assert_eq!(config.candidate("/usersettings"), None);
+/// assert_eq!(config.body_limit, 256.kibibytes());
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DiffConfig {
+    /// The fraction of eligible requests to compare, from `0.0` to `1.0`.
+    ///
+    /// **default: `0.1`**
+    pub rate: f64,
+    /// A map from path prefixes of live routes to the prefixes of their
+    /// candidates. Every prefix must begin with `/`.
+    ///
+    /// **default: `{}`**
+    pub candidates: IndexMap<String, String>,
+    /// The largest body to compare. Larger or streamed bodies aren't compared,
+    /// though statuses and headers still are.
+    ///
+    /// **default: `256KiB`**
+    pub body_limit: ByteUnit,
+    /// Response headers, compared case-insensitively, whose values must match.
+    ///
+    /// **default: `["Content-Type", "Location"]`**
+    pub headers: Vec<String>,
+}
+
+impl Default for DiffConfig {
+    fn default() -> Self {
+        DiffConfig {
+            rate: 0.1,
+            candidates: IndexMap::new(),
+            body_limit: 256.kibibytes(),
+            headers: vec!["Content-Type".into(), "Location".into()],
+        }
+    }
+}
+
+impl DiffConfig {
+    /// Returns the path of the candidate for a request to `path` using the
+    /// longest matching live prefix, if any. Prefixes are matched segment-wise.
+    pub fn candidate(&self, path: &str) -> Option<String> {
+        let (rest, candidate) = self.candidates.iter()
+            .filter_map(|(live, candidate)| {
+                let rest = path.strip_prefix(live.trim_end_matches('/'))?;
+                (rest.is_empty() || rest.starts_with('/')).then_some((rest, candidate))
+            })
+            .min_by_key(|(rest, _)| rest.len())?;
+
+        match format!("{}{}", candidate.trim_end_matches('/'), rest) {
+            path if path.is_empty() => Some("/".into()),
+            path => Some(path),
+        }
+    }
+}
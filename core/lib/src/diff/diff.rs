@@ -0,0 +1,316 @@
+use std::io::Cursor;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use state::InitCell;
+
+use crate::{Rocket, Request, Response, Data, Build};
+use crate::diff::DiffConfig;
+use crate::fairing::{self, Fairing, Info, Kind};
+use crate::http::{Header, Status};
+use crate::http::uri::Origin;
+use crate::outcome::Outcome;
+use crate::trace::Trace;
+
+/// A [`Fairing`] that [compares](crate::diff) sampled responses with those of
+/// candidate routes.
+///
+/// The configuration is read from the `diff` configuration parameter at
+/// ignition. Candidates added via [`Diff::candidate()`] are added to those
+/// configured, replacing any configured for the same live prefix, and a rate
+/// set via [`Diff::rate()`] overrides the configured one. If the parameter is
+/// invalid, a prefix doesn't begin with `/`, or the rate isn't between `0.0`
+/// and `1.0`, ignition fails.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::diff::Diff;
+/// use rocket::local::blocking::Client;
+///
+/// let diff = Diff::new().candidate("/users", "/next/users");
+/// let client = Client::debug(rocket::build().attach(diff)).unwrap();
+///
+/// let diff = client.rocket().fairing::<Diff>().unwrap();
+/// assert_eq!(diff.compared(), 0);
+/// assert_eq!(diff.mismatched(), 0);
+/// ```
+pub struct Diff {
+    candidates: Vec<(String, String)>,
+    rate: Option<f64>,
+    config: InitCell<DiffConfig>,
+    compared: AtomicU64,
+    mismatched: AtomicU64,
+}
+
+/// The normalized parts of a response that are compared.
+struct Snapshot {
+    status: Status,
+    headers: Vec<Option<String>>,
+    /// The body, if it's sized and within the limit.
+    body: Option<Vec<u8>>,
+    #[cfg_attr(not(feature = "json"), allow(dead_code))]
+    json: bool,
+}
+
+impl Diff {
+    /// Returns a `Diff` fairing.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::diff::Diff;
+    ///
+    /// let diff = Diff::new();
+    /// ```
+    pub fn new() -> Self {
+        Diff {
+            candidates: vec![],
+            rate: None,
+            config: InitCell::new(),
+            compared: AtomicU64::new(0),
+            mismatched: AtomicU64::new(0),
+        }
+    }
+
+    /// Compares responses to requests under the path prefix `live` with those
+    /// of the route handling the same request under the prefix `candidate`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::diff::Diff;
+    ///
+    /// let diff = Diff::new()
+    ///     .candidate("/users", "/next/users")
+    ///     .candidate("/orders", "/next/orders");
+    /// ```
+    pub fn candidate<L, C>(mut self, live: L, candidate: C) -> Self
+        where L: Into<String>, C: Into<String>
+    {
+        self.candidates.push((live.into(), candidate.into()));
+        self
+    }
+
+    /// Compares the fraction `rate`, from `0.0` to `1.0`, of eligible
+    /// requests, overriding [`DiffConfig::rate`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::diff::Diff;
+    ///
+    /// let diff = Diff::new().rate(1.0);
+    /// ```
+    pub fn rate(mut self, rate: f64) -> Self {
+        self.rate = Some(rate);
+        self
+    }
+
+    /// Returns the number of responses compared so far.
+    pub fn compared(&self) -> u64 {
+        self.compared.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of compared responses that differed so far.
+    pub fn mismatched(&self) -> u64 {
+        self.mismatched.load(Ordering::Relaxed)
+    }
+}
+
+impl Default for Diff {
+    fn default() -> Self {
+        Diff::new()
+    }
+}
+
+impl Snapshot {
+    /// Normalizes `res`, reading its body into memory if it's sized and no
+    /// larger than the configured limit. The body is restored after reading.
+    async fn of(res: &mut Response<'_>, config: &DiffConfig) -> Snapshot {
+        let headers = config.headers.iter()
+            .map(|name| res.headers().get_one(name).map(|v| v.to_string()))
+            .collect();
+
+        let json = res.content_type().map_or(false, |ct| ct.is_json());
+        let size = res.body_mut().size().await;
+        let body = match size {
+            Some(size) if size as u64 <= config.body_limit.as_u64() => {
+                match res.body_mut().to_bytes().await {
+                    Ok(bytes) => {
+                        res.set_sized_body(bytes.len(), Cursor::new(bytes.clone()));
+                        Some(bytes)
+                    }
+                    Err(_) => None,
+                }
+            }
+            _ => None,
+        };
+
+        Snapshot { status: res.status(), headers, body, json }
+    }
+
+    /// The snapshot of a request that failed or forwarded with `status`.
+    fn failed(status: Status, config: &DiffConfig) -> Snapshot {
+        Snapshot { status, headers: vec![None; config.headers.len()], body: None, json: false }
+    }
+
+    /// Describes how the body of `other` differs from `self`'s, if it does.
+    /// Bodies that weren't both read aren't compared.
+    fn body_diff(&self, other: &Snapshot) -> Option<String> {
+        let (Some(a), Some(b)) = (&self.body, &other.body) else {
+            return None;
+        };
+
+        #[cfg(feature = "json")]
+        if self.json && other.json {
+            let values = (serde_json::from_slice(a), serde_json::from_slice(b));
+            if let (Ok(a), Ok(b)) = values {
+                return json_diff(&a, &b, String::new())
+                    .map(|pointer| format!("JSON differs at `{pointer}`"));
+            }
+        }
+
+        let first = a.iter().zip(b.iter()).position(|(a, b)| a != b);
+        match first {
+            Some(i) => Some(format!("bytes differ at offset {i}")),
+            None if a.len() != b.len() => {
+                Some(format!("lengths differ: {} != {}", a.len(), b.len()))
+            }
+            None => None,
+        }
+    }
+}
+
+/// Returns the JSON pointer of the first difference between `a` and `b`.
+#[cfg(feature = "json")]
+fn json_diff(a: &serde_json::Value, b: &serde_json::Value, path: String) -> Option<String> {
+    use serde_json::Value;
+
+    let pointer = |key: &str| format!("{path}/{}", key.replace('~', "~0").replace('/', "~1"));
+    match (a, b) {
+        (Value::Object(a), Value::Object(b)) => {
+            let keys = a.keys().chain(b.keys().filter(|k| !a.contains_key(*k)));
+            keys.find_map(|key| match (a.get(key), b.get(key)) {
+                (Some(a), Some(b)) => json_diff(a, b, pointer(key)),
+                _ => Some(pointer(key)),
+            })
+        }
+        (Value::Array(a), Value::Array(b)) => {
+            let first = a.iter().zip(b.iter()).enumerate()
+                .find_map(|(i, (a, b))| json_diff(a, b, pointer(&i.to_string())));
+
+            match first {
+                Some(pointer) => Some(pointer),
+                None if a.len() != b.len() => Some(pointer(&a.len().min(b.len()).to_string())),
+                None => None,
+            }
+        }
+        (a, b) if a == b => None,
+        _ => Some(if path.is_empty() { "/".into() } else { path }),
+    }
+}
+
+#[crate::async_trait]
+impl Fairing for Diff {
+    fn info(&self) -> Info {
+        Info { name: "Diff", kind: Kind::Ignite | Kind::Response | Kind::Singleton }
+    }
+
+    async fn on_ignite(&self, rocket: Rocket<Build>) -> fairing::Result {
+        let mut config = match rocket.figment().contains("diff") {
+            true => match rocket.figment().extract_inner::<DiffConfig>("diff") {
+                Ok(config) => config,
+                Err(e) => {
+                    e.trace_error();
+                    return Err(rocket);
+                }
+            },
+            false => DiffConfig::default(),
+        };
+
+        config.candidates.extend(self.candidates.iter().cloned());
+        config.rate = self.rate.unwrap_or(config.rate);
+        if !(0.0..=1.0).contains(&config.rate) {
+            error!(name: "diff", rate = config.rate, "diff rate must be from 0.0 to 1.0");
+            return Err(rocket);
+        }
+
+        let mut prefixes = config.candidates.iter().flat_map(|(live, c)| [live, c]);
+        if let Some(prefix) = prefixes.find(|p| !p.starts_with('/')) {
+            error!(name: "diff", prefix, "diff prefixes must begin with `/`");
+            return Err(rocket);
+        }
+
+        if config.candidates.is_empty() {
+            info!(name: "diff", "no diff candidates configured: diffing disabled");
+        } else {
+            info!(name: "diff", candidates = config.candidates.len(), rate = config.rate,
+                "comparing responses with candidates");
+        }
+
+        self.config.set(config);
+        Ok(rocket)
+    }
+
+    async fn on_response<'r>(&self, req: &'r Request<'_>, res: &mut Response<'r>) {
+        let Some(config) = self.config.try_get() else { return };
+        if !req.method().is_safe() {
+            return;
+        }
+
+        let Some(path) = config.candidate(req.uri().path().as_str()) else { return };
+        if config.rate < 1.0 && rand::random::<f64>() >= config.rate {
+            return;
+        }
+
+        let uri = match req.uri().query() {
+            Some(query) => format!("{path}?{query}"),
+            None => path,
+        };
+
+        let uri = match Origin::parse_owned(uri) {
+            Ok(uri) => uri,
+            Err(e) => return debug!(name: "diff", uri = %req.uri(), "invalid candidate URI: {e}"),
+        };
+
+        // Dispatch the same request to the candidate. Only routing happens:
+        // neither fairings nor catchers run.
+        let rocket = req.rocket();
+        let mut request = Request::new(rocket, req.method(), uri);
+        for header in req.headers().iter() {
+            request.add_header(Header::new(header.name.to_string(), header.value.into_owned()));
+        }
+
+        request.add_header(Header::new("X-Diff-Candidate", "1"));
+        let live = Snapshot::of(res, config).await;
+        let candidate = match rocket.route(&request, Data::local(vec![])).await {
+            Outcome::Success(mut response) => Snapshot::of(&mut response, config).await,
+            Outcome::Error(status) | Outcome::Forward((_, status)) => {
+                Snapshot::failed(status, config)
+            }
+        };
+
+        self.compared.fetch_add(1, Ordering::Relaxed);
+        let headers = config.headers.iter()
+            .zip(live.headers.iter().zip(candidate.headers.iter()))
+            .filter(|(_, (a, b))| a != b)
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>();
+
+        let body = live.body_diff(&candidate);
+        if live.status == candidate.status && headers.is_empty() && body.is_none() {
+            return debug!(name: "diff", uri = %req.uri(), candidate = %request.uri(),
+                "candidate response matches");
+        }
+
+        self.mismatched.fetch_add(1, Ordering::Relaxed);
+        warn!(name: "diff",
+            uri = %req.uri(),
+            candidate = %request.uri(),
+            live_status = live.status.code,
+            candidate_status = candidate.status.code,
+            headers = (!headers.is_empty()).then(|| headers.join(", ")),
+            body,
+            "candidate response differs");
+    }
+}
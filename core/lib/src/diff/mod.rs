@@ -0,0 +1,85 @@
+//! Response diffing: comparing live responses with those of candidate routes.
+//!
+//! When rewriting a legacy endpoint, the [`Diff`] fairing validates the
+//! rewrite against real traffic before it replaces the original. The new
+//! implementation is mounted as a _candidate_ under a separate path prefix.
+//! For a sampled fraction of requests under a _live_ prefix, the request is
+//! dispatched internally to the candidate as well, with the live prefix
+//! replaced by the candidate's, and the two responses are compared. Clients
+//! only ever receive the live response.
+//!
+//! Responses are normalized before they're compared: only the status, the
+//! values of the configured `headers`, and the body are considered. With the
+//! `json` feature enabled, JSON bodies are compared structurally, so that key
+//! order and whitespace don't matter. Every mismatch is logged at the `WARN`
+//! level with structured fields naming what differs: the statuses, the
+//! mismatched headers, and the offset or [JSON pointer] of the first
+//! difference in the body. The numbers of responses compared and mismatched
+//! are available via the `Diff` fairing.
+//!
+//! A request is compared unless:
+//!
+//!   * its path isn't under a configured live prefix,
+//!   * it isn't sampled, per `rate`, or
+//!   * its method isn't safe, i.e., it isn't `GET`, `HEAD`, `OPTIONS`, or
+//!     `TRACE`, since the candidate would repeat its side effects.
+//!
+//! The candidate request carries the live request's headers and an
+//! `X-Diff-Candidate: 1` header, but no body. It is only routed: neither
+//! fairings nor catchers run for it, so a candidate that forwards or fails is
+//! compared by status alone. Bodies that are streamed or exceed `body_limit`
+//! aren't compared.
+//!
+//! Because the candidate is dispatched before the live response is sent,
+//! sampled requests take as long as both routes combined. `Diff` is meant for
+//! development and staging. Responses are compared as response fairings
+//! attached before `Diff` have left them, so attach `Diff` before fairings
+//! that transform responses, like compression.
+//!
+//! [JSON pointer]: https://datatracker.ietf.org/doc/html/rfc6901
+//!
+//! # Configuration
+//!
+//! The fairing is configured via the `diff` configuration parameter, which is
+//! deserialized as a [`DiffConfig`]. Without candidates, nothing is compared,
+//! so the fairing can be attached unconditionally and enabled per environment:
+//!
+//! ```toml
+//! [staging.diff]
+//! rate = 0.25
+//! headers = ["Content-Type", "Cache-Control"]
+//!
+//! [staging.diff.candidates]
+//! "/users" = "/next/users"
+//! ```
+//!
+//! # Example
+//!
+//! ```rust
+//! # #[macro_use] extern crate rocket;
+//! use rocket::diff::Diff;
+//!
+//! #[get("/<id>")]
+//! fn legacy_user(id: usize) -> String {
+//!     format!("user {id}")
+//! }
+//!
+//! #[get("/<id>")]
+//! fn user(id: usize) -> String {
+//!     format!("user {}", id)
+//! }
+//!
+//! #[launch]
+//! fn rocket() -> _ {
+//!     rocket::build()
+//!         .mount("/users", routes![legacy_user])
+//!         .mount("/next/users", routes![user])
+//!         .attach(Diff::new().candidate("/users", "/next/users").rate(0.05))
+//! }
+//! ```
+
+mod config;
+mod diff;
+
+pub use config::DiffConfig;
+pub use diff::Diff;
@@ -0,0 +1,170 @@
+//! Service discovery registration.
+//!
+//! The [`Advertise`] fairing closes the loop for self-registering services: at
+//! liftoff, it passes the endpoints Rocket is listening on to a [`Discovery`]
+//! implementation, which can register them with a service registry such as
+//! Consul, etcd, or DNS. When shutdown is triggered, the same endpoints are
+//! deregistered before connections are drained, so that a registry stops
+//! routing traffic to an instance as it leaves, as in a blue/green deployment.
+//!
+//! Endpoints are only deregistered if they were registered successfully.
+//! Failures to register or deregister are logged at the `ERROR` level and
+//! otherwise ignored: an unavailable registry doesn't prevent launch.
+//!
+//! Both methods of [`Discovery`] default to doing nothing, as does the
+//! implementation for `()`.
+//!
+//! # Example
+//!
+//! ```rust
+//! # #[macro_use] extern crate rocket;
+//! use rocket::{Rocket, Orbit};
+//! use rocket::listener::Endpoint;
+//! use rocket::discovery::{self, Advertise, Discovery};
+//!
+//! struct Registry {
+//!     service: &'static str,
+//! }
+//!
+//! #[rocket::async_trait]
+//! impl Discovery for Registry {
+//!     async fn register(
+//!         &self,
+//!         _: &Rocket<Orbit>,
+//!         endpoints: &[Endpoint]
+//!     ) -> discovery::Result {
+//!         for endpoint in endpoints {
+//!             println!("registering {} at {}", self.service, endpoint);
+//!         }
+//!
+//!         Ok(())
+//!     }
+//!
+//!     async fn deregister(
+//!         &self,
+//!         _: &Rocket<Orbit>,
+//!         endpoints: &[Endpoint]
+//!     ) -> discovery::Result {
+//!         for endpoint in endpoints {
+//!             println!("deregistering {} at {}", self.service, endpoint);
+//!         }
+//!
+//!         Ok(())
+//!     }
+//! }
+//!
+//! #[launch]
+//! fn rocket() -> _ {
+//!     rocket::build().attach(Advertise::new(Registry { service: "api" }))
+//! }
+//! ```
+
+use parking_lot::Mutex;
+
+use crate::{Rocket, Orbit};
+use crate::fairing::{Fairing, Info, Kind};
+use crate::listener::Endpoint;
+
+/// An error registering or deregistering endpoints.
+pub type Error = Box<dyn std::error::Error + Send + Sync>;
+
+/// The result of registering or deregistering endpoints.
+pub type Result<T = (), E = Error> = std::result::Result<T, E>;
+
+/// A service registry with which endpoints are registered.
+///
+/// See the [module level docs](self) for details and an example.
+#[crate::async_trait]
+pub trait Discovery: Send + Sync + 'static {
+    /// Registers `endpoints` at liftoff.
+    ///
+    /// The default implementation does nothing.
+    async fn register(&self, _rocket: &Rocket<Orbit>, _endpoints: &[Endpoint]) -> Result {
+        Ok(())
+    }
+
+    /// Deregisters `endpoints`, previously registered, when shutdown is
+    /// triggered.
+    ///
+    /// The default implementation does nothing.
+    async fn deregister(&self, _rocket: &Rocket<Orbit>, _endpoints: &[Endpoint]) -> Result {
+        Ok(())
+    }
+}
+
+/// A no-op registry.
+impl Discovery for () { }
+
+/// A [`Fairing`] that registers and deregisters endpoints with a
+/// [`Discovery`] registry.
+///
+/// See the [module level docs](self) for details.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::discovery::Advertise;
+/// use rocket::local::blocking::Client;
+///
+/// let client = Client::debug(rocket::build().attach(Advertise::new(()))).unwrap();
+/// let advertise = client.rocket().fairing::<Advertise<()>>().unwrap();
+/// assert_eq!(advertise.registered().len(), 1);
+/// ```
+pub struct Advertise<D> {
+    discovery: D,
+    registered: Mutex<Option<Vec<Endpoint>>>,
+}
+
+impl<D: Discovery> Advertise<D> {
+    /// Returns an `Advertise` fairing that registers endpoints with
+    /// `discovery`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::discovery::Advertise;
+    ///
+    /// let fairing = Advertise::new(());
+    /// ```
+    pub fn new(discovery: D) -> Self {
+        Advertise { discovery, registered: Mutex::new(None) }
+    }
+
+    /// Returns the registry.
+    pub fn discovery(&self) -> &D {
+        &self.discovery
+    }
+
+    /// Returns the endpoints currently registered.
+    pub fn registered(&self) -> Vec<Endpoint> {
+        self.registered.lock().clone().unwrap_or_default()
+    }
+}
+
+#[crate::async_trait]
+impl<D: Discovery> Fairing for Advertise<D> {
+    fn info(&self) -> Info {
+        Info { name: "Advertise", kind: Kind::Liftoff | Kind::Shutdown }
+    }
+
+    async fn on_liftoff(&self, rocket: &Rocket<Orbit>) {
+        let endpoints: Vec<_> = rocket.endpoints().cloned().collect();
+        match self.discovery.register(rocket, &endpoints).await {
+            Ok(()) => {
+                info!(name: "discovery", endpoints = endpoints.len(), "endpoints registered");
+                *self.registered.lock() = Some(endpoints);
+            }
+            Err(e) => error!(name: "discovery", error = %e, "failed to register endpoints"),
+        }
+    }
+
+    async fn on_shutdown(&self, rocket: &Rocket<Orbit>) {
+        let Some(endpoints) = self.registered.lock().take() else { return };
+        match self.discovery.deregister(rocket, &endpoints).await {
+            Ok(()) => {
+                info!(name: "discovery", endpoints = endpoints.len(), "endpoints deregistered");
+            }
+            Err(e) => error!(name: "discovery", error = %e, "failed to deregister endpoints"),
+        }
+    }
+}
@@ -0,0 +1,200 @@
+//! Serving requests handed over by serverless and edge runtimes.
+//!
+//! Serverless platforms and edge runtimes don't give an application a socket
+//! to listen on. Instead, they hand over individual requests and expect a
+//! response for each. This module runs Rocket's routing, guards, fairings, and
+//! responders for such requests, bypassing listeners entirely:
+//!
+//!   1. Call [`init()`] once, when the runtime starts the application, to
+//!      ignite the instance and run its liftoff fairings.
+//!   2. Call [`handle()`] for every request the runtime hands over.
+//!
+//! Requests and responses are [`http`] types with fully buffered bodies, the
+//! common denominator of edge runtimes. Responses which upgrade the connection
+//! aren't supported: the upgrade is ignored. There is no graceful shutdown;
+//! the runtime owns the lifetime of the application.
+//!
+//! To serve several applications or manage the instance manually, use an
+//! [`Edge`] directly instead of the global instance.
+//!
+//! Requests are still handled on a Tokio runtime, so the runtime must be able
+//! to host one: this module does not make Rocket compile for `wasm32` targets.
+//!
+//! This module is only available when the `edge` feature is enabled.
+//!
+//! # Example
+//!
+//! ```rust
+//! # #[macro_use] extern crate rocket;
+//! use rocket::edge::{self, http};
+//!
+//! #[get("/hello/<name>")]
+//! fn hello(name: &str) -> String {
+//!     format!("Hello, {name}!")
+//! }
+//!
+//! # rocket::async_test(async {
+//! edge::init(rocket::build().mount("/", routes![hello])).await.unwrap();
+//!
+//! let request = http::Request::get("/hello/edge").body(vec![]).unwrap();
+//! let response = edge::handle(request).await;
+//! assert_eq!(response.status(), 200);
+//! assert_eq!(response.body(), b"Hello, edge!");
+//! # });
+//! ```
+
+use std::{fmt, io};
+use std::sync::Arc;
+
+use state::InitCell;
+use tokio::io::AsyncReadExt;
+
+use crate::{Rocket, Phase, Orbit, Error};
+use crate::error::ErrorKind;
+use crate::data::Data;
+use crate::erased::ErasedRequest;
+use crate::listener::Endpoint;
+use crate::request::ConnectionMeta;
+use crate::server::NoBody;
+
+#[doc(no_inline)]
+pub use http;
+
+/// A request handed over by an edge runtime.
+pub type Request = http::Request<Vec<u8>>;
+
+/// A response returned to an edge runtime.
+pub type Response = http::Response<Vec<u8>>;
+
+/// An instance of Rocket in orbit that serves requests handed over to it.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::edge::{Edge, http};
+///
+/// # rocket::async_test(async {
+/// let edge = Edge::new(rocket::build()).await.unwrap();
+/// let request = http::Request::get("/").body(vec![]).unwrap();
+/// assert_eq!(edge.handle(request).await.status(), 404);
+/// # });
+/// ```
+pub struct Edge {
+    rocket: Arc<Rocket<Orbit>>,
+}
+
+static EDGE: InitCell<Edge> = InitCell::new();
+
+/// Ignites `rocket`, runs its liftoff fairings, and makes it the instance
+/// that serves requests passed to [`handle()`].
+///
+/// # Errors
+///
+/// Returns an error if igniting or launching `rocket` fails or, with an
+/// [`ErrorKind::Io`] of kind [`AlreadyExists`](io::ErrorKind::AlreadyExists),
+/// if an instance was already initialized. In the latter case, `rocket` is
+/// dropped and the existing instance remains.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::edge;
+///
+/// # rocket::async_test(async {
+/// edge::init(rocket::build()).await.unwrap();
+/// assert!(edge::init(rocket::build()).await.is_err());
+/// # });
+/// ```
+pub async fn init<P: Phase>(rocket: Rocket<P>) -> Result<(), Error> {
+    fn already_initialized() -> Error {
+        let msg = "edge instance already initialized";
+        Error::new(ErrorKind::Io(io::Error::new(io::ErrorKind::AlreadyExists, msg)))
+    }
+
+    if EDGE.try_get().is_some() {
+        return Err(already_initialized());
+    }
+
+    let edge = Edge::new(rocket).await?;
+    match EDGE.set(edge) {
+        true => Ok(()),
+        false => Err(already_initialized()),
+    }
+}
+
+/// Handles `request` with the instance initialized via [`init()`].
+///
+/// If no instance was initialized, responds with `503 Service Unavailable`.
+pub async fn handle(request: Request) -> Response {
+    match EDGE.try_get() {
+        Some(edge) => edge.handle(request).await,
+        None => {
+            error!(name: "edge", "request handed over before `edge::init()`");
+            let mut response = Response::new(vec![]);
+            *response.status_mut() = http::StatusCode::SERVICE_UNAVAILABLE;
+            response
+        }
+    }
+}
+
+impl Edge {
+    /// Ignites `rocket` and runs its liftoff fairings, returning an `Edge`
+    /// that serves requests with it.
+    pub async fn new<P: Phase>(rocket: Rocket<P>) -> Result<Edge, Error> {
+        let rocket = rocket.local_launch(Endpoint::new("edge")).await?;
+        Ok(Edge { rocket: Arc::new(rocket) })
+    }
+
+    /// Returns the instance of Rocket serving requests.
+    pub fn rocket(&self) -> &Rocket<Orbit> {
+        &self.rocket
+    }
+
+    /// Dispatches `request` exactly as if it had been received by a listener
+    /// and returns the response, with its body fully read.
+    pub async fn handle(&self, request: Request) -> Response {
+        let (parts, body) = request.into_parts();
+        let request = ErasedRequest::new(self.rocket.clone(), parts, |rocket, parts| {
+            crate::Request::from_hyp(rocket, parts, ConnectionMeta::default())
+                .unwrap_or_else(|e| e)
+        });
+
+        let response = request.into_response(
+            NoBody,
+            |rocket, request, data| {
+                *data = Data::local(body);
+                Box::pin(rocket.preprocess(request, data))
+            },
+            |token, rocket, request, data| Box::pin(async move {
+                if !request.errors.is_empty() {
+                    return rocket.dispatch_error(crate::http::Status::BadRequest, request).await;
+                }
+
+                rocket.dispatch(token, request, data).await
+            })
+        ).await;
+
+        let mut builder = http::Response::builder().status(response.inner().status().code);
+        for header in response.inner().headers().iter() {
+            builder = builder.header(header.name().as_str(), header.value());
+        }
+
+        let mut body = vec![];
+        if let Err(e) = Box::pin(response).read_to_end(&mut body).await {
+            warn!(name: "edge", error = %e, "failed to read response body");
+        }
+
+        builder.body(body).unwrap_or_else(|e| {
+            error!(name: "edge", error = %e, "invalid response");
+            let mut response = Response::new(vec![]);
+            *response.status_mut() = http::StatusCode::INTERNAL_SERVER_ERROR;
+            response
+        })
+    }
+}
+
+impl fmt::Debug for Edge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.rocket.fmt(f)
+    }
+}
@@ -0,0 +1,60 @@
+use serde::{Deserialize, Serialize};
+
+/// The response envelope configuration: the `envelope` configuration
+/// parameter.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::envelope::EnvelopeConfig;
+/// use rocket::figment::{Figment, providers::{Format, Toml}};
+///
+/// let figment = Figment::from(Toml::string(r#"
+///     [envelope]
+///     scopes = ["/api", "/v2"]
+///     request_id_header = "X-Request-Id"
+/// "#));
+///
+/// let config: EnvelopeConfig = figment.extract_inner("envelope").unwrap();
+/// assert_eq!(config.scopes, ["/api", "/v2"]);
+/// assert_eq!(config.request_id_header.as_deref(), Some("X-Request-Id"));
+///
+/// let config = EnvelopeConfig::default();
+/// assert_eq!(config.scopes, ["/"]);
+/// assert!(config.in_scope("/users/1"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EnvelopeConfig {
+    /// The path prefixes of requests whose responses are enveloped. A prefix
+    /// applies to a request if its path is equal to it or, segment-wise,
+    /// begins with it.
+    ///
+    /// **default: `["/"]`**
+    pub scopes: Vec<String>,
+    /// The name of the request header whose value is reported as the request
+    /// ID, if any. When `None` or the header is missing, the ID Rocket assigns
+    /// the request when tracing, if any, is reported.
+    ///
+    /// **default: `None`**
+    pub request_id_header: Option<String>,
+}
+
+impl Default for EnvelopeConfig {
+    fn default() -> Self {
+        EnvelopeConfig {
+            scopes: vec!["/".into()],
+            request_id_header: None,
+        }
+    }
+}
+
+impl EnvelopeConfig {
+    /// Returns `true` if responses to requests to `path` are enveloped.
+    pub fn in_scope(&self, path: &str) -> bool {
+        self.scopes.iter().any(|prefix| {
+            let prefix = prefix.trim_end_matches('/');
+            path.strip_prefix(prefix).map_or(false, |rest| rest.is_empty() || rest.starts_with('/'))
+        })
+    }
+}
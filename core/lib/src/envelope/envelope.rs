@@ -0,0 +1,191 @@
+use std::io::Cursor;
+use std::time::Instant;
+
+use state::InitCell;
+use serde_json::{json, Value};
+
+use crate::{Rocket, Request, Response, Data, Build};
+use crate::fairing::{self, Fairing, Info, Kind};
+use crate::http::{ContentType, Status};
+use crate::trace::Trace;
+use crate::envelope::EnvelopeConfig;
+
+/// A [`Fairing`] that wraps JSON responses in a [standard
+/// envelope](crate::envelope).
+///
+/// Scopes are read from the `envelope` configuration parameter at ignition,
+/// if it is set, and extended with those set via [`Envelope::scope()`]. If the
+/// parameter is invalid, ignition fails. When no scopes are configured either
+/// way, responses to all requests are enveloped.
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::envelope::Envelope;
+/// use rocket::local::blocking::Client;
+/// use rocket::serde::json::{Json, Value};
+///
+/// #[get("/api/n")]
+/// fn n() -> Json<usize> { Json(7) }
+///
+/// let rocket = rocket::build()
+///     .mount("/", routes![n])
+///     .attach(Envelope::new().scope("/api"));
+///
+/// let client = Client::debug(rocket).unwrap();
+/// let body: Value = client.get("/api/n").dispatch().into_json().unwrap();
+/// assert_eq!(body["data"], 7);
+/// assert!(body["meta"]["duration_ms"].is_u64());
+/// ```
+pub struct Envelope {
+    scopes: Vec<String>,
+    config: InitCell<EnvelopeConfig>,
+}
+
+/// When a request was received.
+struct Received(Option<Instant>);
+
+impl Envelope {
+    /// Returns an `Envelope` fairing.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::envelope::Envelope;
+    ///
+    /// let envelope = Envelope::new();
+    /// ```
+    pub fn new() -> Self {
+        Envelope { scopes: vec![], config: InitCell::new() }
+    }
+
+    /// Envelopes responses to requests to `path` and paths under it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::envelope::Envelope;
+    ///
+    /// let envelope = Envelope::new().scope("/api").scope("/v2");
+    /// ```
+    pub fn scope<P: Into<String>>(mut self, path: P) -> Self {
+        self.scopes.push(path.into());
+        self
+    }
+
+    /// Returns the request ID of `req`, if any.
+    fn request_id(config: &EnvelopeConfig, req: &Request<'_>) -> Option<String> {
+        let header = config.request_id_header.as_deref();
+        if let Some(id) = header.and_then(|name| req.headers().get_one(name)) {
+            return Some(id.to_string());
+        }
+
+        #[cfg(feature = "trace")]
+        let traced = crate::trace::subscriber::RequestId::current().map(|id| format!("{id:x}"));
+
+        #[cfg(not(feature = "trace"))]
+        let traced = None;
+
+        traced
+    }
+}
+
+impl Default for Envelope {
+    fn default() -> Self {
+        Envelope::new()
+    }
+}
+
+#[crate::async_trait]
+impl Fairing for Envelope {
+    fn info(&self) -> Info {
+        Info { name: "Envelope", kind: Kind::Ignite | Kind::Request | Kind::Response }
+    }
+
+    async fn on_ignite(&self, rocket: Rocket<Build>) -> fairing::Result {
+        let mut config = match rocket.figment().contains("envelope") {
+            true => match rocket.figment().extract_inner::<EnvelopeConfig>("envelope") {
+                Ok(config) => config,
+                Err(e) => {
+                    e.trace_error();
+                    return Err(rocket);
+                }
+            },
+            false => EnvelopeConfig::default(),
+        };
+
+        if !self.scopes.is_empty() {
+            if !rocket.figment().contains("envelope.scopes") {
+                config.scopes.clear();
+            }
+
+            config.scopes.extend(self.scopes.iter().cloned());
+        }
+
+        self.config.set(config);
+        Ok(rocket)
+    }
+
+    async fn on_request(&self, req: &mut Request<'_>, _: &mut Data<'_>) {
+        req.local_cache(|| Received(Some(Instant::now())));
+    }
+
+    async fn on_response<'r>(&self, req: &'r Request<'_>, res: &mut Response<'r>) {
+        let Some(config) = self.config.try_get() else { return };
+        if !config.in_scope(req.uri().path().as_str()) {
+            return;
+        }
+
+        let status = res.status();
+        let error = status.code >= 400;
+        let json = res.content_type().map_or(false, |ct| {
+            ct.is_json() || ct.sub().as_str().ends_with("+json")
+        });
+
+        let empty = res.body().is_none();
+        if (!error && (!json || empty)) || status == Status::NoContent {
+            return;
+        }
+
+        let body = match res.body_mut().to_bytes().await {
+            Ok(body) => body,
+            Err(e) => {
+                warn!(name: "envelope", error = %e, "failed to read response body");
+                return;
+            }
+        };
+
+        let value = json.then(|| serde_json::from_slice::<Value>(&body).ok()).flatten();
+        if !error && value.is_none() {
+            res.set_sized_body(body.len(), Cursor::new(body));
+            return;
+        }
+
+        let received = req.local_cache(|| Received(None));
+        let mut meta = json!({});
+        if let Some(id) = Self::request_id(config, req) {
+            meta["request_id"] = id.into();
+        }
+
+        if let Some(start) = received.0 {
+            meta["duration_ms"] = (start.elapsed().as_millis() as u64).into();
+        }
+
+        let envelope = match error {
+            false => json!({ "data": value, "meta": meta }),
+            true => json!({
+                "error": {
+                    "status": status.code,
+                    "reason": status.reason(),
+                    "detail": value,
+                },
+                "meta": meta,
+            }),
+        };
+
+        let body = envelope.to_string();
+        res.set_header(ContentType::JSON);
+        res.set_sized_body(body.len(), Cursor::new(body));
+    }
+}
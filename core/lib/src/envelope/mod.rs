@@ -0,0 +1,66 @@
+//! Standard response envelopes for JSON APIs.
+//!
+//! The [`Envelope`] fairing wraps the JSON responses to requests in the
+//! configured scopes in a standard envelope, so that handlers return bare
+//! values while clients receive the format a team has mandated:
+//!
+//! ```json
+//! {
+//!   "data": { "id": 1, "name": "bob" },
+//!   "meta": { "request_id": "5d2a6c1f", "duration_ms": 3 }
+//! }
+//! ```
+//!
+//! Error responses, those with a `4xx` or `5xx` status, are enveloped
+//! consistently whatever produced them, be it a handler or a catcher, and
+//! whatever their format. Their `Content-Type` is set to JSON and the body is
+//! replaced with an `error` object carrying the status code and reason. If the
+//! original body was JSON, it is kept as the `detail`:
+//!
+//! ```json
+//! {
+//!   "error": { "status": 404, "reason": "Not Found", "detail": null },
+//!   "meta": { "request_id": "5d2a6c1f", "duration_ms": 0 }
+//! }
+//! ```
+//!
+//! Other responses, such as files and HTML pages, and responses without a
+//! body are left as they are. The `request_id` is omitted when there is none:
+//! when neither the configured request header nor tracing provide one.
+//!
+//! # Configuration
+//!
+//! Scopes are configured via the `envelope` configuration parameter, which is
+//! deserialized as an [`EnvelopeConfig`], and via [`Envelope::scope()`]:
+//!
+//! ```toml
+//! [default.envelope]
+//! scopes = ["/api"]
+//! request_id_header = "X-Request-Id"
+//! ```
+//!
+//! # Example
+//!
+//! ```rust
+//! # #[macro_use] extern crate rocket;
+//! use rocket::envelope::Envelope;
+//! use rocket::serde::json::{json, Value};
+//!
+//! #[get("/api/user")]
+//! fn user() -> Value {
+//!     json!({ "id": 1, "name": "bob" })
+//! }
+//!
+//! #[launch]
+//! fn rocket() -> _ {
+//!     rocket::build()
+//!         .mount("/", routes![user])
+//!         .attach(Envelope::new().scope("/api"))
+//! }
+//! ```
+
+mod config;
+mod envelope;
+
+pub use config::EnvelopeConfig;
+pub use envelope::Envelope;
@@ -132,6 +132,15 @@ impl ErasedResponse {
         &self.response
     }
 
+    pub fn request(&self) -> &Request<'_> {
+        self._request.inner()
+    }
+
+    #[cfg(feature = "cache")]
+    pub fn request_parts(&self) -> &Parts {
+        &self._request._parts
+    }
+
     pub fn with_inner_mut<'a, T>(
         &'a mut self,
         f: impl for<'r> FnOnce(&'a mut Response<'r>) -> T
@@ -9,6 +9,7 @@ use figment::Profile;
 use crate::listener::Endpoint;
 use crate::{Catcher, Ignite, Orbit, Phase, Rocket, Route};
 use crate::trace::Trace;
+use crate::sentinel::Diagnostic;
 
 /// An error that occurred during launch or ignition.
 ///
@@ -19,6 +20,12 @@ use crate::trace::Trace;
 /// instance fails to liftoff or when the Rocket instance fails to shutdown.
 /// Finally, an `Error` may occur when a sentinel requests an abort.
 ///
+/// Ignition checks for all of the failures it can detect independently of one
+/// another, failed ignite fairings, invalid configuration, collisions, and
+/// sentinel aborts, before failing. When more than one is detected, they are
+/// reported together in a single [`ErrorKind::Aggregate`] error, so that they
+/// can all be fixed at once. [`Error::errors()`] iterates over each.
+///
 /// To determine the kind of error that occurred, use [`Error::kind()`].
 ///
 /// # Example
@@ -42,7 +49,8 @@ use crate::trace::Trace;
 /// # }
 /// ```
 pub struct Error {
-    pub(crate) kind: ErrorKind
+    pub(crate) kind: ErrorKind,
+    pub(crate) diagnostics: Vec<Diagnostic>,
 }
 
 /// The error kind that occurred. Returned by [`Error::kind()`].
@@ -83,6 +91,9 @@ pub enum ErrorKind {
     ),
     /// Shutdown failed. Contains the Rocket instance that failed to shutdown.
     Shutdown(Arc<Rocket<Orbit>>),
+    /// More than one independent failure occurred during ignition. Contains
+    /// at least two errors, none of which is itself an aggregate.
+    Aggregate(Vec<Error>),
 }
 
 /// An error that occurs when a value was unexpectedly empty.
@@ -146,7 +157,20 @@ impl std::error::Error for InvalidOption<'_> {}
 impl Error {
     #[inline(always)]
     pub(crate) fn new(kind: ErrorKind) -> Error {
-        Error { kind }
+        Error { kind, diagnostics: vec![] }
+    }
+
+    /// Returns the only error in `errors` or an aggregate of all of them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `errors` is empty.
+    pub(crate) fn aggregate(mut errors: Vec<Error>) -> Error {
+        match errors.len() {
+            0 => panic!("an aggregate error contains at least one error"),
+            1 => errors.remove(0),
+            _ => Error::new(ErrorKind::Aggregate(errors)),
+        }
     }
 
     /// Returns the kind of error that occurred.
@@ -173,6 +197,153 @@ impl Error {
         &self.kind
     }
 
+    /// Returns `true` if this is an error binding to a network interface.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use rocket::*;
+    /// # async fn run() {
+    /// if let Err(e) = rocket::build().launch().await {
+    ///     if e.is_bind() {
+    ///         info!("binding to {:?} failed", e.endpoint());
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    pub fn is_bind(&self) -> bool {
+        matches!(self.kind, ErrorKind::Bind(..))
+    }
+
+    /// Returns `true` if this is, or is an aggregate containing, a route or
+    /// catcher collision error.
+    pub fn is_collision(&self) -> bool {
+        self.errors().any(|e| matches!(e.kind, ErrorKind::Collisions { .. }))
+    }
+
+    /// Returns `true` if this is, or is an aggregate containing, a
+    /// configuration error: either the configuration could not be extracted
+    /// or it is insecure.
+    pub fn is_config(&self) -> bool {
+        self.errors().any(|e| {
+            matches!(e.kind, ErrorKind::Config(_) | ErrorKind::InsecureSecretKey(_))
+        })
+    }
+
+    /// Returns `true` if this is an aggregate of independent errors.
+    pub fn is_aggregate(&self) -> bool {
+        matches!(self.kind, ErrorKind::Aggregate(_))
+    }
+
+    /// Returns an iterator over the errors in this error: each error if this
+    /// is an [aggregate](ErrorKind::Aggregate), otherwise only `self`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #[macro_use] extern crate rocket;
+    /// use rocket::figment::Figment;
+    ///
+    /// #[get("/")] fn a() {}
+    /// #[get("/")] fn b() {}
+    ///
+    /// # rocket::async_test(async {
+    /// let figment = rocket::Config::figment().merge(("workers", "many"));
+    /// let rocket = rocket::custom(figment).mount("/", routes![a, b]);
+    ///
+    /// let error = rocket.ignite().await.unwrap_err();
+    /// assert!(error.is_aggregate());
+    /// assert!(error.is_config() && error.is_collision());
+    /// assert_eq!(error.errors().count(), 2);
+    /// # });
+    /// ```
+    pub fn errors(&self) -> impl Iterator<Item = &Error> {
+        match &self.kind {
+            ErrorKind::Aggregate(errors) => errors.as_slice(),
+            _ => std::slice::from_ref(self),
+        }.iter()
+    }
+
+    /// Returns the endpoint that failed to bind, if this is a bind error and
+    /// the endpoint is known.
+    pub fn endpoint(&self) -> Option<&Endpoint> {
+        match &self.kind {
+            ErrorKind::Bind(endpoint, _) => endpoint.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Returns the configuration error if this is, or is an aggregate
+    /// containing, a configuration error.
+    pub fn config_error(&self) -> Option<&figment::Error> {
+        self.errors().find_map(|e| match &e.kind {
+            ErrorKind::Config(e) => Some(e),
+            _ => None,
+        })
+    }
+
+    /// Returns the pairs of colliding routes and catchers, in that order, if
+    /// this is, or is an aggregate containing, a collision error.
+    pub fn collisions(&self) -> Option<(&[(Route, Route)], &[(Catcher, Catcher)])> {
+        self.errors().find_map(|e| match &e.kind {
+            ErrorKind::Collisions { routes, catchers } => Some((&routes[..], &catchers[..])),
+            _ => None,
+        })
+    }
+
+    /// Returns a diagnostic for each sentinel that triggered an abort if this
+    /// is, or is an aggregate containing, a sentinel abort error. Otherwise,
+    /// returns an empty slice.
+    pub fn sentinel_diagnostics(&self) -> &[Diagnostic] {
+        self.errors()
+            .map(|e| &e.diagnostics[..])
+            .find(|diagnostics| !diagnostics.is_empty())
+            .unwrap_or_default()
+    }
+
+    /// Returns the first [`io::Error`] in this error's chain of sources, if
+    /// any, including `self` when this is an `Io` error.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # async fn run() {
+    /// use std::io;
+    ///
+    /// if let Err(e) = rocket::build().launch().await {
+    ///     match e.io_error().map(|e| e.kind()) {
+    ///         Some(io::ErrorKind::AddrInUse) => { /* rebind to another port */ },
+    ///         Some(io::ErrorKind::PermissionDenied) => { /* exit */ },
+    ///         _ => { /* ... */ },
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    pub fn io_error(&self) -> Option<&io::Error> {
+        let mut error: Option<&(dyn StdError + 'static)> = Some(self);
+        while let Some(e) = error {
+            if let Some(e) = e.downcast_ref::<io::Error>() {
+                return Some(e);
+            }
+
+            error = e.source();
+        }
+
+        None
+    }
+
+    /// Returns `true` if the error is likely to be transient such that
+    /// retrying the same operation may succeed.
+    ///
+    /// Presently, this is the case only for bind errors caused by an address
+    /// that is in use or unavailable, as occurs when a previous instance of the
+    /// server is still shutting down.
+    pub fn is_transient(&self) -> bool {
+        self.is_bind() && self.io_error().map_or(false, |e| matches!(e.kind(),
+            io::ErrorKind::AddrInUse | io::ErrorKind::AddrNotAvailable
+        ))
+    }
+
     /// Given the return value of [`Rocket::launch()`] or [`Rocket::ignite()`],
     /// which return a `Result<Rocket<P>, Error>`, logs the error, if any, and
     /// returns the appropriate exit code.
@@ -196,7 +367,7 @@ impl Error {
             Ok(_) => process::ExitCode::SUCCESS,
             Err(e) => {
                 span_error!("launch failure", "aborting launch due to error" => e.trace_error());
-                process::ExitCode::SUCCESS
+                process::ExitCode::FAILURE
             }
         }
     }
@@ -232,6 +403,7 @@ impl StdError for Error {
             ErrorKind::SentinelAborts(_) => None,
             ErrorKind::Liftoff(_, e) => Some(e),
             ErrorKind::Shutdown(_) => None,
+            ErrorKind::Aggregate(_) => None,
         }
     }
 }
@@ -249,6 +421,7 @@ impl fmt::Display for ErrorKind {
             ErrorKind::SentinelAborts(_) => "sentinel(s) aborted".fmt(f),
             ErrorKind::Liftoff(_, _) => "liftoff failed".fmt(f),
             ErrorKind::Shutdown(_) => "shutdown failed".fmt(f),
+            ErrorKind::Aggregate(errors) => write!(f, "{} errors occurred", errors.len()),
         }
     }
 }
@@ -0,0 +1,168 @@
+//! A broadcast of lifecycle events.
+//!
+//! [`Rocket::events()`](crate::Rocket::events()) subscribes to the lifecycle
+//! events of an instance of Rocket, in any phase, returning an [`Events`]
+//! receiver. Embedders, supervisors, and plugins can use it to react to
+//! ignition, liftoff, shutdown, and failures without writing a fairing for
+//! each hook. The events are, in the order they occur:
+//!
+//!   * [`Event::Ignited`]: ignition succeeded.
+//!   * [`Event::Liftoff`]: liftoff fairings completed; requests are served.
+//!   * [`Event::ShutdownRequested`]: graceful shutdown was triggered.
+//!   * [`Event::Terminated`]: requests were force-terminated, if any were.
+//!   * [`Event::Drained`]: pending I/O completed, or didn't in time.
+//!
+//! [`Event::ListenerClosed`], [`Event::RoutesChanged`], and
+//! [`Event::TaskFailed`] may occur at any point after liftoff.
+//!
+//! A subscriber only receives events emitted after it subscribes. A subscriber
+//! that falls more than [`CAPACITY`] events behind misses the oldest ones. The
+//! receiver ends when the instance of Rocket is dropped.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use rocket::events::Event;
+//!
+//! #[rocket::main]
+//! async fn main() -> Result<(), rocket::Error> {
+//!     let rocket = rocket::build();
+//!     let mut events = rocket.events();
+//!     rocket::tokio::spawn(async move {
+//!         while let Some(event) = events.recv().await {
+//!             match event {
+//!                 Event::ShutdownRequested { .. } => println!("deregistering"),
+//!                 Event::TaskFailed { task, error } => eprintln!("{task}: {error}"),
+//!                 _ => {}
+//!             }
+//!         }
+//!     });
+//!
+//!     let _rocket = rocket.launch().await?;
+//!     Ok(())
+//! }
+//! ```
+
+use futures::stream::{self, Stream};
+use tokio::sync::broadcast::{self, error::RecvError};
+
+use crate::listener::Endpoint;
+use crate::shutdown::{Sig, DrainReport};
+
+/// The number of events a subscriber can fall behind before missing events.
+pub const CAPACITY: usize = 64;
+
+/// A lifecycle event. See the [module docs](self) for details.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum Event {
+    /// Ignition succeeded.
+    Ignited,
+    /// Liftoff fairings completed: requests are being served at `endpoints`.
+    Liftoff {
+        /// The endpoints the server is listening on.
+        endpoints: Vec<Endpoint>,
+    },
+    /// Graceful shutdown was triggered, either by `signal` or, if it is
+    /// `None`, via [`Shutdown::notify()`](crate::Shutdown::notify()).
+    ShutdownRequested {
+        /// The signal that triggered shutdown, if any.
+        signal: Option<Sig>,
+    },
+    /// Requests in flight at the end of the shutdown grace period were
+    /// force-terminated. Emitted just before [`Event::Drained`].
+    Terminated {
+        /// The report of the terminated requests.
+        report: DrainReport,
+    },
+    /// Shutdown completed. If `graceful` is `false`, some I/O was still
+    /// outstanding at the end of the mercy period.
+    Drained {
+        /// Whether all pending I/O completed.
+        graceful: bool,
+    },
+    /// The listener at `endpoint` was closed while the server kept serving on
+    /// others. See [`Rocket::close_listeners()`](crate::Rocket::close_listeners()).
+    ListenerClosed {
+        /// The endpoint of the closed listener.
+        endpoint: Endpoint,
+    },
+    /// Routes were mounted or unmounted while the server was running. See
+    /// [`Rocket::mount_live()`](crate::Rocket::mount_live()).
+    RoutesChanged {
+        /// The number of routes mounted.
+        mounted: usize,
+        /// The number of routes unmounted.
+        unmounted: usize,
+    },
+    /// A background task failed.
+    TaskFailed {
+        /// A description of the task.
+        task: String,
+        /// The error the task failed with.
+        error: String,
+    },
+}
+
+/// A receiver of lifecycle [`Event`]s. Returned by
+/// [`Rocket::events()`](crate::Rocket::events()).
+pub struct Events {
+    receiver: broadcast::Receiver<Event>,
+}
+
+/// The sending half of the lifecycle event broadcast.
+#[derive(Debug, Clone)]
+pub(crate) struct EventBus {
+    sender: broadcast::Sender<Event>,
+}
+
+impl Events {
+    /// Receives the next event. Returns `None` once the instance of Rocket is
+    /// dropped and all events have been received.
+    pub async fn recv(&mut self) -> Option<Event> {
+        loop {
+            match self.receiver.recv().await {
+                Ok(event) => return Some(event),
+                Err(RecvError::Lagged(n)) => warn!(missed = n, "lifecycle subscriber lagged"),
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    }
+
+    /// Returns the next event if one is ready without waiting.
+    pub fn try_recv(&mut self) -> Option<Event> {
+        use tokio::sync::broadcast::error::TryRecvError;
+
+        loop {
+            match self.receiver.try_recv() {
+                Ok(event) => return Some(event),
+                Err(TryRecvError::Lagged(n)) => warn!(missed = n, "lifecycle subscriber lagged"),
+                Err(TryRecvError::Empty | TryRecvError::Closed) => return None,
+            }
+        }
+    }
+
+    /// Converts this receiver into a [`Stream`] of events.
+    pub fn into_stream(self) -> impl Stream<Item = Event> + Send + 'static {
+        stream::unfold(self, |mut events| async move {
+            events.recv().await.map(|event| (event, events))
+        })
+    }
+}
+
+impl EventBus {
+    pub fn subscribe(&self) -> Events {
+        Events { receiver: self.sender.subscribe() }
+    }
+
+    pub fn emit(&self, event: Event) {
+        // An error means there are no subscribers, which is fine.
+        let _ = self.sender.send(event);
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        EventBus { sender: broadcast::channel(CAPACITY).0 }
+    }
+}
@@ -2,6 +2,7 @@ use parking_lot::Mutex;
 use futures::future::{Future, BoxFuture, FutureExt};
 
 use crate::{Rocket, Request, Response, Data, Build, Orbit};
+use crate::response;
 use crate::fairing::{Fairing, Kind, Info, Result};
 use crate::route::RouteUri;
 use crate::trace::Trace;
@@ -13,9 +14,10 @@ use crate::trace::Trace;
 ///
 /// # Usage
 ///
-/// Use [`AdHoc::on_ignite`], [`AdHoc::on_liftoff`], [`AdHoc::on_request()`], or
-/// [`AdHoc::on_response()`] to create an `AdHoc` structure from a function or
-/// closure. Then, simply attach the structure to the `Rocket` instance.
+/// Use [`AdHoc::on_ignite`], [`AdHoc::on_liftoff`], [`AdHoc::on_request()`],
+/// [`AdHoc::on_dispatch()`], or [`AdHoc::on_response()`] to create an `AdHoc`
+/// structure from a function or closure. Then, simply attach the structure to
+/// the `Rocket` instance.
 ///
 /// # Example
 ///
@@ -63,6 +65,10 @@ enum AdHocKind {
     Request(Box<dyn for<'a> Fn(&'a mut Request<'_>, &'a mut Data<'_>)
         -> BoxFuture<'a, ()> + Send + Sync + 'static>),
 
+    /// An ad-hoc **dispatch** fairing. Called just before a request is routed.
+    Dispatch(Box<dyn for<'r> Fn(&'r Request<'_>)
+        -> BoxFuture<'r, Option<response::Result<'r>>> + Send + Sync + 'static>),
+
     /// An ad-hoc **response** fairing. Called when a response is ready to be
     /// sent to a client.
     Response(Box<dyn for<'r, 'b> Fn(&'r Request<'_>, &'b mut Response<'r>)
@@ -159,6 +165,30 @@ impl AdHoc {
         AdHoc { name, kind: AdHocKind::Request(Box::new(f)) }
     }
 
+    /// Constructs an `AdHoc` dispatch fairing named `name`. The function `f`
+    /// will be called and the returned `Future` will be `await`ed by Rocket
+    /// just before a request is routed. If it resolves to `Some`, the request
+    /// is answered without being routed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::fairing::AdHoc;
+    /// use rocket::http::Status;
+    ///
+    /// // A fairing that refuses requests without a `Host` header.
+    /// let fairing = AdHoc::on_dispatch("Host Required", |req| {
+    ///     Box::pin(async move {
+    ///         req.host().is_none().then_some(Err(Status::BadRequest))
+    ///     })
+    /// });
+    /// ```
+    pub fn on_dispatch<F: Send + Sync + 'static>(name: &'static str, f: F) -> AdHoc
+        where F: for<'r> Fn(&'r Request<'_>) -> BoxFuture<'r, Option<response::Result<'r>>>
+    {
+        AdHoc { name, kind: AdHocKind::Dispatch(Box::new(f)) }
+    }
+
     // FIXME(rustc): We'd like to allow passing `async fn` to these methods...
     // https://github.com/rust-lang/rust/issues/64552#issuecomment-666084589
 
@@ -321,7 +351,8 @@ impl AdHoc {
         impl Normalizer {
             fn routes(&self, rocket: &Rocket<Orbit>) -> &[crate::Route] {
                 self.routes.get_or_init(|| {
-                    rocket.routes()
+                    rocket.router.current()
+                        .routes()
                         .filter(|r| r.uri.has_trailing_slash())
                         .cloned()
                         .collect()
@@ -346,7 +377,7 @@ impl AdHoc {
                 // same rank and handler as the `/foo/<bar..>` route and mount
                 // it to this instance of `rocket`. This preserves the previous
                 // matching while still checking request guards.
-                let normalized_trailing = rocket.routes()
+                let normalized_trailing = rocket.routes.iter()
                     .filter(|r| r.uri.metadata.dynamic_trail)
                     .filter(|r| r.uri.path().segments().num() > 1)
                     .filter_map(|route| {
@@ -407,6 +438,7 @@ impl Fairing for AdHoc {
             AdHocKind::Ignite(_) => Kind::Ignite,
             AdHocKind::Liftoff(_) => Kind::Liftoff,
             AdHocKind::Request(_) => Kind::Request,
+            AdHocKind::Dispatch(_) => Kind::Dispatch,
             AdHocKind::Response(_) => Kind::Response,
             AdHocKind::Shutdown(_) => Kind::Shutdown,
         };
@@ -433,6 +465,13 @@ impl Fairing for AdHoc {
         }
     }
 
+    async fn on_dispatch<'r>(&self, req: &'r Request<'_>) -> Option<response::Result<'r>> {
+        match self.kind {
+            AdHocKind::Dispatch(ref f) => f(req).await,
+            _ => None
+        }
+    }
+
     async fn on_response<'r>(&self, req: &'r Request<'_>, res: &mut Response<'r>) {
         if let AdHocKind::Response(ref f) = self.kind {
             f(req, res).await
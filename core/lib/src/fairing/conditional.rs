@@ -0,0 +1,145 @@
+use std::fmt;
+use std::borrow::Cow;
+
+use figment::Figment;
+use state::InitCell;
+
+use crate::{Rocket, Request, Response, Data, Build, Orbit};
+use crate::response;
+use crate::fairing::{Fairing, Info, Kind, Result};
+use crate::route::Condition;
+use crate::trace::Trace;
+
+/// A fairing that is only enabled if a condition on the configuration holds
+/// at ignition. Created via [`Rocket::attach_if()`] and
+/// [`Rocket::attach_when()`].
+///
+/// The fairing takes the place of the one it wraps so that, when enabled, its
+/// callbacks run in attach order. When disabled, none of them run.
+pub(crate) struct Conditional {
+    fairing: Box<dyn Fairing>,
+    gate: Gate,
+    enabled: InitCell<bool>,
+}
+
+enum Gate {
+    Config(Condition),
+    When(Box<dyn Fn(&Figment) -> bool + Send + Sync + 'static>),
+}
+
+impl Conditional {
+    pub fn config<K>(key: K, fairing: Box<dyn Fairing>) -> Self
+        where K: Into<Cow<'static, str>>
+    {
+        let gate = Gate::Config(Condition::new().config(key));
+        Conditional { fairing, gate, enabled: InitCell::new() }
+    }
+
+    pub fn when<F>(predicate: F, fairing: Box<dyn Fairing>) -> Self
+        where F: Fn(&Figment) -> bool + Send + Sync + 'static
+    {
+        let gate = Gate::When(Box::new(predicate));
+        Conditional { fairing, gate, enabled: InitCell::new() }
+    }
+
+    fn enabled(&self) -> bool {
+        self.enabled.try_get().copied().unwrap_or(false)
+    }
+
+    /// The wrapped fairing unless the condition is known not to hold.
+    pub fn inner(&self) -> Option<&dyn Fairing> {
+        match self.enabled.try_get() {
+            Some(false) => None,
+            _ => Some(&*self.fairing),
+        }
+    }
+
+    /// Mutable version of [`Conditional::inner()`].
+    pub fn inner_mut(&mut self) -> Option<&mut dyn Fairing> {
+        match self.enabled.try_get() {
+            Some(false) => None,
+            _ => Some(&mut *self.fairing),
+        }
+    }
+}
+
+impl fmt::Display for Gate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Gate::Config(condition) => condition.fmt(f),
+            Gate::When(_) => f.write_str("predicate"),
+        }
+    }
+}
+
+#[crate::async_trait]
+impl Fairing for Conditional {
+    fn info(&self) -> Info {
+        // The singleton kind is dropped: it would apply to all `Conditional`s.
+        let info = self.fairing.info();
+        let kinds = [Kind::Liftoff, Kind::Request, Kind::Dispatch, Kind::Response, Kind::Shutdown];
+        let kind = kinds.into_iter()
+            .filter(|&kind| info.kind.is(kind))
+            .fold(Kind::Ignite, |acc, kind| acc | kind);
+
+        Info { name: info.name, kind }
+    }
+
+    async fn on_ignite(&self, mut rocket: Rocket<Build>) -> Result {
+        let enabled = match &self.gate {
+            Gate::Config(condition) => match condition.holds(rocket.figment()) {
+                Ok(enabled) => enabled,
+                Err(e) => {
+                    e.trace_error();
+                    return Err(rocket);
+                }
+            },
+            Gate::When(predicate) => predicate(rocket.figment()),
+        };
+
+        self.enabled.set(enabled);
+        let info = self.fairing.info();
+        info!(name: "conditional", fairing = info.name, condition = %self.gate, enabled,
+            "conditional fairing");
+        if !enabled {
+            rocket.fairings.disable(info);
+            return Ok(rocket);
+        }
+
+        match info.kind.is(Kind::Ignite) {
+            true => self.fairing.on_ignite(rocket).await,
+            false => Ok(rocket),
+        }
+    }
+
+    async fn on_liftoff(&self, rocket: &Rocket<Orbit>) {
+        if self.enabled() {
+            self.fairing.on_liftoff(rocket).await
+        }
+    }
+
+    async fn on_request(&self, req: &mut Request<'_>, data: &mut Data<'_>) {
+        if self.enabled() {
+            self.fairing.on_request(req, data).await
+        }
+    }
+
+    async fn on_dispatch<'r>(&self, req: &'r Request<'_>) -> Option<response::Result<'r>> {
+        match self.enabled() {
+            true => self.fairing.on_dispatch(req).await,
+            false => None,
+        }
+    }
+
+    async fn on_response<'r>(&self, req: &'r Request<'_>, res: &mut Response<'r>) {
+        if self.enabled() {
+            self.fairing.on_response(req, res).await
+        }
+    }
+
+    async fn on_shutdown(&self, rocket: &Rocket<Orbit>) {
+        if self.enabled() {
+            self.fairing.on_shutdown(rocket).await
+        }
+    }
+}
@@ -1,5 +1,7 @@
 use crate::{Rocket, Request, Response, Data, Build, Orbit};
+use crate::response;
 use crate::fairing::{Fairing, Info, Kind};
+use crate::request::Stage;
 
 #[derive(Default)]
 pub struct Fairings {
@@ -7,12 +9,15 @@ pub struct Fairings {
     all_fairings: Vec<Box<dyn Fairing>>,
     // Ignite fairings that have failed.
     failures: Vec<Info>,
+    // Conditional fairings whose condition didn't hold.
+    disabled: Vec<Info>,
     // The number of ignite fairings from `self.ignite` we've run.
     num_ignited: usize,
     // The vectors below hold indices into `all_fairings`.
     ignite: Vec<usize>,
     liftoff: Vec<usize>,
     request: Vec<usize>,
+    dispatch: Vec<usize>,
     response: Vec<usize>,
     shutdown: Vec<usize>,
 }
@@ -42,6 +47,7 @@ impl Fairings {
         self.ignite.iter()
             .chain(self.liftoff.iter())
             .chain(self.request.iter())
+            .chain(self.dispatch.iter())
             .chain(self.response.iter())
             .chain(self.shutdown.iter())
     }
@@ -102,6 +108,7 @@ impl Fairings {
                 remove(i, &mut self.ignite);
                 remove(i, &mut self.liftoff);
                 remove(i, &mut self.request);
+                remove(i, &mut self.dispatch);
                 remove(i, &mut self.response);
                 remove(i, &mut self.shutdown);
             }
@@ -112,6 +119,7 @@ impl Fairings {
         if this_info.kind.is(Kind::Ignite) { self.ignite.push(index); }
         if this_info.kind.is(Kind::Liftoff) { self.liftoff.push(index); }
         if this_info.kind.is(Kind::Request) { self.request.push(index); }
+        if this_info.kind.is(Kind::Dispatch) { self.dispatch.push(index); }
         if this_info.kind.is(Kind::Response) { self.response.push(index); }
         if this_info.kind.is(Kind::Shutdown) { self.shutdown.push(index); }
     }
@@ -120,6 +128,8 @@ impl Fairings {
         for fairing in others.all_fairings.drain(..) {
             self.add(fairing);
         }
+
+        self.disabled.append(&mut others.disabled);
     }
 
     pub async fn handle_ignite(mut rocket: Rocket<Build>) -> Rocket<Build> {
@@ -157,14 +167,32 @@ impl Fairings {
     #[inline(always)]
     pub async fn handle_request(&self, req: &mut Request<'_>, data: &mut Data<'_>) {
         for fairing in iter!(self.request) {
-            fairing.on_request(req, data).await
+            let timer = Stage::start(req);
+            fairing.on_request(req, data).await;
+            Stage::record(req, timer, "request", fairing.info().name);
         }
     }
 
+    #[inline(always)]
+    pub async fn handle_dispatch<'r>(&self, req: &'r Request<'_>) -> Option<response::Result<'r>> {
+        for fairing in iter!(self.dispatch) {
+            let timer = Stage::start(req);
+            let answer = fairing.on_dispatch(req).await;
+            Stage::record(req, timer, "dispatch", fairing.info().name);
+            if answer.is_some() {
+                return answer;
+            }
+        }
+
+        None
+    }
+
     #[inline(always)]
     pub async fn handle_response<'r>(&self, req: &'r Request<'_>, res: &mut Response<'r>) {
         for fairing in iter!(self.response) {
+            let timer = Stage::start(req);
             fairing.on_response(req, res).await;
+            Stage::record(req, timer, "response", fairing.info().name);
         }
     }
 
@@ -174,6 +202,14 @@ impl Fairings {
         futures::future::join_all(shutdown_futures).await;
     }
 
+    pub fn disable(&mut self, info: Info) {
+        self.disabled.push(info);
+    }
+
+    pub fn disabled(&self) -> &[Info] {
+        &self.disabled
+    }
+
     pub fn audit(&self) -> Result<(), &[Info]> {
         match &self.failures[..] {
             [] => Ok(()),
@@ -213,6 +249,7 @@ impl std::fmt::Debug for Fairings {
             .field("launch", &debug_info(iter!(self.ignite)))
             .field("liftoff", &debug_info(iter!(self.liftoff)))
             .field("request", &debug_info(iter!(self.request)))
+            .field("dispatch", &debug_info(iter!(self.dispatch)))
             .field("response", &debug_info(iter!(self.response)))
             .field("shutdown", &debug_info(iter!(self.shutdown)))
             .finish()
@@ -39,6 +39,7 @@ pub struct Info {
 ///   * Ignite
 ///   * Liftoff
 ///   * Request
+///   * Dispatch
 ///   * Response
 ///   * Shutdown
 ///
@@ -74,6 +75,9 @@ impl Kind {
     /// [singleton](crate::fairing::Fairing#singletons) fairing.
     pub const Singleton: Kind = Kind(1 << 5);
 
+    /// `Kind` flag representing a request for a 'dispatch' callback.
+    pub const Dispatch: Kind = Kind(1 << 6);
+
     /// Returns `true` if `self` is a superset of `other`. In other words,
     /// returns `true` if all of the kinds in `other` are also in `self`.
     ///
@@ -144,6 +148,7 @@ impl std::fmt::Display for Kind {
         write("ignite", Kind::Ignite)?;
         write("liftoff", Kind::Liftoff)?;
         write("request", Kind::Request)?;
+        write("dispatch", Kind::Dispatch)?;
         write("response", Kind::Response)?;
         write("shutdown", Kind::Shutdown)?;
         write("singleton", Kind::Singleton)
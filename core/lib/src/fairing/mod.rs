@@ -48,19 +48,80 @@
 //! Furthermore, a `Fairing` should take care to act locally so that the actions
 //! of other `Fairings` are not jeopardized. For instance, unless it is made
 //! abundantly clear, a fairing should not rewrite every request.
+//!
+//! ## Scoping
+//!
+//! A fairing's request, dispatch, and response callbacks run for every
+//! request. To run them only for requests under a mount point, attach the
+//! fairing along with the routes it guards via [`Rocket::mount_with()`],
+//! listing fairings with [`fairings!`](crate::fairings!):
+//!
+//! ```rust
+//! # #[macro_use] extern crate rocket;
+//! use rocket::fairing::AdHoc;
+//!
+//! #[get("/users")]
+//! fn users() -> &'static str { "[]" }
+//!
+//! #[launch]
+//! fn rocket() -> _ {
+//!     let audit = AdHoc::on_request("Audit", |req, _| Box::pin(async move {
+//!         println!("admin request: {}", req.uri());
+//!     }));
+//!
+//!     rocket::build().mount_with("/admin", routes![users], fairings![audit])
+//! }
+//! ```
+//!
+//! A scoped fairing's request, dispatch, and response callbacks run, in attach
+//! order, for requests whose path is the base or is under it, segment-wise:
+//! for `/admin`, requests to `/admin` and `/admin/users` but not to
+//! `/administer`. Its other callbacks run as for any attached fairing.
 
 use std::any::Any;
 
 use crate::{Rocket, Request, Response, Data, Build, Orbit};
+use crate::response;
 
 mod fairings;
 mod ad_hoc;
+mod require;
+mod conditional;
+mod scoped;
 mod info_kind;
 
 pub(crate) use self::fairings::Fairings;
+pub(crate) use self::conditional::Conditional;
+pub(crate) use self::scoped::Scoped;
 pub use self::ad_hoc::AdHoc;
+pub use self::require::{Require, Retry, Backoff};
 pub use self::info_kind::{Info, Kind};
 
+/// Creates a `Vec<Box<dyn Fairing>>` from a list of fairings, as taken by
+/// [`Rocket::mount_with()`].
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::fairing::{AdHoc, Fairing};
+///
+/// let fairings: Vec<Box<dyn Fairing>> = fairings![
+///     AdHoc::on_request("A", |_, _| Box::pin(async { })),
+///     AdHoc::on_response("B", |_, _| Box::pin(async { })),
+/// ];
+///
+/// assert_eq!(fairings.len(), 2);
+/// ```
+#[macro_export]
+macro_rules! fairings {
+    ($($fairing:expr),* $(,)?) => (
+        ::std::vec![$(
+            ::std::boxed::Box::new($fairing) as ::std::boxed::Box<dyn $crate::fairing::Fairing>
+        ),*]
+    );
+}
+
 /// A type alias for the return `Result` type of [`Fairing::on_ignite()`].
 pub type Result<T = Rocket<Build>, E = Rocket<Build>> = std::result::Result<T, E>;
 
@@ -75,6 +136,12 @@ pub type Result<T = Rocket<Build>, E = Rocket<Build>> = std::result::Result<T, E
 // check that the user didn't handle the `OPTIONS` request (404) and return an
 // appropriate response. This allows the users to handle `OPTIONS` requests
 // when they'd like but default to the fairing when they don't want to.
+//
+// The exception is a fairing that must act on every request before any handler
+// runs, such as load shedding, quotas, or serving a cached response. Those use
+// the separate dispatch callback, which can answer the request in place of the
+// router, so that request fairings remain purely observational and Rocket's
+// lifecycle needn't know about any particular such fairing.
 
 /// Trait implemented by fairings: Rocket's structured middleware.
 ///
@@ -101,11 +168,11 @@ pub type Result<T = Rocket<Build>, E = Rocket<Build>> = std::result::Result<T, E
 ///
 /// ## Fairing Callbacks
 ///
-/// There are five kinds of fairing callbacks: launch, liftoff, request,
-/// response, and shutdown. A fairing can request any combination of these
-/// callbacks through the `kind` field of the [`Info`] structure returned from
-/// the `info` method. Rocket will only invoke the callbacks identified in the
-/// fairing's [`Kind`].
+/// There are six kinds of fairing callbacks: launch, liftoff, request,
+/// dispatch, response, and shutdown. A fairing can request any combination of
+/// these callbacks through the `kind` field of the [`Info`] structure returned
+/// from the `info` method. Rocket will only invoke the callbacks identified in
+/// the fairing's [`Kind`].
 ///
 /// The callback kinds are as follows:
 ///
@@ -153,6 +220,22 @@ pub type Result<T = Rocket<Build>, E = Rocket<Build>> = std::result::Result<T, E
 ///     via response callbacks. Any modifications to a request are persisted and
 ///     can potentially alter how a request is routed.
 ///
+///   * **<a name="dispatch">Dispatch</a> (`on_dispatch`)**
+///
+///     A dispatch callback, represented by the [`Fairing::on_dispatch()`]
+///     method, is called after all request callbacks have run, just before the
+///     request is routed. It may answer the request in place of the router by
+///     returning `Some`: with `Ok(response)`, the response is used as if a
+///     handler had returned it; with `Err(status)`, the error catcher for
+///     `status` is invoked. In either case, no route is tried, later dispatch
+///     callbacks are skipped, and response callbacks run as usual. If every
+///     dispatch callback returns `None`, the request is routed.
+///
+///     Dispatch callbacks are run in `attach()` order. They are intended for
+///     globally applicable policies that must preempt every handler, such as
+///     load shedding or serving cached responses; prefer [request guards]
+///     otherwise.
+///
 ///   * **<a name="response">Response</a> (`on_response`)**
 ///
 ///     A response callback, represented by the [`Fairing::on_response()`]
@@ -209,9 +292,10 @@ pub type Result<T = Rocket<Build>, E = Rocket<Build>> = std::result::Result<T, E
 ///
 /// A `Fairing` implementation has one required method: [`info`]. A `Fairing`
 /// can also implement any of the available callbacks: `on_ignite`, `on_liftoff`,
-/// `on_request`, and `on_response`. A `Fairing` _must_ set the appropriate
-/// callback kind in the `kind` field of the returned `Info` structure from
-/// [`info`] for a callback to actually be called by Rocket.
+/// `on_request`, `on_dispatch`, `on_response`, and `on_shutdown`. A `Fairing`
+/// _must_ set the appropriate callback kind in the `kind` field of the returned
+/// `Info` structure from [`info`] for a callback to actually be called by
+/// Rocket.
 ///
 /// ## Fairing `Info`
 ///
@@ -251,6 +335,7 @@ pub type Result<T = Rocket<Build>, E = Rocket<Build>> = std::result::Result<T, E
 /// ```rust
 /// use rocket::{Rocket, Request, Data, Response, Build, Orbit};
 /// use rocket::fairing::{self, Fairing, Info, Kind};
+/// use rocket::response;
 ///
 /// # struct MyType;
 /// #[rocket::async_trait]
@@ -275,6 +360,11 @@ pub type Result<T = Rocket<Build>, E = Rocket<Build>> = std::result::Result<T, E
 ///         # unimplemented!()
 ///     }
 ///
+///     async fn on_dispatch<'r>(&self, req: &'r Request<'_>) -> Option<response::Result<'r>> {
+///         /* ... */
+///         # unimplemented!()
+///     }
+///
 ///     async fn on_response<'r>(&self, req: &'r Request<'_>, res: &mut Response<'r>) {
 ///         /* ... */
 ///         # unimplemented!()
@@ -503,6 +593,23 @@ pub trait Fairing: Send + Sync + AsAny + 'static {
     /// The default implementation of this method does nothing.
     async fn on_request(&self, _req: &mut Request<'_>, _data: &mut Data<'_>) {}
 
+    /// The dispatch callback. Returns `Some` to answer the request without
+    /// routing it and `None` to let it be routed.
+    ///
+    /// See [Fairing Callbacks](#dispatch) for complete semantics.
+    ///
+    /// This method is called just before a request is routed if
+    /// `Kind::Dispatch` is in the `kind` field of the `Info` structure for this
+    /// fairing and no earlier dispatch callback has answered the request. The
+    /// `&Request` parameter is the request about to be routed.
+    ///
+    /// ## Default Implementation
+    ///
+    /// The default implementation of this method returns `None`.
+    async fn on_dispatch<'r>(&self, _req: &'r Request<'_>) -> Option<response::Result<'r>> {
+        None
+    }
+
     /// The response callback.
     ///
     /// See [Fairing Callbacks](#response) for complete semantics.
@@ -560,6 +667,11 @@ impl<T: Fairing + ?Sized> Fairing for std::sync::Arc<T> {
         (self as &T).on_request(req, data).await
     }
 
+    #[inline]
+    async fn on_dispatch<'r>(&self, req: &'r Request<'_>) -> Option<response::Result<'r>> {
+        (self as &T).on_dispatch(req).await
+    }
+
     #[inline]
     async fn on_response<'r>(&self, req: &'r Request<'_>, res: &mut Response<'r>) {
         (self as &T).on_response(req, res).await
@@ -577,11 +689,41 @@ impl<T: Any> AsAny for T {
 }
 
 impl dyn Fairing {
+    /// If `self` wraps another fairing, as conditional and scoped fairings do,
+    /// returns the wrapped fairing. Type lookups see through wrappers so that,
+    /// for instance, `Rocket::fairing::<T>()` finds a `T` attached via
+    /// `Rocket::attach_if()` or `Rocket::mount_with()`.
+    fn inner(&self) -> Option<&dyn Fairing> {
+        let any = self.as_any_ref();
+        if let Some(conditional) = any.downcast_ref::<conditional::Conditional>() {
+            return conditional.inner();
+        }
+
+        any.downcast_ref::<scoped::Scoped>().map(|scoped| scoped.inner())
+    }
+
+    /// Mutable version of [`inner()`](Self::inner()).
+    fn inner_mut(&mut self) -> Option<&mut dyn Fairing> {
+        if self.as_any_ref().is::<scoped::Scoped>() {
+            return self.as_any_mut().downcast_mut::<scoped::Scoped>().map(|s| s.inner_mut());
+        }
+
+        self.as_any_mut()
+            .downcast_mut::<conditional::Conditional>()
+            .and_then(|conditional| conditional.inner_mut())
+    }
+
     fn downcast_ref<T: Any>(&self) -> Option<&T> {
-        self.as_any_ref().downcast_ref()
+        match self.as_any_ref().downcast_ref::<T>() {
+            Some(fairing) => Some(fairing),
+            None => self.inner()?.downcast_ref::<T>(),
+        }
     }
 
     fn downcast_mut<T: Any>(&mut self) -> Option<&mut T> {
-        self.as_any_mut().downcast_mut()
+        match self.as_any_ref().is::<T>() {
+            true => self.as_any_mut().downcast_mut::<T>(),
+            false => self.inner_mut()?.downcast_mut::<T>(),
+        }
     }
 }
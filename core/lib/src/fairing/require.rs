@@ -0,0 +1,285 @@
+use std::fmt;
+use std::time::Duration;
+
+use futures::future::BoxFuture;
+use serde::{de, Deserialize};
+
+use crate::{Rocket, Build};
+use crate::fairing::{Fairing, Kind, Info, Result};
+
+/// An ignite fairing that waits for a dependency to become available.
+///
+/// A `Require` fairing runs a check, such as connecting to a database, during
+/// ignition. If the check fails, it is retried according to a [`Retry`]
+/// policy. If it fails on every attempt, ignition and thus launch fail. This
+/// replaces ad-hoc sleep loops in [`AdHoc::try_on_ignite()`] fairings, and
+/// consistently logs each failed attempt.
+///
+/// Checks run in the order they are attached, interleaved with other ignite
+/// fairings. `Require` fairings are typically attached via
+/// [`Rocket::require()`].
+///
+/// [`AdHoc::try_on_ignite()`]: crate::fairing::AdHoc::try_on_ignite()
+///
+/// # Example
+///
+/// ```rust
+/// use std::time::Duration;
+/// use rocket::fairing::{Require, Retry, Backoff};
+///
+/// # async fn ping(_: &str) -> Result<(), std::io::Error> { Ok(()) }
+/// let policy = Retry::new()
+///     .retries(10)
+///     .backoff(Backoff::Exponential)
+///     .delay(Duration::from_millis(200));
+///
+/// let fairing = Require::new("database", policy, |rocket| Box::pin(async move {
+///     let url: String = rocket.figment().extract_inner("database_url")
+///         .map_err(|e| e.to_string())?;
+///
+///     ping(&url).await.map_err(|e| e.to_string())
+/// }));
+/// ```
+pub struct Require {
+    name: &'static str,
+    policy: Retry,
+    check: Box<dyn for<'a> Fn(&'a Rocket<Build>) -> BoxFuture<'a, Result<(), String>>
+        + Send + Sync + 'static>,
+}
+
+/// A policy for retrying a [`Require`] check or binding a
+/// [TCP listener](crate::listener::tcp#bind-retries).
+///
+/// A check is attempted once and then retried up to `retries` times. Before
+/// each retry, the policy waits for a delay determined by its [`Backoff`],
+/// starting at `delay` and never exceeding `max_delay`. Each attempt may be
+/// bounded by a `timeout`, after which it counts as failed.
+///
+/// A `Retry` deserializes from a table with optional `retries`, `backoff`
+/// (`"constant"`, `"linear"`, or `"exponential"`), and `delay`, `max_delay`,
+/// and `timeout` in milliseconds. Missing values are those of
+/// [`Retry::new()`].
+///
+/// # Example
+///
+/// ```rust
+/// use std::time::Duration;
+/// use rocket::fairing::{Retry, Backoff};
+///
+/// let policy = Retry::new();
+/// assert_eq!(policy.delays().count(), 3);
+///
+/// let policy = Retry::new()
+///     .retries(4)
+///     .backoff(Backoff::Exponential)
+///     .delay(Duration::from_secs(1))
+///     .max_delay(Duration::from_secs(5));
+///
+/// let delays: Vec<_> = policy.delays().map(|d| d.as_secs()).collect();
+/// assert_eq!(delays, [1, 2, 4, 5]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Retry {
+    retries: u32,
+    backoff: Backoff,
+    delay: Duration,
+    max_delay: Duration,
+    timeout: Option<Duration>,
+}
+
+/// How the delay between retries grows.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Backoff {
+    /// The delay is always the initial delay.
+    Constant,
+    /// The delay grows by the initial delay after each retry.
+    Linear,
+    /// The delay doubles after each retry.
+    Exponential,
+}
+
+impl Require {
+    /// Returns a `Require` fairing named `name` that runs `check` and retries
+    /// it according to `policy` until it returns `Ok`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::fairing::{Require, Retry};
+    ///
+    /// let fairing = Require::new("cache", Retry::new(), |_| Box::pin(async {
+    ///     Ok::<_, std::io::Error>(())
+    /// }));
+    /// ```
+    pub fn new<F, E>(name: &'static str, policy: Retry, check: F) -> Self
+        where F: for<'a> Fn(&'a Rocket<Build>) -> BoxFuture<'a, Result<(), E>>,
+              F: Send + Sync + 'static,
+              E: fmt::Display + 'static,
+    {
+        fn check_fn<F>(f: F) -> F
+            where F: for<'a> Fn(&'a Rocket<Build>) -> BoxFuture<'a, Result<(), String>>
+        {
+            f
+        }
+
+        let check = check_fn(move |rocket| {
+            let future = check(rocket);
+            Box::pin(async move { future.await.map_err(|e| e.to_string()) })
+        });
+
+        Require { name, policy, check: Box::new(check) }
+    }
+}
+
+impl Retry {
+    /// Returns the default policy: `3` retries with an exponential backoff
+    /// starting at `100ms`, capped at `10s`, and no timeout.
+    pub fn new() -> Self {
+        Retry {
+            retries: 3,
+            backoff: Backoff::Exponential,
+            delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(10),
+            timeout: None,
+        }
+    }
+
+    /// Sets the maximum number of retries after the first attempt.
+    pub fn retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Sets how the delay between retries grows.
+    pub fn backoff(mut self, backoff: Backoff) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Sets the delay before the first retry.
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Sets the maximum delay between retries.
+    pub fn max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// Sets the maximum duration of each attempt.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Returns the delays before each retry, in order.
+    pub fn delays(&self) -> impl Iterator<Item = Duration> + '_ {
+        (0..self.retries).map(move |i| {
+            let delay = match self.backoff {
+                Backoff::Constant => self.delay,
+                Backoff::Linear => self.delay.saturating_mul(i.saturating_add(1)),
+                Backoff::Exponential => self.delay.saturating_mul(2u32.saturating_pow(i)),
+            };
+
+            delay.min(self.max_delay)
+        })
+    }
+}
+
+impl Default for Retry {
+    fn default() -> Self {
+        Retry::new()
+    }
+}
+
+impl<'de> Deserialize<'de> for Retry {
+    fn deserialize<D: de::Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(deny_unknown_fields)]
+        struct Policy {
+            retries: Option<u32>,
+            backoff: Option<Backoff>,
+            delay: Option<u64>,
+            max_delay: Option<u64>,
+            timeout: Option<u64>,
+        }
+
+        let policy = Policy::deserialize(de)?;
+        let default = Retry::new();
+        Ok(Retry {
+            retries: policy.retries.unwrap_or(default.retries),
+            backoff: policy.backoff.unwrap_or(default.backoff),
+            delay: policy.delay.map_or(default.delay, Duration::from_millis),
+            max_delay: policy.max_delay.map_or(default.max_delay, Duration::from_millis),
+            timeout: policy.timeout.map(Duration::from_millis),
+        })
+    }
+}
+
+impl fmt::Display for Backoff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Backoff::Constant => "constant".fmt(f),
+            Backoff::Linear => "linear".fmt(f),
+            Backoff::Exponential => "exponential".fmt(f),
+        }
+    }
+}
+
+impl Require {
+    async fn attempt(&self, rocket: &Rocket<Build>) -> Result<(), String> {
+        let check = (self.check)(rocket);
+        match self.policy.timeout {
+            Some(timeout) => match tokio::time::timeout(timeout, check).await {
+                Ok(result) => result,
+                Err(_) => Err(format!("timed out after {}ms", timeout.as_millis())),
+            },
+            None => check.await,
+        }
+    }
+}
+
+#[crate::async_trait]
+impl Fairing for Require {
+    fn info(&self) -> Info {
+        Info { name: self.name, kind: Kind::Ignite }
+    }
+
+    async fn on_ignite(&self, rocket: Rocket<Build>) -> Result {
+        let attempts = self.policy.retries.saturating_add(1);
+        let mut delays = self.policy.delays();
+        for attempt in 1..=attempts {
+            let error = match self.attempt(&rocket).await {
+                Ok(()) => {
+                    if attempt > 1 {
+                        info!(name: "require", dependency = self.name, attempt,
+                            "dependency is available");
+                    }
+
+                    return Ok(rocket);
+                }
+                Err(error) => error,
+            };
+
+            match delays.next() {
+                Some(delay) => {
+                    warn!(name: "require", dependency = self.name, attempt, of = attempts,
+                        %error, "dependency unavailable: retrying in {}ms", delay.as_millis());
+
+                    tokio::time::sleep(delay).await;
+                }
+                None => {
+                    error!(name: "require", dependency = self.name, attempts, %error,
+                        "dependency unavailable: giving up");
+
+                    return Err(rocket);
+                }
+            }
+        }
+
+        unreachable!("loop returns on the final attempt")
+    }
+}
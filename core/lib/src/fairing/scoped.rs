@@ -0,0 +1,85 @@
+use crate::{Rocket, Request, Response, Data, Build, Orbit};
+use crate::response;
+use crate::fairing::{Fairing, Info, Kind, Result};
+
+/// A fairing whose request, dispatch, and response callbacks only run for
+/// requests under a base path. Created via [`Rocket::mount_with()`].
+///
+/// The fairing takes the place of the one it wraps so that its callbacks run
+/// in attach order. Its ignite, liftoff, and shutdown callbacks always run.
+pub(crate) struct Scoped {
+    base: String,
+    fairing: Box<dyn Fairing>,
+}
+
+impl Scoped {
+    pub fn new(base: &str, fairing: Box<dyn Fairing>) -> Self {
+        Scoped { base: base.trim_end_matches('/').to_owned(), fairing }
+    }
+
+    /// The wrapped fairing.
+    pub fn inner(&self) -> &dyn Fairing {
+        &*self.fairing
+    }
+
+    /// Mutable version of [`Scoped::inner()`].
+    pub fn inner_mut(&mut self) -> &mut dyn Fairing {
+        &mut *self.fairing
+    }
+
+    /// Whether `req`'s path is `base` or is under it, matched segment-wise.
+    fn applies(&self, req: &Request<'_>) -> bool {
+        req.uri().path().as_str()
+            .strip_prefix(&*self.base)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+    }
+}
+
+#[crate::async_trait]
+impl Fairing for Scoped {
+    fn info(&self) -> Info {
+        // The singleton kind is dropped: it would apply to all `Scoped`s. The
+        // ignite kind is always added; `on_ignite()` forwards only if needed.
+        let info = self.fairing.info();
+        let kinds = [Kind::Liftoff, Kind::Request, Kind::Dispatch, Kind::Response, Kind::Shutdown];
+        let kind = kinds.into_iter()
+            .filter(|&kind| info.kind.is(kind))
+            .fold(Kind::Ignite, |acc, kind| acc | kind);
+
+        Info { name: info.name, kind }
+    }
+
+    async fn on_ignite(&self, rocket: Rocket<Build>) -> Result {
+        match self.fairing.info().kind.is(Kind::Ignite) {
+            true => self.fairing.on_ignite(rocket).await,
+            false => Ok(rocket),
+        }
+    }
+
+    async fn on_liftoff(&self, rocket: &Rocket<Orbit>) {
+        self.fairing.on_liftoff(rocket).await
+    }
+
+    async fn on_request(&self, req: &mut Request<'_>, data: &mut Data<'_>) {
+        if self.applies(req) {
+            self.fairing.on_request(req, data).await
+        }
+    }
+
+    async fn on_dispatch<'r>(&self, req: &'r Request<'_>) -> Option<response::Result<'r>> {
+        match self.applies(req) {
+            true => self.fairing.on_dispatch(req).await,
+            false => None,
+        }
+    }
+
+    async fn on_response<'r>(&self, req: &'r Request<'_>, res: &mut Response<'r>) {
+        if self.applies(req) {
+            self.fairing.on_response(req, res).await
+        }
+    }
+
+    async fn on_shutdown(&self, rocket: &Rocket<Orbit>) {
+        self.fairing.on_shutdown(rocket).await
+    }
+}
@@ -2,7 +2,7 @@ use multer::Multipart;
 use either::Either;
 
 use crate::request::{Request, local_cache_once};
-use crate::data::{Data, Limits, Outcome};
+use crate::data::{Data, Limits, Outcome, sniff};
 use crate::http::{RawStr, Status};
 use crate::form::prelude::*;
 
@@ -181,12 +181,18 @@ impl<'r, 'i> MultipartParser<'r, 'i> {
                 }
             };
 
+            let mut data = Data::from(field);
+            if let Err(e) = sniff::verify_field(self.request, &content_type, &mut data).await {
+                let kind = ErrorKind::Custom(Status::UnsupportedMediaType, Box::new(e));
+                return Some(Err(Error::from(kind).with_name(name)));
+            }
+
             Either::Right(DataField {
                 content_type,
                 request: self.request,
                 name: NameView::new(name),
                 file_name: file_name.map(crate::fs::FileName::new),
-                data: Data::from(field),
+                data,
             })
         } else {
             let (mut buf, len) = match field.name() {
@@ -4,6 +4,8 @@ mod server;
 mod named_file;
 mod temp_file;
 mod file_name;
+mod sendfile;
+mod scan;
 
 pub mod rewrite;
 
@@ -11,6 +13,8 @@ pub use server::*;
 pub use named_file::*;
 pub use temp_file::*;
 pub use file_name::*;
+pub use sendfile::*;
+pub use scan::*;
 
 crate::export! {
     /// Generates a crate-relative version of a path.
@@ -6,7 +6,7 @@ use tokio::fs::{File, OpenOptions};
 
 use crate::request::Request;
 use crate::response::{self, Responder};
-use crate::http::ContentType;
+use crate::http::ContentDisposition;
 
 /// A [`Responder`] that sends file data with a Content-Type based on its
 /// file extension.
@@ -144,18 +144,70 @@ impl NamedFile {
     pub fn path(&self) -> &Path {
         self.0.as_path()
     }
+
+    /// Returns a responder that serves this file as a download: with a
+    /// `Content-Disposition` of `attachment` and the file's name, which may
+    /// contain non-ASCII characters, as the suggested file name.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use rocket::get;
+    /// use rocket::fs::NamedFile;
+    /// use rocket::http::ContentDisposition;
+    ///
+    /// #[get("/download")]
+    /// async fn download() -> Option<(ContentDisposition, NamedFile)> {
+    ///     let file = NamedFile::open("reports/März.pdf").await.ok()?;
+    ///     Some(file.attachment())
+    /// }
+    /// ```
+    pub fn attachment(self) -> (ContentDisposition, NamedFile) {
+        (disposition(ContentDisposition::attachment(), self.path()), self)
+    }
+
+    /// Returns a responder that serves this file with a `Content-Disposition`
+    /// of `inline` and the file's name as the suggested file name, should the
+    /// client save it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use rocket::get;
+    /// use rocket::fs::NamedFile;
+    /// use rocket::http::ContentDisposition;
+    ///
+    /// #[get("/view")]
+    /// async fn view() -> Option<(ContentDisposition, NamedFile)> {
+    ///     let file = NamedFile::open("reports/März.pdf").await.ok()?;
+    ///     Some(file.inline())
+    /// }
+    /// ```
+    pub fn inline(self) -> (ContentDisposition, NamedFile) {
+        (disposition(ContentDisposition::inline(), self.path()), self)
+    }
+}
+
+/// Returns `disposition` with the file name of `path`, if it has one.
+fn disposition(disposition: ContentDisposition, path: &Path) -> ContentDisposition {
+    match path.file_name() {
+        Some(name) => disposition.with_filename(name.to_string_lossy().into_owned()),
+        None => disposition,
+    }
 }
 
 /// Streams the named file to the client. Sets or overrides the Content-Type in
 /// the response according to the file's extension if the extension is
-/// recognized. See [`ContentType::from_extension()`] for more information. If
+/// recognized. See [`Rocket::mime_type()`] for more information. If
 /// you would like to stream a file with a different Content-Type than that
 /// implied by its extension, use a [`File`] directly.
+///
+/// [`Rocket::mime_type()`]: crate::Rocket::mime_type()
 impl<'r> Responder<'r, 'static> for NamedFile {
     fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
         let mut response = self.1.respond_to(req)?;
         if let Some(ext) = self.0.extension() {
-            if let Some(ct) = ContentType::from_extension(&ext.to_string_lossy()) {
+            if let Some(ct) = req.rocket().mime_type(&ext.to_string_lossy()) {
                 response.set_header(ct);
             }
         }
@@ -0,0 +1,306 @@
+use std::{fmt, io};
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use crate::http::Status;
+use crate::fs::TempFile;
+
+/// Scans uploaded files before handlers see them.
+///
+/// When a [`Scanner`] is [managed](crate::Rocket::manage()), every
+/// [`TempFile`] data guard, and every `TempFile` form field streamed from the
+/// data of a `multipart/form-data` form, is scanned after it is written to
+/// disk and before the handler runs. A file that fails the scan is deleted and
+/// the guard fails with the [`ScanError`] returned by the scanner:
+///
+///   * As a data guard, the guard fails with the error's
+///     [`status`](ScanError::status()) and an `io::Error` wrapping it.
+///   * As a form field, the field fails with an
+///     [`ErrorKind::Custom`](crate::form::error::ErrorKind::Custom) error with
+///     the same status wrapping it.
+///
+/// Form fields parsed from text values are buffered in memory and aren't
+/// scanned. [`Clamd`] is an implementation that scans files with ClamAV.
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::fs::{TempFile, UploadScanner, Scanner, ScanError};
+/// use rocket::tokio::io::AsyncReadExt;
+///
+/// struct NoExecutables;
+///
+/// #[rocket::async_trait]
+/// impl UploadScanner for NoExecutables {
+///     async fn scan(&self, file: &TempFile<'_>) -> Result<(), ScanError> {
+///         let mut magic = [0; 4];
+///         let n = file.open().await?.read(&mut magic).await?;
+///         match &magic[..n] {
+///             [b'M', b'Z', ..] | [0x7f, b'E', b'L', b'F'] => {
+///                 Err(ScanError::Infected("executable".into()))
+///             }
+///             _ => Ok(()),
+///         }
+///     }
+/// }
+///
+/// #[post("/upload", data = "<file>")]
+/// fn upload(file: TempFile<'_>) { /* `file` isn't an executable. */ }
+///
+/// #[launch]
+/// fn rocket() -> _ {
+///     rocket::build()
+///         .manage(Scanner::new(NoExecutables))
+///         .mount("/", routes![upload])
+/// }
+/// ```
+#[crate::async_trait]
+pub trait UploadScanner: Send + Sync + 'static {
+    /// Scans `file`, returning `Ok` if it may be passed to the handler.
+    async fn scan(&self, file: &TempFile<'_>) -> Result<(), ScanError>;
+}
+
+/// Managed state registering an [`UploadScanner`].
+///
+/// See [`UploadScanner`] for details.
+#[derive(Clone)]
+pub struct Scanner(Arc<dyn UploadScanner>);
+
+/// The reason an [`UploadScanner`] rejected a file.
+#[derive(Debug)]
+pub enum ScanError {
+    /// The file contains the named malware or is otherwise malicious.
+    Infected(String),
+    /// The file may not be accepted for the given legal reason.
+    Prohibited(String),
+    /// The file couldn't be scanned.
+    Io(io::Error),
+}
+
+impl Scanner {
+    /// Returns managed state that scans uploads with `scanner`.
+    pub fn new<S: UploadScanner>(scanner: S) -> Self {
+        Scanner(Arc::new(scanner))
+    }
+
+    /// Scans `file` with the registered scanner.
+    pub async fn scan(&self, file: &TempFile<'_>) -> Result<(), ScanError> {
+        self.0.scan(file).await
+    }
+}
+
+impl ScanError {
+    /// The status of the response for a file rejected with `self`:
+    /// `422 Unprocessable Entity` for [`ScanError::Infected`], `451
+    /// Unavailable For Legal Reasons` for [`ScanError::Prohibited`], and `503
+    /// Service Unavailable` for [`ScanError::Io`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::fs::ScanError;
+    /// use rocket::http::Status;
+    ///
+    /// let error = ScanError::Infected("Eicar-Test-Signature".into());
+    /// assert_eq!(error.status(), Status::UnprocessableEntity);
+    ///
+    /// let error = ScanError::Prohibited("embargoed".into());
+    /// assert_eq!(error.status(), Status::UnavailableForLegalReasons);
+    /// ```
+    pub fn status(&self) -> Status {
+        match self {
+            ScanError::Infected(_) => Status::UnprocessableEntity,
+            ScanError::Prohibited(_) => Status::UnavailableForLegalReasons,
+            ScanError::Io(_) => Status::ServiceUnavailable,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Address {
+    Tcp(String),
+    #[cfg(unix)]
+    Unix(std::path::PathBuf),
+}
+
+/// An [`UploadScanner`] that scans files with a ClamAV `clamd` daemon.
+///
+/// Files are streamed to `clamd` with the `INSTREAM` command over a new
+/// connection for each file. Files in which `clamd` finds a signature are
+/// rejected with [`ScanError::Infected`]. Files that couldn't be scanned,
+/// because `clamd` is unreachable, timed out, or reported an error such as an
+/// exceeded `StreamMaxLength`, are rejected with [`ScanError::Io`].
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use std::time::Duration;
+///
+/// use rocket::fs::{Clamd, Scanner};
+///
+/// #[launch]
+/// fn rocket() -> _ {
+///     let clamd = Clamd::tcp("127.0.0.1:3310").timeout(Duration::from_secs(10));
+///     rocket::build().manage(Scanner::new(clamd))
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Clamd {
+    address: Address,
+    timeout: Duration,
+}
+
+impl Clamd {
+    /// The size of the chunks files are streamed to `clamd` in.
+    const CHUNK_SIZE: usize = 64 * 1024;
+
+    /// Returns a scanner that connects to `clamd` at the TCP socket address
+    /// `address`, such as `127.0.0.1:3310`, with a timeout of 30 seconds.
+    pub fn tcp<A: Into<String>>(address: A) -> Self {
+        Clamd { address: Address::Tcp(address.into()), timeout: Duration::from_secs(30) }
+    }
+
+    /// Returns a scanner that connects to `clamd` at the Unix domain socket
+    /// at `path`, with a timeout of 30 seconds.
+    #[cfg(unix)]
+    #[cfg_attr(nightly, doc(cfg(unix)))]
+    pub fn unix<P: AsRef<std::path::Path>>(path: P) -> Self {
+        let address = Address::Unix(path.as_ref().to_path_buf());
+        Clamd { address, timeout: Duration::from_secs(30) }
+    }
+
+    /// Sets the timeout for scanning a file, including connecting to `clamd`.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    async fn instream<S>(&self, mut stream: S, file: &TempFile<'_>) -> Result<(), ScanError>
+        where S: AsyncRead + AsyncWrite + Unpin
+    {
+        stream.write_all(b"zINSTREAM\0").await?;
+
+        let mut reader = file.open().await?;
+        let mut buf = vec![0; Self::CHUNK_SIZE];
+        loop {
+            let n = reader.read(&mut buf).await?;
+            if n == 0 {
+                break;
+            }
+
+            stream.write_all(&(n as u32).to_be_bytes()).await?;
+            stream.write_all(&buf[..n]).await?;
+        }
+
+        stream.write_all(&[0; 4]).await?;
+        stream.flush().await?;
+
+        let mut reply = Vec::new();
+        stream.read_to_end(&mut reply).await?;
+        parse_reply(&reply)
+    }
+
+    async fn connect_and_scan(&self, file: &TempFile<'_>) -> Result<(), ScanError> {
+        match &self.address {
+            Address::Tcp(addr) => {
+                let stream = tokio::net::TcpStream::connect(addr.as_str()).await?;
+                self.instream(stream, file).await
+            }
+            #[cfg(unix)]
+            Address::Unix(path) => {
+                let stream = tokio::net::UnixStream::connect(path).await?;
+                self.instream(stream, file).await
+            }
+        }
+    }
+}
+
+/// Parses a reply of `clamd` to `zINSTREAM`: `stream: OK`, `stream: $name
+/// FOUND`, or `$message ERROR`.
+fn parse_reply(reply: &[u8]) -> Result<(), ScanError> {
+    let reply = String::from_utf8_lossy(reply);
+    let reply = reply.trim_end_matches('\0').trim();
+    let result = reply.strip_prefix("stream:").map_or(reply, |r| r.trim_start());
+    if result == "OK" {
+        return Ok(());
+    }
+
+    if let Some(name) = result.strip_suffix(" FOUND") {
+        return Err(ScanError::Infected(name.to_string()));
+    }
+
+    let message = format!("clamd: {}", result.strip_suffix(" ERROR").unwrap_or(result));
+    Err(ScanError::Io(io::Error::new(io::ErrorKind::Other, message)))
+}
+
+#[crate::async_trait]
+impl UploadScanner for Clamd {
+    async fn scan(&self, file: &TempFile<'_>) -> Result<(), ScanError> {
+        match tokio::time::timeout(self.timeout, self.connect_and_scan(file)).await {
+            Ok(result) => result,
+            Err(_) => Err(io::Error::new(io::ErrorKind::TimedOut, "clamd timed out").into()),
+        }
+    }
+}
+
+impl fmt::Debug for Scanner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Scanner").finish_non_exhaustive()
+    }
+}
+
+impl fmt::Display for ScanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScanError::Infected(name) => write!(f, "file is infected: {name}"),
+            ScanError::Prohibited(reason) => write!(f, "file is prohibited: {reason}"),
+            ScanError::Io(e) => write!(f, "file couldn't be scanned: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for ScanError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ScanError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ScanError {
+    fn from(error: io::Error) -> Self {
+        ScanError::Io(error)
+    }
+}
+
+impl From<ScanError> for io::Error {
+    fn from(error: ScanError) -> Self {
+        match error {
+            ScanError::Io(e) => e,
+            e => io::Error::new(io::ErrorKind::InvalidData, e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_reply, ScanError};
+
+    #[test]
+    fn test_parse_clamd_reply() {
+        assert!(parse_reply(b"stream: OK\0").is_ok());
+        assert!(parse_reply(b"OK\n").is_ok());
+
+        let error = parse_reply(b"stream: Eicar-Test-Signature FOUND\0").unwrap_err();
+        assert!(matches!(error, ScanError::Infected(name) if name == "Eicar-Test-Signature"));
+
+        let error = parse_reply(b"INSTREAM size limit exceeded. ERROR\0").unwrap_err();
+        assert!(matches!(error, ScanError::Io(e) if e.to_string().contains("size limit")));
+        assert!(matches!(parse_reply(b""), Err(ScanError::Io(_))));
+    }
+}
@@ -0,0 +1,193 @@
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::request::Request;
+use crate::response::{self, Responder, Response};
+use crate::http::{ContentDisposition, Header, RawStr};
+use crate::fs::NamedFile;
+
+/// A [`Responder`] that lets a fronting proxy send a file via
+/// `X-Accel-Redirect` or `X-Sendfile`, streaming it directly otherwise.
+///
+/// Serving large files through Rocket ties up a connection and a task for
+/// the duration of the transfer. Proxies such as nginx, Apache (with
+/// `mod_xsendfile`), and lighttpd can instead send a file on the application's
+/// behalf: the application responds with an empty body and a header naming
+/// the file, and the proxy streams the file, handling range requests and
+/// resumption itself. Authorization remains in Rocket, as the file is only
+/// offloaded after the handler has chosen to serve it.
+///
+/// # Configuration
+///
+/// Offloading is configured via the `sendfile` configuration parameter, a
+/// table with the following keys:
+///
+/// | key        | type     | description                                      |
+/// |------------|----------|--------------------------------------------------|
+/// | `header`   | string   | The header to set. Default: `X-Accel-Redirect`.  |
+/// | `root`     | path     | For `X-Accel-Redirect`, the directory mapped.    |
+/// | `location` | string   | For `X-Accel-Redirect`, the URI `root` maps to.  |
+/// | `always`   | bool     | Offload even if a request doesn't look proxied.  |
+///
+/// With `X-Accel-Redirect`, the header's value is the URI of the file's path
+/// relative to `root`, prefixed with `location`. Files outside of `root` are
+/// streamed directly. With any other header, such as `X-Sendfile` or
+/// `X-Lighttpd-Send-File`, the value is the file's absolute path.
+///
+/// Unless `always` is `true`, a file is only offloaded when the request
+/// appears to come through a proxy, that is, when it carries the configured
+/// [`ip_header`](crate::Config::ip_header) or
+/// [`proxy_proto_header`](crate::Config::proxy_proto_header). Otherwise, as
+/// when `sendfile` is not configured at all, the file is streamed directly as
+/// a [`NamedFile`] would be. Either way, the `Content-Type` is set based on
+/// the file's extension.
+///
+/// # Example
+///
+/// With the following configuration and an nginx `location /protected/ {
+/// internal; alias /srv/files/; }` block, a request for `/files/report.pdf`
+/// is answered with `X-Accel-Redirect: /protected/report.pdf`:
+///
+/// ```toml
+/// [default.sendfile]
+/// root = "/srv/files"
+/// location = "/protected"
+/// ```
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use std::path::{Path, PathBuf};
+/// use rocket::fs::SendFile;
+///
+/// # struct User;
+/// # #[rocket::async_trait]
+/// # impl<'r> rocket::request::FromRequest<'r> for User {
+/// #     type Error = ();
+/// #     async fn from_request(_: &'r rocket::Request<'_>)
+/// #         -> rocket::request::Outcome<Self, ()> { todo!() }
+/// # }
+/// #[get("/files/<path..>")]
+/// async fn file(user: User, path: PathBuf) -> Option<SendFile> {
+///     SendFile::open(Path::new("/srv/files").join(path)).await.ok()
+/// }
+/// ```
+#[derive(Debug)]
+pub struct SendFile(NamedFile);
+
+/// The `sendfile` configuration parameter.
+#[derive(Debug, Deserialize)]
+struct Config {
+    #[serde(default = "Config::default_header")]
+    header: String,
+    root: Option<PathBuf>,
+    #[serde(default)]
+    location: String,
+    #[serde(default)]
+    always: bool,
+}
+
+impl Config {
+    fn default_header() -> String {
+        "X-Accel-Redirect".into()
+    }
+}
+
+impl SendFile {
+    /// Attempts to open the file at `path` in read-only mode. The file is
+    /// opened, even if it will be offloaded, so that a missing or unreadable
+    /// file is reported by the handler.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`NamedFile::open()`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use rocket::get;
+    /// use rocket::fs::SendFile;
+    ///
+    /// #[get("/")]
+    /// async fn index() -> Option<SendFile> {
+    ///     SendFile::open("index.html").await.ok()
+    /// }
+    /// ```
+    pub async fn open<P: AsRef<Path>>(path: P) -> io::Result<SendFile> {
+        NamedFile::open(path).await.map(SendFile)
+    }
+
+    /// Returns the path to the file.
+    pub fn path(&self) -> &Path {
+        self.0.path()
+    }
+
+    /// Returns a responder that serves this file as a download. See
+    /// [`NamedFile::attachment()`]. The `Content-Disposition` is sent whether
+    /// or not the file is offloaded.
+    pub fn attachment(self) -> (ContentDisposition, SendFile) {
+        let (disposition, file) = self.0.attachment();
+        (disposition, SendFile(file))
+    }
+
+    /// Returns a responder that serves this file inline. See
+    /// [`NamedFile::inline()`].
+    pub fn inline(self) -> (ContentDisposition, SendFile) {
+        let (disposition, file) = self.0.inline();
+        (disposition, SendFile(file))
+    }
+
+    /// Returns the offload header for this file, if it should be offloaded.
+    fn offload_header(&self, req: &Request<'_>) -> Option<Header<'static>> {
+        let figment = req.rocket().figment();
+        if !figment.contains("sendfile") {
+            return None;
+        }
+
+        let config = figment.extract_inner::<Config>("sendfile")
+            .map_err(|e| warn!("invalid `sendfile` configuration: {e}"))
+            .ok()?;
+
+        if !config.always && req.real_ip().is_none() && req.proxy_proto().is_none() {
+            return None;
+        }
+
+        let path = match self.path().is_absolute() {
+            true => self.path().to_path_buf(),
+            false => std::env::current_dir().ok()?.join(self.path()),
+        };
+
+        if !config.header.eq_ignore_ascii_case("X-Accel-Redirect") {
+            return Some(Header::new(config.header, path.to_str()?.to_string()));
+        }
+
+        let relative = path.strip_prefix(config.root?).ok()?;
+        let mut uri = config.location.trim_end_matches('/').to_string();
+        for segment in relative.iter() {
+            uri.push('/');
+            uri.push_str(RawStr::new(segment.to_str()?).percent_encode().as_str());
+        }
+
+        Some(Header::new(config.header, uri))
+    }
+}
+
+/// Responds with an empty body and the configured offload header if the file
+/// should be offloaded, and otherwise streams the file as [`NamedFile`] does.
+impl<'r> Responder<'r, 'static> for SendFile {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
+        let Some(header) = self.offload_header(req) else {
+            return self.0.respond_to(req);
+        };
+
+        let mut response = Response::build().header(header).finalize();
+        if let Some(ext) = self.path().extension() {
+            if let Some(ct) = req.rocket().mime_type(&ext.to_string_lossy()) {
+                response.set_header(ct);
+            }
+        }
+
+        Ok(response)
+    }
+}
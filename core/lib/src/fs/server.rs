@@ -5,7 +5,7 @@ use std::borrow::Cow;
 
 use crate::{response, Data, Request, Response};
 use crate::outcome::IntoOutcome;
-use crate::http::{uri::Segments, HeaderMap, Method, ContentType, Status};
+use crate::http::{uri::Segments, HeaderMap, Method, Status};
 use crate::route::{Route, Handler, Outcome};
 use crate::response::Responder;
 use crate::util::Formatter;
@@ -85,6 +85,8 @@ use crate::fs::rewrite::*;
 pub struct FileServer {
     rewrites: Vec<Arc<dyn Rewriter>>,
     rank: isize,
+    #[cfg(feature = "markdown")]
+    markdown: Option<Arc<str>>,
 }
 
 impl FileServer {
@@ -196,7 +198,9 @@ impl FileServer {
     pub fn identity() -> Self {
         Self {
             rewrites: vec![],
-            rank: Self::DEFAULT_RANK
+            rank: Self::DEFAULT_RANK,
+            #[cfg(feature = "markdown")]
+            markdown: None,
         }
     }
 
@@ -323,6 +327,44 @@ impl FileServer {
 
         self.rewrite(Map(f))
     }
+
+    /// Renders served files with an `.md` extension from Markdown to HTML
+    /// pages by substituting them into the page `template`.
+    ///
+    /// Files are rendered and sanitized as by [`Markdown::to_page()`], which
+    /// describes the placeholders in `template`.
+    /// [`markdown::DEFAULT_TEMPLATE`] is a minimal, readable page. Files are
+    /// rendered on every request; headers set by rewrites are preserved.
+    ///
+    /// This method is only available when the `markdown` feature is enabled.
+    ///
+    /// [`Markdown::to_page()`]: crate::response::markdown::Markdown::to_page()
+    /// [`markdown::DEFAULT_TEMPLATE`]: crate::response::markdown::DEFAULT_TEMPLATE
+    ///
+    /// # Example
+    ///
+    /// Serve the files in `docs`, rendering Markdown files as HTML pages:
+    ///
+    /// ```rust,no_run
+    /// # #[macro_use] extern crate rocket;
+    /// use rocket::fs::FileServer;
+    /// use rocket::response::markdown::DEFAULT_TEMPLATE;
+    ///
+    /// #[launch]
+    /// fn rocket() -> _ {
+    ///     let server = FileServer::new("docs")
+    ///         .rewrite(rocket::fs::rewrite::DirIndex::if_exists("index.md"))
+    ///         .markdown(DEFAULT_TEMPLATE);
+    ///
+    ///     rocket::build().mount("/docs", server)
+    /// }
+    /// ```
+    #[cfg(feature = "markdown")]
+    #[cfg_attr(nightly, doc(cfg(feature = "markdown")))]
+    pub fn markdown(mut self, template: &str) -> Self {
+        self.markdown = Some(template.into());
+        self
+    }
 }
 
 impl From<FileServer> for Vec<Route> {
@@ -346,6 +388,11 @@ impl Handler for FileServer {
         }
 
         let (outcome, status) = match response {
+            #[cfg(feature = "markdown")]
+            Some(Rewrite::File(f)) if self.markdown.is_some() && f.is_markdown() => {
+                let template = self.markdown.as_deref().unwrap_or_default();
+                (f.render_markdown(template).await.respond_to(req), Status::NotFound)
+            }
             Some(Rewrite::File(f)) => (f.open().await.respond_to(req), Status::NotFound),
             Some(Rewrite::Redirect(r)) => (r.respond_to(req), Status::InternalServerError),
             None => return Outcome::forward(data, Status::NotFound),
@@ -381,6 +428,39 @@ impl<'r> File<'r> {
     }
 }
 
+#[cfg(feature = "markdown")]
+impl<'r> File<'r> {
+    fn is_markdown(&self) -> bool {
+        self.path.extension().map_or(false, |ext| ext.eq_ignore_ascii_case("md"))
+    }
+
+    async fn render_markdown(self, template: &str) -> std::io::Result<MarkdownFile<'r>> {
+        let markdown = tokio::fs::read_to_string(&self.path).await?;
+        let html = crate::response::markdown::Markdown(markdown).to_page(template);
+        Ok(MarkdownFile { html, headers: self.headers })
+    }
+}
+
+#[cfg(feature = "markdown")]
+struct MarkdownFile<'r> {
+    html: String,
+    headers: HeaderMap<'r>,
+}
+
+#[cfg(feature = "markdown")]
+impl<'r> Responder<'r, 'r> for MarkdownFile<'r> {
+    fn respond_to(self, _: &'r Request<'_>) -> response::Result<'r> {
+        let mut response = Response::new();
+        response.set_header_map(self.headers);
+        if !response.headers().contains("Content-Type") {
+            response.set_header(crate::http::ContentType::HTML);
+        }
+
+        response.set_sized_body(self.html.len(), std::io::Cursor::new(self.html));
+        Ok(response)
+    }
+}
+
 struct NamedFile<'r> {
     file: tokio::fs::File,
     len: u64,
@@ -390,13 +470,13 @@ struct NamedFile<'r> {
 
 // Do we want to allow the user to rewrite the Content-Type?
 impl<'r> Responder<'r, 'r> for NamedFile<'r> {
-    fn respond_to(self, _: &'r Request<'_>) -> response::Result<'r> {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'r> {
         let mut response = Response::new();
         response.set_header_map(self.headers);
         if !response.headers().contains("Content-Type") {
             self.path.extension()
                 .and_then(|ext| ext.to_str())
-                .and_then(ContentType::from_extension)
+                .and_then(|ext| req.rocket().mime_type(ext))
                 .map(|content_type| response.set_header(content_type));
         }
 
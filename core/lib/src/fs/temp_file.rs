@@ -4,9 +4,9 @@ use std::path::{PathBuf, Path};
 use crate::Request;
 use crate::http::{ContentType, Status};
 use crate::data::{self, FromData, Data, Capped, N, Limits};
-use crate::form::{FromFormField, ValueField, DataField, error::Errors};
-use crate::outcome::IntoOutcome;
-use crate::fs::FileName;
+use crate::form::{FromFormField, ValueField, DataField, error::{Errors, ErrorKind}};
+use crate::outcome::{IntoOutcome, try_outcome};
+use crate::fs::{FileName, Scanner, ScanError};
 
 use tokio::task;
 use tokio::fs::{self, File};
@@ -57,6 +57,11 @@ use either::Either;
 /// guard, the extension is identified by the Content-Type of the request, if
 /// any. If there is no Content-Type, the limit `file` is used.
 ///
+/// # Scanning
+///
+/// When a [`Scanner`] is managed, files are scanned before the handler sees
+/// them. See [`UploadScanner`](crate::fs::UploadScanner) for details.
+///
 /// # Cappable
 ///
 /// A data stream can be partially read into a `TempFile` even if the incoming
@@ -533,6 +538,14 @@ impl<'v> TempFile<'v> {
 
         Ok(Capped::new(temp_file, n))
     }
+
+    /// Scans `self` with the managed [`Scanner`], if any.
+    async fn scan(&self, req: &Request<'_>) -> Result<(), ScanError> {
+        match req.rocket().state::<Scanner>() {
+            Some(scanner) => scanner.scan(self).await,
+            None => Ok(()),
+        }
+    }
 }
 
 #[crate::async_trait]
@@ -545,7 +558,11 @@ impl<'v> FromFormField<'v> for Capped<TempFile<'v>> {
     async fn from_data(
         f: DataField<'v, '_>
     ) -> Result<Self, Errors<'v>> {
-        Ok(TempFile::from(f.request, f.data, f.file_name, Some(f.content_type)).await?)
+        let file = TempFile::from(f.request, f.data, f.file_name, Some(f.content_type)).await?;
+        file.scan(f.request).await
+            .map_err(|e| ErrorKind::Custom(e.status(), Box::new(e)))?;
+
+        Ok(file)
     }
 }
 
@@ -562,9 +579,12 @@ impl<'r> FromData<'r> for Capped<TempFile<'_>> {
                 Perhaps you meant to use `Form<TempFile<'_>>` instead?");
         }
 
-        TempFile::from(req, data, None, req.content_type().cloned())
-            .await
-            .or_error(Status::BadRequest)
+        let file = TempFile::from(req, data, None, req.content_type().cloned()).await;
+        let file = try_outcome!(file.or_error(Status::BadRequest));
+        match file.scan(req).await {
+            Ok(()) => data::Outcome::Success(file),
+            Err(e) => data::Outcome::Error((e.status(), e.into())),
+        }
     }
 }
 
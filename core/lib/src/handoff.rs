@@ -0,0 +1,299 @@
+//! Coordinated zero-downtime restarts.
+//!
+//! Restarting a server by starting a new process while the old one shuts down
+//! leaves a window in which neither accepts connections: the old process has
+//! stopped accepting while it drains, and the new one can't bind the address
+//! until the old one has released it. Retrying the bind, as configured via
+//! [`bind_retry`](crate::listener::tcp#bind-retries), shortens the window but
+//! doesn't close it; connections arriving in it are refused.
+//!
+//! The [`Handoff`] fairing closes the window. The old process serves a
+//! _control socket_, a Unix domain socket at a known path. At ignition, the
+//! new process connects to it and the two coordinate:
+//!
+//!   1. The new process asks the old one to hand off its listeners.
+//!   2. The old process sends the new one its listening TCP sockets, then
+//!      stops accepting connections and gracefully shuts down.
+//!   3. Once the old process has drained, it confirms as much to the new one.
+//!   4. The new process takes over the inherited sockets instead of binding
+//!      new ones and starts serving.
+//!
+//! Because the listening sockets are never closed, connections arriving
+//! during the handoff wait in their backlog, to be accepted by the new process,
+//! instead of being refused. If no process serves the control socket, the new
+//! process binds its listeners as usual.
+//!
+//! Only TCP listeners, including those with TLS, bound by
+//! [`Rocket::launch()`](crate::Rocket::launch()) are handed off. An inherited
+//! socket is used when its address is the one the new process would bind. The
+//! new process waits for drain confirmation for at most the
+//! [timeout](Handoff::timeout()), after which it proceeds regardless.
+//!
+//! The control socket is served from the time Rocket is launched until
+//! shutdown, replacing any stale socket at its path. Local clients don't serve
+//! it but do request a handoff when ignited, so `Handoff` should only be
+//! attached when launching.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! # #[macro_use] extern crate rocket;
+//! use rocket::handoff::Handoff;
+//!
+//! #[launch]
+//! fn rocket() -> _ {
+//!     rocket::build().attach(Handoff::new("/run/app/control.sock"))
+//! }
+//! ```
+//!
+//! Running the new binary while the old one is serving hands off the server:
+//!
+//! ```sh
+//! ./app & # old process
+//! ./app & # new process: takes over once the old one drains
+//! ```
+
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::SocketAddr;
+use std::os::fd::{AsRawFd, OwnedFd, RawFd};
+use std::os::unix::net::UnixStream;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use parking_lot::Mutex;
+use tokio::net::TcpListener;
+
+use crate::{Rocket, Build, Orbit};
+use crate::events::Event;
+use crate::fairing::{self, Fairing, Info, Kind};
+use crate::util::{unix, FutureExt};
+
+/// The request a new process sends over the control socket.
+const REQUEST: &str = "HANDOFF";
+
+/// The header of the reply carrying the handed off listeners.
+const LISTENERS: &str = "LISTENERS\n";
+
+/// The maximum number of listeners handed off.
+const MAX_LISTENERS: usize = 16;
+
+/// A [`Fairing`] that coordinates handing off listeners between an old and a
+/// new process. See the [module documentation](crate::handoff) for details.
+pub struct Handoff {
+    control: PathBuf,
+    timeout: Duration,
+    /// Listeners bound, or inherited, by this process.
+    bound: Mutex<Vec<(SocketAddr, RawFd)>>,
+    /// Listeners inherited from an old process and not yet taken over.
+    inherited: Mutex<Vec<(SocketAddr, OwnedFd)>>,
+}
+
+impl Handoff {
+    /// Returns a `Handoff` fairing coordinating over the control socket at
+    /// `control` that waits up to 60 seconds for an old process to drain.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    ///
+    /// use rocket::handoff::Handoff;
+    ///
+    /// let handoff = Handoff::new("/run/app/control.sock")
+    ///     .timeout(Duration::from_secs(30));
+    /// ```
+    pub fn new<P: AsRef<Path>>(control: P) -> Self {
+        Handoff {
+            control: control.as_ref().to_path_buf(),
+            timeout: Duration::from_secs(60),
+            bound: Mutex::new(vec![]),
+            inherited: Mutex::new(vec![]),
+        }
+    }
+
+    /// Sets how long a new process waits for an old one to hand off its
+    /// listeners and drain. The timeout should exceed the old process's
+    /// shutdown [grace and mercy](crate::config::ShutdownConfig) periods.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Takes the listener inherited at `addr`, if any.
+    pub(crate) fn inherit(&self, addr: SocketAddr) -> Option<io::Result<TcpListener>> {
+        let mut inherited = self.inherited.lock();
+        let i = inherited.iter().position(|(a, _)| *a == addr)?;
+        let listener = std::net::TcpListener::from(inherited.remove(i).1);
+        let listener = listener.set_nonblocking(true)
+            .and_then(|_| TcpListener::from_std(listener));
+
+        Some(listener)
+    }
+
+    /// Records `listener` so that it can be handed off.
+    pub(crate) fn register(&self, listener: &TcpListener) {
+        if let Ok(addr) = listener.local_addr() {
+            self.bound.lock().push((addr, listener.as_raw_fd()));
+        }
+    }
+
+    /// Serves the control socket, holding on to `rocket` until a handoff or
+    /// shutdown.
+    pub(crate) fn spawn(&self, rocket: Arc<Rocket<Orbit>>) {
+        let control = self.control.clone();
+        tokio::spawn(async move {
+            let mut rocket = rocket;
+            let _ = std::fs::remove_file(&control);
+            let listener = match tokio::net::UnixListener::bind(&control) {
+                Ok(listener) => listener,
+                Err(e) => {
+                    warn!(name: "handoff", control = %control.display(),
+                        "failed to bind control socket: {e}");
+                    return;
+                }
+            };
+
+            let shutdown = rocket.shutdown();
+            loop {
+                let stream = match listener.accept().race(shutdown.clone()).await.left() {
+                    Some(Ok((stream, _))) => stream,
+                    Some(Err(e)) => {
+                        warn!(name: "handoff", "control socket failed: {e}");
+                        return;
+                    }
+                    None => return,
+                };
+
+                match hand_off(rocket, stream).await {
+                    Ok(()) => return,
+                    Err((r, e)) => {
+                        warn!(name: "handoff", "failed to hand off listeners: {e}");
+                        rocket = r;
+                    }
+                }
+            }
+        });
+    }
+}
+
+/// Asks the process serving the control socket at `control`, if any, to hand
+/// off its listeners and waits up to `timeout` for it to drain. Returns the
+/// inherited listeners.
+fn request(control: &Path, timeout: Duration) -> io::Result<Vec<(SocketAddr, OwnedFd)>> {
+    let stream = match UnixStream::connect(control) {
+        Ok(stream) => stream,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) if e.kind() == io::ErrorKind::ConnectionRefused => return Ok(vec![]),
+        Err(e) => return Err(e),
+    };
+
+    stream.set_read_timeout(Some(timeout))?;
+    stream.set_write_timeout(Some(timeout))?;
+    (&stream).write_all(format!("{REQUEST}\n").as_bytes())?;
+
+    let mut buf = [0; 4096];
+    let (n, fds) = unix::recv_with_fds(&stream, &mut buf, MAX_LISTENERS)?;
+    let reply = std::str::from_utf8(&buf[..n])
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+    let Some(addrs) = reply.strip_prefix(LISTENERS) else {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "invalid handoff reply"));
+    };
+
+    let addrs = addrs.lines()
+        .map(|line| line.parse::<SocketAddr>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+    if addrs.len() != fds.len() {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "listener count mismatch"));
+    }
+
+    let inherited = addrs.into_iter().zip(fds).collect::<Vec<_>>();
+    info!(name: "handoff", listeners = inherited.len(), "listeners handed off: awaiting drain");
+
+    let mut line = String::new();
+    match BufReader::new(&stream).read_line(&mut line) {
+        Ok(_) if line.trim() == "DRAINED" => info!(name: "handoff", "previous process drained"),
+        Ok(_) => warn!(name: "handoff", "previous process didn't drain gracefully"),
+        Err(e) => warn!(name: "handoff", "previous process didn't confirm drain: {e}"),
+    }
+
+    Ok(inherited)
+}
+
+/// Hands off the listeners of `rocket` over `stream` if it carries a request,
+/// then shuts `rocket` down and confirms once it has drained. On error, the
+/// handoff is abandoned and `rocket` is returned.
+async fn hand_off(
+    rocket: Arc<Rocket<Orbit>>,
+    stream: tokio::net::UnixStream,
+) -> Result<(), (Arc<Rocket<Orbit>>, io::Error)> {
+    let Some(handoff) = rocket.fairing::<Handoff>() else {
+        return Err((rocket, io::Error::other("missing handoff fairing")));
+    };
+
+    let bound = handoff.bound.lock().clone();
+    let (count, timeout) = (bound.len(), handoff.timeout);
+    let exchange = tokio::task::spawn_blocking(move || -> io::Result<UnixStream> {
+        let stream = stream.into_std()?;
+        stream.set_nonblocking(false)?;
+        stream.set_read_timeout(Some(timeout))?;
+
+        let mut line = String::new();
+        BufReader::new(&stream).read_line(&mut line)?;
+        if line.trim() != REQUEST {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "invalid handoff request"));
+        }
+
+        let addrs: String = bound.iter().map(|(addr, _)| format!("{addr}\n")).collect();
+        let reply = format!("{LISTENERS}{addrs}");
+        let fds: Vec<RawFd> = bound.iter().map(|(_, fd)| *fd).collect();
+        unix::send_with_fds(&stream, reply.as_bytes(), &fds)?;
+        Ok(stream)
+    });
+
+    let stream = match exchange.await {
+        Ok(Ok(stream)) => stream,
+        Ok(Err(e)) => return Err((rocket, e)),
+        Err(e) => return Err((rocket, io::Error::other(e))),
+    };
+
+    info!(name: "handoff", listeners = count, "listeners handed off: shutting down");
+    let mut events = rocket.events();
+    rocket.shutdown().notify();
+    drop(rocket);
+
+    let graceful = loop {
+        match events.recv().await {
+            Some(Event::Drained { graceful }) => break graceful,
+            Some(_) => continue,
+            None => break false,
+        }
+    };
+
+    let reply = if graceful { "DRAINED\n" } else { "TERMINATED\n" };
+    let _ = tokio::task::spawn_blocking(move || (&stream).write_all(reply.as_bytes())).await;
+    Ok(())
+}
+
+#[crate::async_trait]
+impl Fairing for Handoff {
+    fn info(&self) -> Info {
+        Info { name: "Handoff", kind: Kind::Ignite | Kind::Singleton }
+    }
+
+    async fn on_ignite(&self, rocket: Rocket<Build>) -> fairing::Result {
+        let (control, timeout) = (self.control.clone(), self.timeout);
+        let inherited = tokio::task::spawn_blocking(move || request(&control, timeout)).await;
+        match inherited {
+            Ok(Ok(inherited)) => self.inherited.lock().extend(inherited),
+            Ok(Err(e)) => warn!(name: "handoff", control = %self.control.display(),
+                "handoff failed: binding listeners: {e}"),
+            Err(e) => warn!(name: "handoff", "handoff task failed: {e}"),
+        }
+
+        Ok(rocket)
+    }
+}
@@ -0,0 +1,285 @@
+//! Token authentication for WebSocket and SSE handshakes.
+//!
+//! Browser `WebSocket` and `EventSource` clients cannot set an
+//! `Authorization` header. The [`Authenticated`] request guard instead finds a
+//! token in any of the places a browser client _can_ send one, in order:
+//!
+//!   * **`Sec-WebSocket-Protocol`**: a WebSocket client offers a marker
+//!     subprotocol, by default `bearer`, followed by the token, as in `new
+//!     WebSocket(url, ["bearer", token])`. The server must accept the marker;
+//!     [`ws`](crate::ws) does so automatically via [`accepted_protocol()`].
+//!   * **A query parameter**, by default `access_token`, as in `new
+//!     EventSource("/events?access_token=" + token)`.
+//!   * **The `Authorization` header**, as a `Bearer` token, for non-browser
+//!     clients.
+//!
+//! The token is validated by the [`TokenValidator`] of the managed
+//! [`TokenAuth`], which returns the [`Principal`] it identifies. The guard
+//! succeeds with the principal, which is then available to the handler for the
+//! lifetime of the connection. Clients that can't send a token during the
+//! handshake at all can instead authenticate with their first message; see
+//! [`ws`](crate::ws).
+//!
+//! # Example
+//!
+//! ```rust
+//! # #[macro_use] extern crate rocket;
+//! use rocket::handshake::{TokenAuth, TokenValidator, Principal, Authenticated};
+//! use rocket::response::stream::{EventStream, Event};
+//!
+//! struct Tokens;
+//!
+//! #[rocket::async_trait]
+//! impl TokenValidator for Tokens {
+//!     async fn validate(&self, token: &str) -> Option<Principal> {
+//!         /* verify the token, then: */
+//!         # (token == "secret").then(|| Principal::new("alice"))
+//!     }
+//! }
+//!
+//! #[get("/events")]
+//! fn events(auth: Authenticated) -> EventStream![] {
+//!     let user = auth.principal().id().to_string();
+//!     EventStream! {
+//!         yield Event::data(format!("hello, {user}"));
+//!     }
+//! }
+//!
+//! #[launch]
+//! fn rocket() -> _ {
+//!     rocket::build()
+//!         .manage(TokenAuth::new(Tokens))
+//!         .mount("/", routes![events])
+//! }
+//! ```
+
+use std::collections::BTreeSet;
+
+use crate::Request;
+use crate::http::Status;
+use crate::http::uncased::eq;
+use crate::request::{FromRequest, Outcome};
+
+/// Validates tokens presented during a handshake.
+///
+/// See the [module documentation](crate::handshake) for an example.
+#[crate::async_trait]
+pub trait TokenValidator: Send + Sync + 'static {
+    /// Returns the principal identified by `token`, or `None` if `token` is
+    /// invalid.
+    async fn validate(&self, token: &str) -> Option<Principal>;
+}
+
+/// An authenticated party, such as a user or a service.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::handshake::Principal;
+///
+/// let principal = Principal::new("alice").scope("chat:read");
+/// assert_eq!(principal.id(), "alice");
+/// assert!(principal.has_scope("chat:read"));
+/// assert!(!principal.has_scope("chat:write"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Principal {
+    id: String,
+    scopes: BTreeSet<String>,
+}
+
+/// Authenticates handshakes via a [`TokenValidator`].
+///
+/// A `TokenAuth` is [managed](crate::Rocket::manage()) and used via the
+/// [`Authenticated`] request guard. The marker subprotocol and query parameter
+/// carrying tokens are configurable.
+///
+/// See the [module documentation](crate::handshake) for an example.
+pub struct TokenAuth {
+    validator: Box<dyn TokenValidator>,
+    protocol: Option<String>,
+    query: Option<String>,
+}
+
+/// A request guard that authenticates the request via the managed
+/// [`TokenAuth`].
+///
+/// The guard fails with a status of `401 Unauthorized` if no token is
+/// presented or if the token is invalid. It forwards with a status of `500
+/// Internal Server Error` if no `TokenAuth` is managed.
+///
+/// See the [module documentation](crate::handshake) for an example.
+#[derive(Debug, Clone)]
+pub struct Authenticated {
+    principal: Principal,
+}
+
+/// The error of the [`Authenticated`] request guard.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum HandshakeError {
+    /// No token was presented.
+    Missing,
+    /// The presented token is invalid.
+    Invalid,
+}
+
+/// The outcome of authenticating a request, cached for the request.
+struct Handshake {
+    result: Result<Principal, HandshakeError>,
+    protocol: Option<String>,
+}
+
+impl Principal {
+    /// Returns a principal identified by `id` without scopes.
+    pub fn new<S: Into<String>>(id: S) -> Self {
+        Principal { id: id.into(), scopes: BTreeSet::new() }
+    }
+
+    /// Grants the principal `scope`.
+    pub fn scope<S: Into<String>>(mut self, scope: S) -> Self {
+        self.scopes.insert(scope.into());
+        self
+    }
+
+    /// Returns the principal's ID.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Returns the principal's scopes in lexicographic order.
+    pub fn scopes(&self) -> impl Iterator<Item = &str> {
+        self.scopes.iter().map(|s| s.as_str())
+    }
+
+    /// Returns `true` if the principal was granted `scope`.
+    pub fn has_scope(&self, scope: &str) -> bool {
+        self.scopes.contains(scope)
+    }
+}
+
+impl TokenAuth {
+    /// The default marker subprotocol: `bearer`.
+    pub const DEFAULT_PROTOCOL: &'static str = "bearer";
+
+    /// The default query parameter: `access_token`.
+    pub const DEFAULT_QUERY: &'static str = "access_token";
+
+    /// Returns a `TokenAuth` validating tokens with `validator`, found via the
+    /// [`DEFAULT_PROTOCOL`](Self::DEFAULT_PROTOCOL), the
+    /// [`DEFAULT_QUERY`](Self::DEFAULT_QUERY), or the `Authorization` header.
+    pub fn new<V: TokenValidator>(validator: V) -> Self {
+        TokenAuth {
+            validator: Box::new(validator),
+            protocol: Some(Self::DEFAULT_PROTOCOL.into()),
+            query: Some(Self::DEFAULT_QUERY.into()),
+        }
+    }
+
+    /// Sets the marker subprotocol preceding a token in the
+    /// `Sec-WebSocket-Protocol` header. If `None`, tokens aren't read from
+    /// the header.
+    pub fn protocol<S: Into<String>>(mut self, protocol: Option<S>) -> Self {
+        self.protocol = protocol.map(Into::into);
+        self
+    }
+
+    /// Sets the query parameter carrying a token. If `None`, tokens aren't
+    /// read from the query.
+    ///
+    /// Tokens in the query string may be logged by proxies. Prefer short-lived
+    /// tokens issued for the handshake.
+    pub fn query<S: Into<String>>(mut self, query: Option<S>) -> Self {
+        self.query = query.map(Into::into);
+        self
+    }
+
+    /// Returns the principal identified by `token`, or `None` if `token` is
+    /// invalid.
+    pub async fn validate(&self, token: &str) -> Option<Principal> {
+        self.validator.validate(token).await
+    }
+
+    /// Returns the token presented by `req` and, if it was presented via the
+    /// `Sec-WebSocket-Protocol` header, the marker subprotocol.
+    fn token<'r>(&self, req: &'r Request<'_>) -> Option<(&'r str, Option<String>)> {
+        if let Some(marker) = &self.protocol {
+            let mut offered = req.headers().get("Sec-WebSocket-Protocol")
+                .flat_map(|v| v.split(','))
+                .map(|v| v.trim());
+
+            if offered.by_ref().any(|v| eq(v, marker)) {
+                if let Some(token) = offered.next().filter(|t| !t.is_empty()) {
+                    return Some((token, Some(marker.clone())));
+                }
+            }
+        }
+
+        let query = self.query.as_deref()
+            .and_then(|name| req.query_value::<&str>(name))
+            .and_then(|token| token.ok())
+            .filter(|token| !token.is_empty());
+
+        if let Some(token) = query {
+            return Some((token, None));
+        }
+
+        req.headers().get_one("Authorization")
+            .and_then(|v| v.split_once(' '))
+            .filter(|(scheme, _)| eq(scheme, "Bearer"))
+            .map(|(_, token)| token.trim())
+            .filter(|token| !token.is_empty())
+            .map(|token| (token, None))
+    }
+}
+
+impl Authenticated {
+    /// Returns the authenticated principal.
+    pub fn principal(&self) -> &Principal {
+        &self.principal
+    }
+
+    /// Consumes `self` and returns the authenticated principal.
+    pub fn into_principal(self) -> Principal {
+        self.principal
+    }
+}
+
+/// Returns the marker subprotocol through which `req` was authenticated, if it
+/// was. A WebSocket server must accept this subprotocol in its handshake
+/// response via the `Sec-WebSocket-Protocol` header.
+pub fn accepted_protocol<'r>(req: &'r Request<'_>) -> Option<&'r str> {
+    req.state.cache.try_get::<Handshake>()
+        .filter(|handshake| handshake.result.is_ok())
+        .and_then(|handshake| handshake.protocol.as_deref())
+}
+
+#[crate::async_trait]
+impl<'r> FromRequest<'r> for Authenticated {
+    type Error = HandshakeError;
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, HandshakeError> {
+        let Some(auth) = req.rocket().state::<TokenAuth>() else {
+            error!("`Authenticated` guard used but no `TokenAuth` is managed");
+            return Outcome::Forward(Status::InternalServerError);
+        };
+
+        let handshake = req.local_cache_async(async {
+            let Some((token, protocol)) = auth.token(req) else {
+                return Handshake { result: Err(HandshakeError::Missing), protocol: None };
+            };
+
+            match auth.validate(token).await {
+                Some(principal) => Handshake { result: Ok(principal), protocol },
+                None => Handshake { result: Err(HandshakeError::Invalid), protocol: None },
+            }
+        }).await;
+
+        match &handshake.result {
+            Ok(principal) => {
+                req.set_principal(principal.id());
+                Outcome::Success(Authenticated { principal: principal.clone() })
+            }
+            Err(e) => Outcome::Error((Status::Unauthorized, *e)),
+        }
+    }
+}
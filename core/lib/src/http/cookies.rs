@@ -159,7 +159,6 @@ pub struct CookieJar<'a> {
 #[derive(Copy, Clone)]
 pub(crate) struct CookieState<'a> {
     pub secure: bool,
-    #[cfg_attr(not(feature = "secrets"), allow(unused))]
     pub config: &'a crate::Config,
 }
 
@@ -280,7 +279,10 @@ impl<'a> CookieJar<'a> {
     ///    * `Secure`: `true` if [`Request::context_is_likely_secure()`]
     ///
     /// These defaults ensure maximum usability and security. For additional
-    /// security, you may wish to set the `secure` flag explicitly.
+    /// security, you may wish to set the `secure` flag explicitly. The
+    /// `SameSite` and `Secure` defaults can be changed, and an `HttpOnly`
+    /// default set, per profile via the [`cookies`](crate::config::CookieConfig)
+    /// configuration parameter.
     ///
     /// [`Request::context_is_likely_secure()`]: crate::Request::context_is_likely_secure()
     ///
@@ -325,7 +327,9 @@ impl<'a> CookieJar<'a> {
     ///
     /// These defaults ensure maximum usability and security. For additional
     /// security, you may wish to set the `secure` flag explicitly and
-    /// unconditionally.
+    /// unconditionally. The `SameSite`, `Secure`, and `HttpOnly` defaults can be
+    /// changed per profile via the [`cookies`](crate::config::CookieConfig)
+    /// configuration parameter.
     ///
     /// [`Request::context_is_likely_secure()`]: crate::Request::context_is_likely_secure()
     ///
@@ -511,20 +515,27 @@ impl<'a> CookieJar<'a> {
     /// values are:
     ///
     ///    * `path`: `"/"`
-    ///    * `SameSite`: `Strict`
-    ///    * `Secure`: `true` if `Request::context_is_likely_secure()`
+    ///    * `SameSite`: `config.cookies.default_same_site`
+    ///    * `Secure`: `config.cookies.secure` or, if unset, `true` if
+    ///      `Request::context_is_likely_secure()`
+    ///    * `HttpOnly`: `config.cookies.http_only`, if set
     fn set_defaults(&self, cookie: &mut Cookie<'static>) {
+        let defaults = &self.state.config.cookies;
         if cookie.path().is_none() {
             cookie.set_path("/");
         }
 
         if cookie.same_site().is_none() {
-            cookie.set_same_site(SameSite::Strict);
+            cookie.set_same_site(defaults.default_same_site);
         }
 
-        if cookie.secure().is_none() && self.state.secure {
+        if cookie.secure().is_none() && defaults.secure.unwrap_or(self.state.secure) {
             cookie.set_secure(true);
         }
+
+        if let (None, Some(http_only)) = (cookie.http_only(), defaults.http_only) {
+            cookie.set_http_only(http_only);
+        }
     }
 
     /// For each property below, this method checks if there is a provided value
@@ -547,10 +558,11 @@ impl<'a> CookieJar<'a> {
     /// values are:
     ///
     ///    * `path`: `"/"`
-    ///    * `SameSite`: `Strict`
-    ///    * `HttpOnly`: `true`
+    ///    * `SameSite`: `config.cookies.default_same_site`
+    ///    * `HttpOnly`: `config.cookies.http_only` or, if unset, `true`
     ///    * `Expires`: 1 week from now
-    ///    * `Secure`: `true` if `Request::context_is_likely_secure()`
+    ///    * `Secure`: `config.cookies.secure` or, if unset, `true` if
+    ///      `Request::context_is_likely_secure()`
     #[cfg(feature = "secrets")]
     #[cfg_attr(nightly, doc(cfg(feature = "secrets")))]
     fn set_private_defaults(&self, cookie: &mut Cookie<'static>) {
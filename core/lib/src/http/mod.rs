@@ -4,9 +4,13 @@
 //! HTTP library when needed.
 
 mod cookies;
+mod prefix;
 
 #[doc(inline)]
 pub use rocket_http::*;
 
 #[doc(inline)]
 pub use cookies::*;
+
+#[doc(inline)]
+pub use prefix::{CookiePrefix, CookiePrefixExt, PrefixError};
@@ -0,0 +1,217 @@
+use std::fmt;
+
+use cookie::{Cookie, CookieBuilder};
+
+/// A cookie name prefix from [RFC 6265bis] that restricts how a cookie may be
+/// set.
+///
+/// Browsers reject cookies whose names begin with a prefix but which don't
+/// meet the prefix's requirements:
+///
+///   * `__Secure-`: the cookie must be `Secure`.
+///   * `__Host-`: the cookie must be `Secure`, must have a `Path` of `/`, and
+///     must not have a `Domain`, locking it to the host that set it.
+///
+/// Use [`CookiePrefixExt`] to build cookies that meet these requirements.
+///
+/// [RFC 6265bis]: https://datatracker.ietf.org/doc/html/draft-ietf-httpbis-rfc6265bis#name-cookie-name-prefixes
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum CookiePrefix {
+    /// The `__Host-` prefix.
+    Host,
+    /// The `__Secure-` prefix.
+    Secure,
+}
+
+/// The reason a cookie does not meet the requirements of its [`CookiePrefix`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum PrefixError {
+    /// The cookie is not `Secure`.
+    NotSecure,
+    /// The `__Host-` cookie has a `Domain`.
+    Domain,
+    /// The `__Host-` cookie has a `Path` other than `/`.
+    Path,
+}
+
+impl CookiePrefix {
+    /// Returns the prefix string, `"__Host-"` or `"__Secure-"`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CookiePrefix::Host => "__Host-",
+            CookiePrefix::Secure => "__Secure-",
+        }
+    }
+
+    /// Returns the prefix `name` begins with, if any. As browsers do, prefixes
+    /// are matched case-insensitively.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::CookiePrefix;
+    ///
+    /// assert_eq!(CookiePrefix::of("__Host-id"), Some(CookiePrefix::Host));
+    /// assert_eq!(CookiePrefix::of("__secure-id"), Some(CookiePrefix::Secure));
+    /// assert_eq!(CookiePrefix::of("id"), None);
+    /// ```
+    pub fn of(name: &str) -> Option<CookiePrefix> {
+        let has = |prefix: &str| name.get(..prefix.len())
+            .map_or(false, |start| start.eq_ignore_ascii_case(prefix));
+
+        [CookiePrefix::Host, CookiePrefix::Secure].into_iter().find(|p| has(p.as_str()))
+    }
+
+    /// Checks that `cookie` meets the requirements of this prefix. The name of
+    /// `cookie` is not checked.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::{Cookie, CookiePrefix, PrefixError};
+    ///
+    /// let cookie = Cookie::build(("__Host-id", "1")).secure(true).path("/").build();
+    /// assert!(CookiePrefix::Host.check(&cookie).is_ok());
+    ///
+    /// let cookie = Cookie::build(("__Host-id", "1")).secure(true).domain("rocket.rs");
+    /// assert_eq!(CookiePrefix::Host.check(&cookie.build()), Err(PrefixError::Domain));
+    /// ```
+    pub fn check(&self, cookie: &Cookie<'_>) -> Result<(), PrefixError> {
+        if cookie.secure() != Some(true) {
+            return Err(PrefixError::NotSecure);
+        }
+
+        if *self == CookiePrefix::Host {
+            if cookie.domain().is_some() {
+                return Err(PrefixError::Domain);
+            }
+
+            if cookie.path() != Some("/") {
+                return Err(PrefixError::Path);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn apply<'c>(&self, cookie: impl Into<Cookie<'c>>) -> Result<Cookie<'c>, PrefixError> {
+        let mut cookie = cookie.into();
+        if cookie.secure().is_none() {
+            cookie.set_secure(true);
+        }
+
+        if *self == CookiePrefix::Host && cookie.path().is_none() {
+            cookie.set_path("/");
+        }
+
+        self.check(&cookie)?;
+
+        // Replace any existing prefix so that `__Secure-id` becomes `__Host-id`
+        // rather than `__Host-__Secure-id`, normalizing the prefix's case.
+        let unprefixed = match CookiePrefix::of(cookie.name()) {
+            Some(prefix) => &cookie.name()[prefix.as_str().len()..],
+            None => cookie.name(),
+        };
+
+        let name = format!("{}{}", self.as_str(), unprefixed);
+        cookie.set_name(name);
+        Ok(cookie)
+    }
+}
+
+impl fmt::Display for CookiePrefix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl fmt::Display for PrefixError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PrefixError::NotSecure => f.write_str("prefixed cookies must be `Secure`"),
+            PrefixError::Domain => f.write_str("`__Host-` cookies must not have a `Domain`"),
+            PrefixError::Path => f.write_str("`__Host-` cookies must have a `Path` of `/`"),
+        }
+    }
+}
+
+impl std::error::Error for PrefixError { }
+
+/// Extension methods to build [`CookiePrefix`]ed cookies.
+///
+/// Implemented for [`CookieBuilder`] and [`Cookie`]. Each method prefixes the
+/// cookie's name, replacing any prefix it already has, sets the attributes the
+/// prefix requires unless they were set explicitly, and then validates them,
+/// returning an error if a requirement is not met.
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::http::{Cookie, CookieJar, CookiePrefixExt, PrefixError};
+///
+/// #[get("/login")]
+/// fn login(jar: &CookieJar<'_>) {
+///     // Sets `__Host-session=abc; HttpOnly; Secure; Path=/`.
+///     let cookie = Cookie::build(("session", "abc")).http_only(true).host_prefixed();
+///     jar.add(cookie.expect("cookie meets `__Host-` requirements"));
+/// }
+///
+/// let cookie = Cookie::build(("id", "1")).secure_prefixed().unwrap();
+/// assert_eq!(cookie.name(), "__Secure-id");
+/// assert_eq!(cookie.secure(), Some(true));
+///
+/// let result = Cookie::build(("id", "1")).secure(false).secure_prefixed();
+/// assert_eq!(result.unwrap_err(), PrefixError::NotSecure);
+///
+/// let result = Cookie::build(("id", "1")).path("/app").host_prefixed();
+/// assert_eq!(result.unwrap_err(), PrefixError::Path);
+/// ```
+pub trait CookiePrefixExt<'c>: Sized {
+    /// Builds a `__Host-` cookie: `Secure`, with a `Path` of `/`, and without
+    /// a `Domain`.
+    fn host_prefixed(self) -> Result<Cookie<'c>, PrefixError>;
+
+    /// Builds a `__Secure-` cookie: `Secure`.
+    fn secure_prefixed(self) -> Result<Cookie<'c>, PrefixError>;
+}
+
+impl<'c> CookiePrefixExt<'c> for CookieBuilder<'c> {
+    fn host_prefixed(self) -> Result<Cookie<'c>, PrefixError> {
+        CookiePrefix::Host.apply(self)
+    }
+
+    fn secure_prefixed(self) -> Result<Cookie<'c>, PrefixError> {
+        CookiePrefix::Secure.apply(self)
+    }
+}
+
+impl<'c> CookiePrefixExt<'c> for Cookie<'c> {
+    fn host_prefixed(self) -> Result<Cookie<'c>, PrefixError> {
+        CookiePrefix::Host.apply(self)
+    }
+
+    fn secure_prefixed(self) -> Result<Cookie<'c>, PrefixError> {
+        CookiePrefix::Secure.apply(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn existing_prefixes_are_replaced() {
+        let cookie = Cookie::build(("__Secure-id", "1")).host_prefixed().unwrap();
+        assert_eq!(cookie.name(), "__Host-id");
+
+        let cookie = Cookie::build(("__Host-id", "1")).secure_prefixed().unwrap();
+        assert_eq!(cookie.name(), "__Secure-id");
+
+        let cookie = Cookie::new("__host-id", "1").host_prefixed().unwrap();
+        assert_eq!(cookie.name(), "__Host-id");
+
+        let cookie = Cookie::new("id", "1").host_prefixed().unwrap();
+        assert_eq!(cookie.name(), "__Host-id");
+    }
+}
@@ -0,0 +1,417 @@
+//! A development-only inspector of recent requests and responses.
+//!
+//! The [`Inspector`] fairing records the most recent requests handled by an
+//! application, along with their responses, in an in-memory ring buffer and
+//! serves them at [`/_rocket/requests`](Inspector::PATH) as an HTML page or,
+//! when JSON is preferred by the client, as a JSON array. Each record contains:
+//!
+//!   * the request's method, URI, and headers,
+//!   * the route that handled the request, if any,
+//!   * the response's status and headers,
+//!   * the trail of guards that forwarded or failed (see
+//!     [`Diagnostic`](crate::request::Diagnostic)), and
+//!   * the time elapsed between request and response fairing callbacks.
+//!
+//! With [`Inspector::pipeline()`], the inspector additionally records the
+//! _pipeline_ of a sampled fraction of requests: every fairing, route attempt,
+//! request and data guard, handler, responder, and catcher that ran, nested
+//! and in order, with timings (see [`Stage`]). Pipelines are included in the
+//! JSON records and, when requested via `?folded`, served as folded stacks, one
+//! line per stage, as consumed by flamegraph tools such as [`inferno`]:
+//!
+//! ```sh
+//! curl -s 'localhost:8000/_rocket/requests?folded' | inferno-flamegraph > pipeline.svg
+//! ```
+//!
+//! [`inferno`]: https://github.com/jonhoo/inferno
+//!
+//! Values of headers that typically carry credentials, such as `Cookie` and
+//! `Authorization`, are redacted. The inspector is **only** active in the
+//! `debug` profile. In any other profile, attaching it has no effect: nothing
+//! is recorded and no route is mounted.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! # #[macro_use] extern crate rocket;
+//! use rocket::inspector::Inspector;
+//!
+//! #[launch]
+//! fn rocket() -> _ {
+//!     rocket::build().attach(Inspector::new().capacity(100).pipeline(0.25))
+//! }
+//! ```
+
+use std::collections::VecDeque;
+use std::fmt::Write;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+
+use crate::{Rocket, Request, Response, Data, Build, Route, Config};
+use crate::fairing::{self, Fairing, Info, Kind};
+use crate::http::{Method, ContentType};
+use crate::request::{Stage, Pipeline};
+use crate::route::{Handler, Outcome};
+use crate::util::{EscapeHtml, EscapeJson, is_sensitive_header};
+
+/// A [`Fairing`] that records recent requests and serves them for inspection.
+///
+/// See the [module level docs](self) for details.
+#[derive(Clone)]
+pub struct Inspector {
+    capacity: usize,
+    pipeline: f64,
+    log: Arc<Log>,
+}
+
+#[derive(Default)]
+struct Log {
+    enabled: AtomicBool,
+    next_id: AtomicU64,
+    exchanges: Mutex<VecDeque<Exchange>>,
+}
+
+/// A recorded request and its response.
+struct Exchange {
+    id: u64,
+    method: Method,
+    uri: String,
+    route: Option<String>,
+    route_template: Option<String>,
+    status: u16,
+    elapsed: Option<Duration>,
+    request_headers: Vec<(String, String)>,
+    response_headers: Vec<(String, String)>,
+    diagnostics: Vec<String>,
+    /// The recorded stages, if the request was sampled. Shared so that stages
+    /// recorded after the inspector's response fairing runs are included.
+    pipeline: Option<Arc<Mutex<Vec<Stage>>>>,
+}
+
+/// The instant the request fairing ran for a request.
+struct Started(Instant);
+
+impl Inspector {
+    /// The path at which recorded requests are served.
+    pub const PATH: &'static str = "/_rocket/requests";
+
+    /// The default number of requests that are kept: `50`.
+    pub const DEFAULT_CAPACITY: usize = 50;
+
+    /// Returns a new `Inspector` that keeps the [`DEFAULT_CAPACITY`] most
+    /// recent requests.
+    ///
+    /// [`DEFAULT_CAPACITY`]: Self::DEFAULT_CAPACITY
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::inspector::Inspector;
+    ///
+    /// let inspector = Inspector::new();
+    /// ```
+    pub fn new() -> Self {
+        Inspector { capacity: Self::DEFAULT_CAPACITY, pipeline: 0.0, log: Arc::default() }
+    }
+
+    /// Sets the number of most recent requests that are kept to `capacity`.
+    /// A `capacity` of `0` is treated as `1`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::inspector::Inspector;
+    ///
+    /// let inspector = Inspector::new().capacity(10);
+    /// ```
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity.max(1);
+        self
+    }
+
+    /// Records the pipeline of the fraction `rate`, from `0.0` to `1.0`, of
+    /// requests. Values outside of the range are clamped to it. By default, no
+    /// pipelines are recorded.
+    ///
+    /// Recording begins when the inspector's request callback runs, so request
+    /// fairings attached before the inspector aren't recorded. To record them,
+    /// attach the inspector first.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::inspector::Inspector;
+    ///
+    /// // Record the pipeline of every request.
+    /// let inspector = Inspector::new().pipeline(1.0);
+    /// ```
+    pub fn pipeline(mut self, rate: f64) -> Self {
+        self.pipeline = rate.clamp(0.0, 1.0);
+        self
+    }
+
+    fn headers<'h>(headers: impl Iterator<Item = crate::http::Header<'h>>) -> Vec<(String, String)> {
+        headers.map(|h| {
+                let value = match is_sensitive_header(h.name().as_str()) {
+                    true => "[redacted]".to_string(),
+                    false => h.value().to_string(),
+                };
+
+                (h.name().to_string(), value)
+            })
+            .collect()
+    }
+}
+
+impl Default for Inspector {
+    fn default() -> Self {
+        Inspector::new()
+    }
+}
+
+#[crate::async_trait]
+impl Fairing for Inspector {
+    fn info(&self) -> Info {
+        Info {
+            name: "Inspector",
+            kind: Kind::Ignite | Kind::Request | Kind::Response | Kind::Singleton,
+        }
+    }
+
+    async fn on_ignite(&self, rocket: Rocket<Build>) -> fairing::Result {
+        if rocket.figment().profile() != Config::DEBUG_PROFILE {
+            info!(name: "inspector", "request inspector is only enabled in debug profile");
+            return Ok(rocket);
+        }
+
+        self.log.enabled.store(true, Ordering::Release);
+        info!(name: "inspector", path = Self::PATH, capacity = self.capacity,
+            pipeline = self.pipeline, "request inspector enabled");
+
+        Ok(rocket.mount(Self::PATH, vec![Route::new(Method::Get, "/", self.clone())]))
+    }
+
+    async fn on_request(&self, req: &mut Request<'_>, _: &mut Data<'_>) {
+        if !self.log.enabled.load(Ordering::Acquire) {
+            return;
+        }
+
+        req.local_cache(|| Started(Instant::now()));
+        if self.pipeline == 0.0 || req.uri().path() == Self::PATH {
+            return;
+        }
+
+        if self.pipeline >= 1.0 || rand::random::<f64>() < self.pipeline {
+            Pipeline::begin(req);
+        }
+    }
+
+    async fn on_response<'r>(&self, req: &'r Request<'_>, res: &mut Response<'r>) {
+        if !self.log.enabled.load(Ordering::Acquire) || req.uri().path().as_str() == Self::PATH {
+            return;
+        }
+
+        let exchange = Exchange {
+            id: self.log.next_id.fetch_add(1, Ordering::AcqRel),
+            method: req.method(),
+            uri: req.uri().to_string(),
+            route: req.route().map(|r| match &r.name {
+                Some(name) => format!("{name} ({} {})", r.method, r.uri),
+                None => format!("{} {}", r.method, r.uri),
+            }),
+            route_template: req.route_template().map(|t| t.to_string()),
+            status: res.status().code,
+            elapsed: req.state.cache.try_get::<Started>().map(|s| s.0.elapsed()),
+            request_headers: Self::headers(req.headers().iter()),
+            response_headers: Self::headers(res.headers().iter()),
+            diagnostics: req.diagnostics().iter().map(|d| d.to_string()).collect(),
+            pipeline: Pipeline::shared(req),
+        };
+
+        let mut exchanges = self.log.exchanges.lock();
+        while exchanges.len() >= self.capacity {
+            exchanges.pop_front();
+        }
+
+        exchanges.push_back(exchange);
+    }
+}
+
+#[crate::async_trait]
+impl Handler for Inspector {
+    async fn handle<'r>(&self, req: &'r Request<'_>, _: Data<'r>) -> Outcome<'r> {
+        let json = req.accept().map_or(false, |a| a.preferred().is_json());
+        let exchanges = self.log.exchanges.lock();
+        if req.uri().query().map_or(false, |q| q.segments().any(|(k, _)| k == "folded")) {
+            let body = render_folded(exchanges.iter());
+            drop(exchanges);
+            return Outcome::from(req, (ContentType::Plain, body));
+        }
+
+        let newest_first = exchanges.iter().rev();
+        let body = match json {
+            true => render_json(newest_first),
+            false => render_html(newest_first),
+        };
+
+        drop(exchanges);
+        match json {
+            true => Outcome::from(req, (ContentType::JSON, body)),
+            false => Outcome::from(req, (ContentType::HTML, body)),
+        }
+    }
+}
+
+fn render_json<'a>(exchanges: impl Iterator<Item = &'a Exchange>) -> String {
+    fn headers(out: &mut String, headers: &[(String, String)]) {
+        out.push('[');
+        for (i, (name, value)) in headers.iter().enumerate() {
+            if i != 0 { out.push(','); }
+            let _ = write!(out, "[\"{}\",\"{}\"]", EscapeJson(name), EscapeJson(value));
+        }
+
+        out.push(']');
+    }
+
+    let mut out = String::from("[");
+    for (i, e) in exchanges.enumerate() {
+        if i != 0 { out.push(','); }
+        let _ = write!(out, "{{\"id\":{},\"method\":\"{}\",\"uri\":\"{}\",\"route\":",
+            e.id, e.method, EscapeJson(&e.uri));
+
+        let _ = match &e.route {
+            Some(route) => write!(out, "\"{}\"", EscapeJson(route)),
+            None => write!(out, "null"),
+        };
+
+        let _ = write!(out, ",\"status\":{},\"elapsed_us\":", e.status);
+        let _ = match e.elapsed {
+            Some(elapsed) => write!(out, "{}", elapsed.as_micros()),
+            None => write!(out, "null"),
+        };
+
+        out.push_str(",\"request_headers\":");
+        headers(&mut out, &e.request_headers);
+        out.push_str(",\"response_headers\":");
+        headers(&mut out, &e.response_headers);
+        out.push_str(",\"diagnostics\":[");
+        for (i, diagnostic) in e.diagnostics.iter().enumerate() {
+            if i != 0 { out.push(','); }
+            let _ = write!(out, "\"{}\"", EscapeJson(diagnostic));
+        }
+
+        out.push_str("],\"pipeline\":[");
+        let stages = e.pipeline.as_ref().map(|p| p.lock().clone()).unwrap_or_default();
+        for (i, stage) in stages.iter().enumerate() {
+            if i != 0 { out.push(','); }
+            let _ = write!(out, "{{\"kind\":\"{}\",\"name\":\"{}\",\"parent\":",
+                stage.kind, EscapeJson(&stage.name));
+
+            let _ = match stage.parent {
+                Some(parent) => write!(out, "{parent}"),
+                None => write!(out, "null"),
+            };
+
+            let _ = write!(out, ",\"start_us\":{},\"elapsed_us\":{}}}",
+                stage.start.as_micros(), stage.elapsed.as_micros());
+        }
+
+        out.push_str("]}");
+    }
+
+    out.push(']');
+    out
+}
+
+fn render_folded<'a>(exchanges: impl Iterator<Item = &'a Exchange>) -> String {
+    exchanges
+        .filter_map(|e| {
+            let stages = e.pipeline.as_ref()?.lock().clone();
+            let route = e.route_template.as_deref().unwrap_or(&e.uri);
+            Some(Stage::folded(&format!("{} {}", e.method, route), &stages))
+        })
+        .collect()
+}
+
+fn render_html<'a>(exchanges: impl Iterator<Item = &'a Exchange>) -> String {
+    fn headers(out: &mut String, headers: &[(String, String)]) {
+        out.push_str("<table>");
+        for (name, value) in headers {
+            let _ = write!(out, "<tr><th>{}</th><td>{}</td></tr>",
+                EscapeHtml(name), EscapeHtml(value));
+        }
+
+        out.push_str("</table>");
+    }
+
+    let mut rows = String::new();
+    for e in exchanges {
+        let elapsed = e.elapsed.map_or("-".into(), |d| format!("{:.3}ms", d.as_secs_f64() * 1e3));
+        let route = e.route.as_deref().unwrap_or("-");
+        let _ = write!(rows, "<details><summary>#{} {} {} &rarr; {} ({})</summary>\
+            <p>route: {}</p>",
+            e.id, e.method, EscapeHtml(&e.uri), e.status, elapsed, EscapeHtml(route));
+
+        let stages = e.pipeline.as_ref().map(|p| p.lock().clone()).unwrap_or_default();
+        if !stages.is_empty() {
+            rows.push_str("<h4>Pipeline</h4><table>");
+            for stage in &stages {
+                let (mut depth, mut parent) = (0, stage.parent);
+                while let Some(p) = parent.and_then(|p| stages.get(p)) {
+                    (depth, parent) = (depth + 1, p.parent);
+                }
+
+                let _ = write!(rows, "<tr><th style=\"padding-left: {}em\">{}</th>\
+                    <td>{}</td><td>{:.3}ms</td></tr>",
+                    depth * 2, stage.kind, EscapeHtml(&stage.name),
+                    stage.elapsed.as_secs_f64() * 1e3);
+            }
+
+            rows.push_str("</table>");
+        }
+
+        if !e.diagnostics.is_empty() {
+            rows.push_str("<ul>");
+            for diagnostic in &e.diagnostics {
+                let _ = write!(rows, "<li>{}</li>", EscapeHtml(diagnostic));
+            }
+
+            rows.push_str("</ul>");
+        }
+
+        rows.push_str("<h4>Request Headers</h4>");
+        headers(&mut rows, &e.request_headers);
+        rows.push_str("<h4>Response Headers</h4>");
+        headers(&mut rows, &e.response_headers);
+        rows.push_str("</details>\n");
+    }
+
+    if rows.is_empty() {
+        rows.push_str("<p>No requests have been recorded yet.</p>");
+    }
+
+    format!(r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="utf-8">
+    <meta name="color-scheme" content="light dark">
+    <title>Rocket: Recent Requests</title>
+    <style>
+        body {{ font-family: sans-serif; margin: 2em auto; max-width: 60em; }}
+        summary {{ cursor: pointer; font-family: monospace; }}
+        details {{ margin: 0.5em 0; }}
+        th {{ text-align: left; padding-right: 1em; vertical-align: top; }}
+        td {{ font-family: monospace; word-break: break-all; }}
+    </style>
+</head>
+<body>
+    <h1>Recent Requests</h1>
+    {rows}
+    <hr />
+    <small>Rocket: the inspector is only enabled in the <code>debug</code> profile.</small>
+</body>
+</html>"#)
+}
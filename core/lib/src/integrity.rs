@@ -0,0 +1,367 @@
+//! Integrity manifests of static assets and templates.
+//!
+//! The [`Integrity`] fairing computes, at ignition, a [`Manifest`] of the
+//! SHA-384 digests of the files in directories of static assets and of
+//! templates. The manifest pins the exact files an application was launched
+//! with, supporting deployments that must account for their supply chain:
+//!
+//!   * Each asset's digest is available as a [Subresource Integrity] value,
+//!     `sha384-<base64>`, via [`Manifest::asset()`], and, with the
+//!     `integrity` feature of `rocket_dyn_templates`, via an `integrity`
+//!     template helper, so that pages can emit `integrity` attributes.
+//!   * At liftoff, the number of files and the manifest's
+//!     [fingerprint](Manifest::fingerprint()), a digest of all of its
+//!     entries, are logged, so that deployments can be compared at a glance.
+//!   * When [enabled](Integrity::endpoint()), the manifest is served as JSON
+//!     at [`/_integrity`](Integrity::PATH).
+//!
+//! Files whose names begin with `.` are skipped, as [`FileServer`] hides them
+//! by default. Files are hashed once, at ignition: files changed afterwards
+//! no longer match the manifest. If a directory can't be read, ignition fails.
+//!
+//! [Subresource Integrity]: https://developer.mozilla.org/en-US/docs/Web/Security/Subresource_Integrity
+//! [`FileServer`]: crate::fs::FileServer
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! # #[macro_use] extern crate rocket;
+//! use rocket::fs::{FileServer, relative};
+//! use rocket::integrity::Integrity;
+//!
+//! #[launch]
+//! fn rocket() -> _ {
+//!     let integrity = Integrity::new()
+//!         .assets("/static", relative!("static"))
+//!         .templates(relative!("templates"))
+//!         .endpoint(true);
+//!
+//!     rocket::build()
+//!         .mount("/static", FileServer::new(relative!("static")))
+//!         .attach(integrity)
+//! }
+//! ```
+//!
+//! A page can then pin a script, here in a Tera template:
+//!
+//! ```text
+//! <script src="/static/app.js" integrity="{{ integrity(uri='/static/app.js') }}"
+//!     crossorigin="anonymous"></script>
+//! ```
+
+use std::{fs, io};
+use std::fmt::Write;
+use std::sync::Arc;
+use std::path::{Path, PathBuf};
+use std::collections::BTreeMap;
+
+use ring::digest::{Context, SHA384};
+use state::InitCell;
+
+use crate::{Rocket, Request, Data, Build, Orbit, Route};
+use crate::fairing::{self, Fairing, Info, Kind};
+use crate::figment::value::magic::RelativePathBuf;
+use crate::http::{Method, ContentType};
+use crate::route::{Handler, Outcome};
+use crate::trace::Trace;
+use crate::util::EscapeJson;
+
+/// A [`Fairing`] that computes an integrity [`Manifest`] at ignition.
+///
+/// See the [module level docs](self) for details.
+#[derive(Clone)]
+pub struct Integrity {
+    assets: Vec<(String, PathBuf)>,
+    templates: Option<PathBuf>,
+    endpoint: bool,
+    manifest: Arc<InitCell<Manifest>>,
+}
+
+/// The digests of the static assets and templates an application was launched
+/// with, computed by [`Integrity`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Manifest {
+    assets: BTreeMap<String, String>,
+    templates: BTreeMap<String, String>,
+    fingerprint: String,
+}
+
+impl Integrity {
+    /// The path at which the manifest is served when the endpoint is enabled.
+    pub const PATH: &'static str = "/_integrity";
+
+    /// Returns an `Integrity` fairing without any assets. Templates are read
+    /// from the `template_dir` configuration parameter, if it's set, unless a
+    /// directory is set via [`Integrity::templates()`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::integrity::Integrity;
+    ///
+    /// let integrity = Integrity::new();
+    /// ```
+    pub fn new() -> Self {
+        Integrity {
+            assets: vec![],
+            templates: None,
+            endpoint: false,
+            manifest: Arc::new(InitCell::new()),
+        }
+    }
+
+    /// Hashes the static assets in `dir`, recursively, recording each under
+    /// the URI it's served at: its path relative to `dir` prefixed with
+    /// `prefix`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::integrity::Integrity;
+    ///
+    /// // `static/js/app.js` is recorded as `/static/js/app.js`.
+    /// let integrity = Integrity::new().assets("/static", "static");
+    /// ```
+    pub fn assets<U: Into<String>, P: AsRef<Path>>(mut self, prefix: U, dir: P) -> Self {
+        let prefix = prefix.into().trim_end_matches('/').to_string();
+        self.assets.push((prefix, dir.as_ref().to_path_buf()));
+        self
+    }
+
+    /// Hashes the templates in `dir`, recursively, recording each under its
+    /// path relative to `dir`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::integrity::Integrity;
+    ///
+    /// let integrity = Integrity::new().templates("templates");
+    /// ```
+    pub fn templates<P: AsRef<Path>>(mut self, dir: P) -> Self {
+        self.templates = Some(dir.as_ref().to_path_buf());
+        self
+    }
+
+    /// Sets whether the manifest is served at [`Integrity::PATH`]. Disabled
+    /// by default.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::integrity::Integrity;
+    ///
+    /// let integrity = Integrity::new().endpoint(true);
+    /// ```
+    pub fn endpoint(mut self, enabled: bool) -> Self {
+        self.endpoint = enabled;
+        self
+    }
+
+    /// Returns the manifest once it has been computed at ignition.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::integrity::Integrity;
+    /// use rocket::local::blocking::Client;
+    ///
+    /// let client = Client::debug(rocket::build().attach(Integrity::new())).unwrap();
+    /// let integrity = client.rocket().fairing::<Integrity>().unwrap();
+    /// assert!(integrity.manifest().is_some());
+    /// ```
+    pub fn manifest(&self) -> Option<&Manifest> {
+        self.manifest.try_get()
+    }
+}
+
+impl Default for Integrity {
+    fn default() -> Self {
+        Integrity::new()
+    }
+}
+
+impl Manifest {
+    /// Returns the Subresource Integrity value of the asset served at `uri`,
+    /// if it's in the manifest.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::integrity::Manifest;
+    ///
+    /// fn script(manifest: &Manifest) -> Option<String> {
+    ///     let integrity = manifest.asset("/static/app.js")?;
+    ///     Some(format!(r#"<script src="/static/app.js" integrity="{integrity}"></script>"#))
+    /// }
+    /// ```
+    pub fn asset(&self, uri: &str) -> Option<&str> {
+        self.assets.get(uri).map(|s| s.as_str())
+    }
+
+    /// Returns the digest of the template at `path`, relative to the template
+    /// directory, if it's in the manifest.
+    pub fn template(&self, path: &str) -> Option<&str> {
+        self.templates.get(path).map(|s| s.as_str())
+    }
+
+    /// Returns the URIs of the assets and their digests, ordered by URI.
+    pub fn assets(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.assets.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    /// Returns the paths of the templates and their digests, ordered by path.
+    pub fn templates(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.templates.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    /// Returns a digest of all of the entries in the manifest, in the same
+    /// format as the digests of its entries. Manifests with the same entries
+    /// have the same fingerprint.
+    pub fn fingerprint(&self) -> &str {
+        &self.fingerprint
+    }
+
+    fn new(assets: BTreeMap<String, String>, templates: BTreeMap<String, String>) -> Self {
+        let mut context = Context::new(&SHA384);
+        for (kind, entries) in [("asset", &assets), ("template", &templates)] {
+            for (name, digest) in entries {
+                context.update(format!("{kind} {name} {digest}\n").as_bytes());
+            }
+        }
+
+        let fingerprint = sri(context);
+        Manifest { assets, templates, fingerprint }
+    }
+
+    fn to_json(&self) -> String {
+        fn entries(out: &mut String, entries: &BTreeMap<String, String>) {
+            out.push('{');
+            for (i, (name, digest)) in entries.iter().enumerate() {
+                if i != 0 { out.push(','); }
+                let _ = write!(out, "\"{}\":\"{}\"", EscapeJson(name), digest);
+            }
+
+            out.push('}');
+        }
+
+        let mut out = format!("{{\"fingerprint\":\"{}\",\"assets\":", self.fingerprint);
+        entries(&mut out, &self.assets);
+        out.push_str(",\"templates\":");
+        entries(&mut out, &self.templates);
+        out.push('}');
+        out
+    }
+}
+
+/// Returns the Subresource Integrity value of the digest in `context`.
+fn sri(context: Context) -> String {
+    let digest = context.finish();
+    let mut buf = vec![0; (digest.as_ref().len() + 2) / 3 * 4];
+    let encoded = binascii::b64encode(digest.as_ref(), &mut buf).expect("sufficient buffer");
+    format!("sha384-{}", String::from_utf8_lossy(encoded))
+}
+
+/// Hashes the visible files in `dir`, recursively, recording each in `entries`
+/// under its `/`-separated path relative to `dir` prefixed with `prefix`.
+fn hash_dir(dir: &Path, prefix: &str, entries: &mut BTreeMap<String, String>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if name.starts_with('.') {
+            continue;
+        }
+
+        let path = entry.path();
+        let key = format!("{prefix}/{name}");
+        if fs::metadata(&path)?.is_dir() {
+            hash_dir(&path, &key, entries)?;
+        } else {
+            let mut context = Context::new(&SHA384);
+            context.update(&fs::read(&path)?);
+            entries.insert(key, sri(context));
+        }
+    }
+
+    Ok(())
+}
+
+#[crate::async_trait]
+impl Fairing for Integrity {
+    fn info(&self) -> Info {
+        Info {
+            name: "Integrity",
+            kind: Kind::Ignite | Kind::Liftoff | Kind::Singleton,
+        }
+    }
+
+    async fn on_ignite(&self, rocket: Rocket<Build>) -> fairing::Result {
+        let templates = match &self.templates {
+            Some(dir) => Some(dir.clone()),
+            None => match rocket.figment().extract_inner::<RelativePathBuf>("template_dir") {
+                Ok(dir) => Some(dir.relative()),
+                Err(e) if e.missing() => None,
+                Err(e) => {
+                    e.trace_error();
+                    return Err(rocket);
+                }
+            },
+        };
+
+        let assets = self.assets.clone();
+        let hashed = tokio::task::spawn_blocking(move || {
+            let mut asset_entries = BTreeMap::new();
+            for (prefix, dir) in &assets {
+                hash_dir(dir, prefix, &mut asset_entries).map_err(|e| (dir.clone(), e))?;
+            }
+
+            let mut template_entries = BTreeMap::new();
+            if let Some(dir) = &templates {
+                hash_dir(dir, "", &mut template_entries).map_err(|e| (dir.clone(), e))?;
+                template_entries = template_entries.into_iter()
+                    .map(|(k, v)| (k.trim_start_matches('/').to_string(), v))
+                    .collect();
+            }
+
+            Ok::<_, (PathBuf, io::Error)>(Manifest::new(asset_entries, template_entries))
+        }).await;
+
+        let manifest = match hashed {
+            Ok(Ok(manifest)) => manifest,
+            Ok(Err((dir, e))) => {
+                error!(name: "integrity", dir = %dir.display(), "failed to hash directory: {e}");
+                return Err(rocket);
+            }
+            Err(e) => {
+                error!(name: "integrity", "integrity manifest task failed: {e}");
+                return Err(rocket);
+            }
+        };
+
+        self.manifest.set(manifest);
+        if !self.endpoint {
+            return Ok(rocket);
+        }
+
+        Ok(rocket.mount(Self::PATH, vec![Route::new(Method::Get, "/", self.clone())]))
+    }
+
+    async fn on_liftoff(&self, _: &Rocket<Orbit>) {
+        let Some(manifest) = self.manifest() else { return };
+        info!(name: "integrity",
+            assets = manifest.assets.len(),
+            templates = manifest.templates.len(),
+            fingerprint = manifest.fingerprint(),
+            endpoint = self.endpoint.then_some(Self::PATH),
+            "integrity manifest computed");
+    }
+}
+
+#[crate::async_trait]
+impl Handler for Integrity {
+    async fn handle<'r>(&self, req: &'r Request<'_>, _: Data<'r>) -> Outcome<'r> {
+        let body = self.manifest().map(|m| m.to_json()).unwrap_or_else(|| "null".into());
+        Outcome::from(req, (ContentType::JSON, body))
+    }
+}
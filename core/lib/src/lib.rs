@@ -66,6 +66,46 @@
 //! | `json`          | No       | Support for [JSON (de)serialization].                   |
 //! | `msgpack`       | No       | Support for [MessagePack (de)serialization].            |
 //! | `uuid`          | No       | Support for [UUID value parsing and (de)serialization]. |
+//! | `user-agent`    | No       | Support for [`User-Agent` parsing].                     |
+//! | `signing`       | No       | Support for [signing response bodies].                  |
+//! | `integrity`     | No       | Support for [integrity manifests] of assets.            |
+//! | `markdown`      | No       | Support for [rendering Markdown] responses.             |
+//! | `barcode`       | No       | Support for [QR code and barcode] responses.            |
+//! | `totp`          | No       | Support for [TOTP two-factor authentication].           |
+//! | `passkey`       | No       | Support for [WebAuthn passkey authentication].          |
+//! | `saml`          | No       | Support for [SAML single sign-on].                      |
+//! | `confirm`       | No       | Support for [confirming dangerous actions].             |
+//! | `edge`          | No       | Support for serving requests from [edge runtimes].      |
+//! | `ws`            | No       | Support for [WebSockets].                               |
+//! | `compression`   | No       | Support for [compressing responses].                    |
+//! | `proxy`         | No       | Support for [reverse proxying] to upstream backends.    |
+//! | `quota`         | No       | Support for daily and monthly usage [quotas].           |
+//! | `limiter`       | No       | Support for [rate limiting] requests.                   |
+//! | `authz`         | No       | Support for [cached authorization] decisions.           |
+//! | `paywall`       | No       | Support for [paid features] and usage metering.         |
+//! | `handshake`     | No       | Support for [handshake token authentication].           |
+//! | `cache`         | No       | Support for [caching route responses].                  |
+//! | `budget`        | No       | Support for per-request [time and allocation budgets].  |
+//! | `shed`          | No       | Support for adaptive [load shedding].                   |
+//! | `slo`           | No       | Support for [service level objectives].                 |
+//! | `tunnel`        | No       | Support for [`CONNECT` tunneling].                      |
+//! | `shadow`        | No       | Support for [shadowing traffic] to an upstream.         |
+//! | `diff`          | No       | Support for [diffing responses] with candidate routes.  |
+//! | `discovery`     | No       | Support for [service discovery] registration.           |
+//! | `versioning`    | No       | Support for [API versions], deprecation, and sunset.    |
+//! | `openapi`       | No       | Support for serving [OpenAPI documents].                |
+//! | `consumer`      | No       | Support for [background consumers] of event streams.    |
+//! | `retention`     | No       | Support for [retention] of temporary artifacts.         |
+//! | `handoff`       | No       | Support for zero-downtime [restart handoffs] on Unix.   |
+//! | `cors`          | No       | Support for [Cross-Origin Resource Sharing].            |
+//! | `pack`          | No       | Support for mountable [route packs].                    |
+//! | `envelope`      | No       | Support for JSON [response envelopes].                  |
+//! | `transform`     | No       | Support for declarative [API transformations].          |
+//! | `rpc`           | No       | Support for [JSON-RPC and MessagePack-RPC] endpoints.   |
+//! | `inspector`     | No       | Support for a development [request inspector].          |
+//! | `dev`           | No       | Support for [development-time utilities].               |
+//! | `crash-report`  | No       | Support for writing [crash reports] on failure.         |
+//! | `zip`           | No       | Support for [streaming ZIP archives].                   |
 //! | `tokio-macros`  | No       | Enables the `macros` feature in the exported `tokio`    |
 //! | `http3-preview` | No       | Experimental preview support for [HTTP/3].              |
 //!
@@ -87,6 +127,46 @@
 //! [JSON (de)serialization]: crate::serde::json
 //! [MessagePack (de)serialization]: crate::serde::msgpack
 //! [UUID value parsing and (de)serialization]: crate::serde::uuid
+//! [`User-Agent` parsing]: crate::request::UserAgent
+//! [signing response bodies]: crate::signing
+//! [integrity manifests]: crate::integrity
+//! [rendering Markdown]: crate::response::markdown
+//! [QR code and barcode]: crate::response::barcode
+//! [TOTP two-factor authentication]: crate::auth::totp
+//! [WebAuthn passkey authentication]: crate::auth::passkey
+//! [SAML single sign-on]: crate::auth::saml
+//! [confirming dangerous actions]: crate::auth::confirm
+//! [edge runtimes]: crate::edge
+//! [WebSockets]: crate::ws
+//! [compressing responses]: crate::compression
+//! [reverse proxying]: crate::proxy
+//! [quotas]: crate::quota
+//! [rate limiting]: crate::limiter
+//! [cached authorization]: crate::authz
+//! [paid features]: crate::paywall
+//! [handshake token authentication]: crate::handshake
+//! [caching route responses]: crate::cache
+//! [time and allocation budgets]: crate::budget
+//! [load shedding]: crate::shed
+//! [service level objectives]: crate::slo
+//! [`CONNECT` tunneling]: crate::tunnel
+//! [shadowing traffic]: crate::shadow
+//! [diffing responses]: crate::diff
+//! [service discovery]: crate::discovery
+//! [API versions]: crate::versioning
+//! [OpenAPI documents]: crate::openapi
+//! [background consumers]: crate::consumer
+//! [retention]: crate::retention
+//! [restart handoffs]: crate::handoff
+//! [Cross-Origin Resource Sharing]: crate::cors
+//! [route packs]: crate::pack
+//! [response envelopes]: crate::envelope
+//! [API transformations]: crate::transform
+//! [JSON-RPC and MessagePack-RPC]: crate::rpc
+//! [request inspector]: crate::inspector
+//! [development-time utilities]: crate::dev
+//! [crash reports]: crate::Rocket::launch()
+//! [streaming ZIP archives]: crate::response::stream::ZipStream
 //! [private cookies]: https://rocket.rs/master/guide/requests/#private-cookies
 //! [TLS]: https://rocket.rs/master/guide/configuration/#tls
 //! [mutual TLS]: crate::mtls
@@ -133,6 +213,8 @@ pub mod outcome;
 pub mod data;
 #[doc(hidden)]
 pub mod sentinel;
+pub mod events;
+pub mod supervisor;
 pub mod local;
 pub mod request;
 pub mod response;
@@ -144,6 +226,84 @@ pub mod catcher;
 pub mod route;
 pub mod serde;
 pub mod shield;
+#[cfg(feature = "quota")]
+#[cfg_attr(nightly, doc(cfg(feature = "quota")))]
+pub mod quota;
+#[cfg(feature = "limiter")]
+#[cfg_attr(nightly, doc(cfg(feature = "limiter")))]
+pub mod limiter;
+#[cfg(feature = "authz")]
+#[cfg_attr(nightly, doc(cfg(feature = "authz")))]
+pub mod authz;
+#[cfg(feature = "paywall")]
+#[cfg_attr(nightly, doc(cfg(feature = "paywall")))]
+pub mod paywall;
+#[cfg(any(feature = "totp", feature = "passkey", feature = "saml", feature = "confirm"))]
+#[cfg_attr(nightly, doc(cfg(any(
+    feature = "totp", feature = "passkey", feature = "saml", feature = "confirm"
+))))]
+pub mod auth;
+#[cfg(feature = "handshake")]
+#[cfg_attr(nightly, doc(cfg(feature = "handshake")))]
+pub mod handshake;
+#[cfg(feature = "cache")]
+#[cfg_attr(nightly, doc(cfg(feature = "cache")))]
+pub mod cache;
+#[cfg(feature = "budget")]
+#[cfg_attr(nightly, doc(cfg(feature = "budget")))]
+pub mod budget;
+#[cfg(feature = "shed")]
+#[cfg_attr(nightly, doc(cfg(feature = "shed")))]
+pub mod shed;
+#[cfg(feature = "slo")]
+#[cfg_attr(nightly, doc(cfg(feature = "slo")))]
+pub mod slo;
+#[cfg(feature = "tunnel")]
+#[cfg_attr(nightly, doc(cfg(feature = "tunnel")))]
+pub mod tunnel;
+#[cfg(feature = "shadow")]
+#[cfg_attr(nightly, doc(cfg(feature = "shadow")))]
+pub mod shadow;
+#[cfg(feature = "diff")]
+#[cfg_attr(nightly, doc(cfg(feature = "diff")))]
+pub mod diff;
+#[cfg(feature = "discovery")]
+#[cfg_attr(nightly, doc(cfg(feature = "discovery")))]
+pub mod discovery;
+#[cfg(feature = "versioning")]
+#[cfg_attr(nightly, doc(cfg(feature = "versioning")))]
+pub mod versioning;
+pub mod openapi;
+#[cfg(feature = "consumer")]
+#[cfg_attr(nightly, doc(cfg(feature = "consumer")))]
+pub mod consumer;
+#[cfg(feature = "retention")]
+#[cfg_attr(nightly, doc(cfg(feature = "retention")))]
+pub mod retention;
+#[cfg(all(unix, feature = "handoff"))]
+#[cfg_attr(nightly, doc(cfg(all(unix, feature = "handoff"))))]
+pub mod handoff;
+#[cfg(feature = "cors")]
+#[cfg_attr(nightly, doc(cfg(feature = "cors")))]
+pub mod cors;
+#[cfg(feature = "pack")]
+#[cfg_attr(nightly, doc(cfg(feature = "pack")))]
+pub mod pack;
+#[cfg(feature = "envelope")]
+#[cfg_attr(nightly, doc(cfg(feature = "envelope")))]
+pub mod envelope;
+#[cfg(feature = "transform")]
+#[cfg_attr(nightly, doc(cfg(feature = "transform")))]
+pub mod transform;
+#[cfg(feature = "rpc")]
+#[cfg_attr(nightly, doc(cfg(feature = "rpc")))]
+pub mod rpc;
+#[cfg(feature = "inspector")]
+#[cfg_attr(nightly, doc(cfg(feature = "inspector")))]
+pub mod inspector;
+#[cfg(feature = "dev")]
+#[cfg_attr(nightly, doc(cfg(feature = "dev")))]
+pub mod dev;
 pub mod fs;
 pub mod http;
 pub mod listener;
@@ -154,6 +314,24 @@ pub mod tls;
 #[cfg(feature = "mtls")]
 #[cfg_attr(nightly, doc(cfg(feature = "mtls")))]
 pub mod mtls;
+#[cfg(feature = "signing")]
+#[cfg_attr(nightly, doc(cfg(feature = "signing")))]
+pub mod signing;
+#[cfg(feature = "integrity")]
+#[cfg_attr(nightly, doc(cfg(feature = "integrity")))]
+pub mod integrity;
+#[cfg(feature = "edge")]
+#[cfg_attr(nightly, doc(cfg(feature = "edge")))]
+pub mod edge;
+#[cfg(feature = "ws")]
+#[cfg_attr(nightly, doc(cfg(feature = "ws")))]
+pub mod ws;
+#[cfg(feature = "compression")]
+#[cfg_attr(nightly, doc(cfg(feature = "compression")))]
+pub mod compression;
+#[cfg(feature = "proxy")]
+#[cfg_attr(nightly, doc(cfg(feature = "proxy")))]
+pub mod proxy;
 
 #[path = "rocket.rs"]
 mod rkt;
@@ -164,6 +342,9 @@ mod state;
 mod router;
 mod phase;
 mod erased;
+#[cfg(feature = "crash-report")]
+mod crash;
+mod mime;
 
 #[doc(inline)] pub use rocket_codegen::*;
 
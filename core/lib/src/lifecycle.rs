@@ -1,3 +1,5 @@
+use std::any::Any;
+
 use futures::future::{FutureExt, Future};
 
 use crate::trace::Trace;
@@ -6,12 +8,14 @@ use crate::data::IoHandler;
 use crate::http::{Method, Status, Header};
 use crate::outcome::Outcome;
 use crate::form::Form;
+use crate::request::{Diagnostic, Usage, Stage, Pipeline};
+use crate::shutdown::InFlight;
 use crate::{route, catcher, Rocket, Orbit, Request, Response, Data};
 
 // A token returned to force the execution of one method before another.
 pub(crate) struct RequestToken;
 
-async fn catch_handle<Fut, T, F>(name: Option<&str>, run: F) -> Option<T>
+async fn catch_handle<Fut, T, F>(name: Option<&str>, run: F) -> Result<T, Box<dyn Any + Send>>
     where F: FnOnce() -> Fut, Fut: Future<Output = T>,
 {
     macro_rules! panic_info {
@@ -33,20 +37,20 @@ async fn catch_handle<Fut, T, F>(name: Option<&str>, run: F) -> Option<T>
 
     let run = std::panic::AssertUnwindSafe(run);
     let fut = std::panic::catch_unwind(run)
-        .map_err(|e| panic_info!(name, e))
-        .ok()?;
+        .map_err(|e| panic_info!(name, e))?;
 
     std::panic::AssertUnwindSafe(fut)
         .catch_unwind()
         .await
         .map_err(|e| panic_info!(name, e))
-        .ok()
 }
 
 impl Rocket<Orbit> {
     /// Preprocess the request for Rocket things. Currently, this means:
     ///
+    ///   * Metering the request's size for [`Usage`], if enabled.
     ///   * Rewriting the method in the request if _method form field exists.
+    ///   * Tracking the request as in flight for the shutdown drain report.
     ///   * Run the request fairings.
     ///
     /// This is the only place during lifecycle processing that `Request` is
@@ -56,6 +60,11 @@ impl Rocket<Orbit> {
         req: &mut Request<'_>,
         data: &mut Data<'_>
     ) -> RequestToken {
+        // Count the request head and the bytes of the body as they're read.
+        if self.config.metering {
+            Usage::meter_request(req, data);
+        }
+
         // Check if this is a form and if the form contains the special _method
         // field which we use to reinterpret the request's method.
         if req.method() == Method::Post && req.content_type().map_or(false, |v| v.is_form()) {
@@ -70,6 +79,9 @@ impl Rocket<Orbit> {
             }
         }
 
+        // Track the request until it's dropped for the shutdown drain report.
+        self.in_flight.track(req);
+
         // Run request fairings.
         self.fairings.handle_request(req, data).await;
 
@@ -98,15 +110,31 @@ impl Rocket<Orbit> {
         // Remember if the request is `HEAD` for later body stripping.
         let was_head_request = request.method() == Method::Head;
 
-        // Route the request and run the user's handlers.
-        let mut response = match self.route(request, data).await {
+        // Records the template of the route that handled the request, if any.
+        let record_route = |outcome: &route::Outcome<'_>| {
+            if let (false, Some(template)) = (outcome.is_forward(), request.route_template()) {
+                tracing::Span::current().record("route", template);
+            }
+        };
+
+        // Route the request and run the user's handlers unless a dispatch
+        // fairing answers the request first.
+        let outcome = match self.fairings.handle_dispatch(request).await {
+            Some(Ok(response)) => Outcome::Success(response),
+            Some(Err(status)) => Outcome::Error(status),
+            None => self.route(request, data).await,
+        };
+        record_route(&outcome);
+        let mut response = match outcome {
             Outcome::Success(response) => response,
             Outcome::Forward((data, _)) if request.method() == Method::Head => {
                 tracing::Span::current().record("autohandled", true);
 
                 // Dispatch the request again with Method `GET`.
                 request._set_method(Method::Get);
-                match self.route(request, data).await {
+                let outcome = self.route(request, data).await;
+                record_route(&outcome);
+                match outcome {
                     Outcome::Success(response) => response,
                     Outcome::Error(status) => self.dispatch_error(status, request).await,
                     Outcome::Forward((_, status)) => self.dispatch_error(status, request).await,
@@ -131,7 +159,15 @@ impl Rocket<Orbit> {
             }
         }
 
-        // Run the response fairings.
+        // In debug, summarize why guards forwarded or failed, if any did.
+        if let Some(header) = Diagnostic::header(request) {
+            response.set_header(header);
+        }
+
+        // Run the response fairings. A `Content-Length` set by the responder
+        // becomes the body's declared size so that it can't go stale if a
+        // fairing replaces the body.
+        response.adopt_content_length();
         self.fairings.handle_response(request, &mut response).await;
 
         // Strip the body if this is a `HEAD` request.
@@ -139,14 +175,17 @@ impl Rocket<Orbit> {
             response.strip_body();
         }
 
-        if let Some(size) = response.body_mut().size().await {
-            response.set_raw_header("Content-Length", size.to_string());
-        }
+        response.set_framing_headers().await;
 
         if let Some(alt_svc) = request.rocket().alt_svc() {
             response.set_raw_header("Alt-Svc", alt_svc);
         }
 
+        // Meter the response, completing `Usage` once its body is dropped.
+        if self.config.metering {
+            Usage::meter_response(request, &mut response);
+        }
+
         // TODO: Should upgrades be handled here? We miss them on local clients.
         response
     }
@@ -156,6 +195,15 @@ impl Rocket<Orbit> {
         response: &mut Response<'r>,
         // io_stream: impl Future<Output = io::Result<IoStream>> + Send,
     ) -> Option<(String, Box<dyn IoHandler + 'r>)> {
+        // A successful response to `CONNECT` tunnels without switching protocols.
+        if request.method() == Method::Connect {
+            if !response.status().class().is_success() {
+                return None;
+            }
+
+            return response.take_upgrade().map(|(proto, handler)| (proto.to_string(), handler));
+        }
+
         let upgrades = request.headers().get("upgrade");
         let Ok(upgrade) = response.search_upgrades(upgrades) else {
             info!(
@@ -189,7 +237,7 @@ impl Rocket<Orbit> {
         uri = %request.uri(),
         format = request.format().map(display),
     ))]
-    async fn route<'s, 'r: 's>(
+    pub(crate) async fn route<'s, 'r: 's>(
         &'s self,
         request: &'r Request<'s>,
         mut data: Data<'r>,
@@ -197,14 +245,31 @@ impl Rocket<Orbit> {
         // Go through all matching routes until we fail or succeed or run out of
         // routes to try, in which case we forward with the last status.
         let mut status = Status::NotFound;
-        for route in self.router.route(request) {
+        for route in request.router().route(request) {
             // Retrieve and set the requests parameters.
             route.trace_info();
             request.set_route(route);
+            InFlight::routed(request, route);
+
+            if route.verify_content_type {
+                if let Err(status) = crate::data::sniff::verify_data(request, &mut data).await {
+                    return Outcome::Error(status);
+                }
+            }
 
             let name = route.name.as_deref();
-            let outcome = catch_handle(name, || route.handler.handle(request, data)).await
-                .unwrap_or(Outcome::Error(Status::InternalServerError));
+            let stage_name = || format!("{} {}", route.method, route.uri);
+            let stage = Pipeline::open(request, "route", stage_name);
+            let handle = catch_handle(name, || route.handler.handle(request, data));
+            #[cfg(feature = "budget")]
+            let handle = crate::budget::meter(request, handle);
+            let outcome = handle.await
+                .unwrap_or_else(|panic| {
+                    catcher::record_panic(request, &*panic);
+                    Outcome::Error(Status::InternalServerError)
+                });
+
+            Pipeline::close(request, stage);
 
             // Check if the request processing completed (Some) or if the
             // request needs to be forwarded. If it does, continue the loop
@@ -267,15 +332,24 @@ impl Rocket<Orbit> {
         status: Status,
         req: &'r Request<'s>
     ) -> Result<Response<'r>, Option<Status>> {
-        if let Some(catcher) = self.router.catch(status, req) {
+        let timer = Stage::start(req);
+        if let Some(catcher) = req.router().catch(status, req) {
             catcher.trace_info();
-            catch_handle(catcher.name.as_deref(), || catcher.handler.handle(status, req)).await
+            let name = catcher.name.as_deref();
+            let result = catch_handle(name, || catcher.handler.handle(status, req)).await
+                .ok()
                 .map(|result| result.map_err(Some))
-                .unwrap_or_else(|| Err(None))
+                .unwrap_or_else(|| Err(None));
+
+            let name = catcher.name.clone().unwrap_or_else(|| status.code.to_string().into());
+            Stage::record(req, timer, "catcher", name);
+            result
         } else {
             info!(name: "catcher", name = "rocket::default", "uri.base" = "/", code = status.code,
                 "no registered catcher: using Rocket default");
-            Ok(catcher::default_handler(status, req))
+            let response = catcher::default_handler(status, req);
+            Stage::record(req, timer, "catcher", "rocket::default");
+            Ok(response)
         }
     }
 }
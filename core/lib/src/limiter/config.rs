@@ -0,0 +1,200 @@
+use std::time::{Duration, Instant};
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+/// The rate limit configuration: the `rate_limit` configuration parameter.
+///
+/// The `global` rate applies to every request; all other rates are _named_
+/// and apply to requests to routes with a [`RateLimited`] guard for the name.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::limiter::{RateConfig, Algorithm, Key};
+/// use rocket::figment::{Figment, providers::{Format, Toml}};
+///
+/// let figment = Figment::from(Toml::string(r#"
+///     [rate_limit]
+///     global = { requests = 100, period = 60 }
+///     login = { requests = 5, period = 300, algorithm = "sliding-window" }
+///     api = { requests = 1000, period = 3600, key = { header = "X-Api-Key" } }
+/// "#));
+///
+/// let config: RateConfig = figment.extract_inner("rate_limit").unwrap();
+/// let global = config.global.unwrap();
+/// assert_eq!(global.requests, 100);
+/// assert_eq!(global.algorithm, Algorithm::TokenBucket);
+/// assert_eq!(global.key, Key::Ip);
+///
+/// assert_eq!(config.named["login"].algorithm, Algorithm::SlidingWindow);
+/// assert_eq!(config.named["api"].key, Key::Header("X-Api-Key".into()));
+/// ```
+///
+/// [`RateLimited`]: crate::limiter::RateLimited
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RateConfig {
+    /// The rate applied to every request, if any.
+    ///
+    /// **default: `None`**
+    pub global: Option<Rate>,
+    /// The named rates, applied by [`RateLimited`] guards.
+    ///
+    /// **default: `{}`**
+    ///
+    /// [`RateLimited`]: crate::limiter::RateLimited
+    #[serde(flatten)]
+    pub named: IndexMap<String, Rate>,
+}
+
+/// A rate limit: the number of requests allowed per period and how they are
+/// counted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rate {
+    /// The number of requests allowed per `period`.
+    pub requests: u64,
+    /// The period, in seconds.
+    ///
+    /// **default: `1`**
+    #[serde(default = "Rate::default_period")]
+    pub period: u64,
+    /// The number of requests a [token bucket](Algorithm::TokenBucket) allows
+    /// in a burst. Ignored by other algorithms.
+    ///
+    /// **default: `requests`**
+    #[serde(default)]
+    pub burst: Option<u64>,
+    /// How requests are counted.
+    ///
+    /// **default: [`Algorithm::TokenBucket`]**
+    #[serde(default)]
+    pub algorithm: Algorithm,
+    /// What requests are counted by.
+    ///
+    /// **default: [`Key::Ip`]**
+    #[serde(default)]
+    pub key: Key,
+}
+
+/// How a [`Rate`] counts requests.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Algorithm {
+    /// A bucket of `burst` tokens, refilled at `requests` tokens per `period`.
+    /// Each request takes a token. Allows bursts after idle periods.
+    #[default]
+    TokenBucket,
+    /// A window of `period` seconds sliding over the request history. At most
+    /// `requests` are allowed in any window, as estimated by weighting the
+    /// count of the previous fixed window by its overlap with the sliding one.
+    SlidingWindow,
+}
+
+/// What a [`Rate`] counts requests by.
+///
+/// Requests without a key, such as those missing the header, aren't limited.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Key {
+    /// The client's IP address, as reported by
+    /// [`Request::client_ip()`](crate::Request::client_ip()): `"ip"`.
+    #[default]
+    Ip,
+    /// The value of the named header: `{ header = "X-Api-Key" }`.
+    Header(String),
+    /// The key returned by the extractor registered under the name with
+    /// [`Limiter::extractor()`]: `{ extractor = "tenant" }`.
+    ///
+    /// [`Limiter::extractor()`]: crate::limiter::Limiter::extractor()
+    Extractor(String),
+}
+
+/// The state of a [`Rate`] for one key.
+#[derive(Debug, Copy, Clone)]
+pub(crate) enum State {
+    Bucket { tokens: f64, refilled: Instant },
+    Window { start: Instant, previous: u64, current: u64 },
+}
+
+impl Rate {
+    fn default_period() -> u64 {
+        1
+    }
+
+    /// Returns a description of why `self` is invalid, if it is.
+    pub(crate) fn validate(&self) -> Result<(), &'static str> {
+        match self {
+            Rate { requests: 0, .. } => Err("`requests` must be positive"),
+            Rate { period: 0, .. } => Err("`period` must be positive"),
+            Rate { burst: Some(0), .. } => Err("`burst` must be positive"),
+            _ => Ok(()),
+        }
+    }
+
+    /// The time after which an untouched `State` is the same as a new one.
+    pub(crate) fn idle(&self) -> Duration {
+        Duration::from_secs(self.period.saturating_mul(2))
+    }
+
+    /// Counts a request at `now` in `state`, a new state if `None`. Returns the
+    /// number of requests that remain if the request is allowed and, if it
+    /// isn't, how long until a request would be.
+    pub(crate) fn take(&self, state: &mut Option<State>, now: Instant) -> Result<u64, Duration> {
+        let period = self.period as f64;
+        let limit = self.requests as f64;
+        match self.algorithm {
+            Algorithm::TokenBucket => {
+                let capacity = self.burst.unwrap_or(self.requests) as f64;
+                let per_sec = limit / period;
+                let tokens = match *state {
+                    Some(State::Bucket { tokens, refilled }) => {
+                        let refill = now.saturating_duration_since(refilled).as_secs_f64();
+                        (tokens + refill * per_sec).min(capacity)
+                    }
+                    _ => capacity,
+                };
+
+                if tokens >= 1.0 {
+                    *state = Some(State::Bucket { tokens: tokens - 1.0, refilled: now });
+                    Ok((tokens - 1.0) as u64)
+                } else {
+                    *state = Some(State::Bucket { tokens, refilled: now });
+                    Err(Duration::from_secs_f64((1.0 - tokens) / per_sec))
+                }
+            }
+            Algorithm::SlidingWindow => {
+                let (mut start, mut previous, mut current) = match *state {
+                    Some(State::Window { start, previous, current }) => (start, previous, current),
+                    _ => (now, 0, 0),
+                };
+
+                let windows = now.saturating_duration_since(start).as_secs() / self.period;
+                if windows > 0 {
+                    previous = if windows == 1 { current } else { 0 };
+                    current = 0;
+                    start += Duration::from_secs(windows * self.period);
+                }
+
+                // How far into the current window `now` is, in `[0, 1)`.
+                let elapsed = now.saturating_duration_since(start).as_secs_f64() / period;
+                let estimate = previous as f64 * (1.0 - elapsed) + current as f64;
+                if estimate + 1.0 <= limit {
+                    *state = Some(State::Window { start, previous, current: current + 1 });
+                    return Ok((limit - estimate - 1.0) as u64);
+                }
+
+                *state = Some(State::Window { start, previous, current });
+                let wait = if current as f64 + 1.0 <= limit {
+                    // The previous window's weight must fall for one to fit.
+                    1.0 - (limit - current as f64 - 1.0) / previous as f64 - elapsed
+                } else {
+                    // The current window becomes the previous one first.
+                    1.0 - elapsed + (1.0 - (limit - 1.0) / current as f64)
+                };
+
+                Err(Duration::from_secs_f64(wait.max(0.0) * period))
+            }
+        }
+    }
+}
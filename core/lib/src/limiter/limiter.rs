@@ -0,0 +1,405 @@
+use std::time::{Duration, Instant};
+use std::marker::PhantomData;
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+use std::collections::hash_map::RandomState;
+
+use indexmap::IndexMap;
+use parking_lot::Mutex;
+use state::InitCell;
+
+use crate::{Rocket, Request, Response, Data, Build};
+use crate::response;
+use crate::fairing::{self, Fairing, Info, Kind};
+use crate::http::{Header, Status};
+use crate::request::{FromRequest, Outcome};
+use crate::trace::Trace;
+use crate::limiter::{RateConfig, Rate, Key};
+use crate::limiter::config::State;
+
+/// The number of independently locked shards keys are spread across.
+const SHARDS: usize = 16;
+
+/// The maximum number of keys tracked by a shard.
+const SHARD_CAPACITY: usize = 1024;
+
+/// The number of keys examined for expiry, or eviction, per counted request.
+const SWEEP: usize = 4;
+
+/// The name of the global rate.
+const GLOBAL: &str = "global";
+
+/// A key extractor registered with [`Limiter::extractor()`].
+type Extractor = Box<dyn Fn(&Request<'_>) -> Option<String> + Send + Sync>;
+
+/// A [`Fairing`] that enforces [rate limits](crate::limiter).
+///
+/// Rates are read from the `rate_limit` configuration parameter at ignition.
+/// If the parameter is invalid, including if a rate is keyed by an extractor
+/// that isn't registered, ignition fails. See the [module
+/// documentation](crate::limiter) for details on how rates are enforced.
+///
+/// # Example
+///
+/// Limit requests by the tenant named in a header, or by IP address for
+/// requests without one:
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::limiter::Limiter;
+///
+/// #[launch]
+/// fn rocket() -> _ {
+///     let limiter = Limiter::new().extractor("tenant", |req| {
+///         req.headers().get_one("X-Tenant")
+///             .map(|tenant| tenant.to_string())
+///             .or_else(|| req.client_ip().map(|ip| ip.to_string()))
+///     });
+///
+///     rocket::build().attach(limiter)
+/// }
+/// ```
+pub struct Limiter {
+    extractors: HashMap<String, Extractor>,
+    config: InitCell<RateConfig>,
+    hasher: RandomState,
+    shards: [Mutex<Shard>; SHARDS],
+}
+
+/// The states of the keys hashed to one shard, by `(rate name, key)`, with the
+/// time each was last touched.
+///
+/// A shard holds at most `SHARD_CAPACITY` keys. Rather than scanning every key
+/// when full, each request examines `SWEEP` keys at a rotating cursor, forgets
+/// those that have gone idle and, if a new key doesn't fit, evicts the least
+/// recently touched of them. The work per request is thus constant.
+#[derive(Default)]
+struct Shard {
+    states: IndexMap<(&'static str, String), (Instant, Option<State>)>,
+    cursor: usize,
+}
+
+/// A named rate, as applied by a [`RateLimited`] guard.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::limiter::Limit;
+///
+/// /// Login attempts, limited by `rate_limit.login`.
+/// struct Login;
+///
+/// impl Limit for Login {
+///     const NAME: &'static str = "login";
+/// }
+/// ```
+pub trait Limit: Send + Sync + 'static {
+    /// The name of the rate in the `rate_limit` configuration parameter.
+    const NAME: &'static str;
+}
+
+/// A request guard that admits requests within the named rate `L`.
+///
+/// The guard counts the request toward the rate `L::NAME` in the `rate_limit`
+/// configuration parameter. If the rate is exceeded, the guard fails with
+/// `429 Too Many Requests` and an [`Exceeded`] error, and the response carries
+/// a `Retry-After` header. Requests without a key for the rate are admitted
+/// without being counted. If no [`Limiter`] is attached, or the rate isn't
+/// configured, the guard forwards with a status of `500 Internal Server
+/// Error`.
+///
+/// See the [module documentation](crate::limiter) for an example.
+pub struct RateLimited<L: Limit> {
+    remaining: Option<u64>,
+    _limit: PhantomData<L>,
+}
+
+/// The error of a [`RateLimited`] guard whose rate was exceeded.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Exceeded {
+    /// How long until a request would be admitted.
+    pub retry_after: Duration,
+}
+
+/// How long until requests limited by any rate would be admitted, if any rate
+/// was exceeded.
+#[derive(Default)]
+struct Limited(Mutex<Option<Duration>>);
+
+impl Limiter {
+    /// Returns a `Limiter` fairing with no key extractors.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::limiter::Limiter;
+    ///
+    /// let limiter = Limiter::new();
+    /// ```
+    pub fn new() -> Self {
+        Limiter {
+            extractors: HashMap::new(),
+            config: InitCell::new(),
+            hasher: RandomState::new(),
+            shards: std::array::from_fn(|_| Mutex::default()),
+        }
+    }
+
+    /// Registers `f` as the key extractor named `name`, used by rates with a
+    /// key of `{ extractor = "name" }`. Requests for which `f` returns `None`
+    /// aren't limited. Registering a second extractor with the same name
+    /// replaces the first.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::limiter::Limiter;
+    ///
+    /// let limiter = Limiter::new()
+    ///     .extractor("user", |req| req.cookies().get("user").map(|c| c.value().into()));
+    /// ```
+    pub fn extractor<N, F>(mut self, name: N, f: F) -> Self
+        where N: Into<String>, F: Fn(&Request<'_>) -> Option<String> + Send + Sync + 'static
+    {
+        self.extractors.insert(name.into(), Box::new(f));
+        self
+    }
+
+    /// Returns the key of `req` for `rate`, if it has one.
+    fn key(&self, rate: &Rate, req: &Request<'_>) -> Option<String> {
+        match &rate.key {
+            Key::Ip => req.client_ip().map(|ip| ip.to_string()),
+            Key::Header(name) => req.headers().get_one(name).map(|v| v.to_string()),
+            Key::Extractor(name) => self.extractors.get(name).and_then(|f| f(req)),
+        }
+    }
+
+    /// Counts `req` toward the rate `name`. Returns the number of requests that
+    /// remain, if `req` is counted, or how long until one would be admitted.
+    fn take(
+        &self,
+        name: &'static str,
+        rate: &Rate,
+        req: &Request<'_>
+    ) -> Result<Option<u64>, Duration> {
+        let Some(key) = self.key(rate, req) else { return Ok(None) };
+        let key = (name, key);
+        let now = Instant::now();
+        let mut shard = self.shards[self.hasher.hash_one(&key) as usize % SHARDS].lock();
+        shard.sweep(self.config.try_get(), now);
+        rate.take(shard.touch(key, now), now).map(Some)
+    }
+
+    /// Records that the rate for `req` was exceeded for `retry_after`.
+    fn exceeded(req: &Request<'_>, retry_after: Duration) {
+        let mut limited = req.local_cache(Limited::default).0.lock();
+        *limited = Some(limited.map_or(retry_after, |d| d.max(retry_after)));
+    }
+}
+
+impl Shard {
+    /// Forgets those of the next `SWEEP` keys that have been idle long enough
+    /// for their state to be equivalent to a new one.
+    fn sweep(&mut self, config: Option<&RateConfig>, now: Instant) {
+        for _ in 0..SWEEP {
+            if self.states.is_empty() {
+                return;
+            }
+
+            self.cursor %= self.states.len();
+            let ((name, _), (touched, _)) = self.states.get_index(self.cursor).unwrap();
+            let rate = config.and_then(|c| c.rate(name));
+            if rate.map_or(true, |rate| now.duration_since(*touched) >= rate.idle()) {
+                self.states.swap_remove_index(self.cursor);
+            } else {
+                self.cursor += 1;
+            }
+        }
+    }
+
+    /// Returns the state of `key`, touched at `now`. If `key` is new and the
+    /// shard is full, the least recently touched of the next `SWEEP` keys is
+    /// evicted to make room.
+    fn touch(&mut self, key: (&'static str, String), now: Instant) -> &mut Option<State> {
+        let len = self.states.len();
+        if len >= SHARD_CAPACITY && !self.states.contains_key(&key) {
+            let oldest = (self.cursor..self.cursor + SWEEP)
+                .map(|i| i % len)
+                .min_by_key(|&i| self.states[i].0)
+                .unwrap();
+
+            self.states.swap_remove_index(oldest);
+        }
+
+        let (touched, state) = self.states.entry(key).or_insert((now, None));
+        *touched = now;
+        state
+    }
+}
+
+impl RateConfig {
+    /// Returns the rate named `name`, global or not.
+    fn rate(&self, name: &str) -> Option<&Rate> {
+        match name {
+            GLOBAL => self.global.as_ref(),
+            _ => self.named.get(name),
+        }
+    }
+}
+
+impl Default for Limiter {
+    fn default() -> Self {
+        Limiter::new()
+    }
+}
+
+impl<L: Limit> RateLimited<L> {
+    /// The number of requests that remain for the request's key, or `None` if
+    /// the request has no key for the rate and so wasn't counted.
+    pub fn remaining(&self) -> Option<u64> {
+        self.remaining
+    }
+}
+
+#[crate::async_trait]
+impl<'r, L: Limit> FromRequest<'r> for RateLimited<L> {
+    type Error = Exceeded;
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Exceeded> {
+        let Some(limiter) = req.rocket().fairing::<Limiter>() else {
+            error!("`RateLimited` guard used but no `Limiter` is attached");
+            return Outcome::Forward(Status::InternalServerError);
+        };
+
+        let rate = limiter.config.try_get().and_then(|config| config.named.get(L::NAME));
+        let Some(rate) = rate else {
+            error!(rate = L::NAME, "`RateLimited` guard used but its rate isn't configured");
+            return Outcome::Forward(Status::InternalServerError);
+        };
+
+        match limiter.take(L::NAME, rate, req) {
+            Ok(remaining) => Outcome::Success(RateLimited { remaining, _limit: PhantomData }),
+            Err(retry_after) => {
+                Limiter::exceeded(req, retry_after);
+                Outcome::Error((Status::TooManyRequests, Exceeded { retry_after }))
+            }
+        }
+    }
+}
+
+#[crate::async_trait]
+impl Fairing for Limiter {
+    fn info(&self) -> Info {
+        Info {
+            name: "Limiter",
+            kind: Kind::Ignite | Kind::Request | Kind::Dispatch | Kind::Response | Kind::Singleton,
+        }
+    }
+
+    async fn on_ignite(&self, rocket: Rocket<Build>) -> fairing::Result {
+        let config = match rocket.figment().contains("rate_limit") {
+            true => match rocket.figment().extract_inner::<RateConfig>("rate_limit") {
+                Ok(config) => config,
+                Err(e) => {
+                    e.trace_error();
+                    return Err(rocket);
+                }
+            },
+            false => RateConfig::default(),
+        };
+
+        let rates = config.global.iter().map(|rate| (GLOBAL, rate))
+            .chain(config.named.iter().map(|(name, rate)| (name.as_str(), rate)));
+
+        for (name, rate) in rates {
+            if let Err(e) = rate.validate() {
+                error!(name: "limiter", rate = name, "rate limit configuration is invalid: {e}");
+                return Err(rocket);
+            }
+
+            if let Key::Extractor(extractor) = &rate.key {
+                if !self.extractors.contains_key(extractor) {
+                    error!(name: "limiter", rate = name, extractor,
+                        "rate limit key extractor is not registered");
+
+                    return Err(rocket);
+                }
+            }
+        }
+
+        self.config.set(config);
+        Ok(rocket)
+    }
+
+    async fn on_request(&self, req: &mut Request<'_>, _: &mut Data<'_>) {
+        let Some(rate) = self.config.try_get().and_then(|c| c.global.as_ref()) else { return };
+        if let Err(retry_after) = self.take(GLOBAL, rate, req) {
+            Limiter::exceeded(req, retry_after);
+        }
+    }
+
+    async fn on_dispatch<'r>(&self, req: &'r Request<'_>) -> Option<response::Result<'r>> {
+        req.local_cache(Limited::default).0.lock().map(|_| Err(Status::TooManyRequests))
+    }
+
+    async fn on_response<'r>(&self, req: &'r Request<'_>, res: &mut Response<'r>) {
+        let Some(retry_after) = *req.local_cache(Limited::default).0.lock() else { return };
+        if res.status() == Status::TooManyRequests {
+            let secs = retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0);
+            res.set_header(Header::new("Retry-After", secs.max(1).to_string()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::{Shard, GLOBAL, SHARD_CAPACITY, SWEEP};
+    use crate::limiter::{RateConfig, Rate};
+
+    fn config(period: u64) -> RateConfig {
+        let rate = Rate {
+            requests: 1,
+            period,
+            burst: None,
+            algorithm: Default::default(),
+            key: Default::default(),
+        };
+
+        RateConfig { global: Some(rate), named: Default::default() }
+    }
+
+    #[test]
+    fn shard_is_bounded() {
+        let (config, now) = (config(60), Instant::now());
+        let mut shard = Shard::default();
+        for i in 0..SHARD_CAPACITY * 3 {
+            shard.sweep(Some(&config), now);
+            shard.touch((GLOBAL, i.to_string()), now + Duration::from_millis(i as u64));
+            assert!(shard.states.len() <= SHARD_CAPACITY);
+        }
+
+        // The most recently touched key is never the one evicted.
+        let last = (SHARD_CAPACITY * 3 - 1).to_string();
+        assert!(shard.states.contains_key(&(GLOBAL, last)));
+    }
+
+    #[test]
+    fn idle_keys_are_swept() {
+        let (config, now) = (config(1), Instant::now());
+        let mut shard = Shard::default();
+        for i in 0..SWEEP * 4 {
+            shard.touch((GLOBAL, i.to_string()), now);
+        }
+
+        shard.sweep(Some(&config), now + Duration::from_secs(1));
+        assert_eq!(shard.states.len(), SWEEP * 4);
+
+        for _ in 0..4 {
+            shard.sweep(Some(&config), now + Duration::from_secs(2));
+        }
+
+        assert!(shard.states.is_empty());
+    }
+}
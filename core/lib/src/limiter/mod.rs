@@ -0,0 +1,67 @@
+//! Rate limiting by IP address, header, or custom key.
+//!
+//! The [`Limiter`] fairing limits the rate of requests per _key_: by default,
+//! the client's IP address, but alternatively the value of a header or a key
+//! returned by an extractor registered with [`Limiter::extractor()`]. Requests
+//! without a key are not limited.
+//!
+//! A [`Rate`] allows a number of requests per period, counted by one of two
+//! [`Algorithm`]s: a token bucket, which allows bursts after idle periods, or
+//! a sliding window, which doesn't. Counts are kept in memory, per instance,
+//! for at most 16,384 keys. Idle keys are forgotten and, when that isn't
+//! enough, the least recently seen keys are evicted.
+//!
+//! # Configuration
+//!
+//! Rates are configured via the `rate_limit` configuration parameter, which
+//! is deserialized as a [`RateConfig`]. The `global` rate applies to every
+//! request; other rates are named and apply to the routes that use a
+//! [`RateLimited`] guard for the name:
+//!
+//! ```toml
+//! [default.rate_limit]
+//! global = { requests = 100, period = 1, burst = 200 }
+//! login = { requests = 5, period = 300, algorithm = "sliding-window" }
+//! api = { requests = 1000, period = 3600, key = { header = "X-Api-Key" } }
+//! tenant = { requests = 50, period = 1, key = { extractor = "tenant" } }
+//! ```
+//!
+//! # Enforcement
+//!
+//! Before a request is routed, it's counted toward the global rate. If the
+//! rate is exceeded, the request is not routed; instead, the `429 Too Many
+//! Requests` error catcher is invoked. A [`RateLimited`] guard counts the
+//! request toward its named rate and fails with `429 Too Many Requests` if the
+//! rate is exceeded. In either case, a `Retry-After` header indicating when a
+//! request would next be admitted is added to the response.
+//!
+//! # Example
+//!
+//! ```rust
+//! # #[macro_use] extern crate rocket;
+//! use rocket::limiter::{Limiter, Limit, RateLimited};
+//!
+//! struct Login;
+//!
+//! impl Limit for Login {
+//!     const NAME: &'static str = "login";
+//! }
+//!
+//! #[post("/login")]
+//! fn login(_limited: RateLimited<Login>) -> &'static str {
+//!     "welcome"
+//! }
+//!
+//! #[launch]
+//! fn rocket() -> _ {
+//!     rocket::build()
+//!         .mount("/", routes![login])
+//!         .attach(Limiter::new())
+//! }
+//! ```
+
+mod config;
+mod limiter;
+
+pub use config::{RateConfig, Rate, Algorithm, Key};
+pub use limiter::{Limiter, Limit, RateLimited, Exceeded};
@@ -104,11 +104,11 @@ impl Bind for DefaultListener {
         match config.address {
             #[cfg(feature = "tls")]
             Endpoint::Tcp(_) if config.tls.is_some() => {
-                let listener = <TlsListener<TcpListener> as Bind>::bind(rocket).await?;
+                let listener = TlsListener::configured(bind_tcp(rocket).await?, rocket).await?;
                 Ok(Left(Left(listener)))
             }
             Endpoint::Tcp(_) => {
-                let listener = <TcpListener as Bind>::bind(rocket).await?;
+                let listener = bind_tcp(rocket).await?;
                 Ok(Right(Left(listener)))
             }
             #[cfg(all(unix, feature = "tls"))]
@@ -127,8 +127,48 @@ impl Bind for DefaultListener {
 
     fn bind_endpoint(rocket: &Rocket<Ignite>) -> Result<Endpoint, Self::Error> {
         let config: Config = rocket.figment().extract()?;
-        Ok(config.address)
+        match config.address {
+            #[cfg(feature = "tls")]
+            Endpoint::Tcp(_) if config.tls.is_some() => {
+                Ok(<TlsListener<TcpListener> as Bind>::bind_endpoint(rocket)?)
+            }
+            Endpoint::Tcp(_) => Ok(<TcpListener as Bind>::bind_endpoint(rocket)?),
+            #[cfg(all(unix, feature = "tls"))]
+            Endpoint::Unix(_) if config.tls.is_some() => {
+                Ok(<TlsListener<UnixListener> as Bind>::bind_endpoint(rocket)?)
+            }
+            #[cfg(unix)]
+            Endpoint::Unix(_) => Ok(<UnixListener as Bind>::bind_endpoint(rocket)?),
+            endpoint => Ok(endpoint),
+        }
+    }
+}
+
+/// Binds a TCP listener. If a [`Handoff`] fairing is attached, a listener
+/// handed off by a previous process at the same address is taken over instead,
+/// and the listener is registered so that it can be handed off in turn.
+///
+/// [`Handoff`]: crate::handoff::Handoff
+#[cfg(not(doc))]
+async fn bind_tcp(rocket: &Rocket<Ignite>) -> Result<TcpListener, Error> {
+    #[cfg(all(unix, feature = "handoff"))]
+    if let Some(handoff) = rocket.fairing::<crate::handoff::Handoff>() {
+        let endpoint = <TcpListener as Bind>::bind_endpoint(rocket)?;
+        let inherited = endpoint.tcp().and_then(|addr| Some((addr, handoff.inherit(addr)?)));
+        let listener = match inherited {
+            Some((addr, listener)) => {
+                let listener = listener?;
+                info!(name: "handoff", %addr, "using listener handed off by previous process");
+                listener
+            }
+            None => <TcpListener as Bind>::bind(rocket).await?,
+        };
+
+        handoff.register(&listener);
+        return Ok(listener);
     }
+
+    Ok(<TcpListener as Bind>::bind(rocket).await?)
 }
 
 #[derive(Debug)]
@@ -0,0 +1,441 @@
+//! A FastCGI front-end adapter.
+//!
+//! [`FcgiListener`] wraps another listener, typically a [`UnixListener`] or
+//! [`TcpListener`], whose peer is a web server such as Apache or nginx speaking
+//! FastCGI rather than HTTP. Each FastCGI request is translated into an HTTP
+//! request and served by the same routes, guards, and fairings as any other;
+//! the HTTP response is translated back into FastCGI records.
+//!
+//! Only the `RESPONDER` role is supported. Requests aren't multiplexed: the
+//! connection is closed after each response. The following request parameters
+//! determine the HTTP request:
+//!
+//!   * `REQUEST_METHOD` and `REQUEST_URI`: the method and the URI. If
+//!     `REQUEST_URI` is missing, it is reconstructed from `SCRIPT_NAME`,
+//!     `PATH_INFO`, and `QUERY_STRING`.
+//!   * `HTTP_*`: the headers, with `_` replaced by `-`, as well as
+//!     `CONTENT_TYPE` and `CONTENT_LENGTH`.
+//!   * `REMOTE_ADDR` and `REMOTE_PORT`: the [remote address] of the client.
+//!
+//! # Example
+//!
+//! Serve FastCGI over the Unix socket configured as `address`, for instance
+//! `unix:/run/app.sock`:
+//!
+//! ```rust,no_run
+//! # #[macro_use] extern crate rocket;
+//! use rocket::listener::fcgi::FcgiListener;
+//! use rocket::listener::unix::UnixListener;
+//!
+//! #[rocket::main]
+//! async fn main() -> Result<(), rocket::Error> {
+//!     rocket::build()
+//!         .launch_with::<FcgiListener<UnixListener>>()
+//!         .await?;
+//!
+//!     Ok(())
+//! }
+//! ```
+//!
+//! [`UnixListener`]: crate::listener::unix::UnixListener
+//! [`TcpListener`]: crate::listener::tcp::TcpListener
+//! [remote address]: crate::Request::remote()
+
+use std::io;
+use std::net::{IpAddr, SocketAddr};
+use std::pin::{pin, Pin};
+use std::task::{Context, Poll};
+
+use futures::future::{select, Either};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, DuplexStream, ReadBuf};
+
+use crate::listener::{Bind, Connection, Endpoint, Listener};
+use crate::{Ignite, Rocket};
+
+const VERSION: u8 = 1;
+
+const BEGIN_REQUEST: u8 = 1;
+const ABORT_REQUEST: u8 = 2;
+const END_REQUEST: u8 = 3;
+const PARAMS: u8 = 4;
+const STDIN: u8 = 5;
+const STDOUT: u8 = 6;
+const GET_VALUES: u8 = 9;
+const GET_VALUES_RESULT: u8 = 10;
+const UNKNOWN_TYPE: u8 = 11;
+
+const RESPONDER: u16 = 1;
+const REQUEST_COMPLETE: u8 = 0;
+const UNKNOWN_ROLE: u8 = 3;
+
+/// The size of the buffer between the FastCGI connection and the server.
+const BUFFER: usize = 64 * 1024;
+
+/// The maximum length of a record's content.
+const MAX_CONTENT: usize = u16::MAX as usize;
+
+/// A listener that speaks FastCGI to its peer. See the [module
+/// docs](self) for details.
+pub struct FcgiListener<L> {
+    inner: L,
+}
+
+/// A FastCGI connection, as seen by the HTTP server.
+pub struct FcgiConnection {
+    stream: DuplexStream,
+    peer: io::Result<Endpoint>,
+}
+
+struct Record {
+    kind: u8,
+    id: u16,
+    content: Vec<u8>,
+}
+
+impl<L> FcgiListener<L> {
+    /// Wraps `inner`, speaking FastCGI to the peers of its connections.
+    pub fn new(inner: L) -> Self {
+        FcgiListener { inner }
+    }
+
+    /// Returns the wrapped listener.
+    pub fn into_inner(self) -> L {
+        self.inner
+    }
+}
+
+impl<L: Bind> Bind for FcgiListener<L> {
+    type Error = L::Error;
+
+    async fn bind(rocket: &Rocket<Ignite>) -> Result<Self, Self::Error> {
+        L::bind(rocket).await.map(FcgiListener::new)
+    }
+
+    fn bind_endpoint(rocket: &Rocket<Ignite>) -> Result<Endpoint, Self::Error> {
+        L::bind_endpoint(rocket)
+    }
+}
+
+impl<L: Listener> Listener for FcgiListener<L>
+    where L::Connection: 'static
+{
+    type Accept = L::Accept;
+
+    type Connection = FcgiConnection;
+
+    async fn accept(&self) -> io::Result<Self::Accept> {
+        self.inner.accept().await
+    }
+
+    async fn connect(&self, accept: Self::Accept) -> io::Result<Self::Connection> {
+        let mut conn = self.inner.connect(accept).await?;
+        let (id, params) = loop {
+            let record = Record::read(&mut conn).await?
+                .ok_or_else(|| io::Error::from(io::ErrorKind::UnexpectedEof))?;
+
+            match record.kind {
+                BEGIN_REQUEST => break (record.id, begin(&mut conn, record).await?),
+                GET_VALUES => Record::write(&mut conn, GET_VALUES_RESULT, 0, &values()).await?,
+                kind => {
+                    let content = [kind, 0, 0, 0, 0, 0, 0, 0];
+                    Record::write(&mut conn, UNKNOWN_TYPE, 0, &content).await?;
+                }
+            }
+        };
+
+        let peer = params.iter()
+            .find(|(k, _)| k == "REMOTE_ADDR")
+            .and_then(|(_, v)| v.parse::<IpAddr>().ok())
+            .map(|ip| {
+                let port = params.iter().find(|(k, _)| k == "REMOTE_PORT");
+                let port = port.and_then(|(_, v)| v.parse().ok()).unwrap_or(0);
+                Endpoint::Tcp(SocketAddr::new(ip, port))
+            })
+            .map_or_else(|| conn.endpoint(), Ok);
+
+        let (stream, proxy) = tokio::io::duplex(BUFFER);
+        let (mut proxy_rx, mut proxy_tx) = tokio::io::split(proxy);
+        proxy_tx.write_all(&http_head(&params)).await?;
+
+        tokio::spawn(async move {
+            let (mut conn_rx, mut conn_tx) = tokio::io::split(conn);
+            let inbound = pin!(stdin(&mut conn_rx, &mut proxy_tx, id));
+            let outbound = pin!(stdout(&mut proxy_rx, &mut conn_tx, id));
+            let result = match select(outbound, inbound).await {
+                Either::Left((result, _)) => result,
+                Either::Right((Ok(()), outbound)) => outbound.await,
+                Either::Right((Err(e), _)) => Err(e),
+            };
+
+            if let Err(e) = result {
+                debug!(name: "fastcgi", error = %e, "fastcgi request failed");
+            }
+        });
+
+        Ok(FcgiConnection { stream, peer })
+    }
+
+    fn endpoint(&self) -> io::Result<Endpoint> {
+        self.inner.endpoint()
+    }
+}
+
+/// Handles a `BEGIN_REQUEST` record, returning the request's parameters.
+async fn begin<C>(conn: &mut C, record: Record) -> io::Result<Vec<(String, String)>>
+    where C: AsyncRead + AsyncWrite + Unpin
+{
+    let role = match record.content.get(..2) {
+        Some(&[hi, lo]) => u16::from_be_bytes([hi, lo]),
+        _ => return Err(io::Error::new(io::ErrorKind::InvalidData, "short BEGIN_REQUEST")),
+    };
+
+    if role != RESPONDER {
+        end(conn, record.id, UNKNOWN_ROLE).await?;
+        return Err(io::Error::new(io::ErrorKind::Unsupported, "unsupported fastcgi role"));
+    }
+
+    let mut raw = vec![];
+    loop {
+        let record = Record::read(conn).await?
+            .ok_or_else(|| io::Error::from(io::ErrorKind::UnexpectedEof))?;
+
+        match record.kind {
+            PARAMS if record.content.is_empty() => return Ok(pairs(&raw)),
+            PARAMS => raw.extend_from_slice(&record.content),
+            ABORT_REQUEST => return Err(io::ErrorKind::ConnectionAborted.into()),
+            _ => continue,
+        }
+    }
+}
+
+/// Forwards the request body from `STDIN` records to the server.
+async fn stdin<R, W>(conn: &mut R, proxy: &mut W, id: u16) -> io::Result<()>
+    where R: AsyncRead + Unpin, W: AsyncWrite + Unpin
+{
+    while let Some(record) = Record::read(conn).await? {
+        match record.kind {
+            STDIN if record.id == id && record.content.is_empty() => return Ok(()),
+            STDIN if record.id == id => proxy.write_all(&record.content).await?,
+            ABORT_REQUEST if record.id == id => return Err(io::ErrorKind::ConnectionAborted.into()),
+            _ => continue,
+        }
+    }
+
+    Ok(())
+}
+
+/// Forwards the server's response as CGI output in `STDOUT` records.
+async fn stdout<R, W>(proxy: &mut R, conn: &mut W, id: u16) -> io::Result<()>
+    where R: AsyncRead + Unpin, W: AsyncWrite + Unpin
+{
+    let mut head = vec![];
+    while !head.ends_with(b"\r\n\r\n") {
+        match proxy.read_u8().await {
+            Ok(byte) => head.push(byte),
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof && head.is_empty() => {
+                return end(conn, id, REQUEST_COMPLETE).await;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    // The status line `HTTP/1.0 200 OK` becomes the header `Status: 200 OK`.
+    let head = String::from_utf8_lossy(&head);
+    let (status_line, headers) = head.split_once("\r\n").unwrap_or((&head, ""));
+    let status = status_line.split_once(' ').map_or("500", |(_, status)| status);
+    let output = format!("Status: {status}\r\n{headers}");
+    Record::write(conn, STDOUT, id, output.as_bytes()).await?;
+
+    let mut buf = vec![0; MAX_CONTENT];
+    loop {
+        let n = proxy.read(&mut buf).await?;
+        if n == 0 {
+            break;
+        }
+
+        Record::write(conn, STDOUT, id, &buf[..n]).await?;
+    }
+
+    Record::write(conn, STDOUT, id, &[]).await?;
+    end(conn, id, REQUEST_COMPLETE).await
+}
+
+/// Writes an `END_REQUEST` record with `status`.
+async fn end<W: AsyncWrite + Unpin>(conn: &mut W, id: u16, status: u8) -> io::Result<()> {
+    Record::write(conn, END_REQUEST, id, &[0, 0, 0, 0, status, 0, 0, 0]).await?;
+    conn.flush().await?;
+    conn.shutdown().await
+}
+
+/// The response to a `GET_VALUES` request.
+fn values() -> Vec<u8> {
+    let mut content = vec![];
+    for (name, value) in [("FCGI_MAX_CONNS", "1024"), ("FCGI_MAX_REQS", "1024"),
+        ("FCGI_MPXS_CONNS", "0")]
+    {
+        content.extend_from_slice(&[name.len() as u8, value.len() as u8]);
+        content.extend_from_slice(name.as_bytes());
+        content.extend_from_slice(value.as_bytes());
+    }
+
+    content
+}
+
+/// Parses FastCGI name-value pairs, dropping malformed ones.
+fn pairs(mut raw: &[u8]) -> Vec<(String, String)> {
+    fn length(raw: &mut &[u8]) -> Option<usize> {
+        match raw.first()? {
+            byte if byte & 0x80 == 0 => {
+                *raw = &raw[1..];
+                Some(*byte as usize)
+            }
+            _ => {
+                let bytes: [u8; 4] = raw.get(..4)?.try_into().ok()?;
+                *raw = &raw[4..];
+                Some((u32::from_be_bytes(bytes) & 0x7fff_ffff) as usize)
+            }
+        }
+    }
+
+    let mut pairs = vec![];
+    while !raw.is_empty() {
+        let (Some(name_len), Some(value_len)) = (length(&mut raw), length(&mut raw)) else {
+            break;
+        };
+
+        if raw.len() < name_len + value_len {
+            break;
+        }
+
+        let (name, rest) = raw.split_at(name_len);
+        let (value, rest) = rest.split_at(value_len);
+        raw = rest;
+        if let (Ok(name), Ok(value)) = (std::str::from_utf8(name), std::str::from_utf8(value)) {
+            pairs.push((name.to_string(), value.to_string()));
+        }
+    }
+
+    pairs
+}
+
+/// Builds the head of the HTTP request described by the CGI `params`.
+fn http_head(params: &[(String, String)]) -> Vec<u8> {
+    let param = |name: &str| params.iter().find(|(k, _)| k == name).map(|(_, v)| v.as_str());
+    let method = param("REQUEST_METHOD").unwrap_or("GET");
+    let uri = match param("REQUEST_URI") {
+        Some(uri) => uri.to_string(),
+        None => {
+            let path = format!("{}{}",
+                param("SCRIPT_NAME").unwrap_or(""),
+                param("PATH_INFO").unwrap_or(""));
+
+            let path = if path.is_empty() { "/".into() } else { path };
+            match param("QUERY_STRING").filter(|q| !q.is_empty()) {
+                Some(query) => format!("{path}?{query}"),
+                None => path,
+            }
+        }
+    };
+
+    // HTTP/1.0 responses are never chunked and end by closing the connection.
+    let mut head = format!("{method} {uri} HTTP/1.0\r\nConnection: close\r\n");
+    for (name, value) in params {
+        let name = match name.as_str() {
+            "CONTENT_TYPE" | "CONTENT_LENGTH" if !value.is_empty() => name.as_str(),
+            "HTTP_CONNECTION" | "HTTP_PROXY" => continue,
+            name => match name.strip_prefix("HTTP_") {
+                Some(name) => name,
+                None => continue,
+            },
+        };
+
+        head.push_str(&name.replace('_', "-"));
+        head.push_str(": ");
+        head.push_str(value);
+        head.push_str("\r\n");
+    }
+
+    head.push_str("\r\n");
+    head.into_bytes()
+}
+
+impl Record {
+    /// Reads the next record, or `None` on a clean EOF.
+    async fn read<R: AsyncRead + Unpin>(reader: &mut R) -> io::Result<Option<Record>> {
+        let mut header = [0; 8];
+        match reader.read_exact(&mut header).await {
+            Ok(_) => {},
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(e),
+        }
+
+        if header[0] != VERSION {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "unknown fastcgi version"));
+        }
+
+        let id = u16::from_be_bytes([header[2], header[3]]);
+        let len = u16::from_be_bytes([header[4], header[5]]) as usize;
+        let mut content = vec![0; len + header[6] as usize];
+        reader.read_exact(&mut content).await?;
+        content.truncate(len);
+        Ok(Some(Record { kind: header[1], id, content }))
+    }
+
+    /// Writes `content` as one or more records of `kind`. Empty `content` is
+    /// written as a single empty record.
+    async fn write<W>(writer: &mut W, kind: u8, id: u16, content: &[u8]) -> io::Result<()>
+        where W: AsyncWrite + Unpin
+    {
+        let mut chunks = content.chunks(MAX_CONTENT).peekable();
+        if chunks.peek().is_none() {
+            let [hi, lo] = id.to_be_bytes();
+            return writer.write_all(&[VERSION, kind, hi, lo, 0, 0, 0, 0]).await;
+        }
+
+        for chunk in chunks {
+            let [hi, lo] = id.to_be_bytes();
+            let [len_hi, len_lo] = (chunk.len() as u16).to_be_bytes();
+            writer.write_all(&[VERSION, kind, hi, lo, len_hi, len_lo, 0, 0]).await?;
+            writer.write_all(chunk).await?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Connection for FcgiConnection {
+    fn endpoint(&self) -> io::Result<Endpoint> {
+        match &self.peer {
+            Ok(endpoint) => Ok(endpoint.clone()),
+            Err(e) => Err(io::Error::new(e.kind(), e.to_string())),
+        }
+    }
+}
+
+impl AsyncRead for FcgiConnection {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().stream).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for FcgiConnection {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.get_mut().stream).poll_write(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().stream).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().stream).poll_shutdown(cx)
+    }
+}
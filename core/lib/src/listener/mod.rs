@@ -10,6 +10,7 @@ mod default;
 #[cfg_attr(nightly, doc(cfg(unix)))]
 pub mod unix;
 pub mod tcp;
+pub mod fcgi;
 #[cfg(feature = "http3-preview")]
 pub mod quic;
 
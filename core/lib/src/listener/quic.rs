@@ -21,6 +21,14 @@
 //! > 🚀 Rocket has launched on https://127.0.0.1:8000 (QUIC + mTLS)
 //! ```
 //!
+//! Both listeners are bound from the same [`Config`](crate::Config), with QUIC
+//! on the UDP port matching the TCP port, by
+//! [`launch()`](crate::Rocket::launch()) and by
+//! [`launch_with()`](crate::Rocket::launch_with()) for any listener whose
+//! endpoint is TCP with TLS. Every response advertises the HTTP/3 endpoint to
+//! clients via an `Alt-Svc` header, as in `Alt-Svc: h3=":8000"`, until the
+//! QUIC listener is [closed](crate::Rocket::close_listeners()).
+//!
 //! mTLS is not yet supported via this implementation.
 
 use std::io;
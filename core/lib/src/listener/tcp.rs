@@ -4,20 +4,56 @@
 //!
 //! Reads the following configuration parameters:
 //!
-//! | parameter | type         | default     | note                            |
-//! |-----------|--------------|-------------|---------------------------------|
-//! | `address` | [`Endpoint`] | `127.0.0.1` | must be `tcp:ip`                |
-//! | `port`    | `u16`        | `8000`      | replaces the port in `address ` |
+//! | parameter       | type           | default     | note                            |
+//! |-----------------|----------------|-------------|---------------------------------|
+//! | `address`       | [`Endpoint`]   | `127.0.0.1` | must be `tcp:ip`                |
+//! | `port`          | `u16`          | `8000`      | replaces the port in `address ` |
+//! | `port_fallback` | list of ports  | `[]`        | tried in order if `port` fails  |
+//! | `bind_retry`    | [`Retry`]      | see below   | retry policy for busy addresses |
+//!
+//! # Port Fallback
+//!
+//! If binding to `port` fails because the address is in use, each port in
+//! `port_fallback` is tried in order. A port is either an integer or the string
+//! `"auto"`, which binds to any port the operating system selects. The port
+//! that is ultimately bound is reported at liftoff and available via
+//! [`Rocket::endpoints()`](crate::Rocket::endpoints()).
+//!
+//! ```toml
+//! [default]
+//! port = 8000
+//! port_fallback = [8001, 8002, "auto"]
+//! ```
+//!
+//! # Bind Retries
+//!
+//! If every candidate port is in use, as happens when a previous instance is
+//! still shutting down during a restart, binding is retried per the
+//! [`Retry`] policy in `bind_retry`. Binding isn't retried unless `bind_retry`
+//! is set; values missing from it default to those of [`Retry::new()`]. The
+//! policy's `timeout` doesn't apply. Errors other than an in-use or
+//! unavailable address are never retried.
+//!
+//! ```toml
+//! [default.bind_retry]
+//! retries = 5
+//! delay = 250
+//! ```
+//!
+//! [`Retry`]: crate::fairing::Retry
+//! [`Retry::new()`]: crate::fairing::Retry::new()
 
-use std::io;
+use std::{fmt, io};
 use std::net::{Ipv4Addr, SocketAddr};
 
 use either::{Either, Left, Right};
+use serde::{de, Deserialize};
 
 #[doc(inline)]
 pub use tokio::net::{TcpListener, TcpStream};
 
 use crate::{Ignite, Rocket};
+use crate::fairing::Retry;
 use crate::listener::{Bind, Connection, Endpoint, Listener};
 
 impl Bind for TcpListener {
@@ -29,7 +65,55 @@ impl Bind for TcpListener {
             .ok_or_else(|| io::Error::other("internal error: invalid endpoint"))
             .map_err(Right)?;
 
-        Self::bind(addr).await.map_err(Right)
+        let figment = rocket.figment();
+        let fallback = match figment.extract_inner::<Vec<Port>>("port_fallback") {
+            Ok(ports) => ports,
+            Err(e) if e.missing() => vec![],
+            Err(e) => return Err(Left(e)),
+        };
+
+        let retry = match figment.extract_inner::<Retry>("bind_retry") {
+            Ok(retry) => retry,
+            Err(e) if e.missing() => Retry::new().retries(0),
+            Err(e) => return Err(Left(e)),
+        };
+
+        let candidates: Vec<SocketAddr> = std::iter::once(addr.port())
+            .chain(fallback.iter().map(|p| p.0))
+            .map(|p| SocketAddr::new(addr.ip(), p))
+            .collect();
+
+        let attempts = retry.delays().count() + 1;
+        let mut delays = retry.delays();
+        for attempt in 1..=attempts {
+            let mut last_error = None;
+            for candidate in &candidates {
+                match Self::bind(*candidate).await {
+                    Ok(listener) => {
+                        if candidate != &addr {
+                            warn!(primary = %addr, bound = %candidate,
+                                "primary address unavailable: bound to fallback port");
+                        }
+
+                        return Ok(listener);
+                    }
+                    Err(e) if is_transient(&e) => last_error = Some(e),
+                    Err(e) => return Err(Right(e)),
+                }
+            }
+
+            let error = last_error.expect("at least one candidate");
+            let Some(delay) = delays.next() else {
+                return Err(Right(error));
+            };
+
+            warn!(%addr, %error, attempt, of = attempts,
+                "binding failed: retrying in {}ms", delay.as_millis());
+
+            tokio::time::sleep(delay).await;
+        }
+
+        unreachable!("loop returns on the final attempt")
     }
 
     fn bind_endpoint(rocket: &Rocket<Ignite>) -> Result<Endpoint, Self::Error> {
@@ -74,3 +158,46 @@ impl Connection for TcpStream {
         self.peer_addr().map(Endpoint::Tcp)
     }
 }
+
+fn is_transient(error: &io::Error) -> bool {
+    matches!(error.kind(), io::ErrorKind::AddrInUse | io::ErrorKind::AddrNotAvailable)
+}
+
+/// A port in `port_fallback`: an integer or `"auto"` for an OS-assigned port.
+struct Port(u16);
+
+impl<'de> Deserialize<'de> for Port {
+    fn deserialize<D: de::Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        struct Visitor;
+
+        impl<'de> de::Visitor<'de> for Visitor {
+            type Value = Port;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a port number or \"auto\"")
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<Port, E> {
+                u16::try_from(v)
+                    .map(Port)
+                    .map_err(|_| E::invalid_value(de::Unexpected::Unsigned(v), &self))
+            }
+
+            fn visit_i64<E: de::Error>(self, v: i64) -> Result<Port, E> {
+                u16::try_from(v)
+                    .map(Port)
+                    .map_err(|_| E::invalid_value(de::Unexpected::Signed(v), &self))
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Port, E> {
+                match v {
+                    "auto" => Ok(Port(0)),
+                    _ => Err(E::invalid_value(de::Unexpected::Str(v), &self)),
+                }
+            }
+        }
+
+        de.deserialize_any(Visitor)
+    }
+}
+
@@ -2,6 +2,8 @@ use std::fmt;
 
 use crate::{Request, Data};
 use crate::http::{Status, Method};
+#[cfg(feature = "cache")]
+use crate::http::{Header, ext::IntoOwned};
 use crate::http::uri::Origin;
 
 use super::{Client, LocalResponse};
@@ -98,6 +100,23 @@ impl<'c> LocalRequest<'c> {
             rocket.dispatch(token, req, data)
         }).await;
 
+        // Refresh a stale cached response. Unlike the server, which does so in
+        // the background, wait for the refresh so that tests are deterministic.
+        #[cfg(feature = "cache")]
+        if crate::cache::wants_revalidation(response._request()) {
+            let original = response._request();
+            let uri = original.uri().clone().into_owned();
+            let mut request = Request::new(rocket, Method::Get, uri);
+            for header in original.headers().iter() {
+                request.add_header(Header::new(header.name.to_string(), header.value.into_owned()));
+            }
+
+            crate::cache::mark_revalidation(&request);
+            let mut data = Data::local(vec![]);
+            let token = rocket.preprocess(&mut request, &mut data).await;
+            LocalResponse::new(request, move |req| rocket.dispatch(token, req, data)).await;
+        }
+
         // If the client is tracking cookies, updates the internal cookie jar
         // with the changes reflected by `response`.
         if self.client.tracked {
@@ -2,10 +2,12 @@ use std::io;
 use std::future::Future;
 use std::{pin::Pin, task::{Context, Poll}};
 
-use tokio::io::{AsyncRead, ReadBuf};
+use futures::future::BoxFuture;
+use tokio::io::{AsyncRead, AsyncWrite, DuplexStream, ReadBuf};
 
+use crate::data::{IoHandler, IoStream};
 use crate::http::CookieJar;
-use crate::{Request, Response};
+use crate::{Rocket, Orbit, Request, Response};
 
 /// An `async` response from a dispatched [`LocalRequest`](super::LocalRequest).
 ///
@@ -55,6 +57,7 @@ use crate::{Request, Response};
 pub struct LocalResponse<'c> {
     // XXX: SAFETY: This (dependent) field must come first due to drop order!
     response: Response<'c>,
+    upgrade: Option<(String, Box<dyn IoHandler + 'c>)>,
     cookies: CookieJar<'c>,
     _request: Box<Request<'c>>,
 }
@@ -79,7 +82,8 @@ impl<'c> LocalResponse<'c> {
         //   2) Ensure no refs to `Request` or its contents leak with a lifetime
         //      extending beyond that of `&self`.
         //
-        //      We have no methods that return an `&Request`. However, we must
+        //      We have no methods that return an `&Request` whose lifetime isn't
+        //      bounded by `&self`. However, we must
         //      also ensure that `Response` doesn't leak any such references. To
         //      do so, we don't expose the `Response` directly in any way;
         //      otherwise, methods like `.headers()` could, in conjunction with
@@ -97,13 +101,14 @@ impl<'c> LocalResponse<'c> {
             // known value in `request.cookies()`. This is okay: new cookies
             // should never be added to the resulting jar which is the only time
             // the value is used to set cookie defaults.
-            let response: Response<'c> = f(request).await;
+            let mut response: Response<'c> = f(request).await;
+            let upgrade = Rocket::<Orbit>::extract_io_handler(request, &mut response);
             let mut cookies = CookieJar::new(None, request.rocket());
             for cookie in response.cookies() {
                 cookies.add_original(cookie.into_owned());
             }
 
-            LocalResponse { _request: boxed_req, cookies, response, }
+            LocalResponse { _request: boxed_req, cookies, upgrade, response, }
         }
     }
 }
@@ -117,6 +122,10 @@ impl LocalResponse<'_> {
         &self.cookies
     }
 
+    pub(crate) fn _request(&self) -> &Request<'_> {
+        &self._request
+    }
+
     pub(crate) async fn _into_string(mut self) -> io::Result<String> {
         self.response.body_mut().to_string().await
     }
@@ -125,6 +134,14 @@ impl LocalResponse<'_> {
         self.response.body_mut().to_bytes().await
     }
 
+    #[cfg(feature = "signing")]
+    pub(crate) async fn _into_verified_bytes(self) -> Option<Vec<u8>> {
+        let signer = self._request.rocket().fairing::<crate::signing::Signer>()?;
+        let value = self.response.headers().get_one(signer.header()?)?.to_string();
+        let bytes = self._into_bytes().await.ok()?;
+        signer.verify(&value, &bytes).then_some(bytes)
+    }
+
     #[cfg(feature = "json")]
     async fn _into_json<T>(self) -> Option<T>
         where T: Send + serde::de::DeserializeOwned + 'static
@@ -195,6 +212,60 @@ impl LocalResponse<'_> {
         reader.await.ok()
     }
 
+    /// Completes the protocol upgrade the response accepted, if any, in memory.
+    ///
+    /// As when serving a client over a connection, a request that asks to
+    /// upgrade to a protocol the response supports, via its `Connection` and
+    /// `Upgrade` headers, is answered with `101 Switching Protocols`. This
+    /// method returns the client's end of the upgraded connection, which
+    /// speaks the protocol with the response's I/O handler. The handler is
+    /// driven while the returned stream is read from or written to. Returns
+    /// `None` if the response didn't accept an upgrade or if the upgrade was
+    /// already taken.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #[macro_use] extern crate rocket;
+    /// use rocket::data::Framed;
+    /// use rocket::http::{Header, Status};
+    /// use rocket::local::asynchronous::Client;
+    /// use rocket::response::Upgrade;
+    ///
+    /// #[get("/echo")]
+    /// fn echo() -> Upgrade<'static> {
+    ///     Upgrade::new("x-echo", |io, _| Box::pin(async move {
+    ///         let mut framed = Framed::new(io);
+    ///         while let Some(frame) = framed.recv().await? {
+    ///             framed.send(&frame).await?;
+    ///         }
+    ///
+    ///         Ok(())
+    ///     }))
+    /// }
+    ///
+    /// # rocket::async_test(async {
+    /// let client = Client::debug_with(routes![echo]).await.unwrap();
+    /// let mut response = client.get("/echo")
+    ///     .header(Header::new("Connection", "upgrade"))
+    ///     .header(Header::new("Upgrade", "x-echo"))
+    ///     .dispatch()
+    ///     .await;
+    ///
+    /// assert_eq!(response.status(), Status::SwitchingProtocols);
+    /// let mut framed = Framed::new(response.upgrade().unwrap());
+    /// framed.send(b"ping").await.unwrap();
+    /// assert_eq!(framed.recv().await.unwrap().unwrap(), b"ping");
+    /// # });
+    /// ```
+    pub fn upgrade(&mut self) -> Option<LocalUpgraded<'_>> {
+        let (protocol, handler) = self.upgrade.take()?;
+        let handler: Box<dyn IoHandler + '_> = handler;
+        let (client, server) = tokio::io::duplex(LocalUpgraded::BUFFER_SIZE);
+        let handler = handler.io(IoStream::local(server));
+        Some(LocalUpgraded { protocol, io: client, handler: Some(handler) })
+    }
+
     // Generates the public API methods, which call the private methods above.
     pub_response_impl!("# use rocket::local::asynchronous::Client;\n\
         use rocket::local::asynchronous::LocalResponse;" async await);
@@ -210,6 +281,74 @@ impl AsyncRead for LocalResponse<'_> {
     }
 }
 
+/// The client's end of a connection upgraded in memory, returned by
+/// [`LocalResponse::upgrade()`].
+///
+/// `LocalUpgraded` is [`AsyncRead`], [`AsyncWrite`], and `Unpin`. Reading from
+/// or writing to it also drives the response's I/O handler, which runs until it
+/// returns or the `LocalUpgraded` is dropped. Once the handler returns, reads
+/// see the end of the stream.
+pub struct LocalUpgraded<'a> {
+    protocol: String,
+    io: DuplexStream,
+    handler: Option<BoxFuture<'a, io::Result<()>>>,
+}
+
+impl LocalUpgraded<'_> {
+    /// The number of bytes buffered in each direction.
+    const BUFFER_SIZE: usize = 64 * 1024;
+
+    /// The protocol the connection was upgraded to.
+    pub fn protocol(&self) -> &str {
+        &self.protocol
+    }
+
+    /// Polls the I/O handler, if it's still running, dropping its end of the
+    /// connection once it returns.
+    fn drive(&mut self, cx: &mut Context<'_>) {
+        let Some(handler) = self.handler.as_mut() else { return };
+        if let Poll::Ready(result) = handler.as_mut().poll(cx) {
+            if let Err(e) = result {
+                warn!(protocol = %self.protocol, "local i/o handler failed: {e}");
+            }
+
+            self.handler = None;
+        }
+    }
+}
+
+impl AsyncRead for LocalUpgraded<'_> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        self.drive(cx);
+        Pin::new(&mut self.io).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for LocalUpgraded<'_> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.drive(cx);
+        Pin::new(&mut self.io).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.drive(cx);
+        Pin::new(&mut self.io).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.drive(cx);
+        Pin::new(&mut self.io).poll_shutdown(cx)
+    }
+}
+
 impl std::fmt::Debug for LocalResponse<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         self._response().fmt(f)
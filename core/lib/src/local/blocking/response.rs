@@ -71,6 +71,11 @@ impl LocalResponse<'_> {
         self.client.block_on(self.inner._into_bytes())
     }
 
+    #[cfg(feature = "signing")]
+    fn _into_verified_bytes(self) -> Option<Vec<u8>> {
+        self.client.block_on(self.inner._into_verified_bytes())
+    }
+
     #[cfg(feature = "json")]
     fn _into_json<T: Send + 'static>(self) -> Option<T>
         where T: serde::de::DeserializeOwned
@@ -108,6 +108,34 @@ macro_rules! pub_response_impl {
         self._into_bytes() $(.$suffix)? .ok()
     }
 
+    /// Consumes `self` and reads its body into a `Vec` of bytes if the body's
+    /// [signature](crate::signing) is valid.
+    ///
+    /// Returns `None` if the body is unset, if no [`Signer`] is attached, if
+    /// the response carries no signature, or if the signature doesn't verify.
+    ///
+    /// [`Signer`]: crate::signing::Signer
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    #[doc = $doc_prelude]
+    ///
+    /// # Client::_test(|_, _, response| {
+    /// let response: LocalResponse = response;
+    /// let bytes = response.into_verified_bytes();
+    /// # });
+    /// ```
+    #[cfg(feature = "signing")]
+    #[cfg_attr(nightly, doc(cfg(feature = "signing")))]
+    pub $($prefix)? fn into_verified_bytes(self) -> Option<Vec<u8>> {
+        if self._response().body().is_none() {
+            return None;
+        }
+
+        self._into_verified_bytes() $(.$suffix)?
+    }
+
     /// Consumes `self` and deserializes its body as JSON without buffering in
     /// memory.
     ///
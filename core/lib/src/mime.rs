@@ -0,0 +1,35 @@
+use std::collections::HashMap;
+
+use parking_lot::RwLock;
+
+use crate::http::ContentType;
+use crate::http::uncased::{Uncased, AsUncased};
+
+/// Extension to media type mappings registered via [`Rocket::mime()`].
+///
+/// Managed as state so that it is reachable in every phase and from requests.
+///
+/// [`Rocket::mime()`]: crate::Rocket::mime()
+#[derive(Default)]
+pub(crate) struct MimeTypes(RwLock<HashMap<Uncased<'static>, ContentType>>);
+
+impl MimeTypes {
+    /// Maps `ext`, sans any leading `.`, to `content_type`.
+    pub fn insert(&self, ext: &str, content_type: ContentType) {
+        let ext = ext.strip_prefix('.').unwrap_or(ext).to_string();
+        self.0.write().insert(Uncased::from_owned(ext), content_type);
+    }
+
+    /// Adds the mappings in `other` for extensions not already mapped.
+    pub fn merge(&self, other: MimeTypes) {
+        let mut types = self.0.write();
+        for (ext, content_type) in other.0.into_inner() {
+            types.entry(ext).or_insert(content_type);
+        }
+    }
+
+    /// Returns the media type registered for `ext`, if any.
+    pub fn get(&self, ext: &str) -> Option<ContentType> {
+        self.0.read().get(ext.as_uncased()).cloned()
+    }
+}
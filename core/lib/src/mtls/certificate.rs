@@ -24,6 +24,11 @@ use crate::http::Status;
 /// If the client does not present certificates, the guard _forwards_ with a
 /// status of 401 Unauthorized.
 ///
+/// If the request comes from a proxy that terminates mutual TLS and is
+/// [trusted](crate::mtls::ForwardedCertConfig), the client certificate the
+/// proxy forwarded in the configured header is used instead of any presented
+/// on the connection. Such certificates are not verified by Rocket.
+///
 /// If the certificate chain fails to validate or verify, the guard _fails_ with
 /// the respective [`Error`] a status of 401 Unauthorized.
 ///
@@ -107,6 +112,9 @@ pub struct Certificate<'a> {
 
 pub use rustls::pki_types::CertificateDer;
 
+/// The certificate forwarded by a trusted proxy, if any.
+struct Forwarded(Option<Result<Vec<CertificateDer<'static>>>>);
+
 #[crate::async_trait]
 impl<'r> FromRequest<'r> for Certificate<'r> {
     type Error = Error;
@@ -114,6 +122,17 @@ impl<'r> FromRequest<'r> for Certificate<'r> {
     async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
         use crate::outcome::{try_outcome, IntoOutcome};
 
+        let config = &req.rocket().config.forwarded_certs;
+        let forwarded = req.local_cache(|| {
+            Forwarded(config.forwarded(req).map(|cert| cert.map(|cert| vec![cert])))
+        });
+
+        match &forwarded.0 {
+            Some(Ok(chain)) => return Certificate::parse(chain).or_error(Status::Unauthorized),
+            Some(Err(e)) => return Outcome::Error((Status::Unauthorized, e.clone())),
+            None => {}
+        }
+
         let certs = req.connection
             .peer_certs
             .as_ref()
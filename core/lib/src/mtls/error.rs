@@ -39,6 +39,9 @@ pub enum Error {
     Incomplete(Option<NonZeroUsize>),
     /// The certificate contained `.0` bytes of trailing data.
     Trailing(usize),
+    /// The certificate forwarded by a trusted proxy isn't a valid RFC 9440
+    /// byte sequence.
+    Forwarded,
 }
 
 impl fmt::Display for Error {
@@ -48,6 +51,7 @@ impl fmt::Display for Error {
             Error::Incomplete(_) => write!(f, "incomplete certificate data"),
             Error::Trailing(n) => write!(f, "found {} trailing bytes", n),
             Error::Empty => write!(f, "empty certificate chain"),
+            Error::Forwarded => write!(f, "malformed forwarded certificate"),
             Error::NoSubject => write!(f, "empty subject without subjectAlt"),
             Error::NonCriticalSubjectAlt => write!(f, "empty subject without critical subjectAlt"),
         }
@@ -0,0 +1,179 @@
+use std::net::IpAddr;
+
+use serde::{Deserialize, Deserializer, Serialize, de};
+
+use crate::Request;
+use crate::http::Header;
+use crate::mtls::{CertificateDer, Error, Result};
+
+/// Client certificates forwarded by trusted proxies that terminate mutual TLS.
+///
+/// When a proxy in front of Rocket terminates mutual TLS, the client's
+/// certificate never reaches Rocket's own TLS listener. Instead, the proxy
+/// verifies the certificate and forwards it in a request header as described
+/// by [RFC 9440]: the base64-encoded DER certificate between colons, as in
+/// `Client-Cert: :MIIBqDCCAU6gAwIBAgIB...:`.
+///
+/// When the peer of a connection is one of the `trusted_proxies`, the
+/// [`Certificate`](crate::mtls::Certificate) request guard retrieves the
+/// client certificate from the configured `header`, if it is present,
+/// _instead of_ from the connection. Otherwise, the header is ignored, so
+/// that clients can't forge certificates by setting it themselves. No
+/// proxies are trusted by default. Entries in `trusted_proxies` are IP
+/// addresses, like `10.0.0.2`, or networks in CIDR notation, like
+/// `10.0.0.0/8`.
+///
+/// Rocket doesn't verify forwarded certificates against any CA; it relies on
+/// the proxy to have done so. As such, a trusted proxy must remove the header
+/// from requests without a verified certificate.
+///
+/// ```toml
+/// [default.forwarded_certs]
+/// header = "Client-Cert"
+/// trusted_proxies = ["10.0.0.0/8", "::1"]
+/// ```
+///
+/// [RFC 9440]: https://www.rfc-editor.org/rfc/rfc9440
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::config::Config;
+/// use rocket::figment::{Figment, providers::{Format, Toml}};
+///
+/// let figment = Figment::from(Config::default())
+///     .merge(Toml::string("forwarded_certs.trusted_proxies = [\"10.0.0.0/8\"]"));
+///
+/// let config = Config::from(figment);
+/// assert!(config.forwarded_certs.trusts([10, 1, 2, 3].into()));
+/// assert!(!config.forwarded_certs.trusts([192, 168, 0, 1].into()));
+/// assert_eq!(config.forwarded_certs.header, "Client-Cert");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ForwardedCertConfig {
+    /// The name of the header containing the forwarded certificate.
+    ///
+    /// **default: `"Client-Cert"`**
+    #[serde(deserialize_with = "header_name")]
+    pub header: String,
+    /// IP addresses and CIDR networks of proxies whose forwarded certificates
+    /// are trusted.
+    ///
+    /// **default: `[]`**
+    #[serde(deserialize_with = "networks")]
+    pub trusted_proxies: Vec<String>,
+}
+
+impl ForwardedCertConfig {
+    /// Returns `true` if certificates forwarded by a peer at `ip` are trusted.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::net::Ipv6Addr;
+    /// use rocket::mtls::ForwardedCertConfig;
+    ///
+    /// let config = ForwardedCertConfig {
+    ///     trusted_proxies: vec!["192.168.1.7".into(), "fd00::/8".into()],
+    ///     ..Default::default()
+    /// };
+    ///
+    /// assert!(config.trusts([192, 168, 1, 7].into()));
+    /// assert!(!config.trusts([192, 168, 1, 8].into()));
+    /// assert!(config.trusts("fd12::1".parse().unwrap()));
+    /// assert!(!config.trusts(Ipv6Addr::LOCALHOST.into()));
+    /// ```
+    pub fn trusts(&self, ip: IpAddr) -> bool {
+        self.trusted_proxies.iter()
+            .filter_map(|network| parse_network(network))
+            .any(|(network, len)| contains(network, len, ip))
+    }
+
+    /// Returns the certificate forwarded in `req`, if its peer is trusted and
+    /// it has the header.
+    pub(crate) fn forwarded(&self, req: &Request<'_>) -> Option<Result<CertificateDer<'static>>> {
+        if self.trusted_proxies.is_empty() {
+            return None;
+        }
+
+        let peer = req.remote().and_then(|endpoint| endpoint.ip())?;
+        if !self.trusts(peer) {
+            return None;
+        }
+
+        let value = req.headers().get_one(&self.header)?.trim();
+        let der = value.strip_prefix(':')
+            .and_then(|value| value.strip_suffix(':'))
+            .and_then(decode)
+            .ok_or(Error::Forwarded);
+
+        Some(der.map(CertificateDer::from))
+    }
+}
+
+impl Default for ForwardedCertConfig {
+    fn default() -> Self {
+        ForwardedCertConfig { header: "Client-Cert".into(), trusted_proxies: vec![] }
+    }
+}
+
+/// Parses `ip` or `ip/len` into an address and prefix length.
+fn parse_network(string: &str) -> Option<(IpAddr, u8)> {
+    let (ip, len) = match string.split_once('/') {
+        Some((ip, len)) => (ip.parse::<IpAddr>().ok()?, Some(len.parse::<u8>().ok()?)),
+        None => (string.parse::<IpAddr>().ok()?, None),
+    };
+
+    let max = if ip.is_ipv4() { 32 } else { 128 };
+    match len {
+        Some(len) if len > max => None,
+        len => Some((ip, len.unwrap_or(max))),
+    }
+}
+
+/// Whether the network `network/len` contains `ip`. IPv4-mapped IPv6
+/// addresses are compared as IPv4 addresses.
+fn contains(network: IpAddr, len: u8, ip: IpAddr) -> bool {
+    let ip = match ip {
+        IpAddr::V6(v6) => v6.to_ipv4_mapped().map_or(ip, IpAddr::V4),
+        ip => ip,
+    };
+
+    let (network, ip, bits) = match (network, ip) {
+        (IpAddr::V4(n), IpAddr::V4(ip)) => (u32::from(n) as u128, u32::from(ip) as u128, 32),
+        (IpAddr::V6(n), IpAddr::V6(ip)) => (u128::from(n), u128::from(ip), 128),
+        _ => return false,
+    };
+
+    let mask = match len {
+        0 => 0,
+        len => u128::MAX << (bits - len as u32),
+    };
+
+    (network & mask) == (ip & mask)
+}
+
+fn decode(string: &str) -> Option<Vec<u8>> {
+    // `binascii` requires more space than the actual output for padding.
+    let mut buf = vec![0; string.len()];
+    binascii::b64decode(string.as_bytes(), &mut buf).ok().map(|bytes| bytes.to_vec())
+}
+
+fn header_name<'de, D: Deserializer<'de>>(de: D) -> Result<String, D::Error> {
+    let name = String::deserialize(de)?;
+    match Header::is_valid_name(&name) {
+        true => Ok(name),
+        false => Err(de::Error::invalid_value(de::Unexpected::Str(&name), &"a header name")),
+    }
+}
+
+fn networks<'de, D: Deserializer<'de>>(de: D) -> Result<Vec<String>, D::Error> {
+    let networks = Vec::<String>::deserialize(de)?;
+    if let Some(invalid) = networks.iter().find(|n| parse_network(n).is_none()) {
+        let expected = &"an IP address or CIDR network";
+        return Err(de::Error::invalid_value(de::Unexpected::Str(invalid), expected));
+    }
+
+    Ok(networks)
+}
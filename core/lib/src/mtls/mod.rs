@@ -3,7 +3,8 @@
 //! For details on how to configure mutual TLS, see [`MtlsConfig`] and the [TLS
 //! guide](https://rocket.rs/master/guide/configuration/#tls). See
 //! [`Certificate`] for a request guard that validates, verifies, and retrieves
-//! client certificates.
+//! client certificates, including those [forwarded](ForwardedCertConfig) by
+//! trusted proxies that terminate mutual TLS.
 
 pub mod oid {
     //! Lower-level OID types re-exported from
@@ -34,10 +35,12 @@ mod certificate;
 mod error;
 mod name;
 mod config;
+mod forwarded;
 
 pub use error::Error;
 pub use name::Name;
 pub use config::MtlsConfig;
+pub use forwarded::ForwardedCertConfig;
 pub use certificate::{Certificate, CertificateDer};
 
 /// A type alias for `Result` with the error type set to [`Error`].
@@ -0,0 +1,380 @@
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::fmt::{self, Write};
+
+use indexmap::IndexMap;
+
+use crate::{Rocket, Phase, Route, Catcher};
+use crate::http::{Method, Status, MediaType};
+use crate::openapi::{Operation, Parameter, Location, Response, Schema, OpenApi};
+use crate::util::EscapeJson;
+
+/// An OpenAPI 3.1 document describing the routes of an instance.
+///
+/// A `Document` is collected by [`Rocket::openapi()`] and rendered as JSON by
+/// its `Display` implementation or [`Document::to_json()`].
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::http::Method;
+///
+/// /// Returns the user with ID `id`.
+/// #[get("/users/<id>")]
+/// fn user(id: usize) -> Option<String> {
+///     None
+/// }
+///
+/// let rocket = rocket::build().mount("/api", routes![user]);
+/// let document = rocket.openapi();
+/// let op = &document.paths["/api/users/{id}"][&Method::Get];
+/// assert_eq!(op.summary.as_deref(), Some("Returns the user with ID `id`."));
+/// assert_eq!(op.operation_id.as_deref(), Some("user"));
+/// assert_eq!(op.parameters[0].name, "id");
+/// assert_eq!(op.responses.len(), 2);
+///
+/// let json = document.to_json();
+/// assert!(json.starts_with(r#"{"openapi":"3.1.0""#));
+/// ```
+///
+/// [`Rocket::openapi()`]: crate::Rocket::openapi()
+#[derive(Debug, Clone)]
+pub struct Document {
+    /// Information about the API.
+    pub info: Info,
+    /// The operations, by path template and method, sorted by path.
+    pub paths: IndexMap<String, IndexMap<Method, Operation>>,
+    /// The schemas referred to by the operations, by name.
+    pub components: IndexMap<Cow<'static, str>, Schema>,
+}
+
+/// Information about an API: its title, version, and description.
+#[derive(Debug, Clone)]
+pub struct Info {
+    /// The API's title.
+    pub title: Cow<'static, str>,
+    /// The API's version.
+    pub version: Cow<'static, str>,
+    /// A description of the API, if any.
+    pub description: Option<Cow<'static, str>>,
+}
+
+impl Default for Info {
+    fn default() -> Self {
+        Info { title: "Rocket".into(), version: "0.0.0".into(), description: None }
+    }
+}
+
+/// Returns the key of `method` in an OpenAPI path item, if it has one.
+fn method_key(method: Method) -> Option<&'static str> {
+    match method {
+        Method::Get => Some("get"),
+        Method::Put => Some("put"),
+        Method::Post => Some("post"),
+        Method::Delete => Some("delete"),
+        Method::Options => Some("options"),
+        Method::Head => Some("head"),
+        Method::Patch => Some("patch"),
+        Method::Trace => Some("trace"),
+        _ => None,
+    }
+}
+
+/// Returns whether `path` is `base` or is under it, matched segment-wise.
+fn is_under(path: &str, base: &str) -> bool {
+    let base = base.trim_end_matches('/');
+    path.strip_prefix(base).is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+impl Document {
+    /// Collects the document for the routes and catchers of `rocket`.
+    pub(crate) fn collect<P: Phase>(rocket: &Rocket<P>) -> Self {
+        let info = rocket.fairing::<OpenApi>()
+            .map(|openapi| openapi.info.clone())
+            .unwrap_or_default();
+
+        // Sort so that IDs are deduplicated in a stable order and the route
+        // with the highest priority documents a path and method.
+        let mut routes: Vec<_> = rocket.routes().collect();
+        routes.sort_by(|a, b| {
+            let key = |r: &Route| (r.uri.path().as_str().to_owned(), r.method.as_str(), r.rank);
+            key(a).cmp(&key(b))
+        });
+
+        let catchers: Vec<_> = rocket.catchers().collect();
+        let mut paths: IndexMap<String, IndexMap<Method, Operation>> = IndexMap::new();
+        let mut ids = HashSet::new();
+        for route in routes {
+            let Some(ref op) = route.operation else { continue };
+            if method_key(route.method).is_none() {
+                continue;
+            }
+
+            let (path, params) = Self::template(route);
+            let ops = paths.entry(path).or_default();
+            if ops.contains_key(&route.method) {
+                continue;
+            }
+
+            let op = Self::complete(route, op.clone(), params, &catchers, &mut ids);
+            ops.insert(route.method, op);
+        }
+
+        paths.sort_keys();
+        let mut components = IndexMap::new();
+        let mut pending = vec![];
+        for op in paths.values().flat_map(|ops| ops.values()) {
+            let params = op.parameters.iter().filter_map(|p| p.schema.as_ref());
+            let body = op.request_body.iter().filter_map(|b| b.schema.as_ref());
+            let responses = op.responses.iter().filter_map(|r| r.schema.as_ref());
+            for schema in params.chain(body).chain(responses) {
+                push_refs(schema, &mut pending);
+            }
+        }
+
+        while let Some((name, schema)) = pending.pop() {
+            if !components.contains_key(&name) {
+                let schema = schema();
+                push_refs(&schema, &mut pending);
+                components.insert(name, schema);
+            }
+        }
+
+        components.sort_keys();
+        Document { info, paths, components }
+    }
+
+    /// Returns the OpenAPI path template for `route`, where `<param>` and
+    /// `<param..>` are `{param}`, and the names of its dynamic segments.
+    fn template(route: &Route) -> (String, Vec<String>) {
+        let mut params = vec![];
+        let segments = route.uri.path().as_str().split('/').map(|segment| {
+            let Some(name) = segment.strip_prefix('<').and_then(|s| s.strip_suffix('>')) else {
+                return segment.to_owned();
+            };
+
+            let name = name.trim_end_matches("..");
+            params.push(name.to_owned());
+            format!("{{{}}}", name)
+        });
+
+        (segments.collect::<Vec<_>>().join("/"), params)
+    }
+
+    /// Fills in the parts of `op` that are derived from `route` as mounted and
+    /// from the catchers that can handle its errors.
+    fn complete(
+        route: &Route,
+        mut op: Operation,
+        params: Vec<String>,
+        catchers: &[&Catcher],
+        ids: &mut HashSet<String>,
+    ) -> Operation {
+        for name in params {
+            let declared = op.parameters.iter()
+                .any(|p| p.location == Location::Path && p.name == name);
+
+            if !declared {
+                op.parameters.push(Parameter::path(name, Schema::String { format: None }));
+            }
+        }
+
+        let id = op.operation_id.take().or_else(|| route.name.clone());
+        if let Some(id) = id {
+            let mut unique = id.to_string();
+            for n in 2.. {
+                if ids.insert(unique.clone()) {
+                    break;
+                }
+
+                unique = format!("{id}_{n}");
+            }
+
+            op.operation_id = Some(unique.into());
+        }
+
+        if let Some(ref mut body) = op.request_body {
+            body.media_type = body.media_type.take().or_else(|| route.format.clone());
+        }
+
+        for response in &mut op.responses {
+            if response.schema.is_some() && response.media_type.is_none() {
+                response.media_type = route.format.clone();
+            }
+        }
+
+        let path = route.uri.path();
+        for catcher in catchers.iter().filter(|c| is_under(path.as_str(), c.base().as_str())) {
+            let status = catcher.code.map(Status::new);
+            if !op.responses.iter().any(|r| r.status == status) {
+                op.responses.push(Response { status, ..Response::new(Status::Ok) });
+            }
+        }
+
+        op
+    }
+
+    /// Renders the document as JSON.
+    ///
+    /// This is equivalent to `self.to_string()`.
+    pub fn to_json(&self) -> String {
+        let mut out = String::new();
+        out.push_str("{\"openapi\":\"3.1.0\",\"info\":{");
+        let _ = write!(out, "\"title\":\"{}\"", EscapeJson(&self.info.title));
+        let _ = write!(out, ",\"version\":\"{}\"", EscapeJson(&self.info.version));
+        if let Some(ref description) = self.info.description {
+            let _ = write!(out, ",\"description\":\"{}\"", EscapeJson(description));
+        }
+
+        out.push_str("},\"paths\":{");
+        for (i, (path, ops)) in self.paths.iter().enumerate() {
+            if i != 0 { out.push(','); }
+            let _ = write!(out, "\"{}\":{{", EscapeJson(path));
+            for (j, (method, op)) in ops.iter().enumerate() {
+                if j != 0 { out.push(','); }
+                let key = method_key(*method).unwrap_or("get");
+                let _ = write!(out, "\"{}\":", key);
+                write_operation(&mut out, op);
+            }
+
+            out.push('}');
+        }
+
+        out.push('}');
+        if !self.components.is_empty() {
+            out.push_str(",\"components\":{\"schemas\":{");
+            for (i, (name, schema)) in self.components.iter().enumerate() {
+                if i != 0 { out.push(','); }
+                let _ = write!(out, "\"{}\":", EscapeJson(name));
+                schema.write_json(&mut out, None);
+            }
+
+            out.push_str("}}");
+        }
+
+        out.push('}');
+        out
+    }
+}
+
+/// Pushes the name and schema of every reference in `schema` to `pending`.
+fn push_refs(schema: &Schema, pending: &mut Vec<(Cow<'static, str>, fn() -> Schema)>) {
+    schema.refs(&mut |name, schema| pending.push((name.to_owned().into(), schema)));
+}
+
+/// Writes `{"media/type":{"schema":..}}`, or `{"*/*":{}}`, to `out`.
+fn write_content(out: &mut String, media_type: Option<&MediaType>, schema: &Schema) {
+    match media_type {
+        Some(media_type) => {
+            let _ = write!(out, "{{\"{}\":{{\"schema\":", EscapeJson(&media_type.to_string()));
+        }
+        None => out.push_str("{\"*/*\":{\"schema\":"),
+    }
+
+    schema.write_json(out, None);
+    out.push_str("}}");
+}
+
+fn write_operation(out: &mut String, op: &Operation) {
+    out.push('{');
+    let mut fields = 0;
+    let mut field = |out: &mut String, name: &str| {
+        if fields != 0 { out.push(','); }
+        let _ = write!(out, "\"{}\":", name);
+        fields += 1;
+    };
+
+    if let Some(ref id) = op.operation_id {
+        field(out, "operationId");
+        let _ = write!(out, "\"{}\"", EscapeJson(id));
+    }
+
+    if let Some(ref summary) = op.summary {
+        field(out, "summary");
+        let _ = write!(out, "\"{}\"", EscapeJson(summary));
+    }
+
+    if let Some(ref description) = op.description {
+        field(out, "description");
+        let _ = write!(out, "\"{}\"", EscapeJson(description));
+    }
+
+    if !op.tags.is_empty() {
+        field(out, "tags");
+        out.push('[');
+        for (i, tag) in op.tags.iter().enumerate() {
+            if i != 0 { out.push(','); }
+            let _ = write!(out, "\"{}\"", EscapeJson(tag));
+        }
+
+        out.push(']');
+    }
+
+    if op.deprecated {
+        field(out, "deprecated");
+        out.push_str("true");
+    }
+
+    if !op.parameters.is_empty() {
+        field(out, "parameters");
+        out.push('[');
+        for (i, param) in op.parameters.iter().enumerate() {
+            if i != 0 { out.push(','); }
+            let location = match param.location {
+                Location::Path => "path",
+                Location::Query => "query",
+            };
+
+            let _ = write!(out, "{{\"name\":\"{}\",\"in\":\"{}\",\"required\":{}",
+                EscapeJson(&param.name), location, param.required);
+
+            if let Some(ref schema) = param.schema {
+                out.push_str(",\"schema\":");
+                schema.write_json(out, None);
+            }
+
+            out.push('}');
+        }
+
+        out.push(']');
+    }
+
+    if let Some(ref body) = op.request_body {
+        field(out, "requestBody");
+        out.push_str("{\"content\":");
+        let schema = body.schema.as_ref().unwrap_or(&Schema::Any);
+        write_content(out, body.media_type.as_ref(), schema);
+        let _ = write!(out, ",\"required\":{}}}", body.required);
+    }
+
+    field(out, "responses");
+    out.push('{');
+    for (i, response) in op.responses.iter().enumerate() {
+        if i != 0 { out.push(','); }
+        let description = response.description.as_deref()
+            .or_else(|| response.status.and_then(|s| s.reason()))
+            .unwrap_or("Error");
+
+        match response.status {
+            Some(status) => { let _ = write!(out, "\"{}\":", status.code); }
+            None => out.push_str("\"default\":"),
+        }
+
+        let _ = write!(out, "{{\"description\":\"{}\"", EscapeJson(description));
+        if let Some(ref schema) = response.schema {
+            out.push_str(",\"content\":");
+            write_content(out, response.media_type.as_ref(), schema);
+        }
+
+        out.push('}');
+    }
+
+    out.push_str("}}");
+}
+
+impl fmt::Display for Document {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_json())
+    }
+}
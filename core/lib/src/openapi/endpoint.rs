@@ -0,0 +1,117 @@
+use std::borrow::Cow;
+
+use crate::{Rocket, Request, Data, Build, Route};
+use crate::fairing::{self, Fairing, Kind};
+use crate::http::{Method, ContentType};
+use crate::openapi::Info;
+use crate::route::{Handler, Outcome};
+
+/// A [`Fairing`] that describes the API and serves its [OpenAPI
+/// document](crate::openapi).
+///
+/// At ignition, the fairing mounts an endpoint, at [`OpenApi::PATH`] unless
+/// changed via [`OpenApi::path()`], that responds to `GET` requests with the
+/// document as JSON. The document is collected for every request, so routes
+/// mounted or unmounted while running are reflected. The title, version, and
+/// description set on the fairing are also used by [`Rocket::openapi()`].
+///
+/// If the path doesn't begin with `/`, ignition fails.
+///
+/// [`Rocket::openapi()`]: crate::Rocket::openapi()
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::openapi::OpenApi;
+/// use rocket::local::blocking::Client;
+///
+/// let openapi = OpenApi::new("Tasks", "1.0").description("Manages tasks.");
+/// let client = Client::debug(rocket::build().attach(openapi)).unwrap();
+///
+/// let document = client.rocket().openapi();
+/// assert_eq!(document.info.title, "Tasks");
+///
+/// let response = client.get(OpenApi::PATH).dispatch();
+/// assert!(response.into_string().unwrap().contains(r#""title":"Tasks""#));
+/// ```
+#[derive(Clone)]
+pub struct OpenApi {
+    pub(crate) info: Info,
+    path: Cow<'static, str>,
+}
+
+impl OpenApi {
+    /// The default path at which the document is served.
+    pub const PATH: &'static str = "/openapi.json";
+
+    /// Returns an `OpenApi` fairing describing the API titled `title` at
+    /// version `version`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::openapi::OpenApi;
+    ///
+    /// let openapi = OpenApi::new("Tasks", "1.0");
+    /// ```
+    pub fn new<T, V>(title: T, version: V) -> Self
+        where T: Into<Cow<'static, str>>, V: Into<Cow<'static, str>>
+    {
+        let info = Info { title: title.into(), version: version.into(), description: None };
+        OpenApi { info, path: Self::PATH.into() }
+    }
+
+    /// Sets the description of the API to `description`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::openapi::OpenApi;
+    ///
+    /// let openapi = OpenApi::new("Tasks", "1.0").description("Manages tasks.");
+    /// ```
+    pub fn description<D: Into<Cow<'static, str>>>(mut self, description: D) -> Self {
+        self.info.description = Some(description.into());
+        self
+    }
+
+    /// Sets the path at which the document is served to `path`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::openapi::OpenApi;
+    ///
+    /// let openapi = OpenApi::new("Tasks", "1.0").path("/api/openapi.json");
+    /// ```
+    pub fn path<P: Into<Cow<'static, str>>>(mut self, path: P) -> Self {
+        self.path = path.into();
+        self
+    }
+}
+
+#[crate::async_trait]
+impl Fairing for OpenApi {
+    fn info(&self) -> fairing::Info {
+        fairing::Info { name: "OpenAPI", kind: Kind::Ignite | Kind::Singleton }
+    }
+
+    async fn on_ignite(&self, rocket: Rocket<Build>) -> fairing::Result {
+        if !self.path.starts_with('/') {
+            error!(name: "openapi", path = %self.path, "OpenAPI path must begin with `/`");
+            return Err(rocket);
+        }
+
+        info!(name: "openapi", path = %self.path, "serving OpenAPI document");
+        let route = Route::new(Method::Get, "/", self.clone());
+        Ok(rocket.mount(&*self.path, vec![route]))
+    }
+}
+
+#[crate::async_trait]
+impl Handler for OpenApi {
+    async fn handle<'r>(&self, req: &'r Request<'_>, _: Data<'r>) -> Outcome<'r> {
+        let body = req.rocket().openapi().to_json();
+        Outcome::from(req, (ContentType::JSON, body))
+    }
+}
@@ -0,0 +1,207 @@
+//! OpenAPI 3.1 documents generated from routes.
+//!
+//! Rocket can describe the routes mounted on an instance as an [OpenAPI 3.1]
+//! document. The route attributes record an [`Operation`] for every route they
+//! generate, documenting it by the handler's doc comment and signature:
+//!
+//!   * The first paragraph of the doc comment is the summary, and the rest is
+//!     the description. A `#[deprecated]` handler is marked deprecated.
+//!   * Path and query guards are parameters.
+//!   * The data guard is the request body.
+//!   * The return type is a `200 OK` response, along with a `404 Not Found`
+//!     response if it's an `Option`.
+//!
+//! Guards and return types are described by their [`ToSchema`] implementation,
+//! if they have one. `ToSchema` is implemented for primitives, strings,
+//! collections, and the [`Form`](crate::form::Form) and
+//! [`Json`](crate::serde::json::Json) wrappers, among others, and can be
+//! derived alongside `FromForm` or `Deserialize`. Types without an
+//! implementation are documented without a schema. Request guards aren't
+//! documented.
+//!
+//! With the `openapi` feature enabled, [`Rocket::openapi()`] collects the
+//! document from the routes and catchers of an instance, resolving path
+//! templates, naming operations after their routes, and adding a response for
+//! every catcher registered at or above a route's path. The [`OpenApi`] fairing
+//! sets the title and version of the API and serves the document at
+//! `/openapi.json`.
+//!
+//! [OpenAPI 3.1]: https://spec.openapis.org/oas/v3.1.0
+//! [`Rocket::openapi()`]: crate::Rocket::openapi()
+//!
+//! # Attribute
+//!
+//! The `#[openapi]` attribute, applied to a route handler alongside its route
+//! attribute, adjusts the route's operation:
+//!
+//!   * `skip` leaves the route out of the document.
+//!   * `tag = "name"` adds a tag. It may be repeated.
+//!   * `operation_id = "id"` sets the operation's ID in place of the route's
+//!     name.
+//!
+//! # Example
+//!
+//! ```rust
+//! # #[macro_use] extern crate rocket;
+//! # #[cfg(feature = "openapi")] mod wrapper {
+//! use rocket::form::Form;
+//! use rocket::openapi::{OpenApi, ToSchema};
+//!
+//! /// A task on the list.
+//! #[derive(FromForm, ToSchema)]
+//! struct Task {
+//!     /// What needs to be done.
+//!     description: String,
+//!     complete: bool,
+//! }
+//!
+//! /// Returns the task with ID `id`.
+//! #[get("/tasks/<id>")]
+//! #[openapi(tag = "tasks")]
+//! fn task(id: usize) -> Option<String> {
+//!     None
+//! }
+//!
+//! /// Creates a task.
+//! ///
+//! /// The task is added to the end of the list.
+//! #[post("/tasks", data = "<task>")]
+//! #[openapi(tag = "tasks")]
+//! fn create(task: Form<Task>) -> String {
+//!     task.description.clone()
+//! }
+//!
+//! #[launch]
+//! fn rocket() -> _ {
+//!     rocket::build()
+//!         .mount("/", routes![task, create])
+//!         .attach(OpenApi::new("Tasks", "1.0"))
+//! }
+//! # } // end of cfg wrapper
+//! ```
+
+mod schema;
+mod operation;
+#[cfg(feature = "openapi")]
+mod document;
+#[cfg(feature = "openapi")]
+mod endpoint;
+
+pub use schema::{ToSchema, Schema, Property};
+pub use operation::{Operation, Parameter, Location, Body, Response};
+#[cfg(feature = "openapi")]
+#[cfg_attr(nightly, doc(cfg(feature = "openapi")))]
+pub use document::{Document, Info};
+#[cfg(feature = "openapi")]
+#[cfg_attr(nightly, doc(cfg(feature = "openapi")))]
+pub use endpoint::OpenApi;
+
+#[doc(inline)]
+pub use rocket_codegen::ToSchema;
+
+/// Resolves a `T` to its `ToSchema` implementation, if it has one, returning a
+/// `Resolved` struct with the resolved items.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! resolve_schema {
+    ($T:ty) => ({
+        #[allow(unused_imports)]
+        use $crate::openapi::resolution::{Resolve, DefaultSchema as _};
+
+        $crate::openapi::resolution::Resolved {
+            schema: Resolve::<$T>::schema(),
+            required: Resolve::<$T>::required(),
+            media_type: Resolve::<$T>::media_type(),
+        }
+    })
+}
+
+#[doc(hidden)]
+pub use resolve_schema;
+
+#[doc(hidden)]
+pub mod resolution {
+    use std::borrow::Cow;
+
+    use super::*;
+    use crate::http::{MediaType, Status};
+
+    /// The *magic*, as in [`crate::sentinel::resolution`].
+    ///
+    /// `Resolve<T>::item` for `T: ToSchema` is derived from `<T as ToSchema>`.
+    /// `Resolve<T>::item` for `T: !ToSchema` is `DefaultSchema::item`.
+    pub struct Resolve<T: ?Sized>(std::marker::PhantomData<T>);
+
+    /// Fallback trait "implementing" `ToSchema` for all types. This is what
+    /// Rust will resolve `Resolve<T>::item` to when `T: !ToSchema`.
+    pub trait DefaultSchema {
+        fn schema() -> Option<Schema> { None }
+
+        fn required() -> bool { true }
+
+        fn media_type() -> Option<MediaType> { None }
+    }
+
+    impl<T: ?Sized> DefaultSchema for T {}
+
+    /// "Specialized" "implementation" of `ToSchema` for `T: ToSchema`. This is
+    /// what Rust will resolve `Resolve<T>::item` to when `T: ToSchema`.
+    impl<T: ToSchema + ?Sized> Resolve<T> {
+        pub fn schema() -> Option<Schema> {
+            Some(Schema::of::<T>())
+        }
+
+        pub fn required() -> bool {
+            T::required()
+        }
+
+        pub fn media_type() -> Option<MediaType> {
+            T::media_type()
+        }
+    }
+
+    /// The items of a type's `ToSchema` implementation, if it has one.
+    pub struct Resolved {
+        pub schema: Option<Schema>,
+        pub required: bool,
+        pub media_type: Option<MediaType>,
+    }
+
+    impl Resolved {
+        /// The items of a type that can't be resolved, such as a generic one.
+        pub fn unknown() -> Self {
+            Resolved { schema: None, required: true, media_type: None }
+        }
+
+        pub fn into_parameter(self, name: &'static str, location: Location) -> Parameter {
+            let required = self.required || location == Location::Path;
+            Parameter { name: name.into(), location, required, schema: self.schema }
+        }
+
+        pub fn into_body(self) -> Body {
+            Body { media_type: self.media_type, schema: self.schema, required: self.required }
+        }
+
+        pub fn into_responses(self) -> impl Iterator<Item = Response> {
+            let not_found = (!self.required).then(|| Response::new(Status::NotFound));
+            let ok = Response {
+                status: Some(Status::Ok),
+                description: None,
+                media_type: self.media_type,
+                schema: self.schema,
+            };
+
+            std::iter::once(ok).chain(not_found)
+        }
+
+        pub fn into_property(self, name: &'static str, docs: &'static str) -> Property {
+            let docs = docs.trim();
+            Property {
+                name: name.into(),
+                schema: self.schema.unwrap_or(Schema::Any),
+                required: self.required,
+                description: (!docs.is_empty()).then_some(Cow::Borrowed(docs)),
+            }
+        }
+    }
+}
@@ -0,0 +1,214 @@
+use std::borrow::Cow;
+
+use crate::http::{MediaType, Status};
+use crate::openapi::Schema;
+
+/// The documentation of a route: an OpenAPI [operation].
+///
+/// The route attributes record an `Operation` in [`Route::operation`] for
+/// every route they generate. The summary and description are taken from the
+/// handler's doc comment, the parameters from its path and query guards, the
+/// request body from its data guard, and the responses from its return type.
+/// Routes created manually can be documented by setting the field:
+///
+/// ```rust
+/// use rocket::Route;
+/// use rocket::http::{Method, Status};
+/// use rocket::openapi::{Operation, Parameter, Response, Schema};
+/// # use rocket::route::dummy_handler as handler;
+///
+/// let mut route = Route::new(Method::Get, "/users/<id>", handler);
+/// route.operation = Some(Operation::new("Returns a user.")
+///     .parameter(Parameter::path("id", Schema::String { format: None }))
+///     .response(Response::new(Status::Ok))
+///     .response(Response::new(Status::NotFound)));
+/// ```
+///
+/// Routes without an operation, such as those mounted by fairings, are left
+/// out of the document.
+///
+/// [operation]: https://spec.openapis.org/oas/v3.1.0#operation-object
+/// [`Route::operation`]: crate::Route::operation
+#[derive(Debug, Clone, Default)]
+pub struct Operation {
+    /// A short summary: the first paragraph of the handler's doc comment.
+    pub summary: Option<Cow<'static, str>>,
+    /// A longer description: the rest of the handler's doc comment.
+    pub description: Option<Cow<'static, str>>,
+    /// The operation's ID. Defaults to the route's name.
+    pub operation_id: Option<Cow<'static, str>>,
+    /// The tags grouping the operation, set via `#[openapi(tag = "..")]`.
+    pub tags: Vec<Cow<'static, str>>,
+    /// Whether the operation is deprecated: whether the handler is marked
+    /// `#[deprecated]`.
+    pub deprecated: bool,
+    /// The operation's path and query parameters.
+    pub parameters: Vec<Parameter>,
+    /// The request body, if the route has a data guard.
+    pub request_body: Option<Body>,
+    /// The operation's responses.
+    pub responses: Vec<Response>,
+}
+
+/// Where a [`Parameter`] is found in a request.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Location {
+    /// A path segment.
+    Path,
+    /// A query parameter.
+    Query,
+}
+
+/// A path or query parameter of an [`Operation`].
+#[derive(Debug, Clone)]
+pub struct Parameter {
+    /// The parameter's name.
+    pub name: Cow<'static, str>,
+    /// Where the parameter is found.
+    pub location: Location,
+    /// Whether the parameter must be present. Path parameters are always
+    /// required.
+    pub required: bool,
+    /// The parameter's schema, if known.
+    pub schema: Option<Schema>,
+}
+
+/// The request body of an [`Operation`].
+#[derive(Debug, Clone)]
+pub struct Body {
+    /// The body's media type, if known. Defaults to the route's format.
+    pub media_type: Option<MediaType>,
+    /// The body's schema, if known.
+    pub schema: Option<Schema>,
+    /// Whether the body must be present.
+    pub required: bool,
+}
+
+/// A response of an [`Operation`].
+#[derive(Debug, Clone)]
+pub struct Response {
+    /// The response's status, or `None` for all other statuses.
+    pub status: Option<Status>,
+    /// The response's description. Defaults to the status's reason phrase.
+    pub description: Option<Cow<'static, str>>,
+    /// The body's media type, if known. Defaults to the route's format.
+    pub media_type: Option<MediaType>,
+    /// The body's schema, if known.
+    pub schema: Option<Schema>,
+}
+
+impl Operation {
+    /// Returns an operation documented by `docs`: the first paragraph is the
+    /// summary, and the rest is the description.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::openapi::Operation;
+    ///
+    /// let op = Operation::new("Returns a user.\n\nThe user must exist.");
+    /// assert_eq!(op.summary.as_deref(), Some("Returns a user."));
+    /// assert_eq!(op.description.as_deref(), Some("The user must exist."));
+    ///
+    /// let op = Operation::new("");
+    /// assert!(op.summary.is_none() && op.description.is_none());
+    /// ```
+    pub fn new(docs: &str) -> Self {
+        let docs = docs.trim();
+        let (summary, description) = docs.split_once("\n\n").unwrap_or((docs, ""));
+        let summary = summary.lines().map(str::trim).collect::<Vec<_>>().join(" ");
+        let description = description.trim();
+
+        Operation {
+            summary: (!summary.is_empty()).then(|| summary.into()),
+            description: (!description.is_empty()).then(|| description.to_owned().into()),
+            ..Default::default()
+        }
+    }
+
+    /// Sets the operation's ID to `id`.
+    pub fn operation_id<S: Into<Cow<'static, str>>>(mut self, id: S) -> Self {
+        self.operation_id = Some(id.into());
+        self
+    }
+
+    /// Adds the tag `tag`.
+    pub fn tag<S: Into<Cow<'static, str>>>(mut self, tag: S) -> Self {
+        self.tags.push(tag.into());
+        self
+    }
+
+    /// Marks the operation as deprecated.
+    pub fn deprecated(mut self) -> Self {
+        self.deprecated = true;
+        self
+    }
+
+    /// Adds the parameter `parameter`.
+    pub fn parameter(mut self, parameter: Parameter) -> Self {
+        self.parameters.push(parameter);
+        self
+    }
+
+    /// Sets the request body to `body`.
+    pub fn request_body(mut self, body: Body) -> Self {
+        self.request_body = Some(body);
+        self
+    }
+
+    /// Adds the response `response`.
+    pub fn response(mut self, response: Response) -> Self {
+        self.responses.push(response);
+        self
+    }
+}
+
+impl Parameter {
+    /// Returns a required path parameter named `name` described by `schema`.
+    pub fn path<S: Into<Cow<'static, str>>>(name: S, schema: Schema) -> Self {
+        Parameter {
+            name: name.into(),
+            location: Location::Path,
+            required: true,
+            schema: Some(schema),
+        }
+    }
+
+    /// Returns a query parameter named `name` described by `schema`.
+    pub fn query<S: Into<Cow<'static, str>>>(name: S, schema: Schema, required: bool) -> Self {
+        Parameter {
+            name: name.into(),
+            location: Location::Query,
+            schema: Some(schema),
+            required,
+        }
+    }
+}
+
+impl Body {
+    /// Returns a required body of type `media_type` described by `schema`.
+    pub fn new(media_type: MediaType, schema: Schema) -> Self {
+        Body { media_type: Some(media_type), schema: Some(schema), required: true }
+    }
+}
+
+impl Response {
+    /// Returns a response with status `status` and no body.
+    pub fn new(status: Status) -> Self {
+        Response { status: Some(status), description: None, media_type: None, schema: None }
+    }
+
+    /// Sets the response's description to `description`.
+    pub fn description<S: Into<Cow<'static, str>>>(mut self, description: S) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Sets the response's body to one of type `media_type` described by
+    /// `schema`.
+    pub fn body(mut self, media_type: MediaType, schema: Schema) -> Self {
+        self.media_type = Some(media_type);
+        self.schema = Some(schema);
+        self
+    }
+}
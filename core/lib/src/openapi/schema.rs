@@ -0,0 +1,476 @@
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+#[cfg(feature = "openapi")]
+use std::fmt::Write;
+use std::path::PathBuf;
+
+use crate::http::MediaType;
+#[cfg(feature = "openapi")]
+use crate::util::EscapeJson;
+
+/// A JSON schema describing a parameter, request body, or response body.
+///
+/// Schemas are produced by [`ToSchema`] implementations and rendered as the
+/// [JSON Schema] subset used by OpenAPI 3.1. Named types are described by a
+/// [`Schema::Ref`], which is rendered as a reference to an entry in the
+/// document's `components`.
+///
+/// [JSON Schema]: https://json-schema.org/
+#[derive(Debug, Clone)]
+pub enum Schema {
+    /// Any value.
+    Any,
+    /// A boolean.
+    Boolean,
+    /// An integer with an optional `format`, such as `int32`, and minimum.
+    Integer { format: Option<&'static str>, minimum: Option<i64> },
+    /// A number with an optional `format`, such as `double`.
+    Number { format: Option<&'static str> },
+    /// A string with an optional `format`, such as `uuid` or `date`.
+    String { format: Option<&'static str> },
+    /// A string that is one of the listed values.
+    Enum(Vec<Cow<'static, str>>),
+    /// An array whose items match the schema.
+    Array(Box<Schema>),
+    /// An object whose values match the schema, keyed by any string.
+    Map(Box<Schema>),
+    /// An object with the listed properties.
+    Object(Vec<Property>),
+    /// A reference to the named schema returned by `schema`.
+    Ref { name: Cow<'static, str>, schema: fn() -> Schema },
+}
+
+/// A property of a [`Schema::Object`].
+#[derive(Debug, Clone)]
+pub struct Property {
+    /// The name of the property.
+    pub name: Cow<'static, str>,
+    /// The schema of the property's value.
+    pub schema: Schema,
+    /// Whether the property must be present.
+    pub required: bool,
+    /// A description of the property, if any.
+    pub description: Option<Cow<'static, str>>,
+}
+
+/// Trait implemented by types that can be described by a [`Schema`].
+///
+/// The schema of a route's parameters, data guard, and return type is
+/// resolved from their `ToSchema` implementations by the route attribute.
+/// Types that don't implement `ToSchema` are documented without a schema.
+///
+/// # Deriving
+///
+/// `ToSchema` can be derived for structs with named fields and for enums
+/// whose variants have no fields. A struct is described by an object with a
+/// property for every field, documented by the field's doc comment. A field is
+/// required unless its type is an `Option`. An enum is described by a string
+/// that is the name of one of its variants. The property name of a field can
+/// be changed via `#[schema(name = "...")]`, as when it's renamed for `serde`:
+///
+/// ```rust
+/// use rocket::openapi::{ToSchema, Schema};
+///
+/// #[derive(ToSchema)]
+/// struct Task {
+///     /// What needs to be done.
+///     description: String,
+///     #[schema(name = "done")]
+///     complete: bool,
+///     due: Option<String>,
+/// }
+///
+/// #[derive(ToSchema)]
+/// enum Priority {
+///     Low,
+///     High,
+/// }
+///
+/// assert_eq!(Task::name().as_deref(), Some("Task"));
+/// assert!(matches!(Task::schema(), Schema::Object(props) if props.len() == 3));
+/// assert!(matches!(Priority::schema(), Schema::Enum(names) if names == ["Low", "High"]));
+/// ```
+///
+/// A type without generic type parameters is named after its identifier and
+/// described in the document's `components`. Generic types are described
+/// inline instead.
+///
+/// # Implementing
+///
+/// Only [`ToSchema::schema()`] is required. Wrappers that change how a value
+/// is transmitted, such as [`Json`](crate::serde::json::Json), return their
+/// media type from [`ToSchema::media_type()`]. Types that may be absent, such
+/// as `Option<T>`, return `false` from [`ToSchema::required()`]:
+///
+/// ```rust
+/// use rocket::openapi::{ToSchema, Schema};
+///
+/// struct Celsius(f64);
+///
+/// impl ToSchema for Celsius {
+///     fn schema() -> Schema {
+///         Schema::Number { format: Some("double") }
+///     }
+/// }
+/// ```
+pub trait ToSchema {
+    /// Returns the schema describing `Self`.
+    fn schema() -> Schema;
+
+    /// Returns the name under which the schema is listed in the document's
+    /// `components`, if any. The default returns `None`, describing `Self`
+    /// inline wherever it's used.
+    fn name() -> Option<Cow<'static, str>> {
+        None
+    }
+
+    /// Returns the media type `Self` is transmitted as when it's a request or
+    /// response body, if it's known. The default returns `None`.
+    fn media_type() -> Option<MediaType> {
+        None
+    }
+
+    /// Returns whether a value of `Self` is always present. The default
+    /// returns `true`.
+    fn required() -> bool {
+        true
+    }
+}
+
+impl Schema {
+    /// Returns the schema for `T`: a [`Schema::Ref`] if `T` is named, and
+    /// `T`'s schema otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::openapi::{ToSchema, Schema};
+    ///
+    /// assert!(matches!(Schema::of::<Vec<bool>>(), Schema::Array(_)));
+    /// ```
+    pub fn of<T: ToSchema + ?Sized>() -> Schema {
+        match T::name() {
+            Some(name) => Schema::Ref { name, schema: T::schema },
+            None => T::schema(),
+        }
+    }
+
+    /// Calls `f` with the name and schema of every reference in `self`,
+    /// without following the references themselves.
+    #[cfg(feature = "openapi")]
+    pub(crate) fn refs<'a>(&'a self, f: &mut dyn FnMut(&'a str, fn() -> Schema)) {
+        match self {
+            Schema::Array(schema) | Schema::Map(schema) => schema.refs(f),
+            Schema::Object(props) => props.iter().for_each(|p| p.schema.refs(f)),
+            Schema::Ref { name, schema } => f(&**name, *schema),
+            _ => {}
+        }
+    }
+
+    /// Writes `self` as a JSON object to `out`, with a `description`, if any.
+    #[cfg(feature = "openapi")]
+    pub(crate) fn write_json(&self, out: &mut String, description: Option<&str>) {
+        let start = out.len();
+        out.push('{');
+        match self {
+            Schema::Any => {}
+            Schema::Boolean => out.push_str("\"type\":\"boolean\""),
+            Schema::Integer { format, minimum } => {
+                out.push_str("\"type\":\"integer\"");
+                if let Some(format) = format {
+                    let _ = write!(out, ",\"format\":\"{}\"", format);
+                }
+
+                if let Some(minimum) = minimum {
+                    let _ = write!(out, ",\"minimum\":{}", minimum);
+                }
+            }
+            Schema::Number { format } => {
+                out.push_str("\"type\":\"number\"");
+                if let Some(format) = format {
+                    let _ = write!(out, ",\"format\":\"{}\"", format);
+                }
+            }
+            Schema::String { format } => {
+                out.push_str("\"type\":\"string\"");
+                if let Some(format) = format {
+                    let _ = write!(out, ",\"format\":\"{}\"", format);
+                }
+            }
+            Schema::Enum(values) => {
+                out.push_str("\"type\":\"string\",\"enum\":[");
+                for (i, value) in values.iter().enumerate() {
+                    if i != 0 { out.push(','); }
+                    let _ = write!(out, "\"{}\"", EscapeJson(value));
+                }
+
+                out.push(']');
+            }
+            Schema::Array(items) => {
+                out.push_str("\"type\":\"array\",\"items\":");
+                items.write_json(out, None);
+            }
+            Schema::Map(values) => {
+                out.push_str("\"type\":\"object\",\"additionalProperties\":");
+                values.write_json(out, None);
+            }
+            Schema::Object(props) => {
+                out.push_str("\"type\":\"object\",\"properties\":{");
+                for (i, prop) in props.iter().enumerate() {
+                    if i != 0 { out.push(','); }
+                    let _ = write!(out, "\"{}\":", EscapeJson(&prop.name));
+                    prop.schema.write_json(out, prop.description.as_deref());
+                }
+
+                out.push('}');
+                let mut required = props.iter().filter(|p| p.required).peekable();
+                if required.peek().is_some() {
+                    out.push_str(",\"required\":[");
+                    for (i, prop) in required.enumerate() {
+                        if i != 0 { out.push(','); }
+                        let _ = write!(out, "\"{}\"", EscapeJson(&prop.name));
+                    }
+
+                    out.push(']');
+                }
+            }
+            Schema::Ref { name, .. } => {
+                let _ = write!(out, "\"$ref\":\"#/components/schemas/{}\"", EscapeJson(name));
+            }
+        }
+
+        if let Some(description) = description {
+            if out.len() != start + 1 { out.push(','); }
+            let _ = write!(out, "\"description\":\"{}\"", EscapeJson(description));
+        }
+
+        out.push('}');
+    }
+}
+
+macro_rules! impl_for_integers {
+    ($($T:ty => $format:expr, $minimum:expr),* $(,)?) => ($(
+        impl ToSchema for $T {
+            fn schema() -> Schema {
+                Schema::Integer { format: $format, minimum: $minimum }
+            }
+        }
+    )*)
+}
+
+impl_for_integers! {
+    i8 => Some("int32"), None,
+    i16 => Some("int32"), None,
+    i32 => Some("int32"), None,
+    i64 => Some("int64"), None,
+    isize => Some("int64"), None,
+    i128 => None, None,
+    u8 => Some("int32"), Some(0),
+    u16 => Some("int32"), Some(0),
+    u32 => Some("int64"), Some(0),
+    u64 => None, Some(0),
+    usize => None, Some(0),
+    u128 => None, Some(0),
+}
+
+impl ToSchema for bool {
+    fn schema() -> Schema {
+        Schema::Boolean
+    }
+}
+
+impl ToSchema for f32 {
+    fn schema() -> Schema {
+        Schema::Number { format: Some("float") }
+    }
+}
+
+impl ToSchema for f64 {
+    fn schema() -> Schema {
+        Schema::Number { format: Some("double") }
+    }
+}
+
+impl ToSchema for char {
+    fn schema() -> Schema {
+        Schema::String { format: None }
+    }
+}
+
+impl ToSchema for str {
+    fn schema() -> Schema {
+        Schema::String { format: None }
+    }
+
+    fn media_type() -> Option<MediaType> {
+        Some(MediaType::Plain)
+    }
+}
+
+impl ToSchema for String {
+    fn schema() -> Schema {
+        str::schema()
+    }
+
+    fn media_type() -> Option<MediaType> {
+        str::media_type()
+    }
+}
+
+impl ToSchema for Cow<'_, str> {
+    fn schema() -> Schema {
+        str::schema()
+    }
+
+    fn media_type() -> Option<MediaType> {
+        str::media_type()
+    }
+}
+
+impl ToSchema for PathBuf {
+    fn schema() -> Schema {
+        Schema::String { format: None }
+    }
+}
+
+impl ToSchema for time::Date {
+    fn schema() -> Schema {
+        Schema::String { format: Some("date") }
+    }
+}
+
+impl ToSchema for time::Time {
+    fn schema() -> Schema {
+        Schema::String { format: Some("time") }
+    }
+}
+
+impl ToSchema for time::PrimitiveDateTime {
+    fn schema() -> Schema {
+        Schema::String { format: Some("date-time") }
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl ToSchema for crate::serde::uuid::Uuid {
+    fn schema() -> Schema {
+        Schema::String { format: Some("uuid") }
+    }
+}
+
+impl ToSchema for crate::fs::TempFile<'_> {
+    fn schema() -> Schema {
+        Schema::String { format: Some("binary") }
+    }
+}
+
+impl<T: ToSchema> ToSchema for Vec<T> {
+    fn schema() -> Schema {
+        Schema::Array(Box::new(Schema::of::<T>()))
+    }
+}
+
+impl<T: ToSchema> ToSchema for [T] {
+    fn schema() -> Schema {
+        Schema::Array(Box::new(Schema::of::<T>()))
+    }
+}
+
+impl<K, V: ToSchema, S> ToSchema for HashMap<K, V, S> {
+    fn schema() -> Schema {
+        Schema::Map(Box::new(Schema::of::<V>()))
+    }
+}
+
+impl<K, V: ToSchema> ToSchema for BTreeMap<K, V> {
+    fn schema() -> Schema {
+        Schema::Map(Box::new(Schema::of::<V>()))
+    }
+}
+
+impl<T: ToSchema> ToSchema for Option<T> {
+    fn schema() -> Schema {
+        Schema::of::<T>()
+    }
+
+    fn media_type() -> Option<MediaType> {
+        T::media_type()
+    }
+
+    fn required() -> bool {
+        false
+    }
+}
+
+/// Delegates to `T`, documenting the schema of `T` for both successes and
+/// errors: the schema of `E` isn't known to describe a body.
+impl<T: ToSchema, E> ToSchema for Result<T, E> {
+    fn schema() -> Schema {
+        Schema::of::<T>()
+    }
+
+    fn media_type() -> Option<MediaType> {
+        T::media_type()
+    }
+
+    fn required() -> bool {
+        T::required()
+    }
+}
+
+macro_rules! impl_for_wrappers {
+    ($([$($bound:tt)*] $W:ty),* $(,)?) => ($(
+        impl<T: ToSchema + $($bound)*> ToSchema for $W {
+            fn schema() -> Schema {
+                Schema::of::<T>()
+            }
+
+            fn media_type() -> Option<MediaType> {
+                T::media_type()
+            }
+
+            fn required() -> bool {
+                T::required()
+            }
+        }
+    )*)
+}
+
+impl_for_wrappers! {
+    [?Sized] &T,
+    [?Sized] Box<T>,
+    [] crate::form::Strict<T>,
+    [] crate::form::Lenient<T>,
+}
+
+impl<T: ToSchema> ToSchema for crate::form::Form<T> {
+    fn schema() -> Schema {
+        Schema::of::<T>()
+    }
+
+    fn media_type() -> Option<MediaType> {
+        Some(MediaType::Form)
+    }
+}
+
+#[cfg(feature = "json")]
+impl<T: ToSchema> ToSchema for crate::serde::json::Json<T> {
+    fn schema() -> Schema {
+        Schema::of::<T>()
+    }
+
+    fn media_type() -> Option<MediaType> {
+        Some(MediaType::JSON)
+    }
+}
+
+#[cfg(feature = "msgpack")]
+impl<T: ToSchema> ToSchema for crate::serde::msgpack::MsgPack<T> {
+    fn schema() -> Schema {
+        Schema::of::<T>()
+    }
+
+    fn media_type() -> Option<MediaType> {
+        Some(MediaType::MsgPack)
+    }
+}
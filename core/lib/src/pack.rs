@@ -0,0 +1,326 @@
+//! Mountable units of routes, catchers, fairings, and configuration.
+//!
+//! A [`RoutePack`] bundles everything a library crate needs to export a
+//! complete feature, such as billing or an admin panel, that an application
+//! mounts with a single call to [`Rocket::attach_pack()`]:
+//!
+//!   * **Routes and catchers**, mounted and registered at the base the
+//!     application chooses, isolating the pack in its own namespace.
+//!   * **Fairings**, attached along with the pack.
+//!   * **Configuration**, extracted at ignition from the pack's own section,
+//!     `packs.<name>`, as a [`RoutePack::Config`], and managed as a
+//!     [`PackConfig`], available to the pack's handlers.
+//!   * **Required state**, managed state the application must provide.
+//!   * **Provided state**, managed state the pack provides, which sentinel
+//!     [diagnostics](crate::sentinel::Diagnostic) name the pack as the
+//!     provider of.
+//!
+//! Ignition fails if two attached packs share a name or a type, if their
+//! bases overlap, if the pack's configuration section is invalid, or if
+//! required state is missing. Required state must be managed before ignition
+//! or by fairings attached before the pack.
+//!
+//! # Configuration
+//!
+//! A pack named `billing` is configured via the `packs.billing` configuration
+//! parameter. If it is not set, the default [`RoutePack::Config`] is used.
+//!
+//! ```toml
+//! [default.packs.billing]
+//! currency = "EUR"
+//! ```
+//!
+//! # Example
+//!
+//! ```rust
+//! # #[macro_use] extern crate rocket;
+//! use rocket::{Route, State};
+//! use rocket::pack::{RoutePack, PackConfig, Required};
+//! use rocket::serde::Deserialize;
+//!
+//! struct Ledger;
+//!
+//! #[derive(Default, Deserialize)]
+//! #[serde(crate = "rocket::serde")]
+//! struct BillingConfig {
+//!     currency: String,
+//! }
+//!
+//! #[derive(Default)]
+//! struct BillingPack;
+//!
+//! #[get("/currency")]
+//! fn currency(config: &State<PackConfig<BillingPack>>) -> &str {
+//!     &config.currency
+//! }
+//!
+//! impl RoutePack for BillingPack {
+//!     type Config = BillingConfig;
+//!
+//!     fn name(&self) -> &'static str {
+//!         "billing"
+//!     }
+//!
+//!     fn routes(&self) -> Vec<Route> {
+//!         routes![currency]
+//!     }
+//!
+//!     fn required_state(&self) -> Vec<Required> {
+//!         vec![Required::state::<Ledger>()]
+//!     }
+//! }
+//!
+//! #[launch]
+//! fn rocket() -> _ {
+//!     rocket::build()
+//!         .manage(Ledger)
+//!         .attach_pack("/billing", BillingPack::default())
+//! }
+//! ```
+
+use std::fmt;
+use std::any::TypeId;
+use std::ops::Deref;
+use std::marker::PhantomData;
+
+use serde::de::DeserializeOwned;
+
+use crate::{Rocket, Build, Route, Catcher};
+use crate::fairing::{self, Fairing, Info, Kind};
+use crate::trace::Trace;
+
+/// A mountable unit of routes, catchers, fairings, and configuration.
+///
+/// See the [module level docs](self) for details and an example.
+pub trait RoutePack: Send + Sync + 'static {
+    /// The pack's configuration, extracted from the `packs.<name>`
+    /// configuration parameter. Use `()` for a pack without configuration.
+    type Config: DeserializeOwned + Default + Send + Sync + 'static;
+
+    /// The pack's name, which must be unique among attached packs. Names the
+    /// pack's configuration section.
+    fn name(&self) -> &'static str;
+
+    /// The routes mounted at the pack's base.
+    fn routes(&self) -> Vec<Route> {
+        vec![]
+    }
+
+    /// The catchers registered at the pack's base.
+    fn catchers(&self) -> Vec<Catcher> {
+        vec![]
+    }
+
+    /// The fairings attached with the pack.
+    fn fairings(&self) -> Vec<Box<dyn Fairing>> {
+        vec![]
+    }
+
+    /// The managed state the pack requires.
+    fn required_state(&self) -> Vec<Required> {
+        vec![]
+    }
+
+    /// The managed state the pack provides, typically via its fairings. When
+    /// the state is missing, [sentinel diagnostics] name the pack as its
+    /// provider.
+    ///
+    /// [sentinel diagnostics]: crate::sentinel::Diagnostic
+    fn provides(&self) -> Vec<Provided> {
+        vec![]
+    }
+}
+
+/// The configuration of the pack `P`, managed at ignition.
+///
+/// Dereferences to [`RoutePack::Config`]. Retrieve it in handlers via
+/// `&State<PackConfig<P>>`.
+pub struct PackConfig<P: RoutePack> {
+    config: P::Config,
+    _pack: PhantomData<fn() -> P>,
+}
+
+/// Managed state required by a [`RoutePack`].
+#[derive(Clone, Copy)]
+pub struct Required {
+    type_name: &'static str,
+    is_managed: fn(&Rocket<Build>) -> bool,
+}
+
+/// Managed state provided by a [`RoutePack`].
+#[derive(Debug, Clone, Copy)]
+pub struct Provided {
+    type_id: TypeId,
+    type_name: &'static str,
+}
+
+/// The fairing attached for every pack: checks for conflicts and required
+/// state, then manages the pack's configuration.
+pub(crate) struct Mounted {
+    name: &'static str,
+    base: String,
+    type_id: TypeId,
+    type_name: &'static str,
+    required: Vec<Required>,
+    configure: fn(Rocket<Build>, &'static str) -> fairing::Result,
+}
+
+impl<P: RoutePack> PackConfig<P> {
+    /// Consumes `self` and returns the configuration.
+    pub fn into_inner(self) -> P::Config {
+        self.config
+    }
+}
+
+impl<P: RoutePack> Deref for PackConfig<P> {
+    type Target = P::Config;
+
+    fn deref(&self) -> &Self::Target {
+        &self.config
+    }
+}
+
+impl<P: RoutePack> fmt::Debug for PackConfig<P> where P::Config: fmt::Debug {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.config.fmt(f)
+    }
+}
+
+impl Required {
+    /// Requires managed state of type `T`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::pack::Required;
+    ///
+    /// struct Ledger;
+    ///
+    /// let required = Required::state::<Ledger>();
+    /// assert!(required.type_name().ends_with("Ledger"));
+    /// ```
+    pub fn state<T: Send + Sync + 'static>() -> Self {
+        Required {
+            type_name: std::any::type_name::<T>(),
+            is_managed: |rocket| rocket.state::<T>().is_some(),
+        }
+    }
+
+    /// Returns the name of the required type.
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+}
+
+impl Provided {
+    /// Provides managed state of type `T`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::pack::Provided;
+    ///
+    /// struct PgPool;
+    ///
+    /// let provided = Provided::state::<PgPool>();
+    /// assert!(provided.type_name().ends_with("PgPool"));
+    /// ```
+    pub fn state<T: Send + Sync + 'static>() -> Self {
+        Provided { type_id: TypeId::of::<T>(), type_name: std::any::type_name::<T>() }
+    }
+
+    /// Returns the type ID of the provided type.
+    pub fn type_id(&self) -> TypeId {
+        self.type_id
+    }
+
+    /// Returns the name of the provided type.
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+}
+
+impl fmt::Debug for Required {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Required").field(&self.type_name).finish()
+    }
+}
+
+impl Mounted {
+    pub(crate) fn new<P: RoutePack>(pack: &P, base: String) -> Self {
+        Mounted {
+            name: pack.name(),
+            base,
+            type_id: TypeId::of::<P>(),
+            type_name: std::any::type_name::<P>(),
+            required: pack.required_state(),
+            configure: configure::<P>,
+        }
+    }
+
+    /// Returns `true` if `self` conflicts with the distinct pack `other`.
+    fn conflicts_with(&self, other: &Mounted) -> bool {
+        fn path(base: &str) -> &str {
+            base.split('?').next().unwrap_or(base).trim_end_matches('/')
+        }
+
+        fn is_under(path: &str, prefix: &str) -> bool {
+            path.strip_prefix(prefix).map_or(false, |rest| rest.is_empty() || rest.starts_with('/'))
+        }
+
+        let (a, b) = (path(&self.base), path(&other.base));
+        self.name == other.name || self.type_id == other.type_id
+            || is_under(a, b) || is_under(b, a)
+    }
+}
+
+/// Extracts the configuration of `P` from `packs.<name>` and manages it.
+fn configure<P: RoutePack>(rocket: Rocket<Build>, name: &'static str) -> fairing::Result {
+    let key = format!("packs.{name}");
+    let config = match rocket.figment().contains(&key) {
+        true => match rocket.figment().extract_inner::<P::Config>(&key) {
+            Ok(config) => config,
+            Err(e) => {
+                e.trace_error();
+                return Err(rocket);
+            }
+        },
+        false => P::Config::default(),
+    };
+
+    Ok(rocket.manage(PackConfig::<P> { config, _pack: PhantomData }))
+}
+
+#[crate::async_trait]
+impl Fairing for Mounted {
+    fn info(&self) -> Info {
+        Info { name: "Route Pack", kind: Kind::Ignite }
+    }
+
+    async fn on_ignite(&self, rocket: Rocket<Build>) -> fairing::Result {
+        let conflicts = rocket.fairings::<Mounted>()
+            .filter(|other| !std::ptr::eq(*other, self))
+            .filter(|other| self.conflicts_with(other));
+
+        let mut failed = false;
+        for other in conflicts {
+            error!(name: "pack", pack = self.name, base = %self.base,
+                other.pack = other.name, other.base = %other.base,
+                "route pack `{}` conflicts with route pack `{}`", self.type_name, other.type_name);
+
+            failed = true;
+        }
+
+        for required in self.required.iter().filter(|r| !(r.is_managed)(&rocket)) {
+            error!(name: "pack", pack = self.name, state = required.type_name,
+                "route pack requires managed state that is missing");
+
+            failed = true;
+        }
+
+        match failed {
+            true => Err(rocket),
+            false => (self.configure)(rocket, self.name),
+        }
+    }
+}
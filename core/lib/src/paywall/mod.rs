@@ -0,0 +1,71 @@
+//! Paid features: entitlement checks, `402` responses, and usage metering.
+//!
+//! The [`Paywall`] fairing gates features of an API behind a pluggable
+//! [`Verifier`] so that handlers don't scatter entitlement checks. A verifier
+//! identifies the account a request is billed to, whether by a license key, a
+//! claim in a JWT, or an API key with a balance of credits in a store, and
+//! decides whether it's entitled to a [`Feature`]. [`MemoryCredits`], a
+//! verifier billing API keys from balances kept in memory, is built in.
+//!
+//! Handlers require a feature with the [`Entitlement`] request guard. The
+//! guard consumes the feature's cost from the account when it admits a
+//! request; a handler meters further use, like generated tokens, with
+//! [`Entitlement::consume()`]. Every metered use is passed to the
+//! [metering hooks](Paywall::on_meter()), as for reporting usage to a billing
+//! system.
+//!
+//! # Responses
+//!
+//! A request that isn't entitled, or whose balance is exhausted, fails with
+//! `402 Payment Required`; one whose entitlement can't be verified fails with
+//! `503 Service Unavailable`. `402` responses carry:
+//!
+//!   * `X-Paywall-Reason`: why the request was denied, one of `missing`,
+//!     `invalid`, `not-entitled`, or `exhausted`
+//!   * `Link`: the [purchase URL](Paywall::purchase_url()), if one is set, with
+//!     a relation type of `payment`
+//!
+//! Responses to requests billed to an account with a balance carry the units
+//! that remain in `X-Paywall-Remaining`. A [`Denial`] returned from a handler
+//! responds like a denying guard.
+//!
+//! # Example
+//!
+//! ```rust
+//! # #[macro_use] extern crate rocket;
+//! use rocket::paywall::{Paywall, MemoryCredits, Entitlement, Feature};
+//!
+//! struct Export;
+//!
+//! impl Feature for Export {
+//!     const NAME: &'static str = "export";
+//!     const COST: u64 = 10;
+//! }
+//!
+//! #[get("/report")]
+//! fn report(entitlement: Entitlement<'_>) -> String {
+//!     format!("report for {}", entitlement.account())
+//! }
+//!
+//! #[get("/report/export")]
+//! fn export(_entitlement: Entitlement<'_, Export>) -> &'static str {
+//!     "a,b,c"
+//! }
+//!
+//! #[launch]
+//! fn rocket() -> _ {
+//!     let paywall = Paywall::new(MemoryCredits::new().credit("key-alice", 100))
+//!         .purchase_url("https://example.com/pricing")
+//!         .on_meter(|m| println!("{} used {} units of {}", m.account, m.units, m.feature));
+//!
+//!     rocket::build()
+//!         .attach(paywall)
+//!         .mount("/", routes![report, export])
+//! }
+//! ```
+
+mod verifier;
+mod paywall;
+
+pub use verifier::{Verifier, Grant, Denial, MemoryCredits};
+pub use paywall::{Paywall, Metered, Feature, Access, Entitlement};
@@ -0,0 +1,241 @@
+use std::marker::PhantomData;
+
+use parking_lot::Mutex;
+
+use crate::{Request, Response};
+use crate::fairing::{Fairing, Info, Kind};
+use crate::http::{Header, Status};
+use crate::request::{FromRequest, Outcome};
+use crate::response::{self, Responder};
+use crate::paywall::{Verifier, Grant, Denial};
+
+/// A [`Fairing`] that gates features behind a [`Verifier`] and meters their
+/// use. See the [module documentation](crate::paywall) for details.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::paywall::{Paywall, MemoryCredits};
+///
+/// let paywall = Paywall::new(MemoryCredits::new().credit("key-alice", 100))
+///     .purchase_url("https://example.com/pricing")
+///     .on_meter(|metered| println!("{} used {} units", metered.account, metered.units));
+/// ```
+pub struct Paywall {
+    verifier: Box<dyn Verifier>,
+    purchase_url: Option<String>,
+    hooks: Vec<Box<dyn Fn(&Metered<'_>) + Send + Sync>>,
+}
+
+/// A metered use of a feature, as passed to [metering hooks](Paywall::on_meter()).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Metered<'a> {
+    /// The account billed.
+    pub account: &'a str,
+    /// The name of the feature used.
+    pub feature: &'a str,
+    /// The units consumed.
+    pub units: u64,
+    /// The units remaining in the account's balance, if it has one.
+    pub remaining: Option<u64>,
+}
+
+/// A feature gated by a [`Paywall`], as required by an [`Entitlement`] guard.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::paywall::Feature;
+///
+/// /// Report exports, costing 10 units each.
+/// struct Export;
+///
+/// impl Feature for Export {
+///     const NAME: &'static str = "export";
+///     const COST: u64 = 10;
+/// }
+/// ```
+pub trait Feature: Send + Sync + 'static {
+    /// The name of the feature, as passed to the [`Verifier`].
+    const NAME: &'static str;
+
+    /// The units consumed when the [`Entitlement`] guard admits a request.
+    /// Defaults to `1`.
+    const COST: u64 = 1;
+}
+
+/// The default [`Feature`]: paid access in general, costing `1` unit.
+#[derive(Debug, Clone, Copy)]
+pub struct Access;
+
+impl Feature for Access {
+    const NAME: &'static str = "access";
+}
+
+/// A request guard that admits requests entitled to the feature `F`.
+///
+/// The guard asks the [`Paywall`]'s verifier for a [`Grant`], then consumes
+/// [`F::COST`](Feature::COST) units, if nonzero. If either is denied, the
+/// guard fails with the [`Denial`] and its [status](Denial::status()),
+/// typically `402 Payment Required`. If no `Paywall` is attached, the guard
+/// forwards with a status of `500 Internal Server Error`.
+///
+/// Further units, such as tokens generated for the response, are metered with
+/// [`Entitlement::consume()`].
+///
+/// See the [module documentation](crate::paywall) for an example.
+pub struct Entitlement<'r, F: Feature = Access> {
+    paywall: &'r Paywall,
+    request: &'r Request<'r>,
+    grant: Grant,
+    _feature: PhantomData<F>,
+}
+
+/// The paywall state of a request, reported in response headers.
+#[derive(Default)]
+struct State(Mutex<(Option<&'static str>, Option<u64>)>);
+
+impl Paywall {
+    /// Returns a `Paywall` verifying entitlements with `verifier`.
+    pub fn new<V: Verifier>(verifier: V) -> Self {
+        Paywall { verifier: Box::new(verifier), purchase_url: None, hooks: vec![] }
+    }
+
+    /// Links denied responses to `url`, where entitlements can be purchased,
+    /// via a `Link` header with a relation type of `payment`.
+    pub fn purchase_url<U: Into<String>>(mut self, url: U) -> Self {
+        self.purchase_url = Some(url.into());
+        self
+    }
+
+    /// Adds a metering hook, called with every [`Metered`] use of a feature
+    /// once its units have been consumed, as for exporting usage to a billing
+    /// system. Hooks are called in the order they're added.
+    pub fn on_meter<F>(mut self, hook: F) -> Self
+        where F: Fn(&Metered<'_>) + Send + Sync + 'static
+    {
+        self.hooks.push(Box::new(hook));
+        self
+    }
+
+    /// Consumes `units` of `feature` from the account of `grant` and calls the
+    /// metering hooks.
+    async fn consume(
+        &self,
+        req: &Request<'_>,
+        grant: &mut Grant,
+        feature: &str,
+        units: u64,
+    ) -> Result<(), Denial> {
+        let state = req.local_cache(State::default);
+        let remaining = match self.verifier.consume(grant, feature, units).await {
+            Ok(remaining) => remaining,
+            Err(denial) => {
+                state.0.lock().0 = Some(denial.reason());
+                return Err(denial);
+            }
+        };
+
+        grant.remaining = remaining.or(grant.remaining);
+        state.0.lock().1 = grant.remaining;
+        let metered = Metered { account: &grant.account, feature, units, remaining };
+        self.hooks.iter().for_each(|hook| hook(&metered));
+        Ok(())
+    }
+}
+
+impl<F: Feature> Entitlement<'_, F> {
+    /// The grant the request was admitted with.
+    pub fn grant(&self) -> &Grant {
+        &self.grant
+    }
+
+    /// The account the request is billed to.
+    pub fn account(&self) -> &str {
+        &self.grant.account
+    }
+
+    /// Consumes a further `units` of `F` from the account, as for tokens
+    /// generated by the handler, and calls the metering hooks.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #[macro_use] extern crate rocket;
+    /// use rocket::paywall::{Entitlement, Denial};
+    ///
+    /// #[post("/complete", data = "<prompt>")]
+    /// async fn complete(
+    ///     prompt: &str,
+    ///     mut entitlement: Entitlement<'_>,
+    /// ) -> Result<String, Denial> {
+    ///     let completion = format!("{prompt}..."); // generate the completion
+    ///     let tokens = completion.split_whitespace().count() as u64;
+    ///     entitlement.consume(tokens).await?;
+    ///     Ok(completion)
+    /// }
+    /// ```
+    pub async fn consume(&mut self, units: u64) -> Result<(), Denial> {
+        self.paywall.consume(self.request, &mut self.grant, F::NAME, units).await
+    }
+}
+
+#[crate::async_trait]
+impl<'r, F: Feature> FromRequest<'r> for Entitlement<'r, F> {
+    type Error = Denial;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Denial> {
+        let Some(paywall) = request.rocket().fairing::<Paywall>() else {
+            error!("`Entitlement` guard used but no `Paywall` is attached");
+            return Outcome::Forward(Status::InternalServerError);
+        };
+
+        let mut grant = match paywall.verifier.verify(request, F::NAME).await {
+            Ok(grant) => grant,
+            Err(denial) => {
+                request.local_cache(State::default).0.lock().0 = Some(denial.reason());
+                return Outcome::Error((denial.status(), denial));
+            }
+        };
+
+        request.local_cache(State::default).0.lock().1 = grant.remaining;
+        if F::COST > 0 {
+            if let Err(denial) = paywall.consume(request, &mut grant, F::NAME, F::COST).await {
+                return Outcome::Error((denial.status(), denial));
+            }
+        }
+
+        Outcome::Success(Entitlement { paywall, request, grant, _feature: PhantomData })
+    }
+}
+
+/// Fails with the denial's [status](Denial::status()), so that the catcher
+/// for it responds, and reports the reason like a denying [`Entitlement`].
+impl<'r> Responder<'r, 'static> for Denial {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
+        req.local_cache(State::default).0.lock().0 = Some(self.reason());
+        Err(self.status())
+    }
+}
+
+#[crate::async_trait]
+impl Fairing for Paywall {
+    fn info(&self) -> Info {
+        Info { name: "Paywall", kind: Kind::Response | Kind::Singleton }
+    }
+
+    async fn on_response<'r>(&self, req: &'r Request<'_>, res: &mut Response<'r>) {
+        let (reason, remaining) = *req.local_cache(State::default).0.lock();
+        if let Some(remaining) = remaining {
+            res.set_header(Header::new("X-Paywall-Remaining", remaining.to_string()));
+        }
+
+        let Some(reason) = reason else { return };
+        if res.status() == Status::PaymentRequired {
+            res.set_header(Header::new("X-Paywall-Reason", reason));
+            if let Some(url) = &self.purchase_url {
+                res.set_header(Header::new("Link", format!("<{url}>; rel=\"payment\"")));
+            }
+        }
+    }
+}
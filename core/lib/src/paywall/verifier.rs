@@ -0,0 +1,217 @@
+use std::{fmt, io};
+use std::collections::HashMap;
+
+use parking_lot::Mutex;
+
+use crate::Request;
+use crate::http::Status;
+
+/// Verifies that a request is entitled to a feature and meters its use.
+///
+/// A verifier identifies the account a request is billed to from its
+/// credentials, whether a license key, a claim in a JWT, or an API key with a
+/// balance of credits in a store, and decides whether the account is entitled
+/// to a feature. Verifiers that bill by usage also implement
+/// [`Verifier::consume()`] to deduct units from the account.
+///
+/// # Example
+///
+/// A verifier entitling license keys listed in a set to every feature:
+///
+/// ```rust
+/// use std::collections::HashSet;
+///
+/// use rocket::Request;
+/// use rocket::paywall::{Verifier, Grant, Denial};
+///
+/// struct Licenses(HashSet<String>);
+///
+/// #[rocket::async_trait]
+/// impl Verifier for Licenses {
+///     async fn verify(&self, req: &Request<'_>, _: &str) -> Result<Grant, Denial> {
+///         let key = req.headers().get_one("X-License-Key").ok_or(Denial::Missing)?;
+///         match self.0.contains(key) {
+///             true => Ok(Grant::new(key)),
+///             false => Err(Denial::Invalid),
+///         }
+///     }
+/// }
+/// ```
+#[crate::async_trait]
+pub trait Verifier: Send + Sync + 'static {
+    /// Returns a [`Grant`] for the account `req` is billed to if it is
+    /// entitled to `feature`.
+    async fn verify(&self, req: &Request<'_>, feature: &str) -> Result<Grant, Denial>;
+
+    /// Deducts `units` of `feature` from the account of `grant`, returning the
+    /// units that remain, if the account has a balance. Fails with
+    /// [`Denial::Exhausted`] if fewer than `units` remain.
+    ///
+    /// The default implementation doesn't limit usage and returns `Ok(None)`.
+    async fn consume(
+        &self,
+        grant: &Grant,
+        feature: &str,
+        units: u64,
+    ) -> Result<Option<u64>, Denial> {
+        let _ = (grant, feature, units);
+        Ok(None)
+    }
+}
+
+/// The entitlement of an account, as verified by a [`Verifier`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grant {
+    /// The account the request is billed to.
+    pub account: String,
+    /// The units remaining in the account's balance, if it has one.
+    pub remaining: Option<u64>,
+}
+
+/// Why a request isn't entitled to a feature.
+#[derive(Debug)]
+pub enum Denial {
+    /// The request carries no credentials.
+    Missing,
+    /// The request's credentials are invalid or expired.
+    Invalid,
+    /// The account isn't entitled to the feature, as on a lower plan.
+    NotEntitled,
+    /// The account's balance is exhausted.
+    Exhausted,
+    /// The entitlement couldn't be verified, as when a store is unreachable.
+    Unavailable(io::Error),
+}
+
+/// A [`Verifier`] that bills API keys from balances of credits kept in memory.
+///
+/// The key is read from the `X-Api-Key` header. Every key with a balance is
+/// entitled to every feature; each use deducts from the balance until it is
+/// exhausted. Balances are lost when the application exits and aren't shared
+/// between instances.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::paywall::{Paywall, MemoryCredits};
+///
+/// let credits = MemoryCredits::new()
+///     .credit("key-alice", 1000)
+///     .credit("key-bob", 10);
+///
+/// let paywall = Paywall::new(credits);
+/// ```
+#[derive(Debug, Default)]
+pub struct MemoryCredits {
+    balances: Mutex<HashMap<String, u64>>,
+}
+
+impl Grant {
+    /// Returns a `Grant` for `account` without a balance.
+    pub fn new<A: Into<String>>(account: A) -> Self {
+        Grant { account: account.into(), remaining: None }
+    }
+
+    /// Sets the units remaining in the account's balance.
+    pub fn remaining(mut self, remaining: u64) -> Self {
+        self.remaining = Some(remaining);
+        self
+    }
+}
+
+impl Denial {
+    /// Returns the status of responses to denied requests: `503 Service
+    /// Unavailable` for [`Denial::Unavailable`] and `402 Payment Required`
+    /// otherwise.
+    pub fn status(&self) -> Status {
+        match self {
+            Denial::Unavailable(_) => Status::ServiceUnavailable,
+            _ => Status::PaymentRequired,
+        }
+    }
+
+    /// Returns a short code for the reason, as reported in the
+    /// `X-Paywall-Reason` header: `missing`, `invalid`, `not-entitled`,
+    /// `exhausted`, or `unavailable`.
+    pub fn reason(&self) -> &'static str {
+        match self {
+            Denial::Missing => "missing",
+            Denial::Invalid => "invalid",
+            Denial::NotEntitled => "not-entitled",
+            Denial::Exhausted => "exhausted",
+            Denial::Unavailable(_) => "unavailable",
+        }
+    }
+}
+
+impl fmt::Display for Denial {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Denial::Missing => write!(f, "missing credentials"),
+            Denial::Invalid => write!(f, "invalid credentials"),
+            Denial::NotEntitled => write!(f, "not entitled to feature"),
+            Denial::Exhausted => write!(f, "balance exhausted"),
+            Denial::Unavailable(e) => write!(f, "entitlement unavailable: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for Denial {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Denial::Unavailable(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Denial {
+    fn from(e: io::Error) -> Self {
+        Denial::Unavailable(e)
+    }
+}
+
+impl MemoryCredits {
+    /// Returns a `MemoryCredits` without any balances.
+    pub fn new() -> Self {
+        MemoryCredits::default()
+    }
+
+    /// Adds `credits` to the balance of `key`.
+    pub fn credit<K: Into<String>>(self, key: K, credits: u64) -> Self {
+        self.top_up(&key.into(), credits);
+        self
+    }
+
+    /// Adds `credits` to the balance of `key`, returning the new balance.
+    pub fn top_up(&self, key: &str, credits: u64) -> u64 {
+        let mut balances = self.balances.lock();
+        let balance = balances.entry(key.to_string()).or_default();
+        *balance = balance.saturating_add(credits);
+        *balance
+    }
+
+    /// Returns the balance of `key`, if it has one.
+    pub fn balance(&self, key: &str) -> Option<u64> {
+        self.balances.lock().get(key).copied()
+    }
+}
+
+#[crate::async_trait]
+impl Verifier for MemoryCredits {
+    async fn verify(&self, req: &Request<'_>, _: &str) -> Result<Grant, Denial> {
+        let key = req.headers().get_one("X-Api-Key").ok_or(Denial::Missing)?;
+        match self.balance(key) {
+            Some(0) => Err(Denial::Exhausted),
+            Some(balance) => Ok(Grant::new(key).remaining(balance)),
+            None => Err(Denial::Invalid),
+        }
+    }
+
+    async fn consume(&self, grant: &Grant, _: &str, units: u64) -> Result<Option<u64>, Denial> {
+        let mut balances = self.balances.lock();
+        let balance = balances.get_mut(&grant.account).ok_or(Denial::Invalid)?;
+        *balance = balance.checked_sub(units).ok_or(Denial::Exhausted)?;
+        Ok(Some(*balance))
+    }
+}
@@ -1,11 +1,16 @@
+use std::sync::Arc;
+
 use state::TypeMap;
 use figment::Figment;
 
 use crate::listener::Endpoint;
-use crate::shutdown::Stages;
+use crate::shutdown::{Shutdown, Stages, InFlight};
 use crate::{Catcher, Config, Rocket, Route};
-use crate::router::Router;
+use crate::router::{Router, LiveRouter};
 use crate::fairing::Fairings;
+use crate::sentinel::Provider;
+use crate::events::EventBus;
+use crate::state::Managed;
 
 mod private {
     pub trait Sealed {  }
@@ -90,7 +95,9 @@ phases! {
         pub(crate) catchers: Vec<Catcher>,
         pub(crate) fairings: Fairings,
         pub(crate) figment: Figment,
-        pub(crate) state: TypeMap![Send + Sync],
+        pub(crate) state: Managed,
+        pub(crate) providers: Vec<Provider>,
+        pub(crate) events: EventBus,
     }
 
     /// The second launch [`Phase`]: post-build but pre-orbit. See
@@ -106,6 +113,7 @@ phases! {
         pub(crate) config: Config,
         pub(crate) state: TypeMap![Send + Sync],
         pub(crate) shutdown: Stages,
+        pub(crate) events: EventBus,
     }
 
     /// The final launch [`Phase`]. See [Rocket#orbit](`Rocket#orbit`) for
@@ -114,12 +122,16 @@ phases! {
     /// An instance of `Rocket` in this phase is typed as [`Rocket<Orbit>`] and
     /// represents a running application.
     Orbit (#[derive(Debug)] Orbiting) {
-        pub(crate) router: Router,
+        pub(crate) router: LiveRouter,
         pub(crate) fairings: Fairings,
         pub(crate) figment: Figment,
         pub(crate) config: Config,
         pub(crate) state: TypeMap![Send + Sync],
         pub(crate) shutdown: Stages,
+        pub(crate) events: EventBus,
         pub(crate) endpoints: Vec<Endpoint>,
+        pub(crate) closing: Vec<Shutdown>,
+        pub(crate) alt_svc: Option<Arc<str>>,
+        pub(crate) in_flight: Arc<InFlight>,
     }
 }
@@ -0,0 +1,71 @@
+use std::hash::{Hash, Hasher};
+use std::collections::hash_map::DefaultHasher;
+use std::sync::Arc;
+
+use crate::Request;
+use crate::proxy::resolve::Backend;
+
+/// A strategy for distributing requests among an [`Upstream`]'s backends.
+///
+/// Whatever the strategy, requests are only forwarded to healthy backends
+/// whose circuit isn't open. If a backend can't be connected to, the request
+/// is retried on the next backend the strategy would have chosen.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::proxy::{Upstream, Balance};
+///
+/// let backends = ["http://10.0.0.1:8000", "http://10.0.0.2:8000"];
+/// let upstream = Upstream::new(backends).balance(Balance::Cookie("session".into()));
+/// ```
+///
+/// [`Upstream`]: crate::proxy::Upstream
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum Balance {
+    /// Each backend, in turn. The default.
+    #[default]
+    RoundRobin,
+    /// The backend with the fewest requests in flight, where a request is in
+    /// flight until its response body has been sent or dropped. Ties are
+    /// broken round-robin.
+    LeastConnections,
+    /// The backend chosen by consistent hashing of the value of the named
+    /// cookie, so that requests with the same value, such as those of one
+    /// session, reach the same backend for as long as it's available. When
+    /// backends are added or removed, only the values that hashed to changed
+    /// backends move. Requests without the cookie are balanced round-robin.
+    Cookie(String),
+    /// The backend chosen by consistent hashing of the value of the named
+    /// header, as with [`Balance::Cookie`]. Requests without the header are
+    /// balanced round-robin.
+    Header(String),
+}
+
+impl Balance {
+    /// Orders `candidates` in the order they should be tried for `req`. The
+    /// `n`th round-robin selection is used where one is needed.
+    pub(crate) fn order(&self, req: &Request<'_>, candidates: &mut [Arc<Backend>], n: usize) {
+        candidates.rotate_left(n % candidates.len());
+        let key = match self {
+            Balance::RoundRobin => return,
+            Balance::LeastConnections => {
+                candidates.sort_by_key(|backend| backend.active());
+                return;
+            }
+            Balance::Cookie(name) => req.cookies().get(name).map(|c| c.value()),
+            Balance::Header(name) => req.headers().get_one(name),
+        };
+
+        // Rendezvous hashing: each backend is scored by the hash of the key
+        // and its address, and the highest scoring backend is preferred.
+        if let Some(key) = key {
+            candidates.sort_by_cached_key(|backend| {
+                let mut hasher = DefaultHasher::new();
+                (key, &backend.host, backend.port).hash(&mut hasher);
+                std::cmp::Reverse(hasher.finish())
+            });
+        }
+    }
+}
@@ -0,0 +1,69 @@
+//! Reverse proxying: forwarding requests to upstream backends.
+//!
+//! The [`Upstream`] handler forwards every request under its mount point to
+//! one of a pool of _backends_ and streams the backend's response back to the
+//! client, letting Rocket act as a lightweight gateway in front of other
+//! services. Backends are listed statically or resolved via DNS, from SRV or
+//! A and AAAA records, and are re-resolved as the records' TTLs expire, so
+//! that backends can come and go without redeploying the gateway.
+//!
+//! # Forwarding
+//!
+//! A forwarded request has the method, headers, and body of the original
+//! request, and its path is the part of the original path after the mount
+//! point, prefixed by the backend's path, if any, along with the original
+//! query. Hop-by-hop headers, including those named in a `Connection` header,
+//! aren't forwarded. The `Host` header is set to the backend's authority, the
+//! client's IP address is appended to `X-Forwarded-For`, and the original
+//! `Host` is sent as `X-Forwarded-Host`.
+//!
+//! Requests are forwarded over HTTP/1.1 on a new connection each; only plain
+//! `http://` backends are supported. Request bodies are read into memory
+//! before being forwarded, up to the `proxy` [limit](crate::data::Limits) or
+//! 1MiB if there is none; larger requests fail with `413 Payload Too Large`.
+//! Protocol upgrades, and so WebSockets, aren't forwarded.
+//!
+//! # Backend Selection and Health
+//!
+//! Requests are distributed among the available backends per a [`Balance`]
+//! strategy: round-robin, least connections, or consistent hashing of a
+//! cookie or header for sticky sessions. A backend is available if it's
+//! healthy and its circuit isn't open.
+//!
+//! A backend that can't be connected to is considered unhealthy, and the
+//! request is retried on another backend, up to three in total. With [health
+//! checks](Upstream::health_check()), unhealthy backends are reconsidered once
+//! they pass a check; without, after ten seconds. With a [circuit
+//! breaker](Upstream::circuit_breaker()), a backend whose requests keep
+//! failing, including with `5xx` responses, is taken out of rotation until a
+//! trial request succeeds.
+//!
+//! If no backend is available, requests fail with `503 Service Unavailable`.
+//! If no backend can be connected to or a backend's response is invalid, they
+//! fail with `502 Bad Gateway`, and if a backend doesn't respond within the
+//! [timeout](Upstream::timeout()), with `504 Gateway Timeout`.
+//!
+//! # Example
+//!
+//! ```rust
+//! # #[macro_use] extern crate rocket;
+//! use std::time::Duration;
+//! use rocket::proxy::{Upstream, Balance};
+//!
+//! #[launch]
+//! fn rocket() -> _ {
+//!     let upstream = Upstream::dns("_http._tcp.api.internal")
+//!         .health_check("/health", Duration::from_secs(10))
+//!         .balance(Balance::Cookie("session".into()))
+//!         .circuit_breaker(5, Duration::from_secs(30));
+//!
+//!     rocket::build().mount("/api", upstream)
+//! }
+//! ```
+
+mod resolve;
+mod balance;
+mod upstream;
+
+pub use upstream::Upstream;
+pub use balance::Balance;
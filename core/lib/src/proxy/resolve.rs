@@ -0,0 +1,348 @@
+use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use hickory_resolver::TokioAsyncResolver;
+use parking_lot::Mutex;
+use state::InitCell;
+
+use crate::http::uri::Absolute;
+
+/// How long resolved backends are used for, at least, whatever their TTL.
+const MIN_TTL: Duration = Duration::from_secs(1);
+
+/// How long to wait before resolving again after a lookup fails.
+const RETRY: Duration = Duration::from_secs(5);
+
+/// Where an upstream's backends come from.
+#[derive(Debug)]
+pub(crate) enum Source {
+    /// A fixed list of backends.
+    Static,
+    /// The targets of a service's SRV records.
+    Srv(String),
+    /// The addresses in a host's A and AAAA records, all on one port.
+    Host(String, u16),
+}
+
+/// A server that requests are forwarded to.
+#[derive(Debug)]
+pub(crate) struct Backend {
+    /// The host to connect to: a name or an IP address.
+    pub host: String,
+    pub port: u16,
+    /// The `Host` sent to the backend.
+    pub authority: String,
+    /// The path that prefixes the paths of forwarded requests.
+    pub prefix: String,
+    /// The SRV priority of the backend. Lower is preferred.
+    pub priority: u16,
+    /// When the backend is next considered healthy, if it isn't now.
+    down_until: Mutex<Option<Instant>>,
+    /// The number of requests to the backend in flight.
+    active: AtomicUsize,
+    circuit: Mutex<Circuit>,
+}
+
+/// The state of a backend's circuit breaker.
+#[derive(Debug, Default)]
+struct Circuit {
+    /// Consecutive failed requests.
+    failures: u32,
+    /// When the circuit half-opens, admitting one trial request, if it's open.
+    open_until: Option<Instant>,
+}
+
+/// When and for how long backends' circuits open.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Breaker {
+    /// Consecutive failed requests after which a circuit opens.
+    pub failures: u32,
+    /// How long a circuit stays open before admitting a trial request.
+    pub cooldown: Duration,
+}
+
+/// The backends of an upstream, resolved and refreshed as their TTLs expire.
+pub(crate) struct Pool {
+    source: Source,
+    resolver: InitCell<Result<TokioAsyncResolver, String>>,
+    resolved: Mutex<Resolved>,
+    refresh: tokio::sync::Mutex<()>,
+    next: AtomicUsize,
+    pub checking: AtomicBool,
+}
+
+struct Resolved {
+    backends: Arc<[Arc<Backend>]>,
+    /// When `backends` must be resolved again. `None` if never.
+    expires: Option<Instant>,
+}
+
+impl Backend {
+    fn new(host: String, port: u16, authority: String, priority: u16) -> Self {
+        Backend {
+            host, port, authority, priority,
+            prefix: String::new(),
+            down_until: None.into(),
+            active: AtomicUsize::new(0),
+            circuit: Mutex::default(),
+        }
+    }
+
+    /// Parses an `http://` URI into a backend.
+    pub fn parse(string: &str) -> Result<Backend, String> {
+        let uri = Absolute::parse(string).map_err(|e| format!("invalid URI: {e}"))?;
+        if !uri.scheme().eq_ignore_ascii_case("http") {
+            return Err(format!("unsupported scheme `{}`: expected `http`", uri.scheme()));
+        }
+
+        let Some(authority) = uri.authority() else {
+            return Err("missing host".into());
+        };
+
+        if uri.query().is_some() {
+            return Err("backends can't have a query".into());
+        }
+
+        let host = authority.host().trim_start_matches('[').trim_end_matches(']');
+        let port = authority.port().unwrap_or(80);
+        let mut backend = Backend::new(host.into(), port, authority.to_string(), 0);
+        backend.prefix = uri.path().as_str().trim_end_matches('/').into();
+        Ok(backend)
+    }
+
+    /// Returns `true` if `self` and `other` are the same backend.
+    fn same_as(&self, other: &Backend) -> bool {
+        self.host == other.host
+            && self.port == other.port
+            && self.authority == other.authority
+            && self.priority == other.priority
+    }
+
+    /// Returns `true` if the backend is considered healthy at `now`.
+    pub fn is_up(&self, now: Instant) -> bool {
+        self.down_until.lock().map_or(true, |until| now >= until)
+    }
+
+    /// Returns `true` if the backend is healthy and its circuit isn't open at
+    /// `now`, and so requests can be forwarded to it.
+    pub fn is_available(&self, now: Instant) -> bool {
+        self.is_up(now) && self.circuit.lock().open_until.map_or(true, |until| now >= until)
+    }
+
+    /// Returns the number of requests to the backend in flight.
+    pub fn active(&self) -> usize {
+        self.active.load(Ordering::Acquire)
+    }
+
+    /// Counts a request to the backend as in flight until the returned guard
+    /// is dropped. If the backend's circuit has half-opened, the request is
+    /// its trial, and the circuit stays closed to other requests until the
+    /// trial's outcome is recorded or another `cooldown` passes.
+    pub fn start(self: &Arc<Self>, breaker: Option<Breaker>) -> InFlight {
+        if let Some(breaker) = breaker {
+            let mut circuit = self.circuit.lock();
+            if circuit.open_until.is_some() {
+                circuit.open_until = Some(Instant::now() + breaker.cooldown);
+            }
+        }
+
+        self.active.fetch_add(1, Ordering::AcqRel);
+        InFlight(self.clone())
+    }
+
+    /// Records the outcome of a request to the backend, opening its circuit
+    /// after `breaker.failures` consecutive failures, or after a failed trial.
+    pub fn record(&self, succeeded: bool, breaker: Breaker) {
+        let mut circuit = self.circuit.lock();
+        if succeeded {
+            if circuit.open_until.is_some() {
+                info!(name: "proxy", backend = %self.authority, host = %self.host,
+                    "backend circuit closed");
+            }
+
+            *circuit = Circuit::default();
+            return;
+        }
+
+        circuit.failures = circuit.failures.saturating_add(1);
+        if circuit.failures >= breaker.failures {
+            if circuit.open_until.is_none() {
+                warn!(name: "proxy", backend = %self.authority, host = %self.host,
+                    failures = circuit.failures, "backend circuit opened");
+            }
+
+            circuit.open_until = Some(Instant::now() + breaker.cooldown);
+        }
+    }
+
+    /// Considers the backend unhealthy for `period`.
+    pub fn mark_down(&self, period: Duration) {
+        *self.down_until.lock() = Some(Instant::now() + period);
+    }
+
+    /// Considers the backend healthy.
+    pub fn mark_up(&self) {
+        *self.down_until.lock() = None;
+    }
+}
+
+impl Pool {
+    /// A pool of the fixed `backends`.
+    pub fn fixed(backends: Vec<Backend>) -> Self {
+        let backends = backends.into_iter().map(Arc::new).collect();
+        Pool::new(Source::Static, Resolved { backends, expires: None })
+    }
+
+    /// A pool of backends resolved from `source` on first use.
+    pub fn dns(source: Source) -> Self {
+        let backends = Arc::from(vec![]);
+        Pool::new(source, Resolved { backends, expires: Some(Instant::now()) })
+    }
+
+    fn new(source: Source, resolved: Resolved) -> Self {
+        Pool {
+            source,
+            resolver: InitCell::new(),
+            resolved: Mutex::new(resolved),
+            refresh: tokio::sync::Mutex::new(()),
+            next: AtomicUsize::new(0),
+            checking: AtomicBool::new(false),
+        }
+    }
+
+    /// Returns the current backends, resolving them first if their TTL has
+    /// expired. If resolution fails, the previous backends are returned and
+    /// resolution is retried after a short delay.
+    pub async fn backends(&self) -> Arc<[Arc<Backend>]> {
+        if let Some(backends) = self.fresh() {
+            return backends;
+        }
+
+        // Only one request resolves at a time; the rest wait for its result.
+        let _refresh = self.refresh.lock().await;
+        if let Some(backends) = self.fresh() {
+            return backends;
+        }
+
+        let (backends, expires) = match self.resolve().await {
+            Ok((found, valid_until)) => {
+                let expires = valid_until.max(Instant::now() + MIN_TTL);
+                (self.merge(found), expires)
+            }
+            Err(e) => {
+                warn!(name: "proxy", source = %self.source, "failed to resolve upstream: {e}");
+                (self.resolved.lock().backends.clone(), Instant::now() + RETRY)
+            }
+        };
+
+        let mut resolved = self.resolved.lock();
+        resolved.backends = backends.clone();
+        resolved.expires = Some(expires);
+        backends
+    }
+
+    /// Returns the available backends of the most preferred priority.
+    pub fn candidates(&self, backends: &[Arc<Backend>]) -> Vec<Arc<Backend>> {
+        let now = Instant::now();
+        let available = backends.iter().filter(|b| b.is_available(now));
+        let Some(priority) = available.clone().map(|b| b.priority).min() else {
+            return vec![];
+        };
+
+        available.filter(|b| b.priority == priority).cloned().collect()
+    }
+
+    /// Returns the number of the next round-robin selection.
+    pub fn next(&self) -> usize {
+        self.next.fetch_add(1, Ordering::Relaxed)
+    }
+
+    fn fresh(&self) -> Option<Arc<[Arc<Backend>]>> {
+        let resolved = self.resolved.lock();
+        resolved.expires
+            .map_or(true, |expires| Instant::now() < expires)
+            .then(|| resolved.backends.clone())
+    }
+
+    /// Resolves `self.source`, returning the backends found and when they
+    /// must be resolved again.
+    async fn resolve(&self) -> Result<(Vec<Backend>, Instant), String> {
+        let resolver = self.resolver
+            .get_or_init(|| TokioAsyncResolver::tokio_from_system_conf().map_err(|e| e.to_string()))
+            .as_ref()
+            .map_err(Clone::clone)?;
+
+        match &self.source {
+            Source::Static => Err("static upstreams aren't resolved".into()),
+            Source::Host(host, port) => {
+                let ips = resolver.lookup_ip(host.as_str()).await.map_err(|e| e.to_string())?;
+                let authority = format!("{host}:{port}");
+                let backends = ips.iter()
+                    .map(|ip| Backend::new(ip.to_string(), *port, authority.clone(), 0))
+                    .collect();
+
+                Ok((backends, ips.valid_until()))
+            }
+            Source::Srv(name) => {
+                let srv = resolver.srv_lookup(name.as_str()).await.map_err(|e| e.to_string())?;
+                let mut expires = srv.as_lookup().valid_until();
+                let mut backends = vec![];
+                for record in srv.iter() {
+                    let target = record.target().to_utf8();
+                    let authority = format!("{}:{}", target.trim_end_matches('.'), record.port());
+                    let ips = resolver.lookup_ip(record.target().clone()).await
+                        .map_err(|e| format!("{target}: {e}"))?;
+
+                    expires = expires.min(ips.valid_until());
+                    backends.extend(ips.iter().map(|ip| {
+                        let host = ip.to_string();
+                        Backend::new(host, record.port(), authority.clone(), record.priority())
+                    }));
+                }
+
+                Ok((backends, expires))
+            }
+        }
+    }
+
+    /// Returns `found`, reusing the current backends, and thus their health,
+    /// for those found again.
+    fn merge(&self, found: Vec<Backend>) -> Arc<[Arc<Backend>]> {
+        let current = self.resolved.lock().backends.clone();
+        found.into_iter()
+            .map(|new| {
+                current.iter()
+                    .find(|b| b.same_as(&new))
+                    .cloned()
+                    .unwrap_or_else(|| Arc::new(new))
+            })
+            .collect()
+    }
+}
+
+/// A request to a backend in flight.
+pub(crate) struct InFlight(Arc<Backend>);
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        self.0.active.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+impl fmt::Debug for Pool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Pool").field("source", &self.source).finish_non_exhaustive()
+    }
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::Static => f.write_str("static"),
+            Source::Srv(name) => write!(f, "SRV {name}"),
+            Source::Host(host, port) => write!(f, "{host}:{port}"),
+        }
+    }
+}
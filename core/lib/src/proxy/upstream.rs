@@ -0,0 +1,588 @@
+use std::io;
+use std::convert::Infallible;
+use std::pin::Pin;
+use std::sync::{Arc, Weak};
+use std::sync::atomic::Ordering;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use bytes::Bytes;
+use hyper::body::{Body as HttpBody, Frame, Incoming, SizeHint};
+use hyper::client::conn::http1;
+use hyper_util::rt::TokioIo;
+use pin_project_lite::pin_project;
+use tokio::io::{AsyncRead, ReadBuf};
+use tokio::net::TcpStream;
+use tokio_util::io::StreamReader;
+
+use crate::{Data, Request, Response};
+use crate::data::{ByteUnit, RawStream};
+use crate::http::{Method, Status};
+use crate::proxy::Balance;
+use crate::proxy::resolve::{Backend, Breaker, InFlight, Pool, Source};
+use crate::route::{Route, Handler, Outcome};
+
+/// Custom handler that forwards requests to a pool of backends.
+///
+/// An `Upstream` is mounted like any other handler. Every request to a path
+/// under the mount point, whatever its method, is forwarded to a backend, and
+/// the backend's response is streamed back to the client. See the [module
+/// level docs](crate::proxy) for details on how requests are forwarded.
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::proxy::Upstream;
+///
+/// #[launch]
+/// fn rocket() -> _ {
+///     let backends = ["http://10.0.0.1:8000", "http://10.0.0.2:8000"];
+///     rocket::build().mount("/api", Upstream::new(backends))
+/// }
+/// ```
+///
+/// Backends are either listed statically, via [`Upstream::new()`], or
+/// resolved via DNS, via [`Upstream::dns()`]. Requests are distributed among
+/// them per the [`Balance`] strategy set with [`Upstream::balance()`], and
+/// failing backends can be taken out of rotation with
+/// [`Upstream::circuit_breaker()`]. By default, the routes have a rank of
+/// `10`, which can be changed with [`Upstream::rank()`].
+#[derive(Debug, Clone)]
+pub struct Upstream {
+    pool: Arc<Pool>,
+    health: Option<Arc<HealthCheck>>,
+    balance: Arc<Balance>,
+    breaker: Option<Breaker>,
+    timeout: Duration,
+    rank: isize,
+}
+
+#[derive(Debug)]
+struct HealthCheck {
+    path: String,
+    interval: Duration,
+}
+
+/// Why a request couldn't be forwarded to a backend.
+enum Failure {
+    /// The backend couldn't be connected to, so the request wasn't sent.
+    Connect(io::Error),
+    /// The request was sent, but the exchange failed.
+    Exchange(hyper::Error),
+    /// The backend didn't respond in time.
+    Timeout,
+}
+
+/// A request body that has been read into memory.
+struct Buffered(Option<Bytes>);
+
+pin_project! {
+    /// A response body that keeps its request in flight until it's dropped.
+    struct Counted<R> {
+        #[pin]
+        reader: R,
+        _in_flight: InFlight,
+    }
+}
+
+/// Headers that apply to a single connection and so aren't forwarded, along
+/// with those the forwarded request or response sets itself.
+const HOP_BY_HOP: &[&str] = &[
+    "Connection", "Keep-Alive", "Proxy-Connection", "Proxy-Authenticate",
+    "Proxy-Authorization", "TE", "Trailer", "Transfer-Encoding", "Upgrade",
+    "Host", "Content-Length", "X-Forwarded-For", "X-Forwarded-Host",
+];
+
+/// The methods that requests are forwarded for.
+const METHODS: &[Method] = &[
+    Method::Get, Method::Head, Method::Post, Method::Put, Method::Delete,
+    Method::Patch, Method::Options,
+];
+
+/// How long a backend that can't be connected to is skipped for when there
+/// are no health checks.
+const COOLDOWN: Duration = Duration::from_secs(10);
+
+/// The most backends a request is tried against.
+const MAX_ATTEMPTS: usize = 3;
+
+impl Upstream {
+    /// The default rank of an `Upstream`'s routes: `10`.
+    const DEFAULT_RANK: isize = 10;
+
+    /// The limit on the size of forwarded request bodies when there is no
+    /// `proxy` limit: 1MiB.
+    const DEFAULT_LIMIT: ByteUnit = ByteUnit::Mebibyte(1);
+
+    /// Returns an `Upstream` that forwards requests to `backends`, each an
+    /// `http://` URI. The path of a backend's URI, if any, prefixes the paths
+    /// of requests forwarded to it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `backends` is empty or if any backend isn't a valid `http://`
+    /// URI without a query.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::proxy::Upstream;
+    ///
+    /// let upstream = Upstream::new(["http://10.0.0.1:8000", "http://10.0.0.2:8000/v2"]);
+    /// ```
+    #[track_caller]
+    pub fn new<I, S>(backends: I) -> Self
+        where I: IntoIterator<Item = S>, S: AsRef<str>
+    {
+        let mut parsed = vec![];
+        for uri in backends {
+            match Backend::parse(uri.as_ref()) {
+                Ok(backend) => parsed.push(backend),
+                Err(e) => panic!("invalid upstream backend `{}`: {e}", uri.as_ref()),
+            }
+        }
+
+        assert!(!parsed.is_empty(), "an upstream requires at least one backend");
+        Upstream::with_pool(Pool::fixed(parsed))
+    }
+
+    /// Returns an `Upstream` that forwards requests to backends resolved via
+    /// DNS from `name`.
+    ///
+    /// If `name` begins with `_`, as in `_http._tcp.api.internal`, it names
+    /// SRV records: the backends are the addresses of the records' targets, on
+    /// the records' ports. Requests are forwarded to backends of the most
+    /// preferred priority unless none are healthy. SRV weights are ignored.
+    ///
+    /// Otherwise, `name` is a host and optional port, as in `api.internal:8080`,
+    /// and the backends are the addresses in the host's A and AAAA records, on
+    /// the given port or `80`.
+    ///
+    /// Names are resolved with the system's resolver configuration when the
+    /// first request is forwarded and again each time the records' TTL
+    /// expires. If resolution fails, the previously resolved backends continue
+    /// to be used.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` has an invalid port.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::proxy::Upstream;
+    ///
+    /// let services = Upstream::dns("_http._tcp.api.internal");
+    /// let hosts = Upstream::dns("api.internal:8080");
+    /// ```
+    #[track_caller]
+    pub fn dns<S: Into<String>>(name: S) -> Self {
+        let name = name.into();
+        let source = match name.rsplit_once(':') {
+            _ if name.starts_with('_') => Source::Srv(name),
+            Some((host, port)) => match port.parse() {
+                Ok(port) => Source::Host(host.into(), port),
+                Err(e) => panic!("invalid upstream port in `{name}`: {e}"),
+            },
+            None => Source::Host(name, 80),
+        };
+
+        Upstream::with_pool(Pool::dns(source))
+    }
+
+    fn with_pool(pool: Pool) -> Self {
+        Upstream {
+            pool: Arc::new(pool),
+            health: None,
+            balance: Arc::new(Balance::default()),
+            breaker: None,
+            timeout: Duration::from_secs(30),
+            rank: Self::DEFAULT_RANK,
+        }
+    }
+
+    /// Checks the health of every backend each `interval` by sending it a
+    /// `GET` request for `path`. A backend that doesn't respond in time or
+    /// responds with a status other than `2xx` or `3xx` isn't forwarded
+    /// requests until a later check succeeds.
+    ///
+    /// Checks begin when the first request is forwarded.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use rocket::proxy::Upstream;
+    ///
+    /// let upstream = Upstream::dns("_http._tcp.api.internal")
+    ///     .health_check("/health", Duration::from_secs(5));
+    /// ```
+    pub fn health_check<P: Into<String>>(mut self, path: P, interval: Duration) -> Self {
+        self.health = Some(Arc::new(HealthCheck { path: path.into(), interval }));
+        self
+    }
+
+    /// Sets the strategy for distributing requests among backends. Defaults
+    /// to [`Balance::RoundRobin`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::proxy::{Upstream, Balance};
+    ///
+    /// let upstream = Upstream::dns("_http._tcp.api.internal")
+    ///     .balance(Balance::LeastConnections);
+    /// ```
+    pub fn balance(mut self, balance: Balance) -> Self {
+        self.balance = Arc::new(balance);
+        self
+    }
+
+    /// Opens a backend's circuit after `failures` consecutive failed requests
+    /// to it, taking it out of rotation for `cooldown`. A request fails if the
+    /// backend can't be connected to, doesn't respond in time, responds
+    /// invalidly, or responds with a `5xx` status.
+    ///
+    /// Once `cooldown` has passed, the circuit _half-opens_: a single trial
+    /// request is forwarded to the backend. If it succeeds, the circuit
+    /// closes; otherwise, it opens for another `cooldown`. By default, there
+    /// is no circuit breaking.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `failures` is `0`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use rocket::proxy::Upstream;
+    ///
+    /// let upstream = Upstream::new(["http://10.0.0.1:8000", "http://10.0.0.2:8000"])
+    ///     .circuit_breaker(5, Duration::from_secs(30));
+    /// ```
+    #[track_caller]
+    pub fn circuit_breaker(mut self, failures: u32, cooldown: Duration) -> Self {
+        assert!(failures > 0, "circuit breakers must allow at least one failure");
+        self.breaker = Some(Breaker { failures, cooldown });
+        self
+    }
+
+    /// Sets the time to wait for a backend to accept a connection and, once
+    /// it has, to respond with a response head. Defaults to 30 seconds.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use rocket::proxy::Upstream;
+    ///
+    /// let upstream = Upstream::new(["http://10.0.0.1:8000"])
+    ///     .timeout(Duration::from_secs(5));
+    /// ```
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Sets the rank for the routes created by `self` to `rank`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::proxy::Upstream;
+    ///
+    /// // A `rank` of `15` or higher lets routes of the default rank take
+    /// // precedence over the upstream.
+    /// let upstream = Upstream::new(["http://10.0.0.1:8000"]).rank(15);
+    /// ```
+    pub fn rank(mut self, rank: isize) -> Self {
+        self.rank = rank;
+        self
+    }
+
+    async fn forward<'r>(
+        &self,
+        req: &'r Request<'_>,
+        data: Data<'r>,
+    ) -> Result<Response<'r>, Status> {
+        if req.headers().contains("Upgrade") {
+            debug!(name: "proxy", "protocol upgrades can't be forwarded");
+            return Err(Status::NotImplemented);
+        }
+
+        self.start_health_checks();
+        let limit = req.limits().get("proxy").unwrap_or(Self::DEFAULT_LIMIT);
+        let body = data.open(limit).into_bytes().await.map_err(|e| {
+            debug!(name: "proxy", "failed to read request body: {e}");
+            Status::BadRequest
+        })?;
+
+        if !body.is_complete() {
+            return Err(Status::PayloadTooLarge);
+        }
+
+        let body = body.into_inner();
+        let body = (!body.is_empty()).then(|| Bytes::from(body));
+        let backends = self.pool.backends().await;
+        let mut candidates = self.pool.candidates(&backends);
+        if candidates.is_empty() {
+            warn!(name: "proxy", "no available upstream backends");
+            return Err(Status::ServiceUnavailable);
+        }
+
+        self.balance.order(req, &mut candidates, self.pool.next());
+        for backend in candidates.iter().take(MAX_ATTEMPTS) {
+            let request = forwarded(req, backend, body.clone())?;
+            let in_flight = backend.start(self.breaker);
+            let result = send(backend, request, self.timeout).await;
+            if let Some(breaker) = self.breaker {
+                let succeeded = matches!(&result, Ok(r) if !r.status().is_server_error());
+                backend.record(succeeded, breaker);
+            }
+
+            match result {
+                Ok(response) => return Ok(into_response(response, in_flight)),
+                Err(Failure::Connect(e)) => {
+                    warn!(name: "proxy", backend = %backend.authority, host = %backend.host,
+                        "failed to connect to backend: {e}");
+
+                    let cooldown = self.health.as_ref().map_or(COOLDOWN, |h| h.interval);
+                    backend.mark_down(cooldown);
+                }
+                Err(Failure::Exchange(e)) => {
+                    warn!(name: "proxy", backend = %backend.authority,
+                        "backend exchange failed: {e}");
+                    return Err(Status::BadGateway);
+                }
+                Err(Failure::Timeout) => {
+                    warn!(name: "proxy", backend = %backend.authority, "backend timed out");
+                    return Err(Status::GatewayTimeout);
+                }
+            }
+        }
+
+        Err(Status::BadGateway)
+    }
+
+    /// Spawns the task that checks the health of backends, if there are
+    /// health checks and it hasn't yet been spawned. The task exits once the
+    /// `Upstream` is dropped.
+    fn start_health_checks(&self) {
+        let Some(health) = self.health.clone() else { return };
+        if self.pool.checking.swap(true, Ordering::AcqRel) {
+            return;
+        }
+
+        let (pool, timeout) = (Arc::downgrade(&self.pool), self.timeout);
+        tokio::spawn(health_checks(pool, health, timeout));
+    }
+}
+
+async fn health_checks(pool: Weak<Pool>, health: Arc<HealthCheck>, timeout: Duration) {
+    loop {
+        let Some(pool) = pool.upgrade() else { break };
+        let backends = pool.backends().await;
+        drop(pool);
+
+        let checks = backends.iter().map(|backend| async {
+            let request = hyper::Request::get(health.path.as_str())
+                .header("Host", backend.authority.as_str())
+                .body(Buffered(None));
+
+            let healthy = match request {
+                Ok(request) => match send(backend, request, timeout).await {
+                    Ok(response) => response.status().is_success()
+                        || response.status().is_redirection(),
+                    Err(_) => false,
+                },
+                Err(_) => false,
+            };
+
+            match healthy {
+                true => backend.mark_up(),
+                false => {
+                    if backend.is_up(Instant::now()) {
+                        warn!(name: "proxy", backend = %backend.authority, host = %backend.host,
+                            "backend failed health check");
+                    }
+
+                    backend.mark_down(health.interval * 2);
+                }
+            }
+        });
+
+        futures::future::join_all(checks).await;
+        tokio::time::sleep(health.interval).await;
+    }
+}
+
+/// Builds the request forwarded to `backend` for `req`.
+fn forwarded(
+    req: &Request<'_>,
+    backend: &Backend,
+    body: Option<Bytes>,
+) -> Result<hyper::Request<Buffered>, Status> {
+    let mut path = backend.prefix.clone();
+    let mount_segments = req.route().map_or(0, |r| r.uri.metadata.base_len);
+    for segment in req.uri().path().raw_segments().skip(mount_segments) {
+        path.push('/');
+        path.push_str(segment.as_str());
+    }
+
+    if path.is_empty() {
+        path.push('/');
+    }
+
+    if let Some(query) = req.uri().query() {
+        path.push('?');
+        path.push_str(query.as_str());
+    }
+
+    let connection: Vec<_> = req.headers().get("Connection").flat_map(|v| v.split(',')).collect();
+    let mut builder = hyper::Request::builder()
+        .method(req.method().as_str())
+        .uri(path)
+        .header("Host", backend.authority.as_str());
+
+    for header in req.headers().iter() {
+        let name = header.name().as_str();
+        if !is_hop_by_hop(name, &connection) {
+            builder = builder.header(name, header.value());
+        }
+    }
+
+    let mut forwarded_for: Vec<_> = req.headers().get("X-Forwarded-For").collect();
+    let client_ip = req.client_ip().map(|ip| ip.to_string());
+    forwarded_for.extend(client_ip.as_deref());
+    if !forwarded_for.is_empty() {
+        builder = builder.header("X-Forwarded-For", forwarded_for.join(", "));
+    }
+
+    let host = req.host().map(|host| host.to_string());
+    if let Some(host) = host.as_deref().or_else(|| req.headers().get_one("Host")) {
+        builder = builder.header("X-Forwarded-Host", host);
+    }
+
+    if let Some(body) = &body {
+        builder = builder.header("Content-Length", body.len());
+    }
+
+    builder.body(Buffered(body)).map_err(|e| {
+        debug!(name: "proxy", "request can't be forwarded: {e}");
+        Status::BadRequest
+    })
+}
+
+/// Returns `true` if the header `name` isn't forwarded: it's in [`HOP_BY_HOP`]
+/// or named by the message's `Connection` header values, `connection`.
+fn is_hop_by_hop(name: &str, connection: &[&str]) -> bool {
+    HOP_BY_HOP.iter().copied()
+        .chain(connection.iter().map(|v| v.trim()))
+        .any(|h| h.eq_ignore_ascii_case(name))
+}
+
+/// Sends `request` to `backend` over a new connection.
+async fn send(
+    backend: &Backend,
+    request: hyper::Request<Buffered>,
+    timeout: Duration,
+) -> Result<hyper::Response<Incoming>, Failure> {
+    let connect = TcpStream::connect((&*backend.host, backend.port));
+    let stream = match tokio::time::timeout(timeout, connect).await {
+        Ok(Ok(stream)) => stream,
+        Ok(Err(e)) => return Err(Failure::Connect(e)),
+        Err(_) => return Err(Failure::Connect(io::ErrorKind::TimedOut.into())),
+    };
+
+    let exchange = async {
+        let (mut sender, connection) = http1::handshake(TokioIo::new(stream)).await?;
+        tokio::spawn(async move {
+            if let Err(e) = connection.await {
+                debug!(name: "proxy", "backend connection failed: {e}");
+            }
+        });
+
+        sender.send_request(request).await
+    };
+
+    match tokio::time::timeout(timeout, exchange).await {
+        Ok(result) => result.map_err(Failure::Exchange),
+        Err(_) => Err(Failure::Timeout),
+    }
+}
+
+/// Converts a backend's response into a response to the client, streaming
+/// the body. The request stays `in_flight` until the body is dropped.
+fn into_response<'r>(response: hyper::Response<Incoming>, in_flight: InFlight) -> Response<'r> {
+    let (parts, body) = response.into_parts();
+    let connection: Vec<_> = parts.headers.get_all("Connection").iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .collect();
+
+    let mut builder = Response::build();
+    builder.status(Status::new(parts.status.as_u16()));
+    for (name, value) in &parts.headers {
+        match value.to_str() {
+            Ok(value) if !is_hop_by_hop(name.as_str(), &connection) => {
+                builder.raw_header_adjoin(name.as_str().to_owned(), value.to_owned());
+            }
+            _ => continue,
+        }
+    }
+
+    let reader = StreamReader::new(RawStream::from(body));
+    builder.streamed_body(Counted { reader, _in_flight: in_flight }).finalize()
+}
+
+impl From<Upstream> for Vec<Route> {
+    fn from(upstream: Upstream) -> Self {
+        METHODS.iter()
+            .map(|&method| {
+                let mut route = Route::ranked(upstream.rank, method, "/<path..>", upstream.clone());
+                route.name = Some("Upstream".into());
+                route
+            })
+            .collect()
+    }
+}
+
+#[crate::async_trait]
+impl Handler for Upstream {
+    async fn handle<'r>(&self, req: &'r Request<'_>, data: Data<'r>) -> Outcome<'r> {
+        match self.forward(req, data).await {
+            Ok(response) => Outcome::Success(response),
+            Err(status) => Outcome::Error(status),
+        }
+    }
+}
+
+impl<R: AsyncRead> AsyncRead for Counted<R> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        self.project().reader.poll_read(cx, buf)
+    }
+}
+
+impl HttpBody for Buffered {
+    type Data = Bytes;
+    type Error = Infallible;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        Poll::Ready(self.get_mut().0.take().map(|data| Ok(Frame::data(data))))
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.0.is_none()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        SizeHint::with_exact(self.0.as_ref().map_or(0, |data| data.len() as u64))
+    }
+}
@@ -0,0 +1,139 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use time::{Date, Month, OffsetDateTime};
+
+use crate::http::Status;
+
+/// The quota configuration: the `quota` configuration parameter.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::quota::{QuotaConfig, Unit};
+/// use rocket::figment::{Figment, providers::{Format, Toml}};
+///
+/// let figment = Figment::from(Toml::string(r#"
+///     [quota]
+///     monthly = 5_000_000_000
+///     unit = "bytes"
+///     status = 402
+/// "#));
+///
+/// let config: QuotaConfig = figment.extract_inner("quota").unwrap();
+/// assert_eq!(config.daily, None);
+/// assert_eq!(config.monthly, Some(5_000_000_000));
+/// assert_eq!(config.unit, Unit::Bytes);
+/// assert_eq!(config.status.code, 402);
+/// assert!(config.headers);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QuotaConfig {
+    /// The limit per UTC day, if any.
+    ///
+    /// **default: `None`**
+    pub daily: Option<u64>,
+    /// The limit per UTC calendar month, if any.
+    ///
+    /// **default: `None`**
+    pub monthly: Option<u64>,
+    /// What limits count.
+    ///
+    /// **default: [`Unit::Requests`]**
+    pub unit: Unit,
+    /// The status of the response to a request over quota.
+    ///
+    /// **default: `429 Too Many Requests`**
+    pub status: Status,
+    /// Whether to add `X-Quota-*` headers to responses.
+    ///
+    /// **default: `true`**
+    pub headers: bool,
+}
+
+/// What a quota counts.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Unit {
+    /// Each request counts `1`.
+    Requests,
+    /// Each request counts the bytes it used, as reported by
+    /// [`Usage::total()`](crate::request::Usage::total()).
+    Bytes,
+}
+
+/// A period over which usage is counted.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Period {
+    /// A UTC day.
+    Day,
+    /// A UTC calendar month.
+    Month,
+}
+
+impl Default for QuotaConfig {
+    fn default() -> Self {
+        QuotaConfig {
+            daily: None,
+            monthly: None,
+            unit: Unit::Requests,
+            status: Status::TooManyRequests,
+            headers: true,
+        }
+    }
+}
+
+impl QuotaConfig {
+    /// Returns the configured periods and their limits.
+    pub(crate) fn limits(&self) -> impl Iterator<Item = (Period, u64)> {
+        let daily = self.daily.map(|limit| (Period::Day, limit));
+        let monthly = self.monthly.map(|limit| (Period::Month, limit));
+        daily.into_iter().chain(monthly)
+    }
+}
+
+impl Period {
+    /// Returns the name of the period containing `now`, such as `2024-03-09`
+    /// for a day or `2024-03` for a month, and the time at which the period
+    /// ends.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::quota::Period;
+    /// use time::macros::datetime;
+    ///
+    /// let now = datetime!(2024-12-09 13:37 UTC);
+    /// assert_eq!(Period::Day.window(now), ("2024-12-09".into(), datetime!(2024-12-10 0:00 UTC)));
+    /// assert_eq!(Period::Month.window(now), ("2024-12".into(), datetime!(2025-01-01 0:00 UTC)));
+    /// ```
+    pub fn window(self, now: OffsetDateTime) -> (String, OffsetDateTime) {
+        let date = now.date();
+        match self {
+            Period::Day => {
+                let end = date.next_day().unwrap_or(date);
+                (date.to_string(), end.midnight().assume_utc())
+            }
+            Period::Month => {
+                let (year, month) = match date.month() {
+                    Month::December => (date.year() + 1, Month::January),
+                    month => (date.year(), month.next()),
+                };
+
+                let end = Date::from_calendar_date(year, month, 1).unwrap_or(date);
+                let name = format!("{}-{:02}", date.year(), date.month() as u8);
+                (name, end.midnight().assume_utc())
+            }
+        }
+    }
+}
+
+impl fmt::Display for Period {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Period::Day => "day".fmt(f),
+            Period::Month => "month".fmt(f),
+        }
+    }
+}
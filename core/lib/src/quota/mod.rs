@@ -0,0 +1,69 @@
+//! Daily and monthly usage quotas per principal.
+//!
+//! The [`Quota`] fairing limits how many requests, or how many bytes, each
+//! _principal_ may use per UTC day and per UTC calendar month. A principal is
+//! whatever a request is billed to: by default, the client's IP address, but
+//! typically an API key or account identified by a closure passed to
+//! [`Quota::principal()`]. Requests without a principal are not metered.
+//!
+//! Counts are kept in a [`QuotaStore`]. The default, [`MemoryStore`], keeps
+//! counts in memory; implement `QuotaStore` to share counts between instances
+//! or to persist them across restarts.
+//!
+//! # Configuration
+//!
+//! Quotas are configured via the `quota` configuration parameter, which is
+//! deserialized as a [`QuotaConfig`]:
+//!
+//! ```toml
+//! [default.quota]
+//! daily = 1000
+//! monthly = 20000
+//! unit = "requests"
+//! status = 429
+//! ```
+//!
+//! # Enforcement
+//!
+//! Before a request is routed, the principal's usage in each configured
+//! period is compared to the limit. If any limit has been reached, the request
+//! is not routed; instead, the error catcher for the configured status, `429
+//! Too Many Requests` by default or `402 Payment Required` for paid plans, is
+//! invoked, and a `Retry-After` header indicating when the exhausted period
+//! resets is added to the response. Otherwise, a request counts `1` toward
+//! request quotas when it is received and its [`Usage`] counts toward byte
+//! quotas once its response has been written. Byte quotas thus require
+//! [`metering`](crate::Config::metering) to be enabled; ignition fails
+//! otherwise.
+//!
+//! Unless disabled, every response to a metered request carries headers
+//! reporting the state of the principal's most constrained quota:
+//!
+//!   * `X-Quota-Limit`: the limit of the period
+//!   * `X-Quota-Remaining`: the amount that remains in the period
+//!   * `X-Quota-Reset`: the number of seconds until the period resets
+//!
+//! # Example
+//!
+//! ```rust
+//! # #[macro_use] extern crate rocket;
+//! use rocket::quota::Quota;
+//!
+//! #[launch]
+//! fn rocket() -> _ {
+//!     let quota = Quota::new()
+//!         .principal(|req| req.headers().get_one("X-Api-Key").map(|k| k.to_string()));
+//!
+//!     rocket::build().attach(quota)
+//! }
+//! ```
+//!
+//! [`Usage`]: crate::request::Usage
+
+mod config;
+mod store;
+mod quota;
+
+pub use config::{QuotaConfig, Period, Unit};
+pub use store::{QuotaStore, MemoryStore};
+pub use quota::Quota;
@@ -0,0 +1,223 @@
+use std::sync::Arc;
+use std::collections::HashMap;
+
+use parking_lot::Mutex;
+use state::InitCell;
+use time::OffsetDateTime;
+
+use crate::{Rocket, Request, Response, Data, Build};
+use crate::response;
+use crate::fairing::{self, Fairing, Info, Kind};
+use crate::http::{Header, Status};
+use crate::request::Usage;
+use crate::trace::Trace;
+use crate::quota::{QuotaConfig, QuotaStore, MemoryStore, Unit};
+
+/// A [`Fairing`] that enforces [quotas](crate::quota) per principal.
+///
+/// Limits are read from the `quota` configuration parameter at ignition. If the
+/// parameter is invalid, ignition fails. If no limit is configured, `Quota`
+/// does nothing. See the [module documentation](crate::quota) for details on
+/// how quotas are counted and enforced.
+///
+/// # Example
+///
+/// Bill requests to the account identified by a header and keep counts in a
+/// custom store:
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::quota::{Quota, MemoryStore};
+///
+/// # type SharedStore = MemoryStore;
+/// #[launch]
+/// fn rocket() -> _ {
+///     let quota = Quota::new()
+///         .principal(|req| req.headers().get_one("X-Account").map(|a| a.to_string()))
+///         .store(SharedStore::new());
+///
+///     rocket::build().attach(quota)
+/// }
+/// ```
+pub struct Quota {
+    principal: Box<dyn Fn(&Request<'_>) -> Option<String> + Send + Sync>,
+    store: Arc<dyn QuotaStore>,
+    config: InitCell<QuotaConfig>,
+    pending: Arc<Mutex<HashMap<String, u64>>>,
+}
+
+/// The state of the most constrained quota of a metered request.
+#[derive(Debug, Copy, Clone)]
+struct State {
+    limit: u64,
+    remaining: u64,
+    reset: i64,
+    exceeded: Option<Status>,
+}
+
+/// The quota `State` of a request, if it is metered.
+struct Metered(Option<State>);
+
+impl Quota {
+    /// Returns a `Quota` fairing that identifies principals by client IP
+    /// address and keeps counts in a [`MemoryStore`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::quota::Quota;
+    ///
+    /// let quota = Quota::new();
+    /// ```
+    pub fn new() -> Self {
+        Quota {
+            principal: Box::new(|req| req.client_ip().map(|ip| ip.to_string())),
+            store: Arc::new(MemoryStore::new()),
+            config: InitCell::new(),
+            pending: Arc::default(),
+        }
+    }
+
+    /// Sets the function that identifies the principal of a request. Requests
+    /// for which `f` returns `None` are not metered.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::quota::Quota;
+    ///
+    /// let quota = Quota::new()
+    ///     .principal(|req| req.headers().get_one("X-Api-Key").map(|k| k.to_string()));
+    /// ```
+    pub fn principal<F>(mut self, f: F) -> Self
+        where F: Fn(&Request<'_>) -> Option<String> + Send + Sync + 'static
+    {
+        self.principal = Box::new(f);
+        self
+    }
+
+    /// Sets the store that keeps counts.
+    pub fn store<S: QuotaStore>(mut self, store: S) -> Self {
+        self.store = Arc::new(store);
+        self
+    }
+}
+
+impl Default for Quota {
+    fn default() -> Self {
+        Quota::new()
+    }
+}
+
+#[crate::async_trait]
+impl Fairing for Quota {
+    fn info(&self) -> Info {
+        Info {
+            name: "Quota",
+            kind: Kind::Ignite | Kind::Request | Kind::Dispatch | Kind::Response | Kind::Singleton,
+        }
+    }
+
+    async fn on_ignite(&self, rocket: Rocket<Build>) -> fairing::Result {
+        let config = match rocket.figment().contains("quota") {
+            true => match rocket.figment().extract_inner::<QuotaConfig>("quota") {
+                Ok(config) => config,
+                Err(e) => {
+                    e.trace_error();
+                    return Err(rocket);
+                }
+            },
+            false => QuotaConfig::default(),
+        };
+
+        let bytes = config.unit == Unit::Bytes && config.limits().next().is_some();
+        if bytes && !rocket.config().metering {
+            error!(name: "quota", "byte quotas require `metering` to be enabled");
+            return Err(rocket);
+        }
+
+        self.config.set(config);
+        Ok(rocket)
+    }
+
+    async fn on_request(&self, req: &mut Request<'_>, _: &mut Data<'_>) {
+        let Some(config) = self.config.try_get() else { return };
+        if config.limits().next().is_none() {
+            return;
+        }
+
+        let Some(principal) = (self.principal)(req) else { return };
+        req.set_principal(&*principal);
+        let now = OffsetDateTime::now_utc();
+        let windows: Vec<_> = config.limits()
+            .map(|(period, limit)| {
+                let (name, end) = period.window(now);
+                (format!("{principal}:{period}:{name}"), limit, end)
+            })
+            .collect();
+
+        // Charge the bytes used by the principal's completed requests.
+        let pending = self.pending.lock().remove(&principal);
+        if let Some(amount) = pending {
+            for (key, _, end) in &windows {
+                if let Err(e) = self.store.add(key, amount, *end).await {
+                    warn!(name: "quota", %key, "failed to update quota: {e}");
+                }
+            }
+        }
+
+        let mut state: Option<State> = None;
+        for (key, limit, end) in &windows {
+            let used = match self.store.get(key).await {
+                Ok(used) => used,
+                Err(e) => {
+                    warn!(name: "quota", %key, "failed to read quota: {e}");
+                    continue;
+                }
+            };
+
+            let remaining = limit.saturating_sub(used);
+            if state.map_or(true, |state| remaining < state.remaining) {
+                let reset = (*end - now).whole_seconds();
+                state = Some(State { limit: *limit, remaining, reset, exceeded: None });
+            }
+        }
+
+        if let Some(state) = state.as_mut() {
+            if state.remaining == 0 {
+                state.exceeded = Some(config.status);
+            } else if config.unit == Unit::Requests {
+                state.remaining -= 1;
+                for (key, _, end) in &windows {
+                    if let Err(e) = self.store.add(key, 1, *end).await {
+                        warn!(name: "quota", %key, "failed to update quota: {e}");
+                    }
+                }
+            } else {
+                let pending = self.pending.clone();
+                Usage::of(req).on_complete(move |usage| {
+                    *pending.lock().entry(principal).or_default() += usage.total();
+                });
+            }
+        }
+
+        req.local_cache(|| Metered(state));
+    }
+
+    async fn on_dispatch<'r>(&self, req: &'r Request<'_>) -> Option<response::Result<'r>> {
+        req.local_cache(|| Metered(None)).0.and_then(|state| state.exceeded).map(Err)
+    }
+
+    async fn on_response<'r>(&self, req: &'r Request<'_>, res: &mut Response<'r>) {
+        let Some(state) = req.local_cache(|| Metered(None)).0 else { return };
+        if state.exceeded.is_some() {
+            res.set_header(Header::new("Retry-After", state.reset.to_string()));
+        }
+
+        if self.config.try_get().map_or(false, |config| config.headers) {
+            res.set_header(Header::new("X-Quota-Limit", state.limit.to_string()));
+            res.set_header(Header::new("X-Quota-Remaining", state.remaining.to_string()));
+            res.set_header(Header::new("X-Quota-Reset", state.reset.to_string()));
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use std::io;
+use std::collections::HashMap;
+
+use parking_lot::Mutex;
+use time::OffsetDateTime;
+
+/// Storage for quota counts.
+///
+/// Counts are identified by string keys that name a principal, a period, and a
+/// window, such as `alice:day:2024-03-09`. Each count expires at the end of its
+/// window, after which it is no longer read; stores may discard it then.
+///
+/// Errors are logged and the request in question is allowed: quotas fail open.
+///
+/// # Example
+///
+/// A store that counts nothing, allowing every request:
+///
+/// ```rust
+/// use std::io;
+///
+/// use rocket::quota::{Quota, QuotaStore};
+/// use time::OffsetDateTime;
+///
+/// struct Unlimited;
+///
+/// #[rocket::async_trait]
+/// impl QuotaStore for Unlimited {
+///     async fn get(&self, _: &str) -> io::Result<u64> {
+///         Ok(0)
+///     }
+///
+///     async fn add(&self, _: &str, _: u64, _: OffsetDateTime) -> io::Result<u64> {
+///         Ok(0)
+///     }
+/// }
+///
+/// let quota = Quota::new().store(Unlimited);
+/// ```
+#[crate::async_trait]
+pub trait QuotaStore: Send + Sync + 'static {
+    /// Returns the current count for `key`, or `0` if there is none.
+    async fn get(&self, key: &str) -> io::Result<u64>;
+
+    /// Adds `amount` to the count for `key`, which expires at `expires`, and
+    /// returns the new count.
+    async fn add(&self, key: &str, amount: u64, expires: OffsetDateTime) -> io::Result<u64>;
+}
+
+/// A [`QuotaStore`] that keeps counts in memory.
+///
+/// Counts are lost when the application exits and aren't shared between
+/// instances. Expired counts are discarded as new counts are added.
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    counts: Mutex<HashMap<String, (u64, OffsetDateTime)>>,
+}
+
+impl MemoryStore {
+    /// Returns an empty `MemoryStore`.
+    pub fn new() -> Self {
+        MemoryStore::default()
+    }
+}
+
+#[crate::async_trait]
+impl QuotaStore for MemoryStore {
+    async fn get(&self, key: &str) -> io::Result<u64> {
+        let now = OffsetDateTime::now_utc();
+        let counts = self.counts.lock();
+        Ok(counts.get(key).filter(|(_, expires)| *expires > now).map_or(0, |(n, _)| *n))
+    }
+
+    async fn add(&self, key: &str, amount: u64, expires: OffsetDateTime) -> io::Result<u64> {
+        let now = OffsetDateTime::now_utc();
+        let mut counts = self.counts.lock();
+        if !counts.contains_key(key) {
+            counts.retain(|_, (_, expires)| *expires > now);
+        }
+
+        let (count, _) = counts.entry(key.to_string()).or_insert((0, expires));
+        *count = count.saturating_add(amount);
+        Ok(*count)
+    }
+}
@@ -0,0 +1,160 @@
+use std::fmt;
+use std::borrow::Cow;
+
+use parking_lot::Mutex;
+
+use crate::{Request, Config};
+use crate::http::{Header, Status};
+use crate::util::{EscapeHtml, EscapeJson};
+
+/// A record of why a guard in a route handler forwarded or failed.
+///
+/// In the `debug` profile, code generated by the route attributes records a
+/// `Diagnostic` every time a request, path, query, or data guard forwards or
+/// fails. The recorded trail for a request can be retrieved via
+/// [`Request::diagnostics()`] and is rendered on Rocket's default error pages
+/// and summarized in an `X-Rocket-Debug` response header. In any other
+/// profile, nothing is recorded.
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::Request;
+///
+/// #[catch(422)]
+/// fn unprocessable(req: &Request<'_>) -> String {
+///     req.diagnostics()
+///         .iter()
+///         .map(|d| d.to_string())
+///         .collect::<Vec<_>>()
+///         .join("\n")
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// The kind of guard: one of `"request"`, `"path"`, `"query"`, or
+    /// `"data"`.
+    pub guard: &'static str,
+    /// The name of the handler parameter bound to the guard.
+    pub parameter: &'static str,
+    /// The type of the guard as written in the handler's signature.
+    pub type_name: &'static str,
+    /// The route, as `METHOD /uri`, whose handler ran the guard, if known.
+    pub route: Option<String>,
+    /// `true` if the guard forwarded, `false` if it failed.
+    pub forwarded: bool,
+    /// The status the guard forwarded or failed with.
+    pub status: Status,
+    /// The rendered error, if the guard provided one.
+    pub reason: Option<String>,
+}
+
+/// The request-local trail of diagnostics.
+#[derive(Default)]
+pub(crate) struct Trail(Mutex<Vec<Diagnostic>>);
+
+impl Trail {
+    pub(crate) fn get(&self) -> Vec<Diagnostic> {
+        self.0.lock().clone()
+    }
+}
+
+impl Diagnostic {
+    /// The name of the response header summarizing a request's diagnostics.
+    pub const HEADER: &'static str = "X-Rocket-Debug";
+
+    /// Records a diagnostic in `req`'s trail if `req` is being handled in the
+    /// `debug` profile. Called by code generated by the route attributes.
+    #[doc(hidden)]
+    pub fn record(
+        req: &Request<'_>,
+        guard: &'static str,
+        parameter: &'static str,
+        type_name: &'static str,
+        forwarded: bool,
+        status: Status,
+        reason: Option<&dyn fmt::Display>,
+    ) {
+        if req.rocket().config().profile != Config::DEBUG_PROFILE {
+            return;
+        }
+
+        let diagnostic = Diagnostic {
+            guard,
+            parameter,
+            type_name,
+            route: req.route().map(|r| format!("{} {}", r.method, r.uri)),
+            forwarded,
+            status,
+            reason: reason.map(|r| r.to_string()),
+        };
+
+        req.local_cache(Trail::default).0.lock().push(diagnostic);
+    }
+
+    /// Returns the `X-Rocket-Debug` header summarizing `req`'s trail, if it
+    /// has one. Characters that aren't visible ASCII are replaced with `?`.
+    pub(crate) fn header(req: &Request<'_>) -> Option<Header<'static>> {
+        let trail = req.diagnostics();
+        if trail.is_empty() {
+            return None;
+        }
+
+        let value = trail.iter()
+            .map(|d| d.to_string())
+            .collect::<Vec<_>>()
+            .join("; ")
+            .chars()
+            .map(|c| if c == ' ' || c.is_ascii_graphic() { c } else { '?' })
+            .collect::<String>();
+
+        Some(Header::new(Self::HEADER, value))
+    }
+
+    /// Adds `trail` to the default error page `page`, which is either the HTML
+    /// or JSON variant as indicated by `json`.
+    pub(crate) fn annotate<'a>(page: Cow<'a, str>, json: bool, trail: &[Diagnostic]) -> Cow<'a, str> {
+        if trail.is_empty() {
+            return page;
+        }
+
+        if json {
+            let Some(prefix) = page.strip_suffix("\n  }\n}") else {
+                return page;
+            };
+
+            let items = trail.iter()
+                .map(|d| format!("\"{}\"", EscapeJson(&d.to_string())))
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            format!("{prefix},\n    \"diagnostics\": [{items}]\n  }}\n}}").into()
+        } else {
+            let items = trail.iter()
+                .map(|d| format!("            <li>{}</li>\n", EscapeHtml(&d.to_string())))
+                .collect::<String>();
+
+            let list = format!("<ul align=\"left\">\n{items}        </ul>\n        <hr />");
+            page.replacen("<hr />", &list, 1).into()
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let outcome = if self.forwarded { "forwarded" } else { "failed" };
+        write!(f, "{} guard `{}: {}` {} ({})",
+            self.guard, self.parameter, self.type_name, outcome, self.status.code)?;
+
+        if let Some(route) = &self.route {
+            write!(f, " in {route}")?;
+        }
+
+        if let Some(reason) = &self.reason {
+            write!(f, ": {reason}")?;
+        }
+
+        Ok(())
+    }
+}
@@ -6,7 +6,7 @@ use crate::{Request, Route};
 use crate::outcome::{self, IntoOutcome, Outcome::*};
 
 use crate::http::uri::{Host, Origin};
-use crate::http::{Status, ContentType, Accept, Method, ProxyProto, CookieJar};
+use crate::http::{Status, ContentType, Accept, AcceptLanguage, Method, ProxyProto, CookieJar};
 use crate::listener::Endpoint;
 
 /// Type alias for the `Outcome` of a `FromRequest` conversion.
@@ -155,6 +155,14 @@ pub type Outcome<S, E> = outcome::Outcome<S, (Status, E), Status>;
 ///     [`Request::accept()`]. If the request didn't specify an `Accept`, the
 ///     request is forwarded with a 500 Internal Server Error status.
 ///
+///   * **AcceptLanguage**
+///
+///     Extracts the parsed [`AcceptLanguage`] header from the incoming request
+///     via [`Request::accept_language()`]. If the request didn't specify an
+///     `Accept-Language`, the returned value is empty.
+///
+///     _This implementation always returns successfully._
+///
 ///   * ***IpAddr**
 ///
 ///     Extracts the client ip address of the incoming request as an [`IpAddr`]
@@ -173,6 +181,13 @@ pub type Outcome<S, E> = outcome::Outcome<S, (Status, E), Status>;
 ///     via [`Request::remote()`]. If the remote address is not known, the
 ///     request is forwarded with a 500 Internal Server Error status.
 ///
+///   * **&Usage**
+///
+///     Extracts the [`Usage`](crate::request::Usage) of the incoming request
+///     via [`Usage::of()`](crate::request::Usage::of()).
+///
+///     _This implementation always returns successfully._
+///
 ///   * **Option&lt;T>** _where_ **T: FromRequest**
 ///
 ///     The type `T` is derived from the incoming request using `T`'s
@@ -453,6 +468,15 @@ impl<'r> FromRequest<'r> for &'r Accept {
     }
 }
 
+#[crate::async_trait]
+impl<'r> FromRequest<'r> for AcceptLanguage<'r> {
+    type Error = Infallible;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Infallible> {
+        Success(request.accept_language())
+    }
+}
+
 #[crate::async_trait]
 impl<'r> FromRequest<'r> for &'r ContentType {
     type Error = Infallible;
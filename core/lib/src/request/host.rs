@@ -0,0 +1,196 @@
+use std::fmt;
+use std::ops::Deref;
+
+use crate::Request;
+use crate::http::Status;
+use crate::http::uri::{self, Absolute};
+use crate::outcome::Outcome::*;
+use crate::request::{FromRequest, Outcome};
+
+/// A request guard for the host of a request, validated against the
+/// [`hosts`](crate::config::HostConfig) allowlist.
+///
+/// The host is read from the configured
+/// [`forwarded_header`](crate::config::HostConfig::forwarded_header), if it's
+/// present, and from the `Host` header or request target otherwise. Unlike the
+/// unvalidated [`&uri::Host`](crate::http::uri::Host) guard, this guard fails
+/// with a status of `400 Bad Request` unless the host is allowed. As such, it
+/// can safely be used to build links that leave the application, such as those
+/// in emails. See [`AbsoluteUri`] for a guard that does just that.
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::request::Host;
+///
+/// #[get("/")]
+/// fn index(host: Host<'_>) -> String {
+///     format!("Welcome to {}!", host.domain())
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Host<'r>(uri::Host<'r>);
+
+/// A request guard for the absolute URI of a request: its scheme, its
+/// validated [`Host`], and its origin.
+///
+/// The scheme is `https` if [`Request::context_is_likely_secure()`] and `http`
+/// otherwise, so that it honors TLS and the
+/// [`proxy_proto_header`](crate::Config::proxy_proto_header). The guard fails
+/// as [`Host`] does.
+///
+/// # Example
+///
+/// Build a password reset link that can't be pointed elsewhere via the `Host`
+/// header:
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::request::AbsoluteUri;
+///
+/// #[get("/reset?<token>")]
+/// fn reset(token: &str) { /* .. */ }
+///
+/// #[post("/forgot")]
+/// fn forgot(absolute: AbsoluteUri<'_>) -> String {
+///     let base = absolute.base();
+///     let link = uri!(base, reset("s3cr3t"));
+///     format!("sending {link}")
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AbsoluteUri<'r> {
+    host: Host<'r>,
+    base: Absolute<'static>,
+    uri: Absolute<'static>,
+}
+
+/// The error of the [`Host`] and [`AbsoluteUri`] request guards.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum HostError {
+    /// The request has no host.
+    Missing,
+    /// The forwarded host is malformed.
+    Malformed,
+    /// The host isn't allowed.
+    Disallowed,
+}
+
+impl<'r> Host<'r> {
+    /// Returns the validated host of `req`.
+    fn resolve(req: &'r Request<'_>) -> Result<Self, HostError> {
+        let config = &req.rocket().config().hosts;
+        let forwarded = config.forwarded_header.as_ref()
+            .and_then(|name| req.headers().get_one(name.as_str()))
+            .and_then(|value| value.split(',').next())
+            .map(|value| uri::Host::parse(value.trim()).map_err(|_| HostError::Malformed));
+
+        let host = match forwarded {
+            Some(host) => host?,
+            None => req.host().cloned().ok_or(HostError::Missing)?,
+        };
+
+        if !config.allows(&host) {
+            warn!(name: "host", %host, allowed = ?config.allow, "request to disallowed host");
+            return Err(HostError::Disallowed);
+        }
+
+        Ok(Host(host))
+    }
+
+    /// Returns the underlying [`uri::Host`].
+    pub fn into_inner(self) -> uri::Host<'r> {
+        self.0
+    }
+}
+
+impl<'r> AbsoluteUri<'r> {
+    /// Returns the validated host.
+    pub fn host(&self) -> &Host<'r> {
+        &self.host
+    }
+
+    /// Returns the scheme and host, without a path, for prefixing URIs built
+    /// with [`uri!`](crate::uri!).
+    pub fn base(&self) -> Absolute<'static> {
+        self.base.clone()
+    }
+
+    /// Returns the absolute URI.
+    pub fn into_inner(self) -> Absolute<'static> {
+        self.uri
+    }
+}
+
+impl<'r> Deref for Host<'r> {
+    type Target = uri::Host<'r>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Deref for AbsoluteUri<'_> {
+    type Target = Absolute<'static>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.uri
+    }
+}
+
+impl fmt::Display for Host<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl fmt::Display for AbsoluteUri<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.uri.fmt(f)
+    }
+}
+
+impl fmt::Display for HostError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HostError::Missing => "missing host".fmt(f),
+            HostError::Malformed => "malformed forwarded host".fmt(f),
+            HostError::Disallowed => "disallowed host".fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for HostError {}
+
+#[crate::async_trait]
+impl<'r> FromRequest<'r> for Host<'r> {
+    type Error = HostError;
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, HostError> {
+        match Host::resolve(req) {
+            Ok(host) => Success(host),
+            Err(e) => Error((Status::BadRequest, e)),
+        }
+    }
+}
+
+#[crate::async_trait]
+impl<'r> FromRequest<'r> for AbsoluteUri<'r> {
+    type Error = HostError;
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, HostError> {
+        let host = match Host::resolve(req) {
+            Ok(host) => host,
+            Err(e) => return Error((Status::BadRequest, e)),
+        };
+
+        let scheme = if req.context_is_likely_secure() { "https" } else { "http" };
+        let base = Absolute::parse_owned(format!("{scheme}://{host}"));
+        let uri = Absolute::parse_owned(format!("{scheme}://{host}{}", req.uri()));
+        match (base, uri) {
+            (Ok(base), Ok(uri)) => Success(AbsoluteUri { host, base, uri }),
+            _ => Error((Status::BadRequest, HostError::Malformed)),
+        }
+    }
+}
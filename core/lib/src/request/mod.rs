@@ -4,6 +4,13 @@ mod request;
 mod from_param;
 mod from_request;
 mod atomic_method;
+mod diagnostic;
+mod pipeline;
+mod usage;
+mod host;
+
+#[cfg(feature = "user-agent")]
+mod user_agent;
 
 #[cfg(test)]
 mod tests;
@@ -11,6 +18,14 @@ mod tests;
 pub use self::request::Request;
 pub use self::from_request::{FromRequest, Outcome};
 pub use self::from_param::{FromParam, FromSegments};
+pub use self::diagnostic::Diagnostic;
+pub use self::pipeline::Stage;
+pub use self::usage::Usage;
+pub use self::host::{Host, AbsoluteUri, HostError};
+
+#[cfg(feature = "user-agent")]
+#[cfg_attr(nightly, doc(cfg(feature = "user-agent")))]
+pub use self::user_agent::{UserAgent, DeviceClass};
 
 #[doc(hidden)]
 pub use rocket_codegen::FromParam;
@@ -20,6 +35,11 @@ pub use crate::response::flash::FlashMessage;
 
 pub(crate) use self::request::ConnectionMeta;
 pub(crate) use self::atomic_method::AtomicMethod;
+pub(crate) use self::diagnostic::Trail;
+pub(crate) use self::pipeline::Pipeline;
+
+#[doc(hidden)]
+pub use self::pipeline::Timer;
 
 crate::export! {
     /// Store and immediately retrieve a vector-like value `$v` (`String` or
@@ -0,0 +1,209 @@
+use std::borrow::Cow;
+use std::fmt::Write;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+
+use crate::Request;
+
+/// A timed stage in the processing of a request.
+///
+/// For requests sampled by the [`Inspector`](crate::inspector::Inspector),
+/// Rocket records every request fairing after the inspector, dispatch fairing,
+/// route attempt, request and data guard, handler, responder, catcher, and
+/// response fairing that runs, in the order they start, as a `Stage`. Guards, handlers, and responders are nested
+/// under the route attempt that ran them. The recorded stages of a request can
+/// be retrieved via [`Request::pipeline()`]. For requests that aren't sampled,
+/// nothing is recorded.
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::Request;
+///
+/// #[catch(500)]
+/// fn internal_error(req: &Request<'_>) -> String {
+///     req.pipeline()
+///         .iter()
+///         .map(|s| format!("{} {}: {:?}", s.kind, s.name, s.elapsed))
+///         .collect::<Vec<_>>()
+///         .join("\n")
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stage {
+    /// The kind of stage: one of `"request"`, `"dispatch"`, or `"response"`
+    /// for fairings, `"route"`, `"guard"`, `"handler"`, `"responder"`, or
+    /// `"catcher"`.
+    pub kind: &'static str,
+    /// The name of the fairing, route, guard, handler, responder, or catcher.
+    pub name: Cow<'static, str>,
+    /// The index of the stage this stage is nested under, if any.
+    pub parent: Option<usize>,
+    /// When the stage started, relative to when recording began.
+    pub start: Duration,
+    /// How long the stage ran for, including nested stages. Zero for a stage
+    /// that is still running.
+    pub elapsed: Duration,
+}
+
+/// The request-local recording of a sampled request's stages.
+pub(crate) struct Pipeline {
+    origin: Instant,
+    stages: Arc<Mutex<Vec<Stage>>>,
+    open: Mutex<Option<usize>>,
+}
+
+/// A handle to a stage started via [`Stage::start()`].
+#[doc(hidden)]
+pub struct Timer(Option<Instant>);
+
+impl Pipeline {
+    /// Begins recording the stages of `req`.
+    pub(crate) fn begin(req: &Request<'_>) {
+        req.local_cache(|| Pipeline {
+            origin: Instant::now(),
+            stages: Arc::default(),
+            open: Mutex::new(None),
+        });
+    }
+
+    /// Returns the stages of `req`, shared so that stages recorded later are
+    /// seen, if `req` is being recorded.
+    pub(crate) fn shared(req: &Request<'_>) -> Option<Arc<Mutex<Vec<Stage>>>> {
+        req.state.cache.try_get::<Pipeline>().map(|p| p.stages.clone())
+    }
+
+    /// Opens a stage of `kind` named `name()` that stages recorded until it's
+    /// [closed](Self::close()) are nested under, returning its index.
+    pub(crate) fn open<F, N>(req: &Request<'_>, kind: &'static str, name: F) -> Option<usize>
+        where F: FnOnce() -> N, N: Into<Cow<'static, str>>
+    {
+        let pipeline = req.state.cache.try_get::<Pipeline>()?;
+        let mut stages = pipeline.stages.lock();
+        stages.push(Stage {
+            kind,
+            name: name().into(),
+            parent: None,
+            start: pipeline.origin.elapsed(),
+            elapsed: Duration::ZERO,
+        });
+
+        let index = stages.len() - 1;
+        *pipeline.open.lock() = Some(index);
+        Some(index)
+    }
+
+    /// Closes the stage at `index`, as returned by [`Self::open()`].
+    pub(crate) fn close(req: &Request<'_>, index: Option<usize>) {
+        let (Some(pipeline), Some(i)) = (req.state.cache.try_get::<Pipeline>(), index) else {
+            return;
+        };
+
+        if let Some(stage) = pipeline.stages.lock().get_mut(i) {
+            stage.elapsed = pipeline.origin.elapsed().saturating_sub(stage.start);
+        }
+
+        let mut open = pipeline.open.lock();
+        if *open == Some(i) {
+            *open = None;
+        }
+    }
+}
+
+impl Stage {
+    /// Starts timing a stage of `req` if `req` is being recorded. Called by
+    /// Rocket and by code generated by the route attributes.
+    #[doc(hidden)]
+    #[inline]
+    pub fn start(req: &Request<'_>) -> Timer {
+        Timer(req.state.cache.try_get::<Pipeline>().map(|_| Instant::now()))
+    }
+
+    /// Records the stage timed by `timer` as a stage of `kind` named `name`,
+    /// nested under the open stage, if any. Called by Rocket and by code
+    /// generated by the route attributes.
+    #[doc(hidden)]
+    pub fn record<N>(req: &Request<'_>, timer: Timer, kind: &'static str, name: N)
+        where N: Into<Cow<'static, str>>
+    {
+        let (Some(started), Some(pipeline)) = (timer.0, req.state.cache.try_get::<Pipeline>())
+        else {
+            return;
+        };
+
+        let stage = Stage {
+            kind,
+            name: name.into(),
+            parent: *pipeline.open.lock(),
+            start: started.saturating_duration_since(pipeline.origin),
+            elapsed: started.elapsed(),
+        };
+
+        pipeline.stages.lock().push(stage);
+    }
+
+    /// Renders `stages` as folded stacks, as consumed by flamegraph tools, one
+    /// line per stage with its frames rooted at `root` and weighted by the
+    /// stage's self time in microseconds: its elapsed time less that of the
+    /// stages nested under it. Stages without self time are omitted.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use rocket::request::Stage;
+    ///
+    /// let stages = [
+    ///     Stage {
+    ///         kind: "route",
+    ///         name: "GET /".into(),
+    ///         parent: None,
+    ///         start: Duration::from_micros(5),
+    ///         elapsed: Duration::from_micros(100),
+    ///     },
+    ///     Stage {
+    ///         kind: "handler",
+    ///         name: "index".into(),
+    ///         parent: Some(0),
+    ///         start: Duration::from_micros(10),
+    ///         elapsed: Duration::from_micros(60),
+    ///     },
+    /// ];
+    ///
+    /// let folded = Stage::folded("GET /", &stages);
+    /// assert_eq!(folded, "GET /;route GET / 40\nGET /;route GET /;handler index 60\n");
+    /// ```
+    pub fn folded(root: &str, stages: &[Stage]) -> String {
+        fn frame(kind: &str, name: &str) -> String {
+            format!("{kind} {name}").replace([';', '\n'], ",")
+        }
+
+        let mut out = String::new();
+        for (i, stage) in stages.iter().enumerate() {
+            let mut frames = vec![frame(stage.kind, &stage.name)];
+            let mut at = i;
+            while let Some(p) = stages[at].parent.filter(|&p| p < at) {
+                frames.push(frame(stages[p].kind, &stages[p].name));
+                at = p;
+            }
+
+            frames.push(root.replace([';', '\n'], ","));
+            frames.reverse();
+
+            let nested = stages.iter()
+                .filter(|s| s.parent == Some(i))
+                .map(|s| s.elapsed)
+                .sum::<Duration>();
+
+            let own = stage.elapsed.saturating_sub(nested).as_micros();
+            if own > 0 {
+                let _ = writeln!(out, "{} {}", frames.join(";"), own);
+            }
+        }
+
+        out
+    }
+}
@@ -11,11 +11,14 @@ use futures::future::BoxFuture;
 use ref_swap::OptionRefSwap;
 
 use crate::{Rocket, Route, Orbit};
+use crate::router::Router;
 use crate::request::{FromParam, FromSegments, FromRequest, Outcome, AtomicMethod};
+use crate::request::{Diagnostic, Trail, Stage, Pipeline};
 use crate::form::{self, ValueField, FromForm};
 use crate::data::Limits;
+use crate::catcher::Catalog;
 
-use crate::http::ProxyProto;
+use crate::http::{ProxyProto, AcceptLanguage};
 use crate::http::{Method, Header, HeaderMap, ContentType, Accept, MediaType, CookieJar, Cookie};
 use crate::http::uri::{fmt::Path, Origin, Segments, Host, Authority};
 use crate::listener::{Certificates, Endpoint};
@@ -56,24 +59,28 @@ impl ConnectionMeta {
 /// Information derived from the request.
 pub(crate) struct RequestState<'r> {
     pub rocket: &'r Rocket<Orbit>,
+    pub router: Arc<Router>,
     pub route: OptionRefSwap<'r, Route>,
     pub cookies: CookieJar<'r>,
     pub accept: InitCell<Option<Accept>>,
     pub content_type: InitCell<Option<ContentType>>,
     pub cache: Arc<TypeMap![Send + Sync]>,
     pub host: Option<Host<'r>>,
+    pub connect_target: Option<Authority<'r>>,
 }
 
 impl Clone for RequestState<'_> {
     fn clone(&self) -> Self {
         RequestState {
             rocket: self.rocket,
+            router: self.router.clone(),
             route: OptionRefSwap::new(self.route.load(Ordering::Acquire)),
             cookies: self.cookies.clone(),
             accept: self.accept.clone(),
             content_type: self.content_type.clone(),
             cache: self.cache.clone(),
             host: self.host.clone(),
+            connect_target: self.connect_target.clone(),
         }
     }
 }
@@ -94,12 +101,14 @@ impl<'r> Request<'r> {
             connection: ConnectionMeta::default(),
             state: RequestState {
                 rocket,
+                router: rocket.router.current(),
                 route: OptionRefSwap::new(None),
                 cookies: CookieJar::new(None, rocket),
                 accept: InitCell::new(),
                 content_type: InitCell::new(),
                 cache: Arc::new(<TypeMap![Send + Sync]>::new()),
                 host: None,
+                connect_target: None,
             }
         }
     }
@@ -299,6 +308,39 @@ impl<'r> Request<'r> {
         self.state.host = Some(host);
     }
 
+    /// Returns the target of a `CONNECT` request: the authority, such as
+    /// `db.internal:5432`, the client asks to be tunneled to. Returns `None`
+    /// for any other request.
+    ///
+    /// A `CONNECT` request's URI is an authority rather than a path. Such a
+    /// request is routed as if its URI were `/`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use rocket::uri;
+    /// # let c = rocket::local::blocking::Client::debug_with(vec![]).unwrap();
+    /// # let mut req = c.get("/");
+    /// # let request = req.inner_mut();
+    /// assert!(request.connect_target().is_none());
+    ///
+    /// request.set_connect_target(uri!("db.internal:5432"));
+    /// let target = request.connect_target().unwrap();
+    /// assert_eq!(target.host(), "db.internal");
+    /// assert_eq!(target.port(), Some(5432));
+    /// ```
+    #[inline(always)]
+    pub fn connect_target(&self) -> Option<&Authority<'r>> {
+        self.state.connect_target.as_ref()
+    }
+
+    /// Sets the target of `self`, a `CONNECT` request, to `target`. See
+    /// [`Request::connect_target()`].
+    #[inline(always)]
+    pub fn set_connect_target(&mut self, target: Authority<'r>) {
+        self.state.connect_target = Some(target);
+    }
+
     /// Returns the raw address of the remote connection that initiated this
     /// request if the address is known. If the address is not known, `None` is
     /// returned.
@@ -612,6 +654,38 @@ impl<'r> Request<'r> {
         self.headers.replace(header);
     }
 
+    /// Removes all headers with name `name` from `self`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::{ContentType, Header};
+    /// # let c = rocket::local::blocking::Client::debug_with(vec![]).unwrap();
+    /// # let mut req = c.get("/");
+    /// # let request = req.inner_mut();
+    ///
+    /// request.add_header(ContentType::HTML);
+    /// request.add_header(Header::new("X-Custom", "one"));
+    /// request.add_header(Header::new("X-Custom", "two"));
+    /// assert_eq!(request.headers().len(), 3);
+    ///
+    /// request.remove_header("X-Custom");
+    /// assert_eq!(request.headers().len(), 1);
+    ///
+    /// request.remove_header("Content-Type");
+    /// assert_eq!(request.content_type(), None);
+    /// ```
+    #[inline]
+    pub fn remove_header(&mut self, name: &str) {
+        if name.eq_ignore_ascii_case("Content-Type") {
+            self.state.content_type = InitCell::new();
+        } else if name.eq_ignore_ascii_case("Accept") {
+            self.state.accept = InitCell::new();
+        }
+
+        self.headers.remove(name);
+    }
+
     /// Returns the Content-Type header of `self`. If the header is not present,
     /// returns `None`.
     ///
@@ -654,6 +728,50 @@ impl<'r> Request<'r> {
             .as_ref()
     }
 
+    /// Returns the parsed Accept-Language header of `self`. If the header is
+    /// not present, the returned value [is empty](AcceptLanguage::is_empty()).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::Header;
+    ///
+    /// # let c = rocket::local::blocking::Client::debug_with(vec![]).unwrap();
+    /// # let get = |uri| c.get(uri);
+    /// assert!(get("/").accept_language().is_empty());
+    ///
+    /// let req = get("/").header(Header::new("Accept-Language", "de-CH, en;q=0.5"));
+    /// let accept = req.accept_language();
+    /// assert_eq!(accept.negotiate(&["en", "de"]), Some(&"de"));
+    /// ```
+    pub fn accept_language(&self) -> AcceptLanguage<'_> {
+        self.headers().get_one("Accept-Language").map(AcceptLanguage::parse).unwrap_or_default()
+    }
+
+    /// Returns the locale negotiated between the Accept-Language header of
+    /// `self` and the locales of the managed [`Catalog`], if any. Returns
+    /// `None` if no `Catalog` is managed.
+    ///
+    /// [`Catalog`]: crate::catcher::Catalog
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::catcher::Catalog;
+    /// use rocket::http::Header;
+    /// use rocket::local::blocking::Client;
+    ///
+    /// let catalog = Catalog::new().message("de", 404, "Nicht gefunden", "Nicht gefunden.");
+    /// let client = Client::debug(rocket::build().manage(catalog)).unwrap();
+    ///
+    /// let req = client.get("/").header(Header::new("Accept-Language", "de-CH, en;q=0.5"));
+    /// assert_eq!(req.locale(), Some("de"));
+    /// assert_eq!(client.get("/").locale(), None);
+    /// ```
+    pub fn locale(&self) -> Option<&'r str> {
+        self.rocket().state::<Catalog>().and_then(|catalog| catalog.negotiate(self))
+    }
+
     /// Returns the media type "format" of the request.
     ///
     /// The returned `MediaType` is derived from either the `Content-Type` or
@@ -783,6 +901,99 @@ impl<'r> Request<'r> {
         self.state.route.load(Ordering::Acquire)
     }
 
+    /// Returns the [path template](crate::route::RouteUri::template()) of the
+    /// presently matched route, such as `/users/<id>`, if any.
+    ///
+    /// Use the template instead of the request's path to label requests in
+    /// metrics and logs: there is one template per route but potentially
+    /// unboundedly many paths. Rocket records the template as the `route`
+    /// field of the `request` span for requests that are handled by a route.
+    /// As with [`Request::route()`], this method returns `None` before routing
+    /// has commenced.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::fairing::AdHoc;
+    ///
+    /// let metrics = AdHoc::on_response("Metrics", |req, res| Box::pin(async move {
+    ///     let route = req.route_template().unwrap_or("<unmatched>");
+    ///     println!("{} {} => {}", req.method(), route, res.status());
+    /// }));
+    /// ```
+    #[inline]
+    pub fn route_template(&self) -> Option<&'r str> {
+        self.route().map(|route| route.uri.template())
+    }
+
+    /// Records `principal` as the identity the request is made on behalf of.
+    ///
+    /// The principal is included in the shutdown [`DrainReport`] if the
+    /// request is force-terminated. Rocket records it automatically for
+    /// [`Authenticated`] handshakes and requests metered by a [`Quota`].
+    ///
+    /// [`DrainReport`]: crate::shutdown::DrainReport
+    /// [`Authenticated`]: crate::handshake::Authenticated
+    /// [`Quota`]: crate::quota::Quota
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::fairing::AdHoc;
+    ///
+    /// let identify = AdHoc::on_request("Identify", |req, _| Box::pin(async move {
+    ///     if let Some(user) = req.headers().get_one("X-User") {
+    ///         req.set_principal(user);
+    ///     }
+    /// }));
+    /// ```
+    pub fn set_principal<P: Into<String>>(&self, principal: P) {
+        crate::shutdown::InFlight::identify(self, principal.into());
+    }
+
+    /// Returns the trail of [`Diagnostic`]s recorded for guards that forwarded
+    /// or failed while handling this request, in the order they occurred.
+    ///
+    /// Diagnostics are only recorded in the `debug` profile. In any other
+    /// profile, the returned vector is always empty.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # let c = rocket::local::blocking::Client::debug_with(vec![]).unwrap();
+    /// # let request = c.get("/");
+    /// for diagnostic in request.diagnostics() {
+    ///     println!("{}", diagnostic);
+    /// }
+    /// ```
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        self.state.cache.try_get::<Trail>()
+            .map(|trail| trail.get())
+            .unwrap_or_default()
+    }
+
+    /// Returns the [`Stage`]s recorded so far while handling this request, in
+    /// the order they started.
+    ///
+    /// Stages are only recorded for requests sampled by the
+    /// [`Inspector`](crate::inspector::Inspector). For any other request, the
+    /// returned vector is always empty.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # let c = rocket::local::blocking::Client::debug_with(vec![]).unwrap();
+    /// # let request = c.get("/");
+    /// for stage in request.pipeline() {
+    ///     println!("{} {} took {:?}", stage.kind, stage.name, stage.elapsed);
+    /// }
+    /// ```
+    pub fn pipeline(&self) -> Vec<Stage> {
+        Pipeline::shared(self)
+            .map(|stages| stages.lock().clone())
+            .unwrap_or_default()
+    }
+
     /// Invokes the request guard implementation for `T`, returning its outcome.
     ///
     /// # Example
@@ -1062,6 +1273,14 @@ impl<'r> Request<'r> {
             .flatten()
     }
 
+    /// The route table `self` is routed against: the one that was current when
+    /// `self` was created. Holding it keeps the routes `self` refers to alive
+    /// even if the table is replaced while `self` is being handled.
+    #[inline(always)]
+    pub(crate) fn router(&self) -> &Arc<Router> {
+        &self.state.router
+    }
+
     /// Set `self`'s parameters given that the route used to reach this request
     /// was `route`. Use during routing when attempting a given route.
     #[inline(always)]
@@ -1104,6 +1323,13 @@ impl<'r> Request<'r> {
             }),
         };
 
+        // A `CONNECT` request's URI is the authority to tunnel to.
+        let connect_target = match method {
+            Method::Connect if hyper.uri.path_and_query().is_none() => hyper.uri.authority()
+                .and_then(|authority| Authority::parse(authority.as_str()).ok()),
+            _ => None,
+        };
+
         // TODO: Keep around not just the path/query, but the rest, if there?
         let uri = hyper.uri.path_and_query()
             .map(|uri| {
@@ -1125,12 +1351,16 @@ impl<'r> Request<'r> {
                 Origin::new(uri.path(), uri.query().map(Cow::Borrowed))
             })
             .unwrap_or_else(|| {
-                errors.push(RequestError::InvalidUri(hyper.uri.clone()));
+                if connect_target.is_none() {
+                    errors.push(RequestError::InvalidUri(hyper.uri.clone()));
+                }
+
                 Origin::root().clone()
             });
 
         // Construct the request object; fill in metadata and headers next.
         let mut request = Request::new(rocket, method, uri);
+        request.state.connect_target = connect_target;
         request.errors = errors;
 
         // Set the passed in connection metadata.
@@ -0,0 +1,247 @@
+use std::fmt;
+use std::sync::Arc;
+use std::convert::Infallible;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+use parking_lot::Mutex;
+
+use crate::{Request, Response};
+use crate::data::Data;
+use crate::http::HeaderMap;
+use crate::request::{FromRequest, Outcome};
+
+/// The number of bytes read and written on behalf of a request.
+///
+/// When [`metering`](crate::Config::metering) is enabled, Rocket tracks the
+/// size of every request and its response in four counters:
+///
+///   * the request's head: its request line and headers
+///   * the request's body: the bytes of the body read by the application
+///   * the response's head: its status line and headers
+///   * the response's body: the bytes of the body written to the client
+///
+/// Heads are measured as they would be serialized in HTTP/1.1, including
+/// line terminators, so that sizes are independent of the protocol in use.
+/// Headers added by the HTTP implementation after Rocket produces a response,
+/// such as `Date` and transfer framing, are not included.
+///
+/// The usage of a request is retrieved via [`Usage::of()`] or as a request
+/// guard. The counters are updated as data is read and written: in a handler,
+/// only the request's head and the body data read so far are known. Once the
+/// response body has been written or the response is dropped, usage is
+/// _complete_: a `DEBUG` level `usage` event is emitted in the request's span
+/// and callbacks registered via [`Usage::on_complete()`] are invoked. This
+/// makes `Usage` suitable for metering and billing.
+///
+/// Metering is disabled by default. When disabled, all counters remain `0`
+/// and usage never completes. Enable it by setting `metering = true` in
+/// `Rocket.toml` or via `ROCKET_METERING=true`.
+///
+/// # Example
+///
+/// Record the usage of every request:
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::fairing::AdHoc;
+/// use rocket::request::Usage;
+///
+/// # fn bill(_: &str, _: u64) { }
+/// #[launch]
+/// fn rocket() -> _ {
+///     rocket::build().attach(AdHoc::on_request("Metering", |req, _| Box::pin(async move {
+///         let key = req.headers().get_one("X-Api-Key").unwrap_or_default().to_string();
+///         Usage::of(req).on_complete(move |usage| bill(&key, usage.total()));
+///     })))
+/// }
+/// ```
+///
+/// Report the usage of the request so far in a handler:
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::request::Usage;
+///
+/// #[post("/upload", data = "<data>")]
+/// fn upload(data: Vec<u8>, usage: &Usage) -> String {
+///     format!("read {} of {} bytes", data.len(), usage.request_body())
+/// }
+/// ```
+#[derive(Clone, Default)]
+pub struct Usage(Arc<Counters>);
+
+#[derive(Default)]
+struct Counters {
+    request_head: AtomicU64,
+    request_body: AtomicU64,
+    response_head: AtomicU64,
+    response_body: AtomicU64,
+    complete: AtomicBool,
+    callbacks: Mutex<Vec<Box<dyn FnOnce(&Usage) + Send>>>,
+}
+
+/// Completes the usage when dropped, along with the response body.
+struct Meter(Usage, tracing::Span);
+
+impl Usage {
+    /// Returns the usage of `request`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::request::Usage;
+    ///
+    /// # let c = rocket::local::blocking::Client::debug_with(vec![]).unwrap();
+    /// # let request = c.get("/");
+    /// let usage = Usage::of(&request);
+    /// assert_eq!(usage.request_body(), 0);
+    /// assert!(!usage.is_complete());
+    /// ```
+    pub fn of<'r>(request: &'r Request<'_>) -> &'r Usage {
+        request.local_cache(Usage::default)
+    }
+
+    /// Returns the size, in bytes, of the request line and headers.
+    pub fn request_head(&self) -> u64 {
+        self.0.request_head.load(Ordering::Acquire)
+    }
+
+    /// Returns the number of bytes of the request body read so far.
+    pub fn request_body(&self) -> u64 {
+        self.0.request_body.load(Ordering::Acquire)
+    }
+
+    /// Returns the size, in bytes, of the response's status line and headers,
+    /// or `0` if the response has yet to be produced.
+    pub fn response_head(&self) -> u64 {
+        self.0.response_head.load(Ordering::Acquire)
+    }
+
+    /// Returns the number of bytes of the response body written so far.
+    pub fn response_body(&self) -> u64 {
+        self.0.response_body.load(Ordering::Acquire)
+    }
+
+    /// Returns the number of bytes read: `request_head() + request_body()`.
+    pub fn received(&self) -> u64 {
+        self.request_head() + self.request_body()
+    }
+
+    /// Returns the number of bytes written: `response_head() +
+    /// response_body()`.
+    pub fn sent(&self) -> u64 {
+        self.response_head() + self.response_body()
+    }
+
+    /// Returns the total number of bytes read and written.
+    pub fn total(&self) -> u64 {
+        self.received() + self.sent()
+    }
+
+    /// Returns `true` if the response has been written or dropped and the
+    /// counters are thus final.
+    pub fn is_complete(&self) -> bool {
+        self.0.complete.load(Ordering::Acquire)
+    }
+
+    /// Registers `f` to be called with the final usage once it is complete. If
+    /// usage is already complete, `f` is called immediately.
+    ///
+    /// Callbacks run on the task writing the response and should thus not
+    /// block; send the usage elsewhere to perform expensive work.
+    pub fn on_complete<F>(&self, f: F)
+        where F: FnOnce(&Usage) + Send + 'static
+    {
+        let mut callbacks = self.0.callbacks.lock();
+        if self.is_complete() {
+            drop(callbacks);
+            return f(self);
+        }
+
+        callbacks.push(Box::new(f));
+    }
+
+    /// Records the size of `request`'s head and meters its body via `data`.
+    pub(crate) fn meter_request(request: &Request<'_>, data: &mut Data<'_>) {
+        let usage = Usage::of(request).clone();
+        let uri = request.uri();
+        let query = uri.query().map_or(0, |q| q.as_str().len() + 1);
+        let line = request.method().as_str().len() + uri.path().as_str().len() + query + 12;
+        usage.0.request_head.store(head_size(line, request.headers()), Ordering::Release);
+        data.chain_inspect(move |bytes| {
+            usage.0.request_body.fetch_add(bytes.len() as u64, Ordering::AcqRel);
+        });
+    }
+
+    /// Records the size of `response`'s head and meters its body, completing
+    /// usage once the body is dropped.
+    pub(crate) fn meter_response(request: &Request<'_>, response: &mut Response<'_>) {
+        let usage = Usage::of(request).clone();
+        let line = response.status().reason_lossy().len() + 15;
+        usage.0.response_head.store(head_size(line, response.headers()), Ordering::Release);
+
+        let meter = Meter(usage, tracing::Span::current());
+        response.body_mut().inspect(move |n| {
+            meter.0.0.response_body.fetch_add(n as u64, Ordering::AcqRel);
+        });
+    }
+
+    fn complete(&self) {
+        let mut guard = self.0.callbacks.lock();
+        if self.0.complete.swap(true, Ordering::AcqRel) {
+            return;
+        }
+
+        let callbacks = std::mem::take(&mut *guard);
+        drop(guard);
+        debug!(
+            name: "usage",
+            request_head = self.request_head(),
+            request_body = self.request_body(),
+            response_head = self.response_head(),
+            response_body = self.response_body(),
+            "request complete"
+        );
+
+        for callback in callbacks {
+            callback(self);
+        }
+    }
+}
+
+/// The size of a message head with a start line of `line` bytes, including its
+/// `\r\n`, and `headers`, each as `name: value\r\n`, followed by `\r\n`.
+fn head_size(line: usize, headers: &HeaderMap<'_>) -> u64 {
+    let headers: usize = headers.iter()
+        .map(|h| h.name().as_str().len() + h.value().len() + 4)
+        .sum();
+
+    (line + headers + 2) as u64
+}
+
+impl Drop for Meter {
+    fn drop(&mut self) {
+        self.1.in_scope(|| self.0.complete());
+    }
+}
+
+#[crate::async_trait]
+impl<'r> FromRequest<'r> for &'r Usage {
+    type Error = Infallible;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Infallible> {
+        Outcome::Success(Usage::of(request))
+    }
+}
+
+impl fmt::Debug for Usage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Usage")
+            .field("request_head", &self.request_head())
+            .field("request_body", &self.request_body())
+            .field("response_head", &self.response_head())
+            .field("response_body", &self.response_body())
+            .field("complete", &self.is_complete())
+            .finish()
+    }
+}
@@ -0,0 +1,393 @@
+use std::fmt;
+
+use crate::request::{Request, FromRequest, Outcome};
+use crate::http::Status;
+
+/// The class of device a [`UserAgent`] runs on.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum DeviceClass {
+    /// A desktop or laptop computer.
+    Desktop,
+    /// A phone.
+    Mobile,
+    /// A tablet.
+    Tablet,
+    /// An automated client: a crawler, a monitor, or a command-line tool.
+    Bot,
+    /// A device that could not be classified.
+    Unknown,
+}
+
+/// A parsed `User-Agent` header and [User-Agent Client Hints].
+///
+/// `UserAgent` extracts a client's browser or tool family, its version, its
+/// operating system, and its [`DeviceClass`]. Parsing is heuristic and
+/// intentionally small: it recognizes the major browsers, common crawlers, and
+/// command-line tools, and is meant for analytics and coarse conditional
+/// behavior, not for feature detection.
+///
+/// When a client sends the low-entropy client hints `Sec-CH-UA`,
+/// `Sec-CH-UA-Mobile`, and `Sec-CH-UA-Platform`, they take precedence over
+/// the `User-Agent` header, which browsers increasingly freeze.
+///
+/// # Request Guard
+///
+/// `&UserAgent` is a request guard. The header is parsed at most once per
+/// request, the first time the guard or [`UserAgent::of()`] is used. If the
+/// request has neither a `User-Agent` header nor a `Sec-CH-UA` hint, the
+/// request is forwarded with a 500 Internal Server Error status.
+///
+/// This type is only available when the `user-agent` feature is enabled.
+///
+/// [User-Agent Client Hints]: https://wicg.github.io/ua-client-hints/
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::request::{UserAgent, DeviceClass};
+///
+/// #[get("/")]
+/// fn index(ua: Option<&UserAgent>) -> &'static str {
+///     match ua.map(|ua| ua.device()) {
+///         Some(DeviceClass::Mobile) => "Hello, phone!",
+///         Some(DeviceClass::Bot) => "Hello, robot!",
+///         _ => "Hello!",
+///     }
+/// }
+///
+/// let ua = UserAgent::parse("Mozilla/5.0 (X11; Linux x86_64; rv:124.0) \
+///     Gecko/20100101 Firefox/124.0");
+///
+/// assert_eq!(ua.family(), "Firefox");
+/// assert_eq!(ua.version(), Some("124.0"));
+/// assert_eq!(ua.major_version(), Some(124));
+/// assert_eq!(ua.os(), Some("Linux"));
+/// assert_eq!(ua.device(), DeviceClass::Desktop);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UserAgent {
+    raw: String,
+    family: String,
+    version: Option<String>,
+    os: Option<String>,
+    device: DeviceClass,
+}
+
+/// Browsers identified by a product token, in order of precedence: Chromium
+/// derivatives include `Chrome/` and `Safari/`, Chrome includes `Safari/`.
+const BROWSERS: &[(&str, &str)] = &[
+    ("Edg/", "Edge"),
+    ("EdgA/", "Edge"),
+    ("EdgiOS/", "Edge"),
+    ("Edge/", "Edge"),
+    ("OPR/", "Opera"),
+    ("SamsungBrowser/", "Samsung Internet"),
+    ("FxiOS/", "Firefox"),
+    ("Firefox/", "Firefox"),
+    ("CriOS/", "Chrome"),
+    ("Chrome/", "Chrome"),
+    ("Chromium/", "Chromium"),
+];
+
+/// Substrings, matched case-insensitively, that identify automated clients.
+const BOTS: &[&str] = &["bot", "crawl", "spider", "slurp", "curl", "wget", "python", "http"];
+
+/// Platform markers and the operating system they indicate, in order.
+const PLATFORMS: &[(&str, &str)] = &[
+    ("Windows", "Windows"),
+    ("Android", "Android"),
+    ("iPhone", "iOS"),
+    ("iPad", "iOS"),
+    ("iPod", "iOS"),
+    ("CrOS", "ChromeOS"),
+    ("Mac OS X", "macOS"),
+    ("Macintosh", "macOS"),
+    ("Linux", "Linux"),
+];
+
+impl UserAgent {
+    /// Parses a `User-Agent` header value.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::request::{UserAgent, DeviceClass};
+    ///
+    /// let ua = UserAgent::parse("Mozilla/5.0 (iPhone; CPU iPhone OS 17_4 like Mac OS X) \
+    ///     AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.4 Mobile/15E148 Safari/604.1");
+    ///
+    /// assert_eq!(ua.family(), "Safari");
+    /// assert_eq!(ua.version(), Some("17.4"));
+    /// assert_eq!(ua.os(), Some("iOS"));
+    /// assert_eq!(ua.device(), DeviceClass::Mobile);
+    ///
+    /// let ua = UserAgent::parse("curl/8.5.0");
+    /// assert_eq!(ua.family(), "curl");
+    /// assert_eq!(ua.device(), DeviceClass::Bot);
+    /// ```
+    pub fn parse(value: &str) -> UserAgent {
+        let products: Vec<(&str, Option<&str>)> = value.split_whitespace()
+            .map(|token| token.trim_matches(|c| matches!(c, '(' | ')' | ';' | ',')))
+            .filter(|token| !token.is_empty())
+            .map(|token| match token.split_once('/') {
+                Some((name, version)) => (name, Some(version).filter(|v| !v.is_empty())),
+                None => (token, None),
+            })
+            .collect();
+
+        let version_of = |name: &str| products.iter()
+            .find(|(n, _)| *n == name)
+            .and_then(|(_, v)| *v);
+
+        let lowercase = value.to_ascii_lowercase();
+        let is_bot = BOTS.iter().any(|bot| lowercase.contains(bot));
+        let browser = BROWSERS.iter().find(|(token, _)| value.contains(token));
+        let (family, version) = match browser {
+            _ if is_bot => {
+                let bot = products.iter()
+                    .find(|(name, _)| BOTS.iter().any(|b| name.to_ascii_lowercase().contains(b)))
+                    .or(products.first());
+
+                bot.map_or(("Unknown", None), |&(name, version)| (name, version))
+            }
+            Some((token, family)) => (*family, version_of(token.trim_end_matches('/'))),
+            None if value.contains("Safari/") && value.contains("Version/") => {
+                ("Safari", version_of("Version"))
+            }
+            None if value.contains("Trident/") || value.contains("MSIE ") => {
+                ("Internet Explorer", None)
+            }
+            None => products.first().map_or(("Unknown", None), |&(name, v)| (name, v)),
+        };
+
+        let os = PLATFORMS.iter().find(|(marker, _)| value.contains(marker)).map(|(_, os)| *os);
+        let device = if is_bot {
+            DeviceClass::Bot
+        } else if value.contains("iPad") || value.contains("Tablet")
+            || (os == Some("Android") && !value.contains("Mobile"))
+        {
+            DeviceClass::Tablet
+        } else if value.contains("Mobi") || value.contains("iPhone") || value.contains("iPod") {
+            DeviceClass::Mobile
+        } else if os.is_some() {
+            DeviceClass::Desktop
+        } else {
+            DeviceClass::Unknown
+        };
+
+        UserAgent {
+            raw: value.into(),
+            family: family.into(),
+            version: version.map(|v| v.into()),
+            os: os.map(|os| os.into()),
+            device,
+        }
+    }
+
+    /// Returns the cached `UserAgent` of `req`, parsing its `User-Agent`
+    /// header and client hints if this is the first call for `req`. Returns
+    /// `None` if `req` has neither a `User-Agent` header nor a `Sec-CH-UA`
+    /// hint.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::Header;
+    /// use rocket::request::UserAgent;
+    ///
+    /// # let c = rocket::local::blocking::Client::debug_with(vec![]).unwrap();
+    /// let req = c.get("/").header(Header::new("User-Agent", "curl/8.5.0"));
+    /// let ua = UserAgent::of(&req).unwrap();
+    /// assert_eq!(ua.family(), "curl");
+    /// ```
+    pub fn of<'r>(req: &'r Request<'_>) -> Option<&'r UserAgent> {
+        req.local_cache(|| {
+            let headers = req.headers();
+            let hints = headers.get_one("Sec-CH-UA");
+            let mut ua = match (headers.get_one("User-Agent"), hints) {
+                (Some(value), _) => UserAgent::parse(value),
+                (None, Some(_)) => UserAgent::parse(""),
+                (None, None) => return None,
+            };
+
+            if let Some(hints) = hints {
+                ua.apply_hints(hints, headers.get_one("Sec-CH-UA-Mobile"),
+                    headers.get_one("Sec-CH-UA-Platform"));
+            }
+
+            Some(ua)
+        }).as_ref()
+    }
+
+    /// Overrides parsed values with those from client hints. The brand is the
+    /// first one that isn't a "GREASE" brand, preferring any brand over the
+    /// generic `Chromium`.
+    fn apply_hints(&mut self, brands: &str, mobile: Option<&str>, platform: Option<&str>) {
+        let unquote = |s: &str| s.trim().trim_matches('"').to_string();
+        let brands: Vec<(String, Option<String>)> = brands.split(',')
+            .map(|entry| {
+                let mut parts = entry.split(';');
+                let name = unquote(parts.next().unwrap_or(""));
+                let version = parts.filter_map(|p| p.trim().strip_prefix("v=")).next();
+                (name, version.map(unquote))
+            })
+            .filter(|(name, _)| !name.is_empty() && !name.contains("Not"))
+            .collect();
+
+        let brand = brands.iter()
+            .find(|(name, _)| name != "Chromium")
+            .or(brands.first());
+
+        if let Some((name, version)) = brand {
+            self.family = match name.strip_prefix("Google ").unwrap_or(name) {
+                "Microsoft Edge" => "Edge",
+                name => name,
+            }.into();
+
+            self.version = version.clone();
+        }
+
+        if let Some(platform) = platform.map(unquote).filter(|p| !p.is_empty()) {
+            self.os = Some(match platform.as_str() {
+                "macOS" | "Mac OS X" => "macOS".into(),
+                "Chrome OS" => "ChromeOS".into(),
+                _ => platform,
+            });
+        }
+
+        if self.device != DeviceClass::Bot {
+            match mobile.map(str::trim) {
+                Some("?1") => self.device = DeviceClass::Mobile,
+                Some("?0") if self.device == DeviceClass::Mobile => {
+                    self.device = DeviceClass::Desktop
+                }
+                _ if self.device == DeviceClass::Unknown && self.os.is_some() => {
+                    self.device = DeviceClass::Desktop
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Returns the raw `User-Agent` header value. Empty if only client hints
+    /// were sent.
+    pub fn raw(&self) -> &str {
+        &self.raw
+    }
+
+    /// Returns the browser or tool family, such as `Chrome`, `Firefox`,
+    /// `Safari`, `Edge`, `Googlebot`, or `curl`, or `Unknown`.
+    pub fn family(&self) -> &str {
+        &self.family
+    }
+
+    /// Returns the full version of the browser or tool, if known.
+    pub fn version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
+
+    /// Returns the major version of the browser or tool, if known.
+    pub fn major_version(&self) -> Option<u32> {
+        self.version()?.split('.').next()?.parse().ok()
+    }
+
+    /// Returns the operating system, such as `Windows`, `macOS`, `Linux`,
+    /// `Android`, `iOS`, or `ChromeOS`, if known.
+    pub fn os(&self) -> Option<&str> {
+        self.os.as_deref()
+    }
+
+    /// Returns the class of device.
+    pub fn device(&self) -> DeviceClass {
+        self.device
+    }
+
+    /// Returns `true` if the client is an automated client.
+    pub fn is_bot(&self) -> bool {
+        self.device == DeviceClass::Bot
+    }
+}
+
+impl fmt::Display for UserAgent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.family)?;
+        if let Some(version) = &self.version {
+            write!(f, " {}", version)?;
+        }
+
+        if let Some(os) = &self.os {
+            write!(f, " ({})", os)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[crate::async_trait]
+impl<'r> FromRequest<'r> for &'r UserAgent {
+    type Error = std::convert::Infallible;
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        match UserAgent::of(req) {
+            Some(ua) => Outcome::Success(ua),
+            None => Outcome::Forward(Status::InternalServerError),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{UserAgent, DeviceClass, DeviceClass::*};
+
+    #[track_caller]
+    fn assert_ua(value: &str, expected: (&str, Option<u32>, Option<&str>, DeviceClass)) {
+        let ua = UserAgent::parse(value);
+        assert_eq!((ua.family(), ua.major_version(), ua.os(), ua.device()), expected);
+    }
+
+    #[test]
+    fn parse_user_agents() {
+        const WEBKIT: &str = "AppleWebKit/537.36 (KHTML, like Gecko)";
+
+        assert_ua(&format!("Mozilla/5.0 (Windows NT 10.0; Win64; x64) {WEBKIT} \
+            Chrome/124.0.0.0 Safari/537.36"), ("Chrome", Some(124), Some("Windows"), Desktop));
+        assert_ua(&format!("Mozilla/5.0 (Windows NT 10.0; Win64; x64) {WEBKIT} \
+            Chrome/124.0.0.0 Safari/537.36 Edg/124.0.2478.51"),
+            ("Edge", Some(124), Some("Windows"), Desktop));
+        assert_ua(&format!("Mozilla/5.0 (Linux; Android 14; Pixel 8) {WEBKIT} \
+            Chrome/124.0.0.0 Mobile Safari/537.36"),
+            ("Chrome", Some(124), Some("Android"), Mobile));
+        assert_ua(&format!("Mozilla/5.0 (Linux; Android 13; SM-X700) {WEBKIT} \
+            Chrome/124.0.0.0 Safari/537.36"), ("Chrome", Some(124), Some("Android"), Tablet));
+        assert_ua("Mozilla/5.0 (iPad; CPU OS 17_4 like Mac OS X) AppleWebKit/605.1.15 \
+            (KHTML, like Gecko) Version/17.4 Mobile/15E148 Safari/604.1",
+            ("Safari", Some(17), Some("iOS"), Tablet));
+        assert_ua("Mozilla/5.0 (Macintosh; Intel Mac OS X 14_4) AppleWebKit/605.1.15 \
+            (KHTML, like Gecko) Version/17.4 Safari/605.1.15",
+            ("Safari", Some(17), Some("macOS"), Desktop));
+        assert_ua("Mozilla/5.0 (compatible; Googlebot/2.1; +http://www.google.com/bot.html)",
+            ("Googlebot", Some(2), None, Bot));
+        assert_ua("Wget/1.21.4", ("Wget", Some(1), None, Bot));
+        assert_ua("SomeApp", ("SomeApp", None, None, Unknown));
+        assert_ua("", ("Unknown", None, None, Unknown));
+    }
+
+    #[test]
+    fn client_hints_override() {
+        let mut ua = UserAgent::parse("Mozilla/5.0 (Linux; Android 10; K) AppleWebKit/537.36 \
+            (KHTML, like Gecko) Chrome/124.0.0.0 Mobile Safari/537.36");
+
+        let brands = r#""Chromium";v="124", "Google Chrome";v="124", "Not-A.Brand";v="99""#;
+        ua.apply_hints(brands, Some("?0"), Some("\"Windows\""));
+        assert_eq!(ua.family(), "Chrome");
+        assert_eq!(ua.version(), Some("124"));
+        assert_eq!(ua.os(), Some("Windows"));
+        assert_eq!(ua.device(), Desktop);
+
+        let mut ua = UserAgent::parse("");
+        ua.apply_hints(r#""Not_A Brand";v="8", "Chromium";v="120""#, Some("?1"), None);
+        assert_eq!(ua.family(), "Chromium");
+        assert_eq!(ua.device(), Mobile);
+    }
+}
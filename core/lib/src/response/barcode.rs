@@ -0,0 +1,415 @@
+//! Rendering QR codes and barcodes as SVG or PNG images.
+//!
+//! The [`QrCode`] and [`Barcode`] responders encode their data when they're
+//! rendered and respond with an SVG image, by default, or a PNG image. They're
+//! handy for 2FA enrollment, tickets, and links to be scanned by phones:
+//!
+//! ```rust
+//! # #[macro_use] extern crate rocket;
+//! use rocket::response::barcode::{QrCode, Barcode};
+//!
+//! #[get("/ticket/<id>")]
+//! fn ticket(id: u32) -> String {
+//!     format!("Ticket #{id}")
+//! }
+//!
+//! #[get("/ticket/<id>/qr")]
+//! fn ticket_qr(id: u32) -> QrCode {
+//!     QrCode::new(uri!("https://example.com", ticket(id)))
+//! }
+//!
+//! #[get("/ticket/<id>/barcode")]
+//! fn ticket_barcode(id: u32) -> Barcode {
+//!     Barcode::code128(format!("T-{id:08}")).png().scale(3)
+//! }
+//! ```
+//!
+//! Encoding fails if the data can't be encoded, for instance because it's too
+//! long for a QR code or contains characters not supported by a barcode
+//! symbology. The responders then fail with a status of `500`. To handle
+//! errors instead, render with [`QrCode::to_svg()`] and friends directly.
+//!
+//! This module is only available when the `barcode` feature is enabled.
+
+use std::fmt;
+
+use crate::http::{ContentType, Status};
+use crate::request::Request;
+use crate::response::{self, Responder};
+
+/// The error returned when data can't be encoded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error(String);
+
+/// The error correction level of a [`QrCode`].
+///
+/// Higher levels allow a code to be scanned even when more of it is damaged
+/// or obscured at the expense of a larger code.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EcLevel {
+    /// Recovers from about 7% of the code being damaged.
+    Low,
+    /// Recovers from about 15% of the code being damaged. The default.
+    #[default]
+    Medium,
+    /// Recovers from about 25% of the code being damaged.
+    Quartile,
+    /// Recovers from about 30% of the code being damaged.
+    High,
+}
+
+/// The image format of a rendered code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Svg,
+    Png,
+}
+
+/// A responder that renders a QR code.
+///
+/// Responds with the QR code for the data as an SVG image unless
+/// [`QrCode::png()`] is called, in which case it responds with a PNG image.
+/// See the [module docs](self) for details.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::response::barcode::{QrCode, EcLevel};
+///
+/// let code = QrCode::new("otpauth://totp/Rocket:alice?secret=JBSWY3DPEHPK3PXP")
+///     .ec_level(EcLevel::High)
+///     .scale(4);
+///
+/// let svg = code.to_svg().unwrap();
+/// assert!(svg.starts_with("<svg"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QrCode {
+    data: String,
+    ec_level: EcLevel,
+    scale: usize,
+    margin: usize,
+    format: Format,
+}
+
+/// A responder that renders a one-dimensional barcode.
+///
+/// Responds with the barcode for the data as an SVG image unless
+/// [`Barcode::png()`] is called, in which case it responds with a PNG image.
+/// See the [module docs](self) for details.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::response::barcode::Barcode;
+///
+/// let code = Barcode::ean13("750103131130").height(30);
+/// let svg = code.to_svg().unwrap();
+/// assert!(svg.starts_with("<svg"));
+///
+/// assert!(Barcode::ean13("not digits").to_svg().is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Barcode {
+    symbology: Symbology,
+    data: String,
+    height: usize,
+    scale: usize,
+    margin: usize,
+    format: Format,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Symbology {
+    Code128,
+    Code39,
+    Ean13,
+}
+
+/// A grid of modules, the black and white squares that make up a code.
+struct Modules {
+    width: usize,
+    /// Each row, `true` for dark modules, repeated `row_height` times.
+    rows: Vec<Vec<bool>>,
+    row_height: usize,
+}
+
+impl QrCode {
+    /// Returns a QR code for `data` with an error correction level of
+    /// [`EcLevel::Medium`], a scale of `8`, and a margin of `4`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #[macro_use] extern crate rocket;
+    /// use rocket::response::barcode::QrCode;
+    ///
+    /// #[get("/")]
+    /// fn index() -> &'static str { "Hello!" }
+    ///
+    /// let code = QrCode::new(uri!("https://rocket.rs", index));
+    /// ```
+    pub fn new<T: fmt::Display>(data: T) -> QrCode {
+        QrCode {
+            data: data.to_string(),
+            ec_level: EcLevel::default(),
+            scale: 8,
+            margin: 4,
+            format: Format::Svg,
+        }
+    }
+
+    /// Sets the error correction level to `ec_level`.
+    pub fn ec_level(mut self, ec_level: EcLevel) -> Self {
+        self.ec_level = ec_level;
+        self
+    }
+
+    /// Sets the size of each module to `scale` pixels.
+    pub fn scale(mut self, scale: usize) -> Self {
+        self.scale = scale.max(1);
+        self
+    }
+
+    /// Sets the size of the blank margin around the code to `margin` modules.
+    pub fn margin(mut self, margin: usize) -> Self {
+        self.margin = margin;
+        self
+    }
+
+    /// Responds with a PNG image.
+    pub fn png(mut self) -> Self {
+        self.format = Format::Png;
+        self
+    }
+
+    /// Responds with an SVG image. This is the default.
+    pub fn svg(mut self) -> Self {
+        self.format = Format::Svg;
+        self
+    }
+
+    /// Renders the QR code as an SVG image.
+    pub fn to_svg(&self) -> Result<String, Error> {
+        Ok(self.modules()?.to_svg(self.scale, self.margin))
+    }
+
+    /// Renders the QR code as a PNG image.
+    pub fn to_png(&self) -> Result<Vec<u8>, Error> {
+        self.modules()?.to_png(self.scale, self.margin)
+    }
+
+    fn modules(&self) -> Result<Modules, Error> {
+        let ec_level = match self.ec_level {
+            EcLevel::Low => qrcode::EcLevel::L,
+            EcLevel::Medium => qrcode::EcLevel::M,
+            EcLevel::Quartile => qrcode::EcLevel::Q,
+            EcLevel::High => qrcode::EcLevel::H,
+        };
+
+        let code = qrcode::QrCode::with_error_correction_level(&self.data, ec_level)
+            .map_err(|e| Error(e.to_string()))?;
+
+        let width = code.width();
+        let colors = code.to_colors();
+        let rows = colors.chunks(width)
+            .map(|row| row.iter().map(|c| *c == qrcode::Color::Dark).collect())
+            .collect();
+
+        Ok(Modules { width, rows, row_height: 1 })
+    }
+}
+
+impl Barcode {
+    /// Returns a Code 128 barcode for `data`, which must be printable ASCII.
+    ///
+    /// Like all barcodes, it has a height of `50` modules, a scale of `2`, and
+    /// a margin of `10` modules.
+    pub fn code128<T: fmt::Display>(data: T) -> Barcode {
+        Barcode::with(Symbology::Code128, data)
+    }
+
+    /// Returns a Code 39 barcode for `data`, which must consist of uppercase
+    /// letters, digits, spaces, and any of `-.$/+%`.
+    pub fn code39<T: fmt::Display>(data: T) -> Barcode {
+        Barcode::with(Symbology::Code39, data)
+    }
+
+    /// Returns an EAN-13 barcode for the 12 `digits`. The check digit is
+    /// computed and appended.
+    pub fn ean13<T: fmt::Display>(digits: T) -> Barcode {
+        Barcode::with(Symbology::Ean13, digits)
+    }
+
+    fn with<T: fmt::Display>(symbology: Symbology, data: T) -> Barcode {
+        Barcode {
+            symbology,
+            data: data.to_string(),
+            height: 50,
+            scale: 2,
+            margin: 10,
+            format: Format::Svg,
+        }
+    }
+
+    /// Sets the height of the bars to `height` modules.
+    pub fn height(mut self, height: usize) -> Self {
+        self.height = height.max(1);
+        self
+    }
+
+    /// Sets the size of each module to `scale` pixels.
+    pub fn scale(mut self, scale: usize) -> Self {
+        self.scale = scale.max(1);
+        self
+    }
+
+    /// Sets the size of the blank margin around the bars to `margin` modules.
+    pub fn margin(mut self, margin: usize) -> Self {
+        self.margin = margin;
+        self
+    }
+
+    /// Responds with a PNG image.
+    pub fn png(mut self) -> Self {
+        self.format = Format::Png;
+        self
+    }
+
+    /// Responds with an SVG image. This is the default.
+    pub fn svg(mut self) -> Self {
+        self.format = Format::Svg;
+        self
+    }
+
+    /// Renders the barcode as an SVG image.
+    pub fn to_svg(&self) -> Result<String, Error> {
+        Ok(self.modules()?.to_svg(self.scale, self.margin))
+    }
+
+    /// Renders the barcode as a PNG image.
+    pub fn to_png(&self) -> Result<Vec<u8>, Error> {
+        self.modules()?.to_png(self.scale, self.margin)
+    }
+
+    fn modules(&self) -> Result<Modules, Error> {
+        use barcoders::sym::{code128::Code128, code39::Code39, ean13::EAN13};
+
+        let error = |e: barcoders::error::Error| Error(e.to_string());
+        let bars = match self.symbology {
+            // Code 128 data starts with its character set: `Ɓ` selects set B.
+            Symbology::Code128 => {
+                Code128::new(format!("Ɓ{}", self.data)).map_err(error)?.encode()
+            }
+            Symbology::Code39 => Code39::new(&*self.data).map_err(error)?.encode(),
+            Symbology::Ean13 => EAN13::new(&*self.data).map_err(error)?.encode(),
+        };
+
+        let row = bars.iter().map(|bar| *bar == 1).collect();
+        Ok(Modules { width: bars.len(), rows: vec![row], row_height: self.height })
+    }
+}
+
+impl Modules {
+    /// Returns the width and height of the image in pixels.
+    fn size(&self, scale: usize, margin: usize) -> (usize, usize) {
+        let width = self.width + 2 * margin;
+        let height = self.rows.len() * self.row_height + 2 * margin;
+        (width * scale, height * scale)
+    }
+
+    fn to_svg(&self, scale: usize, margin: usize) -> String {
+        use std::fmt::Write;
+
+        let (width, height) = self.size(scale, margin);
+        let mut path = String::new();
+        for (y, row) in self.rows.iter().enumerate() {
+            let y = y * self.row_height + margin;
+            let mut x = 0;
+            while x < row.len() {
+                let run = row[x..].iter().take_while(|dark| **dark == row[x]).count();
+                if row[x] {
+                    let _ = write!(path, "M{} {y}h{run}v{}h-{run}z", x + margin, self.row_height);
+                }
+
+                x += run;
+            }
+        }
+
+        format!("<svg xmlns=\"http://www.w3.org/2000/svg\" \
+            width=\"{width}\" height=\"{height}\" viewBox=\"0 0 {} {}\" \
+            shape-rendering=\"crispEdges\">\
+            <rect width=\"100%\" height=\"100%\" fill=\"#fff\"/>\
+            <path fill=\"#000\" d=\"{path}\"/></svg>",
+            width / scale, height / scale)
+    }
+
+    fn to_png(&self, scale: usize, margin: usize) -> Result<Vec<u8>, Error> {
+        let (width, height) = self.size(scale, margin);
+        let mut pixels = vec![0xFF; width * height];
+        for (y, row) in self.rows.iter().enumerate() {
+            for (x, _) in row.iter().enumerate().filter(|(_, dark)| **dark) {
+                let top = (y * self.row_height + margin) * scale;
+                let left = (x + margin) * scale;
+                for py in top..(top + self.row_height * scale) {
+                    pixels[py * width + left..][..scale].fill(0);
+                }
+            }
+        }
+
+        let error = |e: png::EncodingError| Error(e.to_string());
+        let mut bytes = vec![];
+        let mut encoder = png::Encoder::new(&mut bytes, width as u32, height as u32);
+        encoder.set_color(png::ColorType::Grayscale);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().map_err(error)?;
+        writer.write_image_data(&pixels).map_err(error)?;
+        writer.finish().map_err(error)?;
+        Ok(bytes)
+    }
+}
+
+/// Renders `format`, responding with `500` if encoding fails.
+fn respond<'r>(
+    req: &'r Request<'_>,
+    format: Format,
+    svg: impl FnOnce() -> Result<String, Error>,
+    png: impl FnOnce() -> Result<Vec<u8>, Error>,
+) -> response::Result<'static> {
+    let result = match format {
+        Format::Svg => svg().map(|svg| (ContentType::SVG, svg.into_bytes())),
+        Format::Png => png().map(|png| (ContentType::PNG, png)),
+    };
+
+    match result {
+        Ok(image) => image.respond_to(req),
+        Err(e) => {
+            error!(name: "barcode", error = %e, "failed to encode code");
+            Err(Status::InternalServerError)
+        }
+    }
+}
+
+/// Responds with the rendered QR code or fails with `500` if the data can't
+/// be encoded.
+impl<'r> Responder<'r, 'static> for QrCode {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
+        respond(req, self.format, || self.to_svg(), || self.to_png())
+    }
+}
+
+/// Responds with the rendered barcode or fails with `500` if the data can't
+/// be encoded.
+impl<'r> Responder<'r, 'static> for Barcode {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
+        respond(req, self.format, || self.to_svg(), || self.to_png())
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "failed to encode: {}", self.0)
+    }
+}
+
+impl std::error::Error for Error {}
@@ -131,6 +131,19 @@ impl<'r> Body<'r> {
         self.max_chunk = max_chunk;
     }
 
+    /// Calls `f` with the number of bytes read each time the body is read. `f`
+    /// is dropped along with the body.
+    pub(crate) fn inspect<F>(&mut self, f: F)
+        where F: FnMut(usize) + Send + 'r
+    {
+        let f: Box<dyn FnMut(usize) + Send + 'r> = Box::new(f);
+        self.inner = match std::mem::replace(&mut self.inner, Inner::None) {
+            Inner::Seekable(b) => Inner::Seekable(Box::pin(Inspected(b, f))),
+            Inner::Unsized(b) => Inner::Unsized(Box::pin(Inspected(b, f))),
+            inner => inner,
+        };
+    }
+
     pub(crate) fn strip(&mut self) {
         let body = std::mem::take(self);
         *self = match body.inner {
@@ -139,7 +152,13 @@ impl<'r> Body<'r> {
                 inner: Inner::Phantom(b),
                 max_chunk: body.max_chunk,
             },
-            Inner::Unsized(_) | Inner::None => Body::default()
+            // Keep a declared size; otherwise, the size is unknown, not `0`.
+            Inner::Unsized(_) => Body {
+                size: body.size,
+                inner: Inner::None,
+                max_chunk: body.max_chunk,
+            },
+            Inner::None => Body::default()
         };
     }
 
@@ -182,9 +201,10 @@ impl<'r> Body<'r> {
     /// A body's preset size, which may have been computed by a previous call to
     /// [`Body::size()`].
     ///
-    /// Unsized bodies _always_ return `None`, while sized bodies return `Some`
-    /// if the body size was supplied directly on creation or a call to
-    /// [`Body::size()`] successfully computed the size and `None` otherwise.
+    /// Unsized bodies return `None` unless a size was declared via
+    /// [`Body::set_size()`], while sized bodies return `Some` if the body size
+    /// was supplied directly on creation, declared, or successfully computed by
+    /// a call to [`Body::size()`], and `None` otherwise.
     ///
     /// # Example
     ///
@@ -222,6 +242,46 @@ impl<'r> Body<'r> {
         self.size
     }
 
+    /// Declares the size of the body, in bytes, to be `size`, replacing any
+    /// preset size.
+    ///
+    /// Rocket sets the `Content-Length` of a response from its body's size
+    /// just before the response is written out, discarding any
+    /// `Content-Length` or `Transfer-Encoding` headers set by responders or
+    /// fairings. A response fairing that replaces a body using
+    /// [`Response::set_streamed_body()`] or [`Response::set_sized_body()`] thus
+    /// needn't adjust any headers. A fairing that knows the size of a body it
+    /// produces, but can't make it seekable, should declare that size with
+    /// this method so that it is sent as the `Content-Length`; the body must
+    /// then be exactly `size` bytes long. Declaring a size of `None` makes an
+    /// unsized body chunk-encoded and a sized body's size computed by seeking.
+    ///
+    /// A `Content-Length` header set by a responder is treated as a
+    /// declaration of the size of the responder's body.
+    ///
+    /// [`Response::set_streamed_body()`]: crate::Response::set_streamed_body()
+    /// [`Response::set_sized_body()`]: crate::Response::set_sized_body()
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io::Cursor;
+    /// use rocket::response::Response;
+    ///
+    /// let body = "Hello, Rocketeers!";
+    /// let mut r = Response::build()
+    ///     .streamed_body(Cursor::new(body))
+    ///     .finalize();
+    ///
+    /// assert_eq!(r.body().preset_size(), None);
+    ///
+    /// r.body_mut().set_size(body.len());
+    /// assert_eq!(r.body().preset_size(), Some(body.len()));
+    /// ```
+    pub fn set_size<S: Into<Option<usize>>>(&mut self, size: S) {
+        self.size = size.into();
+    }
+
     /// Returns the maximum chunk size for chunked transfers.
     ///
     /// If none is explicitly set, defaults to [`Body::DEFAULT_MAX_CHUNK`].
@@ -411,6 +471,35 @@ impl AsyncRead for Body<'_> {
     }
 }
 
+/// A reader that calls a function with the number of bytes of each read.
+struct Inspected<'r, R>(R, Box<dyn FnMut(usize) + Send + 'r>);
+
+impl<R: AsyncRead + Unpin> AsyncRead for Inspected<'_, R> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let filled = buf.filled().len();
+        let result = Pin::new(&mut self.0).poll_read(cx, buf);
+        if let Poll::Ready(Ok(())) = result {
+            (self.1)(buf.filled().len() - filled);
+        }
+
+        result
+    }
+}
+
+impl<R: AsyncSeek + Unpin> AsyncSeek for Inspected<'_, R> {
+    fn start_seek(mut self: Pin<&mut Self>, position: io::SeekFrom) -> io::Result<()> {
+        Pin::new(&mut self.0).start_seek(position)
+    }
+
+    fn poll_complete(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<u64>> {
+        Pin::new(&mut self.0).poll_complete(cx)
+    }
+}
+
 impl fmt::Debug for Inner<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -33,7 +33,7 @@
 
 use crate::request::Request;
 use crate::response::{self, Response, Responder};
-use crate::http::ContentType;
+use crate::http::{ContentType, ContentDisposition};
 
 macro_rules! ctrs {
     ($($name:ident: $ct:ident, $name_str:expr, $ct_str:expr),+) => {
@@ -85,3 +85,26 @@ impl<'r, 'o: 'r, R: Responder<'r, 'o>> Responder<'r, 'o> for (ContentType, R) {
             .ok()
     }
 }
+
+/// Sets the `Content-Disposition` of the response to the value in `.0`,
+/// otherwise delegating to the responder in `.1`.
+///
+/// # Example
+///
+/// ```rust
+/// # use rocket::get;
+/// use rocket::http::ContentDisposition;
+///
+/// #[get("/report")]
+/// fn report() -> (ContentDisposition, &'static str) {
+///     (ContentDisposition::attachment().with_filename("Q3 Übersicht.csv"), "q,total\n3,42")
+/// }
+/// ```
+impl<'r, 'o: 'r, R: Responder<'r, 'o>> Responder<'r, 'o> for (ContentDisposition, R) {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'o> {
+        Response::build()
+            .merge(self.1.respond_to(req)?)
+            .header(self.0)
+            .ok()
+    }
+}
@@ -13,6 +13,13 @@ const FLASH_COOKIE_NAME: &str = "_flash";
 // Character to use as a delimiter after the cookie's name's length.
 const FLASH_COOKIE_DELIM: char = ':';
 
+// Character that begins a flash cookie containing a list of messages.
+const FLASH_COOKIE_LIST: char = '~';
+
+// Maximum length of a flash cookie's value. Browsers reject cookies larger than
+// 4096 bytes, including the name and attributes.
+const FLASH_COOKIE_MAX_LEN: usize = 4000;
+
 /// Sets a "flash" cookie that will be removed when it is accessed. The
 /// analogous request type is [`FlashMessage`].
 ///
@@ -34,6 +41,23 @@ const FLASH_COOKIE_DELIM: char = ':';
 /// Messages can be retrieved on the request side via the [`FlashMessage`] type
 /// and the [kind](#method.kind) and [message](#method.message) methods.
 ///
+/// # Multiple Messages
+///
+/// Additional messages can be queued with [`Flash::and()`]. Messages are also
+/// queued across responders: if a flash cookie that hasn't been consumed
+/// already exists when a `Flash` responds, its messages are retained and the
+/// new ones are appended. All messages are retrieved with
+/// [`FlashMessage::messages()`]; `kind()` and `message()` return those of the
+/// first message.
+///
+/// # Payloads
+///
+/// With the `json` feature enabled, a message can carry any serializable value
+/// via [`Flash::with_payload()`], retrieved with [`FlashEntry::payload()`].
+/// Payloads are stored in the cookie as JSON. As browsers limit cookies to
+/// roughly 4KiB, payloads should be small: an ID or a few form values, not a
+/// document.
+///
 /// # Response
 ///
 /// The `Responder` implementation for `Flash` sets the message cookie and then
@@ -75,12 +99,23 @@ const FLASH_COOKIE_DELIM: char = ':';
 /// receive the standard welcome message.
 #[derive(Debug)]
 pub struct Flash<R> {
-    kind: String,
-    message: String,
+    entries: Vec<FlashEntry>,
     consumed: AtomicBool,
     inner: R,
 }
 
+/// A single message in a [`Flash`] cookie.
+///
+/// Retrieved via [`FlashMessage::messages()`]. The `Serialize` implementation
+/// emits a structure with `kind`, `message`, and, with the `json` feature
+/// enabled, `payload` fields, the latter being `null` when there is no payload.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FlashEntry {
+    kind: String,
+    message: String,
+    payload: Option<String>,
+}
+
 /// Type alias to retrieve [`Flash`] messages from a request.
 ///
 /// # Flash Cookie
@@ -89,11 +124,26 @@ pub struct Flash<R> {
 /// there is a flash cookie present (set by the `Flash` `Responder`), a
 /// `FlashMessage` request guard will succeed.
 ///
-/// The flash cookie is cleared if either the [`kind()`] or [`message()`] method is
-/// called. If neither method is called, the flash cookie is not cleared.
+/// The flash cookie is cleared if any of the [`kind()`], [`message()`], or
+/// [`messages()`] methods is called. If none is called, the flash cookie is not
+/// cleared.
+///
+/// # Templates
+///
+/// `FlashMessage` implements `Serialize`, emitting the `kind` and `message` of
+/// the first message as well as all messages, including payloads, as
+/// `messages`. Serializing consumes the flash cookie. In a Handlebars
+/// template, for instance, messages can be rendered as follows:
+///
+/// ```handlebars
+/// {{#each flash.messages}}
+///   <div class="alert alert-{{this.kind}}">{{this.message}}</div>
+/// {{/each}}
+/// ```
 ///
 /// [`kind()`]: Flash::kind()
 /// [`message()`]: Flash::message()
+/// [`messages()`]: Flash::messages()
 pub type FlashMessage<'a> = crate::response::Flash<&'a CookieJar<'a>>;
 
 impl<R> Flash<R> {
@@ -113,13 +163,65 @@ impl<R> Flash<R> {
     /// ```
     pub fn new<K: Into<String>, M: Into<String>>(res: R, kind: K, message: M) -> Flash<R> {
         Flash {
-            kind: kind.into(),
-            message: message.into(),
+            entries: vec![FlashEntry::new(kind, message)],
             consumed: AtomicBool::default(),
             inner: res,
         }
     }
 
+    /// Queues an additional message with the given `kind` and `message`.
+    ///
+    /// All queued messages are stored in a single cookie. If the cookie would
+    /// exceed 4000 bytes, the oldest messages are dropped, with a warning,
+    /// until it doesn't.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use rocket::response::{Redirect, Flash};
+    ///
+    /// # #[allow(unused_variables)]
+    /// let message = Flash::success(Redirect::to("/"), "Profile saved.")
+    ///     .and("warning", "Your email address is unverified.");
+    /// ```
+    pub fn and<K: Into<String>, M: Into<String>>(mut self, kind: K, message: M) -> Flash<R> {
+        self.entries.push(FlashEntry::new(kind, message));
+        self
+    }
+
+    /// Attaches `payload`, serialized as JSON, to the most recently queued
+    /// message, replacing any existing payload. If `payload` fails to
+    /// serialize, the message is left without a payload.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[macro_use] extern crate rocket;
+    /// use rocket::response::{Redirect, Flash};
+    /// use rocket::serde::Serialize;
+    ///
+    /// #[derive(Serialize)]
+    /// #[serde(crate = "rocket::serde")]
+    /// struct Undo {
+    ///     item: usize,
+    /// }
+    ///
+    /// #[post("/delete/<item>")]
+    /// fn delete(item: usize) -> Flash<Redirect> {
+    ///     Flash::success(Redirect::to(uri!("/")), "Item deleted.")
+    ///         .with_payload(&Undo { item })
+    /// }
+    /// ```
+    #[cfg(feature = "json")]
+    #[cfg_attr(nightly, doc(cfg(feature = "json")))]
+    pub fn with_payload<T: Serialize + ?Sized>(mut self, payload: &T) -> Flash<R> {
+        if let Some(entry) = self.entries.last_mut() {
+            entry.payload = serde_json::to_string(payload).ok();
+        }
+
+        self
+    }
+
     /// Constructs a "success" `Flash` message with the given `responder` and
     /// `message`.
     ///
@@ -174,34 +276,160 @@ impl<R> Flash<R> {
         Flash::new(responder, "error", message.into())
     }
 
-    fn cookie(&self) -> Cookie<'static> {
-        let content = format!("{}{}{}{}",
-            self.kind.len(), FLASH_COOKIE_DELIM, self.kind, self.message);
+    /// Encodes `entries` as a flash cookie. A single message without a payload
+    /// is encoded as `{kind.len()}:{kind}{message}`. Otherwise, the cookie
+    /// begins with `~` followed by, for each message, the kind, message, and
+    /// payload, each as `{field.len()}:{field}`, with an empty payload meaning
+    /// none.
+    fn cookie(entries: &[FlashEntry]) -> Cookie<'static> {
+        fn field(content: &mut String, field: &str) {
+            content.push_str(&field.len().to_string());
+            content.push(FLASH_COOKIE_DELIM);
+            content.push_str(field);
+        }
+
+        let mut content = String::new();
+        match entries {
+            [entry] if entry.payload.is_none() => {
+                field(&mut content, &entry.kind);
+                content.push_str(&entry.message);
+            }
+            entries => {
+                content.push(FLASH_COOKIE_LIST);
+                for entry in entries {
+                    field(&mut content, &entry.kind);
+                    field(&mut content, &entry.message);
+                    field(&mut content, entry.payload.as_deref().unwrap_or(""));
+                }
+            }
+        }
 
         Cookie::build((FLASH_COOKIE_NAME, content))
             .max_age(Duration::minutes(5))
             .build()
     }
+
+    /// Parses the contents of a flash cookie as encoded by [`Flash::cookie()`].
+    fn parse(content: &str) -> Option<Vec<FlashEntry>> {
+        fn field(content: &str) -> Option<(&str, &str)> {
+            let (len, rest) = content.split_once(FLASH_COOKIE_DELIM)?;
+            let len = len.parse::<usize>().ok()?;
+            Some((rest.get(..len)?, rest.get(len..)?))
+        }
+
+        let Some(mut rest) = content.strip_prefix(FLASH_COOKIE_LIST) else {
+            let (kind, message) = field(content)?;
+            return Some(vec![FlashEntry::new(kind, message)]);
+        };
+
+        let mut entries = vec![];
+        while !rest.is_empty() {
+            let (kind, next) = field(rest)?;
+            let (message, next) = field(next)?;
+            let (payload, next) = field(next)?;
+            let payload = (!payload.is_empty()).then(|| payload.to_string());
+            entries.push(FlashEntry { payload, ..FlashEntry::new(kind, message) });
+            rest = next;
+        }
+
+        (!entries.is_empty()).then_some(entries)
+    }
 }
 
 /// Sets the message cookie and then uses the wrapped responder to complete the
 /// response. In other words, simply sets a cookie and delegates the rest of the
 /// response handling to the wrapped responder. As a result, the `Outcome` of
 /// the response is the `Outcome` of the wrapped `Responder`.
+///
+/// If a flash cookie that hasn't been consumed is present, its messages are
+/// retained and this `Flash`'s messages are queued after them. If the cookie
+/// would exceed 4000 bytes, the oldest messages are dropped until it doesn't or
+/// only the newest message remains.
 impl<'r, 'o: 'r, R: Responder<'r, 'o>> Responder<'r, 'o> for Flash<R> {
     fn respond_to(self, req: &'r Request<'_>) -> response::Result<'o> {
-        req.cookies().add(self.cookie());
+        let mut entries = req.cookies().get_pending(FLASH_COOKIE_NAME)
+            .and_then(|cookie| Flash::<()>::parse(cookie.value()))
+            .unwrap_or_default();
+
+        entries.extend(self.entries);
+        let mut cookie = Flash::<()>::cookie(&entries);
+        let queued = entries.len();
+        while cookie.value().len() > FLASH_COOKIE_MAX_LEN && entries.len() > 1 {
+            entries.remove(0);
+            cookie = Flash::<()>::cookie(&entries);
+        }
+
+        if entries.len() < queued {
+            warn!(dropped = queued - entries.len(), max = FLASH_COOKIE_MAX_LEN,
+                "flash cookie too large: dropping oldest messages");
+        }
+
+        if cookie.value().len() > FLASH_COOKIE_MAX_LEN {
+            warn!(length = cookie.value().len(), max = FLASH_COOKIE_MAX_LEN,
+                "flash message is too large and may be rejected by clients");
+        }
+
+        req.cookies().add(cookie);
         self.inner.respond_to(req)
     }
 }
 
+impl FlashEntry {
+    fn new<K: Into<String>, M: Into<String>>(kind: K, message: M) -> Self {
+        FlashEntry { kind: kind.into(), message: message.into(), payload: None }
+    }
+
+    /// Returns the `kind` of this message.
+    pub fn kind(&self) -> &str {
+        &self.kind
+    }
+
+    /// Returns the `message` contents of this message.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Deserializes and returns the payload of this message. Returns `None` if
+    /// there is no payload or if it fails to deserialize as a `T`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #[macro_use] extern crate rocket;
+    /// use rocket::request::FlashMessage;
+    /// use rocket::serde::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// #[serde(crate = "rocket::serde")]
+    /// struct Undo {
+    ///     item: usize,
+    /// }
+    ///
+    /// #[get("/")]
+    /// fn index(flash: Option<FlashMessage<'_>>) -> String {
+    ///     let undo = flash.as_ref()
+    ///         .and_then(|flash| flash.messages().first())
+    ///         .and_then(|entry| entry.payload::<Undo>());
+    ///
+    ///     match undo {
+    ///         Some(undo) => format!("Deleted item {}. Undo?", undo.item),
+    ///         None => "Welcome!".into(),
+    ///     }
+    /// }
+    /// ```
+    #[cfg(feature = "json")]
+    #[cfg_attr(nightly, doc(cfg(feature = "json")))]
+    pub fn payload<T: serde::de::DeserializeOwned>(&self) -> Option<T> {
+        serde_json::from_str(self.payload.as_deref()?).ok()
+    }
+}
+
 impl<'r> FlashMessage<'r> {
-    /// Constructs a new message with the given name and message for the given
-    /// request.
-    fn named<S: Into<String>>(kind: S, message: S, req: &'r Request<'_>) -> Self {
+    /// Constructs a new flash message containing `entries`, which must be
+    /// non-empty, for the given request.
+    fn from_entries(entries: Vec<FlashEntry>, req: &'r Request<'_>) -> Self {
         Flash {
-            kind: kind.into(),
-            message: message.into(),
+            entries,
             consumed: AtomicBool::new(false),
             inner: req.cookies(),
         }
@@ -215,22 +443,48 @@ impl<'r> FlashMessage<'r> {
         }
     }
 
-    /// Returns a tuple of `(kind, message)`, consuming `self`.
+    /// Returns a tuple of `(kind, message)` of the first message, consuming
+    /// `self`.
     pub fn into_inner(self) -> (String, String) {
         self.clear_cookie_if_needed();
-        (self.kind, self.message)
+        let entry = self.entries.into_iter().next().expect("non-empty flash");
+        (entry.kind, entry.message)
     }
 
-    /// Returns the `kind` of this message.
+    /// Returns the `kind` of the first message.
     pub fn kind(&self) -> &str {
         self.clear_cookie_if_needed();
-        &self.kind
+        &self.entries[0].kind
     }
 
-    /// Returns the `message` contents of this message.
+    /// Returns the `message` contents of the first message.
     pub fn message(&self) -> &str {
         self.clear_cookie_if_needed();
-        &self.message
+        &self.entries[0].message
+    }
+
+    /// Returns all messages in the order they were queued. There is always at
+    /// least one.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #[macro_use] extern crate rocket;
+    /// use rocket::request::FlashMessage;
+    ///
+    /// #[get("/")]
+    /// fn index(flash: Option<FlashMessage<'_>>) -> String {
+    ///     flash.map(|flash| {
+    ///         flash.messages().iter()
+    ///             .map(|m| format!("{}: {}", m.kind(), m.message()))
+    ///             .collect::<Vec<_>>()
+    ///             .join("\n")
+    ///     }).unwrap_or_else(|| "Welcome!".to_string())
+    /// }
+    /// ```
+    pub fn messages(&self) -> &[FlashEntry] {
+        self.clear_cookie_if_needed();
+        &self.entries
     }
 }
 
@@ -244,27 +498,35 @@ impl<'r> FromRequest<'r> for FlashMessage<'r> {
     type Error = ();
 
     async fn from_request(req: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
-        req.cookies().get(FLASH_COOKIE_NAME).ok_or(()).and_then(|cookie| {
-            // Parse the flash message.
-            let content = cookie.value();
-            let (len_str, kv) = match content.find(FLASH_COOKIE_DELIM) {
-                Some(i) => (&content[..i], &content[(i + 1)..]),
-                None => return Err(()),
-            };
-
-            match len_str.parse::<usize>() {
-                Ok(i) if i <= kv.len() => Ok(Flash::named(&kv[..i], &kv[i..], req)),
-                _ => Err(())
-            }
-        }).or_error(Status::BadRequest)
+        req.cookies().get(FLASH_COOKIE_NAME)
+            .and_then(|cookie| Flash::<()>::parse(cookie.value()))
+            .map(|entries| Flash::from_entries(entries, req))
+            .ok_or(())
+            .or_error(Status::BadRequest)
     }
 }
 
 impl Serialize for FlashMessage<'_> {
     fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
-        let mut flash = ser.serialize_struct("Flash", 2)?;
+        let mut flash = ser.serialize_struct("Flash", 3)?;
         flash.serialize_field("kind", self.kind())?;
         flash.serialize_field("message", self.message())?;
+        flash.serialize_field("messages", self.messages())?;
         flash.end()
     }
 }
+
+impl Serialize for FlashEntry {
+    fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        let mut entry = ser.serialize_struct("FlashEntry", 3)?;
+        entry.serialize_field("kind", self.kind())?;
+        entry.serialize_field("message", self.message())?;
+
+        #[cfg(feature = "json")] {
+            let payload = self.payload::<serde_json::Value>();
+            entry.serialize_field("payload", &payload)?;
+        }
+
+        entry.end()
+    }
+}
@@ -0,0 +1,239 @@
+//! Rendering Markdown to HTML.
+//!
+//! The [`Markdown`] responder renders [CommonMark] to HTML with the common
+//! GitHub extensions: tables, strikethrough, task lists, and footnotes. Fenced
+//! code blocks in a language that's recognized, like `rust` in ```` ```rust
+//! ````, are highlighted with CSS classes; [`highlight_css()`] returns a
+//! stylesheet for them. The rendered HTML is always sanitized: raw HTML in the
+//! Markdown is reduced to a safe subset without scripts, styles, or event
+//! handlers, so Markdown from untrusted sources can be rendered safely.
+//!
+//! A [`FileServer`] can render `.md` files it serves as HTML pages via
+//! [`FileServer::markdown()`].
+//!
+//! This module is only available when the `markdown` feature is enabled.
+//!
+//! # Example
+//!
+//! ```rust
+//! # #[macro_use] extern crate rocket;
+//! use rocket::response::markdown::Markdown;
+//!
+//! #[get("/")]
+//! fn index() -> Markdown<&'static str> {
+//!     Markdown("# Hello, _world_!")
+//! }
+//!
+//! let html = Markdown("# Hi <script>alert(1)</script>").to_html();
+//! assert_eq!(html, "<h1>Hi </h1>\n");
+//! ```
+//!
+//! [CommonMark]: https://commonmark.org/
+//! [`FileServer`]: crate::fs::FileServer
+//! [`FileServer::markdown()`]: crate::fs::FileServer::markdown()
+
+use std::sync::OnceLock;
+
+use pulldown_cmark::{CodeBlockKind, Event, Options, Parser, Tag, TagEnd};
+use syntect::highlighting::ThemeSet;
+use syntect::html::{ClassStyle, ClassedHTMLGenerator};
+use syntect::parsing::SyntaxSet;
+use syntect::util::LinesWithEndings;
+
+use crate::request::Request;
+use crate::response::{self, Responder, content::RawHtml};
+use crate::util::EscapeHtml;
+
+/// The page template used by [`FileServer::markdown()`] by default.
+///
+/// The template is a minimal HTML document styled to be readable and with
+/// code highlighting. See [`Markdown::to_page()`] for the placeholders.
+///
+/// [`FileServer::markdown()`]: crate::fs::FileServer::markdown()
+pub const DEFAULT_TEMPLATE: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+  <meta charset="utf-8">
+  <meta name="viewport" content="width=device-width, initial-scale=1">
+  <title>{title}</title>
+  <style>
+    body { max-width: 50em; margin: 0 auto; padding: 1em; font-family: sans-serif; }
+    pre { padding: 1em; overflow-x: auto; background: #f6f8fa; }
+    {style}
+  </style>
+</head>
+<body>
+{content}
+</body>
+</html>
+"#;
+
+/// The highlighting theme used by [`highlight_css()`].
+const THEME: &str = "InspiredGitHub";
+
+/// A responder that renders Markdown to sanitized HTML.
+///
+/// Responds with the HTML rendered by [`Markdown::to_html()`] and a
+/// Content-Type of `text/html`. See the [module docs](self) for details.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Markdown<T>(pub T);
+
+impl<T: AsRef<str>> Markdown<T> {
+    /// Renders the Markdown to sanitized HTML.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::response::markdown::Markdown;
+    ///
+    /// let html = Markdown("Some *emphasis* and `code`.").to_html();
+    /// assert_eq!(html, "<p>Some <em>emphasis</em> and <code>code</code>.</p>\n");
+    /// ```
+    pub fn to_html(&self) -> String {
+        render(self.0.as_ref()).1
+    }
+
+    /// Renders the Markdown to sanitized HTML and substitutes it into the
+    /// page `template`, replacing the following placeholders:
+    ///
+    ///   * `{title}`: the text of the first heading, or the empty string
+    ///   * `{style}`: the stylesheet returned by [`highlight_css()`]
+    ///   * `{content}`: the rendered HTML
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::response::markdown::Markdown;
+    ///
+    /// let page = Markdown("# Docs & Co\nHi!").to_page("<title>{title}</title>{content}");
+    /// assert_eq!(page, "<title>Docs &amp; Co</title><h1>Docs &amp; Co</h1>\n<p>Hi!</p>\n");
+    /// ```
+    pub fn to_page(&self, template: &str) -> String {
+        let (title, html) = render(self.0.as_ref());
+        template.replace("{title}", &EscapeHtml(&title).to_string())
+            .replace("{style}", &highlight_css())
+            .replace("{content}", &html)
+    }
+}
+
+/// Renders `markdown` to sanitized HTML, returning the text of the first
+/// heading, if any, and the HTML.
+fn render(markdown: &str) -> (String, String) {
+    let options = Options::ENABLE_TABLES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS
+        | Options::ENABLE_FOOTNOTES;
+
+    let mut title: Option<String> = None;
+    let mut in_title = false;
+    let mut code: Option<(String, String)> = None;
+    let mut events = vec![];
+    for event in Parser::new_ext(markdown, options) {
+        match event {
+            Event::Start(Tag::Heading { .. }) if title.is_none() => {
+                in_title = true;
+                title = Some(String::new());
+            }
+            Event::End(TagEnd::Heading(_)) => in_title = false,
+            Event::Text(ref text) | Event::Code(ref text) if in_title => {
+                title.get_or_insert_with(String::new).push_str(text);
+            }
+            _ => {}
+        }
+
+        match event {
+            Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(ref info))) => {
+                let lang = info.split([',', ' ']).next().unwrap_or_default();
+                if syntaxes().find_syntax_by_token(lang).is_some() {
+                    code = Some((lang.to_string(), String::new()));
+                    continue;
+                }
+            }
+            Event::Text(ref text) if code.is_some() => {
+                if let Some((_, code)) = code.as_mut() {
+                    code.push_str(text);
+                }
+
+                continue;
+            }
+            Event::End(TagEnd::CodeBlock) if code.is_some() => {
+                let (lang, code) = code.take().expect("code block");
+                events.push(Event::Html(highlight(&lang, &code).into()));
+                continue;
+            }
+            _ => {}
+        }
+
+        events.push(event);
+    }
+
+    let mut html = String::new();
+    pulldown_cmark::html::push_html(&mut html, events.into_iter());
+    (title.unwrap_or_default(), sanitizer().clean(&html).to_string())
+}
+
+/// Highlights `code` in the language with token `lang` as HTML with classes.
+fn highlight(lang: &str, code: &str) -> String {
+    let syntaxes = syntaxes();
+    let syntax = syntaxes.find_syntax_by_token(lang)
+        .unwrap_or_else(|| syntaxes.find_syntax_plain_text());
+
+    let mut generator = ClassedHTMLGenerator::new_with_class_style(
+        syntax, syntaxes, ClassStyle::Spaced
+    );
+
+    for line in LinesWithEndings::from(code) {
+        if let Err(e) = generator.parse_html_for_line_which_includes_newline(line) {
+            warn!(name: "markdown", %lang, error = %e, "failed to highlight code");
+            return format!("<pre><code>{}</code></pre>", EscapeHtml(code));
+        }
+    }
+
+    let lang = EscapeHtml(lang);
+    format!("<pre class=\"code\"><code class=\"language-{lang}\">{}</code></pre>",
+        generator.finalize())
+}
+
+/// Returns a stylesheet for highlighted code in rendered Markdown.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::response::markdown;
+///
+/// assert!(markdown::highlight_css().contains(".code"));
+/// ```
+pub fn highlight_css() -> String {
+    static CSS: OnceLock<String> = OnceLock::new();
+    CSS.get_or_init(|| {
+        let themes = ThemeSet::load_defaults();
+        syntect::html::css_for_theme_with_class_style(&themes.themes[THEME], ClassStyle::Spaced)
+            .unwrap_or_default()
+    }).clone()
+}
+
+fn syntaxes() -> &'static SyntaxSet {
+    static SYNTAXES: OnceLock<SyntaxSet> = OnceLock::new();
+    SYNTAXES.get_or_init(SyntaxSet::load_defaults_newlines)
+}
+
+fn sanitizer() -> &'static ammonia::Builder<'static> {
+    static SANITIZER: OnceLock<ammonia::Builder<'static>> = OnceLock::new();
+    SANITIZER.get_or_init(|| {
+        let mut builder = ammonia::Builder::default();
+        builder.add_tag_attributes("pre", ["class"])
+            .add_tag_attributes("code", ["class"])
+            .add_tag_attributes("span", ["class"])
+            .add_tag_attributes("input", ["type", "checked", "disabled"])
+            .add_tags(["input"]);
+
+        builder
+    })
+}
+
+/// Responds with the rendered HTML. Never fails.
+impl<'r, T: AsRef<str>> Responder<'r, 'static> for Markdown<T> {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
+        RawHtml(self.to_html()).respond_to(req)
+    }
+}
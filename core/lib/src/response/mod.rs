@@ -18,6 +18,9 @@ mod redirect;
 mod response;
 mod debug;
 mod body;
+mod throttle;
+mod upgrade;
+mod render;
 
 pub(crate) mod flash;
 
@@ -25,6 +28,14 @@ pub mod content;
 pub mod status;
 pub mod stream;
 
+#[cfg(feature = "markdown")]
+#[cfg_attr(nightly, doc(cfg(feature = "markdown")))]
+pub mod markdown;
+
+#[cfg(feature = "barcode")]
+#[cfg_attr(nightly, doc(cfg(feature = "barcode")))]
+pub mod barcode;
+
 #[doc(hidden)]
 pub use rocket_codegen::Responder;
 
@@ -32,8 +43,11 @@ pub use self::response::{Response, Builder};
 pub use self::body::Body;
 pub use self::responder::Responder;
 pub use self::redirect::Redirect;
-pub use self::flash::Flash;
+pub use self::flash::{Flash, FlashEntry};
 pub use self::debug::Debug;
+pub use self::throttle::{Throttled, Throttle};
+pub use self::upgrade::Upgrade;
+pub use self::render::{Render, RenderOutput, Rendered};
 
 /// Type alias for the `Result` of a [`Responder::respond_to()`] call.
 pub type Result<'r> = std::result::Result<Response<'r>, crate::http::Status>;
@@ -1,6 +1,6 @@
 use crate::request::Request;
 use crate::response::{self, Response, Responder};
-use crate::http::uri::Reference;
+use crate::http::uri::{Reference, Host};
 use crate::http::Status;
 
 /// An empty redirect response to a given URL.
@@ -43,12 +43,43 @@ use crate::http::Status;
 /// }
 /// ```
 ///
+/// To additionally carry over the query string of the current request, use
+/// [`Redirect::preserve_query()`]. Fragments need no special handling: when a
+/// redirect URI has no fragment, browsers retain the fragment of the original
+/// URL.
+///
+/// # Preserving the Request Method
+///
+/// Only `307` ([`Redirect::temporary()`]) and `308` ([`Redirect::permanent()`])
+/// redirects guarantee that the client resends the request with the same
+/// method and body. Clients may, and browsers do, change the method of a `POST`
+/// request to `GET` when following a `301` or `302` redirect, and always use
+/// `GET` for a `303` redirect. Use [`Redirect::preserve_method()`] to upgrade a
+/// redirect to its method-preserving equivalent.
+///
+/// # Redirecting Back
+///
+/// [`Redirect::see_other_back()`] redirects to the page named by a `return_to`
+/// query parameter or, absent one, the `Referer` header, as after a login form.
+/// Both are client-controlled, so the target is validated to prevent open
+/// redirects.
+///
 /// [`Origin`]: crate::http::uri::Origin
 /// [`uri!`]: ../macro.uri.html
 #[derive(Debug, Clone)]
-pub struct Redirect(Status, Option<Reference<'static>>);
+pub struct Redirect {
+    status: Status,
+    uri: Option<Reference<'static>>,
+    preserve_query: bool,
+}
 
 impl Redirect {
+    /// Constructs a redirect with `status` to `uri` that doesn't preserve the
+    /// query.
+    fn new<U: TryInto<Reference<'static>>>(status: Status, uri: U) -> Redirect {
+        Redirect { status, uri: uri.try_into().ok(), preserve_query: false }
+    }
+
     /// Construct a temporary "see other" (303) redirect response. This is the
     /// typical response when redirecting a user to another page. This type of
     /// redirect indicates that the client should look elsewhere, but always via
@@ -64,7 +95,7 @@ impl Redirect {
     /// let redirect = Redirect::to(uri!("https://domain.com#foo"));
     /// ```
     pub fn to<U: TryInto<Reference<'static>>>(uri: U) -> Redirect {
-        Redirect(Status::SeeOther, uri.try_into().ok())
+        Redirect::new(Status::SeeOther, uri)
     }
 
     /// Construct a "temporary" (307) redirect response. This response instructs
@@ -84,7 +115,7 @@ impl Redirect {
     /// let redirect = Redirect::temporary(format!("some-{}-thing", "crazy"));
     /// ```
     pub fn temporary<U: TryInto<Reference<'static>>>(uri: U) -> Redirect {
-        Redirect(Status::TemporaryRedirect, uri.try_into().ok())
+        Redirect::new(Status::TemporaryRedirect, uri)
     }
 
    /// Construct a "permanent" (308) redirect response. This redirect must only
@@ -104,7 +135,7 @@ impl Redirect {
    /// let redirect = Redirect::permanent(format!("some-{}-thing", "crazy"));
    /// ```
    pub fn permanent<U: TryInto<Reference<'static>>>(uri: U) -> Redirect {
-       Redirect(Status::PermanentRedirect, uri.try_into().ok())
+       Redirect::new(Status::PermanentRedirect, uri)
    }
 
    /// Construct a temporary "found" (302) redirect response. This response
@@ -124,7 +155,7 @@ impl Redirect {
    /// let redirect = Redirect::found(format!("some-{}-thing", "crazy"));
    /// ```
    pub fn found<U: TryInto<Reference<'static>>>(uri: U) -> Redirect {
-       Redirect(Status::Found, uri.try_into().ok())
+       Redirect::new(Status::Found, uri)
    }
 
    /// Construct a permanent "moved" (301) redirect response. This response
@@ -142,13 +173,169 @@ impl Redirect {
    /// let redirect = Redirect::moved(format!("some-{}-thing", "crazy"));
    /// ```
    pub fn moved<U: TryInto<Reference<'static>>>(uri: U) -> Redirect {
-       Redirect(Status::MovedPermanently, uri.try_into().ok())
+       Redirect::new(Status::MovedPermanently, uri)
    }
 
     pub fn map_uri<U: TryInto<Reference<'static>>>(self, f: impl FnOnce(Reference<'static>) -> U)
         -> Redirect
     {
-        Redirect(self.0, self.1.and_then(|p| f(p).try_into().ok()))
+        Redirect { uri: self.uri.and_then(|p| f(p).try_into().ok()), ..self }
+    }
+
+    /// Construct a "see other" (303) redirect back to the page the client came
+    /// from, or to `fallback` if there is no valid such page.
+    ///
+    /// The target is taken from the `return_to` query parameter of `req` or, if
+    /// it is missing or invalid, from the `Referer` header. A target is valid
+    /// if it is:
+    ///
+    ///   * a relative URI with an absolute path, such as `/account?tab=2`, or
+    ///   * an absolute `http` or `https` URI whose host, including the port,
+    ///     is the host of `req` or is in `allowed`.
+    ///
+    /// All other targets, including scheme-relative URIs such as
+    /// `//evil.com`, paths with backslashes, and URIs with other schemes, are
+    /// rejected, preventing the redirect from being used to send users to
+    /// arbitrary sites.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[macro_use] extern crate rocket;
+    /// use rocket::request::Request;
+    /// use rocket::response::{self, Redirect, Responder};
+    /// use rocket::http::uri::Host;
+    ///
+    /// #[get("/")]
+    /// fn index() { /* .. */ }
+    ///
+    /// /// Redirects back to the page the user came from.
+    /// struct Back;
+    ///
+    /// impl<'r> Responder<'r, 'static> for Back {
+    ///     fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
+    ///         let accounts = Host::new(uri!("accounts.rocket.rs"));
+    ///         Redirect::see_other_back(req, &[accounts], uri!(index)).respond_to(req)
+    ///     }
+    /// }
+    ///
+    /// #[post("/login")]
+    /// fn login() -> Back {
+    ///     /* log the user in */
+    ///     Back
+    /// }
+    /// ```
+    pub fn see_other_back<'h, U, W>(req: &Request<'_>, allowed: W, fallback: U) -> Redirect
+        where U: TryInto<Reference<'static>>, W: IntoIterator<Item = &'h Host<'h>>
+    {
+        let allowed: Vec<_> = allowed.into_iter().collect();
+        let return_to = req.query_value::<String>("return_to").and_then(|r| r.ok());
+        let referer = req.headers().get_one("Referer").map(|s| s.to_string());
+        return_to.into_iter()
+            .chain(referer)
+            .find_map(|target| Self::back_target(req, &allowed, target))
+            .map(|uri| Redirect::new(Status::SeeOther, uri))
+            .unwrap_or_else(|| Redirect::to(fallback))
+    }
+
+    /// Returns `target` as a URI if it is a safe target for a redirect back.
+    fn back_target(req: &Request<'_>, allowed: &[&Host<'_>], target: String)
+        -> Option<Reference<'static>>
+    {
+        if target.contains('\\') || target.bytes().any(|b| b.is_ascii_control()) {
+            return None;
+        }
+
+        let uri = Reference::parse(&target).ok()?;
+        match (uri.scheme(), uri.authority()) {
+            (None, None) if target.starts_with('/') => {}
+            (Some(scheme), Some(authority)) => {
+                let http = ["http", "https"].iter().any(|s| s.eq_ignore_ascii_case(scheme));
+                let host = Host::new(authority.clone());
+                let known = req.host().map_or(false, |h| *h == host)
+                    || allowed.iter().any(|h| **h == host);
+
+                if !http || !known || authority.user_info().is_some() {
+                    return None;
+                }
+            }
+            _ => return None,
+        }
+
+        Reference::parse_owned(target).ok()
+    }
+
+    /// Carry over the query string of the current request to the redirect URI
+    /// if the redirect URI has no query of its own.
+    ///
+    /// This is useful when redirecting to a route via [`uri!`] while retaining
+    /// parameters, such as tracking or pagination parameters, that the route
+    /// doesn't declare. The fragment of the redirect URI, if any, is retained.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[macro_use] extern crate rocket;
+    /// use rocket::response::Redirect;
+    ///
+    /// #[get("/articles/<id>")]
+    /// fn article(id: usize) { /* .. */ }
+    ///
+    /// // A request to `/posts/7?utm_source=feed` is redirected to
+    /// // `/articles/7?utm_source=feed#comments`.
+    /// #[get("/posts/<id>")]
+    /// fn post(id: usize) -> Redirect {
+    ///     Redirect::permanent(uri!(_, article(id), "#comments")).preserve_query()
+    /// }
+    /// ```
+    ///
+    /// [`uri!`]: ../macro.uri.html
+    pub fn preserve_query(mut self) -> Redirect {
+        self.preserve_query = true;
+        self
+    }
+
+    /// Upgrade this redirect to one that requires the client to reissue the
+    /// request with the same method and body: a `303` or `302` redirect
+    /// becomes a `307` redirect, and a `301` redirect becomes a `308`
+    /// redirect. `307` and `308` redirects are unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[macro_use] extern crate rocket;
+    /// use rocket::response::Redirect;
+    ///
+    /// // Resend `POST`s to `/v1/upload` to `/v2/upload`, as a `POST`.
+    /// #[post("/v1/upload")]
+    /// fn upload() -> Redirect {
+    ///     Redirect::moved(uri!("/v2/upload")).preserve_method()
+    /// }
+    /// ```
+    pub fn preserve_method(mut self) -> Redirect {
+        self.status = match self.status {
+            Status::MovedPermanently => Status::PermanentRedirect,
+            Status::SeeOther | Status::Found => Status::TemporaryRedirect,
+            status => status,
+        };
+
+        self
+    }
+
+    /// Returns the status of this redirect.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[macro_use] extern crate rocket;
+    /// use rocket::response::Redirect;
+    /// use rocket::http::Status;
+    ///
+    /// let redirect = Redirect::found(uri!("/")).preserve_method();
+    /// assert_eq!(redirect.status(), Status::TemporaryRedirect);
+    /// ```
+    pub fn status(&self) -> Status {
+        self.status
     }
 }
 
@@ -157,11 +344,18 @@ impl Redirect {
 /// value used to create the `Responder` is an invalid URI, an error of
 /// `Status::InternalServerError` is returned.
 impl<'r> Responder<'r, 'static> for Redirect {
-    fn respond_to(self, _: &'r Request<'_>) -> response::Result<'static> {
-        if let Some(uri) = self.1 {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
+        if let Some(uri) = self.uri {
+            let mut location = uri.to_string();
+            let query = req.uri().query();
+            if let (true, None, Some(query)) = (self.preserve_query, uri.query(), query) {
+                let at = location.find('#').unwrap_or(location.len());
+                location.insert_str(at, &format!("?{}", query.as_str()));
+            }
+
             Response::build()
-                .status(self.0)
-                .raw_header("Location", uri.to_string())
+                .status(self.status)
+                .raw_header("Location", location)
                 .ok()
         } else {
             error!("Invalid URI used for redirect.");
@@ -0,0 +1,293 @@
+use std::io;
+use std::any::type_name;
+use std::pin::Pin;
+use std::future::Future;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use tokio::io::{AsyncRead, AsyncWrite, DuplexStream, ReadBuf};
+
+use crate::{Request, Rocket, Ignite};
+use crate::http::{Status, ContentType, ContentDisposition};
+use crate::response::{self, Response, Responder};
+use crate::sentinel::{Sentinel, Missing};
+
+/// The size of the buffer between a renderer and the response body.
+const BUFFER_SIZE: usize = 64 * 1024;
+
+/// A renderer of documents, such as PDFs, from handler data.
+///
+/// A `Render` implementation turns an [`Input`](Render::Input), the data a
+/// handler produces, into a document written to a [`RenderOutput`]. Renderers
+/// are typically provided by integrations wrapping an external tool, like
+/// `wkhtmltopdf`, `typst`, or a headless browser, and are registered as
+/// managed state. Handlers respond with [`Rendered`], which looks up the
+/// renderer, streams its output to the client, and enforces a timeout.
+///
+/// # Example
+///
+/// A renderer piping HTML through `wkhtmltopdf`:
+///
+/// ```rust
+/// use std::io;
+/// use std::process::Stdio;
+///
+/// use rocket::http::ContentType;
+/// use rocket::response::{Render, RenderOutput};
+/// use rocket::tokio::io::AsyncWriteExt;
+/// use rocket::tokio::process::Command;
+///
+/// struct WkHtmlToPdf;
+///
+/// #[rocket::async_trait]
+/// impl Render for WkHtmlToPdf {
+///     type Input = String;
+///
+///     fn content_type(&self) -> ContentType {
+///         ContentType::PDF
+///     }
+///
+///     async fn render(&self, html: String, mut output: RenderOutput) -> io::Result<()> {
+///         let mut child = Command::new("wkhtmltopdf")
+///             .args(["--quiet", "-", "-"])
+///             .stdin(Stdio::piped())
+///             .stdout(Stdio::piped())
+///             .kill_on_drop(true)
+///             .spawn()?;
+///
+///         let mut stdin = child.stdin.take().unwrap();
+///         let mut stdout = child.stdout.take().unwrap();
+///         let write = async move { stdin.write_all(html.as_bytes()).await };
+///         let copy = rocket::tokio::io::copy(&mut stdout, &mut output);
+///         let (written, copied) = rocket::tokio::join!(write, copy);
+///         written?;
+///         copied?;
+///
+///         match child.wait().await? {
+///             status if status.success() => Ok(()),
+///             status => Err(io::Error::other(format!("wkhtmltopdf failed: {status}"))),
+///         }
+///     }
+/// }
+/// ```
+#[crate::async_trait]
+pub trait Render: Send + Sync + 'static {
+    /// The data a document is rendered from.
+    type Input: Send + 'static;
+
+    /// The `Content-Type` of rendered documents.
+    fn content_type(&self) -> ContentType;
+
+    /// The default time allowed to render a document. Rendering that takes
+    /// longer is aborted. Defaults to 30 seconds.
+    fn timeout(&self) -> Duration {
+        Duration::from_secs(30)
+    }
+
+    /// Renders a document from `input`, writing it to `output`. Returns once
+    /// the document is completely written.
+    ///
+    /// Output is sent to the client as it is written. Writes fail if the
+    /// client disconnects, at which point rendering should stop.
+    async fn render(&self, input: Self::Input, output: RenderOutput) -> io::Result<()>;
+}
+
+/// The sink a [`Render`] implementation writes a document to.
+///
+/// `RenderOutput` implements [`AsyncWrite`]. Writes wait while the client
+/// isn't keeping up, so at most a fixed amount of a document is buffered.
+pub struct RenderOutput(DuplexStream);
+
+/// A responder that renders a document via a managed [`Render`]er.
+///
+/// # Responder
+///
+/// The renderer of type `R` is retrieved from managed state, and its
+/// [`content_type()`](Render::content_type()) is set as the response's
+/// `Content-Type`. If a [`filename`](Rendered::filename()) is set, a
+/// `Content-Disposition` of `attachment` with that file name is added. The body
+/// is [unsized](crate::response::Body#unsized) and rendered as it's sent:
+/// output is streamed as soon as the renderer writes it.
+///
+/// If rendering fails or exceeds the [timeout](Rendered::timeout()), the
+/// response is aborted and the client receives a truncated document. If no `R`
+/// is managed, the response fails with a `500`.
+///
+/// # Sentinel
+///
+/// A `Rendered<R>` is a [`Sentinel`] that aborts launch if no `R` is managed.
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// # use std::io;
+/// # use rocket::http::ContentType;
+/// # use rocket::response::{Render, RenderOutput};
+/// # struct WkHtmlToPdf;
+/// # #[rocket::async_trait]
+/// # impl Render for WkHtmlToPdf {
+/// #     type Input = String;
+/// #     fn content_type(&self) -> ContentType { ContentType::PDF }
+/// #     async fn render(&self, _: String, _: RenderOutput) -> io::Result<()> { Ok(()) }
+/// # }
+/// use std::time::Duration;
+///
+/// use rocket::response::Rendered;
+///
+/// #[get("/invoice/<id>")]
+/// fn invoice(id: usize) -> Rendered<WkHtmlToPdf> {
+///     let html = format!("<h1>Invoice #{id}</h1>");
+///     Rendered::new(html)
+///         .filename(format!("invoice-{id}.pdf"))
+///         .timeout(Duration::from_secs(10))
+/// }
+///
+/// #[launch]
+/// fn rocket() -> _ {
+///     rocket::build()
+///         .manage(WkHtmlToPdf)
+///         .mount("/", routes![invoice])
+/// }
+/// ```
+pub struct Rendered<R: Render> {
+    input: R::Input,
+    filename: Option<String>,
+    timeout: Option<Duration>,
+}
+
+/// The body of a [`Rendered`] response: drives rendering as it's read.
+struct RenderBody<'r> {
+    render: Option<Pin<Box<dyn Future<Output = io::Result<()>> + Send + 'r>>>,
+    reader: DuplexStream,
+}
+
+impl<R: Render> Rendered<R> {
+    /// Renders a document from `input` with the managed `R`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use std::io;
+    /// # use rocket::http::ContentType;
+    /// # use rocket::response::{Render, RenderOutput};
+    /// # struct Typst;
+    /// # #[rocket::async_trait]
+    /// # impl Render for Typst {
+    /// #     type Input = String;
+    /// #     fn content_type(&self) -> ContentType { ContentType::PDF }
+    /// #     async fn render(&self, _: String, _: RenderOutput) -> io::Result<()> { Ok(()) }
+    /// # }
+    /// use rocket::response::Rendered;
+    ///
+    /// let report: Rendered<Typst> = Rendered::new("= Quarterly Report".into());
+    /// ```
+    pub fn new(input: R::Input) -> Self {
+        Rendered { input, filename: None, timeout: None }
+    }
+
+    /// Sets the file name suggested to the client via `Content-Disposition`.
+    pub fn filename<N: Into<String>>(mut self, filename: N) -> Self {
+        self.filename = Some(filename.into());
+        self
+    }
+
+    /// Sets the time allowed to render the document, overriding the
+    /// renderer's [default](Render::timeout()).
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+}
+
+impl<'r, R: Render> Responder<'r, 'r> for Rendered<R> {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'r> {
+        let Some(renderer) = req.rocket().state::<R>() else {
+            error!(type_name = type_name::<R>(),
+                "retrieving unmanaged renderer\n\
+                renderer must be managed via `rocket.manage()`");
+
+            return Err(Status::InternalServerError);
+        };
+
+        let Rendered { input, filename, timeout } = self;
+        let timeout = timeout.unwrap_or_else(|| renderer.timeout());
+        let (reader, writer) = tokio::io::duplex(BUFFER_SIZE);
+        let render = async move {
+            let rendering = renderer.render(input, RenderOutput(writer));
+            match tokio::time::timeout(timeout, rendering).await {
+                Ok(result) => result,
+                Err(_) => Err(io::Error::new(io::ErrorKind::TimedOut, "rendering timed out")),
+            }
+        };
+
+        let mut response = Response::build();
+        response.header(renderer.content_type());
+        if let Some(filename) = filename {
+            response.header(ContentDisposition::attachment().with_filename(filename));
+        }
+
+        let body = RenderBody { render: Some(Box::pin(render)), reader };
+        response.streamed_body(body).ok()
+    }
+}
+
+impl<R: Render> Sentinel for Rendered<R> {
+    fn abort(rocket: &Rocket<Ignite>) -> bool {
+        if rocket.state::<R>().is_none() {
+            error!(type_name = type_name::<R>(),
+                "unmanaged renderer detected\n\
+                ensure renderer is being managed via `rocket.manage()`");
+
+            return true;
+        }
+
+        false
+    }
+
+    fn missing(rocket: &Rocket<Ignite>) -> Option<Missing> {
+        rocket.state::<R>().is_none().then(|| Missing::new::<R>()
+            .suggestion(format!("manage a `{}` via `rocket.manage()`", type_name::<R>())))
+    }
+}
+
+impl AsyncRead for RenderBody<'_> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        if let Some(render) = this.render.as_mut() {
+            if let Poll::Ready(result) = render.as_mut().poll(cx) {
+                // Dropping the render future drops the writer, ending the body
+                // once everything written has been read.
+                this.render = None;
+                if let Err(e) = result {
+                    warn!("rendering failed: {e}");
+                    return Poll::Ready(Err(e));
+                }
+            }
+        }
+
+        Pin::new(&mut this.reader).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for RenderOutput {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.0).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.0).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.0).poll_shutdown(cx)
+    }
+}
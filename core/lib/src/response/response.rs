@@ -479,6 +479,10 @@ impl<'r> Builder<'r> {
 /// If a connection _is not_ upgraded due to an error, even though there was a
 /// matching, registered protocol, the `IoHandler` is not invoked, and the
 /// original response is sent to the client without alteration.
+///
+/// The [`Upgrade`](crate::response::Upgrade) responder packages this mechanism
+/// for custom protocols, handing a handler the raw `IoStream` along with a
+/// [`Shutdown`](crate::Shutdown) handle.
 #[derive(Default)]
 pub struct Response<'r> {
     status: Option<Status>,
@@ -792,6 +796,13 @@ impl<'r> Response<'r> {
         &self.body
     }
 
+    /// Removes and returns any registered handler. Used for `CONNECT`
+    /// requests, which tunnel without naming a protocol.
+    pub(crate) fn take_upgrade(&mut self) -> Option<(Uncased<'r>, Box<dyn IoHandler + 'r>)> {
+        let protocol = self.upgrade.keys().next()?.clone();
+        self.upgrade.remove_entry(&protocol)
+    }
+
     /// Returns `Ok(Some(_))` if `self` contains a suitable handler for any of
     /// the comma-separated protocol strings in `I`. Returns `Err(_)` if
     /// `protocols` is non-empty but no match was found in `self`. If `self`
@@ -884,6 +895,45 @@ impl<'r> Response<'r> {
         self.body.strip();
     }
 
+    // Adopts a `Content-Length` header as the declared size of an unsized body
+    // and removes it so that it can't go stale if the body is later replaced.
+    pub(crate) fn adopt_content_length(&mut self) {
+        let Some(length) = self.headers.get_one("Content-Length") else { return };
+        match length.trim().parse::<usize>() {
+            Ok(size) if self.body.preset_size().is_none() => self.body.set_size(size),
+            Ok(size) if self.body.preset_size() == Some(size) => {},
+            _ => warn!(%length, size = self.body.preset_size(),
+                "ignoring `Content-Length` header that doesn't match body"),
+        }
+
+        self.headers.remove("Content-Length");
+    }
+
+    // Replaces any framing headers with a `Content-Length` computed from the
+    // body, if its size is known. Otherwise, the body is chunk-encoded.
+    pub(crate) async fn set_framing_headers(&mut self) {
+        let length = self.headers.get_one("Content-Length").map(|v| v.to_string());
+        self.headers.remove("Content-Length");
+        self.headers.remove("Transfer-Encoding");
+
+        let size = self.body.size().await;
+        if let Some(length) = length {
+            if size.map(|size| size.to_string()).as_ref() != Some(&length) {
+                warn!(%length, size, "ignoring `Content-Length` header that doesn't match body");
+            }
+        }
+
+        // These responses have no body, so they mustn't have a `Content-Length`.
+        let status = self.status();
+        let bodiless = status.class().is_informational()
+            || status == Status::NoContent
+            || status == Status::NotModified;
+
+        if let Some(size) = size.filter(|_| !bodiless) {
+            self.set_raw_header("Content-Length", size.to_string());
+        }
+    }
+
     /// Sets the body of `self` to be the fixed-sized `body` with size
     /// `size`, which may be `None`. If `size` is `None`, the body's size will
     /// be computing with calls to `seek` just before being written out in a
@@ -0,0 +1,283 @@
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use futures::stream::{self, Stream, StreamExt};
+use parking_lot::Mutex;
+use tokio::sync::broadcast::{self, error::RecvError};
+
+use crate::request::{self, FromRequest, Request};
+use crate::response::stream::{Event, EventStream};
+
+/// A broadcast channel of [`Event`]s with an optional replay buffer.
+///
+/// Every event sent via [`EventChannel::send()`] is delivered to every current
+/// subscriber. Each event is assigned a sequential numeric `id`, replacing any
+/// `id` previously set on the event, which clients echo back via the
+/// `Last-Event-ID` header when they reconnect.
+///
+/// When a channel is created with a replay buffer via
+/// [`EventChannel::with_replay()`], the most recent events are kept for a
+/// bounded time. A client that reconnects with a `Last-Event-ID` first
+/// receives, in order, all buffered events it missed and then live events.
+/// Events that have been evicted from the buffer, either because the buffer is
+/// full or because their TTL elapsed, are not replayed.
+///
+/// # Example
+///
+/// ```rust
+/// # use rocket::{get, post, routes};
+/// use std::time::Duration;
+///
+/// use rocket::State;
+/// use rocket::response::stream::{Event, EventChannel, EventStream, LastEventId};
+///
+/// #[get("/events")]
+/// fn events(channel: &State<EventChannel>, last: LastEventId) -> EventStream![] {
+///     channel.subscribe(last.as_deref())
+/// }
+///
+/// #[post("/message", data = "<message>")]
+/// fn message(channel: &State<EventChannel>, message: String) {
+///     channel.send(Event::data(message));
+/// }
+///
+/// #[rocket::launch]
+/// fn rocket() -> _ {
+///     // Keep up to 128 events for up to 5 minutes for reconnecting clients.
+///     let channel = EventChannel::with_replay(1024, 128, Duration::from_secs(300));
+///
+///     rocket::build()
+///         .manage(channel)
+///         .mount("/", routes![events, message])
+/// }
+/// ```
+#[derive(Clone)]
+pub struct EventChannel {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    sender: broadcast::Sender<(u64, Event)>,
+    replay: Option<(usize, Duration)>,
+    state: Mutex<State>,
+}
+
+struct State {
+    next_id: u64,
+    buffer: VecDeque<(u64, Instant, Event)>,
+}
+
+impl EventChannel {
+    /// Creates a new channel that can hold up to `capacity` events not yet
+    /// received by every subscriber. Subscribers that fall further behind
+    /// miss the oldest events. No replay buffer is configured.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is `0`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::response::stream::EventChannel;
+    ///
+    /// let channel = EventChannel::new(1024);
+    /// ```
+    pub fn new(capacity: usize) -> Self {
+        EventChannel::build(capacity, None)
+    }
+
+    /// Creates a new channel like [`EventChannel::new()`] with a replay buffer
+    /// that retains up to `replay` of the most recent events for up to `ttl`
+    /// each. A `replay` of `0` configures no replay buffer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is `0`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use rocket::response::stream::EventChannel;
+    ///
+    /// let channel = EventChannel::with_replay(1024, 64, Duration::from_secs(60));
+    /// ```
+    pub fn with_replay(capacity: usize, replay: usize, ttl: Duration) -> Self {
+        EventChannel::build(capacity, (replay > 0).then_some((replay, ttl)))
+    }
+
+    fn build(capacity: usize, replay: Option<(usize, Duration)>) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+        let state = State { next_id: 0, buffer: VecDeque::new() };
+        EventChannel { inner: Arc::new(Inner { sender, replay, state: Mutex::new(state) }) }
+    }
+
+    /// Sends `event` to all current subscribers and stores it in the replay
+    /// buffer, if there is one. Returns the `id` assigned to the event.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::response::stream::{Event, EventChannel};
+    ///
+    /// let channel = EventChannel::new(16);
+    /// assert_eq!(channel.send(Event::data("first")), 0);
+    /// assert_eq!(channel.send(Event::data("second")), 1);
+    /// ```
+    pub fn send(&self, event: Event) -> u64 {
+        let mut state = self.inner.state.lock();
+        let id = state.next_id;
+        state.next_id += 1;
+
+        let event = event.id(id.to_string());
+        if let Some((capacity, ttl)) = self.inner.replay {
+            Self::evict(&mut state.buffer, capacity - 1, ttl);
+            state.buffer.push_back((id, Instant::now(), event.clone()));
+        }
+
+        // An error means there are no subscribers, which is fine.
+        let _ = self.inner.sender.send((id, event));
+        id
+    }
+
+    /// Returns the number of current subscribers.
+    pub fn subscribers(&self) -> usize {
+        self.inner.sender.receiver_count()
+    }
+
+    /// Subscribes to the channel, returning an [`EventStream`] of its events.
+    ///
+    /// If `last_event_id` is the `id` of an event sent via this channel, which
+    /// is typically obtained from the [`LastEventId`] request guard, buffered
+    /// events sent after it are replayed before any live events. Otherwise,
+    /// only live events are streamed.
+    pub fn subscribe(&self, last_event_id: Option<&str>) -> EventStream<impl Stream<Item = Event>> {
+        EventStream::from(self.events(last_event_id))
+    }
+
+    fn events(&self, last_event_id: Option<&str>) -> impl Stream<Item = Event> {
+        let last_seen = last_event_id.and_then(|id| id.trim().parse::<u64>().ok());
+
+        // Subscribe while holding the lock so no event is missed or repeated
+        // between the replayed events and the live ones.
+        let mut state = self.inner.state.lock();
+        let receiver = self.inner.sender.subscribe();
+        let replayed: Vec<Event> = match (last_seen, self.inner.replay) {
+            (Some(last), Some((capacity, ttl))) => {
+                Self::evict(&mut state.buffer, capacity, ttl);
+                state.buffer.iter()
+                    .filter(|(id, ..)| *id > last)
+                    .map(|(.., event)| event.clone())
+                    .collect()
+            }
+            _ => vec![],
+        };
+
+        drop(state);
+        let live = stream::unfold(receiver, |mut receiver| async move {
+            loop {
+                match receiver.recv().await {
+                    Ok((_, event)) => return Some((event, receiver)),
+                    Err(RecvError::Lagged(n)) => warn!(missed = n, "event subscriber lagged"),
+                    Err(RecvError::Closed) => return None,
+                }
+            }
+        });
+
+        stream::iter(replayed).chain(live)
+    }
+
+    /// Evicts events so that at most `keep` unexpired ones remain.
+    fn evict(buffer: &mut VecDeque<(u64, Instant, Event)>, keep: usize, ttl: Duration) {
+        while buffer.len() > keep || buffer.front().map_or(false, |(_, at, _)| at.elapsed() > ttl) {
+            buffer.pop_front();
+        }
+    }
+}
+
+/// A request guard for the value of the `Last-Event-ID` header.
+///
+/// Derefs to `Option<String>`: `None` if the header is missing. Never fails.
+/// See [`EventChannel`] for an example.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct LastEventId(pub Option<String>);
+
+impl std::ops::Deref for LastEventId {
+    type Target = Option<String>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[crate::async_trait]
+impl<'r> FromRequest<'r> for LastEventId {
+    type Error = std::convert::Infallible;
+
+    async fn from_request(req: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        let id = req.headers().get_one("Last-Event-ID").map(|id| id.to_string());
+        request::Outcome::Success(LastEventId(id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replays_missed_events() {
+        crate::async_test(async {
+            let channel = EventChannel::with_replay(16, 2, Duration::from_secs(60));
+            for i in 0..4 {
+                channel.send(Event::data(i.to_string()));
+            }
+
+            // Only the last two events are buffered; the client saw `1`.
+            let stream = channel.events(Some("1"));
+            channel.send(Event::data("4"));
+            let events: Vec<_> = stream.take(3).collect().await;
+            let expected = vec![
+                Event::data("2").id("2"),
+                Event::data("3").id("3"),
+                Event::data("4").id("4"),
+            ];
+
+            assert_eq!(events, expected);
+        })
+    }
+
+    #[test]
+    fn no_replay_without_buffer_or_id() {
+        crate::async_test(async {
+            let channel = EventChannel::new(16);
+            channel.send(Event::data("a"));
+            let stream = channel.events(Some("0"));
+            channel.send(Event::data("b"));
+            let events: Vec<_> = stream.take(1).collect().await;
+            assert_eq!(events, vec![Event::data("b").id("1")]);
+
+            let channel = EventChannel::with_replay(16, 8, Duration::from_secs(60));
+            channel.send(Event::data("a"));
+            let stream = channel.events(None);
+            channel.send(Event::data("b"));
+            let events: Vec<_> = stream.take(1).collect().await;
+            assert_eq!(events, vec![Event::data("b").id("1")]);
+        })
+    }
+
+    #[test]
+    fn expired_events_are_not_replayed() {
+        crate::async_test(async {
+            let channel = EventChannel::with_replay(16, 8, Duration::ZERO);
+            channel.send(Event::data("a"));
+            std::thread::sleep(Duration::from_millis(5));
+            let stream = channel.events(Some("0"));
+            channel.send(Event::data("b"));
+            channel.send(Event::data("c"));
+            let events: Vec<_> = stream.take(2).collect().await;
+            assert_eq!(events, vec![Event::data("b").id("1"), Event::data("c").id("2")]);
+        })
+    }
+}
@@ -194,6 +194,9 @@ mod text;
 mod one;
 mod sse;
 mod raw_sse;
+mod channel;
+#[cfg(feature = "zip")]
+mod zip;
 
 pub(crate) use self::raw_sse::*;
 
@@ -202,6 +205,10 @@ pub use self::text::TextStream;
 pub use self::bytes::ByteStream;
 pub use self::reader::ReaderStream;
 pub use self::sse::{Event, EventStream};
+pub use self::channel::{EventChannel, LastEventId};
+#[cfg(feature = "zip")]
+#[cfg_attr(nightly, doc(cfg(feature = "zip")))]
+pub use self::zip::{ZipStream, ZipEntry};
 
 crate::export! {
     /// Retrofitted support for [`Stream`]s with `yield`, `for await` syntax.
@@ -0,0 +1,515 @@
+use std::io;
+use std::path::Path;
+use std::pin::Pin;
+
+use bytes::Bytes;
+use futures::stream::{Stream, StreamExt};
+use time::OffsetDateTime;
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio_util::io::StreamReader;
+
+use crate::request::Request;
+use crate::response::{self, Response, Responder};
+use crate::http::{ContentType, ContentDisposition};
+
+/// A file in a [`ZipStream`]: a path in the archive and a reader of contents.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::response::stream::ZipEntry;
+///
+/// let entry = ZipEntry::new("docs/hello.txt", &b"Hello, world!"[..])?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub struct ZipEntry {
+    name: String,
+    modified: Option<OffsetDateTime>,
+    reader: Pin<Box<dyn AsyncRead + Send>>,
+}
+
+impl ZipEntry {
+    /// Creates an entry at path `name` in the archive whose contents are read
+    /// from `reader`.
+    ///
+    /// Backslashes in `name` are converted into forward slashes, and leading
+    /// slashes as well as `.` and `..` segments are removed so that the entry
+    /// cannot be extracted outside of the target directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of kind [`InvalidInput`](io::ErrorKind::InvalidInput)
+    /// if the resulting name is longer than 65,535 bytes, the longest a ZIP
+    /// archive can record.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::response::stream::ZipEntry;
+    ///
+    /// let entry = ZipEntry::new("report.csv", &b"a,b,c"[..])?;
+    ///
+    /// let name = "a".repeat(u16::MAX as usize + 1);
+    /// assert!(ZipEntry::new(name, &b"a,b,c"[..]).is_err());
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn new<N, R>(name: N, reader: R) -> io::Result<Self>
+        where N: AsRef<str>, R: AsyncRead + Send + 'static
+    {
+        let name = name.as_ref()
+            .split(['/', '\\'])
+            .filter(|s| !s.is_empty() && *s != "." && *s != "..")
+            .collect::<Vec<_>>()
+            .join("/");
+
+        if name.len() > u16::MAX as usize {
+            let msg = "ZIP entry name exceeds 65,535 bytes";
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, msg));
+        }
+
+        Ok(ZipEntry { name, modified: None, reader: Box::pin(reader) })
+    }
+
+    /// Opens the file at `path` and returns an entry named after its file name
+    /// with the file's modification time.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if opening the file or reading its metadata fails, or
+    /// if the file name is rejected by [`ZipEntry::new()`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::response::stream::ZipEntry;
+    ///
+    /// # async fn f() -> std::io::Result<()> {
+    /// let entry = ZipEntry::file("static/index.html").await?;
+    /// # Ok(()) }
+    /// ```
+    pub async fn file<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let path = path.as_ref();
+        let file = tokio::fs::File::open(path).await?;
+        let modified = file.metadata().await?.modified().ok();
+        let name = path.file_name().map(|n| n.to_string_lossy()).unwrap_or_default();
+        let entry = ZipEntry::new(name, file)?;
+        Ok(match modified {
+            Some(time) => entry.modified(time.into()),
+            None => entry,
+        })
+    }
+
+    /// Sets the modification time recorded for the entry. Defaults to the
+    /// earliest representable time, `1980-01-01 00:00:00`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::response::stream::ZipEntry;
+    /// use rocket::time::macros::datetime;
+    ///
+    /// let entry = ZipEntry::new("a.txt", &b"a"[..])?
+    ///     .modified(datetime!(2024-01-01 12:00 UTC));
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn modified(mut self, time: OffsetDateTime) -> Self {
+        self.modified = Some(time);
+        self
+    }
+
+    /// The entry's `(time, date)` in MS-DOS format.
+    fn dos_time(&self) -> (u16, u16) {
+        match self.modified {
+            Some(t) if t.year() >= 1980 && t.year() <= 2107 => {
+                let (h, m, s) = (t.hour() as u16, t.minute() as u16, t.second() as u16);
+                let (y, mo, d) = ((t.year() - 1980) as u16, t.month() as u16, t.day() as u16);
+                ((h << 11) | (m << 5) | (s / 2), (y << 9) | (mo << 5) | d)
+            }
+            _ => (0, (1 << 5) | 1),
+        }
+    }
+}
+
+/// A potentially infinite stream of files zipped on the fly.
+///
+/// A `ZipStream` writes a ZIP archive of the [`ZipEntry`]s yielded by a stream
+/// `S` directly into the response body. Entries are read and archived only as
+/// fast as the client receives them, and nothing is buffered to disk or held
+/// in memory beyond a single chunk of a single entry, making a `ZipStream`
+/// suitable for bulk downloads of arbitrarily large payloads. Entries are
+/// stored without compression, and archives larger than 4GiB or with more
+/// than 65,535 entries use the ZIP64 extensions. Individual entries, however,
+/// must be smaller than 4GiB: since an entry's size isn't known until it has
+/// been streamed, its local header can't announce ZIP64 sizes.
+///
+/// # Responder
+///
+/// `ZipStream` is a (potentially infinite) responder. The response
+/// `Content-Type` is set to [`ZIP`](ContentType::ZIP) and, if a
+/// [`filename`](ZipStream::filename()) is set, a `Content-Disposition` of
+/// `attachment` with that file name is added. The body is
+/// [unsized](crate::response::Body#unsized), and archive bytes are sent as
+/// soon as they are produced.
+///
+/// If reading an entry fails or an entry reaches 4GiB, the response is aborted
+/// and the client receives a truncated, invalid archive.
+///
+/// # Example
+///
+/// ```rust
+/// # use rocket::*;
+/// use rocket::response::stream::{ZipStream, ZipEntry};
+/// use rocket::futures::stream;
+///
+/// #[get("/download")]
+/// fn download() -> ZipStream<impl stream::Stream<Item = ZipEntry>> {
+///     let entries = (1..=3).map(|i| {
+///         let contents = format!("This is file {}.", i);
+///         ZipEntry::new(format!("files/{}.txt", i), std::io::Cursor::new(contents))
+///     });
+///
+///     // Our names are short, so `ZipEntry::new()` can't fail.
+///     let entries = entries.filter_map(Result::ok);
+///
+///     ZipStream::from(stream::iter(entries)).filename("files.zip")
+/// }
+/// ```
+pub struct ZipStream<S> {
+    stream: S,
+    filename: Option<String>,
+}
+
+impl<S: Stream<Item = ZipEntry>> ZipStream<S> {
+    /// Sets the file name suggested to the client via `Content-Disposition`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::response::stream::{ZipStream, ZipEntry};
+    /// use rocket::futures::stream;
+    ///
+    /// let zip = ZipStream::from(stream::empty::<ZipEntry>()).filename("empty.zip");
+    /// ```
+    pub fn filename<N: Into<String>>(mut self, filename: N) -> Self {
+        self.filename = Some(filename.into());
+        self
+    }
+
+    fn into_bytes(self) -> impl Stream<Item = io::Result<Bytes>> {
+        let entries = self.stream;
+        async_stream::try_stream! {
+            let mut entries = std::pin::pin!(entries);
+            let mut offset = 0u64;
+            let mut directory = Vec::new();
+            let mut buf = vec![0u8; 64 * 1024];
+
+            while let Some(mut entry) = entries.next().await {
+                let header = LocalHeader::new(&entry, offset);
+                let bytes = header.local_header();
+                offset += bytes.len() as u64;
+                yield bytes;
+
+                let (mut crc, mut size) = (Crc32::new(), 0u64);
+                loop {
+                    let n = entry.reader.read(&mut buf).await?;
+                    if n == 0 {
+                        break;
+                    }
+
+                    crc.update(&buf[..n]);
+                    size += n as u64;
+                    if size >= ZIP64_MARKER as u64 {
+                        let msg = format!("ZIP entry {:?} exceeds 4GiB", header.name);
+                        Err(io::Error::new(io::ErrorKind::InvalidData, msg))?;
+                    }
+
+                    yield Bytes::copy_from_slice(&buf[..n]);
+                }
+
+                let header = header.finish(crc.finish(), size);
+                let bytes = header.descriptor();
+                offset += size + bytes.len() as u64;
+                yield bytes;
+                directory.push(header);
+            }
+
+            yield central_directory(&directory, offset);
+        }
+    }
+}
+
+impl<S> From<S> for ZipStream<S> {
+    /// Creates a `ZipStream` from a [`Stream`] of [`ZipEntry`]s.
+    fn from(stream: S) -> Self {
+        ZipStream { stream, filename: None }
+    }
+}
+
+impl<'r, S: Stream<Item = ZipEntry> + Send + 'r> Responder<'r, 'r> for ZipStream<S> {
+    fn respond_to(mut self, _: &'r Request<'_>) -> response::Result<'r> {
+        let mut response = Response::build();
+        response.header(ContentType::ZIP);
+        if let Some(filename) = self.filename.take() {
+            response.header(ContentDisposition::attachment().with_filename(filename));
+        }
+
+        response.streamed_body(StreamReader::new(self.into_bytes())).ok()
+    }
+}
+
+/// Flags: sizes and CRC follow in a data descriptor (bit 3); UTF-8 names (bit 11).
+const FLAGS: u16 = (1 << 3) | (1 << 11);
+
+/// `0xFFFF_FFFF`: the value of a 32-bit field whose value is in a ZIP64 field,
+/// and so the size at which an entry is refused.
+const ZIP64_MARKER: u32 = u32::MAX;
+
+/// The per-entry information needed for the data descriptor and directory.
+struct LocalHeader {
+    name: String,
+    time: u16,
+    date: u16,
+    offset: u64,
+    crc: u32,
+    size: u64,
+}
+
+impl LocalHeader {
+    fn new(entry: &ZipEntry, offset: u64) -> Self {
+        let (time, date) = entry.dos_time();
+        LocalHeader { name: entry.name.clone(), time, date, offset, crc: 0, size: 0 }
+    }
+
+    fn finish(self, crc: u32, size: u64) -> Self {
+        LocalHeader { crc, size, ..self }
+    }
+
+    fn version(&self) -> u16 {
+        if self.offset >= ZIP64_MARKER as u64 { 45 } else { 20 }
+    }
+
+    fn local_header(&self) -> Bytes {
+        let mut out = Vec::with_capacity(30 + self.name.len());
+        put32(&mut out, 0x04034b50);
+        put16(&mut out, 20);
+        put16(&mut out, FLAGS);
+        put16(&mut out, 0);
+        put16(&mut out, self.time);
+        put16(&mut out, self.date);
+        put32(&mut out, 0);
+        put32(&mut out, 0);
+        put32(&mut out, 0);
+        put16(&mut out, self.name.len() as u16);
+        put16(&mut out, 0);
+        out.extend_from_slice(self.name.as_bytes());
+        out.into()
+    }
+
+    fn descriptor(&self) -> Bytes {
+        let mut out = Vec::with_capacity(16);
+        put32(&mut out, 0x08074b50);
+        put32(&mut out, self.crc);
+        put32(&mut out, self.size as u32);
+        put32(&mut out, self.size as u32);
+        out.into()
+    }
+
+    fn directory_header(&self, out: &mut Vec<u8>) {
+        let mut extra = Vec::new();
+        let offset = if self.offset >= ZIP64_MARKER as u64 {
+            put64(&mut extra, self.offset);
+            ZIP64_MARKER
+        } else {
+            self.offset as u32
+        };
+
+        put32(out, 0x02014b50);
+        put16(out, self.version());
+        put16(out, self.version());
+        put16(out, FLAGS);
+        put16(out, 0);
+        put16(out, self.time);
+        put16(out, self.date);
+        put32(out, self.crc);
+        put32(out, self.size as u32);
+        put32(out, self.size as u32);
+        put16(out, self.name.len() as u16);
+        put16(out, if extra.is_empty() { 0 } else { extra.len() as u16 + 4 });
+        put16(out, 0);
+        put16(out, 0);
+        put16(out, 0);
+        put32(out, 0);
+        put32(out, offset);
+        out.extend_from_slice(self.name.as_bytes());
+        if !extra.is_empty() {
+            put16(out, 0x0001);
+            put16(out, extra.len() as u16);
+            out.extend_from_slice(&extra);
+        }
+    }
+}
+
+/// The central directory and end of central directory record(s).
+fn central_directory(headers: &[LocalHeader], offset: u64) -> Bytes {
+    let mut out = Vec::new();
+    headers.iter().for_each(|h| h.directory_header(&mut out));
+
+    let (count, size) = (headers.len() as u64, out.len() as u64);
+    let end = offset + size;
+    let zip64 = count >= u16::MAX as u64 || size.max(offset) >= ZIP64_MARKER as u64;
+    if zip64 {
+        put32(&mut out, 0x06064b50);
+        put64(&mut out, 44);
+        put16(&mut out, 45);
+        put16(&mut out, 45);
+        put32(&mut out, 0);
+        put32(&mut out, 0);
+        put64(&mut out, count);
+        put64(&mut out, count);
+        put64(&mut out, size);
+        put64(&mut out, offset);
+
+        put32(&mut out, 0x07064b50);
+        put32(&mut out, 0);
+        put64(&mut out, end);
+        put32(&mut out, 1);
+    }
+
+    put32(&mut out, 0x06054b50);
+    put16(&mut out, 0);
+    put16(&mut out, 0);
+    put16(&mut out, count.min(u16::MAX as u64) as u16);
+    put16(&mut out, count.min(u16::MAX as u64) as u16);
+    put32(&mut out, size.min(ZIP64_MARKER as u64) as u32);
+    put32(&mut out, offset.min(ZIP64_MARKER as u64) as u32);
+    put16(&mut out, 0);
+    out.into()
+}
+
+fn put16(out: &mut Vec<u8>, value: u16) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+fn put32(out: &mut Vec<u8>, value: u32) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+fn put64(out: &mut Vec<u8>, value: u64) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+/// CRC-32 (IEEE 802.3), as required by ZIP.
+struct Crc32(u32);
+
+impl Crc32 {
+    const TABLE: [u32; 256] = {
+        let mut table = [0u32; 256];
+        let mut i = 0;
+        while i < 256 {
+            let mut crc = i as u32;
+            let mut k = 0;
+            while k < 8 {
+                crc = if crc & 1 == 1 { 0xEDB88320 ^ (crc >> 1) } else { crc >> 1 };
+                k += 1;
+            }
+
+            table[i] = crc;
+            i += 1;
+        }
+
+        table
+    };
+
+    fn new() -> Self {
+        Crc32(!0)
+    }
+
+    fn update(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = Self::TABLE[((self.0 ^ byte as u32) & 0xFF) as usize] ^ (self.0 >> 8);
+        }
+    }
+
+    fn finish(&self) -> u32 {
+        !self.0
+    }
+}
+
+#[cfg(test)]
+mod zip_tests {
+    use futures::stream;
+    use tokio::io::AsyncReadExt;
+
+    use super::*;
+
+    fn zip(entries: Vec<ZipEntry>) -> Vec<u8> {
+        crate::async_test(async move {
+            let zip = ZipStream::from(stream::iter(entries));
+            let mut reader = std::pin::pin!(StreamReader::new(zip.into_bytes()));
+            let mut bytes = vec![];
+            reader.read_to_end(&mut bytes).await.expect("zip");
+            bytes
+        })
+    }
+
+    fn u16_at(bytes: &[u8], at: usize) -> u16 {
+        u16::from_le_bytes(bytes[at..at + 2].try_into().unwrap())
+    }
+
+    fn u32_at(bytes: &[u8], at: usize) -> u32 {
+        u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap())
+    }
+
+    #[test]
+    fn crc32() {
+        let mut crc = Crc32::new();
+        crc.update(b"123456789");
+        assert_eq!(crc.finish(), 0xCBF43926);
+    }
+
+    #[test]
+    fn archive_layout() {
+        let bytes = zip(vec![
+            ZipEntry::new("/../hello.txt", &b"hello"[..]).unwrap(),
+            ZipEntry::new("dir\\world.txt", &b"world!"[..]).unwrap(),
+        ]);
+
+        // Local header, name, data, descriptor for each entry.
+        assert_eq!(u32_at(&bytes, 0), 0x04034b50);
+        assert_eq!(&bytes[30..39], b"hello.txt");
+        assert_eq!(&bytes[39..44], b"hello");
+        assert_eq!(u32_at(&bytes, 44), 0x08074b50);
+        assert_eq!(u32_at(&bytes, 48), 0x3610a686);
+        assert_eq!(u32_at(&bytes, 52), 5);
+
+        let second = 44 + 16;
+        assert_eq!(u32_at(&bytes, second), 0x04034b50);
+        assert_eq!(&bytes[second + 30..second + 43], b"dir/world.txt");
+
+        // End of central directory: two entries, directory where it says.
+        let eocd = bytes.len() - 22;
+        assert_eq!(u32_at(&bytes, eocd), 0x06054b50);
+        assert_eq!(u16_at(&bytes, eocd + 10), 2);
+        let directory = u32_at(&bytes, eocd + 16) as usize;
+        assert_eq!(u32_at(&bytes, directory), 0x02014b50);
+        assert_eq!(u32_at(&bytes, directory + 16), 0x3610a686);
+        assert_eq!(u32_at(&bytes, directory + 42), 0);
+        assert_eq!(directory + u32_at(&bytes, eocd + 12) as usize, eocd);
+    }
+
+    #[test]
+    fn entry_names() {
+        let name = |n: &str| ZipEntry::new(n, &b""[..]).map(|e| e.name);
+        assert_eq!(name("/a/../hello.txt").unwrap(), "a/hello.txt");
+        assert_eq!(name("./a\\b//c.txt").unwrap(), "a/b/c.txt");
+        assert_eq!(name(&"a".repeat(u16::MAX as usize)).unwrap().len(), u16::MAX as usize);
+        assert!(name(&"a".repeat(u16::MAX as usize + 1)).is_err());
+    }
+
+    #[test]
+    fn empty_archive() {
+        let bytes = zip(vec![]);
+        assert_eq!(bytes.len(), 22);
+        assert_eq!(u32_at(&bytes, 0), 0x06054b50);
+    }
+}
@@ -0,0 +1,253 @@
+use std::io;
+use std::pin::Pin;
+use std::future::Future;
+use std::task::{Context, Poll, ready};
+use std::time::Duration;
+
+use indexmap::IndexMap;
+use state::InitCell;
+use tokio::io::{AsyncRead, ReadBuf};
+use tokio::time::{Instant, Sleep};
+
+use crate::{Rocket, Request, Response, Build};
+use crate::data::ByteUnit;
+use crate::fairing::{self, Fairing, Info, Kind};
+use crate::response::{self, Responder, Body};
+use crate::trace::Trace;
+
+/// Limits the rate at which the body of the wrapped responder is written.
+///
+/// The body is written at no more than the configured rate, in bytes per
+/// second, using a token bucket that allows bursts of up to a tenth of a
+/// second's worth of data. Throttling is useful to share bandwidth fairly on
+/// constrained servers and to test how clients behave on slow connections.
+///
+/// Throttled bodies are always streamed: a `Content-Length` the wrapped
+/// responder would have sent is not sent. A rate of `0` disables throttling.
+/// `Throttled` takes precedence over any rate configured via [`Throttle`].
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::data::ToByteUnit;
+/// use rocket::fs::NamedFile;
+/// use rocket::response::Throttled;
+///
+/// #[get("/download")]
+/// async fn download() -> Option<Throttled<NamedFile>> {
+///     let file = NamedFile::open("big.iso").await.ok()?;
+///     Some(Throttled::new(file, 256.kibibytes()))
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Throttled<R> {
+    responder: R,
+    rate: ByteUnit,
+}
+
+/// A [`Fairing`] that throttles response bodies under configured paths.
+///
+/// `Throttle` reads the `throttle` configuration parameter, a table mapping
+/// path prefixes to rates in bytes per second, and limits the rate at which the
+/// body of each response to a request under a configured prefix is written as
+/// if it were wrapped in [`Throttled`]. Prefixes are matched segment-wise, so a
+/// prefix of `/files` applies to `/files/a` but not to `/filesystem`, and the
+/// longest matching prefix applies. Responses from [`Throttled`] responders
+/// are left as is.
+///
+/// If the `throttle` configuration parameter is invalid, ignition fails.
+///
+/// # Example
+///
+/// ```toml
+/// [default.throttle]
+/// "/downloads" = "1MiB"
+/// "/downloads/free" = "128KiB"
+/// ```
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::response::Throttle;
+///
+/// #[launch]
+/// fn rocket() -> _ {
+///     rocket::build().attach(Throttle::new())
+/// }
+/// ```
+#[derive(Default)]
+pub struct Throttle {
+    scopes: InitCell<IndexMap<String, ByteUnit>>,
+}
+
+/// Marks a request whose response was throttled by `Throttled`.
+struct Throttling(bool);
+
+impl<R> Throttled<R> {
+    /// Wraps `responder`, limiting its body to `rate` bytes per second.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::data::ToByteUnit;
+    /// use rocket::response::Throttled;
+    ///
+    /// let response = Throttled::new("Hello, slowly!", 4.bytes());
+    /// ```
+    pub fn new(responder: R, rate: ByteUnit) -> Self {
+        Throttled { responder, rate }
+    }
+}
+
+/// Throttles the body of the wrapped responder's response, if any. Errors and
+/// forwards from the wrapped responder are propagated.
+impl<'r, 'o: 'r, R: Responder<'r, 'o>> Responder<'r, 'o> for Throttled<R> {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'o> {
+        let mut response = self.responder.respond_to(req)?;
+        req.local_cache(|| Throttling(true));
+        throttle(&mut response, self.rate);
+        Ok(response)
+    }
+}
+
+impl Throttle {
+    /// Returns a `Throttle` fairing that applies the configured rates.
+    pub fn new() -> Self {
+        Throttle::default()
+    }
+
+    /// Returns the rate of the longest configured prefix of `path`.
+    fn rate(&self, path: &str) -> Option<ByteUnit> {
+        let matches = |prefix: &str| {
+            let prefix = prefix.trim_end_matches('/');
+            path.strip_prefix(prefix).map_or(false, |r| r.is_empty() || r.starts_with('/'))
+        };
+
+        self.scopes.try_get()?.iter()
+            .filter(|(prefix, _)| matches(prefix))
+            .max_by_key(|(prefix, _)| prefix.trim_end_matches('/').len())
+            .map(|(_, rate)| *rate)
+    }
+}
+
+#[crate::async_trait]
+impl Fairing for Throttle {
+    fn info(&self) -> Info {
+        Info {
+            name: "Throttle",
+            kind: Kind::Ignite | Kind::Response | Kind::Singleton,
+        }
+    }
+
+    async fn on_ignite(&self, rocket: Rocket<Build>) -> fairing::Result {
+        let scopes = match rocket.figment().contains("throttle") {
+            true => match rocket.figment().extract_inner("throttle") {
+                Ok(scopes) => scopes,
+                Err(e) => {
+                    e.trace_error();
+                    return Err(rocket);
+                }
+            },
+            false => IndexMap::new(),
+        };
+
+        self.scopes.set(scopes);
+        Ok(rocket)
+    }
+
+    async fn on_response<'r>(&self, req: &'r Request<'_>, res: &mut Response<'r>) {
+        if req.local_cache(|| Throttling(false)).0 {
+            return;
+        }
+
+        if let Some(rate) = self.rate(req.uri().path().as_str()) {
+            throttle(res, rate);
+        }
+    }
+}
+
+/// Replaces the body of `response`, if any, with one throttled to `rate`.
+fn throttle(response: &mut Response<'_>, rate: ByteUnit) {
+    if rate.as_u64() == 0 || response.body().is_none() {
+        return;
+    }
+
+    let max_chunk = response.body().max_chunk_size();
+    let body = response.body_mut().take();
+    response.set_streamed_body(ThrottledBody::new(body, rate.as_u64()));
+    response.set_max_chunk_size(max_chunk);
+}
+
+/// A token bucket limiting reads from `inner` to `rate` bytes per second.
+struct ThrottledBody<'r> {
+    inner: Body<'r>,
+    rate: u64,
+    capacity: u64,
+    tokens: u64,
+    refilled: Instant,
+    sleep: Option<Pin<Box<Sleep>>>,
+    scratch: Vec<u8>,
+}
+
+impl<'r> ThrottledBody<'r> {
+    fn new(inner: Body<'r>, rate: u64) -> Self {
+        let capacity = (rate / 10).max(1);
+        ThrottledBody {
+            inner,
+            rate,
+            capacity,
+            tokens: capacity,
+            refilled: Instant::now(),
+            sleep: None,
+            scratch: vec![],
+        }
+    }
+
+    /// Adds the tokens accrued since the last refill.
+    fn refill(&mut self) {
+        let elapsed = self.refilled.elapsed();
+        let accrued = (elapsed.as_secs_f64() * self.rate as f64) as u64;
+        if accrued > 0 {
+            self.tokens = self.tokens.saturating_add(accrued).min(self.capacity);
+            self.refilled += Duration::from_secs_f64(accrued as f64 / self.rate as f64);
+        }
+    }
+}
+
+impl AsyncRead for ThrottledBody<'_> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        if buf.remaining() == 0 {
+            return Poll::Ready(Ok(()));
+        }
+
+        loop {
+            if let Some(sleep) = this.sleep.as_mut() {
+                ready!(sleep.as_mut().poll(cx));
+                this.sleep = None;
+            }
+
+            this.refill();
+            let wanted = this.capacity.min(buf.remaining() as u64);
+            if this.tokens == 0 {
+                let wait = Duration::from_secs_f64(wanted as f64 / this.rate as f64);
+                this.sleep = Some(Box::pin(tokio::time::sleep(wait)));
+                continue;
+            }
+
+            let n = this.tokens.min(wanted) as usize;
+            this.scratch.resize(n, 0);
+            let mut limited = ReadBuf::new(&mut this.scratch[..n]);
+            ready!(Pin::new(&mut this.inner).poll_read(cx, &mut limited))?;
+
+            let read = limited.filled();
+            buf.put_slice(read);
+            this.tokens -= read.len() as u64;
+            return Poll::Ready(Ok(()));
+        }
+    }
+}
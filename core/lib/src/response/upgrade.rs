@@ -0,0 +1,129 @@
+use std::io;
+use std::borrow::Cow;
+
+use futures::future::BoxFuture;
+
+use crate::{Request, Response};
+use crate::data::{IoHandler, IoStream};
+use crate::http::{Method, Status};
+use crate::http::uncased::eq;
+use crate::response::{self, Responder};
+use crate::shutdown::Shutdown;
+
+/// A responder that upgrades the connection to a custom protocol.
+///
+/// If the request asks to upgrade to `protocol` via its `Connection` and
+/// `Upgrade` headers, Rocket responds with `101 Switching Protocols` and then
+/// calls the handler with the raw, upgraded [`IoStream`] and a [`Shutdown`]
+/// handle. This is lower-level than WebSockets: the handler speaks the
+/// protocol itself, for instance by exchanging length-delimited binary frames
+/// via [`Framed`](crate::data::Framed).
+///
+/// The handler runs until it returns, independently of the request. When
+/// Rocket shuts down, the `Shutdown` handle resolves at the start of the grace
+/// period, giving the handler an opportunity to end the protocol cleanly. At
+/// the end of the mercy period, the connection is closed regardless.
+///
+/// If the request doesn't ask to upgrade to `protocol`, the response is `426
+/// Upgrade Required` with an `Upgrade` header naming `protocol`.
+///
+/// # Tunneling
+///
+/// A `CONNECT` request asks to tunnel to the authority in
+/// [`Request::connect_target()`] rather than to switch protocols. In response
+/// to one, `Upgrade` responds with `200 OK`, regardless of `protocol`, and then
+/// calls the handler with the tunneled stream. See [`crate::tunnel`] for a
+/// forward proxy built this way.
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::data::Framed;
+/// use rocket::response::Upgrade;
+/// use rocket::tokio::select;
+///
+/// #[get("/echo")]
+/// fn echo() -> Upgrade<'static> {
+///     Upgrade::new("x-echo", |io, shutdown| Box::pin(async move {
+///         let mut framed = Framed::new(io);
+///         loop {
+///             select! {
+///                 frame = framed.recv() => match frame? {
+///                     Some(frame) => framed.send(&frame).await?,
+///                     None => return Ok(()),
+///                 },
+///                 _ = shutdown.clone() => return framed.send(b"bye").await,
+///             }
+///         }
+///     }))
+/// }
+/// ```
+pub struct Upgrade<'r> {
+    protocol: Cow<'static, str>,
+    handler: Box<dyn FnOnce(IoStream, Shutdown) -> BoxFuture<'r, io::Result<()>> + Send + 'r>,
+}
+
+/// The I/O handler of an accepted [`Upgrade`].
+struct Upgraded<'r> {
+    upgrade: Upgrade<'r>,
+    shutdown: Shutdown,
+}
+
+impl<'r> Upgrade<'r> {
+    /// Upgrades to `protocol`, handling the upgraded connection with
+    /// `handler`.
+    ///
+    /// The `handler` must return a `Box`ed and `Pin`ned future. The connection
+    /// is closed when the future resolves.
+    pub fn new<P, F>(protocol: P, handler: F) -> Self
+        where P: Into<Cow<'static, str>>,
+              F: FnOnce(IoStream, Shutdown) -> BoxFuture<'r, io::Result<()>> + Send + 'r
+    {
+        Upgrade { protocol: protocol.into(), handler: Box::new(handler) }
+    }
+
+    /// Returns the protocol to upgrade to.
+    pub fn protocol(&self) -> &str {
+        &self.protocol
+    }
+}
+
+impl<'r, 'o: 'r> Responder<'r, 'o> for Upgrade<'o> {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'o> {
+        let protocol = self.protocol.clone();
+        let shutdown = req.rocket().shutdown();
+        if req.method() == Method::Connect {
+            return Response::build()
+                .status(Status::Ok)
+                .upgrade(protocol, Upgraded { upgrade: self, shutdown })
+                .ok();
+        }
+
+        let headers = req.headers();
+        let is_upgrade = headers.get("Connection")
+            .any(|h| h.split(',').any(|v| eq(v.trim(), "upgrade")));
+
+        let wants_protocol = headers.get("Upgrade")
+            .any(|h| h.split(',').any(|v| eq(v.trim(), &self.protocol)));
+
+        if !is_upgrade || !wants_protocol {
+            return Response::build()
+                .status(Status::UpgradeRequired)
+                .raw_header("Connection", "Upgrade")
+                .raw_header("Upgrade", self.protocol)
+                .ok();
+        }
+
+        Response::build()
+            .upgrade(protocol, Upgraded { upgrade: self, shutdown })
+            .ok()
+    }
+}
+
+#[crate::async_trait]
+impl IoHandler for Upgraded<'_> {
+    async fn io(self: Box<Self>, io: IoStream) -> io::Result<()> {
+        (self.upgrade.handler)(io, self.shutdown).await
+    }
+}
@@ -0,0 +1,63 @@
+use std::time::Duration;
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+/// The retention configuration: the `retention` configuration parameter.
+///
+/// # Example
+///
+/// ```rust
+/// use std::time::Duration;
+///
+/// use rocket::retention::RetentionConfig;
+/// use rocket::figment::{Figment, providers::{Format, Toml}};
+///
+/// let figment = Figment::from(Toml::string(r#"
+///     [retention]
+///     interval_secs = 60
+///     ttl_secs = { temp = 3600, "cache" = 600 }
+/// "#));
+///
+/// let config: RetentionConfig = figment.extract_inner("retention").unwrap();
+/// assert_eq!(config.interval(), Duration::from_secs(60));
+/// assert_eq!(config.ttl("temp"), Some(Duration::from_secs(3600)));
+/// assert_eq!(config.ttl("uploads"), None);
+///
+/// let config = RetentionConfig::default();
+/// assert_eq!(config.interval(), Duration::from_secs(300));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetentionConfig {
+    /// The interval, in seconds, between sweeps.
+    ///
+    /// **default: `300`**
+    pub interval_secs: u64,
+    /// The TTLs, in seconds, of targets by name. A configured TTL overrides
+    /// the one a target was added with.
+    ///
+    /// **default: `{}`**
+    pub ttl_secs: IndexMap<String, u64>,
+}
+
+impl Default for RetentionConfig {
+    fn default() -> Self {
+        RetentionConfig {
+            interval_secs: 300,
+            ttl_secs: IndexMap::new(),
+        }
+    }
+}
+
+impl RetentionConfig {
+    /// Returns the interval between sweeps.
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(self.interval_secs)
+    }
+
+    /// Returns the configured TTL of the target named `name`, if any.
+    pub fn ttl(&self, name: &str) -> Option<Duration> {
+        self.ttl_secs.get(name).map(|secs| Duration::from_secs(*secs))
+    }
+}
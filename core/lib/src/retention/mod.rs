@@ -0,0 +1,68 @@
+//! Retention of temporary artifacts.
+//!
+//! Long-running servers accumulate artifacts: temporary files of uploads that
+//! were never persisted or cleaned up, captures written to a directory,
+//! responses stored in a [`Cache`], spooled messages. The [`Retention`]
+//! fairing keeps these from filling disks and memory by periodically pruning
+//! every registered target of the items older than its TTL.
+//!
+//! A target is any [`Reclaim`] implementation. The following are built in:
+//!
+//!   * [`TempFiles`]: abandoned `TempFile`s in the configured `temp_dir`.
+//!   * [`Directory`]: files in a directory, such as one holding captures.
+//!   * [`CachedResponses`]: responses stored by the [`Cache`] fairing. Requires
+//!     the `cache` feature.
+//!
+//! Sweeps start when Rocket is launched, with one right away, and repeat at
+//! the configured interval until shutdown. Sweeps aren't started by local
+//! clients; [`Retention::sweep()`] sweeps on demand. Every sweep that removes
+//! items is logged at the `INFO` level with the space reclaimed. Totals per
+//! target are available via [`Retention::reclaimed()`] and
+//! [`Retention::sweeps()`].
+//!
+//! [`Cache`]: crate::cache::Cache
+//!
+//! # Configuration
+//!
+//! The interval and per-target TTLs are configured via the `retention`
+//! configuration parameter, which is deserialized as a [`RetentionConfig`].
+//! Configured TTLs override those targets are added with. If the parameter is
+//! invalid, ignition fails.
+//!
+//! ```toml
+//! [default.retention]
+//! interval_secs = 600
+//!
+//! [default.retention.ttl_secs]
+//! temp = 86400
+//! captures = 3600
+//! ```
+//!
+//! # Example
+//!
+//! ```rust
+//! # #[macro_use] extern crate rocket;
+//! use std::time::Duration;
+//!
+//! use rocket::retention::{Retention, TempFiles, Directory};
+//!
+//! #[launch]
+//! fn rocket() -> _ {
+//!     let hour = Duration::from_secs(60 * 60);
+//!     let retention = Retention::new()
+//!         .target("temp", 24 * hour, TempFiles)
+//!         .target("captures", hour, Directory::new("captures").prefix("capture-"));
+//!
+//!     rocket::build().attach(retention)
+//! }
+//! ```
+
+mod config;
+mod target;
+mod sweeper;
+
+pub use config::RetentionConfig;
+pub use target::{Reclaim, Reclaimed, Directory, TempFiles};
+#[cfg(feature = "cache")]
+pub use target::CachedResponses;
+pub use sweeper::Retention;
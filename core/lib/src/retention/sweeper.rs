@@ -0,0 +1,164 @@
+use std::sync::Arc;
+use std::time::Duration;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use state::InitCell;
+
+use crate::{Rocket, Build, Orbit};
+use crate::data::ToByteUnit;
+use crate::fairing::{self, Fairing, Info, Kind};
+use crate::trace::Trace;
+use crate::util::FutureExt;
+use crate::retention::{RetentionConfig, Reclaim, Reclaimed};
+
+/// A [`Fairing`] that periodically prunes expired artifacts. See the [module
+/// documentation](crate::retention) for details.
+///
+/// # Example
+///
+/// ```rust
+/// use std::time::Duration;
+///
+/// use rocket::retention::{Retention, TempFiles};
+///
+/// let retention = Retention::new().target("temp", Duration::from_secs(3600), TempFiles);
+/// assert_eq!(retention.reclaimed("temp").unwrap().bytes, 0);
+/// assert!(retention.reclaimed("cache").is_none());
+/// ```
+pub struct Retention {
+    targets: Vec<Target>,
+    config: InitCell<RetentionConfig>,
+}
+
+/// A named target and the totals it has reclaimed.
+struct Target {
+    name: &'static str,
+    ttl: Duration,
+    target: Box<dyn Reclaim>,
+    items: AtomicU64,
+    bytes: AtomicU64,
+    sweeps: AtomicU64,
+}
+
+impl Retention {
+    /// Returns a `Retention` fairing without any targets.
+    pub fn new() -> Self {
+        Retention { targets: vec![], config: InitCell::new() }
+    }
+
+    /// Adds `target`, named `name`, whose items expire after `ttl` unless a
+    /// different TTL for `name` is configured. Replaces any existing target
+    /// named `name`.
+    pub fn target<T: Reclaim>(mut self, name: &'static str, ttl: Duration, target: T) -> Self {
+        self.targets.retain(|t| t.name != name);
+        self.targets.push(Target {
+            name, ttl,
+            target: Box::new(target),
+            items: AtomicU64::new(0),
+            bytes: AtomicU64::new(0),
+            sweeps: AtomicU64::new(0),
+        });
+
+        self
+    }
+
+    /// Returns the total reclaimed by the target named `name` across all
+    /// sweeps, or `None` if there is no such target.
+    pub fn reclaimed(&self, name: &str) -> Option<Reclaimed> {
+        let target = self.targets.iter().find(|t| t.name == name)?;
+        let items = target.items.load(Ordering::Relaxed);
+        let bytes = target.bytes.load(Ordering::Relaxed);
+        Some(Reclaimed { items, bytes })
+    }
+
+    /// Returns the number of completed sweeps of the target named `name`, or
+    /// `None` if there is no such target.
+    pub fn sweeps(&self, name: &str) -> Option<u64> {
+        let target = self.targets.iter().find(|t| t.name == name)?;
+        Some(target.sweeps.load(Ordering::Relaxed))
+    }
+
+    /// Sweeps every target once, returning the total reclaimed by this sweep.
+    /// A target that fails is logged and skipped.
+    ///
+    /// Sweeps run periodically once Rocket is launched; this method sweeps
+    /// on demand, for instance from an administrative route or in tests.
+    pub async fn sweep(&self, rocket: &Rocket<Orbit>) -> Reclaimed {
+        let mut total = Reclaimed::default();
+        for target in &self.targets {
+            let ttl = self.config.try_get()
+                .and_then(|config| config.ttl(target.name))
+                .unwrap_or(target.ttl);
+
+            match target.target.reclaim(rocket, ttl).await {
+                Ok(reclaimed) => {
+                    target.items.fetch_add(reclaimed.items, Ordering::Relaxed);
+                    target.bytes.fetch_add(reclaimed.bytes, Ordering::Relaxed);
+                    target.sweeps.fetch_add(1, Ordering::Relaxed);
+                    if reclaimed.items > 0 {
+                        info!(name: "retention", target = target.name, items = reclaimed.items,
+                            bytes = %reclaimed.bytes.bytes(), "reclaimed expired items");
+                    }
+
+                    total += reclaimed;
+                }
+                Err(e) => {
+                    warn!(name: "retention", target = target.name, "sweep failed: {e}");
+                }
+            }
+        }
+
+        total
+    }
+
+    /// Spawns the periodic sweep, which holds on to `rocket` until shutdown.
+    pub(crate) fn spawn(&self, rocket: Arc<Rocket<Orbit>>) {
+        let interval = self.config.try_get().map_or(Duration::from_secs(300), |c| c.interval());
+        tokio::spawn(async move {
+            let shutdown = rocket.shutdown();
+            let sweeps = async {
+                let Some(this) = rocket.fairing::<Retention>() else { return };
+                loop {
+                    this.sweep(&rocket).await;
+                    tokio::time::sleep(interval).await;
+                }
+            };
+
+            sweeps.race(shutdown).await;
+        });
+    }
+}
+
+impl Default for Retention {
+    fn default() -> Self {
+        Retention::new()
+    }
+}
+
+#[crate::async_trait]
+impl Fairing for Retention {
+    fn info(&self) -> Info {
+        Info { name: "Retention", kind: Kind::Ignite | Kind::Singleton }
+    }
+
+    async fn on_ignite(&self, rocket: Rocket<Build>) -> fairing::Result {
+        let config = match rocket.figment().contains("retention") {
+            true => match rocket.figment().extract_inner::<RetentionConfig>("retention") {
+                Ok(config) => config,
+                Err(e) => {
+                    e.trace_error();
+                    return Err(rocket);
+                }
+            },
+            false => RetentionConfig::default(),
+        };
+
+        if config.interval_secs == 0 {
+            error!(name: "retention", "`retention.interval_secs` must be greater than 0");
+            return Err(rocket);
+        }
+
+        self.config.set(config);
+        Ok(rocket)
+    }
+}
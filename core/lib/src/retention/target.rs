@@ -0,0 +1,192 @@
+use std::{fs, io};
+use std::ops::{Add, AddAssign};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use crate::{Rocket, Orbit};
+#[cfg(feature = "cache")]
+use crate::cache::Cache;
+
+/// What a sweep of a [`Reclaim`] target removed.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct Reclaimed {
+    /// The number of files, entries, or other items removed.
+    pub items: u64,
+    /// The number of bytes the removed items occupied.
+    pub bytes: u64,
+}
+
+/// A store of artifacts that expire, pruned by [`Retention`].
+///
+/// [`Retention`]: crate::retention::Retention
+///
+/// # Example
+///
+/// ```rust
+/// use std::io;
+/// use std::time::Duration;
+///
+/// use rocket::{Rocket, Orbit};
+/// use rocket::retention::{Reclaim, Reclaimed};
+///
+/// # struct Spool;
+/// # impl Spool { async fn expire(&self, _: Duration) -> io::Result<(u64, u64)> { Ok((0, 0)) } }
+/// #[rocket::async_trait]
+/// impl Reclaim for Spool {
+///     async fn reclaim(&self, _: &Rocket<Orbit>, ttl: Duration) -> io::Result<Reclaimed> {
+///         let (items, bytes) = self.expire(ttl).await?;
+///         Ok(Reclaimed { items, bytes })
+///     }
+/// }
+/// ```
+#[crate::async_trait]
+pub trait Reclaim: Send + Sync + 'static {
+    /// Removes items older than `ttl`, returning what was removed.
+    async fn reclaim(&self, rocket: &Rocket<Orbit>, ttl: Duration) -> io::Result<Reclaimed>;
+}
+
+/// Prunes files older than a TTL from a directory.
+///
+/// Files whose last modification is at least a TTL ago, optionally only
+/// those whose names begin with a [prefix](Directory::prefix()), are removed
+/// from the directory and, recursively, its subdirectories. Subdirectories
+/// left empty are removed as well. A missing directory is empty.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::retention::Directory;
+///
+/// let captures = Directory::new("/var/lib/app/captures").prefix("capture-");
+/// ```
+#[derive(Debug, Clone)]
+pub struct Directory {
+    path: PathBuf,
+    prefix: Option<String>,
+}
+
+/// Prunes abandoned temporary files from the configured
+/// [`temp_dir`](crate::Config::temp_dir).
+///
+/// Only files named like those created by [`TempFile`](crate::fs::TempFile),
+/// beginning with `.tmp`, are removed, and only from the top level of the
+/// directory. Since files of uploads in progress are continually modified,
+/// the TTL should exceed the time an upload may take between writes.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct TempFiles;
+
+/// Evicts responses stored by the attached [`Cache`] fairing, if any, that
+/// are expired or were stored at least a TTL ago.
+///
+/// The reclaimed size is that of the evicted response bodies.
+#[derive(Debug, Default, Copy, Clone)]
+#[cfg(feature = "cache")]
+#[cfg_attr(nightly, doc(cfg(feature = "cache")))]
+pub struct CachedResponses;
+
+impl Directory {
+    /// Returns a target that prunes files in `path`.
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Directory { path: path.as_ref().to_path_buf(), prefix: None }
+    }
+
+    /// Only prunes files whose names begin with `prefix`.
+    pub fn prefix<P: Into<String>>(mut self, prefix: P) -> Self {
+        self.prefix = Some(prefix.into());
+        self
+    }
+}
+
+/// Removes files in `dir` modified before `cutoff` whose names begin with
+/// `prefix`, if any, recursing into subdirectories when `recurse`.
+fn prune(
+    dir: &Path,
+    prefix: Option<&str>,
+    cutoff: SystemTime,
+    recurse: bool,
+) -> io::Result<Reclaimed> {
+    let mut reclaimed = Reclaimed::default();
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(reclaimed),
+        Err(e) => return Err(e),
+    };
+
+    for entry in entries {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        if metadata.is_dir() {
+            if recurse {
+                reclaimed += prune(&entry.path(), prefix, cutoff, recurse)?;
+
+                // Fails harmlessly if the directory isn't empty.
+                let _ = fs::remove_dir(entry.path());
+            }
+
+            continue;
+        }
+
+        let name = entry.file_name();
+        let matches = prefix.map_or(true, |p| name.to_string_lossy().starts_with(p));
+        if matches && metadata.is_file() && metadata.modified()? <= cutoff {
+            match fs::remove_file(entry.path()) {
+                Ok(()) => reclaimed += Reclaimed { items: 1, bytes: metadata.len() },
+                Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    Ok(reclaimed)
+}
+
+/// Runs `prune()` on a blocking thread.
+async fn prune_blocking(
+    dir: PathBuf,
+    prefix: Option<String>,
+    ttl: Duration,
+    recurse: bool,
+) -> io::Result<Reclaimed> {
+    let cutoff = SystemTime::now().checked_sub(ttl).unwrap_or(SystemTime::UNIX_EPOCH);
+    tokio::task::spawn_blocking(move || prune(&dir, prefix.as_deref(), cutoff, recurse))
+        .await
+        .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?
+}
+
+#[crate::async_trait]
+impl Reclaim for Directory {
+    async fn reclaim(&self, _: &Rocket<Orbit>, ttl: Duration) -> io::Result<Reclaimed> {
+        prune_blocking(self.path.clone(), self.prefix.clone(), ttl, true).await
+    }
+}
+
+#[crate::async_trait]
+impl Reclaim for TempFiles {
+    async fn reclaim(&self, rocket: &Rocket<Orbit>, ttl: Duration) -> io::Result<Reclaimed> {
+        let temp_dir = rocket.config().temp_dir.relative();
+        prune_blocking(temp_dir, Some(".tmp".into()), ttl, false).await
+    }
+}
+
+#[cfg(feature = "cache")]
+#[crate::async_trait]
+impl Reclaim for CachedResponses {
+    async fn reclaim(&self, rocket: &Rocket<Orbit>, ttl: Duration) -> io::Result<Reclaimed> {
+        let (items, bytes) = rocket.fairing::<Cache>().map_or((0, 0), |cache| cache.evict(ttl));
+        Ok(Reclaimed { items, bytes })
+    }
+}
+
+impl Add for Reclaimed {
+    type Output = Reclaimed;
+
+    fn add(self, rhs: Reclaimed) -> Reclaimed {
+        Reclaimed { items: self.items + rhs.items, bytes: self.bytes + rhs.bytes }
+    }
+}
+
+impl AddAssign for Reclaimed {
+    fn add_assign(&mut self, rhs: Reclaimed) {
+        *self = *self + rhs;
+    }
+}
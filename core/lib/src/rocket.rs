@@ -1,7 +1,8 @@
 use std::fmt;
+use std::borrow::Cow;
 use std::ops::{Deref, DerefMut};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use std::any::Any;
 use std::future::Future;
 use std::panic::Location;
@@ -9,18 +10,30 @@ use std::panic::Location;
 use either::Either;
 use figment::{Figment, Provider};
 use futures::TryFutureExt;
+use futures::future::BoxFuture;
 
-use crate::shutdown::{Stages, Shutdown};
+use crate::shutdown::{Stages, Shutdown, InFlight};
 use crate::trace::{Trace, TraceAll};
 use crate::{sentinel, shield::Shield, Catcher, Config, Route};
 use crate::listener::{Bind, DefaultListener, Endpoint, Listener};
-use crate::router::Router;
-use crate::fairing::{Fairing, Fairings};
+use crate::router::{Router, LiveRouter};
+use crate::fairing::{Fairing, Fairings, Conditional, Scoped, Require, Retry};
+#[cfg(feature = "consumer")]
+use crate::consumer::{Consume, Consumer};
+#[cfg(feature = "retention")]
+use crate::retention::Retention;
+#[cfg(feature = "pack")]
+use crate::pack::{RoutePack, Mounted};
 use crate::phase::{Phase, Build, Building, Ignite, Igniting, Orbit, Orbiting};
 use crate::phase::{Stateful, StateRef, StateRefMut, State};
+use crate::http::ContentType;
 use crate::http::uri::Origin;
 use crate::http::ext::IntoOwned;
 use crate::error::{Error, ErrorKind};
+#[cfg(feature = "crash-report")]
+use crate::crash::CrashReport;
+use crate::mime::MimeTypes;
+use crate::events::{Event, Events};
 
 /// The application server itself.
 ///
@@ -368,6 +381,56 @@ impl Rocket<Build> {
             |r, route| r.0.routes.push(route))
     }
 
+    /// Mounts all of the `routes` at `base`, as [`Rocket::mount()`] does, and
+    /// attaches `fairings` scoped to `base`: their request and response
+    /// callbacks only run for requests whose path is `base` or is under it,
+    /// matched segment-wise. Their other callbacks run as usual. Fairings are
+    /// typically listed via [`fairings!`](crate::fairings!).
+    ///
+    /// A scoped fairing is retrievable via [`Rocket::fairing()`] but, as with
+    /// [`Rocket::attach_if()`], is never treated as a
+    /// [singleton](crate::fairing::Fairing#singletons).
+    ///
+    /// # Panics
+    ///
+    /// Panics as [`Rocket::mount()`] does.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #[macro_use] extern crate rocket;
+    /// use rocket::fairing::AdHoc;
+    /// use rocket::http::Header;
+    ///
+    /// #[get("/users")]
+    /// fn users() -> &'static str { "[]" }
+    ///
+    /// #[launch]
+    /// fn rocket() -> _ {
+    ///     let private = AdHoc::on_response("Private", |_, res| Box::pin(async move {
+    ///         res.set_header(Header::new("Cache-Control", "private"));
+    ///     }));
+    ///
+    ///     rocket::build().mount_with("/admin", routes![users], fairings![private])
+    /// }
+    /// ```
+    #[must_use]
+    #[track_caller]
+    pub fn mount_with<'a, B, R>(self, base: B, routes: R, fairings: Vec<Box<dyn Fairing>>) -> Self
+        where B: TryInto<Origin<'a>> + Clone + fmt::Display,
+              B::Error: fmt::Display,
+              R: Into<Vec<Route>>
+    {
+        let mut rocket = self.mount(base.clone(), routes);
+        if let Ok(base) = base.try_into() {
+            for fairing in fairings {
+                rocket.fairings.add(Box::new(Scoped::new(base.path().as_str(), fairing)));
+            }
+        }
+
+        rocket
+    }
+
     /// Registers all of the catchers in the supplied vector, scoped to `base`.
     ///
     /// # Panics
@@ -449,7 +512,7 @@ impl Rocket<Build> {
     /// }
     /// ```
     #[must_use]
-    pub fn manage<T>(self, state: T) -> Self
+    pub fn manage<T>(mut self, state: T) -> Self
         where T: Send + Sync + 'static
     {
         let type_name = std::any::type_name::<T>();
@@ -461,6 +524,57 @@ impl Rocket<Build> {
         self
     }
 
+    /// Registers `media_type` as the media type of files with extension `ext`,
+    /// overriding Rocket's built-in mapping for `ext`, if any.
+    ///
+    /// Registered mappings are used to set the `Content-Type` of files served
+    /// by [`FileServer`], [`NamedFile`], and [`SendFile`], and are returned by
+    /// [`Rocket::mime_type()`]. Extensions are matched case-insensitively, and
+    /// a leading `.` in `ext` is ignored. This method can be called any number
+    /// of times; a later mapping for the same extension replaces an earlier
+    /// one.
+    ///
+    /// [`FileServer`]: crate::fs::FileServer
+    /// [`NamedFile`]: crate::fs::NamedFile
+    /// [`SendFile`]: crate::fs::SendFile
+    ///
+    /// # Panics
+    ///
+    /// Panics if `media_type` is not a valid media type or a known shorthand
+    /// as accepted by [`ContentType::parse_flexible()`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::ContentType;
+    ///
+    /// let rocket = rocket::build()
+    ///     .mime(".wasm2", "application/wasm2")
+    ///     .mime("md", "text/markdown; charset=utf-8");
+    ///
+    /// let wasm2 = ContentType::new("application", "wasm2");
+    /// assert_eq!(rocket.mime_type("wasm2"), Some(wasm2));
+    /// assert_eq!(rocket.mime_type("MD").unwrap().to_string(), "text/markdown; charset=utf-8");
+    /// ```
+    #[must_use]
+    #[track_caller]
+    pub fn mime(mut self, ext: &str, media_type: &str) -> Self {
+        let Some(content_type) = ContentType::parse_flexible(media_type) else {
+            error!(ext, media_type, "invalid media type for extension");
+            panic!("aborting due to invalid media type");
+        };
+
+        if self.state.try_get::<MimeTypes>().is_none() {
+            self.state.set(MimeTypes::default());
+        }
+
+        if let Some(types) = self.state.try_get::<MimeTypes>() {
+            types.insert(ext, content_type);
+        }
+
+        self
+    }
+
     /// Attaches a fairing to this instance of Rocket. No fairings are eagerly
     /// executed; fairings are executed at their appropriate time.
     ///
@@ -491,6 +605,293 @@ impl Rocket<Build> {
         self
     }
 
+    /// Attaches `fairing` if the boolean configuration parameter `key`, a
+    /// dotted path, is `true` at ignition. A missing parameter is `false`; a
+    /// parameter that isn't a boolean fails ignition.
+    ///
+    /// The decision is made during ignition, after all configuration is
+    /// known, so that optional fairings can be toggled by configuration alone.
+    /// An enabled fairing's callbacks run in attach order, as if it were
+    /// attached via [`Rocket::attach()`]. A disabled fairing is reported as
+    /// `disabled` in the fairings logged at ignition, and none of its
+    /// callbacks run.
+    ///
+    /// A conditional fairing is retrievable via [`Rocket::fairing()`] unless
+    /// its condition is known not to hold. Unlike an attached fairing, it is
+    /// never treated as a [singleton](crate::fairing::Fairing#singletons).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::fairing::AdHoc;
+    ///
+    /// #[rocket::launch]
+    /// fn rocket() -> _ {
+    ///     let metrics = AdHoc::on_response("Metrics", |_, res| Box::pin(async move {
+    ///         /* record metrics for `res` */
+    ///     }));
+    ///
+    ///     rocket::build().attach_if("metrics.enabled", metrics)
+    /// }
+    /// ```
+    #[must_use]
+    pub fn attach_if<K, F>(mut self, key: K, fairing: F) -> Self
+        where K: Into<Cow<'static, str>>, F: Fairing
+    {
+        self.fairings.add(Box::new(Conditional::config(key, Box::new(fairing))));
+        self
+    }
+
+    /// Attaches `fairing` if `predicate` returns `true` when called with the
+    /// configuration [`Figment`] at ignition.
+    ///
+    /// The fairing is enabled or disabled as with [`Rocket::attach_if()`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::fairing::AdHoc;
+    ///
+    /// #[rocket::launch]
+    /// fn rocket() -> _ {
+    ///     let admin = AdHoc::on_request("Admin", |_, _| Box::pin(async { }));
+    ///
+    ///     rocket::build().attach_when(|figment| {
+    ///         figment.extract_inner::<String>("admin.token").is_ok()
+    ///     }, admin)
+    /// }
+    /// ```
+    #[must_use]
+    pub fn attach_when<P, F>(mut self, predicate: P, fairing: F) -> Self
+        where P: Fn(&Figment) -> bool + Send + Sync + 'static, F: Fairing
+    {
+        self.fairings.add(Box::new(Conditional::when(predicate, Box::new(fairing))));
+        self
+    }
+
+    /// Attaches a [`Consume`] fairing named `name` that runs `consumer` in the
+    /// background from liftoff until shutdown, restarting it according to
+    /// `policy` when it fails. See [`consumer`](crate::consumer) for details.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # #[macro_use] extern crate rocket;
+    /// use rocket::{Rocket, Orbit, Shutdown};
+    /// use rocket::consumer::{self, Consumer};
+    /// use rocket::fairing::Retry;
+    ///
+    /// struct Audit;
+    ///
+    /// #[rocket::async_trait]
+    /// impl Consumer for Audit {
+    ///     async fn consume(&self, _: &Rocket<Orbit>, shutdown: Shutdown) -> consumer::Result {
+    ///         /* consume audit events until `shutdown` resolves */
+    ///         shutdown.await;
+    ///         Ok(())
+    ///     }
+    /// }
+    ///
+    /// #[launch]
+    /// fn rocket() -> _ {
+    ///     rocket::build().consume("audit", Retry::new(), Audit)
+    /// }
+    /// ```
+    ///
+    /// [`Consume`]: crate::consumer::Consume
+    #[must_use]
+    #[cfg(feature = "consumer")]
+    #[cfg_attr(nightly, doc(cfg(feature = "consumer")))]
+    pub fn consume<C: Consumer>(self, name: &'static str, policy: Retry, consumer: C) -> Self {
+        self.attach(Consume::new(name, policy, consumer))
+    }
+
+    /// Attaches a [`Require`] fairing named `name` that waits for a dependency
+    /// during ignition: `check` is run and retried according to `policy` until
+    /// it succeeds. If it fails on every attempt, ignition fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # #[macro_use] extern crate rocket;
+    /// use std::time::Duration;
+    /// use rocket::fairing::{Retry, Backoff};
+    ///
+    /// # async fn connect(_: String) -> Result<(), std::io::Error> { Ok(()) }
+    /// #[launch]
+    /// fn rocket() -> _ {
+    ///     let policy = Retry::new().retries(10).backoff(Backoff::Exponential);
+    ///     rocket::build()
+    ///         .require("database", policy, |rocket| Box::pin(async move {
+    ///             let url: String = rocket.figment().extract_inner("database_url")
+    ///                 .map_err(|e| e.to_string())?;
+    ///
+    ///             connect(url).await.map_err(|e| e.to_string())
+    ///         }))
+    /// }
+    /// ```
+    ///
+    /// [`Require`]: crate::fairing::Require
+    #[must_use]
+    pub fn require<F, E>(self, name: &'static str, policy: Retry, check: F) -> Self
+        where F: for<'a> Fn(&'a Rocket<Build>) -> BoxFuture<'a, Result<(), E>>,
+              F: Send + Sync + 'static,
+              E: fmt::Display + 'static,
+    {
+        self.attach(Require::new(name, policy, check))
+    }
+
+    /// Attaches the [`RoutePack`] `pack` at `base`: mounts its routes and
+    /// registers its catchers at `base`, attaches its fairings, and, at
+    /// ignition, checks it for conflicts and required state and manages its
+    /// configuration. See the [`pack`](crate::pack) module for details.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `base` is not a valid static path: a valid origin URI without
+    /// dynamic parameters.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # #[macro_use] extern crate rocket;
+    /// use rocket::Route;
+    /// use rocket::pack::RoutePack;
+    ///
+    /// #[get("/invoices")]
+    /// fn invoices() -> &'static str { "[]" }
+    ///
+    /// struct BillingPack;
+    ///
+    /// impl RoutePack for BillingPack {
+    ///     type Config = ();
+    ///
+    ///     fn name(&self) -> &'static str { "billing" }
+    ///
+    ///     fn routes(&self) -> Vec<Route> { routes![invoices] }
+    /// }
+    ///
+    /// #[launch]
+    /// fn rocket() -> _ {
+    ///     rocket::build().attach_pack("/billing", BillingPack)
+    /// }
+    /// ```
+    #[must_use]
+    #[track_caller]
+    #[cfg(feature = "pack")]
+    #[cfg_attr(nightly, doc(cfg(feature = "pack")))]
+    pub fn attach_pack<'a, B, P>(self, base: B, pack: P) -> Self
+        where B: TryInto<Origin<'a>> + Clone + fmt::Display,
+              B::Error: fmt::Display,
+              P: RoutePack
+    {
+        let mounted = Mounted::new(&pack, base.to_string());
+        let mut rocket = self.mount(base.clone(), pack.routes())
+            .register(base, pack.catchers());
+
+        pack.fairings().into_iter().for_each(|fairing| rocket.fairings.add(fairing));
+        for provided in pack.provides() {
+            let name = format!("route pack `{}`", pack.name()).into();
+            let type_id = provided.type_id();
+            rocket.providers.push(sentinel::Provider { type_id, name, attached: true });
+        }
+
+        rocket.attach(mounted)
+    }
+
+    /// Registers `provider`, typically a fairing or [route pack], as a
+    /// provider of the type `T`. When a sentinel aborts because `T` is
+    /// missing, its [diagnostic] suggests attaching `provider`.
+    ///
+    /// [route pack]: crate::pack::RoutePack
+    /// [diagnostic]: crate::sentinel::Diagnostic
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// struct PgPool;
+    ///
+    /// let rocket = rocket::build().provider::<PgPool>("BillingPack");
+    /// ```
+    #[must_use]
+    pub fn provider<T, N>(mut self, provider: N) -> Self
+        where T: ?Sized + 'static, N: Into<Cow<'static, str>>
+    {
+        let type_id = std::any::TypeId::of::<T>();
+        let name = provider.into();
+        self.providers.push(sentinel::Provider { type_id, name, attached: false });
+        self
+    }
+
+    /// Merges `other` into `self`, composing two independently built
+    /// applications into one.
+    ///
+    /// The routes, catchers, fairings, and managed state of `other` are moved
+    /// into `self`. Fairings are attached in their original order after those
+    /// of `self`, so a [singleton](crate::fairing::Kind::Singleton) fairing
+    /// attached to `other` replaces one attached to `self`. The configuration
+    /// figments are [joined](Figment::join()): values in `self` take precedence
+    /// over values in `other`. Media types registered via [`Rocket::mime()`]
+    /// are merged likewise. Subscribers to the [`events`](Rocket::events()) of
+    /// `other` receive no events.
+    ///
+    /// As with [`Rocket::mount()`], route and catcher collisions are reported
+    /// on ignition.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` and `other` both manage state of the same type.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #[macro_use] extern crate rocket;
+    /// struct Billing;
+    ///
+    /// #[get("/invoices")]
+    /// fn invoices() -> &'static str { "[]" }
+    ///
+    /// fn billing() -> rocket::Rocket<rocket::Build> {
+    ///     rocket::build()
+    ///         .mount("/billing", routes![invoices])
+    ///         .manage(Billing)
+    /// }
+    ///
+    /// #[launch]
+    /// fn rocket() -> _ {
+    ///     rocket::build().merge(billing())
+    /// }
+    /// ```
+    #[must_use]
+    #[track_caller]
+    pub fn merge(mut self, mut other: Rocket<Build>) -> Self {
+        self.routes.append(&mut other.routes);
+        self.catchers.append(&mut other.catchers);
+        self.fairings.append(&mut other.fairings);
+        self.providers.append(&mut other.providers);
+
+        let figment = std::mem::take(&mut self.figment);
+        self.figment = figment.join(std::mem::take(&mut other.figment));
+
+        if let Some(types) = other.state.take::<MimeTypes>() {
+            match self.state.try_get::<MimeTypes>() {
+                Some(ours) => ours.merge(types),
+                None => { self.state.set(types); }
+            }
+        }
+
+        if let Err(conflicts) = self.state.append(std::mem::take(&mut other.state)) {
+            for type_name in conflicts {
+                error!(type_name, location = %Location::caller(),
+                    "state is managed by both merged instances");
+            }
+
+            panic!("aborting due to duplicated managed state");
+        }
+
+        self
+    }
+
     /// Returns a `Future` that transitions this instance of `Rocket` into the
     /// _ignite_ phase.
     ///
@@ -507,7 +908,11 @@ impl Rocket<Build> {
     ///   * No [`Sentinel`](crate::Sentinel) triggered an abort.
     ///
     /// If any of these conditions fail to be met, a respective [`Error`] is
-    /// returned.
+    /// returned. Conditions are checked independently of one another where
+    /// possible, so that when more than one fails, a single
+    /// [aggregate](crate::error::ErrorKind::Aggregate) error reports all of the
+    /// failures. Sentinels are only queried when a valid `Config` was
+    /// extracted.
     ///
     /// [configured]: Rocket::figment()
     ///
@@ -534,61 +939,119 @@ impl Rocket<Build> {
     /// }
     /// ```
     pub async fn ignite(mut self) -> Result<Rocket<Ignite>, Error> {
+        let mut errors = vec![];
         self = Fairings::handle_ignite(self).await;
-        self.fairings.audit().map_err(|f| ErrorKind::FailedFairings(f.to_vec()))?;
+        if let Err(failures) = self.fairings.audit() {
+            errors.push(Error::new(ErrorKind::FailedFairings(failures.to_vec())));
+        }
 
         // Extract the configuration; initialize default trace subscriber.
         #[allow(unused_mut)]
-        let mut config = Config::try_from(&self.figment).map_err(ErrorKind::Config)?;
-        crate::trace::init(&config);
+        let mut config = match Config::try_from(&self.figment) {
+            Ok(config) => Some(config),
+            Err(e) => {
+                errors.push(Error::new(ErrorKind::Config(e)));
+                None
+            }
+        };
+
+        crate::trace::init(config.as_ref());
 
         // Check for safely configured secrets.
         #[cfg(feature = "secrets")]
-        if !config.secret_key.is_provided() {
+        if let Some(config) = config.as_mut().filter(|c| !c.secret_key.is_provided()) {
             if config.profile != Config::DEBUG_PROFILE {
-                return Err(Error::new(ErrorKind::InsecureSecretKey(config.profile.clone())));
-            }
-
-            if config.secret_key.is_zero() {
+                let profile = config.profile.clone();
+                errors.push(Error::new(ErrorKind::InsecureSecretKey(profile)));
+            } else if config.secret_key.is_zero() {
                 config.secret_key = crate::config::SecretKey::generate()
                     .unwrap_or_else(crate::config::SecretKey::zero);
             }
         }
 
-        // Initialize the router; check for collisions.
+        // Initialize the router with routes whose conditions hold; check for
+        // collisions.
+        let (mut routes, mut disabled) = (vec![], vec![]);
+        for route in self.routes.iter() {
+            let condition = route.condition.as_ref();
+            match condition.map_or(Ok(true), |c| c.holds(self.figment())) {
+                Ok(true) => routes.push(route.clone()),
+                Ok(false) => disabled.push(route.clone()),
+                Err(e) => errors.push(Error::new(ErrorKind::Config(e))),
+            }
+        }
+
         let mut router = Router::new();
-        self.routes.clone().into_iter().for_each(|r| router.add_route(r));
+        routes.iter().cloned().for_each(|r| router.add_route(r));
         self.catchers.clone().into_iter().for_each(|c| router.add_catcher(c));
-        router.finalize().map_err(|(r, c)| ErrorKind::Collisions { routes: r, catchers: c, })?;
+        if let Err((r, c)) = router.finalize() {
+            errors.push(Error::new(ErrorKind::Collisions { routes: r, catchers: c }));
+        }
+
+        // Sentinels can only be queried against a configured instance.
+        let Some(config) = config else {
+            return Err(Error::aggregate(errors));
+        };
 
         // Finally, freeze managed state for faster access later.
-        self.state.freeze();
+        let mut state = std::mem::take(&mut self.state).into_type_map();
+        state.freeze();
 
         // Log everything we know: config, routes, catchers, fairings.
         // TODO: Store/print managed state type names?
-        let fairings = self.fairings.unique_set();
-        span_info!("config", profile = %self.figment().profile() => {
-            config.trace_info();
-            self.figment().trace_debug();
-        });
-
-        span_info!("routes", count = self.routes.len() => self.routes().trace_all_info());
-        span_info!("catchers", count = self.catchers.len() => self.catchers().trace_all_info());
-        span_info!("fairings", count = fairings.len() => fairings.trace_all_info());
+        if errors.is_empty() {
+            let fairings = self.fairings.unique_set();
+            span_info!("config", profile = %self.figment().profile() => {
+                config.trace_info();
+                self.figment().trace_debug();
+            });
+
+            span_info!("routes", count = routes.len() => {
+                routes.iter().trace_all_info();
+                if !disabled.is_empty() {
+                    span_info!("disabled", count = disabled.len() => {
+                        disabled.iter().trace_all_info()
+                    });
+                }
+            });
+
+            span_info!("catchers", count = self.catchers.len() => {
+                self.catchers.iter().trace_all_info()
+            });
+
+            span_info!("fairings", count = fairings.len() => {
+                fairings.trace_all_info();
+                let disabled = self.fairings.disabled();
+                if !disabled.is_empty() {
+                    span_info!("disabled", count = disabled.len() => {
+                        disabled.iter().trace_all_info()
+                    });
+                }
+            });
+        }
 
         // Ignite the rocket.
+        let providers = std::mem::take(&mut self.providers);
         let rocket: Rocket<Ignite> = Rocket(Igniting {
             shutdown: Stages::new(),
             figment: self.0.figment,
             fairings: self.0.fairings,
-            state: self.0.state,
-            router, config,
+            events: self.0.events,
+            router, config, state,
         });
 
         // Query the sentinels, abort if requested.
-        let sentinels = rocket.routes().flat_map(|r| r.sentinels.iter());
-        sentinel::query(sentinels, &rocket).map_err(ErrorKind::SentinelAborts)?;
+        let sentinels = rocket.router.routes().flat_map(|r| r.sentinels.iter());
+        if let Err(aborted) = sentinel::query(sentinels, &rocket) {
+            let diagnostics = sentinel::diagnose(&aborted, &rocket, &providers);
+            errors.push(Error { kind: ErrorKind::SentinelAborts(aborted), diagnostics });
+        }
 
+        if !errors.is_empty() {
+            return Err(Error::aggregate(errors));
+        }
+
+        rocket.events.emit(Event::Ignited);
         Ok(rocket)
     }
 }
@@ -649,13 +1112,20 @@ impl Rocket<Ignite> {
 
     pub(crate) fn into_orbit(self, endpoints: Vec<Endpoint>) -> Rocket<Orbit> {
         Rocket(Orbiting {
+            closing: endpoints.iter().map(|_| Shutdown::new()).collect(),
+            alt_svc: crate::server::alt_svc(&endpoints),
+            in_flight: {
+                let record = self.0.config.shutdown.drain_report;
+                Arc::new(InFlight::new(self.0.shutdown.grace.clone(), record))
+            },
             endpoints,
-            router: self.0.router,
+            router: LiveRouter::new(self.0.router),
             fairings: self.0.fairings,
             figment: self.0.figment,
             config: self.0.config,
             state: self.0.state,
             shutdown: self.0.shutdown,
+            events: self.0.events,
         })
     }
 
@@ -669,12 +1139,29 @@ impl Rocket<Ignite> {
         let rocket = self.listen_and_serve(listener, |rocket| async move {
             let rocket = Arc::new(rocket);
 
-            rocket.shutdown.spawn_listener(&rocket.config.shutdown);
+            rocket.shutdown.spawn_listener(&rocket.config.shutdown, &rocket.events);
             if let Err(e) = tokio::spawn(Rocket::liftoff(rocket.clone())).await {
+                let error = e.to_string();
+                rocket.events.emit(Event::TaskFailed { task: "liftoff".into(), error });
                 let rocket = rocket.try_wait_shutdown().await.map(Box::new);
                 return Err(ErrorKind::Liftoff(rocket, e).into());
             }
 
+            #[cfg(feature = "consumer")]
+            for consumer in rocket.fairings.filter::<Consume>() {
+                consumer.spawn(rocket.clone());
+            }
+
+            #[cfg(feature = "retention")]
+            for retention in rocket.fairings.filter::<Retention>() {
+                retention.spawn(rocket.clone());
+            }
+
+            #[cfg(all(unix, feature = "handoff"))]
+            for handoff in rocket.fairings.filter::<crate::handoff::Handoff>() {
+                handoff.spawn(rocket.clone());
+            }
+
             Ok(rocket)
         }).await?;
 
@@ -712,18 +1199,28 @@ impl Rocket<Orbit> {
 
         let config = &self.config.shutdown;
         let wait = Duration::from_micros(250);
+        let start = Instant::now();
         for period in [wait, config.grace(), wait, config.mercy(), wait * 4] {
             if Arc::strong_count(&self) == 1 { break }
             tokio::time::sleep(period).await;
         }
 
+        // Report the requests cut short by the end of the grace period.
+        let graceful = Arc::strong_count(&self) == 1;
+        let report = self.in_flight.report(graceful, start.elapsed());
+        if report.count > 0 {
+            self.events.emit(Event::Terminated { report });
+        }
+
         match Arc::try_unwrap(self) {
             Ok(rocket) => {
                 info!("Graceful shutdown completed successfully.");
+                rocket.events.emit(Event::Drained { graceful: true });
                 Ok(rocket.deorbit())
             }
             Err(rocket) => {
                 warn!("Shutdown failed: outstanding background I/O.");
+                rocket.events.emit(Event::Drained { graceful: false });
                 Err(rocket)
             }
         }
@@ -731,12 +1228,13 @@ impl Rocket<Orbit> {
 
     pub(crate) fn deorbit(self) -> Rocket<Ignite> {
         Rocket(Igniting {
-            router: self.0.router,
+            router: self.0.router.into_inner(),
             fairings: self.0.fairings,
             figment: self.0.figment,
             config: self.0.config,
             state: self.0.state,
             shutdown: self.0.shutdown,
+            events: self.0.events,
         })
     }
 
@@ -753,6 +1251,7 @@ impl Rocket<Orbit> {
         }
 
         tracing::info!(name: "liftoff", endpoint = %rocket.endpoints[0]);
+        rocket.events.emit(Event::Liftoff { endpoints: rocket.endpoints.clone() });
     }
 
     /// Returns the finalized, active configuration. This is guaranteed to
@@ -777,6 +1276,29 @@ impl Rocket<Orbit> {
         &self.config
     }
 
+    /// Returns an iterator over the endpoints the server is actually bound to
+    /// and listening on.
+    ///
+    /// The endpoints reflect the final bind result, which may differ from the
+    /// configured address, for example when a fallback port was used or when
+    /// port `0` was configured.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # #[macro_use] extern crate rocket;
+    /// use rocket::fairing::AdHoc;
+    ///
+    /// #[launch]
+    /// fn rocket() -> _ {
+    ///     rocket::build()
+    ///         .attach(AdHoc::on_liftoff("Endpoints", |rocket| Box::pin(async move {
+    ///             for endpoint in rocket.endpoints() {
+    ///                 println!("Listening on {endpoint}");
+    ///             }
+    ///         })))
+    /// }
+    /// ```
     pub fn endpoints(&self) -> impl Iterator<Item = &Endpoint> {
         self.endpoints.iter()
     }
@@ -811,12 +1333,212 @@ impl Rocket<Orbit> {
     pub fn shutdown(&self) -> Shutdown {
         self.shutdown.start.clone()
     }
+
+    /// Gracefully closes the listeners whose endpoints satisfy `f` while the
+    /// remaining listeners keep serving. Returns the number of listeners
+    /// closed.
+    ///
+    /// A closed listener stops accepting connections. Requests in progress on
+    /// its existing connections complete, after which the connections close.
+    /// Closing listeners never shuts the server down: if `f` is satisfied by
+    /// every listener still open, no listener is closed and `0` is returned.
+    /// Use [`Rocket::shutdown()`] to shut down instead.
+    ///
+    /// Rocket serves on more than one listener when HTTP/3 is enabled, where
+    /// HTTP/1 and HTTP/2 are served over TCP alongside HTTP/3 over QUIC.
+    /// Closed listeners remain in [`Rocket::endpoints()`].
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # #[macro_use] extern crate rocket;
+    /// use rocket::tokio::{self, time};
+    /// use rocket::fairing::AdHoc;
+    ///
+    /// #[launch]
+    /// fn rocket() -> _ {
+    ///     rocket::build()
+    ///         .attach(AdHoc::on_liftoff("TCP Sunset", |rocket| Box::pin(async move {
+    ///             time::sleep(time::Duration::from_secs(60)).await;
+    ///             rocket.close_listeners(|endpoint| endpoint.quic().is_none());
+    ///         })))
+    /// }
+    /// ```
+    pub fn close_listeners<F>(&self, f: F) -> usize
+        where F: FnMut(&Endpoint) -> bool
+    {
+        crate::server::close_listeners(&self.endpoints, &self.closing, &self.events, f)
+    }
+
+    /// Returns a snapshot of the routes currently being served, including
+    /// those mounted or unmounted via [`Rocket::mount_live()`] and
+    /// [`Rocket::unmount_live()`]. Unlike [`Rocket::routes()`], which returns
+    /// the routes mounted at launch, later changes are reflected in later
+    /// snapshots.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #[macro_use] extern crate rocket;
+    /// use rocket::local::blocking::Client;
+    ///
+    /// #[get("/hello")]
+    /// fn hello() -> &'static str {
+    ///     "Hello, world!"
+    /// }
+    ///
+    /// let client = Client::debug_with(vec![]).unwrap();
+    /// client.rocket().mount_live("/plugin", routes![hello]).unwrap();
+    ///
+    /// let live = client.rocket().live_routes();
+    /// assert!(live.routes().any(|r| r.uri == "/plugin/hello"));
+    /// assert_eq!(client.rocket().routes().count(), 0);
+    /// ```
+    pub fn live_routes(&self) -> crate::route::LiveRoutes {
+        crate::route::LiveRoutes(self.router.current())
+    }
+
+    /// Mounts `routes` at `base` while the server is running, as
+    /// [`Rocket::mount()`] does before launch. Returns the number of routes
+    /// mounted.
+    ///
+    /// The routes are added to a copy of the route table that then atomically
+    /// replaces it: a request is routed entirely against the table current
+    /// when its routing began, and later requests see the new routes. As at
+    /// ignition, routes whose [`condition`](Route::condition) doesn't hold are
+    /// skipped. Sentinels are not queried. Emits [`Event::RoutesChanged`].
+    ///
+    /// A replaced table is freed once the last request routed against it
+    /// completes. Routes are shared between tables, so a table costs only its
+    /// index.
+    ///
+    /// # Errors
+    ///
+    /// If a route collides with one that's mounted, or a route's condition
+    /// can't be evaluated, no routes are mounted and an error is returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `base` is not a valid origin URI.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #[macro_use] extern crate rocket;
+    /// use rocket::local::blocking::Client;
+    /// use rocket::http::Status;
+    ///
+    /// #[get("/hello")]
+    /// fn hello() -> &'static str {
+    ///     "Hello, world!"
+    /// }
+    ///
+    /// let client = Client::debug_with(vec![]).unwrap();
+    /// assert_eq!(client.get("/plugin/hello").dispatch().status(), Status::NotFound);
+    ///
+    /// client.rocket().mount_live("/plugin", routes![hello]).unwrap();
+    /// assert_eq!(client.get("/plugin/hello").dispatch().status(), Status::Ok);
+    ///
+    /// client.rocket().unmount_live(|route| route.uri.base() == "/plugin");
+    /// assert_eq!(client.get("/plugin/hello").dispatch().status(), Status::NotFound);
+    /// ```
+    #[track_caller]
+    pub fn mount_live<'a, B, R>(&self, base: B, routes: R) -> Result<usize, Error>
+        where B: TryInto<Origin<'a>> + Clone + fmt::Display,
+              B::Error: fmt::Display,
+              R: Into<Vec<Route>>
+    {
+        let mut base = match base.clone().try_into() {
+            Ok(origin) => origin.into_owned(),
+            Err(e) => {
+                error!(%base, location = %Location::caller(), "invalid route base uri: {e}");
+                panic!("aborting due to route base error");
+            }
+        };
+
+        if base.query().is_some() {
+            warn!(%base, location = %Location::caller(), "query in route base is ignored");
+            base.clear_query();
+        }
+
+        let mut mounted = vec![];
+        for route in routes.into() {
+            let route = route.rebase(base.clone());
+            match route.condition.as_ref().map_or(Ok(true), |c| c.holds(self.figment())) {
+                Ok(true) => mounted.push(route),
+                Ok(false) => debug!(uri = %route.uri, "route condition doesn't hold: skipping"),
+                Err(e) => return Err(Error::new(ErrorKind::Config(e))),
+            }
+        }
+
+        if mounted.is_empty() {
+            return Ok(0);
+        }
+
+        self.router.update(|router| {
+            mounted.iter().cloned().for_each(|r| router.add_route(r));
+            router.finalize()
+                .map_err(|(routes, catchers)| ErrorKind::Collisions { routes, catchers })
+        })?;
+
+        span_info!("mounted", base = %base, count = mounted.len() => {
+            mounted.iter().trace_all_info()
+        });
+
+        self.events.emit(Event::RoutesChanged { mounted: mounted.len(), unmounted: 0 });
+        Ok(mounted.len())
+    }
+
+    /// Unmounts every route satisfying `f` while the server is running,
+    /// whether it was mounted before launch or via [`Rocket::mount_live()`].
+    /// Returns the number of routes unmounted.
+    ///
+    /// As with [`Rocket::mount_live()`], the route table is replaced
+    /// atomically: requests already routed to an unmounted route complete.
+    /// Emits [`Event::RoutesChanged`] if any routes are unmounted.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #[macro_use] extern crate rocket;
+    /// use rocket::local::blocking::Client;
+    ///
+    /// #[get("/")]
+    /// fn index() { }
+    ///
+    /// let client = Client::debug_with(routes![index]).unwrap();
+    /// assert_eq!(client.rocket().unmount_live(|route| route.name.is_none()), 0);
+    /// assert_eq!(client.rocket().unmount_live(|route| route.uri == "/"), 1);
+    /// assert_eq!(client.rocket().live_routes().routes().count(), 0);
+    /// ```
+    pub fn unmount_live<F>(&self, f: F) -> usize
+        where F: FnMut(&Route) -> bool
+    {
+        let unmounted = self.router
+            .update(|router| match router.remove_routes(f) {
+                0 => Err(()),
+                n => Ok(n),
+            })
+            .unwrap_or(0);
+
+        if unmounted > 0 {
+            info!(name: "routes", count = unmounted, "unmounted routes");
+            self.events.emit(Event::RoutesChanged { mounted: 0, unmounted });
+        }
+
+        unmounted
+    }
 }
 
 impl<P: Phase> Rocket<P> {
     /// Returns an iterator over all of the routes mounted on this instance of
     /// Rocket. The order is unspecified.
     ///
+    /// Once in orbit, these are the routes mounted at launch. Use
+    /// [`Rocket::live_routes()`] for the routes currently being served,
+    /// including those mounted or unmounted via [`Rocket::mount_live()`] and
+    /// [`Rocket::unmount_live()`].
+    ///
     /// # Example
     ///
     /// ```rust
@@ -841,7 +1563,7 @@ impl<P: Phase> Rocket<P> {
         match self.0.as_ref() {
             StateRef::Build(p) => Either::Left(p.routes.iter()),
             StateRef::Ignite(p) => Either::Right(p.router.routes()),
-            StateRef::Orbit(p) => Either::Right(p.router.routes()),
+            StateRef::Orbit(p) => Either::Right(p.router.launched().routes()),
         }
     }
 
@@ -872,10 +1594,40 @@ impl<P: Phase> Rocket<P> {
         match self.0.as_ref() {
             StateRef::Build(p) => Either::Left(p.catchers.iter()),
             StateRef::Ignite(p) => Either::Right(p.router.catchers()),
-            StateRef::Orbit(p) => Either::Right(p.router.catchers()),
+            StateRef::Orbit(p) => Either::Right(p.router.launched().catchers()),
         }
     }
 
+    /// Returns the [OpenAPI document](crate::openapi) describing the routes
+    /// mounted on this instance of Rocket and the responses of its catchers.
+    ///
+    /// The document's title, version, and description are taken from the
+    /// attached [`OpenApi`](crate::openapi::OpenApi) fairing, if any. Routes
+    /// without an operation, such as those mounted by fairings, are left out.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use rocket::*;
+    /// use rocket::http::Method;
+    ///
+    /// /// Says hello.
+    /// #[get("/hello/<name>")]
+    /// fn hello(name: &str) -> String {
+    ///     format!("Hello, {name}!")
+    /// }
+    ///
+    /// let rocket = rocket::build().mount("/", routes![hello]);
+    /// let document = rocket.openapi();
+    /// let op = &document.paths["/hello/{name}"][&Method::Get];
+    /// assert_eq!(op.summary.as_deref(), Some("Says hello."));
+    /// ```
+    #[cfg(feature = "openapi")]
+    #[cfg_attr(nightly, doc(cfg(feature = "openapi")))]
+    pub fn openapi(&self) -> crate::openapi::Document {
+        crate::openapi::Document::collect(self)
+    }
+
     /// Returns `Some` of the managed state value for the type `T` if it is
     /// being managed by `self`. Otherwise, returns `None`.
     ///
@@ -896,6 +1648,28 @@ impl<P: Phase> Rocket<P> {
         }
     }
 
+    /// Returns the media type of files with extension `ext`: the one registered
+    /// via [`Rocket::mime()`], if any, and otherwise the one given by
+    /// [`ContentType::from_extension()`].
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::ContentType;
+    ///
+    /// let rocket = rocket::build().mime("json", "application/vnd.api+json");
+    /// assert_eq!(rocket.mime_type("html"), Some(ContentType::HTML));
+    /// assert_eq!(rocket.mime_type(".json").unwrap().sub(), "vnd.api+json");
+    /// assert_eq!(rocket.mime_type("unknown"), None);
+    /// ```
+    pub fn mime_type(&self, ext: &str) -> Option<ContentType> {
+        let ext = ext.strip_prefix('.').unwrap_or(ext);
+        self.state::<MimeTypes>()
+            .and_then(|types| types.get(ext))
+            .or_else(|| ContentType::from_extension(ext))
+    }
+
     /// Returns a reference to the first fairing of type `F` if it is attached.
     /// Otherwise, returns `None`.
     ///
@@ -1124,6 +1898,31 @@ impl<P: Phase> Rocket<P> {
         }
     }
 
+    /// Subscribes to the lifecycle events of this instance, returning a
+    /// receiver of every [`Event`] emitted from now on. See
+    /// [`events`](crate::events) for details.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::events::Event;
+    ///
+    /// # rocket::async_test(async {
+    /// let rocket = rocket::build();
+    /// let mut events = rocket.events();
+    ///
+    /// let _rocket = rocket.ignite().await.unwrap();
+    /// assert!(matches!(events.try_recv(), Some(Event::Ignited)));
+    /// # });
+    /// ```
+    pub fn events(&self) -> Events {
+        match self.0.as_ref() {
+            StateRef::Build(p) => p.events.subscribe(),
+            StateRef::Ignite(p) => p.events.subscribe(),
+            StateRef::Orbit(p) => p.events.subscribe(),
+        }
+    }
+
     async fn into_ignite(self) -> Result<Rocket<Ignite>, Error> {
         match self.0.into_state() {
             State::Build(s) => Rocket::from(s).ignite().await,
@@ -1165,9 +1964,23 @@ impl<P: Phase> Rocket<P> {
     /// # Error
     ///
     /// If there is a problem starting the application or the application fails
-    /// unexpectedly while running, an [`Error`] is returned. Note that a value
-    /// of type `Error` panics if dropped without first being inspected. See the
-    /// [`Error`] documentation for more information.
+    /// unexpectedly while running, an [`Error`] is returned. See the [`Error`]
+    /// documentation for more information.
+    ///
+    /// # Crash Reports
+    ///
+    /// With the `crash-report` feature enabled, if the `crash_report`
+    /// configuration parameter is set to a path, a machine-readable JSON crash
+    /// report is written to that path when launch resolves with an `Err`. The
+    /// path is logged in a final `crash_report` trace event. The report
+    /// contains the selected profile, a snapshot of the configuration with
+    /// sensitive values redacted, the route and catcher table, the error
+    /// chain, and a backtrace captured according to `RUST_BACKTRACE`.
+    ///
+    /// ```toml
+    /// [default]
+    /// crash_report = "/var/log/my-app/crash.json"
+    /// ```
     ///
     /// # Example
     ///
@@ -1185,6 +1998,18 @@ impl<P: Phase> Rocket<P> {
     }
 
     pub async fn launch_with<B: Bind>(self) -> Result<Rocket<Ignite>, Error> {
+        #[cfg(feature = "crash-report")]
+        let report = CrashReport::prepare(&self);
+        let result = self._launch_with::<B>().await;
+        #[cfg(feature = "crash-report")]
+        if let (Some(report), Err(e)) = (report, &result) {
+            report.report(e);
+        }
+
+        result
+    }
+
+    async fn _launch_with<B: Bind>(self) -> Result<Rocket<Ignite>, Error> {
         let rocket = self.into_ignite().await?;
         let bind_endpoint = B::bind_endpoint(&rocket).ok();
         let listener: B = B::bind(&rocket).await
@@ -1212,14 +2037,36 @@ impl<P: Phase> Rocket<P> {
               F: Future<Output = Result<L, E>>,
               E: std::error::Error + Send + 'static
     {
-        let listener = listener.map_err(|e| ErrorKind::Bind(None, Box::new(e))).await?;
-        self.into_ignite().await?._launch(listener).await
+        #[cfg(feature = "crash-report")]
+        let report = CrashReport::prepare(&self);
+        let result: Result<_, Error> = async move {
+            let listener = listener.map_err(|e| ErrorKind::Bind(None, Box::new(e))).await?;
+            self.into_ignite().await?._launch(listener).await
+        }.await;
+
+        #[cfg(feature = "crash-report")]
+        if let (Some(report), Err(e)) = (report, &result) {
+            report.report(e);
+        }
+
+        result
     }
 
     pub async fn launch_on<L>(self, listener: L) -> Result<Rocket<Ignite>, Error>
         where L: Listener + 'static,
     {
-        self.into_ignite().await?._launch(listener).await
+        #[cfg(feature = "crash-report")]
+        let report = CrashReport::prepare(&self);
+        let result: Result<_, Error> = async move {
+            self.into_ignite().await?._launch(listener).await
+        }.await;
+
+        #[cfg(feature = "crash-report")]
+        if let (Some(report), Err(e)) = (report, &result) {
+            report.report(e);
+        }
+
+        result
     }
 }
 
@@ -0,0 +1,274 @@
+use std::borrow::Cow;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use rand::Rng;
+
+use crate::{Request, Data, Route};
+use crate::http::{Cookie, SameSite, Status};
+use crate::route::{Handler, Outcome};
+
+/// A mountable canary release: two implementations of the same routes with a
+/// traffic split between them.
+///
+/// A `Canary` pairs _stable_ routes with _canary_ routes that have the same
+/// method, URI, and format and mounts each pair as a single route. Each
+/// request to a paired route is handled by one of the two implementations,
+/// the _variant_ assigned to the request, which is, in order:
+///
+///   1. The variant named by the request's selection header, if one is
+///      [configured](Canary::header()) and its value is `stable` or `canary`.
+///   2. The variant named by the request's assignment cookie,
+///      `rocket_canary_{name}` by default, if its value is `stable` or
+///      `canary`.
+///   3. Otherwise, `canary` for a random [`percent`](Canary::percent()) of
+///      requests and `stable` for the rest.
+///
+/// A randomly assigned variant is recorded in the assignment cookie, so that
+/// the client keeps seeing the same implementation for the rest of its
+/// session. Changing the split, even while the server is running via
+/// [`Canary::ramp()`], only affects clients that weren't yet assigned a
+/// variant. Since clients can set the header and the cookie themselves, a
+/// `Canary` selects implementations; it doesn't restrict access to them.
+///
+/// Stable routes without a canary counterpart are mounted as they are. Canary
+/// routes without a stable counterpart only handle requests assigned the
+/// `canary` variant; other requests are forwarded with a status of `404 Not
+/// Found`.
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::route::Canary;
+///
+/// mod v1 {
+///     #[get("/search?<q>")]
+///     pub fn search(q: &str) -> String { format!("v1: {q}") }
+/// }
+///
+/// mod v2 {
+///     #[get("/search?<q>")]
+///     pub fn search(q: &str) -> String { format!("v2: {q}") }
+/// }
+///
+/// #[launch]
+/// fn rocket() -> _ {
+///     let search = Canary::new("search")
+///         .stable(routes![v1::search])
+///         .canary(routes![v2::search])
+///         .percent(10)
+///         .header("X-Canary");
+///
+///     rocket::build().mount("/", search)
+/// }
+/// ```
+#[derive(Clone)]
+pub struct Canary {
+    stable: Vec<Route>,
+    canary: Vec<Route>,
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    name: Cow<'static, str>,
+    cookie: Cow<'static, str>,
+    header: Option<Cow<'static, str>>,
+    percent: AtomicU8,
+}
+
+/// The handler of a paired route: dispatches to the assigned variant.
+#[derive(Clone)]
+struct Split {
+    stable: Option<Box<dyn Handler>>,
+    canary: Box<dyn Handler>,
+    inner: Arc<Inner>,
+}
+
+impl Canary {
+    /// Returns a canary release named `name`, with no routes and all traffic
+    /// assigned to the stable variant. The name identifies the release in
+    /// logs and in the default assignment cookie name.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::route::Canary;
+    ///
+    /// let canary = Canary::new("search");
+    /// assert_eq!(canary.canary_percent(), 0);
+    /// ```
+    pub fn new<N: Into<Cow<'static, str>>>(name: N) -> Self {
+        let name = name.into();
+        Canary {
+            stable: vec![],
+            canary: vec![],
+            inner: Arc::new(Inner {
+                cookie: format!("rocket_canary_{name}").into(),
+                header: None,
+                percent: AtomicU8::new(0),
+                name,
+            }),
+        }
+    }
+
+    /// Adds `routes` to the stable implementation.
+    pub fn stable<R: Into<Vec<Route>>>(mut self, routes: R) -> Self {
+        self.stable.extend(routes.into());
+        self
+    }
+
+    /// Adds `routes` to the canary implementation.
+    pub fn canary<R: Into<Vec<Route>>>(mut self, routes: R) -> Self {
+        self.canary.extend(routes.into());
+        self
+    }
+
+    /// Assigns `percent` of clients, at most `100`, to the canary variant.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::route::Canary;
+    ///
+    /// let canary = Canary::new("search").percent(10);
+    /// assert_eq!(canary.canary_percent(), 10);
+    /// ```
+    pub fn percent(self, percent: u8) -> Self {
+        self.ramp(percent);
+        self
+    }
+
+    /// Selects the variant named by the value of the header `name`, `stable`
+    /// or `canary`, when present.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called after the `Canary` is mounted.
+    pub fn header<N: Into<Cow<'static, str>>>(mut self, name: N) -> Self {
+        self.inner_mut().header = Some(name.into());
+        self
+    }
+
+    /// Sets the name of the assignment cookie. Defaults to
+    /// `rocket_canary_{name}`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called after the `Canary` is mounted.
+    pub fn cookie<N: Into<Cow<'static, str>>>(mut self, name: N) -> Self {
+        self.inner_mut().cookie = name.into();
+        self
+    }
+
+    /// Changes the percent of clients, at most `100`, newly assigned to the
+    /// canary variant. Unlike the other methods, `ramp()` takes effect on a
+    /// clone of a `Canary` that is already mounted.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #[macro_use] extern crate rocket;
+    /// use rocket::route::Canary;
+    ///
+    /// # #[get("/")] fn v1() {}
+    /// # #[get("/")] fn v2() {}
+    /// let canary = Canary::new("index").stable(routes![v1]).canary(routes![v2]);
+    /// let rocket = rocket::build().mount("/", canary.clone());
+    ///
+    /// // Later, gradually...
+    /// canary.ramp(50);
+    /// assert_eq!(canary.canary_percent(), 50);
+    /// ```
+    pub fn ramp(&self, percent: u8) {
+        self.inner.percent.store(percent.min(100), Ordering::Relaxed);
+    }
+
+    /// Returns the percent of clients newly assigned to the canary variant.
+    pub fn canary_percent(&self) -> u8 {
+        self.inner.percent.load(Ordering::Relaxed)
+    }
+
+    fn inner_mut(&mut self) -> &mut Inner {
+        Arc::get_mut(&mut self.inner).expect("canary is configured before it's mounted")
+    }
+}
+
+impl Inner {
+    /// Returns `true` if `req` is assigned the canary variant, assigning and
+    /// recording a variant if it doesn't have one.
+    fn assign(&self, req: &Request<'_>) -> bool {
+        let parse = |value: &str| match value {
+            "canary" => Some(true),
+            "stable" => Some(false),
+            _ => None,
+        };
+
+        let header = self.header.as_deref().and_then(|h| req.headers().get_one(h));
+        if let Some(canary) = header.and_then(parse) {
+            return canary;
+        }
+
+        let cookie = req.cookies().get(&self.cookie).map(|c| c.value());
+        if let Some(canary) = cookie.and_then(parse) {
+            return canary;
+        }
+
+        let percent = self.percent.load(Ordering::Relaxed);
+        let canary = rand::thread_rng().gen_range(0..100) < percent;
+        let variant = if canary { "canary" } else { "stable" };
+        debug!(name: "canary", canary = %self.name, variant, "assigned variant");
+        req.cookies().add(Cookie::build((self.cookie.to_string(), variant))
+            .path("/")
+            .http_only(true)
+            .same_site(SameSite::Lax));
+
+        canary
+    }
+}
+
+impl From<Canary> for Vec<Route> {
+    fn from(canary: Canary) -> Self {
+        let Canary { stable, canary: mut canaries, inner } = canary;
+        let split = |stable: Option<Box<dyn Handler>>, canary: Route| {
+            let handler = Split { stable, canary: canary.handler.clone(), inner: inner.clone() };
+            (Box::new(handler) as Box<dyn Handler>, canary)
+        };
+
+        let mut routes = vec![];
+        for mut route in stable {
+            let counterpart = canaries.iter().position(|c| {
+                c.method == route.method
+                    && c.format == route.format
+                    && c.uri.unmounted() == route.uri.unmounted()
+            });
+
+            if let Some(i) = counterpart {
+                let (handler, canary) = split(Some(route.handler.clone()), canaries.remove(i));
+                route.handler = handler;
+                route.sentinels.extend(canary.sentinels);
+            }
+
+            routes.push(route);
+        }
+
+        for route in canaries {
+            let (handler, mut route) = split(None, route);
+            route.handler = handler;
+            routes.push(route);
+        }
+
+        routes
+    }
+}
+
+#[crate::async_trait]
+impl Handler for Split {
+    async fn handle<'r>(&self, req: &'r Request<'_>, data: Data<'r>) -> Outcome<'r> {
+        match (self.inner.assign(req), &self.stable) {
+            (true, _) => self.canary.handle(req, data).await,
+            (false, Some(stable)) => stable.handle(req, data).await,
+            (false, None) => Outcome::forward(data, Status::NotFound),
+        }
+    }
+}
@@ -0,0 +1,140 @@
+use std::fmt;
+use std::borrow::Cow;
+
+use figment::Figment;
+
+/// A condition under which a [`Route`](crate::Route) is mounted.
+///
+/// A route with a condition is only mounted at ignition if the condition
+/// holds: if the Cargo feature it is gated on, if any, was enabled when it was
+/// compiled and the configuration parameter it is gated on, if any, is
+/// `true`. A missing parameter is `false`; a parameter that isn't a boolean
+/// fails ignition. Routes that aren't mounted are reported as `disabled` in
+/// the routes logged at ignition and don't collide with mounted routes.
+///
+/// Conditions are usually set via `#[route_if]`, which additionally compiles
+/// a handler gated on a feature only when the feature is enabled:
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// #[route_if(config = "features.beta")]
+/// #[get("/beta")]
+/// fn beta() -> &'static str { "beta" }
+///
+/// #[route_if(feature = "preview", config = "features.preview")]
+/// #[get("/preview")]
+/// fn preview() -> &'static str { "preview" }
+/// ```
+///
+/// They may also be set directly on manually constructed routes:
+///
+/// ```rust
+/// use rocket::Route;
+/// use rocket::http::Method;
+/// use rocket::route::Condition;
+/// # use rocket::route::dummy_handler as handler;
+///
+/// let mut route = Route::new(Method::Get, "/beta", handler);
+/// route.condition = Some(Condition::new().config("features.beta"));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Condition {
+    feature: Option<(Cow<'static, str>, bool)>,
+    config: Option<Cow<'static, str>>,
+}
+
+impl Condition {
+    /// Returns a condition that always holds.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::route::Condition;
+    /// use rocket::figment::Figment;
+    ///
+    /// let condition = Condition::new();
+    /// assert!(condition.holds(&Figment::new()).unwrap());
+    /// ```
+    pub fn new() -> Self {
+        Condition::default()
+    }
+
+    /// Gates on the Cargo feature `name`, which is `enabled` or not.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::route::Condition;
+    /// use rocket::figment::Figment;
+    ///
+    /// let condition = Condition::new().feature("beta", cfg!(feature = "beta"));
+    /// assert_eq!(condition.feature_name(), Some("beta"));
+    /// assert!(!condition.holds(&Figment::new()).unwrap());
+    /// ```
+    pub fn feature<N: Into<Cow<'static, str>>>(mut self, name: N, enabled: bool) -> Self {
+        self.feature = Some((name.into(), enabled));
+        self
+    }
+
+    /// Gates on the boolean configuration parameter `key`, a dotted path.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::route::Condition;
+    /// use rocket::figment::Figment;
+    ///
+    /// let condition = Condition::new().config("features.beta");
+    /// assert_eq!(condition.config_key(), Some("features.beta"));
+    ///
+    /// let figment = Figment::new().merge(("features.beta", true));
+    /// assert!(condition.holds(&figment).unwrap());
+    /// ```
+    pub fn config<K: Into<Cow<'static, str>>>(mut self, key: K) -> Self {
+        self.config = Some(key.into());
+        self
+    }
+
+    /// Returns the name of the Cargo feature gated on, if any.
+    pub fn feature_name(&self) -> Option<&str> {
+        self.feature.as_ref().map(|(name, _)| &**name)
+    }
+
+    /// Returns the key of the configuration parameter gated on, if any.
+    pub fn config_key(&self) -> Option<&str> {
+        self.config.as_deref()
+    }
+
+    /// Returns `true` if the condition holds given the configuration
+    /// `figment`, and an error if the gating parameter isn't a boolean.
+    pub fn holds(&self, figment: &Figment) -> Result<bool, figment::Error> {
+        if let Some((_, false)) = self.feature {
+            return Ok(false);
+        }
+
+        match &self.config {
+            Some(key) if figment.contains(key) => figment.extract_inner::<bool>(key),
+            Some(_) => Ok(false),
+            None => Ok(true),
+        }
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some((name, enabled)) = &self.feature {
+            let state = if *enabled { "enabled" } else { "disabled" };
+            write!(f, "feature = {name:?} ({state})")?;
+        }
+
+        if let Some(key) = &self.config {
+            if self.feature.is_some() {
+                write!(f, ", ")?;
+            }
+
+            write!(f, "config = {key:?}")?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,38 @@
+use std::fmt;
+use std::sync::Arc;
+
+use crate::{Route, Catcher};
+use crate::router::Router;
+
+/// A snapshot of the route table of a running instance, as returned by
+/// [`Rocket::live_routes()`](crate::Rocket::live_routes()).
+///
+/// The snapshot is the table that was current when it was taken. Routes
+/// mounted or unmounted via [`Rocket::mount_live()`] and
+/// [`Rocket::unmount_live()`] afterwards aren't reflected, but a snapshot
+/// keeps the routes it contains alive while it's held.
+///
+/// [`Rocket::mount_live()`]: crate::Rocket::mount_live()
+/// [`Rocket::unmount_live()`]: crate::Rocket::unmount_live()
+#[derive(Clone)]
+pub struct LiveRoutes(pub(crate) Arc<Router>);
+
+impl LiveRoutes {
+    /// Returns an iterator over the routes in the snapshot. The order is
+    /// unspecified.
+    pub fn routes(&self) -> impl Iterator<Item = &Route> {
+        self.0.routes()
+    }
+
+    /// Returns an iterator over the catchers in the snapshot. The order is
+    /// unspecified.
+    pub fn catchers(&self) -> impl Iterator<Item = &Catcher> {
+        self.0.catchers()
+    }
+}
+
+impl fmt::Debug for LiveRoutes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
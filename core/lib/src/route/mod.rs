@@ -4,9 +4,15 @@ mod route;
 mod handler;
 mod uri;
 mod segment;
+mod condition;
+mod canary;
+mod live;
 
 pub use route::*;
 pub use handler::*;
 pub use uri::*;
+pub use condition::Condition;
+pub use canary::Canary;
+pub use live::LiveRoutes;
 
 pub(crate) use segment::Segment;
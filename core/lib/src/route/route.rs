@@ -1,9 +1,10 @@
 use std::fmt;
 use std::borrow::Cow;
 
-use crate::http::{uri, Method, MediaType};
-use crate::route::{Handler, RouteUri, BoxFuture};
+use crate::http::{uri, Method, MediaType, Header};
+use crate::route::{Handler, RouteUri, BoxFuture, Condition};
 use crate::sentinel::Sentry;
+use crate::openapi::Operation;
 
 /// A request handling route.
 ///
@@ -174,6 +175,19 @@ pub struct Route {
     pub rank: isize,
     /// The media type this route matches against, if any.
     pub format: Option<MediaType>,
+    /// The condition under which this route is mounted, if any. A route with
+    /// a condition that doesn't hold at ignition is not mounted.
+    pub condition: Option<Condition>,
+    /// Whether request bodies, and data fields of multipart forms, are
+    /// rejected when their declared `Content-Type` doesn't match the format
+    /// sniffed from their leading bytes. Set via `#[data(verify_content_type)]`.
+    pub verify_content_type: bool,
+    /// Headers set on responses to requests handled by this route, unless the
+    /// response already contains them. Set via `#[headers]`.
+    pub headers: Vec<Header<'static>>,
+    /// The route's OpenAPI operation, if it's documented. Set by the route
+    /// attributes unless `#[openapi(skip)]` is used.
+    pub operation: Option<Operation>,
     /// The discovered sentinels.
     pub(crate) sentinels: Vec<Sentry>,
     /// The file, line, and column where the route was defined, if known.
@@ -250,6 +264,10 @@ impl Route {
         Route {
             name: None,
             format: None,
+            condition: None,
+            verify_content_type: false,
+            headers: Vec::new(),
+            operation: None,
             sentinels: Vec::new(),
             handler: Box::new(handler),
             location: None,
@@ -352,6 +370,10 @@ impl fmt::Debug for Route {
             .field("uri", &self.uri)
             .field("rank", &self.rank)
             .field("format", &self.format)
+            .field("condition", &self.condition)
+            .field("verify_content_type", &self.verify_content_type)
+            .field("headers", &self.headers)
+            .field("operation", &self.operation)
             .finish()
     }
 }
@@ -374,6 +396,14 @@ pub struct StaticInfo {
     /// Route-derived sentinels, if any.
     /// This isn't `&'static [SentryInfo]` because `type_name()` isn't `const`.
     pub sentinels: Vec<Sentry>,
+    /// The condition set via `#[route_if]`, if any.
+    pub condition: Option<Condition>,
+    /// Whether `#[data(verify_content_type)]` was set.
+    pub verify_content_type: bool,
+    /// The headers set via `#[headers]`, as name-value pairs.
+    pub headers: &'static [(&'static str, &'static str)],
+    /// The OpenAPI operation, unless `#[openapi(skip)]` was set.
+    pub operation: Option<Operation>,
     /// The file, line, and column where the route was defined.
     pub location: (&'static str, u32, u32),
 }
@@ -391,6 +421,10 @@ impl From<StaticInfo> for Route {
             rank: info.rank.unwrap_or_else(|| uri.default_rank()),
             format: info.format,
             sentinels: info.sentinels.into_iter().collect(),
+            condition: info.condition,
+            verify_content_type: info.verify_content_type,
+            headers: info.headers.iter().map(|&(name, value)| Header::new(name, value)).collect(),
+            operation: info.operation,
             location: Some(info.location),
             uri,
         }
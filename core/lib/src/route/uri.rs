@@ -210,6 +210,32 @@ impl<'a> RouteUri<'a> {
         &self.unmounted_origin
     }
 
+    /// The route's path template: the path of the route URI, including the
+    /// base, with dynamic segments as written, such as `/users/<id>`.
+    ///
+    /// Unlike a request's path, the template is shared by every request the
+    /// route handles and so is suitable as a low-cardinality label in metrics
+    /// and access logs. See [`Request::route_template()`].
+    ///
+    /// [`Request::route_template()`]: crate::Request::route_template()
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::Route;
+    /// use rocket::http::Method;
+    /// # use rocket::route::dummy_handler as handler;
+    /// # use rocket::uri;
+    ///
+    /// let route = Route::new(Method::Get, "/<id>/posts/<path..>?<page>&a=1", handler);
+    /// let route = route.rebase(uri!("/users"));
+    /// assert_eq!(route.uri.template(), "/users/<id>/posts/<path..>");
+    /// ```
+    #[inline(always)]
+    pub fn template(&self) -> &str {
+        self.uri.path().as_str()
+    }
+
     /// Get the default rank of a route with this URI.
     ///
     /// The route's default rank is determined based on the presence or absence
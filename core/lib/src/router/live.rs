@@ -0,0 +1,85 @@
+use std::fmt;
+use std::sync::Arc;
+
+use arc_swap::ArcSwap;
+use parking_lot::Mutex;
+
+use crate::router::Router;
+
+/// The route table of a running instance, which can be replaced while
+/// requests are being routed.
+///
+/// Readers load the current table without locking. Writers derive a new table
+/// from the current one and swap it in atomically. Each request holds onto the
+/// table that was current when it arrived and is routed entirely against it,
+/// so a replaced table is freed when the last request holding it is dropped.
+/// Routes are shared between tables, so a table costs only its index.
+pub(crate) struct LiveRouter {
+    current: ArcSwap<Router>,
+    launched: Router,
+    update: Mutex<()>,
+}
+
+impl LiveRouter {
+    pub fn new(router: Router) -> Self {
+        let launched = router.clone();
+        LiveRouter { current: ArcSwap::from_pointee(router), launched, update: Mutex::new(()) }
+    }
+
+    /// Returns the table at launch, before any live updates.
+    #[inline]
+    pub fn launched(&self) -> &Router {
+        &self.launched
+    }
+
+    /// Returns the current table.
+    #[inline]
+    pub fn current(&self) -> Arc<Router> {
+        self.current.load_full()
+    }
+
+    /// Derives a new table from the current one via `f` and, if `f` succeeds,
+    /// makes it current. Updates are serialized, so none are lost.
+    pub fn update<T, E, F>(&self, f: F) -> Result<T, E>
+        where F: FnOnce(&mut Router) -> Result<T, E>
+    {
+        let _update = self.update.lock();
+        let mut table = Router::clone(&self.current.load());
+        let value = f(&mut table)?;
+        self.current.store(Arc::new(table));
+        Ok(value)
+    }
+
+    /// Returns the current table, consuming `self`.
+    pub fn into_inner(self) -> Router {
+        let table = self.current.into_inner();
+        Arc::try_unwrap(table).unwrap_or_else(|table| Router::clone(&table))
+    }
+}
+
+impl fmt::Debug for LiveRouter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self.current.load(), f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::{LiveRouter, Router};
+
+    #[test]
+    fn replaced_tables_are_freed() {
+        let live = LiveRouter::new(Router::new());
+        let held = live.current();
+        assert_eq!(Arc::strong_count(&held), 2);
+
+        live.update(|_| Ok::<_, ()>(())).unwrap();
+        assert_eq!(Arc::strong_count(&held), 1);
+        assert!(!Arc::ptr_eq(&held, &live.current()));
+
+        assert!(live.update(|_| Err::<(), _>(())).is_err());
+        assert_eq!(Arc::strong_count(&live.current()), 2);
+    }
+}
@@ -73,6 +73,13 @@ impl Route {
             && queries_match(self, request)
             && formats_match(self, request)
     }
+
+    /// Returns `true` if `self` would match `request` were the request's
+    /// method and format those of the route.
+    #[cfg(feature = "cors")]
+    pub(crate) fn matches_uri(&self, request: &Request<'_>) -> bool {
+        paths_match(self, request) && queries_match(self, request)
+    }
 }
 
 impl Catcher {
@@ -1,8 +1,10 @@
 //! Rocket's router.
 
 mod router;
+mod live;
 mod collider;
 mod matcher;
 
 pub(crate) use router::*;
+pub(crate) use live::*;
 pub(crate) use collider::*;
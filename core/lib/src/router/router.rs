@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::sync::Arc;
 
 use crate::request::Request;
 use crate::http::{Method, Status};
@@ -6,10 +7,12 @@ use crate::http::{Method, Status};
 use crate::{Route, Catcher};
 use crate::router::Collide;
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub(crate) struct Router {
-    routes: HashMap<Method, Vec<Route>>,
-    catchers: HashMap<Option<u16>, Vec<Catcher>>,
+    // Routes and catchers are shared so that cloning a router, as
+    // `LiveRouter` does, is cheap.
+    routes: HashMap<Method, Vec<Arc<Route>>>,
+    catchers: HashMap<Option<u16>, Vec<Arc<Catcher>>>,
 }
 
 pub type Collisions<T> = Vec<(T, T)>;
@@ -21,24 +24,37 @@ impl Router {
 
     pub fn add_route(&mut self, route: Route) {
         let routes = self.routes.entry(route.method).or_default();
-        routes.push(route);
+        routes.push(Arc::new(route));
         routes.sort_by_key(|r| r.rank);
     }
 
+    /// Removes every route satisfying `f`, returning the number removed.
+    pub fn remove_routes<F: FnMut(&Route) -> bool>(&mut self, mut f: F) -> usize {
+        let mut removed = 0;
+        for routes in self.routes.values_mut() {
+            let len = routes.len();
+            routes.retain(|route| !f(route));
+            removed += len - routes.len();
+        }
+
+        self.routes.retain(|_, routes| !routes.is_empty());
+        removed
+    }
+
     pub fn add_catcher(&mut self, catcher: Catcher) {
         let catchers = self.catchers.entry(catcher.code).or_default();
-        catchers.push(catcher);
+        catchers.push(Arc::new(catcher));
         catchers.sort_by_key(|c| c.rank);
     }
 
     #[inline]
     pub fn routes(&self) -> impl Iterator<Item = &Route> + Clone {
-        self.routes.values().flat_map(|v| v.iter())
+        self.routes.values().flat_map(|v| v.iter().map(|r| &**r))
     }
 
     #[inline]
     pub fn catchers(&self) -> impl Iterator<Item = &Catcher> + Clone {
-        self.catchers.values().flat_map(|v| v.iter())
+        self.catchers.values().flat_map(|v| v.iter().map(|c| &**c))
     }
 
     pub fn route<'r, 'a: 'r>(
@@ -48,17 +64,19 @@ impl Router {
         // Note that routes are presorted by ascending rank on each `add`.
         self.routes.get(&req.method())
             .into_iter()
-            .flat_map(move |routes| routes.iter().filter(move |r| r.matches(req)))
+            .flat_map(move |routes| routes.iter().map(|r| &**r).filter(move |r| r.matches(req)))
     }
 
     // For many catchers, using aho-corasick or similar should be much faster.
     pub fn catch<'r>(&self, status: Status, req: &'r Request<'r>) -> Option<&Catcher> {
         // Note that catchers are presorted by descending base length.
         let explicit = self.catchers.get(&Some(status.code))
-            .and_then(|c| c.iter().find(|c| c.matches(status, req)));
+            .and_then(|c| c.iter().find(|c| c.matches(status, req)))
+            .map(|c| &**c);
 
         let default = self.catchers.get(&None)
-            .and_then(|c| c.iter().find(|c| c.matches(status, req)));
+            .and_then(|c| c.iter().find(|c| c.matches(status, req)))
+            .map(|c| &**c);
 
         match (explicit, default) {
             (None, None) => None,
@@ -0,0 +1,104 @@
+use std::fmt;
+
+use serde::{Serialize, Deserialize};
+use serde_json::Value;
+
+/// An error returned by an RPC method: a JSON-RPC 2.0 error object.
+///
+/// Methods registered with [`Rpc::method()`](crate::rpc::Rpc::method()) may
+/// return any error type that converts into an `RpcError`. Implementing
+/// `From<E> for RpcError` for an application error type `E` thus maps its
+/// values to error codes. The codes from `-32768` to `-32000` are reserved by
+/// JSON-RPC; the predefined ones are available as constants.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::rpc::RpcError;
+///
+/// enum LedgerError {
+///     Overdrawn(u64),
+///     Unavailable,
+/// }
+///
+/// impl From<LedgerError> for RpcError {
+///     fn from(error: LedgerError) -> Self {
+///         match error {
+///             LedgerError::Overdrawn(by) => RpcError::new(1, "account overdrawn").data(by),
+///             LedgerError::Unavailable => RpcError::internal("ledger unavailable"),
+///         }
+///     }
+/// }
+///
+/// let error = RpcError::from(LedgerError::Overdrawn(10));
+/// assert_eq!(error.code, 1);
+/// assert_eq!(error.data, Some(10.into()));
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RpcError {
+    /// The error code.
+    pub code: i64,
+    /// A short description of the error.
+    pub message: String,
+    /// Additional information about the error, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+impl RpcError {
+    /// The request isn't valid JSON or MessagePack.
+    pub const PARSE_ERROR: i64 = -32700;
+
+    /// The request isn't a valid request object.
+    pub const INVALID_REQUEST: i64 = -32600;
+
+    /// The method doesn't exist.
+    pub const METHOD_NOT_FOUND: i64 = -32601;
+
+    /// The parameters aren't valid for the method.
+    pub const INVALID_PARAMS: i64 = -32602;
+
+    /// The method failed internally.
+    pub const INTERNAL_ERROR: i64 = -32603;
+
+    /// Returns an error with `code` and `message` and without data.
+    pub fn new<M: Into<String>>(code: i64, message: M) -> Self {
+        RpcError { code, message: message.into(), data: None }
+    }
+
+    /// Sets the additional information about the error to `data`. If `data`
+    /// fails to serialize, the error has no data.
+    pub fn data<T: Serialize>(mut self, data: T) -> Self {
+        self.data = serde_json::to_value(data).ok();
+        self
+    }
+
+    /// Returns an [`INTERNAL_ERROR`](Self::INTERNAL_ERROR) with `message`.
+    pub fn internal<M: fmt::Display>(message: M) -> Self {
+        RpcError::new(Self::INTERNAL_ERROR, message.to_string())
+    }
+
+    pub(crate) fn parse_error<M: fmt::Display>(message: M) -> Self {
+        RpcError::new(Self::PARSE_ERROR, "parse error").data(message.to_string())
+    }
+
+    pub(crate) fn invalid_request<M: fmt::Display>(message: M) -> Self {
+        RpcError::new(Self::INVALID_REQUEST, "invalid request").data(message.to_string())
+    }
+
+    pub(crate) fn method_not_found(method: &str) -> Self {
+        RpcError::new(Self::METHOD_NOT_FOUND, "method not found").data(method)
+    }
+
+    pub(crate) fn invalid_params<M: fmt::Display>(message: M) -> Self {
+        RpcError::new(Self::INVALID_PARAMS, "invalid params").data(message.to_string())
+    }
+}
+
+impl fmt::Display for RpcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.message, self.code)
+    }
+}
+
+impl std::error::Error for RpcError { }
@@ -0,0 +1,57 @@
+//! JSON-RPC 2.0 and MessagePack-RPC endpoints.
+//!
+//! An [`Rpc`] is a mountable handler that maps method names to async functions
+//! with typed parameters and results, for services that expose RPC alongside
+//! REST. When mounted, it handles `POST` requests to its mount point:
+//!
+//!   * A [JSON-RPC 2.0] request body is a call object or a batch: an array of
+//!     call objects. Calls in a batch run concurrently; their responses are
+//!     returned in an array in the same order. Calls without an `id` are
+//!     _notifications_ and aren't responded to. A body with only
+//!     notifications is responded to with `204 No Content`.
+//!
+//!   * A [MessagePack-RPC] request body, sent with a `Content-Type` of
+//!     `application/msgpack` when the `msgpack` feature is enabled, is a
+//!     request, `[0, msgid, method, params]`, responded to with `[1, msgid,
+//!     error, result]`, or a notification, `[2, method, params]`, responded to
+//!     with `204 No Content`. Errors are JSON-RPC error objects.
+//!
+//! Bodies are limited by the `json` and `msgpack` [limits](crate::data::Limits)
+//! respectively. A MessagePack-RPC body that isn't a valid message fails with
+//! `400 Bad Request`; invalid JSON-RPC calls are responded to with the
+//! appropriate JSON-RPC [error](RpcError).
+//!
+//! [JSON-RPC 2.0]: https://www.jsonrpc.org/specification
+//! [MessagePack-RPC]: https://github.com/msgpack-rpc/msgpack-rpc/blob/master/spec.md
+//!
+//! # Example
+//!
+//! ```rust
+//! use rocket::{Rocket, Build};
+//! use rocket::rpc::{Rpc, RpcError};
+//! # use rocket::local::blocking::Client;
+//! # use rocket::serde::json::{json, Value};
+//!
+//! fn rocket() -> Rocket<Build> {
+//!     let rpc = Rpc::new()
+//!         .method("subtract", |(a, b): (i64, i64)| async move {
+//!             a.checked_sub(b).ok_or_else(|| RpcError::new(1, "overflow"))
+//!         });
+//!
+//!     rocket::build().mount("/rpc", rpc)
+//! }
+//!
+//! # let client = Client::debug(rocket()).unwrap();
+//! # let response = client.post("/rpc")
+//! #     .body(r#"{ "jsonrpc": "2.0", "method": "subtract", "params": [42, 23], "id": 1 }"#)
+//! #     .dispatch();
+//! #
+//! # let response: Value = response.into_json().unwrap();
+//! # assert_eq!(response, json!({ "jsonrpc": "2.0", "result": 19, "id": 1 }));
+//! ```
+
+mod error;
+mod rpc;
+
+pub use error::RpcError;
+pub use rpc::Rpc;
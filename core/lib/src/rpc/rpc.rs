@@ -0,0 +1,277 @@
+use std::fmt;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+
+use futures::future::{self, BoxFuture};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+
+use crate::{Request, Data, Route};
+use crate::data::Limits;
+use crate::http::{ContentType, Method, Status};
+use crate::route::{Handler, Outcome};
+use crate::rpc::RpcError;
+use crate::util::Formatter;
+
+type MethodFn = dyn Fn(Value) -> BoxFuture<'static, Result<Value, RpcError>> + Send + Sync;
+
+/// A mountable JSON-RPC 2.0 and MessagePack-RPC endpoint.
+///
+/// An `Rpc` maps method names to async functions registered via
+/// [`Rpc::method()`]. When mounted, it handles `POST` requests to its mount
+/// point. See the [module level docs](crate::rpc) for details.
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::rpc::{Rpc, RpcError};
+///
+/// #[launch]
+/// fn rocket() -> _ {
+///     let rpc = Rpc::new()
+///         .method("add", |(a, b): (i64, i64)| async move { Ok::<_, RpcError>(a + b) })
+///         .method("ping", |()| async { Ok::<_, RpcError>("pong") });
+///
+///     rocket::build().mount("/rpc", rpc)
+/// }
+/// ```
+#[derive(Clone, Default)]
+pub struct Rpc {
+    methods: HashMap<Cow<'static, str>, Arc<MethodFn>>,
+}
+
+impl Rpc {
+    /// Returns an endpoint without any methods.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::rpc::Rpc;
+    ///
+    /// let rpc = Rpc::new();
+    /// assert_eq!(rpc.methods().count(), 0);
+    /// ```
+    pub fn new() -> Self {
+        Rpc::default()
+    }
+
+    /// Registers `function` as the method `name`, replacing any method of the
+    /// same name.
+    ///
+    /// When called, the method's parameters are deserialized into `P`: by
+    /// position when they're an array, as with a tuple, and by name when
+    /// they're an object, as with a struct. Absent parameters are `null`, from
+    /// which `()` deserializes. Parameters that fail to deserialize result in an
+    /// [`INVALID_PARAMS`](RpcError::INVALID_PARAMS) error. The result of the
+    /// method is serialized into the response; an error is converted into an
+    /// [`RpcError`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::rpc::{Rpc, RpcError};
+    /// use rocket::serde::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// # #[serde(crate = "rocket::serde")]
+    /// struct Greet { name: String }
+    ///
+    /// let rpc = Rpc::new()
+    ///     .method("greet", |p: Greet| async move {
+    ///         Ok::<_, RpcError>(format!("Hello, {}!", p.name))
+    ///     });
+    ///
+    /// assert_eq!(rpc.methods().collect::<Vec<_>>(), ["greet"]);
+    /// ```
+    pub fn method<N, P, R, E, F, Fut>(mut self, name: N, function: F) -> Self
+        where N: Into<Cow<'static, str>>,
+              P: DeserializeOwned,
+              R: Serialize,
+              E: Into<RpcError>,
+              F: Fn(P) -> Fut + Send + Sync + 'static,
+              Fut: Future<Output = Result<R, E>> + Send + 'static,
+    {
+        let method = move |params: Value| -> BoxFuture<'static, Result<Value, RpcError>> {
+            let future = match serde_json::from_value::<P>(params) {
+                Ok(params) => function(params),
+                Err(e) => return Box::pin(future::ready(Err(RpcError::invalid_params(e)))),
+            };
+
+            Box::pin(async move {
+                let result = future.await.map_err(Into::<RpcError>::into)?;
+                serde_json::to_value(result).map_err(RpcError::internal)
+            })
+        };
+
+        self.methods.insert(name.into(), Arc::new(method));
+        self
+    }
+
+    /// Returns the names of the registered methods, in no particular order.
+    pub fn methods(&self) -> impl Iterator<Item = &str> {
+        self.methods.keys().map(|name| &**name)
+    }
+
+    async fn invoke(&self, method: &str, params: Value) -> Result<Value, RpcError> {
+        match self.methods.get(method) {
+            Some(function) => function(params).await,
+            None => Err(RpcError::method_not_found(method)),
+        }
+    }
+
+    /// Handles a JSON-RPC request body. Returns `None` if there's nothing to
+    /// respond with: the request only contained notifications.
+    async fn handle_json(&self, body: &[u8]) -> Option<Value> {
+        let request = match serde_json::from_slice::<Value>(body) {
+            Ok(request) => request,
+            Err(e) => return Some(response(Value::Null, Err(RpcError::parse_error(e)))),
+        };
+
+        match request {
+            Value::Array(calls) if calls.is_empty() => {
+                let error = RpcError::invalid_request("empty batch");
+                Some(response(Value::Null, Err(error)))
+            }
+            Value::Array(calls) => {
+                let calls = calls.into_iter().map(|call| self.call(call));
+                let responses: Vec<_> = future::join_all(calls).await
+                    .into_iter()
+                    .flatten()
+                    .collect();
+
+                (!responses.is_empty()).then_some(Value::Array(responses))
+            }
+            call => self.call(call).await,
+        }
+    }
+
+    /// Handles a single JSON-RPC call, returning its response, if any.
+    async fn call(&self, call: Value) -> Option<Value> {
+        let Value::Object(mut call) = call else {
+            let error = RpcError::invalid_request("expected an object");
+            return Some(response(Value::Null, Err(error)));
+        };
+
+        let id = match call.remove("id") {
+            Some(id @ (Value::String(_) | Value::Number(_) | Value::Null)) => Some(id),
+            Some(_) => {
+                let error = RpcError::invalid_request("invalid `id`");
+                return Some(response(Value::Null, Err(error)));
+            }
+            None => None,
+        };
+
+        let params = call.remove("params").unwrap_or(Value::Null);
+        let valid_params = matches!(params, Value::Array(_) | Value::Object(_) | Value::Null);
+        let result = match (call.get("jsonrpc"), call.get("method"), valid_params) {
+            (Some(v), _, _) if *v != "2.0" => Err(RpcError::invalid_request("expected `2.0`")),
+            (None, _, _) => Err(RpcError::invalid_request("missing `jsonrpc`")),
+            (_, Some(Value::String(method)), true) => self.invoke(method, params).await,
+            (_, Some(Value::String(_)), false) => {
+                Err(RpcError::invalid_request("invalid `params`"))
+            }
+            (_, _, _) => Err(RpcError::invalid_request("invalid `method`")),
+        };
+
+        if let Err(e) = &result {
+            let method = call.get("method").and_then(|m| m.as_str()).unwrap_or_default();
+            debug!(name: "rpc", method, code = e.code, message = %e.message, "call failed");
+        }
+
+        // Notifications are never responded to, even when they fail.
+        id.map(|id| response(id, result))
+    }
+
+    /// Handles a MessagePack-RPC request body: `[0, msgid, method, params]`
+    /// for requests and `[2, method, params]` for notifications. Returns
+    /// `Err` if the body isn't a valid request and `Ok(None)` if it's a
+    /// notification.
+    #[cfg(feature = "msgpack")]
+    async fn handle_msgpack(&self, body: &[u8]) -> Result<Option<Vec<u8>>, RpcError> {
+        let message: Vec<Value> = rmp_serde::from_slice(body).map_err(RpcError::parse_error)?;
+        let message = match &message[..] {
+            [kind, id, Value::String(method), Value::Array(_)] if kind == 0 => {
+                Some((id.clone(), method.clone(), message[3].clone()))
+            }
+            [kind, Value::String(method), params @ Value::Array(_)] if kind == 2 => {
+                let _ = self.invoke(method, params.clone()).await;
+                None
+            }
+            _ => return Err(RpcError::invalid_request("expected a request or notification")),
+        };
+
+        let Some((id, method, params)) = message else {
+            return Ok(None);
+        };
+
+        let response = match self.invoke(&method, params).await {
+            Ok(result) => json!([1, id, null, result]),
+            Err(error) => json!([1, id, error, null]),
+        };
+
+        rmp_serde::to_vec(&response).map(Some).map_err(RpcError::internal)
+    }
+}
+
+/// A JSON-RPC 2.0 response object for the call with `id`.
+fn response(id: Value, result: Result<Value, RpcError>) -> Value {
+    match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "result": result, "id": id }),
+        Err(error) => json!({ "jsonrpc": "2.0", "error": error, "id": id }),
+    }
+}
+
+impl From<Rpc> for Vec<Route> {
+    fn from(rpc: Rpc) -> Self {
+        let mut route = Route::new(Method::Post, "/", rpc);
+        route.name = Some("Rpc".into());
+        vec![route]
+    }
+}
+
+#[crate::async_trait]
+impl Handler for Rpc {
+    async fn handle<'r>(&self, req: &'r Request<'_>, data: Data<'r>) -> Outcome<'r> {
+        #[cfg(feature = "msgpack")]
+        if req.content_type().map_or(false, |ct| ct.is_msgpack()) {
+            let limit = req.limits().get("msgpack").unwrap_or(Limits::MESSAGE_PACK);
+            let body = match data.open(limit).into_bytes().await {
+                Ok(body) if body.is_complete() => body.into_inner(),
+                Ok(_) => return Outcome::error(Status::PayloadTooLarge),
+                Err(_) => return Outcome::error(Status::BadRequest),
+            };
+
+            return match self.handle_msgpack(&body).await {
+                Ok(Some(response)) => Outcome::from(req, (ContentType::MsgPack, response)),
+                Ok(None) => Outcome::from(req, Status::NoContent),
+                Err(e) => {
+                    debug!(name: "rpc", code = e.code, message = %e.message, "invalid request");
+                    Outcome::error(Status::BadRequest)
+                }
+            };
+        }
+
+        let limit = req.limits().get("json").unwrap_or(Limits::JSON);
+        let body = match data.open(limit).into_bytes().await {
+            Ok(body) if body.is_complete() => body.into_inner(),
+            Ok(_) => return Outcome::error(Status::PayloadTooLarge),
+            Err(_) => return Outcome::error(Status::BadRequest),
+        };
+
+        match self.handle_json(&body).await {
+            Some(response) => Outcome::from(req, (ContentType::JSON, response.to_string())),
+            None => Outcome::from(req, Status::NoContent),
+        }
+    }
+}
+
+impl fmt::Debug for Rpc {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let methods = Formatter(|f| f.debug_list().entries(self.methods()).finish());
+        f.debug_struct("Rpc").field("methods", &methods).finish()
+    }
+}
@@ -1,7 +1,8 @@
 use std::fmt;
 use std::any::TypeId;
+use std::borrow::Cow;
 
-use crate::{Rocket, Ignite};
+use crate::{Rocket, Ignite, Route};
 
 /// An automatic last line of defense against launching an invalid [`Rocket`].
 ///
@@ -275,15 +276,52 @@ use crate::{Rocket, Ignite};
 /// If a `MyResponder` is returned by any mounted route, its `abort()` method
 /// will be invoked. If the required conditions aren't met, signaled by
 /// returning `true` from `abort()`, Rocket aborts launch.
+///
+/// # Diagnostics
+///
+/// When a sentinel aborts, Rocket reports a [`Diagnostic`] listing the routes
+/// that depend on the sentinel along with a suggestion. A sentinel can make
+/// the suggestion actionable by describing what it found [`Missing`] and which
+/// fairing usually provides it:
+///
+/// ```rust
+/// use rocket::{Rocket, Ignite, Sentinel};
+/// use rocket::sentinel::Missing;
+/// # struct MyResponder;
+/// # struct T;
+///
+/// impl Sentinel for MyResponder {
+///     fn abort(rocket: &Rocket<Ignite>) -> bool {
+///         rocket.state::<T>().is_none()
+///     }
+///
+///     fn missing(rocket: &Rocket<Ignite>) -> Option<Missing> {
+///         rocket.state::<T>().is_none().then(|| Missing::new::<T>().provider("TFairing"))
+///     }
+/// }
+/// ```
+///
+/// Applications and [route packs](crate::pack) can additionally register the
+/// providers of types via [`Rocket::provider()`].
 pub trait Sentinel {
     /// Returns `true` if launch should be aborted and `false` otherwise.
     fn abort(rocket: &Rocket<Ignite>) -> bool;
+
+    /// Returns what is missing, if anything, for diagnosing an abort. The
+    /// default implementation returns `None`.
+    fn missing(_: &Rocket<Ignite>) -> Option<Missing> {
+        None
+    }
 }
 
 impl<T: Sentinel> Sentinel for Option<T> {
     fn abort(rocket: &Rocket<Ignite>) -> bool {
         T::abort(rocket)
     }
+
+    fn missing(rocket: &Rocket<Ignite>) -> Option<Missing> {
+        T::missing(rocket)
+    }
 }
 
 // In the next impls, we want to run _both_ sentinels _without_ short
@@ -299,6 +337,9 @@ impl<T: Sentinel, E: Sentinel> Sentinel for Result<T, E> {
         let right = E::abort(rocket);
         left || right
     }
+    fn missing(rocket: &Rocket<Ignite>) -> Option<Missing> {
+        T::missing(rocket).or_else(|| E::missing(rocket))
+    }
 }
 
 impl<T: Sentinel, E: Sentinel> Sentinel for either::Either<T, E> {
@@ -307,6 +348,9 @@ impl<T: Sentinel, E: Sentinel> Sentinel for either::Either<T, E> {
         let right = E::abort(rocket);
         left || right
     }
+    fn missing(rocket: &Rocket<Ignite>) -> Option<Missing> {
+        T::missing(rocket).or_else(|| E::missing(rocket))
+    }
 }
 
 /// A sentinel that never aborts. The `Responder` impl for `Debug` will never be
@@ -350,6 +394,9 @@ pub struct Sentry {
     /// The value of `<T as Sentinel>::abort` or the fallback.
     #[doc(hidden)]
     pub abort: fn(&Rocket<Ignite>) -> bool,
+    /// The value of `<T as Sentinel>::missing` or the fallback.
+    #[doc(hidden)]
+    pub missing: fn(&Rocket<Ignite>) -> Option<Missing>,
 }
 
 impl Sentry {
@@ -424,6 +471,205 @@ pub(crate) fn query<'s>(
     }
 }
 
+/// What a [`Sentinel`] found missing when it triggered an abort.
+///
+/// Returned by [`Sentinel::missing()`] and reported in a [`Diagnostic`].
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::sentinel::Missing;
+///
+/// struct PgPool;
+///
+/// let missing = Missing::new::<PgPool>()
+///     .provider("Db::init()")
+///     .suggestion("attach `Db::init()` and configure `databases.db`");
+///
+/// assert!(missing.type_name().ends_with("PgPool"));
+/// assert_eq!(missing.get_provider(), Some("Db::init()"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Missing {
+    type_id: TypeId,
+    type_name: &'static str,
+    provider: Option<Cow<'static, str>>,
+    suggestion: Option<Cow<'static, str>>,
+}
+
+/// A diagnostic for a [`Sentinel`] that triggered an abort.
+///
+/// Diagnostics are reported when ignition fails due to sentinel aborts and
+/// are available via [`Error::sentinel_diagnostics()`]. Each identifies the
+/// routes that depend on the sentinel, the missing type, if known, its
+/// providers, and a suggestion.
+///
+/// [`Error::sentinel_diagnostics()`]: crate::Error::sentinel_diagnostics()
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::State;
+///
+/// struct PgPool;
+///
+/// #[get("/")]
+/// fn index(pool: &State<PgPool>) { }
+///
+/// # rocket::async_test(async {
+/// let rocket = rocket::build()
+///     .mount("/", routes![index])
+///     .provider::<PgPool>("BillingPack");
+///
+/// let error = rocket.ignite().await.unwrap_err();
+/// let diagnostic = &error.sentinel_diagnostics()[0];
+/// assert_eq!(diagnostic.routes()[0].name.as_deref(), Some("index"));
+/// assert!(diagnostic.missing().unwrap().type_name().ends_with("PgPool"));
+/// assert!(diagnostic.suggestion().starts_with("attach BillingPack to provide"));
+/// # });
+/// ```
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    sentry: Sentry,
+    routes: Vec<Route>,
+    missing: Option<Missing>,
+    providers: Vec<Provider>,
+}
+
+/// A provider of a type, registered via [`Rocket::provider()`] or by an
+/// attached [`RoutePack`](crate::pack::RoutePack).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Provider {
+    pub(crate) type_id: TypeId,
+    pub(crate) name: Cow<'static, str>,
+    pub(crate) attached: bool,
+}
+
+impl Missing {
+    /// Returns a `Missing` for the type `T`.
+    pub fn new<T: ?Sized + 'static>() -> Self {
+        Missing {
+            type_id: TypeId::of::<T>(),
+            type_name: std::any::type_name::<T>(),
+            provider: None,
+            suggestion: None,
+        }
+    }
+
+    /// Sets the name of what usually provides the missing type, such as a
+    /// fairing, to `provider`.
+    pub fn provider<P: Into<Cow<'static, str>>>(mut self, provider: P) -> Self {
+        self.provider = Some(provider.into());
+        self
+    }
+
+    /// Sets the suggestion for providing the missing type to `suggestion`.
+    pub fn suggestion<S: Into<Cow<'static, str>>>(mut self, suggestion: S) -> Self {
+        self.suggestion = Some(suggestion.into());
+        self
+    }
+
+    /// Returns the type ID of the missing type.
+    pub fn type_id(&self) -> TypeId {
+        self.type_id
+    }
+
+    /// Returns the name of the missing type.
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// Returns the name of what usually provides the missing type, if set.
+    pub fn get_provider(&self) -> Option<&str> {
+        self.provider.as_deref()
+    }
+
+    /// Returns the suggestion for providing the missing type, if set.
+    pub fn get_suggestion(&self) -> Option<&str> {
+        self.suggestion.as_deref()
+    }
+}
+
+impl Diagnostic {
+    /// Returns the sentinel that triggered the abort.
+    pub fn sentry(&self) -> &Sentry {
+        &self.sentry
+    }
+
+    /// Returns the mounted routes that depend on the sentinel.
+    pub fn routes(&self) -> &[Route] {
+        &self.routes
+    }
+
+    /// Returns what the sentinel found missing, if it reported it.
+    pub fn missing(&self) -> Option<&Missing> {
+        self.missing.as_ref()
+    }
+
+    /// Returns the names of the known providers of the missing type: those
+    /// registered via [`Rocket::provider()`] and by route packs, then the one
+    /// reported by the sentinel.
+    pub fn providers(&self) -> impl Iterator<Item = &str> {
+        let registered = self.providers.iter().map(|p| &*p.name);
+        let reported = self.missing.as_ref().and_then(|m| m.get_provider());
+        registered.chain(reported.filter(|r| !self.providers.iter().any(|p| p.name == *r)))
+    }
+
+    /// Returns a suggestion for resolving the abort.
+    pub fn suggestion(&self) -> String {
+        let Some(missing) = &self.missing else {
+            return format!("see the documentation of `{}` for its requirements",
+                self.sentry.type_name);
+        };
+
+        let type_name = missing.type_name;
+        let unattached: Vec<_> = self.providers.iter()
+            .filter(|p| !p.attached)
+            .map(|p| &*p.name)
+            .chain(missing.get_provider().filter(|r| !self.providers.iter().any(|p| p.name == *r)))
+            .collect();
+
+        if !unattached.is_empty() {
+            return format!("attach {} to provide `{type_name}`", unattached.join(" or "));
+        }
+
+        if let Some(p) = self.providers.first() {
+            return format!("`{type_name}` should be provided by {}, which is attached: \
+                ensure it is enabled and configured", p.name);
+        }
+
+        match missing.get_suggestion() {
+            Some(suggestion) => suggestion.to_string(),
+            None => format!("provide `{type_name}` before ignition"),
+        }
+    }
+}
+
+/// Returns a diagnostic for each of the `aborted` sentinels.
+pub(crate) fn diagnose(
+    aborted: &[Sentry],
+    rocket: &Rocket<Ignite>,
+    providers: &[Provider],
+) -> Vec<Diagnostic> {
+    aborted.iter()
+        .map(|sentry| {
+            let missing = (sentry.missing)(rocket);
+            let routes = rocket.router.routes()
+                .filter(|r| r.sentinels.iter().any(|s| s.type_id == sentry.type_id))
+                .cloned()
+                .collect();
+
+            let providers = providers.iter()
+                .filter(|p| missing.as_ref().map_or(false, |m| m.type_id == p.type_id))
+                .cloned()
+                .collect();
+
+            Diagnostic { sentry: *sentry, routes, missing, providers }
+        })
+        .collect()
+}
+
 impl fmt::Debug for Sentry {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Sentry")
@@ -452,6 +698,7 @@ macro_rules! resolve {
             location: (std::file!(), std::line!(), std::column!()),
             specialized: Resolve::<$T>::SPECIALIZED,
             abort: Resolve::<$T>::abort,
+            missing: Resolve::<$T>::missing,
         }
     })
 }
@@ -477,6 +724,8 @@ pub mod resolution {
         const SPECIALIZED: bool = false;
 
         fn abort(_: &Rocket<Ignite>) -> bool { false }
+
+        fn missing(_: &Rocket<Ignite>) -> Option<Missing> { None }
     }
 
     impl<T: ?Sized> DefaultSentinel for T {}
@@ -489,6 +738,10 @@ pub mod resolution {
         pub fn abort(rocket: &Rocket<Ignite>) -> bool {
             T::abort(rocket)
         }
+
+        pub fn missing(rocket: &Rocket<Ignite>) -> Option<Missing> {
+            T::missing(rocket)
+        }
     }
 }
 
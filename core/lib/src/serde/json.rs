@@ -1,6 +1,11 @@
 //! Automatic JSON (de)serialization support.
 //!
-//! See [`Json`] for details.
+//! See [`Json`] for details. For `PATCH` requests, the [`JsonPatch`] and
+//! [`MergePatch`] data guards parse and apply RFC 6902 JSON Patch and RFC 7386
+//! JSON Merge Patch documents, respectively. For sparse fieldsets, as in
+//! `?fields=id,name`, the [`Sparse`] responder serializes only the fields
+//! selected by the [`Fields`] query guard. For hypermedia APIs, the [`Hal`]
+//! responder serializes a resource with HAL links and embedded resources.
 //!
 //! # Enabling
 //!
@@ -39,6 +44,17 @@ use serde::{Serialize, Deserialize};
 #[doc(hidden)]
 pub use serde_json;
 
+mod patch;
+mod sparse;
+mod hal;
+
+pub use patch::{JsonPatch, MergePatch, Operation, Pointer, PatchError};
+pub use hal::{Hal, Link};
+pub use sparse::{Fields, Fieldset, Sparse};
+
+#[doc(inline)]
+pub use rocket_codegen::Fieldset;
+
 /// The JSON guard: easily consume and return JSON.
 ///
 /// ## Sending JSON
@@ -0,0 +1,364 @@
+use std::fmt;
+
+use indexmap::IndexMap;
+use serde::{Serialize, Serializer};
+use serde::ser::{self, SerializeMap};
+
+use crate::request::Request;
+use crate::response::{self, Responder};
+use crate::http::{ContentType, Status};
+use crate::serde::json::{Value, serde_json};
+
+/// A HAL ([JSON Hypertext Application Language]) resource responder.
+///
+/// A `Hal<T>` serializes a `T`, which must serialize as a JSON object, along
+/// with the reserved `_links` and `_embedded` members. Links are typically
+/// built from typed URIs, the output of [`uri!`](crate::uri!), which keeps them
+/// in sync with routes. The response has a `Content-Type` of
+/// `application/hal+json`.
+///
+/// A relation with one link or embedded resource serializes as an object, and
+/// one with several as an array. Use [`Hal::links()`] and [`Hal::embed_all()`]
+/// to always serialize an array.
+///
+/// [JSON Hypertext Application Language]:
+///     https://datatracker.ietf.org/doc/html/draft-kelly-json-hal
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::serde::Serialize;
+/// use rocket::serde::json::{Hal, Link};
+///
+/// #[derive(Serialize)]
+/// #[serde(crate = "rocket::serde")]
+/// struct Order { id: usize, total: f64 }
+///
+/// #[derive(Serialize)]
+/// #[serde(crate = "rocket::serde")]
+/// struct Customer { id: usize, name: &'static str }
+///
+/// #[get("/customers/<id>")]
+/// fn customer(id: usize) -> Hal<Customer> {
+///     Hal::new(Customer { id, name: "bob" })
+///         .link("self", uri!(customer(id)))
+/// }
+///
+/// #[get("/orders/<id>")]
+/// fn order(id: usize) -> Hal<Order> {
+///     let bob = customer(7);
+///     Hal::new(Order { id, total: 30.0 })
+///         .link("self", uri!(order(id)))
+///         .link("customer", Link::new(uri!(customer(7))).title("Bob"))
+///         .embed("customer", bob)
+/// }
+/// ```
+///
+/// The response to `GET /orders/1` is:
+///
+/// ```json
+/// {
+///   "id": 1,
+///   "total": 30.0,
+///   "_links": {
+///     "self": { "href": "/orders/1" },
+///     "customer": { "href": "/customers/7", "title": "Bob" }
+///   },
+///   "_embedded": {
+///     "customer": {
+///       "id": 7,
+///       "name": "bob",
+///       "_links": { "self": { "href": "/customers/7" } }
+///     }
+///   }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Hal<T> {
+    value: T,
+    links: IndexMap<String, Relation<Link>>,
+    embedded: IndexMap<String, Relation<Value>>,
+    /// The first error serializing an embedded resource, if any.
+    error: Option<String>,
+}
+
+/// A HAL link object.
+///
+/// A link is built from its target, typically a typed URI, via [`Link::new()`]
+/// or `From`. The optional members of the link object are set via the builder
+/// methods.
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::serde::json::{json, Link};
+///
+/// #[get("/items/<id>")]
+/// fn item(id: usize) { /* .. */ }
+///
+/// let link = Link::new(uri!(item(10))).title("Item 10");
+/// assert_eq!(link.href(), "/items/10");
+///
+/// let link = Link::new("/items{?page}").templated(true);
+/// assert_eq!(rocket::serde::json::to_value(&link).unwrap(), json!({
+///     "href": "/items{?page}",
+///     "templated": true
+/// }));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Link {
+    href: String,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    templated: bool,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    media_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    deprecation: Option<String>,
+}
+
+/// The links or embedded resources of a relation.
+#[derive(Debug, Clone)]
+enum Relation<T> {
+    One(T),
+    Many(Vec<T>),
+}
+
+impl<T> Hal<T> {
+    /// Returns a HAL resource with the state `value` and no links or embedded
+    /// resources.
+    pub fn new(value: T) -> Self {
+        Hal { value, links: IndexMap::new(), embedded: IndexMap::new(), error: None }
+    }
+
+    /// Adds `link` to the links of the relation `rel`.
+    pub fn link<R, L>(mut self, rel: R, link: L) -> Self
+        where R: Into<String>, L: Into<Link>
+    {
+        add(&mut self.links, rel.into(), link.into());
+        self
+    }
+
+    /// Adds `links` to the links of the relation `rel`, which always
+    /// serializes as an array.
+    pub fn links<R, I>(mut self, rel: R, links: I) -> Self
+        where R: Into<String>, I: IntoIterator, I::Item: Into<Link>
+    {
+        let relation = self.links.entry(rel.into()).or_insert(Relation::Many(vec![]));
+        links.into_iter().for_each(|link| relation.push(link.into()));
+        self
+    }
+
+    /// Embeds `resource` in the relation `rel`.
+    ///
+    /// If `resource` fails to serialize, so does `self`: responding with it
+    /// fails with `500 Internal Server Error`.
+    pub fn embed<R: Into<String>, U: Serialize>(mut self, rel: R, resource: U) -> Self {
+        if let Some(value) = self.to_value(&resource) {
+            add(&mut self.embedded, rel.into(), value);
+        }
+
+        self
+    }
+
+    /// Embeds `resources` in the relation `rel`, which always serializes as an
+    /// array.
+    ///
+    /// If any of `resources` fails to serialize, so does `self`: responding
+    /// with it fails with `500 Internal Server Error`.
+    pub fn embed_all<R, I>(mut self, rel: R, resources: I) -> Self
+        where R: Into<String>, I: IntoIterator, I::Item: Serialize
+    {
+        let values: Vec<_> = resources.into_iter().filter_map(|r| self.to_value(&r)).collect();
+        let relation = self.embedded.entry(rel.into()).or_insert(Relation::Many(vec![]));
+        values.into_iter().for_each(|value| relation.push(value));
+        self
+    }
+
+    /// Returns the resource's state.
+    pub fn get_ref(&self) -> &T {
+        &self.value
+    }
+
+    /// Consumes `self` and returns the resource's state.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+/// Adds `item` to the relation `rel` in `map`.
+fn add<T>(map: &mut IndexMap<String, Relation<T>>, rel: String, item: T) {
+    match map.get_mut(&rel) {
+        Some(relation) => relation.push(item),
+        None => { map.insert(rel, Relation::One(item)); }
+    }
+}
+
+impl<T> Hal<T> {
+    /// Serializes the embedded `resource`, recording the first error.
+    fn to_value<U: Serialize>(&mut self, resource: &U) -> Option<Value> {
+        match serde_json::to_value(resource) {
+            Ok(value) => Some(value),
+            Err(e) => {
+                self.error.get_or_insert_with(|| e.to_string());
+                None
+            }
+        }
+    }
+}
+
+impl Link {
+    /// Returns a link to `href`.
+    pub fn new<H: fmt::Display>(href: H) -> Self {
+        Link {
+            href: href.to_string(),
+            templated: false,
+            media_type: None,
+            name: None,
+            title: None,
+            deprecation: None,
+        }
+    }
+
+    /// Returns the link's target.
+    pub fn href(&self) -> &str {
+        &self.href
+    }
+
+    /// Sets whether the target is a URI template.
+    pub fn templated(mut self, templated: bool) -> Self {
+        self.templated = templated;
+        self
+    }
+
+    /// Sets the expected media type of the target.
+    pub fn media_type<S: Into<String>>(mut self, media_type: S) -> Self {
+        self.media_type = Some(media_type.into());
+        self
+    }
+
+    /// Sets the name, which distinguishes links of the same relation.
+    pub fn name<S: Into<String>>(mut self, name: S) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Sets the human-readable title.
+    pub fn title<S: Into<String>>(mut self, title: S) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Marks the link as deprecated, with `info` describing the deprecation.
+    pub fn deprecation<S: Into<String>>(mut self, info: S) -> Self {
+        self.deprecation = Some(info.into());
+        self
+    }
+}
+
+macro_rules! impl_link_from_uri {
+    ($($T:ident),*) => ($(
+        impl From<crate::http::uri::$T<'_>> for Link {
+            fn from(uri: crate::http::uri::$T<'_>) -> Self {
+                Link::new(uri)
+            }
+        }
+    )*)
+}
+
+impl_link_from_uri!(Origin, Absolute, Reference);
+
+impl From<&str> for Link {
+    fn from(href: &str) -> Self {
+        Link::new(href)
+    }
+}
+
+impl From<String> for Link {
+    fn from(href: String) -> Self {
+        Link::new(href)
+    }
+}
+
+impl<T> Relation<T> {
+    fn push(&mut self, item: T) {
+        let items = match std::mem::replace(self, Relation::Many(vec![])) {
+            Relation::One(first) => vec![first, item],
+            Relation::Many(mut items) => {
+                items.push(item);
+                items
+            }
+        };
+
+        *self = Relation::Many(items);
+    }
+}
+
+impl<T: Serialize> Serialize for Relation<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Relation::One(item) => item.serialize(serializer),
+            Relation::Many(items) => items.serialize(serializer),
+        }
+    }
+}
+
+impl<T: Serialize> Serialize for Hal<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct Resource<'a, T> {
+            #[serde(flatten)]
+            value: &'a T,
+            #[serde(flatten)]
+            reserved: Reserved<'a>,
+        }
+
+        struct Reserved<'a> {
+            links: &'a IndexMap<String, Relation<Link>>,
+            embedded: &'a IndexMap<String, Relation<Value>>,
+        }
+
+        impl Serialize for Reserved<'_> {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                let mut map = serializer.serialize_map(None)?;
+                if !self.links.is_empty() {
+                    map.serialize_entry("_links", self.links)?;
+                }
+
+                if !self.embedded.is_empty() {
+                    map.serialize_entry("_embedded", self.embedded)?;
+                }
+
+                map.end()
+            }
+        }
+
+        if let Some(error) = &self.error {
+            return Err(ser::Error::custom(format!("embedded resource: {error}")));
+        }
+
+        let reserved = Reserved { links: &self.links, embedded: &self.embedded };
+        Resource { value: &self.value, reserved }.serialize(serializer)
+    }
+}
+
+/// Serializes the resource into JSON. Returns a response with Content-Type
+/// `application/hal+json` and a fixed-size body with the serialized resource.
+/// If serialization fails, an `Err` of `Status::InternalServerError` is
+/// returned.
+impl<'r, T: Serialize> Responder<'r, 'static> for Hal<T> {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
+        let string = serde_json::to_string(&self)
+            .map_err(|e| {
+                error!("JSON serialize failure: {}", e);
+                Status::InternalServerError
+            })?;
+
+        (ContentType::new("application", "hal+json"), string).respond_to(req)
+    }
+}
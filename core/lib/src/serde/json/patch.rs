@@ -0,0 +1,521 @@
+use std::{fmt, error};
+use std::marker::PhantomData;
+
+use serde::{Serialize, Deserialize};
+use serde::de::DeserializeOwned;
+
+use crate::request::Request;
+use crate::data::{Data, FromData, Outcome};
+use crate::serde::json::{Json, Error, Value, serde_json};
+
+/// A JSON Patch ([RFC 6902]) data guard.
+///
+/// A `JsonPatch<T>` is a sequence of [`Operation`]s that modify a JSON
+/// document. Each operation is validated as the request body is parsed: a
+/// patch with an unknown operation, a missing member, or an invalid JSON
+/// Pointer is rejected with `422 Unprocessable Entity`. Failures are otherwise
+/// identical to those of [`Json`].
+///
+/// The patch is applied to a `T` via [`JsonPatch::apply()`]. The `T` is
+/// serialized to JSON, patched, and deserialized. The patch is applied
+/// atomically: if any operation fails, the `T` is left as it was and a
+/// [`PatchError`] identifying the operation is returned.
+///
+/// [RFC 6902]: https://datatracker.ietf.org/doc/html/rfc6902
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::http::Status;
+/// use rocket::serde::{Serialize, Deserialize};
+/// use rocket::serde::json::{Json, JsonPatch};
+///
+/// #[derive(Serialize, Deserialize)]
+/// #[serde(crate = "rocket::serde")]
+/// struct User {
+///     name: String,
+///     tags: Vec<String>,
+/// }
+///
+/// # fn load(id: usize) -> User { User { name: "bob".into(), tags: vec![] } }
+/// #[patch("/users/<id>", data = "<patch>")]
+/// fn patch(id: usize, patch: JsonPatch<User>) -> Result<Json<User>, Status> {
+///     let user = load(id);
+///     patch.apply(&user).map(Json).map_err(|_| Status::Conflict)
+/// }
+/// ```
+pub struct JsonPatch<T> {
+    operations: Vec<Operation>,
+    _target: PhantomData<fn() -> T>,
+}
+
+/// A JSON Merge Patch ([RFC 7386]) data guard.
+///
+/// A `MergePatch<T>` is a JSON document describing changes to a JSON
+/// document: members of objects in the patch replace those in the target,
+/// members that are `null` remove those in the target, and any other value
+/// replaces the target entirely. Any JSON document is a valid merge patch.
+/// Failures to parse are identical to those of [`Json`].
+///
+/// The patch is applied to a `T` via [`MergePatch::apply()`], which fails only
+/// if the patched document isn't a valid `T`.
+///
+/// [RFC 7386]: https://datatracker.ietf.org/doc/html/rfc7386
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::http::Status;
+/// use rocket::serde::{Serialize, Deserialize};
+/// use rocket::serde::json::{Json, MergePatch};
+///
+/// #[derive(Serialize, Deserialize)]
+/// #[serde(crate = "rocket::serde")]
+/// struct User {
+///     name: String,
+///     email: Option<String>,
+/// }
+///
+/// # fn load(id: usize) -> User { User { name: "bob".into(), email: None } }
+/// #[patch("/users/<id>", format = "application/merge-patch+json", data = "<patch>")]
+/// fn patch(id: usize, patch: MergePatch<User>) -> Result<Json<User>, Status> {
+///     let user = load(id);
+///     patch.apply(&user).map(Json).map_err(|_| Status::UnprocessableEntity)
+/// }
+/// ```
+pub struct MergePatch<T> {
+    patch: Value,
+    _target: PhantomData<fn() -> T>,
+}
+
+/// A JSON Patch operation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum Operation {
+    /// Adds `value` at `path`, inserting it if `path` is an array index.
+    Add {
+        /// The location to add to.
+        path: Pointer,
+        /// The value to add.
+        value: Value,
+    },
+    /// Removes the value at `path`.
+    Remove {
+        /// The location to remove.
+        path: Pointer,
+    },
+    /// Replaces the value at `path` with `value`.
+    Replace {
+        /// The location to replace.
+        path: Pointer,
+        /// The replacement.
+        value: Value,
+    },
+    /// Removes the value at `from` and adds it at `path`.
+    Move {
+        /// The location to move from.
+        from: Pointer,
+        /// The location to move to.
+        path: Pointer,
+    },
+    /// Adds a copy of the value at `from` at `path`.
+    Copy {
+        /// The location to copy from.
+        from: Pointer,
+        /// The location to copy to.
+        path: Pointer,
+    },
+    /// Fails unless the value at `path` is equal to `value`.
+    Test {
+        /// The location to test.
+        path: Pointer,
+        /// The expected value.
+        value: Value,
+    },
+}
+
+/// A valid JSON Pointer ([RFC 6901]).
+///
+/// A pointer is either empty, referring to the whole document, or a sequence
+/// of `/`-prefixed reference tokens in which `~` is only used in the escapes
+/// `~0` and `~1`.
+///
+/// [RFC 6901]: https://datatracker.ietf.org/doc/html/rfc6901
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::serde::json::Pointer;
+///
+/// let pointer = Pointer::parse("/a~1b/0").unwrap();
+/// assert_eq!(pointer.tokens().collect::<Vec<_>>(), ["a/b", "0"]);
+///
+/// assert!(Pointer::parse("a").is_none());
+/// assert!(Pointer::parse("/a~2").is_none());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Pointer(String);
+
+/// An error applying a [`JsonPatch`] or [`MergePatch`].
+#[derive(Debug)]
+pub enum PatchError {
+    /// The operation at `index` refers to a `path` that doesn't exist.
+    Missing {
+        /// The index of the operation in the patch.
+        index: usize,
+        /// The location that doesn't exist.
+        path: Pointer,
+    },
+    /// The operation at `index` refers to an array with a `path` whose last
+    /// token isn't a valid index into it.
+    InvalidIndex {
+        /// The index of the operation in the patch.
+        index: usize,
+        /// The location with the invalid array index.
+        path: Pointer,
+    },
+    /// The `move` operation at `index` moves a value into one of its children.
+    InvalidMove {
+        /// The index of the operation in the patch.
+        index: usize,
+        /// The location moved from.
+        from: Pointer,
+        /// The location moved to.
+        path: Pointer,
+    },
+    /// The `test` operation at `index` failed.
+    TestFailed {
+        /// The index of the operation in the patch.
+        index: usize,
+        /// The location tested.
+        path: Pointer,
+    },
+    /// The target couldn't be converted to JSON, or the patched document
+    /// couldn't be converted to the target type.
+    Convert(serde_json::Error),
+}
+
+impl<T> JsonPatch<T> {
+    /// Returns the operations in this patch.
+    pub fn operations(&self) -> &[Operation] {
+        &self.operations
+    }
+
+    /// Applies this patch to the JSON document `value`. If an operation
+    /// fails, `value` is left unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::serde::json::{json, JsonPatch, Value};
+    ///
+    /// let patch: JsonPatch<Value> = JsonPatch::from_value(json!([
+    ///     { "op": "replace", "path": "/name", "value": "alice" },
+    ///     { "op": "add", "path": "/tags/-", "value": "admin" },
+    /// ])).unwrap();
+    ///
+    /// let mut value = json!({ "name": "bob", "tags": [] });
+    /// patch.patch(&mut value).unwrap();
+    /// assert_eq!(value, json!({ "name": "alice", "tags": ["admin"] }));
+    /// ```
+    pub fn patch(&self, value: &mut Value) -> Result<(), PatchError> {
+        let mut patched = value.clone();
+        for (index, operation) in self.operations.iter().enumerate() {
+            operation.apply(index, &mut patched)?;
+        }
+
+        *value = patched;
+        Ok(())
+    }
+
+    /// Parses a patch from the JSON document `value`.
+    pub fn from_value(value: Value) -> Result<Self, serde_json::Error> {
+        let operations = serde_json::from_value(value)?;
+        Ok(JsonPatch { operations, _target: PhantomData })
+    }
+}
+
+impl<T: Serialize + DeserializeOwned> JsonPatch<T> {
+    /// Returns the result of applying this patch to `target`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::serde::json::{json, JsonPatch, PatchError};
+    ///
+    /// let patch: JsonPatch<Vec<u8>> = JsonPatch::from_value(json!([
+    ///     { "op": "test", "path": "/0", "value": 1 },
+    ///     { "op": "remove", "path": "/0" },
+    /// ])).unwrap();
+    ///
+    /// assert_eq!(patch.apply(&vec![1, 2]).unwrap(), [2]);
+    /// assert!(matches!(patch.apply(&vec![2]), Err(PatchError::TestFailed { index: 0, .. })));
+    /// ```
+    pub fn apply(&self, target: &T) -> Result<T, PatchError> {
+        let mut value = serde_json::to_value(target).map_err(PatchError::Convert)?;
+        self.patch(&mut value)?;
+        serde_json::from_value(value).map_err(PatchError::Convert)
+    }
+}
+
+impl<T> MergePatch<T> {
+    /// Returns the patch document.
+    pub fn document(&self) -> &Value {
+        &self.patch
+    }
+
+    /// Applies this patch to the JSON document `value`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::serde::json::{json, MergePatch, Value};
+    ///
+    /// let patch: MergePatch<Value> = MergePatch::from_value(json!({
+    ///     "email": null,
+    ///     "name": { "first": "Alice" },
+    /// }));
+    ///
+    /// let mut value = json!({ "email": "bob@example.com", "name": "Bob" });
+    /// patch.patch(&mut value);
+    /// assert_eq!(value, json!({ "name": { "first": "Alice" } }));
+    /// ```
+    pub fn patch(&self, value: &mut Value) {
+        merge(value, &self.patch)
+    }
+
+    /// Returns a merge patch with the document `value`.
+    pub fn from_value(value: Value) -> Self {
+        MergePatch { patch: value, _target: PhantomData }
+    }
+}
+
+impl<T: Serialize + DeserializeOwned> MergePatch<T> {
+    /// Returns the result of applying this patch to `target`.
+    pub fn apply(&self, target: &T) -> Result<T, PatchError> {
+        let mut value = serde_json::to_value(target).map_err(PatchError::Convert)?;
+        self.patch(&mut value);
+        serde_json::from_value(value).map_err(PatchError::Convert)
+    }
+}
+
+/// Applies the merge patch `patch` to `target` as described in RFC 7386.
+fn merge(target: &mut Value, patch: &Value) {
+    let Value::Object(patch) = patch else {
+        *target = patch.clone();
+        return;
+    };
+
+    if !target.is_object() {
+        *target = Value::Object(Default::default());
+    }
+
+    if let Value::Object(target) = target {
+        for (key, value) in patch {
+            match value {
+                Value::Null => { target.remove(key); }
+                _ => merge(target.entry(key.as_str()).or_insert(Value::Null), value),
+            }
+        }
+    }
+}
+
+impl Pointer {
+    /// Returns `pointer` as a `Pointer` if it is a valid JSON Pointer.
+    pub fn parse<S: Into<String>>(pointer: S) -> Option<Pointer> {
+        let pointer = pointer.into();
+        let valid = (pointer.is_empty() || pointer.starts_with('/'))
+            && pointer.split('~').skip(1).all(|s| s.starts_with(['0', '1']));
+
+        valid.then_some(Pointer(pointer))
+    }
+
+    /// Returns the pointer as a string.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns the unescaped reference tokens in this pointer.
+    pub fn tokens(&self) -> impl Iterator<Item = String> + '_ {
+        self.0.split('/').skip(1).map(|t| t.replace("~1", "/").replace("~0", "~"))
+    }
+
+    /// Splits the pointer into the pointer to its parent and its last,
+    /// unescaped, token, unless this pointer refers to the whole document.
+    fn split_last(&self) -> Option<(&str, String)> {
+        let (parent, last) = self.0.rsplit_once('/')?;
+        Some((parent, last.replace("~1", "/").replace("~0", "~")))
+    }
+
+    /// Returns `true` if `self` refers to a proper descendant of `other`.
+    fn is_under(&self, other: &Pointer) -> bool {
+        self.0.strip_prefix(&other.0).map_or(false, |rest| rest.starts_with('/'))
+    }
+}
+
+impl TryFrom<String> for Pointer {
+    type Error = String;
+
+    fn try_from(pointer: String) -> Result<Self, Self::Error> {
+        Pointer::parse(pointer.as_str()).ok_or_else(|| format!("invalid JSON pointer: {pointer:?}"))
+    }
+}
+
+impl From<Pointer> for String {
+    fn from(pointer: Pointer) -> Self {
+        pointer.0
+    }
+}
+
+impl fmt::Display for Pointer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// Parses `token` as an index into an array of length `len`, allowing `-`, the
+/// index past the end, when `append`.
+fn array_index(token: &str, len: usize, append: bool) -> Option<usize> {
+    if append && token == "-" {
+        return Some(len);
+    }
+
+    let canonical = token == "0" || !token.starts_with('0');
+    if token.is_empty() || !canonical || !token.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    token.parse().ok().filter(|&i| i < len + usize::from(append))
+}
+
+impl Operation {
+    fn apply(&self, index: usize, doc: &mut Value) -> Result<(), PatchError> {
+        match self {
+            Operation::Add { path, value } => add(index, doc, path, value.clone()),
+            Operation::Remove { path } => remove(index, doc, path).map(|_| ()),
+            Operation::Replace { path, value } => match doc.pointer_mut(path.as_str()) {
+                Some(target) => {
+                    *target = value.clone();
+                    Ok(())
+                }
+                None => Err(PatchError::Missing { index, path: path.clone() }),
+            },
+            Operation::Move { from, path } => {
+                if path.is_under(from) {
+                    let (from, path) = (from.clone(), path.clone());
+                    return Err(PatchError::InvalidMove { index, from, path });
+                }
+
+                let value = remove(index, doc, from)?;
+                add(index, doc, path, value)
+            }
+            Operation::Copy { from, path } => match doc.pointer(from.as_str()) {
+                Some(value) => add(index, doc, path, value.clone()),
+                None => Err(PatchError::Missing { index, path: from.clone() }),
+            },
+            Operation::Test { path, value } => match doc.pointer(path.as_str()) {
+                Some(actual) if actual == value => Ok(()),
+                _ => Err(PatchError::TestFailed { index, path: path.clone() }),
+            },
+        }
+    }
+}
+
+fn add(index: usize, doc: &mut Value, path: &Pointer, value: Value) -> Result<(), PatchError> {
+    let Some((parent, token)) = path.split_last() else {
+        *doc = value;
+        return Ok(());
+    };
+
+    match doc.pointer_mut(parent) {
+        Some(Value::Object(map)) => {
+            map.insert(token, value);
+            Ok(())
+        }
+        Some(Value::Array(vec)) => match array_index(&token, vec.len(), true) {
+            Some(i) => {
+                vec.insert(i, value);
+                Ok(())
+            }
+            None => Err(PatchError::InvalidIndex { index, path: path.clone() }),
+        },
+        _ => Err(PatchError::Missing { index, path: path.clone() }),
+    }
+}
+
+fn remove(index: usize, doc: &mut Value, path: &Pointer) -> Result<Value, PatchError> {
+    let missing = || PatchError::Missing { index, path: path.clone() };
+    let (parent, token) = path.split_last().ok_or_else(missing)?;
+    match doc.pointer_mut(parent) {
+        Some(Value::Object(map)) => map.remove(&token).ok_or_else(missing),
+        Some(Value::Array(vec)) => match array_index(&token, vec.len(), false) {
+            Some(i) => Ok(vec.remove(i)),
+            None => Err(PatchError::InvalidIndex { index, path: path.clone() }),
+        },
+        _ => Err(missing()),
+    }
+}
+
+impl fmt::Display for PatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Missing { index, path } => {
+                write!(f, "operation {index}: path "{path}" does not exist")
+            }
+            Self::InvalidIndex { index, path } => {
+                write!(f, "operation {index}: path "{path}" has an invalid array index")
+            }
+            Self::InvalidMove { index, from, path } => {
+                write!(f, "operation {index}: cannot move "{from}" into its child "{path}"")
+            }
+            Self::TestFailed { index, path } => {
+                write!(f, "operation {index}: test of path "{path}" failed")
+            }
+            Self::Convert(e) => write!(f, "conversion error: {}", e),
+        }
+    }
+}
+
+impl error::Error for PatchError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Convert(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+#[crate::async_trait]
+impl<'r, T> FromData<'r> for JsonPatch<T> {
+    type Error = Error<'r>;
+
+    async fn from_data(req: &'r Request<'_>, data: Data<'r>) -> Outcome<'r, Self> {
+        <Json<Vec<Operation>> as FromData>::from_data(req, data).await
+            .map(|operations| JsonPatch { operations: operations.0, _target: PhantomData })
+    }
+}
+
+#[crate::async_trait]
+impl<'r, T> FromData<'r> for MergePatch<T> {
+    type Error = Error<'r>;
+
+    async fn from_data(req: &'r Request<'_>, data: Data<'r>) -> Outcome<'r, Self> {
+        <Json<Value> as FromData>::from_data(req, data).await
+            .map(|patch| MergePatch { patch: patch.0, _target: PhantomData })
+    }
+}
+
+impl<T> fmt::Debug for JsonPatch<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("JsonPatch").field(&self.operations).finish()
+    }
+}
+
+impl<T> fmt::Debug for MergePatch<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("MergePatch").field(&self.patch).finish()
+    }
+}
@@ -0,0 +1,435 @@
+use std::fmt;
+
+use serde::ser::{Serialize, Serializer, SerializeSeq, SerializeStruct};
+
+use crate::request::Request;
+use crate::response::{self, Responder, content};
+use crate::form::prelude as form;
+use crate::http::Status;
+use crate::serde::json::serde_json;
+
+/// A type with fields that can be selected via [`Fields`].
+///
+/// `FIELDS` is the allowlist of fields, by serialized name, that a request may
+/// select. Fields not in the allowlist can't be selected and are always
+/// serialized. A [`Sparse`] response selecting a field not in the allowlist
+/// fails with `400 Bad Request`.
+///
+/// This trait can be derived for structs with named fields. See
+/// [`Fieldset`](macro@crate::Fieldset) for details.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::serde::json::Fieldset;
+///
+/// struct User {
+///     id: usize,
+///     name: String,
+///     email: String,
+/// }
+///
+/// impl Fieldset for User {
+///     const FIELDS: &'static [&'static str] = &["name", "email"];
+/// }
+/// ```
+pub trait Fieldset {
+    /// The names of the fields that can be selected.
+    const FIELDS: &'static [&'static str];
+}
+
+/// The selected fields: a query guard for `?fields=id,name`.
+///
+/// `Fields` is a form guard that parses a comma-separated list of field names.
+/// Whitespace around names and empty names are ignored. When the field is
+/// missing, or the list is empty, all fields are selected. `Fields` is
+/// typically used with [`Sparse`] to respond with only the selected fields.
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::serde::json::Fields;
+///
+/// #[get("/users?<fields>")]
+/// fn users(fields: Fields) -> String {
+///     match fields.is_all() {
+///         true => "all".into(),
+///         false => fields.iter().collect::<Vec<_>>().join(" and "),
+///     }
+/// }
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Fields(Vec<String>);
+
+/// A responder that serializes only the selected fields of a `T` as JSON.
+///
+/// If `T` serializes as a struct, only the fields in `T::FIELDS` that are
+/// selected, and those not in `T::FIELDS`, are serialized. If `T` serializes as
+/// a sequence, such as a `Vec`, the same applies to each element. Nested
+/// values are serialized in full. Fields are skipped as `T` is serialized: no
+/// intermediate JSON value is created and filtered.
+///
+/// If a selected field isn't in `T::FIELDS`, the response fails with `400 Bad
+/// Request`. `Sparse<T>` also implements [`Serialize`] directly, without the
+/// check.
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::serde::Serialize;
+/// use rocket::serde::json::{Fields, Fieldset, Sparse};
+///
+/// #[derive(Serialize, Fieldset)]
+/// #[serde(crate = "rocket::serde")]
+/// struct User {
+///     #[fieldset(always)]
+///     id: usize,
+///     name: String,
+///     email: String,
+/// }
+///
+/// # fn load() -> Vec<User> { vec![] }
+/// /// `GET /users?fields=name` responds with `[{ "id": 1, "name": "bob" }]`.
+/// #[get("/users?<fields>")]
+/// fn users(fields: Fields) -> Sparse<Vec<User>> {
+///     Sparse::new(fields, load())
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sparse<T> {
+    fields: Fields,
+    value: T,
+}
+
+impl<T: Fieldset> Fieldset for Vec<T> {
+    const FIELDS: &'static [&'static str] = T::FIELDS;
+}
+
+impl<T: Fieldset> Fieldset for [T] {
+    const FIELDS: &'static [&'static str] = T::FIELDS;
+}
+
+impl<T: Fieldset> Fieldset for Option<T> {
+    const FIELDS: &'static [&'static str] = T::FIELDS;
+}
+
+impl<T: Fieldset + ?Sized> Fieldset for &T {
+    const FIELDS: &'static [&'static str] = T::FIELDS;
+}
+
+impl Fields {
+    /// Returns a selection of all fields.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::serde::json::Fields;
+    ///
+    /// let fields = Fields::all();
+    /// assert!(fields.is_all());
+    /// assert!(fields.contains("name"));
+    /// ```
+    pub fn all() -> Self {
+        Fields(vec![])
+    }
+
+    /// Returns a selection of the fields named `names`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::serde::json::Fields;
+    ///
+    /// let fields = Fields::new(["id", "name"]);
+    /// assert!(!fields.is_all());
+    /// assert!(fields.contains("name"));
+    /// assert!(!fields.contains("email"));
+    /// ```
+    pub fn new<I, S>(names: I) -> Self
+        where I: IntoIterator<Item = S>, S: Into<String>
+    {
+        Fields(names.into_iter().map(Into::into).collect())
+    }
+
+    /// Returns `true` if all fields are selected.
+    pub fn is_all(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns `true` if the field `name` is selected.
+    pub fn contains(&self, name: &str) -> bool {
+        self.is_all() || self.0.iter().any(|field| field == name)
+    }
+
+    /// Returns the names of the selected fields. Empty if all are selected.
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.0.iter().map(|field| field.as_str())
+    }
+}
+
+impl<T> Sparse<T> {
+    /// Returns a `Sparse` that serializes the `fields` of `value`.
+    pub fn new(fields: Fields, value: T) -> Self {
+        Sparse { fields, value }
+    }
+
+    /// Returns the selected fields.
+    pub fn fields(&self) -> &Fields {
+        &self.fields
+    }
+
+    /// Consumes `self` and returns the wrapped value.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+#[crate::async_trait]
+impl<'v> form::FromFormField<'v> for Fields {
+    fn from_value(field: form::ValueField<'v>) -> Result<Self, form::Errors<'v>> {
+        let names = field.value.split(',').map(str::trim).filter(|name| !name.is_empty());
+        Ok(Fields::new(names))
+    }
+
+    fn default() -> Option<Self> {
+        Some(Fields::all())
+    }
+}
+
+impl<T: Serialize + Fieldset> Serialize for Sparse<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let filter = Filter { fields: &self.fields, allowed: T::FIELDS };
+        self.value.serialize(Select { inner: serializer, filter })
+    }
+}
+
+/// Serializes the selected fields of `T` as JSON. If a selected field isn't in
+/// `T::FIELDS`, returns `Err` of `Status::BadRequest`. If serialization fails,
+/// returns `Err` of `Status::InternalServerError`.
+impl<'r, T: Serialize + Fieldset> Responder<'r, 'static> for Sparse<T> {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
+        let selectable = |field: &&str| T::FIELDS.iter().any(|f| f == field);
+        if let Some(field) = self.fields.iter().find(|field| !selectable(field)) {
+            debug!(field, selectable = ?T::FIELDS, "field is not selectable");
+            return Err(Status::BadRequest);
+        }
+
+        let string = serde_json::to_string(&self)
+            .map_err(|e| {
+                error!("JSON serialize failure: {}", e);
+                Status::InternalServerError
+            })?;
+
+        content::RawJson(string).respond_to(req)
+    }
+}
+
+/// Which fields of a struct to serialize.
+#[derive(Copy, Clone)]
+struct Filter<'a> {
+    fields: &'a Fields,
+    allowed: &'static [&'static str],
+}
+
+impl Filter<'_> {
+    fn keeps(&self, field: &str) -> bool {
+        !self.allowed.iter().any(|allowed| *allowed == field) || self.fields.contains(field)
+    }
+}
+
+/// A value to be serialized via [`Select`].
+struct Filtered<'a, T: ?Sized> {
+    value: &'a T,
+    filter: Filter<'a>,
+}
+
+impl<T: Serialize + ?Sized> Serialize for Filtered<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.value.serialize(Select { inner: serializer, filter: self.filter })
+    }
+}
+
+/// A serializer that skips the struct fields not kept by `filter`.
+struct Select<'a, S> {
+    inner: S,
+    filter: Filter<'a>,
+}
+
+struct SelectSeq<'a, S> {
+    inner: S,
+    filter: Filter<'a>,
+}
+
+struct SelectStruct<'a, S> {
+    inner: S,
+    filter: Filter<'a>,
+}
+
+macro_rules! forward {
+    ($($method:ident($T:ty)),* $(,)?) => ($(
+        fn $method(self, v: $T) -> Result<S::Ok, S::Error> {
+            self.inner.$method(v)
+        }
+    )*)
+}
+
+impl<'a, S: Serializer> Serializer for Select<'a, S> {
+    type Ok = S::Ok;
+    type Error = S::Error;
+    type SerializeSeq = SelectSeq<'a, S::SerializeSeq>;
+    type SerializeTuple = S::SerializeTuple;
+    type SerializeTupleStruct = S::SerializeTupleStruct;
+    type SerializeTupleVariant = S::SerializeTupleVariant;
+    type SerializeMap = S::SerializeMap;
+    type SerializeStruct = SelectStruct<'a, S::SerializeStruct>;
+    type SerializeStructVariant = S::SerializeStructVariant;
+
+    forward! {
+        serialize_bool(bool),
+        serialize_i8(i8), serialize_i16(i16), serialize_i32(i32), serialize_i64(i64),
+        serialize_i128(i128),
+        serialize_u8(u8), serialize_u16(u16), serialize_u32(u32), serialize_u64(u64),
+        serialize_u128(u128),
+        serialize_f32(f32), serialize_f64(f64),
+        serialize_char(char),
+        serialize_str(&str),
+        serialize_bytes(&[u8]),
+        serialize_unit_struct(&'static str),
+    }
+
+    fn serialize_none(self) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_none()
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_some(&Filtered { value, filter: self.filter })
+    }
+
+    fn serialize_unit(self) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_unit()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+    ) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_unit_variant(name, index, variant)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_newtype_struct(name, &Filtered { value, filter: self.filter })
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_newtype_variant(name, index, variant, value)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, S::Error> {
+        let inner = self.inner.serialize_seq(len)?;
+        Ok(SelectSeq { inner, filter: self.filter })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, S::Error> {
+        self.inner.serialize_tuple(len)
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, S::Error> {
+        self.inner.serialize_tuple_struct(name, len)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, S::Error> {
+        self.inner.serialize_tuple_variant(name, index, variant, len)
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, S::Error> {
+        self.inner.serialize_map(len)
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, S::Error> {
+        let inner = self.inner.serialize_struct(name, len)?;
+        Ok(SelectStruct { inner, filter: self.filter })
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, S::Error> {
+        self.inner.serialize_struct_variant(name, index, variant, len)
+    }
+
+    fn collect_str<T: fmt::Display + ?Sized>(self, value: &T) -> Result<S::Ok, S::Error> {
+        self.inner.collect_str(value)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.inner.is_human_readable()
+    }
+}
+
+impl<S: SerializeSeq> SerializeSeq for SelectSeq<'_, S> {
+    type Ok = S::Ok;
+    type Error = S::Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), S::Error> {
+        self.inner.serialize_element(&Filtered { value, filter: self.filter })
+    }
+
+    fn end(self) -> Result<S::Ok, S::Error> {
+        self.inner.end()
+    }
+}
+
+impl<S: SerializeStruct> SerializeStruct for SelectStruct<'_, S> {
+    type Ok = S::Ok;
+    type Error = S::Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), S::Error> {
+        match self.filter.keeps(key) {
+            true => self.inner.serialize_field(key, value),
+            false => self.inner.skip_field(key),
+        }
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), S::Error> {
+        self.inner.skip_field(key)
+    }
+
+    fn end(self) -> Result<S::Ok, S::Error> {
+        self.inner.end()
+    }
+}
@@ -6,40 +6,64 @@ use std::time::Duration;
 use hyper::service::service_fn;
 use hyper_util::rt::{TokioExecutor, TokioIo, TokioTimer};
 use hyper_util::server::conn::auto::Builder;
-use futures::{Future, TryFutureExt};
+use futures::{Future, TryFutureExt, future::Either};
 use tokio::io::{AsyncRead, AsyncWrite};
+use tracing::instrument::WithSubscriber;
 
 use crate::{Ignite, Orbit, Request, Rocket};
 use crate::request::ConnectionMeta;
 use crate::erased::{ErasedRequest, ErasedResponse, ErasedIoHandler};
-use crate::listener::{Listener, Connection, BouncedExt, CancellableExt};
+use crate::listener::{Listener, Connection, Endpoint, BouncedExt, CancellableExt};
 use crate::error::log_server_error;
 use crate::data::{IoStream, RawStream};
 use crate::util::{spawn_inspect, FutureExt, ReaderStream};
 use crate::http::Status;
-use crate::trace::{Trace, TraceAll};
+use crate::events::{Event, EventBus};
+use crate::trace::{Trace, TraceAll, unsampled};
+use crate::shutdown::Shutdown;
 
 type Result<T, E = crate::Error> = std::result::Result<T, E>;
 
 impl Rocket<Orbit> {
+    fn service<T: for<'a> Into<RawStream<'a>>>(
+        self: Arc<Self>,
+        parts: http::request::Parts,
+        stream: T,
+        upgrade: Option<hyper::upgrade::OnUpgrade>,
+        connection: ConnectionMeta,
+    ) -> impl Future<Output = Result<hyper::Response<ReaderStream<ErasedResponse>>, http::Error>> {
+        let uri = self.config.access_log.redact_uri(&parts.uri);
+        let sampled = self.config.access_log.sampled(parts.uri.path());
+        let response = self._service(uri, parts, stream, upgrade, connection);
+        match sampled {
+            true => Either::Left(response),
+            false => Either::Right(response.with_subscriber(unsampled::dispatch())),
+        }
+    }
+
     #[tracing::instrument("request", skip_all, fields(
         method = %parts.method,
-        uri = %parts.uri,
+        uri = %uri,
+        route,
         autohandled
     ))]
-    async fn service<T: for<'a> Into<RawStream<'a>>>(
+    async fn _service<T: for<'a> Into<RawStream<'a>>>(
         self: Arc<Self>,
+        uri: String,
         parts: http::request::Parts,
         stream: T,
         upgrade: Option<hyper::upgrade::OnUpgrade>,
         connection: ConnectionMeta,
     ) -> Result<hyper::Response<ReaderStream<ErasedResponse>>, http::Error> {
         connection.trace_debug();
-        let request = ErasedRequest::new(self, parts, |rocket, parts| {
+        let request = ErasedRequest::new(self.clone(), parts, |rocket, parts| {
             Request::from_hyp(rocket, parts, connection).unwrap_or_else(|e| e)
         });
 
-        span_debug!("request headers" => request.inner().headers().iter().trace_all_debug());
+        let access_log = &self.config.access_log;
+        span_debug!("request headers" => request.inner().headers().iter()
+            .map(|header| access_log.redact_header(header))
+            .trace_all_debug());
         let mut response = request.into_response(
             stream,
             |rocket, request, data| Box::pin(rocket.preprocess(request, data)),
@@ -54,11 +78,21 @@ impl Rocket<Orbit> {
 
         // TODO: Should upgrades be handled in dispatch?
         response.inner().trace_info();
-        span_debug!("response headers" => response.inner().headers().iter().trace_all_debug());
+        span_debug!("response headers" => response.inner().headers().iter()
+            .map(|header| access_log.redact_header(header))
+            .trace_all_debug());
         let io_handler = response.make_io_handler(Rocket::extract_io_handler);
         if let (Some((proto, handler)), Some(upgrade)) = (io_handler, upgrade) {
             let upgrade = upgrade.map_ok(IoStream::from).map_err(io::Error::other);
-            tokio::task::spawn(io_handler_task(proto, upgrade, handler));
+            let events = self.events.clone();
+            tokio::task::spawn(io_handler_task(proto, upgrade, handler, events));
+        }
+
+        // Refresh a stale cached response once this one is under way.
+        #[cfg(feature = "cache")]
+        if crate::cache::wants_revalidation(response.request()) {
+            let parts = revalidation_parts(response.request_parts());
+            tokio::spawn(self.clone().revalidate(uri, parts));
         }
 
         let mut builder = hyper::Response::builder();
@@ -71,32 +105,127 @@ impl Rocket<Orbit> {
         builder.body(ReaderStream::with_capacity(response, chunk_size))
     }
 
-    pub(crate) fn alt_svc(&self) -> Option<&'static str> {
-        cfg!(feature = "http3-preview").then(|| {
-            static ALT_SVC: state::InitCell<Option<String>> = state::InitCell::new();
+    #[cfg(feature = "cache")]
+    #[tracing::instrument("request", skip_all, fields(
+        method = %parts.method,
+        uri = %uri,
+        route,
+        autohandled,
+        revalidation = true,
+    ))]
+    async fn revalidate(self: Arc<Self>, uri: String, parts: http::request::Parts) {
+        let request = ErasedRequest::new(self, parts, |rocket, parts| {
+            let request = Request::from_hyp(rocket, parts, ConnectionMeta::default())
+                .unwrap_or_else(|e| e);
+
+            crate::cache::mark_revalidation(&request);
+            request
+        });
+
+        // The response is dropped unread: the cache stores it, if it can.
+        let _response = request.into_response(
+            NoBody,
+            |rocket, request, data| Box::pin(rocket.preprocess(request, data)),
+            |token, rocket, request, data| Box::pin(rocket.dispatch(token, request, data)),
+        ).await;
+    }
 
-            ALT_SVC.get_or_init(|| {
-                let addr = self.endpoints().find_map(|v| v.quic())?;
-                Some(format!("h3=\":{}\"", addr.port()))
-            }).as_deref()
-        })?
+    /// The `Alt-Svc` header value advertising this instance's HTTP/3
+    /// endpoint, if its QUIC listener is open.
+    pub(crate) fn alt_svc(&self) -> Option<&str> {
+        let alt_svc = self.alt_svc.as_deref()?;
+        self.endpoints.iter()
+            .zip(&self.closing)
+            .any(|(endpoint, close)| endpoint.quic().is_some() && !close.notified())
+            .then_some(alt_svc)
     }
 }
 
+/// The `Alt-Svc` header value advertising the HTTP/3 endpoint among
+/// `endpoints`, if there is one. Computed once, at liftoff.
+pub(crate) fn alt_svc(endpoints: &[Endpoint]) -> Option<Arc<str>> {
+    if !cfg!(feature = "http3-preview") {
+        return None;
+    }
+
+    let addr = endpoints.iter().find_map(|endpoint| endpoint.quic())?;
+    Some(format!("h3=\":{}\"", addr.port()).into())
+}
+
+/// The body of a request dispatched by Rocket itself.
+#[cfg(any(feature = "cache", feature = "edge"))]
+pub(crate) struct NoBody;
+
+#[cfg(any(feature = "cache", feature = "edge"))]
+impl From<NoBody> for RawStream<'_> {
+    fn from(_: NoBody) -> Self {
+        RawStream::Empty
+    }
+}
+
+/// Returns the parts of a body-less `GET` request with the URI and headers of
+/// the request with `parts`.
+#[cfg(feature = "cache")]
+fn revalidation_parts(parts: &http::request::Parts) -> http::request::Parts {
+    let mut request = http::Request::new(());
+    *request.uri_mut() = parts.uri.clone();
+    *request.version_mut() = parts.version;
+    *request.headers_mut() = parts.headers.clone();
+    request.into_parts().0
+}
+
+/// Notifies the `closing` signal, parallel to `endpoints`, of each open
+/// listener whose endpoint satisfies `f`, unless doing so would close every
+/// open listener. Returns the number of listeners closed.
+pub(crate) fn close_listeners<F>(
+    endpoints: &[Endpoint],
+    closing: &[Shutdown],
+    events: &EventBus,
+    mut f: F,
+) -> usize
+    where F: FnMut(&Endpoint) -> bool
+{
+    let open = endpoints.iter().zip(closing).filter(|(_, close)| !close.notified());
+    let (matched, rest): (Vec<_>, Vec<_>) = open.partition(|(endpoint, _)| f(endpoint));
+    if rest.is_empty() && !matched.is_empty() {
+        warn!(name: "listener", "refusing to close every listener: shut down instead");
+        return 0;
+    }
+
+    for (endpoint, close) in &matched {
+        info!(name: "listener", %endpoint, "closing listener");
+        close.notify();
+        events.emit(Event::ListenerClosed { endpoint: (*endpoint).clone() });
+    }
+
+    matched.len()
+}
+
 #[tracing::instrument("upgrade", skip_all, fields(protocol = proto))]
-async fn io_handler_task<S>(proto: String, stream: S, mut handler: ErasedIoHandler)
-    where S: Future<Output = io::Result<IoStream>>
+async fn io_handler_task<S>(
+    proto: String,
+    stream: S,
+    mut handler: ErasedIoHandler,
+    events: EventBus,
+) where S: Future<Output = io::Result<IoStream>>
 {
+    let task = || format!("i/o handler for `{proto}`");
     let stream = match stream.await {
         Ok(stream) => stream,
-        Err(e) => return warn!(error = %e, "i/o upgrade failed"),
+        Err(e) => {
+            warn!(error = %e, "i/o upgrade failed");
+            return events.emit(Event::TaskFailed { task: task(), error: e.to_string() });
+        }
     };
 
     debug!("i/o upgrade succeeded");
     if let Err(e) = handler.take().io(stream).await {
         match e.kind() {
             io::ErrorKind::BrokenPipe => warn!("i/o handler closed"),
-            _ => warn!(error = %e, "i/o handler terminated unsuccessfully"),
+            _ => {
+                warn!(error = %e, "i/o handler terminated unsuccessfully");
+                events.emit(Event::TaskFailed { task: task(), error: e.to_string() });
+            }
         }
     }
 }
@@ -123,8 +252,9 @@ impl Rocket<Ignite> {
             let rocket = self.into_orbit(vec![h3listener.endpoint()?, endpoint]);
             let rocket = orbit_callback(rocket).await?;
 
-            let http12 = tokio::task::spawn(rocket.clone().serve12(listener));
-            let http3 = tokio::task::spawn(rocket.clone().serve3(h3listener));
+            let (close3, close12) = (rocket.closing[0].clone(), rocket.closing[1].clone());
+            let http12 = tokio::task::spawn(rocket.clone().serve12(listener, close12));
+            let http3 = tokio::task::spawn(rocket.clone().serve3(h3listener, close3));
             let (r1, r2) = tokio::join!(http12, http3);
             r1.map_err(|e| ErrorKind::Liftoff(Err(rocket.clone()), e))??;
             r2.map_err(|e| ErrorKind::Liftoff(Err(rocket.clone()), e))??;
@@ -138,13 +268,16 @@ impl Rocket<Ignite> {
 
         let rocket = self.into_orbit(vec![endpoint]);
         let rocket = orbit_callback(rocket).await?;
-        rocket.clone().serve12(listener).await?;
+        let close = rocket.closing[0].clone();
+        rocket.clone().serve12(listener, close).await?;
         Ok(rocket)
     }
 }
 
 impl Rocket<Orbit> {
-    pub(crate) async fn serve12<L>(self: Arc<Self>, listener: L) -> Result<()>
+    /// Serves HTTP/1 and HTTP/2 on `listener` until shutdown or, for this
+    /// listener alone, until `close` is notified.
+    pub(crate) async fn serve12<L>(self: Arc<Self>, listener: L, close: Shutdown) -> Result<()>
         where L: Listener + 'static,
               L::Connection: AsyncRead + AsyncWrite
     {
@@ -168,10 +301,13 @@ impl Rocket<Orbit> {
         }
 
         let (listener, server) = (Arc::new(listener.bounced()), Arc::new(builder));
-        while let Some(accept) = listener.accept().race(self.shutdown()).await.left().transpose()? {
+        let stop = || self.shutdown().race(close.clone());
+        while let Some(accept) = listener.accept().race(stop()).await.left().transpose()? {
             let (listener, rocket, server) = (listener.clone(), self.clone(), server.clone());
+            let close = close.clone();
             spawn_inspect(|e| log_server_error(&**e), async move {
-                let conn = listener.connect(accept).race_io(rocket.shutdown()).await?;
+                let stop = || rocket.shutdown().race(close.clone());
+                let conn = listener.connect(accept).race_io(stop()).await?;
                 let meta = ConnectionMeta::new(conn.endpoint(), conn.certificates());
                 let service = service_fn(|mut req| {
                     let upgrade = hyper::upgrade::on(&mut req);
@@ -181,7 +317,7 @@ impl Rocket<Orbit> {
 
                 let io = TokioIo::new(conn.cancellable(rocket.shutdown.clone()));
                 let mut server = pin!(server.serve_connection_with_upgrades(io, service));
-                match server.as_mut().race(rocket.shutdown()).await.left() {
+                match server.as_mut().race(stop()).await.left() {
                     Some(result) => result,
                     None => {
                         server.as_mut().graceful_shutdown();
@@ -195,14 +331,20 @@ impl Rocket<Orbit> {
     }
 
     #[cfg(feature = "http3-preview")]
-    async fn serve3(self: Arc<Self>, listener: crate::listener::quic::QuicListener) -> Result<()> {
+    async fn serve3(
+        self: Arc<Self>,
+        listener: crate::listener::quic::QuicListener,
+        close: Shutdown,
+    ) -> Result<()> {
         let rocket = self.clone();
         let listener = Arc::new(listener);
-        while let Some(Some(accept)) = listener.accept().race(rocket.shutdown()).await.left() {
-            let (listener, rocket) = (listener.clone(), rocket.clone());
+        let stop = || rocket.shutdown().race(close.clone());
+        while let Some(Some(accept)) = listener.accept().race(stop()).await.left() {
+            let (listener, rocket, close) = (listener.clone(), rocket.clone(), close.clone());
             spawn_inspect(|e: &io::Error| log_server_error(e), async move {
-                let mut stream = listener.connect(accept).race_io(rocket.shutdown()).await?;
-                while let Some(mut conn) = stream.accept().race_io(rocket.shutdown()).await? {
+                let stop = || rocket.shutdown().race(close.clone());
+                let mut stream = listener.connect(accept).race_io(stop()).await?;
+                while let Some(mut conn) = stream.accept().race_io(stop()).await? {
                     let rocket = rocket.clone();
                     spawn_inspect(|e: &io::Error| log_server_error(e), async move {
                         let meta = ConnectionMeta::new(conn.endpoint(), None);
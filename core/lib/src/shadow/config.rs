@@ -0,0 +1,84 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::data::{ByteUnit, ToByteUnit};
+
+/// The shadowing configuration: the `shadow` configuration parameter.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::shadow::ShadowConfig;
+/// use rocket::data::ToByteUnit;
+/// use rocket::figment::{Figment, providers::{Format, Toml}};
+///
+/// let figment = Figment::from(Toml::string(r#"
+///     [shadow]
+///     upstream = "http://canary.internal:8000"
+///     rate = 0.25
+/// "#));
+///
+/// let config: ShadowConfig = figment.extract_inner("shadow").unwrap();
+/// assert_eq!(config.upstream.as_deref(), Some("http://canary.internal:8000"));
+/// assert_eq!(config.rate, 0.25);
+/// assert_eq!(config.body_limit, 64.kibibytes());
+/// assert_eq!(config.timeout_secs, 5);
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ShadowConfig {
+    /// The upstream to mirror requests to, as `http://host[:port][/path]`.
+    /// When `None`, no requests are mirrored.
+    ///
+    /// **default: `None`**
+    pub upstream: Option<String>,
+    /// The fraction of requests to mirror, from `0.0` to `1.0`.
+    ///
+    /// **default: `0.1`**
+    pub rate: f64,
+    /// The largest request body to mirror. Requests with larger bodies aren't
+    /// mirrored.
+    ///
+    /// **default: `64KiB`**
+    pub body_limit: ByteUnit,
+    /// The time, in seconds, to wait for the upstream to receive a mirrored
+    /// request and respond.
+    ///
+    /// **default: `5`**
+    pub timeout_secs: u64,
+    /// The largest number of mirrored requests outstanding at once. Requests
+    /// sampled beyond it aren't mirrored.
+    ///
+    /// **default: `64`**
+    pub max_in_flight: usize,
+    /// Headers, compared case-insensitively, to remove from mirrored requests.
+    ///
+    /// **default: `[]`**
+    pub exclude_headers: Vec<String>,
+}
+
+impl Default for ShadowConfig {
+    fn default() -> Self {
+        ShadowConfig {
+            upstream: None,
+            rate: 0.1,
+            body_limit: 64.kibibytes(),
+            timeout_secs: 5,
+            max_in_flight: 64,
+            exclude_headers: vec![],
+        }
+    }
+}
+
+impl ShadowConfig {
+    /// Returns the time to wait for the upstream.
+    pub fn timeout(&self) -> Duration {
+        Duration::from_secs(self.timeout_secs)
+    }
+
+    /// Returns `true` if `header` should be removed from mirrored requests.
+    pub fn excludes(&self, header: &str) -> bool {
+        self.exclude_headers.iter().any(|h| h.eq_ignore_ascii_case(header))
+    }
+}
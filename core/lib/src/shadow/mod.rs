@@ -0,0 +1,61 @@
+//! Traffic shadowing: mirroring sampled requests to a shadow upstream.
+//!
+//! The [`Shadow`] fairing copies a sampled fraction of requests, their method,
+//! URI, headers, and body, to a _shadow_ upstream, such as a new
+//! implementation of the application, so that it can be tested against
+//! production traffic. Mirroring is asynchronous and one-way: the application
+//! handles every request as usual, never waits on the upstream, and ignores
+//! its responses.
+//!
+//! A request is mirrored unless:
+//!
+//!   * it isn't sampled, per `rate`,
+//!   * it is a `CONNECT` request or asks for a protocol upgrade,
+//!   * its body exceeds `body_limit`, or
+//!   * `max_in_flight` mirrored requests are already outstanding.
+//!
+//! Mirrored requests are sent over HTTP/1.1 with a `Host` of the upstream, an
+//! `X-Shadow-Request: 1` header so that the upstream can avoid side effects,
+//! and every header of the original request but hop-by-hop headers and those
+//! in `exclude_headers`. The upstream's path, if any, prefixes the request's.
+//! Only plain `http://` upstreams are supported.
+//!
+//! Because a sampled request's body is read into memory before the request is
+//! handled, and because mirrored requests carry credentials like `Cookie` and
+//! `Authorization` headers unless excluded, only trusted upstreams should be
+//! configured. The number of requests mirrored, failed, and skipped is
+//! available via the `Shadow` fairing. Failures are logged at the `DEBUG`
+//! level.
+//!
+//! # Configuration
+//!
+//! The fairing is configured via the `shadow` configuration parameter, which
+//! is deserialized as a [`ShadowConfig`]. Without an `upstream`, nothing is
+//! mirrored, so the fairing can be attached unconditionally and enabled per
+//! environment:
+//!
+//! ```toml
+//! [release.shadow]
+//! upstream = "http://canary.internal:8000"
+//! rate = 0.05
+//! body_limit = "64KiB"
+//! exclude_headers = ["Authorization"]
+//! ```
+//!
+//! # Example
+//!
+//! ```rust
+//! # #[macro_use] extern crate rocket;
+//! use rocket::shadow::Shadow;
+//!
+//! #[launch]
+//! fn rocket() -> _ {
+//!     rocket::build().attach(Shadow::new().upstream("http://canary.internal:8000").rate(0.05))
+//! }
+//! ```
+
+mod config;
+mod shadow;
+
+pub use config::ShadowConfig;
+pub use shadow::Shadow;
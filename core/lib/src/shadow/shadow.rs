@@ -0,0 +1,293 @@
+use std::io;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+use state::InitCell;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+use crate::{Rocket, Request, Data, Build};
+use crate::fairing::{self, Fairing, Info, Kind};
+use crate::http::Method;
+use crate::http::uri::Absolute;
+use crate::shadow::ShadowConfig;
+use crate::trace::Trace;
+
+/// A [`Fairing`] that [mirrors](crate::shadow) sampled requests to a shadow
+/// upstream.
+///
+/// The configuration is read from the `shadow` configuration parameter at
+/// ignition; an upstream or rate set via [`Shadow::upstream()`] or
+/// [`Shadow::rate()`] overrides it. If the parameter is invalid, the upstream
+/// isn't an `http://` URI, or the rate isn't between `0.0` and `1.0`,
+/// ignition fails.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::shadow::Shadow;
+/// use rocket::local::blocking::Client;
+///
+/// let shadow = Shadow::new().upstream("http://canary.internal:8000");
+/// let client = Client::debug(rocket::build().attach(shadow)).unwrap();
+///
+/// let shadow = client.rocket().fairing::<Shadow>().unwrap();
+/// assert_eq!(shadow.mirrored(), 0);
+/// assert_eq!(shadow.in_flight(), 0);
+/// ```
+#[derive(Clone)]
+pub struct Shadow {
+    upstream: Option<String>,
+    rate: Option<f64>,
+    inner: Arc<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    target: InitCell<Option<(ShadowConfig, Arc<Upstream>)>>,
+    in_flight: AtomicUsize,
+    mirrored: AtomicU64,
+    failed: AtomicU64,
+    skipped: AtomicU64,
+}
+
+/// A parsed `http://` upstream.
+#[derive(Debug)]
+struct Upstream {
+    host: String,
+    port: u16,
+    authority: String,
+    prefix: String,
+}
+
+/// Headers that apply to a single connection and so aren't mirrored, along
+/// with those the mirrored request sets itself.
+const HOP_BY_HOP: &[&str] = &[
+    "Connection", "Keep-Alive", "Proxy-Connection", "Proxy-Authenticate",
+    "Proxy-Authorization", "TE", "Trailer", "Transfer-Encoding", "Upgrade",
+    "Host", "Content-Length", "X-Shadow-Request",
+];
+
+impl Shadow {
+    /// Returns a `Shadow` fairing.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::shadow::Shadow;
+    ///
+    /// let shadow = Shadow::new();
+    /// ```
+    pub fn new() -> Self {
+        Shadow { upstream: None, rate: None, inner: Arc::default() }
+    }
+
+    /// Mirrors requests to `upstream`, of the form `http://host[:port][/path]`,
+    /// overriding [`ShadowConfig::upstream`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::shadow::Shadow;
+    ///
+    /// let shadow = Shadow::new().upstream("http://canary.internal:8000/v2");
+    /// ```
+    pub fn upstream<T: Into<String>>(mut self, upstream: T) -> Self {
+        self.upstream = Some(upstream.into());
+        self
+    }
+
+    /// Mirrors the fraction `rate`, from `0.0` to `1.0`, of requests,
+    /// overriding [`ShadowConfig::rate`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::shadow::Shadow;
+    ///
+    /// let shadow = Shadow::new().rate(0.01);
+    /// ```
+    pub fn rate(mut self, rate: f64) -> Self {
+        self.rate = Some(rate);
+        self
+    }
+
+    /// Returns the number of requests the upstream received so far.
+    pub fn mirrored(&self) -> u64 {
+        self.inner.mirrored.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of requests that failed to reach the upstream, or
+    /// that it didn't respond to in time, so far.
+    pub fn failed(&self) -> u64 {
+        self.inner.failed.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of sampled requests that weren't mirrored so far,
+    /// because their body was too large or too many requests were in flight.
+    pub fn skipped(&self) -> u64 {
+        self.inner.skipped.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of mirrored requests currently outstanding.
+    pub fn in_flight(&self) -> usize {
+        self.inner.in_flight.load(Ordering::Acquire)
+    }
+
+    fn skip(&self, req: &Request<'_>, reason: &str) {
+        self.inner.skipped.fetch_add(1, Ordering::Relaxed);
+        debug!(name: "shadow", uri = %req.uri(), "request not mirrored: {reason}");
+    }
+}
+
+impl Default for Shadow {
+    fn default() -> Self {
+        Shadow::new()
+    }
+}
+
+impl Upstream {
+    fn parse(string: &str) -> Result<Upstream, String> {
+        let uri = Absolute::parse(string).map_err(|e| format!("invalid URI: {e}"))?;
+        if !uri.scheme().eq_ignore_ascii_case("http") {
+            return Err(format!("unsupported scheme `{}`: expected `http`", uri.scheme()));
+        }
+
+        let Some(authority) = uri.authority() else {
+            return Err("missing host".into());
+        };
+
+        if uri.query().is_some() {
+            return Err("upstreams can't have a query".into());
+        }
+
+        let host = authority.host().trim_start_matches('[').trim_end_matches(']');
+        Ok(Upstream {
+            host: host.into(),
+            port: authority.port().unwrap_or(80),
+            authority: authority.to_string(),
+            prefix: uri.path().as_str().trim_end_matches('/').into(),
+        })
+    }
+
+    /// Sends `message` to the upstream and reads, then discards, its response.
+    async fn send(&self, message: &[u8]) -> io::Result<()> {
+        let mut stream = TcpStream::connect((&*self.host, self.port)).await?;
+        stream.write_all(message).await?;
+        stream.flush().await?;
+        tokio::io::copy(&mut stream, &mut tokio::io::sink()).await?;
+        Ok(())
+    }
+}
+
+#[crate::async_trait]
+impl Fairing for Shadow {
+    fn info(&self) -> Info {
+        Info { name: "Shadow", kind: Kind::Ignite | Kind::Request | Kind::Singleton }
+    }
+
+    async fn on_ignite(&self, rocket: Rocket<Build>) -> fairing::Result {
+        let mut config = match rocket.figment().contains("shadow") {
+            true => match rocket.figment().extract_inner::<ShadowConfig>("shadow") {
+                Ok(config) => config,
+                Err(e) => {
+                    e.trace_error();
+                    return Err(rocket);
+                }
+            },
+            false => ShadowConfig::default(),
+        };
+
+        config.upstream = self.upstream.clone().or(config.upstream);
+        config.rate = self.rate.unwrap_or(config.rate);
+        if !(0.0..=1.0).contains(&config.rate) {
+            error!(name: "shadow", rate = config.rate, "shadow rate must be from 0.0 to 1.0");
+            return Err(rocket);
+        }
+
+        let Some(upstream) = config.upstream.as_deref() else {
+            info!(name: "shadow", "no shadow upstream configured: mirroring disabled");
+            self.inner.target.set(None);
+            return Ok(rocket);
+        };
+
+        match Upstream::parse(upstream) {
+            Ok(parsed) => {
+                info!(name: "shadow", upstream, rate = config.rate, "mirroring requests");
+                self.inner.target.set(Some((config, Arc::new(parsed))));
+                Ok(rocket)
+            }
+            Err(e) => {
+                error!(name: "shadow", upstream, "invalid shadow upstream: {e}");
+                Err(rocket)
+            }
+        }
+    }
+
+    async fn on_request(&self, req: &mut Request<'_>, data: &mut Data<'_>) {
+        let Some((config, upstream)) = self.inner.target.try_get().and_then(Option::as_ref)
+        else {
+            return;
+        };
+
+        if req.method() == Method::Connect || req.headers().contains("Upgrade") {
+            return;
+        }
+
+        if config.rate < 1.0 && rand::random::<f64>() >= config.rate {
+            return;
+        }
+
+        let limit = config.body_limit.as_u64();
+        let length = req.headers().get_one("Content-Length").and_then(|v| v.parse().ok());
+        if length.map_or(false, |length: u64| length > limit) {
+            return self.skip(req, "body exceeds limit");
+        }
+
+        if self.inner.in_flight.fetch_add(1, Ordering::AcqRel) >= config.max_in_flight {
+            self.inner.in_flight.fetch_sub(1, Ordering::AcqRel);
+            return self.skip(req, "too many requests in flight");
+        }
+
+        let limit = usize::try_from(limit).unwrap_or(usize::MAX);
+        let body = data.buffer(limit.saturating_add(1)).await.to_vec();
+        if !data.peek_complete() || body.len() > limit {
+            self.inner.in_flight.fetch_sub(1, Ordering::AcqRel);
+            return self.skip(req, "body exceeds limit");
+        }
+
+        let mut head = format!("{} {}{} HTTP/1.1\r\n", req.method(), upstream.prefix, req.uri());
+        head.push_str(&format!("Host: {}\r\n", upstream.authority));
+        for header in req.headers().iter() {
+            let name = header.name().as_str();
+            if !HOP_BY_HOP.iter().any(|h| h.eq_ignore_ascii_case(name)) && !config.excludes(name) {
+                head.push_str(&format!("{}: {}\r\n", name, header.value()));
+            }
+        }
+
+        head.push_str(&format!("X-Shadow-Request: 1\r\nContent-Length: {}\r\n", body.len()));
+        head.push_str("Connection: close\r\n\r\n");
+        let mut message = head.into_bytes();
+        message.extend_from_slice(&body);
+
+        let (inner, upstream, timeout) = (self.inner.clone(), upstream.clone(), config.timeout());
+        let uri = req.uri().to_string();
+        tokio::spawn(async move {
+            let result = tokio::time::timeout(timeout, upstream.send(&message)).await;
+            inner.in_flight.fetch_sub(1, Ordering::AcqRel);
+            match result {
+                Ok(Ok(())) => {
+                    inner.mirrored.fetch_add(1, Ordering::Relaxed);
+                }
+                Ok(Err(e)) => {
+                    inner.failed.fetch_add(1, Ordering::Relaxed);
+                    debug!(name: "shadow", %uri, error = %e, "mirrored request failed");
+                }
+                Err(_) => {
+                    inner.failed.fetch_add(1, Ordering::Relaxed);
+                    debug!(name: "shadow", %uri, "mirrored request timed out");
+                }
+            }
+        });
+    }
+}
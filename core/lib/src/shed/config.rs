@@ -0,0 +1,130 @@
+use std::time::Duration;
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use crate::http::Status;
+
+/// The load shedding configuration: the `shed` configuration parameter.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::shed::{ShedConfig, Priority};
+/// use rocket::figment::{Figment, providers::{Format, Toml}};
+///
+/// let figment = Figment::from(Toml::string(r#"
+///     [shed]
+///     max_in_flight = 256
+///     target_millis = 20
+///     priorities = { "/health" = "critical", "/export" = "bulk" }
+/// "#));
+///
+/// let config: ShedConfig = figment.extract_inner("shed").unwrap();
+/// assert_eq!(config.max_in_flight, Some(256));
+/// assert_eq!(config.target_millis, Some(20));
+/// assert_eq!(config.interval_millis, 100);
+/// assert_eq!(config.status.code, 503);
+/// assert_eq!(config.retry_after, None);
+/// assert_eq!(config.priorities["/health"], Priority::Critical);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ShedConfig {
+    /// The maximum number of requests handled at once, if any. Requests
+    /// arriving when as many are in flight are shed.
+    ///
+    /// **default: `None`**
+    pub max_in_flight: Option<usize>,
+    /// The maximum number of requests handled at once beyond which
+    /// [`Priority::Bulk`] requests are shed, if any. When `None`, half of
+    /// `max_in_flight`.
+    ///
+    /// **default: `None`**
+    pub max_bulk_in_flight: Option<usize>,
+    /// The target latency, in milliseconds, if any. Latency-based shedding is
+    /// disabled when `None`.
+    ///
+    /// **default: `None`**
+    pub target_millis: Option<u64>,
+    /// The interval, in milliseconds, for which latency must exceed the target
+    /// before requests are shed.
+    ///
+    /// **default: `100`**
+    pub interval_millis: u64,
+    /// The status of the response to a shed request.
+    ///
+    /// **default: `503 Service Unavailable`**
+    pub status: Status,
+    /// The value, in seconds, of the `Retry-After` header of the response to a
+    /// shed request, if any.
+    ///
+    /// **default: `None`**
+    pub retry_after: Option<u64>,
+    /// The priorities of requests by path prefix. A prefix applies to a
+    /// request if its path is equal to it or, segment-wise, begins with it.
+    /// When more than one applies, the longest does. Requests to which none
+    /// applies are [`Priority::Normal`].
+    ///
+    /// **default: `{}`**
+    pub priorities: IndexMap<String, Priority>,
+}
+
+/// The priority of a request when [shedding load](crate::shed).
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Priority {
+    /// Never shed, such as health checks and payment callbacks.
+    Critical,
+    /// Shed when overloaded.
+    #[default]
+    Normal,
+    /// Shed first: beyond `max_bulk_in_flight` and whenever latency has
+    /// exceeded the target, such as bulk exports.
+    Bulk,
+}
+
+impl Default for ShedConfig {
+    fn default() -> Self {
+        ShedConfig {
+            max_in_flight: None,
+            max_bulk_in_flight: None,
+            target_millis: None,
+            interval_millis: 100,
+            status: Status::ServiceUnavailable,
+            retry_after: None,
+            priorities: IndexMap::new(),
+        }
+    }
+}
+
+impl ShedConfig {
+    /// Returns the target latency, if any.
+    pub fn target(&self) -> Option<Duration> {
+        self.target_millis.map(Duration::from_millis)
+    }
+
+    /// Returns the interval.
+    pub fn interval(&self) -> Duration {
+        Duration::from_millis(self.interval_millis)
+    }
+
+    /// Returns the in-flight limit for [`Priority::Bulk`] requests, if any.
+    pub fn bulk_limit(&self) -> Option<usize> {
+        self.max_bulk_in_flight.or(self.max_in_flight.map(|max| max / 2))
+    }
+
+    /// Returns the priority of requests to `path`.
+    pub fn priority(&self, path: &str) -> Priority {
+        self.priorities.iter()
+            .filter(|(prefix, _)| in_scope(prefix, path))
+            .max_by_key(|(prefix, _)| prefix.trim_end_matches('/').len())
+            .map_or(Priority::Normal, |(_, priority)| *priority)
+    }
+}
+
+/// Returns `true` if `path` is equal to or, segment-wise, begins with `prefix`.
+fn in_scope(prefix: &str, path: &str) -> bool {
+    let prefix = prefix.trim_end_matches('/');
+    path.strip_prefix(prefix).map_or(false, |rest| rest.is_empty() || rest.starts_with('/'))
+}
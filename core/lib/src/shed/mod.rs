@@ -0,0 +1,80 @@
+//! Adaptive load shedding.
+//!
+//! The [`Shedder`] fairing rejects requests early, before they are routed,
+//! when the application is overloaded. Rejected requests are answered by the
+//! error catcher for the configured status, `503 Service Unavailable` by
+//! default, without running any handler. Shedding a few requests quickly keeps
+//! the latency of the rest low, where a hard cap on connections lets queues,
+//! and thus latency, grow until clients time out.
+//!
+//! Two signals identify overload:
+//!
+//!   * **Queue depth.** When `max_in_flight` requests are already being
+//!     handled, every new request is shed.
+//!
+//!   * **Latency.** Following [CoDel], once the latency of every request
+//!     completed during an `interval` has exceeded `target`, the application
+//!     is considered overloaded. A request is then shed, and further requests
+//!     are shed at intervals that shrink with the square root of the number
+//!     shed, until a request completes within `target` again.
+//!
+//! # Priorities
+//!
+//! Each request has a [`Priority`], determined by its path: the scope it is
+//! under. Priorities are configured via the `priorities` of [`ShedConfig`] or
+//! [`Shedder::priority()`].
+//!
+//!   * [`Priority::Critical`] requests, such as health checks and payment
+//!     callbacks, are never shed. They count toward requests in flight.
+//!   * [`Priority::Normal`] requests are shed as described above.
+//!   * [`Priority::Bulk`] requests are shed first: when `max_bulk_in_flight`,
+//!     by default half of `max_in_flight`, requests are in flight and, while
+//!     latency exceeds the target, every one of them.
+//!
+//! Entering and leaving the overloaded state is logged at the `WARN` and
+//! `INFO` levels, respectively. The number of requests shed and in flight are
+//! available via [`Shedder::shed()`], [`Shedder::shed_of()`], and
+//! [`Shedder::in_flight()`].
+//!
+//! # Configuration
+//!
+//! Thresholds are configured via the `shed` configuration parameter, which is
+//! deserialized as a [`ShedConfig`]:
+//!
+//! ```toml
+//! [default.shed]
+//! max_in_flight = 512
+//! target_millis = 50
+//! interval_millis = 500
+//! status = 503
+//! retry_after = 1
+//!
+//! [default.shed.priorities]
+//! "/health" = "critical"
+//! "/payments/callback" = "critical"
+//! "/export" = "bulk"
+//! ```
+//!
+//! # Example
+//!
+//! ```rust
+//! # #[macro_use] extern crate rocket;
+//! use rocket::shed::{Shedder, Priority};
+//!
+//! #[launch]
+//! fn rocket() -> _ {
+//!     let shedder = Shedder::new()
+//!         .priority("/health", Priority::Critical)
+//!         .priority("/export", Priority::Bulk);
+//!
+//!     rocket::build().attach(shedder)
+//! }
+//! ```
+//!
+//! [CoDel]: https://queue.acm.org/detail.cfm?id=2209336
+
+mod config;
+mod shedder;
+
+pub use config::{ShedConfig, Priority};
+pub use shedder::Shedder;
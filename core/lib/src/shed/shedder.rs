@@ -0,0 +1,280 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+use indexmap::IndexMap;
+use parking_lot::Mutex;
+use state::InitCell;
+
+use crate::{Rocket, Request, Response, Data, Build};
+use crate::response;
+use crate::fairing::{self, Fairing, Info, Kind};
+use crate::http::{Header, Status};
+use crate::trace::Trace;
+use crate::shed::{ShedConfig, Priority};
+
+/// A [`Fairing`] that [sheds load](crate::shed) when overloaded.
+///
+/// Thresholds are read from the `shed` configuration parameter at ignition. If
+/// the parameter is invalid, ignition fails. If no threshold is configured,
+/// `Shedder` only counts requests in flight. Priorities set via
+/// [`Shedder::priority()`] apply to paths without a configured priority.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::shed::Shedder;
+/// use rocket::local::blocking::Client;
+///
+/// let client = Client::debug(rocket::build().attach(Shedder::new())).unwrap();
+/// client.get("/").dispatch();
+///
+/// let shedder = client.rocket().fairing::<Shedder>().unwrap();
+/// assert_eq!(shedder.shed(), 0);
+/// assert_eq!(shedder.in_flight(), 0);
+/// ```
+pub struct Shedder {
+    priorities: IndexMap<String, Priority>,
+    config: InitCell<ShedConfig>,
+    in_flight: Arc<AtomicUsize>,
+    shed: [AtomicU64; 3],
+    codel: Mutex<Codel>,
+}
+
+/// The decision for a request.
+enum Decision {
+    /// `Shedder` isn't attached.
+    Untracked,
+    /// The request was admitted and is in flight until the ticket is dropped.
+    Admitted(Ticket),
+    /// The request was shed.
+    Shed(Status),
+}
+
+/// A request in flight.
+struct Ticket {
+    start: Instant,
+    in_flight: Arc<AtomicUsize>,
+}
+
+/// The state of the CoDel control law.
+#[derive(Default)]
+struct Codel {
+    /// When latency, having exceeded the target, will have done so for an
+    /// interval, if it has exceeded the target.
+    first_above: Option<Instant>,
+    /// Whether latency has exceeded the target for an interval.
+    above: bool,
+    /// Whether requests are being shed.
+    dropping: bool,
+    /// When the next request is to be shed, if `dropping`.
+    drop_next: Option<Instant>,
+    /// The number of requests shed since `dropping` began.
+    count: u32,
+}
+
+impl Shedder {
+    /// Returns a `Shedder` fairing.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::shed::Shedder;
+    ///
+    /// let shedder = Shedder::new();
+    /// ```
+    pub fn new() -> Self {
+        Shedder {
+            priorities: IndexMap::new(),
+            config: InitCell::new(),
+            in_flight: Arc::new(AtomicUsize::new(0)),
+            shed: Default::default(),
+            codel: Mutex::new(Codel::default()),
+        }
+    }
+
+    /// Sets the priority of requests to `path` and paths under it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::shed::{Shedder, Priority};
+    ///
+    /// let shedder = Shedder::new()
+    ///     .priority("/health", Priority::Critical)
+    ///     .priority("/reports/export", Priority::Bulk);
+    /// ```
+    pub fn priority<P: Into<String>>(mut self, path: P, priority: Priority) -> Self {
+        self.priorities.insert(path.into(), priority);
+        self
+    }
+
+    /// Returns the number of requests shed so far.
+    pub fn shed(&self) -> u64 {
+        self.shed.iter().map(|count| count.load(Ordering::Relaxed)).sum()
+    }
+
+    /// Returns the number of requests of priority `priority` shed so far.
+    pub fn shed_of(&self, priority: Priority) -> u64 {
+        self.shed[priority as usize].load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of requests currently in flight.
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::Acquire)
+    }
+
+    /// Returns `true` if a request of `priority` arriving `now` should be shed.
+    fn should_shed(&self, config: &ShedConfig, priority: Priority, now: Instant) -> bool {
+        let limit = match priority {
+            Priority::Critical => return false,
+            Priority::Normal => config.max_in_flight,
+            Priority::Bulk => config.bulk_limit(),
+        };
+
+        if limit.map_or(false, |max| self.in_flight() >= max) {
+            return true;
+        }
+
+        if config.target().is_none() {
+            return false;
+        }
+
+        let mut codel = self.codel.lock();
+        match priority {
+            Priority::Bulk if codel.above => true,
+            _ => codel.shed(now, config.interval()),
+        }
+    }
+}
+
+impl Default for Shedder {
+    fn default() -> Self {
+        Shedder::new()
+    }
+}
+
+impl Drop for Ticket {
+    fn drop(&mut self) {
+        self.in_flight.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+impl Codel {
+    /// The interval after the `count`th request shed until the next.
+    fn control_law(interval: Duration, count: u32) -> Duration {
+        interval.div_f64(f64::from(count.max(1)).sqrt())
+    }
+
+    /// Records the `latency` of a request completed at `now`.
+    fn observe(&mut self, latency: Duration, now: Instant, target: Duration, interval: Duration) {
+        if latency < target {
+            self.first_above = None;
+            self.above = false;
+            return;
+        }
+
+        match self.first_above {
+            None => self.first_above = Some(now + interval),
+            Some(deadline) if now >= deadline => self.above = true,
+            Some(_) => {}
+        }
+    }
+
+    /// Returns `true` if a request arriving `now` should be shed.
+    fn shed(&mut self, now: Instant, interval: Duration) -> bool {
+        if !self.above {
+            if self.dropping {
+                self.dropping = false;
+                info!(name: "shed", shed = self.count, "latency recovered: no longer shedding");
+            }
+
+            return false;
+        }
+
+        if !self.dropping {
+            self.dropping = true;
+            self.count = 1;
+            self.drop_next = Some(now + Self::control_law(interval, self.count));
+            warn!(name: "shed", "latency exceeded target for an interval: shedding load");
+            return true;
+        }
+
+        match self.drop_next {
+            Some(next) if now >= next => {
+                self.count = self.count.saturating_add(1);
+                self.drop_next = Some(next + Self::control_law(interval, self.count));
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+#[crate::async_trait]
+impl Fairing for Shedder {
+    fn info(&self) -> Info {
+        Info {
+            name: "Shedder",
+            kind: Kind::Ignite | Kind::Request | Kind::Dispatch | Kind::Response | Kind::Singleton,
+        }
+    }
+
+    async fn on_ignite(&self, rocket: Rocket<Build>) -> fairing::Result {
+        let mut config = match rocket.figment().contains("shed") {
+            true => match rocket.figment().extract_inner::<ShedConfig>("shed") {
+                Ok(config) => config,
+                Err(e) => {
+                    e.trace_error();
+                    return Err(rocket);
+                }
+            },
+            false => ShedConfig::default(),
+        };
+
+        for (path, priority) in &self.priorities {
+            config.priorities.entry(path.clone()).or_insert(*priority);
+        }
+
+        self.config.set(config);
+        Ok(rocket)
+    }
+
+    async fn on_request(&self, req: &mut Request<'_>, _: &mut Data<'_>) {
+        let Some(config) = self.config.try_get() else { return };
+        let now = Instant::now();
+        let priority = config.priority(req.uri().path().as_str());
+        if self.should_shed(config, priority, now) {
+            self.shed[priority as usize].fetch_add(1, Ordering::Relaxed);
+            debug!(name: "shed", ?priority, in_flight = self.in_flight(), "request shed");
+            req.local_cache(|| Decision::Shed(config.status));
+            return;
+        }
+
+        self.in_flight.fetch_add(1, Ordering::AcqRel);
+        let in_flight = self.in_flight.clone();
+        req.local_cache(|| Decision::Admitted(Ticket { start: now, in_flight }));
+    }
+
+    async fn on_dispatch<'r>(&self, req: &'r Request<'_>) -> Option<response::Result<'r>> {
+        match req.local_cache(|| Decision::Untracked) {
+            Decision::Shed(status) => Some(Err(*status)),
+            _ => None,
+        }
+    }
+
+    async fn on_response<'r>(&self, req: &'r Request<'_>, res: &mut Response<'r>) {
+        let Some(config) = self.config.try_get() else { return };
+        match req.local_cache(|| Decision::Untracked) {
+            Decision::Admitted(ticket) => if let Some(target) = config.target() {
+                let now = Instant::now();
+                let latency = now - ticket.start;
+                self.codel.lock().observe(latency, now, target, config.interval());
+            },
+            Decision::Shed(_) => if let Some(secs) = config.retry_after {
+                res.set_header(Header::new("Retry-After", secs.to_string()));
+            },
+            Decision::Untracked => {},
+        }
+    }
+}
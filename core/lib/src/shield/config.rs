@@ -0,0 +1,190 @@
+use std::collections::HashMap;
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use time::Duration;
+
+use crate::http::{Header, uri::Absolute, uncased::UncasedStr, ext::IntoOwned};
+use crate::shield::{Policy, Csp, Hsts, Permission, Allow, SecurityTxt};
+
+/// The [`Shield`] configuration: the `shield` configuration parameter.
+///
+/// Policies configured here take precedence over those enabled in code, so
+/// headers can be tightened, or loosened, without changing the application.
+/// Each field that is set replaces the corresponding policy, and policies named
+/// in `disable` are removed after all others are applied. If any field is
+/// invalid, ignition fails.
+///
+/// ```toml
+/// [default.shield]
+/// csp = { default-src = ["'self'"], img-src = ["'self'", "data:"] }
+/// hsts = { max_age = 63072000, include_subdomains = true }
+/// permissions = { camera = [], geolocation = ["self", "https://maps.rocket.rs"] }
+/// disable = ["X-Frame-Options"]
+///
+/// [default.shield.security_txt]
+/// contact = ["mailto:security@rocket.rs"]
+/// expires = "2030-01-01T00:00:00Z"
+/// ```
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::shield::ShieldConfig;
+/// use rocket::figment::{Figment, providers::{Format, Toml}};
+///
+/// let figment = Figment::from(Toml::string(r#"
+///     [shield]
+///     csp = { default-src = ["'self'"] }
+///     hsts = { max_age = 600 }
+/// "#));
+///
+/// let config: ShieldConfig = figment.extract_inner("shield").unwrap();
+/// assert_eq!(config.csp.unwrap()["default-src"], ["'self'"]);
+/// assert_eq!(config.hsts.unwrap().max_age, 600);
+/// assert!(config.permissions.is_none());
+/// ```
+///
+/// [`Shield`]: crate::shield::Shield
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ShieldConfig {
+    /// Enables [`Csp`] with the given directives and their sources. Sources
+    /// are written as they appear in the header, keywords quoted.
+    ///
+    /// **default: `None`**
+    pub csp: Option<IndexMap<String, Vec<String>>>,
+    /// Enables [`Hsts`].
+    ///
+    /// **default: `None`**
+    pub hsts: Option<HstsConfig>,
+    /// Enables [`Permission`] with the given features and their allow lists.
+    /// An allow list contains `"self"`, `"*"`, or origins such as
+    /// `"https://rocket.rs"`. An empty list blocks the feature.
+    ///
+    /// **default: `None`**
+    pub permissions: Option<IndexMap<String, Vec<String>>>,
+    /// Names of policy headers to disable, such as `"X-Frame-Options"`.
+    ///
+    /// **default: `[]`**
+    pub disable: Vec<String>,
+    /// A [`SecurityTxt`] to serve at `/.well-known/security.txt`.
+    ///
+    /// **default: `None`**
+    pub security_txt: Option<SecurityTxt>,
+}
+
+/// The configuration of [`Hsts`] in [`ShieldConfig`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HstsConfig {
+    /// The `max-age`, in seconds.
+    ///
+    /// **default: `31536000` (365 days)**
+    pub max_age: u32,
+    /// Whether the policy applies to subdomains.
+    ///
+    /// **default: `false`**
+    pub include_subdomains: bool,
+    /// Whether to request inclusion in the HSTS preload list. Implies
+    /// `include_subdomains`. See [`Hsts::Preload`].
+    ///
+    /// **default: `false`**
+    pub preload: bool,
+}
+
+impl Default for HstsConfig {
+    fn default() -> Self {
+        HstsConfig { max_age: 31536000, include_subdomains: false, preload: false }
+    }
+}
+
+impl From<&HstsConfig> for Hsts {
+    fn from(config: &HstsConfig) -> Self {
+        let age = Duration::seconds(config.max_age.into());
+        match config {
+            HstsConfig { preload: true, .. } => Hsts::Preload(age),
+            HstsConfig { include_subdomains: true, .. } => Hsts::IncludeSubDomains(age),
+            _ => Hsts::Enable(age),
+        }
+    }
+}
+
+fn is_token(s: &str) -> bool {
+    !s.is_empty() && s.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-')
+}
+
+impl ShieldConfig {
+    /// Applies the configured policies to `policies`, returning a description
+    /// of the first invalid field, if any.
+    pub(crate) fn apply(
+        &self,
+        policies: &mut HashMap<&'static UncasedStr, Header<'static>>
+    ) -> Result<(), String> {
+        if let Some(directives) = &self.csp {
+            let mut csp = Csp::empty();
+            for (name, sources) in directives {
+                if !is_token(name) {
+                    return Err(format!("invalid CSP directive {:?}", name));
+                }
+
+                let invalid = |s: &String| s.is_empty() || s.chars().any(|c| {
+                    c == ';' || c == ',' || c.is_whitespace() || c.is_control()
+                });
+
+                if let Some(source) = sources.iter().find(|s| invalid(s)) {
+                    return Err(format!("invalid CSP source {:?} in {:?}", source, name));
+                }
+
+                csp = csp.directive(name, sources);
+            }
+
+            policies.insert(Csp::NAME.into(), csp.header());
+        }
+
+        if let Some(hsts) = &self.hsts {
+            policies.insert(Hsts::NAME.into(), Hsts::from(hsts).header());
+        }
+
+        if let Some(features) = &self.permissions {
+            let mut value = Vec::with_capacity(features.len());
+            for (feature, allow) in features {
+                if !is_token(feature) {
+                    return Err(format!("invalid permission feature {:?}", feature));
+                }
+
+                let mut list = Vec::with_capacity(allow.len());
+                for origin in allow {
+                    let allow = match origin.as_str() {
+                        "self" => Allow::This,
+                        "*" => Allow::Any,
+                        _ => match Absolute::parse(origin) {
+                            Ok(uri) if uri.authority().map_or(false, |a| !a.host().is_empty()) => {
+                                Allow::Origin(uri.into_owned())
+                            }
+                            _ => return Err(format!("invalid origin {origin:?} for {feature:?}")),
+                        }
+                    };
+
+                    list.push(allow);
+                }
+
+                if list.contains(&Allow::Any) {
+                    list = vec![Allow::Any];
+                }
+
+                let list: Vec<_> = list.iter().map(|allow| allow.rendered()).collect();
+                value.push(format!("{}=({})", feature, list.join(" ")));
+            }
+
+            let header = Header::new(Permission::NAME, value.join(", "));
+            policies.insert(Permission::NAME.into(), header);
+        }
+
+        for name in &self.disable {
+            policies.remove(UncasedStr::new(name));
+        }
+
+        Ok(())
+    }
+}
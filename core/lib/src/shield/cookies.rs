@@ -0,0 +1,123 @@
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use parking_lot::Mutex;
+
+use crate::{Rocket, Request, Response, Orbit, Config};
+use crate::fairing::{Fairing, Info, Kind};
+use crate::http::{Cookie, CookiePrefix};
+
+/// A [`Fairing`] that warns about session cookies set without a
+/// [`CookiePrefix`] and about prefixed cookies browsers will reject.
+///
+/// Outside of the `debug` profile, `CookiePrefixes` inspects the `Set-Cookie`
+/// headers of every response and logs a warning, once per cookie name, when:
+///
+///   * a session cookie's name lacks a `__Host-` or `__Secure-` prefix, or
+///   * a prefixed cookie does not meet its prefix's requirements, in which
+///     case browsers silently reject it.
+///
+/// A session cookie is one without `Expires` or `Max-Age` as well as any
+/// cookie named via [`CookiePrefixes::session()`]. Use
+/// [`CookiePrefixExt`](crate::http::CookiePrefixExt) to build prefixed cookies.
+/// Responses are never modified.
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::shield::CookiePrefixes;
+///
+/// #[launch]
+/// fn rocket() -> _ {
+///     rocket::build().attach(CookiePrefixes::new().session("user_id"))
+/// }
+/// ```
+#[derive(Default)]
+pub struct CookiePrefixes {
+    sessions: Vec<String>,
+    enabled: AtomicBool,
+    reported: Mutex<HashSet<String>>,
+}
+
+impl CookiePrefixes {
+    /// Returns a `CookiePrefixes` policy that treats cookies without
+    /// `Expires` or `Max-Age` as session cookies.
+    pub fn new() -> Self {
+        CookiePrefixes::default()
+    }
+
+    /// Additionally treats cookies named `name` as session cookies, even if
+    /// they expire. The name is compared without any prefix.
+    pub fn session<N: Into<String>>(mut self, name: N) -> Self {
+        self.sessions.push(name.into());
+        self
+    }
+
+    /// Returns the problem with `cookie`, if any.
+    fn check(&self, cookie: &Cookie<'_>) -> Option<String> {
+        match CookiePrefix::of(cookie.name()) {
+            Some(prefix) => prefix.check(cookie).err().map(|e| e.to_string()),
+            None => {
+                let session = cookie.expires().is_none() && cookie.max_age().is_none();
+                let named = self.sessions.iter().any(|n| n == cookie.name());
+                (session || named).then(|| {
+                    "session cookie lacks a `__Host-` or `__Secure-` prefix".to_string()
+                })
+            }
+        }
+    }
+}
+
+#[crate::async_trait]
+impl Fairing for CookiePrefixes {
+    fn info(&self) -> Info {
+        Info {
+            name: "Cookie Prefixes",
+            kind: Kind::Liftoff | Kind::Response | Kind::Singleton,
+        }
+    }
+
+    async fn on_liftoff(&self, rocket: &Rocket<Orbit>) {
+        let enabled = rocket.config().profile != Config::DEBUG_PROFILE;
+        self.enabled.store(enabled, Ordering::Release);
+    }
+
+    async fn on_response<'r>(&self, _: &'r Request<'_>, res: &mut Response<'r>) {
+        if !self.enabled.load(Ordering::Acquire) {
+            return;
+        }
+
+        for value in res.headers().get("Set-Cookie") {
+            let Ok(cookie) = Cookie::parse(value) else { continue };
+            let Some(problem) = self.check(&cookie) else { continue };
+            if self.reported.lock().insert(cookie.name().to_string()) {
+                warn!(name: "cookies", cookie = cookie.name(), "{problem}");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::CookiePrefixExt;
+
+    #[test]
+    fn check_cookies() {
+        let policy = CookiePrefixes::new().session("user_id");
+        assert!(policy.check(&Cookie::new("theme", "dark")).is_some());
+
+        let cookie = Cookie::build(("theme", "dark")).max_age(time::Duration::DAY);
+        assert!(policy.check(&cookie.build()).is_none());
+
+        let cookie = Cookie::build(("user_id", "1")).max_age(time::Duration::DAY);
+        assert!(policy.check(&cookie.build()).is_some());
+
+        let cookie = Cookie::build(("id", "1")).host_prefixed().unwrap();
+        assert!(policy.check(&cookie).is_none());
+
+        let cookie = Cookie::build(("__Host-id", "1")).secure(true).build();
+        assert!(policy.check(&cookie).unwrap().contains("Path"));
+    }
+}
@@ -0,0 +1,226 @@
+use std::fmt;
+
+use crate::{Rocket, Build, Config};
+use crate::config::CookieConfig;
+use crate::http::SameSite;
+use crate::fairing::{self, Fairing, Info, Kind};
+use crate::shield::{Shield, Hsts, NoSniff, Frame};
+
+/// An insecure configuration detected by [`SecurityLint`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    /// A stable identifier for the check, usable with [`SecurityLint::allow()`].
+    pub code: &'static str,
+    /// What is insecure and how to fix it.
+    pub message: String,
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {}", self.code, self.message)
+    }
+}
+
+/// An opt-in [`Fairing`] that checks for insecure header, cookie, and TLS
+/// configurations at ignition.
+///
+/// When the application is ignited in any profile other than `debug`,
+/// `SecurityLint` inspects the attached [`Shield`], the TLS and proxy
+/// configuration, and the [`cookies`](crate::config::CookieConfig) defaults,
+/// and reports every insecure combination it finds, each with an actionable
+/// message. HTTPS is considered in use if `tls` is configured or if
+/// `proxy_proto_header` is set, indicating that a proxy terminates TLS. The
+/// checks, identified by their code, are:
+///
+/// | code                    | finding                                                 |
+/// |-------------------------|---------------------------------------------------------|
+/// | `shield-missing`        | `Shield` is not attached or has no enabled policies.    |
+/// | `nosniff-disabled`      | `Shield` does not enable [`NoSniff`].                   |
+/// | `frame-disabled`        | `Shield` does not enable [`Frame`].                     |
+/// | `tls-unavailable`       | `tls` is configured but the `tls` feature is disabled.  |
+/// | `hsts-without-tls`      | [`Hsts`] is enabled but HTTPS is never used.            |
+/// | `insecure-cookies`      | Cookies are never marked `Secure`: HTTPS is never used. |
+/// | `cookies-not-secure`    | HTTPS is used but `cookies.secure` is `false`.          |
+/// | `cookies-not-http-only` | `cookies.http_only` is `false`.                         |
+/// | `same-site-none`        | `cookies.default_same_site` is `"none"`.                |
+///
+/// By default, findings are logged as warnings. With [`SecurityLint::deny()`],
+/// ignition fails if there are any findings. Checks can be silenced
+/// individually with [`SecurityLint::allow()`].
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::shield::SecurityLint;
+///
+/// #[launch]
+/// fn rocket() -> _ {
+///     // Refuse to launch with an insecure configuration outside of `debug`,
+///     // but allow pages to be framed.
+///     rocket::build().attach(SecurityLint::deny().allow("frame-disabled"))
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SecurityLint {
+    deny: bool,
+    allowed: Vec<&'static str>,
+}
+
+impl SecurityLint {
+    /// Returns a `SecurityLint` that logs findings as warnings.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::shield::SecurityLint;
+    ///
+    /// let lint = SecurityLint::warn();
+    /// ```
+    pub fn warn() -> Self {
+        SecurityLint { deny: false, allowed: vec![] }
+    }
+
+    /// Returns a `SecurityLint` that logs findings as errors and fails
+    /// ignition if there are any.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::shield::SecurityLint;
+    ///
+    /// let lint = SecurityLint::deny();
+    /// ```
+    pub fn deny() -> Self {
+        SecurityLint { deny: true, allowed: vec![] }
+    }
+
+    /// Silences the check identified by `code`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::shield::SecurityLint;
+    ///
+    /// let lint = SecurityLint::deny().allow("frame-disabled");
+    /// ```
+    pub fn allow(mut self, code: &'static str) -> Self {
+        self.allowed.push(code);
+        self
+    }
+
+    /// Runs all checks against `rocket`, regardless of its profile, and
+    /// returns the findings that aren't [allowed](SecurityLint::allow()).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::shield::{SecurityLint, Shield};
+    ///
+    /// let rocket = rocket::build().attach(Shield::new());
+    /// let findings = SecurityLint::warn().check(&rocket);
+    /// assert!(findings.iter().any(|f| f.code == "shield-missing"));
+    /// ```
+    pub fn check(&self, rocket: &Rocket<Build>) -> Vec<Finding> {
+        let figment = rocket.figment();
+        let tls = figment.contains("tls");
+        let proxied = figment.extract_inner::<String>(Config::PROXY_PROTO_HEADER).is_ok();
+        let https = (tls && cfg!(feature = "tls")) || proxied;
+        let shield = rocket.fairing::<Shield>();
+
+        let mut findings = vec![];
+        let mut find = |code, message: &str| {
+            findings.push(Finding { code, message: message.into() })
+        };
+
+        match shield {
+            Some(shield) if !shield.is_empty() => {
+                if !shield.is_enabled::<NoSniff>() {
+                    find("nosniff-disabled", "`Shield` does not enable `NoSniff`: browsers may \
+                        MIME-sniff responses. Enable it with `.enable(NoSniff::default())`.");
+                }
+
+                if !shield.is_enabled::<Frame>() {
+                    find("frame-disabled", "`Shield` does not enable `Frame`: pages may be \
+                        framed by other sites. Enable it with `.enable(Frame::default())`.");
+                }
+
+                if shield.is_enabled::<Hsts>() && !https {
+                    find("hsts-without-tls", "`Hsts` is enabled but neither `tls` nor \
+                        `proxy_proto_header` is configured: browsers ignore HSTS over HTTP. \
+                        Configure TLS or, if a proxy terminates TLS, `proxy_proto_header`.");
+                }
+            }
+            _ => find("shield-missing", "`Shield` is not attached or enables no policies: \
+                responses lack security headers. Attach `Shield::default()`."),
+        }
+
+        if tls && !cfg!(feature = "tls") {
+            find("tls-unavailable", "`tls` is configured but the `tls` feature is disabled: \
+                the server will use plain HTTP. Enable the `tls` feature of `rocket`.");
+        }
+
+        if !https {
+            find("insecure-cookies", "neither `tls` nor `proxy_proto_header` is configured: \
+                cookies are never marked `Secure`. Configure TLS or, if a proxy terminates \
+                TLS, set `proxy_proto_header` to the header it sets, e.g. `X-Forwarded-Proto`.");
+        }
+
+        let cookies = figment.extract_inner::<CookieConfig>("cookies").unwrap_or_default();
+        if https && cookies.secure == Some(false) {
+            find("cookies-not-secure", "HTTPS is used but `cookies.secure` is `false`: \
+                cookies may be sent over plain HTTP. Remove `cookies.secure` or set it to \
+                `true`.");
+        }
+
+        if cookies.http_only == Some(false) {
+            find("cookies-not-http-only", "`cookies.http_only` is `false`: scripts can read \
+                cookies, including private cookies, exposing them to cross-site scripting. \
+                Remove `cookies.http_only` or set it to `true`.");
+        }
+
+        if cookies.default_same_site == SameSite::None {
+            find("same-site-none", "`cookies.default_same_site` is `\"none\"`: cookies are \
+                sent with cross-site requests, enabling cross-site request forgery. Set it to \
+                `\"lax\"` or `\"strict\"`.");
+        }
+
+        findings.retain(|f| !self.allowed.contains(&f.code));
+        findings
+    }
+}
+
+#[crate::async_trait]
+impl Fairing for SecurityLint {
+    fn info(&self) -> Info {
+        Info {
+            name: "Security Lint",
+            kind: Kind::Ignite | Kind::Singleton,
+        }
+    }
+
+    async fn on_ignite(&self, rocket: Rocket<Build>) -> fairing::Result {
+        if rocket.figment().profile() == Config::DEBUG_PROFILE {
+            return Ok(rocket);
+        }
+
+        let findings = self.check(&rocket);
+        if findings.is_empty() {
+            return Ok(rocket);
+        }
+
+        span_warn!("security lint", findings = findings.len() => {
+            for finding in &findings {
+                match self.deny {
+                    true => error!(code = finding.code, "{}", finding.message),
+                    false => warn!(code = finding.code, "{}", finding.message),
+                }
+            }
+        });
+
+        match self.deny {
+            true => Err(rocket),
+            false => Ok(rocket),
+        }
+    }
+}
@@ -10,16 +10,20 @@
 //!
 //! # Supported Headers
 //!
-//! | HTTP Header                 | Description                            | Policy         | Default? |
-//! | --------------------------- | -------------------------------------- | -------------- | -------- |
-//! | [X-XSS-Protection]          | Prevents some reflected XSS attacks.   | [`XssFilter`]  | ✗        |
-//! | [X-Content-Type-Options]    | Prevents client sniffing of MIME type. | [`NoSniff`]    | ✔        |
-//! | [X-Frame-Options]           | Prevents [clickjacking].               | [`Frame`]      | ✔        |
-//! | [Strict-Transport-Security] | Enforces strict use of HTTPS.          | [`Hsts`]       | ?        |
-//! | [Expect-CT]                 | Enables certificate transparency.      | [`ExpectCt`]   | ✗        |
-//! | [Referrer-Policy]           | Enables referrer policy.               | [`Referrer`]   | ✗        |
-//! | [X-DNS-Prefetch-Control]    | Controls browser DNS prefetching.      | [`Prefetch`]   | ✗        |
-//! | [Permissions-Policy]        | Allows or block browser features.      | [`Permission`] | ✔        |
+//! | HTTP Header                    | Description                            | Policy         | Default? |
+//! | ------------------------------ | -------------------------------------- | -------------- | -------- |
+//! | [X-XSS-Protection]             | Prevents some reflected XSS attacks.   | [`XssFilter`]  | ✗        |
+//! | [X-Content-Type-Options]       | Prevents client sniffing of MIME type. | [`NoSniff`]    | ✔        |
+//! | [X-Frame-Options]              | Prevents [clickjacking].               | [`Frame`]      | ✔        |
+//! | [Strict-Transport-Security]    | Enforces strict use of HTTPS.          | [`Hsts`]       | ?        |
+//! | [Expect-CT]                    | Enables certificate transparency.      | [`ExpectCt`]   | ✗        |
+//! | [Referrer-Policy]              | Enables referrer policy.               | [`Referrer`]   | ✗        |
+//! | [X-DNS-Prefetch-Control]       | Controls browser DNS prefetching.      | [`Prefetch`]   | ✗        |
+//! | [Permissions-Policy]           | Allows or block browser features.      | [`Permission`] | ✔        |
+//! | [Content-Security-Policy]      | Restricts the sources of content.      | [`Csp`]        | ✗        |
+//! | [Cross-Origin-Opener-Policy]   | Isolates the browsing context.         | [`Coop`]       | ✗        |
+//! | [Cross-Origin-Embedder-Policy] | Restricts embedded resources.          | [`Coep`]       | ✗        |
+//! | [Cross-Origin-Resource-Policy] | Restricts who may embed a resource.    | [`Corp`]       | ✗        |
 //!
 //! <small>? If TLS is enabled in a non-debug profile, HSTS is automatically
 //! enabled with its default policy and a warning is logged at liftoff.</small>
@@ -33,6 +37,10 @@
 //! [X-DNS-Prefetch-Control]: https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/X-DNS-Prefetch-Control
 //! [clickjacking]: https://en.wikipedia.org/wiki/Clickjacking
 //! [Permissions-Policy]: https://github.com/w3c/webappsec-permissions-policy/blob/a45df7b237e2a85e1909d7f226ca4eb4ce5095ba/permissions-policy-explainer.md
+//! [Content-Security-Policy]: https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Content-Security-Policy
+//! [Cross-Origin-Opener-Policy]: https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Cross-Origin-Opener-Policy
+//! [Cross-Origin-Embedder-Policy]: https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Cross-Origin-Embedder-Policy
+//! [Cross-Origin-Resource-Policy]: https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Cross-Origin-Resource-Policy
 //!
 //! [`XssFilter`]: self::XssFilter
 //! [`NoSniff`]: self::NoSniff
@@ -81,6 +89,42 @@
 //!     .disable::<NoSniff>();
 //! ```
 //!
+//! Frontends that use `SharedArrayBuffer` must be [cross-origin isolated]. The
+//! [`Shield::cross_origin_isolated()`] preset enables the necessary [`Coop`],
+//! [`Coep`], and [`Corp`] policies in addition to the defaults.
+//!
+//! [cross-origin isolated]: https://developer.mozilla.org/en-US/docs/Web/API/crossOriginIsolated
+//!
+//! # Configuration
+//!
+//! Policies can also be set via the `shield` configuration parameter, a
+//! [`ShieldConfig`], which takes precedence over policies enabled in code. This
+//! lets operators tighten headers without rebuilding the application:
+//!
+//! ```toml
+//! [release.shield]
+//! csp = { default-src = ["'self'"], frame-ancestors = [] }
+//! hsts = { max_age = 63072000, include_subdomains = true }
+//! permissions = { camera = [], microphone = ["self"] }
+//! disable = ["X-Frame-Options"]
+//! ```
+//!
+//! The same parameter can configure a [`SecurityTxt`], which is then served at
+//! `/.well-known/security.txt`.
+//!
+//! # Scoped Headers
+//!
+//! `Shield` applies the same policies to every response. To add, override, or
+//! remove request or response headers only under certain paths, possibly via
+//! configuration, attach [`HeaderRules`].
+//!
+//! # Linting
+//!
+//! To check for insecure combinations of `Shield` policies, cookie, and TLS
+//! settings outside of the `debug` profile, attach [`SecurityLint`]. To be
+//! warned about session cookies that lack a `__Host-` or `__Secure-` prefix,
+//! attach [`CookiePrefixes`].
+//!
 //! # FAQ
 //!
 //! * **Which policies should I choose?**
@@ -99,6 +143,16 @@
 
 mod shield;
 mod policy;
+mod rules;
+mod lint;
+mod cookies;
+mod config;
+mod security_txt;
 
 pub use self::shield::Shield;
+pub use self::config::{ShieldConfig, HstsConfig};
+pub use self::security_txt::SecurityTxt;
 pub use self::policy::*;
+pub use self::rules::{HeaderRules, HeaderRule, HeaderActions};
+pub use self::lint::{SecurityLint, Finding};
+pub use self::cookies::CookiePrefixes;
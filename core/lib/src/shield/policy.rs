@@ -84,6 +84,10 @@ impl_policy!(ExpectCt, "Expect-CT");
 impl_policy!(Referrer, "Referrer-Policy");
 impl_policy!(Prefetch, "X-DNS-Prefetch-Control");
 impl_policy!(Permission, "Permissions-Policy");
+impl_policy!(Csp, "Content-Security-Policy");
+impl_policy!(Coop, "Cross-Origin-Opener-Policy");
+impl_policy!(Coep, "Cross-Origin-Embedder-Policy");
+impl_policy!(Corp, "Cross-Origin-Resource-Policy");
 
 /// The [Referrer-Policy] header: controls the value set by the browser for the
 /// [Referer] header.
@@ -429,6 +433,214 @@ impl From<&Prefetch> for Header<'static> {
     }
 }
 
+/// The [Cross-Origin-Opener-Policy] header: isolates the browsing context.
+///
+/// Tells the browser whether a top-level document may share its browsing
+/// context group with cross-origin documents it opens or that open it. When
+/// isolated, cross-origin windows can't reference or script each other, which
+/// mitigates cross-origin attacks such as [XS-Leaks]. Together with [`Coep`],
+/// a `same-origin` policy makes the document [cross-origin isolated], enabling
+/// features such as `SharedArrayBuffer`. See [`Shield::cross_origin_isolated()`].
+///
+/// [Cross-Origin-Opener-Policy]: https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Cross-Origin-Opener-Policy
+/// [XS-Leaks]: https://xsleaks.dev
+/// [cross-origin isolated]: https://developer.mozilla.org/en-US/docs/Web/API/crossOriginIsolated
+/// [`Shield::cross_origin_isolated()`]: crate::shield::Shield::cross_origin_isolated()
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Coop {
+    /// Shares the browsing context group with any document. This is the
+    /// browser default.
+    UnsafeNone,
+    /// Shares the browsing context group with same-origin documents and with
+    /// popups the document opens that don't set a policy.
+    SameOriginAllowPopups,
+    /// Shares the browsing context group only with same-origin documents
+    /// that also set this policy. This is the shield policy default.
+    #[default]
+    SameOrigin,
+}
+
+impl From<&Coop> for Header<'static> {
+    fn from(coop: &Coop) -> Self {
+        let policy_string = match coop {
+            Coop::UnsafeNone => "unsafe-none",
+            Coop::SameOriginAllowPopups => "same-origin-allow-popups",
+            Coop::SameOrigin => "same-origin",
+        };
+
+        Header::new(Coop::NAME, policy_string)
+    }
+}
+
+/// The [Cross-Origin-Embedder-Policy] header: restricts embedded cross-origin
+/// resources.
+///
+/// Tells the browser to only load cross-origin resources, such as images,
+/// scripts, and frames, into the document if they explicitly allow it, via
+/// [CORS] or a [`Corp`] header, or, with `credentialless`, to load them
+/// without credentials. Together with a `same-origin` [`Coop`], this makes the
+/// document [cross-origin isolated]. See [`Shield::cross_origin_isolated()`].
+///
+/// [Cross-Origin-Embedder-Policy]: https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Cross-Origin-Embedder-Policy
+/// [CORS]: https://developer.mozilla.org/en-US/docs/Web/HTTP/CORS
+/// [cross-origin isolated]: https://developer.mozilla.org/en-US/docs/Web/API/crossOriginIsolated
+/// [`Shield::cross_origin_isolated()`]: crate::shield::Shield::cross_origin_isolated()
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Coep {
+    /// Allows loading any cross-origin resource. This is the browser default.
+    UnsafeNone,
+    /// Only allows loading cross-origin resources that grant permission via
+    /// CORS or a `Cross-Origin-Resource-Policy` header. This is the shield
+    /// policy default.
+    #[default]
+    RequireCorp,
+    /// Loads cross-origin `no-cors` requests without credentials, such as
+    /// cookies, instead of requiring them to grant permission.
+    Credentialless,
+}
+
+impl From<&Coep> for Header<'static> {
+    fn from(coep: &Coep) -> Self {
+        let policy_string = match coep {
+            Coep::UnsafeNone => "unsafe-none",
+            Coep::RequireCorp => "require-corp",
+            Coep::Credentialless => "credentialless",
+        };
+
+        Header::new(Coep::NAME, policy_string)
+    }
+}
+
+/// The [Cross-Origin-Resource-Policy] header: restricts who may embed a
+/// resource.
+///
+/// Tells the browser to block `no-cors` requests for the resource from other
+/// origins or sites, mitigating speculative side-channel attacks such as
+/// [Spectre]. Resources meant to be embedded by cross-origin isolated
+/// documents on other origins must set `cross-origin`.
+///
+/// [Cross-Origin-Resource-Policy]: https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Cross-Origin-Resource-Policy
+/// [Spectre]: https://meltdownattack.com
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Corp {
+    /// Only allows requests from the same site.
+    SameSite,
+    /// Only allows requests from the same origin. This is the shield policy
+    /// default.
+    #[default]
+    SameOrigin,
+    /// Allows requests from any origin.
+    CrossOrigin,
+}
+
+impl From<&Corp> for Header<'static> {
+    fn from(corp: &Corp) -> Self {
+        let policy_string = match corp {
+            Corp::SameSite => "same-site",
+            Corp::SameOrigin => "same-origin",
+            Corp::CrossOrigin => "cross-origin",
+        };
+
+        Header::new(Corp::NAME, policy_string)
+    }
+}
+
+/// The [Content-Security-Policy] header: restricts the sources of content.
+///
+/// Tells the browser which sources scripts, styles, images, frames, and other
+/// content may be loaded from, mitigating cross-site scripting and data
+/// injection attacks. A policy is a list of directives, each with a list of
+/// sources, set via the chainable [`directive()`](Self::directive()) method.
+/// Sources are written as they appear in the header, keywords quoted:
+///
+/// ```rust
+/// use rocket::shield::{Shield, Csp};
+///
+/// let csp = Csp::default()
+///     .directive("img-src", ["'self'", "data:"])
+///     .directive("frame-ancestors", ["'none'"]);
+///
+/// rocket::build().attach(Shield::default().enable(csp));
+/// ```
+///
+/// # Default
+///
+/// The default returned via [`Csp::default()`] only allows content from the
+/// serving origin: `default-src 'self'`.
+///
+/// [Content-Security-Policy]: https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Content-Security-Policy
+#[derive(Debug, PartialEq, Clone)]
+pub struct Csp(IndexMap<String, Vec<String>>);
+
+impl Default for Csp {
+    fn default() -> Self {
+        Csp::empty().directive("default-src", ["'self'"])
+    }
+}
+
+impl Csp {
+    /// A policy with no directives.
+    pub(crate) fn empty() -> Self {
+        Csp(IndexMap::new())
+    }
+
+    /// Sets the sources of the directive `name` to `sources`. An empty list of
+    /// sources is rendered as `'none'`.
+    ///
+    /// This supersedes any sources previously set for `name`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::shield::Csp;
+    ///
+    /// let csp = Csp::default()
+    ///     .directive("script-src", ["'self'", "https://cdn.rocket.rs"])
+    ///     .directive("object-src", Vec::<String>::new());
+    /// ```
+    pub fn directive<N, S>(mut self, name: N, sources: S) -> Self
+        where N: Into<String>, S: IntoIterator, S::Item: Into<String>
+    {
+        self.0.insert(name.into(), sources.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Returns the sources of the directive `name`, if it is set.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::shield::Csp;
+    ///
+    /// let csp = Csp::default();
+    /// assert_eq!(csp.get("default-src").unwrap(), &["'self'"]);
+    /// assert!(csp.get("img-src").is_none());
+    /// ```
+    pub fn get(&self, name: &str) -> Option<&[String]> {
+        Some(self.0.get(name)?)
+    }
+
+    /// Returns an iterator over the pairs of directive names and their sources
+    /// in the order in which they were first set.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &[String])> {
+        self.0.iter().map(|(name, sources)| (name.as_str(), &**sources))
+    }
+}
+
+impl From<&Csp> for Header<'static> {
+    fn from(csp: &Csp) -> Self {
+        let value = csp.0.iter()
+            .map(|(name, sources)| match sources.is_empty() {
+                true => format!("{} 'none'", name),
+                false => format!("{} {}", name, sources.join(" ")),
+            })
+            .collect::<Vec<_>>()
+            .join("; ");
+
+        Header::new(Csp::NAME, value)
+    }
+}
+
 /// The [Permissions-Policy] header: allow or block the use of browser features.
 ///
 /// Tells the browser to allow or block the use of a browser feature in the
@@ -689,7 +901,7 @@ pub enum Allow {
 }
 
 impl Allow {
-    fn rendered(&self) -> Cow<'static, str> {
+    pub(crate) fn rendered(&self) -> Cow<'static, str> {
         match self {
             Allow::Origin(uri) => {
                 let mut string = String::with_capacity(32);
@@ -0,0 +1,301 @@
+use indexmap::IndexMap;
+use serde::Deserialize;
+use state::InitCell;
+
+use crate::{Rocket, Request, Response, Data, Build};
+use crate::fairing::{self, Fairing, Info, Kind};
+use crate::http::Header;
+use crate::trace::Trace;
+
+/// Header changes applied by a [`HeaderRule`] to a request or response.
+///
+/// Changes are applied in the following order: headers in `remove` are
+/// removed, headers in `set` are set, replacing any existing values, and
+/// headers in `default` are set only if the request or response doesn't
+/// already contain a header of the same name.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::shield::HeaderActions;
+///
+/// let actions = HeaderActions::new()
+///     .set("X-Frame-Options", "DENY")
+///     .set_default("Cache-Control", "no-store")
+///     .remove("Server");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct HeaderActions {
+    /// Headers to set, replacing existing values.
+    pub set: IndexMap<String, String>,
+    /// Headers to set only when absent.
+    pub default: IndexMap<String, String>,
+    /// Names of headers to remove.
+    pub remove: Vec<String>,
+}
+
+/// Header changes for requests and responses whose path is under `path`.
+///
+/// A rule applies to a request if its path is equal to `path` or, segment-wise,
+/// begins with `path`. As such, a rule for `/app` applies to `/app` and
+/// `/app/login` but not to `/apple`, and a rule for `/` applies to all
+/// requests. See [`HeaderRules`] for usage.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::shield::{HeaderRule, HeaderActions};
+///
+/// let rule = HeaderRule::new("/api")
+///     .request(HeaderActions::new().set_default("Accept", "application/json"))
+///     .response(HeaderActions::new().remove("Server"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct HeaderRule {
+    /// The path prefix the rule applies to.
+    #[serde(default = "HeaderRule::root")]
+    pub path: String,
+    /// The changes applied to incoming requests.
+    #[serde(default)]
+    pub request: HeaderActions,
+    /// The changes applied to outgoing responses.
+    #[serde(default)]
+    pub response: HeaderActions,
+}
+
+/// A [`Fairing`] that applies [`HeaderRule`]s to requests and responses.
+///
+/// Unlike [`Shield`](crate::shield::Shield), which sets headers on every
+/// response, `HeaderRules` applies header changes only under certain paths and
+/// to requests as well as responses. Rules can be configured programmatically
+/// and via the `headers` configuration parameter, an array of rules. Rules from
+/// configuration are applied after programmatically added rules, and rules are
+/// otherwise applied in the order they were added, so later rules take
+/// precedence over earlier ones.
+///
+/// Request changes are applied before routing, so they are observed by
+/// request guards and handlers, and by request fairings attached after
+/// `HeaderRules`. Response changes are applied after the `Server` header is
+/// set and so can remove it.
+///
+/// If the `headers` configuration parameter is invalid, or any configured
+/// header name or value is invalid, ignition fails.
+///
+/// # Example
+///
+/// Add `X-Frame-Options: DENY` under `/app` and strip `Server` everywhere via
+/// `Rocket.toml`:
+///
+/// ```toml
+/// [default]
+/// headers = [
+///     { path = "/app", response.set = { "X-Frame-Options" = "DENY" } },
+///     { path = "/", response.remove = ["Server"] },
+/// ]
+/// ```
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::shield::{HeaderRules, HeaderRule, HeaderActions};
+///
+/// #[launch]
+/// fn rocket() -> _ {
+///     // Rules in `Rocket.toml` are applied after this one.
+///     let rule = HeaderRule::new("/api")
+///         .request(HeaderActions::new().set_default("Accept", "application/json"));
+///
+///     rocket::build().attach(HeaderRules::new().rule(rule))
+/// }
+/// ```
+#[derive(Default)]
+pub struct HeaderRules {
+    rules: Vec<HeaderRule>,
+    configured: InitCell<Vec<HeaderRule>>,
+}
+
+impl HeaderActions {
+    /// Returns an empty set of header changes.
+    pub fn new() -> Self {
+        HeaderActions::default()
+    }
+
+    /// Sets the header `name` to `value`, replacing existing values.
+    pub fn set<N: Into<String>, V: Into<String>>(mut self, name: N, value: V) -> Self {
+        self.set.insert(name.into(), value.into());
+        self
+    }
+
+    /// Sets the header `name` to `value` if no header named `name` exists.
+    pub fn set_default<N: Into<String>, V: Into<String>>(mut self, name: N, value: V) -> Self {
+        self.default.insert(name.into(), value.into());
+        self
+    }
+
+    /// Removes all headers named `name`.
+    pub fn remove<N: Into<String>>(mut self, name: N) -> Self {
+        self.remove.push(name.into());
+        self
+    }
+
+    fn is_empty(&self) -> bool {
+        self.set.is_empty() && self.default.is_empty() && self.remove.is_empty()
+    }
+
+    /// Returns a description of the first invalid header name or value.
+    fn validate(&self) -> Result<(), String> {
+        let is_token = |s: &str| !s.is_empty() && s.bytes().all(|b| {
+            b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b)
+        });
+
+        let is_value = |s: &str| s.bytes().all(|b| b == b'\t' || !b.is_ascii_control());
+
+        let mut names = self.set.keys().chain(self.default.keys()).chain(&self.remove);
+        if let Some(name) = names.find(|name| !is_token(name.as_str())) {
+            return Err(format!("invalid header name {:?}", name));
+        }
+
+        let mut values = self.set.values().chain(self.default.values());
+        if let Some(value) = values.find(|value| !is_value(value.as_str())) {
+            return Err(format!("invalid header value {:?}", value));
+        }
+
+        Ok(())
+    }
+}
+
+impl HeaderRule {
+    fn root() -> String {
+        "/".into()
+    }
+
+    /// Returns a rule with no header changes for requests under `path`.
+    pub fn new<P: Into<String>>(path: P) -> Self {
+        HeaderRule {
+            path: path.into(),
+            request: HeaderActions::default(),
+            response: HeaderActions::default(),
+        }
+    }
+
+    /// Sets the changes applied to matching requests.
+    pub fn request(mut self, actions: HeaderActions) -> Self {
+        self.request = actions;
+        self
+    }
+
+    /// Sets the changes applied to responses to matching requests.
+    pub fn response(mut self, actions: HeaderActions) -> Self {
+        self.response = actions;
+        self
+    }
+
+    /// Whether this rule applies to a request for `path`.
+    fn matches(&self, path: &str) -> bool {
+        let prefix = self.path.trim_end_matches('/');
+        path.strip_prefix(prefix).map_or(false, |rest| rest.is_empty() || rest.starts_with('/'))
+    }
+}
+
+impl HeaderRules {
+    /// Returns a `HeaderRules` fairing with no programmatic rules. Rules from
+    /// the `headers` configuration parameter are applied nonetheless.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::shield::HeaderRules;
+    ///
+    /// let rules = HeaderRules::new();
+    /// ```
+    pub fn new() -> Self {
+        HeaderRules::default()
+    }
+
+    /// Adds `rule`, applied after all previously added rules.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::shield::{HeaderRules, HeaderRule, HeaderActions};
+    ///
+    /// let rules = HeaderRules::new()
+    ///     .rule(HeaderRule::new("/").response(HeaderActions::new().remove("Server")))
+    ///     .rule(HeaderRule::new("/app")
+    ///         .response(HeaderActions::new().set("X-Frame-Options", "DENY")));
+    /// ```
+    pub fn rule(mut self, rule: HeaderRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    fn matching<'a>(&'a self, path: &'a str) -> impl Iterator<Item = &'a HeaderRule> + 'a {
+        let configured = self.configured.try_get().map(|v| v.as_slice()).unwrap_or_default();
+        self.rules.iter().chain(configured).filter(move |rule| rule.matches(path))
+    }
+}
+
+#[crate::async_trait]
+impl Fairing for HeaderRules {
+    fn info(&self) -> Info {
+        Info {
+            name: "Header Rules",
+            kind: Kind::Ignite | Kind::Request | Kind::Response | Kind::Singleton,
+        }
+    }
+
+    async fn on_ignite(&self, rocket: Rocket<Build>) -> fairing::Result {
+        let configured = match rocket.figment().contains("headers") {
+            true => match rocket.figment().extract_inner::<Vec<HeaderRule>>("headers") {
+                Ok(rules) => rules,
+                Err(e) => {
+                    e.trace_error();
+                    return Err(rocket);
+                }
+            },
+            false => vec![],
+        };
+
+        for rule in self.rules.iter().chain(&configured) {
+            if let Err(e) = rule.request.validate().and_then(|_| rule.response.validate()) {
+                error!(name: "headers", path = %rule.path, "header rule is invalid: {e}");
+                return Err(rocket);
+            }
+        }
+
+        self.configured.set(configured);
+        Ok(rocket)
+    }
+
+    async fn on_request(&self, req: &mut Request<'_>, _: &mut Data<'_>) {
+        let path = req.uri().path().as_str().to_owned();
+        for actions in self.matching(&path).map(|r| &r.request).filter(|a| !a.is_empty()) {
+            actions.remove.iter().for_each(|name| req.remove_header(name));
+            for (name, value) in &actions.set {
+                req.replace_header(Header::new(name.clone(), value.clone()));
+            }
+
+            for (name, value) in &actions.default {
+                if !req.headers().contains(name.as_str()) {
+                    req.add_header(Header::new(name.clone(), value.clone()));
+                }
+            }
+        }
+    }
+
+    async fn on_response<'r>(&self, req: &'r Request<'_>, res: &mut Response<'r>) {
+        let path = req.uri().path().as_str();
+        for actions in self.matching(path).map(|r| &r.response).filter(|a| !a.is_empty()) {
+            actions.remove.iter().for_each(|name| res.remove_header(name));
+            for (name, value) in &actions.set {
+                res.set_header(Header::new(name.clone(), value.clone()));
+            }
+
+            for (name, value) in &actions.default {
+                if !res.headers().contains(name.as_str()) {
+                    res.set_header(Header::new(name.clone(), value.clone()));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,177 @@
+use std::fmt::{self, Write};
+
+use serde::{Deserialize, Serialize};
+use time::{OffsetDateTime, format_description::well_known::Rfc3339};
+
+use crate::{Request, Data, Route};
+use crate::http::{Method, ContentType};
+use crate::route::{Handler, Outcome};
+
+/// A [`security.txt`] file: how to report security vulnerabilities.
+///
+/// `SecurityTxt` is a handler that serves a `security.txt` file as described
+/// in [RFC 9116]. It can be configured via the `shield.security_txt`
+/// configuration parameter, in which case [`Shield`] mounts it at
+/// `/.well-known/security.txt`, or mounted programmatically:
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::shield::SecurityTxt;
+///
+/// #[launch]
+/// fn rocket() -> _ {
+///     let security_txt = SecurityTxt::new("mailto:security@rocket.rs", "2030-01-01T00:00:00Z")
+///         .preferred_language("en");
+///
+///     rocket::build().mount("/.well-known", security_txt)
+/// }
+/// ```
+///
+/// In `Rocket.toml`:
+///
+/// ```toml
+/// [default.shield.security_txt]
+/// contact = ["mailto:security@rocket.rs", "https://rocket.rs/security"]
+/// expires = "2030-01-01T00:00:00Z"
+/// preferred_languages = ["en", "fr"]
+/// ```
+///
+/// The `contact` and `expires` fields are required. `expires` must be an RFC
+/// 3339 date-time. If either is invalid, ignition fails. An expired file is
+/// served nonetheless, but a warning is logged at ignition.
+///
+/// [`security.txt`]: https://securitytxt.org/
+/// [RFC 9116]: https://www.rfc-editor.org/rfc/rfc9116
+/// [`Shield`]: crate::shield::Shield
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SecurityTxt {
+    /// URIs to contact about vulnerabilities: `Contact`.
+    pub contact: Vec<String>,
+    /// The RFC 3339 date-time after which the file is stale: `Expires`.
+    pub expires: String,
+    /// URIs of keys to encrypt reports with: `Encryption`.
+    #[serde(default)]
+    pub encryption: Vec<String>,
+    /// URIs of pages acknowledging reporters: `Acknowledgments`.
+    #[serde(default)]
+    pub acknowledgments: Vec<String>,
+    /// Languages reports may be written in: `Preferred-Languages`.
+    #[serde(default)]
+    pub preferred_languages: Vec<String>,
+    /// URIs the file is canonically served at: `Canonical`.
+    #[serde(default)]
+    pub canonical: Vec<String>,
+    /// URIs of the vulnerability disclosure policy: `Policy`.
+    #[serde(default)]
+    pub policy: Vec<String>,
+    /// URIs of security-related job listings: `Hiring`.
+    #[serde(default)]
+    pub hiring: Vec<String>,
+}
+
+impl SecurityTxt {
+    /// Returns a `SecurityTxt` with one `contact` URI that expires at the RFC
+    /// 3339 date-time `expires`.
+    pub fn new<C: Into<String>, E: Into<String>>(contact: C, expires: E) -> Self {
+        SecurityTxt {
+            contact: vec![contact.into()],
+            expires: expires.into(),
+            encryption: vec![],
+            acknowledgments: vec![],
+            preferred_languages: vec![],
+            canonical: vec![],
+            policy: vec![],
+            hiring: vec![],
+        }
+    }
+
+    /// Adds a `contact` URI.
+    pub fn contact<C: Into<String>>(mut self, contact: C) -> Self {
+        self.contact.push(contact.into());
+        self
+    }
+
+    /// Adds a preferred language for reports.
+    pub fn preferred_language<L: Into<String>>(mut self, language: L) -> Self {
+        self.preferred_languages.push(language.into());
+        self
+    }
+
+    /// Adds the URI of a vulnerability disclosure policy.
+    pub fn policy<P: Into<String>>(mut self, policy: P) -> Self {
+        self.policy.push(policy.into());
+        self
+    }
+
+    /// Returns the parsed `expires` date-time or a description of why the file
+    /// is invalid.
+    pub(crate) fn validate(&self) -> Result<OffsetDateTime, String> {
+        if self.contact.is_empty() {
+            return Err("at least one `contact` is required".into());
+        }
+
+        let mut values = self.fields()
+            .flat_map(|(_, values)| values)
+            .chain(&self.preferred_languages)
+            .chain([&self.expires]);
+
+        if let Some(value) = values.find(|v| v.chars().any(|c| c.is_control())) {
+            return Err(format!("invalid value {:?}", value));
+        }
+
+        OffsetDateTime::parse(&self.expires, &Rfc3339)
+            .map_err(|e| format!("invalid `expires` {:?}: {}", self.expires, e))
+    }
+
+    fn fields(&self) -> impl Iterator<Item = (&'static str, &Vec<String>)> {
+        [
+            ("Contact", &self.contact),
+            ("Encryption", &self.encryption),
+            ("Acknowledgments", &self.acknowledgments),
+            ("Canonical", &self.canonical),
+            ("Policy", &self.policy),
+            ("Hiring", &self.hiring),
+        ].into_iter()
+    }
+}
+
+impl fmt::Display for SecurityTxt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (name, values) in self.fields() {
+            for value in values {
+                writeln!(f, "{}: {}", name, value)?;
+            }
+        }
+
+        writeln!(f, "Expires: {}", self.expires)?;
+        if !self.preferred_languages.is_empty() {
+            f.write_str("Preferred-Languages: ")?;
+            for (i, language) in self.preferred_languages.iter().enumerate() {
+                if i != 0 {
+                    f.write_str(", ")?;
+                }
+
+                f.write_str(language)?;
+            }
+
+            f.write_char('\n')?;
+        }
+
+        Ok(())
+    }
+}
+
+impl From<SecurityTxt> for Vec<Route> {
+    fn from(security_txt: SecurityTxt) -> Self {
+        let mut route = Route::new(Method::Get, "/security.txt", security_txt);
+        route.name = Some("SecurityTxt".into());
+        vec![route]
+    }
+}
+
+#[crate::async_trait]
+impl Handler for SecurityTxt {
+    async fn handle<'r>(&self, req: &'r Request<'_>, _: Data<'r>) -> Outcome<'r> {
+        Outcome::from(req, (ContentType::Plain, self.to_string()))
+    }
+}
@@ -1,10 +1,14 @@
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicBool, Ordering};
 
-use crate::{Rocket, Request, Response, Orbit, Config};
-use crate::fairing::{Fairing, Info, Kind};
+use state::InitCell;
+use time::OffsetDateTime;
+
+use crate::{Rocket, Request, Response, Build, Orbit, Config};
+use crate::fairing::{self, Fairing, Info, Kind};
 use crate::http::{Header, uncased::UncasedStr};
-use crate::shield::{Frame, Hsts, NoSniff, Permission, Policy};
+use crate::shield::{Frame, Hsts, NoSniff, Permission, Policy, ShieldConfig};
+use crate::shield::{Coop, Coep, Corp};
 use crate::trace::{Trace, TraceAll};
 
 /// A [`Fairing`] that injects browser security and privacy headers into all
@@ -55,23 +59,44 @@ use crate::trace::{Trace, TraceAll};
 /// does contain the header, a warning is emitted, and the header is not
 /// overwritten.
 ///
+/// Headers declared on a route via [`#[headers]`](macro@crate::headers) are
+/// also set on responses to requests handled by that route, again unless the
+/// response already contains them. They replace any enabled policy for the
+/// same header on those responses, so a route can, for instance, relax the
+/// `X-Frame-Options` policy or add `X-Robots-Tag: noindex`.
+///
 /// # TLS and HSTS
 ///
 /// If TLS is configured and enabled when the application is launched in a
 /// non-debug profile, HSTS is automatically enabled with its default policy and
 /// a warning is logged. To get rid of this warning, explicitly
 /// [`Shield::enable()`] an [`Hsts`] policy.
+///
+/// # Configuration
+///
+/// At ignition, policies are amended with those in the `shield` configuration
+/// parameter, if it is set, which is deserialized as a [`ShieldConfig`].
+/// Configured policies take precedence over those enabled in code. If the
+/// parameter is invalid, ignition fails.
 pub struct Shield {
     /// Enabled policies where the key is the header name.
     policies: HashMap<&'static UncasedStr, Header<'static>>,
+    /// `policies` amended by configuration, set at ignition.
+    configured: InitCell<HashMap<&'static UncasedStr, Header<'static>>>,
     /// Whether to enforce HSTS even though the user didn't enable it.
     force_hsts: AtomicBool,
 }
 
 impl Clone for Shield {
     fn clone(&self) -> Self {
+        let configured = InitCell::new();
+        if let Some(policies) = self.configured.try_get() {
+            configured.set(policies.clone());
+        }
+
         Self {
             policies: self.policies.clone(),
+            configured,
             force_hsts: AtomicBool::from(self.force_hsts.load(Ordering::Acquire)),
         }
     }
@@ -112,10 +137,43 @@ impl Shield {
     pub fn new() -> Self {
         Shield {
             policies: HashMap::new(),
+            configured: InitCell::new(),
             force_hsts: AtomicBool::new(false),
         }
     }
 
+    /// Returns the default `Shield` with the policies needed to make documents
+    /// [cross-origin isolated] enabled, as required by frontends that use
+    /// `SharedArrayBuffer` or high-resolution timers: [`Coop::SameOrigin`],
+    /// [`Coep::RequireCorp`], and [`Corp::SameOrigin`].
+    ///
+    /// Cross-origin resources embedded by isolated documents, such as images
+    /// from a CDN, must then be served with CORS or a `cross-origin` [`Corp`]
+    /// header. Alternatively, enable [`Coep::Credentialless`] to load them
+    /// without credentials instead.
+    ///
+    /// [cross-origin isolated]: https://developer.mozilla.org/en-US/docs/Web/API/crossOriginIsolated
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::shield::{Shield, Coop, Coep, Corp, Frame};
+    ///
+    /// let shield = Shield::cross_origin_isolated();
+    /// assert!(shield.is_enabled::<Coop>());
+    /// assert!(shield.is_enabled::<Coep>());
+    /// assert!(shield.is_enabled::<Corp>());
+    /// assert!(shield.is_enabled::<Frame>());
+    ///
+    /// let shield = Shield::cross_origin_isolated().enable(Coep::Credentialless);
+    /// ```
+    pub fn cross_origin_isolated() -> Self {
+        Shield::default()
+            .enable(Coop::SameOrigin)
+            .enable(Coep::RequireCorp)
+            .enable(Corp::SameOrigin)
+    }
+
     /// Enables the policy header `policy`.
     ///
     /// If the policy was previously enabled, the configuration is replaced
@@ -149,7 +207,8 @@ impl Shield {
         self
     }
 
-    /// Returns `true` if the policy `P` is enabled.
+    /// Returns `true` if the policy `P` is enabled, taking configuration into
+    /// account once `self` has been ignited.
     ///
     /// # Example
     ///
@@ -169,7 +228,17 @@ impl Shield {
     /// assert!(!shield.is_enabled::<Referrer>());
     /// ```
     pub fn is_enabled<P: Policy>(&self) -> bool {
-        self.policies.contains_key(UncasedStr::new(P::NAME))
+        self.active().contains_key(UncasedStr::new(P::NAME))
+    }
+
+    /// Returns `true` if no policy is enabled.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.active().is_empty()
+    }
+
+    /// The enabled policies, as amended by configuration if ignited.
+    fn active(&self) -> &HashMap<&'static UncasedStr, Header<'static>> {
+        self.configured.try_get().unwrap_or(&self.policies)
     }
 }
 
@@ -178,12 +247,53 @@ impl Fairing for Shield {
     fn info(&self) -> Info {
         Info {
             name: "Shield",
-            kind: Kind::Liftoff | Kind::Response | Kind::Singleton,
+            kind: Kind::Ignite | Kind::Liftoff | Kind::Response | Kind::Singleton,
+        }
+    }
+
+    async fn on_ignite(&self, rocket: Rocket<Build>) -> fairing::Result {
+        if !rocket.figment().contains("shield") {
+            return Ok(rocket);
+        }
+
+        let config = match rocket.figment().extract_inner::<ShieldConfig>("shield") {
+            Ok(config) => config,
+            Err(e) => {
+                e.trace_error();
+                return Err(rocket);
+            }
+        };
+
+        let mut policies = self.policies.clone();
+        if let Err(e) = config.apply(&mut policies) {
+            error!(name: "shield", "shield configuration is invalid: {e}");
+            return Err(rocket);
+        }
+
+        self.configured.set(policies);
+        let Some(security_txt) = config.security_txt else {
+            return Ok(rocket);
+        };
+
+        match security_txt.validate() {
+            Ok(expires) => {
+                if expires <= OffsetDateTime::now_utc() {
+                    let expires = &security_txt.expires;
+                    warn!(name: "shield", expires, "security.txt has expired");
+                }
+
+                Ok(rocket.mount("/.well-known", security_txt))
+            }
+            Err(e) => {
+                error!(name: "shield", "security.txt configuration is invalid: {e}");
+                Err(rocket)
+            }
         }
     }
 
     async fn on_liftoff(&self, rocket: &Rocket<Orbit>) {
-        if self.policies.is_empty() {
+        let policies = self.active();
+        if policies.is_empty() {
             return;
         }
 
@@ -195,8 +305,8 @@ impl Fairing for Shield {
             self.force_hsts.store(true, Ordering::Release);
         }
 
-        span_info!("shield", policies = self.policies.len() => {
-            self.policies.values().trace_all_info();
+        span_info!("shield", policies = policies.len() => {
+            policies.values().trace_all_info();
 
             if force_hsts {
                 warn!("Detected TLS-enabled liftoff without enabling HSTS.\n\
@@ -206,10 +316,23 @@ impl Fairing for Shield {
         })
     }
 
-    async fn on_response<'r>(&self, _: &'r Request<'_>, response: &mut Response<'r>) {
+    async fn on_response<'r>(&self, req: &'r Request<'_>, response: &mut Response<'r>) {
+        // Set the headers declared by the route via `#[headers]` first: they
+        // take precedence over policies but not over the handler's response.
+        let route_headers = req.route().map_or(&[][..], |route| route.headers.as_slice());
+        for header in route_headers {
+            if !response.headers().contains(header.name()) {
+                response.set_header(header.clone());
+            }
+        }
+
         // Set all of the headers in `self.policies` in `response` as long as
         // the header is not already in the response.
-        for header in self.policies.values() {
+        for header in self.active().values() {
+            if route_headers.iter().any(|h| h.name() == header.name()) {
+                continue
+            }
+
             if response.headers().contains(header.name()) {
                 span_warn!("shield", "shield refusing to overwrite existing response header" => {
                     header.trace_warn();
@@ -189,6 +189,16 @@ pub struct ShutdownConfig {
     /// **default: `true`**
     #[serde(deserialize_with = "figment::util::bool_from_str_or_int")]
     pub force: bool,
+    /// Whether to record the method, URI, route, and principal of every
+    /// request in flight so that requests force-terminated at shutdown can be
+    /// described individually in the [`DrainReport`]. Otherwise, only their
+    /// number is reported.
+    ///
+    /// [`DrainReport`]: crate::shutdown::DrainReport
+    ///
+    /// **default: `false`**
+    #[serde(deserialize_with = "figment::util::bool_from_str_or_int")]
+    pub drain_report: bool,
     /// PRIVATE: This structure may grow (but never change otherwise) in a
     /// non-breaking release. As such, constructing this structure should
     /// _always_ be done using a public constructor or update syntax:
@@ -216,6 +226,7 @@ impl Default for ShutdownConfig {
             grace: 2,
             mercy: 3,
             force: true,
+            drain_report: false,
             __non_exhaustive: (),
         }
     }
@@ -0,0 +1,227 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+
+use crate::{Request, Route, Shutdown};
+use crate::http::Method;
+
+/// A report of how shutdown drained in-flight requests.
+///
+/// When graceful shutdown ends, the requests that were still being handled or
+/// responded to at the end of the [grace period](crate::config::ShutdownConfig),
+/// when Rocket cancels application I/O, and were thus force-terminated, are
+/// reported. The report is logged as a warning and emitted as
+/// [`Event::Terminated`] when any request was terminated. It's available via
+/// [`Handle::drain_report()`] regardless.
+///
+/// A request is in flight from the moment it's received until its response,
+/// including its body, has been written. Requests that repeatedly appear in
+/// reports, and the time they had been running, indicate how the grace and
+/// mercy periods should be tuned.
+///
+/// By default, only the [`count`](DrainReport::count) of terminated requests
+/// is reported. Each request is described in
+/// [`terminated`](DrainReport::terminated) only if
+/// [`shutdown.drain_report`](crate::config::ShutdownConfig::drain_report) is
+/// enabled, as doing so has a cost on every request.
+///
+/// [`Event::Terminated`]: crate::events::Event::Terminated
+/// [`Handle::drain_report()`]: crate::supervisor::Handle::drain_report()
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct DrainReport {
+    /// Whether all pending I/O completed before the end of the mercy period.
+    pub graceful: bool,
+    /// How long shutdown waited for pending I/O to complete.
+    pub waited: Duration,
+    /// The number of requests force-terminated at shutdown.
+    pub count: usize,
+    /// The requests force-terminated at shutdown, longest-running first. Empty
+    /// unless `shutdown.drain_report` is enabled.
+    pub terminated: Vec<Terminated>,
+}
+
+/// A request force-terminated at shutdown. See [`DrainReport`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Terminated {
+    /// The method of the request.
+    pub method: Method,
+    /// The URI of the request, as received.
+    pub uri: String,
+    /// The name of the route handling the request or, if it's unnamed, its
+    /// URI template. `None` if the request hadn't been routed.
+    pub route: Option<String>,
+    /// How long the request had been in flight when it was terminated.
+    pub elapsed: Duration,
+    /// The principal the request was made on behalf of, if it's known. See
+    /// [`Request::set_principal()`].
+    pub principal: Option<String>,
+}
+
+/// The number of shards requests are spread over when they're recorded, so
+/// that concurrent requests rarely contend for the same lock.
+const SHARDS: usize = 32;
+
+/// The requests in flight in an instance of Rocket.
+///
+/// Requests are always counted. They're only recorded, in one of `SHARDS`
+/// maps keyed by ID, if `record` is set.
+#[derive(Debug)]
+pub(crate) struct InFlight {
+    next: AtomicU64,
+    grace: Shutdown,
+    record: bool,
+    active: AtomicUsize,
+    late: AtomicUsize,
+    shards: Box<[Mutex<HashMap<u64, Entry>>]>,
+    terminated: Mutex<Vec<Terminated>>,
+    report: Mutex<Option<DrainReport>>,
+}
+
+#[derive(Debug)]
+struct Entry {
+    method: Method,
+    uri: String,
+    route: Option<String>,
+    start: Instant,
+    principal: Option<String>,
+}
+
+/// A request in flight until the ticket, cached in the request, is dropped.
+/// A request dropped after the grace period was cut short by shutdown.
+struct Ticket {
+    id: u64,
+    in_flight: Arc<InFlight>,
+}
+
+impl InFlight {
+    /// Tracks requests as force-terminated once `grace` is notified. Requests
+    /// are described individually in the report only if `record` is `true`.
+    pub fn new(grace: Shutdown, record: bool) -> Self {
+        InFlight {
+            next: AtomicU64::new(0),
+            grace,
+            record,
+            active: AtomicUsize::new(0),
+            late: AtomicUsize::new(0),
+            shards: (0..SHARDS).map(|_| Mutex::default()).collect(),
+            terminated: Mutex::new(vec![]),
+            report: Mutex::new(None),
+        }
+    }
+
+    fn shard(&self, id: u64) -> &Mutex<HashMap<u64, Entry>> {
+        &self.shards[id as usize % SHARDS]
+    }
+
+    /// Tracks `req` as in flight until it's dropped.
+    pub fn track(self: &Arc<Self>, req: &Request<'_>) {
+        let id = self.next.fetch_add(1, Ordering::Relaxed);
+        self.active.fetch_add(1, Ordering::AcqRel);
+        if self.record {
+            let entry = Entry {
+                method: req.method(),
+                uri: req.uri().to_string(),
+                route: None,
+                start: Instant::now(),
+                principal: None,
+            };
+
+            self.shard(id).lock().insert(id, entry);
+        }
+
+        let ticket = Ticket { id, in_flight: self.clone() };
+        req.local_cache(move || Some(ticket));
+    }
+
+    /// Records that `req` is being handled by `route`.
+    pub fn routed(req: &Request<'_>, route: &Route) {
+        Self::update(req, |entry| {
+            let route = route.name.as_deref().unwrap_or(route.uri.template());
+            entry.route = Some(route.to_string());
+        });
+    }
+
+    /// Records that `req` is made on behalf of `principal`.
+    pub fn identify(req: &Request<'_>, principal: String) {
+        Self::update(req, |entry| entry.principal = Some(principal));
+    }
+
+    fn update<F: FnOnce(&mut Entry)>(req: &Request<'_>, f: F) {
+        let Some(ticket) = req.local_cache(|| None::<Ticket>) else { return };
+        if ticket.in_flight.record {
+            if let Some(entry) = ticket.in_flight.shard(ticket.id).lock().get_mut(&ticket.id) {
+                f(entry);
+            }
+        }
+    }
+
+    /// Reports the requests terminated after the grace period and those still
+    /// in flight, logging and recording the report.
+    pub fn report(&self, graceful: bool, waited: Duration) -> DrainReport {
+        let count = self.late.load(Ordering::Acquire) + self.active.load(Ordering::Acquire);
+        let mut terminated = std::mem::take(&mut *self.terminated.lock());
+        for shard in self.shards.iter() {
+            terminated.extend(shard.lock().values().map(Entry::terminated));
+        }
+
+        terminated.sort_by(|a, b| b.elapsed.cmp(&a.elapsed));
+        if count > 0 {
+            span_warn!("drain", terminated = count, waited = ?waited => {
+                for request in &terminated {
+                    warn!(
+                        method = %request.method,
+                        uri = %request.uri,
+                        route = request.route.as_deref(),
+                        elapsed = ?request.elapsed,
+                        principal = request.principal.as_deref(),
+                        "request force-terminated"
+                    );
+                }
+            });
+        }
+
+        let report = DrainReport { graceful, waited, count, terminated };
+        *self.report.lock() = Some(report.clone());
+        report
+    }
+
+    /// The report recorded at shutdown, if shutdown has completed.
+    pub fn drain_report(&self) -> Option<DrainReport> {
+        self.report.lock().clone()
+    }
+}
+
+impl Entry {
+    fn terminated(&self) -> Terminated {
+        Terminated {
+            method: self.method,
+            uri: self.uri.clone(),
+            route: self.route.clone(),
+            elapsed: self.start.elapsed(),
+            principal: self.principal.clone(),
+        }
+    }
+}
+
+impl Drop for Ticket {
+    fn drop(&mut self) {
+        let in_flight = &self.in_flight;
+        let entry = match in_flight.record {
+            true => in_flight.shard(self.id).lock().remove(&self.id),
+            false => None,
+        };
+
+        in_flight.active.fetch_sub(1, Ordering::AcqRel);
+        if in_flight.grace.notified() {
+            in_flight.late.fetch_add(1, Ordering::AcqRel);
+            if let Some(entry) = entry {
+                in_flight.terminated.lock().push(entry.terminated());
+            }
+        }
+    }
+}
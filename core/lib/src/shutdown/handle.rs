@@ -5,6 +5,7 @@ use std::pin::Pin;
 use futures::{FutureExt, StreamExt};
 
 use crate::shutdown::{ShutdownConfig, TripWire};
+use crate::events::{Event, EventBus};
 use crate::request::{FromRequest, Outcome, Request};
 
 /// A request guard and future for graceful shutdown.
@@ -77,7 +78,7 @@ pub struct Stages {
 }
 
 impl Shutdown {
-    fn new() -> Self {
+    pub(crate) fn new() -> Self {
         Shutdown {
             wire: TripWire::new(),
         }
@@ -153,7 +154,7 @@ impl Stages {
         }
     }
 
-    pub(crate) fn spawn_listener(&self, config: &ShutdownConfig) {
+    pub(crate) fn spawn_listener(&self, config: &ShutdownConfig, events: &EventBus) {
         use futures::stream;
         use futures::future::{select, Either};
 
@@ -163,13 +164,20 @@ impl Stages {
         };
 
         let start  = self.start.clone();
+        let events = events.clone();
         let (grace, grace_duration)  = (self.grace.clone(), config.grace());
         let (mercy, mercy_duration)  = (self.mercy.clone(), config.mercy());
         tokio::spawn(async move {
-            if let Either::Left((sig, start)) = select(signal.next(), start).await {
-                warn!("Received {}. Shutdown started.", sig.unwrap());
-                start.notify();
-            }
+            let signal = match select(signal.next(), start).await {
+                Either::Left((sig, start)) => {
+                    warn!("Received {}. Shutdown started.", sig.unwrap());
+                    start.notify();
+                    sig
+                }
+                Either::Right(_) => None,
+            };
+
+            events.emit(Event::ShutdownRequested { signal });
 
             tokio::time::sleep(grace_duration).await;
             warn!("Shutdown grace period elapsed. Shutting down I/O.");
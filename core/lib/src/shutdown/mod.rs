@@ -4,10 +4,13 @@ mod tripwire;
 mod handle;
 mod sig;
 mod config;
+mod drain;
 
 pub(crate) use tripwire::TripWire;
 pub(crate) use handle::Stages;
+pub(crate) use drain::InFlight;
 
 pub use config::ShutdownConfig;
 pub use handle::Shutdown;
 pub use sig::Sig;
+pub use drain::{DrainReport, Terminated};
@@ -0,0 +1,114 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::data::{ByteUnit, ToByteUnit};
+
+/// The signing configuration: the `signing` configuration parameter.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::signing::{SigningConfig, Algorithm};
+/// use rocket::figment::{Figment, providers::{Format, Toml}};
+///
+/// let figment = Figment::from(Toml::string(r#"
+///     [signing]
+///     algorithm = "ed25519"
+///     key = "/xcZs0sFpBFCwyYqq0XqBvd7T8T+o6Iwy1bLO9oo3Fs="
+///     key_id = "2024-01"
+/// "#));
+///
+/// let config: SigningConfig = figment.extract_inner("signing").unwrap();
+/// assert_eq!(config.algorithm, Algorithm::Ed25519);
+/// assert_eq!(config.key_id.as_deref(), Some("2024-01"));
+/// assert_eq!(config.header, "X-Signature");
+/// ```
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SigningConfig {
+    /// The signature algorithm.
+    ///
+    /// **default: [`Algorithm::HmacSha256`]**
+    pub algorithm: Algorithm,
+    /// The base64-encoded key: a secret of at least 32 bytes for
+    /// [`Algorithm::HmacSha256`] or a 32-byte seed for [`Algorithm::Ed25519`].
+    ///
+    /// A key can be generated with `openssl rand -base64 32`.
+    ///
+    /// **default: none; a key must be configured**
+    pub key: String,
+    /// An identifier for the key, sent along with signatures to aid rotation.
+    ///
+    /// **default: `None`**
+    pub key_id: Option<String>,
+    /// The name of the header that carries signatures.
+    ///
+    /// **default: `X-Signature`**
+    pub header: String,
+    /// The size of the largest body that is signed.
+    ///
+    /// **default: `1 MiB`**
+    pub limit: ByteUnit,
+}
+
+/// A signature algorithm.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Algorithm {
+    /// HMAC with SHA-256: `hmac-sha256`.
+    #[serde(rename = "hmac-sha256")]
+    HmacSha256,
+    /// Ed25519: `ed25519`.
+    #[serde(rename = "ed25519")]
+    Ed25519,
+}
+
+impl Algorithm {
+    /// Returns the name of the algorithm as it appears in configuration and in
+    /// signatures.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::signing::Algorithm;
+    ///
+    /// assert_eq!(Algorithm::HmacSha256.as_str(), "hmac-sha256");
+    /// assert_eq!(Algorithm::Ed25519.as_str(), "ed25519");
+    /// ```
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Algorithm::HmacSha256 => "hmac-sha256",
+            Algorithm::Ed25519 => "ed25519",
+        }
+    }
+}
+
+impl Default for SigningConfig {
+    fn default() -> Self {
+        SigningConfig {
+            algorithm: Algorithm::HmacSha256,
+            key: String::new(),
+            key_id: None,
+            header: "X-Signature".into(),
+            limit: 1.mebibytes(),
+        }
+    }
+}
+
+impl fmt::Debug for SigningConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SigningConfig")
+            .field("algorithm", &self.algorithm)
+            .field("key", &"[redacted]")
+            .field("key_id", &self.key_id)
+            .field("header", &self.header)
+            .field("limit", &self.limit)
+            .finish()
+    }
+}
+
+impl fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
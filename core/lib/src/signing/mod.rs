@@ -0,0 +1,75 @@
+//! Detached signatures of response bodies.
+//!
+//! The [`Signer`] fairing signs the body of every response with a key read
+//! from configuration and attaches the signature in a header so that CDNs,
+//! object stores, and clients downstream can verify that a body is the one the
+//! application produced. Two algorithms are supported:
+//!
+//!   * [`Algorithm::HmacSha256`]: HMAC-SHA256 with a shared secret. Anyone who
+//!     can verify a signature can also produce one.
+//!   * [`Algorithm::Ed25519`]: Ed25519 signatures. Verification requires only
+//!     the [public key](Signer::public_key()), which can be published.
+//!
+//! # Configuration
+//!
+//! The signing key is configured via the `signing` configuration parameter,
+//! which is deserialized as a [`SigningConfig`]. Attaching a `Signer` without
+//! a valid configuration fails ignition.
+//!
+//! ```toml
+//! [default.signing]
+//! algorithm = "ed25519"
+//! key = "/xcZs0sFpBFCwyYqq0XqBvd7T8T+o6Iwy1bLO9oo3Fs="
+//! key_id = "2024-01"
+//! header = "X-Signature"
+//! limit = "1 MiB"
+//! ```
+//!
+//! # Signatures
+//!
+//! Signatures cover the exact bytes of the response body and nothing else. The
+//! signature header's value is a list of parameters:
+//!
+//! ```text
+//! X-Signature: alg="ed25519", keyid="2024-01", sig="<base64 signature>"
+//! ```
+//!
+//! where `keyid` is present only if a `key_id` is configured. Bodies that are
+//! streamed, and thus have no known size, or that are larger than the
+//! configured `limit` are not signed. Neither are responses without a body.
+//!
+//! Response fairings run in the order they are attached. Attach `Signer` after
+//! any fairing that modifies response bodies so that the final body is signed.
+//!
+//! # Verification
+//!
+//! Signatures can be verified with [`Signer::verify()`] or, without a
+//! `Signer`, with [`verify()`]. With a local client, a response's body can be
+//! read and verified at once via `LocalResponse::into_verified_bytes()`:
+//!
+//! ```rust
+//! # #[macro_use] extern crate rocket;
+//! use rocket::signing::Signer;
+//! use rocket::local::blocking::Client;
+//! use rocket::figment::providers::{Format, Toml};
+//!
+//! #[get("/")]
+//! fn index() -> &'static str {
+//!     "Hello, world!"
+//! }
+//!
+//! let figment = rocket::Config::figment().merge(Toml::string(r#"
+//!     signing.key = "/xcZs0sFpBFCwyYqq0XqBvd7T8T+o6Iwy1bLO9oo3Fs="
+//! "#));
+//!
+//! let rocket = rocket::custom(figment).mount("/", routes![index]).attach(Signer::new());
+//! let client = Client::debug(rocket).unwrap();
+//! let body = client.get("/").dispatch().into_verified_bytes();
+//! assert_eq!(body.unwrap(), b"Hello, world!");
+//! ```
+
+mod config;
+mod signer;
+
+pub use config::{SigningConfig, Algorithm};
+pub use signer::{Signer, verify};
@@ -0,0 +1,275 @@
+use std::io::Cursor;
+
+use ring::hmac;
+use ring::signature::{self, Ed25519KeyPair, KeyPair, UnparsedPublicKey};
+use state::InitCell;
+
+use crate::{Rocket, Request, Response, Build};
+use crate::data::ByteUnit;
+use crate::fairing::{self, Fairing, Info, Kind};
+use crate::http::Header;
+use crate::trace::Trace;
+use crate::signing::{SigningConfig, Algorithm};
+
+/// A [`Fairing`] that [signs](crate::signing) response bodies.
+///
+/// The key is read from the `signing` configuration parameter at ignition. If
+/// the parameter is missing or invalid, ignition fails. See the [module
+/// documentation](crate::signing) for details on what is signed and how.
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::signing::Signer;
+///
+/// #[launch]
+/// fn rocket() -> _ {
+///     rocket::build().attach(Signer::new())
+/// }
+/// ```
+pub struct Signer {
+    state: InitCell<State>,
+}
+
+/// The key and parameters read from a [`SigningConfig`].
+struct State {
+    key: Key,
+    key_id: Option<String>,
+    header: String,
+    limit: ByteUnit,
+}
+
+enum Key {
+    Hmac(hmac::Key),
+    Ed25519(Ed25519KeyPair),
+}
+
+impl Signer {
+    /// Returns a `Signer` fairing.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::signing::Signer;
+    ///
+    /// let signer = Signer::new();
+    /// ```
+    pub fn new() -> Self {
+        Signer { state: InitCell::new() }
+    }
+
+    /// Returns the name of the header that carries signatures or `None` if
+    /// `self` has not been ignited.
+    pub fn header(&self) -> Option<&str> {
+        self.state.try_get().map(|state| state.header.as_str())
+    }
+
+    /// Returns the base64-encoded Ed25519 public key that verifies signatures
+    /// or `None` if `self` has not been ignited or doesn't sign with
+    /// [`Algorithm::Ed25519`].
+    pub fn public_key(&self) -> Option<String> {
+        match &self.state.try_get()?.key {
+            Key::Ed25519(pair) => Some(encode(pair.public_key().as_ref())),
+            Key::Hmac(_) => None,
+        }
+    }
+
+    /// Returns the signature header value for `body` or `None` if `self` has
+    /// not been ignited.
+    pub fn sign(&self, body: &[u8]) -> Option<String> {
+        self.state.try_get().map(|state| state.sign(body))
+    }
+
+    /// Returns `true` if `value`, the value of a signature header, is a valid
+    /// signature of `body` by `self`'s key.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #[macro_use] extern crate rocket;
+    /// use rocket::signing::Signer;
+    /// use rocket::local::blocking::Client;
+    /// use rocket::figment::providers::{Format, Toml};
+    ///
+    /// #[get("/")]
+    /// fn index() -> &'static str {
+    ///     "Hello, world!"
+    /// }
+    ///
+    /// let figment = rocket::Config::figment().merge(Toml::string(r#"
+    ///     signing.key = "/xcZs0sFpBFCwyYqq0XqBvd7T8T+o6Iwy1bLO9oo3Fs="
+    /// "#));
+    ///
+    /// let rocket = rocket::custom(figment).mount("/", routes![index]).attach(Signer::new());
+    /// let client = Client::debug(rocket).unwrap();
+    /// let response = client.get("/").dispatch();
+    /// let value = response.headers().get_one("X-Signature").unwrap().to_string();
+    ///
+    /// let signer = client.rocket().fairing::<Signer>().unwrap();
+    /// assert!(signer.verify(&value, b"Hello, world!"));
+    /// assert!(!signer.verify(&value, b"Goodbye, world!"));
+    /// ```
+    pub fn verify(&self, value: &str, body: &[u8]) -> bool {
+        let Some(state) = self.state.try_get() else { return false };
+        match &state.key {
+            Key::Hmac(key) => parse(value, Algorithm::HmacSha256)
+                .map_or(false, |sig| hmac::verify(key, body, &sig).is_ok()),
+            Key::Ed25519(pair) => {
+                verify(Algorithm::Ed25519, pair.public_key().as_ref(), value, body)
+            }
+        }
+    }
+}
+
+impl Default for Signer {
+    fn default() -> Self {
+        Signer::new()
+    }
+}
+
+/// Returns `true` if `value`, the value of a signature header, is a valid
+/// `algorithm` signature of `body` by `key`: the shared secret for
+/// [`Algorithm::HmacSha256`] or the public key for [`Algorithm::Ed25519`].
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::signing::{verify, Algorithm};
+///
+/// let value = r#"alg="hmac-sha256", sig="e8vhAZ9p2bHm4ye2UrbMXBFMdjSj+JdUQ4eQjzVyA1Q=""#;
+/// assert!(!verify(Algorithm::HmacSha256, b"not the key", value, b"body"));
+/// ```
+pub fn verify(algorithm: Algorithm, key: &[u8], value: &str, body: &[u8]) -> bool {
+    let Some(sig) = parse(value, algorithm) else { return false };
+    match algorithm {
+        Algorithm::HmacSha256 => {
+            let key = hmac::Key::new(hmac::HMAC_SHA256, key);
+            hmac::verify(&key, body, &sig).is_ok()
+        }
+        Algorithm::Ed25519 => {
+            let key = UnparsedPublicKey::new(&signature::ED25519, key);
+            key.verify(body, &sig).is_ok()
+        }
+    }
+}
+
+impl State {
+    fn new(config: SigningConfig) -> Result<Self, String> {
+        if config.key.is_empty() {
+            return Err("missing key".into());
+        }
+
+        let bytes = decode(&config.key).ok_or("key is not valid base64")?;
+        let key = match config.algorithm {
+            Algorithm::HmacSha256 if bytes.len() < 32 => {
+                return Err(format!("key is {} bytes; at least 32 are required", bytes.len()));
+            }
+            Algorithm::HmacSha256 => Key::Hmac(hmac::Key::new(hmac::HMAC_SHA256, &bytes)),
+            Algorithm::Ed25519 => Ed25519KeyPair::from_seed_unchecked(&bytes)
+                .map(Key::Ed25519)
+                .map_err(|e| format!("invalid Ed25519 seed: {e}"))?,
+        };
+
+        Ok(State { key, key_id: config.key_id, header: config.header, limit: config.limit })
+    }
+
+    fn sign(&self, body: &[u8]) -> String {
+        let (algorithm, sig) = match &self.key {
+            Key::Hmac(key) => (Algorithm::HmacSha256, encode(hmac::sign(key, body).as_ref())),
+            Key::Ed25519(pair) => (Algorithm::Ed25519, encode(pair.sign(body).as_ref())),
+        };
+
+        match &self.key_id {
+            Some(id) => format!("alg=\"{algorithm}\", keyid=\"{id}\", sig=\"{sig}\""),
+            None => format!("alg=\"{algorithm}\", sig=\"{sig}\""),
+        }
+    }
+}
+
+/// Returns the decoded `sig` parameter of the signature header `value` if its
+/// `alg` parameter is `algorithm`.
+fn parse(value: &str, algorithm: Algorithm) -> Option<Vec<u8>> {
+    let (mut alg, mut sig) = (None, None);
+    for param in value.split(',') {
+        let (name, value) = param.split_once('=')?;
+        let value = value.trim().trim_matches('"');
+        match name.trim() {
+            "alg" => alg = Some(value),
+            "sig" => sig = Some(value),
+            _ => continue,
+        }
+    }
+
+    match alg? == algorithm.as_str() {
+        true => decode(sig?),
+        false => None,
+    }
+}
+
+fn encode(bytes: &[u8]) -> String {
+    let mut buf = vec![0; (bytes.len() + 2) / 3 * 4];
+    let encoded = binascii::b64encode(bytes, &mut buf).expect("sufficient buffer");
+    String::from_utf8_lossy(encoded).into_owned()
+}
+
+fn decode(string: &str) -> Option<Vec<u8>> {
+    // `binascii` requires more space than the actual output for padding.
+    let mut buf = vec![0; string.len()];
+    binascii::b64decode(string.trim().as_bytes(), &mut buf).ok().map(|bytes| bytes.to_vec())
+}
+
+#[crate::async_trait]
+impl Fairing for Signer {
+    fn info(&self) -> Info {
+        Info {
+            name: "Signer",
+            kind: Kind::Ignite | Kind::Response | Kind::Singleton,
+        }
+    }
+
+    async fn on_ignite(&self, rocket: Rocket<Build>) -> fairing::Result {
+        let config = match rocket.figment().extract_inner::<SigningConfig>("signing") {
+            Ok(config) => config,
+            Err(e) => {
+                e.trace_error();
+                return Err(rocket);
+            }
+        };
+
+        let algorithm = config.algorithm;
+        match State::new(config) {
+            Ok(state) => {
+                self.state.set(state);
+                Ok(rocket)
+            }
+            Err(e) => {
+                error!(name: "signing", %algorithm, "invalid signing configuration: {e}");
+                Err(rocket)
+            }
+        }
+    }
+
+    async fn on_response<'r>(&self, req: &'r Request<'_>, res: &mut Response<'r>) {
+        let Some(state) = self.state.try_get() else { return };
+        if res.body().is_none() {
+            return;
+        }
+
+        match res.body_mut().size().await {
+            Some(size) if size as u64 <= state.limit.as_u64() => {},
+            _ => return,
+        }
+
+        let body = match res.body_mut().to_bytes().await {
+            Ok(body) => body,
+            Err(e) => {
+                warn!(name: "signing", uri = %req.uri(), "failed to read body to sign: {e}");
+                return;
+            }
+        };
+
+        res.set_header(Header::new(state.header.clone(), state.sign(&body)));
+        res.set_sized_body(body.len(), Cursor::new(body));
+    }
+}
@@ -0,0 +1,246 @@
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+/// The SLO configuration: the `slo` configuration parameter.
+///
+/// # Example
+///
+/// ```rust
+/// use std::time::Duration;
+/// use rocket::slo::SloConfig;
+/// use rocket::figment::{Figment, providers::{Format, Toml}};
+///
+/// let figment = Figment::from(Toml::string(r#"
+///     [slo]
+///     short_window_secs = 60
+///     objectives = { "/api" = "99.9% < 300ms" }
+/// "#));
+///
+/// let config: SloConfig = figment.extract_inner("slo").unwrap();
+/// assert_eq!(config.short_window_secs, 60);
+/// assert_eq!(config.long_window_secs, 3600);
+/// assert_eq!(config.burn_rate_alert, 14.4);
+/// assert_eq!(config.path, None);
+///
+/// let objective = &config.objectives["/api"];
+/// assert_eq!(objective.target(), 99.9);
+/// assert_eq!(objective.get_latency(), Some(Duration::from_millis(300)));
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SloConfig {
+    /// The objectives by scope, a path prefix. A scope applies to a request if
+    /// its path is equal to it or, segment-wise, begins with it. When more
+    /// than one applies, the longest does.
+    ///
+    /// **default: `{}`**
+    pub objectives: IndexMap<String, Objective>,
+    /// The length, in seconds, of the short window over which the burn rate
+    /// is computed.
+    ///
+    /// **default: `300`**
+    pub short_window_secs: u64,
+    /// The length, in seconds, of the long window over which the burn rate
+    /// and the remaining budget are computed.
+    ///
+    /// **default: `3600`**
+    pub long_window_secs: u64,
+    /// The burn rate which, when exceeded over both windows, triggers an
+    /// alert.
+    ///
+    /// **default: `14.4`**
+    pub burn_rate_alert: f64,
+    /// The path at which reports are served as JSON, if any.
+    ///
+    /// **default: `None`**
+    pub path: Option<String>,
+}
+
+/// A service level objective, such as `99.9% < 300ms`.
+///
+/// An objective is the percentage of requests that must succeed: complete
+/// without a server error and, if a latency is set, in less than it. An
+/// objective is parsed from and serialized as a string of the form
+/// `<target>%` or `<target>% < <latency>`, where `latency` is a number
+/// followed by one of `us`, `ms`, or `s`.
+///
+/// # Example
+///
+/// ```rust
+/// use std::time::Duration;
+/// use rocket::slo::Objective;
+///
+/// let objective: Objective = "99.5% < 1s".parse().unwrap();
+/// assert_eq!(objective, Objective::new(99.5).latency(Duration::from_secs(1)));
+/// assert_eq!(objective.to_string(), "99.5% < 1000ms");
+///
+/// assert!("100%".parse::<Objective>().is_err());
+/// assert!("99% > 1s".parse::<Objective>().is_err());
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Objective {
+    target: f64,
+    latency: Option<Duration>,
+}
+
+impl Default for SloConfig {
+    fn default() -> Self {
+        SloConfig {
+            objectives: IndexMap::new(),
+            short_window_secs: 300,
+            long_window_secs: 3600,
+            burn_rate_alert: 14.4,
+            path: None,
+        }
+    }
+}
+
+impl SloConfig {
+    /// Returns the short window.
+    pub fn short_window(&self) -> Duration {
+        Duration::from_secs(self.short_window_secs.max(1))
+    }
+
+    /// Returns the long window.
+    pub fn long_window(&self) -> Duration {
+        Duration::from_secs(self.long_window_secs.max(1))
+    }
+
+    /// Returns the scope and objective applying to requests to `path`, if any.
+    pub fn objective(&self, path: &str) -> Option<(&str, &Objective)> {
+        self.objectives.iter()
+            .filter(|(scope, _)| in_scope(scope, path))
+            .max_by_key(|(scope, _)| scope.trim_end_matches('/').len())
+            .map(|(scope, objective)| (scope.as_str(), objective))
+    }
+}
+
+impl Objective {
+    /// Returns an objective requiring `target` percent of requests to succeed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `target` is not strictly between `0` and `100`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::slo::Objective;
+    ///
+    /// let objective = Objective::new(99.9);
+    /// assert_eq!(objective.to_string(), "99.9%");
+    /// ```
+    #[track_caller]
+    pub fn new(target: f64) -> Self {
+        assert!(target > 0.0 && target < 100.0, "objective target must be in (0, 100)");
+        Objective { target, latency: None }
+    }
+
+    /// Additionally requires requests to complete in less than `latency`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use rocket::slo::Objective;
+    ///
+    /// let objective = Objective::new(99.9).latency(Duration::from_millis(300));
+    /// assert_eq!(objective.to_string(), "99.9% < 300ms");
+    /// ```
+    pub fn latency(mut self, latency: Duration) -> Self {
+        self.latency = Some(latency);
+        self
+    }
+
+    /// Returns the target percentage of successful requests.
+    pub fn target(&self) -> f64 {
+        self.target
+    }
+
+    /// Returns the latency requests must complete within, if any.
+    pub fn get_latency(&self) -> Option<Duration> {
+        self.latency
+    }
+
+    /// Returns the error budget: the fraction of requests that may fail.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::slo::Objective;
+    ///
+    /// assert!((Objective::new(99.9).budget() - 0.001).abs() < 1e-9);
+    /// ```
+    pub fn budget(&self) -> f64 {
+        1.0 - self.target / 100.0
+    }
+}
+
+impl FromStr for Objective {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (target, latency) = match s.split_once('<') {
+            Some((target, latency)) => (target.trim(), Some(latency.trim())),
+            None => (s.trim(), None),
+        };
+
+        let target = target.strip_suffix('%')
+            .and_then(|target| target.trim().parse::<f64>().ok())
+            .filter(|target| *target > 0.0 && *target < 100.0)
+            .ok_or_else(|| format!("invalid objective target in `{s}`: expected 0 < n% < 100"))?;
+
+        let latency = latency.map(|latency| {
+            let (n, unit) = latency.find(|c: char| !c.is_ascii_digit())
+                .map_or((latency, ""), |i| latency.split_at(i));
+
+            let n: u64 = n.parse().map_err(|_| format!("invalid objective latency in `{s}`"))?;
+            match unit.trim() {
+                "us" => Ok(Duration::from_micros(n)),
+                "ms" => Ok(Duration::from_millis(n)),
+                "s" => Ok(Duration::from_secs(n)),
+                _ => Err(format!("invalid latency unit in `{s}`: expected `us`, `ms`, or `s`")),
+            }
+        });
+
+        Ok(Objective { target, latency: latency.transpose()? })
+    }
+}
+
+impl TryFrom<String> for Objective {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<Objective> for String {
+    fn from(objective: Objective) -> Self {
+        objective.to_string()
+    }
+}
+
+impl fmt::Display for Objective {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}%", self.target)?;
+        match self.latency {
+            Some(latency) if latency.subsec_micros() % 1000 != 0 => {
+                write!(f, " < {}us", latency.as_micros())
+            }
+            Some(latency) => write!(f, " < {}ms", latency.as_millis()),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Returns `true` if `path` is equal to or, segment-wise, begins with `prefix`.
+fn in_scope(prefix: &str, path: &str) -> bool {
+    let prefix = prefix.trim_end_matches('/');
+    path.strip_prefix(prefix).map_or(false, |rest| rest.is_empty() || rest.starts_with('/'))
+}
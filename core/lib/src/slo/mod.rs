@@ -0,0 +1,58 @@
+//! Per-scope service level objectives and error budgets.
+//!
+//! The [`SloTracker`] fairing measures requests against an [`Objective`]
+//! declared for each scope, a path prefix, such as `99.9% < 300ms`: 99.9% of
+//! requests to the scope must complete without a server error (`5XX`) and,
+//! optionally, in less than 300ms. The remaining 0.1% is the scope's _error
+//! budget_. Requests to which no scope applies are not tracked.
+//!
+//! For each scope, the tracker computes the _burn rate_ over a short and a
+//! long window: the rate at which the budget is consumed, where `1.0` consumes
+//! exactly the budget over the window and `14.4` consumes a 30-day budget in
+//! about two days. When the burn rate over both windows exceeds
+//! `burn_rate_alert`, the budget is burning fast and a `WARN` event is traced;
+//! when either falls below it again, an `INFO` event is. Requiring both windows
+//! keeps alerts both fast to fire and fast to reset.
+//!
+//! Reports are available via [`SloTracker::reports()`] and, when `path` is
+//! configured, served as JSON at that path.
+//!
+//! # Configuration
+//!
+//! Objectives and thresholds are configured via the `slo` configuration
+//! parameter, which is deserialized as an [`SloConfig`]:
+//!
+//! ```toml
+//! [default.slo]
+//! short_window_secs = 300
+//! long_window_secs = 3600
+//! burn_rate_alert = 14.4
+//! path = "/_rocket/slo"
+//!
+//! [default.slo.objectives]
+//! "/api" = "99.9% < 300ms"
+//! "/checkout" = "99.95%"
+//! ```
+//!
+//! # Example
+//!
+//! ```rust
+//! # #[macro_use] extern crate rocket;
+//! use std::time::Duration;
+//! use rocket::slo::{SloTracker, Objective};
+//!
+//! #[launch]
+//! fn rocket() -> _ {
+//!     let tracker = SloTracker::new()
+//!         .objective("/api", Objective::new(99.9).latency(Duration::from_millis(300)))
+//!         .objective("/checkout", "99.95%".parse().unwrap());
+//!
+//!     rocket::build().attach(tracker)
+//! }
+//! ```
+
+mod config;
+mod tracker;
+
+pub use config::{SloConfig, Objective};
+pub use tracker::{SloTracker, Report};
@@ -0,0 +1,327 @@
+use std::fmt::Write;
+use std::sync::Arc;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use indexmap::IndexMap;
+use parking_lot::Mutex;
+use state::InitCell;
+
+use crate::{Rocket, Request, Response, Data, Build, Route};
+use crate::fairing::{self, Fairing, Info, Kind};
+use crate::http::{Method, ContentType};
+use crate::route::{Handler, Outcome};
+use crate::trace::Trace;
+use crate::util::EscapeJson;
+use crate::slo::{SloConfig, Objective};
+
+/// A [`Fairing`] that tracks [service level objectives](crate::slo).
+///
+/// Objectives and thresholds are read from the `slo` configuration parameter
+/// at ignition. If the parameter is invalid, ignition fails. Objectives set via
+/// [`SloTracker::objective()`] apply to scopes without a configured objective.
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::slo::{SloTracker, Objective};
+/// use rocket::local::blocking::Client;
+///
+/// #[get("/api/ping")]
+/// fn ping() -> &'static str { "pong" }
+///
+/// let tracker = SloTracker::new().objective("/api", Objective::new(99.0));
+/// let rocket = rocket::build().mount("/", routes![ping]).attach(tracker);
+/// let client = Client::debug(rocket).unwrap();
+/// client.get("/api/ping").dispatch();
+///
+/// let tracker = client.rocket().fairing::<SloTracker>().unwrap();
+/// let report = tracker.report("/api").unwrap();
+/// assert_eq!(report.requests, 1);
+/// assert_eq!(report.bad, 0);
+/// assert_eq!(report.budget_remaining, 1.0);
+/// assert!(!report.alerting);
+/// ```
+#[derive(Clone)]
+pub struct SloTracker {
+    objectives: IndexMap<String, Objective>,
+    inner: Arc<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    config: InitCell<SloConfig>,
+    scopes: Mutex<IndexMap<String, Scope>>,
+}
+
+/// The tracked state of a scope.
+struct Scope {
+    short: Window,
+    long: Window,
+    alerting: bool,
+}
+
+/// Request counts over a sliding window, kept in a fixed number of buckets.
+struct Window {
+    width: Duration,
+    buckets: VecDeque<Bucket>,
+}
+
+#[derive(Clone, Copy)]
+struct Bucket {
+    index: u64,
+    requests: u64,
+    bad: u64,
+}
+
+/// The instant the request fairing ran for a request.
+struct Started(Instant);
+
+/// A report on the objective of a scope.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    /// The scope: a path prefix.
+    pub scope: String,
+    /// The scope's objective.
+    pub objective: Objective,
+    /// The number of requests over the long window.
+    pub requests: u64,
+    /// The number of requests over the long window that missed the objective.
+    pub bad: u64,
+    /// The rate at which the error budget is consumed over the short window.
+    pub short_burn_rate: f64,
+    /// The rate at which the error budget is consumed over the long window.
+    pub long_burn_rate: f64,
+    /// The fraction of the error budget remaining over the long window. May be
+    /// negative when the budget is exhausted.
+    pub budget_remaining: f64,
+    /// Whether the budget is burning fast: both burn rates exceed the alert
+    /// threshold.
+    pub alerting: bool,
+}
+
+impl SloTracker {
+    /// The number of buckets in a window.
+    const BUCKETS: u64 = 60;
+
+    /// Returns an `SloTracker` fairing.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::slo::SloTracker;
+    ///
+    /// let tracker = SloTracker::new();
+    /// ```
+    pub fn new() -> Self {
+        SloTracker { objectives: IndexMap::new(), inner: Arc::default() }
+    }
+
+    /// Sets the objective of requests to `scope` and paths under it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use rocket::slo::{SloTracker, Objective};
+    ///
+    /// let tracker = SloTracker::new()
+    ///     .objective("/api", Objective::new(99.9).latency(Duration::from_millis(300)));
+    /// ```
+    pub fn objective<S: Into<String>>(mut self, scope: S, objective: Objective) -> Self {
+        self.objectives.insert(scope.into(), objective);
+        self
+    }
+
+    /// Returns the report on `scope`, if it has an objective.
+    pub fn report(&self, scope: &str) -> Option<Report> {
+        self.reports().into_iter().find(|report| report.scope == scope)
+    }
+
+    /// Returns reports on every scope with an objective, in configuration
+    /// order. Reports are empty before ignition.
+    pub fn reports(&self) -> Vec<Report> {
+        let Some(config) = self.inner.config.try_get() else { return vec![] };
+        let now = Instant::now();
+        let mut scopes = self.inner.scopes.lock();
+        config.objectives.iter()
+            .map(|(scope, objective)| {
+                let state = scopes.entry(scope.clone()).or_insert_with(|| Scope::new(config));
+                state.report(scope, objective, now, config.burn_rate_alert)
+            })
+            .collect()
+    }
+
+    /// Records a request to `scope`, `bad` if it missed `objective`.
+    fn record(&self, config: &SloConfig, scope: &str, objective: &Objective, bad: bool) {
+        let now = Instant::now();
+        let mut scopes = self.inner.scopes.lock();
+        let state = scopes.entry(scope.into()).or_insert_with(|| Scope::new(config));
+        state.short.record(now, bad);
+        state.long.record(now, bad);
+
+        let report = state.report(scope, objective, now, config.burn_rate_alert);
+        match (state.alerting, report.alerting) {
+            (false, true) => warn!(name: "slo", scope, %objective,
+                short_burn_rate = report.short_burn_rate,
+                long_burn_rate = report.long_burn_rate,
+                budget_remaining = report.budget_remaining,
+                "error budget is burning fast"),
+            (true, false) => info!(name: "slo", scope, %objective,
+                short_burn_rate = report.short_burn_rate,
+                long_burn_rate = report.long_burn_rate,
+                "error budget is no longer burning fast"),
+            _ => {}
+        }
+
+        state.alerting = report.alerting;
+    }
+}
+
+impl Default for SloTracker {
+    fn default() -> Self {
+        SloTracker::new()
+    }
+}
+
+impl Scope {
+    fn new(config: &SloConfig) -> Self {
+        Scope {
+            short: Window::new(config.short_window()),
+            long: Window::new(config.long_window()),
+            alerting: false,
+        }
+    }
+
+    fn report(&self, scope: &str, objective: &Objective, now: Instant, alert: f64) -> Report {
+        let burn_rate = |(requests, bad): (u64, u64)| match requests {
+            0 => 0.0,
+            n => (bad as f64 / n as f64) / objective.budget(),
+        };
+
+        let (requests, bad) = self.long.counts(now);
+        let short_burn_rate = burn_rate(self.short.counts(now));
+        let long_burn_rate = burn_rate((requests, bad));
+        Report {
+            scope: scope.into(),
+            objective: *objective,
+            requests,
+            bad,
+            short_burn_rate,
+            long_burn_rate,
+            budget_remaining: match requests {
+                0 => 1.0,
+                n => 1.0 - bad as f64 / (n as f64 * objective.budget()),
+            },
+            alerting: short_burn_rate > alert && long_burn_rate > alert,
+        }
+    }
+}
+
+impl Window {
+    fn new(length: Duration) -> Self {
+        let width = (length / SloTracker::BUCKETS as u32).max(Duration::from_millis(1));
+        Window { width, buckets: VecDeque::new() }
+    }
+
+    /// The index of the bucket `now` falls into.
+    fn index(&self, now: Instant) -> u64 {
+        static EPOCH: std::sync::OnceLock<Instant> = std::sync::OnceLock::new();
+        let elapsed = now.saturating_duration_since(*EPOCH.get_or_init(Instant::now));
+        (elapsed.as_nanos() / self.width.as_nanos()) as u64
+    }
+
+    fn record(&mut self, now: Instant, bad: bool) {
+        let index = self.index(now);
+        while self.buckets.front().map_or(false, |b| b.index + SloTracker::BUCKETS <= index) {
+            self.buckets.pop_front();
+        }
+
+        match self.buckets.back_mut() {
+            Some(bucket) if bucket.index == index => {
+                bucket.requests += 1;
+                bucket.bad += bad as u64;
+            }
+            _ => self.buckets.push_back(Bucket { index, requests: 1, bad: bad as u64 }),
+        }
+    }
+
+    /// Returns the number of requests and bad requests in the window at `now`.
+    fn counts(&self, now: Instant) -> (u64, u64) {
+        let index = self.index(now);
+        self.buckets.iter()
+            .filter(|b| b.index + SloTracker::BUCKETS > index)
+            .fold((0, 0), |(requests, bad), b| (requests + b.requests, bad + b.bad))
+    }
+}
+
+#[crate::async_trait]
+impl Fairing for SloTracker {
+    fn info(&self) -> Info {
+        Info {
+            name: "SLO Tracker",
+            kind: Kind::Ignite | Kind::Request | Kind::Response | Kind::Singleton,
+        }
+    }
+
+    async fn on_ignite(&self, rocket: Rocket<Build>) -> fairing::Result {
+        let mut config = match rocket.figment().contains("slo") {
+            true => match rocket.figment().extract_inner::<SloConfig>("slo") {
+                Ok(config) => config,
+                Err(e) => {
+                    e.trace_error();
+                    return Err(rocket);
+                }
+            },
+            false => SloConfig::default(),
+        };
+
+        for (scope, objective) in &self.objectives {
+            config.objectives.entry(scope.clone()).or_insert(*objective);
+        }
+
+        let path = config.path.clone();
+        self.inner.config.set(config);
+        match path {
+            Some(path) => Ok(rocket.mount(path, vec![Route::new(Method::Get, "/", self.clone())])),
+            None => Ok(rocket),
+        }
+    }
+
+    async fn on_request(&self, req: &mut Request<'_>, _: &mut Data<'_>) {
+        if self.inner.config.try_get().is_some() {
+            req.local_cache(|| Started(Instant::now()));
+        }
+    }
+
+    async fn on_response<'r>(&self, req: &'r Request<'_>, res: &mut Response<'r>) {
+        let Some(config) = self.inner.config.try_get() else { return };
+        let Some(Started(start)) = req.state.cache.try_get::<Started>() else { return };
+        let Some((scope, objective)) = config.objective(req.uri().path().as_str()) else {
+            return;
+        };
+
+        let too_slow = objective.get_latency().map_or(false, |max| start.elapsed() >= max);
+        let bad = res.status().class().is_server_error() || too_slow;
+        self.record(config, scope, objective, bad);
+    }
+}
+
+#[crate::async_trait]
+impl Handler for SloTracker {
+    async fn handle<'r>(&self, req: &'r Request<'_>, _: Data<'r>) -> Outcome<'r> {
+        let mut out = String::from("[");
+        for (i, r) in self.reports().iter().enumerate() {
+            if i != 0 { out.push(','); }
+            let _ = write!(out, "{{\"scope\":\"{}\",\"objective\":\"{}\",\"requests\":{},\
+                \"bad\":{},\"short_burn_rate\":{},\"long_burn_rate\":{},\
+                \"budget_remaining\":{},\"alerting\":{}}}",
+                EscapeJson(&r.scope), r.objective, r.requests, r.bad, r.short_burn_rate,
+                r.long_burn_rate, r.budget_remaining, r.alerting);
+        }
+
+        out.push(']');
+        Outcome::from(req, (ContentType::JSON, out))
+    }
+}
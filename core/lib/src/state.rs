@@ -1,10 +1,13 @@
 use std::fmt;
 use std::ops::Deref;
-use std::any::type_name;
+use std::any::{type_name, Any, TypeId};
+
+use state::TypeMap;
 
 use ref_cast::RefCast;
 
 use crate::{Phase, Rocket, Ignite, Sentinel};
+use crate::sentinel::Missing;
 use crate::request::{self, FromRequest, Request};
 use crate::outcome::Outcome;
 use crate::http::Status;
@@ -222,6 +225,11 @@ impl<T: Send + Sync + 'static> Sentinel for &State<T> {
 
         false
     }
+
+    fn missing(rocket: &Rocket<Ignite>) -> Option<Missing> {
+        rocket.state::<T>().is_none().then(|| Missing::new::<T>()
+            .suggestion(format!("manage a `{}` via `rocket.manage()`", type_name::<T>())))
+    }
 }
 
 impl<T: Send + Sync + fmt::Display + 'static> fmt::Display for State<T> {
@@ -238,3 +246,89 @@ impl<T: Send + Sync + 'static> Deref for State<T> {
         &self.0
     }
 }
+
+type AnyState = Box<dyn Any + Send + Sync>;
+
+/// Managed state during the build phase.
+///
+/// Unlike a `TypeMap`, values can be moved out, as [`Rocket::merge()`] does.
+/// The values are moved into a `TypeMap` on ignition.
+#[derive(Default)]
+pub(crate) struct Managed {
+    values: Vec<ManagedValue>,
+}
+
+struct ManagedValue {
+    type_id: TypeId,
+    type_name: &'static str,
+    value: AnyState,
+    install: fn(AnyState, &TypeMap![Send + Sync]),
+}
+
+impl Managed {
+    /// Manages `value` unless a value of type `T` is already managed. Returns
+    /// `true` if `value` is now managed.
+    pub fn set<T: Send + Sync + 'static>(&mut self, value: T) -> bool {
+        if self.contains(TypeId::of::<T>()) {
+            return false;
+        }
+
+        self.values.push(ManagedValue {
+            type_id: TypeId::of::<T>(),
+            type_name: type_name::<T>(),
+            value: Box::new(value),
+            install: |value, map| {
+                if let Ok(value) = value.downcast::<T>() {
+                    map.set::<T>(*value);
+                }
+            },
+        });
+
+        true
+    }
+
+    pub fn try_get<T: Send + Sync + 'static>(&self) -> Option<&T> {
+        self.values.iter().find_map(|v| v.value.downcast_ref())
+    }
+
+    /// Removes and returns the value of type `T`, if any.
+    pub fn take<T: Send + Sync + 'static>(&mut self) -> Option<T> {
+        let i = self.values.iter().position(|v| v.type_id == TypeId::of::<T>())?;
+        self.values.remove(i).value.downcast().ok().map(|value| *value)
+    }
+
+    /// Moves all of the values in `other` into `self`. If a type is managed by
+    /// both, nothing is moved and the names of all such types are returned.
+    pub fn append(&mut self, other: Managed) -> Result<(), Vec<&'static str>> {
+        let conflicts: Vec<_> = other.values.iter()
+            .filter(|v| self.contains(v.type_id))
+            .map(|v| v.type_name)
+            .collect();
+
+        if !conflicts.is_empty() {
+            return Err(conflicts);
+        }
+
+        self.values.extend(other.values);
+        Ok(())
+    }
+
+    pub fn into_type_map(self) -> TypeMap![Send + Sync] {
+        let map = <TypeMap![Send + Sync]>::new();
+        for managed in self.values {
+            (managed.install)(managed.value, &map);
+        }
+
+        map
+    }
+
+    fn contains(&self, type_id: TypeId) -> bool {
+        self.values.iter().any(|v| v.type_id == type_id)
+    }
+}
+
+impl fmt::Debug for Managed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.values.iter().map(|v| v.type_name)).finish()
+    }
+}
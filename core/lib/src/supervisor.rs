@@ -0,0 +1,297 @@
+//! Supervising an embedded Rocket from the outside.
+//!
+//! [`Rocket::launch()`] consumes the instance and runs until shutdown, which
+//! is inconvenient when Rocket is one part of a larger application such as a
+//! GUI or an agent running a local HTTP server. A supervisor [`Handle`] instead
+//! launches Rocket in the background and remains with the caller, who can:
+//!
+//!   * query the [`State`] of the server, its endpoints, and [`Stats`],
+//!   * inspect the [`DrainReport`] of its last shutdown,
+//!   * wait for the server to be [`ready`](Handle::ready()) to serve requests,
+//!   * [`reload`](Handle::reload()) it: gracefully shut it down and launch an
+//!     instance built anew, picking up configuration changes,
+//!   * [`close`](Handle::close_listeners()) some of its listeners while the
+//!     others keep serving,
+//!   * [`shut it down`](Handle::shutdown()), and
+//!   * [`wait`](Handle::wait()) for it to exit.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! # #[macro_use] extern crate rocket;
+//! use rocket::supervisor::Handle;
+//!
+//! #[get("/")]
+//! fn index() -> &'static str { "Hello, world!" }
+//!
+//! #[rocket::main]
+//! async fn main() -> Result<(), rocket::Error> {
+//!     let handle = Handle::launch(|| rocket::build().mount("/", routes![index]));
+//!     if let Some(endpoints) = handle.ready().await {
+//!         println!("serving at {}", endpoints[0]);
+//!     }
+//!
+//!     /* ... run the rest of the application ... */
+//!
+//!     handle.shutdown();
+//!     handle.wait().await?;
+//!     Ok(())
+//! }
+//! ```
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+
+use parking_lot::Mutex;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+use crate::{Rocket, Build, Ignite, Orbit, Request, Response, Data, Error, Shutdown};
+use crate::fairing::{Fairing, Info, Kind};
+use crate::listener::Endpoint;
+use crate::events::EventBus;
+use crate::shutdown::{DrainReport, InFlight};
+
+/// The state of a supervised server.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum State {
+    /// The server is being built, ignited, or bound.
+    Launching,
+    /// Liftoff completed: the server is serving requests.
+    Running,
+    /// The server is shutting down, to exit or to be reloaded.
+    ShuttingDown,
+    /// The server exited. See [`Handle::wait()`] for the result.
+    Exited,
+}
+
+/// Statistics about a supervised server, across reloads.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Stats {
+    /// The number of times an instance was launched, including reloads.
+    pub launches: u64,
+    /// The number of requests received.
+    pub requests: u64,
+    /// The number of requests currently being handled.
+    pub active: usize,
+}
+
+/// A handle to a Rocket server launched in the background.
+///
+/// See the [module docs](self) for an example.
+pub struct Handle {
+    inner: Arc<Inner>,
+    task: JoinHandle<Result<Rocket<Ignite>, Error>>,
+}
+
+struct Inner {
+    state: watch::Sender<State>,
+    endpoints: Mutex<Vec<Endpoint>>,
+    shutdown: Mutex<Option<Shutdown>>,
+    closing: Mutex<Option<(Vec<Shutdown>, EventBus)>>,
+    in_flight: Mutex<Option<Arc<InFlight>>>,
+    drained: Mutex<Option<DrainReport>>,
+    stop: AtomicBool,
+    reload: AtomicBool,
+    launches: AtomicU64,
+    requests: AtomicU64,
+    active: AtomicUsize,
+}
+
+/// The fairing attached to each supervised instance.
+struct Supervised(Arc<Inner>);
+
+impl Handle {
+    /// Launches the instance of Rocket returned by `build` in the background,
+    /// returning a handle to it. `build` is called again to rebuild the
+    /// instance on every [`reload()`](Handle::reload()).
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a Tokio runtime.
+    pub fn launch<F>(mut build: F) -> Handle
+        where F: FnMut() -> Rocket<Build> + Send + 'static
+    {
+        let inner = Arc::new(Inner {
+            state: watch::Sender::new(State::Launching),
+            endpoints: Mutex::new(vec![]),
+            shutdown: Mutex::new(None),
+            closing: Mutex::new(None),
+            in_flight: Mutex::new(None),
+            drained: Mutex::new(None),
+            stop: AtomicBool::new(false),
+            reload: AtomicBool::new(false),
+            launches: AtomicU64::new(0),
+            requests: AtomicU64::new(0),
+            active: AtomicUsize::new(0),
+        });
+
+        let task = tokio::spawn({
+            let inner = inner.clone();
+            async move {
+                loop {
+                    inner.state.send_replace(State::Launching);
+                    inner.reload.store(false, Ordering::Release);
+                    inner.launches.fetch_add(1, Ordering::Relaxed);
+
+                    let result = build().attach(Supervised(inner.clone())).launch().await;
+                    inner.shutdown.lock().take();
+                    inner.closing.lock().take();
+                    inner.endpoints.lock().clear();
+                    if let Some(in_flight) = inner.in_flight.lock().take() {
+                        *inner.drained.lock() = in_flight.drain_report();
+                    }
+
+                    let reload = inner.reload.load(Ordering::Acquire);
+                    if result.is_err() || !reload || inner.stop.load(Ordering::Acquire) {
+                        inner.state.send_replace(State::Exited);
+                        return result;
+                    }
+
+                    info!(name: "supervisor", "reloading");
+                }
+            }
+        });
+
+        Handle { inner, task }
+    }
+
+    /// Returns the current state of the server.
+    pub fn state(&self) -> State {
+        *self.inner.state.borrow()
+    }
+
+    /// Returns the endpoints the server is listening on. Empty unless the
+    /// server is [`State::Running`] or [`State::ShuttingDown`].
+    pub fn endpoints(&self) -> Vec<Endpoint> {
+        self.inner.endpoints.lock().clone()
+    }
+
+    /// Returns statistics about the server.
+    pub fn stats(&self) -> Stats {
+        Stats {
+            launches: self.inner.launches.load(Ordering::Relaxed),
+            requests: self.inner.requests.load(Ordering::Relaxed),
+            active: self.inner.active.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Returns the report of how the last shutdown, including one to
+    /// [`reload()`](Handle::reload()), drained in-flight requests. `None` if
+    /// no instance has shut down yet.
+    ///
+    /// See [`DrainReport`] for details.
+    pub fn drain_report(&self) -> Option<DrainReport> {
+        self.inner.drained.lock().clone()
+    }
+
+    /// Waits until the server is running, returning its endpoints, or until it
+    /// exits, returning `None`. After a [`reload()`](Handle::reload()), waits
+    /// for the reloaded instance.
+    pub async fn ready(&self) -> Option<Vec<Endpoint>> {
+        let mut state = self.inner.state.subscribe();
+        let state = state.wait_for(|s| matches!(s, State::Running | State::Exited)).await;
+        match state.as_deref() {
+            Ok(State::Running) => Some(self.endpoints()),
+            _ => None,
+        }
+    }
+
+    /// Gracefully shuts the server down and launches a newly built instance in
+    /// its place. If the server isn't running yet, the reload happens
+    /// immediately after liftoff.
+    pub fn reload(&self) {
+        self.inner.reload.store(true, Ordering::Release);
+        self.inner.notify_shutdown();
+    }
+
+    /// Gracefully closes the listeners whose endpoints satisfy `f` while the
+    /// remaining listeners keep serving, returning the number closed. Returns
+    /// `0` if the server isn't running. See [`Rocket::close_listeners()`] for
+    /// details.
+    ///
+    /// Listeners are opened anew when the server is
+    /// [`reload`](Handle::reload())ed.
+    pub fn close_listeners<F>(&self, f: F) -> usize
+        where F: FnMut(&Endpoint) -> bool
+    {
+        let endpoints = self.inner.endpoints.lock();
+        match &*self.inner.closing.lock() {
+            Some((closing, events)) => {
+                crate::server::close_listeners(&endpoints, closing, events, f)
+            }
+            None => 0,
+        }
+    }
+
+    /// Gracefully shuts the server down. If the server isn't running yet, it
+    /// shuts down immediately after liftoff.
+    pub fn shutdown(&self) {
+        self.inner.stop.store(true, Ordering::Release);
+        self.inner.notify_shutdown();
+    }
+
+    /// Returns `true` if the server exited.
+    pub fn is_finished(&self) -> bool {
+        self.task.is_finished()
+    }
+
+    /// Waits for the server to exit, returning the result of the final
+    /// [`Rocket::launch()`].
+    ///
+    /// # Panics
+    ///
+    /// Resumes the panic if launching panicked.
+    pub async fn wait(self) -> Result<Rocket<Ignite>, Error> {
+        match self.task.await {
+            Ok(result) => result,
+            Err(e) => std::panic::resume_unwind(e.into_panic()),
+        }
+    }
+}
+
+impl Inner {
+    fn notify_shutdown(&self) {
+        if let Some(shutdown) = &*self.shutdown.lock() {
+            self.state.send_replace(State::ShuttingDown);
+            shutdown.notify();
+        }
+    }
+}
+
+#[crate::async_trait]
+impl Fairing for Supervised {
+    fn info(&self) -> Info {
+        Info {
+            name: "Supervisor",
+            kind: Kind::Liftoff | Kind::Request | Kind::Response | Kind::Shutdown
+        }
+    }
+
+    async fn on_liftoff(&self, rocket: &Rocket<Orbit>) {
+        *self.0.endpoints.lock() = rocket.endpoints().cloned().collect();
+        *self.0.shutdown.lock() = Some(rocket.shutdown());
+        *self.0.closing.lock() = Some((rocket.closing.clone(), rocket.events.clone()));
+        *self.0.in_flight.lock() = Some(rocket.in_flight.clone());
+        self.0.state.send_replace(State::Running);
+
+        // Honor requests made before the shutdown handle was available.
+        if self.0.stop.load(Ordering::Acquire) || self.0.reload.load(Ordering::Acquire) {
+            self.0.notify_shutdown();
+        }
+    }
+
+    async fn on_request(&self, _: &mut Request<'_>, _: &mut Data<'_>) {
+        self.0.requests.fetch_add(1, Ordering::Relaxed);
+        self.0.active.fetch_add(1, Ordering::Relaxed);
+    }
+
+    async fn on_response<'r>(&self, _: &'r Request<'_>, _: &mut Response<'r>) {
+        self.0.active.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    async fn on_shutdown(&self, _: &Rocket<Orbit>) {
+        self.0.state.send_replace(State::ShuttingDown);
+    }
+}
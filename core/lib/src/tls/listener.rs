@@ -31,6 +31,13 @@ impl<L> TlsListener<L>
             config,
         })
     }
+
+    /// Wraps `listener` per the `tls` configuration of `rocket`.
+    pub(crate) async fn configured(listener: L, rocket: &Rocket<Ignite>) -> Result<Self> {
+        let mut config: TlsConfig = rocket.figment().extract_inner("tls")?;
+        config.resolver = DynResolver::extract(rocket);
+        Self::from(listener, config).await
+    }
 }
 
 impl<L: Bind> Bind for TlsListener<L>
@@ -40,9 +47,7 @@ impl<L: Bind> Bind for TlsListener<L>
 
     async fn bind(rocket: &Rocket<Ignite>) -> Result<Self, Self::Error> {
         let listener = L::bind(rocket).map_err(|e| Error::Bind(Box::new(e))).await?;
-        let mut config: TlsConfig = rocket.figment().extract_inner("tls")?;
-        config.resolver = DynResolver::extract(rocket);
-        Self::from(listener, config).await
+        Self::configured(listener, rocket).await
     }
 
     fn bind_endpoint(rocket: &Rocket<Ignite>) -> Result<Endpoint, Self::Error> {
@@ -7,6 +7,7 @@ mod traceable;
 pub mod subscriber;
 
 pub(crate) mod level;
+pub(crate) mod unsampled;
 
 #[doc(inline)]
 pub use macros::*;
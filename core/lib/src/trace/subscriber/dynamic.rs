@@ -9,8 +9,9 @@ use tracing_subscriber::registry::{Registry, LookupSpan};
 use tracing_subscriber::layer::{Context, Layer, Layered, SubscriberExt};
 use tracing_subscriber::util::SubscriberInitExt;
 
-use crate::config::Config;
+use crate::config::{Config, ScrubConfig};
 use crate::trace::subscriber::{Compact, Pretty, RequestId, RequestIdLayer, RocketFmt};
+use crate::trace::subscriber::{RecentEvents, scrub};
 use crate::trace::TraceFormat;
 
 /// A subscriber that is either a [`Pretty`] or [`Compact`] [`RocketFmt`].
@@ -61,6 +62,11 @@ impl RocketDynFmt {
             return;
         }
 
+        // Only keep per-request event history, used by the debug error page,
+        // when running in the debug profile.
+        RecentEvents::enable(config.map_or(false, |c| c.profile == Config::DEBUG_PROFILE));
+        scrub::install(config.map_or(&ScrubConfig::default(), |c| &c.log_scrub));
+
         let formatter = Self::new(config);
         if let Some(handle) = HANDLE.get() {
             return assert!(handle.modify(|layer| *layer = formatter).is_ok());
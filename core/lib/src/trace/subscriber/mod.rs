@@ -4,11 +4,12 @@ mod compact;
 mod dynamic;
 mod common;
 mod request_id;
+pub(crate) mod scrub;
 
 pub use pretty::Pretty;
 pub use compact::Compact;
 pub use common::RocketFmt;
-pub use request_id::{RequestId, RequestIdLayer};
+pub use request_id::{RequestId, RequestIdLayer, RecentEvents};
 pub use dynamic::RocketDynFmt;
 
 pub(crate) use visit::{RecordDisplay, Data};
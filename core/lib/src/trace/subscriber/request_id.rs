@@ -1,12 +1,16 @@
-use std::fmt;
+use std::fmt::{self, Write};
 use std::hash::{Hash, Hasher};
 use std::thread::ThreadId;
 use std::cell::Cell;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
 
-use tracing::Subscriber;
+use tracing::{Event, Subscriber};
 use tracing::span::{Attributes, Id};
 use tracing_subscriber::{layer::Context, Layer};
-use tracing_subscriber::registry::{LookupSpan, SpanRef};
+use tracing_subscriber::registry::{LookupSpan, Registry, SpanRef};
+
+use crate::trace::subscriber::RecordDisplay;
 
 pub struct RequestIdLayer;
 
@@ -16,6 +20,15 @@ pub struct RequestId(u128);
 #[derive(Default)]
 pub struct IdentHasher(u128);
 
+/// The most recent events emitted while handling a request, rendered as text.
+///
+/// Events are only recorded when enabled via [`RecentEvents::enable()`], which
+/// Rocket does when tracing is initialized with a `debug` profile config.
+#[derive(Debug, Default, Clone)]
+pub struct RecentEvents(VecDeque<String>);
+
+static RECORD_EVENTS: AtomicBool = AtomicBool::new(false);
+
 impl RequestId {
     fn new() -> Self {
         thread_local! {
@@ -59,6 +72,46 @@ impl RequestId {
     }
 }
 
+impl RecentEvents {
+    /// The maximum number of events kept per request.
+    const CAPACITY: usize = 64;
+
+    /// Enables or disables recording of recent events.
+    pub fn enable(enabled: bool) {
+        RECORD_EVENTS.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Returns the events recorded so far for the request being handled in
+    /// the current span, oldest first. Returns an empty vector if recording is
+    /// disabled or there is no current request span.
+    pub fn current() -> Vec<String> {
+        tracing::dispatcher::get_default(|dispatch| {
+            let registry = dispatch.downcast_ref::<Registry>()?;
+            let span = registry.span(&tracing::Span::current().id()?)?;
+            let request = span.scope().find(|span| span.name() == "request")?;
+            let events = request.extensions().get::<Self>()?.0.iter().cloned().collect();
+            Some(events)
+        }).unwrap_or_default()
+    }
+
+    fn push(&mut self, event: &Event<'_>) {
+        let meta = event.metadata();
+        let mut line = format!("{:<5} {}:", meta.level(), meta.target());
+        event.record_display(|field, value| {
+            let _ = match field.name() {
+                "message" => write!(line, " {value}"),
+                name => write!(line, " {name}={value}"),
+            };
+        });
+
+        if self.0.len() == Self::CAPACITY {
+            self.0.pop_front();
+        }
+
+        self.0.push_back(line);
+    }
+}
+
 impl RequestIdLayer {
     thread_local! {
         static CURRENT_REQUEST_ID: Cell<Option<RequestId>> = Cell::new(None);
@@ -90,6 +143,25 @@ impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for RequestIdLayer {
             Self::CURRENT_REQUEST_ID.set(None);
         }
     }
+
+    fn on_event(&self, event: &Event<'_>, ctxt: Context<'_, S>) {
+        if !RECORD_EVENTS.load(Ordering::Relaxed) {
+            return;
+        }
+
+        let Some(span) = ctxt.event_span(event) else { return };
+        if let Some(request) = span.scope().find(|span| span.name() == "request") {
+            let mut extensions = request.extensions_mut();
+            match extensions.get_mut::<RecentEvents>() {
+                Some(events) => events.push(event),
+                None => {
+                    let mut events = RecentEvents::default();
+                    events.push(event);
+                    extensions.insert(events);
+                }
+            }
+        }
+    }
 }
 
 impl fmt::Display for RequestId {
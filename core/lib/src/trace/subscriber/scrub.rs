@@ -0,0 +1,63 @@
+use std::borrow::Cow;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use parking_lot::RwLock;
+use regex::Regex;
+
+use crate::config::ScrubConfig;
+
+/// The compiled rules of a [`ScrubConfig`].
+struct Scrubber {
+    fields: Vec<String>,
+    pattern: Option<Regex>,
+}
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+static SCRUBBER: RwLock<Option<Scrubber>> = parking_lot::const_rwlock(None);
+
+/// Replaces the rules applied by [`scrub()`] with those in `config`.
+pub(crate) fn install(config: &ScrubConfig) {
+    let patterns: Vec<_> = config.patterns.iter()
+        .map(|pattern| format!("(?:{pattern})"))
+        .collect();
+
+    // Patterns are validated when the configuration is extracted.
+    let pattern = match patterns.is_empty() {
+        true => None,
+        false => Regex::new(&patterns.join("|")).ok(),
+    };
+
+    let scrubber = Scrubber { fields: config.fields.clone(), pattern };
+    *SCRUBBER.write() = Some(scrubber);
+    ENABLED.store(!config.is_empty(), Ordering::Release);
+}
+
+/// Returns `true` if there are rules to apply.
+#[inline(always)]
+pub(crate) fn enabled() -> bool {
+    ENABLED.load(Ordering::Acquire)
+}
+
+/// Returns `value`, the value of the field `name`, with the installed rules
+/// applied.
+pub(crate) fn scrub<'a>(name: &str, value: &'a str) -> Cow<'a, str> {
+    let scrubber = SCRUBBER.read();
+    let Some(scrubber) = scrubber.as_ref() else {
+        return Cow::Borrowed(value);
+    };
+
+    let last = name.rsplit('.').next().unwrap_or(name);
+    let named = |scrubbed: &String| {
+        scrubbed.eq_ignore_ascii_case(name) || scrubbed.eq_ignore_ascii_case(last)
+    };
+
+    if scrubber.fields.iter().any(named) {
+        return Cow::Borrowed(ScrubConfig::REDACTED);
+    }
+
+    match &scrubber.pattern {
+        Some(pattern) => pattern.replace_all(value, ScrubConfig::REDACTED),
+        None => Cow::Borrowed(value),
+    }
+}
@@ -5,6 +5,7 @@ use tracing::field::{Field, Visit};
 use tracing_subscriber::field::RecordFields;
 
 use crate::util::Formatter;
+use crate::trace::subscriber::scrub;
 
 pub trait RecordDisplay: RecordFields {
     fn find_map_display<T, F: Fn(&dyn fmt::Display) -> T>(&self, name: &str, f: F) -> Option<T>;
@@ -45,11 +46,18 @@ impl std::ops::Index<&str> for Data {
 
 impl Visit for Data {
     fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
-        self.map.push((field.name(), format!("{:?}", value)));
+        let value = format!("{:?}", value);
+        match scrub::enabled() {
+            true => self.map.push((field.name(), scrub::scrub(field.name(), &value).into_owned())),
+            false => self.map.push((field.name(), value)),
+        }
     }
 
     fn record_str(&mut self, field: &Field, value: &str) {
-        self.map.push((field.name(), value.into()));
+        match scrub::enabled() {
+            true => self.map.push((field.name(), scrub::scrub(field.name(), value).into_owned())),
+            false => self.map.push((field.name(), value.into())),
+        }
     }
 }
 
@@ -65,11 +73,17 @@ impl<T: RecordFields> RecordDisplay for T {
 
         impl<F: FnMut(&Field, &dyn fmt::Display)> Visit for DisplayVisit<F> {
             fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
-                (self.0)(field, &Formatter(|f| value.fmt(f)));
+                match scrub::enabled() {
+                    true => (self.0)(field, &scrub::scrub(field.name(), &format!("{:?}", value))),
+                    false => (self.0)(field, &Formatter(|f| value.fmt(f))),
+                }
             }
 
             fn record_str(&mut self, field: &Field, value: &str) {
-                (self.0)(field, &value)
+                match scrub::enabled() {
+                    true => (self.0)(field, &scrub::scrub(field.name(), value)),
+                    false => (self.0)(field, &value),
+                }
             }
         }
 
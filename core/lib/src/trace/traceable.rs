@@ -1,7 +1,7 @@
 use std::error::Error as StdError;
 
 use crate::request::ConnectionMeta;
-use crate::sentinel::Sentry;
+use crate::sentinel::{Sentry, Diagnostic};
 use crate::util::Formatter;
 use crate::{route, Catcher, Config, Error, Request, Response, Route};
 use crate::error::ErrorKind;
@@ -81,17 +81,22 @@ impl Trace for Config {
             http2 = cfg!(feature = "http2"),
             log_level = self.log_level.map(|l| l.as_str()),
             log_format = ?self.log_format,
+            log_scrub.fields = ?self.log_scrub.fields,
+            log_scrub.patterns = self.log_scrub.patterns.len(),
             cli_colors = %self.cli_colors,
             workers = self.workers,
             max_blocking = self.max_blocking,
             ident = %self.ident,
             ip_header = self.ip_header.as_ref().map(|s| s.as_str()),
             proxy_proto_header = self.proxy_proto_header.as_ref().map(|s| s.as_str()),
+            hosts.allow = ?self.hosts.allow,
+            hosts.forwarded_header = self.hosts.forwarded_header.as_ref().map(|s| s.as_str()),
             limits = %Formatter(|f| f.debug_map()
                 .entries(self.limits.limits.iter().map(|(k, v)| (k.as_str(), display(v))))
                 .finish()),
             temp_dir = %self.temp_dir.relative().display(),
             keep_alive = (self.keep_alive != 0).then_some(self.keep_alive),
+            metering = self.metering,
             shutdown.ctrlc = self.shutdown.ctrlc,
             shutdown.signals = %{
                 #[cfg(not(unix))] {
@@ -107,6 +112,12 @@ impl Trace for Config {
                 shutdown.grace = self.shutdown.grace,
                 shutdown.mercy = self.shutdown.mercy,
                 shutdown.force = self.shutdown.force,
+                shutdown.drain_report = self.shutdown.drain_report,
+            cookies.default_same_site = %self.cookies.default_same_site,
+            cookies.secure = self.cookies.secure,
+            cookies.http_only = self.cookies.http_only,
+            access_log.redact = ?self.access_log.redact,
+            access_log.sample = self.access_log.sample.len(),
         }
 
         #[cfg(feature = "secrets")] {
@@ -134,6 +145,17 @@ impl Trace for Config {
                 }
             }
         }
+
+        #[cfg(feature = "mtls")] {
+            let forwarded = &self.forwarded_certs;
+            if !forwarded.trusted_proxies.is_empty() {
+                event! { level, "forwarded_certs",
+                    header = %forwarded.header,
+                    trusted_proxies = ?forwarded.trusted_proxies,
+                    "accepting client certificates forwarded by trusted proxies"
+                }
+            }
+        }
     }
 }
 
@@ -147,6 +169,7 @@ impl Trace for Route {
             uri.base = %self.uri.base(),
             uri.unmounted = %self.uri.unmounted(),
             format = self.format.as_ref().map(display),
+            condition = self.condition.as_ref().map(display),
             location = self.location.as_ref()
                 .map(|(file, line, _)| Formatter(move |f| write!(f, "{file}:{line}")))
                 .map(display),
@@ -260,6 +283,25 @@ impl Trace for Response<'_> {
 impl Trace for Error {
     fn trace(&self, level: Level) {
         self.kind.trace(level);
+        if !self.diagnostics.is_empty() {
+            let span = span!(level, "diagnostics", count = self.diagnostics.len());
+            span.in_scope(|| self.diagnostics.iter().trace_all(level));
+        }
+    }
+}
+
+impl Trace for Diagnostic {
+    fn trace(&self, level: Level) {
+        let span = span!(level, "sentinel", type_name = self.sentry().type_name());
+        span.in_scope(|| {
+            event!(level, "diagnostic",
+                missing = self.missing().map(|m| m.type_name()),
+                providers = %Formatter(|f| f.debug_list().entries(self.providers()).finish()),
+                suggestion = %self.suggestion(),
+            );
+
+            self.routes().iter().trace_all(level);
+        });
     }
 }
 
@@ -351,6 +393,10 @@ impl Trace for ErrorKind {
             ),
             Liftoff(_, reason) => event!(level, "panic", %reason, "liftoff fairing failed"),
             Shutdown(_) => event!(level, "shutdown", "shutdown failed"),
+            Aggregate(errors) => {
+                let span = span!(level, "errors", count = errors.len(), "multiple errors occurred");
+                span.in_scope(|| errors.iter().trace_all(level));
+            }
         }
     }
 }
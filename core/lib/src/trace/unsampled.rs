@@ -0,0 +1,73 @@
+use std::sync::OnceLock;
+
+use tracing::{Dispatch, Event, Level, Metadata, Subscriber};
+use tracing::span::{Attributes, Current, Id, Record};
+use tracing::subscriber::Interest;
+
+/// A subscriber for requests the access log doesn't sample: forwards only
+/// warnings and errors to the default subscriber.
+struct Unsampled(Dispatch);
+
+/// Returns the dispatcher requests the access log doesn't sample run with.
+pub(crate) fn dispatch() -> Dispatch {
+    static DISPATCH: OnceLock<Dispatch> = OnceLock::new();
+
+    DISPATCH.get_or_init(|| {
+        let inner = tracing::dispatcher::get_default(|dispatch| dispatch.clone());
+        Dispatch::new(Unsampled(inner))
+    }).clone()
+}
+
+impl Subscriber for Unsampled {
+    fn register_callsite(&self, metadata: &'static Metadata<'static>) -> Interest {
+        // The interest of other dispatchers in the callsite may differ.
+        match self.0.register_callsite(metadata).is_never() {
+            true => Interest::never(),
+            false => Interest::sometimes(),
+        }
+    }
+
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        *metadata.level() <= Level::WARN && self.0.enabled(metadata)
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        self.0.new_span(span)
+    }
+
+    fn record(&self, span: &Id, values: &Record<'_>) {
+        self.0.record(span, values)
+    }
+
+    fn record_follows_from(&self, span: &Id, follows: &Id) {
+        self.0.record_follows_from(span, follows)
+    }
+
+    fn event_enabled(&self, event: &Event<'_>) -> bool {
+        self.0.event_enabled(event)
+    }
+
+    fn event(&self, event: &Event<'_>) {
+        self.0.event(event)
+    }
+
+    fn enter(&self, span: &Id) {
+        self.0.enter(span)
+    }
+
+    fn exit(&self, span: &Id) {
+        self.0.exit(span)
+    }
+
+    fn clone_span(&self, id: &Id) -> Id {
+        self.0.clone_span(id)
+    }
+
+    fn try_close(&self, id: Id) -> bool {
+        self.0.try_close(id)
+    }
+
+    fn current_span(&self) -> Current {
+        self.0.current_span()
+    }
+}
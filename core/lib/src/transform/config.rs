@@ -0,0 +1,218 @@
+use indexmap::IndexMap;
+use serde::Deserialize;
+
+use crate::http::Method;
+use crate::http::uri::Origin;
+use crate::transform::Template;
+
+/// Changes applied by a [`TransformRule`] to a request or response.
+///
+/// Changes are applied in the following order: headers in `rename` are
+/// renamed, replacing any existing headers of the new name, headers in
+/// `remove` are removed, and headers in `set` are set to their rendered
+/// [template](crate::transform#templates), replacing existing values.
+/// Finally, if `body` is set, the body is replaced with the rendered template.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::transform::TransformActions;
+///
+/// let actions = TransformActions::new()
+///     .rename("X-Api-Key", "Authorization")
+///     .remove("Cookie")
+///     .set("X-Client", "{{ header.User-Agent | lower }}")
+///     .body(r#"{ "query": {{ body.q | json }} }"#);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct TransformActions {
+    /// Headers to rename, from the key to the value.
+    pub rename: IndexMap<String, String>,
+    /// Names of headers to remove.
+    pub remove: Vec<String>,
+    /// Headers to set to a template, replacing existing values.
+    pub set: IndexMap<String, String>,
+    /// A template for the body.
+    pub body: Option<String>,
+}
+
+/// Transformations for requests and responses whose path is under `path`.
+///
+/// A rule applies to a request if its method is `method`, when one is set,
+/// and its path is equal to `path` or, segment-wise, begins with `path`. As
+/// such, a rule for `/v1` applies to `/v1` and `/v1/users` but not to `/v10`.
+/// If `rewrite` is set, the `path` prefix of matching requests is replaced
+/// with `rewrite` before routing. See [`Transforms`](crate::transform::Transforms)
+/// for usage.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::http::Method;
+/// use rocket::transform::{TransformRule, TransformActions};
+///
+/// let rule = TransformRule::new("/v1/users")
+///     .method(Method::Post)
+///     .rewrite("/v2/accounts")
+///     .request(TransformActions::new().body(r#"{ "name": {{ body.username | json }} }"#))
+///     .response(TransformActions::new().set("Deprecation", "true"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct TransformRule {
+    /// The path prefix the rule applies to.
+    #[serde(default = "TransformRule::root")]
+    pub path: String,
+    /// The method the rule applies to, or any method if `None`.
+    #[serde(default)]
+    pub method: Option<Method>,
+    /// The path prefix that replaces `path` in matching requests.
+    #[serde(default)]
+    pub rewrite: Option<String>,
+    /// The changes applied to incoming requests.
+    #[serde(default)]
+    pub request: TransformActions,
+    /// The changes applied to outgoing responses.
+    #[serde(default)]
+    pub response: TransformActions,
+}
+
+/// The templates of [`TransformActions`], parsed.
+#[derive(Debug)]
+pub(crate) struct Templates {
+    pub(crate) set: Vec<(String, Template)>,
+    pub(crate) body: Option<Template>,
+}
+
+impl TransformActions {
+    /// Returns an empty set of changes.
+    pub fn new() -> Self {
+        TransformActions::default()
+    }
+
+    /// Renames headers named `from` to `to`.
+    pub fn rename<F: Into<String>, T: Into<String>>(mut self, from: F, to: T) -> Self {
+        self.rename.insert(from.into(), to.into());
+        self
+    }
+
+    /// Removes all headers named `name`.
+    pub fn remove<N: Into<String>>(mut self, name: N) -> Self {
+        self.remove.push(name.into());
+        self
+    }
+
+    /// Sets the header `name` to the rendered `template`.
+    pub fn set<N: Into<String>, T: Into<String>>(mut self, name: N, template: T) -> Self {
+        self.set.insert(name.into(), template.into());
+        self
+    }
+
+    /// Replaces the body with the rendered `template`.
+    pub fn body<T: Into<String>>(mut self, template: T) -> Self {
+        self.body = Some(template.into());
+        self
+    }
+
+    /// Parses the templates, returning a description of the first invalid
+    /// header name or template.
+    pub(crate) fn compile(&self) -> Result<Templates, String> {
+        let is_token = |s: &str| !s.is_empty() && s.bytes().all(|b| {
+            b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b)
+        });
+
+        let mut names = self.rename.keys()
+            .chain(self.rename.values())
+            .chain(&self.remove)
+            .chain(self.set.keys());
+
+        if let Some(name) = names.find(|name| !is_token(name.as_str())) {
+            return Err(format!("invalid header name {:?}", name));
+        }
+
+        let parse = |template: &str| Template::parse(template)
+            .map_err(|e| format!("invalid template {:?}: {}", template, e));
+
+        let set = self.set.iter()
+            .map(|(name, template)| Ok((name.clone(), parse(template)?)))
+            .collect::<Result<_, String>>()?;
+
+        let body = self.body.as_deref().map(parse).transpose()?;
+        Ok(Templates { set, body })
+    }
+}
+
+impl TransformRule {
+    fn root() -> String {
+        "/".into()
+    }
+
+    /// Returns a rule with no changes for requests under `path`.
+    pub fn new<P: Into<String>>(path: P) -> Self {
+        TransformRule {
+            path: path.into(),
+            method: None,
+            rewrite: None,
+            request: TransformActions::default(),
+            response: TransformActions::default(),
+        }
+    }
+
+    /// Applies the rule only to requests with method `method`.
+    pub fn method(mut self, method: Method) -> Self {
+        self.method = Some(method);
+        self
+    }
+
+    /// Replaces the `path` prefix of matching requests with `prefix`.
+    pub fn rewrite<P: Into<String>>(mut self, prefix: P) -> Self {
+        self.rewrite = Some(prefix.into());
+        self
+    }
+
+    /// Sets the changes applied to matching requests.
+    pub fn request(mut self, actions: TransformActions) -> Self {
+        self.request = actions;
+        self
+    }
+
+    /// Sets the changes applied to responses to matching requests.
+    pub fn response(mut self, actions: TransformActions) -> Self {
+        self.response = actions;
+        self
+    }
+
+    /// Returns a description of the rule's invalid `rewrite`, if it is.
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        let Some(rewrite) = self.rewrite.as_deref() else {
+            return Ok(());
+        };
+
+        match Origin::parse(rewrite) {
+            Ok(uri) if uri.query().is_none() => Ok(()),
+            Ok(_) => Err(format!("rewrite {:?} can't have a query", rewrite)),
+            Err(e) => Err(format!("invalid rewrite {:?}: {}", rewrite, e)),
+        }
+    }
+
+    /// Whether this rule applies to a `method` request for `path`.
+    pub(crate) fn matches(&self, method: Method, path: &str) -> bool {
+        self.method.map_or(true, |m| m == method) && self.rest(path).is_some()
+    }
+
+    /// Returns `path` rewritten by this rule, if it has a `rewrite`.
+    pub(crate) fn rewritten(&self, path: &str) -> Option<String> {
+        let prefix = self.rewrite.as_deref()?.trim_end_matches('/');
+        let rewritten = format!("{}{}", prefix, self.rest(path)?);
+        match rewritten.is_empty() {
+            true => Some("/".into()),
+            false => Some(rewritten),
+        }
+    }
+
+    /// The part of `path` after `self.path`, if `path` is under it.
+    fn rest<'a>(&self, path: &'a str) -> Option<&'a str> {
+        let prefix = self.path.trim_end_matches('/');
+        path.strip_prefix(prefix).filter(|rest| rest.is_empty() || rest.starts_with('/'))
+    }
+}
@@ -0,0 +1,91 @@
+//! Declarative request and response transformations for API gateways.
+//!
+//! The [`Transforms`] fairing rewrites requests before they are routed and
+//! responses before they are sent, as described by [`TransformRule`]s, so that
+//! an application can serve clients that expect a different API shape, such
+//! as a legacy version, without duplicating handlers. A rule can rename,
+//! remove, and set headers, rewrite the path, and replace the body:
+//!
+//! ```toml
+//! [[default.transforms]]
+//! path = "/v1/users"
+//! rewrite = "/v2/accounts"
+//! request.rename = { "X-Api-Key" = "Authorization" }
+//! request.body = '{ "name": {{ body.username | json }}, "via": "v1" }'
+//! response.set = { "X-Upstream-Status" = "{{ status }}" }
+//! ```
+//!
+//! # Templates
+//!
+//! Header values set by a rule and body replacements are _templates_: text
+//! with embedded `{{ expression }}`s, each of which is replaced by its value
+//! when the template is rendered. Templates are parsed at ignition and can't
+//! do anything but read the values below, so they are safe to load from
+//! configuration. An expression is a value followed by any number of filters,
+//! each preceded by `|`. The values are:
+//!
+//! | Value            | Description                                            |
+//! |------------------|--------------------------------------------------------|
+//! | `method`         | the request method, e.g., `GET`                        |
+//! | `path`           | the request path, e.g., `/v1/users`                    |
+//! | `status`         | the response status code; missing in requests          |
+//! | `header.NAME`    | the first value of the request header `NAME`           |
+//! | `query.NAME`     | the value of the request query parameter `NAME`        |
+//! | `segment.N`      | the `N`th path segment, from `0`, after the mount point |
+//! | `body`           | the body being transformed, parsed as JSON             |
+//! | `body.a.b[0]`    | a field or element of the JSON body                    |
+//! | `"text"`         | the string `text`, with `\"` and `\\` escapes          |
+//!
+//! Values that don't exist, such as absent headers, are _missing_. Missing
+//! values render as nothing; JSON strings render without quotes and other
+//! JSON values as JSON. The filters are:
+//!
+//! | Filter           | Description                                            |
+//! |------------------|--------------------------------------------------------|
+//! | `json`           | renders the value as JSON; missing values as `null`    |
+//! | `lower`, `upper` | converts the value to lowercase or uppercase           |
+//! | `trim`           | removes leading and trailing whitespace                |
+//! | `urlencode`      | percent-encodes all but unreserved characters          |
+//! | `default("x")`   | replaces a missing, `null`, or empty value with `x`    |
+//!
+//! For instance, `{{ header.X-User | lower | json }}` renders the lowercased
+//! value of the `X-User` header as a JSON string, or `null` if there's no such
+//! header.
+//!
+//! # Configuration
+//!
+//! Rules are configured via the `transforms` configuration parameter, which is
+//! deserialized as an array of [`TransformRule`]s, and via
+//! [`Transforms::rule()`].
+//!
+//! # Example
+//!
+//! ```rust
+//! # #[macro_use] extern crate rocket;
+//! use rocket::transform::{Transforms, TransformRule, TransformActions};
+//! use rocket::serde::json::{json, Value};
+//!
+//! #[post("/v2/accounts", data = "<account>")]
+//! fn create(account: Value) -> Value {
+//!     json!({ "created": account["name"] })
+//! }
+//!
+//! #[launch]
+//! fn rocket() -> _ {
+//!     let legacy = TransformRule::new("/v1/users")
+//!         .rewrite("/v2/accounts")
+//!         .request(TransformActions::new().body(r#"{ "name": {{ body.username | json }} }"#));
+//!
+//!     rocket::build()
+//!         .mount("/", routes![create])
+//!         .attach(Transforms::new().rule(legacy))
+//! }
+//! ```
+
+mod config;
+mod template;
+mod transform;
+
+pub use config::{TransformRule, TransformActions};
+pub use template::{Template, TemplateError};
+pub use transform::Transforms;
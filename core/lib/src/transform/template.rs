@@ -0,0 +1,456 @@
+use std::fmt;
+
+use serde_json::Value;
+
+use crate::Request;
+use crate::http::Status;
+
+/// A parsed template: text with embedded `{{ expression }}`s.
+///
+/// See the [module docs](crate::transform#templates) for the syntax.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::transform::Template;
+///
+/// assert!(Template::parse("Bearer {{ header.X-Token | trim }}").is_ok());
+/// assert!(Template::parse(r#"{ "id": {{ body.user.id | json }} }"#).is_ok());
+///
+/// assert!(Template::parse("{{ header.X-Token").is_err());
+/// assert!(Template::parse("{{ env.HOME }}").is_err());
+/// assert!(Template::parse("{{ path | shout }}").is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template {
+    parts: Vec<Part>,
+    source: String,
+}
+
+/// An error parsing a [`Template`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TemplateError {
+    /// The byte offset in the template at which the error occurred.
+    pub offset: usize,
+    /// A description of the error.
+    pub message: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Part {
+    Text(String),
+    Expr(Source, Vec<Filter>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Source {
+    Method,
+    Path,
+    Status,
+    Header(String),
+    Query(String),
+    Segment(usize),
+    Body(Vec<Key>),
+    Literal(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Key {
+    Field(String),
+    Index(usize),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Filter {
+    Json,
+    Lower,
+    Upper,
+    Trim,
+    UrlEncode,
+    Default(String),
+}
+
+/// The value of an expression as filters are applied.
+enum Val {
+    Missing,
+    Str(String),
+    Json(Value),
+}
+
+/// What a template is rendered against.
+pub(crate) struct Context<'a, 'r> {
+    pub(crate) req: &'a Request<'r>,
+    pub(crate) status: Option<Status>,
+    pub(crate) body: Option<&'a Value>,
+}
+
+/// A cursor over a template's expression.
+struct Parser<'a> {
+    input: &'a str,
+    offset: usize,
+}
+
+impl Template {
+    /// Parses `string` as a template.
+    pub fn parse(string: &str) -> Result<Template, TemplateError> {
+        let mut parts = vec![];
+        let mut rest = string;
+        while let Some(start) = rest.find("{{") {
+            if start > 0 {
+                parts.push(Part::Text(rest[..start].into()));
+            }
+
+            let offset = string.len() - rest.len() + start + 2;
+            let Some(end) = find_close(&rest[start + 2..]) else {
+                return Err(TemplateError { offset, message: "unclosed `{{`".into() });
+            };
+
+            let mut parser = Parser { input: &rest[start + 2..start + 2 + end], offset };
+            parts.push(parser.expr()?);
+            rest = &rest[start + 2 + end + 2..];
+        }
+
+        if !rest.is_empty() {
+            parts.push(Part::Text(rest.into()));
+        }
+
+        Ok(Template { parts, source: string.into() })
+    }
+
+    /// Returns the template's source.
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// Returns `true` if the template refers to `body`.
+    pub(crate) fn uses_body(&self) -> bool {
+        self.parts.iter().any(|p| matches!(p, Part::Expr(Source::Body(_), _)))
+    }
+
+    pub(crate) fn render(&self, cx: &Context<'_, '_>) -> String {
+        let mut output = String::new();
+        for part in &self.parts {
+            match part {
+                Part::Text(text) => output.push_str(text),
+                Part::Expr(source, filters) => {
+                    let value = filters.iter().fold(source.eval(cx), |v, f| f.apply(v));
+                    match value {
+                        Val::Missing => {},
+                        Val::Str(s) | Val::Json(Value::String(s)) => output.push_str(&s),
+                        Val::Json(v) => output.push_str(&v.to_string()),
+                    }
+                }
+            }
+        }
+
+        output
+    }
+}
+
+impl<'a> Parser<'a> {
+    fn expr(&mut self) -> Result<Part, TemplateError> {
+        let source = self.source()?;
+        let mut filters = vec![];
+        while self.eat('|') {
+            filters.push(self.filter()?);
+        }
+
+        self.skip_whitespace();
+        match self.input.is_empty() {
+            true => Ok(Part::Expr(source, filters)),
+            false => Err(self.error("unexpected input after expression")),
+        }
+    }
+
+    fn source(&mut self) -> Result<Source, TemplateError> {
+        self.skip_whitespace();
+        if self.input.starts_with('"') {
+            return self.string().map(Source::Literal);
+        }
+
+        let offset = self.offset;
+        let word = self.word();
+        let source = match word.split_once('.') {
+            None if word == "method" => Source::Method,
+            None if word == "path" => Source::Path,
+            None if word == "status" => Source::Status,
+            None if word == "body" => Source::Body(vec![]),
+            Some(("header", name)) if !name.is_empty() => Source::Header(name.into()),
+            Some(("query", name)) if !name.is_empty() => Source::Query(name.into()),
+            Some(("segment", n)) => match n.parse() {
+                Ok(n) => Source::Segment(n),
+                Err(_) => return Err(self.error_at(offset, "expected a segment index")),
+            },
+            Some(("body", path)) => Source::Body(parse_keys(path).ok_or_else(|| {
+                self.error_at(offset, "invalid body path")
+            })?),
+            _ if word.is_empty() => return Err(self.error_at(offset, "expected an expression")),
+            _ => return Err(self.error_at(offset, format!("unknown value `{word}`"))),
+        };
+
+        Ok(source)
+    }
+
+    fn filter(&mut self) -> Result<Filter, TemplateError> {
+        self.skip_whitespace();
+        let offset = self.offset;
+        let filter = match self.word() {
+            "json" => Filter::Json,
+            "lower" => Filter::Lower,
+            "upper" => Filter::Upper,
+            "trim" => Filter::Trim,
+            "urlencode" => Filter::UrlEncode,
+            "default" => {
+                if !self.eat('(') {
+                    return Err(self.error("expected `(`"));
+                }
+
+                self.skip_whitespace();
+                let value = self.string()?;
+                if !self.eat(')') {
+                    return Err(self.error("expected `)`"));
+                }
+
+                Filter::Default(value)
+            }
+            "" => return Err(self.error_at(offset, "expected a filter")),
+            word => return Err(self.error_at(offset, format!("unknown filter `{word}`"))),
+        };
+
+        Ok(filter)
+    }
+
+    /// Parses a double-quoted string with `\"` and `\\` escapes.
+    fn string(&mut self) -> Result<String, TemplateError> {
+        if !self.input.starts_with('"') {
+            return Err(self.error("expected a string"));
+        }
+
+        let mut string = String::new();
+        let mut chars = self.input.char_indices().skip(1);
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.advance(i + 1);
+                    return Ok(string);
+                }
+                '\\' => match chars.next() {
+                    Some((_, c @ ('"' | '\\'))) => string.push(c),
+                    _ => return Err(self.error("invalid escape in string")),
+                },
+                c => string.push(c),
+            }
+        }
+
+        Err(self.error("unterminated string"))
+    }
+
+    /// Consumes and returns the longest prefix without whitespace or syntax.
+    fn word(&mut self) -> &'a str {
+        let input = self.input;
+        let len = input.find(|c: char| c.is_whitespace() || "|()\"".contains(c))
+            .unwrap_or(input.len());
+
+        self.advance(len);
+        &input[..len]
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        self.skip_whitespace();
+        let matched = self.input.starts_with(c);
+        if matched {
+            self.advance(c.len_utf8());
+        }
+
+        matched
+    }
+
+    fn skip_whitespace(&mut self) {
+        let trimmed = self.input.trim_start();
+        self.advance(self.input.len() - trimmed.len());
+    }
+
+    fn advance(&mut self, n: usize) {
+        self.input = &self.input[n..];
+        self.offset += n;
+    }
+
+    fn error<M: Into<String>>(&self, message: M) -> TemplateError {
+        self.error_at(self.offset, message)
+    }
+
+    fn error_at<M: Into<String>>(&self, offset: usize, message: M) -> TemplateError {
+        TemplateError { offset, message: message.into() }
+    }
+}
+
+impl Source {
+    fn eval(&self, cx: &Context<'_, '_>) -> Val {
+        let string = |s: Option<&str>| s.map_or(Val::Missing, |s| Val::Str(s.into()));
+        match self {
+            Source::Method => Val::Str(cx.req.method().as_str().into()),
+            Source::Path => Val::Str(cx.req.uri().path().as_str().into()),
+            Source::Status => cx.status.map_or(Val::Missing, |s| Val::Str(s.code.to_string())),
+            Source::Header(name) => string(cx.req.headers().get_one(name)),
+            Source::Query(name) => string(cx.req.query_value::<&str>(name).and_then(Result::ok)),
+            Source::Segment(n) => string(cx.req.routed_segment(*n)),
+            Source::Literal(s) => Val::Str(s.clone()),
+            Source::Body(keys) => {
+                let value = cx.body.and_then(|body| keys.iter().try_fold(body, |v, key| match key {
+                    Key::Field(name) => v.get(name),
+                    Key::Index(i) => v.get(i),
+                }));
+
+                value.map_or(Val::Missing, |v| Val::Json(v.clone()))
+            }
+        }
+    }
+}
+
+impl Filter {
+    fn apply(&self, value: Val) -> Val {
+        let map = |value: Val, f: fn(&str) -> String| match value {
+            Val::Missing => Val::Missing,
+            Val::Str(s) | Val::Json(Value::String(s)) => Val::Str(f(&s)),
+            Val::Json(v) => Val::Str(f(&v.to_string())),
+        };
+
+        match self {
+            Filter::Json => Val::Str(match value {
+                Val::Missing => "null".into(),
+                Val::Str(s) => Value::String(s).to_string(),
+                Val::Json(v) => v.to_string(),
+            }),
+            Filter::Lower => map(value, str::to_lowercase),
+            Filter::Upper => map(value, str::to_uppercase),
+            Filter::Trim => map(value, |s| s.trim().into()),
+            Filter::UrlEncode => map(value, percent_encode),
+            Filter::Default(default) => match value {
+                Val::Missing | Val::Json(Value::Null) => Val::Str(default.clone()),
+                Val::Str(s) | Val::Json(Value::String(s)) if s.is_empty() => {
+                    Val::Str(default.clone())
+                }
+                value => value,
+            },
+        }
+    }
+}
+
+impl fmt::Display for Template {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.source.fmt(f)
+    }
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at offset {}", self.message, self.offset)
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+/// Returns the index of the `}}` closing an expression, ignoring any in
+/// string literals.
+fn find_close(input: &str) -> Option<usize> {
+    let (mut in_string, mut escaped) = (false, false);
+    for (i, c) in input.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            '}' if !in_string && input[i..].starts_with("}}") => return Some(i),
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// Parses a body path like `user.emails[0]`.
+fn parse_keys(path: &str) -> Option<Vec<Key>> {
+    let mut keys = vec![];
+    for field in path.split('.') {
+        let (name, mut indices) = match field.find('[') {
+            Some(i) => (&field[..i], &field[i..]),
+            None => (field, ""),
+        };
+
+        if !name.is_empty() {
+            keys.push(Key::Field(name.into()));
+        } else if keys.is_empty() || indices.is_empty() {
+            return None;
+        }
+
+        while let Some(rest) = indices.strip_prefix('[') {
+            let (index, rest) = rest.split_once(']')?;
+            keys.push(Key::Index(index.parse().ok()?));
+            indices = rest;
+        }
+
+        if !indices.is_empty() {
+            return None;
+        }
+    }
+
+    Some(keys)
+}
+
+/// Percent-encodes all but unreserved characters in `string`.
+fn percent_encode(string: &str) -> String {
+    string.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_errors_have_offsets() {
+        let error = Template::parse("ab {{ nope }}").unwrap_err();
+        assert_eq!(error.offset, 6);
+        assert!(error.message.contains("nope"));
+
+        let error = Template::parse("{{ path | default(\"x) }}").unwrap_err();
+        assert_eq!(error.message, "unclosed `{{`");
+
+        assert!(Template::parse("{{ }}").is_err());
+        assert!(Template::parse("{{ path path }}").is_err());
+        assert!(Template::parse("{{ segment.x }}").is_err());
+        assert!(Template::parse("{{ body.a[x] }}").is_err());
+        assert!(Template::parse("{{ body.[0] }}").is_err());
+        assert!(Template::parse("{{ path | default(x) }}").is_err());
+    }
+
+    #[test]
+    fn body_paths_parse() {
+        let keys = parse_keys("user.emails[0][1].address").unwrap();
+        assert_eq!(keys, vec![
+            Key::Field("user".into()),
+            Key::Field("emails".into()),
+            Key::Index(0),
+            Key::Index(1),
+            Key::Field("address".into()),
+        ]);
+
+        assert!(parse_keys("a..b").is_none());
+        assert!(parse_keys("a[0]x").is_none());
+    }
+
+    #[test]
+    fn strings_may_contain_braces() {
+        let template = Template::parse(r#"{{ "}}" }}-{{ "\"" }}"#).unwrap();
+        assert_eq!(template.parts, vec![
+            Part::Expr(Source::Literal("}}".into()), vec![]),
+            Part::Text("-".into()),
+            Part::Expr(Source::Literal("\"".into()), vec![]),
+        ]);
+    }
+}
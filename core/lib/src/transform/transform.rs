@@ -0,0 +1,306 @@
+use std::io::Cursor;
+
+use serde_json::Value;
+use state::InitCell;
+
+use crate::{Rocket, Request, Response, Data, Build};
+use crate::data::Limits;
+use crate::fairing::{self, Fairing, Info, Kind};
+use crate::http::Header;
+use crate::http::uri::Origin;
+use crate::trace::Trace;
+use crate::transform::TransformRule;
+use crate::transform::config::Templates;
+use crate::transform::template::Context;
+
+/// A [`Fairing`] that applies [`TransformRule`]s to requests and responses.
+///
+/// Rules can be configured programmatically and via the `transforms`
+/// configuration parameter, an array of rules. Rules from configuration are
+/// applied after programmatically added rules, and rules are otherwise applied
+/// in the order they were added, each observing the changes of the ones before
+/// it. Which rules apply to a response is decided by the request as it was
+/// received, before any path was rewritten.
+///
+/// Request changes are applied before routing, so they are observed by
+/// request guards and handlers, and by request fairings attached after
+/// `Transforms`. If several matching rules rewrite the path, the last one
+/// wins. Request bodies are only buffered when a matching rule refers to
+/// them. A body larger than the `json` [limit](crate::data::Limits) isn't
+/// transformed, nor available to templates, and a warning is logged. Likewise,
+/// rendered header values that aren't valid header values are dropped with a
+/// warning. Response body templates only apply to responses with a body.
+///
+/// If the `transforms` configuration parameter is invalid, or any header name,
+/// template, or rewrite is invalid, ignition fails.
+///
+/// # Example
+///
+/// Serve a legacy API's paths and payloads from a new implementation via
+/// `Rocket.toml`:
+///
+/// ```toml
+/// [[default.transforms]]
+/// path = "/v1/users"
+/// method = "POST"
+/// rewrite = "/v2/accounts"
+/// request.rename = { "X-Api-Key" = "Authorization" }
+/// request.body = '{ "name": {{ body.username | json }} }'
+/// response.set = { "Deprecation" = "true" }
+/// ```
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::transform::{Transforms, TransformRule, TransformActions};
+///
+/// #[launch]
+/// fn rocket() -> _ {
+///     // Rules in `Rocket.toml` are applied after this one.
+///     let rule = TransformRule::new("/api")
+///         .request(TransformActions::new().set("X-Forwarded-Path", "{{ path }}"));
+///
+///     rocket::build().attach(Transforms::new().rule(rule))
+/// }
+/// ```
+#[derive(Default)]
+pub struct Transforms {
+    rules: Vec<TransformRule>,
+    compiled: InitCell<Vec<Compiled>>,
+}
+
+/// A rule with its templates parsed.
+struct Compiled {
+    rule: TransformRule,
+    request: Templates,
+    response: Templates,
+}
+
+/// The indices of the rules that matched a request.
+struct Matched(Vec<usize>);
+
+impl Transforms {
+    /// Returns a `Transforms` fairing with no programmatic rules. Rules from
+    /// the `transforms` configuration parameter are applied nonetheless.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::transform::Transforms;
+    ///
+    /// let transforms = Transforms::new();
+    /// ```
+    pub fn new() -> Self {
+        Transforms::default()
+    }
+
+    /// Adds `rule`, applied after all previously added rules.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::transform::{Transforms, TransformRule, TransformActions};
+    ///
+    /// let transforms = Transforms::new()
+    ///     .rule(TransformRule::new("/old").rewrite("/new"))
+    ///     .rule(TransformRule::new("/")
+    ///         .response(TransformActions::new().rename("X-Trace", "X-Request-Id")));
+    /// ```
+    pub fn rule(mut self, rule: TransformRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    fn compiled(&self) -> &[Compiled] {
+        self.compiled.try_get().map(|v| v.as_slice()).unwrap_or_default()
+    }
+
+    /// Buffers the body of `req` if it is within the `json` limit.
+    async fn buffer(req: &Request<'_>, data: &mut Data<'_>) -> Option<Vec<u8>> {
+        let limit = req.limits().get("json").unwrap_or(Limits::JSON).as_u64();
+        let limit = usize::try_from(limit).unwrap_or(usize::MAX);
+        let body = data.buffer(limit.saturating_add(1)).await.to_vec();
+        if !data.peek_complete() || body.len() > limit {
+            warn!(name: "transforms", uri = %req.uri(), "request body exceeds limit");
+            return None;
+        }
+
+        Some(body)
+    }
+}
+
+impl Templates {
+    fn uses_body(&self) -> bool {
+        self.body.is_some() || self.set.iter().any(|(_, t)| t.uses_body())
+    }
+
+    /// Renders the header templates, dropping invalid values.
+    fn headers(&self, cx: &Context<'_, '_>) -> Vec<Header<'static>> {
+        let is_value = |s: &str| s.bytes().all(|b| b == b'\t' || !b.is_ascii_control());
+        self.set.iter()
+            .map(|(name, template)| (name, template.render(cx)))
+            .filter(|(name, value)| {
+                let valid = is_value(value);
+                if !valid {
+                    warn!(name: "transforms", header = %name, "rendered invalid header value");
+                }
+
+                valid
+            })
+            .map(|(name, value)| Header::new(name.clone(), value))
+            .collect()
+    }
+}
+
+#[crate::async_trait]
+impl Fairing for Transforms {
+    fn info(&self) -> Info {
+        Info {
+            name: "Transforms",
+            kind: Kind::Ignite | Kind::Request | Kind::Response | Kind::Singleton,
+        }
+    }
+
+    async fn on_ignite(&self, rocket: Rocket<Build>) -> fairing::Result {
+        let configured = match rocket.figment().contains("transforms") {
+            true => match rocket.figment().extract_inner::<Vec<TransformRule>>("transforms") {
+                Ok(rules) => rules,
+                Err(e) => {
+                    e.trace_error();
+                    return Err(rocket);
+                }
+            },
+            false => vec![],
+        };
+
+        let mut compiled = vec![];
+        for rule in self.rules.iter().cloned().chain(configured) {
+            let templates = rule.validate()
+                .and_then(|_| Ok((rule.request.compile()?, rule.response.compile()?)));
+
+            match templates {
+                Ok((request, response)) => compiled.push(Compiled { rule, request, response }),
+                Err(e) => {
+                    error!(name: "transforms", path = %rule.path, "transform rule is invalid: {e}");
+                    return Err(rocket);
+                }
+            }
+        }
+
+        self.compiled.set(compiled);
+        Ok(rocket)
+    }
+
+    async fn on_request(&self, req: &mut Request<'_>, data: &mut Data<'_>) {
+        let path = req.uri().path().as_str().to_owned();
+        let matched: Vec<usize> = self.compiled().iter()
+            .enumerate()
+            .filter(|(_, c)| c.rule.matches(req.method(), &path))
+            .map(|(i, _)| i)
+            .collect();
+
+        let rules: Vec<&Compiled> = matched.iter().map(|&i| &self.compiled()[i]).collect();
+        req.local_cache(|| Matched(matched));
+
+        let mut body = match rules.iter().any(|c| c.request.uses_body()) {
+            true => Self::buffer(req, data).await,
+            false => None,
+        };
+
+        let mut body_changed = false;
+        for compiled in &rules {
+            let actions = &compiled.rule.request;
+            for (from, to) in &actions.rename {
+                let values: Vec<String> = req.headers().get(from).map(String::from).collect();
+                if !values.is_empty() {
+                    req.remove_header(from);
+                    req.remove_header(to);
+                    values.into_iter().for_each(|v| req.add_header(Header::new(to.clone(), v)));
+                }
+            }
+
+            actions.remove.iter().for_each(|name| req.remove_header(name));
+
+            let value = body.as_deref().and_then(|b| serde_json::from_slice::<Value>(b).ok());
+            let cx = Context { req: &*req, status: None, body: value.as_ref() };
+            let headers = compiled.request.headers(&cx);
+            let rendered = compiled.request.body.as_ref()
+                .filter(|_| body.is_some())
+                .map(|template| template.render(&cx));
+
+            headers.into_iter().for_each(|header| req.replace_header(header));
+            if let Some(rendered) = rendered {
+                body = Some(rendered.into_bytes());
+                body_changed = true;
+            }
+        }
+
+        if let Some(body) = body.filter(|_| body_changed) {
+            req.remove_header("Transfer-Encoding");
+            req.replace_header(Header::new("Content-Length", body.len().to_string()));
+            *data = Data::local(body);
+        }
+
+        let rewritten = rules.iter().rev().find_map(|c| c.rule.rewritten(&path));
+        if let Some(rewritten) = rewritten {
+            let uri = match req.uri().query() {
+                Some(query) => format!("{}?{}", rewritten, query),
+                None => rewritten,
+            };
+
+            match Origin::parse_owned(uri) {
+                Ok(uri) => req.set_uri(uri),
+                Err(e) => warn!(name: "transforms", %path, error = %e, "invalid rewritten path"),
+            }
+        }
+    }
+
+    async fn on_response<'r>(&self, req: &'r Request<'_>, res: &mut Response<'r>) {
+        let Matched(matched) = req.local_cache(|| Matched(vec![]));
+        let rules: Vec<&Compiled> = matched.iter().map(|&i| &self.compiled()[i]).collect();
+        if rules.is_empty() {
+            return;
+        }
+
+        let has_body = !res.body().is_none();
+        let mut body = None;
+        if has_body && rules.iter().any(|c| c.response.uses_body()) {
+            match res.body_mut().to_bytes().await {
+                Ok(bytes) => body = Some(bytes),
+                Err(e) => {
+                    warn!(name: "transforms", error = %e, "failed to read response body");
+                    return;
+                }
+            }
+        }
+
+        for compiled in &rules {
+            let actions = &compiled.rule.response;
+            for (from, to) in &actions.rename {
+                let values: Vec<String> = res.headers().get(from).map(String::from).collect();
+                if !values.is_empty() {
+                    res.remove_header(from);
+                    res.remove_header(to);
+                    values.into_iter().for_each(|v| res.adjoin_header(Header::new(to.clone(), v)));
+                }
+            }
+
+            actions.remove.iter().for_each(|name| res.remove_header(name));
+
+            let value = body.as_deref().and_then(|b| serde_json::from_slice::<Value>(b).ok());
+            let cx = Context { req, status: Some(res.status()), body: value.as_ref() };
+            let headers = compiled.response.headers(&cx);
+            let rendered = compiled.response.body.as_ref()
+                .filter(|_| body.is_some())
+                .map(|template| template.render(&cx));
+
+            headers.into_iter().for_each(|header| { res.set_header(header); });
+            if let Some(rendered) = rendered {
+                body = Some(rendered.into_bytes());
+            }
+        }
+
+        if let Some(body) = body {
+            res.set_sized_body(body.len(), Cursor::new(body));
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// The tunnel configuration: the `tunnel` configuration parameter.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::tunnel::TunnelConfig;
+/// use rocket::figment::{Figment, providers::{Format, Toml}};
+///
+/// let figment = Figment::from(Toml::string(r#"
+///     [tunnel]
+///     allow = ["db.internal:5432", "*.svc.internal:443"]
+/// "#));
+///
+/// let config: TunnelConfig = figment.extract_inner("tunnel").unwrap();
+/// assert_eq!(config.connect_timeout_secs, 10);
+/// assert!(config.allows("db.internal", 5432));
+/// assert!(config.allows("api.svc.internal", 443));
+/// assert!(!config.allows("svc.internal", 443));
+/// assert!(!config.allows("db.internal", 22));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TunnelConfig {
+    /// The targets that may be tunneled to, as `host:port`. The host may be
+    /// `*`, any host, or begin with `*.`, any subdomain of the rest. The port
+    /// may be `*`, any port. Hosts are compared case-insensitively.
+    ///
+    /// **default: `[]`**
+    pub allow: Vec<String>,
+    /// The time, in seconds, to wait for a connection to the target.
+    ///
+    /// **default: `10`**
+    pub connect_timeout_secs: u64,
+}
+
+impl Default for TunnelConfig {
+    fn default() -> Self {
+        TunnelConfig { allow: vec![], connect_timeout_secs: 10 }
+    }
+}
+
+impl TunnelConfig {
+    /// Returns the time to wait for a connection to the target.
+    pub fn connect_timeout(&self) -> Duration {
+        Duration::from_secs(self.connect_timeout_secs)
+    }
+
+    /// Returns `true` if tunneling to `host` on `port` is allowed.
+    pub fn allows(&self, host: &str, port: u16) -> bool {
+        self.allow.iter().any(|allowed| {
+            let Some((allowed_host, allowed_port)) = allowed.rsplit_once(':') else {
+                return false;
+            };
+
+            let port_matches = allowed_port == "*" || allowed_port.parse() == Ok(port);
+            let host_matches = match allowed_host.strip_prefix("*.") {
+                _ if allowed_host == "*" => true,
+                Some(domain) => host.len() > domain.len() + 1
+                    && host.as_bytes()[host.len() - domain.len() - 1] == b'.'
+                    && host[host.len() - domain.len()..].eq_ignore_ascii_case(domain),
+                None => host.eq_ignore_ascii_case(allowed_host),
+            };
+
+            port_matches && host_matches
+        })
+    }
+}
@@ -0,0 +1,54 @@
+//! A constrained forward proxy: `CONNECT` tunneling.
+//!
+//! The [`Tunnel`] fairing lets clients open TCP tunnels through the
+//! application via `CONNECT` requests, as in `curl -p -x app.internal:8000
+//! http://db.internal:5432`, so that internal tooling can reach a fixed set of
+//! services through a single entry point. Only targets on the allowlist can be
+//! reached; by default, none can.
+//!
+//! For each `CONNECT` request, the tunnel:
+//!
+//!   * responds `400 Bad Request` if the target has no port,
+//!   * responds `403 Forbidden` if the target isn't allowed,
+//!   * responds `502 Bad Gateway` or `504 Gateway Timeout` if the target can't
+//!     be reached within `connect_timeout_secs`, and otherwise
+//!   * responds `200 OK` and copies bytes between the client and the target
+//!     until either closes the connection or Rocket shuts down.
+//!
+//! Tunnels are built on [`Upgrade`](crate::response::Upgrade); routes
+//! handling `CONNECT` requests can be written the same way, with the target
+//! available via [`Request::connect_target()`](crate::Request::connect_target).
+//!
+//! The bytes sent and received through tunnels, as well as the number of
+//! tunnels opened and active, are available via the `Tunnel` fairing. Opening
+//! and closing a tunnel is logged at the `INFO` level, the latter with the
+//! number of bytes it carried.
+//!
+//! # Configuration
+//!
+//! Targets are allowed via the `tunnel` configuration parameter, which is
+//! deserialized as a [`TunnelConfig`]:
+//!
+//! ```toml
+//! [default.tunnel]
+//! allow = ["db.internal:5432", "*.svc.internal:443", "10.0.0.7:*"]
+//! connect_timeout_secs = 5
+//! ```
+//!
+//! # Example
+//!
+//! ```rust
+//! # #[macro_use] extern crate rocket;
+//! use rocket::tunnel::Tunnel;
+//!
+//! #[launch]
+//! fn rocket() -> _ {
+//!     rocket::build().attach(Tunnel::new().allow("db.internal:5432"))
+//! }
+//! ```
+
+mod config;
+mod tunnel;
+
+pub use config::TunnelConfig;
+pub use tunnel::Tunnel;
@@ -0,0 +1,237 @@
+use std::io;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+use either::Either;
+use state::InitCell;
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio::net::TcpStream;
+
+use crate::{Rocket, Request, Data, Build, Route};
+use crate::fairing::{self, Fairing, Info, Kind};
+use crate::http::{Method, Status};
+use crate::response::Upgrade;
+use crate::route::{Handler, Outcome};
+use crate::trace::Trace;
+use crate::tunnel::TunnelConfig;
+use crate::util::FutureExt;
+
+/// A [`Fairing`] that tunnels [`CONNECT` requests](crate::tunnel) to allowed
+/// targets.
+///
+/// Allowed targets are read from the `tunnel` configuration parameter at
+/// ignition; targets allowed via [`Tunnel::allow()`] are added to them. If the
+/// parameter is invalid, ignition fails. The fairing mounts a route at `/`
+/// handling `CONNECT` requests.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::tunnel::Tunnel;
+/// use rocket::local::blocking::Client;
+///
+/// let tunnel = Tunnel::new().allow("db.internal:5432");
+/// let client = Client::debug(rocket::build().attach(tunnel)).unwrap();
+///
+/// let tunnel = client.rocket().fairing::<Tunnel>().unwrap();
+/// assert_eq!(tunnel.opened(), 0);
+/// assert_eq!(tunnel.bytes_sent(), 0);
+/// ```
+#[derive(Clone)]
+pub struct Tunnel {
+    allow: Vec<String>,
+    inner: Arc<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    config: InitCell<TunnelConfig>,
+    opened: AtomicU64,
+    active: AtomicUsize,
+    sent: AtomicU64,
+    received: AtomicU64,
+}
+
+/// A stream to a tunnel's target that accounts for the bytes through it.
+struct Metered {
+    stream: TcpStream,
+    inner: Arc<Inner>,
+    sent: u64,
+    received: u64,
+}
+
+impl Tunnel {
+    /// Returns a `Tunnel` fairing.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::tunnel::Tunnel;
+    ///
+    /// let tunnel = Tunnel::new();
+    /// ```
+    pub fn new() -> Self {
+        Tunnel { allow: vec![], inner: Arc::default() }
+    }
+
+    /// Allows tunneling to `target`, of the form `host:port`. See
+    /// [`TunnelConfig::allow`] for the supported wildcards.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::tunnel::Tunnel;
+    ///
+    /// let tunnel = Tunnel::new()
+    ///     .allow("db.internal:5432")
+    ///     .allow("*.svc.internal:443");
+    /// ```
+    pub fn allow<T: Into<String>>(mut self, target: T) -> Self {
+        self.allow.push(target.into());
+        self
+    }
+
+    /// Returns the number of tunnels opened so far.
+    pub fn opened(&self) -> u64 {
+        self.inner.opened.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of tunnels currently open.
+    pub fn active(&self) -> usize {
+        self.inner.active.load(Ordering::Acquire)
+    }
+
+    /// Returns the number of bytes sent from clients to targets so far.
+    pub fn bytes_sent(&self) -> u64 {
+        self.inner.sent.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of bytes received by clients from targets so far.
+    pub fn bytes_received(&self) -> u64 {
+        self.inner.received.load(Ordering::Relaxed)
+    }
+}
+
+impl Default for Tunnel {
+    fn default() -> Self {
+        Tunnel::new()
+    }
+}
+
+#[crate::async_trait]
+impl Fairing for Tunnel {
+    fn info(&self) -> Info {
+        Info { name: "Tunnel", kind: Kind::Ignite | Kind::Singleton }
+    }
+
+    async fn on_ignite(&self, rocket: Rocket<Build>) -> fairing::Result {
+        let mut config = match rocket.figment().contains("tunnel") {
+            true => match rocket.figment().extract_inner::<TunnelConfig>("tunnel") {
+                Ok(config) => config,
+                Err(e) => {
+                    e.trace_error();
+                    return Err(rocket);
+                }
+            },
+            false => TunnelConfig::default(),
+        };
+
+        config.allow.extend(self.allow.iter().cloned());
+        self.inner.config.set(config);
+        Ok(rocket.mount("/", vec![Route::new(Method::Connect, "/", self.clone())]))
+    }
+}
+
+#[crate::async_trait]
+impl Handler for Tunnel {
+    async fn handle<'r>(&self, req: &'r Request<'_>, _: Data<'r>) -> Outcome<'r> {
+        let Some(config) = self.inner.config.try_get() else {
+            return Outcome::Error(Status::InternalServerError);
+        };
+
+        let Some((host, port)) = req.connect_target().and_then(|t| Some((t.host(), t.port()?)))
+        else {
+            return Outcome::Error(Status::BadRequest);
+        };
+
+        if !config.allows(host, port) {
+            warn!(name: "tunnel", host, port, "tunnel to disallowed target refused");
+            return Outcome::Error(Status::Forbidden);
+        }
+
+        let connect = TcpStream::connect((host, port));
+        let stream = match tokio::time::timeout(config.connect_timeout(), connect).await {
+            Ok(Ok(stream)) => stream,
+            Ok(Err(e)) => {
+                warn!(name: "tunnel", host, port, error = %e, "tunnel target unreachable");
+                return Outcome::Error(Status::BadGateway);
+            }
+            Err(_) => {
+                warn!(name: "tunnel", host, port, "tunnel target connection timed out");
+                return Outcome::Error(Status::GatewayTimeout);
+            }
+        };
+
+        let target = format!("{host}:{port}");
+        let inner = self.inner.clone();
+        Outcome::from(req, Upgrade::new("connect", move |mut io, shutdown| Box::pin(async move {
+            inner.opened.fetch_add(1, Ordering::Relaxed);
+            inner.active.fetch_add(1, Ordering::AcqRel);
+            info!(name: "tunnel", %target, "tunnel opened");
+
+            let mut stream = Metered { stream, inner: inner.clone(), sent: 0, received: 0 };
+            let result = tokio::io::copy_bidirectional(&mut io, &mut stream).race(shutdown).await;
+            inner.active.fetch_sub(1, Ordering::AcqRel);
+            info!(name: "tunnel", %target, sent = stream.sent, received = stream.received,
+                "tunnel closed");
+
+            match result {
+                Either::Left(result) => result.map(|_| ()),
+                Either::Right(_) => Ok(()),
+            }
+        })))
+    }
+}
+
+impl AsyncRead for Metered {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        let filled = buf.filled().len();
+        let result = Pin::new(&mut this.stream).poll_read(cx, buf);
+        let n = (buf.filled().len() - filled) as u64;
+        this.received += n;
+        this.inner.received.fetch_add(n, Ordering::Relaxed);
+        result
+    }
+}
+
+impl AsyncWrite for Metered {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        let result = Pin::new(&mut this.stream).poll_write(cx, buf);
+        if let Poll::Ready(Ok(n)) = result {
+            this.sent += n as u64;
+            this.inner.sent.fetch_add(n as u64, Ordering::Relaxed);
+        }
+
+        result
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().stream).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().stream).poll_shutdown(cx)
+    }
+}
@@ -0,0 +1,55 @@
+use std::fmt::{self, Write};
+
+/// Displays a string with HTML special characters escaped.
+pub struct EscapeHtml<'a>(pub &'a str);
+
+/// Displays a string escaped for use inside a JSON string literal.
+pub struct EscapeJson<'a>(pub &'a str);
+
+impl fmt::Display for EscapeHtml<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for c in self.0.chars() {
+            match c {
+                '&' => f.write_str("&amp;")?,
+                '<' => f.write_str("&lt;")?,
+                '>' => f.write_str("&gt;")?,
+                '"' => f.write_str("&quot;")?,
+                '\'' => f.write_str("&#x27;")?,
+                c => f.write_char(c)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for EscapeJson<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for c in self.0.chars() {
+            match c {
+                '"' => f.write_str("\\\"")?,
+                '\\' => f.write_str("\\\\")?,
+                '\n' => f.write_str("\\n")?,
+                '\r' => f.write_str("\\r")?,
+                '\t' => f.write_str("\\t")?,
+                c if c.is_control() => write!(f, "\\u{:04x}", c as u32)?,
+                c => f.write_char(c)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes() {
+        assert_eq!(EscapeHtml("<a href=\"x\">&'</a>").to_string(),
+            "&lt;a href=&quot;x&quot;&gt;&amp;&#x27;&lt;/a&gt;");
+
+        assert_eq!(EscapeJson("a\"b\\c\nd\u{1}").to_string(), "a\\\"b\\\\c\\nd\\u0001");
+    }
+}
@@ -1,6 +1,7 @@
 mod chain;
 mod reader_stream;
 mod join;
+mod escape;
 
 #[cfg(unix)]
 pub mod unix;
@@ -8,6 +9,7 @@ pub mod unix;
 pub use chain::Chain;
 pub use reader_stream::ReaderStream;
 pub use join::join;
+pub use escape::{EscapeHtml, EscapeJson};
 
 #[track_caller]
 pub fn spawn_inspect<E, F, Fut>(or: F, future: Fut)
@@ -46,6 +48,14 @@ pub trait FutureExt: Future + Sized {
 
 impl<F: Future + Sized> FutureExt for F { }
 
+/// Returns `true` if the header named `name` typically carries credentials
+/// and should never be displayed or recorded verbatim.
+pub fn is_sensitive_header(name: &str) -> bool {
+    const SENSITIVE: &[&str] = &["Cookie", "Set-Cookie", "Authorization", "Proxy-Authorization"];
+
+    SENSITIVE.iter().any(|h| name.eq_ignore_ascii_case(h))
+}
+
 pub struct Formatter<F: Fn(&mut fmt::Formatter<'_>) -> fmt::Result>(pub F);
 
 impl<F: Fn(&mut fmt::Formatter<'_>) -> fmt::Result> fmt::Debug for Formatter<F> {
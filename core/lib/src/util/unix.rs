@@ -1,5 +1,9 @@
 use std::io;
 use std::os::fd::AsRawFd;
+#[cfg(feature = "handoff")]
+use std::mem;
+#[cfg(feature = "handoff")]
+use std::os::fd::{FromRawFd, OwnedFd, RawFd};
 
 pub fn lock_exclusive_nonblocking<T: AsRawFd>(file: &T) -> io::Result<()> {
     let raw_fd = file.as_raw_fd();
@@ -23,3 +27,100 @@ pub fn unlock_nonblocking<T: AsRawFd>(file: &T) -> io::Result<()> {
         _ => Err(io::Error::last_os_error()),
     }
 }
+
+/// Sends `payload` over the Unix domain socket `socket` along with copies of
+/// the file descriptors `fds`.
+#[cfg(feature = "handoff")]
+pub fn send_with_fds<S: AsRawFd>(socket: &S, payload: &[u8], fds: &[RawFd]) -> io::Result<()> {
+    let fds_len = mem::size_of_val(fds) as u32;
+    let space = unsafe { libc::CMSG_SPACE(fds_len) } as usize;
+    let mut control = vec![0u64; space.div_ceil(mem::size_of::<u64>())];
+
+    let mut iov = libc::iovec {
+        iov_base: payload.as_ptr() as *mut libc::c_void,
+        iov_len: payload.len(),
+    };
+
+    let mut msg: libc::msghdr = unsafe { mem::zeroed() };
+    msg.msg_iov = &mut iov;
+    msg.msg_iovlen = 1;
+    if !fds.is_empty() {
+        msg.msg_control = control.as_mut_ptr() as *mut libc::c_void;
+        msg.msg_controllen = space as _;
+
+        // SAFETY: `control` has room for a header with `fds.len()` fds.
+        unsafe {
+            let cmsg = libc::CMSG_FIRSTHDR(&msg);
+            (*cmsg).cmsg_level = libc::SOL_SOCKET;
+            (*cmsg).cmsg_type = libc::SCM_RIGHTS;
+            (*cmsg).cmsg_len = libc::CMSG_LEN(fds_len) as _;
+            let data = libc::CMSG_DATA(cmsg) as *mut RawFd;
+            std::ptr::copy_nonoverlapping(fds.as_ptr(), data, fds.len());
+        }
+    }
+
+    match unsafe { libc::sendmsg(socket.as_raw_fd(), &msg, 0) } {
+        n if n < 0 => Err(io::Error::last_os_error()),
+        n if (n as usize) < payload.len() => Err(io::ErrorKind::WriteZero.into()),
+        _ => Ok(()),
+    }
+}
+
+/// Receives a message into `buf` from the Unix domain socket `socket` along
+/// with up to `max_fds` file descriptors. Returns the length of the message
+/// and the received file descriptors.
+#[cfg(feature = "handoff")]
+pub fn recv_with_fds<S: AsRawFd>(
+    socket: &S,
+    buf: &mut [u8],
+    max_fds: usize,
+) -> io::Result<(usize, Vec<OwnedFd>)> {
+    let space = unsafe { libc::CMSG_SPACE((max_fds * mem::size_of::<RawFd>()) as u32) } as usize;
+    let mut control = vec![0u64; space.div_ceil(mem::size_of::<u64>())];
+
+    let mut iov = libc::iovec {
+        iov_base: buf.as_mut_ptr() as *mut libc::c_void,
+        iov_len: buf.len(),
+    };
+
+    let mut msg: libc::msghdr = unsafe { mem::zeroed() };
+    msg.msg_iov = &mut iov;
+    msg.msg_iovlen = 1;
+    msg.msg_control = control.as_mut_ptr() as *mut libc::c_void;
+    msg.msg_controllen = space as _;
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    let flags = libc::MSG_CMSG_CLOEXEC;
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+    let flags = 0;
+
+    let n = unsafe { libc::recvmsg(socket.as_raw_fd(), &mut msg, flags) };
+    if n < 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    let mut fds = vec![];
+
+    // SAFETY: `msg` was filled in by `recvmsg()`, which only writes complete
+    // control messages into `control`.
+    unsafe {
+        let mut cmsg = libc::CMSG_FIRSTHDR(&msg);
+        while !cmsg.is_null() {
+            if (*cmsg).cmsg_level == libc::SOL_SOCKET && (*cmsg).cmsg_type == libc::SCM_RIGHTS {
+                let data = libc::CMSG_DATA(cmsg) as *const RawFd;
+                let len = (*cmsg).cmsg_len as usize - libc::CMSG_LEN(0) as usize;
+                for i in 0..len / mem::size_of::<RawFd>() {
+                    fds.push(OwnedFd::from_raw_fd(std::ptr::read_unaligned(data.add(i))));
+                }
+            }
+
+            cmsg = libc::CMSG_NXTHDR(&msg, cmsg);
+        }
+    }
+
+    if msg.msg_flags & libc::MSG_CTRUNC != 0 {
+        return Err(io::Error::other("received too many file descriptors"));
+    }
+
+    Ok((n as usize, fds))
+}
@@ -0,0 +1,207 @@
+use std::fmt;
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use time::Date;
+
+/// The API versioning configuration: the `versioning` configuration parameter.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::versioning::{VersioningConfig, Status};
+/// use rocket::time::macros::date;
+/// use rocket::figment::{Figment, providers::{Format, Toml}};
+///
+/// let figment = Figment::from(Toml::string(r#"
+///     [versioning.versions.v1]
+///     prefix = "/api/v1"
+///     deprecated = "2025-01-01"
+///     sunset = "2027-01-01"
+///
+///     [versioning.versions.v2]
+///     prefix = "/api/v2"
+/// "#));
+///
+/// let config: VersioningConfig = figment.extract_inner("versioning").unwrap();
+/// assert_eq!(config.path, "/_rocket/versions");
+///
+/// let (name, v1) = config.version_of("/api/v1/users/<id>").unwrap();
+/// assert_eq!(name, "v1");
+/// assert_eq!(v1.status(date!(2026 - 06 - 01)), Status::Deprecated);
+/// assert!(config.version_of("/api/v10/users").is_none());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VersioningConfig {
+    /// The path the versions endpoint is mounted at. Must begin with `/`.
+    ///
+    /// **default: `"/_rocket/versions"`**
+    pub path: String,
+    /// A map from version names to the versions.
+    ///
+    /// **default: `{}`**
+    pub versions: IndexMap<String, ApiVersion>,
+}
+
+/// A version of an API, identified by the path prefix it's mounted under.
+///
+/// Dates are (de)serialized as `YYYY-MM-DD`.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::versioning::ApiVersion;
+/// use rocket::time::macros::date;
+///
+/// let v1 = ApiVersion::new("/api/v1")
+///     .deprecated(date!(2025 - 01 - 01))
+///     .sunset(date!(2027 - 01 - 01))
+///     .link("https://example.com/migrate");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApiVersion {
+    /// The path prefix the version's routes are mounted under. Must begin with
+    /// `/`.
+    pub prefix: String,
+    /// The date the version was or will be deprecated, if any.
+    #[serde(default, with = "date")]
+    pub deprecated: Option<Date>,
+    /// The date the version was or will be retired, if any. Must not precede
+    /// the deprecation date.
+    #[serde(default, with = "date")]
+    pub sunset: Option<Date>,
+    /// A URL to documentation on migrating away from the version, if any.
+    #[serde(default)]
+    pub link: Option<String>,
+}
+
+/// The status of an [`ApiVersion`] as of a given date.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Status {
+    /// The version isn't deprecated yet.
+    Current,
+    /// The version is deprecated but its sunset, if any, hasn't passed.
+    Deprecated,
+    /// The version's sunset has passed.
+    Sunset,
+}
+
+impl Default for VersioningConfig {
+    fn default() -> Self {
+        VersioningConfig {
+            path: "/_rocket/versions".into(),
+            versions: IndexMap::new(),
+        }
+    }
+}
+
+impl VersioningConfig {
+    /// Returns the name and version whose prefix is the longest to match
+    /// `path` segment-wise, if any.
+    pub fn version_of(&self, path: &str) -> Option<(&str, &ApiVersion)> {
+        self.versions.iter()
+            .filter(|(_, version)| version.contains(path))
+            .max_by_key(|(_, version)| version.prefix.trim_end_matches('/').len())
+            .map(|(name, version)| (name.as_str(), version))
+    }
+}
+
+impl ApiVersion {
+    /// Returns a current version mounted under `prefix`.
+    pub fn new<P: Into<String>>(prefix: P) -> Self {
+        ApiVersion { prefix: prefix.into(), deprecated: None, sunset: None, link: None }
+    }
+
+    /// Sets the date the version was or will be deprecated.
+    pub fn deprecated(mut self, date: Date) -> Self {
+        self.deprecated = Some(date);
+        self
+    }
+
+    /// Sets the date the version was or will be retired.
+    pub fn sunset(mut self, date: Date) -> Self {
+        self.sunset = Some(date);
+        self
+    }
+
+    /// Sets a URL to documentation on migrating away from the version.
+    pub fn link<L: Into<String>>(mut self, url: L) -> Self {
+        self.link = Some(url.into());
+        self
+    }
+
+    /// Returns the status of the version as of `today`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::versioning::{ApiVersion, Status};
+    /// use rocket::time::macros::date;
+    ///
+    /// let v1 = ApiVersion::new("/api/v1")
+    ///     .deprecated(date!(2025 - 01 - 01))
+    ///     .sunset(date!(2027 - 01 - 01));
+    ///
+    /// assert_eq!(v1.status(date!(2024 - 12 - 31)), Status::Current);
+    /// assert_eq!(v1.status(date!(2025 - 01 - 01)), Status::Deprecated);
+    /// assert_eq!(v1.status(date!(2027 - 01 - 01)), Status::Sunset);
+    /// ```
+    pub fn status(&self, today: Date) -> Status {
+        if self.sunset.is_some_and(|sunset| sunset <= today) {
+            Status::Sunset
+        } else if self.deprecated.is_some_and(|deprecated| deprecated <= today) {
+            Status::Deprecated
+        } else {
+            Status::Current
+        }
+    }
+
+    /// Whether `path` is under the version's prefix, matched segment-wise.
+    pub(crate) fn contains(&self, path: &str) -> bool {
+        path.strip_prefix(self.prefix.trim_end_matches('/'))
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+    }
+}
+
+impl Status {
+    /// The status as it's reported by the versions endpoint.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Status::Current => "current",
+            Status::Deprecated => "deprecated",
+            Status::Sunset => "sunset",
+        }
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
+
+/// Formats `date` as `YYYY-MM-DD`.
+pub(crate) fn ymd(date: Date) -> String {
+    format!("{:04}-{:02}-{:02}", date.year(), date.month() as u8, date.day())
+}
+
+/// (De)serializes an `Option<Date>` as an optional `YYYY-MM-DD` string.
+mod date {
+    use serde::{Deserialize, Deserializer, Serializer, de::Error};
+    use time::{Date, macros::format_description};
+
+    pub fn serialize<S: Serializer>(date: &Option<Date>, ser: S) -> Result<S::Ok, S::Error> {
+        match date {
+            Some(date) => ser.serialize_some(&super::ymd(*date)),
+            None => ser.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(de: D) -> Result<Option<Date>, D::Error> {
+        let Some(string) = Option::<String>::deserialize(de)? else { return Ok(None) };
+        Date::parse(&string, format_description!("[year]-[month]-[day]"))
+            .map(Some)
+            .map_err(|e| D::Error::custom(format!("invalid date `{string}`: {e}")))
+    }
+}
@@ -0,0 +1,107 @@
+//! API versions, their deprecation and sunset, and an endpoint listing them.
+//!
+//! The [`Versioning`] fairing describes the versions of an API by the path
+//! prefix each is mounted under, say `/api/v1` and `/api/v2`, along with the
+//! date a version was or will be deprecated and the date it stops being
+//! served. At ignition, the fairing mounts an endpoint, at `/_rocket/versions`
+//! by default, that lists every version with its dates, its status, and the
+//! routes mounted under its prefix, so that clients can programmatically track
+//! what the server supports.
+//!
+//! Responses from a route under a version's prefix carry that version's
+//! lifecycle, unless the response already sets the header:
+//!
+//!   * `Deprecation: @<unix-time>`, per [RFC 9745], once a deprecation date
+//!     is set, even if it's in the future.
+//!   * `Sunset: <HTTP-date>`, per [RFC 8594], once a sunset date is set.
+//!   * `Link: <url>; rel="deprecation"`, or `rel="sunset"` if the version
+//!     isn't deprecated, when a `link` to migration documentation is set.
+//!
+//! The fairing only describes versions: routes under a version past its
+//! sunset are still served. A route belongs to the version with the longest
+//! prefix that matches its path segment-wise.
+//!
+//! [RFC 9745]: https://www.rfc-editor.org/rfc/rfc9745
+//! [RFC 8594]: https://www.rfc-editor.org/rfc/rfc8594
+//!
+//! # Endpoint
+//!
+//! The endpoint responds to `GET` requests with JSON of the following form,
+//! where dates are `YYYY-MM-DD`, absent dates and links are `null`, and
+//! `status` is `current`, `deprecated`, or `sunset` as of the request:
+//!
+//! ```json
+//! {
+//!   "versions": [
+//!     {
+//!       "name": "v1",
+//!       "prefix": "/api/v1",
+//!       "status": "deprecated",
+//!       "deprecated": "2025-01-01",
+//!       "sunset": "2027-01-01",
+//!       "link": "https://example.com/migrate",
+//!       "routes": [
+//!         { "name": "users", "method": "GET", "uri": "/api/v1/users/<id>" }
+//!       ]
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! # Configuration
+//!
+//! The fairing is configured via the `versioning` configuration parameter,
+//! which is deserialized as a [`VersioningConfig`]:
+//!
+//! ```toml
+//! [default.versioning]
+//! path = "/_rocket/versions"
+//!
+//! [default.versioning.versions.v1]
+//! prefix = "/api/v1"
+//! deprecated = "2025-01-01"
+//! sunset = "2027-01-01"
+//! link = "https://example.com/migrate"
+//!
+//! [default.versioning.versions.v2]
+//! prefix = "/api/v2"
+//! ```
+//!
+//! # Example
+//!
+//! ```rust
+//! # #[macro_use] extern crate rocket;
+//! use rocket::versioning::{Versioning, ApiVersion};
+//! use rocket::time::macros::date;
+//!
+//! #[get("/users/<id>")]
+//! fn user_v1(id: usize) -> String {
+//!     format!("user {id}")
+//! }
+//!
+//! #[get("/users/<id>")]
+//! fn user_v2(id: usize) -> String {
+//!     format!("{{\"id\":{id}}}")
+//! }
+//!
+//! #[launch]
+//! fn rocket() -> _ {
+//!     let v1 = ApiVersion::new("/api/v1")
+//!         .deprecated(date!(2025 - 01 - 01))
+//!         .sunset(date!(2027 - 01 - 01))
+//!         .link("https://example.com/migrate");
+//!
+//!     rocket::build()
+//!         .mount("/api/v1", routes![user_v1])
+//!         .mount("/api/v2", routes![user_v2])
+//!         .attach(Versioning::new()
+//!             .version("v1", v1)
+//!             .version("v2", ApiVersion::new("/api/v2")))
+//! }
+//! ```
+
+mod config;
+mod versioning;
+
+pub use config::{VersioningConfig, ApiVersion, Status};
+pub use versioning::Versioning;
@@ -0,0 +1,212 @@
+use std::fmt::Write;
+use std::sync::Arc;
+
+use state::InitCell;
+use time::{Date, OffsetDateTime};
+
+use crate::{Rocket, Request, Response, Data, Build, Route};
+use crate::fairing::{self, Fairing, Info, Kind};
+use crate::http::{Header, Method, ContentType};
+use crate::route::{Handler, Outcome};
+use crate::trace::Trace;
+use crate::util::EscapeJson;
+use crate::versioning::{VersioningConfig, ApiVersion};
+use crate::versioning::config::ymd;
+
+/// A [`Fairing`] that describes [API versions](crate::versioning) and serves
+/// an endpoint listing them.
+///
+/// The configuration is read from the `versioning` configuration parameter at
+/// ignition. Versions added via [`Versioning::version()`] are added to those
+/// configured, replacing any configured with the same name. If the parameter
+/// is invalid, the endpoint path or a prefix doesn't begin with `/`, or a
+/// version's sunset precedes its deprecation, ignition fails.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::versioning::{Versioning, ApiVersion};
+/// use rocket::local::blocking::Client;
+///
+/// let versioning = Versioning::new().version("v2", ApiVersion::new("/api/v2"));
+/// let client = Client::debug(rocket::build().attach(versioning)).unwrap();
+///
+/// let versioning = client.rocket().fairing::<Versioning>().unwrap();
+/// let config = versioning.config().unwrap();
+/// assert_eq!(config.version_of("/api/v2/users").unwrap().0, "v2");
+/// ```
+#[derive(Clone)]
+pub struct Versioning {
+    versions: Vec<(String, ApiVersion)>,
+    config: Arc<InitCell<VersioningConfig>>,
+}
+
+impl Versioning {
+    /// Returns a `Versioning` fairing.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::versioning::Versioning;
+    ///
+    /// let versioning = Versioning::new();
+    /// ```
+    pub fn new() -> Self {
+        Versioning { versions: vec![], config: Arc::new(InitCell::new()) }
+    }
+
+    /// Adds the version `version` named `name`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::versioning::{Versioning, ApiVersion};
+    /// use rocket::time::macros::date;
+    ///
+    /// let versioning = Versioning::new()
+    ///     .version("v1", ApiVersion::new("/api/v1").deprecated(date!(2025 - 01 - 01)))
+    ///     .version("v2", ApiVersion::new("/api/v2"));
+    /// ```
+    pub fn version<N: Into<String>>(mut self, name: N, version: ApiVersion) -> Self {
+        self.versions.push((name.into(), version));
+        self
+    }
+
+    /// Returns the configuration in effect, once the fairing has ignited.
+    pub fn config(&self) -> Option<&VersioningConfig> {
+        self.config.try_get()
+    }
+
+    /// Renders the versions endpoint's JSON, listing `routes` by version.
+    fn to_json(config: &VersioningConfig, routes: &[&Route], today: Date) -> String {
+        let mut out = String::from("{\"versions\":[");
+        for (i, (name, version)) in config.versions.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+
+            let date = |date: Option<Date>| {
+                date.map_or("null".into(), |date| format!("\"{}\"", ymd(date)))
+            };
+
+            let link = version.link.as_ref()
+                .map_or("null".into(), |link| format!("\"{}\"", EscapeJson(link)));
+
+            let _ = write!(out, "{{\"name\":\"{}\",\"prefix\":\"{}\",\"status\":\"{}\",\
+                \"deprecated\":{},\"sunset\":{},\"link\":{},\"routes\":[",
+                EscapeJson(name), EscapeJson(&version.prefix), version.status(today),
+                date(version.deprecated), date(version.sunset), link);
+
+            let routes = routes.iter()
+                .filter(|r| config.version_of(r.uri.template()).is_some_and(|(n, _)| n == name));
+
+            for (j, route) in routes.enumerate() {
+                let name = route.name.as_ref()
+                    .map_or("null".into(), |name| format!("\"{}\"", EscapeJson(name)));
+
+                let _ = write!(out, "{}{{\"name\":{},\"method\":\"{}\",\"uri\":\"{}\"}}",
+                    if j > 0 { "," } else { "" }, name, route.method,
+                    EscapeJson(route.uri.template()));
+            }
+
+            out.push_str("]}");
+        }
+
+        out.push_str("]}");
+        out
+    }
+}
+
+impl Default for Versioning {
+    fn default() -> Self {
+        Versioning::new()
+    }
+}
+
+/// Formats `date` at midnight UTC as an HTTP-date, per RFC 9110.
+fn http_date(date: Date) -> String {
+    format!("{:.3}, {:02} {:.3} {:04} 00:00:00 GMT",
+        date.weekday().to_string(), date.day(), date.month().to_string(), date.year())
+}
+
+#[crate::async_trait]
+impl Fairing for Versioning {
+    fn info(&self) -> Info {
+        Info { name: "Versioning", kind: Kind::Ignite | Kind::Response | Kind::Singleton }
+    }
+
+    async fn on_ignite(&self, rocket: Rocket<Build>) -> fairing::Result {
+        let mut config = match rocket.figment().contains("versioning") {
+            true => match rocket.figment().extract_inner::<VersioningConfig>("versioning") {
+                Ok(config) => config,
+                Err(e) => {
+                    e.trace_error();
+                    return Err(rocket);
+                }
+            },
+            false => VersioningConfig::default(),
+        };
+
+        config.versions.extend(self.versions.iter().cloned());
+        let mut paths = std::iter::once(&config.path)
+            .chain(config.versions.values().map(|v| &v.prefix));
+
+        if let Some(path) = paths.find(|p| !p.starts_with('/')) {
+            error!(name: "versioning", path, "versioning paths must begin with `/`");
+            return Err(rocket);
+        }
+
+        let premature = |v: &ApiVersion| v.sunset.is_some() && v.sunset < v.deprecated;
+        if let Some((name, _)) = config.versions.iter().find(|(_, v)| premature(v)) {
+            error!(name: "versioning", version = name, "version sunset precedes deprecation");
+            return Err(rocket);
+        }
+
+        info!(name: "versioning", versions = config.versions.len(), path = %config.path,
+            "serving API versions");
+
+        let path = config.path.clone();
+        self.config.set(config);
+        Ok(rocket.mount(path, vec![Route::new(Method::Get, "/", self.clone())]))
+    }
+
+    async fn on_response<'r>(&self, req: &'r Request<'_>, res: &mut Response<'r>) {
+        let Some(config) = self.config.try_get() else { return };
+        let Some(route) = req.route() else { return };
+        let Some((_, version)) = config.version_of(route.uri.template()) else { return };
+
+        if let Some(date) = version.deprecated {
+            if !res.headers().contains("Deprecation") {
+                let time = date.midnight().assume_utc().unix_timestamp();
+                res.set_header(Header::new("Deprecation", format!("@{time}")));
+            }
+        }
+
+        if let Some(date) = version.sunset {
+            if !res.headers().contains("Sunset") {
+                res.set_header(Header::new("Sunset", http_date(date)));
+            }
+        }
+
+        let rel = match (version.deprecated, version.sunset) {
+            (Some(_), _) => "deprecation",
+            (None, Some(_)) => "sunset",
+            (None, None) => return,
+        };
+
+        if let Some(link) = &version.link {
+            res.adjoin_header(Header::new("Link", format!("<{link}>; rel=\"{rel}\"")));
+        }
+    }
+}
+
+#[crate::async_trait]
+impl Handler for Versioning {
+    async fn handle<'r>(&self, req: &'r Request<'_>, _: Data<'r>) -> Outcome<'r> {
+        let config = self.config.get();
+        let today = OffsetDateTime::now_utc().date();
+        let routes = req.router().routes().collect::<Vec<_>>();
+        let body = Self::to_json(config, &routes, today);
+        Outcome::from(req, (ContentType::JSON, body))
+    }
+}
@@ -0,0 +1,205 @@
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use futures::{StreamExt, SinkExt, Sink};
+use futures::stream::{Stream, FusedStream};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio_tungstenite::WebSocketStream;
+
+use crate::data::IoStream;
+use crate::handshake::{TokenAuth, Principal};
+use crate::ws::frame::{Message, CloseFrame, CloseCode};
+use crate::ws::result::{Result, Error};
+use crate::ws::tungstenite::protocol::Role;
+
+/// A readable and writeable WebSocket [`Message`] `async` stream.
+///
+/// This struct implements [`Stream`] and [`Sink`], allowing for `async` reading
+/// and writing of [`Message`]s. The [`StreamExt`] and [`SinkExt`] traits can be
+/// imported to provide additional functionality for streams and sinks:
+///
+/// ```rust
+/// # use rocket::get;
+/// # use rocket::ws;
+/// use rocket::futures::{SinkExt, StreamExt};
+///
+/// #[get("/echo/manual")]
+/// fn echo_manual<'r>(ws: ws::WebSocket) -> ws::Channel<'r> {
+///     ws.channel(move |mut stream| Box::pin(async move {
+///         while let Some(message) = stream.next().await {
+///             let _ = stream.send(message?).await;
+///         }
+///
+///         Ok(())
+///     }))
+/// }
+/// ```
+///
+/// Handlers are passed a `DuplexStream` over the upgraded [`IoStream`]. A
+/// client's end of a connection, as used to test handlers with a [local
+/// client](crate::local), is created via [`DuplexStream::client()`].
+///
+/// [`StreamExt`]: crate::futures::StreamExt
+/// [`SinkExt`]: crate::futures::SinkExt
+pub struct DuplexStream<S = IoStream>(tokio_tungstenite::WebSocketStream<S>);
+
+impl<S: AsyncRead + AsyncWrite + Unpin> DuplexStream<S> {
+    pub(crate) async fn new(stream: S, config: crate::ws::Config) -> Self {
+        Self::with_role(stream, Role::Server, config).await
+    }
+
+    async fn with_role(stream: S, role: Role, config: crate::ws::Config) -> Self {
+        let inner = WebSocketStream::from_raw_socket(stream, role, Some(config));
+        DuplexStream(inner.await)
+    }
+
+    /// Speaks the client's side of the WebSocket protocol over `io`, an
+    /// already upgraded connection.
+    ///
+    /// This is typically used to test WebSocket handlers with a local client,
+    /// whose responses can be [upgraded] in memory.
+    ///
+    /// [upgraded]: crate::local::asynchronous::LocalResponse::upgrade()
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use rocket::{get, routes};
+    /// use rocket::ws;
+    /// use rocket::http::{Header, Status};
+    /// use rocket::local::asynchronous::Client;
+    /// use rocket::futures::{SinkExt, StreamExt};
+    ///
+    /// #[get("/echo")]
+    /// fn echo(ws: ws::WebSocket) -> ws::Stream!['static] {
+    ///     ws.stream(|io| io)
+    /// }
+    ///
+    /// # rocket::async_test(async {
+    /// let client = Client::debug_with(routes![echo]).await.unwrap();
+    /// let mut response = client.get("/echo")
+    ///     .header(Header::new("Connection", "Upgrade"))
+    ///     .header(Header::new("Upgrade", "websocket"))
+    ///     .header(Header::new("Sec-WebSocket-Version", "13"))
+    ///     .header(Header::new("Sec-WebSocket-Key", "dGhlIHNhbXBsZSBub25jZQ=="))
+    ///     .dispatch()
+    ///     .await;
+    ///
+    /// assert_eq!(response.status(), Status::SwitchingProtocols);
+    /// let io = response.upgrade().unwrap();
+    /// let mut stream = ws::stream::DuplexStream::client(io, ws::Config::default()).await;
+    /// stream.send("hello".into()).await.unwrap();
+    /// assert_eq!(stream.next().await.unwrap().unwrap(), "hello".into());
+    /// # });
+    /// ```
+    pub async fn client(io: S, config: crate::ws::Config) -> Self {
+        Self::with_role(io, Role::Client, config).await
+    }
+
+    /// Close the stream now. This does not typically need to be called.
+    pub async fn close(&mut self, msg: Option<CloseFrame<'_>>) -> Result<()> {
+        self.0.close(msg).await
+    }
+
+    /// Authenticate the client with its first message, which must be a text
+    /// message containing a token, optionally prefixed with `Bearer `, and
+    /// must arrive within `deadline`. The token is validated by `auth`.
+    ///
+    /// Returns the [`Principal`] identified by the token. If the client
+    /// doesn't send a valid token in time, the stream is closed with a
+    /// [`Policy`](crate::ws::frame::CloseCode::Policy) close code and `None` is
+    /// returned.
+    ///
+    /// Use this when a client can't present a token during the handshake. See
+    /// [`crate::handshake`] for authenticating the handshake itself.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use rocket::get;
+    /// # use rocket::ws;
+    /// use std::time::Duration;
+    ///
+    /// use rocket::State;
+    /// use rocket::futures::SinkExt;
+    /// use rocket::handshake::TokenAuth;
+    ///
+    /// #[get("/chat")]
+    /// fn chat<'r>(ws: ws::WebSocket, auth: &'r State<TokenAuth>) -> ws::Channel<'r> {
+    ///     ws.channel(move |mut stream| Box::pin(async move {
+    ///         let deadline = Duration::from_secs(5);
+    ///         let Some(principal) = stream.authenticate(auth, deadline).await? else {
+    ///             return Ok(());
+    ///         };
+    ///
+    ///         let _ = stream.send(format!("hello, {}", principal.id()).into()).await;
+    ///         Ok(())
+    ///     }))
+    /// }
+    /// ```
+    pub async fn authenticate(
+        &mut self,
+        auth: &TokenAuth,
+        deadline: Duration,
+    ) -> Result<Option<Principal>> {
+        let principal = match tokio::time::timeout(deadline, self.next()).await {
+            Ok(Some(Ok(Message::Text(text)))) => {
+                let token = text.trim();
+                let token = token.strip_prefix("Bearer ").unwrap_or(token).trim();
+                auth.validate(token).await.ok_or("invalid token")
+            }
+            Ok(Some(Ok(_))) => Err("expected a token"),
+            Ok(Some(Err(e))) => return Err(e),
+            Ok(None) => return Ok(None),
+            Err(_) => Err("authentication timed out"),
+        };
+
+        match principal {
+            Ok(principal) => Ok(Some(principal)),
+            Err(reason) => {
+                let frame = CloseFrame { code: CloseCode::Policy, reason: reason.into() };
+                self.close(Some(frame)).await?;
+                Ok(None)
+            }
+        }
+    }
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> Stream for DuplexStream<S> {
+    type Item = Result<Message>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.get_mut().0.poll_next_unpin(cx)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> FusedStream for DuplexStream<S> {
+    fn is_terminated(&self) -> bool {
+        self.0.is_terminated()
+    }
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> Sink<Message> for DuplexStream<S> {
+    type Error = Error;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.get_mut().0.poll_ready_unpin(cx)
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: Message) -> Result<(), Self::Error> {
+        self.get_mut().0.start_send_unpin(item)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.get_mut().0.poll_flush_unpin(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.get_mut().0.poll_close_unpin(cx)
+    }
+}
@@ -0,0 +1,288 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use tokio::sync::mpsc;
+
+use crate::{Rocket, Build, Orbit};
+use crate::fairing::{self, Fairing, Info, Kind};
+use crate::ws::Message;
+
+/// Named groups of WebSocket connections with broadcast and presence.
+///
+/// A `Rooms<R, P>` tracks which connections are in which rooms, where rooms are
+/// identified by values of type `R` and each connection carries presence
+/// information of type `P`, such as a user name. A connection is registered
+/// via [`Rooms::connect()`], which returns a [`Member`] handle through which
+/// the connection joins and leaves rooms, broadcasts to them, and receives
+/// messages broadcast by others.
+///
+/// Cleanup is automatic: when a `Member` is dropped, typically because its
+/// WebSocket disconnected and its handler returned, it leaves every room it
+/// joined. Rooms without members are removed. `Rooms` is a [`Fairing`]: when
+/// attached, it places itself in managed state and, on shutdown, disconnects
+/// every member so that their handlers can finish.
+///
+/// # Example
+///
+/// ```rust
+/// # use rocket::{get, routes};
+/// use rocket::State;
+/// use rocket::futures::{SinkExt, StreamExt};
+/// use rocket::futures::future::{select, Either};
+/// use rocket::ws;
+///
+/// type Chat = ws::Rooms<String, String>;
+///
+/// #[get("/chat/<room>?<user>")]
+/// fn chat(
+///     ws: ws::WebSocket,
+///     room: String,
+///     user: String,
+///     rooms: &State<Chat>,
+/// ) -> ws::Channel<'static> {
+///     let rooms = rooms.inner().clone();
+///     ws.channel(move |mut stream| Box::pin(async move {
+///         let mut member = rooms.connect(user);
+///         member.join(room.clone());
+///
+///         loop {
+///             // Wait for a message from the client or from another member.
+///             let event = match select(stream.next(), Box::pin(member.recv())).await {
+///                 Either::Left((message, _)) => Either::Left(message),
+///                 Either::Right((message, _)) => Either::Right(message),
+///             };
+///
+///             match event {
+///                 Either::Left(Some(message)) => { member.broadcast(&room, message?); }
+///                 Either::Right(Some(message)) => stream.send(message).await?,
+///                 Either::Left(None) | Either::Right(None) => break,
+///             }
+///         }
+///
+///         Ok(())
+///     }))
+/// }
+///
+/// #[get("/chat/<room>/who")]
+/// fn who(room: String, rooms: &State<Chat>) -> String {
+///     rooms.presence(&room).join(", ")
+/// }
+///
+/// #[rocket::launch]
+/// fn rocket() -> _ {
+///     rocket::build()
+///         .mount("/", routes![chat, who])
+///         .attach(Chat::new())
+/// }
+/// ```
+pub struct Rooms<R, P = ()> {
+    inner: Arc<Inner<R, P>>,
+}
+
+struct Inner<R, P> {
+    next_id: AtomicU64,
+    state: Mutex<State<R, P>>,
+}
+
+struct State<R, P> {
+    closed: bool,
+    members: HashMap<u64, Entry<P>>,
+    rooms: HashMap<R, Vec<u64>>,
+}
+
+struct Entry<P> {
+    presence: P,
+    sender: mpsc::UnboundedSender<Message>,
+}
+
+/// A connection registered with [`Rooms`].
+///
+/// Returned by [`Rooms::connect()`]. Leaves all joined rooms when dropped.
+pub struct Member<R: Hash + Eq, P> {
+    id: u64,
+    rooms: Rooms<R, P>,
+    receiver: mpsc::UnboundedReceiver<Message>,
+}
+
+impl<R, P> Clone for Rooms<R, P> {
+    fn clone(&self) -> Self {
+        Rooms { inner: self.inner.clone() }
+    }
+}
+
+impl<R: Hash + Eq + Clone, P: Clone> Default for Rooms<R, P> {
+    fn default() -> Self {
+        Rooms::new()
+    }
+}
+
+impl<R: Hash + Eq, P> Rooms<R, P> {
+    fn state(&self) -> std::sync::MutexGuard<'_, State<R, P>> {
+        self.inner.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn disconnect(&self, id: u64) {
+        let mut state = self.state();
+        state.members.remove(&id);
+        state.rooms.retain(|_, ids| {
+            ids.retain(|member| *member != id);
+            !ids.is_empty()
+        });
+    }
+}
+
+impl<R: Hash + Eq + Clone, P: Clone> Rooms<R, P> {
+    /// Creates a new set of rooms with no members.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::ws;
+    ///
+    /// let rooms = ws::Rooms::<String, String>::new();
+    /// ```
+    pub fn new() -> Self {
+        let state = State { closed: false, members: HashMap::new(), rooms: HashMap::new() };
+        Rooms { inner: Arc::new(Inner { next_id: AtomicU64::new(0), state: Mutex::new(state) }) }
+    }
+
+    /// Registers a new connection with presence information `presence`. The
+    /// connection is initially in no rooms.
+    ///
+    /// If the rooms have been [closed](Rooms::close()), the returned member
+    /// never receives any messages.
+    pub fn connect(&self, presence: P) -> Member<R, P> {
+        let id = self.inner.next_id.fetch_add(1, Ordering::Relaxed);
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let mut state = self.state();
+        if !state.closed {
+            state.members.insert(id, Entry { presence, sender });
+        }
+
+        Member { id, rooms: self.clone(), receiver }
+    }
+
+    /// Sends `message` to every member of `room`. Returns the number of
+    /// members the message was sent to.
+    pub fn broadcast(&self, room: &R, message: Message) -> usize {
+        self.send(room, message, None)
+    }
+
+    fn send(&self, room: &R, message: Message, except: Option<u64>) -> usize {
+        let state = self.state();
+        let Some(ids) = state.rooms.get(room) else { return 0 };
+        ids.iter()
+            .filter(|id| Some(**id) != except)
+            .filter_map(|id| state.members.get(id))
+            .filter(|member| member.sender.send(message.clone()).is_ok())
+            .count()
+    }
+
+    /// Returns the presence information of every member of `room`, in the
+    /// order they joined.
+    pub fn presence(&self, room: &R) -> Vec<P> {
+        let state = self.state();
+        state.rooms.get(room)
+            .map(|ids| ids.iter().filter_map(|id| state.members.get(id)))
+            .into_iter()
+            .flatten()
+            .map(|member| member.presence.clone())
+            .collect()
+    }
+
+    /// Returns the number of members in `room`.
+    pub fn count(&self, room: &R) -> usize {
+        self.state().rooms.get(room).map_or(0, |ids| ids.len())
+    }
+
+    /// Returns every room with at least one member.
+    pub fn rooms(&self) -> Vec<R> {
+        self.state().rooms.keys().cloned().collect()
+    }
+
+    /// Disconnects every member and refuses new ones. Each member's
+    /// [`Member::recv()`] returns `None` once pending messages are drained.
+    pub fn close(&self) {
+        let mut state = self.state();
+        state.closed = true;
+        state.members.clear();
+        state.rooms.clear();
+    }
+
+    fn join(&self, id: u64, room: R) {
+        let mut state = self.state();
+        if state.members.contains_key(&id) {
+            let ids = state.rooms.entry(room).or_default();
+            if !ids.contains(&id) {
+                ids.push(id);
+            }
+        }
+    }
+
+    fn leave(&self, id: u64, room: &R) {
+        let mut state = self.state();
+        if let Some(ids) = state.rooms.get_mut(room) {
+            ids.retain(|member| *member != id);
+            if ids.is_empty() {
+                state.rooms.remove(room);
+            }
+        }
+    }
+}
+
+impl<R: Hash + Eq + Clone, P: Clone> Member<R, P> {
+    /// Joins `room`. Joining a room more than once has no further effect.
+    pub fn join(&self, room: R) {
+        self.rooms.join(self.id, room);
+    }
+
+    /// Leaves `room`, if this member had joined it.
+    pub fn leave(&self, room: &R) {
+        self.rooms.leave(self.id, room);
+    }
+
+    /// Sends `message` to every _other_ member of `room`. Returns the number of
+    /// members the message was sent to.
+    pub fn broadcast(&self, room: &R, message: Message) -> usize {
+        self.rooms.send(room, message, Some(self.id))
+    }
+
+    /// Returns `true` if this member is in `room`.
+    pub fn is_in(&self, room: &R) -> bool {
+        self.rooms.state().rooms.get(room).map_or(false, |ids| ids.contains(&self.id))
+    }
+
+    /// Receives the next message broadcast to this member. Returns `None` when
+    /// the rooms have been closed and all pending messages were received.
+    pub async fn recv(&mut self) -> Option<Message> {
+        self.receiver.recv().await
+    }
+}
+
+impl<R: Hash + Eq, P> Drop for Member<R, P> {
+    fn drop(&mut self) {
+        self.rooms.disconnect(self.id);
+    }
+}
+
+#[crate::async_trait]
+impl<R, P> Fairing for Rooms<R, P>
+    where R: Hash + Eq + Clone + Send + Sync + 'static, P: Clone + Send + Sync + 'static
+{
+    fn info(&self) -> Info {
+        Info {
+            name: "WebSocket Rooms",
+            kind: Kind::Ignite | Kind::Shutdown | Kind::Singleton,
+        }
+    }
+
+    async fn on_ignite(&self, rocket: Rocket<Build>) -> fairing::Result {
+        Ok(rocket.manage(self.clone()))
+    }
+
+    async fn on_shutdown(&self, _: &Rocket<Orbit>) {
+        self.close();
+    }
+}
@@ -1,27 +1,28 @@
 use std::io;
 
-use rocket::data::{IoHandler, IoStream};
-use rocket::futures::{self, StreamExt, SinkExt, future::BoxFuture, stream::SplitStream};
-use rocket::response::{self, Responder, Response};
-use rocket::request::{FromRequest, Request, Outcome};
-use rocket::http::Status;
+use futures::{StreamExt, SinkExt, future::BoxFuture, stream::SplitStream};
 
-use crate::{Config, Message};
-use crate::stream::DuplexStream;
-use crate::result::{Result, Error};
+use crate::data::{IoHandler, IoStream};
+use crate::response::{self, Responder, Response};
+use crate::request::{FromRequest, Request, Outcome};
+use crate::http::Status;
+use crate::handshake::accepted_protocol;
+use crate::ws::{Config, Message};
+use crate::ws::stream::DuplexStream;
+use crate::ws::result::{Result, Error};
 
 /// A request guard identifying WebSocket requests. Converts into a [`Channel`]
 /// or [`MessageStream`].
 ///
-/// For example usage, see the [crate docs](crate#usage).
+/// For example usage, see the [module docs](crate::ws#usage).
 ///
 /// ## Details
 ///
-/// This is the entrypoint to the library. Every WebSocket response _must_
+/// This is the entrypoint to WebSocket support. Every WebSocket response _must_
 /// initiate via the `WebSocket` request guard. The guard identifies valid
 /// WebSocket connection requests and, if the request is valid, succeeds to be
 /// converted into a streaming WebSocket response via
-/// [`Stream!`](crate::Stream!), [`WebSocket::channel()`], or
+/// [`Stream!`](crate::ws::Stream!), [`WebSocket::channel()`], or
 /// [`WebSocket::stream()`]. The connection can be configured via
 /// [`WebSocket::config()`]; see [`Config`] for details on configuring a
 /// connection.
@@ -29,7 +30,10 @@ use crate::result::{Result, Error};
 /// ### Forwarding
 ///
 /// If the incoming request is not a valid WebSocket request, the guard
-/// forwards with a status of `BadRequest`. The guard never fails.
+/// forwards with a status of `426 Upgrade Required`, so that a route serving
+/// both WebSocket and plain requests at the same path can be ranked after the
+/// WebSocket route and, if none is, the request is answered with `426`. The
+/// guard never fails.
 pub struct WebSocket {
     config: Config,
     key: String,
@@ -42,7 +46,7 @@ impl WebSocket {
     ///
     /// ```rust
     /// # use rocket::get;
-    /// # use rocket_ws as ws;
+    /// # use rocket::ws;
     /// #
     /// #[get("/echo")]
     /// fn echo_stream(ws: ws::WebSocket) -> ws::Stream!['static] {
@@ -72,7 +76,7 @@ impl WebSocket {
     /// The `handler` must return a `Box`ed and `Pin`ned future: calling
     /// [`Box::pin()`] with a future does just this as is the preferred
     /// mechanism to create a `Box<Pin<Future>>`. The future must return a
-    /// [`Result<()>`](crate::result::Result). The WebSocket connection is
+    /// [`Result<()>`](crate::ws::result::Result). The WebSocket connection is
     /// closed successfully if the future returns `Ok` and with an error if
     /// the future returns `Err`.
     ///
@@ -86,7 +90,7 @@ impl WebSocket {
     ///
     /// ```rust
     /// # use rocket::get;
-    /// # use rocket_ws as ws;
+    /// # use rocket::ws;
     /// use rocket::futures::{SinkExt, StreamExt};
     ///
     /// #[get("/hello/<name>")]
@@ -119,7 +123,7 @@ impl WebSocket {
     ///
     /// This method takes a `FnOnce` `stream` that consumes a read-only stream
     /// and returns a stream of [`Message`]s. While the returned stream can be
-    /// constructed in any manner, the [`Stream!`](crate::Stream!) macro is the
+    /// constructed in any manner, the [`Stream!`](crate::ws::Stream!) macro is the
     /// preferred method. In any case, the stream must be `Send`.
     ///
     /// The returned stream must emit items of type `Result<Message>`. Items
@@ -131,7 +135,7 @@ impl WebSocket {
     ///
     /// ```rust
     /// # use rocket::get;
-    /// # use rocket_ws as ws;
+    /// # use rocket::ws;
     ///
     /// // Use `Stream!`, which internally calls `WebSocket::stream()`.
     /// #[get("/echo?stream")]
@@ -175,7 +179,7 @@ impl WebSocket {
     ///
     /// ```rust
     /// # use rocket::get;
-    /// # use rocket_ws as ws;
+    /// # use rocket::ws;
     /// #
     /// #[get("/echo")]
     /// fn echo_stream(ws: ws::WebSocket) -> ws::Stream!['static] {
@@ -204,20 +208,20 @@ pub struct Channel<'r> {
 /// [`Stream!`] macro, which expands to both the type itself and an expression
 /// which evaluates to this type. See [`Stream!`] for details.
 ///
-/// [`Stream!`]: crate::Stream!
+/// [`Stream!`]: crate::ws::Stream!
 // TODO: Get rid of this or `Channel` via a single `enum`.
 pub struct MessageStream<'r, S> {
     ws: WebSocket,
     handler: Box<dyn FnOnce(SplitStream<DuplexStream>) -> S + Send + 'r>
 }
 
-#[rocket::async_trait]
+#[crate::async_trait]
 impl<'r> FromRequest<'r> for WebSocket {
     type Error = std::convert::Infallible;
 
     async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
-        use crate::tungstenite::handshake::derive_accept_key;
-        use rocket::http::uncased::eq;
+        use crate::ws::tungstenite::handshake::derive_accept_key;
+        use crate::http::uncased::eq;
 
         let headers = req.headers();
         let is_upgrade = headers.get("Connection")
@@ -232,34 +236,42 @@ impl<'r> FromRequest<'r> for WebSocket {
             Some(key) if is_upgrade && is_ws && is_13 => {
                 Outcome::Success(WebSocket { key, config: Config::default() })
             },
-            Some(_) | None => Outcome::Forward(Status::BadRequest)
+            Some(_) | None => Outcome::Forward(Status::UpgradeRequired)
         }
     }
 }
 
 impl<'r, 'o: 'r> Responder<'r, 'o> for Channel<'o> {
-    fn respond_to(self, _: &'r Request<'_>) -> response::Result<'o> {
-        Response::build()
-            .raw_header("Sec-Websocket-Version", "13")
-            .raw_header("Sec-WebSocket-Accept", self.ws.key.clone())
-            .upgrade("websocket", self)
-            .ok()
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'o> {
+        let mut response = Response::build();
+        response.raw_header("Sec-Websocket-Version", "13")
+            .raw_header("Sec-WebSocket-Accept", self.ws.key.clone());
+
+        if let Some(protocol) = accepted_protocol(req) {
+            response.raw_header("Sec-WebSocket-Protocol", protocol.to_string());
+        }
+
+        response.upgrade("websocket", self).ok()
     }
 }
 
 impl<'r, 'o: 'r, S> Responder<'r, 'o> for MessageStream<'o, S>
     where S: futures::Stream<Item = Result<Message>> + Send + 'o
 {
-    fn respond_to(self, _: &'r Request<'_>) -> response::Result<'o> {
-        Response::build()
-            .raw_header("Sec-Websocket-Version", "13")
-            .raw_header("Sec-WebSocket-Accept", self.ws.key.clone())
-            .upgrade("websocket", self)
-            .ok()
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'o> {
+        let mut response = Response::build();
+        response.raw_header("Sec-Websocket-Version", "13")
+            .raw_header("Sec-WebSocket-Accept", self.ws.key.clone());
+
+        if let Some(protocol) = accepted_protocol(req) {
+            response.raw_header("Sec-WebSocket-Protocol", protocol.to_string());
+        }
+
+        response.upgrade("websocket", self).ok()
     }
 }
 
-#[rocket::async_trait]
+#[crate::async_trait]
 impl IoHandler for Channel<'_> {
     async fn io(self: Box<Self>, io: IoStream) -> io::Result<()> {
         let stream = DuplexStream::new(io, self.ws.config).await;
@@ -268,14 +280,14 @@ impl IoHandler for Channel<'_> {
     }
 }
 
-#[rocket::async_trait]
+#[crate::async_trait]
 impl<'r, S> IoHandler for MessageStream<'r, S>
     where S: futures::Stream<Item = Result<Message>> + Send + 'r
 {
     async fn io(self: Box<Self>, io: IoStream) -> io::Result<()> {
         let (mut sink, source) = DuplexStream::new(io, self.ws.config).await.split();
         let stream = (self.handler)(source);
-        rocket::tokio::pin!(stream);
+        tokio::pin!(stream);
         while let Some(msg) = stream.next().await {
             let result = match msg {
                 Ok(msg) if msg.is_close() => return Ok(()),
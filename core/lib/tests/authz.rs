@@ -0,0 +1,98 @@
+#![cfg(feature = "authz")]
+
+#[macro_use] extern crate rocket;
+
+use std::sync::Arc;
+use std::time::Duration;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use rocket::State;
+use rocket::authz::{Authorizer, Authz, Decision, PolicyEngine};
+use rocket::local::blocking::Client;
+
+struct Counting(Arc<AtomicUsize>);
+
+#[rocket::async_trait]
+impl PolicyEngine for Counting {
+    async fn decide(&self, principal: &str, _: &str, _: &str) -> Decision {
+        self.0.fetch_add(1, Ordering::SeqCst);
+        match principal {
+            "alice" => Decision::Allow,
+            _ => Decision::Deny,
+        }
+    }
+}
+
+#[get("/<user>/<doc>")]
+async fn read(user: &str, doc: &str, authz: Authz<'_>) -> String {
+    let first = authz.allowed(user, "read", doc).await;
+    let second = authz.allowed(user, "read", doc).await;
+    format!("{first}:{second}")
+}
+
+#[delete("/<doc>")]
+async fn delete(doc: &str, authz: Authz<'_>, authorizer: &State<Authorizer>) -> String {
+    let before = authz.allowed("alice", "read", doc).await;
+    authorizer.invalidate_resource(doc);
+    let after = authz.allowed("alice", "read", doc).await;
+    format!("{before}:{after}")
+}
+
+fn client(ttl: Option<Duration>) -> (Client, Arc<AtomicUsize>) {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let mut authorizer = Authorizer::new(Counting(calls.clone()));
+    if let Some(ttl) = ttl {
+        authorizer = authorizer.ttl(ttl);
+    }
+
+    let rocket = rocket::build().manage(authorizer).mount("/", routes![read, delete]);
+    (Client::debug(rocket).unwrap(), calls)
+}
+
+#[test]
+fn per_request_cache() {
+    let (client, calls) = client(None);
+    assert_eq!(client.get("/alice/a").dispatch().into_string().unwrap(), "true:true");
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+    assert_eq!(client.get("/bob/a").dispatch().into_string().unwrap(), "false:false");
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+    // Without a TTL, decisions aren't cached across requests.
+    client.get("/alice/a").dispatch();
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+}
+
+#[test]
+fn cross_request_cache() {
+    let (client, calls) = client(Some(Duration::from_secs(60)));
+    client.get("/alice/a").dispatch();
+    client.get("/alice/a").dispatch();
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+    client.get("/alice/b").dispatch();
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn invalidation() {
+    let (client, calls) = client(Some(Duration::from_secs(60)));
+    client.get("/alice/a").dispatch();
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+    // Invalidation applies to the in-flight request and to later requests.
+    assert_eq!(client.delete("/a").dispatch().into_string().unwrap(), "true:true");
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+    client.get("/alice/a").dispatch();
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn expiry() {
+    let (client, calls) = client(Some(Duration::from_millis(50)));
+    client.get("/alice/a").dispatch();
+    std::thread::sleep(Duration::from_millis(100));
+    client.get("/alice/a").dispatch();
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+}
@@ -0,0 +1,105 @@
+#![cfg(feature = "barcode")]
+
+#[macro_use] extern crate rocket;
+
+use rocket::http::{ContentType, Status};
+use rocket::local::blocking::Client;
+use rocket::response::barcode::{QrCode, Barcode, EcLevel};
+
+#[get("/<id>")]
+fn ticket(id: u32) -> String {
+    format!("Ticket #{id}")
+}
+
+#[get("/qr/<id>?<png>")]
+fn qr(id: u32, png: bool) -> QrCode {
+    let code = QrCode::new(uri!("https://example.com", ticket(id))).scale(2);
+    if png { code.png() } else { code }
+}
+
+#[get("/ean/<digits>")]
+fn ean(digits: &str) -> Barcode {
+    Barcode::ean13(digits).png().scale(1).height(20)
+}
+
+#[get("/code128/<data>")]
+fn code128(data: &str) -> Barcode {
+    Barcode::code128(data)
+}
+
+/// Returns the width and height of the PNG `image`.
+fn png_size(image: &[u8]) -> (u32, u32) {
+    assert!(image.starts_with(b"\x89PNG\r\n\x1a\n"));
+    let width = u32::from_be_bytes(image[16..20].try_into().unwrap());
+    let height = u32::from_be_bytes(image[20..24].try_into().unwrap());
+    (width, height)
+}
+
+fn client() -> Client {
+    Client::debug_with(routes![ticket, qr, ean, code128]).unwrap()
+}
+
+#[test]
+fn qr_code_responses() {
+    let client = client();
+    let response = client.get("/qr/7").dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.content_type(), Some(ContentType::SVG));
+    let svg = response.into_string().unwrap();
+    assert!(svg.starts_with("<svg"));
+    assert!(svg.ends_with("</svg>"));
+
+    // `https://example.com/7` fits in a version 2 code: 25 modules + margins.
+    assert!(svg.contains("width=\"66\" height=\"66\" viewBox=\"0 0 33 33\""));
+
+    let response = client.get("/qr/7?png").dispatch();
+    assert_eq!(response.content_type(), Some(ContentType::PNG));
+    assert_eq!(png_size(&response.into_bytes().unwrap()), (66, 66));
+}
+
+#[test]
+fn qr_code_rendering() {
+    let code = QrCode::new("hi").scale(1).margin(0);
+    let svg = code.to_svg().unwrap();
+    assert!(svg.contains("viewBox=\"0 0 21 21\""));
+    assert_eq!(png_size(&code.png().to_png().unwrap()), (21, 21));
+
+    let larger = QrCode::new("hi").ec_level(EcLevel::High).scale(3);
+    assert_eq!(png_size(&larger.to_png().unwrap()), (87, 87));
+
+    let too_long = QrCode::new("x".repeat(8000));
+    assert!(too_long.to_svg().is_err());
+    assert!(too_long.to_png().is_err());
+}
+
+#[test]
+fn barcode_responses() {
+    let client = client();
+    let response = client.get("/ean/750103131130").dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.content_type(), Some(ContentType::PNG));
+
+    // EAN-13 codes are 95 modules wide.
+    assert_eq!(png_size(&response.into_bytes().unwrap()), (115, 40));
+
+    let response = client.get("/ean/12345").dispatch();
+    assert_eq!(response.status(), Status::InternalServerError);
+
+    let response = client.get("/code128/Rocket-1").dispatch();
+    assert_eq!(response.content_type(), Some(ContentType::SVG));
+    assert!(response.into_string().unwrap().contains("<path fill=\"#000\" d=\"M10 10h"));
+
+    let response = client.get("/code128/%E2%9C%93").dispatch();
+    assert_eq!(response.status(), Status::InternalServerError);
+}
+
+#[test]
+fn barcode_rendering() {
+    let code = Barcode::code39("ROCKET 42").margin(0).scale(1).height(1);
+    let svg = code.to_svg().unwrap();
+    assert!(svg.contains("height=\"1\""));
+    assert!(svg.contains("M0 0h1v1h-1z"));
+
+    assert!(Barcode::code39("lowercase").to_svg().is_err());
+    assert!(Barcode::ean13("75010313113x").to_png().is_err());
+}
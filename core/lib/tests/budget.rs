@@ -0,0 +1,85 @@
+#![cfg(feature = "budget")]
+
+#[macro_use] extern crate rocket;
+
+use std::time::Duration;
+
+use rocket::budget::{Budget, Cost, Counting};
+use rocket::data::ToByteUnit;
+use rocket::fairing::AdHoc;
+use rocket::figment::providers::{Format, Toml};
+use rocket::http::Header;
+use rocket::local::blocking::Client;
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting::system();
+
+#[get("/small")]
+fn small() -> &'static str {
+    "small"
+}
+
+#[get("/large")]
+fn large() -> String {
+    let buffer = std::hint::black_box(vec![b'a'; 4 * 1024 * 1024]);
+    format!("{}", buffer.len())
+}
+
+#[get("/slow")]
+async fn slow() -> &'static str {
+    rocket::tokio::time::sleep(Duration::from_millis(150)).await;
+    "slow"
+}
+
+fn client() -> Client {
+    let figment = rocket::Config::figment().merge(Toml::string(r#"
+        budget.millis = 100
+        budget.allocated = "1 MiB"
+    "#));
+
+    let rocket = rocket::custom(figment)
+        .mount("/", routes![small, large, slow])
+        .attach(Budget::new())
+        .attach(AdHoc::on_response("Cost", |req, res| Box::pin(async move {
+            let cost = Cost::of(req);
+            res.set_header(Header::new("X-Allocated", cost.allocated().as_u64().to_string()));
+            res.set_header(Header::new("X-Elapsed", cost.elapsed().as_millis().to_string()));
+        })));
+
+    Client::debug(rocket).unwrap()
+}
+
+fn header(response: &rocket::local::blocking::LocalResponse<'_>, name: &str) -> u64 {
+    response.headers().get_one(name).unwrap().parse().unwrap()
+}
+
+fn outliers(client: &Client) -> u64 {
+    client.rocket().fairing::<Budget>().unwrap().outliers()
+}
+
+#[test]
+fn measures_handler_allocations() {
+    let client = client();
+    let response = client.get("/small").dispatch();
+    assert!(header(&response, "X-Allocated") < 1.mebibytes().as_u64());
+    assert_eq!(outliers(&client), 0);
+
+    let response = client.get("/large").dispatch();
+    assert!(header(&response, "X-Allocated") >= 4.mebibytes().as_u64());
+    assert_eq!(outliers(&client), 1);
+}
+
+#[test]
+fn measures_wall_time() {
+    let client = client();
+    let response = client.get("/slow").dispatch();
+    assert!(header(&response, "X-Elapsed") >= 150);
+    assert_eq!(outliers(&client), 1);
+}
+
+#[test]
+fn invalid_config_fails_ignition() {
+    let figment = rocket::Config::figment().merge(Toml::string("budget.millis = -1"));
+    let rocket = rocket::custom(figment).attach(Budget::new());
+    assert!(Client::debug(rocket).is_err());
+}
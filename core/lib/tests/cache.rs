@@ -0,0 +1,200 @@
+#![cfg(feature = "cache")]
+
+#[macro_use] extern crate rocket;
+
+use std::sync::atomic::{AtomicUsize, AtomicBool, Ordering};
+
+use rocket::State;
+use rocket::cache::Cache;
+use rocket::http::{Header, Status};
+use rocket::local::blocking::Client;
+
+#[derive(Default)]
+struct Counter(AtomicUsize, AtomicBool);
+
+#[derive(Responder)]
+struct Cached(String, Header<'static>);
+
+fn cached(counter: &Counter, control: &'static str) -> Result<Cached, Status> {
+    if counter.1.load(Ordering::SeqCst) {
+        return Err(Status::InternalServerError);
+    }
+
+    let count = counter.0.fetch_add(1, Ordering::SeqCst) + 1;
+    Ok(Cached(count.to_string(), Header::new("Cache-Control", control)))
+}
+
+#[get("/fresh")]
+fn fresh(counter: &State<Counter>) -> Result<Cached, Status> {
+    cached(counter, "public, max-age=60")
+}
+
+#[get("/swr")]
+fn swr(counter: &State<Counter>) -> Result<Cached, Status> {
+    cached(counter, "max-age=0, stale-while-revalidate=60")
+}
+
+#[get("/sie")]
+fn sie(counter: &State<Counter>) -> Result<Cached, Status> {
+    cached(counter, "max-age=0, stale-if-error=60")
+}
+
+#[get("/no-store")]
+fn no_store(counter: &State<Counter>) -> Result<Cached, Status> {
+    cached(counter, "no-store, max-age=60")
+}
+
+#[derive(Responder)]
+struct Varied(Cached, Header<'static>);
+
+#[get("/vary")]
+fn vary(counter: &State<Counter>) -> Result<Varied, Status> {
+    Ok(Varied(cached(counter, "max-age=60")?, Header::new("Vary", "Accept-Language")))
+}
+
+fn client() -> Client {
+    let rocket = rocket::build()
+        .manage(Counter::default())
+        .mount("/", routes![fresh, swr, sie, no_store, vary])
+        .attach(Cache::new());
+
+    Client::debug(rocket).unwrap()
+}
+
+fn body(client: &Client, uri: &'static str) -> String {
+    client.get(uri).dispatch().into_string().unwrap()
+}
+
+#[test]
+fn fresh_responses_are_served_from_cache() {
+    let client = client();
+    let response = client.get("/fresh").dispatch();
+    assert!(response.headers().get_one("Age").is_none());
+    assert_eq!(response.into_string().unwrap(), "1");
+
+    let response = client.get("/fresh").dispatch();
+    assert_eq!(response.headers().get_one("Age"), Some("0"));
+    assert_eq!(response.headers().get_one("Content-Length"), Some("1"));
+    assert_eq!(response.into_string().unwrap(), "1");
+
+    let response = client.head("/fresh").dispatch();
+    assert_eq!(response.headers().get_one("Age"), Some("0"));
+
+    client.rocket().state::<Counter>().unwrap().0.store(10, Ordering::SeqCst);
+    client.rocket().fairing::<Cache>().unwrap().purge();
+    assert_eq!(body(&client, "/fresh"), "11");
+}
+
+#[test]
+fn stale_responses_are_served_while_revalidating() {
+    let client = client();
+    assert_eq!(body(&client, "/swr"), "1");
+
+    // The stale "1" is served; the revalidation stores "2".
+    assert_eq!(body(&client, "/swr"), "1");
+    assert_eq!(body(&client, "/swr"), "2");
+    assert_eq!(body(&client, "/swr"), "3");
+}
+
+#[test]
+fn stale_responses_are_served_on_error() {
+    let client = client();
+    assert_eq!(body(&client, "/sie"), "1");
+    assert_eq!(body(&client, "/sie"), "2");
+
+    client.rocket().state::<Counter>().unwrap().1.store(true, Ordering::SeqCst);
+    let response = client.get("/sie").dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert!(response.headers().get_one("Age").is_some());
+    assert_eq!(response.into_string().unwrap(), "2");
+
+    // Without a stored response, the error is returned.
+    let response = client.get("/fresh").dispatch();
+    assert_eq!(response.status(), Status::InternalServerError);
+}
+
+#[test]
+fn uncacheable_responses_are_not_stored() {
+    let client = client();
+    assert_eq!(body(&client, "/no-store"), "1");
+    assert_eq!(body(&client, "/no-store"), "2");
+}
+
+#[test]
+fn varying_responses_are_stored_per_variant() {
+    let client = client();
+    let get = |lang: &'static str| {
+        let request = client.get("/vary").header(Header::new("Accept-Language", lang));
+        request.dispatch().into_string().unwrap()
+    };
+
+    assert_eq!(get("en"), "1");
+    assert_eq!(get("fr"), "2");
+    assert_eq!(get("en"), "1");
+    assert_eq!(get("fr"), "2");
+    assert_eq!(body(&client, "/vary"), "3");
+}
+
+mod coalescing {
+    use super::*;
+
+    use std::time::Duration;
+
+    use rocket::tokio::{join, time::sleep};
+    use rocket::local::asynchronous::Client;
+
+    #[get("/slow?<control>")]
+    async fn slow(counter: &State<Counter>, control: &str) -> Cached {
+        let count = counter.0.fetch_add(1, Ordering::SeqCst) + 1;
+        sleep(Duration::from_millis(200)).await;
+        Cached(count.to_string(), Header::new("Cache-Control", control.to_string()))
+    }
+
+    async fn client(cache: Cache) -> Client {
+        let rocket = rocket::build()
+            .manage(Counter::default())
+            .mount("/", routes![slow])
+            .attach(cache);
+
+        Client::debug(rocket).await.unwrap()
+    }
+
+    async fn counts(client: &Client, uri: &str) -> (usize, [String; 3]) {
+        let (a, b, c) = join!(
+            client.get(uri).dispatch(),
+            client.get(uri).dispatch(),
+            client.head(uri).dispatch(),
+        );
+
+        let bodies = [a.into_string().await, b.into_string().await, c.into_string().await]
+            .map(Option::unwrap_or_default);
+
+        (client.rocket().state::<Counter>().unwrap().0.load(Ordering::SeqCst), bodies)
+    }
+
+    #[rocket::async_test]
+    async fn concurrent_misses_are_coalesced() {
+        let client = client(Cache::new()).await;
+        let (count, [a, b, _]) = counts(&client, "/slow?control=max-age%3D60").await;
+        assert_eq!(count, 1);
+        assert_eq!((a.as_str(), b.as_str()), ("1", "1"));
+    }
+
+    #[rocket::async_test]
+    async fn uncacheable_responses_are_fetched_by_each_waiter() {
+        let client = client(Cache::new()).await;
+        let (count, _) = counts(&client, "/slow?control=no-store").await;
+        assert_eq!(count, 3);
+    }
+
+    #[rocket::async_test]
+    async fn waiters_time_out_individually() {
+        let client = client(Cache::new().coalesce_timeout(Duration::from_millis(10))).await;
+        let (count, _) = counts(&client, "/slow?control=max-age%3D60").await;
+        assert_eq!(count, 3);
+
+        let client = client(Cache::new().coalesce_timeout(Duration::ZERO)).await;
+        let (count, _) = counts(&client, "/slow?control=max-age%3D60").await;
+        assert_eq!(count, 3);
+    }
+}
@@ -0,0 +1,113 @@
+#[macro_use] extern crate rocket;
+
+use rocket::http::{Cookie, Header, Status};
+use rocket::local::blocking::Client;
+use rocket::route::Canary;
+
+mod v1 {
+    #[get("/search?<q>")]
+    pub fn search(q: &str) -> String { format!("v1: {q}") }
+
+    #[get("/legacy")]
+    pub fn legacy() -> &'static str { "legacy" }
+}
+
+mod v2 {
+    #[get("/search?<q>")]
+    pub fn search(q: &str) -> String { format!("v2: {q}") }
+
+    #[get("/preview")]
+    pub fn preview() -> &'static str { "preview" }
+}
+
+fn canary() -> Canary {
+    Canary::new("search")
+        .stable(routes![v1::search, v1::legacy])
+        .canary(routes![v2::search, v2::preview])
+        .header("X-Canary")
+}
+
+fn body(client: &Client, uri: &str) -> Option<String> {
+    client.get(uri).dispatch().into_string()
+}
+
+#[test]
+fn routes_are_paired() {
+    let routes: Vec<rocket::Route> = canary().into();
+    assert_eq!(routes.len(), 3);
+
+    // The pair doesn't collide with itself.
+    let client = Client::debug(rocket::build().mount("/api", canary())).unwrap();
+    assert_eq!(client.rocket().routes().count(), 3);
+    assert_eq!(body(&client, "/api/legacy").unwrap(), "legacy");
+}
+
+#[test]
+fn traffic_is_split_by_percent() {
+    let canary = canary();
+    let client = Client::untracked(rocket::build().mount("/", canary.clone())).unwrap();
+    for _ in 0..10 {
+        assert_eq!(body(&client, "/search?q=a").unwrap(), "v1: a");
+    }
+
+    // Canary-only routes aren't served to stable requests.
+    assert_eq!(client.get("/preview").dispatch().status(), Status::NotFound);
+
+    canary.ramp(100);
+    for _ in 0..10 {
+        assert_eq!(body(&client, "/search?q=b").unwrap(), "v2: b");
+    }
+
+    assert_eq!(body(&client, "/preview").unwrap(), "preview");
+}
+
+#[test]
+fn assignment_is_sticky() {
+    let canary = canary().percent(100);
+    let client = Client::tracked(rocket::build().mount("/", canary.clone())).unwrap();
+
+    let response = client.get("/search?q=a").dispatch();
+    let cookie = response.cookies().get("rocket_canary_search").unwrap();
+    assert_eq!(cookie.value(), "canary");
+    assert_eq!(response.into_string().unwrap(), "v2: a");
+
+    // Ramping down doesn't reassign clients that already have a variant.
+    canary.ramp(0);
+    assert_eq!(body(&client, "/search?q=b").unwrap(), "v2: b");
+
+    let client = Client::tracked(rocket::build().mount("/", canary)).unwrap();
+    assert_eq!(body(&client, "/search?q=c").unwrap(), "v1: c");
+    let response = client.get("/search?q=d").dispatch();
+    assert!(response.cookies().get("rocket_canary_search").is_none());
+}
+
+#[test]
+fn header_and_cookie_select_variants() {
+    let client = Client::untracked(rocket::build().mount("/", canary().percent(100))).unwrap();
+    let response = client.get("/search?q=a")
+        .header(Header::new("X-Canary", "stable"))
+        .dispatch();
+
+    assert!(response.cookies().get("rocket_canary_search").is_none());
+    assert_eq!(response.into_string().unwrap(), "v1: a");
+
+    let response = client.get("/search?q=b")
+        .cookie(Cookie::new("rocket_canary_search", "stable"))
+        .dispatch();
+
+    assert_eq!(response.into_string().unwrap(), "v1: b");
+
+    // The header takes precedence over the cookie; unknown values are ignored.
+    let response = client.get("/search?q=c")
+        .header(Header::new("X-Canary", "canary"))
+        .cookie(Cookie::new("rocket_canary_search", "stable"))
+        .dispatch();
+
+    assert_eq!(response.into_string().unwrap(), "v2: c");
+
+    let response = client.get("/search?q=d")
+        .header(Header::new("X-Canary", "other"))
+        .dispatch();
+
+    assert_eq!(response.into_string().unwrap(), "v2: d");
+}
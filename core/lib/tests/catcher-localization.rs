@@ -0,0 +1,91 @@
+#[macro_use] extern crate rocket;
+
+use rocket::Request;
+use rocket::catcher::Catalog;
+use rocket::http::{Accept, Header, Status};
+use rocket::local::blocking::Client;
+
+#[catch(410)]
+fn gone(req: &Request) -> String {
+    let catalog = req.rocket().state::<Catalog>().unwrap();
+    let message = catalog.localize(req, Status::Gone).map_or("gone", |m| &*m.description);
+    format!("{}: {message}", req.locale().unwrap_or("-"))
+}
+
+#[get("/gone")]
+fn removed() -> Status {
+    Status::Gone
+}
+
+#[get("/conflict")]
+fn conflict() -> Status {
+    Status::Conflict
+}
+
+fn client() -> Client {
+    let catalog = Catalog::new()
+        .message("de", 404, "Nicht gefunden", "Die Ressource <b> fehlt.")
+        .message("de", 410, "Verschwunden", "Nicht mehr da.")
+        .message("fr", 404, "Introuvable", "La ressource \"est\" introuvable.");
+
+    let rocket = rocket::build()
+        .manage(catalog)
+        .mount("/", routes![removed, conflict])
+        .register("/", catchers![gone]);
+
+    Client::debug(rocket).unwrap()
+}
+
+#[test]
+fn default_catcher_is_localized() {
+    let client = client();
+    let response = client.get("/nope")
+        .header(Header::new("Accept-Language", "de-CH, en;q=0.5"))
+        .dispatch();
+
+    assert_eq!(response.status(), Status::NotFound);
+    assert_eq!(response.headers().get_one("Content-Language"), Some("de"));
+    let html = response.into_string().unwrap();
+    assert!(html.contains(r#"<html lang="de">"#));
+    assert!(html.contains("404: Nicht gefunden"));
+    assert!(html.contains("Die Ressource &lt;b&gt; fehlt."));
+
+    let response = client.get("/nope")
+        .header(Header::new("Accept-Language", "fr"))
+        .header(Accept::JSON)
+        .dispatch();
+
+    let json = response.into_string().unwrap();
+    assert!(json.contains(r#""code": 404"#));
+    assert!(json.contains(r#""reason": "Introuvable""#));
+    assert!(json.contains(r#""description": "La ressource \"est\" introuvable.""#));
+}
+
+#[test]
+fn unlocalized_errors_use_english() {
+    let client = client();
+    let response = client.get("/nope").dispatch();
+    assert!(response.headers().get_one("Content-Language").is_none());
+    assert!(response.into_string().unwrap().contains("404: Not Found"));
+
+    // There's no `fr` message for a `409`.
+    let response = client.get("/conflict")
+        .header(Header::new("Accept-Language", "ja, fr;q=0.1"))
+        .dispatch();
+
+    assert!(response.headers().get_one("Content-Language").is_none());
+    assert!(response.into_string().unwrap().contains(r#"<html lang="en">"#));
+}
+
+#[test]
+fn user_catchers_see_the_locale() {
+    let client = client();
+    let response = client.get("/gone")
+        .header(Header::new("Accept-Language", "de"))
+        .dispatch();
+
+    assert_eq!(response.into_string().unwrap(), "de: Nicht mehr da.");
+
+    let response = client.get("/gone").header(Header::new("Accept-Language", "fr")).dispatch();
+    assert_eq!(response.into_string().unwrap(), "fr: gone");
+}
@@ -0,0 +1,151 @@
+#![cfg(feature = "compression")]
+
+#[macro_use] extern crate rocket;
+
+use async_compression::tokio::bufread::{BrotliDecoder, GzipDecoder, ZstdDecoder};
+use rocket::compression::Compression;
+use rocket::figment::Figment;
+use rocket::http::{ContentType, Header};
+use rocket::local::asynchronous::{Client, LocalResponse};
+use rocket::tokio::io::AsyncReadExt;
+
+#[derive(Responder)]
+struct WithHeader {
+    body: String,
+    header: Header<'static>,
+}
+
+fn text() -> String {
+    "Rocket compresses responses. ".repeat(100)
+}
+
+#[get("/text")]
+fn plain() -> String {
+    text()
+}
+
+#[get("/small")]
+fn small() -> &'static str {
+    "tiny"
+}
+
+#[get("/image")]
+fn image() -> (ContentType, Vec<u8>) {
+    (ContentType::PNG, vec![7; 4096])
+}
+
+#[get("/json")]
+fn json() -> (ContentType, String) {
+    (ContentType::JSON, format!("[{:?}]", text()))
+}
+
+#[get("/events")]
+fn events() -> (ContentType, String) {
+    (ContentType::EventStream, format!("data: {}\n\n", text()))
+}
+
+#[get("/encoded")]
+fn encoded() -> WithHeader {
+    WithHeader { body: text(), header: Header::new("Content-Encoding", "identity") }
+}
+
+#[get("/tagged")]
+fn tagged() -> WithHeader {
+    WithHeader { body: text(), header: Header::new("ETag", "\"v1\"") }
+}
+
+async fn client(figment: Figment) -> Client {
+    let rocket = rocket::custom(figment)
+        .mount("/", routes![plain, small, image, json, events, encoded, tagged])
+        .attach(Compression::new());
+
+    Client::debug(rocket).await.unwrap()
+}
+
+async fn get<'c>(client: &'c Client, uri: &'static str, accept: &str) -> LocalResponse<'c> {
+    client.get(uri)
+        .header(Header::new("Accept-Encoding", accept.to_string()))
+        .dispatch()
+        .await
+}
+
+async fn decode(response: LocalResponse<'_>) -> String {
+    let encoding = response.headers().get_one("Content-Encoding").map(|e| e.to_string());
+    let bytes = response.into_bytes().await.unwrap();
+    let mut decoded = String::new();
+    match encoding.as_deref() {
+        Some("br") => BrotliDecoder::new(&bytes[..]).read_to_string(&mut decoded).await,
+        Some("zstd") => ZstdDecoder::new(&bytes[..]).read_to_string(&mut decoded).await,
+        Some("gzip") => GzipDecoder::new(&bytes[..]).read_to_string(&mut decoded).await,
+        _ => (&bytes[..]).read_to_string(&mut decoded).await,
+    }.unwrap();
+
+    decoded
+}
+
+#[rocket::async_test]
+async fn encoding_is_negotiated() {
+    let client = client(rocket::Config::figment()).await;
+    let cases = [
+        ("gzip", Some("gzip")),
+        ("gzip, br", Some("br")),
+        ("zstd;q=0.1, gzip;q=0.9", Some("gzip")),
+        ("deflate, ZSTD", Some("zstd")),
+        ("*", Some("br")),
+        ("br;q=0, *", Some("zstd")),
+        ("gzip;q=0, *;q=0", None),
+        ("identity", None),
+        ("", None),
+    ];
+
+    for (accept, expected) in cases {
+        let response = get(&client, "/text", accept).await;
+        let headers = response.headers();
+        assert_eq!(headers.get_one("Content-Encoding"), expected, "{accept:?}");
+        assert_eq!(headers.get_one("Vary"), Some("Accept-Encoding"));
+        assert_eq!(headers.get_one("Content-Length").is_some(), expected.is_none());
+        assert_eq!(decode(response).await, text());
+    }
+}
+
+#[rocket::async_test]
+async fn incompressible_responses_are_untouched() {
+    let client = client(rocket::Config::figment()).await;
+    for uri in ["/small", "/image", "/events", "/encoded"] {
+        let response = get(&client, uri, "gzip").await;
+        assert_ne!(response.headers().get_one("Content-Encoding"), Some("gzip"), "{uri}");
+        assert!(response.headers().get_one("Vary").is_none(), "{uri}");
+        assert!(response.headers().get_one("Content-Length").is_some(), "{uri}");
+    }
+
+    let response = get(&client, "/tagged", "gzip").await;
+    assert_eq!(response.headers().get_one("ETag"), Some("W/\"v1\""));
+    assert_eq!(decode(response).await, text());
+
+    let response = get(&client, "/tagged", "identity").await;
+    assert_eq!(response.headers().get_one("ETag"), Some("\"v1\""));
+}
+
+#[rocket::async_test]
+async fn configuration_is_respected() {
+    let figment = rocket::Config::figment()
+        .merge(("compression.encodings", ["gzip"]))
+        .merge(("compression.types", ["application/*"]))
+        .merge(("compression.min_size", 0));
+
+    let client = client(figment).await;
+    let response = get(&client, "/json", "br, gzip").await;
+    assert_eq!(response.headers().get_one("Content-Encoding"), Some("gzip"));
+    assert_eq!(decode(response).await, format!("[{:?}]", text()));
+
+    let response = get(&client, "/text", "br, gzip").await;
+    assert!(response.headers().get_one("Content-Encoding").is_none());
+
+    let figment = rocket::Config::figment().merge(("compression.types", ["not a type"]));
+    let rocket = rocket::custom(figment).attach(Compression::new());
+    assert!(Client::debug(rocket).await.is_err());
+
+    let figment = rocket::Config::figment().merge(("compression.encodings", ["deflate"]));
+    let rocket = rocket::custom(figment).attach(Compression::new());
+    assert!(Client::debug(rocket).await.is_err());
+}
@@ -0,0 +1,108 @@
+#[macro_use] extern crate rocket;
+
+use rocket::fairing::AdHoc;
+use rocket::figment::Figment;
+use rocket::http::Header;
+use rocket::local::blocking::Client;
+
+#[cfg(feature = "limiter")]
+use rocket::figment::providers::{Format, Toml};
+#[cfg(feature = "limiter")]
+use rocket::http::Status;
+#[cfg(feature = "limiter")]
+use rocket::limiter::{Limit, Limiter, RateLimited};
+
+#[get("/")]
+fn index() -> &'static str {
+    "index"
+}
+
+#[cfg(feature = "limiter")]
+struct Login;
+
+#[cfg(feature = "limiter")]
+impl Limit for Login {
+    const NAME: &'static str = "login";
+}
+
+#[cfg(feature = "limiter")]
+#[post("/login")]
+fn login(_limited: RateLimited<Login>) { }
+
+fn header(name: &'static str) -> AdHoc {
+    AdHoc::on_response(name, move |_, res| Box::pin(async move {
+        res.adjoin_header(Header::new("X-Fairing", name));
+    }))
+}
+
+fn fairings(figment: Figment) -> Vec<String> {
+    let rocket = rocket::custom(figment)
+        .mount("/", routes![index])
+        .attach(header("first"))
+        .attach_if("metrics.enabled", header("metrics"))
+        .attach_when(|figment| figment.contains("admin"), header("admin"))
+        .attach(header("last"));
+
+    let client = Client::debug(rocket).unwrap();
+    let response = client.get("/").dispatch();
+    response.headers().get("X-Fairing").map(String::from).collect()
+}
+
+#[test]
+fn fairings_are_attached_conditionally() {
+    let figment = rocket::Config::figment();
+    assert_eq!(fairings(figment.clone()), ["first", "last"]);
+
+    let enabled = figment.clone().merge(("metrics.enabled", true));
+    assert_eq!(fairings(enabled), ["first", "metrics", "last"]);
+
+    let enabled = figment.clone().merge(("metrics.enabled", true)).merge(("admin.token", "x"));
+    assert_eq!(fairings(enabled), ["first", "metrics", "admin", "last"]);
+
+    let disabled = figment.merge(("metrics.enabled", false)).merge(("admin", 1));
+    assert_eq!(fairings(disabled), ["first", "admin", "last"]);
+}
+
+#[test]
+fn conditional_ignite_fairings_run_only_if_enabled() {
+    let rocket = |enabled: bool| rocket::custom(rocket::Config::figment().merge(("seed", enabled)))
+        .attach_if("seed", AdHoc::on_ignite("Seed", |rocket| async {
+            rocket.manage(42usize)
+        }));
+
+    let client = Client::debug(rocket(true)).unwrap();
+    assert_eq!(client.rocket().state::<usize>(), Some(&42));
+
+    let client = Client::debug(rocket(false)).unwrap();
+    assert!(client.rocket().state::<usize>().is_none());
+}
+
+#[test]
+fn non_boolean_flags_fail_ignition() {
+    let figment = rocket::Config::figment().merge(("metrics.enabled", "yes"));
+    let rocket = rocket::custom(figment).attach_if("metrics.enabled", header("metrics"));
+    assert!(Client::debug(rocket).is_err());
+}
+
+#[test]
+#[cfg(feature = "limiter")]
+fn conditional_fairings_are_retrievable_if_enabled() {
+    let rocket = |enabled: bool| {
+        let figment = rocket::Config::figment()
+            .merge(("limiter", enabled))
+            .merge(Toml::string("rate_limit.login = { requests = 1 }"));
+
+        rocket::custom(figment)
+            .mount("/", routes![login])
+            .attach_if("limiter", Limiter::new())
+    };
+
+    let client = Client::debug(rocket(true)).unwrap();
+    assert!(client.rocket().fairing::<Limiter>().is_some());
+    let login = || client.post("/login").remote("tcp:1.1.1.1:8000").dispatch().status();
+    assert_eq!(login(), Status::Ok);
+    assert_eq!(login(), Status::TooManyRequests);
+
+    let client = Client::debug(rocket(false)).unwrap();
+    assert!(client.rocket().fairing::<Limiter>().is_none());
+}
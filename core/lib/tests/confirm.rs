@@ -0,0 +1,99 @@
+#![cfg(feature = "confirm")]
+
+#[macro_use] extern crate rocket;
+
+use std::time::Duration;
+
+use rocket::auth::confirm::{self, Action, Confirm};
+use rocket::config::SecretKey;
+use rocket::http::{Header, Status};
+use rocket::local::blocking::Client;
+
+struct DeleteUser;
+
+impl Action for DeleteUser {
+    const NAME: &'static str = "delete-user";
+}
+
+struct PurgeCache;
+
+impl Action for PurgeCache {
+    const NAME: &'static str = "purge-cache";
+    const TTL: Duration = Duration::ZERO;
+}
+
+#[get("/users/<id>/delete")]
+fn prepare_delete(key: &SecretKey, id: u64) -> String {
+    Confirm::<DeleteUser>::token(key, &format!("/admin/users/{id}"))
+}
+
+#[delete("/users/<id>")]
+fn delete(confirm: Confirm<DeleteUser>, id: u64) -> String {
+    assert!(confirm.expires_at() > confirm.issued_at());
+    format!("deleted {id}")
+}
+
+#[get("/cache/purge")]
+fn prepare_purge(key: &SecretKey) -> String {
+    Confirm::<PurgeCache>::token(key, "/admin/cache")
+}
+
+#[delete("/cache")]
+fn purge(_confirm: Confirm<PurgeCache>) -> &'static str {
+    "purged"
+}
+
+fn client() -> Client {
+    let routes = routes![prepare_delete, delete, prepare_purge, purge];
+    Client::debug(rocket::build().mount("/admin", routes)).unwrap()
+}
+
+fn token(client: &Client, uri: &str) -> Header<'static> {
+    let token = client.get(uri).dispatch().into_string().unwrap();
+    Header::new(confirm::HEADER, token)
+}
+
+#[test]
+fn confirmed_requests_succeed() {
+    let client = client();
+    let token = token(&client, "/admin/users/7/delete");
+    let response = client.delete("/admin/users/7").header(token).dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.into_string().unwrap(), "deleted 7");
+}
+
+#[test]
+fn unconfirmed_requests_are_refused() {
+    let client = client();
+    let response = client.delete("/admin/users/7").dispatch();
+    assert_eq!(response.status(), Status::PreconditionRequired);
+
+    let response = client.delete("/admin/users/7")
+        .header(Header::new(confirm::HEADER, "not-a-token"))
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Forbidden);
+
+    // Tokens are bound to the path they were issued for...
+    let token = token(&client, "/admin/users/7/delete");
+    let response = client.delete("/admin/users/8").header(token.clone()).dispatch();
+    assert_eq!(response.status(), Status::Forbidden);
+
+    // ...to their action...
+    let response = client.delete("/admin/cache").header(token).dispatch();
+    assert_eq!(response.status(), Status::Forbidden);
+
+    // ...and to the application's secret key.
+    let other = client();
+    let token = token(&other, "/admin/users/7/delete");
+    let response = client.delete("/admin/users/7").header(token).dispatch();
+    assert_eq!(response.status(), Status::Forbidden);
+}
+
+#[test]
+fn expired_tokens_are_refused() {
+    let client = client();
+    let token = token(&client, "/admin/cache/purge");
+    let response = client.delete("/admin/cache").header(token).dispatch();
+    assert_eq!(response.status(), Status::Forbidden);
+}
@@ -0,0 +1,86 @@
+#![cfg(feature = "consumer")]
+
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::Duration;
+
+use rocket::{Rocket, Orbit, Shutdown};
+use rocket::config::Config;
+use rocket::consumer::{self, Consume, Consumer};
+use rocket::fairing::Retry;
+use rocket::listener::tcp::TcpListener;
+use rocket::local::blocking::Client;
+
+struct Flaky {
+    failures: usize,
+    runs: AtomicUsize,
+    drained: Arc<AtomicBool>,
+}
+
+#[rocket::async_trait]
+impl Consumer for Flaky {
+    async fn consume(&self, rocket: &Rocket<Orbit>, shutdown: Shutdown) -> consumer::Result {
+        if self.runs.fetch_add(1, Ordering::SeqCst) < self.failures {
+            return Err("broker unavailable".into());
+        }
+
+        assert_eq!(rocket.state::<&str>(), Some(&"ledger"));
+        rocket.shutdown().notify();
+        shutdown.await;
+        self.drained.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+fn flaky(failures: usize) -> (Flaky, Arc<AtomicBool>) {
+    let drained = Arc::new(AtomicBool::new(false));
+    (Flaky { failures, runs: AtomicUsize::new(0), drained: drained.clone() }, drained)
+}
+
+async fn launch(consumer: Flaky, policy: Retry) -> Rocket<rocket::Ignite> {
+    let rocket = rocket::custom(Config::debug_default())
+        .manage("ledger")
+        .consume("flaky", policy, consumer);
+
+    let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, 0));
+    rocket.try_launch_on(TcpListener::bind(addr)).await.unwrap()
+}
+
+#[rocket::async_test]
+async fn failed_consumers_restart_and_drain() {
+    let (consumer, drained) = flaky(2);
+    let policy = Retry::new().delay(Duration::from_millis(10));
+    let rocket = launch(consumer, policy).await;
+    assert_eq!(rocket.fairing::<Consume>().unwrap().starts(), 3);
+    assert!(drained.load(Ordering::SeqCst));
+}
+
+#[rocket::async_test]
+async fn exhausted_consumers_are_abandoned() {
+    let (consumer, drained) = flaky(usize::MAX);
+    let policy = Retry::new().retries(1).delay(Duration::from_millis(10));
+
+    let rocket = rocket::custom(Config::debug_default())
+        .consume("flaky", policy, consumer)
+        .ignite().await
+        .unwrap();
+
+    let shutdown = rocket.shutdown();
+    rocket::tokio::spawn(async move {
+        rocket::tokio::time::sleep(Duration::from_millis(250)).await;
+        shutdown.notify();
+    });
+
+    let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, 0));
+    let rocket = rocket.try_launch_on(TcpListener::bind(addr)).await.unwrap();
+    assert_eq!(rocket.fairing::<Consume>().unwrap().starts(), 2);
+    assert!(!drained.load(Ordering::SeqCst));
+}
+
+#[test]
+fn local_clients_dont_start_consumers() {
+    let (consumer, _) = flaky(0);
+    let client = Client::debug(rocket::build().consume("flaky", Retry::new(), consumer)).unwrap();
+    assert_eq!(client.rocket().fairing::<Consume>().unwrap().starts(), 0);
+}
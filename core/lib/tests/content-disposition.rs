@@ -0,0 +1,42 @@
+#[macro_use] extern crate rocket;
+
+use rocket::fs::{NamedFile, relative};
+use rocket::http::{ContentDisposition, ContentType};
+use rocket::local::blocking::Client;
+
+#[get("/download")]
+async fn download() -> Option<(ContentDisposition, NamedFile)> {
+    let file = NamedFile::open(relative!("tests/static/other/hello.txt")).await.ok()?;
+    Some(file.attachment())
+}
+
+#[get("/view")]
+async fn view() -> Option<(ContentDisposition, NamedFile)> {
+    let file = NamedFile::open(relative!("tests/static/other/hello.txt")).await.ok()?;
+    Some(file.inline())
+}
+
+#[get("/report")]
+fn report() -> (ContentDisposition, &'static str) {
+    (ContentDisposition::attachment().with_filename("Größe €.csv"), "size\n1")
+}
+
+#[test]
+fn disposition_responders() {
+    let client = Client::debug_with(routes![download, view, report]).unwrap();
+
+    let response = client.get("/download").dispatch();
+    assert_eq!(response.content_type(), Some(ContentType::Plain));
+    assert_eq!(response.headers().get_one("Content-Disposition"),
+        Some("attachment; filename=\"hello.txt\""));
+
+    let response = client.get("/view").dispatch();
+    assert_eq!(response.headers().get_one("Content-Disposition"),
+        Some("inline; filename=\"hello.txt\""));
+
+    let response = client.get("/report").dispatch();
+    assert_eq!(response.headers().get_one("Content-Disposition"),
+        Some(concat!("attachment; filename=\"Gr__e _.csv\"; ",
+            "filename*=UTF-8''Gr%C3%B6%C3%9Fe%20%E2%82%AC.csv")));
+    assert_eq!(response.into_string().unwrap(), "size\n1");
+}
@@ -1,11 +1,65 @@
 #[macro_use]
 extern crate rocket;
 
+use std::io::Cursor;
+
+use rocket::{Request, Response};
+use rocket::http::Status;
+use rocket::fairing::AdHoc;
+use rocket::local::blocking::Client;
+use rocket::response::{self, Responder};
+use rocket::response::stream::ByteStream;
+
 #[get("/")]
 fn index() -> String {
     "Hello, world!".into()
 }
 
+struct Declared(&'static str);
+
+impl<'r> Responder<'r, 'static> for Declared {
+    fn respond_to(self, _: &'r Request<'_>) -> response::Result<'static> {
+        Response::build()
+            .raw_header("Content-Length", self.0.len().to_string())
+            .raw_header("Transfer-Encoding", "chunked")
+            .streamed_body(Cursor::new(self.0))
+            .ok()
+    }
+}
+
+#[get("/declared")]
+fn declared() -> Declared {
+    Declared("streamed with a length")
+}
+
+#[get("/streamed")]
+fn streamed() -> ByteStream![&'static [u8]] {
+    ByteStream! { yield &b"hi"[..]; }
+}
+
+#[get("/empty")]
+fn empty() -> Status {
+    Status::NoContent
+}
+
+fn client() -> Client {
+    let rocket = rocket::build()
+        .mount("/", routes![index, declared, streamed, empty])
+        .attach(AdHoc::on_response("Shout", |req, res| Box::pin(async move {
+            if req.uri().query().map_or(false, |q| q == "shout") {
+                let body = res.body_mut().to_string().await.unwrap();
+                res.set_raw_header("Content-Length", "1");
+                res.set_streamed_body(Cursor::new(body.to_uppercase() + "!"));
+            } else if req.uri().query().map_or(false, |q| q == "declare") {
+                let body = res.body_mut().to_string().await.unwrap() + "!";
+                res.set_streamed_body(Cursor::new(body.clone()));
+                res.body_mut().set_size(body.len());
+            }
+        })));
+
+    Client::debug(rocket).unwrap()
+}
+
 #[test]
 fn content_length_header() {
     let rocket = rocket::build().mount("/", routes![index]);
@@ -13,3 +67,44 @@ fn content_length_header() {
     let response = client.get("/").dispatch();
     assert!(response.headers().get_one("Content-Length").is_some());
 }
+
+#[test]
+fn responder_content_length_is_declared_size() {
+    let client = client();
+    let response = client.get("/declared").dispatch();
+    assert_eq!(response.headers().get_one("Content-Length"), Some("22"));
+    assert!(response.headers().get_one("Transfer-Encoding").is_none());
+    assert_eq!(response.into_string().unwrap(), "streamed with a length");
+
+    let response = client.head("/declared").dispatch();
+    assert_eq!(response.headers().get_one("Content-Length"), Some("22"));
+}
+
+#[test]
+fn transformed_bodies_are_reframed() {
+    let client = client();
+    // Both the responder's and the fairing's lengths are stale.
+    for uri in ["/?shout", "/declared?shout"] {
+        let response = client.get(uri).dispatch();
+        assert!(response.headers().get_one("Content-Length").is_none());
+        assert!(response.into_string().unwrap().ends_with('!'));
+    }
+
+    let response = client.get("/?declare").dispatch();
+    assert_eq!(response.headers().get_one("Content-Length"), Some("14"));
+    assert_eq!(response.into_string().unwrap(), "Hello, world!!");
+}
+
+#[test]
+fn unsized_and_bodiless_responses() {
+    let client = client();
+    let response = client.get("/streamed").dispatch();
+    assert!(response.headers().get_one("Content-Length").is_none());
+
+    let response = client.head("/streamed").dispatch();
+    assert!(response.headers().get_one("Content-Length").is_none());
+
+    let response = client.get("/empty").dispatch();
+    assert_eq!(response.status(), Status::NoContent);
+    assert!(response.headers().get_one("Content-Length").is_none());
+}
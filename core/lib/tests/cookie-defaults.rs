@@ -0,0 +1,39 @@
+#[macro_use] extern crate rocket;
+
+use rocket::http::{Cookie, CookieJar, SameSite};
+use rocket::local::blocking::Client;
+use rocket::figment::providers::{Format, Toml};
+
+#[get("/")]
+fn index(jar: &CookieJar<'_>) {
+    jar.add(("default", "value"));
+    jar.add(Cookie::build(("explicit", "value")).same_site(SameSite::Strict).secure(false));
+}
+
+fn client(toml: &str) -> Client {
+    let figment = rocket::Config::figment().merge(Toml::string(toml));
+    Client::debug(rocket::custom(figment).mount("/", routes![index])).unwrap()
+}
+
+#[test]
+fn builtin_defaults() {
+    let client = client("");
+    let response = client.get("/").dispatch();
+    let cookie = response.cookies().get("default").unwrap();
+    assert_eq!(cookie.same_site(), Some(SameSite::Strict));
+    assert_eq!(cookie.secure(), None);
+}
+
+#[test]
+fn configured_defaults() {
+    let client = client(r#"cookies = { default_same_site = "lax", secure = true }"#);
+    let response = client.get("/").dispatch();
+    let cookie = response.cookies().get("default").unwrap();
+    assert_eq!(cookie.same_site(), Some(SameSite::Lax));
+    assert_eq!(cookie.secure(), Some(true));
+
+    // Attributes set explicitly are not overridden.
+    let cookie = response.cookies().get("explicit").unwrap();
+    assert_eq!(cookie.same_site(), Some(SameSite::Strict));
+    assert_ne!(cookie.secure(), Some(true));
+}
@@ -0,0 +1,200 @@
+#![cfg(feature = "cors")]
+
+#[macro_use] extern crate rocket;
+
+use rocket::cors::Cors;
+use rocket::http::{Header, Method, Status};
+use rocket::figment::Figment;
+use rocket::local::blocking::{Client, LocalResponse};
+
+#[get("/items")]
+fn list() -> &'static str { "[]" }
+
+#[post("/items")]
+fn create() { }
+
+#[get("/items/<_id>")]
+fn item() { }
+
+#[delete("/items/<_id>")]
+fn remove() { }
+
+#[put("/items/<_id>")]
+fn update() { }
+
+#[options("/custom")]
+fn custom() -> &'static str { "custom" }
+
+fn launch(figment: Figment) -> Client {
+    let rocket = rocket::custom(figment)
+        .mount("/", routes![list, create, item, remove, custom])
+        .attach(Cors::new());
+
+    Client::debug(rocket).unwrap()
+}
+
+fn preflight<'c>(client: &'c Client, uri: &'c str, origin: &str) -> LocalResponse<'c> {
+    client.req(Method::Options, uri)
+        .header(Header::new("Origin", origin.to_string()))
+        .header(Header::new("Access-Control-Request-Method", "POST"))
+        .dispatch()
+}
+
+fn cors(client: &Client) -> &Cors {
+    client.rocket().fairing::<Cors>().unwrap()
+}
+
+#[test]
+fn preflight_methods_come_from_routes() {
+    let client = launch(rocket::Config::figment());
+
+    let response = preflight(&client, "/items", "https://a.com");
+    assert_eq!(response.status(), Status::NoContent);
+    let headers = response.headers();
+    assert_eq!(headers.get_one("Access-Control-Allow-Origin"), Some("*"));
+    assert_eq!(headers.get_one("Access-Control-Allow-Methods"), Some("GET, HEAD, POST"));
+    assert!(headers.get_one("Content-Type").is_none());
+
+    let response = preflight(&client, "/items/7", "https://a.com");
+    let methods = response.headers().get_one("Access-Control-Allow-Methods");
+    assert_eq!(methods, Some("DELETE, GET, HEAD"));
+
+    let response = preflight(&client, "/missing", "https://a.com");
+    assert_eq!(response.status(), Status::NotFound);
+    assert!(response.headers().get_one("Access-Control-Allow-Methods").is_none());
+
+    let response = preflight(&client, "/custom", "https://a.com");
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.into_string().unwrap(), "custom");
+}
+
+#[test]
+fn preflights_are_cached_per_uri_and_origin() {
+    let client = launch(rocket::Config::figment());
+
+    preflight(&client, "/items/1", "https://a.com");
+    preflight(&client, "/items/1", "https://b.com");
+    assert_eq!(cors(&client).cached(), 1);
+
+    preflight(&client, "/items/2", "https://a.com");
+    assert_eq!(cors(&client).cached(), 2);
+
+    let client = launch(rocket::Config::figment().merge(("cors.credentials", true)));
+    preflight(&client, "/items/1", "https://a.com");
+    let response = preflight(&client, "/items/1", "https://b.com");
+    assert_eq!(cors(&client).cached(), 2);
+
+    let headers = response.headers();
+    assert_eq!(headers.get_one("Access-Control-Allow-Origin"), Some("https://b.com"));
+    assert_eq!(headers.get_one("Access-Control-Allow-Credentials"), Some("true"));
+    assert_eq!(headers.get_one("Vary"), Some("Origin"));
+}
+
+#[test]
+fn live_route_changes_invalidate_preflights() {
+    let client = launch(rocket::Config::figment());
+    let response = preflight(&client, "/items/1", "https://a.com");
+    let methods = response.headers().get_one("Access-Control-Allow-Methods");
+    assert_eq!(methods, Some("DELETE, GET, HEAD"));
+    assert_eq!(cors(&client).cached(), 1);
+
+    client.rocket().mount_live("/", routes![update]).unwrap();
+    let response = preflight(&client, "/items/1", "https://a.com");
+    let methods = response.headers().get_one("Access-Control-Allow-Methods");
+    assert_eq!(methods, Some("DELETE, GET, HEAD, PUT"));
+    assert_eq!(cors(&client).cached(), 1);
+}
+
+#[test]
+fn simple_requests_and_disallowed_origins() {
+    let figment = rocket::Config::figment()
+        .merge(("cors.origins", ["https://a.com"]))
+        .merge(("cors.expose_headers", ["X-Total-Count"]));
+
+    let rocket = rocket::custom(figment)
+        .mount("/", routes![list, create])
+        .attach(Cors::new());
+
+    let client = Client::debug(rocket).unwrap();
+    let response = client.get("/items").header(Header::new("Origin", "https://A.com")).dispatch();
+    let headers = response.headers();
+    assert_eq!(headers.get_one("Access-Control-Allow-Origin"), Some("https://A.com"));
+    assert_eq!(headers.get_one("Access-Control-Expose-Headers"), Some("X-Total-Count"));
+
+    let response = client.get("/items").header(Header::new("Origin", "https://b.com")).dispatch();
+    assert!(response.headers().get_one("Access-Control-Allow-Origin").is_none());
+
+    let response = preflight(&client, "/items", "https://b.com");
+    assert_eq!(response.status(), Status::NotFound);
+
+    let response = client.get("/items").dispatch();
+    assert!(response.headers().get_one("Access-Control-Allow-Origin").is_none());
+}
+
+#[test]
+fn requested_headers_are_reflected_unless_configured() {
+    let client = launch(rocket::Config::figment());
+    let response = client.req(Method::Options, "/items")
+        .header(Header::new("Origin", "https://a.com"))
+        .header(Header::new("Access-Control-Request-Method", "POST"))
+        .header(Header::new("Access-Control-Request-Headers", "X-Custom"))
+        .dispatch();
+
+    let headers = response.headers();
+    assert_eq!(headers.get_one("Access-Control-Allow-Headers"), Some("X-Custom"));
+    assert_eq!(headers.get_one("Vary"), Some("Access-Control-Request-Headers"));
+
+    let figment = rocket::Config::figment()
+        .merge(("cors.headers", ["Content-Type", "Authorization"]))
+        .merge(("cors.max_age", 60));
+
+    let client = launch(figment);
+    let response = client.req(Method::Options, "/items")
+        .header(Header::new("Origin", "https://a.com"))
+        .header(Header::new("Access-Control-Request-Method", "POST"))
+        .header(Header::new("Access-Control-Request-Headers", "X-Custom"))
+        .dispatch();
+
+    let headers = response.headers();
+    let allowed = headers.get_one("Access-Control-Allow-Headers");
+    assert_eq!(allowed, Some("Content-Type, Authorization"));
+    assert_eq!(headers.get_one("Access-Control-Max-Age"), Some("60"));
+    assert!(headers.get_one("Vary").is_none());
+}
+
+#[test]
+fn configured_methods_restrict_allowed_methods() {
+    let client = launch(rocket::Config::figment().merge(("cors.methods", ["GET", "POST"])));
+
+    let response = preflight(&client, "/items", "https://a.com");
+    assert_eq!(response.status(), Status::NoContent);
+    let methods = response.headers().get_one("Access-Control-Allow-Methods");
+    assert_eq!(methods, Some("GET, POST"));
+
+    let response = preflight(&client, "/items/7", "https://a.com");
+    let methods = response.headers().get_one("Access-Control-Allow-Methods");
+    assert_eq!(methods, Some("GET"));
+
+    let origin = Header::new("Origin", "https://a.com");
+    let response = client.delete("/items/7").header(origin.clone()).dispatch();
+    assert!(response.headers().get_one("Access-Control-Allow-Origin").is_none());
+
+    let response = client.get("/items/7").header(origin).dispatch();
+    assert_eq!(response.headers().get_one("Access-Control-Allow-Origin"), Some("*"));
+
+    let client = launch(rocket::Config::figment().merge(("cors.methods", ["PUT"])));
+    let response = preflight(&client, "/items", "https://a.com");
+    assert_eq!(response.status(), Status::NotFound);
+    assert!(response.headers().get_one("Access-Control-Allow-Methods").is_none());
+}
+
+#[test]
+fn invalid_config_fails_ignition() {
+    let figment = rocket::Config::figment().merge(("cors.origins", 10));
+    let rocket = rocket::custom(figment).attach(Cors::new());
+    assert!(Client::debug(rocket).is_err());
+
+    let figment = rocket::Config::figment().merge(("cors.methods", ["BOGUS"]));
+    let rocket = rocket::custom(figment).attach(Cors::new());
+    assert!(Client::debug(rocket).is_err());
+}
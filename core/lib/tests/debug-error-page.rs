@@ -0,0 +1,58 @@
+#[macro_use] extern crate rocket;
+
+use rocket::http::{Accept, Header, Status};
+use rocket::local::blocking::Client;
+
+#[get("/panic")]
+fn panics() -> &'static str {
+    panic!("<oh no>")
+}
+
+#[get("/status")]
+fn status() -> Status {
+    Status::InternalServerError
+}
+
+fn client() -> Client {
+    Client::debug_with(routes![panics, status]).unwrap()
+}
+
+#[test]
+fn debug_page_shows_panic_handler_and_request() {
+    let client = client();
+    let response = client.get("/panic")
+        .header(Header::new("X-Custom", "visible"))
+        .header(Header::new("Authorization", "Bearer hunter2"))
+        .dispatch();
+
+    assert_eq!(response.status(), Status::InternalServerError);
+    let body = response.into_string().unwrap();
+    assert!(body.contains("handler panicked: &lt;oh no&gt;"), "{body}");
+    assert!(body.contains("<td>panics</td>"), "{body}");
+    assert!(body.contains("debug-error-page.rs"), "{body}");
+    assert!(body.contains("<td>visible</td>"), "{body}");
+    assert!(body.contains("[redacted]"), "{body}");
+    assert!(!body.contains("hunter2"), "{body}");
+}
+
+#[test]
+fn debug_page_without_captured_error() {
+    let client = client();
+    let response = client.get("/status").dispatch();
+    assert_eq!(response.status(), Status::InternalServerError);
+
+    let body = response.into_string().unwrap();
+    assert!(body.contains("No error was captured"), "{body}");
+    assert!(body.contains("<td>GET /status</td>"), "{body}");
+}
+
+#[test]
+fn json_clients_get_default_error() {
+    let client = client();
+    let response = client.get("/panic").header(Accept::JSON).dispatch();
+    assert_eq!(response.status(), Status::InternalServerError);
+
+    let body = response.into_string().unwrap();
+    assert!(body.contains("\"code\": 500"), "{body}");
+    assert!(!body.contains("oh no"), "{body}");
+}
@@ -0,0 +1,61 @@
+#![cfg(feature = "dev")]
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use rocket::dev::{Action, Reloader};
+use rocket::fairing::AdHoc;
+use rocket::figment::Figment;
+use rocket::tokio::sync::{mpsc, oneshot};
+use rocket::tokio::time::timeout;
+
+#[rocket::async_test]
+async fn reloader_invokes_callbacks_and_restarts() {
+    let dir = tempfile::tempdir().unwrap();
+    let watched = dir.path().join("watched");
+    std::fs::create_dir(&watched).unwrap();
+    let trigger = dir.path().join("Rocket.toml");
+
+    let (changes, mut changed) = mpsc::unbounded_channel::<Vec<PathBuf>>();
+    let reloader = Reloader::new()
+        .interval(Duration::from_millis(100))
+        .watch(&watched, move |paths| {
+            let _ = changes.send(paths.to_vec());
+            Action::Continue
+        })
+        .restart_on(&trigger);
+
+    let (report, reported) = oneshot::channel();
+    let figment = Figment::from(rocket::Config::debug_default()).merge(("port", 0));
+    let rocket = rocket::custom(figment)
+        .attach(reloader)
+        .attach(AdHoc::on_liftoff("Touch", {
+            let (watched, trigger) = (watched.clone(), trigger.clone());
+            move |_| Box::pin(async move {
+                rocket::tokio::spawn(async move {
+                    // The reloader takes its first snapshot concurrently with
+                    // this fairing, so create files until a change is seen.
+                    let mut i = 0;
+                    let paths = loop {
+                        std::fs::write(watched.join(format!("{i}.html")), "hi").unwrap();
+                        match timeout(Duration::from_secs(1), changed.recv()).await {
+                            Ok(paths) => break paths.unwrap(),
+                            Err(_) => i += 1,
+                        }
+                    };
+
+                    std::fs::write(&trigger, "").unwrap();
+                    let _ = report.send(paths);
+                });
+            })
+        }));
+
+    let rocket = timeout(Duration::from_secs(30), rocket.launch()).await
+        .expect("reloader restarted the application")
+        .unwrap();
+
+    assert!(Reloader::restart_requested(&rocket));
+    let paths = reported.await.unwrap();
+    assert!(!paths.is_empty());
+    assert!(paths.iter().all(|path| path.parent() == Some(&*watched)), "{paths:?}");
+}
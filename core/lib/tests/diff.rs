@@ -0,0 +1,136 @@
+#![cfg(feature = "diff")]
+
+#[macro_use] extern crate rocket;
+
+use rocket::{Rocket, Build, Config};
+use rocket::diff::Diff;
+use rocket::figment::Figment;
+use rocket::http::Status;
+use rocket::local::asynchronous::Client;
+
+#[get("/<id>")]
+fn legacy_user(id: usize) -> String {
+    format!("user {id}")
+}
+
+#[get("/<id>")]
+fn user(id: usize) -> Option<String> {
+    (id != 13).then(|| format!("user {id}"))
+}
+
+#[get("/greeting")]
+fn legacy_greeting() -> &'static str {
+    "Hello, world!"
+}
+
+#[get("/greeting")]
+fn greeting() -> &'static str {
+    "Hello, World!"
+}
+
+#[post("/<id>")]
+fn update(id: usize) -> String {
+    format!("updated {id}")
+}
+
+fn rocket(diff: Diff) -> Rocket<Build> {
+    rocket::custom(Config::debug_default())
+        .mount("/users", routes![legacy_user, update, legacy_greeting])
+        .mount("/next/users", routes![user, greeting])
+        .attach(diff.candidate("/users", "/next/users").rate(1.0))
+}
+
+#[rocket::async_test]
+async fn matching_and_mismatching_responses_are_counted() {
+    let client = Client::debug(rocket(Diff::new())).await.unwrap();
+    let diff = client.rocket().fairing::<Diff>().unwrap();
+
+    let response = client.get("/users/7").dispatch().await;
+    assert_eq!(response.into_string().await.unwrap(), "user 7");
+    assert_eq!((diff.compared(), diff.mismatched()), (1, 0));
+
+    // The candidate forwards: the statuses differ, but the client is unaffected.
+    let response = client.get("/users/13").dispatch().await;
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.into_string().await.unwrap(), "user 13");
+    assert_eq!((diff.compared(), diff.mismatched()), (2, 1));
+
+    let response = client.get("/users/greeting").dispatch().await;
+    assert_eq!(response.into_string().await.unwrap(), "Hello, world!");
+    assert_eq!((diff.compared(), diff.mismatched()), (3, 2));
+}
+
+#[rocket::async_test]
+async fn ineligible_requests_are_not_compared() {
+    let client = Client::debug(rocket(Diff::new())).await.unwrap();
+    let diff = client.rocket().fairing::<Diff>().unwrap();
+
+    let response = client.post("/users/7").dispatch().await;
+    assert_eq!(response.into_string().await.unwrap(), "updated 7");
+
+    let response = client.get("/usersettings").dispatch().await;
+    assert_eq!(response.status(), Status::NotFound);
+    assert_eq!(diff.compared(), 0);
+
+    let client = Client::debug(rocket(Diff::new().rate(0.0))).await.unwrap();
+    client.get("/users/7").dispatch().await;
+    assert_eq!(client.rocket().fairing::<Diff>().unwrap().compared(), 0);
+}
+
+#[rocket::async_test]
+async fn invalid_config_fails_ignition() {
+    let rocket = rocket(Diff::new().rate(2.0));
+    assert!(rocket.ignite().await.is_err());
+
+    let figment = Figment::from(Config::debug_default())
+        .merge(("diff.candidates.users", "/next/users"));
+
+    let rocket = rocket::custom(figment).attach(Diff::new());
+    assert!(rocket.ignite().await.is_err());
+}
+
+#[cfg(feature = "json")]
+mod json {
+    use super::*;
+    use rocket::http::ContentType;
+    use rocket::serde::json::{json, Value};
+
+    #[get("/")]
+    fn legacy() -> Value {
+        json!({ "id": 1, "tags": ["a", "b"], "name": "Bob" })
+    }
+
+    #[get("/")]
+    fn reordered() -> (ContentType, &'static str) {
+        (ContentType::JSON, r#"{ "name": "Bob", "id": 1, "tags": ["a", "b"] }"#)
+    }
+
+    #[get("/")]
+    fn changed() -> Value {
+        json!({ "id": 1, "tags": ["a", "c"], "name": "Bob" })
+    }
+
+    #[rocket::async_test]
+    async fn json_bodies_are_compared_structurally() {
+        let rocket = rocket::custom(Config::debug_default())
+            .mount("/profile", routes![legacy])
+            .mount("/reordered", routes![reordered])
+            .mount("/changed", routes![changed])
+            .attach(Diff::new().candidate("/profile", "/reordered").rate(1.0));
+
+        let client = Client::debug(rocket).await.unwrap();
+        client.get("/profile").dispatch().await;
+        let diff = client.rocket().fairing::<Diff>().unwrap();
+        assert_eq!((diff.compared(), diff.mismatched()), (1, 0));
+
+        let rocket = rocket::custom(Config::debug_default())
+            .mount("/profile", routes![legacy])
+            .mount("/changed", routes![changed])
+            .attach(Diff::new().candidate("/profile", "/changed").rate(1.0));
+
+        let client = Client::debug(rocket).await.unwrap();
+        client.get("/profile").dispatch().await;
+        let diff = client.rocket().fairing::<Diff>().unwrap();
+        assert_eq!((diff.compared(), diff.mismatched()), (1, 1));
+    }
+}
@@ -0,0 +1,67 @@
+#![cfg(feature = "discovery")]
+
+use std::io;
+
+use parking_lot::Mutex;
+
+use rocket::{Rocket, Orbit};
+use rocket::listener::Endpoint;
+use rocket::local::asynchronous::Client;
+use rocket::discovery::{self, Advertise, Discovery};
+
+#[derive(Default)]
+struct Registry {
+    fail: bool,
+    events: Mutex<Vec<String>>,
+}
+
+#[rocket::async_trait]
+impl Discovery for Registry {
+    async fn register(&self, _: &Rocket<Orbit>, endpoints: &[Endpoint]) -> discovery::Result {
+        if self.fail {
+            return Err(io::Error::other("registry unavailable").into());
+        }
+
+        self.events.lock().extend(endpoints.iter().map(|e| format!("+{e}")));
+        Ok(())
+    }
+
+    async fn deregister(&self, _: &Rocket<Orbit>, endpoints: &[Endpoint]) -> discovery::Result {
+        self.events.lock().extend(endpoints.iter().map(|e| format!("-{e}")));
+        Ok(())
+    }
+}
+
+fn events(rocket: &Rocket<Orbit>) -> Vec<String> {
+    let advertise = rocket.fairing::<Advertise<Registry>>().unwrap();
+    advertise.discovery().events.lock().clone()
+}
+
+#[rocket::async_test]
+async fn registers_at_liftoff_and_deregisters_at_shutdown() {
+    let rocket = rocket::build().attach(Advertise::new(Registry::default()));
+    let client = Client::debug(rocket).await.unwrap();
+
+    let endpoint = client.rocket().endpoints().next().unwrap().to_string();
+    assert_eq!(events(client.rocket()), [format!("+{endpoint}")]);
+
+    let advertise = client.rocket().fairing::<Advertise<Registry>>().unwrap();
+    assert_eq!(advertise.registered().len(), 1);
+
+    let rocket = client.terminate().await;
+    assert_eq!(events(&rocket), [format!("+{endpoint}"), format!("-{endpoint}")]);
+
+    let advertise = rocket.fairing::<Advertise<Registry>>().unwrap();
+    assert!(advertise.registered().is_empty());
+}
+
+#[rocket::async_test]
+async fn does_not_deregister_after_failed_registration() {
+    let registry = Registry { fail: true, ..Default::default() };
+    let rocket = rocket::build().attach(Advertise::new(registry));
+    let client = Client::debug(rocket).await.unwrap();
+    assert!(events(client.rocket()).is_empty());
+
+    let rocket = client.terminate().await;
+    assert!(events(&rocket).is_empty());
+}
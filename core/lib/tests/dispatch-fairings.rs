@@ -0,0 +1,85 @@
+#[macro_use] extern crate rocket;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use rocket::{Rocket, Build, Response};
+use rocket::fairing::AdHoc;
+use rocket::http::{Header, Status};
+use rocket::local::blocking::Client;
+
+static HANDLED: AtomicUsize = AtomicUsize::new(0);
+
+#[get("/<path>")]
+fn index(path: &str) -> String {
+    HANDLED.fetch_add(1, Ordering::SeqCst);
+    path.into()
+}
+
+#[get("/<path>")]
+fn echo(path: &str) -> String {
+    path.into()
+}
+
+#[catch(503)]
+fn unavailable() -> &'static str {
+    "unavailable"
+}
+
+fn rocket() -> Rocket<Build> {
+    let deny = AdHoc::on_dispatch("Deny", |req| Box::pin(async move {
+        (req.uri().path() == "/deny").then_some(Err(Status::ServiceUnavailable))
+    }));
+
+    let answer = AdHoc::on_dispatch("Answer", |req| Box::pin(async move {
+        matches!(req.uri().path().as_str(), "/answer" | "/deny")
+            .then(|| Ok(Response::build().status(Status::Accepted).finalize()))
+    }));
+
+    let tag = AdHoc::on_response("Tag", |_, res| Box::pin(async move {
+        res.set_header(Header::new("X-Tag", "true"));
+    }));
+
+    rocket::build()
+        .mount("/", routes![index])
+        .register("/", catchers![unavailable])
+        .attach(deny)
+        .attach(answer)
+        .attach(tag)
+}
+
+#[test]
+fn dispatch_fairings_answer_without_routing() {
+    let client = Client::debug(rocket()).unwrap();
+    let before = HANDLED.load(Ordering::SeqCst);
+
+    let response = client.get("/answer").dispatch();
+    assert_eq!(response.status(), Status::Accepted);
+    assert_eq!(response.headers().get_one("X-Tag"), Some("true"));
+
+    // The first fairing to answer wins; an error is handled by a catcher.
+    let response = client.get("/deny").dispatch();
+    assert_eq!(response.status(), Status::ServiceUnavailable);
+    assert_eq!(response.headers().get_one("X-Tag"), Some("true"));
+    assert_eq!(response.into_string().unwrap(), "unavailable");
+    assert_eq!(HANDLED.load(Ordering::SeqCst), before);
+
+    let response = client.get("/other").dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.into_string().unwrap(), "other");
+    assert_eq!(HANDLED.load(Ordering::SeqCst), before + 1);
+}
+
+#[test]
+fn scoped_dispatch_fairings_only_answer_under_base() {
+    let answer = AdHoc::on_dispatch("Answer", |_| Box::pin(async move {
+        Some(Err(Status::ServiceUnavailable))
+    }));
+
+    let rocket = rocket::build()
+        .mount("/", routes![echo])
+        .mount_with("/admin", routes![echo], fairings![answer]);
+
+    let client = Client::debug(rocket).unwrap();
+    assert_eq!(client.get("/admin/users").dispatch().status(), Status::ServiceUnavailable);
+    assert_eq!(client.get("/users").dispatch().into_string().unwrap(), "users");
+}
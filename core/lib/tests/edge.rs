@@ -0,0 +1,36 @@
+#![cfg(feature = "edge")]
+
+#[macro_use] extern crate rocket;
+
+use rocket::edge::{Edge, http};
+use rocket::http::CookieJar;
+
+#[post("/echo/<name>", data = "<body>")]
+fn echo(name: &str, body: &str, cookies: &CookieJar<'_>) -> String {
+    let greeting = cookies.get("greeting").map_or("Hello", |c| c.value());
+    format!("{greeting}, {name}: {body}")
+}
+
+#[rocket::async_test]
+async fn edge_requests_are_dispatched() {
+    let edge = Edge::new(rocket::build().mount("/", routes![echo])).await.unwrap();
+
+    let request = http::Request::post("/echo/edge")
+        .header("Cookie", "greeting=Hi")
+        .body(b"ping".to_vec())
+        .unwrap();
+
+    let response = edge.handle(request).await;
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["content-type"], "text/plain; charset=utf-8");
+    assert_eq!(response.body(), b"Hi, edge: ping");
+
+    let request = http::Request::get("/echo/edge").body(vec![]).unwrap();
+    assert_eq!(edge.handle(request).await.status(), 404);
+}
+
+#[rocket::async_test]
+async fn uninitialized_edge_is_unavailable() {
+    let request = http::Request::get("/").body(vec![]).unwrap();
+    assert_eq!(rocket::edge::handle(request).await.status(), 503);
+}
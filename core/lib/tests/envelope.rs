@@ -0,0 +1,108 @@
+#![cfg(feature = "envelope")]
+
+#[macro_use] extern crate rocket;
+
+use rocket::envelope::Envelope;
+use rocket::http::{ContentType, Header, Status};
+use rocket::local::blocking::Client;
+use rocket::serde::json::{json, Json, Value};
+
+#[get("/api/user")]
+fn user() -> Value {
+    json!({ "id": 1, "name": "bob" })
+}
+
+#[get("/api/text")]
+fn text() -> &'static str {
+    "plain"
+}
+
+#[get("/api/conflict")]
+fn conflict() -> (Status, Json<&'static str>) {
+    (Status::Conflict, Json("already exists"))
+}
+
+#[get("/other")]
+fn other() -> Value {
+    json!([1, 2])
+}
+
+fn launch(envelope: Envelope, config: Option<(&str, Value)>) -> Client {
+    let mut figment = rocket::Config::figment();
+    if let Some((key, value)) = config {
+        figment = figment.merge((key, value));
+    }
+
+    let rocket = rocket::custom(figment)
+        .mount("/", routes![user, text, conflict, other])
+        .attach(envelope);
+
+    Client::debug(rocket).unwrap()
+}
+
+fn get(client: &Client, uri: &str) -> (Status, Value) {
+    let response = client.get(uri).header(Header::new("X-Request-Id", "abc")).dispatch();
+    assert_eq!(response.content_type(), Some(ContentType::JSON), "{uri}");
+    (response.status(), response.into_json().unwrap())
+}
+
+#[test]
+fn envelopes_json_responses_in_scope() {
+    let client = launch(Envelope::new().scope("/api"), None);
+
+    let (status, body) = get(&client, "/api/user");
+    assert_eq!(status, Status::Ok);
+    assert_eq!(body["data"], json!({ "id": 1, "name": "bob" }));
+    assert!(body["meta"]["duration_ms"].is_u64());
+
+    let (_, body) = get(&client, "/other");
+    assert_eq!(body, json!([1, 2]));
+
+    let response = client.get("/api/text").dispatch();
+    assert_eq!(response.into_string().unwrap(), "plain");
+}
+
+#[test]
+fn envelopes_errors_consistently() {
+    let client = launch(Envelope::new().scope("/api"), None);
+
+    let (status, body) = get(&client, "/api/conflict");
+    assert_eq!(status, Status::Conflict);
+    assert_eq!(body["error"], json!({
+        "status": 409,
+        "reason": "Conflict",
+        "detail": "already exists"
+    }));
+
+    let (status, body) = get(&client, "/api/missing");
+    assert_eq!(status, Status::NotFound);
+    assert_eq!(body["error"], json!({ "status": 404, "reason": "Not Found", "detail": null }));
+    assert!(body.get("data").is_none());
+
+    let response = client.get("/missing").dispatch();
+    assert_eq!(response.content_type(), Some(ContentType::HTML));
+}
+
+#[test]
+fn configured_scopes_and_request_id() {
+    let config = json!({ "scopes": ["/other"], "request_id_header": "X-Request-Id" });
+    let client = launch(Envelope::new(), Some(("envelope", config.clone())));
+
+    let (_, body) = get(&client, "/other");
+    assert_eq!(body["data"], json!([1, 2]));
+    assert_eq!(body["meta"]["request_id"], "abc");
+
+    let (_, body) = get(&client, "/api/user");
+    assert_eq!(body, json!({ "id": 1, "name": "bob" }));
+
+    let client = launch(Envelope::new().scope("/api"), Some(("envelope", config)));
+    let (_, body) = get(&client, "/api/user");
+    assert_eq!(body["data"]["id"], 1);
+}
+
+#[test]
+fn invalid_config_fails_ignition() {
+    let figment = rocket::Config::figment().merge(("envelope.scopes", 10));
+    let rocket = rocket::custom(figment).attach(Envelope::new());
+    assert!(Client::debug(rocket).is_err());
+}
@@ -0,0 +1,101 @@
+#[macro_use] extern crate rocket;
+
+use std::io;
+use std::net::Ipv4Addr;
+
+use rocket::State;
+use rocket::error::ErrorKind;
+use rocket::fairing::AdHoc;
+use rocket::figment::Figment;
+use rocket::listener::Endpoint;
+
+#[get("/")] fn a() { }
+#[get("/")] fn b() { }
+#[get("/state")] fn state(_n: &State<usize>) { }
+
+#[rocket::async_test]
+async fn collision_accessors() {
+    let error = rocket::custom(rocket::Config::debug_default())
+        .mount("/", routes![a, b])
+        .ignite()
+        .await
+        .unwrap_err();
+
+    assert!(error.is_collision());
+    assert!(!error.is_bind() && !error.is_config() && !error.is_transient());
+    assert!(error.endpoint().is_none() && error.io_error().is_none());
+
+    let (routes, catchers) = error.collisions().unwrap();
+    assert_eq!(routes.len(), 1);
+    assert!(catchers.is_empty());
+}
+
+#[rocket::async_test]
+async fn config_accessors() {
+    let figment = Figment::from(rocket::Config::debug_default())
+        .merge(("workers", "not a number"));
+
+    let error = rocket::custom(figment).ignite().await.unwrap_err();
+    assert!(error.is_config());
+    assert!(error.config_error().is_some());
+    assert!(error.collisions().is_none());
+}
+
+#[rocket::async_test]
+async fn independent_failures_are_aggregated() {
+    let error = rocket::custom(rocket::Config::debug_default())
+        .mount("/", routes![a, b, state])
+        .attach(AdHoc::try_on_ignite("Fails", |rocket| async move { Err(rocket) }))
+        .ignite()
+        .await
+        .unwrap_err();
+
+    assert!(error.is_aggregate() && error.is_collision());
+    assert_eq!(error.sentinel_diagnostics().len(), 1);
+    let kinds: Vec<_> = error.errors().map(|e| e.kind()).collect();
+    assert!(matches!(kinds[..], [
+        ErrorKind::FailedFairings(_),
+        ErrorKind::Collisions { .. },
+        ErrorKind::SentinelAborts(_),
+    ]));
+
+    // Sentinels aren't queried without a valid configuration.
+    let figment = Figment::from(rocket::Config::debug_default())
+        .merge(("workers", "not a number"));
+
+    let error = rocket::custom(figment)
+        .mount("/", routes![a, b, state])
+        .ignite()
+        .await
+        .unwrap_err();
+
+    assert!(error.is_config() && error.is_collision());
+    assert!(error.config_error().is_some() && error.sentinel_diagnostics().is_empty());
+    assert_eq!(error.errors().count(), 2);
+
+    // A single failure isn't aggregated.
+    let error = rocket::custom(rocket::Config::debug_default())
+        .mount("/", routes![state])
+        .ignite()
+        .await
+        .unwrap_err();
+
+    assert!(!error.is_aggregate());
+    assert!(matches!(error.kind(), ErrorKind::SentinelAborts(_)));
+    assert_eq!(error.errors().count(), 1);
+}
+
+#[rocket::async_test]
+async fn bind_accessors() {
+    let taken = rocket::tokio::net::TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+    let addr = taken.local_addr().unwrap();
+    let figment = Figment::from(rocket::Config::debug_default())
+        .merge(("address", addr.ip()))
+        .merge(("port", addr.port()));
+
+    let error = rocket::custom(figment).launch().await.unwrap_err();
+    assert!(error.is_bind());
+    assert!(error.is_transient());
+    assert_eq!(error.endpoint(), Some(&Endpoint::Tcp(addr)));
+    assert_eq!(error.io_error().map(|e| e.kind()), Some(io::ErrorKind::AddrInUse));
+}
@@ -0,0 +1,74 @@
+use std::net::Ipv4Addr;
+
+use rocket::events::Event;
+use rocket::fairing::AdHoc;
+use rocket::figment::Figment;
+
+#[rocket::async_test]
+async fn local_clients_emit_ignition_and_liftoff() {
+    let rocket = rocket::build();
+    let mut events = rocket.events();
+    let _client = rocket::local::asynchronous::Client::debug(rocket).await.unwrap();
+
+    assert!(matches!(events.recv().await, Some(Event::Ignited)));
+    match events.recv().await {
+        Some(Event::Liftoff { endpoints }) => assert_eq!(endpoints.len(), 1),
+        event => panic!("expected liftoff, got {event:?}"),
+    }
+
+    assert!(events.try_recv().is_none());
+}
+
+#[rocket::async_test]
+async fn launches_emit_the_full_lifecycle() {
+    let figment = Figment::from(rocket::Config::debug_default())
+        .merge(("address", Ipv4Addr::LOCALHOST))
+        .merge(("port", 0));
+
+    let rocket = rocket::custom(figment)
+        .attach(AdHoc::on_liftoff("Shutdown", |rocket| Box::pin(async move {
+            rocket.shutdown().notify();
+        })));
+
+    let mut events = rocket.events();
+    let _rocket = rocket.launch().await.unwrap();
+
+    assert!(matches!(events.recv().await, Some(Event::Ignited)));
+    assert!(matches!(events.recv().await, Some(Event::Liftoff { .. })));
+
+    // Shutdown is requested and drained by different tasks, in either order.
+    let (mut requested, mut drained) = (false, false);
+    for _ in 0..2 {
+        match events.recv().await {
+            Some(Event::ShutdownRequested { signal: None }) => requested = true,
+            Some(Event::Drained { graceful: true }) => drained = true,
+            event => panic!("unexpected event: {event:?}"),
+        }
+    }
+
+    assert!(requested && drained);
+}
+
+#[rocket::async_test]
+async fn the_last_open_listener_is_never_closed() {
+    let figment = Figment::from(rocket::Config::debug_default())
+        .merge(("address", Ipv4Addr::LOCALHOST))
+        .merge(("port", 0));
+
+    let rocket = rocket::custom(figment)
+        .attach(AdHoc::on_liftoff("Close", |rocket| Box::pin(async move {
+            assert_eq!(rocket.close_listeners(|_| false), 0);
+            assert_eq!(rocket.close_listeners(|_| true), 0);
+            rocket.shutdown().notify();
+        })));
+
+    let mut events = rocket.events();
+    let _rocket = rocket.launch().await.unwrap();
+
+    assert!(matches!(events.recv().await, Some(Event::Ignited)));
+    assert!(matches!(events.recv().await, Some(Event::Liftoff { .. })));
+    for _ in 0..2 {
+        let event = events.recv().await;
+        assert!(!matches!(event, Some(Event::ListenerClosed { .. })), "{event:?}");
+    }
+}
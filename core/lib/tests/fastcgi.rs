@@ -0,0 +1,99 @@
+#[macro_use] extern crate rocket;
+
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use rocket::fairing::AdHoc;
+use rocket::figment::Figment;
+use rocket::listener::fcgi::FcgiListener;
+use rocket::tokio::io::{AsyncReadExt, AsyncWriteExt};
+use rocket::tokio::net::{TcpListener, TcpStream};
+
+#[post("/echo?<name>", data = "<body>")]
+fn echo(name: &str, body: &str, remote: SocketAddr) -> String {
+    format!("{name} from {}: {body}", remote.ip())
+}
+
+fn record(kind: u8, content: &[u8]) -> Vec<u8> {
+    let len = (content.len() as u16).to_be_bytes();
+    let mut record = vec![1, kind, 0, 1, len[0], len[1], 0, 0];
+    record.extend_from_slice(content);
+    record
+}
+
+fn params(pairs: &[(&str, &str)]) -> Vec<u8> {
+    let mut content = vec![];
+    for (name, value) in pairs {
+        content.extend_from_slice(&[name.len() as u8, value.len() as u8]);
+        content.extend_from_slice(name.as_bytes());
+        content.extend_from_slice(value.as_bytes());
+    }
+
+    content
+}
+
+/// Sends a FastCGI request and returns the CGI output of the response.
+async fn request(stream: &mut TcpStream) -> String {
+    let mut request = record(1, &[0, 1, 0, 0, 0, 0, 0, 0]);
+    request.extend(record(4, &params(&[
+        ("REQUEST_METHOD", "POST"),
+        ("REQUEST_URI", "/echo?name=fcgi"),
+        ("CONTENT_TYPE", "text/plain"),
+        ("CONTENT_LENGTH", "4"),
+        ("HTTP_HOST", "example.com"),
+        ("REMOTE_ADDR", "10.1.2.3"),
+        ("REMOTE_PORT", "4567"),
+    ])));
+
+    request.extend(record(4, &[]));
+    request.extend(record(5, b"ping"));
+    request.extend(record(5, &[]));
+    stream.write_all(&request).await.unwrap();
+
+    let mut output = vec![];
+    loop {
+        let mut header = [0; 8];
+        stream.read_exact(&mut header).await.unwrap();
+        let len = u16::from_be_bytes([header[4], header[5]]) as usize;
+        let mut content = vec![0; len + header[6] as usize];
+        stream.read_exact(&mut content).await.unwrap();
+        match header[1] {
+            6 => output.extend_from_slice(&content[..len]),
+            3 => return String::from_utf8(output).unwrap(),
+            kind => panic!("unexpected record type {kind}"),
+        }
+    }
+}
+
+#[rocket::async_test]
+async fn fastcgi_requests_are_served() {
+    let figment = Figment::from(rocket::Config::debug_default());
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let served = Arc::new(AtomicBool::new(false));
+    let rocket = rocket::custom(figment)
+        .mount("/", routes![echo])
+        .attach(AdHoc::on_liftoff("Client", {
+            let served = served.clone();
+            move |rocket| Box::pin(async move {
+                let shutdown = rocket.shutdown();
+                let exchange = rocket::tokio::spawn(async move {
+                    let mut stream = TcpStream::connect(addr).await.unwrap();
+                    let output = request(&mut stream).await;
+                    assert!(output.starts_with("Status: 200 OK\r\n"), "{output}");
+                    assert!(output.to_ascii_lowercase().contains("content-type: text/plain"));
+                    assert!(output.ends_with("\r\n\r\nfcgi from 10.1.2.3: ping"), "{output}");
+                });
+
+                rocket::tokio::spawn(async move {
+                    served.store(exchange.await.is_ok(), Ordering::Release);
+                    shutdown.notify();
+                });
+            })
+        }));
+
+    rocket.launch_on(FcgiListener::new(listener)).await.unwrap();
+    assert!(served.load(Ordering::Acquire));
+}
@@ -0,0 +1,101 @@
+#[macro_use] extern crate rocket;
+
+use rocket::request::FlashMessage;
+use rocket::response::Flash;
+use rocket::local::blocking::Client;
+
+#[post("/many")]
+fn many() -> Flash<&'static str> {
+    Flash::success("ok", "Saved.").and("warning", "Unverified email.")
+}
+
+#[post("/one")]
+fn one() -> Flash<&'static str> {
+    Flash::error("ok", "Failed.")
+}
+
+#[post("/long")]
+fn long() -> Flash<&'static str> {
+    Flash::warning("ok", "x".repeat(1000))
+}
+
+#[get("/")]
+fn index(flash: Option<FlashMessage<'_>>) -> String {
+    flash.map(|flash| {
+        flash.messages().iter()
+            .map(|m| format!("{}: {}", m.kind(), m.message()))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }).unwrap_or_default()
+}
+
+#[cfg(feature = "json")]
+#[post("/payload")]
+fn payload() -> Flash<&'static str> {
+    Flash::success("ok", "Deleted.").with_payload(&[1, 2, 3]).and("info", "Bye.")
+}
+
+#[cfg(feature = "json")]
+#[get("/payload")]
+fn read_payload(flash: FlashMessage<'_>) -> String {
+    let payloads = flash.messages().iter()
+        .map(|m| m.payload::<Vec<u8>>())
+        .collect::<Vec<_>>();
+
+    format!("{:?}", payloads)
+}
+
+fn client() -> Client {
+    #[allow(unused_mut)]
+    let mut routes = routes![many, one, long, index];
+    #[cfg(feature = "json")]
+    routes.extend(routes![payload, read_payload]);
+    Client::tracked(rocket::build().mount("/", routes)).unwrap()
+}
+
+#[test]
+fn multiple_messages() {
+    let client = client();
+    client.post("/many").dispatch();
+    let response = client.get("/").dispatch();
+    assert_eq!(response.into_string().unwrap(), "success: Saved.\nwarning: Unverified email.");
+    assert_eq!(client.get("/").dispatch().into_string().unwrap(), "");
+}
+
+#[test]
+fn unconsumed_messages_are_queued() {
+    let client = client();
+    client.post("/one").dispatch();
+    client.post("/many").dispatch();
+    let response = client.get("/").dispatch();
+    assert_eq!(response.into_string().unwrap(),
+        "error: Failed.\nsuccess: Saved.\nwarning: Unverified email.");
+
+    // Once consumed, new messages replace the old ones.
+    client.post("/one").dispatch();
+    assert_eq!(client.get("/").dispatch().into_string().unwrap(), "error: Failed.");
+}
+
+#[test]
+fn queued_messages_are_capped() {
+    let client = client();
+    client.post("/one").dispatch();
+    for _ in 0..8 {
+        client.post("/long").dispatch();
+    }
+
+    // The oldest messages are dropped to keep the cookie under 4000 bytes.
+    let response = client.get("/").dispatch().into_string().unwrap();
+    let messages: Vec<_> = response.lines().collect();
+    assert_eq!(messages.len(), 3);
+    assert!(messages.iter().all(|m| m.starts_with("warning: xxx")));
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn typed_payloads() {
+    let client = client();
+    client.post("/payload").dispatch();
+    let response = client.get("/payload").dispatch();
+    assert_eq!(response.into_string().unwrap(), "[Some([1, 2, 3]), None]");
+}
@@ -0,0 +1,112 @@
+#![cfg(feature = "mtls")]
+
+#[macro_use] extern crate rocket;
+
+use rocket::figment::Figment;
+use rocket::http::{Header, Status};
+use rocket::local::blocking::Client;
+use rocket::mtls::{self, Certificate, ForwardedCertConfig};
+
+const CLIENT_PEM: &str = include_str!("../../../examples/tls/private/client.pem");
+
+#[get("/")]
+fn name(cert: mtls::Result<Certificate<'_>>) -> String {
+    match cert {
+        Ok(cert) => cert.subject().common_name().unwrap_or_default().to_string(),
+        Err(e) => format!("error: {e}"),
+    }
+}
+
+/// The client certificate as an RFC 9440 `Client-Cert` header value.
+fn client_cert() -> String {
+    let base64: String = CLIENT_PEM.lines()
+        .skip_while(|line| *line != "-----BEGIN CERTIFICATE-----")
+        .skip(1)
+        .take_while(|line| !line.starts_with("-----END"))
+        .collect();
+
+    format!(":{base64}:")
+}
+
+fn launch(figment: Figment) -> Client {
+    Client::debug(rocket::custom(figment).mount("/", routes![name])).unwrap()
+}
+
+#[test]
+fn trusted_proxies_forward_certificates() {
+    let figment = rocket::Config::figment()
+        .merge(("forwarded_certs.trusted_proxies", ["10.0.0.0/8", "::1"]));
+
+    let client = launch(figment);
+    let response = client.get("/")
+        .remote("tcp:10.1.2.3:443")
+        .header(Header::new("Client-Cert", client_cert()))
+        .dispatch();
+
+    assert_eq!(response.into_string().unwrap(), "Rocket TLS Example");
+
+    let response = client.get("/")
+        .remote("tcp:[::1]:443")
+        .header(Header::new("Client-Cert", client_cert()))
+        .dispatch();
+
+    assert_eq!(response.into_string().unwrap(), "Rocket TLS Example");
+
+    let response = client.get("/")
+        .remote("tcp:10.1.2.3:443")
+        .header(Header::new("Client-Cert", "MIIB:"))
+        .dispatch();
+
+    assert_eq!(response.into_string().unwrap(), "error: malformed forwarded certificate");
+
+    // Without a header, the connection's certificates, here none, are used.
+    let response = client.get("/").remote("tcp:10.1.2.3:443").dispatch();
+    assert_eq!(response.status(), Status::Unauthorized);
+}
+
+#[test]
+fn untrusted_peers_cannot_forward_certificates() {
+    let figment = rocket::Config::figment()
+        .merge(("forwarded_certs.trusted_proxies", ["10.0.0.1"]))
+        .merge(("forwarded_certs.header", "X-Client-Cert"));
+
+    let client = launch(figment);
+    let untrusted = [("tcp:10.0.0.2:443", "X-Client-Cert"), ("tcp:10.0.0.1:443", "Client-Cert")];
+    for (remote, header) in untrusted {
+        let response = client.get("/")
+            .remote(remote)
+            .header(Header::new(header, client_cert()))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Unauthorized);
+    }
+
+    let response = client.get("/")
+        .remote("tcp:10.0.0.1:443")
+        .header(Header::new("X-Client-Cert", client_cert()))
+        .dispatch();
+
+    assert_eq!(response.into_string().unwrap(), "Rocket TLS Example");
+
+    // No proxies are trusted by default.
+    let client = launch(rocket::Config::figment());
+    let response = client.get("/")
+        .remote("tcp:127.0.0.1:443")
+        .header(Header::new("Client-Cert", client_cert()))
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Unauthorized);
+}
+
+#[test]
+fn invalid_configuration_is_rejected() {
+    let config = |figment: Figment| figment.extract::<ForwardedCertConfig>();
+
+    assert!(config(Figment::new().merge(("trusted_proxies", ["10.0.0.0/33"]))).is_err());
+    assert!(config(Figment::new().merge(("trusted_proxies", ["proxy.internal"]))).is_err());
+    assert!(config(Figment::new().merge(("header", "Client Cert"))).is_err());
+
+    let valid = config(Figment::new().merge(("header", "X-Cert"))).unwrap();
+    assert_eq!(valid.header, "X-Cert");
+    assert!(valid.trusted_proxies.is_empty());
+}
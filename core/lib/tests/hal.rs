@@ -0,0 +1,92 @@
+#![cfg(feature = "json")]
+
+#[macro_use] extern crate rocket;
+
+use rocket::http::{ContentType, Status};
+use rocket::local::blocking::Client;
+use rocket::serde::Serialize;
+use rocket::serde::json::{json, Hal, Link, Value};
+
+#[derive(Serialize)]
+#[serde(crate = "rocket::serde")]
+struct Item {
+    id: usize,
+}
+
+#[get("/items/<id>")]
+fn item(id: usize) -> Hal<Item> {
+    Hal::new(Item { id }).link("self", uri!(item(id)))
+}
+
+#[get("/items?<page>")]
+fn items(page: usize) -> Hal<Value> {
+    Hal::new(json!({ "page": page }))
+        .link("self", uri!(items(page)))
+        .link("next", uri!(items(page + 1)))
+        .link("find", Link::new("/items/{id}").templated(true).name("by-id"))
+        .links("curies", Vec::<Link>::new())
+        .embed_all("items", (0..2).map(|id| item(page * 10 + id)))
+}
+
+#[get("/many")]
+fn many() -> Hal<Value> {
+    Hal::new(json!({}))
+        .link("alternate", "/a")
+        .link("alternate", Link::new("/b").media_type("text/html").title("B"))
+        .embed("first", Item { id: 1 })
+        .embed("first", Item { id: 2 })
+}
+
+#[get("/broken")]
+fn broken() -> Hal<Value> {
+    let unserializable = std::collections::BTreeMap::from([(vec![1u8], 1)]);
+    Hal::new(json!({})).embed("ok", Item { id: 1 }).embed("broken", unserializable)
+}
+
+#[test]
+fn hal_resources() {
+    let client = Client::debug_with(routes![item, items, many]).unwrap();
+
+    let response = client.get("/items/3").dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.content_type(), Some(ContentType::new("application", "hal+json")));
+    assert_eq!(response.into_json::<Value>().unwrap(), json!({
+        "id": 3,
+        "_links": { "self": { "href": "/items/3" } }
+    }));
+
+    let response = client.get("/items?page=1").dispatch();
+    assert_eq!(response.into_json::<Value>().unwrap(), json!({
+        "page": 1,
+        "_links": {
+            "self": { "href": "/items?page=1" },
+            "next": { "href": "/items?page=2" },
+            "find": { "href": "/items/{id}", "templated": true, "name": "by-id" },
+            "curies": []
+        },
+        "_embedded": {
+            "items": [
+                { "id": 10, "_links": { "self": { "href": "/items/10" } } },
+                { "id": 11, "_links": { "self": { "href": "/items/11" } } }
+            ]
+        }
+    }));
+
+    let response = client.get("/many").dispatch();
+    assert_eq!(response.into_json::<Value>().unwrap(), json!({
+        "_links": {
+            "alternate": [
+                { "href": "/a" },
+                { "href": "/b", "type": "text/html", "title": "B" }
+            ]
+        },
+        "_embedded": { "first": [{ "id": 1 }, { "id": 2 }] }
+    }));
+}
+
+#[test]
+fn unserializable_embedded_resources_fail_to_respond() {
+    let client = Client::debug_with(routes![broken]).unwrap();
+    let response = client.get("/broken").dispatch();
+    assert_eq!(response.status(), Status::InternalServerError);
+}
@@ -0,0 +1,85 @@
+#![cfg(all(unix, feature = "handoff"))]
+
+#[macro_use] extern crate rocket;
+
+use std::net::{Ipv4Addr, SocketAddr};
+use std::path::Path;
+
+use rocket::{Rocket, Build, State};
+use rocket::events::Event;
+use rocket::figment::Figment;
+use rocket::handoff::Handoff;
+use rocket::tokio::io::{AsyncReadExt, AsyncWriteExt};
+use rocket::tokio::net::TcpStream;
+
+#[get("/")]
+fn index(name: &State<&'static str>) -> &'static str {
+    name
+}
+
+fn build(name: &'static str, port: u16, control: &Path) -> Rocket<Build> {
+    let figment = Figment::from(rocket::Config::debug_default())
+        .merge(("address", Ipv4Addr::LOCALHOST))
+        .merge(("port", port));
+
+    rocket::custom(figment)
+        .mount("/", routes![index])
+        .manage(name)
+        .attach(Handoff::new(control))
+}
+
+async fn liftoff(rocket: Rocket<Build>) -> SocketAddr {
+    let mut events = rocket.events();
+    rocket::tokio::spawn(rocket.launch());
+    loop {
+        match events.recv().await {
+            Some(Event::Liftoff { endpoints }) => return endpoints[0].tcp().unwrap(),
+            Some(_) => continue,
+            None => panic!("rocket failed to launch"),
+        }
+    }
+}
+
+async fn get(addr: SocketAddr) -> String {
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    stream.write_all(b"GET / HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+        .await.unwrap();
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    response
+}
+
+#[rocket::async_test]
+async fn new_process_takes_over_listeners() {
+    let control = std::env::temp_dir().join(format!("rocket-handoff-{}", std::process::id()));
+    let _ = std::fs::remove_file(&control);
+
+    let old = build("old", 0, &control);
+    let mut old_events = old.events();
+    let addr = liftoff(old).await;
+    assert!(get(addr).await.ends_with("old"));
+
+    let new_addr = liftoff(build("new", addr.port(), &control)).await;
+    assert_eq!(new_addr, addr);
+    assert!(get(addr).await.ends_with("new"));
+
+    let drained = loop {
+        match old_events.recv().await {
+            Some(Event::Drained { graceful }) => break graceful,
+            Some(_) => continue,
+            None => panic!("old rocket never drained"),
+        }
+    };
+
+    assert!(drained);
+}
+
+#[rocket::async_test]
+async fn missing_control_socket_binds_as_usual() {
+    let control = std::env::temp_dir().join("rocket-handoff-missing.sock");
+    let _ = std::fs::remove_file(&control);
+
+    let addr = liftoff(build("only", 0, &control)).await;
+    assert!(get(addr).await.ends_with("only"));
+}
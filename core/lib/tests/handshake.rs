@@ -0,0 +1,88 @@
+#![cfg(feature = "handshake")]
+
+#[macro_use] extern crate rocket;
+
+use rocket::http::{Header, Status};
+use rocket::local::blocking::Client;
+use rocket::handshake::{TokenAuth, TokenValidator, Principal, Authenticated};
+
+struct Tokens;
+
+#[rocket::async_trait]
+impl TokenValidator for Tokens {
+    async fn validate(&self, token: &str) -> Option<Principal> {
+        (token == "secret").then(|| Principal::new("alice").scope("events"))
+    }
+}
+
+#[get("/events")]
+fn events(auth: Authenticated) -> String {
+    let principal = auth.into_principal();
+    format!("{}:{}", principal.id(), principal.scopes().collect::<Vec<_>>().join(","))
+}
+
+fn launch(auth: Option<TokenAuth>) -> Client {
+    let rocket = rocket::build().mount("/", routes![events]);
+    match auth {
+        Some(auth) => Client::debug(rocket.manage(auth)).unwrap(),
+        None => Client::debug(rocket).unwrap(),
+    }
+}
+
+#[test]
+fn tokens_are_read_from_query_protocol_and_header() {
+    let client = launch(Some(TokenAuth::new(Tokens)));
+    let response = client.get("/events?access_token=secret").dispatch();
+    assert_eq!(response.into_string().unwrap(), "alice:events");
+
+    let response = client.get("/events")
+        .header(Header::new("Sec-WebSocket-Protocol", "chat, bearer, secret"))
+        .dispatch();
+
+    assert_eq!(response.into_string().unwrap(), "alice:events");
+
+    let response = client.get("/events")
+        .header(Header::new("Authorization", "Bearer secret"))
+        .dispatch();
+
+    assert_eq!(response.into_string().unwrap(), "alice:events");
+}
+
+#[test]
+fn missing_or_invalid_tokens_are_unauthorized() {
+    let client = launch(Some(TokenAuth::new(Tokens)));
+    assert_eq!(client.get("/events").dispatch().status(), Status::Unauthorized);
+
+    let response = client.get("/events?access_token=wrong").dispatch();
+    assert_eq!(response.status(), Status::Unauthorized);
+
+    let response = client.get("/events")
+        .header(Header::new("Sec-WebSocket-Protocol", "bearer"))
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Unauthorized);
+}
+
+#[test]
+fn token_sources_are_configurable() {
+    let auth = TokenAuth::new(Tokens).query(Some("token")).protocol(None::<String>);
+    let client = launch(Some(auth));
+    let response = client.get("/events?token=secret").dispatch();
+    assert_eq!(response.status(), Status::Ok);
+
+    let response = client.get("/events?access_token=secret").dispatch();
+    assert_eq!(response.status(), Status::Unauthorized);
+
+    let response = client.get("/events")
+        .header(Header::new("Sec-WebSocket-Protocol", "bearer, secret"))
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Unauthorized);
+}
+
+#[test]
+fn unmanaged_token_auth_is_an_error() {
+    let client = launch(None);
+    let response = client.get("/events?access_token=secret").dispatch();
+    assert_eq!(response.status(), Status::InternalServerError);
+}
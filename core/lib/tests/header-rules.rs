@@ -0,0 +1,64 @@
+#[macro_use] extern crate rocket;
+
+use rocket::Config;
+use rocket::http::{Accept, Status};
+use rocket::local::blocking::Client;
+use rocket::figment::providers::{Format, Toml};
+use rocket::shield::{HeaderRules, HeaderRule, HeaderActions};
+
+#[get("/<_..>")]
+fn accept(accept: Option<&Accept>) -> String {
+    accept.map_or("none".into(), |accept| accept.to_string())
+}
+
+fn client(toml: &str, rules: HeaderRules) -> Result<Client, rocket::Error> {
+    let figment = Config::figment().merge(Toml::string(toml));
+    Client::debug(rocket::custom(figment).mount("/", routes![accept]).attach(rules))
+}
+
+#[test]
+fn configured_rules_apply_per_path() {
+    let client = client(r#"
+        headers = [
+            { path = "/app", response.set = { "X-Frame-Options" = "DENY" } },
+            { path = "/", response.remove = ["Server"] },
+        ]
+    "#, HeaderRules::new()).unwrap();
+
+    let response = client.get("/app/login").dispatch();
+    assert_eq!(response.headers().get_one("X-Frame-Options"), Some("DENY"));
+    assert!(!response.headers().contains("Server"));
+
+    let response = client.get("/apple").dispatch();
+    assert_ne!(response.headers().get_one("X-Frame-Options"), Some("DENY"));
+    assert!(!response.headers().contains("Server"));
+}
+
+#[test]
+fn programmatic_request_rules() {
+    let rules = HeaderRules::new()
+        .rule(HeaderRule::new("/api")
+            .request(HeaderActions::new().set_default("Accept", "application/json")))
+        .rule(HeaderRule::new("/forced")
+            .request(HeaderActions::new().set("Accept", "text/plain")));
+
+    let client = client("", rules).unwrap();
+    assert_eq!(client.get("/api/x").dispatch().into_string().unwrap(), "application/json");
+    assert_eq!(client.get("/other").dispatch().into_string().unwrap(), "none");
+
+    let response = client.get("/api").header(Accept::HTML).dispatch();
+    assert_eq!(response.into_string().unwrap(), "text/html");
+
+    let response = client.get("/forced").header(Accept::HTML).dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.into_string().unwrap(), "text/plain");
+}
+
+#[test]
+fn invalid_rules_fail_ignition() {
+    let toml = r#"headers = [{ path = "/", response.set = { "Bad Name" = "x" } }]"#;
+    assert!(client(toml, HeaderRules::new()).is_err());
+
+    let toml = r#"headers = "nope""#;
+    assert!(client(toml, HeaderRules::new()).is_err());
+}
@@ -0,0 +1,96 @@
+#[macro_use] extern crate rocket;
+
+use rocket::{Rocket, Build};
+use rocket::http::{Header, Status};
+use rocket::http::uri::Host as RawHost;
+use rocket::local::blocking::Client;
+use rocket::request::{AbsoluteUri, Host};
+
+#[get("/host")]
+fn host(host: Host<'_>) -> String {
+    host.to_string()
+}
+
+#[get("/reset?<token>")]
+fn reset(token: &str) -> &str {
+    token
+}
+
+#[post("/forgot")]
+fn forgot(absolute: AbsoluteUri<'_>) -> String {
+    let base = absolute.base();
+    uri!(base, reset("s3cr3t")).to_string()
+}
+
+fn rocket() -> Rocket<Build> {
+    let figment = rocket::Config::figment()
+        .merge(("hosts.allow", ["example.com", "*.example.com:8443"]))
+        .merge(("hosts.forwarded_header", "X-Forwarded-Host"))
+        .merge(("proxy_proto_header", "X-Forwarded-Proto"));
+
+    rocket::custom(figment).mount("/", routes![host, reset, forgot])
+}
+
+#[test]
+fn allowed_hosts_pass() {
+    let client = Client::debug(rocket()).unwrap();
+
+    let mut req = client.get("/host");
+    req.inner_mut().set_host(RawHost::from(uri!("EXAMPLE.com:8000")));
+    assert_eq!(req.dispatch().into_string().unwrap(), "EXAMPLE.com:8000");
+
+    let mut req = client.get("/host");
+    req.inner_mut().set_host(RawHost::from(uri!("api.example.com:8443")));
+    assert_eq!(req.dispatch().into_string().unwrap(), "api.example.com:8443");
+}
+
+#[test]
+fn disallowed_and_missing_hosts_fail() {
+    let client = Client::debug(rocket()).unwrap();
+
+    let mut req = client.get("/host");
+    req.inner_mut().set_host(RawHost::from(uri!("attacker.com")));
+    assert_eq!(req.dispatch().status(), Status::BadRequest);
+
+    let mut req = client.get("/host");
+    req.inner_mut().set_host(RawHost::from(uri!("api.example.com")));
+    assert_eq!(req.dispatch().status(), Status::BadRequest);
+
+    let mut req = client.get("/host");
+    req.inner_mut().set_host(RawHost::from(uri!("example.com.attacker.com")));
+    assert_eq!(req.dispatch().status(), Status::BadRequest);
+
+    assert_eq!(client.get("/host").dispatch().status(), Status::BadRequest);
+}
+
+#[test]
+fn forwarded_hosts_take_precedence() {
+    let client = Client::debug(rocket()).unwrap();
+
+    let mut req = client.get("/host").header(Header::new("X-Forwarded-Host", "example.com"));
+    req.inner_mut().set_host(RawHost::from(uri!("internal:8000")));
+    assert_eq!(req.dispatch().into_string().unwrap(), "example.com");
+
+    let mut req = client.get("/host").header(Header::new("X-Forwarded-Host", "attacker.com"));
+    req.inner_mut().set_host(RawHost::from(uri!("example.com")));
+    assert_eq!(req.dispatch().status(), Status::BadRequest);
+
+    let req = client.get("/host").header(Header::new("X-Forwarded-Host", "exa mple.com"));
+    assert_eq!(req.dispatch().status(), Status::BadRequest);
+}
+
+#[test]
+fn absolute_uris_use_validated_host_and_scheme() {
+    let client = Client::debug(rocket()).unwrap();
+
+    let req = client.post("/forgot").header(Header::new("X-Forwarded-Host", "example.com"));
+    assert_eq!(req.dispatch().into_string().unwrap(), "http://example.com/reset?token=s3cr3t");
+
+    let req = client.post("/forgot")
+        .header(Header::new("X-Forwarded-Host", "example.com"))
+        .header(Header::new("X-Forwarded-Proto", "https"));
+    assert_eq!(req.dispatch().into_string().unwrap(), "https://example.com/reset?token=s3cr3t");
+
+    let req = client.post("/forgot").header(Header::new("X-Forwarded-Host", "attacker.com"));
+    assert_eq!(req.dispatch().status(), Status::BadRequest);
+}
@@ -0,0 +1,101 @@
+#![cfg(feature = "inspector")]
+
+#[macro_use] extern crate rocket;
+
+use rocket::http::{Accept, Header, Status};
+use rocket::inspector::Inspector;
+use rocket::local::blocking::Client;
+
+#[get("/hello/<n>")]
+fn hello(n: usize) -> String {
+    format!("hello {n}")
+}
+
+fn client(inspector: Inspector) -> Client {
+    let rocket = rocket::build().mount("/", routes![hello]).attach(inspector);
+    Client::debug(rocket).unwrap()
+}
+
+#[test]
+fn records_recent_requests() {
+    let client = client(Inspector::new());
+    client.get("/hello/1").header(Header::new("Cookie", "secret=hunter2")).dispatch();
+    client.get("/hello/nope").dispatch();
+
+    let response = client.get(Inspector::PATH).header(Accept::JSON).dispatch();
+    assert_eq!(response.status(), Status::Ok);
+
+    let body = response.into_string().unwrap();
+    assert!(body.starts_with("[{\"id\":1,\"method\":\"GET\",\"uri\":\"/hello/nope\""), "{body}");
+    assert!(body.contains("\"status\":422"), "{body}");
+    assert!(body.contains("path guard `n: usize` forwarded (422)"), "{body}");
+    assert!(body.contains("\"uri\":\"/hello/1\",\"route\":\"hello (GET /hello/<n>)\""), "{body}");
+    assert!(body.contains("[\"Cookie\",\"[redacted]\"]"), "{body}");
+    assert!(!body.contains("hunter2"), "{body}");
+    assert!(!body.contains("_rocket"), "{body}");
+
+    let response = client.get(Inspector::PATH).dispatch();
+    let body = response.into_string().unwrap();
+    assert!(body.contains("#1 GET /hello/nope &rarr; 422"), "{body}");
+    assert!(body.contains("#0 GET /hello/1 &rarr; 200"), "{body}");
+}
+
+#[test]
+fn keeps_only_most_recent() {
+    let client = client(Inspector::new().capacity(2));
+    for i in 0..5 {
+        client.get(format!("/hello/{i}")).dispatch();
+    }
+
+    let response = client.get(Inspector::PATH).header(Accept::JSON).dispatch();
+    let body = response.into_string().unwrap();
+    assert!(body.contains("/hello/4") && body.contains("/hello/3"), "{body}");
+    assert!(!body.contains("/hello/2"), "{body}");
+}
+
+#[get("/slow")]
+fn slow(_method: rocket::http::Method) -> &'static str {
+    std::thread::sleep(std::time::Duration::from_millis(2));
+    "done"
+}
+
+#[test]
+fn records_sampled_pipelines() {
+    let rocket = rocket::build()
+        .mount("/", routes![slow])
+        .attach(Inspector::new().pipeline(1.0))
+        .attach(rocket::fairing::AdHoc::on_request("Nop", |_, _| Box::pin(async {})))
+        .attach(rocket::fairing::AdHoc::on_dispatch("Pass", |_| Box::pin(async { None })));
+
+    let client = Client::debug(rocket).unwrap();
+    client.get("/slow").dispatch();
+
+    let response = client.get(Inspector::PATH).header(Accept::JSON).dispatch();
+    let body = response.into_string().unwrap();
+    assert!(body.contains("{\"kind\":\"request\",\"name\":\"Nop\",\"parent\":null"), "{body}");
+    assert!(body.contains("{\"kind\":\"dispatch\",\"name\":\"Pass\",\"parent\":null"), "{body}");
+    assert!(body.contains("{\"kind\":\"route\",\"name\":\"GET /slow\",\"parent\":null"), "{body}");
+    assert!(body.contains("{\"kind\":\"guard\",\"name\":\"_method: rocket::http::Method\""),
+        "{body}");
+    assert!(body.contains("{\"kind\":\"handler\",\"name\":\"slow\""), "{body}");
+    assert!(body.contains("{\"kind\":\"response\",\"name\":\"Inspector\""), "{body}");
+
+    let response = client.get(format!("{}?folded", Inspector::PATH)).dispatch();
+    assert_eq!(response.content_type(), Some(rocket::http::ContentType::Plain));
+    let body = response.into_string().unwrap();
+    assert!(body.lines().any(|l| l.starts_with("GET /slow;route GET /slow;handler slow ")),
+        "{body}");
+}
+
+#[test]
+fn pipelines_are_not_recorded_by_default() {
+    let client = client(Inspector::new());
+    client.get("/hello/1").dispatch();
+
+    let response = client.get(Inspector::PATH).header(Accept::JSON).dispatch();
+    let body = response.into_string().unwrap();
+    assert!(body.contains("\"pipeline\":[]"), "{body}");
+    assert!(client.get(format!("{}?folded", Inspector::PATH)).dispatch().into_string()
+        .unwrap()
+        .is_empty());
+}
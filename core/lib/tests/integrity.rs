@@ -0,0 +1,101 @@
+#![cfg(feature = "integrity")]
+
+use std::fs;
+use std::path::Path;
+
+use rocket::Config;
+use rocket::figment::Figment;
+use rocket::http::{ContentType, Status};
+use rocket::integrity::Integrity;
+use rocket::local::blocking::Client;
+
+const APP_JS_SRI: &str = "sha384-05ppHfj5uUjTrkhigMzhTN1E3gbaEYzbkhXj9PeB826jenLRpBDHbtzVoINFRCvL";
+
+fn write(root: &Path, path: &str, contents: &str) {
+    let path = root.join(path);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, contents).unwrap();
+}
+
+fn fixture() -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    write(dir.path(), "static/app.js", "console.log(1);\n");
+    write(dir.path(), "static/css/site.css", "body {}\n");
+    write(dir.path(), "static/.secret", "hidden");
+    write(dir.path(), "templates/index.html.tera", "{{ name }}");
+    dir
+}
+
+#[test]
+fn manifest_is_computed_at_ignition() {
+    let dir = fixture();
+    let integrity = Integrity::new()
+        .assets("/static/", dir.path().join("static"))
+        .templates(dir.path().join("templates"));
+
+    let client = Client::debug(rocket::build().attach(integrity)).unwrap();
+    let manifest = client.rocket().fairing::<Integrity>().unwrap().manifest().unwrap();
+    assert_eq!(manifest.asset("/static/app.js"), Some(APP_JS_SRI));
+    assert!(manifest.asset("/static/css/site.css").unwrap().starts_with("sha384-"));
+    assert!(manifest.asset("/static/.secret").is_none());
+    assert_eq!(manifest.assets().count(), 2);
+
+    let templates: Vec<_> = manifest.templates().map(|(path, _)| path).collect();
+    assert_eq!(templates, ["index.html.tera"]);
+    assert!(manifest.fingerprint().starts_with("sha384-"));
+
+    // The endpoint is disabled by default.
+    assert_eq!(client.get(Integrity::PATH).dispatch().status(), Status::NotFound);
+}
+
+#[test]
+fn fingerprint_changes_with_contents() {
+    let dir = fixture();
+    let fingerprint = || {
+        let integrity = Integrity::new().assets("/static", dir.path().join("static"));
+        let client = Client::debug(rocket::build().attach(integrity)).unwrap();
+        let integrity = client.rocket().fairing::<Integrity>().unwrap();
+        integrity.manifest().unwrap().fingerprint().to_string()
+    };
+
+    let before = fingerprint();
+    assert_eq!(before, fingerprint());
+
+    write(dir.path(), "static/app.js", "console.log(2);\n");
+    assert_ne!(before, fingerprint());
+}
+
+#[test]
+fn templates_default_to_template_dir() {
+    let dir = fixture();
+    let figment = Figment::from(Config::debug_default())
+        .merge(("template_dir", dir.path().join("templates")));
+
+    let client = Client::debug(rocket::custom(figment).attach(Integrity::new())).unwrap();
+    let manifest = client.rocket().fairing::<Integrity>().unwrap().manifest().unwrap();
+    assert!(manifest.template("index.html.tera").is_some());
+}
+
+#[test]
+fn endpoint_serves_manifest() {
+    let dir = fixture();
+    let integrity = Integrity::new()
+        .assets("/static", dir.path().join("static"))
+        .endpoint(true);
+
+    let client = Client::debug(rocket::build().attach(integrity)).unwrap();
+    let response = client.get(Integrity::PATH).dispatch();
+    assert_eq!(response.content_type(), Some(ContentType::JSON));
+
+    let body = response.into_string().unwrap();
+    assert!(body.starts_with("{\"fingerprint\":\"sha384-"));
+    assert!(body.contains(&format!("\"/static/app.js\":\"{APP_JS_SRI}\"")));
+    assert!(body.ends_with(",\"templates\":{}}"));
+}
+
+#[test]
+fn unreadable_directory_fails_ignition() {
+    let dir = fixture();
+    let integrity = Integrity::new().assets("/static", dir.path().join("missing"));
+    assert!(Client::debug(rocket::build().attach(integrity)).is_err());
+}
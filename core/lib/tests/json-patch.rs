@@ -0,0 +1,168 @@
+#![cfg(feature = "json")]
+
+#[macro_use] extern crate rocket;
+
+use rocket::http::{ContentType, Status};
+use rocket::local::blocking::Client;
+use rocket::serde::{Serialize, Deserialize};
+use rocket::serde::json::{json, Json, JsonPatch, MergePatch, PatchError, Value};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(crate = "rocket::serde")]
+struct User {
+    name: String,
+    email: Option<String>,
+    tags: Vec<String>,
+}
+
+fn user() -> User {
+    User { name: "bob".into(), email: Some("bob@rocket.rs".into()), tags: vec!["a".into()] }
+}
+
+#[patch("/json", data = "<patch>")]
+fn json_patch(patch: JsonPatch<User>) -> Result<Json<User>, (Status, String)> {
+    patch.apply(&user()).map(Json).map_err(|e| (Status::Conflict, e.to_string()))
+}
+
+#[patch("/merge", data = "<patch>")]
+fn merge_patch(patch: MergePatch<User>) -> Result<Json<User>, Status> {
+    patch.apply(&user()).map(Json).map_err(|_| Status::UnprocessableEntity)
+}
+
+fn client() -> Client {
+    Client::debug_with(routes![json_patch, merge_patch]).unwrap()
+}
+
+fn patch(operations: Value) -> JsonPatch<Value> {
+    JsonPatch::from_value(operations).unwrap()
+}
+
+#[test]
+fn json_patch_guard() {
+    let client = client();
+    let response = client.patch("/json")
+        .header(ContentType::new("application", "json-patch+json"))
+        .body(r#"[
+            { "op": "replace", "path": "/name", "value": "alice" },
+            { "op": "remove", "path": "/email" },
+            { "op": "add", "path": "/email", "value": null },
+            { "op": "add", "path": "/tags/0", "value": "b" },
+            { "op": "copy", "from": "/tags/1", "path": "/tags/-" }
+        ]"#)
+        .dispatch();
+
+    let tags = vec!["b".into(), "a".into(), "a".into()];
+    let expected = User { name: "alice".into(), email: None, tags };
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.into_json::<User>().unwrap(), expected);
+
+    let response = client.patch("/json")
+        .body(r#"[{ "op": "remove", "path": "/missing" }]"#)
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Conflict);
+    assert_eq!(response.into_string().unwrap(), r#"operation 0: path "/missing" does not exist"#);
+
+    for invalid in [
+        r#"[{ "op": "frobnicate", "path": "/name" }]"#,
+        r#"[{ "op": "add", "path": "/name" }]"#,
+        r#"[{ "op": "remove", "path": "name" }]"#,
+        r#"[{ "op": "remove", "path": "/name~2" }]"#,
+    ] {
+        let response = client.patch("/json").body(invalid).dispatch();
+        assert_eq!(response.status(), Status::UnprocessableEntity, "{invalid}");
+    }
+}
+
+#[test]
+fn merge_patch_guard() {
+    let client = client();
+    let response = client.patch("/merge")
+        .header(ContentType::new("application", "merge-patch+json"))
+        .body(r#"{ "email": null, "tags": ["c"] }"#)
+        .dispatch();
+
+    let expected = User { name: "bob".into(), email: None, tags: vec!["c".into()] };
+    assert_eq!(response.into_json::<User>().unwrap(), expected);
+
+    let response = client.patch("/merge").body(r#"{ "name": null }"#).dispatch();
+    assert_eq!(response.status(), Status::UnprocessableEntity);
+}
+
+#[test]
+fn json_patch_operations() {
+    let mut doc = json!({ "a": { "b": [1, 2, 3] }, "c": "d" });
+
+    patch(json!([{ "op": "move", "from": "/a/b/0", "path": "/a/b/-" }])).patch(&mut doc).unwrap();
+    assert_eq!(doc["a"]["b"], json!([2, 3, 1]));
+
+    patch(json!([{ "op": "move", "from": "/c", "path": "/a/c" }])).patch(&mut doc).unwrap();
+    assert_eq!(doc, json!({ "a": { "b": [2, 3, 1], "c": "d" } }));
+
+    patch(json!([{ "op": "add", "path": "", "value": [] }])).patch(&mut doc).unwrap();
+    assert_eq!(doc, json!([]));
+
+    let mut doc = json!({ "a/b": { "~": 1 } });
+    patch(json!([{ "op": "test", "path": "/a~1b/~0", "value": 1 }])).patch(&mut doc).unwrap();
+}
+
+#[test]
+fn json_patch_errors() {
+    let original = json!({ "a": [1, 2], "b": { "c": true } });
+    let apply = |operations| {
+        let mut doc = original.clone();
+        let result = patch(operations).patch(&mut doc);
+        assert_eq!(doc, original, "failed patches leave the document unchanged");
+        result.unwrap_err()
+    };
+
+    let error = apply(json!([
+        { "op": "remove", "path": "/b/c" },
+        { "op": "add", "path": "/x/y", "value": 1 }
+    ]));
+
+    assert!(matches!(error, PatchError::Missing { index: 1, ref path } if path.as_str() == "/x/y"));
+
+    for path in ["/a/2", "/a/01", "/a/-"] {
+        let error = apply(json!([{ "op": "remove", "path": path }]));
+        assert!(matches!(error, PatchError::InvalidIndex { index: 0, .. }), "{path}");
+    }
+
+    let error = apply(json!([{ "op": "add", "path": "/a/3", "value": 3 }]));
+    assert!(matches!(error, PatchError::InvalidIndex { .. }));
+
+    let error = apply(json!([{ "op": "move", "from": "/b", "path": "/b/d" }]));
+    assert!(matches!(error, PatchError::InvalidMove { .. }));
+
+    let error = apply(json!([{ "op": "test", "path": "/b/c", "value": false }]));
+    assert!(matches!(error, PatchError::TestFailed { .. }));
+
+    let error = apply(json!([{ "op": "replace", "path": "/z", "value": false }]));
+    assert!(matches!(error, PatchError::Missing { .. }));
+}
+
+#[test]
+fn merge_patch_rfc_examples() {
+    let cases = [
+        (json!({"a": "b"}), json!({"a": "c"}), json!({"a": "c"})),
+        (json!({"a": "b"}), json!({"b": "c"}), json!({"a": "b", "b": "c"})),
+        (json!({"a": "b"}), json!({"a": null}), json!({})),
+        (json!({"a": "b", "b": "c"}), json!({"a": null}), json!({"b": "c"})),
+        (json!({"a": ["b"]}), json!({"a": "c"}), json!({"a": "c"})),
+        (json!({"a": "c"}), json!({"a": ["b"]}), json!({"a": ["b"]})),
+        (json!({"a": {"b": "c"}}), json!({"a": {"b": "d", "c": null}}), json!({"a": {"b": "d"}})),
+        (json!({"a": [{"b": "c"}]}), json!({"a": [1]}), json!({"a": [1]})),
+        (json!(["a", "b"]), json!(["c", "d"]), json!(["c", "d"])),
+        (json!({"a": "b"}), json!(["c"]), json!(["c"])),
+        (json!({"a": "foo"}), json!(null), json!(null)),
+        (json!({"a": "foo"}), json!("bar"), json!("bar")),
+        (json!({"e": null}), json!({"a": 1}), json!({"e": null, "a": 1})),
+        (json!([1, 2]), json!({"a": "b", "c": null}), json!({"a": "b"})),
+        (json!({}), json!({"a": {"bb": {"ccc": null}}}), json!({"a": {"bb": {}}})),
+    ];
+
+    for (mut target, patch, expected) in cases {
+        MergePatch::<Value>::from_value(patch).patch(&mut target);
+        assert_eq!(target, expected);
+    }
+}
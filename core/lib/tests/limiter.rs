@@ -0,0 +1,153 @@
+#![cfg(feature = "limiter")]
+
+#[macro_use] extern crate rocket;
+
+use rocket::limiter::{Limiter, Limit, RateLimited};
+use rocket::data::ToByteUnit;
+use rocket::http::{Header, Status};
+use rocket::local::blocking::{Client, LocalResponse};
+use rocket::figment::providers::{Format, Toml};
+
+struct Login;
+
+impl Limit for Login {
+    const NAME: &'static str = "login";
+}
+
+struct Api;
+
+impl Limit for Api {
+    const NAME: &'static str = "api";
+}
+
+#[get("/")]
+fn index() -> &'static str {
+    "Hello, world!"
+}
+
+#[post("/login")]
+fn login(limited: RateLimited<Login>) -> String {
+    format!("{:?}", limited.remaining())
+}
+
+#[get("/api")]
+fn api(_limited: RateLimited<Api>) { }
+
+fn client(toml: &str) -> Client {
+    let figment = rocket::Config::figment().merge(Toml::string(toml));
+    let limiter = Limiter::new()
+        .extractor("tenant", |req| req.headers().get_one("X-Tenant").map(|t| t.to_string()));
+
+    let rocket = rocket::custom(figment)
+        .mount("/", routes![index, login, api])
+        .attach(limiter);
+
+    Client::debug(rocket).unwrap()
+}
+
+fn get<'c>(client: &'c Client, uri: &'static str, ip: &'static str) -> LocalResponse<'c> {
+    let endpoint = format!("tcp:{ip}:8000");
+    client.get(uri).remote(endpoint.as_str()).dispatch()
+}
+
+#[test]
+fn global_token_bucket() {
+    let client = client("rate_limit.global = { requests = 1, period = 10, burst = 2 }");
+    assert_eq!(get(&client, "/", "1.1.1.1").status(), Status::Ok);
+    assert_eq!(get(&client, "/", "1.1.1.1").status(), Status::Ok);
+
+    let response = get(&client, "/", "1.1.1.1");
+    assert_eq!(response.status(), Status::TooManyRequests);
+    let retry_after: u64 = response.headers().get_one("Retry-After").unwrap().parse().unwrap();
+    assert!((9..=10).contains(&retry_after));
+
+    // Other IPs have their own buckets; requests without an IP aren't limited.
+    assert_eq!(get(&client, "/", "2.2.2.2").status(), Status::Ok);
+    assert_eq!(client.get("/").dispatch().status(), Status::Ok);
+    assert_eq!(client.get("/").dispatch().status(), Status::Ok);
+    assert_eq!(client.get("/").dispatch().status(), Status::Ok);
+}
+
+#[test]
+fn guard_sliding_window() {
+    let client = client(r#"
+        [rate_limit]
+        login = { requests = 2, period = 60, algorithm = "sliding-window" }
+    "#);
+
+    for remaining in ["Some(1)", "Some(0)"] {
+        let response = client.post("/login").remote("tcp:1.1.1.1:8000").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.into_string().unwrap(), remaining);
+    }
+
+    let response = client.post("/login").remote("tcp:1.1.1.1:8000").dispatch();
+    assert_eq!(response.status(), Status::TooManyRequests);
+    let retry_after: u64 = response.headers().get_one("Retry-After").unwrap().parse().unwrap();
+    assert!((89..=90).contains(&retry_after));
+
+    // Unguarded routes aren't limited.
+    let response = get(&client, "/", "1.1.1.1");
+    assert_eq!(response.status(), Status::Ok);
+    assert!(response.headers().get_one("Retry-After").is_none());
+
+    let response = client.post("/login").dispatch();
+    assert_eq!(response.into_string().unwrap(), "None");
+}
+
+#[test]
+fn header_and_extractor_keys() {
+    let client = client(r#"
+        [rate_limit]
+        global = { requests = 1, period = 60, key = { extractor = "tenant" } }
+        api = { requests = 1, period = 60, key = { header = "X-Api-Key" } }
+    "#);
+
+    let request = |key: &'static str, tenant: &'static str| client.get("/api")
+        .header(Header::new("X-Api-Key", key))
+        .header(Header::new("X-Tenant", tenant))
+        .dispatch();
+
+    assert_eq!(request("a", "t1").status(), Status::Ok);
+    assert_eq!(request("a", "t2").status(), Status::TooManyRequests);
+    assert_eq!(request("b", "t3").status(), Status::Ok);
+    assert_eq!(request("c", "t1").status(), Status::TooManyRequests);
+}
+
+#[test]
+fn data_limits_are_independent_of_rate_limits() {
+    let client = client(r#"
+        [limits]
+        form = "1 MiB"
+        rate = "2 MiB"
+
+        [rate_limit]
+        global = { requests = 1, period = 60 }
+    "#);
+
+    let limits = &client.rocket().config().limits;
+    assert_eq!(limits.get("form"), Some(1.mebibytes()));
+    assert_eq!(limits.get("rate"), Some(2.mebibytes()));
+    assert_eq!(get(&client, "/", "1.1.1.1").status(), Status::Ok);
+    assert_eq!(get(&client, "/", "1.1.1.1").status(), Status::TooManyRequests);
+}
+
+#[test]
+fn misconfiguration() {
+    let rocket = rocket::build().mount("/", routes![login]).attach(Limiter::new());
+    let client = Client::debug(rocket).unwrap();
+    let response = client.post("/login").remote("tcp:1.1.1.1:8000").dispatch();
+    assert_eq!(response.status(), Status::InternalServerError);
+
+    for toml in [
+        "rate_limit.global = { requests = 0 }",
+        "rate_limit.login = { requests = 1, period = 0 }",
+        "rate_limit.login = { requests = 1, burst = 0 }",
+        r#"rate_limit.login = { requests = 1, key = { extractor = "user" } }"#,
+        r#"rate_limit.login = { requests = 1, algorithm = "leaky-bucket" }"#,
+    ] {
+        let figment = rocket::Config::figment().merge(Toml::string(toml));
+        let rocket = rocket::custom(figment).attach(Limiter::new());
+        assert!(Client::debug(rocket).is_err(), "{toml}");
+    }
+}
@@ -0,0 +1,69 @@
+#[macro_use] extern crate rocket;
+
+use rocket::Request;
+use rocket::events::Event;
+use rocket::fairing::AdHoc;
+use rocket::http::Status;
+use rocket::local::blocking::Client;
+
+#[get("/hello")]
+fn hello() -> &'static str {
+    "hello"
+}
+
+#[get("/hello", rank = 2)]
+fn hello_again() -> &'static str {
+    "hello again"
+}
+
+#[post("/plugins/<name>")]
+fn load(req: &Request<'_>, name: &str) -> Result<String, Status> {
+    let base = format!("/{name}");
+    let count = req.rocket().mount_live(base, routes![hello]).map_err(|_| Status::Conflict)?;
+    Ok(count.to_string())
+}
+
+#[delete("/plugins/<name>")]
+fn unload(req: &Request<'_>, name: &str) -> String {
+    let base = format!("/{name}");
+    req.rocket().unmount_live(|route| route.uri.base() == base.as_str()).to_string()
+}
+
+#[test]
+fn routes_can_be_mounted_and_unmounted_from_handlers() {
+    let client = Client::debug_with(routes![load, unload]).unwrap();
+    let mut events = client.rocket().events();
+    assert_eq!(client.get("/a/hello").dispatch().status(), Status::NotFound);
+
+    assert_eq!(client.post("/plugins/a").dispatch().into_string().unwrap(), "1");
+    assert_eq!(client.get("/a/hello").dispatch().into_string().unwrap(), "hello");
+    assert!(client.rocket().live_routes().routes().any(|r| r.uri == "/a/hello"));
+    assert!(matches!(events.try_recv(), Some(Event::RoutesChanged { mounted: 1, unmounted: 0 })));
+
+    assert_eq!(client.post("/plugins/a").dispatch().status(), Status::Conflict);
+    assert_eq!(client.rocket().live_routes().routes().count(), 3);
+
+    assert_eq!(client.delete("/plugins/a").dispatch().into_string().unwrap(), "1");
+    assert_eq!(client.get("/a/hello").dispatch().status(), Status::NotFound);
+    assert_eq!(client.delete("/plugins/a").dispatch().into_string().unwrap(), "0");
+    assert!(matches!(events.try_recv(), Some(Event::RoutesChanged { mounted: 0, unmounted: 1 })));
+    assert!(events.try_recv().is_none());
+}
+
+#[test]
+fn routes_can_be_mounted_on_liftoff() {
+    let rocket = rocket::build()
+        .mount("/", routes![hello])
+        .attach(AdHoc::on_liftoff("Plugins", |rocket| Box::pin(async move {
+            rocket.mount_live("/", routes![hello_again]).unwrap();
+            rocket.mount_live("/b", routes![hello, hello_again]).unwrap();
+        })));
+
+    let client = Client::debug(rocket).unwrap();
+    assert_eq!(client.get("/hello").dispatch().into_string().unwrap(), "hello");
+    assert_eq!(client.get("/b/hello").dispatch().into_string().unwrap(), "hello");
+
+    assert_eq!(client.rocket().unmount_live(|route| route.name.as_deref() == Some("hello")), 2);
+    assert_eq!(client.get("/hello").dispatch().into_string().unwrap(), "hello again");
+    assert_eq!(client.get("/b/hello").dispatch().into_string().unwrap(), "hello again");
+}
@@ -0,0 +1,73 @@
+#![cfg(feature = "markdown")]
+
+#[macro_use] extern crate rocket;
+
+use rocket::http::{ContentType, Header, Status};
+use rocket::local::blocking::Client;
+use rocket::fs::{FileServer, relative};
+use rocket::response::markdown::Markdown;
+
+#[get("/")]
+fn index() -> Markdown<String> {
+    Markdown("# Hi\n\n<img src=x onerror=alert(1)>\n\n| a |\n|---|\n| *b* |".into())
+}
+
+fn client() -> Client {
+    let docs = FileServer::new(relative!("tests/docs"))
+        .markdown("<title>{title}</title>\n{content}")
+        .map(|mut f, _| {
+            f.headers.add(Header::new("X-Docs", "1"));
+            f.into()
+        });
+
+    let rocket = rocket::build()
+        .mount("/", routes![index])
+        .mount("/docs", docs)
+        .mount("/raw", FileServer::new(relative!("tests/docs")));
+
+    Client::debug(rocket).unwrap()
+}
+
+#[test]
+fn markdown_responder_renders_sanitized_html() {
+    let client = client();
+    let response = client.get("/").dispatch();
+    assert_eq!(response.content_type(), Some(ContentType::HTML));
+
+    let html = response.into_string().unwrap();
+    assert!(html.starts_with("<h1>Hi</h1>"));
+    assert!(html.contains("<table>"));
+    assert!(html.contains("<em>b</em>"));
+    assert!(html.contains("<img src=\"x\">"));
+    assert!(!html.contains("onerror"));
+}
+
+#[test]
+fn code_blocks_are_highlighted() {
+    let html = Markdown("```rust\nfn main() {}\n```\n\n```nope\n<b>\n```").to_html();
+    assert!(html.contains("<pre class=\"code\"><code class=\"language-rust\">"));
+    assert!(html.contains("<span class=\"source rust\">"));
+    assert!(html.contains("<code class=\"language-nope\">&lt;b&gt;\n</code>"));
+}
+
+#[test]
+fn file_server_renders_markdown_pages() {
+    let client = client();
+    let response = client.get("/docs/guide.md").dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.content_type(), Some(ContentType::HTML));
+    assert_eq!(response.headers().get_one("X-Docs"), Some("1"));
+
+    let html = response.into_string().unwrap();
+    assert!(html.starts_with("<title>Guides &amp; Notes</title>\n<h1>Guides &amp; Notes</h1>"));
+    assert!(html.contains("<em>text</em>"));
+    assert!(html.contains("language-rust"));
+    assert!(!html.contains("script"));
+
+    let response = client.get("/raw/guide.md").dispatch();
+    assert_eq!(response.content_type(), Some(ContentType::Markdown));
+    assert!(response.into_string().unwrap().starts_with("# Guides & Notes"));
+
+    let response = client.get("/docs/missing.md").dispatch();
+    assert_eq!(response.status(), Status::NotFound);
+}
@@ -0,0 +1,83 @@
+#[macro_use] extern crate rocket;
+
+use rocket::{Build, Rocket, State};
+use rocket::fairing::AdHoc;
+use rocket::http::Header;
+use rocket::local::blocking::Client;
+
+struct Billing(&'static str);
+struct Accounts(usize);
+
+#[get("/invoices")]
+fn invoices(billing: &State<Billing>) -> &'static str {
+    billing.0
+}
+
+#[get("/<id>")]
+fn account(id: usize, accounts: &State<Accounts>) -> String {
+    format!("{id}/{}", accounts.0)
+}
+
+#[catch(404)]
+fn billing_not_found() -> &'static str {
+    "no such invoice"
+}
+
+fn billing() -> Rocket<Build> {
+    rocket::build()
+        .mount("/billing", routes![invoices])
+        .register("/billing", catchers![billing_not_found])
+        .manage(Billing("[]"))
+        .mime("inv", "application/x-invoice")
+        .attach(AdHoc::on_response("Billing", |_, res| Box::pin(async move {
+            res.set_header(Header::new("X-Billing", "yes"));
+        })))
+}
+
+fn accounts() -> Rocket<Build> {
+    rocket::build()
+        .mount("/accounts", routes![account])
+        .manage(Accounts(3))
+        .mime("inv", "text/plain")
+}
+
+#[test]
+fn merged_instances_serve_everything() {
+    let rocket = accounts().merge(billing());
+    assert_eq!(rocket.mime_type("inv").unwrap().to_string(), "text/plain");
+
+    let client = Client::debug(rocket).unwrap();
+    let response = client.get("/billing/invoices").dispatch();
+    assert_eq!(response.headers().get_one("X-Billing"), Some("yes"));
+    assert_eq!(response.into_string().unwrap(), "[]");
+
+    assert_eq!(client.get("/accounts/7").dispatch().into_string().unwrap(), "7/3");
+    assert_eq!(client.get("/billing/nope").dispatch().into_string().unwrap(), "no such invoice");
+}
+
+#[test]
+fn figments_are_joined() {
+    let first = rocket::custom(rocket::Config::figment().merge(("app.name", "first")));
+    let second = rocket::custom(rocket::Config::figment()
+        .merge(("app.name", "second"))
+        .merge(("app.region", "eu")));
+
+    let rocket = first.merge(second);
+    assert_eq!(rocket.figment().extract_inner::<String>("app.name").unwrap(), "first");
+    assert_eq!(rocket.figment().extract_inner::<String>("app.region").unwrap(), "eu");
+}
+
+#[test]
+#[should_panic(expected = "duplicated managed state")]
+fn conflicting_state_panics() {
+    let _ = billing().merge(rocket::build().manage(Billing("{}")));
+}
+
+#[test]
+fn colliding_routes_fail_ignition() {
+    assert!(Client::debug(billing().merge(billing_without_state())).is_err());
+}
+
+fn billing_without_state() -> Rocket<Build> {
+    rocket::build().mount("/billing", routes![invoices])
+}
@@ -0,0 +1,49 @@
+#[macro_use] extern crate rocket;
+
+use rocket::fs::{FileServer, NamedFile, relative};
+use rocket::http::ContentType;
+use rocket::local::blocking::Client;
+
+#[get("/named/<file>")]
+async fn named(file: &str) -> Option<NamedFile> {
+    NamedFile::open(format!("{}/{}", relative!("tests/static/other"), file)).await.ok()
+}
+
+fn client() -> Client {
+    let rocket = rocket::build()
+        .mime(".TXT", "text/x-hello")
+        .mime("wasm2", "application/wasm2")
+        .mount("/", routes![named])
+        .mount("/static", FileServer::new(relative!("tests/static/other")));
+
+    Client::debug(rocket).unwrap()
+}
+
+#[test]
+fn registered_types_override_builtins() {
+    let client = client();
+    let hello = ContentType::new("text", "x-hello");
+    assert_eq!(client.get("/named/hello.txt").dispatch().content_type(), Some(hello.clone()));
+    assert_eq!(client.get("/static/hello.txt").dispatch().content_type(), Some(hello));
+
+    // Unregistered extensions fall back to the built-in table.
+    let response = client.get("/static/index.htm").dispatch();
+    assert_eq!(response.content_type(), Some(ContentType::HTML));
+}
+
+#[test]
+fn lookup() {
+    let client = client();
+    let rocket = client.rocket();
+    assert_eq!(rocket.mime_type("wasm2"), Some(ContentType::new("application", "wasm2")));
+    assert_eq!(rocket.mime_type(".Wasm2"), Some(ContentType::new("application", "wasm2")));
+    assert_eq!(rocket.mime_type("txt"), Some(ContentType::new("text", "x-hello")));
+    assert_eq!(rocket.mime_type("css"), Some(ContentType::CSS));
+    assert_eq!(rocket.mime_type("wasm3"), None);
+}
+
+#[test]
+#[should_panic]
+fn invalid_media_type() {
+    let _ = rocket::build().mime("bad", "not a media type");
+}
@@ -0,0 +1,157 @@
+#![cfg(feature = "openapi")]
+
+#[macro_use] extern crate rocket;
+
+use rocket::form::Form;
+use rocket::http::{Method, Status, ContentType};
+use rocket::local::blocking::Client;
+use rocket::openapi::{OpenApi, ToSchema, Schema, Location};
+
+/// A task on the list.
+#[derive(FromForm, ToSchema)]
+struct Task<'r> {
+    /// What needs to be done.
+    description: &'r str,
+    #[field(name = "done")]
+    #[schema(name = "done")]
+    complete: bool,
+    tags: Vec<Tag>,
+}
+
+#[derive(FromForm, ToSchema)]
+struct Tag {
+    name: String,
+}
+
+#[derive(FromForm, ToSchema)]
+struct Filter {
+    complete: Option<bool>,
+}
+
+/// Returns the task with ID `id`.
+///
+/// Tasks are numbered from zero.
+#[get("/<id>?<verbose>")]
+#[openapi(tag = "tasks")]
+fn task(id: usize, verbose: Option<bool>) -> Option<String> {
+    verbose.map(|_| id.to_string())
+}
+
+/// Lists tasks.
+#[get("/?<filter..>")]
+#[openapi(tag = "tasks", operation_id = "listTasks")]
+fn tasks(filter: Filter) -> String {
+    format!("{:?}", filter.complete)
+}
+
+#[post("/", data = "<task>")]
+fn create(task: Form<Task<'_>>) -> Status {
+    match task.complete {
+        true => Status::Created,
+        false => Status::Accepted,
+    }
+}
+
+#[deprecated]
+#[delete("/<_>")]
+fn remove() { }
+
+#[openapi(skip)]
+#[get("/health")]
+fn health() { }
+
+#[catch(404)]
+fn not_found() { }
+
+#[catch(default)]
+fn fallback() { }
+
+#[allow(deprecated)]
+fn rocket() -> rocket::Rocket<rocket::Build> {
+    rocket::build()
+        .mount("/tasks", routes![task, tasks, create, remove])
+        .mount("/", routes![health])
+        .register("/tasks", catchers![not_found])
+        .register("/", catchers![fallback])
+}
+
+#[test]
+fn operations_are_documented_from_handlers() {
+    let document = rocket().openapi();
+    assert_eq!(document.info.title, "Rocket");
+    assert_eq!(document.paths.keys().collect::<Vec<_>>(), ["/tasks", "/tasks/{id}"]);
+
+    let op = &document.paths["/tasks/{id}"][&Method::Get];
+    assert_eq!(op.operation_id.as_deref(), Some("task"));
+    assert_eq!(op.summary.as_deref(), Some("Returns the task with ID `id`."));
+    assert_eq!(op.description.as_deref(), Some("Tasks are numbered from zero."));
+    assert_eq!(op.tags, ["tasks"]);
+    assert!(!op.deprecated);
+
+    let params: Vec<_> = op.parameters.iter()
+        .map(|p| (&*p.name, p.location, p.required))
+        .collect();
+
+    assert_eq!(params, [("id", Location::Path, true), ("verbose", Location::Query, false)]);
+    assert!(matches!(op.parameters[0].schema, Some(Schema::Integer { minimum: Some(0), .. })));
+
+    let statuses: Vec<_> = op.responses.iter().map(|r| r.status).collect();
+    assert_eq!(statuses, [Some(Status::Ok), Some(Status::NotFound), None]);
+    assert_eq!(op.responses[0].media_type, Some(ContentType::Plain.media_type().clone()));
+
+    let op = &document.paths["/tasks"][&Method::Get];
+    assert_eq!(op.operation_id.as_deref(), Some("listTasks"));
+    let filter = &op.parameters[0];
+    assert!(matches!(filter.schema, Some(Schema::Ref { ref name, .. }) if name == "Filter"));
+
+    let op = &document.paths["/tasks"][&Method::Post];
+    let body = op.request_body.as_ref().unwrap();
+    assert_eq!(body.media_type, Some(ContentType::Form.media_type().clone()));
+    assert!(matches!(body.schema, Some(Schema::Ref { ref name, .. }) if name == "Task"));
+    assert!(op.summary.is_none());
+
+    let op = &document.paths["/tasks/{id}"][&Method::Delete];
+    assert!(op.deprecated);
+    assert_eq!(op.parameters[0].name, "_");
+}
+
+#[test]
+fn document_renders_as_json() {
+    let json = rocket().openapi().to_json();
+    assert!(json.starts_with(r#"{"openapi":"3.1.0","info":{"title":"Rocket","version":"0.0.0"}"#));
+    assert!(json.contains(r#""operationId":"listTasks""#));
+    assert!(json.contains(r#""tags":["tasks"]"#));
+    assert!(json.contains(r#""deprecated":true"#));
+    assert!(json.contains(r#""404":{"description":"Not Found"}"#));
+    assert!(json.contains(r#""default":{"description":"Error"}"#));
+    assert!(json.contains(r##""schema":{"$ref":"#/components/schemas/Filter"}"##));
+    assert!(json.contains(r##""items":{"$ref":"#/components/schemas/Tag"}"##));
+    assert!(json.contains(r#""description":{"type":"string","description":"What needs"#));
+    assert!(json.contains(r#""Tag":{"type":"object","properties":{"name":{"type":"string"}}"#));
+    assert!(!json.contains("/health"));
+}
+
+#[test]
+fn fairing_serves_the_current_document() {
+    let openapi = OpenApi::new("Tasks", "1.0").path("/api.json");
+    let client = Client::debug(rocket().attach(openapi)).unwrap();
+    let response = client.get("/api.json").dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.content_type(), Some(ContentType::JSON));
+
+    let json = response.into_string().unwrap();
+    assert!(json.contains(r#""info":{"title":"Tasks","version":"1.0"}"#));
+    assert!(!json.contains("/api.json"));
+    assert!(!json.contains("/v2/tasks"));
+
+    client.rocket().mount_live("/v2/tasks", routes![task]).unwrap();
+    let json = client.get("/api.json").dispatch().into_string().unwrap();
+    assert!(json.contains(r#""/v2/tasks/{id}""#));
+    assert!(json.contains(r#""operationId":"task_2""#));
+}
+
+#[test]
+fn invalid_fairing_path_fails_ignition() {
+    let rocket = rocket::build().attach(OpenApi::new("Tasks", "1.0").path("api.json"));
+    assert!(Client::debug(rocket).is_err());
+}
@@ -0,0 +1,98 @@
+#[macro_use] extern crate rocket;
+
+use rocket::http::{Accept, Header, Status};
+use rocket::request::{self, Request, FromRequest};
+use rocket::local::blocking::Client;
+
+struct Admin;
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for Admin {
+    type Error = &'static str;
+
+    async fn from_request(req: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        match req.headers().get_one("Role") {
+            Some("admin") => request::Outcome::Success(Admin),
+            Some(_) => request::Outcome::Error((Status::Forbidden, "not an admin")),
+            None => request::Outcome::Forward(Status::Unauthorized),
+        }
+    }
+}
+
+#[get("/admin")]
+fn admin(_admin: Admin) -> &'static str {
+    "admin"
+}
+
+#[get("/item/<id>")]
+fn item(id: usize) -> String {
+    id.to_string()
+}
+
+#[get("/search?<limit>")]
+fn search(limit: u8) -> String {
+    limit.to_string()
+}
+
+fn client() -> Client {
+    Client::debug_with(routes![admin, item, search]).unwrap()
+}
+
+#[test]
+fn forwarding_request_guard_is_reported() {
+    let client = client();
+    let response = client.get("/admin").dispatch();
+    assert_eq!(response.status(), Status::Unauthorized);
+
+    let debug = response.headers().get_one("X-Rocket-Debug").unwrap();
+    assert!(debug.starts_with("request guard `_admin: Admin` forwarded (401)"), "{debug}");
+    assert!(debug.contains("in GET /admin"), "{debug}");
+
+    let body = response.into_string().unwrap();
+    assert!(body.contains("request guard `_admin: Admin` forwarded (401)"), "{body}");
+}
+
+#[test]
+fn failing_request_guard_is_reported() {
+    let client = client();
+    let response = client.get("/admin").header(Header::new("Role", "user")).dispatch();
+    assert_eq!(response.status(), Status::Forbidden);
+
+    let debug = response.headers().get_one("X-Rocket-Debug").unwrap();
+    assert!(debug.contains("`_admin: Admin` failed (403)"), "{debug}");
+    assert!(debug.ends_with(": not an admin"), "{debug}");
+}
+
+#[test]
+fn path_and_query_parse_failures_are_reported() {
+    let client = client();
+    let response = client.get("/item/abc").dispatch();
+    assert_eq!(response.status(), Status::UnprocessableEntity);
+
+    let debug = response.headers().get_one("X-Rocket-Debug").unwrap();
+    assert!(debug.starts_with("path guard `id: usize` forwarded (422)"), "{debug}");
+
+    let body = response.into_string().unwrap();
+    assert!(body.contains("`id: usize` forwarded (422) in GET /item/&lt;id&gt;"), "{body}");
+
+    let response = client.get("/search?limit=1000").header(Accept::JSON).dispatch();
+    assert_eq!(response.status(), Status::UnprocessableEntity);
+
+    let debug = response.headers().get_one("X-Rocket-Debug").unwrap();
+    assert!(debug.starts_with("query guard `limit: u8` forwarded (422)"), "{debug}");
+
+    let body = response.into_string().unwrap();
+    assert!(body.contains("\"diagnostics\": [\"query guard `limit: u8`"), "{body}");
+}
+
+#[test]
+fn successful_requests_have_no_trail() {
+    let client = client();
+    let response = client.get("/item/10").dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert!(response.headers().get_one("X-Rocket-Debug").is_none());
+
+    let response = client.get("/nowhere").dispatch();
+    assert_eq!(response.status(), Status::NotFound);
+    assert!(response.headers().get_one("X-Rocket-Debug").is_none());
+}
@@ -0,0 +1,271 @@
+#![cfg(feature = "passkey")]
+
+#[macro_use] extern crate rocket;
+
+use std::io;
+use std::sync::Mutex;
+
+use ring::digest::{digest, SHA256};
+use ring::signature::{Ed25519KeyPair, KeyPair};
+use rocket::auth::passkey::*;
+use rocket::http::{CookieJar, Status};
+use rocket::local::blocking::Client;
+use rocket::serde::json::{json, Value};
+
+#[derive(Default)]
+struct Store(Mutex<Vec<Credential>>);
+
+#[rocket::async_trait]
+impl CredentialStore for Store {
+    async fn user_handle(&self, username: &str) -> Option<Vec<u8>> {
+        let credentials = self.0.lock().unwrap();
+        credentials.iter().find(|c| c.username == username).map(|c| c.user_handle.clone())
+    }
+
+    async fn credentials(&self, user_handle: &[u8]) -> Vec<Credential> {
+        let credentials = self.0.lock().unwrap();
+        credentials.iter().filter(|c| c.user_handle == user_handle).cloned().collect()
+    }
+
+    async fn credential(&self, id: &[u8]) -> Option<Credential> {
+        self.0.lock().unwrap().iter().find(|c| c.id == id).cloned()
+    }
+
+    async fn register(&self, credential: Credential) -> io::Result<()> {
+        self.0.lock().unwrap().push(credential);
+        Ok(())
+    }
+
+    async fn update(&self, credential: &Credential) -> io::Result<()> {
+        let mut credentials = self.0.lock().unwrap();
+        if let Some(c) = credentials.iter_mut().find(|c| c.id == credential.id) {
+            c.sign_count = credential.sign_count;
+        }
+
+        Ok(())
+    }
+}
+
+/// A software Ed25519 authenticator.
+struct Authenticator {
+    key: Ed25519KeyPair,
+    id: Vec<u8>,
+    count: u32,
+}
+
+impl Authenticator {
+    fn new(seed: u8) -> Self {
+        let key = Ed25519KeyPair::from_seed_unchecked(&[seed; 32]).unwrap();
+        Authenticator { key, id: vec![seed; 16], count: 0 }
+    }
+
+    fn authenticator_data(&self, flags: u8, attested: bool) -> Vec<u8> {
+        let mut data = digest(&SHA256, b"example.com").as_ref().to_vec();
+        data.push(flags | if attested { 0x40 } else { 0 });
+        data.extend_from_slice(&self.count.to_be_bytes());
+        if attested {
+            data.extend_from_slice(&[0; 16]);
+            data.extend_from_slice(&(self.id.len() as u16).to_be_bytes());
+            data.extend_from_slice(&self.id);
+            // COSE key: { 1: OKP, 3: EdDSA, -1: Ed25519, -2: x }
+            data.extend_from_slice(&[0xa4, 0x01, 0x01, 0x03, 0x27, 0x20, 0x06, 0x21, 0x58, 0x20]);
+            data.extend_from_slice(self.key.public_key().as_ref());
+        }
+
+        data
+    }
+
+    fn create(&self, options: &Value, origin: &str) -> Value {
+        let client_data = client_data("webauthn.create", options, origin);
+        let auth_data = self.authenticator_data(0x05, true);
+
+        // { "fmt": "none", "attStmt": {}, "authData": auth_data }
+        let mut attestation = b"\xa3\x63fmt\x64none\x67attStmt\xa0\x68authData\x59".to_vec();
+        attestation.extend_from_slice(&(auth_data.len() as u16).to_be_bytes());
+        attestation.extend_from_slice(&auth_data);
+
+        json!({
+            "id": encode(&self.id),
+            "rawId": encode(&self.id),
+            "type": "public-key",
+            "response": {
+                "clientDataJSON": encode(client_data.as_bytes()),
+                "attestationObject": encode(&attestation),
+            },
+        })
+    }
+
+    fn get(&mut self, options: &Value, origin: &str) -> Value {
+        self.count += 1;
+        let client_data = client_data("webauthn.get", options, origin);
+        let auth_data = self.authenticator_data(0x05, false);
+        let message = [&auth_data[..], digest(&SHA256, client_data.as_bytes()).as_ref()].concat();
+
+        json!({
+            "id": encode(&self.id),
+            "rawId": encode(&self.id),
+            "type": "public-key",
+            "response": {
+                "clientDataJSON": encode(client_data.as_bytes()),
+                "authenticatorData": encode(&auth_data),
+                "signature": encode(self.key.sign(&message).as_ref()),
+                "userHandle": null,
+            },
+        })
+    }
+}
+
+fn client_data(kind: &str, options: &Value, origin: &str) -> String {
+    json!({ "type": kind, "challenge": options["challenge"], "origin": origin }).to_string()
+}
+
+fn encode(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+    let mut string = String::new();
+    for chunk in bytes.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0u32, |n, (i, b)| n | ((*b as u32) << (16 - 8 * i)));
+        for i in 0..=chunk.len() {
+            string.push(ALPHABET[((n >> (18 - 6 * i)) & 0x3f) as usize] as char);
+        }
+    }
+
+    string
+}
+
+#[get("/whoami")]
+fn whoami(user: PasskeyUser) -> String {
+    user.username().to_string()
+}
+
+#[post("/logout")]
+fn logout(jar: &CookieJar<'_>) {
+    PasskeyUser::logout(jar);
+}
+
+fn client() -> Client {
+    let passkeys = Passkeys::new("example.com", "https://example.com", Store::default());
+    let rocket = rocket::build()
+        .mount("/passkey", passkeys)
+        .mount("/", routes![whoami, logout]);
+
+    Client::tracked(rocket).unwrap()
+}
+
+fn challenge(client: &Client, path: &str, body: Value) -> Value {
+    let response = client.post(path).json(&body).dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    response.into_json().unwrap()
+}
+
+#[test]
+fn register_and_login() {
+    let client = client();
+    let mut authenticator = Authenticator::new(1);
+
+    let options = challenge(&client, "/passkey/register/challenge", json!({ "username": "alice" }));
+    assert_eq!(options["rp"]["id"], "example.com");
+    assert_eq!(options["user"]["name"], "alice");
+    assert_eq!(options["attestation"], "none");
+
+    let credential = authenticator.create(&options, "https://example.com");
+    let response = client.post("/passkey/register/verify").json(&credential).dispatch();
+    assert_eq!(response.into_json::<Value>().unwrap(), json!({ "username": "alice" }));
+    assert_eq!(client.get("/whoami").dispatch().into_string().unwrap(), "alice");
+
+    // The challenge can't be reused.
+    let response = client.post("/passkey/register/verify").json(&credential).dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
+
+    client.post("/logout").dispatch();
+    assert_eq!(client.get("/whoami").dispatch().status(), Status::Unauthorized);
+
+    let options = challenge(&client, "/passkey/login/challenge", json!({ "username": "alice" }));
+    assert_eq!(options["rpId"], "example.com");
+    assert_eq!(options["allowCredentials"][0]["id"], encode(&authenticator.id));
+
+    let assertion = authenticator.get(&options, "https://example.com");
+    let response = client.post("/passkey/login/verify").json(&assertion).dispatch();
+    assert_eq!(response.into_json::<Value>().unwrap(), json!({ "username": "alice" }));
+    assert_eq!(client.get("/whoami").dispatch().into_string().unwrap(), "alice");
+
+    // A signed-in user can add another passkey.
+    let second = Authenticator::new(2);
+    let options = challenge(&client, "/passkey/register/challenge", json!({}));
+    assert_eq!(options["user"]["name"], "alice");
+    assert_eq!(options["excludeCredentials"].as_array().unwrap().len(), 1);
+
+    let credential = second.create(&options, "https://example.com");
+    let response = client.post("/passkey/register/verify").json(&credential).dispatch();
+    assert_eq!(response.status(), Status::Ok);
+
+    // Without a username, any discoverable passkey is accepted.
+    client.post("/logout").dispatch();
+    let options = challenge(&client, "/passkey/login/challenge", json!({}));
+    assert_eq!(options["allowCredentials"], json!([]));
+
+    let assertion = authenticator.get(&options, "https://example.com");
+    let response = client.post("/passkey/login/verify").json(&assertion).dispatch();
+    assert_eq!(response.status(), Status::Ok);
+}
+
+#[test]
+fn invalid_ceremonies_are_rejected() {
+    let client = client();
+    let mut authenticator = Authenticator::new(3);
+
+    // Verification requires a challenge.
+    let response = client.post("/passkey/register/verify").json(&json!({})).dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
+
+    let options = challenge(&client, "/passkey/register/challenge", json!({ "username": "bob" }));
+    let credential = authenticator.create(&options, "https://evil.com");
+    let response = client.post("/passkey/register/verify").json(&credential).dispatch();
+    assert_eq!(response.status(), Status::Unauthorized);
+
+    let options = challenge(&client, "/passkey/register/challenge", json!({ "username": "bob" }));
+    let credential = authenticator.create(&options, "https://example.com");
+    let response = client.post("/passkey/register/verify").json(&credential).dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    client.post("/logout").dispatch();
+
+    // Usernames are unique.
+    let response = client.post("/passkey/register/challenge")
+        .json(&json!({ "username": "bob" }))
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Conflict);
+
+    // A login challenge can't be used for registration and vice versa.
+    let options = challenge(&client, "/passkey/login/challenge", json!({ "username": "bob" }));
+    let credential = authenticator.create(&options, "https://example.com");
+    let response = client.post("/passkey/register/verify").json(&credential).dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
+
+    // A bad signature.
+    let options = challenge(&client, "/passkey/login/challenge", json!({ "username": "bob" }));
+    let mut assertion = authenticator.get(&options, "https://example.com");
+    assertion["response"]["signature"] = encode(&[0; 64]).into();
+    let response = client.post("/passkey/login/verify").json(&assertion).dispatch();
+    assert_eq!(response.status(), Status::Unauthorized);
+
+    // A signature counter that doesn't increase.
+    authenticator.count = 0;
+    let options = challenge(&client, "/passkey/login/challenge", json!({ "username": "bob" }));
+    let assertion = authenticator.get(&options, "https://example.com");
+    let response = client.post("/passkey/login/verify").json(&assertion).dispatch();
+    assert_eq!(response.status(), Status::Ok);
+
+    authenticator.count = 0;
+    let options = challenge(&client, "/passkey/login/challenge", json!({ "username": "bob" }));
+    let assertion = authenticator.get(&options, "https://example.com");
+    let response = client.post("/passkey/login/verify").json(&assertion).dispatch();
+    assert_eq!(response.status(), Status::Unauthorized);
+
+    // A plain cookie with the same name isn't accepted.
+    client.post("/logout").dispatch();
+    let response = client.get("/whoami")
+        .cookie(("rocket_passkey_user", r#"{"username":"bob"}"#))
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Unauthorized);
+}
@@ -0,0 +1,108 @@
+#![cfg(feature = "paywall")]
+
+#[macro_use] extern crate rocket;
+
+use std::sync::{Arc, Mutex};
+
+use rocket::{Rocket, Build, Config};
+use rocket::http::{Header, Status};
+use rocket::local::asynchronous::Client;
+use rocket::paywall::{Paywall, MemoryCredits, Entitlement, Feature, Denial};
+
+struct Export;
+
+impl Feature for Export {
+    const NAME: &'static str = "export";
+    const COST: u64 = 10;
+}
+
+#[get("/report")]
+fn report(entitlement: Entitlement<'_>) -> String {
+    format!("report for {}", entitlement.account())
+}
+
+#[get("/export")]
+fn export(_entitlement: Entitlement<'_, Export>) -> &'static str {
+    "a,b,c"
+}
+
+#[get("/complete/<tokens>")]
+async fn complete(tokens: u64, mut entitlement: Entitlement<'_>) -> Result<String, Denial> {
+    entitlement.consume(tokens).await?;
+    Ok(format!("{tokens} tokens"))
+}
+
+fn key(key: &'static str) -> Header<'static> {
+    Header::new("X-Api-Key", key)
+}
+
+fn rocket(metered: Arc<Mutex<Vec<(String, String, u64)>>>) -> Rocket<Build> {
+    let credits = MemoryCredits::new().credit("alice", 15).credit("bob", 0);
+    let paywall = Paywall::new(credits)
+        .purchase_url("https://example.com/pricing")
+        .on_meter(move |m| {
+            metered.lock().unwrap().push((m.account.to_string(), m.feature.to_string(), m.units));
+        });
+
+    rocket::custom(Config::debug_default())
+        .attach(paywall)
+        .mount("/", routes![report, export, complete])
+}
+
+#[rocket::async_test]
+async fn entitled_requests_are_metered() {
+    let metered = Arc::new(Mutex::new(vec![]));
+    let client = Client::debug(rocket(metered.clone())).await.unwrap();
+
+    let response = client.get("/report").header(key("alice")).dispatch().await;
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.headers().get_one("X-Paywall-Remaining"), Some("14"));
+    assert_eq!(response.into_string().await.unwrap(), "report for alice");
+
+    let response = client.get("/export").header(key("alice")).dispatch().await;
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.headers().get_one("X-Paywall-Remaining"), Some("4"));
+
+    let response = client.get("/complete/3").header(key("alice")).dispatch().await;
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.headers().get_one("X-Paywall-Remaining"), Some("0"));
+
+    assert_eq!(*metered.lock().unwrap(), vec![
+        ("alice".to_string(), "access".to_string(), 1),
+        ("alice".to_string(), "export".to_string(), 10),
+        ("alice".to_string(), "access".to_string(), 1),
+        ("alice".to_string(), "access".to_string(), 3),
+    ]);
+}
+
+#[rocket::async_test]
+async fn denied_requests_are_payment_required() {
+    let client = Client::debug(rocket(Arc::default())).await.unwrap();
+
+    let response = client.get("/report").dispatch().await;
+    assert_eq!(response.status(), Status::PaymentRequired);
+    assert_eq!(response.headers().get_one("X-Paywall-Reason"), Some("missing"));
+    assert_eq!(response.headers().get_one("Link"),
+        Some("<https://example.com/pricing>; rel=\"payment\""));
+
+    let response = client.get("/report").header(key("eve")).dispatch().await;
+    assert_eq!(response.status(), Status::PaymentRequired);
+    assert_eq!(response.headers().get_one("X-Paywall-Reason"), Some("invalid"));
+
+    let response = client.get("/report").header(key("bob")).dispatch().await;
+    assert_eq!(response.status(), Status::PaymentRequired);
+    assert_eq!(response.headers().get_one("X-Paywall-Reason"), Some("exhausted"));
+
+    // Admitted for 1 unit, but the handler's 20 further units aren't covered.
+    let response = client.get("/complete/20").header(key("alice")).dispatch().await;
+    assert_eq!(response.status(), Status::PaymentRequired);
+    assert_eq!(response.headers().get_one("X-Paywall-Reason"), Some("exhausted"));
+}
+
+#[rocket::async_test]
+async fn entitlement_without_paywall_forwards() {
+    let rocket = rocket::custom(Config::debug_default()).mount("/", routes![report]);
+    let client = Client::debug(rocket).await.unwrap();
+    let response = client.get("/report").dispatch().await;
+    assert_eq!(response.status(), Status::InternalServerError);
+}
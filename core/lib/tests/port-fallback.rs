@@ -0,0 +1,47 @@
+use std::net::Ipv4Addr;
+use std::sync::{Arc, Mutex};
+
+use rocket::fairing::AdHoc;
+use rocket::figment::{Figment, providers::{Format, Toml}};
+use rocket::listener::Endpoint;
+
+#[rocket::async_test]
+async fn binds_to_fallback_port() {
+    let taken = rocket::tokio::net::TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+    let taken = taken.local_addr().unwrap();
+
+    let figment = Figment::from(rocket::Config::debug_default())
+        .merge(("address", taken.ip()))
+        .merge(("port", taken.port()))
+        .merge(Toml::string(&format!("port_fallback = [{}, \"auto\"]", taken.port())));
+
+    let bound: Arc<Mutex<Option<Endpoint>>> = Arc::default();
+    let rocket = rocket::custom(figment)
+        .attach(AdHoc::on_liftoff("Record", {
+            let bound = bound.clone();
+            move |rocket| Box::pin(async move {
+                *bound.lock().unwrap() = rocket.endpoints().next().cloned();
+                rocket.shutdown().notify();
+            })
+        }));
+
+    rocket.launch().await.unwrap();
+    let bound = bound.lock().unwrap().clone().expect("liftoff ran");
+    assert_eq!(bound.ip(), Some(taken.ip()));
+    assert_ne!(bound.port(), Some(taken.port()));
+}
+
+#[rocket::async_test]
+async fn retries_then_fails() {
+    let taken = rocket::tokio::net::TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+    let taken = taken.local_addr().unwrap();
+
+    let figment = Figment::from(rocket::Config::debug_default())
+        .merge(("address", taken.ip()))
+        .merge(("port", taken.port()))
+        .merge(("bind_retry.retries", 2))
+        .merge(("bind_retry.delay", 1));
+
+    let error = rocket::custom(figment).launch().await.unwrap_err();
+    assert!(error.is_bind() && error.is_transient());
+}
@@ -0,0 +1,204 @@
+#![cfg(feature = "proxy")]
+
+use std::net::{Ipv4Addr, SocketAddr};
+use std::time::Duration;
+
+use rocket::http::{Header, Status};
+use rocket::local::asynchronous::Client;
+use rocket::proxy::{Upstream, Balance};
+use rocket::tokio::io::{AsyncReadExt, AsyncWriteExt};
+use rocket::tokio::net::TcpListener;
+
+/// Starts a backend that responds to each request with `status`, an
+/// `X-Backend: {name}` header, and the request itself, head and body.
+async fn backend(name: &'static str, status: &'static str) -> SocketAddr {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    rocket::tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let mut request = vec![];
+            while !request.ends_with(b"\r\n\r\n") {
+                request.push(stream.read_u8().await.unwrap());
+            }
+
+            let head = String::from_utf8(request.clone()).unwrap();
+            let length: usize = head.lines()
+                .find_map(|line| line.to_ascii_lowercase().strip_prefix("content-length: ")
+                    .map(|v| v.parse().unwrap()))
+                .unwrap_or(0);
+
+            let mut body = vec![0; length];
+            stream.read_exact(&mut body).await.unwrap();
+            request.extend(body);
+
+            let head = format!(
+                "HTTP/1.1 {status}\r\nX-Backend: {name}\r\nKeep-Alive: timeout=5\r\n\
+                Content-Length: {}\r\nConnection: close\r\n\r\n",
+                request.len()
+            );
+
+            stream.write_all(head.as_bytes()).await.unwrap();
+            stream.write_all(&request).await.unwrap();
+        }
+    });
+
+    addr
+}
+
+/// Returns an address that nothing is listening on.
+async fn unused() -> SocketAddr {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+    listener.local_addr().unwrap()
+}
+
+async fn launch(upstream: Upstream) -> Client {
+    let figment = rocket::Config::figment().merge(("limits.proxy", 16));
+    let rocket = rocket::custom(figment).mount("/api", upstream);
+    Client::tracked(rocket).await.unwrap()
+}
+
+#[rocket::async_test]
+async fn requests_are_forwarded() {
+    let addr = backend("a", "201 Created").await;
+    let client = launch(Upstream::new([format!("http://{addr}/v2/")])).await;
+    let response = client.post("/api/users/a%20b?x=1")
+        .header(Header::new("Host", "gateway.example"))
+        .header(Header::new("X-Custom", "yes"))
+        .header(Header::new("Connection", "X-Private"))
+        .header(Header::new("X-Private", "hunter2"))
+        .remote("tcp:10.1.1.1:8000")
+        .body("hello")
+        .dispatch()
+        .await;
+
+    assert_eq!(response.status(), Status::Created);
+    assert_eq!(response.headers().get_one("X-Backend"), Some("a"));
+    assert!(response.headers().get_one("Keep-Alive").is_none());
+
+    let request = response.into_string().await.unwrap();
+    let request = request.to_ascii_lowercase();
+    assert!(request.starts_with("post /v2/users/a%20b?x=1 http/1.1\r\n"), "{request}");
+    assert!(request.contains(&format!("\r\nhost: {addr}\r\n")));
+    assert!(request.contains("\r\nx-custom: yes\r\n"));
+    assert!(request.contains("\r\nx-forwarded-for: 10.1.1.1\r\n"));
+    assert!(request.contains("\r\nx-forwarded-host: gateway.example\r\n"));
+    assert!(request.contains("\r\ncontent-length: 5\r\n"));
+    assert!(!request.contains("hunter2"));
+    assert!(request.ends_with("\r\n\r\nhello"));
+
+    for (path, forwarded) in [("/api", "/v2"), ("/api/", "/v2/")] {
+        let request = client.get(path).dispatch().await.into_string().await.unwrap();
+        assert!(request.starts_with(&format!("GET {forwarded} HTTP/1.1\r\n")), "{request}");
+    }
+}
+
+#[rocket::async_test]
+async fn requests_are_balanced_and_retried() {
+    let (a, b) = (backend("a", "201 Created").await, backend("b", "201 Created").await);
+    let down = unused().await;
+    let backends = [format!("http://{a}"), format!("http://{down}"), format!("http://{b}")];
+    let client = launch(Upstream::new(backends)).await;
+
+    let mut seen = vec![];
+    for _ in 0..4 {
+        let response = client.get("/api/").dispatch().await;
+        assert_eq!(response.status(), Status::Created);
+        seen.push(response.headers().get_one("X-Backend").unwrap().to_string());
+    }
+
+    seen.sort();
+    assert_eq!(seen, ["a", "a", "b", "b"]);
+}
+
+#[rocket::async_test]
+async fn least_connections() {
+    let (a, b) = (backend("a", "201 Created").await, backend("b", "201 Created").await);
+    let upstream = Upstream::new([format!("http://{a}"), format!("http://{b}")])
+        .balance(Balance::LeastConnections);
+
+    let client = launch(upstream).await;
+    let held = client.get("/api/").dispatch().await;
+    assert_eq!(held.headers().get_one("X-Backend"), Some("a"));
+
+    // `a` has a request in flight until `held`'s body is dropped.
+    for _ in 0..2 {
+        let response = client.get("/api/").dispatch().await;
+        assert_eq!(response.headers().get_one("X-Backend"), Some("b"));
+    }
+
+    drop(held);
+    let mut seen = vec![];
+    for _ in 0..2 {
+        let response = client.get("/api/").dispatch().await;
+        seen.push(response.headers().get_one("X-Backend").unwrap().to_string());
+    }
+
+    seen.sort();
+    assert_eq!(seen, ["a", "b"]);
+}
+
+#[rocket::async_test]
+async fn consistent_hashing() {
+    let mut backends = vec![];
+    for name in ["a", "b", "c"] {
+        backends.push(format!("http://{}", backend(name, "201 Created").await));
+    }
+
+    let client = launch(Upstream::new(backends).balance(Balance::Header("X-User".into()))).await;
+    let mut seen = std::collections::HashSet::new();
+    for user in 0..16 {
+        let get = || client.get("/api/").header(Header::new("X-User", user.to_string()));
+        let first = get().dispatch().await.headers().get_one("X-Backend").unwrap().to_string();
+        for _ in 0..3 {
+            let response = get().dispatch().await;
+            assert_eq!(response.headers().get_one("X-Backend"), Some(&*first));
+        }
+
+        seen.insert(first);
+    }
+
+    assert!(seen.len() > 1);
+}
+
+#[rocket::async_test]
+async fn circuit_breaking() {
+    let (a, b) = (backend("a", "500 Internal Server Error").await, backend("b", "200 OK").await);
+    let upstream = Upstream::new([format!("http://{a}"), format!("http://{b}")])
+        .circuit_breaker(2, Duration::from_millis(250));
+
+    // `a` fails twice, opening its circuit.
+    let client = launch(upstream).await;
+    for status in [500, 200, 500, 200, 200, 200] {
+        assert_eq!(client.get("/api/").dispatch().await.status().code, status);
+    }
+
+    // Once the circuit half-opens, `a` gets a single trial request, which
+    // fails, opening the circuit again.
+    rocket::tokio::time::sleep(Duration::from_millis(300)).await;
+    let mut statuses = vec![];
+    for _ in 0..4 {
+        statuses.push(client.get("/api/").dispatch().await.status().code);
+    }
+
+    statuses.sort();
+    assert_eq!(statuses, [200, 200, 200, 500]);
+}
+
+#[rocket::async_test]
+async fn failures_are_reported() {
+    let down = unused().await;
+    let client = launch(Upstream::new([format!("http://{down}")])).await;
+    assert_eq!(client.get("/api/").dispatch().await.status(), Status::BadGateway);
+    assert_eq!(client.get("/api/").dispatch().await.status(), Status::ServiceUnavailable);
+
+    let addr = backend("a", "201 Created").await;
+    let client = launch(Upstream::new([format!("http://{addr}")])).await;
+    let response = client.post("/api/").body("more than sixteen bytes").dispatch().await;
+    assert_eq!(response.status(), Status::PayloadTooLarge);
+}
+
+#[test]
+#[should_panic(expected = "unsupported scheme")]
+fn invalid_backends_panic() {
+    let _ = Upstream::new(["https://10.0.0.1"]);
+}
@@ -0,0 +1,83 @@
+#![cfg(feature = "quota")]
+
+#[macro_use] extern crate rocket;
+
+use rocket::quota::Quota;
+use rocket::http::{Header, Status};
+use rocket::local::blocking::{Client, LocalResponse};
+use rocket::figment::providers::{Format, Toml};
+
+#[get("/")]
+fn index() -> &'static str {
+    "Hello, world!"
+}
+
+fn client(toml: &str) -> Client {
+    let figment = rocket::Config::figment().merge(Toml::string(toml));
+    let quota = Quota::new()
+        .principal(|req| req.headers().get_one("X-Api-Key").map(|k| k.to_string()));
+
+    Client::debug(rocket::custom(figment).mount("/", routes![index]).attach(quota)).unwrap()
+}
+
+fn get<'c>(client: &'c Client, key: &'static str) -> LocalResponse<'c> {
+    client.get("/").header(Header::new("X-Api-Key", key)).dispatch()
+}
+
+#[test]
+fn request_quota() {
+    let client = client("quota = { daily = 2, monthly = 100 }");
+    for remaining in ["1", "0"] {
+        let response = get(&client, "alice");
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.headers().get_one("X-Quota-Limit"), Some("2"));
+        assert_eq!(response.headers().get_one("X-Quota-Remaining"), Some(remaining));
+        assert!(response.headers().get_one("X-Quota-Reset").is_some());
+    }
+
+    let response = get(&client, "alice");
+    assert_eq!(response.status(), Status::TooManyRequests);
+    assert_eq!(response.headers().get_one("X-Quota-Remaining"), Some("0"));
+    assert_eq!(response.headers().get_one("Retry-After"),
+        response.headers().get_one("X-Quota-Reset"));
+
+    // Other principals have their own quotas; requests without one aren't metered.
+    assert_eq!(get(&client, "bob").status(), Status::Ok);
+    let response = client.get("/").dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert!(response.headers().get_one("X-Quota-Limit").is_none());
+}
+
+#[test]
+fn byte_quota() {
+    let client = client(r#"
+        metering = true
+        quota = { monthly = 64, unit = "bytes", status = 402 }
+    "#);
+
+    let response = get(&client, "alice");
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.headers().get_one("X-Quota-Remaining"), Some("64"));
+    assert_eq!(response.into_string().unwrap(), "Hello, world!");
+
+    // The completed request's usage is charged before the next one is checked.
+    let response = get(&client, "alice");
+    assert_eq!(response.status(), Status::PaymentRequired);
+    assert!(response.headers().get_one("Retry-After").is_some());
+}
+
+#[test]
+fn byte_quota_requires_metering() {
+    let figment = rocket::Config::figment()
+        .merge(Toml::string(r#"quota = { monthly = 64, unit = "bytes" }"#));
+
+    assert!(Client::debug(rocket::custom(figment).attach(Quota::new())).is_err());
+}
+
+#[test]
+fn no_limits() {
+    let client = client("");
+    let response = get(&client, "alice");
+    assert_eq!(response.status(), Status::Ok);
+    assert!(response.headers().get_one("X-Quota-Limit").is_none());
+}
@@ -0,0 +1,69 @@
+#[macro_use] extern crate rocket;
+
+use rocket::State;
+use rocket::data::{RangedUpload, RangeError};
+use rocket::http::{Header, Status};
+use rocket::local::blocking::Client;
+use rocket::tokio::sync::Mutex;
+
+#[patch("/", data = "<upload>")]
+async fn upload(
+    upload: Result<RangedUpload<'_>, RangeError>,
+    target: &State<Mutex<Vec<u8>>>,
+) -> Result<String, Status> {
+    let upload = upload.map_err(|e| e.status())?;
+    let mut target = target.lock().await;
+    let length = upload.append_to(&mut *target).await.map_err(|e| e.status())?;
+    Ok(length.to_string())
+}
+
+fn client() -> Client {
+    let rocket = rocket::build()
+        .manage(Mutex::new(Vec::<u8>::new()))
+        .mount("/", routes![upload]);
+
+    Client::debug(rocket).unwrap()
+}
+
+fn send(client: &Client, range: &str, body: &'static str) -> (Status, Option<String>) {
+    let response = client.patch("/")
+        .header(Header::new("Content-Range", range.to_string()))
+        .body(body)
+        .dispatch();
+
+    (response.status(), response.into_string())
+}
+
+#[test]
+fn ranged_upload_appends_chunks() {
+    let client = client();
+    assert_eq!(send(&client, "bytes 0-4/11", "hello"), (Status::Ok, Some("5".into())));
+    assert_eq!(send(&client, "bytes 5-10/11", " world"), (Status::Ok, Some("11".into())));
+
+    let target = client.rocket().state::<Mutex<Vec<u8>>>().unwrap();
+    assert_eq!(&*target.try_lock().unwrap(), b"hello world");
+}
+
+#[test]
+fn ranged_upload_retries_are_idempotent() {
+    let client = client();
+    assert_eq!(send(&client, "bytes 0-4/*", "hello").0, Status::Ok);
+    assert_eq!(send(&client, "bytes 0-4/*", "hello"), (Status::Ok, Some("5".into())));
+    assert_eq!(send(&client, "bytes 3-6/*", "lo w"), (Status::Ok, Some("7".into())));
+
+    let target = client.rocket().state::<Mutex<Vec<u8>>>().unwrap();
+    assert_eq!(&*target.try_lock().unwrap(), b"hello w");
+}
+
+#[test]
+fn ranged_upload_rejects_bad_ranges() {
+    let client = client();
+    assert_eq!(send(&client, "bytes 2-4/*", "llo").0, Status::Conflict);
+    assert_eq!(send(&client, "bytes 0-9/5", "hello").0, Status::RangeNotSatisfiable);
+    assert_eq!(send(&client, "0-4/5", "hello").0, Status::BadRequest);
+    assert_eq!(send(&client, "bytes 0-9/*", "hello").0, Status::BadRequest);
+    assert_eq!(send(&client, "bytes 0-1/*", "hello").0, Status::BadRequest);
+
+    let response = client.patch("/").body("hello").dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
+}
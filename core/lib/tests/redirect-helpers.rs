@@ -0,0 +1,88 @@
+#[macro_use] extern crate rocket;
+
+use rocket::Request;
+use rocket::http::{Header, Status};
+use rocket::http::uri::Host;
+use rocket::local::blocking::Client;
+use rocket::response::{self, Redirect, Responder};
+
+struct Back;
+
+impl<'r> Responder<'r, 'static> for Back {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
+        let allowed = Host::new(uri!("accounts.rocket.rs"));
+        Redirect::see_other_back(req, &[allowed], uri!("/home")).respond_to(req)
+    }
+}
+
+#[post("/login")]
+fn login() -> Back {
+    Back
+}
+
+#[get("/posts/<id>")]
+fn post(id: usize) -> Redirect {
+    Redirect::permanent(uri!(_, article(id), "#comments")).preserve_query()
+}
+
+#[get("/articles/<_>")]
+fn article(_id: usize) { }
+
+#[post("/v1")]
+fn moved() -> Redirect {
+    Redirect::moved(uri!("/v2")).preserve_method()
+}
+
+fn client() -> Client {
+    Client::debug_with(routes![login, post, article, moved]).unwrap()
+}
+
+#[test]
+fn see_other_back_validates_target() {
+    let client = client();
+    let back = |query: &str, referer: Option<&str>| {
+        let mut req = client.post(format!("/login{}", query));
+        req.inner_mut().set_host(Host::from(uri!("rocket.rs")));
+        if let Some(referer) = referer {
+            req.add_header(Header::new("Referer", referer.to_string()));
+        }
+
+        let response = req.dispatch();
+        assert_eq!(response.status(), Status::SeeOther);
+        response.headers().get_one("Location").map(|s| s.to_string())
+    };
+
+    assert_eq!(back("", None).unwrap(), "/home");
+    assert_eq!(back("?return_to=/account%3Ftab%3D2", None).unwrap(), "/account?tab=2");
+    assert_eq!(back("", Some("https://rocket.rs/guide")).unwrap(), "https://rocket.rs/guide");
+    assert_eq!(back("", Some("https://accounts.rocket.rs/")).unwrap(),
+        "https://accounts.rocket.rs/");
+
+    // Targets on other hosts or that browsers may treat as such are rejected.
+    assert_eq!(back("?return_to=//evil.com", None).unwrap(), "/home");
+    assert_eq!(back("?return_to=/%5Cevil.com", None).unwrap(), "/home");
+    assert_eq!(back("?return_to=javascript:alert(1)", None).unwrap(), "/home");
+    assert_eq!(back("", Some("https://evil.com/")).unwrap(), "/home");
+    assert_eq!(back("", Some("ftp://rocket.rs/")).unwrap(), "/home");
+    assert_eq!(back("", Some("https://rocket.rs:8000/")).unwrap(), "/home");
+
+    // An invalid `return_to` falls back to the `Referer`.
+    let target = back("?return_to=https://evil.com", Some("/guide")).unwrap();
+    assert_eq!(target, "/guide");
+}
+
+#[test]
+fn preserve_query_and_method() {
+    let client = client();
+    let response = client.get("/posts/7?utm_source=feed").dispatch();
+    assert_eq!(response.status(), Status::PermanentRedirect);
+    let location = response.headers().get_one("Location");
+    assert_eq!(location, Some("/articles/7?utm_source=feed#comments"));
+
+    let response = client.get("/posts/7").dispatch();
+    assert_eq!(response.headers().get_one("Location"), Some("/articles/7#comments"));
+
+    let response = client.post("/v1").dispatch();
+    assert_eq!(response.status(), Status::PermanentRedirect);
+    assert_eq!(response.headers().get_one("Location"), Some("/v2"));
+}
@@ -0,0 +1,116 @@
+#[macro_use] extern crate rocket;
+
+use std::io;
+use std::time::Duration;
+
+use rocket::{Rocket, Build, Config};
+use rocket::error::ErrorKind::SentinelAborts;
+use rocket::http::{ContentType, Status};
+use rocket::local::asynchronous::Client;
+use rocket::response::{Render, RenderOutput, Rendered};
+use rocket::tokio::io::AsyncWriteExt;
+
+/// Renders `(pages, delay)` into `pages` "pages", waiting `delay` before each.
+struct Pages;
+
+#[rocket::async_trait]
+impl Render for Pages {
+    type Input = (usize, Duration);
+
+    fn content_type(&self) -> ContentType {
+        ContentType::PDF
+    }
+
+    fn timeout(&self) -> Duration {
+        Duration::from_secs(5)
+    }
+
+    async fn render(&self, input: Self::Input, mut output: RenderOutput) -> io::Result<()> {
+        let (pages, delay) = input;
+        for i in 0..pages {
+            rocket::tokio::time::sleep(delay).await;
+            output.write_all(format!("[page {i}]").as_bytes()).await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Fails after rendering a page.
+struct Broken;
+
+#[rocket::async_trait]
+impl Render for Broken {
+    type Input = ();
+
+    fn content_type(&self) -> ContentType {
+        ContentType::Plain
+    }
+
+    async fn render(&self, _: (), mut output: RenderOutput) -> io::Result<()> {
+        output.write_all(b"[page 0]").await?;
+        Err(io::Error::other("out of ink"))
+    }
+}
+
+#[get("/pages/<n>")]
+fn pages(n: usize) -> Rendered<Pages> {
+    Rendered::new((n, Duration::ZERO)).filename("pages.pdf")
+}
+
+#[get("/slow")]
+fn slow() -> Rendered<Pages> {
+    Rendered::new((2, Duration::from_secs(1))).timeout(Duration::from_millis(100))
+}
+
+#[get("/broken")]
+fn broken() -> Rendered<Broken> {
+    Rendered::new(())
+}
+
+fn rocket() -> Rocket<Build> {
+    rocket::custom(Config::debug_default())
+        .manage(Pages)
+        .manage(Broken)
+        .mount("/", routes![pages, slow, broken])
+}
+
+#[rocket::async_test]
+async fn rendered_documents_are_streamed() {
+    let client = Client::debug(rocket()).await.unwrap();
+    let response = client.get("/pages/3").dispatch().await;
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.content_type(), Some(ContentType::PDF));
+
+    let disposition = response.headers().get_one("Content-Disposition").unwrap();
+    assert!(disposition.starts_with("attachment"));
+    assert!(disposition.contains("pages.pdf"));
+    assert_eq!(response.into_string().await.unwrap(), "[page 0][page 1][page 2]");
+
+    // Documents larger than the buffer are streamed through it.
+    let response = client.get("/pages/20000").dispatch().await;
+    let body = response.into_string().await.unwrap();
+    assert!(body.ends_with("[page 19999]"));
+}
+
+#[rocket::async_test]
+async fn failed_or_slow_rendering_aborts() {
+    let client = Client::debug(rocket()).await.unwrap();
+    let response = client.get("/slow").dispatch().await;
+    assert_eq!(response.status(), Status::Ok);
+    assert!(response.into_string().await.is_none());
+
+    let response = client.get("/broken").dispatch().await;
+    assert!(response.into_string().await.is_none());
+}
+
+#[rocket::async_test]
+async fn unmanaged_renderers_abort_launch() {
+    let err = rocket::custom(Config::debug_default())
+        .manage(Pages)
+        .mount("/", routes![pages, broken])
+        .ignite().await
+        .unwrap_err();
+
+    assert!(matches!(err.kind(), SentinelAborts(vec) if vec.len() == 1));
+}
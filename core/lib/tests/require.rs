@@ -0,0 +1,72 @@
+use std::sync::Arc;
+use std::time::Duration;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use rocket::fairing::{Retry, Backoff};
+
+fn policy(retries: u32) -> Retry {
+    Retry::new().retries(retries).backoff(Backoff::Constant).delay(Duration::from_millis(1))
+}
+
+fn flaky(failures: usize) -> (rocket::Rocket<rocket::Build>, Arc<AtomicUsize>) {
+    let attempts = Arc::new(AtomicUsize::new(0));
+    let counter = attempts.clone();
+    let rocket = rocket::build().require("flaky", policy(3), move |_| {
+        let attempt = counter.fetch_add(1, Ordering::SeqCst);
+        Box::pin(async move {
+            match attempt < failures {
+                true => Err(format!("attempt {} failed", attempt + 1)),
+                false => Ok(()),
+            }
+        })
+    });
+
+    (rocket, attempts)
+}
+
+#[rocket::async_test]
+async fn retries_until_available() {
+    let (rocket, attempts) = flaky(0);
+    assert!(rocket.ignite().await.is_ok());
+    assert_eq!(attempts.load(Ordering::SeqCst), 1);
+
+    let (rocket, attempts) = flaky(3);
+    assert!(rocket.ignite().await.is_ok());
+    assert_eq!(attempts.load(Ordering::SeqCst), 4);
+}
+
+#[rocket::async_test]
+async fn fails_ignition_when_retries_are_exhausted() {
+    let (rocket, attempts) = flaky(4);
+    assert!(rocket.ignite().await.is_err());
+    assert_eq!(attempts.load(Ordering::SeqCst), 4);
+}
+
+#[rocket::async_test]
+async fn times_out_attempts() {
+    let policy = policy(1).timeout(Duration::from_millis(10));
+    let rocket = rocket::build().require("slow", policy, |_| Box::pin(async {
+        rocket::tokio::time::sleep(Duration::from_secs(10)).await;
+        Ok::<_, std::io::Error>(())
+    }));
+
+    assert!(rocket.ignite().await.is_err());
+}
+
+#[test]
+fn backoff_delays() {
+    let delays = |backoff| -> Vec<u64> {
+        Retry::new()
+            .retries(5)
+            .backoff(backoff)
+            .delay(Duration::from_millis(10))
+            .max_delay(Duration::from_millis(100))
+            .delays()
+            .map(|d| d.as_millis() as u64)
+            .collect()
+    };
+
+    assert_eq!(delays(Backoff::Constant), [10, 10, 10, 10, 10]);
+    assert_eq!(delays(Backoff::Linear), [10, 20, 30, 40, 50]);
+    assert_eq!(delays(Backoff::Exponential), [10, 20, 40, 80, 100]);
+}
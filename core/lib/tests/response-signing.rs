@@ -0,0 +1,96 @@
+#![cfg(feature = "signing")]
+
+#[macro_use] extern crate rocket;
+
+use rocket::http::Status;
+use rocket::signing::{Signer, Algorithm, verify};
+use rocket::local::blocking::Client;
+use rocket::response::stream::ByteStream;
+use rocket::figment::providers::{Format, Toml};
+
+const HMAC_KEY: &str = "MDEyMzQ1Njc4OWFiY2RlZjAxMjM0NTY3ODlhYmNkZWY=";
+
+#[get("/")]
+fn index() -> &'static str {
+    "Hello, world!"
+}
+
+#[get("/large")]
+fn large() -> Vec<u8> {
+    vec![b'a'; 2048]
+}
+
+#[get("/stream")]
+fn stream() -> ByteStream![&'static [u8]] {
+    ByteStream! { yield &b"hi"[..]; }
+}
+
+#[get("/empty")]
+fn empty() -> Status {
+    Status::NoContent
+}
+
+fn client(toml: &str) -> Result<Client, rocket::Error> {
+    let figment = rocket::Config::figment().merge(Toml::string(toml));
+    let rocket = rocket::custom(figment)
+        .mount("/", routes![index, large, stream, empty])
+        .attach(Signer::new());
+
+    Client::debug(rocket)
+}
+
+#[test]
+fn hmac_signatures() {
+    let client = client(&format!("signing = {{ key = '{HMAC_KEY}', limit = '1 KiB' }}")).unwrap();
+    let response = client.get("/").dispatch();
+    let value = response.headers().get_one("X-Signature").unwrap();
+    assert_eq!(value, r#"alg="hmac-sha256", sig="K80DnNZmbA6xQM47UorASK5WDgm9MMvPABMBjUXoAKo=""#);
+
+    let key = b"0123456789abcdef0123456789abcdef";
+    assert!(verify(Algorithm::HmacSha256, key, value, b"Hello, world!"));
+    assert!(!verify(Algorithm::HmacSha256, key, value, b"Hello, world?"));
+    assert!(!verify(Algorithm::Ed25519, key, value, b"Hello, world!"));
+    assert_eq!(response.into_verified_bytes().unwrap(), b"Hello, world!");
+
+    let signer = client.rocket().fairing::<Signer>().unwrap();
+    assert!(signer.public_key().is_none());
+
+    // Bodies that are too large, streamed, or missing aren't signed.
+    for uri in ["/large", "/stream", "/empty"] {
+        let response = client.get(uri).dispatch();
+        assert!(response.headers().get_one("X-Signature").is_none());
+        assert!(response.into_verified_bytes().is_none());
+    }
+}
+
+#[test]
+fn ed25519_signatures() {
+    let client = client(&format!(r#"
+        [signing]
+        algorithm = "ed25519"
+        key = "{HMAC_KEY}"
+        key_id = "k1"
+        header = "Body-Signature"
+    "#)).unwrap();
+
+    let response = client.get("/").dispatch();
+    let value = response.headers().get_one("Body-Signature").unwrap().to_string();
+    assert!(value.starts_with(r#"alg="ed25519", keyid="k1", sig=""#));
+
+    let signer = client.rocket().fairing::<Signer>().unwrap();
+    assert!(signer.public_key().is_some());
+    assert!(signer.verify(&value, b"Hello, world!"));
+    assert!(!signer.verify(&value, b"Hello, world?"));
+    assert_eq!(signer.sign(b"Hello, world!").unwrap(), value);
+
+    let response = client.get("/large").dispatch();
+    assert_eq!(response.into_verified_bytes().unwrap(), vec![b'a'; 2048]);
+}
+
+#[test]
+fn invalid_configuration_fails_ignition() {
+    assert!(client("").is_err());
+    assert!(client("signing.key = 'c2hvcnQ='").is_err());
+    assert!(client("signing.key = 'not base64!'").is_err());
+    assert!(client(&format!("signing = {{ key = '{HMAC_KEY}', algorithm = 'rsa' }}")).is_err());
+}
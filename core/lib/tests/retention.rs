@@ -0,0 +1,77 @@
+#![cfg(feature = "retention")]
+
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use rocket::figment::Figment;
+use rocket::local::asynchronous::Client;
+use rocket::retention::{Retention, Directory, TempFiles, Reclaimed};
+
+fn scratch(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("rocket-retention-{}-{name}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("nested")).unwrap();
+    fs::write(dir.join("capture-1"), "12345").unwrap();
+    fs::write(dir.join("nested/capture-2"), "123").unwrap();
+    fs::write(dir.join("keep"), "1").unwrap();
+    fs::write(dir.join(".tmp-upload"), "1234567").unwrap();
+    dir
+}
+
+async fn client(retention: Retention, figment: Figment) -> Client {
+    let figment = Figment::from(rocket::Config::debug_default()).merge(figment);
+    Client::debug(rocket::custom(figment).attach(retention)).await.unwrap()
+}
+
+#[rocket::async_test]
+async fn expired_files_are_pruned() {
+    let dir = scratch("expired");
+    let retention = Retention::new()
+        .target("captures", Duration::ZERO, Directory::new(&dir).prefix("capture-"));
+
+    let client = client(retention, Figment::new()).await;
+    let retention = client.rocket().fairing::<Retention>().unwrap();
+    let reclaimed = retention.sweep(client.rocket()).await;
+    assert_eq!(reclaimed, Reclaimed { items: 2, bytes: 8 });
+    assert!(dir.join("keep").exists());
+    assert!(!dir.join("capture-1").exists());
+    assert!(!dir.join("nested").exists());
+
+    let reclaimed = retention.sweep(client.rocket()).await;
+    assert_eq!(reclaimed, Reclaimed::default());
+    assert_eq!(retention.reclaimed("captures"), Some(Reclaimed { items: 2, bytes: 8 }));
+    assert_eq!(retention.sweeps("captures"), Some(2));
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[rocket::async_test]
+async fn configured_ttls_override_targets() {
+    let dir = scratch("configured");
+    let retention = Retention::new()
+        .target("captures", Duration::ZERO, Directory::new(&dir))
+        .target("temp", Duration::ZERO, TempFiles);
+
+    let figment = Figment::from(("retention.ttl_secs.captures", 3600))
+        .merge(("temp_dir", &dir));
+
+    let client = client(retention, figment).await;
+    let retention = client.rocket().fairing::<Retention>().unwrap();
+    let reclaimed = retention.sweep(client.rocket()).await;
+    assert_eq!(reclaimed, Reclaimed { items: 1, bytes: 7 });
+    assert_eq!(retention.reclaimed("captures"), Some(Reclaimed::default()));
+    assert!(!dir.join(".tmp-upload").exists());
+    assert!(dir.join("capture-1").exists());
+    assert!(dir.join("nested/capture-2").exists());
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[rocket::async_test]
+async fn invalid_config_fails_ignition() {
+    let figment = Figment::from(rocket::Config::debug_default())
+        .merge(("retention.interval_secs", 0));
+
+    let rocket = rocket::custom(figment).attach(Retention::new());
+
+    assert!(rocket.ignite().await.is_err());
+}
@@ -0,0 +1,87 @@
+#[macro_use] extern crate rocket;
+
+use rocket::http::{Header, Status};
+use rocket::local::blocking::Client;
+
+#[get("/private")]
+#[headers(x_robots_tag = "noindex", cache_control = "no-store")]
+fn private() -> &'static str {
+    "private"
+}
+
+#[headers(x_frame_options = "DENY")]
+#[get("/frame")]
+fn frame() -> &'static str {
+    "frame"
+}
+
+#[derive(Responder)]
+#[response(content_type = "text")]
+struct Cached(&'static str, Header<'static>);
+
+#[get("/cached")]
+#[headers(cache_control = "no-store")]
+fn cached() -> Cached {
+    Cached("cached", Header::new("Cache-Control", "max-age=60"))
+}
+
+#[get("/missing")]
+#[headers(x_robots_tag = "noindex")]
+fn missing() -> Option<&'static str> {
+    None
+}
+
+#[get("/public")]
+fn public() -> &'static str {
+    "public"
+}
+
+fn client() -> Client {
+    Client::debug_with(routes![private, frame, cached, missing, public]).unwrap()
+}
+
+#[test]
+fn headers_are_recorded() {
+    let routes = routes![private, public];
+    let headers = routes[0].headers.iter()
+        .map(|h| (h.name().as_str(), h.value()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(headers, [("X-Robots-Tag", "noindex"), ("Cache-Control", "no-store")]);
+    assert!(routes[1].headers.is_empty());
+}
+
+#[test]
+fn headers_are_set_on_responses() {
+    let client = client();
+    let response = client.get("/private").dispatch();
+    assert_eq!(response.headers().get_one("X-Robots-Tag"), Some("noindex"));
+    assert_eq!(response.headers().get_one("Cache-Control"), Some("no-store"));
+
+    let response = client.get("/missing").dispatch();
+    assert_eq!(response.status(), Status::NotFound);
+    assert_eq!(response.headers().get_one("X-Robots-Tag"), Some("noindex"));
+
+    let response = client.get("/public").dispatch();
+    assert!(response.headers().get_one("X-Robots-Tag").is_none());
+    assert!(response.headers().get_one("Cache-Control").is_none());
+}
+
+#[test]
+fn handler_headers_take_precedence() {
+    let client = client();
+    let response = client.get("/cached").dispatch();
+    let values = response.headers().get("Cache-Control").collect::<Vec<_>>();
+    assert_eq!(values, ["max-age=60"]);
+}
+
+#[test]
+fn headers_replace_shield_policies() {
+    let client = client();
+    let response = client.get("/frame").dispatch();
+    let values = response.headers().get("X-Frame-Options").collect::<Vec<_>>();
+    assert_eq!(values, ["DENY"]);
+
+    let response = client.get("/public").dispatch();
+    assert_eq!(response.headers().get_one("X-Frame-Options"), Some("SAMEORIGIN"));
+}
@@ -0,0 +1,80 @@
+#[macro_use] extern crate rocket;
+
+use rocket::Route;
+use rocket::http::{Method, Status};
+use rocket::figment::Figment;
+use rocket::local::blocking::Client;
+use rocket::route::Condition;
+
+#[route_if(config = "features.beta")]
+#[get("/beta")]
+fn beta() -> &'static str { "beta" }
+
+#[get("/beta", rank = 2)]
+#[route_if(config = "features.beta")]
+fn beta_ranked() -> &'static str { "ranked" }
+
+#[route_if(feature = "json", config = "features.json")]
+#[get("/json/<n>")]
+fn json(n: usize) -> String { n.to_string() }
+
+#[get("/beta")]
+fn stable() -> &'static str { "stable" }
+
+fn launch(figment: Figment) -> Result<Client, rocket::Error> {
+    let rocket = rocket::custom(figment).mount("/", routes![beta, json]);
+    Client::debug(rocket)
+}
+
+#[test]
+fn conditions_are_recorded() {
+    let routes = routes![beta, beta_ranked, json, stable];
+    let condition = Condition::new().config("features.beta");
+    assert_eq!(routes[0].condition.as_ref(), Some(&condition));
+    assert_eq!(routes[1].condition.as_ref(), Some(&condition));
+
+    let condition = routes[2].condition.as_ref().unwrap();
+    assert_eq!(condition.feature_name(), Some("json"));
+    assert_eq!(condition.config_key(), Some("features.json"));
+    assert!(routes[3].condition.is_none());
+}
+
+#[test]
+fn routes_are_mounted_when_config_holds() {
+    let client = launch(rocket::Config::figment()).unwrap();
+    assert_eq!(client.get("/beta").dispatch().status(), Status::NotFound);
+    assert_eq!(client.rocket().routes().count(), 0);
+
+    let client = launch(rocket::Config::figment().merge(("features.beta", false))).unwrap();
+    assert_eq!(client.get("/beta").dispatch().status(), Status::NotFound);
+
+    let client = launch(rocket::Config::figment().merge(("features.beta", true))).unwrap();
+    assert_eq!(client.get("/beta").dispatch().into_string().unwrap(), "beta");
+
+    assert!(launch(rocket::Config::figment().merge(("features.beta", "yes"))).is_err());
+}
+
+#[test]
+fn routes_are_mounted_when_feature_is_enabled() {
+    let figment = rocket::Config::figment().merge(("features.json", true));
+    let client = launch(figment).unwrap();
+    let response = client.get("/json/10").dispatch();
+    match cfg!(feature = "json") {
+        true => assert_eq!(response.into_string().unwrap(), "10"),
+        false => assert_eq!(response.status(), Status::NotFound),
+    }
+
+    assert_eq!(uri!(json(10)), "/json/10");
+}
+
+#[test]
+fn disabled_routes_do_not_collide() {
+    let rocket = rocket::build().mount("/", routes![beta, stable]);
+    let client = Client::debug(rocket).unwrap();
+    assert_eq!(client.get("/beta").dispatch().into_string().unwrap(), "stable");
+
+    let mut route = Route::new(Method::Get, "/", rocket::route::dummy_handler);
+    route.condition = Some(Condition::new().feature("never", false));
+    let client = Client::debug(rocket::build().mount("/", vec![route])).unwrap();
+    assert_eq!(client.get("/").dispatch().status(), Status::NotFound);
+}
@@ -0,0 +1,155 @@
+#![cfg(feature = "pack")]
+
+#[macro_use] extern crate rocket;
+
+use rocket::{Rocket, Build, Route, Catcher, State};
+use rocket::fairing::{AdHoc, Fairing};
+use rocket::figment::Figment;
+use rocket::http::Status;
+use rocket::local::blocking::Client;
+use rocket::pack::{RoutePack, PackConfig, Required};
+use rocket::serde::Deserialize;
+
+struct Ledger;
+
+#[derive(Deserialize)]
+#[serde(crate = "rocket::serde")]
+struct BillingConfig {
+    currency: String,
+}
+
+impl Default for BillingConfig {
+    fn default() -> Self {
+        BillingConfig { currency: "USD".into() }
+    }
+}
+
+struct BillingPack {
+    name: &'static str,
+}
+
+#[get("/currency")]
+fn currency(config: &State<PackConfig<BillingPack>>) -> &str {
+    &config.currency
+}
+
+#[catch(404)]
+fn not_found() -> &'static str {
+    "no such invoice"
+}
+
+impl RoutePack for BillingPack {
+    type Config = BillingConfig;
+
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn routes(&self) -> Vec<Route> {
+        routes![currency]
+    }
+
+    fn catchers(&self) -> Vec<Catcher> {
+        catchers![not_found]
+    }
+
+    fn fairings(&self) -> Vec<Box<dyn Fairing>> {
+        vec![Box::new(AdHoc::on_response("Billing Header", |req, res| Box::pin(async move {
+            if req.uri().path().starts_with("/billing") {
+                res.set_raw_header("X-Billing", "1");
+            }
+        })))]
+    }
+
+    fn required_state(&self) -> Vec<Required> {
+        vec![Required::state::<Ledger>()]
+    }
+}
+
+struct AdminPack;
+
+#[get("/")]
+fn admin() -> &'static str {
+    "admin"
+}
+
+impl RoutePack for AdminPack {
+    type Config = ();
+
+    fn name(&self) -> &'static str {
+        "admin"
+    }
+
+    fn routes(&self) -> Vec<Route> {
+        routes![admin]
+    }
+}
+
+fn billing() -> BillingPack {
+    BillingPack { name: "billing" }
+}
+
+fn build(figment: Figment) -> Rocket<Build> {
+    rocket::custom(figment).manage(Ledger)
+}
+
+#[test]
+fn pack_is_mounted_at_base() {
+    let rocket = build(rocket::Config::figment())
+        .attach_pack("/billing", billing())
+        .attach_pack("/admin", AdminPack);
+
+    let client = Client::debug(rocket).unwrap();
+    let response = client.get("/billing/currency").dispatch();
+    assert_eq!(response.headers().get_one("X-Billing"), Some("1"));
+    assert_eq!(response.into_string().unwrap(), "USD");
+
+    let response = client.get("/billing/missing").dispatch();
+    assert_eq!(response.status(), Status::NotFound);
+    assert_eq!(response.into_string().unwrap(), "no such invoice");
+
+    let response = client.get("/missing").dispatch();
+    assert_ne!(response.into_string().unwrap(), "no such invoice");
+
+    assert_eq!(client.get("/admin").dispatch().into_string().unwrap(), "admin");
+}
+
+#[test]
+fn pack_config_is_read_from_its_section() {
+    let figment = rocket::Config::figment().merge(("packs.billing.currency", "EUR"));
+    let client = Client::debug(build(figment).attach_pack("/billing", billing())).unwrap();
+    let response = client.get("/billing/currency").dispatch();
+    assert_eq!(response.into_string().unwrap(), "EUR");
+
+    let figment = rocket::Config::figment().merge(("packs.billing.currency", 10));
+    assert!(Client::debug(build(figment).attach_pack("/billing", billing())).is_err());
+}
+
+#[test]
+fn missing_required_state_fails_ignition() {
+    let rocket = rocket::build().attach_pack("/billing", billing());
+    assert!(Client::debug(rocket).is_err());
+}
+
+#[test]
+fn conflicting_packs_fail_ignition() {
+    let rocket = build(rocket::Config::figment())
+        .attach_pack("/billing", billing())
+        .attach_pack("/admin", AdminPack)
+        .attach_pack("/admin/v2", BillingPack { name: "billing-v2" });
+
+    assert!(Client::debug(rocket).is_err());
+
+    let rocket = build(rocket::Config::figment())
+        .attach_pack("/billing", billing())
+        .attach_pack("/invoices", AdminPack)
+        .attach_pack("/admin", AdminPack);
+
+    assert!(Client::debug(rocket).is_err());
+
+    let rocket = build(rocket::Config::figment())
+        .attach_pack("/billing", billing())
+        .attach_pack("/billing-v2", AdminPack);
+
+    assert!(Client::debug(rocket).is_ok());
+}
@@ -0,0 +1,30 @@
+#[macro_use] extern crate rocket;
+
+use rocket::fairing::AdHoc;
+use rocket::http::Header;
+use rocket::local::blocking::Client;
+
+#[get("/<_>/posts/<_..>?<page>")]
+fn posts(page: Option<usize>) -> Option<usize> {
+    page
+}
+
+#[test]
+fn route_template_is_path_template() {
+    let rocket = rocket::build()
+        .mount("/users", routes![posts])
+        .attach(AdHoc::on_response("Route", |req, res| Box::pin(async move {
+            let template = req.route_template().unwrap_or("<unmatched>");
+            res.set_header(Header::new("X-Route", template));
+        })));
+
+    let client = Client::debug(rocket).unwrap();
+    let response = client.get("/users/1/posts/a/b?page=2").dispatch();
+    assert_eq!(response.headers().get_one("X-Route"), Some("/users/<_>/posts/<_..>"));
+
+    let response = client.get("/users/2/posts/c").dispatch();
+    assert_eq!(response.headers().get_one("X-Route"), Some("/users/<_>/posts/<_..>"));
+
+    let response = client.get("/other").dispatch();
+    assert_eq!(response.headers().get_one("X-Route"), Some("<unmatched>"));
+}
@@ -0,0 +1,141 @@
+#![cfg(feature = "rpc")]
+
+use rocket::http::Status;
+use rocket::local::blocking::Client;
+use rocket::rpc::{Rpc, RpcError};
+use rocket::serde::Deserialize;
+use rocket::serde::json::{json, Value};
+
+#[derive(Deserialize)]
+#[serde(crate = "rocket::serde")]
+struct Greet {
+    name: String,
+}
+
+enum AppError {
+    Negative(i64),
+}
+
+impl From<AppError> for RpcError {
+    fn from(error: AppError) -> Self {
+        match error {
+            AppError::Negative(n) => RpcError::new(7, "negative").data(n),
+        }
+    }
+}
+
+fn client() -> Client {
+    let rpc = Rpc::new()
+        .method("add", |(a, b): (i64, i64)| async move { Ok::<_, RpcError>(a + b) })
+        .method("greet", |p: Greet| async move { Ok::<_, RpcError>(format!("hi {}", p.name)) })
+        .method("sqrt", |(n,): (i64,)| async move {
+            match n < 0 {
+                true => Err(AppError::Negative(n)),
+                false => Ok((n as f64).sqrt()),
+            }
+        });
+
+    Client::debug(rocket::build().mount("/rpc", rpc)).unwrap()
+}
+
+fn call(client: &Client, body: Value) -> Option<Value> {
+    let response = client.post("/rpc").body(body.to_string()).dispatch();
+    match response.status() {
+        Status::NoContent => None,
+        _ => Some(response.into_json().unwrap()),
+    }
+}
+
+#[test]
+fn calls_are_dispatched_to_methods() {
+    let client = client();
+    let response = call(&client, json!({
+        "jsonrpc": "2.0", "method": "add", "params": [1, 2], "id": 1
+    }));
+
+    assert_eq!(response, Some(json!({ "jsonrpc": "2.0", "result": 3, "id": 1 })));
+
+    let response = call(&client, json!({
+        "jsonrpc": "2.0", "method": "greet", "params": { "name": "bob" }, "id": "a"
+    }));
+
+    assert_eq!(response, Some(json!({ "jsonrpc": "2.0", "result": "hi bob", "id": "a" })));
+
+    let response = call(&client, json!({ "jsonrpc": "2.0", "method": "add", "params": [1, 2] }));
+    assert_eq!(response, None);
+}
+
+#[test]
+fn errors_are_mapped_to_codes() {
+    let client = client();
+    let error = |body: Value| call(&client, body).unwrap()["error"].clone();
+
+    let e = error(json!({ "jsonrpc": "2.0", "method": "sqrt", "params": [-4], "id": 1 }));
+    assert_eq!(e, json!({ "code": 7, "message": "negative", "data": -4 }));
+
+    let e = error(json!({ "jsonrpc": "2.0", "method": "nope", "id": 1 }));
+    assert_eq!(e["code"], RpcError::METHOD_NOT_FOUND);
+
+    let e = error(json!({ "jsonrpc": "2.0", "method": "add", "params": ["x"], "id": 1 }));
+    assert_eq!(e["code"], RpcError::INVALID_PARAMS);
+
+    let e = error(json!({ "jsonrpc": "1.0", "method": "add", "id": 1 }));
+    assert_eq!(e["code"], RpcError::INVALID_REQUEST);
+
+    let e = error(json!({ "jsonrpc": "2.0", "method": "add", "params": 1, "id": 1 }));
+    assert_eq!(e["code"], RpcError::INVALID_REQUEST);
+
+    let e = error(json!([]));
+    assert_eq!(e["code"], RpcError::INVALID_REQUEST);
+
+    let response = client.post("/rpc").body("{ nope").dispatch();
+    let response: Value = response.into_json().unwrap();
+    assert_eq!(response["error"]["code"], RpcError::PARSE_ERROR);
+    assert_eq!(response["id"], Value::Null);
+}
+
+#[test]
+fn batches_are_answered_in_order() {
+    let client = client();
+    let response = call(&client, json!([
+        { "jsonrpc": "2.0", "method": "add", "params": [1, 1], "id": 1 },
+        { "jsonrpc": "2.0", "method": "add", "params": [5, 5] },
+        { "jsonrpc": "2.0", "method": "nope", "id": 2 },
+        7,
+    ])).unwrap();
+
+    let responses = response.as_array().unwrap();
+    assert_eq!(responses.len(), 3);
+    assert_eq!(responses[0]["result"], 2);
+    assert_eq!(responses[1]["error"]["code"], RpcError::METHOD_NOT_FOUND);
+    assert_eq!(responses[2]["error"]["code"], RpcError::INVALID_REQUEST);
+
+    let notifications = json!([{ "jsonrpc": "2.0", "method": "add", "params": [1, 1] }]);
+    assert_eq!(call(&client, notifications), None);
+}
+
+#[test]
+#[cfg(feature = "msgpack")]
+fn msgpack_rpc_is_supported() {
+    use rocket::http::ContentType;
+    use rocket::serde::msgpack;
+
+    let client = client();
+    let post = |message: Value| {
+        let body = msgpack::to_vec(&message).unwrap();
+        client.post("/rpc").header(ContentType::MsgPack).body(body).dispatch()
+    };
+
+    let response = post(json!([0, 9, "add", [2, 3]]));
+    assert_eq!(response.content_type(), Some(ContentType::MsgPack));
+    let response: Value = msgpack::from_slice(&response.into_bytes().unwrap()).unwrap();
+    assert_eq!(response, json!([1, 9, null, 5]));
+
+    let response = post(json!([0, 10, "sqrt", [-1]]));
+    let response: Value = msgpack::from_slice(&response.into_bytes().unwrap()).unwrap();
+    assert_eq!(response[2]["code"], 7);
+    assert_eq!(response[3], Value::Null);
+
+    assert_eq!(post(json!([2, "add", [2, 3]])).status(), Status::NoContent);
+    assert_eq!(post(json!([5, "add"])).status(), Status::BadRequest);
+}
@@ -0,0 +1,321 @@
+#![cfg(feature = "saml")]
+
+#[macro_use] extern crate rocket;
+
+use ring::digest::{digest, SHA256};
+use ring::rand::SystemRandom;
+use ring::signature::{EcdsaKeyPair, ECDSA_P256_SHA256_FIXED_SIGNING};
+use rocket::auth::saml::{Saml, IdentityProvider, SamlUser};
+use rocket::http::{ContentType, CookieJar, RawStr, Status};
+use rocket::local::blocking::{Client, LocalResponse};
+use rocket::time::{Duration, OffsetDateTime};
+
+const CERTIFICATE: &[u8] =
+    include_bytes!("../../../examples/tls/private/ecdsa_nistp256_sha256_cert.pem");
+
+const KEY: &str = include_str!("../../../examples/tls/private/ecdsa_nistp256_sha256_key_pkcs8.pem");
+
+const IDP: &str = "https://idp.example.com";
+const SP: &str = "https://sp.example.com";
+const ACS: &str = "https://sp.example.com/saml/acs";
+
+const PROTOCOL: &str = "urn:oasis:names:tc:SAML:2.0:protocol";
+const ASSERTION: &str = "urn:oasis:names:tc:SAML:2.0:assertion";
+const DSIG: &str = "http://www.w3.org/2000/09/xmldsig#";
+const XSI: &str = "http://www.w3.org/2001/XMLSchema-instance";
+
+/// An assertion issued by the IdP, written in exclusive canonical form so
+/// that it can be signed without a canonicalizer.
+struct Assertion {
+    request_id: String,
+    issuer: &'static str,
+    audience: &'static str,
+    recipient: &'static str,
+    name_id: &'static str,
+    expires_in: Duration,
+    signed: bool,
+}
+
+impl Assertion {
+    fn new(request_id: &str) -> Self {
+        Assertion {
+            request_id: request_id.into(),
+            issuer: IDP,
+            audience: SP,
+            recipient: ACS,
+            name_id: "alice@example.com",
+            expires_in: Duration::minutes(5),
+            signed: true,
+        }
+    }
+
+    /// Returns the assertion in canonical form if `signature` is `None`, or
+    /// as it appears in a response, with `signature`, otherwise.
+    fn xml(&self, signature: Option<&str>) -> String {
+        let now = OffsetDateTime::now_utc();
+        let (issued, later) = (instant(now - Duration::minutes(1)), instant(now + self.expires_in));
+        let (saml, xsi, confirmation_end) = match signature {
+            None => (format!(" xmlns:saml=\"{ASSERTION}\""), format!(" xmlns:xsi=\"{XSI}\""),
+                "></saml:SubjectConfirmationData>"),
+            Some(_) => (String::new(), String::new(), "/>"),
+        };
+
+        let value = |v: &str| {
+            format!("<saml:AttributeValue{xsi} xsi:type=\"xs:string\">{v}</saml:AttributeValue>")
+        };
+
+        format!("<saml:Assertion{saml} ID=\"_assertion\" IssueInstant=\"{issued}\" Version=\"2.0\">\
+            <saml:Issuer>{}</saml:Issuer>{}\
+            <saml:Subject><saml:NameID \
+            Format=\"urn:oasis:names:tc:SAML:1.1:nameid-format:emailAddress\">{}</saml:NameID>\
+            <saml:SubjectConfirmation Method=\"urn:oasis:names:tc:SAML:2.0:cm:bearer\">\
+            <saml:SubjectConfirmationData InResponseTo=\"{}\" NotOnOrAfter=\"{later}\" \
+            Recipient=\"{}\"{confirmation_end}</saml:SubjectConfirmation></saml:Subject>\
+            <saml:Conditions NotBefore=\"{issued}\" NotOnOrAfter=\"{later}\">\
+            <saml:AudienceRestriction><saml:Audience>{}</saml:Audience></saml:AudienceRestriction>\
+            </saml:Conditions>\
+            <saml:AuthnStatement AuthnInstant=\"{issued}\" SessionIndex=\"_session\">\
+            <saml:AuthnContext><saml:AuthnContextClassRef>\
+            urn:oasis:names:tc:SAML:2.0:ac:classes:Password\
+            </saml:AuthnContextClassRef></saml:AuthnContext></saml:AuthnStatement>\
+            <saml:AttributeStatement>\
+            <saml:Attribute Name=\"groups\">{}{}</saml:Attribute>\
+            <saml:Attribute Name=\"email\">{}</saml:Attribute>\
+            </saml:AttributeStatement></saml:Assertion>",
+            self.issuer, signature.unwrap_or_default(), self.name_id, self.request_id,
+            self.recipient, self.audience, value("admins"), value("staff"), value(self.name_id))
+    }
+
+    /// Returns the response containing the assertion, signed by the IdP if
+    /// `self.signed`.
+    fn response(&self) -> String {
+        let signature = match self.signed {
+            true => sign(&self.xml(None)),
+            false => String::new(),
+        };
+
+        format!("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+            <samlp:Response xmlns:samlp=\"{PROTOCOL}\" xmlns:saml=\"{ASSERTION}\" \
+            xmlns:xs=\"http://www.w3.org/2001/XMLSchema\" xmlns:xsi=\"{XSI}\" ID=\"_response\" \
+            Version=\"2.0\" IssueInstant=\"{}\" Destination=\"{ACS}\" InResponseTo=\"{}\">\n  \
+            <saml:Issuer>{IDP}</saml:Issuer>\n  \
+            <samlp:Status><samlp:StatusCode Value=\"urn:oasis:names:tc:SAML:2.0:status:Success\"/>\
+            </samlp:Status>\n  {}\n</samlp:Response>",
+            instant(OffsetDateTime::now_utc()), self.request_id, self.xml(Some(&signature)))
+    }
+}
+
+/// Returns an enveloped signature of the canonical `assertion`.
+fn sign(assertion: &str) -> String {
+    let digest_value = encode(digest(&SHA256, assertion.as_bytes()).as_ref());
+    let signed_info = format!("<ds:CanonicalizationMethod \
+        Algorithm=\"http://www.w3.org/2001/10/xml-exc-c14n#\"></ds:CanonicalizationMethod>\
+        <ds:SignatureMethod Algorithm=\"http://www.w3.org/2001/04/xmldsig-more#ecdsa-sha256\">\
+        </ds:SignatureMethod><ds:Reference URI=\"#_assertion\"><ds:Transforms>\
+        <ds:Transform Algorithm=\"http://www.w3.org/2000/09/xmldsig#enveloped-signature\">\
+        </ds:Transform><ds:Transform Algorithm=\"http://www.w3.org/2001/10/xml-exc-c14n#\">\
+        </ds:Transform></ds:Transforms>\
+        <ds:DigestMethod Algorithm=\"http://www.w3.org/2001/04/xmlenc#sha256\"></ds:DigestMethod>\
+        <ds:DigestValue>{digest_value}</ds:DigestValue></ds:Reference>");
+
+    let canonical = format!("<ds:SignedInfo xmlns:ds=\"{DSIG}\">{signed_info}</ds:SignedInfo>");
+    let rng = SystemRandom::new();
+    let key = EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, &pem(KEY), &rng).unwrap();
+    let signature = key.sign(&rng, canonical.as_bytes()).unwrap();
+
+    format!("<ds:Signature xmlns:ds=\"{DSIG}\"><ds:SignedInfo>{signed_info}</ds:SignedInfo>\
+        <ds:SignatureValue>\n{}\n</ds:SignatureValue></ds:Signature>",
+        encode(signature.as_ref()))
+}
+
+fn instant(time: OffsetDateTime) -> String {
+    format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z", time.year(), time.month() as u8,
+        time.day(), time.hour(), time.minute(), time.second())
+}
+
+const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+fn encode(bytes: &[u8]) -> String {
+    let mut string = String::new();
+    for chunk in bytes.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0u32, |n, (i, b)| n | ((*b as u32) << (16 - 8 * i)));
+        for i in 0..4 {
+            match i <= chunk.len() {
+                true => string.push(ALPHABET[((n >> (18 - 6 * i)) & 0x3f) as usize] as char),
+                false => string.push('='),
+            }
+        }
+    }
+
+    string
+}
+
+fn decode(string: &str) -> Vec<u8> {
+    let sextets: Vec<u32> = string.bytes()
+        .filter_map(|c| ALPHABET.iter().position(|a| *a == c))
+        .map(|i| i as u32)
+        .collect();
+
+    let mut bytes = vec![];
+    for chunk in sextets.chunks(4) {
+        let n = chunk.iter().enumerate().fold(0, |n, (i, s)| n | (s << (18 - 6 * i)));
+        bytes.extend_from_slice(&n.to_be_bytes()[1..chunk.len()]);
+    }
+
+    bytes
+}
+
+/// Decodes the contents of a PEM file.
+fn pem(pem: &str) -> Vec<u8> {
+    let body: String = pem.lines().filter(|line| !line.starts_with("-----")).collect();
+    decode(&body)
+}
+
+#[get("/whoami")]
+fn whoami(user: SamlUser) -> String {
+    let principal = user.principal();
+    let scopes: Vec<_> = principal.scopes().collect();
+    format!("{} {} {}", principal.id(), scopes.join(","), user.attribute("email").unwrap())
+}
+
+#[post("/logout")]
+fn logout(jar: &CookieJar<'_>) {
+    SamlUser::logout(jar);
+}
+
+fn client() -> Client {
+    let idp = IdentityProvider::new(IDP, "https://idp.example.com/sso?tenant=1")
+        .certificate(CERTIFICATE)
+        .unwrap();
+
+    let saml = Saml::new(SP, ACS, idp)
+        .name_id_format("urn:oasis:names:tc:SAML:1.1:nameid-format:emailAddress")
+        .scope_attribute("groups")
+        .attribute("email");
+
+    let rocket = rocket::build()
+        .mount("/saml", saml)
+        .mount("/", routes![whoami, logout]);
+
+    Client::tracked(rocket).unwrap()
+}
+
+/// Starts a sign-in, returning the ID of the `AuthnRequest` and the
+/// `RelayState`.
+fn login(client: &Client, redirect: &str) -> (String, String) {
+    let response = client.get(format!("/saml/login?redirect={redirect}")).dispatch();
+    assert_eq!(response.status(), Status::SeeOther);
+
+    let location = response.headers().get_one("Location").unwrap();
+    let query = location.strip_prefix("https://idp.example.com/sso?tenant=1&").unwrap();
+    let param = |name: &str| query.split('&')
+        .find_map(|field| field.strip_prefix(name)?.strip_prefix('='))
+        .map(|value| RawStr::new(value).url_decode().unwrap().into_owned())
+        .unwrap();
+
+    // The request is deflated in a single stored block.
+    let deflated = decode(&param("SAMLRequest"));
+    assert_eq!(deflated[0], 1);
+    let request = String::from_utf8(deflated[5..].to_vec()).unwrap();
+    assert!(request.starts_with("<samlp:AuthnRequest"));
+    assert!(request.contains(&format!("AssertionConsumerServiceURL=\"{ACS}\"")));
+    assert!(request.contains(&format!("<saml:Issuer>{SP}</saml:Issuer>")));
+
+    let id = request.split("ID=\"").nth(1).unwrap().split('"').next().unwrap();
+    (id.to_string(), param("RelayState"))
+}
+
+fn consume<'c>(client: &'c Client, response: &str, relay_state: &str) -> LocalResponse<'c> {
+    let encoded = encode(response.as_bytes());
+    client.post("/saml/acs")
+        .header(ContentType::Form)
+        .body(format!("SAMLResponse={}&RelayState={relay_state}",
+            RawStr::new(&encoded).percent_encode()))
+        .dispatch()
+}
+
+#[test]
+fn metadata() {
+    let client = client();
+    let response = client.get("/saml/metadata").dispatch();
+    assert_eq!(response.content_type(), Some(ContentType::new("application", "samlmetadata+xml")));
+
+    let metadata = response.into_string().unwrap();
+    assert!(metadata.contains(&format!("entityID=\"{SP}\"")));
+    assert!(metadata.contains(&format!("Location=\"{ACS}\"")));
+    assert!(metadata.contains("nameid-format:emailAddress</md:NameIDFormat>"));
+}
+
+#[test]
+fn sign_in() {
+    let client = client();
+    assert_eq!(client.get("/whoami").dispatch().status(), Status::Unauthorized);
+
+    let (id, relay_state) = login(&client, "/account");
+    let saml_response = Assertion::new(&id).response();
+    let response = consume(&client, &saml_response, &relay_state);
+    assert_eq!(response.status(), Status::SeeOther);
+    assert_eq!(response.headers().get_one("Location"), Some("/account"));
+
+    let whoami = client.get("/whoami").dispatch().into_string().unwrap();
+    assert_eq!(whoami, "alice@example.com admins,staff alice@example.com");
+
+    // A response can't be replayed.
+    client.post("/logout").dispatch();
+    let response = consume(&client, &saml_response, &relay_state);
+    assert_eq!(response.status(), Status::BadRequest);
+    assert_eq!(client.get("/whoami").dispatch().status(), Status::Unauthorized);
+
+    // Redirects are limited to this server.
+    let (id, relay_state) = login(&client, "//evil.example.com");
+    let response = consume(&client, &Assertion::new(&id).response(), &relay_state);
+    assert_eq!(response.headers().get_one("Location"), Some("/"));
+}
+
+#[test]
+fn invalid_responses_are_refused() {
+    let client = client();
+    let refused = |assertion: &dyn Fn(&str) -> Assertion| {
+        let (id, relay_state) = login(&client, "/");
+        let response = consume(&client, &assertion(&id).response(), &relay_state);
+        assert_eq!(response.status(), Status::Unauthorized);
+        assert_eq!(client.get("/whoami").dispatch().status(), Status::Unauthorized);
+    };
+
+    refused(&|_| Assertion::new("_another_request"));
+    refused(&|id| Assertion { issuer: "https://evil.example.com", ..Assertion::new(id) });
+    refused(&|id| Assertion { audience: "https://other.example.com", ..Assertion::new(id) });
+    refused(&|id| Assertion { recipient: "https://other.example.com/acs", ..Assertion::new(id) });
+    refused(&|id| Assertion { expires_in: Duration::minutes(-5), ..Assertion::new(id) });
+    refused(&|id| Assertion { signed: false, ..Assertion::new(id) });
+
+    // Tampering with a signed assertion invalidates its digest.
+    let (id, relay_state) = login(&client, "/");
+    let tampered = Assertion::new(&id).response().replace(">alice@", ">mallory@");
+    let response = consume(&client, &tampered, &relay_state);
+    assert_eq!(response.status(), Status::Unauthorized);
+
+    // Duplicate IDs are refused, preventing signature wrapping.
+    let (id, relay_state) = login(&client, "/");
+    let wrapped = Assertion::new(&id).response()
+        .replacen("<saml:Issuer>", "<saml:Issuer ID=\"_assertion\">", 1);
+
+    let response = consume(&client, &wrapped, &relay_state);
+    assert_eq!(response.status(), Status::Unauthorized);
+
+    // The `RelayState` must match the pending sign-in's.
+    let (id, _) = login(&client, "/");
+    let response = consume(&client, &Assertion::new(&id).response(), "forged");
+    assert_eq!(response.status(), Status::BadRequest);
+
+    let (_, relay_state) = login(&client, "/");
+    let response = consume(&client, "<samlp:Response", &relay_state);
+    assert_eq!(response.status(), Status::BadRequest);
+
+    // A plain cookie with the same name isn't accepted.
+    let response = client.get("/whoami")
+        .cookie(("rocket_saml_user", r#"{"name_id":"mallory"}"#))
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Unauthorized);
+}
@@ -0,0 +1,95 @@
+#[macro_use] extern crate rocket;
+
+use rocket::{Rocket, Build};
+use rocket::fairing::AdHoc;
+use rocket::http::{Header, Status};
+use rocket::local::blocking::Client;
+
+#[cfg(feature = "limiter")]
+use rocket::figment::providers::{Format, Toml};
+#[cfg(feature = "limiter")]
+use rocket::limiter::{Limit, Limiter, RateLimited};
+
+#[get("/users")]
+fn users() -> &'static str {
+    "users"
+}
+
+#[get("/")]
+fn index() -> &'static str {
+    "index"
+}
+
+#[get("/administer")]
+fn administer() -> &'static str {
+    "administer"
+}
+
+#[cfg(feature = "limiter")]
+struct Login;
+
+#[cfg(feature = "limiter")]
+impl Limit for Login {
+    const NAME: &'static str = "login";
+}
+
+#[cfg(feature = "limiter")]
+#[post("/login")]
+fn login(_limited: RateLimited<Login>) { }
+
+fn rocket() -> Rocket<Build> {
+    let auth = AdHoc::on_request("Auth", |req, _| Box::pin(async move {
+        if req.headers().get_one("X-Token") != Some("secret") {
+            req.set_uri(uri!("/denied"));
+        }
+    }));
+
+    let tag = AdHoc::on_response("Tag", |_, res| Box::pin(async move {
+        res.set_header(Header::new("X-Admin", "true"));
+    }));
+
+    rocket::build()
+        .mount("/", routes![index, administer])
+        .mount_with("/admin", routes![users, index], fairings![auth, tag])
+}
+
+#[test]
+fn scoped_fairings_run_under_base() {
+    let client = Client::debug(rocket()).unwrap();
+    for uri in ["/admin", "/admin/users"] {
+        let response = client.get(uri).dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+        assert_eq!(response.headers().get_one("X-Admin"), None);
+
+        let response = client.get(uri).header(Header::new("X-Token", "secret")).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.headers().get_one("X-Admin"), Some("true"));
+    }
+}
+
+#[test]
+fn scoped_fairings_skip_other_paths() {
+    let client = Client::debug(rocket()).unwrap();
+    for (uri, body) in [("/", "index"), ("/administer", "administer")] {
+        let response = client.get(uri).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert!(!response.headers().contains("X-Admin"));
+        assert_eq!(response.into_string().unwrap(), body);
+    }
+}
+
+#[test]
+#[cfg(feature = "limiter")]
+fn scoped_fairings_are_retrievable() {
+    let figment = rocket::Config::figment()
+        .merge(Toml::string("rate_limit.login = { requests = 1 }"));
+
+    let rocket = rocket::custom(figment)
+        .mount_with("/admin", routes![login], fairings![Limiter::new()]);
+
+    let client = Client::debug(rocket).unwrap();
+    assert!(client.rocket().fairing::<Limiter>().is_some());
+    let login = || client.post("/admin/login").remote("tcp:1.1.1.1:8000").dispatch().status();
+    assert_eq!(login(), Status::Ok);
+    assert_eq!(login(), Status::TooManyRequests);
+}
@@ -0,0 +1,76 @@
+use rocket::{Config, Build, Rocket};
+use rocket::shield::{SecurityLint, Shield, Hsts, Frame};
+
+fn rocket(profile: &str, proxy_proto_header: Option<&str>) -> Rocket<Build> {
+    let mut figment = Config::figment()
+        .select(profile)
+        .merge(("secret_key", "hPRYyVRiMyxpw5sBB1XeCMN1kFsDCqKvBi2QJxBVHQk="));
+
+    if let Some(header) = proxy_proto_header {
+        figment = figment.merge((Config::PROXY_PROTO_HEADER, header));
+    }
+
+    rocket::custom(figment)
+}
+
+fn codes(lint: &SecurityLint, rocket: &Rocket<Build>) -> Vec<&'static str> {
+    lint.check(rocket).into_iter().map(|f| f.code).collect()
+}
+
+#[test]
+fn default_shield_without_https() {
+    let rocket = rocket("release", None);
+    assert_eq!(codes(&SecurityLint::warn(), &rocket), ["insecure-cookies"]);
+
+    let rocket = rocket.attach(Shield::default().disable::<Frame>().enable(Hsts::default()));
+    let codes = codes(&SecurityLint::warn(), &rocket);
+    assert_eq!(codes, ["frame-disabled", "hsts-without-tls", "insecure-cookies"]);
+}
+
+#[test]
+fn proxy_enables_https() {
+    let rocket = rocket("release", Some("X-Forwarded-Proto"))
+        .attach(Shield::default().enable(Hsts::default()));
+
+    assert!(codes(&SecurityLint::deny(), &rocket).is_empty());
+}
+
+#[test]
+fn missing_shield_and_allow() {
+    let rocket = rocket("release", None).attach(Shield::new());
+    assert_eq!(codes(&SecurityLint::warn(), &rocket), ["shield-missing", "insecure-cookies"]);
+
+    let lint = SecurityLint::warn().allow("insecure-cookies");
+    assert_eq!(codes(&lint, &rocket), ["shield-missing"]);
+}
+
+#[test]
+fn insecure_cookie_defaults() {
+    let figment = rocket("release", Some("X-Forwarded-Proto")).figment().clone()
+        .merge(("cookies.secure", false))
+        .merge(("cookies.http_only", false))
+        .merge(("cookies.default_same_site", "none"));
+
+    let codes = codes(&SecurityLint::warn(), &rocket::custom(figment));
+    assert_eq!(codes, ["cookies-not-secure", "cookies-not-http-only", "same-site-none"]);
+
+    let figment = rocket("release", None).figment().clone().merge(("cookies.secure", false));
+    let codes = codes(&SecurityLint::warn(), &rocket::custom(figment));
+    assert_eq!(codes, ["insecure-cookies"]);
+}
+
+#[rocket::async_test]
+async fn deny_fails_ignition_outside_debug() {
+    let result = rocket("release", None).attach(SecurityLint::deny()).ignite().await;
+    assert!(result.is_err());
+
+    let result = rocket("release", None).attach(SecurityLint::warn()).ignite().await;
+    assert!(result.is_ok());
+
+    let result = rocket("debug", None).attach(SecurityLint::deny()).ignite().await;
+    assert!(result.is_ok());
+
+    let lint = SecurityLint::deny().allow("insecure-cookies");
+    let result = rocket("release", None).attach(lint).ignite().await;
+    assert!(result.is_ok());
+}
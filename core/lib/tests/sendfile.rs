@@ -0,0 +1,80 @@
+#[macro_use] extern crate rocket;
+
+use std::path::Path;
+
+use rocket::fs::{SendFile, relative};
+use rocket::http::{ContentType, Header, Status};
+use rocket::local::blocking::Client;
+use rocket::figment::providers::{Format, Toml};
+
+#[get("/<path>")]
+async fn file(path: &str) -> Option<SendFile> {
+    SendFile::open(Path::new(relative!("tests/static/other")).join(path)).await.ok()
+}
+
+#[get("/outside")]
+async fn outside() -> Option<SendFile> {
+    SendFile::open(relative!("tests/static/index.html")).await.ok()
+}
+
+fn client(config: &str) -> Client {
+    let figment = rocket::Config::figment().merge(Toml::string(config));
+    Client::debug(rocket::custom(figment).mount("/", routes![file, outside])).unwrap()
+}
+
+fn accel_config() -> String {
+    format!("sendfile = {{ root = {:?}, location = \"/protected/\" }}",
+        relative!("tests/static/other"))
+}
+
+#[test]
+fn offloads_proxied_requests() {
+    let client = client(&accel_config());
+    let response = client.get("/hello.txt")
+        .header(Header::new("X-Real-IP", "1.2.3.4"))
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.content_type(), Some(ContentType::Plain));
+    assert_eq!(response.headers().get_one("X-Accel-Redirect"), Some("/protected/hello.txt"));
+    assert!(response.into_string().unwrap().is_empty());
+}
+
+#[test]
+fn streams_unproxied_requests() {
+    let client = client(&accel_config());
+    let response = client.get("/hello.txt").dispatch();
+    assert!(response.headers().get_one("X-Accel-Redirect").is_none());
+    assert!(!response.into_string().unwrap().is_empty());
+}
+
+#[test]
+fn streams_files_outside_root() {
+    let client = client(&accel_config());
+    let response = client.get("/outside")
+        .header(Header::new("X-Real-IP", "1.2.3.4"))
+        .dispatch();
+
+    assert!(response.headers().get_one("X-Accel-Redirect").is_none());
+    assert!(!response.into_string().unwrap().is_empty());
+}
+
+#[test]
+fn x_sendfile_always() {
+    let client = client("sendfile = { header = \"X-Sendfile\", always = true }");
+    let response = client.get("/hello.txt").dispatch();
+    let expected = Path::new(relative!("tests/static/other")).join("hello.txt");
+    assert_eq!(response.headers().get_one("X-Sendfile"), expected.to_str());
+    assert!(response.into_string().unwrap().is_empty());
+}
+
+#[test]
+fn streams_without_config() {
+    let client = client("");
+    let response = client.get("/hello.txt")
+        .header(Header::new("X-Real-IP", "1.2.3.4"))
+        .dispatch();
+
+    assert!(response.headers().get_one("X-Accel-Redirect").is_none());
+    assert!(!response.into_string().unwrap().is_empty());
+}
@@ -0,0 +1,111 @@
+use rocket::{*, error::ErrorKind::SentinelAborts};
+use rocket::sentinel::{Sentinel, Missing};
+#[cfg(feature = "pack")]
+use rocket::pack::{RoutePack, Provided};
+
+struct PgPool;
+
+#[get("/invoices")]
+fn invoices(_pool: &State<PgPool>) {}
+
+#[get("/invoices/<_id>")]
+fn invoice(_id: usize, _pool: &State<PgPool>) {}
+
+#[get("/health")]
+fn health() {}
+
+struct Mailer;
+
+impl Sentinel for Mailer {
+    fn abort(rocket: &Rocket<Ignite>) -> bool {
+        rocket.state::<PgPool>().is_none()
+    }
+
+    fn missing(rocket: &Rocket<Ignite>) -> Option<Missing> {
+        rocket.state::<PgPool>()
+            .is_none()
+            .then(|| Missing::new::<PgPool>().provider("MailPack"))
+    }
+}
+
+#[get("/mail")]
+fn mail() -> Option<Mailer> { None }
+
+#[cfg(feature = "pack")]
+struct BillingPack;
+
+#[cfg(feature = "pack")]
+impl RoutePack for BillingPack {
+    type Config = ();
+
+    fn name(&self) -> &'static str {
+        "billing"
+    }
+
+    fn provides(&self) -> Vec<Provided> {
+        vec![Provided::state::<PgPool>()]
+    }
+}
+
+async fn ignite(rocket: Rocket<Build>) -> Error {
+    rocket.reconfigure(Config::debug_default())
+        .mount("/", routes![invoices, invoice, health])
+        .ignite().await
+        .unwrap_err()
+}
+
+#[async_test]
+async fn diagnostics_list_routes_and_missing_type() {
+    let error = ignite(rocket::build()).await;
+    assert!(matches!(error.kind(), SentinelAborts(vec) if vec.len() == 1));
+
+    let diagnostic = &error.sentinel_diagnostics()[0];
+    let mut uris: Vec<_> = diagnostic.routes().iter().map(|r| r.uri.to_string()).collect();
+    uris.sort();
+    assert_eq!(uris, ["/invoices", "/invoices/<_id>"]);
+
+    let missing = diagnostic.missing().unwrap();
+    assert!(missing.type_name().ends_with("PgPool"));
+    assert_eq!(diagnostic.providers().count(), 0);
+    assert!(diagnostic.suggestion().contains("via `rocket.manage()`"));
+}
+
+#[async_test]
+async fn diagnostics_suggest_registered_providers() {
+    let error = ignite(rocket::build().provider::<PgPool>("BillingPack")).await;
+    let diagnostic = &error.sentinel_diagnostics()[0];
+    assert_eq!(diagnostic.providers().collect::<Vec<_>>(), ["BillingPack"]);
+    assert!(diagnostic.suggestion().starts_with("attach BillingPack to provide"));
+}
+
+#[async_test]
+#[cfg(feature = "pack")]
+async fn diagnostics_suggest_attached_route_packs() {
+    let error = ignite(rocket::build().attach_pack("/billing", BillingPack)).await;
+    let diagnostic = &error.sentinel_diagnostics()[0];
+    assert_eq!(diagnostic.providers().collect::<Vec<_>>(), ["route pack `billing`"]);
+    assert!(diagnostic.suggestion().contains("which is attached"));
+}
+
+#[async_test]
+async fn custom_sentinels_report_missing_types() {
+    let error = rocket::build()
+        .reconfigure(Config::debug_default())
+        .mount("/", routes![mail])
+        .ignite().await
+        .unwrap_err();
+
+    let diagnostic = &error.sentinel_diagnostics()[0];
+    assert!(diagnostic.sentry().type_name().contains("Mailer"));
+    assert_eq!(diagnostic.routes()[0].uri.to_string(), "/mail");
+    assert_eq!(diagnostic.providers().collect::<Vec<_>>(), ["MailPack"]);
+    assert!(diagnostic.suggestion().starts_with("attach MailPack to provide"));
+
+    let result = rocket::build()
+        .reconfigure(Config::debug_default())
+        .mount("/", routes![mail])
+        .manage(PgPool)
+        .ignite().await;
+
+    assert!(result.is_ok());
+}
@@ -0,0 +1,133 @@
+#![cfg(feature = "shadow")]
+
+#[macro_use] extern crate rocket;
+
+use std::net::{Ipv4Addr, SocketAddr};
+
+use rocket::figment::Figment;
+use rocket::http::{Header, Status};
+use rocket::local::asynchronous::Client;
+use rocket::shadow::{Shadow, ShadowConfig};
+use rocket::tokio::io::{AsyncReadExt, AsyncWriteExt};
+use rocket::tokio::net::TcpListener;
+use rocket::tokio::sync::mpsc;
+
+#[post("/echo", data = "<body>")]
+fn echo(body: String) -> String {
+    body
+}
+
+/// Starts an upstream that sends each request it receives to the returned
+/// receiver and responds `204 No Content`.
+async fn upstream() -> (SocketAddr, mpsc::UnboundedReceiver<String>) {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (tx, rx) = mpsc::unbounded_channel();
+    rocket::tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let mut request = vec![];
+            while !request.ends_with(b"\r\n\r\n") {
+                request.push(stream.read_u8().await.unwrap());
+            }
+
+            let head = String::from_utf8(request.clone()).unwrap();
+            let length: usize = head.lines()
+                .find_map(|line| line.strip_prefix("Content-Length: "))
+                .map_or(0, |v| v.parse().unwrap());
+
+            let mut body = vec![0; length];
+            stream.read_exact(&mut body).await.unwrap();
+            request.extend(body);
+            stream.write_all(b"HTTP/1.1 204 No Content\r\n\r\n").await.unwrap();
+            tx.send(String::from_utf8(request).unwrap()).unwrap();
+        }
+    });
+
+    (addr, rx)
+}
+
+async fn launch(shadow: Shadow, figment: Figment) -> Client {
+    let rocket = rocket::custom(figment).mount("/", routes![echo]).attach(shadow);
+    Client::tracked(rocket).await.unwrap()
+}
+
+#[rocket::async_test]
+async fn sampled_requests_are_mirrored() {
+    let (addr, mut mirrored) = upstream().await;
+    let shadow = Shadow::new().upstream(format!("http://{addr}/shadow/")).rate(1.0);
+    let figment = rocket::Config::figment().merge(("shadow.exclude_headers", ["X-Secret"]));
+    let client = launch(shadow.clone(), figment).await;
+
+    let response = client.post("/echo?x=1")
+        .header(Header::new("X-Custom", "yes"))
+        .header(Header::new("X-Secret", "hunter2"))
+        .body("hello")
+        .dispatch()
+        .await;
+
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.into_string().await.unwrap(), "hello");
+
+    let request = mirrored.recv().await.unwrap();
+    assert!(request.starts_with("POST /shadow/echo?x=1 HTTP/1.1\r\n"), "{request}");
+    assert!(request.contains(&format!("\r\nHost: {addr}\r\n")));
+    assert!(request.contains("\r\nX-Custom: yes\r\n"));
+    assert!(request.contains("\r\nX-Shadow-Request: 1\r\n"));
+    assert!(request.contains("\r\nContent-Length: 5\r\n"));
+    assert!(!request.contains("hunter2"));
+    assert!(request.ends_with("\r\n\r\nhello"));
+
+    while shadow.in_flight() > 0 {
+        rocket::tokio::task::yield_now().await;
+    }
+
+    assert_eq!(shadow.mirrored(), 1);
+    assert_eq!(shadow.failed(), 0);
+}
+
+#[rocket::async_test]
+async fn large_bodies_and_unsampled_requests_are_not_mirrored() {
+    let (addr, mut mirrored) = upstream().await;
+    let figment = rocket::Config::figment()
+        .merge(("shadow.upstream", format!("http://{addr}")))
+        .merge(("shadow.rate", 1.0))
+        .merge(("shadow.body_limit", 4));
+
+    let shadow = Shadow::new();
+    let client = launch(shadow.clone(), figment.clone()).await;
+    let response = client.post("/echo").body("hello").dispatch().await;
+    assert_eq!(response.into_string().await.unwrap(), "hello");
+    assert_eq!(shadow.skipped(), 1);
+
+    client.post("/echo").body("hi").dispatch().await;
+    assert!(mirrored.recv().await.unwrap().ends_with("\r\n\r\nhi"));
+
+    let shadow = Shadow::new().rate(0.0);
+    let client = launch(shadow.clone(), figment).await;
+    client.post("/echo").body("hi").dispatch().await;
+    assert_eq!(shadow.in_flight(), 0);
+    assert_eq!(shadow.skipped(), 0);
+    assert!(mirrored.try_recv().is_err());
+}
+
+#[rocket::async_test]
+async fn invalid_configuration_fails_ignition() {
+    let rocket = rocket::build().attach(Shadow::new().upstream("https://canary.internal"));
+    assert!(Client::debug(rocket).await.is_err());
+
+    let rocket = rocket::build().attach(Shadow::new().upstream("canary.internal"));
+    assert!(Client::debug(rocket).await.is_err());
+
+    let rocket = rocket::build().attach(Shadow::new().upstream("http://canary.internal").rate(2.0));
+    assert!(Client::debug(rocket).await.is_err());
+
+    let figment = rocket::Config::figment().merge(("shadow.rate", "often"));
+    assert!(Client::debug(rocket::custom(figment).attach(Shadow::new())).await.is_err());
+
+    // Without an upstream, nothing is mirrored.
+    let shadow = Shadow::new().rate(1.0);
+    let client = launch(shadow.clone(), rocket::Config::figment()).await;
+    client.post("/echo").body("hi").dispatch().await;
+    assert_eq!(shadow.in_flight(), 0);
+    assert_eq!(ShadowConfig::default().upstream, None);
+}
@@ -0,0 +1,141 @@
+#![cfg(feature = "shed")]
+
+#[macro_use] extern crate rocket;
+
+use std::time::Duration;
+
+use rocket::shed::{Shedder, Priority};
+use rocket::http::Status;
+use rocket::figment::providers::{Format, Toml};
+use rocket::local::asynchronous::Client;
+
+#[get("/fast")]
+fn fast() -> &'static str {
+    "fast"
+}
+
+#[get("/slow")]
+async fn slow() -> &'static str {
+    rocket::tokio::time::sleep(Duration::from_millis(20)).await;
+    "slow"
+}
+
+#[get("/health")]
+fn health() -> &'static str {
+    "ok"
+}
+
+#[get("/export/<_n>")]
+async fn export(_n: usize) -> &'static str {
+    rocket::tokio::time::sleep(Duration::from_millis(200)).await;
+    "exported"
+}
+
+#[get("/wait")]
+async fn wait() -> &'static str {
+    rocket::tokio::time::sleep(Duration::from_millis(200)).await;
+    "waited"
+}
+
+async fn client(config: &str) -> Client {
+    let figment = rocket::Config::figment().merge(Toml::string(config));
+    let rocket = rocket::custom(figment)
+        .mount("/", routes![fast, slow, wait, health, export])
+        .attach(Shedder::new().priority("/health", Priority::Critical));
+
+    Client::untracked(rocket).await.unwrap()
+}
+
+fn shed(client: &Client) -> u64 {
+    client.rocket().fairing::<Shedder>().unwrap().shed()
+}
+
+#[rocket::async_test]
+async fn sheds_beyond_max_in_flight() {
+    let client = client("shed = { max_in_flight = 1, retry_after = 2 }").await;
+    let waiting = client.get("/wait").dispatch();
+    let shed_request = async {
+        rocket::tokio::time::sleep(Duration::from_millis(50)).await;
+        client.get("/fast").dispatch().await
+    };
+
+    let (waited, response) = rocket::tokio::join!(waiting, shed_request);
+    assert_eq!(waited.status(), Status::Ok);
+    assert_eq!(response.status(), Status::ServiceUnavailable);
+    assert_eq!(response.headers().get_one("Retry-After"), Some("2"));
+    assert_eq!(shed(&client), 1);
+
+    // Requests are in flight until their responses are dropped.
+    drop(waited);
+    let response = client.get("/fast").dispatch().await;
+    assert_eq!(response.status(), Status::Ok);
+    assert!(response.headers().get_one("Retry-After").is_none());
+
+    drop(response);
+    assert_eq!(client.rocket().fairing::<Shedder>().unwrap().in_flight(), 0);
+}
+
+#[rocket::async_test]
+async fn sheds_when_latency_exceeds_target() {
+    let client = client("shed = { target_millis = 5, interval_millis = 10, status = 429 }").await;
+    assert_eq!(client.get("/slow").dispatch().await.status(), Status::Ok);
+    assert_eq!(client.get("/slow").dispatch().await.status(), Status::Ok);
+
+    // Latency has exceeded the target for an interval: shed.
+    assert_eq!(client.get("/fast").dispatch().await.status(), Status::TooManyRequests);
+    assert_eq!(shed(&client), 1);
+
+    // The next request is admitted, and its latency ends shedding.
+    assert_eq!(client.get("/fast").dispatch().await.status(), Status::Ok);
+    assert_eq!(client.get("/fast").dispatch().await.status(), Status::Ok);
+    assert_eq!(shed(&client), 1);
+}
+
+#[rocket::async_test]
+async fn admits_everything_by_default() {
+    let client = client("").await;
+    for _ in 0..10 {
+        assert_eq!(client.get("/slow").dispatch().await.status(), Status::Ok);
+    }
+
+    assert_eq!(shed(&client), 0);
+}
+
+#[rocket::async_test]
+async fn sheds_by_priority() {
+    let config = r#"shed = { max_in_flight = 2, priorities = { "/export" = "bulk" } }"#;
+    let client = client(config).await;
+    let exports = async {
+        let first = client.get("/export/1").dispatch().await;
+        (first.status(), first)
+    };
+
+    // With one bulk request in flight, half of `max_in_flight`, the next is
+    // shed but normal and critical requests are still admitted.
+    let rest = async {
+        rocket::tokio::time::sleep(Duration::from_millis(50)).await;
+        let second = client.get("/export/2").dispatch().await.status();
+        let waiting = client.get("/wait").dispatch();
+        let more = async {
+            rocket::tokio::time::sleep(Duration::from_millis(50)).await;
+            let normal = client.get("/fast").dispatch().await.status();
+            let critical = client.get("/health").dispatch().await.status();
+            (normal, critical)
+        };
+
+        let (waited, (normal, critical)) = rocket::tokio::join!(waiting, more);
+        (second, waited.status(), normal, critical)
+    };
+
+    let ((first, _), (second, waited, normal, critical)) = rocket::tokio::join!(exports, rest);
+    assert_eq!(first, Status::Ok);
+    assert_eq!(second, Status::ServiceUnavailable);
+    assert_eq!(waited, Status::Ok);
+    assert_eq!(normal, Status::ServiceUnavailable);
+    assert_eq!(critical, Status::Ok);
+
+    let shedder = client.rocket().fairing::<Shedder>().unwrap();
+    assert_eq!(shedder.shed_of(Priority::Bulk), 1);
+    assert_eq!(shedder.shed_of(Priority::Normal), 1);
+    assert_eq!(shedder.shed_of(Priority::Critical), 0);
+}
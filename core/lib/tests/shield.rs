@@ -250,3 +250,129 @@ fn permission_test() {
             "accelerometer=(\"http://rocket.rs\" \"https://rocket.rs\"), usb=()");
     });
 }
+
+#[test]
+fn csp_test() {
+    let shield = Shield::default().enable(Csp::default());
+    dispatch!(shield, |r: LocalResponse<'_>| {
+        assert_header!(r, "Content-Security-Policy", "default-src 'self'");
+    });
+
+    let csp = Csp::default()
+        .directive("img-src", ["'self'", "data:"])
+        .directive("object-src", Vec::<String>::new())
+        .directive("default-src", ["https://rocket.rs"]);
+
+    dispatch!(Shield::default().enable(csp), |r: LocalResponse<'_>| {
+        assert_header!(r, "Content-Security-Policy",
+            "default-src https://rocket.rs; img-src 'self' data:; object-src 'none'");
+    });
+}
+
+fn configured(toml: &str) -> Result<Client, rocket::Error> {
+    use rocket::figment::providers::{Format, Toml};
+
+    let figment = Config::figment().merge(Toml::string(toml));
+    Client::debug(rocket::custom(figment).mount("/", routes![hello]))
+}
+
+#[test]
+fn configured_policies_test() {
+    let client = configured(r#"
+        [shield]
+        csp = { default-src = ["'self'"], frame-ancestors = [] }
+        hsts = { max_age = 600, include_subdomains = true }
+        permissions = { camera = [], usb = ["self", "https://rocket.rs/"], midi = ["self", "*"] }
+        disable = ["x-frame-options"]
+    "#).unwrap();
+
+    let response = client.get("/").dispatch();
+    assert_header!(response, "Content-Security-Policy",
+        "default-src 'self'; frame-ancestors 'none'");
+    assert_header!(response, "Strict-Transport-Security", "max-age=600; includeSubDomains");
+    assert_header!(response, "Permissions-Policy",
+        "camera=(), usb=(self \"https://rocket.rs\"), midi=(*)");
+    assert_header!(response, "X-Content-Type-Options", "nosniff");
+    assert_no_header!(response, "X-Frame-Options");
+
+    // Configuration takes precedence over policies enabled in code.
+    let shield = Shield::new().enable(Hsts::Preload(Duration::days(400)));
+    let client = configured("shield.hsts.max_age = 60").unwrap();
+    let rocket = client.rocket();
+    assert!(rocket.fairing::<Shield>().unwrap().is_enabled::<Hsts>());
+    let response = client.get("/").dispatch();
+    assert_header!(response, "Strict-Transport-Security", "max-age=60");
+
+    let figment = Config::figment().merge(("shield.disable", ["Strict-Transport-Security"]));
+    let rocket = rocket::custom(figment).mount("/", routes![hello]).attach(shield);
+    let client = Client::debug(rocket).unwrap();
+    assert!(!client.rocket().fairing::<Shield>().unwrap().is_enabled::<Hsts>());
+    let response = client.get("/").dispatch();
+    assert_no_header!(response, "Strict-Transport-Security");
+}
+
+#[test]
+fn invalid_configured_policies_test() {
+    assert!(configured("shield.csp = { 'default src' = [] }").is_err());
+    assert!(configured("shield.csp = { default-src = ['a; b'] }").is_err());
+    assert!(configured("shield.permissions = { camera = ['rocket.rs'] }").is_err());
+    assert!(configured("shield.hsts = { max_age = -1 }").is_err());
+    assert!(configured("shield.security_txt = { contact = [] , expires = 'never' }").is_err());
+    assert!(configured("shield.security_txt = { contact = ['mailto:a@b.c'], expires = 'never' }")
+        .is_err());
+}
+
+#[test]
+fn security_txt_test() {
+    let client = configured(r#"
+        [shield.security_txt]
+        contact = ["mailto:security@rocket.rs", "https://rocket.rs/security"]
+        expires = "2030-01-01T00:00:00Z"
+        preferred_languages = ["en", "fr"]
+        policy = ["https://rocket.rs/policy"]
+    "#).unwrap();
+
+    let response = client.get("/.well-known/security.txt").dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.content_type(), Some(rocket::http::ContentType::Plain));
+    assert_eq!(response.into_string().unwrap(), "\
+        Contact: mailto:security@rocket.rs\n\
+        Contact: https://rocket.rs/security\n\
+        Policy: https://rocket.rs/policy\n\
+        Expires: 2030-01-01T00:00:00Z\n\
+        Preferred-Languages: en, fr\n");
+
+    let security_txt = SecurityTxt::new("mailto:security@rocket.rs", "2030-01-01T00:00:00Z");
+    let rocket = rocket::build().mount("/.well-known", security_txt);
+    let client = Client::debug(rocket).unwrap();
+    let response = client.get("/.well-known/security.txt").dispatch();
+    assert_eq!(response.into_string().unwrap(),
+        "Contact: mailto:security@rocket.rs\nExpires: 2030-01-01T00:00:00Z\n");
+}
+
+#[test]
+fn cross_origin_test() {
+    dispatch!(Shield::cross_origin_isolated(), |response: LocalResponse<'_>| {
+        assert_header!(response, "Cross-Origin-Opener-Policy", "same-origin");
+        assert_header!(response, "Cross-Origin-Embedder-Policy", "require-corp");
+        assert_header!(response, "Cross-Origin-Resource-Policy", "same-origin");
+        assert_header!(response, "X-Frame-Options", "SAMEORIGIN");
+    });
+
+    let shield = Shield::cross_origin_isolated()
+        .enable(Coep::Credentialless)
+        .enable(Corp::CrossOrigin);
+
+    dispatch!(shield, |response: LocalResponse<'_>| {
+        assert_header!(response, "Cross-Origin-Opener-Policy", "same-origin");
+        assert_header!(response, "Cross-Origin-Embedder-Policy", "credentialless");
+        assert_header!(response, "Cross-Origin-Resource-Policy", "cross-origin");
+    });
+
+    let shield = Shield::default().enable(Coop::SameOriginAllowPopups);
+    dispatch!(shield, |response: LocalResponse<'_>| {
+        assert_header!(response, "Cross-Origin-Opener-Policy", "same-origin-allow-popups");
+        assert_no_header!(response, "Cross-Origin-Embedder-Policy");
+        assert_no_header!(response, "Cross-Origin-Resource-Policy");
+    });
+}
@@ -0,0 +1,123 @@
+#![cfg(feature = "slo")]
+
+#[macro_use] extern crate rocket;
+
+use std::time::Duration;
+
+use rocket::slo::{SloTracker, Objective};
+use rocket::http::{ContentType, Status};
+use rocket::figment::providers::{Format, Toml};
+use rocket::local::blocking::Client;
+
+#[get("/api/ok")]
+fn ok() -> &'static str {
+    "ok"
+}
+
+#[get("/api/fail")]
+fn fail() -> Status {
+    Status::InternalServerError
+}
+
+#[get("/api/slow")]
+fn slow() -> &'static str {
+    std::thread::sleep(Duration::from_millis(50));
+    "slow"
+}
+
+#[get("/other")]
+fn other() -> Status {
+    Status::InternalServerError
+}
+
+fn launch(config: &str) -> Client {
+    let figment = rocket::Config::figment().merge(Toml::string(config));
+    let rocket = rocket::custom(figment)
+        .mount("/", routes![ok, fail, slow, other])
+        .attach(SloTracker::new().objective("/api", Objective::new(90.0)));
+
+    Client::debug(rocket).unwrap()
+}
+
+fn tracker(client: &Client) -> &SloTracker {
+    client.rocket().fairing::<SloTracker>().unwrap()
+}
+
+#[test]
+fn counts_requests_per_scope() {
+    let client = launch("");
+    for _ in 0..9 {
+        client.get("/api/ok").dispatch();
+    }
+
+    client.get("/api/fail").dispatch();
+    client.get("/other").dispatch();
+
+    let report = tracker(&client).report("/api").unwrap();
+    assert_eq!(report.objective, Objective::new(90.0));
+    assert_eq!((report.requests, report.bad), (10, 1));
+    assert!((report.long_burn_rate - 1.0).abs() < 1e-9);
+    assert!((report.short_burn_rate - 1.0).abs() < 1e-9);
+    assert!(report.budget_remaining.abs() < 1e-9);
+    assert!(!report.alerting);
+    assert!(tracker(&client).report("/other").is_none());
+}
+
+#[test]
+fn slow_requests_miss_latency_objectives() {
+    let client = launch(r#"slo.objectives = { "/api" = "99% < 10ms" }"#);
+    client.get("/api/ok").dispatch();
+    client.get("/api/slow").dispatch();
+
+    let report = tracker(&client).report("/api").unwrap();
+    assert_eq!(report.objective.to_string(), "99% < 10ms");
+    assert_eq!((report.requests, report.bad), (2, 1));
+    assert!(report.budget_remaining < 0.0);
+}
+
+#[test]
+fn fast_burn_alerts() {
+    let client = launch("slo.burn_rate_alert = 5.0");
+    client.get("/api/ok").dispatch();
+    assert!(!tracker(&client).report("/api").unwrap().alerting);
+
+    client.get("/api/fail").dispatch();
+    let report = tracker(&client).report("/api").unwrap();
+    assert!((report.short_burn_rate - 5.0).abs() < 1e-9);
+    assert!(!report.alerting);
+
+    client.get("/api/fail").dispatch();
+    let report = tracker(&client).report("/api").unwrap();
+    assert!(report.short_burn_rate > 5.0 && report.long_burn_rate > 5.0);
+    assert!(report.alerting);
+
+    for _ in 0..10 {
+        client.get("/api/ok").dispatch();
+    }
+
+    assert!(!tracker(&client).report("/api").unwrap().alerting);
+}
+
+#[test]
+fn reports_are_served_when_configured() {
+    let client = launch("");
+    assert_eq!(client.get("/_rocket/slo").dispatch().status(), Status::NotFound);
+
+    let client = launch(r#"slo.path = "/_rocket/slo""#);
+    client.get("/api/fail").dispatch();
+    let response = client.get("/_rocket/slo").dispatch();
+    assert_eq!(response.content_type(), Some(ContentType::JSON));
+
+    let body = response.into_string().unwrap();
+    assert!(body.starts_with(r#"[{"scope":"/api","objective":"90%","requests":1,"bad":1,"#));
+    assert!(body.contains(r#""alerting":false"#));
+}
+
+#[test]
+fn invalid_objectives_fail_ignition() {
+    let figment = rocket::Config::figment().merge(("slo.objectives./api", "100%"));
+    assert!(Client::debug(rocket::custom(figment).attach(SloTracker::new())).is_err());
+
+    let figment = rocket::Config::figment().merge(("slo.objectives./api", "99% < 1m"));
+    assert!(Client::debug(rocket::custom(figment).attach(SloTracker::new())).is_err());
+}
@@ -0,0 +1,91 @@
+#![cfg(feature = "json")]
+
+#[macro_use] extern crate rocket;
+
+use rocket::http::Status;
+use rocket::local::blocking::Client;
+use rocket::serde::Serialize;
+use rocket::serde::json::{self, json, Fields, Fieldset, Sparse, Value};
+
+#[derive(Serialize, Fieldset)]
+#[serde(crate = "rocket::serde")]
+struct User {
+    #[fieldset(always)]
+    id: usize,
+    name: &'static str,
+    #[serde(rename = "mail")]
+    #[fieldset(name = "mail")]
+    email: Option<&'static str>,
+    address: Address,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "rocket::serde")]
+struct Address {
+    city: &'static str,
+    id: usize,
+}
+
+fn users() -> Vec<User> {
+    let (a, b) = (Address { city: "a", id: 10 }, Address { city: "b", id: 20 });
+    vec![
+        User { id: 1, name: "bob", email: None, address: a },
+        User { id: 2, name: "sam", email: Some("s@rocket.rs"), address: b },
+    ]
+}
+
+#[get("/users?<fields>")]
+fn list(fields: Fields) -> Sparse<Vec<User>> {
+    Sparse::new(fields, users())
+}
+
+#[get("/user?<fields>")]
+fn one(fields: Fields) -> Sparse<User> {
+    Sparse::new(fields, users().remove(0))
+}
+
+fn get(client: &Client, uri: &str) -> Value {
+    let response = client.get(uri).dispatch();
+    assert_eq!(response.status(), Status::Ok, "{uri}");
+    response.into_json().unwrap()
+}
+
+#[test]
+fn derived_allowlist() {
+    assert_eq!(User::FIELDS, ["name", "mail", "address"]);
+    assert_eq!(<Vec<User>>::FIELDS, User::FIELDS);
+}
+
+#[test]
+fn sparse_fieldsets() {
+    let client = Client::debug_with(routes![list, one]).unwrap();
+
+    assert_eq!(get(&client, "/user"), json::to_value(&users()[0]).unwrap());
+    assert_eq!(get(&client, "/user?fields="), json::to_value(&users()[0]).unwrap());
+    assert_eq!(get(&client, "/user?fields=name"), json!({ "id": 1, "name": "bob" }));
+    assert_eq!(get(&client, "/user?fields=mail,%20name"), json!({
+        "id": 1,
+        "name": "bob",
+        "mail": null
+    }));
+
+    assert_eq!(get(&client, "/user?fields=address"), json!({
+        "id": 1,
+        "address": { "city": "a", "id": 10 }
+    }));
+
+    assert_eq!(get(&client, "/users?fields=mail"), json!([
+        { "id": 1, "mail": null },
+        { "id": 2, "mail": "s@rocket.rs" },
+    ]));
+
+    for uri in ["/user?fields=id", "/user?fields=email", "/users?fields=name,city"] {
+        assert_eq!(client.get(uri).dispatch().status(), Status::BadRequest, "{uri}");
+    }
+}
+
+#[test]
+fn sparse_serialize() {
+    let sparse = Sparse::new(Fields::new(["name"]), Some(users().remove(1)));
+    assert_eq!(json::to_string(&sparse).unwrap(), r#"{"id":2,"name":"sam"}"#);
+}
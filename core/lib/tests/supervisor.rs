@@ -0,0 +1,129 @@
+#[macro_use] extern crate rocket;
+
+use std::net::Ipv4Addr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use rocket::{Rocket, Build, Request};
+use rocket::http::Method;
+use rocket::figment::Figment;
+use rocket::supervisor::{Handle, State};
+use rocket::tokio::io::{AsyncReadExt, AsyncWriteExt};
+use rocket::tokio::net::TcpStream;
+
+#[get("/")]
+fn index() -> &'static str {
+    "Hello, world!"
+}
+
+#[get("/slow")]
+async fn slow(req: &Request<'_>) -> &'static str {
+    req.set_principal("alice");
+    rocket::tokio::time::sleep(Duration::from_secs(30)).await;
+    "Finally!"
+}
+
+fn build() -> Rocket<Build> {
+    let figment = Figment::from(rocket::Config::debug_default())
+        .merge(("address", Ipv4Addr::LOCALHOST))
+        .merge(("port", 0));
+
+    rocket::custom(figment).mount("/", routes![index])
+}
+
+#[rocket::async_test]
+async fn supervised_servers_run_reload_and_exit() {
+    let builds = Arc::new(AtomicUsize::new(0));
+    let handle = Handle::launch({
+        let builds = builds.clone();
+        move || {
+            builds.fetch_add(1, Ordering::Relaxed);
+            build()
+        }
+    });
+
+    let endpoints = handle.ready().await.unwrap();
+    assert_eq!(handle.state(), State::Running);
+    assert_eq!(handle.stats().launches, 1);
+
+    let addr = endpoints[0].tcp().unwrap();
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    stream.write_all(b"GET / HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+        .await.unwrap();
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    assert!(response.ends_with("Hello, world!"));
+    assert_eq!(handle.stats().requests, 1);
+
+    handle.reload();
+    while handle.stats().launches < 2 {
+        rocket::tokio::task::yield_now().await;
+    }
+
+    assert!(handle.ready().await.is_some());
+    assert_eq!(builds.load(Ordering::Relaxed), 2);
+
+    handle.shutdown();
+    assert!(handle.wait().await.is_ok());
+}
+
+#[rocket::async_test]
+async fn early_shutdown_happens_at_liftoff() {
+    let handle = Handle::launch(build);
+    handle.shutdown();
+    assert!(handle.wait().await.is_ok());
+}
+
+#[rocket::async_test]
+async fn failed_launches_exit() {
+    let handle = Handle::launch(|| {
+        let figment = Figment::from(rocket::Config::debug_default()).merge(("port", "invalid"));
+        rocket::custom(figment)
+    });
+    assert!(handle.ready().await.is_none());
+    assert_eq!(handle.state(), State::Exited);
+    assert!(handle.wait().await.is_err());
+}
+
+#[rocket::async_test]
+async fn force_terminated_requests_are_reported() {
+    let handle = Handle::launch(|| {
+        let mut config = rocket::Config::debug_default();
+        config.shutdown.grace = 0;
+        config.shutdown.mercy = 0;
+        config.shutdown.drain_report = true;
+
+        let figment = Figment::from(config)
+            .merge(("address", Ipv4Addr::LOCALHOST))
+            .merge(("port", 0));
+
+        rocket::custom(figment).mount("/", routes![index, slow])
+    });
+
+    let endpoints = handle.ready().await.unwrap();
+    assert!(handle.drain_report().is_none());
+
+    let mut stream = TcpStream::connect(endpoints[0].tcp().unwrap()).await.unwrap();
+    stream.write_all(b"GET /slow HTTP/1.1\r\nHost: localhost\r\n\r\n").await.unwrap();
+    while handle.stats().active == 0 {
+        rocket::tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    handle.shutdown();
+    while !handle.is_finished() {
+        rocket::tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    let report = handle.drain_report().unwrap();
+    assert_eq!(report.count, 1);
+    assert_eq!(report.terminated.len(), 1);
+
+    let request = &report.terminated[0];
+    assert_eq!(request.method, Method::Get);
+    assert_eq!(request.uri, "/slow");
+    assert_eq!(request.route.as_deref(), Some("slow"));
+    assert_eq!(request.principal.as_deref(), Some("alice"));
+    assert!(request.elapsed < Duration::from_secs(30));
+}
@@ -0,0 +1,55 @@
+#[macro_use] extern crate rocket;
+
+use std::time::{Duration, Instant};
+
+use rocket::Config;
+use rocket::data::ToByteUnit;
+use rocket::figment::providers::{Format, Toml};
+use rocket::local::blocking::Client;
+use rocket::response::{Throttle, Throttled};
+
+const BODY: &str = "0123456789";
+
+#[get("/throttled")]
+fn throttled() -> Throttled<String> {
+    Throttled::new(BODY.repeat(20), 1.kilobytes())
+}
+
+#[get("/<_..>")]
+fn body() -> String {
+    BODY.repeat(20)
+}
+
+fn client() -> Client {
+    let figment = Config::figment().merge(Toml::string(r#"
+        throttle = { "/slow" = "1KB", "/slow/fast" = "1MB" }
+    "#));
+
+    let rocket = rocket::custom(figment)
+        .mount("/", routes![throttled, body])
+        .attach(Throttle::new());
+
+    Client::debug(rocket).unwrap()
+}
+
+fn timed(client: &Client, uri: &str) -> Duration {
+    let start = Instant::now();
+    let response = client.get(uri).dispatch();
+    assert_eq!(response.into_string().unwrap(), BODY.repeat(20));
+    start.elapsed()
+}
+
+#[test]
+fn throttled_responder() {
+    // 200 bytes at 1000 B/s with a 100 byte burst takes at least 100ms.
+    let client = client();
+    assert!(timed(&client, "/throttled") >= Duration::from_millis(90));
+}
+
+#[test]
+fn configured_scopes() {
+    let client = client();
+    assert!(timed(&client, "/slow/file") >= Duration::from_millis(90));
+    assert!(timed(&client, "/slow/fast/file") < Duration::from_millis(90));
+    assert!(timed(&client, "/slowly") < Duration::from_millis(90));
+}
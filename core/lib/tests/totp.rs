@@ -0,0 +1,145 @@
+#![cfg(feature = "totp")]
+
+#[macro_use] extern crate rocket;
+
+use std::time::{Duration, UNIX_EPOCH};
+
+use rocket::auth::totp::*;
+use rocket::http::{CookieJar, Status};
+use rocket::local::blocking::Client;
+
+#[test]
+fn rfc6238_test_vectors() {
+    let sha1 = Totp::new(Secret::from_bytes(b"12345678901234567890"));
+    let sha256 = Totp::new(Secret::from_bytes(b"12345678901234567890123456789012"))
+        .algorithm(Algorithm::Sha256);
+    let sha512 = Totp::new(Secret::from_bytes(
+        b"1234567890123456789012345678901234567890123456789012345678901234"
+    )).algorithm(Algorithm::Sha512);
+
+    let vectors = [
+        (59, "94287082", "46119246", "90693936"),
+        (1111111109, "07081804", "68084774", "25091201"),
+        (1234567890, "89005924", "91819424", "93441116"),
+        (2000000000, "69279037", "90698825", "38618901"),
+    ];
+
+    for (secs, expected_sha1, expected_sha256, expected_sha512) in vectors {
+        let time = UNIX_EPOCH + Duration::from_secs(secs);
+        assert_eq!(sha1.clone().digits(8).code_at(time), expected_sha1);
+        assert_eq!(sha256.clone().digits(8).code_at(time), expected_sha256);
+        assert_eq!(sha512.clone().digits(8).code_at(time), expected_sha512);
+        assert_eq!(sha1.code_at(time), expected_sha1[2..]);
+    }
+}
+
+#[test]
+fn verification_allows_drift() {
+    let totp = Totp::new(Secret::generate());
+    let now = UNIX_EPOCH + Duration::from_secs(1_700_000_010);
+    let step = 1_700_000_010 / 30;
+    let code = totp.code_at(now);
+
+    assert_eq!(totp.verify_at(&code, now), Some(step));
+    assert_eq!(totp.verify_at(&code, now + Duration::from_secs(30)), Some(step));
+    assert_eq!(totp.verify_at(&code, now - Duration::from_secs(30)), Some(step));
+    assert_eq!(totp.verify_at(&code, now + Duration::from_secs(60)), None);
+    assert_eq!(totp.clone().skew(0).verify_at(&code, now + Duration::from_secs(30)), None);
+    assert_eq!(totp.clone().skew(2).verify_at(&code, now + Duration::from_secs(60)), Some(step));
+
+    let spaced = format!("{} {}", &code[..3], &code[3..]);
+    assert_eq!(totp.verify_at(&spaced, now), Some(step));
+    assert_eq!(totp.verify_at("", now), None);
+    assert_eq!(totp.verify_at(&code[..5], now), None);
+
+    let minute = totp.clone().period(Duration::from_secs(60));
+    let code = minute.code_at(now);
+    assert_eq!(minute.verify_at(&code, now + Duration::from_secs(50)), Some(step / 2));
+}
+
+#[test]
+fn secrets_and_provisioning() {
+    let secret = Secret::generate();
+    assert_eq!(secret.as_bytes().len(), Secret::LEN);
+    assert_eq!(Secret::from_base32(&secret.to_base32()), Some(secret.clone()));
+    assert_eq!(format!("{secret:?}"), "Secret(..)");
+
+    assert_eq!(Secret::from_bytes(b"foobar").to_base32(), "MZXW6YTBOI");
+    assert_eq!(Secret::from_base32("MZXW6YTBOI======").unwrap().as_bytes(), b"foobar");
+    assert!(Secret::from_base32("not base32!").is_none());
+    assert!(Secret::from_base32("").is_none());
+
+    let totp = Totp::new(Secret::from_bytes(b"foobar"))
+        .algorithm(Algorithm::Sha256)
+        .digits(8)
+        .period(Duration::from_secs(60));
+
+    assert_eq!(totp.provisioning_uri("ACME: Co", "bob"),
+        "otpauth://totp/ACME%3A%20Co:bob?secret=MZXW6YTBOI&issuer=ACME%3A%20Co\
+        &algorithm=SHA256&digits=8&period=60");
+}
+
+#[test]
+fn recovery_codes_are_single_use() {
+    let codes = recovery_codes(8);
+    assert_eq!(codes.len(), 8);
+    assert!(codes.iter().all(|c| c.len() == 11 && c.as_bytes()[5] == b'-'));
+
+    let mut hashes: Vec<_> = codes.iter().map(|c| hash_recovery_code(c)).collect();
+    assert_eq!(hash_recovery_code(&codes[0]), hash_recovery_code(&codes[0].replace('-', " ")));
+
+    assert!(redeem_recovery_code(&codes[0], &mut hashes));
+    assert!(!redeem_recovery_code(&codes[0], &mut hashes));
+    assert!(redeem_recovery_code(&codes[7].to_uppercase().replace('-', ""), &mut hashes));
+    assert!(!redeem_recovery_code("aaaaa-aaaaa", &mut hashes));
+    assert_eq!(hashes.len(), 6);
+}
+
+#[post("/verify")]
+fn verify(jar: &CookieJar<'_>) {
+    StepUp::grant(jar, Duration::from_secs(300));
+}
+
+#[post("/expired")]
+fn expired(jar: &CookieJar<'_>) {
+    StepUp::grant(jar, Duration::ZERO);
+}
+
+#[post("/revoke")]
+fn revoke(jar: &CookieJar<'_>) {
+    StepUp::revoke(jar);
+}
+
+#[get("/sensitive")]
+fn sensitive(step_up: StepUp) -> String {
+    let remaining = step_up.expires_at().duration_since(step_up.verified_at()).unwrap();
+    format!("{}", remaining.as_secs())
+}
+
+#[test]
+fn step_up_guard() {
+    let routes = routes![verify, expired, revoke, sensitive];
+    let client = Client::tracked(rocket::build().mount("/", routes)).unwrap();
+
+    let response = client.get("/sensitive").dispatch();
+    assert_eq!(response.status(), Status::Unauthorized);
+
+    client.post("/verify").dispatch();
+    let response = client.get("/sensitive").dispatch();
+    assert_eq!(response.into_string().unwrap(), "300");
+
+    client.post("/revoke").dispatch();
+    let response = client.get("/sensitive").dispatch();
+    assert_eq!(response.status(), Status::Unauthorized);
+
+    client.post("/expired").dispatch();
+    let response = client.get("/sensitive").dispatch();
+    assert_eq!(response.status(), Status::Unauthorized);
+
+    // A plain cookie with the same name isn't accepted.
+    let response = client.get("/sensitive")
+        .cookie(("rocket_step_up", "0:99999999999"))
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Unauthorized);
+}
@@ -0,0 +1,135 @@
+#![cfg(feature = "transform")]
+
+#[macro_use] extern crate rocket;
+
+use rocket::http::{ContentType, Header, Method, Status};
+use rocket::local::blocking::Client;
+use rocket::request::{FromRequest, Outcome, Request};
+use rocket::serde::json::{json, Value};
+use rocket::transform::{Transforms, TransformRule, TransformActions};
+
+#[post("/v2/accounts?<tag>", data = "<account>")]
+fn create(account: Value, tag: Option<&str>) -> Value {
+    json!({ "account": account, "tag": tag })
+}
+
+struct Keys(Option<String>, Option<String>);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for Keys {
+    type Error = ();
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, ()> {
+        let header = |name| req.headers().get_one(name).map(String::from);
+        Outcome::Success(Keys(header("Authorization"), header("X-Api-Key")))
+    }
+}
+
+#[get("/v2/accounts/<id>")]
+fn account(id: usize, keys: Keys) -> Value {
+    json!({ "id": id, "auth": keys.0, "key": keys.1 })
+}
+
+#[get("/plain")]
+fn plain() -> &'static str {
+    "hello"
+}
+
+fn client(transforms: Transforms) -> Client {
+    let rocket = rocket::build()
+        .mount("/", routes![create, account, plain])
+        .attach(transforms);
+
+    Client::debug(rocket).unwrap()
+}
+
+#[test]
+fn requests_are_rewritten() {
+    let rule = TransformRule::new("/v1/users")
+        .rewrite("/v2/accounts")
+        .request(TransformActions::new()
+            .rename("X-Api-Key", "Authorization")
+            .body(r#"{ "name": {{ body.username | upper | json }}, "via": {{ path | json }} }"#));
+
+    let client = client(Transforms::new().rule(rule));
+    let response = client.post("/v1/users?tag=old")
+        .header(ContentType::JSON)
+        .body(r#"{ "username": "bob" }"#)
+        .dispatch();
+
+    let body: Value = response.into_json().unwrap();
+    assert_eq!(body, json!({ "account": { "name": "BOB", "via": "/v1/users" }, "tag": "old" }));
+
+    let response = client.get("/v1/users/7").header(Header::new("X-Api-Key", "k")).dispatch();
+    let body: Value = response.into_json().unwrap();
+    assert_eq!(body, json!({ "id": 7, "auth": "k", "key": null }));
+
+    // Paths outside of the rule are left as they are.
+    assert_eq!(client.get("/v1/usersx/7").dispatch().status(), Status::NotFound);
+}
+
+#[test]
+fn responses_are_transformed() {
+    let rule = TransformRule::new("/")
+        .method(Method::Get)
+        .response(TransformActions::new()
+            .set("X-Status", "{{ status }}")
+            .set("X-Id", r#"{{ body.id | default("none") }}"#)
+            .body(r#"{ "data": {{ body | json }}, "user": {{ header.X-User | json }} }"#));
+
+    let client = client(Transforms::new().rule(rule));
+    let response = client.get("/v2/accounts/3").header(Header::new("X-User", "me")).dispatch();
+    assert_eq!(response.headers().get_one("X-Status"), Some("200"));
+    assert_eq!(response.headers().get_one("X-Id"), Some("3"));
+    let body: Value = response.into_json().unwrap();
+    assert_eq!(body["data"]["id"], 3);
+    assert_eq!(body["user"], "me");
+
+    // Non-JSON bodies are missing; the method must match.
+    let response = client.get("/plain").dispatch();
+    assert_eq!(response.headers().get_one("X-Id"), Some("none"));
+    assert_eq!(response.into_string().unwrap(), r#"{ "data": null, "user": null }"#);
+
+    let response = client.post("/v2/accounts").json(&json!({ "name": "a" })).dispatch();
+    assert!(response.headers().get_one("X-Status").is_none());
+}
+
+#[test]
+fn configured_rules_apply_after_programmatic_ones() {
+    let figment = rocket::Config::figment().merge(("transforms", [json!({
+        "path": "/plain",
+        "response": { "rename": { "X-First": "X-Second" } },
+    })]));
+
+    let rule = TransformRule::new("/plain")
+        .response(TransformActions::new().set("X-First", "{{ method | lower }}").remove("Server"));
+
+    let rocket = rocket::custom(figment)
+        .mount("/", routes![plain])
+        .attach(Transforms::new().rule(rule));
+
+    let client = Client::debug(rocket).unwrap();
+    let response = client.get("/plain").dispatch();
+    assert!(response.headers().get_one("X-First").is_none());
+    assert_eq!(response.headers().get_one("X-Second"), Some("get"));
+    assert!(response.headers().get_one("Server").is_none());
+    assert_eq!(response.into_string().unwrap(), "hello");
+}
+
+#[test]
+fn invalid_rules_fail_ignition() {
+    let invalid = [
+        TransformRule::new("/").rewrite("/a?b"),
+        TransformRule::new("/").request(TransformActions::new().set("X", "{{ nope }}")),
+        TransformRule::new("/").response(TransformActions::new().body("{{ path ")),
+        TransformRule::new("/").request(TransformActions::new().rename("X", "Bad Name")),
+    ];
+
+    for rule in invalid {
+        let rocket = rocket::build().attach(Transforms::new().rule(rule));
+        assert!(Client::debug(rocket).is_err());
+    }
+
+    let figment = rocket::Config::figment().merge(("transforms", "nope"));
+    assert!(Client::debug(rocket::custom(figment).attach(Transforms::new())).is_err());
+}
@@ -0,0 +1,116 @@
+#![cfg(feature = "tunnel")]
+
+use std::net::Ipv4Addr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use rocket::fairing::AdHoc;
+use rocket::figment::Figment;
+use rocket::http::{Method, Status, uri::Authority};
+use rocket::local::blocking::Client;
+use rocket::tokio::io::{AsyncReadExt, AsyncWriteExt};
+use rocket::tokio::net::{TcpListener, TcpStream};
+use rocket::tunnel::{Tunnel, TunnelConfig};
+
+#[test]
+fn config_allows_listed_targets() {
+    let config = TunnelConfig {
+        allow: vec!["db.internal:5432".into(), "*.svc.internal:*".into(), "*:22".into()],
+        ..Default::default()
+    };
+
+    assert!(config.allows("db.internal", 5432));
+    assert!(config.allows("DB.Internal", 5432));
+    assert!(!config.allows("db.internal", 5433));
+    assert!(!config.allows("db.internal.evil", 5432));
+
+    assert!(config.allows("api.svc.internal", 443));
+    assert!(config.allows("a.b.svc.internal", 8080));
+    assert!(!config.allows("svc.internal", 443));
+    assert!(!config.allows("evilsvc.internal", 443));
+
+    assert!(config.allows("anything", 22));
+    assert!(!TunnelConfig::default().allows("db.internal", 5432));
+}
+
+#[test]
+fn connect_requests_are_checked() {
+    let rocket = rocket::build().attach(Tunnel::new().allow("db.internal:5432"));
+    let client = Client::debug(rocket).unwrap();
+
+    let mut request = client.req(Method::Connect, "/");
+    request.inner_mut().set_connect_target(Authority::parse("db.internal:22").unwrap());
+    assert_eq!(request.dispatch().status(), Status::Forbidden);
+
+    let mut request = client.req(Method::Connect, "/");
+    request.inner_mut().set_connect_target(Authority::parse("db.internal").unwrap());
+    assert_eq!(request.dispatch().status(), Status::BadRequest);
+
+    assert_eq!(client.req(Method::Connect, "/").dispatch().status(), Status::BadRequest);
+
+    let tunnel = client.rocket().fairing::<Tunnel>().unwrap();
+    assert_eq!(tunnel.opened(), 0);
+}
+
+#[test]
+fn invalid_config_fails_ignition() {
+    let figment = rocket::Config::figment().merge(("tunnel.allow", 10));
+    assert!(Client::debug(rocket::custom(figment).attach(Tunnel::new())).is_err());
+}
+
+#[rocket::async_test]
+async fn tunnels_carry_bytes_to_allowed_targets() {
+    let target = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+    let target_addr = target.local_addr().unwrap();
+    rocket::tokio::spawn(async move {
+        let (mut stream, _) = target.accept().await.unwrap();
+        let mut buf = [0; 4];
+        stream.read_exact(&mut buf).await.unwrap();
+        stream.write_all(&buf).await.unwrap();
+    });
+
+    let figment = Figment::from(rocket::Config::debug_default())
+        .merge(("address", Ipv4Addr::LOCALHOST))
+        .merge(("port", 0))
+        .merge(("tunnel.allow", [target_addr.to_string()]));
+
+    let tunnel = Tunnel::new();
+    let tunneled = Arc::new(AtomicBool::new(false));
+    let rocket = rocket::custom(figment)
+        .attach(tunnel.clone())
+        .attach(AdHoc::on_liftoff("Client", {
+            let tunneled = tunneled.clone();
+            move |rocket| Box::pin(async move {
+                let addr = rocket.endpoints().next().unwrap().tcp().unwrap();
+                let shutdown = rocket.shutdown();
+                let exchange = rocket::tokio::spawn(async move {
+                    let mut stream = TcpStream::connect(addr).await.unwrap();
+                    let request = format!("CONNECT {target_addr} HTTP/1.1\r\n\
+                        Host: {target_addr}\r\n\r\n");
+
+                    stream.write_all(request.as_bytes()).await.unwrap();
+                    let mut head = vec![];
+                    while !head.ends_with(b"\r\n\r\n") {
+                        head.push(stream.read_u8().await.unwrap());
+                    }
+
+                    assert!(String::from_utf8(head).unwrap().starts_with("HTTP/1.1 200"));
+                    stream.write_all(b"ping").await.unwrap();
+                    let mut buf = [0; 4];
+                    stream.read_exact(&mut buf).await.unwrap();
+                    assert_eq!(&buf, b"ping");
+                });
+
+                rocket::tokio::spawn(async move {
+                    tunneled.store(exchange.await.is_ok(), Ordering::Release);
+                    shutdown.notify();
+                });
+            })
+        }));
+
+    rocket.launch().await.unwrap();
+    assert!(tunneled.load(Ordering::Acquire));
+    assert_eq!(tunnel.opened(), 1);
+    assert_eq!(tunnel.bytes_sent(), 4);
+    assert_eq!(tunnel.bytes_received(), 4);
+}
@@ -0,0 +1,108 @@
+#[macro_use] extern crate rocket;
+
+use std::net::Ipv4Addr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use rocket::data::{Framed, ToByteUnit};
+use rocket::fairing::AdHoc;
+use rocket::figment::Figment;
+use rocket::http::{Header, Status};
+use rocket::local::blocking::Client;
+use rocket::response::Upgrade;
+use rocket::tokio::io::{AsyncReadExt, AsyncWriteExt};
+use rocket::tokio::net::TcpStream;
+
+#[get("/echo")]
+fn echo() -> Upgrade<'static> {
+    Upgrade::new("x-echo", |io, _| Box::pin(async move {
+        let mut framed = Framed::new(io);
+        while let Some(frame) = framed.recv().await? {
+            framed.send(&frame).await?;
+        }
+
+        Ok(())
+    }))
+}
+
+#[test]
+fn non_upgrade_requests_require_upgrade() {
+    let client = Client::debug(rocket::build().mount("/", routes![echo])).unwrap();
+    let response = client.get("/echo").dispatch();
+    assert_eq!(response.status(), Status::UpgradeRequired);
+    assert_eq!(response.headers().get_one("Upgrade"), Some("x-echo"));
+
+    let response = client.get("/echo")
+        .header(Header::new("Connection", "upgrade"))
+        .header(Header::new("Upgrade", "h2c"))
+        .dispatch();
+
+    assert_eq!(response.status(), Status::UpgradeRequired);
+}
+
+#[rocket::async_test]
+async fn framed_round_trips_and_limits_frames() {
+    let (client, server) = rocket::tokio::io::duplex(64);
+    let mut client = Framed::new(client);
+    let mut server = Framed::new(server).max_frame(8.bytes());
+
+    client.send(b"").await.unwrap();
+    client.send(b"hello").await.unwrap();
+    assert_eq!(server.recv().await.unwrap().unwrap(), b"");
+    assert_eq!(server.recv().await.unwrap().unwrap(), b"hello");
+
+    client.send(b"too long!").await.unwrap();
+    assert!(server.recv().await.is_err());
+    assert!(server.send(b"too long!").await.is_err());
+
+    let (client, server) = rocket::tokio::io::duplex(64);
+    let mut server = Framed::new(server);
+    let mut client = client;
+    client.write_all(&[0, 0, 0, 4, b'a']).await.unwrap();
+    drop(client);
+    assert!(server.recv().await.is_err());
+}
+
+#[rocket::async_test]
+async fn upgraded_connections_speak_the_protocol() {
+    let figment = Figment::from(rocket::Config::debug_default())
+        .merge(("address", Ipv4Addr::LOCALHOST))
+        .merge(("port", 0));
+
+    let exchanged = Arc::new(AtomicBool::new(false));
+    let rocket = rocket::custom(figment)
+        .mount("/", routes![echo])
+        .attach(AdHoc::on_liftoff("Client", {
+            let exchanged = exchanged.clone();
+            move |rocket| Box::pin(async move {
+                let addr = rocket.endpoints().next().unwrap().tcp().unwrap();
+                let shutdown = rocket.shutdown();
+                let exchange = rocket::tokio::spawn(async move {
+                    let mut stream = TcpStream::connect(addr).await.unwrap();
+                    stream.write_all(b"GET /echo HTTP/1.1\r\nHost: localhost\r\n\
+                        Connection: Upgrade\r\nUpgrade: x-echo\r\n\r\n").await.unwrap();
+
+                    let mut head = vec![];
+                    while !head.ends_with(b"\r\n\r\n") {
+                        head.push(stream.read_u8().await.unwrap());
+                    }
+
+                    let head = String::from_utf8(head).unwrap();
+                    assert!(head.starts_with("HTTP/1.1 101"));
+                    assert!(head.to_ascii_lowercase().contains("upgrade: x-echo"));
+
+                    let mut framed = Framed::new(stream);
+                    framed.send(b"ping").await.unwrap();
+                    assert_eq!(framed.recv().await.unwrap().unwrap(), b"ping");
+                });
+
+                rocket::tokio::spawn(async move {
+                    exchanged.store(exchange.await.is_ok(), Ordering::Release);
+                    shutdown.notify();
+                });
+            })
+        }));
+
+    rocket.launch().await.unwrap();
+    assert!(exchanged.load(Ordering::Acquire));
+}
@@ -0,0 +1,101 @@
+#[macro_use] extern crate rocket;
+
+use rocket::form::Form;
+use rocket::fs::{TempFile, UploadScanner, Scanner, ScanError};
+use rocket::http::{ContentType, Status};
+use rocket::local::blocking::Client;
+use rocket::tokio::io::AsyncReadExt;
+
+const EICAR: &str = "X5O!P%@AP[4\\PZX54(P^)7CC)7}$EICAR-STANDARD-ANTIVIRUS-TEST-FILE!$H+H*";
+
+struct Signatures;
+
+#[rocket::async_trait]
+impl UploadScanner for Signatures {
+    async fn scan(&self, file: &TempFile<'_>) -> Result<(), ScanError> {
+        let mut content = String::new();
+        file.open().await?.read_to_string(&mut content).await?;
+        if content.contains("EICAR") {
+            return Err(ScanError::Infected("Eicar-Test-Signature".into()));
+        } else if content.contains("embargoed") {
+            return Err(ScanError::Prohibited("embargoed content".into()));
+        }
+
+        Ok(())
+    }
+}
+
+#[post("/", data = "<file>")]
+fn upload(file: TempFile<'_>) -> String {
+    file.len().to_string()
+}
+
+#[derive(FromForm)]
+struct Upload<'r> {
+    file: TempFile<'r>,
+}
+
+#[post("/form", data = "<form>")]
+fn form(form: Form<Upload<'_>>) -> String {
+    form.file.len().to_string()
+}
+
+fn client(scanner: bool) -> Client {
+    let rocket = rocket::build().mount("/", routes![upload, form]);
+    match scanner {
+        true => Client::debug(rocket.manage(Scanner::new(Signatures))).unwrap(),
+        false => Client::debug(rocket).unwrap(),
+    }
+}
+
+#[test]
+fn clean_uploads_are_accepted() {
+    let scanned = client(true);
+    let response = scanned.post("/").header(ContentType::Plain).body("hello").dispatch();
+    assert_eq!(response.into_string().unwrap(), "5");
+
+    let unscanned = client(false);
+    let response = unscanned.post("/").header(ContentType::Plain).body(EICAR).dispatch();
+    assert_eq!(response.into_string().unwrap(), EICAR.len().to_string());
+}
+
+#[test]
+fn rejected_uploads_map_to_statuses() {
+    let client = client(true);
+    let response = client.post("/").header(ContentType::Plain).body(EICAR).dispatch();
+    assert_eq!(response.status(), Status::UnprocessableEntity);
+
+    let response = client.post("/").body("some embargoed text").dispatch();
+    assert_eq!(response.status(), Status::UnavailableForLegalReasons);
+}
+
+fn multipart(content: &str) -> String {
+    [
+        "--X-BOUNDARY",
+        r#"Content-Disposition: form-data; name="file"; filename="file.txt""#,
+        "Content-Type: text/plain",
+        "",
+        content,
+        "--X-BOUNDARY--",
+        "",
+    ].join("\r\n")
+}
+
+#[test]
+fn form_fields_are_scanned() {
+    let client = client(true);
+    let form_data: ContentType = "multipart/form-data; boundary=X-BOUNDARY".parse().unwrap();
+    let response = client.post("/form")
+        .header(form_data.clone())
+        .body(multipart("hello"))
+        .dispatch();
+
+    assert_eq!(response.into_string().unwrap(), "5");
+
+    let response = client.post("/form")
+        .header(form_data)
+        .body(multipart("embargoed"))
+        .dispatch();
+
+    assert_eq!(response.status(), Status::UnavailableForLegalReasons);
+}
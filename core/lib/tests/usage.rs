@@ -0,0 +1,105 @@
+#[macro_use] extern crate rocket;
+
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use rocket::Config;
+use rocket::fairing::AdHoc;
+use rocket::request::Usage;
+use rocket::http::Header;
+use rocket::local::blocking::{Client, LocalResponse};
+
+#[post("/echo", data = "<body>")]
+fn echo(body: String, usage: &Usage) -> String {
+    format!("{}:{}", body, usage.request_body())
+}
+
+#[get("/echo")]
+fn get_echo() -> &'static str {
+    "hi"
+}
+
+fn client() -> (Client, Arc<Mutex<Vec<Usage>>>, Arc<AtomicUsize>) {
+    let (usages, completions) = (Arc::new(Mutex::new(vec![])), Arc::new(AtomicUsize::new(0)));
+    let (log, count) = (usages.clone(), completions.clone());
+    let config = Config { metering: true, ..Config::debug_default() };
+    let rocket = rocket::custom(config)
+        .mount("/", routes![echo, get_echo])
+        .attach(AdHoc::on_request("Meter", move |req, _| {
+            let count = count.clone();
+            Usage::of(req).on_complete(move |_| { count.fetch_add(1, Ordering::SeqCst); });
+            Box::pin(async {})
+        }))
+        .attach(AdHoc::on_response("Log", move |req, _| {
+            log.lock().unwrap().push(Usage::of(req).clone());
+            Box::pin(async {})
+        }));
+
+    (Client::untracked(rocket).unwrap(), usages, completions)
+}
+
+fn response_head(response: &LocalResponse<'_>) -> u64 {
+    let headers: usize = response.headers().iter()
+        .map(|h| format!("{}: {}\r\n", h.name(), h.value()).len())
+        .sum();
+
+    (format!("HTTP/1.1 {}\r\n", response.status()).len() + headers + 2) as u64
+}
+
+#[test]
+fn counts_request_and_response_bytes() {
+    let (client, usages, completions) = client();
+    let response = client.post("/echo").body("hello").dispatch();
+    let head = response_head(&response);
+    assert_eq!(response.into_string().unwrap(), "hello:5");
+
+    let usage = usages.lock().unwrap().pop().unwrap();
+    assert!(usage.is_complete());
+    assert_eq!(completions.load(Ordering::SeqCst), 1);
+    assert_eq!(usage.request_head(), "POST /echo HTTP/1.1\r\n\r\n".len() as u64);
+    assert_eq!(usage.request_body(), 5);
+    assert_eq!(usage.response_head(), head);
+    assert_eq!(usage.response_body(), 7);
+    assert_eq!(usage.total(), usage.received() + usage.sent());
+}
+
+#[test]
+fn completes_when_response_is_dropped() {
+    let (client, usages, completions) = client();
+    let response = client.get("/echo").header(Header::new("X-Foo", "bar")).dispatch();
+    let usage = usages.lock().unwrap().pop().unwrap();
+    assert!(!usage.is_complete());
+    assert_eq!(usage.request_head(), "GET /echo HTTP/1.1\r\nX-Foo: bar\r\n\r\n".len() as u64);
+    assert_eq!(completions.load(Ordering::SeqCst), 0);
+
+    drop(response);
+    assert!(usage.is_complete());
+    assert_eq!(usage.response_body(), 0);
+    assert_eq!(completions.load(Ordering::SeqCst), 1);
+
+    // Callbacks registered after completion are called immediately.
+    let called = Arc::new(AtomicUsize::new(0));
+    let inner = called.clone();
+    usage.on_complete(move |_| { inner.fetch_add(1, Ordering::SeqCst); });
+    assert_eq!(called.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn head_responses_have_no_body() {
+    let (client, usages, _) = client();
+    let response = client.head("/echo").dispatch();
+    assert!(response.into_string().unwrap_or_default().is_empty());
+
+    let usage = usages.lock().unwrap().pop().unwrap();
+    assert!(usage.is_complete());
+    assert!(usage.response_head() > 0);
+    assert_eq!(usage.response_body(), 0);
+}
+
+#[test]
+fn nothing_is_metered_unless_enabled() {
+    let rocket = rocket::build().mount("/", routes![echo]);
+    let client = Client::debug(rocket).unwrap();
+    let response = client.post("/echo").body("hello").dispatch();
+    assert_eq!(response.into_string().unwrap(), "hello:0");
+}
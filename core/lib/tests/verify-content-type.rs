@@ -0,0 +1,104 @@
+#[macro_use] extern crate rocket;
+
+use rocket::form::Form;
+use rocket::http::{ContentType, Status};
+use rocket::local::blocking::Client;
+
+const PNG: &[u8] = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR\0\0\0\x01";
+
+#[post("/", data = "<body>")]
+#[data(verify_content_type)]
+fn verified(body: Vec<u8>) -> String {
+    body.len().to_string()
+}
+
+#[post("/unverified", data = "<body>")]
+fn unverified(body: Vec<u8>) -> String {
+    body.len().to_string()
+}
+
+#[derive(FromForm)]
+struct Upload<'r> {
+    file: Option<&'r [u8]>,
+}
+
+#[data(verify_content_type)]
+#[post("/form", data = "<form>")]
+fn form(form: Form<Upload<'_>>) -> String {
+    form.file.map_or(0, |file| file.len()).to_string()
+}
+
+fn client() -> Client {
+    Client::debug_with(routes![verified, unverified, form]).unwrap()
+}
+
+#[test]
+fn option_is_recorded() {
+    let routes = routes![verified, unverified, form];
+    assert!(routes[0].verify_content_type);
+    assert!(!routes[1].verify_content_type);
+    assert!(routes[2].verify_content_type);
+}
+
+#[test]
+fn matching_bodies_are_accepted() {
+    let client = client();
+    let response = client.post("/").header(ContentType::PNG).body(PNG).dispatch();
+    assert_eq!(response.into_string().unwrap(), PNG.len().to_string());
+
+    let response = client.post("/").header(ContentType::JSON).body("[1, 2]").dispatch();
+    assert_eq!(response.into_string().unwrap(), "6");
+
+    let response = client.post("/").header(ContentType::Binary).body("\x01\x02").dispatch();
+    assert_eq!(response.into_string().unwrap(), "2");
+}
+
+#[test]
+fn spoofed_bodies_are_rejected() {
+    let client = client();
+    let html = "<html><script>alert(1)</script></html>";
+    let response = client.post("/").header(ContentType::PNG).body(html).dispatch();
+    assert_eq!(response.status(), Status::UnsupportedMediaType);
+
+    let response = client.post("/").header(ContentType::GIF).body(PNG).dispatch();
+    assert_eq!(response.status(), Status::UnsupportedMediaType);
+
+    let response = client.post("/").header(ContentType::Binary).body(PNG).dispatch();
+    assert_eq!(response.status(), Status::UnsupportedMediaType);
+
+    let response = client.post("/unverified").header(ContentType::PNG).body(html).dispatch();
+    assert_eq!(response.into_string().unwrap(), html.len().to_string());
+}
+
+fn multipart(content_type: &str, data: &[u8]) -> Vec<u8> {
+    let mut body = [
+        "--X-BOUNDARY",
+        r#"Content-Disposition: form-data; name="file"; filename="avatar.png""#,
+        format!("Content-Type: {content_type}").as_str(),
+        "",
+        "",
+    ].join("\r\n").into_bytes();
+
+    body.extend_from_slice(data);
+    body.extend_from_slice(b"\r\n--X-BOUNDARY--\r\n");
+    body
+}
+
+#[test]
+fn form_data_fields_are_verified() {
+    let client = client();
+    let form_data: ContentType = "multipart/form-data; boundary=X-BOUNDARY".parse().unwrap();
+    let response = client.post("/form")
+        .header(form_data.clone())
+        .body(multipart("image/png", PNG))
+        .dispatch();
+
+    assert_eq!(response.into_string().unwrap(), PNG.len().to_string());
+
+    let response = client.post("/form")
+        .header(form_data)
+        .body(multipart("image/png", b"<html><body>hi</body></html>"))
+        .dispatch();
+
+    assert_eq!(response.status(), Status::UnsupportedMediaType);
+}
@@ -0,0 +1,112 @@
+#![cfg(feature = "versioning")]
+
+#[macro_use] extern crate rocket;
+
+use rocket::{Rocket, Build, Config};
+use rocket::figment::Figment;
+use rocket::http::{ContentType, Status};
+use rocket::local::blocking::Client;
+use rocket::time::macros::date;
+use rocket::versioning::{Versioning, ApiVersion};
+
+#[get("/users/<id>")]
+fn user_v1(id: usize) -> String {
+    format!("user {id}")
+}
+
+#[get("/users/<id>")]
+fn user_v2(id: usize) -> String {
+    format!("user {id}")
+}
+
+#[get("/health")]
+fn health() -> &'static str {
+    "ok"
+}
+
+fn rocket(versioning: Versioning) -> Rocket<Build> {
+    let v1 = ApiVersion::new("/api/v1")
+        .deprecated(date!(2025 - 01 - 01))
+        .sunset(date!(2100 - 01 - 01))
+        .link("https://example.com/migrate");
+
+    rocket::custom(Config::debug_default())
+        .mount("/api/v1", routes![user_v1])
+        .mount("/api/v2", routes![user_v2])
+        .mount("/", routes![health])
+        .attach(versioning.version("v1", v1).version("v2", ApiVersion::new("/api/v2/")))
+}
+
+#[test]
+fn lists_routes_by_version() {
+    let client = Client::debug(rocket(Versioning::new())).unwrap();
+    let response = client.get("/_rocket/versions").dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.content_type(), Some(ContentType::JSON));
+    assert_eq!(response.into_string().unwrap(), concat!(
+        "{\"versions\":[",
+        "{\"name\":\"v1\",\"prefix\":\"/api/v1\",\"status\":\"deprecated\",",
+        "\"deprecated\":\"2025-01-01\",\"sunset\":\"2100-01-01\",",
+        "\"link\":\"https://example.com/migrate\",\"routes\":[",
+        "{\"name\":\"user_v1\",\"method\":\"GET\",\"uri\":\"/api/v1/users/<id>\"}]},",
+        "{\"name\":\"v2\",\"prefix\":\"/api/v2/\",\"status\":\"current\",",
+        "\"deprecated\":null,\"sunset\":null,\"link\":null,\"routes\":[",
+        "{\"name\":\"user_v2\",\"method\":\"GET\",\"uri\":\"/api/v2/users/<id>\"}]}",
+        "]}"
+    ));
+}
+
+#[test]
+fn deprecated_versions_are_announced() {
+    let client = Client::debug(rocket(Versioning::new())).unwrap();
+    let response = client.get("/api/v1/users/1").dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.headers().get_one("Deprecation"), Some("@1735689600"));
+    assert_eq!(response.headers().get_one("Sunset"), Some("Fri, 01 Jan 2100 00:00:00 GMT"));
+    assert_eq!(response.headers().get_one("Link"),
+        Some("<https://example.com/migrate>; rel=\"deprecation\""));
+
+    for uri in ["/api/v2/users/1", "/health"] {
+        let response = client.get(uri).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert!(!response.headers().contains("Deprecation"));
+        assert!(!response.headers().contains("Sunset"));
+        assert!(!response.headers().contains("Link"));
+    }
+}
+
+#[test]
+fn configured_versions() {
+    let figment = Figment::from(Config::debug_default())
+        .merge(("versioning.path", "/versions"))
+        .merge(("versioning.versions.v0.prefix", "/api/v0"))
+        .merge(("versioning.versions.v0.deprecated", "2020-01-01"))
+        .merge(("versioning.versions.v0.sunset", "2021-01-01"));
+
+    let client = Client::debug(rocket::custom(figment).attach(Versioning::new())).unwrap();
+    let response = client.get("/versions").dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert!(response.into_string().unwrap().contains("\"status\":\"sunset\""));
+    assert_eq!(client.get("/_rocket/versions").dispatch().status(), Status::NotFound);
+}
+
+#[test]
+fn invalid_versions_fail_ignition() {
+    let invalid = [
+        ApiVersion::new("api/v1"),
+        ApiVersion::new("/api/v1").deprecated(date!(2025 - 01 - 01)).sunset(date!(2024 - 01 - 01)),
+    ];
+
+    for version in invalid {
+        let rocket = rocket::custom(Config::debug_default())
+            .attach(Versioning::new().version("v1", version));
+
+        assert!(Client::debug(rocket).is_err());
+    }
+
+    let figment = Figment::from(Config::debug_default())
+        .merge(("versioning.versions.v1.prefix", "/api/v1"))
+        .merge(("versioning.versions.v1.sunset", "01/01/2030"));
+
+    assert!(Client::debug(rocket::custom(figment).attach(Versioning::new())).is_err());
+}
@@ -0,0 +1,72 @@
+#![cfg(feature = "ws")]
+
+#[macro_use] extern crate rocket;
+
+use rocket::ws;
+use rocket::futures::{SinkExt, StreamExt};
+use rocket::http::{Header, Status};
+use rocket::local::asynchronous::{Client, LocalRequest};
+
+#[get("/echo")]
+fn echo(ws: ws::WebSocket) -> ws::Stream!['static] {
+    ws::Stream! { ws =>
+        for await message in ws {
+            yield message?;
+        }
+    }
+}
+
+#[get("/hello/<name>")]
+fn hello(ws: ws::WebSocket, name: &str) -> ws::Channel<'_> {
+    ws.channel(move |mut stream| Box::pin(async move {
+        stream.send(format!("Hello, {name}!").into()).await
+    }))
+}
+
+fn handshake(request: LocalRequest<'_>) -> LocalRequest<'_> {
+    request
+        .header(Header::new("Connection", "Upgrade"))
+        .header(Header::new("Upgrade", "websocket"))
+        .header(Header::new("Sec-WebSocket-Version", "13"))
+        .header(Header::new("Sec-WebSocket-Key", "dGhlIHNhbXBsZSBub25jZQ=="))
+}
+
+#[async_test]
+async fn echoes_over_local_client() {
+    let client = Client::debug_with(routes![echo]).await.unwrap();
+    let mut response = handshake(client.get("/echo")).dispatch().await;
+    assert_eq!(response.status(), Status::SwitchingProtocols);
+    assert_eq!(response.headers().get_one("Sec-WebSocket-Accept"),
+        Some("s3pPLMBiTxaQ9kYGzzhZRbK+xOo="));
+
+    let io = response.upgrade().unwrap();
+    assert_eq!(io.protocol(), "websocket");
+
+    let mut stream = ws::stream::DuplexStream::client(io, ws::Config::default()).await;
+    for message in ["hi", "there"] {
+        stream.send(message.into()).await.unwrap();
+        assert_eq!(stream.next().await.unwrap().unwrap(), message.into());
+    }
+
+    stream.close(None).await.unwrap();
+    assert!(response.upgrade().is_none());
+}
+
+#[async_test]
+async fn channel_borrows_from_request() {
+    let client = Client::debug_with(routes![hello]).await.unwrap();
+    let mut response = handshake(client.get("/hello/Bob")).dispatch().await;
+    assert_eq!(response.status(), Status::SwitchingProtocols);
+
+    let io = response.upgrade().unwrap();
+    let mut stream = ws::stream::DuplexStream::client(io, ws::Config::default()).await;
+    assert_eq!(stream.next().await.unwrap().unwrap(), "Hello, Bob!".into());
+}
+
+#[async_test]
+async fn requires_upgrade() {
+    let client = Client::debug_with(routes![echo]).await.unwrap();
+    let mut response = client.get("/echo").dispatch().await;
+    assert_eq!(response.status(), Status::UpgradeRequired);
+    assert!(response.upgrade().is_none());
+}
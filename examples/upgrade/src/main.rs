@@ -1,5 +1,6 @@
 #[macro_use] extern crate rocket;
 
+use rocket::ws;
 use rocket::fs::{self, FileServer};
 use rocket::futures::{SinkExt, StreamExt};
 
@@ -30,5 +30,7 @@ pushd "${PROJECT_ROOT}" > /dev/null 2>&1
         -p rocket_db_pools \
         -p rocket_sync_db_pools \
         -p rocket_dyn_templates \
-        -p rocket_ws
+        -p rocket_ws \
+        -p rocket_media \
+        -p rocket_scim
 popd > /dev/null 2>&1
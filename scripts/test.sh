@@ -98,12 +98,18 @@ function test_contrib() {
     tera
     handlebars
     minijinja
+    tera,handlebars,minijinja,integrity
   )
 
   WS_FEATURES=(
     tungstenite
   )
 
+  MEDIA_FEATURES=(
+    default
+    avif
+  )
+
   for feature in "${DB_POOLS_FEATURES[@]}"; do
     echo ":: Building and testing db_pools [$feature]..."
     $CARGO test -p rocket_db_pools --no-default-features --features $feature $@
@@ -123,6 +129,14 @@ function test_contrib() {
     echo ":: Building and testing ws [$feature]..."
     $CARGO test -p rocket_ws --no-default-features --features $feature $@
   done
+
+  for feature in "${MEDIA_FEATURES[@]}"; do
+    echo ":: Building and testing media [$feature]..."
+    $CARGO test -p rocket_media --no-default-features --features $feature $@
+  done
+
+  echo ":: Building and testing scim..."
+  $CARGO test -p rocket_scim $@
 }
 
 function test_core() {
@@ -136,6 +150,46 @@ function test_core() {
     json
     msgpack
     uuid
+    user-agent
+    signing
+    integrity
+    markdown
+    barcode
+    totp
+    passkey
+    saml
+    confirm
+    edge
+    ws
+    compression
+    proxy
+    quota
+    limiter
+    authz
+    paywall
+    handshake
+    cache
+    budget
+    shed
+    slo
+    tunnel
+    shadow
+    diff
+    discovery
+    versioning
+    openapi
+    consumer
+    retention
+    handoff
+    cors
+    pack
+    envelope
+    transform
+    rpc
+    inspector
+    dev
+    crash-report
+    zip
     trace
   )
 